@@ -21,3 +21,10 @@ cargo make stest \
     -p risingwave_source \
     -p risingwave_storage \
     -p risingwave_stream
+
+echo "--- Run integration tests in deterministic simulation mode"
+cargo make sit-test --no-fail-fast --features failpoints
+
+echo "--- Run e2e sqllogictest in deterministic simulation mode"
+cargo make sslt -- './e2e_test/streaming/**/*.slt' -j 8
+cargo make sslt -- './e2e_test/batch/**/*.slt' -j 8
@@ -15,29 +15,42 @@
 use std::fmt::{Debug, Formatter};
 
 use futures::StreamExt;
+use prost::Message;
 use risingwave_common::array::DataChunk;
 use risingwave_common::error::Result;
+use risingwave_common::util::addr::HostAddr;
 use risingwave_pb::batch_plan::exchange_source::LocalExecutePlan::Plan;
 use risingwave_pb::batch_plan::{ExchangeSource as ProstExchangeSource, TaskOutputId};
-use risingwave_pb::task_service::{ExecuteRequest, GetDataResponse};
-use risingwave_rpc_client::{ComputeClient, ExchangeSource};
+use risingwave_pb::common::buffer::CompressionType;
+use risingwave_pb::task_service::{ExecuteRequest, GetDataResponse, GetDataWithPermitsRequest};
+use risingwave_rpc_client::permits::PermitSender;
+use risingwave_rpc_client::{ComputeClientPool, ExchangeSource};
 use tonic::Streaming;
 
 /// Use grpc client as the source.
 pub struct GrpcExchangeSource {
     stream: Streaming<GetDataResponse>,
 
+    /// Returns permits to the sender after taking each chunk. `None` in the local execution mode,
+    /// where the data is sent back without flow control.
+    permit_sender: Option<PermitSender<GetDataWithPermitsRequest>>,
+
     task_output_id: TaskOutputId,
 }
 
 impl GrpcExchangeSource {
-    pub async fn create(exchange_source: ProstExchangeSource) -> Result<Self> {
-        let addr = exchange_source.get_host()?.into();
+    /// Create a source with a client from `client_pool`. Opening the stream is retried if the
+    /// connection to the peer is broken.
+    pub async fn create(
+        client_pool: &ComputeClientPool,
+        exchange_source: ProstExchangeSource,
+        compression: CompressionType,
+    ) -> Result<Self> {
+        let addr: HostAddr = exchange_source.get_host()?.into();
         let task_output_id = exchange_source.get_task_output_id()?.clone();
         let task_id = task_output_id.get_task_id()?.clone();
-        let client = ComputeClient::new(addr).await?;
         let local_execute_plan = exchange_source.local_execute_plan;
-        let stream = match local_execute_plan {
+        let (stream, permit_sender) = match local_execute_plan {
             // When in the local execution mode, `GrpcExchangeSource` would send out
             // `ExecuteRequest` and get the data chunks back in a single RPC.
             Some(local_execute_plan) => {
@@ -47,12 +60,27 @@ impl GrpcExchangeSource {
                     plan: plan.plan,
                     epoch: plan.epoch,
                 };
-                client.execute(execute_request).await?
+                let stream = client_pool
+                    .retry(addr, |client| {
+                        let execute_request = execute_request.clone();
+                        async move { client.execute(execute_request).await }
+                    })
+                    .await?;
+                (stream, None)
+            }
+            None => {
+                let (stream, permit_sender) = client_pool
+                    .retry(addr, |client| {
+                        let task_output_id = task_output_id.clone();
+                        async move { client.get_data(task_output_id, compression).await }
+                    })
+                    .await?;
+                (stream, Some(permit_sender))
             }
-            None => client.get_data(task_output_id.clone()).await?,
         };
         let source = Self {
             stream,
+            permit_sender,
             task_output_id,
         };
         Ok(source)
@@ -75,6 +103,9 @@ impl ExchangeSource for GrpcExchangeSource {
             Some(r) => r,
         };
         let task_data = res?;
+        if let Some(permit_sender) = &self.permit_sender {
+            permit_sender.add_permits(task_data.encoded_len());
+        }
         let data = DataChunk::from_protobuf(task_data.get_record_batch()?)?.compact()?;
         trace!(
             "Receiver taskOutput = {:?}, data = {:?}",
@@ -79,16 +79,18 @@ mod tests {
 
     use risingwave_common::util::addr::HostAddr;
     use risingwave_pb::batch_plan::{ExchangeSource as ProstExchangeSource, TaskId, TaskOutputId};
+    use risingwave_pb::common::buffer::CompressionType;
     use risingwave_pb::data::DataChunk;
     use risingwave_pb::task_service::exchange_service_server::{
         ExchangeService, ExchangeServiceServer,
     };
     use risingwave_pb::task_service::{
-        GetDataRequest, GetDataResponse, GetStreamRequest, GetStreamResponse,
+        GetDataRequest, GetDataResponse, GetDataWithPermitsRequest, GetStreamRequest,
+        GetStreamResponse, GetStreamWithPermitsRequest,
     };
-    use risingwave_rpc_client::ExchangeSource;
+    use risingwave_rpc_client::{ComputeClientPool, ExchangeSource, RetryPolicy};
     use tokio_stream::wrappers::ReceiverStream;
-    use tonic::{Request, Response, Status};
+    use tonic::{Request, Response, Status, Streaming};
 
     use crate::execution::grpc_exchange::GrpcExchangeSource;
 
@@ -99,12 +101,22 @@ mod tests {
     #[async_trait::async_trait]
     impl ExchangeService for FakeExchangeService {
         type GetDataStream = ReceiverStream<Result<GetDataResponse, Status>>;
+        type GetDataWithPermitsStream = ReceiverStream<Result<GetDataResponse, Status>>;
         type GetStreamStream = ReceiverStream<std::result::Result<GetStreamResponse, Status>>;
+        type GetStreamWithPermitsStream =
+            ReceiverStream<std::result::Result<GetStreamResponse, Status>>;
 
         async fn get_data(
             &self,
             _: Request<GetDataRequest>,
         ) -> Result<Response<Self::GetDataStream>, Status> {
+            unimplemented!()
+        }
+
+        async fn get_data_with_permits(
+            &self,
+            _: Request<Streaming<GetDataWithPermitsRequest>>,
+        ) -> Result<Response<Self::GetDataWithPermitsStream>, Status> {
             let (tx, rx) = tokio::sync::mpsc::channel(10);
             self.rpc_called.store(true, Ordering::SeqCst);
             for _ in 0..3 {
@@ -124,6 +136,13 @@ mod tests {
         ) -> Result<Response<Self::GetStreamStream>, Status> {
             unimplemented!()
         }
+
+        async fn get_stream_with_permits(
+            &self,
+            _request: Request<Streaming<GetStreamWithPermitsRequest>>,
+        ) -> Result<Response<Self::GetStreamWithPermitsStream>, Status> {
+            unimplemented!()
+        }
     }
 
     #[tokio::test(flavor = "multi_thread")]
@@ -159,8 +178,13 @@ mod tests {
             }),
             host: Some(HostAddr::from(addr).to_protobuf()),
             local_execute_plan: None,
+            co_located: false,
         };
-        let mut src = GrpcExchangeSource::create(exchange_source).await.unwrap();
+        let client_pool = ComputeClientPool::default();
+        let mut src =
+            GrpcExchangeSource::create(&client_pool, exchange_source, CompressionType::None)
+                .await
+                .unwrap();
         for _ in 0..3 {
             assert!(src.take_data().await.unwrap().is_some());
         }
@@ -182,8 +206,11 @@ mod tests {
             }),
             host: Some(addr.to_protobuf()),
             local_execute_plan: None,
+            co_located: false,
         };
-        let res = GrpcExchangeSource::create(exchange_source).await;
+        let client_pool = ComputeClientPool::default().with_retry_policy(RetryPolicy::no_retry());
+        let res =
+            GrpcExchangeSource::create(&client_pool, exchange_source, CompressionType::None).await;
         assert!(res.is_err());
     }
 }
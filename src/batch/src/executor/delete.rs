@@ -20,7 +20,7 @@ use risingwave_common::error::ErrorCode::InternalError;
 use risingwave_common::error::{ErrorCode, Result, RwError};
 use risingwave_common::types::DataType;
 use risingwave_pb::batch_plan::plan_node::NodeBody;
-use risingwave_source::SourceManagerRef;
+use risingwave_source::{DmlPriority, SourceManagerRef};
 
 use crate::executor::{
     BoxedDataChunkStream, BoxedExecutor, BoxedExecutorBuilder, Executor, ExecutorBuilder,
@@ -83,7 +83,7 @@ impl DeleteExecutor {
 
             let chunk = StreamChunk::from_parts(vec![Op::Delete; len], data_chunk);
 
-            let notifier = source.write_chunk(chunk)?;
+            let notifier = source.write_chunk(chunk, DmlPriority::High).await?;
             notifiers.push(notifier);
         }
 
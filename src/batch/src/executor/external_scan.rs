@@ -0,0 +1,154 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use futures_async_stream::try_stream;
+use itertools::Itertools;
+use risingwave_common::array::DataChunk;
+use risingwave_common::catalog::{ColumnDesc, Schema};
+use risingwave_common::error::{Result, RwError, ToRwResult};
+use risingwave_common::util::chunk_coalesce::DEFAULT_CHUNK_BUFFER_SIZE;
+use risingwave_connector::lake::{self, delta, LakeFile, LakeLocation};
+use risingwave_pb::batch_plan::plan_node::NodeBody;
+use risingwave_pb::plan_common::ExternalTableFormat;
+
+use crate::executor::{
+    BoxedDataChunkStream, BoxedExecutor, BoxedExecutorBuilder, Executor, ExecutorBuilder,
+};
+use crate::task::BatchTaskContext;
+
+/// Reads the Parquet files of an external table. Files in partitions whose values are not allowed
+/// by `partition_filters` are skipped without being read.
+///
+/// A Delta table is read at the snapshot of its latest version when the scan starts, so files
+/// committed or removed concurrently are never partially seen.
+pub struct ExternalScanExecutor {
+    location: String,
+    format: ExternalTableFormat,
+    schema: Schema,
+    /// `(column, values)` pairs. A file is read only if its value of each `column` is in `values`.
+    partition_filters: Vec<(String, Vec<String>)>,
+    identity: String,
+}
+
+impl ExternalScanExecutor {
+    pub fn new(
+        location: String,
+        format: ExternalTableFormat,
+        schema: Schema,
+        partition_filters: Vec<(String, Vec<String>)>,
+        identity: String,
+    ) -> Self {
+        Self {
+            location,
+            format,
+            schema,
+            partition_filters,
+            identity,
+        }
+    }
+
+    fn should_read(&self, file: &LakeFile) -> bool {
+        self.partition_filters
+            .iter()
+            .all(|(column, values)| match file.partition_value(column) {
+                Some(value) => values.iter().any(|v| v == value),
+                None => true,
+            })
+    }
+}
+
+pub struct ExternalScanExecutorBuilder {}
+
+#[async_trait::async_trait]
+impl BoxedExecutorBuilder for ExternalScanExecutorBuilder {
+    async fn new_boxed_executor<C: BatchTaskContext>(
+        source: &ExecutorBuilder<C>,
+        inputs: Vec<BoxedExecutor>,
+    ) -> Result<BoxedExecutor> {
+        ensure!(
+            inputs.is_empty(),
+            "External scan should not have input executor!"
+        );
+        let scan_node = try_match_expand!(
+            source.plan_node().get_node_body().unwrap(),
+            NodeBody::ExternalScan
+        )?;
+
+        let schema = Schema::new(
+            scan_node
+                .column_descs
+                .iter()
+                .map(|column_desc| (&ColumnDesc::from(column_desc.clone())).into())
+                .collect_vec(),
+        );
+        let partition_filters = scan_node
+            .partition_filters
+            .iter()
+            .map(|filter| (filter.column.clone(), filter.values.clone()))
+            .collect();
+        Ok(Box::new(ExternalScanExecutor::new(
+            scan_node.location.clone(),
+            scan_node.get_format()?,
+            schema,
+            partition_filters,
+            source.plan_node().get_identity().clone(),
+        )))
+    }
+}
+
+impl Executor for ExternalScanExecutor {
+    fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    fn identity(&self) -> &str {
+        &self.identity
+    }
+
+    fn execute(self: Box<Self>) -> BoxedDataChunkStream {
+        self.do_execute()
+    }
+}
+
+impl ExternalScanExecutor {
+    #[try_stream(boxed, ok = DataChunk, error = RwError)]
+    async fn do_execute(self: Box<Self>) {
+        let location = LakeLocation::parse(&self.location).to_rw_result()?;
+        let store = location.connect().await;
+        let files = match self.format {
+            ExternalTableFormat::Parquet => lake::list_files(store.as_ref(), location.prefix())
+                .await
+                .to_rw_result()?,
+            ExternalTableFormat::Delta => {
+                delta::load_snapshot(store.as_ref(), location.prefix(), None)
+                    .await
+                    .to_rw_result()?
+                    .files
+            }
+        };
+        for file in files.iter().filter(|file| self.should_read(file)) {
+            let chunks = lake::read_file(
+                store.as_ref(),
+                file,
+                &self.schema,
+                DEFAULT_CHUNK_BUFFER_SIZE,
+            )
+            .await
+            .to_rw_result()?;
+            for chunk in chunks {
+                yield chunk;
+            }
+        }
+    }
+}
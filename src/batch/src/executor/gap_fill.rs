@@ -0,0 +1,197 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+
+use futures_async_stream::try_stream;
+use risingwave_common::array::{DataChunk, Row};
+use risingwave_common::catalog::Schema;
+use risingwave_common::error::{Result, RwError};
+use risingwave_common::types::NaiveDateTimeWrapper;
+use risingwave_common::util::chunk_coalesce::DataChunkBuilder;
+use risingwave_common::util::gap_fill::GapFiller;
+use risingwave_pb::batch_plan::plan_node::NodeBody;
+use risingwave_pb::plan_common::GapFillStrategy;
+
+use crate::executor::{
+    BoxedDataChunkStream, BoxedExecutor, BoxedExecutorBuilder, Executor, ExecutorBuilder,
+};
+use crate::task::BatchTaskContext;
+
+/// [`GapFillExecutor`] keeps the input rows of each partition in memory, and emits them sorted by
+/// time together with the rows generated for the empty buckets between them.
+pub struct GapFillExecutor {
+    child: BoxedExecutor,
+    filler: GapFiller,
+    schema: Schema,
+    identity: String,
+}
+
+impl Executor for GapFillExecutor {
+    fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    fn identity(&self) -> &str {
+        &self.identity
+    }
+
+    fn execute(self: Box<Self>) -> BoxedDataChunkStream {
+        self.do_execute()
+    }
+}
+
+impl GapFillExecutor {
+    pub(super) fn new(child: BoxedExecutor, filler: GapFiller, identity: String) -> Self {
+        let schema = child.schema().clone();
+        Self {
+            child,
+            filler,
+            schema,
+            identity,
+        }
+    }
+
+    #[try_stream(boxed, ok = DataChunk, error = RwError)]
+    async fn do_execute(self: Box<Self>) {
+        let mut partitions: BTreeMap<Row, Vec<(NaiveDateTimeWrapper, Row)>> = BTreeMap::new();
+        #[for_await]
+        for chunk in self.child.execute() {
+            let chunk = chunk?;
+            for row in chunk.rows() {
+                let partition = row.row_by_indices(&self.filler.partition_by);
+                let row = row.to_owned_row();
+                let time = self.filler.time_of(&row)?;
+                partitions.entry(partition).or_default().push((time, row));
+            }
+        }
+
+        let mut chunk_builder = DataChunkBuilder::with_default_size(self.schema.data_types());
+        for (_, mut rows) in partitions {
+            rows.sort_by_key(|(time, _)| *time);
+            let mut rows = rows.into_iter().map(|(_, row)| row).peekable();
+            while let Some(row) = rows.next() {
+                let filled = match rows.peek() {
+                    Some(next) => self.filler.fill(&row, next)?,
+                    None => vec![],
+                };
+                for row in std::iter::once(row).chain(filled) {
+                    if let Some(chunk) = chunk_builder.append_one_row_from_datums(row.0.iter())? {
+                        yield chunk;
+                    }
+                }
+            }
+        }
+        if let Some(chunk) = chunk_builder.consume_all()? {
+            yield chunk;
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl BoxedExecutorBuilder for GapFillExecutor {
+    async fn new_boxed_executor<C: BatchTaskContext>(
+        source: &ExecutorBuilder<C>,
+        mut inputs: Vec<BoxedExecutor>,
+    ) -> Result<BoxedExecutor> {
+        ensure!(
+            inputs.len() == 1,
+            "GapFillExecutor should have only one child!"
+        );
+        let gap_fill_node = try_match_expand!(
+            source.plan_node().get_node_body().unwrap(),
+            NodeBody::GapFill
+        )?;
+        let filler = GapFiller::new(
+            gap_fill_node.time_col as usize,
+            gap_fill_node.get_interval()?.into(),
+            gap_fill_node
+                .partition_by
+                .iter()
+                .map(|&idx| idx as usize)
+                .collect(),
+            gap_fill_node
+                .strategies
+                .iter()
+                .map(|&strategy| GapFillStrategy::from_i32(strategy).unwrap())
+                .collect(),
+        );
+        Ok(Box::new(Self::new(
+            inputs.remove(0),
+            filler,
+            source.plan_node().get_identity().clone(),
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use risingwave_common::array::DataChunkTestExt;
+    use risingwave_common::catalog::Field;
+    use risingwave_common::types::{DataType, IntervalUnit};
+
+    use super::*;
+    use crate::executor::test_utils::MockExecutor;
+
+    #[tokio::test]
+    async fn test_gap_fill() {
+        let mut child = MockExecutor::new(Schema::new(vec![
+            Field::unnamed(DataType::Int32),
+            Field::unnamed(DataType::Timestamp),
+            Field::unnamed(DataType::Int64),
+            Field::unnamed(DataType::Float64),
+        ]));
+        child.add(DataChunk::from_pretty(
+            &"i TS        I  F
+              1 ^10:03:00 13 4.0
+              2 ^10:00:00 20 0.0
+              1 ^10:00:00 10 1.0
+              2 ^10:01:00 21 1.0"
+                .replace('^', "2022-2-2T"),
+        ));
+        let filler = GapFiller::new(
+            1,
+            IntervalUnit::from_minutes(1),
+            vec![0],
+            vec![
+                GapFillStrategy::None,
+                GapFillStrategy::None,
+                GapFillStrategy::Locf,
+                GapFillStrategy::Interpolate,
+            ],
+        );
+        let executor = Box::new(GapFillExecutor::new(
+            Box::new(child),
+            filler,
+            "GapFillExecutor".to_string(),
+        ));
+        let mut stream = executor.execute();
+        let chunk = stream.next().await.unwrap().unwrap();
+        assert!(stream.next().await.is_none());
+        assert_eq!(
+            chunk,
+            DataChunk::from_pretty(
+                &"i TS        I  F
+                  1 ^10:00:00 10 1.0
+                  1 ^10:01:00 10 2.0
+                  1 ^10:02:00 10 3.0
+                  1 ^10:03:00 13 4.0
+                  2 ^10:00:00 20 0.0
+                  2 ^10:01:00 21 1.0"
+                    .replace('^', "2022-2-2T"),
+            )
+        );
+    }
+}
@@ -67,7 +67,9 @@ impl CreateSource for DefaultCreateSource {
         let task_output_id = prost_source.get_task_output_id()?;
         let task_id = TaskId::from(task_output_id.get_task_id()?);
 
-        if context.is_local_addr(&peer_addr) {
+        // The scheduler tags sources on the same compute node, while the address check covers
+        // plans not built by the distributed scheduler.
+        if prost_source.co_located || context.is_local_addr(&peer_addr) {
             trace!("Exchange locally [{:?}]", task_output_id);
 
             Ok(Box::new(LocalExchangeSource::create(
@@ -83,7 +85,12 @@ impl CreateSource for DefaultCreateSource {
             );
 
             Ok(Box::new(
-                GrpcExchangeSource::create(prost_source.clone()).await?,
+                GrpcExchangeSource::create(
+                    &context.client_pool(),
+                    prost_source.clone(),
+                    context.exchange_compression(),
+                )
+                .await?,
             ))
         }
     }
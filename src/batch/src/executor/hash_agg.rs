@@ -14,6 +14,7 @@
 
 use std::collections::HashMap;
 use std::marker::PhantomData;
+use std::mem::size_of;
 use std::sync::Arc;
 use std::vec;
 
@@ -35,7 +36,7 @@ use risingwave_pb::batch_plan::HashAggNode;
 use crate::executor::{
     BoxedDataChunkStream, BoxedExecutor, BoxedExecutorBuilder, Executor, ExecutorBuilder,
 };
-use crate::task::{BatchTaskContext, TaskId};
+use crate::task::{BatchTaskContext, MemoryContext, OperatorMemory, TaskId};
 
 type AggHashMap<K> = HashMap<K, Vec<BoxedAggState>, PrecomputedBuildHasher>;
 
@@ -59,6 +60,7 @@ pub struct HashAggExecutorBuilder {
     schema: Schema,
     task_id: TaskId,
     identity: String,
+    memory_context: MemoryContext,
 }
 
 impl HashAggExecutorBuilder {
@@ -67,6 +69,7 @@ impl HashAggExecutorBuilder {
         child: BoxedExecutor,
         task_id: TaskId,
         identity: String,
+        memory_context: MemoryContext,
     ) -> Result<BoxedExecutor> {
         let group_key_columns = hash_agg_node
             .get_group_keys()
@@ -104,6 +107,7 @@ impl HashAggExecutorBuilder {
             schema: Schema { fields },
             task_id,
             identity,
+            memory_context,
         };
 
         Ok(HashAggExecutorBuilderDispatcher::dispatch_by_kind(
@@ -132,6 +136,7 @@ impl BoxedExecutorBuilder for HashAggExecutorBuilder {
             inputs.remove(0),
             source.task_id.clone(),
             identity,
+            source.context().memory_context(),
         )
     }
 }
@@ -148,6 +153,8 @@ pub(crate) struct HashAggExecutor<K> {
     group_key_types: Vec<DataType>,
     schema: Schema,
     identity: String,
+    /// Memory held by the hash table of the groups
+    memory: OperatorMemory,
     _phantom: PhantomData<K>,
 }
 
@@ -159,6 +166,7 @@ impl<K> HashAggExecutor<K> {
             child: builder.child,
             group_key_types: builder.group_key_types,
             schema: builder.schema,
+            memory: builder.memory_context.operator(builder.identity.clone()),
             identity: builder.identity,
             _phantom: PhantomData,
         }
@@ -181,9 +189,10 @@ impl<K: HashKey + Send + Sync> Executor for HashAggExecutor<K> {
 
 impl<K: HashKey + Send + Sync> HashAggExecutor<K> {
     #[try_stream(boxed, ok = DataChunk, error = RwError)]
-    async fn do_execute(self: Box<Self>) {
+    async fn do_execute(mut self: Box<Self>) {
         // hash map for each agg groups
         let mut groups = AggHashMap::<K>::default();
+        let group_size = size_of::<K>() + self.agg_factories.len() * size_of::<BoxedAggState>();
 
         // consume all chunks to compute the agg result
         #[for_await]
@@ -191,6 +200,9 @@ impl<K: HashKey + Send + Sync> HashAggExecutor<K> {
             let chunk = chunk?.compact()?;
             let keys = K::build(self.group_key_columns.as_slice(), &chunk)?;
             for (row_id, key) in keys.into_iter().enumerate() {
+                if !groups.contains_key(&key) {
+                    self.memory.add(group_size)?;
+                }
                 let mut err_flag = Ok(());
                 let states: &mut Vec<BoxedAggState> = groups.entry(key).or_insert_with(|| {
                     self.agg_factories
@@ -322,6 +334,7 @@ mod tests {
             Box::new(src_exec),
             TaskId::default(),
             "HashAggExecutor".to_string(),
+            MemoryContext::default(),
         )
         .unwrap();
 
@@ -387,6 +400,7 @@ mod tests {
             Box::new(src_exec),
             TaskId::default(),
             "HashAggExecutor".to_string(),
+            MemoryContext::default(),
         )
         .unwrap();
         let schema = Schema {
@@ -25,7 +25,7 @@ use risingwave_common::error::ErrorCode::InternalError;
 use risingwave_common::error::{ErrorCode, Result, RwError};
 use risingwave_common::types::DataType;
 use risingwave_pb::batch_plan::plan_node::NodeBody;
-use risingwave_source::SourceManagerRef;
+use risingwave_source::{DmlPriority, SourceManagerRef};
 
 use crate::executor::{
     BoxedDataChunkStream, BoxedExecutor, BoxedExecutorBuilder, Executor, ExecutorBuilder,
@@ -37,6 +37,8 @@ pub struct InsertExecutor {
     /// Target table id.
     table_id: TableId,
     source_manager: SourceManagerRef,
+    /// Priority of the inserted rows, low for bulk inserts.
+    priority: DmlPriority,
 
     child: BoxedExecutor,
     schema: Schema,
@@ -44,10 +46,16 @@ pub struct InsertExecutor {
 }
 
 impl InsertExecutor {
-    pub fn new(table_id: TableId, source_manager: SourceManagerRef, child: BoxedExecutor) -> Self {
+    pub fn new(
+        table_id: TableId,
+        source_manager: SourceManagerRef,
+        priority: DmlPriority,
+        child: BoxedExecutor,
+    ) -> Self {
         Self {
             table_id,
             source_manager,
+            priority,
             child,
             schema: Schema {
                 fields: vec![Field::unnamed(DataType::Int64)],
@@ -99,7 +107,8 @@ impl InsertExecutor {
             let columns = rowid_column.chain(child_columns).collect();
             let chunk = StreamChunk::new(vec![Op::Insert; len], columns, None);
 
-            let notifier = source.write_chunk(chunk)?;
+            // Waits here if too many rows are buffered, so that the child is not polled further.
+            let notifier = source.write_chunk(chunk, self.priority).await?;
             notifiers.push(notifier);
         }
 
@@ -140,6 +149,11 @@ impl BoxedExecutorBuilder for InsertExecutor {
         )?;
 
         let table_id = TableId::from(&insert_node.table_source_ref_id);
+        let priority = if insert_node.bulk {
+            DmlPriority::Low
+        } else {
+            DmlPriority::High
+        };
 
         Ok(Box::new(Self::new(
             table_id,
@@ -147,6 +161,7 @@ impl BoxedExecutorBuilder for InsertExecutor {
                 .context()
                 .source_manager_ref()
                 .ok_or_else(|| InternalError("Source manager not found".to_string()))?,
+            priority,
             inputs.remove(0),
         )))
     }
@@ -233,6 +248,7 @@ mod tests {
         let insert_executor = Box::new(InsertExecutor::new(
             table_id,
             source_manager.clone(),
+            DmlPriority::High,
             Box::new(mock_executor),
         ));
         let handle = tokio::spawn(async move {
@@ -0,0 +1,300 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use futures_async_stream::try_stream;
+use itertools::Itertools;
+use risingwave_common::array::{DataChunk, Row};
+use risingwave_common::catalog::Schema;
+use risingwave_common::error::{ErrorCode, Result, RwError};
+use risingwave_common::types::{to_datum_ref, ScalarImpl, ToOwnedDatum};
+use risingwave_common::util::chunk_coalesce::DataChunkBuilder;
+use risingwave_pb::batch_plan::plan_node::NodeBody;
+
+use crate::executor::join::JoinType;
+use crate::executor::{
+    BoxedDataChunkStream, BoxedExecutor, BoxedExecutorBuilder, Executor, ExecutorBuilder,
+};
+use crate::task::BatchTaskContext;
+
+/// [`AsOfJoinExecutor`] matches each left row with the latest right row of the same join keys,
+/// whose time is no later than the left row's. The right rows of each join key are kept in memory
+/// sorted by time, and each left row finds its match with a binary search. Rows with a NULL join
+/// key or time never match. If several right rows have the same latest time, any one of them may be
+/// chosen.
+pub struct AsOfJoinExecutor {
+    left: BoxedExecutor,
+    right: BoxedExecutor,
+    /// Either [`JoinType::Inner`] or [`JoinType::LeftOuter`].
+    join_type: JoinType,
+    left_keys: Vec<usize>,
+    right_keys: Vec<usize>,
+    left_asof_key: usize,
+    right_asof_key: usize,
+    /// Whether a right row at the same time as the left row matches.
+    inclusive: bool,
+    schema: Schema,
+    identity: String,
+}
+
+impl Executor for AsOfJoinExecutor {
+    fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    fn identity(&self) -> &str {
+        &self.identity
+    }
+
+    fn execute(self: Box<Self>) -> BoxedDataChunkStream {
+        self.do_execute()
+    }
+}
+
+impl AsOfJoinExecutor {
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn new(
+        left: BoxedExecutor,
+        right: BoxedExecutor,
+        join_type: JoinType,
+        left_keys: Vec<usize>,
+        right_keys: Vec<usize>,
+        left_asof_key: usize,
+        right_asof_key: usize,
+        inclusive: bool,
+        identity: String,
+    ) -> Self {
+        assert!(matches!(join_type, JoinType::Inner | JoinType::LeftOuter));
+        let schema = left
+            .schema()
+            .fields()
+            .iter()
+            .chain(right.schema().fields())
+            .cloned()
+            .collect();
+        Self {
+            left,
+            right,
+            join_type,
+            left_keys,
+            right_keys,
+            left_asof_key,
+            right_asof_key,
+            inclusive,
+            schema,
+            identity,
+        }
+    }
+
+    #[try_stream(boxed, ok = DataChunk, error = RwError)]
+    async fn do_execute(self: Box<Self>) {
+        // The right rows of each join key, sorted by time.
+        let mut right_rows: HashMap<Row, Vec<(ScalarImpl, Row)>> = HashMap::new();
+        #[for_await]
+        for chunk in self.right.execute() {
+            let chunk = chunk?;
+            for row in chunk.rows() {
+                let key = row.row_by_indices(&self.right_keys);
+                let time = match row.value_at(self.right_asof_key).to_owned_datum() {
+                    Some(time) => time,
+                    None => continue,
+                };
+                if key.0.iter().any(Option::is_none) {
+                    continue;
+                }
+                right_rows
+                    .entry(key)
+                    .or_default()
+                    .push((time, row.to_owned_row()));
+            }
+        }
+        for rows in right_rows.values_mut() {
+            rows.sort_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs));
+        }
+
+        let right_nulls = Row(vec![None; self.right.schema().len()]);
+        let mut chunk_builder = DataChunkBuilder::with_default_size(self.schema.data_types());
+        #[for_await]
+        for chunk in self.left.execute() {
+            let chunk = chunk?;
+            for row in chunk.rows() {
+                let time = row.value_at(self.left_asof_key).to_owned_datum();
+                let rows = right_rows.get(&row.row_by_indices(&self.left_keys));
+                let matched = match (time, rows) {
+                    (Some(time), Some(rows)) => {
+                        let end = if self.inclusive {
+                            rows.partition_point(|(right_time, _)| *right_time <= time)
+                        } else {
+                            rows.partition_point(|(right_time, _)| *right_time < time)
+                        };
+                        end.checked_sub(1).map(|idx| &rows[idx].1)
+                    }
+                    _ => None,
+                };
+                let right_row = match matched {
+                    Some(right_row) => right_row,
+                    None if self.join_type == JoinType::LeftOuter => &right_nulls,
+                    None => continue,
+                };
+                let datum_refs = row.values().chain(right_row.0.iter().map(to_datum_ref));
+                if let Some(chunk) = chunk_builder.append_one_row_from_datum_refs(datum_refs)? {
+                    yield chunk;
+                }
+            }
+        }
+        if let Some(chunk) = chunk_builder.consume_all()? {
+            yield chunk;
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl BoxedExecutorBuilder for AsOfJoinExecutor {
+    async fn new_boxed_executor<C: BatchTaskContext>(
+        source: &ExecutorBuilder<C>,
+        mut inputs: Vec<BoxedExecutor>,
+    ) -> Result<BoxedExecutor> {
+        ensure!(
+            inputs.len() == 2,
+            "AsOfJoinExecutor should have 2 children!"
+        );
+
+        let as_of_join_node = try_match_expand!(
+            source.plan_node().get_node_body().unwrap(),
+            NodeBody::AsOfJoin
+        )?;
+        let join_type = JoinType::from_prost(as_of_join_node.get_join_type()?);
+        if !matches!(join_type, JoinType::Inner | JoinType::LeftOuter) {
+            return Err(ErrorCode::NotImplemented(
+                format!("{:?} ASOF join", join_type),
+                None.into(),
+            )
+            .into());
+        }
+        let right = inputs.remove(1);
+        let left = inputs.remove(0);
+        Ok(Box::new(Self::new(
+            left,
+            right,
+            join_type,
+            as_of_join_node
+                .left_key
+                .iter()
+                .map(|&idx| idx as usize)
+                .collect_vec(),
+            as_of_join_node
+                .right_key
+                .iter()
+                .map(|&idx| idx as usize)
+                .collect_vec(),
+            as_of_join_node.left_asof_key as usize,
+            as_of_join_node.right_asof_key as usize,
+            as_of_join_node.inclusive,
+            source.plan_node().get_identity().clone(),
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use risingwave_common::array::DataChunkTestExt;
+    use risingwave_common::catalog::Field;
+    use risingwave_common::types::DataType;
+
+    use super::*;
+    use crate::executor::test_utils::MockExecutor;
+
+    /// Trades `(symbol, time)` joined with quotes `(symbol, time, price)`.
+    async fn execute(join_type: JoinType, inclusive: bool) -> DataChunk {
+        let mut trades = MockExecutor::new(Schema::new(vec![
+            Field::unnamed(DataType::Int32),
+            Field::unnamed(DataType::Int64),
+        ]));
+        trades.add(DataChunk::from_pretty(
+            "i I
+             1 10
+             1 25
+             2 15
+             3 30
+             1 .",
+        ));
+        let mut quotes = MockExecutor::new(Schema::new(vec![
+            Field::unnamed(DataType::Int32),
+            Field::unnamed(DataType::Int64),
+            Field::unnamed(DataType::Int64),
+        ]));
+        quotes.add(DataChunk::from_pretty(
+            "i I I
+             1 20 102
+             1 10 101
+             2 20 201
+             . 10 999",
+        ));
+        quotes.add(DataChunk::from_pretty(
+            "i I I
+             1 25 103",
+        ));
+        let executor = Box::new(AsOfJoinExecutor::new(
+            Box::new(trades),
+            Box::new(quotes),
+            join_type,
+            vec![0],
+            vec![0],
+            1,
+            1,
+            inclusive,
+            "AsOfJoinExecutor".to_string(),
+        ));
+        let mut stream = executor.execute();
+        let chunk = stream.next().await.unwrap().unwrap();
+        assert!(stream.next().await.is_none());
+        chunk
+    }
+
+    #[tokio::test]
+    async fn test_asof_inner_join() {
+        assert_eq!(
+            execute(JoinType::Inner, true).await,
+            DataChunk::from_pretty(
+                "i I i I I
+                 1 10 1 10 101
+                 1 25 1 25 103"
+            )
+        );
+        assert_eq!(
+            execute(JoinType::Inner, false).await,
+            DataChunk::from_pretty(
+                "i I i I I
+                 1 25 1 20 102"
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_asof_left_join() {
+        assert_eq!(
+            execute(JoinType::LeftOuter, true).await,
+            DataChunk::from_pretty(
+                "i I i I I
+                 1 10 1 10 101
+                 1 25 1 25 103
+                 2 15 . .  .
+                 3 30 . .  .
+                 1 .  . .  ."
+            )
+        );
+    }
+}
@@ -14,6 +14,7 @@
 
 use std::convert::TryInto;
 use std::marker::PhantomData;
+use std::mem::size_of;
 
 use futures::StreamExt;
 use futures_async_stream::try_stream;
@@ -31,7 +32,7 @@ use crate::executor::join::JoinType;
 use crate::executor::{
     BoxedDataChunkStream, BoxedExecutor, BoxedExecutorBuilder, Executor, ExecutorBuilder,
 };
-use crate::task::{BatchTaskContext, TaskId};
+use crate::task::{BatchTaskContext, MemoryContext, OperatorMemory, TaskId};
 
 /// Parameters of equi-join.
 ///
@@ -73,6 +74,8 @@ pub(super) struct HashJoinExecutor<K> {
     schema: Schema,
     output_indices: Vec<usize>,
     identity: String,
+    /// Memory held by the build side
+    memory: OperatorMemory,
     _phantom: PhantomData<K>,
 }
 
@@ -140,6 +143,8 @@ impl<K: HashKey + Send + Sync> HashJoinExecutor<K> {
 
         while let Some(chunk) = right_child_stream.next().await {
             let chunk = chunk?;
+            self.memory
+                .add(chunk.estimated_heap_size() + chunk.cardinality() * size_of::<K>())?;
             build_table.append_build_chunk(chunk)?;
         }
         let mut probe_table: ProbeTable<K> = build_table.try_into()?;
@@ -173,7 +178,9 @@ impl<K: HashKey + Send + Sync> HashJoinExecutor<K> {
 
                 probe_table.reset_result_index();
 
-                if let Some(data_chunk) = output_data_chunk && data_chunk.cardinality() > 0 {
+                if let Some(data_chunk) = output_data_chunk
+                    && data_chunk.cardinality() > 0
+                {
                     yield data_chunk.reorder_columns(&self.output_indices);
                 }
             } else {
@@ -201,7 +208,9 @@ impl<K: HashKey + Send + Sync> HashJoinExecutor<K> {
                         } else {
                             state = HashJoinState::Done;
                         }
-                        if let Some(data_chunk) = output_data_chunk && data_chunk.cardinality() > 0 {
+                        if let Some(data_chunk) = output_data_chunk
+                            && data_chunk.cardinality() > 0
+                        {
                             yield data_chunk.reorder_columns(&self.output_indices);
                         }
                     }
@@ -247,12 +256,14 @@ impl<K> HashJoinExecutor<K> {
         schema: Schema,
         identity: String,
         output_indices: Vec<usize>,
+        memory_context: MemoryContext,
     ) -> Self {
         HashJoinExecutor {
             left_child: Some(left_child),
             right_child: Some(right_child),
             params,
             schema,
+            memory: memory_context.operator(identity.clone()),
             identity,
             _phantom: PhantomData,
             output_indices,
@@ -267,6 +278,7 @@ pub struct HashJoinExecutorBuilder {
     output_indices: Vec<usize>,
     schema: Schema,
     task_id: TaskId,
+    memory_context: MemoryContext,
 }
 
 struct HashJoinExecutorBuilderDispatcher;
@@ -284,6 +296,7 @@ impl HashKeyDispatcher for HashJoinExecutorBuilderDispatcher {
             input.schema,
             format!("HashJoinExecutor{:?}", input.task_id),
             input.output_indices,
+            input.memory_context,
         ))
     }
 }
@@ -384,6 +397,7 @@ impl BoxedExecutorBuilder for HashJoinExecutorBuilder {
             schema: actual_schema,
             task_id: context.task_id.clone(),
             output_indices,
+            memory_context: context.context().memory_context(),
         };
 
         Ok(HashJoinExecutorBuilderDispatcher::dispatch_by_kind(
@@ -414,6 +428,7 @@ mod tests {
     use crate::executor::join::JoinType;
     use crate::executor::test_utils::MockExecutor;
     use crate::executor::BoxedExecutor;
+    use crate::task::MemoryContext;
     struct DataChunkMerger {
         data_types: Vec<DataType>,
         array_builders: Vec<ArrayBuilderImpl>,
@@ -654,6 +669,7 @@ mod tests {
                 schema,
                 "HashJoinExecutor2".to_string(),
                 (0..schema_len).into_iter().collect_vec(),
+                MemoryContext::default(),
             )) as BoxedExecutor
         }
 
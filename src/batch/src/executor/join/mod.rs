@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod asof_join;
 mod chunked_data;
 pub mod hash_join;
 mod hash_join_state;
@@ -19,6 +20,7 @@ pub mod nested_loop_join;
 mod row_level_iter;
 mod sort_merge_join;
 
+pub use asof_join::*;
 pub use chunked_data::*;
 pub use hash_join::*;
 pub use nested_loop_join::*;
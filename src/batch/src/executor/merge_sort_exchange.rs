@@ -31,7 +31,7 @@ use crate::executor::{
     BoxedDataChunkStream, BoxedExecutor, BoxedExecutorBuilder, CreateSource, DefaultCreateSource,
     Executor, ExecutorBuilder,
 };
-use crate::task::{BatchTaskContext, TaskId};
+use crate::task::{BatchTaskContext, OperatorMemory, TaskId};
 
 pub type MergeSortExchangeExecutor<C> = MergeSortExchangeExecutorImpl<DefaultCreateSource, C>;
 
@@ -52,6 +52,8 @@ pub struct MergeSortExchangeExecutorImpl<CS, C> {
     schema: Schema,
     task_id: TaskId,
     identity: String,
+    /// Memory held by the chunks kept for each source
+    memory: OperatorMemory,
 }
 
 impl<CS: 'static + Send + CreateSource, C: BatchTaskContext> MergeSortExchangeExecutorImpl<CS, C> {
@@ -60,14 +62,12 @@ impl<CS: 'static + Send + CreateSource, C: BatchTaskContext> MergeSortExchangeEx
     async fn get_source_chunk(&mut self, source_idx: usize) -> Result<()> {
         assert!(source_idx < self.source_inputs.len());
         let res = self.sources[source_idx].take_data().await?;
-        match res {
-            Some(chunk) => {
-                assert_ne!(chunk.cardinality(), 0);
-                let _ = std::mem::replace(&mut self.source_inputs[source_idx], Some(chunk));
-            }
-            None => {
-                let _ = std::mem::replace(&mut self.source_inputs[source_idx], None);
-            }
+        if let Some(chunk) = &res {
+            assert_ne!(chunk.cardinality(), 0);
+            self.memory.add(chunk.estimated_heap_size())?;
+        }
+        if let Some(chunk) = std::mem::replace(&mut self.source_inputs[source_idx], res) {
+            self.memory.release(chunk.estimated_heap_size());
         }
         Ok(())
     }
@@ -225,6 +225,10 @@ impl BoxedExecutorBuilder for MergeSortExchangeExecutorBuilder {
             source_creators,
             schema: Schema { fields },
             task_id: source.task_id.clone(),
+            memory: source
+                .context()
+                .memory_context()
+                .operator(source.plan_node().get_identity().clone()),
             identity: source.plan_node().get_identity().clone(),
         }))
     }
@@ -242,7 +246,7 @@ mod tests {
 
     use super::*;
     use crate::executor::test_utils::{FakeCreateSource, FakeExchangeSource};
-    use crate::task::ComputeNodeContext;
+    use crate::task::{ComputeNodeContext, MemoryContext};
 
     #[tokio::test]
     async fn test_exchange_multiple_sources() {
@@ -283,6 +287,7 @@ mod tests {
             },
             task_id: TaskId::default(),
             identity: "MergeSortExchangeExecutor2".to_string(),
+            memory: MemoryContext::default().operator("MergeSortExchangeExecutor2"),
         });
 
         let mut stream = executor.execute();
@@ -13,7 +13,9 @@
 // limitations under the License.
 
 mod delete;
+mod external_scan;
 mod filter;
+mod gap_fill;
 mod generic_exchange;
 mod hash_agg;
 mod hop_window;
@@ -32,13 +34,16 @@ mod table_function;
 pub mod test_utils;
 mod top_n;
 mod trace;
+mod unload;
 mod update;
 mod values;
 
 use async_recursion::async_recursion;
 pub use delete::*;
+pub use external_scan::*;
 pub use filter::*;
 use futures::stream::BoxStream;
+pub use gap_fill::*;
 pub use generic_exchange::*;
 pub use hash_agg::*;
 pub use hop_window::*;
@@ -60,6 +65,7 @@ pub use sort_agg::*;
 pub use table_function::*;
 pub use top_n::*;
 pub use trace::*;
+pub use unload::*;
 pub use update::*;
 pub use values::*;
 
@@ -188,6 +194,10 @@ impl<'a, C: BatchTaskContext> ExecutorBuilder<'a, C> {
             NodeBody::TableFunction => TableFunctionExecutorBuilder,
             NodeBody::HopWindow => HopWindowExecutor,
             NodeBody::SysRowSeqScan => SysRowSeqScanExecutorBuilder,
+            NodeBody::AsOfJoin => AsOfJoinExecutor,
+            NodeBody::GapFill => GapFillExecutor,
+            NodeBody::ExternalScan => ExternalScanExecutor,
+            NodeBody::Unload => UnloadExecutor,
         }
         .await?;
         let input_desc = real_executor.identity().to_string();
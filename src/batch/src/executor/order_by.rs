@@ -33,7 +33,7 @@ use risingwave_pb::batch_plan::plan_node::NodeBody;
 use crate::executor::{
     BoxedDataChunkStream, BoxedExecutor, BoxedExecutorBuilder, Executor, ExecutorBuilder,
 };
-use crate::task::BatchTaskContext;
+use crate::task::{BatchTaskContext, MemoryContext, OperatorMemory};
 
 pub struct OrderByExecutor {
     child: Option<BoxedExecutor>,
@@ -48,6 +48,7 @@ pub struct OrderByExecutor {
     identity: String,
     chunk_size: usize,
     schema: Schema,
+    memory: OperatorMemory,
 }
 
 #[expect(clippy::too_many_arguments)]
@@ -64,8 +65,10 @@ impl OrderByExecutor {
         disable_encoding: bool,
         identity: String,
         chunk_size: usize,
+        memory_context: MemoryContext,
     ) -> Self {
         let schema = child.schema().clone();
+        let memory = memory_context.operator(identity.clone());
         Self {
             child: Some(child),
             sorted_indices,
@@ -79,6 +82,7 @@ impl OrderByExecutor {
             identity,
             chunk_size,
             schema,
+            memory,
         }
     }
 }
@@ -116,6 +120,7 @@ impl BoxedExecutorBuilder for OrderByExecutor {
             false,
             source.plan_node().get_identity().clone(),
             DEFAULT_CHUNK_BUFFER_SIZE,
+            source.context().memory_context(),
         )))
     }
 }
@@ -176,9 +181,12 @@ impl OrderByExecutor {
         let mut stream = self.child.take().unwrap().execute();
         while let Some(chunk) = stream.next().await {
             let chunk = chunk?;
+            self.memory.add(chunk.estimated_heap_size())?;
             if !self.disable_encoding && self.encodable {
-                self.encoded_keys
-                    .push(encode_chunk(&chunk, self.order_pairs.clone()));
+                let encoded_keys = encode_chunk(&chunk, self.order_pairs.clone());
+                self.memory
+                    .add(encoded_keys.iter().map(|key| key.len()).sum())?;
+                self.encoded_keys.push(encoded_keys);
             }
             self.chunks.push(chunk);
             self.sorted_indices
@@ -323,6 +331,7 @@ mod tests {
             false,
             "OrderByExecutor2".to_string(),
             DEFAULT_CHUNK_BUFFER_SIZE,
+            MemoryContext::default(),
         ));
         let fields = &order_by_executor.schema().fields;
         assert_eq!(fields[0].data_type, DataType::Int32);
@@ -379,6 +388,7 @@ mod tests {
             false,
             "OrderByExecutor2".to_string(),
             DEFAULT_CHUNK_BUFFER_SIZE,
+            MemoryContext::default(),
         ));
         let fields = &order_by_executor.schema().fields;
         assert_eq!(fields[0].data_type, DataType::Float32);
@@ -435,6 +445,7 @@ mod tests {
             false,
             "OrderByExecutor2".to_string(),
             DEFAULT_CHUNK_BUFFER_SIZE,
+            MemoryContext::default(),
         ));
         let fields = &order_by_executor.schema().fields;
         assert_eq!(fields[0].data_type, DataType::Varchar);
@@ -17,6 +17,8 @@ use std::sync::Arc;
 use futures::pin_mut;
 use futures_async_stream::try_stream;
 use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use risingwave_common::array::{DataChunk, Row};
 use risingwave_common::catalog::{ColumnDesc, ColumnId, OrderedColumnDesc, Schema, TableId};
 use risingwave_common::error::{Result, RwError};
@@ -24,7 +26,8 @@ use risingwave_common::types::Datum;
 use risingwave_common::util::sort_util::OrderType;
 use risingwave_expr::expr::LiteralExpression;
 use risingwave_pb::batch_plan::plan_node::NodeBody;
-use risingwave_pb::batch_plan::{scan_range, ScanRange};
+use risingwave_pb::batch_plan::table_sample::Method;
+use risingwave_pb::batch_plan::{scan_range, ScanRange, TableSample};
 use risingwave_pb::plan_common::CellBasedTableDesc;
 use risingwave_storage::table::cell_based_table::{BatchDedupPkIter, BatchIter, CellBasedTable};
 use risingwave_storage::table::TableIter;
@@ -44,6 +47,8 @@ pub struct RowSeqScanExecutor<S: StateStore> {
     identity: String,
     stats: Arc<BatchMetrics>,
     scan_type: ScanType<S>,
+    sample: Option<TableSample>,
+    limit: Option<usize>,
 }
 
 pub enum ScanType<S: StateStore> {
@@ -68,9 +73,24 @@ impl<S: StateStore> RowSeqScanExecutor<S> {
             identity,
             stats,
             scan_type,
+            sample: None,
+            limit: None,
         }
     }
 
+    /// Only returns a sample of the scanned rows, for `TABLESAMPLE`.
+    pub fn with_sample(mut self, sample: Option<TableSample>) -> Self {
+        self.sample = sample;
+        self
+    }
+
+    /// Stops after reading `limit` rows, so that no more rows than needed by a `LIMIT` are read
+    /// from the storage.
+    pub fn with_limit(mut self, limit: Option<usize>) -> Self {
+        self.limit = limit;
+        self
+    }
+
     // TODO: Remove this when we support real partition-scan.
     // For shared storage like Hummock, we are using a fake partition-scan now. If `self.primary` is
     // false, we'll ignore this scanning and yield no chunk.
@@ -86,6 +106,51 @@ impl RowSeqScanExecutorBuilder {
     pub const DEFAULT_CHUNK_SIZE: usize = 1024;
 }
 
+/// Samples the chunks yielded by the iterator of a scan. `SYSTEM` keeps or drops a whole chunk
+/// read from the iterator at a time, while `BERNOULLI` keeps or drops each row.
+///
+/// TODO: skip the unsampled vnodes in storage once vnodes are encoded into the keys, so that a
+/// `SYSTEM` sample doesn't read the whole table.
+struct TableSampler {
+    method: Method,
+    probability: f64,
+    rng: StdRng,
+}
+
+impl TableSampler {
+    fn new(sample: &TableSample) -> Result<Self> {
+        Ok(Self {
+            method: sample.get_method()?,
+            probability: (sample.percentage / 100.0).clamp(0.0, 1.0),
+            rng: StdRng::seed_from_u64(sample.seed),
+        })
+    }
+
+    /// Returns `None` if no rows of `chunk` are sampled.
+    fn sample(&mut self, chunk: DataChunk) -> Result<Option<DataChunk>> {
+        match self.method {
+            Method::System => {
+                if self.rng.gen_bool(self.probability) {
+                    Ok(Some(chunk))
+                } else {
+                    Ok(None)
+                }
+            }
+            Method::Bernoulli => {
+                let visibility = (0..chunk.capacity())
+                    .map(|_| self.rng.gen_bool(self.probability))
+                    .collect_vec();
+                if !visibility.contains(&true) {
+                    return Ok(None);
+                }
+                Ok(Some(
+                    chunk.with_visibility(visibility.try_into()?).compact()?,
+                ))
+            }
+        }
+    }
+}
+
 fn is_full_range<T>(bounds: &impl RangeBounds<T>) -> bool {
     matches!(bounds.start_bound(), Bound::Unbounded)
         && matches!(bounds.end_bound(), Bound::Unbounded)
@@ -186,8 +251,15 @@ impl BoxedExecutorBuilder for RowSeqScanExecutorBuilder {
                 None,
             );
 
+            let reverse = seq_scan_node.reverse;
             let scan_type = if pk_prefix_value.size() == 0 && is_full_range(&next_col_bounds) {
-                let iter = table.batch_dedup_pk_iter(source.epoch, &pk_descs).await?;
+                let iter = if reverse {
+                    table
+                        .batch_backward_dedup_pk_iter(source.epoch, &pk_descs)
+                        .await?
+                } else {
+                    table.batch_dedup_pk_iter(source.epoch, &pk_descs).await?
+                };
                 ScanType::TableScan(iter)
             } else if pk_prefix_value.size() == pk_descs.len() {
                 keyspace.state_store().wait_epoch(source.epoch).await?;
@@ -196,26 +268,51 @@ impl BoxedExecutorBuilder for RowSeqScanExecutorBuilder {
             } else {
                 assert!(pk_prefix_value.size() < pk_descs.len());
 
-                let iter = if is_full_range(&next_col_bounds) {
-                    table
-                        .batch_iter_with_pk_prefix(source.epoch, pk_prefix_value)
-                        .await?
-                } else {
-                    table
-                        .batch_iter_with_pk_bounds(source.epoch, pk_prefix_value, next_col_bounds)
-                        .await?
+                let iter = match (is_full_range(&next_col_bounds), reverse) {
+                    (true, false) => {
+                        table
+                            .batch_iter_with_pk_prefix(source.epoch, pk_prefix_value)
+                            .await?
+                    }
+                    (true, true) => {
+                        table
+                            .batch_backward_iter_with_pk_prefix(source.epoch, pk_prefix_value)
+                            .await?
+                    }
+                    (false, false) => {
+                        table
+                            .batch_iter_with_pk_bounds(
+                                source.epoch,
+                                pk_prefix_value,
+                                next_col_bounds,
+                            )
+                            .await?
+                    }
+                    (false, true) => {
+                        table
+                            .batch_backward_iter_with_pk_bounds(
+                                source.epoch,
+                                pk_prefix_value,
+                                next_col_bounds,
+                            )
+                            .await?
+                    }
                 };
                 ScanType::RangeScan(iter)
             };
 
-            Ok(Box::new(RowSeqScanExecutor::new(
-                table.schema().clone(),
-                scan_type,
-                RowSeqScanExecutorBuilder::DEFAULT_CHUNK_SIZE,
-                source.task_id.task_id == 0,
-                source.plan_node().get_identity().clone(),
-                batch_stats,
-            )))
+            Ok(Box::new(
+                RowSeqScanExecutor::new(
+                    table.schema().clone(),
+                    scan_type,
+                    RowSeqScanExecutorBuilder::DEFAULT_CHUNK_SIZE,
+                    source.task_id.task_id == 0,
+                    source.plan_node().get_identity().clone(),
+                    batch_stats,
+                )
+                .with_sample(seq_scan_node.sample.clone())
+                .with_limit((seq_scan_node.limit > 0).then(|| seq_scan_node.limit as usize)),
+            ))
         })
     }
 }
@@ -238,20 +335,29 @@ impl<S: StateStore> RowSeqScanExecutor<S> {
     #[try_stream(boxed, ok = DataChunk, error = RwError)]
     async fn do_execute(self: Box<Self>) {
         if !self.should_ignore() {
+            let mut sampler = self.sample.as_ref().map(TableSampler::new).transpose()?;
+            let mut sample = move |chunk: DataChunk| match &mut sampler {
+                Some(sampler) => sampler.sample(chunk),
+                None => Ok(Some(chunk)),
+            };
+            let mut remaining = self.limit.unwrap_or(usize::MAX);
             match self.scan_type {
                 ScanType::TableScan(iter) => {
                     pin_mut!(iter);
-                    loop {
+                    while remaining > 0 {
                         let timer = self.stats.row_seq_scan_next_duration.start_timer();
 
                         let chunk = iter
-                            .collect_data_chunk(&self.schema, Some(self.chunk_size))
+                            .collect_data_chunk(&self.schema, Some(self.chunk_size.min(remaining)))
                             .await
                             .map_err(RwError::from)?;
                         timer.observe_duration();
 
                         if let Some(chunk) = chunk {
-                            yield chunk
+                            remaining -= chunk.cardinality();
+                            if let Some(chunk) = sample(chunk)? {
+                                yield chunk
+                            }
                         } else {
                             break;
                         }
@@ -259,18 +365,21 @@ impl<S: StateStore> RowSeqScanExecutor<S> {
                 }
                 ScanType::RangeScan(iter) => {
                     pin_mut!(iter);
-                    loop {
+                    while remaining > 0 {
                         // TODO: same as TableScan except iter type
                         let timer = self.stats.row_seq_scan_next_duration.start_timer();
 
                         let chunk = iter
-                            .collect_data_chunk(&self.schema, Some(self.chunk_size))
+                            .collect_data_chunk(&self.schema, Some(self.chunk_size.min(remaining)))
                             .await
                             .map_err(RwError::from)?;
                         timer.observe_duration();
 
                         if let Some(chunk) = chunk {
-                            yield chunk
+                            remaining -= chunk.cardinality();
+                            if let Some(chunk) = sample(chunk)? {
+                                yield chunk
+                            }
                         } else {
                             break;
                         }
@@ -278,10 +387,57 @@ impl<S: StateStore> RowSeqScanExecutor<S> {
                 }
                 ScanType::PointGet(row) => {
                     if let Some(row) = row {
-                        yield DataChunk::from_rows(&[row], &self.schema.data_types())?;
+                        let chunk = DataChunk::from_rows(&[row], &self.schema.data_types())?;
+                        if let Some(chunk) = sample(chunk)? {
+                            yield chunk
+                        }
                     }
                 }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::{Array as _, I64Array};
+    use risingwave_common::test_prelude::DataChunkTestExt;
+
+    use super::*;
+
+    fn sample_rows(method: Method, percentage: f64, seed: u64) -> Vec<Option<i64>> {
+        let mut sampler = TableSampler::new(&TableSample {
+            method: method as i32,
+            percentage,
+            seed,
+        })
+        .unwrap();
+        (0..10)
+            .filter_map(|i| {
+                let chunk = DataChunk::from_pretty(&format!("I\n{}\n{}", i * 2, i * 2 + 1));
+                sampler.sample(chunk).unwrap()
+            })
+            .flat_map(|chunk| {
+                let column = chunk.column_at(0).array();
+                let array: &I64Array = column.as_ref().into();
+                array.iter().collect_vec()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_table_sampler() {
+        for method in [Method::System, Method::Bernoulli] {
+            assert!(sample_rows(method, 0.0, 42).is_empty());
+            assert_eq!(sample_rows(method, 100.0, 42).len(), 20);
+            // The same seed gives the same sample.
+            assert_eq!(sample_rows(method, 50.0, 42), sample_rows(method, 50.0, 42));
+        }
+        // A `SYSTEM` sample keeps or drops the rows of a chunk together.
+        let rows = sample_rows(Method::System, 50.0, 42);
+        assert_eq!(rows.len() % 2, 0);
+        for pair in rows.chunks(2) {
+            assert_eq!(pair[0].unwrap() + 1, pair[1].unwrap());
+        }
+    }
+}
@@ -0,0 +1,245 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use futures_async_stream::try_stream;
+use itertools::Itertools;
+use risingwave_common::array::{ArrayBuilder, DataChunk, I64ArrayBuilder, Utf8ArrayBuilder};
+use risingwave_common::catalog::{Field, Schema};
+use risingwave_common::error::{Result, RwError, ToRwResult};
+use risingwave_common::types::DataType;
+use risingwave_connector::lake::unload::{PartWriter, UnloadFormat};
+use risingwave_connector::lake::LakeLocation;
+use risingwave_pb::batch_plan::plan_node::NodeBody;
+use risingwave_pb::batch_plan::unload_node;
+
+use crate::executor::{
+    BoxedDataChunkStream, BoxedExecutor, BoxedExecutorBuilder, Executor, ExecutorBuilder,
+};
+use crate::task::{BatchTaskContext, TaskId};
+
+/// A file is completed and a new one started once it has this many rows.
+const MAX_ROWS_PER_FILE: usize = 1_000_000;
+
+/// [`UnloadExecutor`] writes the rows of its child to part files under `location`, named after
+/// the task so that the tasks of a query never write the same file. It outputs the location and
+/// the row count of each written file once all of them are completed. No file is written if the
+/// child outputs no rows.
+pub struct UnloadExecutor {
+    child: BoxedExecutor,
+    location: String,
+    format: UnloadFormat,
+    /// The names of the columns in the written files.
+    column_names: Vec<String>,
+    /// `part-<query>-<stage>-<task>`, followed by the sequence number of the file.
+    file_prefix: String,
+    schema: Schema,
+    identity: String,
+}
+
+impl UnloadExecutor {
+    pub fn new(
+        child: BoxedExecutor,
+        location: String,
+        format: UnloadFormat,
+        column_names: Vec<String>,
+        task_id: &TaskId,
+        identity: String,
+    ) -> Self {
+        Self {
+            child,
+            location,
+            format,
+            column_names,
+            file_prefix: format!(
+                "part-{}-{}-{}",
+                task_id.query_id, task_id.stage_id, task_id.task_id
+            ),
+            schema: Schema::new(vec![
+                Field::with_name(DataType::Varchar, "path"),
+                Field::with_name(DataType::Int64, "rows"),
+            ]),
+            identity,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl BoxedExecutorBuilder for UnloadExecutor {
+    async fn new_boxed_executor<C: BatchTaskContext>(
+        source: &ExecutorBuilder<C>,
+        mut inputs: Vec<BoxedExecutor>,
+    ) -> Result<BoxedExecutor> {
+        ensure!(inputs.len() == 1, "Unload executor should have 1 child!");
+        let unload_node = try_match_expand!(
+            source.plan_node().get_node_body().unwrap(),
+            NodeBody::Unload
+        )?;
+        let format = match unload_node.get_format()? {
+            unload_node::Format::Parquet => UnloadFormat::Parquet,
+            unload_node::Format::Csv => UnloadFormat::Csv,
+        };
+        Ok(Box::new(Self::new(
+            inputs.remove(0),
+            unload_node.location.clone(),
+            format,
+            unload_node.column_names.clone(),
+            source.task_id,
+            source.plan_node().get_identity().clone(),
+        )))
+    }
+}
+
+impl Executor for UnloadExecutor {
+    fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    fn identity(&self) -> &str {
+        &self.identity
+    }
+
+    fn execute(self: Box<Self>) -> BoxedDataChunkStream {
+        self.do_execute()
+    }
+}
+
+impl UnloadExecutor {
+    #[try_stream(boxed, ok = DataChunk, error = RwError)]
+    async fn do_execute(self: Box<Self>) {
+        let location = LakeLocation::parse(&self.location).to_rw_result()?;
+        let store = location.connect().await;
+        let file_schema = Schema::new(
+            self.child
+                .schema()
+                .fields()
+                .iter()
+                .zip_eq(&self.column_names)
+                .map(|(field, name)| Field::with_name(field.data_type(), name))
+                .collect(),
+        );
+
+        let mut writer: Option<PartWriter> = None;
+        // `(location, rows)` of the completed files.
+        let mut files = vec![];
+
+        #[for_await]
+        for chunk in self.child.execute() {
+            let chunk = chunk?;
+            if chunk.cardinality() == 0 {
+                continue;
+            }
+            if writer.is_none() {
+                let file_name = format!(
+                    "{}-{}.{}",
+                    self.file_prefix,
+                    files.len(),
+                    self.format.extension()
+                );
+                writer = Some(
+                    PartWriter::new(
+                        store.as_ref(),
+                        &format!("{}{}", location.prefix(), file_name),
+                        self.format,
+                        file_schema.clone(),
+                    )
+                    .await
+                    .to_rw_result()?,
+                );
+                files.push((
+                    format!("{}/{}", self.location.trim_end_matches('/'), file_name),
+                    0,
+                ));
+            }
+            let part = writer.as_mut().unwrap();
+            part.write(&chunk).await.to_rw_result()?;
+            if part.rows() >= MAX_ROWS_PER_FILE {
+                let rows = writer.take().unwrap().finish().await.to_rw_result()?;
+                files.last_mut().unwrap().1 = rows;
+            }
+        }
+        if let Some(writer) = writer {
+            let rows = writer.finish().await.to_rw_result()?;
+            files.last_mut().unwrap().1 = rows;
+        }
+
+        if files.is_empty() {
+            return Ok(());
+        }
+        let mut path_builder = Utf8ArrayBuilder::new(files.len())?;
+        let mut rows_builder = I64ArrayBuilder::new(files.len())?;
+        for (path, rows) in &files {
+            path_builder.append(Some(path.as_str()))?;
+            rows_builder.append(Some(*rows as i64))?;
+        }
+        yield DataChunk::new(
+            vec![path_builder.finish()?.into(), rows_builder.finish()?.into()],
+            files.len(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use risingwave_common::array::{Array, DataChunkTestExt};
+    use risingwave_common::catalog::schema_test_utils;
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::executor::test_utils::MockExecutor;
+
+    #[tokio::test]
+    async fn test_unload_executor() {
+        let dir = TempDir::new().unwrap();
+        let location = format!("disk://{}", dir.path().to_str().unwrap());
+
+        let mut mock_executor = MockExecutor::new(schema_test_utils::ii());
+        mock_executor.add(DataChunk::from_pretty(
+            "i i
+             1 2
+             3 4",
+        ));
+        mock_executor.add(DataChunk::from_pretty(
+            "i i
+             5 6",
+        ));
+        let task_id = TaskId {
+            task_id: 2,
+            stage_id: 1,
+            query_id: "q".to_string(),
+        };
+        let executor = Box::new(UnloadExecutor::new(
+            Box::new(mock_executor),
+            location.clone(),
+            UnloadFormat::Csv,
+            vec!["v1".to_string(), "v2".to_string()],
+            &task_id,
+            "UnloadExecutor".to_string(),
+        ));
+
+        let mut stream = executor.execute();
+        let chunk = stream.next().await.unwrap().unwrap();
+        assert_eq!(chunk.cardinality(), 1);
+        let path = format!("{}/part-q-1-2-0.csv", location);
+        assert_eq!(
+            chunk.column_at(0).array().as_utf8().value_at(0),
+            Some(path.as_str())
+        );
+        assert_eq!(chunk.column_at(1).array().as_int64().value_at(0), Some(3));
+        assert!(stream.next().await.is_none());
+
+        let content = std::fs::read_to_string(dir.path().join("part-q-1-2-0.csv")).unwrap();
+        assert_eq!(content, "v1,v2\n1,2\n3,4\n5,6\n");
+    }
+}
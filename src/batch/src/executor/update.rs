@@ -22,7 +22,7 @@ use risingwave_common::error::{ErrorCode, Result, RwError};
 use risingwave_common::types::DataType;
 use risingwave_expr::expr::{build_from_prost, BoxedExpression};
 use risingwave_pb::batch_plan::plan_node::NodeBody;
-use risingwave_source::SourceManagerRef;
+use risingwave_source::{DmlPriority, SourceManagerRef};
 
 use crate::executor::{
     BoxedDataChunkStream, BoxedExecutor, BoxedExecutorBuilder, Executor, ExecutorBuilder,
@@ -133,7 +133,7 @@ impl UpdateExecutor {
 
             let stream_chunk = StreamChunk::new(ops, columns, None);
 
-            let notifier = source.write_chunk(stream_chunk)?;
+            let notifier = source.write_chunk(stream_chunk, DmlPriority::High).await?;
             notifiers.push(notifier);
         }
 
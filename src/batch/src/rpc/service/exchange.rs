@@ -12,8 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use risingwave_common::error::{Result, ToRwResult};
+use prost::Message;
+use risingwave_common::array::compression::compress_columns;
+use risingwave_common::error::{ErrorCode, Result, ToRwResult};
+use risingwave_pb::common::buffer::CompressionType;
 use risingwave_pb::task_service::GetDataResponse;
+use risingwave_rpc_client::permits::Permits;
 use tonic::Status;
 
 type ExchangeDataSender = tokio::sync::mpsc::Sender<std::result::Result<GetDataResponse, Status>>;
@@ -26,13 +30,34 @@ pub trait ExchangeWriter: Send {
 pub struct GrpcExchangeWriter {
     sender: ExchangeDataSender,
     written_chunks: usize,
+    /// Compression of the chunks, requested by the receiver.
+    compression: CompressionType,
+    /// Permits granted by the receiver. Each write waits for the permits of its message.
+    permits: Option<Permits>,
 }
 
 impl GrpcExchangeWriter {
     pub fn new(sender: ExchangeDataSender) -> Self {
+        Self::with_compression(sender, CompressionType::None)
+    }
+
+    pub fn with_compression(sender: ExchangeDataSender, compression: CompressionType) -> Self {
         Self {
             sender,
             written_chunks: 0,
+            compression,
+            permits: None,
+        }
+    }
+
+    pub fn with_flow_control(
+        sender: ExchangeDataSender,
+        compression: CompressionType,
+        permits: Permits,
+    ) -> Self {
+        Self {
+            permits: Some(permits),
+            ..Self::with_compression(sender, compression)
         }
     }
 
@@ -43,8 +68,19 @@ impl GrpcExchangeWriter {
 
 #[async_trait::async_trait]
 impl ExchangeWriter for GrpcExchangeWriter {
-    async fn write(&mut self, data: GetDataResponse) -> Result<()> {
+    async fn write(&mut self, mut data: GetDataResponse) -> Result<()> {
         self.written_chunks += 1;
+        if let Some(chunk) = &mut data.record_batch {
+            compress_columns(&mut chunk.columns, self.compression)?;
+        }
+        if let Some(permits) = &self.permits {
+            if !permits.acquire(data.encoded_len()).await {
+                return Err(ErrorCode::InternalError(
+                    "exchange receiver is gone before taking all data".to_string(),
+                )
+                .into());
+            }
+        }
         self.sender
             .send(Ok(data))
             .await
@@ -54,7 +90,11 @@ impl ExchangeWriter for GrpcExchangeWriter {
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
+    use prost::Message;
     use risingwave_pb::task_service::GetDataResponse;
+    use risingwave_rpc_client::permits::Permits;
 
     use crate::rpc::service::exchange::{ExchangeWriter, GrpcExchangeWriter};
 
@@ -74,4 +114,19 @@ mod tests {
         let res = writer.write(GetDataResponse::default()).await;
         assert!(res.is_err());
     }
+
+    #[tokio::test]
+    async fn test_write_without_permits() {
+        let (tx, _rx) = tokio::sync::mpsc::channel(10);
+        let data = GetDataResponse {
+            record_batch: Some(Default::default()),
+            ..Default::default()
+        };
+        let permits = Permits::new(data.encoded_len() as u32);
+        let mut writer = GrpcExchangeWriter::with_flow_control(tx, Default::default(), permits);
+        writer.write(data.clone()).await.unwrap();
+        // The window is not returned by the receiver, so the writer is blocked.
+        let blocked = tokio::time::timeout(Duration::from_millis(100), writer.write(data));
+        assert!(blocked.await.is_err());
+    }
 }
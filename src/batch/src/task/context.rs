@@ -18,11 +18,13 @@ use risingwave_common::catalog::SysCatalogReaderRef;
 use risingwave_common::error::ErrorCode::InternalError;
 use risingwave_common::error::Result;
 use risingwave_common::util::addr::{is_local_address, HostAddr};
+use risingwave_pb::common::buffer::CompressionType;
+use risingwave_rpc_client::ComputeClientPoolRef;
 use risingwave_source::SourceManagerRef;
 use risingwave_storage::StateStoreImpl;
 
 use crate::executor::BatchMetrics;
-use crate::task::{BatchEnvironment, TaskOutput, TaskOutputId};
+use crate::task::{BatchEnvironment, MemoryContext, TaskOutput, TaskOutputId};
 
 /// Context for batch task execution.
 ///
@@ -39,6 +41,9 @@ pub trait BatchTaskContext: Clone + Send + Sync + 'static {
     /// Whether `peer_addr` is in same as current task.
     fn is_local_addr(&self, peer_addr: &HostAddr) -> bool;
 
+    /// Pool of the clients to other compute nodes, used by remote exchanges.
+    fn client_pool(&self) -> ComputeClientPoolRef;
+
     fn source_manager_ref(&self) -> Option<SourceManagerRef>;
 
     fn try_get_source_manager_ref(&self) -> Result<SourceManagerRef> {
@@ -56,12 +61,23 @@ pub trait BatchTaskContext: Clone + Send + Sync + 'static {
     }
 
     fn stats(&self) -> Arc<BatchMetrics>;
+
+    /// Compression requested for chunks received from remote exchange.
+    fn exchange_compression(&self) -> CompressionType {
+        CompressionType::None
+    }
+
+    /// Memory accounting of the task, shared by its executors.
+    fn memory_context(&self) -> MemoryContext {
+        MemoryContext::default()
+    }
 }
 
 /// Batch task context on compute node.
 #[derive(Clone)]
 pub struct ComputeNodeContext {
     env: BatchEnvironment,
+    memory_context: MemoryContext,
 }
 
 impl BatchTaskContext for ComputeNodeContext {
@@ -79,6 +95,10 @@ impl BatchTaskContext for ComputeNodeContext {
         is_local_address(self.env.server_address(), peer_addr)
     }
 
+    fn client_pool(&self) -> ComputeClientPoolRef {
+        self.env.client_pool()
+    }
+
     fn source_manager_ref(&self) -> Option<SourceManagerRef> {
         Some(self.env.source_manager_ref())
     }
@@ -90,17 +110,27 @@ impl BatchTaskContext for ComputeNodeContext {
     fn stats(&self) -> Arc<BatchMetrics> {
         self.env.stats()
     }
+
+    fn exchange_compression(&self) -> CompressionType {
+        self.env.config().exchange_compression.into()
+    }
+
+    fn memory_context(&self) -> MemoryContext {
+        self.memory_context.clone()
+    }
 }
 
 impl ComputeNodeContext {
     #[cfg(test)]
     pub fn new_for_test() -> Self {
-        Self {
-            env: BatchEnvironment::for_test(),
-        }
+        Self::new(BatchEnvironment::for_test())
     }
 
     pub fn new(env: BatchEnvironment) -> Self {
-        Self { env }
+        let memory_context = MemoryContext::new(env.config().task_memory_limit_bytes);
+        Self {
+            env,
+            memory_context,
+        }
     }
 }
@@ -16,6 +16,7 @@ use std::sync::Arc;
 
 use risingwave_common::config::BatchConfig;
 use risingwave_common::util::addr::HostAddr;
+use risingwave_rpc_client::{ComputeClientPool, ComputeClientPoolRef};
 use risingwave_source::{SourceManager, SourceManagerRef};
 use risingwave_storage::StateStoreImpl;
 
@@ -48,6 +49,9 @@ pub struct BatchEnvironment {
 
     /// Statistics.
     stats: Arc<BatchMetrics>,
+
+    /// Clients to other compute nodes, shared with the streaming remote inputs.
+    client_pool: ComputeClientPoolRef,
 }
 
 impl BatchEnvironment {
@@ -59,6 +63,7 @@ impl BatchEnvironment {
         worker_id: WorkerNodeId,
         state_store: StateStoreImpl,
         stats: Arc<BatchMetrics>,
+        client_pool: ComputeClientPoolRef,
     ) -> Self {
         BatchEnvironment {
             server_addr,
@@ -68,6 +73,7 @@ impl BatchEnvironment {
             worker_id,
             state_store,
             stats,
+            client_pool,
         }
     }
 
@@ -87,6 +93,7 @@ impl BatchEnvironment {
                 StateStoreMetrics::unused(),
             )),
             stats: Arc::new(BatchMetrics::unused()),
+            client_pool: Arc::new(ComputeClientPool::default()),
         }
     }
 
@@ -121,4 +128,8 @@ impl BatchEnvironment {
     pub fn stats(&self) -> Arc<BatchMetrics> {
         self.stats.clone()
     }
+
+    pub fn client_pool(&self) -> ComputeClientPoolRef {
+        self.client_pool.clone()
+    }
 }
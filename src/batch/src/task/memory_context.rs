@@ -0,0 +1,179 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Write;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use itertools::Itertools;
+use parking_lot::Mutex;
+use risingwave_common::error::{ErrorCode, Result};
+
+/// Tracks the memory used by the operators of a batch task against the memory limit of the task.
+///
+/// Operators holding data for the whole input, such as hash tables, sort buffers and exchange
+/// buffers, account the memory they hold with an [`OperatorMemory`]. A task exceeding its limit
+/// fails with an error listing the memory used by each operator instead of growing until the
+/// compute node is killed. There's no spilling to disk yet, so exceeding the limit always fails
+/// the task.
+#[derive(Clone, Default)]
+pub struct MemoryContext {
+    inner: Arc<MemoryContextInner>,
+}
+
+#[derive(Default)]
+struct MemoryContextInner {
+    /// Limit of the memory used by the task in bytes. 0 means unlimited.
+    limit: usize,
+
+    /// Memory used by all operators of the task.
+    used: AtomicUsize,
+
+    /// Memory used by each operator, by the identity of the operator.
+    operators: Mutex<Vec<(String, Arc<AtomicUsize>)>>,
+}
+
+impl MemoryContext {
+    /// Creates the memory context of a task which may use at most `limit` bytes. 0 means
+    /// unlimited.
+    pub fn new(limit: usize) -> Self {
+        Self {
+            inner: Arc::new(MemoryContextInner {
+                limit,
+                ..Default::default()
+            }),
+        }
+    }
+
+    pub fn limit(&self) -> usize {
+        self.inner.limit
+    }
+
+    /// Memory used by all operators of the task in bytes.
+    pub fn used(&self) -> usize {
+        self.inner.used.load(Ordering::Relaxed)
+    }
+
+    /// Returns the accounting of the memory used by the operator `identity`.
+    pub fn operator(&self, identity: impl Into<String>) -> OperatorMemory {
+        let used = Arc::new(AtomicUsize::new(0));
+        self.inner
+            .operators
+            .lock()
+            .push((identity.into(), used.clone()));
+        OperatorMemory {
+            context: self.clone(),
+            used,
+        }
+    }
+
+    /// Memory used by each operator holding memory, from the largest.
+    pub fn breakdown(&self) -> Vec<(String, usize)> {
+        self.inner
+            .operators
+            .lock()
+            .iter()
+            .map(|(identity, used)| (identity.clone(), used.load(Ordering::Relaxed)))
+            .filter(|(_, used)| *used > 0)
+            .sorted_by(|(_, a), (_, b)| b.cmp(a))
+            .collect()
+    }
+
+    fn limit_exceeded(&self) -> ErrorCode {
+        let mut message = format!(
+            "batch task used {} bytes of memory exceeding its limit of {} bytes, used by",
+            self.used(),
+            self.limit()
+        );
+        for (identity, used) in self.breakdown() {
+            write!(message, " {}: {} bytes,", identity, used).unwrap();
+        }
+        message.pop();
+        ErrorCode::ResourceLimitExceeded(message)
+    }
+}
+
+/// Memory used by one operator of a batch task, which is released when dropped.
+pub struct OperatorMemory {
+    context: MemoryContext,
+    used: Arc<AtomicUsize>,
+}
+
+impl OperatorMemory {
+    /// Accounts `bytes` more memory held by the operator. Returns an error if the task exceeds
+    /// its memory limit.
+    pub fn add(&mut self, bytes: usize) -> Result<()> {
+        self.used.fetch_add(bytes, Ordering::Relaxed);
+        let used = self.context.inner.used.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        let limit = self.context.limit();
+        if limit > 0 && used > limit {
+            return Err(self.context.limit_exceeded().into());
+        }
+        Ok(())
+    }
+
+    /// Releases `bytes` of memory no longer held by the operator.
+    pub fn release(&mut self, bytes: usize) {
+        let bytes = bytes.min(self.used());
+        self.used.fetch_sub(bytes, Ordering::Relaxed);
+        self.context.inner.used.fetch_sub(bytes, Ordering::Relaxed);
+    }
+
+    /// Memory held by the operator in bytes.
+    pub fn used(&self) -> usize {
+        self.used.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for OperatorMemory {
+    fn drop(&mut self) {
+        self.release(self.used());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_context() {
+        let context = MemoryContext::new(100);
+        let mut agg = context.operator("HashAgg 1");
+        let mut sort = context.operator("OrderBy 2");
+        agg.add(30).unwrap();
+        sort.add(60).unwrap();
+        assert_eq!(context.used(), 90);
+
+        let err = agg.add(20).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Resource limit exceeded: batch task used 110 bytes of memory exceeding its limit of \
+             100 bytes, used by OrderBy 2: 60 bytes, HashAgg 1: 50 bytes"
+        );
+
+        agg.release(50);
+        assert_eq!(context.used(), 60);
+        drop(sort);
+        assert_eq!(context.used(), 0);
+        assert!(context.breakdown().is_empty());
+    }
+
+    #[test]
+    fn test_unlimited() {
+        let context = MemoryContext::default();
+        let mut op = context.operator("HashJoin 1");
+        op.add(usize::MAX / 2).unwrap();
+        assert_eq!(context.used(), usize::MAX / 2);
+    }
+}
@@ -14,6 +14,7 @@
 
 pub use context::*;
 pub use env::*;
+pub use memory_context::*;
 pub use task_execution::*;
 pub use task_manager::*;
 
@@ -24,6 +25,7 @@ mod data_chunk_in_channel;
 mod env;
 mod fifo_channel;
 mod hash_shuffle_channel;
+mod memory_context;
 mod task_execution;
 mod task_manager;
 
@@ -22,7 +22,9 @@ use risingwave_common::error::{Result, RwError};
 use risingwave_pb::batch_plan::{
     PlanFragment, TaskId as ProstTaskId, TaskOutputId as ProstTaskOutputId,
 };
+use risingwave_pb::common::buffer::CompressionType;
 use risingwave_pb::task_service::GetDataResponse;
+use risingwave_rpc_client::permits::Permits;
 use tokio::sync::mpsc::Sender;
 use tonic::Status;
 
@@ -73,12 +75,19 @@ impl BatchManager {
         tx: Sender<std::result::Result<GetDataResponse, Status>>,
         peer_addr: SocketAddr,
         pb_task_output_id: &ProstTaskOutputId,
+        compression: CompressionType,
+        permits: Option<Permits>,
     ) -> Result<()> {
         let task_id = TaskOutputId::try_from(pb_task_output_id)?;
         tracing::trace!(target: "events::compute::exchange", peer_addr = %peer_addr, from = ?task_id, "serve exchange RPC");
         let mut task_output = self.take_output(pb_task_output_id)?;
         tokio::spawn(async move {
-            let mut writer = GrpcExchangeWriter::new(tx.clone());
+            let mut writer = match permits {
+                Some(permits) => {
+                    GrpcExchangeWriter::with_flow_control(tx.clone(), compression, permits)
+                }
+                None => GrpcExchangeWriter::with_compression(tx.clone(), compression),
+            };
             match task_output.take_data(&mut writer).await {
                 Ok(_) => {
                     tracing::trace!(
@@ -157,6 +157,9 @@ async fn main() {
         local_object_store: "memory".to_string(),
         share_buffer_compaction_worker_threads_number: 1,
         share_buffer_upload_concurrency: 4,
+        shared_buffer_compression_algorithm: "None".to_string(),
+        shared_buffer_compression_level: 0,
+        scan_prefetch_block_count: 0,
     });
 
     let (_env, hummock_manager_ref, _cluster_manager_ref, worker_node) =
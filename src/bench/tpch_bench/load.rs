@@ -0,0 +1,83 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Instant;
+
+use anyhow::Result;
+use itertools::Itertools;
+use risingwave_connector::datagen::tpch::{TpchGenerator, TpchRow, TpchTable};
+use serde_json::Value;
+use tokio_postgres::Client;
+
+use crate::Opts;
+
+pub(crate) async fn create_tables(client: &Client, ddl: &str) -> Result<()> {
+    for table in TpchTable::ALL {
+        // `DROP TABLE` fails if the table does not exist, which is expected for a fresh cluster.
+        let _ = client
+            .simple_query(&format!("DROP TABLE {};", table.name()))
+            .await;
+    }
+    client.simple_query(ddl).await?;
+    Ok(())
+}
+
+fn literal(value: &Value) -> String {
+    match value {
+        Value::String(s) => format!("'{}'", s.replace('\'', "''")),
+        Value::Null => "NULL".to_string(),
+        v => v.to_string(),
+    }
+}
+
+fn insert_statement(table: TpchTable, rows: &[TpchRow]) -> String {
+    format!(
+        "INSERT INTO {} ({}) VALUES {};",
+        table.name(),
+        table.columns().join(", "),
+        rows.iter()
+            .map(|row| format!("({})", row.iter().map(literal).join(", ")))
+            .join(", ")
+    )
+}
+
+/// Generates the data of all tables and inserts it in batches.
+pub(crate) async fn load_tables(client: &Client, opts: &Opts) -> Result<()> {
+    let generator = TpchGenerator::new(opts.scale_factor, opts.seed);
+    for table in TpchTable::ALL {
+        let start = Instant::now();
+        let mut rows = Vec::with_capacity(opts.batch_size);
+        let mut row_count = 0;
+        for offset in 0..generator.row_count(table) {
+            rows.extend(generator.generate(table, offset));
+            if rows.len() >= opts.batch_size {
+                client.simple_query(&insert_statement(table, &rows)).await?;
+                row_count += rows.len();
+                rows.clear();
+            }
+        }
+        if !rows.is_empty() {
+            client.simple_query(&insert_statement(table, &rows)).await?;
+            row_count += rows.len();
+        }
+        println!(
+            "loaded {} rows into {} in {:?}",
+            row_count,
+            table.name(),
+            start.elapsed()
+        );
+    }
+    client.simple_query("FLUSH;").await?;
+    Ok(())
+}
@@ -0,0 +1,133 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod load;
+mod report;
+mod run;
+
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use report::Report;
+use tokio_postgres::NoTls;
+
+#[derive(Parser, Debug)]
+pub(crate) struct Opts {
+    // ----- connection -----
+    #[clap(long, default_value = "localhost")]
+    host: String,
+
+    #[clap(long, default_value_t = 4566)]
+    port: u16,
+
+    #[clap(long, default_value = "dev")]
+    db: String,
+
+    #[clap(long, default_value = "root")]
+    user: String,
+
+    // ----- data -----
+    /// Scale factor of the generated data. 1 means about 1GB of data.
+    #[clap(long, default_value_t = 0.01)]
+    scale_factor: f64,
+
+    #[clap(long, default_value_t = 0)]
+    seed: u64,
+
+    /// Number of rows in one `INSERT` statement.
+    #[clap(long, default_value_t = 1000)]
+    batch_size: usize,
+
+    /// Do not create and load the tables, e.g. if they are loaded by a previous run.
+    #[clap(long)]
+    skip_load: bool,
+
+    // ----- queries -----
+    /// Planner test file containing the DDL (`create_tables`) and the queries to run.
+    #[clap(
+        long,
+        default_value = "src/frontend/test_runner/tests/testdata/tpch.yaml"
+    )]
+    queries: String,
+
+    /// Comma separated ids of the queries to run, e.g. `tpch_q1,tpch_q3`. All queries are run if
+    /// not specified.
+    #[clap(long)]
+    filter: Option<String>,
+
+    #[clap(long, default_value_t = 1)]
+    warmup: usize,
+
+    #[clap(long, default_value_t = 3)]
+    iterations: usize,
+
+    // ----- report -----
+    /// Path to write the JSON report to.
+    #[clap(long, default_value = "tpch-bench-report.json")]
+    report: String,
+
+    /// Report of a previous run to compare with.
+    #[clap(long)]
+    baseline: Option<String>,
+
+    /// A query is regarded as regressed if its mean latency grows by more than this ratio.
+    #[clap(long, default_value_t = 0.1)]
+    regression_threshold: f64,
+
+    /// Exit with an error if any query fails or regresses.
+    #[clap(long)]
+    fail_on_regression: bool,
+}
+
+/// This is used to benchmark the batch engine with TPC-H queries against a running cluster.
+/// For usage, see `README.md`
+#[tokio::main(flavor = "multi_thread")]
+async fn main() -> Result<()> {
+    let opts = Opts::parse();
+    println!("Configurations:\n {:?}", &opts);
+
+    let (client, connection) = tokio_postgres::Config::new()
+        .host(&opts.host)
+        .port(opts.port)
+        .dbname(&opts.db)
+        .user(&opts.user)
+        .connect(NoTls)
+        .await?;
+    tokio::spawn(async move {
+        if let Err(e) = connection.await {
+            eprintln!("connection error: {}", e);
+        }
+    });
+
+    let suite = run::Suite::from_file(&opts.queries, opts.filter.as_deref())?;
+    if !opts.skip_load {
+        load::create_tables(&client, &suite.create_tables).await?;
+        load::load_tables(&client, &opts).await?;
+    }
+
+    let report = run::run_queries(&client, &suite, &opts).await;
+    report.print();
+    report.write(&opts.report)?;
+
+    let mut ok = report.all_succeeded();
+    if let Some(baseline) = &opts.baseline {
+        let baseline = Report::read(baseline)?;
+        let comparison = report.compare(&baseline, opts.regression_threshold);
+        comparison.print();
+        ok &= comparison.regressions().next().is_none();
+    }
+    if opts.fail_on_regression && !ok {
+        return Err(anyhow!("some queries failed or regressed"));
+    }
+    Ok(())
+}
@@ -0,0 +1,190 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// 64-bit FNV-1a hash, unlike `DefaultHasher` whose algorithm may change between Rust releases.
+fn fnv1a_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct QueryReport {
+    pub id: String,
+    pub rows: usize,
+    pub latencies_ms: Vec<f64>,
+    /// Hash of the plan, so that plan changes can be spotted without diffing `plan`. It's stable
+    /// across builds and platforms, as reports of different builds are compared.
+    pub plan_hash: u64,
+    pub plan: String,
+    pub error: Option<String>,
+}
+
+impl QueryReport {
+    pub fn new(id: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            rows: 0,
+            latencies_ms: vec![],
+            plan_hash: 0,
+            plan: String::new(),
+            error: None,
+        }
+    }
+
+    pub fn set_plan(&mut self, plan: String) {
+        self.plan_hash = fnv1a_hash(plan.as_bytes());
+        self.plan = plan;
+    }
+
+    pub fn mean_ms(&self) -> Option<f64> {
+        if self.latencies_ms.is_empty() {
+            None
+        } else {
+            Some(self.latencies_ms.iter().sum::<f64>() / self.latencies_ms.len() as f64)
+        }
+    }
+
+    fn min_ms(&self) -> f64 {
+        self.latencies_ms.iter().cloned().fold(f64::NAN, f64::min)
+    }
+
+    fn max_ms(&self) -> f64 {
+        self.latencies_ms.iter().cloned().fold(f64::NAN, f64::max)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct Report {
+    pub scale_factor: f64,
+    pub queries: Vec<QueryReport>,
+}
+
+impl Report {
+    pub fn new(scale_factor: f64) -> Self {
+        Self {
+            scale_factor,
+            queries: vec![],
+        }
+    }
+
+    pub fn read(path: &str) -> Result<Self> {
+        Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+    }
+
+    pub fn write(&self, path: &str) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn all_succeeded(&self) -> bool {
+        self.queries.iter().all(|q| q.error.is_none())
+    }
+
+    pub fn print(&self) {
+        println!("scale factor: {}", self.scale_factor);
+        println!(
+            "{:<12} {:>8} {:>12} {:>12} {:>12}  plan hash",
+            "query", "rows", "mean(ms)", "min(ms)", "max(ms)"
+        );
+        for q in &self.queries {
+            match (&q.error, q.mean_ms()) {
+                (Some(e), _) => println!("{:<12} failed: {}", q.id, e),
+                (None, Some(mean)) => println!(
+                    "{:<12} {:>8} {:>12.2} {:>12.2} {:>12.2}  {:016x}",
+                    q.id,
+                    q.rows,
+                    mean,
+                    q.min_ms(),
+                    q.max_ms(),
+                    q.plan_hash
+                ),
+                (None, None) => println!("{:<12} not run", q.id),
+            }
+        }
+    }
+
+    /// Compares the mean latency and plan of each query with `baseline`. Queries that are not in
+    /// both reports are skipped.
+    pub fn compare(&self, baseline: &Report, threshold: f64) -> Comparison {
+        let baseline: HashMap<_, _> = baseline.queries.iter().map(|q| (&q.id, q)).collect();
+        let entries = self
+            .queries
+            .iter()
+            .filter_map(|current| {
+                let base = baseline.get(&current.id)?;
+                let (base_ms, current_ms) = (base.mean_ms()?, current.mean_ms()?);
+                let ratio = current_ms / base_ms;
+                Some(ComparisonEntry {
+                    id: current.id.clone(),
+                    baseline_ms: base_ms,
+                    current_ms,
+                    ratio,
+                    plan_changed: base.plan != current.plan,
+                    regressed: ratio > 1.0 + threshold,
+                })
+            })
+            .collect();
+        Comparison { entries }
+    }
+}
+
+pub(crate) struct ComparisonEntry {
+    pub id: String,
+    pub baseline_ms: f64,
+    pub current_ms: f64,
+    pub ratio: f64,
+    pub plan_changed: bool,
+    pub regressed: bool,
+}
+
+pub(crate) struct Comparison {
+    pub entries: Vec<ComparisonEntry>,
+}
+
+impl Comparison {
+    pub fn regressions(&self) -> impl Iterator<Item = &ComparisonEntry> {
+        self.entries.iter().filter(|e| e.regressed)
+    }
+
+    pub fn print(&self) {
+        println!(
+            "{:<12} {:>14} {:>14} {:>8}  note",
+            "query", "baseline(ms)", "current(ms)", "ratio"
+        );
+        for e in &self.entries {
+            let mut notes = vec![];
+            if e.regressed {
+                notes.push("REGRESSED");
+            }
+            if e.plan_changed {
+                notes.push("plan changed");
+            }
+            println!(
+                "{:<12} {:>14.2} {:>14.2} {:>8.2}  {}",
+                e.id,
+                e.baseline_ms,
+                e.current_ms,
+                e.ratio,
+                notes.join(", ")
+            );
+        }
+        println!("{} regressed queries", self.regressions().count());
+    }
+}
@@ -0,0 +1,118 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Instant;
+
+use anyhow::{anyhow, Result};
+use itertools::Itertools;
+use serde::Deserialize;
+use tokio_postgres::{Client, SimpleQueryMessage};
+
+use crate::report::{QueryReport, Report};
+use crate::Opts;
+
+const CREATE_TABLES_ID: &str = "create_tables";
+
+/// A case of the planner test file. Other fields like the expected plans are ignored.
+#[derive(Deserialize)]
+struct TestCase {
+    id: Option<String>,
+    sql: String,
+}
+
+pub(crate) struct Suite {
+    pub create_tables: String,
+    /// Ids and SQLs of the queries.
+    pub queries: Vec<(String, String)>,
+}
+
+impl Suite {
+    pub fn from_file(path: &str, filter: Option<&str>) -> Result<Self> {
+        let cases: Vec<TestCase> = serde_yaml::from_str(&std::fs::read_to_string(path)?)?;
+        let filter = filter.map(|filter| filter.split(',').map(str::trim).collect_vec());
+
+        let mut create_tables = None;
+        let mut queries = vec![];
+        for case in cases {
+            match case.id {
+                Some(id) if id == CREATE_TABLES_ID => create_tables = Some(case.sql),
+                Some(id) => {
+                    if filter.as_ref().map_or(true, |f| f.contains(&id.as_str())) {
+                        queries.push((id, case.sql));
+                    }
+                }
+                None => {}
+            }
+        }
+        Ok(Self {
+            create_tables: create_tables
+                .ok_or_else(|| anyhow!("no `{}` case in {}", CREATE_TABLES_ID, path))?,
+            queries,
+        })
+    }
+}
+
+/// Runs the query and returns the number of rows in the result.
+async fn run_query(client: &Client, sql: &str) -> Result<usize> {
+    let messages = client.simple_query(sql).await?;
+    Ok(messages
+        .iter()
+        .filter(|m| matches!(m, SimpleQueryMessage::Row(_)))
+        .count())
+}
+
+async fn explain(client: &Client, sql: &str) -> Result<String> {
+    let messages = client.simple_query(&format!("EXPLAIN {}", sql)).await?;
+    Ok(messages
+        .iter()
+        .filter_map(|m| match m {
+            SimpleQueryMessage::Row(row) => row.get(0).map(str::to_string),
+            _ => None,
+        })
+        .join("\n"))
+}
+
+async fn bench_query(client: &Client, id: &str, sql: &str, opts: &Opts) -> QueryReport {
+    let mut report = QueryReport::new(id);
+    let result: Result<()> = async {
+        report.set_plan(explain(client, sql).await?);
+        for _ in 0..opts.warmup {
+            run_query(client, sql).await?;
+        }
+        for _ in 0..opts.iterations {
+            let start = Instant::now();
+            report.rows = run_query(client, sql).await?;
+            report
+                .latencies_ms
+                .push(start.elapsed().as_secs_f64() * 1000.0);
+        }
+        Ok(())
+    }
+    .await;
+    if let Err(e) = result {
+        report.error = Some(e.to_string());
+    }
+    report
+}
+
+pub(crate) async fn run_queries(client: &Client, suite: &Suite, opts: &Opts) -> Report {
+    let mut report = Report::new(opts.scale_factor);
+    for (id, sql) in &suite.queries {
+        println!("running {}", id);
+        report
+            .queries
+            .push(bench_query(client, id, sql, opts).await);
+    }
+    report
+}
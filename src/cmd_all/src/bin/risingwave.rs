@@ -27,7 +27,7 @@ use std::pin::Pin;
 
 use anyhow::{bail, Result};
 use clap::StructOpt;
-use risingwave_cmd_all::playground;
+use risingwave_cmd_all::{playground, PlaygroundOpts};
 
 type RwFns =
     HashMap<&'static str, Box<dyn Fn(Vec<String>) -> Box<dyn Future<Output = Result<()>>>>>;
@@ -138,10 +138,12 @@ async fn main() -> Result<()> {
     );
 
     // playground
-    for fn_name in ["play", "playground"] {
+    for fn_name in ["play", "playground", "standalone"] {
         fns.insert(
             fn_name,
-            Box::new(|_: Vec<String>| Box::new(async move { playground().await })),
+            Box::new(|args: Vec<String>| {
+                Box::new(async move { playground(PlaygroundOpts::parse_from(args)).await })
+            }),
         );
     }
 
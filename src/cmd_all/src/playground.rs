@@ -17,7 +17,7 @@ use std::ffi::OsString;
 use std::process::Command;
 
 use anyhow::{anyhow, Result};
-use clap::StructOpt;
+use clap::{Parser, StructOpt};
 use risedev::{
     CompactorService, ComputeNodeService, ConfigExpander, FrontendService, MetaNodeService,
     ServiceConfig,
@@ -50,14 +50,52 @@ pub enum RisingWaveService {
     Compactor(Vec<OsString>),
 }
 
-pub async fn playground() -> Result<()> {
+/// Command-line arguments for the playground (a.k.a. standalone) mode, which runs all node roles
+/// in one process.
+#[derive(Parser, Debug)]
+pub struct PlaygroundOpts {
+    /// The risedev profile in `risedev.yml` under the working directory to launch the services
+    /// with, defaults to `PLAYGROUND_PROFILE` or `playground`. All components are started with
+    /// the default options if `risedev.yml` is not found.
+    #[clap(long)]
+    pub profile: Option<String>,
+
+    /// Stores the data of the state store in this local directory instead of in memory. Note
+    /// that the metadata is still kept in memory, so the data can't be recovered after restart.
+    #[clap(long)]
+    pub data_dir: Option<String>,
+}
+
+impl PlaygroundOpts {
+    fn state_store(&self) -> String {
+        match &self.data_dir {
+            Some(dir) => format!("hummock+disk://{}", dir),
+            None => "hummock+memory".to_string(),
+        }
+    }
+}
+
+/// Overrides `--state-store` of the compute node if `--data-dir` is specified.
+fn apply_data_dir(args: &mut Vec<OsString>, opts: &PlaygroundOpts) {
+    if opts.data_dir.is_none() {
+        return;
+    }
+    match args.iter().position(|arg| arg == "--state-store") {
+        Some(idx) if idx + 1 < args.len() => args[idx + 1] = opts.state_store().into(),
+        _ => args.extend(["--state-store".into(), opts.state_store().into()]),
+    }
+}
+
+pub async fn playground(opts: PlaygroundOpts) -> Result<()> {
     eprintln!("launching playground");
 
     risingwave_logging::oneshot_common();
     risingwave_logging::init_risingwave_logger(false, true);
 
-    let profile = if let Ok(profile) = std::env::var("PLAYGROUND_PROFILE") {
-        profile.to_string()
+    let profile = if let Some(profile) = &opts.profile {
+        profile.clone()
+    } else if let Ok(profile) = std::env::var("PLAYGROUND_PROFILE") {
+        profile
     } else {
         "playground".to_string()
     };
@@ -75,9 +113,9 @@ pub async fn playground() -> Result<()> {
                     ServiceConfig::ComputeNode(c) => {
                         let mut command = Command::new("compute-node");
                         ComputeNodeService::apply_command_args(&mut command, c)?;
-                        rw_services.push(RisingWaveService::Compute(
-                            command.get_args().map(ToOwned::to_owned).collect(),
-                        ));
+                        let mut args = command.get_args().map(ToOwned::to_owned).collect();
+                        apply_data_dir(&mut args, &opts);
+                        rw_services.push(RisingWaveService::Compute(args));
                     }
                     ServiceConfig::MetaNode(c) => {
                         let mut command = Command::new("meta-node");
@@ -111,7 +149,7 @@ pub async fn playground() -> Result<()> {
             tracing::warn!("Failed to load risedev config. All components will be started using the default command line options.\n{}", e);
             vec![
                 RisingWaveService::Meta(vec!["--backend".into(), "mem".into()]),
-                RisingWaveService::Compute(vec!["--state-store".into(), "hummock+memory".into()]),
+                RisingWaveService::Compute(vec!["--state-store".into(), opts.state_store().into()]),
                 RisingWaveService::Frontend(vec![]),
             ]
         }
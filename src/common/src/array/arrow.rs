@@ -0,0 +1,335 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Conversion between [`DataChunk`] and Apache Arrow [`RecordBatch`].
+//!
+//! Fixed-size values are copied into Arrow buffers in a single pass, and strings are copied
+//! together with their offsets. `TIMESTAMP`, `TIME` and `TIMESTAMP WITH TIME ZONE` are mapped to
+//! microsecond precision, which is the precision of these types in SQL.
+
+use std::sync::Arc;
+
+use arrow::array::{
+    Array as ArrowArray, ArrayRef as ArrowArrayRef, BooleanArray, Date32Array, Float32Array,
+    Float64Array, Int16Array, Int32Array, Int64Array, StringArray, Time64MicrosecondArray,
+    TimestampMicrosecondArray,
+};
+use arrow::datatypes::{
+    DataType as ArrowDataType, Field as ArrowField, Schema as ArrowSchema, TimeUnit,
+};
+use arrow::record_batch::RecordBatch;
+use chrono::{Datelike, NaiveDate, NaiveTime, Timelike};
+use itertools::Itertools;
+
+use crate::array::error::anyhow;
+use crate::array::{
+    Array, ArrayBuilder, ArrayImpl, ArrayResult, BoolArrayBuilder, Column, DataChunk,
+    F32ArrayBuilder, F64ArrayBuilder, I16ArrayBuilder, I32ArrayBuilder, I64ArrayBuilder,
+    NaiveDateArrayBuilder, NaiveDateTimeArrayBuilder, NaiveTimeArrayBuilder, Utf8ArrayBuilder,
+};
+use crate::catalog::{Field, Schema};
+use crate::types::{
+    DataType, NaiveDateTimeWrapper, NaiveDateWrapper, NaiveTimeWrapper, OrderedF32, OrderedF64,
+    UNIX_EPOCH_DAYS,
+};
+
+/// Time zone of Arrow timestamps converted from `TIMESTAMP WITH TIME ZONE`, whose values are
+/// always stored in UTC.
+const UTC: &str = "+00:00";
+
+impl TryFrom<&DataType> for ArrowDataType {
+    type Error = crate::array::ArrayError;
+
+    fn try_from(data_type: &DataType) -> ArrayResult<Self> {
+        Ok(match data_type {
+            DataType::Boolean => ArrowDataType::Boolean,
+            DataType::Int16 => ArrowDataType::Int16,
+            DataType::Int32 => ArrowDataType::Int32,
+            DataType::Int64 => ArrowDataType::Int64,
+            DataType::Float32 => ArrowDataType::Float32,
+            DataType::Float64 => ArrowDataType::Float64,
+            DataType::Varchar => ArrowDataType::Utf8,
+            DataType::Date => ArrowDataType::Date32,
+            DataType::Time => ArrowDataType::Time64(TimeUnit::Microsecond),
+            DataType::Timestamp => ArrowDataType::Timestamp(TimeUnit::Microsecond, None),
+            DataType::Timestampz => {
+                ArrowDataType::Timestamp(TimeUnit::Microsecond, Some(UTC.to_string()))
+            }
+            _ => return Err(anyhow!("data type {:?} is not supported by arrow", data_type).into()),
+        })
+    }
+}
+
+impl TryFrom<&ArrowDataType> for DataType {
+    type Error = crate::array::ArrayError;
+
+    fn try_from(data_type: &ArrowDataType) -> ArrayResult<Self> {
+        Ok(match data_type {
+            ArrowDataType::Boolean => DataType::Boolean,
+            ArrowDataType::Int16 => DataType::Int16,
+            ArrowDataType::Int32 => DataType::Int32,
+            ArrowDataType::Int64 => DataType::Int64,
+            ArrowDataType::Float32 => DataType::Float32,
+            ArrowDataType::Float64 => DataType::Float64,
+            ArrowDataType::Utf8 => DataType::Varchar,
+            ArrowDataType::Date32 => DataType::Date,
+            ArrowDataType::Time64(TimeUnit::Microsecond) => DataType::Time,
+            ArrowDataType::Timestamp(TimeUnit::Microsecond, None) => DataType::Timestamp,
+            ArrowDataType::Timestamp(TimeUnit::Microsecond, Some(_)) => DataType::Timestampz,
+            _ => {
+                return Err(anyhow!("arrow data type {:?} is not supported", data_type).into());
+            }
+        })
+    }
+}
+
+/// Converts the schema of a chunk to an Arrow schema. All fields are nullable.
+pub fn to_arrow_schema(schema: &Schema) -> ArrayResult<ArrowSchema> {
+    let fields = schema
+        .fields()
+        .iter()
+        .map(|field| {
+            Ok(ArrowField::new(
+                &field.name,
+                (&field.data_type).try_into()?,
+                true,
+            ))
+        })
+        .collect::<ArrayResult<Vec<_>>>()?;
+    Ok(ArrowSchema::new(fields))
+}
+
+pub fn from_arrow_schema(schema: &ArrowSchema) -> ArrayResult<Schema> {
+    let fields = schema
+        .fields()
+        .iter()
+        .map(|field| {
+            Ok(Field::with_name(
+                field.data_type().try_into()?,
+                field.name(),
+            ))
+        })
+        .collect::<ArrayResult<Vec<_>>>()?;
+    Ok(Schema::new(fields))
+}
+
+fn date_to_days(date: NaiveDateWrapper) -> i32 {
+    date.0.num_days_from_ce() - UNIX_EPOCH_DAYS
+}
+
+fn time_to_micros(time: NaiveTimeWrapper) -> i64 {
+    time.0.num_seconds_from_midnight() as i64 * 1_000_000 + time.0.nanosecond() as i64 / 1_000
+}
+
+fn datetime_to_micros(datetime: NaiveDateTimeWrapper) -> i64 {
+    datetime.0.timestamp() * 1_000_000 + datetime.0.timestamp_subsec_micros() as i64
+}
+
+/// Converts an array to an Arrow array of type `data_type`. The data type is required because
+/// `TIMESTAMP WITH TIME ZONE` is stored in an [`ArrayImpl::Int64`].
+pub fn to_arrow_array(array: &ArrayImpl, data_type: &DataType) -> ArrayResult<ArrowArrayRef> {
+    Ok(match (array, data_type) {
+        (ArrayImpl::Bool(array), _) => Arc::new(BooleanArray::from_iter(array.iter())),
+        (ArrayImpl::Int16(array), _) => Arc::new(Int16Array::from_iter(array.iter())),
+        (ArrayImpl::Int32(array), _) => Arc::new(Int32Array::from_iter(array.iter())),
+        (ArrayImpl::Int64(array), DataType::Timestampz) => Arc::new(
+            TimestampMicrosecondArray::from_iter(array.iter()).with_timezone(UTC.to_string()),
+        ),
+        (ArrayImpl::Int64(array), _) => Arc::new(Int64Array::from_iter(array.iter())),
+        (ArrayImpl::Float32(array), _) => Arc::new(Float32Array::from_iter(
+            array.iter().map(|v| v.map(|v| v.0)),
+        )),
+        (ArrayImpl::Float64(array), _) => Arc::new(Float64Array::from_iter(
+            array.iter().map(|v| v.map(|v| v.0)),
+        )),
+        (ArrayImpl::Utf8(array), _) => Arc::new(StringArray::from_iter(array.iter())),
+        (ArrayImpl::NaiveDate(array), _) => Arc::new(Date32Array::from_iter(
+            array.iter().map(|v| v.map(date_to_days)),
+        )),
+        (ArrayImpl::NaiveTime(array), _) => Arc::new(Time64MicrosecondArray::from_iter(
+            array.iter().map(|v| v.map(time_to_micros)),
+        )),
+        (ArrayImpl::NaiveDateTime(array), _) => Arc::new(TimestampMicrosecondArray::from_iter(
+            array.iter().map(|v| v.map(datetime_to_micros)),
+        )),
+        _ => {
+            return Err(anyhow!("array of {} is not supported by arrow", array.get_ident()).into())
+        }
+    })
+}
+
+macro_rules! downcast {
+    ($array:expr, $ty:ty) => {
+        $array
+            .as_any()
+            .downcast_ref::<$ty>()
+            .ok_or_else(|| anyhow!("failed to downcast arrow array to {}", stringify!($ty)))?
+    };
+}
+
+/// Appends each value of `iter` to a new array builder of type `$builder`.
+macro_rules! build {
+    ($builder:ty, $len:expr, $iter:expr) => {{
+        let mut builder = <$builder>::new($len)?;
+        for value in $iter {
+            builder.append(value)?;
+        }
+        ArrayImpl::from(builder.finish()?)
+    }};
+}
+
+pub fn from_arrow_array(array: &dyn ArrowArray) -> ArrayResult<ArrayImpl> {
+    let len = array.len();
+    Ok(match array.data_type() {
+        ArrowDataType::Boolean => {
+            build!(BoolArrayBuilder, len, downcast!(array, BooleanArray).iter())
+        }
+        ArrowDataType::Int16 => build!(I16ArrayBuilder, len, downcast!(array, Int16Array).iter()),
+        ArrowDataType::Int32 => build!(I32ArrayBuilder, len, downcast!(array, Int32Array).iter()),
+        ArrowDataType::Int64 => build!(I64ArrayBuilder, len, downcast!(array, Int64Array).iter()),
+        ArrowDataType::Float32 => build!(
+            F32ArrayBuilder,
+            len,
+            downcast!(array, Float32Array)
+                .iter()
+                .map(|v| v.map(OrderedF32::from))
+        ),
+        ArrowDataType::Float64 => build!(
+            F64ArrayBuilder,
+            len,
+            downcast!(array, Float64Array)
+                .iter()
+                .map(|v| v.map(OrderedF64::from))
+        ),
+        ArrowDataType::Utf8 => build!(Utf8ArrayBuilder, len, downcast!(array, StringArray).iter()),
+        ArrowDataType::Date32 => {
+            let values = downcast!(array, Date32Array)
+                .iter()
+                .map(|v| {
+                    v.map(|days| {
+                        NaiveDate::from_num_days_from_ce_opt(days + UNIX_EPOCH_DAYS)
+                            .map(NaiveDateWrapper::new)
+                            .ok_or_else(|| anyhow!("date out of range: {} days", days))
+                    })
+                    .transpose()
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            build!(NaiveDateArrayBuilder, len, values)
+        }
+        ArrowDataType::Time64(TimeUnit::Microsecond) => {
+            let values = downcast!(array, Time64MicrosecondArray)
+                .iter()
+                .map(|v| {
+                    v.map(|micros| {
+                        NaiveTime::from_num_seconds_from_midnight_opt(
+                            (micros / 1_000_000) as u32,
+                            (micros % 1_000_000) as u32 * 1_000,
+                        )
+                        .map(NaiveTimeWrapper::new)
+                        .ok_or_else(|| anyhow!("time out of range: {} us", micros))
+                    })
+                    .transpose()
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            build!(NaiveTimeArrayBuilder, len, values)
+        }
+        ArrowDataType::Timestamp(TimeUnit::Microsecond, None) => {
+            let values = downcast!(array, TimestampMicrosecondArray)
+                .iter()
+                .map(|v| {
+                    v.map(|micros| {
+                        NaiveDateTimeWrapper::with_secs_nsecs(
+                            micros.div_euclid(1_000_000),
+                            micros.rem_euclid(1_000_000) as u32 * 1_000,
+                        )
+                    })
+                    .transpose()
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            build!(NaiveDateTimeArrayBuilder, len, values)
+        }
+        ArrowDataType::Timestamp(TimeUnit::Microsecond, Some(_)) => build!(
+            I64ArrayBuilder,
+            len,
+            downcast!(array, TimestampMicrosecondArray).iter()
+        ),
+        data_type => {
+            return Err(anyhow!("arrow data type {:?} is not supported", data_type).into());
+        }
+    })
+}
+
+impl DataChunk {
+    /// Converts the visible rows of the chunk to a [`RecordBatch`] of `schema`.
+    pub fn to_record_batch(&self, schema: &Schema) -> ArrayResult<RecordBatch> {
+        let chunk = self.clone().compact()?;
+        let columns = chunk
+            .columns()
+            .iter()
+            .zip_eq(schema.fields())
+            .map(|(column, field)| to_arrow_array(column.array_ref(), &field.data_type))
+            .collect::<ArrayResult<Vec<_>>>()?;
+        RecordBatch::try_new(Arc::new(to_arrow_schema(schema)?), columns)
+            .map_err(|e| anyhow!(e).into())
+    }
+
+    pub fn from_record_batch(batch: &RecordBatch) -> ArrayResult<Self> {
+        let columns = batch
+            .columns()
+            .iter()
+            .map(|array| Ok(Column::new(Arc::new(from_arrow_array(array.as_ref())?))))
+            .collect::<ArrayResult<Vec<_>>>()?;
+        Ok(DataChunk::new(columns, batch.num_rows()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::{DataChunkTestExt, I64Array};
+
+    #[test]
+    fn test_data_chunk_round_trip() {
+        let chunk = DataChunk::from_pretty(
+            "i  T   F    TS
+             1  abc 1.5  2022-07-01T12:00:00.123456
+             .  .   .    .
+             3  def -2.0 1969-12-31T23:59:59.5 D",
+        );
+        let schema = Schema::new(vec![
+            Field::with_name(DataType::Int32, "i"),
+            Field::with_name(DataType::Varchar, "t"),
+            Field::with_name(DataType::Float64, "f"),
+            Field::with_name(DataType::Timestamp, "ts"),
+        ]);
+        let batch = chunk.to_record_batch(&schema).unwrap();
+        assert_eq!(batch.num_rows(), 2);
+        assert_eq!(from_arrow_schema(&batch.schema()).unwrap(), schema);
+
+        let converted = DataChunk::from_record_batch(&batch).unwrap();
+        assert_eq!(converted, chunk.compact().unwrap());
+    }
+
+    #[test]
+    fn test_timestampz() {
+        let array = ArrayImpl::Int64(I64Array::from_slice(&[Some(1_000_000), None]).unwrap());
+        let arrow_array = to_arrow_array(&array, &DataType::Timestampz).unwrap();
+        assert_eq!(
+            arrow_array.data_type(),
+            &ArrowDataType::Timestamp(TimeUnit::Microsecond, Some(UTC.to_string()))
+        );
+        let converted = from_arrow_array(arrow_array.as_ref()).unwrap();
+        assert_eq!(converted, array);
+    }
+}
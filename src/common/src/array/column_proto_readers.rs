@@ -23,11 +23,11 @@ use crate::array::value_reader::{PrimitiveValueReader, VarSizedValueReader};
 use crate::array::{
     Array, ArrayBuilder, ArrayImpl, ArrayMeta, ArrayResult, BoolArray, IntervalArrayBuilder,
     NaiveDateArrayBuilder, NaiveDateTimeArrayBuilder, NaiveTimeArrayBuilder, PrimitiveArrayBuilder,
-    PrimitiveArrayItemType,
+    PrimitiveArrayItemType, UuidArrayBuilder,
 };
 use crate::buffer::Bitmap;
 use crate::types::interval::IntervalUnit;
-use crate::types::{NaiveDateTimeWrapper, NaiveDateWrapper, NaiveTimeWrapper};
+use crate::types::{NaiveDateTimeWrapper, NaiveDateWrapper, NaiveTimeWrapper, Uuid};
 
 // TODO: Use techniques like apache arrow flight RPC to eliminate deserialization.
 // https://arrow.apache.org/docs/format/Flight.html
@@ -114,6 +114,14 @@ pub fn read_interval_unit(cursor: &mut Cursor<&[u8]>) -> ArrayResult<IntervalUni
     }
 }
 
+fn read_uuid(cursor: &mut Cursor<&[u8]>) -> ArrayResult<Uuid> {
+    let mut bytes = [0; 16];
+    match cursor.read_exact(&mut bytes) {
+        Ok(()) => Ok(Uuid::from_bytes(bytes)),
+        Err(e) => bail!("Failed to read Uuid from buffer: {}", e),
+    }
+}
+
 macro_rules! read_one_value_array {
     ($({ $type:ident, $builder:ty }),*) => {
         paste! {
@@ -146,6 +154,7 @@ macro_rules! read_one_value_array {
 
 read_one_value_array! {
     { IntervalUnit, IntervalArrayBuilder },
+    { Uuid, UuidArrayBuilder },
     { NaiveDate, NaiveDateArrayBuilder },
     { NaiveTime, NaiveTimeArrayBuilder },
     { NaiveDateTime, NaiveDateTimeArrayBuilder }
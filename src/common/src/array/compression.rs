@@ -0,0 +1,178 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Compression of serialized arrays sent over exchange.
+//!
+//! Buffers of an array no smaller than [`COMPRESSION_THRESHOLD`] are compressed in place, and the
+//! algorithm is recorded in [`Buffer::compression`]. [`ArrayImpl::from_protobuf`] decompresses
+//! buffers by what is recorded, so the receiver does not need to know how the sender compressed.
+//!
+//! [`ArrayImpl::from_protobuf`]: crate::array::ArrayImpl::from_protobuf
+
+use std::borrow::Cow;
+
+use risingwave_pb::common::buffer::CompressionType;
+use risingwave_pb::common::Buffer;
+use risingwave_pb::data::{Array as ProstArray, Column as ProstColumn};
+
+use crate::array::ArrayResult;
+
+/// Buffers smaller than this are sent as-is, since compressing them saves little.
+pub const COMPRESSION_THRESHOLD: usize = 4096;
+
+const ZSTD_LEVEL: i32 = 1;
+
+fn compress_buffer(buffer: &mut Buffer, compression: CompressionType) -> ArrayResult<()> {
+    if buffer.body.len() < COMPRESSION_THRESHOLD || buffer.compression() != CompressionType::None {
+        return Ok(());
+    }
+    let compressed = match compression {
+        CompressionType::Invalid | CompressionType::None => return Ok(()),
+        CompressionType::Lz4 => lz4::block::compress(&buffer.body, None, true)?,
+        CompressionType::Zstd => zstd::bulk::compress(&buffer.body, ZSTD_LEVEL)?,
+    };
+    // Keep incompressible data as-is.
+    if compressed.len() < buffer.body.len() {
+        buffer.body = compressed;
+        buffer.set_compression(compression);
+    }
+    Ok(())
+}
+
+fn decompress_buffer(buffer: &mut Buffer) -> ArrayResult<()> {
+    let decompressed = match buffer.compression() {
+        CompressionType::Invalid | CompressionType::None => return Ok(()),
+        CompressionType::Lz4 => lz4::block::decompress(&buffer.body, None)?,
+        CompressionType::Zstd => zstd::stream::decode_all(buffer.body.as_slice())?,
+    };
+    buffer.body = decompressed;
+    buffer.set_compression(CompressionType::None);
+    Ok(())
+}
+
+fn for_each_buffer(
+    array: &mut ProstArray,
+    f: &mut impl FnMut(&mut Buffer) -> ArrayResult<()>,
+) -> ArrayResult<()> {
+    if let Some(null_bitmap) = &mut array.null_bitmap {
+        f(null_bitmap)?;
+    }
+    for buffer in &mut array.values {
+        f(buffer)?;
+    }
+    if let Some(struct_data) = &mut array.struct_array_data {
+        for child in &mut struct_data.children_array {
+            for_each_buffer(child, f)?;
+        }
+    }
+    if let Some(list_data) = &mut array.list_array_data {
+        if let Some(value) = &mut list_data.value {
+            for_each_buffer(value, f)?;
+        }
+    }
+    Ok(())
+}
+
+fn is_compressed(array: &ProstArray) -> bool {
+    let compressed = |buffer: &Buffer| {
+        !matches!(
+            buffer.compression(),
+            CompressionType::Invalid | CompressionType::None
+        )
+    };
+    array.null_bitmap.iter().any(compressed)
+        || array.values.iter().any(compressed)
+        || array
+            .struct_array_data
+            .iter()
+            .any(|struct_data| struct_data.children_array.iter().any(is_compressed))
+        || array
+            .list_array_data
+            .iter()
+            .any(|list_data| list_data.value.iter().any(is_compressed))
+}
+
+/// Compresses buffers of `array` no smaller than [`COMPRESSION_THRESHOLD`] with `compression`.
+pub fn compress_array(array: &mut ProstArray, compression: CompressionType) -> ArrayResult<()> {
+    if matches!(
+        compression,
+        CompressionType::Invalid | CompressionType::None
+    ) {
+        return Ok(());
+    }
+    for_each_buffer(array, &mut |buffer| compress_buffer(buffer, compression))
+}
+
+pub fn compress_columns(
+    columns: &mut [ProstColumn],
+    compression: CompressionType,
+) -> ArrayResult<()> {
+    for column in columns {
+        if let Some(array) = &mut column.array {
+            compress_array(array, compression)?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns `array` with all buffers decompressed. It's borrowed if nothing is compressed.
+pub fn decompress_array(array: &ProstArray) -> ArrayResult<Cow<'_, ProstArray>> {
+    if !is_compressed(array) {
+        return Ok(Cow::Borrowed(array));
+    }
+    let mut array = array.clone();
+    for_each_buffer(&mut array, &mut decompress_buffer)?;
+    Ok(Cow::Owned(array))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::{ArrayImpl, I64Array, Utf8Array};
+
+    #[test]
+    fn test_compress_array() {
+        let strings = (0..4096)
+            .map(|i| if i % 7 == 0 { None } else { Some("risingwave") })
+            .collect::<Vec<_>>();
+        let numbers = (0..4096).map(|i| Some(i % 16)).collect::<Vec<_>>();
+
+        for compression in [CompressionType::Lz4, CompressionType::Zstd] {
+            for array in [
+                ArrayImpl::from(Utf8Array::from_slice(&strings).unwrap()),
+                ArrayImpl::from(I64Array::from_slice(&numbers).unwrap()),
+            ] {
+                let mut prost = array.to_protobuf();
+                let len = prost.values.iter().map(|b| b.body.len()).sum::<usize>();
+                compress_array(&mut prost, compression).unwrap();
+                assert!(is_compressed(&prost));
+                assert!(prost.values.iter().map(|b| b.body.len()).sum::<usize>() < len);
+                let decoded = ArrayImpl::from_protobuf(&prost, array.len()).unwrap();
+                assert_eq!(decoded, array);
+            }
+        }
+    }
+
+    #[test]
+    fn test_small_buffer_not_compressed() {
+        let array = ArrayImpl::from(I64Array::from_slice(&[Some(1), None, Some(3)]).unwrap());
+        let mut prost = array.to_protobuf();
+        compress_array(&mut prost, CompressionType::Zstd).unwrap();
+        assert!(!is_compressed(&prost));
+        assert!(matches!(
+            decompress_array(&prost).unwrap(),
+            Cow::Borrowed(_)
+        ));
+    }
+}
@@ -170,6 +170,20 @@ impl DataChunk {
         }
     }
 
+    /// Estimates the heap size of the chunk in bytes, e.g. for the memory accounting of batch
+    /// executors.
+    pub fn estimated_heap_size(&self) -> usize {
+        let vis_size = match &self.vis2 {
+            Vis::Bitmap(b) => b.len() / 8,
+            Vis::Compact(_) => 0,
+        };
+        self.columns
+            .iter()
+            .map(|column| column.array_ref().estimated_heap_size())
+            .sum::<usize>()
+            + vis_size
+    }
+
     /// `capacity` returns physical length of any chunk column
     pub fn capacity(&self) -> usize {
         match &self.vis2 {
@@ -738,6 +752,17 @@ mod tests {
         assert_eq!(chunk_after_serde.rows().count(), 10);
         assert_eq!(chunk_after_serde.cardinality(), 10);
     }
+    #[test]
+    fn test_estimated_heap_size() {
+        let chunk = DataChunk::from_pretty(
+            "I T
+             1 abc
+             2 de",
+        );
+        // 2 i64s, 3 offsets and 5 bytes of the strings, with the null bitmaps rounded down.
+        assert_eq!(chunk.estimated_heap_size(), 16 + 24 + 5);
+    }
+
     #[test]
     fn reorder_columns() {
         let chunk = DataChunk::from_pretty(
@@ -0,0 +1,181 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use crate::array::{
+    Array, ArrayBuilder, ArrayError, ArrayResult, BoolArray, Utf8Array, Utf8ArrayBuilder,
+};
+use crate::buffer::{Bitmap, BitmapBuilder};
+
+/// `DictionaryArray` is a dictionary-encoded [`Utf8Array`]. Each non-null row stores the code of
+/// its value in `dictionary`, so that kernels can be evaluated once per dictionary entry instead of
+/// once per row.
+#[derive(Debug)]
+pub struct DictionaryArray {
+    /// Values referenced by `codes`. It never contains nulls.
+    dictionary: Utf8Array,
+    /// Code of each row. The code of a null row is meaningless.
+    codes: Vec<u32>,
+    bitmap: Bitmap,
+}
+
+impl DictionaryArray {
+    pub fn encode(array: &Utf8Array) -> ArrayResult<Self> {
+        let mut dictionary = Utf8ArrayBuilder::new(0)?;
+        let mut lookup = HashMap::new();
+        let mut codes = Vec::with_capacity(array.len());
+        let mut bitmap = BitmapBuilder::with_capacity(array.len());
+        for value in array.iter() {
+            bitmap.append(value.is_some());
+            let code = match value {
+                Some(value) => match lookup.get(value) {
+                    Some(&code) => code,
+                    None => {
+                        let code = lookup.len() as u32;
+                        dictionary.append(Some(value))?;
+                        lookup.insert(value, code);
+                        code
+                    }
+                },
+                None => 0,
+            };
+            codes.push(code);
+        }
+        Ok(Self {
+            dictionary: dictionary.finish()?,
+            codes,
+            bitmap: bitmap.finish(),
+        })
+    }
+
+    pub fn decode(&self) -> ArrayResult<Utf8Array> {
+        let mut builder = Utf8ArrayBuilder::new(self.len())?;
+        for value in self.iter() {
+            builder.append(value)?;
+        }
+        builder.finish()
+    }
+
+    pub fn len(&self) -> usize {
+        self.codes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.codes.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Option<&str>> + '_ {
+        self.codes
+            .iter()
+            .zip(self.bitmap.iter())
+            .map(|(&code, not_null)| {
+                if not_null {
+                    self.dictionary.value_at(code as usize)
+                } else {
+                    None
+                }
+            })
+    }
+
+    /// Number of entries in the dictionary.
+    pub fn cardinality(&self) -> usize {
+        self.dictionary.len()
+    }
+
+    pub fn dictionary(&self) -> &Utf8Array {
+        &self.dictionary
+    }
+
+    pub fn codes(&self) -> &[u32] {
+        &self.codes
+    }
+
+    /// Applies the string function `f` to each dictionary entry. The codes are kept, so the result
+    /// is still dictionary-encoded.
+    pub fn map_values<F, E>(&self, mut f: F) -> Result<DictionaryArray, E>
+    where
+        F: FnMut(&str) -> Result<String, E>,
+        E: From<ArrayError>,
+    {
+        let mut dictionary = Utf8ArrayBuilder::new(self.cardinality())?;
+        for value in self.dictionary.iter().flatten() {
+            dictionary.append(Some(f(value)?.as_str()))?;
+        }
+        Ok(DictionaryArray {
+            dictionary: dictionary.finish()?,
+            codes: self.codes.clone(),
+            bitmap: self.bitmap.clone(),
+        })
+    }
+
+    /// Evaluates the predicate `f` once per dictionary entry, then gathers the results by code.
+    /// Null rows evaluate to null.
+    pub fn map_to_bool<F, E>(&self, mut f: F) -> Result<BoolArray, E>
+    where
+        F: FnMut(&str) -> Result<bool, E>,
+        E: From<ArrayError>,
+    {
+        let results = self
+            .dictionary
+            .iter()
+            .flatten()
+            .map(f)
+            .collect::<Result<Vec<_>, E>>()?;
+        let data = self
+            .codes
+            .iter()
+            .zip(self.bitmap.iter())
+            .map(|(&code, not_null)| not_null && results[code as usize])
+            .collect::<Vec<_>>();
+        Ok(BoolArray::new(self.bitmap.clone(), Bitmap::try_from(data)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dictionary_array() {
+        let array =
+            Utf8Array::from_slice(&[Some("us"), None, Some("cn"), Some("us"), Some("us")]).unwrap();
+        let encoded = DictionaryArray::encode(&array).unwrap();
+        assert_eq!(encoded.cardinality(), 2);
+        assert_eq!(encoded.codes(), &[0, 0, 1, 0, 0]);
+        assert!(encoded.iter().eq(array.iter()));
+        assert!(encoded.decode().unwrap().iter().eq(array.iter()));
+
+        let upper = encoded
+            .map_values(|v| Ok::<_, ArrayError>(v.to_uppercase()))
+            .unwrap();
+        assert_eq!(
+            upper.iter().collect::<Vec<_>>(),
+            vec![Some("US"), None, Some("CN"), Some("US"), Some("US")]
+        );
+
+        let mut evaluated = 0;
+        let is_us = encoded
+            .map_to_bool(|v| {
+                evaluated += 1;
+                Ok::<_, ArrayError>(v == "us")
+            })
+            .unwrap();
+        assert_eq!(evaluated, 2);
+        assert_eq!(
+            is_us.iter().collect::<Vec<_>>(),
+            vec![Some(true), None, Some(false), Some(true), Some(true)]
+        );
+    }
+}
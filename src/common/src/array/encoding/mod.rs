@@ -0,0 +1,256 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Optional in-memory encodings of arrays.
+//!
+//! Encoded arrays are not [`Array`]s themselves: they are built from a plain array, evaluated with
+//! the kernels provided here and decoded back before being handed to other code. The kernels work
+//! on the encoded form directly, which evaluates an expression once per distinct value or per run
+//! rather than once per row for low-cardinality `VARCHAR` columns.
+
+mod dictionary;
+mod run_length;
+
+use std::collections::HashSet;
+
+pub use dictionary::DictionaryArray;
+pub use run_length::RunLengthArray;
+
+use crate::array::{
+    Array, ArrayBuilder, ArrayError, ArrayImpl, ArrayResult, BoolArray, BoolArrayBuilder,
+    Utf8Array, Utf8ArrayBuilder,
+};
+
+/// Use run-length encoding if runs are at least this long on average.
+const MIN_AVG_RUN_LENGTH: usize = 4;
+/// Use dictionary encoding if each distinct value appears at least this many times on average.
+const MIN_AVG_DICTIONARY_REPEATS: usize = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    Plain,
+    Dictionary,
+    RunLength,
+}
+
+/// Statistics of a [`Utf8Array`] to choose its [`Encoding`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EncodingStats {
+    pub len: usize,
+    pub distinct_count: usize,
+    pub run_count: usize,
+}
+
+impl EncodingStats {
+    pub fn collect(array: &Utf8Array) -> Self {
+        let mut distinct = HashSet::new();
+        let mut run_count = 0;
+        let mut last = None;
+        for (idx, value) in array.iter().enumerate() {
+            if idx == 0 || value != last {
+                run_count += 1;
+            }
+            if let Some(value) = value {
+                distinct.insert(value);
+            }
+            last = value;
+        }
+        Self {
+            len: array.len(),
+            distinct_count: distinct.len(),
+            run_count,
+        }
+    }
+
+    pub fn choose(&self) -> Encoding {
+        if self.len == 0 {
+            Encoding::Plain
+        } else if self.run_count * MIN_AVG_RUN_LENGTH <= self.len {
+            Encoding::RunLength
+        } else if self.distinct_count * MIN_AVG_DICTIONARY_REPEATS <= self.len
+            && self.distinct_count <= u32::MAX as usize
+        {
+            Encoding::Dictionary
+        } else {
+            Encoding::Plain
+        }
+    }
+}
+
+/// A `VARCHAR` column in the encoding chosen by [`EncodingStats::choose`].
+#[derive(Debug)]
+pub enum EncodedUtf8Array {
+    Plain(Utf8Array),
+    Dictionary(DictionaryArray),
+    RunLength(RunLengthArray<Utf8Array>),
+}
+
+impl EncodedUtf8Array {
+    pub fn encode(array: Utf8Array) -> ArrayResult<Self> {
+        let encoding = EncodingStats::collect(&array).choose();
+        Self::encode_as(array, encoding)
+    }
+
+    /// Encodes `array` if an encoding other than [`Encoding::Plain`] pays off.
+    pub fn try_encode(array: &Utf8Array) -> ArrayResult<Option<Self>> {
+        Ok(match EncodingStats::collect(array).choose() {
+            Encoding::Plain => None,
+            Encoding::Dictionary => Some(Self::Dictionary(DictionaryArray::encode(array)?)),
+            Encoding::RunLength => Some(Self::RunLength(RunLengthArray::encode(array)?)),
+        })
+    }
+
+    pub fn encode_as(array: Utf8Array, encoding: Encoding) -> ArrayResult<Self> {
+        Ok(match encoding {
+            Encoding::Plain => Self::Plain(array),
+            Encoding::Dictionary => Self::Dictionary(DictionaryArray::encode(&array)?),
+            Encoding::RunLength => Self::RunLength(RunLengthArray::encode(&array)?),
+        })
+    }
+
+    pub fn encoding(&self) -> Encoding {
+        match self {
+            Self::Plain(_) => Encoding::Plain,
+            Self::Dictionary(_) => Encoding::Dictionary,
+            Self::RunLength(_) => Encoding::RunLength,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Plain(array) => array.len(),
+            Self::Dictionary(array) => array.len(),
+            Self::RunLength(array) => array.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn decode(self) -> ArrayResult<Utf8Array> {
+        match self {
+            Self::Plain(array) => Ok(array),
+            Self::Dictionary(array) => array.decode(),
+            Self::RunLength(array) => array.decode(),
+        }
+    }
+
+    /// Applies the string function `f` to each non-null value. `f` is called once per dictionary
+    /// entry or per run if the array is encoded, and the result keeps the encoding.
+    pub fn map<F, E>(&self, mut f: F) -> Result<Self, E>
+    where
+        F: FnMut(&str) -> Result<String, E>,
+        E: From<ArrayError>,
+    {
+        Ok(match self {
+            Self::Plain(array) => {
+                let mut builder = Utf8ArrayBuilder::new(array.len())?;
+                for value in array.iter() {
+                    match value {
+                        Some(value) => builder.append(Some(f(value)?.as_str()))?,
+                        None => builder.append(None)?,
+                    }
+                }
+                Self::Plain(builder.finish()?)
+            }
+            Self::Dictionary(array) => Self::Dictionary(array.map_values(f)?),
+            Self::RunLength(array) => {
+                Self::RunLength(array.map::<Utf8Array, _, _>(|v| v.map(&mut f).transpose())?)
+            }
+        })
+    }
+
+    /// Evaluates the predicate `f` on each non-null value. Null values evaluate to null.
+    pub fn map_to_bool<F, E>(&self, mut f: F) -> Result<BoolArray, E>
+    where
+        F: FnMut(&str) -> Result<bool, E>,
+        E: From<ArrayError>,
+    {
+        match self {
+            Self::Plain(array) => {
+                let mut builder = BoolArrayBuilder::new(array.len())?;
+                for value in array.iter() {
+                    builder.append(value.map(&mut f).transpose()?)?;
+                }
+                Ok(builder.finish()?)
+            }
+            Self::Dictionary(array) => array.map_to_bool(f),
+            Self::RunLength(array) => Ok(array
+                .map::<BoolArray, _, _>(|v| v.map(&mut f).transpose())?
+                .decode()?),
+        }
+    }
+
+    /// Decodes into a plain [`ArrayImpl`].
+    pub fn into_array(self) -> ArrayResult<ArrayImpl> {
+        Ok(self.decode()?.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_choose_encoding() {
+        let sorted = Utf8Array::from_slice(&[Some("a"); 8]).unwrap();
+        assert_eq!(
+            EncodingStats::collect(&sorted).choose(),
+            Encoding::RunLength
+        );
+
+        let low_cardinality = Utf8Array::from_slice(
+            &(0..16)
+                .map(|i| Some(if i % 2 == 0 { "a" } else { "b" }))
+                .collect::<Vec<_>>(),
+        )
+        .unwrap();
+        assert_eq!(
+            EncodingStats::collect(&low_cardinality).choose(),
+            Encoding::Dictionary
+        );
+
+        let distinct = Utf8Array::from_slice(&[Some("a"), Some("b"), None, Some("c")]).unwrap();
+        assert_eq!(EncodingStats::collect(&distinct).choose(), Encoding::Plain);
+    }
+
+    #[test]
+    fn test_encoded_kernels() {
+        let data = [Some("x"), Some("x"), None, Some("yy"), Some("x")];
+        for encoding in [Encoding::Plain, Encoding::Dictionary, Encoding::RunLength] {
+            let array =
+                EncodedUtf8Array::encode_as(Utf8Array::from_slice(&data).unwrap(), encoding)
+                    .unwrap();
+            assert_eq!(array.encoding(), encoding);
+
+            let lengths = array
+                .map_to_bool(|v| Ok::<_, ArrayError>(v.len() > 1))
+                .unwrap();
+            assert_eq!(
+                lengths.iter().collect::<Vec<_>>(),
+                vec![Some(false), Some(false), None, Some(true), Some(false)]
+            );
+
+            let upper = array
+                .map(|v| Ok::<_, ArrayError>(v.to_uppercase()))
+                .unwrap();
+            assert_eq!(upper.encoding(), encoding);
+            assert_eq!(
+                upper.decode().unwrap().iter().collect::<Vec<_>>(),
+                vec![Some("X"), Some("X"), None, Some("YY"), Some("X")]
+            );
+        }
+    }
+}
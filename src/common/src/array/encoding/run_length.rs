@@ -0,0 +1,144 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::array::{Array, ArrayBuilder, ArrayError, ArrayResult};
+use crate::types::{Scalar, ScalarRef, ScalarRefImpl};
+
+/// `RunLengthArray` stores consecutive equal values of an array as one run. `values` holds the
+/// value of each run, and `run_ends` the exclusive end row of each run.
+#[derive(Debug)]
+pub struct RunLengthArray<A: Array> {
+    values: A,
+    run_ends: Vec<usize>,
+}
+
+impl<A: Array> RunLengthArray<A> {
+    pub fn encode(array: &A) -> ArrayResult<Self> {
+        let mut values = A::Builder::with_meta(0, array.array_meta())?;
+        let mut run_ends: Vec<usize> = vec![];
+        let mut last: Option<Option<ScalarRefImpl<'_>>> = None;
+        for value in array.iter() {
+            let scalar = value.map(Into::into);
+            if last == Some(scalar) {
+                *run_ends.last_mut().unwrap() += 1;
+            } else {
+                values.append(value)?;
+                run_ends.push(run_ends.last().copied().unwrap_or(0) + 1);
+                last = Some(scalar);
+            }
+        }
+        Ok(Self {
+            values: values.finish()?,
+            run_ends,
+        })
+    }
+
+    pub fn decode(&self) -> ArrayResult<A> {
+        let mut builder = A::Builder::with_meta(self.len(), self.values.array_meta())?;
+        for (value, len) in self.runs() {
+            for _ in 0..len {
+                builder.append(value)?;
+            }
+        }
+        builder.finish()
+    }
+
+    pub fn len(&self) -> usize {
+        self.run_ends.last().copied().unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.run_ends.is_empty()
+    }
+
+    pub fn run_count(&self) -> usize {
+        self.run_ends.len()
+    }
+
+    /// Values of the runs.
+    pub fn values(&self) -> &A {
+        &self.values
+    }
+
+    /// Returns the value and the length of each run.
+    pub fn runs(&self) -> impl Iterator<Item = (Option<A::RefItem<'_>>, usize)> + '_ {
+        let lens = self.run_ends.iter().scan(0, |start, &end| {
+            let len = end - *start;
+            *start = end;
+            Some(len)
+        });
+        self.values.iter().zip(lens)
+    }
+
+    /// Returns the value of each row.
+    pub fn iter(&self) -> impl Iterator<Item = Option<A::RefItem<'_>>> + '_ {
+        self.runs()
+            .flat_map(|(value, len)| std::iter::repeat(value).take(len))
+    }
+
+    /// Applies `f` once per run instead of once per row, and keeps the result run-length encoded.
+    pub fn map<B, F, E>(&self, mut f: F) -> Result<RunLengthArray<B>, E>
+    where
+        B: Array,
+        F: FnMut(Option<A::RefItem<'_>>) -> Result<Option<B::OwnedItem>, E>,
+        E: From<ArrayError>,
+    {
+        let mut values = B::Builder::new(self.run_count())?;
+        for value in self.values.iter() {
+            let mapped = f(value)?;
+            values.append(mapped.as_ref().map(|v| v.as_scalar_ref()))?;
+        }
+        Ok(RunLengthArray {
+            values: values.finish()?,
+            run_ends: self.run_ends.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::{I32Array, Utf8Array};
+
+    #[test]
+    fn test_run_length_array() {
+        let array = Utf8Array::from_slice(&[
+            Some("a"),
+            Some("a"),
+            None,
+            None,
+            Some("b"),
+            Some("a"),
+            Some("a"),
+        ])
+        .unwrap();
+        let encoded = RunLengthArray::encode(&array).unwrap();
+        assert_eq!(encoded.len(), 7);
+        assert_eq!(encoded.run_count(), 4);
+        assert_eq!(
+            encoded.runs().collect::<Vec<_>>(),
+            vec![(Some("a"), 2), (None, 2), (Some("b"), 1), (Some("a"), 2)]
+        );
+        assert!(encoded.iter().eq(array.iter()));
+        assert!(encoded.decode().unwrap().iter().eq(array.iter()));
+
+        let lens = encoded
+            .map::<I32Array, _, ArrayError>(|v| Ok(v.map(|v| v.len() as i32)))
+            .unwrap();
+        assert_eq!(
+            lens.iter().collect::<Vec<_>>(),
+            vec![Some(1), Some(1), None, None, Some(1), Some(1), Some(1)]
+        );
+    }
+}
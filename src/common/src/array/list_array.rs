@@ -189,6 +189,12 @@ impl Array for ListArray {
         &self.bitmap
     }
 
+    fn estimated_heap_size(&self) -> usize {
+        self.offsets.len() * std::mem::size_of::<usize>()
+            + self.value.estimated_heap_size()
+            + self.bitmap.len() / 8
+    }
+
     fn set_bitmap(&mut self, bitmap: Bitmap) {
         self.bitmap = bitmap;
     }
@@ -14,13 +14,16 @@
 
 //! `Array` defines all in-memory representations of vectorized execution framework.
 
+pub mod arrow;
 mod bool_array;
 mod chrono_array;
 pub mod column;
 mod column_proto_readers;
+pub mod compression;
 mod data_chunk;
 pub mod data_chunk_iter;
 mod decimal_array;
+pub mod encoding;
 pub mod error;
 pub mod interval_array;
 mod iterator;
@@ -31,6 +34,7 @@ pub mod stream_chunk;
 mod stream_chunk_iter;
 pub mod struct_array;
 mod utf8_array;
+mod uuid_array;
 mod value_reader;
 
 use std::convert::From;
@@ -55,6 +59,7 @@ use risingwave_pb::data::{Array as ProstArray, ArrayType as ProstArrayType};
 pub use stream_chunk::{Op, StreamChunk, StreamChunkTestExt};
 pub use struct_array::{StructArray, StructArrayBuilder, StructRef, StructValue};
 pub use utf8_array::*;
+pub use uuid_array::{UuidArray, UuidArrayBuilder};
 
 pub use self::error::ArrayError;
 use crate::array::iterator::ArrayImplIterator;
@@ -207,6 +212,12 @@ pub trait Array: std::fmt::Debug + Send + Sync + Sized + 'static + Into<ArrayImp
     fn array_meta(&self) -> ArrayMeta {
         ArrayMeta::Simple
     }
+
+    /// Estimates the heap size of the array in bytes, e.g. for the memory accounting of batch
+    /// executors.
+    fn estimated_heap_size(&self) -> usize {
+        self.len() * std::mem::size_of::<Self::OwnedItem>() + self.null_bitmap().len() / 8
+    }
 }
 
 /// The creation of [`Array`] typically does not rely on [`DataType`].
@@ -261,6 +272,7 @@ macro_rules! for_all_variants {
             { Bool, bool, BoolArray, BoolArrayBuilder },
             { Decimal, decimal, DecimalArray, DecimalArrayBuilder },
             { Interval, interval, IntervalArray, IntervalArrayBuilder },
+            { Uuid, uuid, UuidArray, UuidArrayBuilder },
             { NaiveDate, naivedate, NaiveDateArray, NaiveDateArrayBuilder },
             { NaiveDateTime, naivedatetime, NaiveDateTimeArray, NaiveDateTimeArrayBuilder },
             { NaiveTime, naivetime, NaiveTimeArray, NaiveTimeArrayBuilder },
@@ -466,6 +478,12 @@ macro_rules! impl_array {
                 }
             }
 
+            pub fn estimated_heap_size(&self) -> usize {
+                match self {
+                    $( Self::$variant_name(inner) => inner.estimated_heap_size(), )*
+                }
+            }
+
             pub fn to_protobuf(&self) -> ProstArray {
                 match self {
                     $( Self::$variant_name(inner) => inner.to_protobuf(), )*
@@ -556,6 +574,8 @@ impl ArrayImpl {
     pub fn from_protobuf(array: &ProstArray, cardinality: usize) -> ArrayResult<Self> {
         use self::column_proto_readers::*;
         use crate::array::value_reader::*;
+        let array = compression::decompress_array(array)?;
+        let array = &*array;
         let array = match array.array_type() {
             ProstArrayType::Int16 => read_numeric_array::<i16, I16ValueReader>(array, cardinality)?,
             ProstArrayType::Int32 => read_numeric_array::<i32, I32ValueReader>(array, cardinality)?,
@@ -577,6 +597,7 @@ impl ArrayImpl {
             ProstArrayType::Time => read_naive_time_array(array, cardinality)?,
             ProstArrayType::Timestamp => read_naive_date_time_array(array, cardinality)?,
             ProstArrayType::Interval => read_interval_unit_array(array, cardinality)?,
+            ProstArrayType::Uuid => read_uuid_array(array, cardinality)?,
             ProstArrayType::Struct => StructArray::from_protobuf(array)?,
             ProstArrayType::List => ListArray::from_protobuf(array)?,
         };
@@ -27,7 +27,7 @@ use crate::buffer::{Bitmap, BitmapBuilder};
 use crate::for_all_native_types;
 use crate::types::interval::IntervalUnit;
 use crate::types::{
-    NaiveDateTimeWrapper, NaiveDateWrapper, NaiveTimeWrapper, NativeType, Scalar, ScalarRef,
+    NaiveDateTimeWrapper, NaiveDateWrapper, NaiveTimeWrapper, NativeType, Scalar, ScalarRef, Uuid,
 };
 
 /// Physical type of array items which have fixed size.
@@ -128,6 +128,7 @@ macro_rules! impl_primitive_for_others {
 
 impl_primitive_for_others! {
     { IntervalUnit, Interval, Interval },
+    { Uuid, Uuid, Uuid },
     { NaiveDateWrapper, Date, NaiveDate },
     { NaiveTimeWrapper, Time, NaiveTime },
     { NaiveDateTimeWrapper, Timestamp, NaiveDateTime }
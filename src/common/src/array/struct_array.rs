@@ -192,6 +192,14 @@ impl Array for StructArray {
         &self.bitmap
     }
 
+    fn estimated_heap_size(&self) -> usize {
+        self.children
+            .iter()
+            .map(|child| child.estimated_heap_size())
+            .sum::<usize>()
+            + self.bitmap.len() / 8
+    }
+
     fn set_bitmap(&mut self, bitmap: Bitmap) {
         self.bitmap = bitmap;
     }
@@ -711,6 +711,22 @@ impl<K: LruKey, T: LruValue> LruCache<K, T> {
             .sum()
     }
 
+    /// Changes the capacity of the cache. If the cache is shrunk, unreferenced entries are evicted
+    /// immediately, while referenced ones are evicted when released.
+    pub fn set_capacity(&self, capacity: usize) {
+        let per_shard = capacity / self.shards.len();
+        let mut to_delete = vec![];
+        for shard in &self.shards {
+            let mut shard = shard.lock();
+            shard.capacity = per_shard;
+            unsafe {
+                shard.evict_from_lru(0, &mut to_delete);
+            }
+        }
+        // do not deallocate data with holding mutex.
+        drop(to_delete);
+    }
+
     fn shard(&self, hash: u64) -> usize {
         hash as usize % self.shards.len()
     }
@@ -1116,6 +1132,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_set_capacity() {
+        let cache = Arc::new(LruCache::new(0, 4));
+        for key in ["a", "b", "c", "d"] {
+            cache.insert(key.to_string(), 0, 1, key.to_string());
+        }
+        assert_eq!(4, cache.get_memory_usage());
+
+        cache.set_capacity(3);
+        assert_eq!(3, cache.get_memory_usage());
+        assert!(cache.lookup(0, &"a".to_string()).is_none());
+
+        // Entries still referenced are evicted when released.
+        let entry = cache.lookup(0, &"b".to_string()).unwrap();
+        cache.set_capacity(0);
+        assert_eq!(1, cache.get_memory_usage());
+        drop(entry);
+        assert_eq!(0, cache.get_memory_usage());
+
+        cache.set_capacity(4);
+        cache.insert("e".to_string(), 0, 1, "e".to_string());
+        assert_eq!(1, cache.get_memory_usage());
+    }
+
     #[derive(Default, Debug)]
     struct TestLruCacheEventListener {
         evicted: Mutex<HashMap<String, String>>,
@@ -78,6 +78,16 @@ pub struct OrderedColumnDesc {
 }
 
 impl ColumnDesc {
+    /// Returns the number of dimensions of a `VECTOR(n)` column, whose type is
+    /// `DOUBLE PRECISION[]` with the type name `VECTOR(n)`.
+    pub fn vector_dims(&self) -> Option<usize> {
+        self.type_name
+            .strip_prefix("VECTOR(")?
+            .strip_suffix(')')?
+            .parse()
+            .ok()
+    }
+
     pub fn unnamed(column_id: ColumnId, data_type: DataType) -> ColumnDesc {
         ColumnDesc {
             data_type,
@@ -38,6 +38,73 @@ pub const DEFAULT_SUPPER_USER_FOR_PG: &str = "postgres";
 
 pub const RESERVED_PG_CATALOG_TABLE_ID: i32 = 1000;
 
+/// The property of a materialized view to checkpoint its streaming job every given number of
+/// barriers, e.g. `WITH (checkpoint_frequency = 10)`.
+pub const CHECKPOINT_FREQUENCY_KEY: &str = "checkpoint_frequency";
+
+/// The property of a materialized view to schedule its actors only to the compute nodes of the
+/// given resource group, e.g. `WITH (resource_group = 'reporting')`.
+pub const RESOURCE_GROUP_KEY: &str = "resource_group";
+
+/// The resource group of the compute nodes started without `--resource-group`, and of the
+/// streaming jobs created without [`RESOURCE_GROUP_KEY`].
+pub const DEFAULT_RESOURCE_GROUP: &str = "default";
+
+/// The property of an append-only table to expire its rows older than the given interval in
+/// compaction, e.g. `WITH (retention = '7 days')`.
+pub const RETENTION_KEY: &str = "retention";
+
+/// The retention of a table in seconds, normalized from [`RETENTION_KEY`] by the frontend.
+pub const RETENTION_SECONDS_KEY: &str = "retention_seconds";
+
+/// The property of an append-only table naming its event-time column, e.g.
+/// `WITH (watermark = 'ts', watermark_delay = '5 minutes')`. Joins and aggregations keyed on the
+/// column drop the late rows behind the watermark and purge the state older than it.
+pub const WATERMARK_KEY: &str = "watermark";
+
+/// The property of how long the watermark lags behind the largest event time seen, defaulting to
+/// zero.
+pub const WATERMARK_DELAY_KEY: &str = "watermark_delay";
+
+/// The watermark delay in seconds, normalized from [`WATERMARK_DELAY_KEY`] by the frontend.
+pub const WATERMARK_DELAY_SECONDS_KEY: &str = "watermark_delay_seconds";
+
+/// The property of a table naming the timestamp column its rows are partitioned by, e.g.
+/// `WITH (partition_by = 'ts', partition_interval = '1 day')`. The rows are stored in the order of
+/// the column, so that each partition is a contiguous key range of the table.
+pub const PARTITION_BY_KEY: &str = "partition_by";
+
+/// The property of the time span of each partition, defaulting to one day.
+pub const PARTITION_INTERVAL_KEY: &str = "partition_interval";
+
+/// The partition interval in seconds, normalized from [`PARTITION_INTERVAL_KEY`] by the frontend.
+pub const PARTITION_INTERVAL_SECONDS_KEY: &str = "partition_interval_seconds";
+
+/// The property of an append-only partitioned table to expire its partitions ending before the
+/// given interval ago in compaction, e.g. `WITH (partition_retention = '30 days')`.
+pub const PARTITION_RETENTION_KEY: &str = "partition_retention";
+
+/// The partition retention in seconds, normalized from [`PARTITION_RETENTION_KEY`] by the
+/// frontend.
+pub const PARTITION_RETENTION_SECONDS_KEY: &str = "partition_retention_seconds";
+
+/// The default partition interval in seconds.
+pub const DEFAULT_PARTITION_INTERVAL_SECONDS: u32 = 24 * 3600;
+
+/// The property of a full-text index, created by `CREATE INDEX ... USING FULLTEXT`, naming the
+/// indexed text column.
+pub const FULLTEXT_INDEX_COLUMN_KEY: &str = "fulltext_index_column";
+
+/// The name of the lexeme column of a full-text index.
+pub const LEXEME_COLUMN_NAME: &str = "_lexeme";
+
+/// The property of an IVF index on vectors, created by `CREATE INDEX ... USING IVF`, naming the
+/// indexed vector column.
+pub const IVF_INDEX_COLUMN_KEY: &str = "ivf_index_column";
+
+/// The name of the list column of an IVF index.
+pub const IVF_LIST_COLUMN_NAME: &str = "_ivf_list";
+
 /// The local system catalog reader in the frontend node.
 #[async_trait]
 pub trait SysCatalogReader: Sync + Send + 'static {
@@ -36,6 +36,18 @@ pub struct TableDesc {
 
     /// Whether the table source is append-only
     pub appendonly: bool,
+
+    /// The event-time watermark of the table, if any.
+    pub watermark: Option<WatermarkDesc>,
+}
+
+/// An event-time watermark on a timestamp column of a table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WatermarkDesc {
+    /// Index of the event-time column in `columns`.
+    pub column_index: usize,
+    /// How long the watermark lags behind the largest event time seen.
+    pub delay_ms: u64,
 }
 
 impl TableDesc {
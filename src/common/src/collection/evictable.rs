@@ -16,9 +16,32 @@ use std::alloc::{Allocator, Global};
 use std::cmp::Eq;
 use std::hash::{BuildHasher, Hash};
 use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use lru::{DefaultHasher, LruCache};
 
+/// Fraction in permille of their target capacity that caches keep when evicting. The memory
+/// manager of the compute node shrinks it under memory pressure, and grows it back when the
+/// pressure is gone. It's process-wide as the memory usage is only known for the whole process.
+static CACHE_CAPACITY_PERMILLE: AtomicUsize = AtomicUsize::new(1000);
+
+/// Returns the fraction in permille of their target capacity that caches keep when evicting.
+pub fn cache_capacity_permille() -> usize {
+    CACHE_CAPACITY_PERMILLE.load(Ordering::Relaxed)
+}
+
+/// Sets the fraction in permille of their target capacity that caches keep when evicting.
+pub fn set_cache_capacity_permille(permille: usize) {
+    CACHE_CAPACITY_PERMILLE.store(permille.min(1000), Ordering::Relaxed);
+}
+
+/// Returns the capacity a cache of `target_cap` keeps when evicting under the current memory
+/// pressure. Non-empty caches keep at least one item.
+pub fn scaled_capacity(target_cap: usize) -> usize {
+    let scaled = (target_cap as u128 * cache_capacity_permille() as u128 / 1000) as usize;
+    scaled.max(target_cap.min(1))
+}
+
 /// A wrapper for [`LruCache`] which provides manual eviction.
 pub struct EvictableHashMap<K, V, S = DefaultHasher, A: Clone + Allocator = Global> {
     inner: LruCache<K, V, S, A>,
@@ -80,9 +103,10 @@ impl<K: Hash + Eq, V, S: BuildHasher, A: Clone + Allocator> EvictableHashMap<K,
         self.inner.get_mut(key).unwrap()
     }
 
-    /// Evict items in the map and only keep up-to `target_cap` items.
+    /// Evict items in the map and only keep up-to `target_cap` items, fewer under memory
+    /// pressure.
     pub fn evict_to_target_cap(&mut self) {
-        self.inner.resize(self.target_cap);
+        self.inner.resize(scaled_capacity(self.target_cap));
         self.inner.resize(usize::MAX);
     }
 
@@ -134,4 +158,12 @@ mod tests {
         assert!(map.get(&(items_count - target_cap - 1)).is_none());
         assert!(map.get(&(items_count - target_cap)).is_some());
     }
+
+    #[test]
+    fn test_scaled_capacity() {
+        assert_eq!(scaled_capacity(0), 0);
+        assert_eq!(scaled_capacity(1), 1);
+        assert_eq!(scaled_capacity(1 << 16), 1 << 16);
+        assert_eq!(scaled_capacity(usize::MAX), usize::MAX);
+    }
 }
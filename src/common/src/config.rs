@@ -13,18 +13,27 @@
 // limitations under the License.
 
 use std::fs;
-use std::path::PathBuf;
 
+use risingwave_pb::common::buffer::CompressionType;
 use serde::{Deserialize, Serialize};
 
 use crate::error::ErrorCode::InternalError;
 use crate::error::{Result, RwError};
 
+/// Prefix of the environment variables overriding the config file. For example,
+/// `RW_CONFIG__STREAMING__CHECKPOINT_INTERVAL_MS=500` overrides `checkpoint_interval_ms` in section
+/// `[streaming]`.
+pub const CONFIG_ENV_PREFIX: &str = "RW_CONFIG__";
+
+/// Configs shared by all node types, with a section per subsystem. Each node only respects the
+/// sections of the subsystems it runs, but the whole file is validated so that a single file can be
+/// used for the cluster.
+///
 /// TODO(TaoWu): The configs here may be preferable to be managed under corresponding module
 /// separately.
 #[derive(Clone, Debug, Serialize, Deserialize, Default)]
 #[serde(deny_unknown_fields)]
-pub struct ComputeNodeConfig {
+pub struct RwConfig {
     // For connection
     #[serde(default)]
     pub server: ServerConfig,
@@ -40,29 +49,111 @@ pub struct ComputeNodeConfig {
     // Below for Hummock.
     #[serde(default)]
     pub storage: StorageConfig,
+
+    // Below for audit and slow query logging in frontend.
+    #[serde(default)]
+    pub audit_log: AuditLogConfig,
+
+    // Below for storage quotas in frontend.
+    #[serde(default)]
+    pub storage_quota: StorageQuotaConfig,
+
+    // Below for automatic parallelism tuning in meta.
+    #[serde(default)]
+    pub auto_parallelism: AutoParallelismConfig,
 }
 
-pub fn load_config(path: &str) -> ComputeNodeConfig {
-    if path.is_empty() {
-        tracing::warn!("risingwave.toml not found, using default config.");
-        return ComputeNodeConfig::default();
+impl RwConfig {
+    /// Returns the config in TOML with all the defaults filled, as used by
+    /// `--print-effective-config`.
+    pub fn to_toml_string(&self) -> String {
+        toml::to_string(self).unwrap()
     }
+}
+
+/// Loads the config for any node type. The config is layered as the defaults, the config file at
+/// `path` if given, and then the environment variables prefixed with [`CONFIG_ENV_PREFIX`].
+/// Unknown sections or keys in either the file or the environment variables are rejected.
+pub fn load_config(path: &str) -> Result<RwConfig> {
+    let config = if path.is_empty() {
+        tracing::warn!("risingwave.toml not found, using default config.");
+        toml::value::Table::new()
+    } else {
+        let config_str = fs::read_to_string(path).map_err(|e| {
+            RwError::from(InternalError(format!(
+                "failed to open config file '{}': {}",
+                path, e
+            )))
+        })?;
+        toml::from_str(&config_str)
+            .map_err(|e| RwError::from(InternalError(format!("parse error {}", e))))?
+    };
+    build_config(config, std::env::vars())
+}
 
-    ComputeNodeConfig::init(path.to_owned().into()).unwrap()
+fn build_config(
+    mut config: toml::value::Table,
+    vars: impl Iterator<Item = (String, String)>,
+) -> Result<RwConfig> {
+    apply_env_overrides(&mut config, vars)?;
+    toml::Value::Table(config)
+        .try_into()
+        .map_err(|e| RwError::from(InternalError(format!("invalid config: {}", e))))
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize, Default)]
-#[serde(deny_unknown_fields)]
-pub struct FrontendConfig {
-    // For connection
-    #[serde(default)]
-    pub server: ServerConfig,
+fn apply_env_overrides(
+    config: &mut toml::value::Table,
+    vars: impl Iterator<Item = (String, String)>,
+) -> Result<()> {
+    for (name, value) in vars {
+        let path = match name.strip_prefix(CONFIG_ENV_PREFIX) {
+            Some(path) => path,
+            None => continue,
+        };
+        let (section_name, key) = path.split_once("__").ok_or_else(|| {
+            RwError::from(InternalError(format!(
+                "invalid config env var {}, expect {}<SECTION>__<KEY>",
+                name, CONFIG_ENV_PREFIX
+            )))
+        })?;
+        let section = config
+            .entry(section_name.to_lowercase())
+            .or_insert_with(|| toml::Value::Table(Default::default()));
+        match section {
+            toml::Value::Table(section) => {
+                section.insert(key.to_lowercase(), parse_env_value(&value));
+            }
+            _ => {
+                return Err(RwError::from(InternalError(format!(
+                    "invalid config env var {}, {} is not a section",
+                    name, section_name
+                ))))
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Parses the value of an environment variable as a TOML value, or a string if it's not valid
+/// TOML, so that `lz4` doesn't need to be quoted.
+fn parse_env_value(value: &str) -> toml::Value {
+    format!("v = {}", value)
+        .parse::<toml::value::Table>()
+        .ok()
+        .and_then(|mut table| table.remove("v"))
+        .unwrap_or_else(|| toml::Value::String(value.to_string()))
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct ServerConfig {
     #[serde(default = "default::heartbeat_interval_ms")]
     pub heartbeat_interval_ms: u32,
+
+    /// The deadline of graceful shutdown after receiving `SIGTERM`. Frontends drain the
+    /// connections and compute nodes wait for the next checkpoint until the deadline.
+    #[serde(default = "default::graceful_shutdown_timeout_ms")]
+    pub graceful_shutdown_timeout_ms: u32,
 }
 
 impl Default for ServerConfig {
@@ -71,11 +162,182 @@ impl Default for ServerConfig {
     }
 }
 
+/// Configs of the structured audit log in frontend. Every statement is checked against these
+/// rules after it finishes, and matching ones are written as JSON lines to the audit log target.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AuditLogConfig {
+    /// Statements running longer than this are logged as slow queries. 0 disables slow query
+    /// logging.
+    #[serde(default = "default::slow_query_threshold_ms")]
+    pub slow_query_threshold_ms: u64,
+
+    /// Whether to log all DDL statements.
+    #[serde(default = "default::log_ddl")]
+    pub log_ddl: bool,
+
+    /// Whether to log all failed statements.
+    #[serde(default = "default::log_failed_statements")]
+    pub log_failed_statements: bool,
+
+    /// Number of recent records kept in memory and exposed by the `rw_audit_log` system table.
+    /// 0 means records are only written to the log.
+    #[serde(default = "default::audit_log_system_table_capacity")]
+    pub system_table_capacity: usize,
+
+    /// Object store location, e.g. `s3://bucket/replay`, where the plans of slow and failed
+    /// queries are captured as bundles that can be replayed by `REPLAY`. Empty disables capturing.
+    #[serde(default = "default::replay_capture_location")]
+    pub replay_capture_location: String,
+}
+
+impl Default for AuditLogConfig {
+    fn default() -> Self {
+        toml::from_str("").unwrap()
+    }
+}
+
+/// Configs of the per-database storage quota checked by frontend before running DML statements.
+/// The usage of a database is the total estimated size of its tables, materialized views and
+/// indexes, as reported by the `rw_table_storage` system table.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StorageQuotaConfig {
+    /// Storage quota of each database in MB. 0 disables the quota.
+    #[serde(default = "default::database_storage_quota_mb")]
+    pub database_storage_quota_mb: u64,
+
+    /// Whether to reject DML statements on a database exceeding its quota. If disabled, an alert
+    /// is only logged.
+    #[serde(default = "default::reject_writes_over_quota")]
+    pub reject_writes_over_quota: bool,
+}
+
+impl Default for StorageQuotaConfig {
+    fn default() -> Self {
+        toml::from_str("").unwrap()
+    }
+}
+
+/// Configs of the controller in meta that tunes the parallelism of fragments according to their
+/// backpressure. Backpressure of a fragment is measured as the fraction of time its upstream
+/// actors are blocked on sending to it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AutoParallelismConfig {
+    /// Whether the parallelism changes are only proposed or also applied.
+    #[serde(default)]
+    pub mode: AutoParallelismMode,
+
+    /// Interval at which the backpressure of the fragments is evaluated.
+    #[serde(default = "default::auto_parallelism_evaluation_interval_sec")]
+    pub evaluation_interval_sec: u64,
+
+    /// Minimum parallelism a fragment is scaled in to.
+    #[serde(default = "default::auto_parallelism_min_parallelism")]
+    pub min_parallelism: usize,
+
+    /// Maximum parallelism a fragment is scaled out to. 0 means all parallel units of the resource
+    /// group of the fragment.
+    #[serde(default = "default::auto_parallelism_max_parallelism")]
+    pub max_parallelism: usize,
+
+    /// A fragment is scaled out if its upstream actors are blocked on it for more than this
+    /// fraction of the time.
+    #[serde(default = "default::auto_parallelism_scale_out_threshold")]
+    pub scale_out_threshold: f64,
+
+    /// A fragment is scaled in if its upstream actors are blocked on it for less than this
+    /// fraction of the time in `scale_in_windows` consecutive evaluations.
+    #[serde(default = "default::auto_parallelism_scale_in_threshold")]
+    pub scale_in_threshold: f64,
+
+    /// Number of consecutive evaluations below `scale_in_threshold` before scaling in.
+    #[serde(default = "default::auto_parallelism_scale_in_windows")]
+    pub scale_in_windows: usize,
+}
+
+impl Default for AutoParallelismConfig {
+    fn default() -> Self {
+        toml::from_str("").unwrap()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AutoParallelismMode {
+    /// The controller is not started.
+    #[default]
+    Disabled,
+    /// Parallelism changes are logged and exported as metrics, but not applied.
+    Propose,
+    /// Parallelism changes are applied by rescheduling the fragments.
+    Apply,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct BatchConfig {
     // #[serde(default = "default::chunk_size")]
     // pub chunk_size: u32,
+    /// Compression requested for chunks received from remote exchange.
+    #[serde(default)]
+    pub exchange_compression: ExchangeCompression,
+
+    /// Number of optimized batch plans cached in each frontend, keyed by the normalized statement.
+    /// 0 disables the plan cache.
+    #[serde(default = "default::plan_cache_capacity")]
+    pub plan_cache_capacity: usize,
+
+    /// Whether statements only differing in their constants share a generic plan in the plan
+    /// cache.
+    #[serde(default = "default::plan_cache_auto_prepare")]
+    pub plan_cache_auto_prepare: bool,
+
+    /// Number of batch query results cached in each frontend, which are served to identical
+    /// queries reading the same epoch. 0 disables the result cache.
+    #[serde(default = "default::result_cache_capacity")]
+    pub result_cache_capacity: usize,
+
+    /// Results of more rows than this are not cached.
+    #[serde(default = "default::result_cache_max_rows")]
+    pub result_cache_max_rows: usize,
+
+    /// Cached results expire after this duration even if no newer epoch is committed.
+    #[serde(default = "default::result_cache_ttl_ms")]
+    pub result_cache_ttl_ms: u64,
+
+    /// Memory in bytes the hash tables, sort buffers and exchange buffers of a batch task may use
+    /// on a compute node. A task exceeding it fails with the memory used by each of its operators.
+    /// 0 means unlimited.
+    #[serde(default = "default::task_memory_limit_bytes")]
+    pub task_memory_limit_bytes: usize,
+
+    /// Rows written to a table by DML statements that a compute node buffers before they're taken
+    /// by the streaming job, for each priority. Further writes wait for the buffered rows to be
+    /// taken, or fail if `dml_fail_on_full` is set.
+    #[serde(default = "default::dml_channel_max_buffered_rows")]
+    pub dml_channel_max_buffered_rows: usize,
+
+    /// Whether DML statements fail instead of waiting when the buffer of the table is full.
+    #[serde(default)]
+    pub dml_fail_on_full: bool,
+
+    /// How long a frontend holds an `INSERT ... VALUES` statement of constants, so that the
+    /// concurrent ones into the same table are coalesced into a single insert. 0 disables the
+    /// batching.
+    #[serde(default)]
+    pub insert_batch_max_delay_ms: u64,
+
+    /// A batch of coalesced inserts is sent without waiting for the delay once it has this many
+    /// rows.
+    #[serde(default = "default::insert_batch_max_rows")]
+    pub insert_batch_max_rows: usize,
+
+    /// Local directories that `disk://` locations of `UNLOAD` and external tables must be under.
+    /// `disk://` locations are rejected if empty.
+    #[serde(default)]
+    pub lake_disk_roots: Vec<String>,
 }
 
 impl Default for BatchConfig {
@@ -91,6 +353,29 @@ pub struct StreamingConfig {
     // pub chunk_size: u32,
     #[serde(default = "default::checkpoint_interval_ms")]
     pub checkpoint_interval_ms: u32,
+
+    /// Number of barriers between two checkpoints. Writes of barriers not checkpointed are
+    /// committed together with the next checkpoint.
+    #[serde(default = "default::checkpoint_frequency")]
+    pub checkpoint_frequency: usize,
+
+    /// Compression requested for chunks received from remote exchange.
+    #[serde(default)]
+    pub exchange_compression: ExchangeCompression,
+
+    /// Memory in bytes the compute node may use. The executor caches are shrunk as the memory
+    /// allocated approaches it. 0 disables the memory manager.
+    #[serde(default = "default::total_memory_available_bytes")]
+    pub total_memory_available_bytes: usize,
+
+    /// Interval at which the memory manager checks the memory allocated.
+    #[serde(default = "default::memory_manager_interval_ms")]
+    pub memory_manager_interval_ms: u64,
+
+    /// Maximum number of keys whose states are fetched from the state store concurrently when a
+    /// hash join or hash aggregation processes a chunk.
+    #[serde(default = "default::state_fetch_concurrency")]
+    pub state_fetch_concurrency: usize,
 }
 
 impl Default for StreamingConfig {
@@ -99,6 +384,27 @@ impl Default for StreamingConfig {
     }
 }
 
+/// Compression of chunks sent over remote exchange. Only buffers larger than
+/// [`COMPRESSION_THRESHOLD`](crate::array::compression::COMPRESSION_THRESHOLD) are compressed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExchangeCompression {
+    #[default]
+    None,
+    Lz4,
+    Zstd,
+}
+
+impl From<ExchangeCompression> for CompressionType {
+    fn from(compression: ExchangeCompression) -> Self {
+        match compression {
+            ExchangeCompression::None => CompressionType::None,
+            ExchangeCompression::Lz4 => CompressionType::Lz4,
+            ExchangeCompression::Zstd => CompressionType::Zstd,
+        }
+    }
+}
+
 /// Currently all configurations are server before they can be specified with DDL syntaxes.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
@@ -161,6 +467,19 @@ pub struct StorageConfig {
     /// Number of tasks shared buffer can upload in parallel.
     #[serde(default = "default::share_buffer_upload_concurrency")]
     pub share_buffer_upload_concurrency: usize,
+
+    /// Compression algorithm of the L0 SSTs built from shared buffer, one of `None`, `Lz4` and
+    /// `Zstd`. SSTs of the other levels are compressed as configured in the compaction group.
+    #[serde(default = "default::shared_buffer_compression_algorithm")]
+    pub shared_buffer_compression_algorithm: String,
+
+    /// Compression level of `shared_buffer_compression_algorithm`. 0 means the default level.
+    #[serde(default = "default::shared_buffer_compression_level")]
+    pub shared_buffer_compression_level: u32,
+
+    /// Number of blocks to fetch in background ahead of sequential scans. 0 disables prefetching.
+    #[serde(default = "default::scan_prefetch_block_count")]
+    pub scan_prefetch_block_count: usize,
 }
 
 impl Default for StorageConfig {
@@ -169,42 +488,17 @@ impl Default for StorageConfig {
     }
 }
 
-impl ComputeNodeConfig {
-    pub fn init(path: PathBuf) -> Result<ComputeNodeConfig> {
-        let config_str = fs::read_to_string(path.clone()).map_err(|e| {
-            RwError::from(InternalError(format!(
-                "failed to open config file '{}': {}",
-                path.to_string_lossy(),
-                e
-            )))
-        })?;
-        let config: ComputeNodeConfig = toml::from_str(config_str.as_str())
-            .map_err(|e| RwError::from(InternalError(format!("parse error {}", e))))?;
-        Ok(config)
-    }
-}
-
-impl FrontendConfig {
-    pub fn init(path: PathBuf) -> Result<Self> {
-        let config_str = fs::read_to_string(path.clone()).map_err(|e| {
-            RwError::from(InternalError(format!(
-                "failed to open config file '{}': {}",
-                path.to_string_lossy(),
-                e
-            )))
-        })?;
-        let config: FrontendConfig = toml::from_str(config_str.as_str())
-            .map_err(|e| RwError::from(InternalError(format!("parse error {}", e))))?;
-        Ok(config)
-    }
-}
-
-mod default {
+/// Default values of the configurations, also used where the configuration isn't available.
+pub mod default {
 
     pub fn heartbeat_interval_ms() -> u32 {
         1000
     }
 
+    pub fn graceful_shutdown_timeout_ms() -> u32 {
+        30000
+    }
+
     pub fn chunk_size() -> u32 {
         1024
     }
@@ -270,7 +564,174 @@ mod default {
         100
     }
 
+    pub fn checkpoint_frequency() -> usize {
+        1
+    }
+
+    pub fn total_memory_available_bytes() -> usize {
+        0
+    }
+
+    pub fn memory_manager_interval_ms() -> u64 {
+        1000
+    }
+
+    pub fn state_fetch_concurrency() -> usize {
+        16
+    }
+
     pub fn share_buffer_upload_concurrency() -> usize {
         8
     }
+
+    pub fn shared_buffer_compression_algorithm() -> String {
+        "None".to_string()
+    }
+
+    pub fn shared_buffer_compression_level() -> u32 {
+        0
+    }
+
+    pub fn scan_prefetch_block_count() -> usize {
+        8
+    }
+
+    pub fn plan_cache_capacity() -> usize {
+        1024
+    }
+
+    pub fn plan_cache_auto_prepare() -> bool {
+        true
+    }
+
+    pub fn result_cache_capacity() -> usize {
+        0
+    }
+
+    pub fn result_cache_max_rows() -> usize {
+        10000
+    }
+
+    pub fn result_cache_ttl_ms() -> u64 {
+        10000
+    }
+
+    pub fn task_memory_limit_bytes() -> usize {
+        0
+    }
+
+    pub fn dml_channel_max_buffered_rows() -> usize {
+        32768
+    }
+
+    pub fn insert_batch_max_rows() -> usize {
+        1024
+    }
+
+    pub fn slow_query_threshold_ms() -> u64 {
+        5000
+    }
+
+    pub fn log_ddl() -> bool {
+        true
+    }
+
+    pub fn log_failed_statements() -> bool {
+        true
+    }
+
+    pub fn audit_log_system_table_capacity() -> usize {
+        1024
+    }
+
+    pub fn replay_capture_location() -> String {
+        String::new()
+    }
+
+    pub fn database_storage_quota_mb() -> u64 {
+        0
+    }
+
+    pub fn reject_writes_over_quota() -> bool {
+        true
+    }
+
+    pub fn auto_parallelism_evaluation_interval_sec() -> u64 {
+        60
+    }
+
+    pub fn auto_parallelism_min_parallelism() -> usize {
+        1
+    }
+
+    pub fn auto_parallelism_max_parallelism() -> usize {
+        0
+    }
+
+    pub fn auto_parallelism_scale_out_threshold() -> f64 {
+        0.5
+    }
+
+    pub fn auto_parallelism_scale_in_threshold() -> f64 {
+        0.05
+    }
+
+    pub fn auto_parallelism_scale_in_windows() -> usize {
+        5
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_from_str(config_str: &str, vars: Vec<(&str, &str)>) -> Result<RwConfig> {
+        build_config(
+            toml::from_str(config_str).unwrap(),
+            vars.into_iter()
+                .map(|(name, value)| (name.to_string(), value.to_string())),
+        )
+    }
+
+    #[test]
+    fn test_env_overrides() {
+        let config = load_from_str(
+            "[streaming]\ncheckpoint_interval_ms = 100",
+            vec![
+                ("RW_CONFIG__STREAMING__CHECKPOINT_INTERVAL_MS", "500"),
+                ("RW_CONFIG__BATCH__EXCHANGE_COMPRESSION", "lz4"),
+                ("RW_CONFIG__AUDIT_LOG__LOG_DDL", "false"),
+                ("RW_CONFIG__AUTO_PARALLELISM__MODE", "propose"),
+                ("UNRELATED", "1"),
+            ],
+        )
+        .unwrap();
+        assert_eq!(config.streaming.checkpoint_interval_ms, 500);
+        assert_eq!(config.batch.exchange_compression, ExchangeCompression::Lz4);
+        assert!(!config.audit_log.log_ddl);
+        assert_eq!(config.auto_parallelism.mode, AutoParallelismMode::Propose);
+        assert_eq!(
+            config.storage.block_cache_capacity_mb,
+            default::block_cache_capacity_mb()
+        );
+    }
+
+    #[test]
+    fn test_unknown_keys() {
+        assert!(load_from_str("[streaming]\nno_such_key = 1", vec![]).is_err());
+        assert!(load_from_str("[no_such_section]", vec![]).is_err());
+        assert!(load_from_str("", vec![("RW_CONFIG__STORAGE__NO_SUCH_KEY", "1")]).is_err());
+        assert!(load_from_str("", vec![("RW_CONFIG__STORAGE", "1")]).is_err());
+    }
+
+    #[test]
+    fn test_print_effective_config() {
+        let config = load_from_str("[server]\nheartbeat_interval_ms = 10", vec![]).unwrap();
+        let printed = load_from_str(&config.to_toml_string(), vec![]).unwrap();
+        assert_eq!(printed.server.heartbeat_interval_ms, 10);
+        assert_eq!(
+            printed.storage.data_directory,
+            config.storage.data_directory
+        );
+    }
 }
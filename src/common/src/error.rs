@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use std::alloc::Layout;
-use std::backtrace::Backtrace;
+use std::backtrace::{Backtrace, BacktraceStatus};
 use std::convert::Infallible;
 use std::fmt::{Debug, Display, Formatter};
 use std::io::Error as IoError;
@@ -115,6 +115,8 @@ pub enum ErrorCode {
     ParseError(String),
     #[error("Bind error: {0}")]
     BindError(String),
+    #[error("Plan error: {0}")]
+    PlanError(String),
     #[error("Catalog error: {0}")]
     CatalogError(BoxedError),
     #[error("Out of range")]
@@ -153,6 +155,15 @@ pub enum ErrorCode {
     #[error("unrecognized configuration parameter \"{0}\"")]
     UnrecognizedConfigurationParameter(String),
 
+    #[error("Storage quota exceeded: {0}")]
+    StorageQuotaExceeded(String),
+
+    #[error("Resource limit exceeded: {0}")]
+    ResourceLimitExceeded(String),
+
+    #[error("Permission denied: {0}")]
+    PermissionDenied(String),
+
     /// `Eof` represents an upstream node will not generate new data. This error is rare in our
     /// system, currently only used in the `BatchQueryExecutor` as an ephemeral solution.
     #[error("End of the stream")]
@@ -178,8 +189,84 @@ pub fn parse_error(msg: impl Into<String>) -> RwError {
 pub struct RwError {
     inner: Arc<ErrorCode>,
     backtrace: Arc<Backtrace>,
+    report: ErrorReport,
+}
+
+/// What's reported to the client besides the message. Each field overrides the one derived from
+/// the [`ErrorCode`] if set, e.g. for the errors decoded from other nodes.
+#[derive(Clone, Debug, Default)]
+struct ErrorReport {
+    sqlstate: Option<String>,
+    hint: Option<String>,
+    retryable: Option<bool>,
+}
+
+/// Decode the [`RwError`] carried by `status`, if it's raised by another node.
+fn decode_grpc_status(status: &tonic::Status) -> Option<Status> {
+    status
+        .metadata()
+        .get_bin(RW_ERROR_GRPC_HEADER)
+        .and_then(|header| header.to_bytes().ok())
+        .and_then(|bytes| Status::decode(bytes).ok())
+}
+
+impl ErrorReport {
+    /// Decode the report of the [`RwError`] carried by `status`, if it's raised by another node.
+    fn from_grpc_status(status: &tonic::Status) -> Self {
+        match decode_grpc_status(status) {
+            Some(status) => Self {
+                sqlstate: (!status.sqlstate.is_empty()).then(|| status.sqlstate),
+                hint: (!status.hint.is_empty()).then(|| status.hint),
+                retryable: Some(status.retryable),
+            },
+            None => Self::default(),
+        }
+    }
 }
 
+/// An error raised by another node, with the causal chain and the backtrace captured there.
+#[derive(Clone)]
+pub struct RemoteError {
+    message: String,
+    context: Vec<String>,
+    backtrace: Option<String>,
+}
+
+impl RemoteError {
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The causes of the error, from the outermost to the root one.
+    pub fn context(&self) -> &[String] {
+        &self.context
+    }
+
+    pub fn backtrace(&self) -> Option<&str> {
+        self.backtrace.as_deref()
+    }
+}
+
+impl Display for RemoteError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)?;
+        for cause in &self.context {
+            write!(f, ": {}", cause)?;
+        }
+        Ok(())
+    }
+}
+
+// `ErrorCode::RpcError` is formatted with `Debug`, which shouldn't differ from the `Display` with
+// the causal chain.
+impl Debug for RemoteError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+impl std::error::Error for RemoteError {}
+
 impl From<RwError> for tonic::Status {
     fn from(err: RwError) -> Self {
         match *err.inner {
@@ -205,16 +292,97 @@ impl RwError {
     /// We can't use grpc/tonic's library directly because we need to customized error code and
     /// information.
     fn to_status(&self) -> Status {
-        // TODO: We need better error reporting for stacktrace.
+        let message = self.to_string();
         Status {
             code: self.inner.get_code() as i32,
-            message: self.to_string(),
+            context: self.context(&message),
+            backtrace: self.captured_backtrace().unwrap_or_default(),
+            message,
+            sqlstate: self.sqlstate().to_string(),
+            hint: self.hint().unwrap_or_default().to_string(),
+            retryable: self.is_retryable(),
+        }
+    }
+
+    /// The causes of the error not mentioned in `message` yet, from the outermost to the root one.
+    fn context(&self, message: &str) -> Vec<String> {
+        let mut context: Vec<String> = vec![];
+        let mut source = std::error::Error::source(&*self.inner);
+        while let Some(err) = source {
+            let cause = err.to_string();
+            if !message.contains(&cause) && !context.iter().any(|c| c.contains(&cause)) {
+                context.push(cause);
+            }
+            source = err.source();
+        }
+        context
+    }
+
+    /// The backtrace of the error if it's captured, i.e. `RUST_BACKTRACE` is enabled. The one
+    /// captured by the remote node is preferred for the errors raised by other nodes.
+    fn captured_backtrace(&self) -> Option<String> {
+        if let Some(remote) = self.remote() {
+            return remote.backtrace().map(str::to_string);
+        }
+        let backtrace = self.inner.backtrace().unwrap_or(&*self.backtrace);
+        (backtrace.status() == BacktraceStatus::Captured).then(|| backtrace.to_string())
+    }
+
+    /// The error raised by the other node, if it is.
+    pub fn remote(&self) -> Option<&RemoteError> {
+        match &*self.inner {
+            ErrorCode::RpcError(err) => err.downcast_ref::<RemoteError>(),
+            _ => None,
         }
     }
 
     pub fn inner(&self) -> &ErrorCode {
         &self.inner
     }
+
+    /// The SQLSTATE code reported to the client.
+    pub fn sqlstate(&self) -> &str {
+        self.report
+            .sqlstate
+            .as_deref()
+            .unwrap_or_else(|| self.inner.sqlstate())
+    }
+
+    /// The hint reported to the client on how to fix the error.
+    pub fn hint(&self) -> Option<&str> {
+        self.report
+            .hint
+            .as_deref()
+            .or_else(|| self.inner.hint())
+            .or_else(|| {
+                self.is_retryable()
+                    .then(|| "The failure may be transient, please retry the statement.")
+            })
+    }
+
+    /// Whether the failure is transient, so that the statement may succeed if retried.
+    pub fn is_retryable(&self) -> bool {
+        self.report
+            .retryable
+            .unwrap_or_else(|| self.inner.is_retryable())
+    }
+
+    pub fn with_sqlstate(mut self, sqlstate: impl Into<String>) -> Self {
+        self.report.sqlstate = Some(sqlstate.into());
+        self
+    }
+
+    pub fn with_hint(mut self, hint: impl Into<String>) -> Self {
+        self.report.hint = Some(hint.into());
+        self
+    }
+
+    /// Report the error to the client with `report`, e.g. the one of the error raised by another
+    /// node.
+    fn with_report(mut self, report: ErrorReport) -> Self {
+        self.report = report;
+        self
+    }
 }
 
 impl From<ErrorCode> for RwError {
@@ -222,6 +390,7 @@ impl From<ErrorCode> for RwError {
         Self {
             inner: Arc::new(code),
             backtrace: Arc::new(Backtrace::capture()),
+            report: ErrorReport::default(),
         }
     }
 }
@@ -231,6 +400,7 @@ impl From<JoinError> for RwError {
         Self {
             inner: Arc::new(ErrorCode::InternalError(join_error.to_string())),
             backtrace: Arc::new(Backtrace::capture()),
+            report: ErrorReport::default(),
         }
     }
 }
@@ -285,7 +455,11 @@ impl Debug for RwError {
             self.inner,
             // Use inner error's backtrace by default, otherwise use the generated one in `From`.
             self.inner.backtrace().unwrap_or(&*self.backtrace)
-        )
+        )?;
+        if let Some(backtrace) = self.remote().and_then(|remote| remote.backtrace()) {
+            write!(f, "\nremote backtrace:\n{}", backtrace)?;
+        }
+        Ok(())
     }
 }
 
@@ -340,9 +514,75 @@ impl ErrorCode {
             ErrorCode::SchedulerError(_) => 30,
             ErrorCode::SinkError(_) => 31,
             ErrorCode::RpcError(_) => 32,
+            ErrorCode::StorageQuotaExceeded(_) => 33,
+            ErrorCode::ResourceLimitExceeded(_) => 34,
+            ErrorCode::PermissionDenied(_) => 35,
+            ErrorCode::PlanError(_) => 36,
             ErrorCode::UnknownError(_) => 101,
         }
     }
+
+    /// The SQLSTATE code of the error. See
+    /// <https://www.postgresql.org/docs/current/errcodes-appendix.html>.
+    pub fn sqlstate(&self) -> &'static str {
+        match self {
+            ErrorCode::OK => "00000",
+            ErrorCode::MemoryError { .. } => "53200",
+            ErrorCode::NotImplemented(..) => "0A000",
+            ErrorCode::IoError(_) => "58030",
+            ErrorCode::StorageError(_)
+            | ErrorCode::ConnectorError(_)
+            | ErrorCode::SinkError(_)
+            | ErrorCode::MetaError(_) => "58000",
+            ErrorCode::ExprError(_) | ErrorCode::ArrayError(_) => "22000",
+            ErrorCode::NumericValueOutOfRange => "22003",
+            ErrorCode::InvalidInputSyntax(_) => "22P02",
+            ErrorCode::InvalidConfigValue { .. } | ErrorCode::InvalidParameterValue(_) => "22023",
+            ErrorCode::RpcError(_) => "08006",
+            ErrorCode::ProtocolError(_) => "08P01",
+            ErrorCode::ParseError(_) => "42601",
+            ErrorCode::BindError(_) | ErrorCode::PlanError(_) | ErrorCode::CatalogError(_) => {
+                "42000"
+            }
+            ErrorCode::ItemNotFound(_) | ErrorCode::UnrecognizedConfigurationParameter(_) => {
+                "42704"
+            }
+            ErrorCode::PermissionDenied(_) => "42501",
+            ErrorCode::StorageQuotaExceeded(_) => "53100",
+            ErrorCode::ResourceLimitExceeded(_) => "53000",
+            ErrorCode::InternalError(_)
+            | ErrorCode::ProstError(_)
+            | ErrorCode::StreamError(_)
+            | ErrorCode::SchedulerError(_)
+            | ErrorCode::TaskNotFound
+            | ErrorCode::MemComparableError(_)
+            | ErrorCode::ValueEncodingError(_)
+            | ErrorCode::UnknownWorker
+            | ErrorCode::Eof
+            | ErrorCode::UnknownError(_) => "XX000",
+        }
+    }
+
+    /// Whether the failure is transient, e.g. a node is restarting or the cluster is busy.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            ErrorCode::RpcError(_) | ErrorCode::MetaError(_) | ErrorCode::ResourceLimitExceeded(_)
+        )
+    }
+
+    /// The hint on how to fix the error.
+    fn hint(&self) -> Option<&'static str> {
+        match self {
+            ErrorCode::UnrecognizedConfigurationParameter(_) => {
+                Some("Use SHOW ALL to list the configuration parameters.")
+            }
+            ErrorCode::StorageQuotaExceeded(_) => {
+                Some("Drop unused tables or materialized views, or raise the quota.")
+            }
+            _ => None,
+        }
+    }
 }
 
 impl PartialEq for ErrorCode {
@@ -372,7 +612,17 @@ impl From<ProstFieldNotFound> for RwError {
 
 impl From<tonic::Status> for RwError {
     fn from(err: tonic::Status) -> Self {
-        ErrorCode::RpcError(err.into()).into()
+        let report = ErrorReport::from_grpc_status(&err);
+        let code = match decode_grpc_status(&err) {
+            // Keep the causal chain of the error raised by another node.
+            Some(status) => ErrorCode::RpcError(Box::new(RemoteError {
+                message: status.message,
+                context: status.context,
+                backtrace: (!status.backtrace.is_empty()).then(|| status.backtrace),
+            })),
+            None => ErrorCode::RpcError(err.into()),
+        };
+        RwError::from(code).with_report(report)
     }
 }
 
@@ -630,6 +880,60 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_error_report() {
+        let err: RwError = ErrorCode::ParseError("syntax".to_string()).into();
+        assert_eq!(err.sqlstate(), "42601");
+        assert_eq!(err.hint(), None);
+        assert!(!err.is_retryable());
+
+        let err: RwError = ErrorCode::MetaError("meta is down".to_string()).into();
+        assert!(err.is_retryable());
+        assert!(err.hint().is_some());
+
+        let err = RwError::from(ErrorCode::CatalogError(anyhow_error!("exists").into()))
+            .with_sqlstate("42P07")
+            .with_hint("hint");
+        assert_eq!(err.sqlstate(), "42P07");
+        assert_eq!(err.hint(), Some("hint"));
+
+        // What's reported to the client is kept across nodes.
+        let remote = RwError::from(tonic::Status::from(err));
+        assert!(matches!(remote.inner(), ErrorCode::RpcError(_)));
+        assert_eq!(remote.sqlstate(), "42P07");
+        assert_eq!(remote.hint(), Some("hint"));
+        assert!(!remote.is_retryable());
+    }
+
+    #[test]
+    fn test_remote_error_context() {
+        #[derive(Debug, Error)]
+        #[error("broken object")]
+        struct ObjectError;
+
+        #[derive(Debug, Error)]
+        #[error("failed to read block")]
+        struct BlockError(#[source] ObjectError);
+
+        let err: RwError = ErrorCode::StreamError(Box::new(BlockError(ObjectError))).into();
+        let remote = RwError::from(tonic::Status::from(err.clone()));
+        let remote_error = remote.remote().unwrap();
+        assert_eq!(remote_error.message(), err.to_string());
+        assert_eq!(
+            remote_error.context(),
+            [
+                "failed to read block".to_string(),
+                "broken object".to_string()
+            ]
+        );
+        assert!(remote.to_string().ends_with(": broken object"));
+
+        // The chain is kept when the error is forwarded by another node again.
+        let forwarded = RwError::from(tonic::Status::from(remote.clone()));
+        assert!(forwarded.to_string().contains("broken object"));
+        assert_eq!(forwarded.to_string(), format!("RPC error: {}", remote));
+    }
+
     #[test]
     fn test_to_rw_result() {
         let res: core::result::Result<(), anyhow::Error> = Err(anyhow::Error::new(
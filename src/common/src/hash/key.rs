@@ -26,9 +26,9 @@ use crate::array::{
 };
 use crate::error::Result;
 use crate::types::{
-    DataType, Datum, Decimal, IntervalUnit, NaiveDateTimeWrapper, NaiveDateWrapper,
-    NaiveTimeWrapper, OrderedF32, OrderedF64, ScalarRef, ToOwnedDatum, VirtualNode,
-    VIRTUAL_NODE_COUNT,
+    virtual_node_count, DataType, Datum, Decimal, IntervalUnit, NaiveDateTimeWrapper,
+    NaiveDateWrapper, NaiveTimeWrapper, OrderedF32, OrderedF64, ScalarRef, ToOwnedDatum, Uuid,
+    VirtualNode,
 };
 use crate::util::hash_util::CRC32FastBuilder;
 
@@ -57,7 +57,7 @@ impl HashCode {
     }
 
     pub fn to_vnode(self) -> VirtualNode {
-        (self.0 % VIRTUAL_NODE_COUNT as u64) as u16
+        (self.0 % virtual_node_count() as u64) as VirtualNode
     }
 }
 
@@ -346,6 +346,19 @@ impl HashKeySerDe<'_> for IntervalUnit {
     }
 }
 
+impl HashKeySerDe<'_> for Uuid {
+    type S = [u8; 16];
+
+    fn serialize(self) -> Self::S {
+        *self.as_bytes()
+    }
+
+    fn deserialize<R: Read>(source: &mut R) -> Self {
+        let value = Self::read_fixed_size_bytes::<R, 16>(source);
+        Uuid::from_bytes(value)
+    }
+}
+
 impl<'a> HashKeySerDe<'a> for &'a str {
     type S = Vec<u8>;
 
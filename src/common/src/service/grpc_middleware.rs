@@ -0,0 +1,162 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use hyper::Body;
+use prometheus::{
+    exponential_buckets, histogram_opts, register_histogram_vec_with_registry,
+    register_int_counter_vec_with_registry, HistogramVec, IntCounterVec, Registry,
+};
+use tower::{Layer, Service};
+use tracing::Instrument;
+
+/// Header carrying the id of a request, which is propagated from the client to the server and
+/// returned in the response, so that the logs of both sides can be correlated.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Header carrying the status code of a gRPC response. It's only in the headers if the service
+/// returns an error, otherwise it's in the trailers and the status is OK.
+const GRPC_STATUS_HEADER: &str = "grpc-status";
+
+/// Metrics of the RPCs served by a gRPC server.
+pub struct GrpcMetrics {
+    /// Latency of each RPC path.
+    pub latency: HistogramVec,
+    /// Number of RPCs of each path by status code.
+    pub requests: IntCounterVec,
+}
+
+impl GrpcMetrics {
+    /// Register the metrics in `registry`, with their names prefixed by `prefix`, e.g. `meta`
+    /// for `meta_grpc_duration_seconds`.
+    pub fn new(registry: &Registry, prefix: &str) -> Self {
+        let opts = histogram_opts!(
+            format!("{}_grpc_duration_seconds", prefix),
+            "gRPC latency of the services",
+            exponential_buckets(0.0001, 2.0, 20).unwrap() // max 52s
+        );
+        let latency = register_histogram_vec_with_registry!(opts, &["path"], registry).unwrap();
+
+        let requests = register_int_counter_vec_with_registry!(
+            format!("{}_grpc_requests_total", prefix),
+            "Number of gRPC requests by status code",
+            &["path", "code"],
+            registry
+        )
+        .unwrap();
+
+        Self { latency, requests }
+    }
+}
+
+/// Layer shared by all gRPC servers, which records the latency and status code of each RPC,
+/// assigns a request id if the client didn't, and runs the RPC in a span with the request id.
+#[derive(Clone)]
+pub struct GrpcMiddlewareLayer {
+    metrics: Arc<GrpcMetrics>,
+}
+
+impl GrpcMiddlewareLayer {
+    pub fn new(metrics: Arc<GrpcMetrics>) -> Self {
+        Self { metrics }
+    }
+}
+
+impl<S> Layer<S> for GrpcMiddlewareLayer {
+    type Service = GrpcMiddleware<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        GrpcMiddleware {
+            inner: service,
+            metrics: self.metrics.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct GrpcMiddleware<S> {
+    inner: S,
+    metrics: Arc<GrpcMetrics>,
+}
+
+impl<S, ResBody> Service<hyper::Request<Body>> for GrpcMiddleware<S>
+where
+    S: Service<hyper::Request<Body>, Response = hyper::Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Error = S::Error;
+    type Future = futures::future::BoxFuture<'static, Result<Self::Response, Self::Error>>;
+    type Response = S::Response;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: hyper::Request<Body>) -> Self::Future {
+        // This is necessary because tonic internally uses `tower::buffer::Buffer`.
+        // See https://github.com/tower-rs/tower/issues/547#issuecomment-767629149
+        // for details on why this is necessary
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        let metrics = self.metrics.clone();
+
+        let request_id = match req.headers().get(REQUEST_ID_HEADER) {
+            Some(request_id) => request_id.clone(),
+            None => {
+                let request_id = uuid::Uuid::new_v4().to_string().parse().unwrap();
+                req.headers_mut()
+                    .insert(REQUEST_ID_HEADER, request_id.clone());
+                request_id
+            }
+        };
+        let path = req.uri().path().to_owned();
+        let span = tracing::debug_span!(
+            "grpc",
+            path = path.as_str(),
+            request_id = request_id.to_str().unwrap_or_default()
+        );
+
+        // TODO: use GAT to avoid static future and Box::pin
+        Box::pin(
+            async move {
+                let timer = metrics.latency.with_label_values(&[&path]).start_timer();
+
+                let result = inner.call(req).await;
+
+                timer.observe_duration();
+                let code = match &result {
+                    Ok(response) => response
+                        .headers()
+                        .get(GRPC_STATUS_HEADER)
+                        .and_then(|code| code.to_str().ok())
+                        .unwrap_or("0")
+                        .to_owned(),
+                    Err(_) => "transport_error".to_owned(),
+                };
+                metrics.requests.with_label_values(&[&path, &code]).inc();
+                if code != "0" {
+                    tracing::debug!("gRPC request failed with status code {}", code);
+                }
+
+                let mut response = result?;
+                response.headers_mut().insert(REQUEST_ID_HEADER, request_id);
+                Ok(response)
+            }
+            .instrument(span),
+        )
+    }
+}
@@ -22,6 +22,8 @@ use tower::make::Shared;
 use tower::ServiceBuilder;
 use tower_http::add_extension::AddExtensionLayer;
 
+use super::{pprof_profile_service, PPROF_PROFILE_PATH};
+
 pub struct MetricsManager {}
 
 impl MetricsManager {
@@ -43,6 +45,9 @@ impl MetricsManager {
     }
 
     async fn metrics_service(req: Request<Body>) -> Result<Response<Body>, hyper::Error> {
+        if req.uri().path() == PPROF_PROFILE_PATH {
+            return Ok(pprof_profile_service(&req).await);
+        }
         let registry = req.extensions().get::<Arc<Registry>>().unwrap();
         let encoder = TextEncoder::new();
         let mut buffer = vec![];
@@ -12,5 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod grpc_middleware;
 mod metrics_manager;
+mod profiling;
+pub use grpc_middleware::{GrpcMetrics, GrpcMiddleware, GrpcMiddlewareLayer, REQUEST_ID_HEADER};
 pub use metrics_manager::MetricsManager;
+pub use profiling::{pprof_profile_service, PPROF_PROFILE_PATH};
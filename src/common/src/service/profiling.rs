@@ -0,0 +1,115 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use hyper::{Body, Request, Response, StatusCode};
+use pprof::protos::Message;
+
+/// Path of the CPU profiling endpoint, served along with the metrics. Profiles are collected
+/// for `seconds` (10 by default) and returned in the pprof protobuf format, which can be read by
+/// `go tool pprof`, or as a flamegraph SVG if `format=svg` is specified. For example,
+/// `/debug/pprof/profile?seconds=30&format=svg`.
+pub const PPROF_PROFILE_PATH: &str = "/debug/pprof/profile";
+
+const DEFAULT_PROFILE_SECONDS: u64 = 10;
+const MAX_PROFILE_SECONDS: u64 = 300;
+/// Sampling frequency in Hz. It's not a multiple of 100 to avoid sampling in lockstep with
+/// periodic tasks.
+const PROFILE_FREQUENCY: i32 = 99;
+
+/// Serve a request to [`PPROF_PROFILE_PATH`] by profiling the CPU of the whole process.
+pub async fn pprof_profile_service(req: &Request<Body>) -> Response<Body> {
+    match profile(req).await {
+        Ok(response) => response,
+        Err((status, message)) => Response::builder()
+            .status(status)
+            .body(Body::from(message))
+            .unwrap(),
+    }
+}
+
+async fn profile(req: &Request<Body>) -> Result<Response<Body>, (StatusCode, String)> {
+    let params: HashMap<String, String> = req
+        .uri()
+        .query()
+        .map(|query| {
+            url::form_urlencoded::parse(query.as_bytes())
+                .into_owned()
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let seconds = match params.get("seconds") {
+        Some(seconds) => seconds
+            .parse::<u64>()
+            .ok()
+            .filter(|seconds| (1..=MAX_PROFILE_SECONDS).contains(seconds))
+            .ok_or_else(|| {
+                (
+                    StatusCode::BAD_REQUEST,
+                    format!(
+                        "seconds must be an integer between 1 and {}",
+                        MAX_PROFILE_SECONDS
+                    ),
+                )
+            })?,
+        None => DEFAULT_PROFILE_SECONDS,
+    };
+    let svg = match params.get("format").map(String::as_str) {
+        None | Some("pprof") => false,
+        Some("svg") => true,
+        Some(format) => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!("unknown format {}, expect pprof or svg", format),
+            ))
+        }
+    };
+
+    // Only one profiler can run in a process at a time.
+    let guard = pprof::ProfilerGuardBuilder::default()
+        .frequency(PROFILE_FREQUENCY)
+        .blocklist(&["libc", "libgcc", "pthread", "vdso"])
+        .build()
+        .map_err(|e| (StatusCode::CONFLICT, e.to_string()))?;
+    tracing::info!("start CPU profiling for {}s", seconds);
+    tokio::time::sleep(Duration::from_secs(seconds)).await;
+    let report = guard
+        .report()
+        .build()
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    drop(guard);
+
+    let mut body = Vec::new();
+    let content_type = if svg {
+        report
+            .flamegraph(&mut body)
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        "image/svg+xml"
+    } else {
+        report
+            .pprof()
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+            .encode(&mut body)
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        "application/octet-stream"
+    };
+
+    Ok(Response::builder()
+        .header(hyper::header::CONTENT_TYPE, content_type)
+        .body(Body::from(body))
+        .unwrap())
+}
@@ -23,3 +23,31 @@ pub const QUERY_MODE: &str = "QUERY_MODE";
 
 /// To force the usage of delta join in streaming execution.
 pub const DELTA_JOIN: &str = "RW_FORCE_DELTA_JOIN";
+
+/// To plan delta joins on tables or materialized views already arranged by the join key, e.g. a
+/// materialized view grouped by the join key, without creating indexes on them.
+pub const DELTA_JOIN_WITHOUT_INDEX: &str = "RW_ENABLE_DELTA_JOIN_WITHOUT_INDEX";
+
+/// To split streaming aggregations into a local phase and a global phase, which combines the
+/// partial results of the local phase after the shuffle.
+pub const TWO_PHASE_AGG: &str = "RW_ENABLE_TWO_PHASE_AGG";
+
+/// To build a new materialized view on the existing ones computing the same subqueries, instead of
+/// recomputing them from the sources.
+pub const MV_REUSE: &str = "RW_ENABLE_MV_REUSE";
+
+/// Aborts queries running longer than this number of milliseconds. 0 means unlimited. The limit of
+/// the user set by `ALTER USER ... SET statement_timeout` can't be raised by the session.
+pub const STATEMENT_TIMEOUT: &str = "STATEMENT_TIMEOUT";
+
+/// The number of lists of an IVF index a nearest neighbor search probes, which trades the recall
+/// of the approximate search for its speed. 0 disables the approximate search, so that the nearest
+/// vectors are found exactly by a full scan.
+pub const IVF_PROBES: &str = "RW_IVF_PROBES";
+
+/// Aborts queries returning more rows than this. 0 means unlimited.
+pub const MAX_RESULT_ROWS: &str = "MAX_RESULT_ROWS";
+
+/// Aborts queries whose results buffered in the frontend take more bytes than this. 0 means
+/// unlimited.
+pub const MEMORY_LIMIT: &str = "MEMORY_LIMIT";
@@ -28,7 +28,7 @@ use rand::{Rng, SeedableRng};
 use crate::array::{Array, ArrayBuilder, ArrayRef, ListValue, StructValue};
 use crate::types::{
     Decimal, IntervalUnit, NaiveDateTimeWrapper, NaiveDateWrapper, NaiveTimeWrapper, NativeType,
-    Scalar,
+    Scalar, Uuid,
 };
 
 pub trait RandValue {
@@ -68,6 +68,12 @@ impl RandValue for IntervalUnit {
     }
 }
 
+impl RandValue for Uuid {
+    fn rand_value<R: Rng>(rand: &mut R) -> Self {
+        Uuid::from_bytes(rand.gen())
+    }
+}
+
 impl RandValue for NaiveDateWrapper {
     fn rand_value<R: Rng>(rand: &mut R) -> Self {
         let max_day = chrono::MAX_DATE.num_days_from_ce();
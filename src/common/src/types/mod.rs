@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::convert::TryFrom;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
 use anyhow::anyhow;
@@ -21,6 +22,7 @@ use risingwave_pb::data::DataType as ProstDataType;
 use serde::{Deserialize, Serialize};
 
 use crate::array::{ArrayError, ArrayResult};
+use crate::error::{ErrorCode, Result as RwResult};
 mod native_type;
 mod ops;
 mod scalar_impl;
@@ -36,6 +38,7 @@ pub use scalar_impl::*;
 pub mod chrono_wrapper;
 pub mod decimal;
 pub mod interval;
+mod uuid;
 
 mod ordered_float;
 
@@ -52,6 +55,7 @@ use paste::paste;
 use prost::Message;
 use risingwave_pb::expr::{ListValue as ProstListValue, StructValue as ProstStructValue};
 
+pub use self::uuid::Uuid;
 use crate::array::{
     read_interval_unit, ArrayBuilderImpl, ListRef, ListValue, PrimitiveArrayItemType, StructRef,
     StructValue,
@@ -64,7 +68,39 @@ pub type ParallelUnitId = u32;
 // consistent hashing.
 pub type VirtualNode = u16;
 pub const VNODE_BITS: usize = 11;
+/// The default number of virtual nodes, used if it's not specified on cluster creation.
 pub const VIRTUAL_NODE_COUNT: usize = 1 << VNODE_BITS;
+/// The minimum number of virtual nodes a cluster can be created with.
+pub const MIN_VIRTUAL_NODE_COUNT: usize = 1 << 8;
+/// The maximum number of virtual nodes a cluster can be created with. Vnodes must fit in
+/// [`VirtualNode`].
+pub const MAX_VIRTUAL_NODE_COUNT: usize = 1 << 15;
+
+/// The number of virtual nodes of the cluster. It's fixed when the cluster is created, and set by
+/// the meta node on startup and by the other nodes when they register to the meta node.
+static CLUSTER_VIRTUAL_NODE_COUNT: AtomicUsize = AtomicUsize::new(VIRTUAL_NODE_COUNT);
+
+/// Returns the number of virtual nodes of the cluster, which is used for mapping hash codes to
+/// vnodes and building vnode mappings.
+pub fn virtual_node_count() -> usize {
+    CLUSTER_VIRTUAL_NODE_COUNT.load(Ordering::Relaxed)
+}
+
+/// Sets the number of virtual nodes of the cluster. It must be a power of two between
+/// [`MIN_VIRTUAL_NODE_COUNT`] and [`MAX_VIRTUAL_NODE_COUNT`].
+pub fn set_virtual_node_count(count: usize) -> RwResult<()> {
+    if !count.is_power_of_two()
+        || !(MIN_VIRTUAL_NODE_COUNT..=MAX_VIRTUAL_NODE_COUNT).contains(&count)
+    {
+        return Err(ErrorCode::InvalidParameterValue(format!(
+            "vnode count must be a power of two between {} and {}, got {}",
+            MIN_VIRTUAL_NODE_COUNT, MAX_VIRTUAL_NODE_COUNT, count
+        ))
+        .into());
+    }
+    CLUSTER_VIRTUAL_NODE_COUNT.store(count, Ordering::Relaxed);
+    Ok(())
+}
 
 pub type OrderedF32 = ordered_float::OrderedFloat<f32>;
 pub type OrderedF64 = ordered_float::OrderedFloat<f64>;
@@ -84,6 +120,7 @@ pub enum DataType {
     Timestamp,
     Timestampz,
     Interval,
+    Uuid,
     Struct { fields: Arc<[DataType]> },
     List { datatype: Box<DataType> },
 }
@@ -115,6 +152,7 @@ impl From<&ProstDataType> for DataType {
             TypeName::Timestampz => DataType::Timestampz,
             TypeName::Decimal => DataType::Decimal,
             TypeName::Interval => DataType::Interval,
+            TypeName::Uuid => DataType::Uuid,
             TypeName::Struct => {
                 let fields: Vec<DataType> = proto.field_type.iter().map(|f| f.into()).collect_vec();
                 DataType::Struct {
@@ -146,6 +184,7 @@ impl DataType {
             DataType::Timestamp => NaiveDateTimeArrayBuilder::new(capacity)?.into(),
             DataType::Timestampz => PrimitiveArrayBuilder::<i64>::new(capacity)?.into(),
             DataType::Interval => IntervalArrayBuilder::new(capacity)?.into(),
+            DataType::Uuid => UuidArrayBuilder::new(capacity)?.into(),
             DataType::Struct { fields } => StructArrayBuilder::with_meta(
                 capacity,
                 ArrayMeta::Struct {
@@ -178,6 +217,7 @@ impl DataType {
             DataType::Timestampz => TypeName::Timestampz,
             DataType::Decimal => TypeName::Decimal,
             DataType::Interval => TypeName::Interval,
+            DataType::Uuid => TypeName::Uuid,
             DataType::Struct { .. } => TypeName::Struct,
             DataType::List { .. } => TypeName::List,
         }
@@ -213,6 +253,7 @@ impl DataType {
             DataType::Timestamp => DataSize::Fixed(size_of::<NaiveDateTimeWrapper>()),
             DataType::Timestampz => DataSize::Fixed(size_of::<NaiveDateTimeWrapper>()),
             DataType::Interval => DataSize::Variable,
+            DataType::Uuid => DataSize::Fixed(size_of::<Uuid>()),
             DataType::Struct { .. } => DataSize::Variable,
             DataType::List { .. } => DataSize::Variable,
         }
@@ -236,7 +277,7 @@ impl DataType {
         match self {
             Boolean | Int16 | Int32 | Int64 => true,
             Float32 | Float64 | Decimal | Date | Varchar | Time | Timestamp | Timestampz
-            | Interval => false,
+            | Interval | Uuid => false,
             Struct { fields } => fields.iter().all(|dt| dt.mem_cmp_eq_value_enc()),
             List { datatype } => datatype.mem_cmp_eq_value_enc(),
         }
@@ -317,6 +358,7 @@ macro_rules! for_all_scalar_variants {
             { Bool, bool, bool, bool },
             { Decimal, decimal, Decimal, Decimal  },
             { Interval, interval, IntervalUnit, IntervalUnit },
+            { Uuid, uuid, Uuid, Uuid },
             { NaiveDate, naivedate, NaiveDateWrapper, NaiveDateWrapper },
             { NaiveDateTime, naivedatetime, NaiveDateTimeWrapper, NaiveDateTimeWrapper },
             { NaiveTime, naivetime, NaiveTimeWrapper, NaiveTimeWrapper },
@@ -585,6 +627,7 @@ impl std::hash::Hash for ScalarImpl {
                     Self::Utf8(s) => s.hash(state),
                     Self::Decimal(decimal) => decimal.hash(state),
                     Self::Interval(interval) => interval.hash(state),
+                    Self::Uuid(uuid) => uuid.hash(state),
                     Self::NaiveDate(naivedate) => naivedate.hash(state),
                     Self::NaiveDateTime(naivedatetime) => naivedatetime.hash(state),
                     Self::NaiveTime(naivetime) => naivetime.hash(state),
@@ -654,6 +697,7 @@ impl ScalarRefImpl<'_> {
                 ser.serialize_decimal(mantissa, scale)?;
             }
             Self::Interval(v) => v.serialize(ser)?,
+            Self::Uuid(v) => v.serialize(ser)?,
             &Self::NaiveDate(v) => ser.serialize_naivedate(v.0.num_days_from_ce())?,
             &Self::NaiveDateTime(v) => {
                 ser.serialize_naivedatetime(v.0.timestamp(), v.0.timestamp_subsec_nanos())?
@@ -708,6 +752,7 @@ impl ScalarImpl {
                 }
             }),
             Ty::Interval => Self::Interval(IntervalUnit::deserialize(de)?),
+            Ty::Uuid => Self::Uuid(Uuid::deserialize(de)?),
             Ty::Time => Self::NaiveTime({
                 let (secs, nano) = de.deserialize_naivetime()?;
                 NaiveTimeWrapper::with_secs_nano(secs, nano)?
@@ -743,6 +788,7 @@ impl ScalarImpl {
             ScalarImpl::Bool(v) => (*v as i8).to_be_bytes().to_vec(),
             ScalarImpl::Decimal(v) => v.to_string().as_bytes().to_vec(),
             ScalarImpl::Interval(v) => v.to_protobuf_owned(),
+            ScalarImpl::Uuid(v) => v.to_protobuf_owned(),
             ScalarImpl::NaiveDate(_) => todo!(),
             ScalarImpl::NaiveDateTime(_) => todo!(),
             ScalarImpl::NaiveTime(_) => todo!(),
@@ -805,6 +851,7 @@ impl ScalarImpl {
                 b,
                 data_type.get_interval_type()?,
             )?),
+            TypeName::Uuid => ScalarImpl::Uuid(Uuid::from_protobuf_bytes(b)?),
             TypeName::Struct => {
                 let struct_value: ProstStructValue = Message::decode(b.as_slice())?;
                 let fields: Vec<Datum> = struct_value
@@ -858,6 +905,17 @@ mod tests {
         serializer.into_inner()
     }
 
+    #[test]
+    fn test_set_virtual_node_count() {
+        assert!(set_virtual_node_count(0).is_err());
+        assert!(set_virtual_node_count(1000).is_err());
+        assert!(set_virtual_node_count(MIN_VIRTUAL_NODE_COUNT / 2).is_err());
+        assert!(set_virtual_node_count(MAX_VIRTUAL_NODE_COUNT * 2).is_err());
+        // Setting the default count keeps other tests unaffected.
+        set_virtual_node_count(VIRTUAL_NODE_COUNT).unwrap();
+        assert_eq!(virtual_node_count(), VIRTUAL_NODE_COUNT);
+    }
+
     #[test]
     fn test_memcomparable() {
         let memcmp_minus_1 = serialize_datum_not_null_into_vec(-1);
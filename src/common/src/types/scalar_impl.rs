@@ -195,6 +195,28 @@ impl<'a> ScalarRef<'a> for IntervalUnit {
     }
 }
 
+/// Implement `Scalar` for `Uuid`.
+impl Scalar for Uuid {
+    type ScalarRefType<'a> = Uuid;
+
+    fn as_scalar_ref(&self) -> Uuid {
+        *self
+    }
+
+    fn to_scalar_value(self) -> ScalarImpl {
+        ScalarImpl::Uuid(self)
+    }
+}
+
+/// Implement `ScalarRef` for `Uuid`.
+impl<'a> ScalarRef<'a> for Uuid {
+    type ScalarType = Uuid;
+
+    fn to_owned_scalar(&self) -> Uuid {
+        *self
+    }
+}
+
 /// Implement `Scalar` for `NaiveDateWrapper`.
 impl Scalar for NaiveDateWrapper {
     type ScalarRefType<'a> = NaiveDateWrapper;
@@ -0,0 +1,103 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::{Display, Formatter};
+use std::io::Write;
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+
+use crate::array::ArrayResult;
+
+/// A UUID stored in 16 bytes rather than in its 36-character text form. The bytes are in the
+/// network byte order, so that both the derived order and the memcomparable encoding sort UUIDs the
+/// same way as PostgreSQL.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub struct Uuid([u8; 16]);
+
+impl Uuid {
+    /// Generates a random (version 4) UUID.
+    pub fn new_v4() -> Self {
+        Self(*::uuid::Uuid::new_v4().as_bytes())
+    }
+
+    pub fn from_bytes(bytes: [u8; 16]) -> Self {
+        Self(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 16] {
+        &self.0
+    }
+
+    pub fn to_protobuf<T: Write>(self, output: &mut T) -> ArrayResult<usize> {
+        output.write(&self.0).map_err(Into::into)
+    }
+
+    pub fn to_protobuf_owned(self) -> Vec<u8> {
+        self.0.to_vec()
+    }
+
+    pub fn from_protobuf_bytes(bytes: &[u8]) -> ArrayResult<Self> {
+        let bytes = bytes
+            .try_into()
+            .map_err(|e| anyhow!("Failed to deserialize uuid, reason: {:?}", e))?;
+        Ok(Self(bytes))
+    }
+}
+
+/// Accepts the input formats of PostgreSQL, e.g. upper case, without hyphens or in braces.
+impl FromStr for Uuid {
+    type Err = ::uuid::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ::uuid::Uuid::parse_str(s).map(|uuid| Self(*uuid.as_bytes()))
+    }
+}
+
+/// Formats in the standard form, e.g. `a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11`.
+impl Display for Uuid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&::uuid::Uuid::from_bytes(self.0).hyphenated(), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uuid() {
+        let uuid = Uuid::from_str("a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11").unwrap();
+        assert_eq!(uuid.to_string(), "a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11");
+        for s in [
+            "A0EEBC99-9C0B-4EF8-BB6D-6BB9BD380A11",
+            "{a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11}",
+            "a0eebc999c0b4ef8bb6d6bb9bd380a11",
+        ] {
+            assert_eq!(Uuid::from_str(s).unwrap(), uuid);
+        }
+        assert!(Uuid::from_str("a0eebc99-9c0b-4ef8-bb6d").is_err());
+
+        assert_eq!(
+            Uuid::from_protobuf_bytes(&uuid.to_protobuf_owned()).unwrap(),
+            uuid
+        );
+        let smaller = Uuid::from_str("a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a10").unwrap();
+        assert!(smaller < uuid);
+        assert_ne!(Uuid::new_v4(), Uuid::new_v4());
+    }
+}
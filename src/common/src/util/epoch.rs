@@ -14,6 +14,8 @@
 
 use core::fmt;
 use std::cmp::Ordering;
+#[cfg(madsim)]
+use std::sync::atomic::{AtomicI64, Ordering as AtomicOrdering};
 use std::time::{Duration, SystemTime};
 
 lazy_static::lazy_static! {
@@ -21,6 +23,17 @@ lazy_static::lazy_static! {
     pub static ref UNIX_SINGULARITY_DATE_EPOCH: SystemTime = SystemTime::UNIX_EPOCH + Duration::from_secs(1_617_235_200);
 }
 
+/// Offset in milliseconds added to the physical time of new epochs, which is used to simulate
+/// clock skew in deterministic simulation tests.
+#[cfg(madsim)]
+static SIMULATED_CLOCK_SKEW_MS: AtomicI64 = AtomicI64::new(0);
+
+/// Shifts the clock used to generate epochs by `skew_ms`. Only available in simulation.
+#[cfg(madsim)]
+pub fn set_simulated_clock_skew(skew_ms: i64) {
+    SIMULATED_CLOCK_SKEW_MS.store(skew_ms, AtomicOrdering::Relaxed);
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Epoch(pub u64);
 
@@ -55,15 +68,36 @@ impl Epoch {
         }
     }
 
+    /// Returns the first epoch of the physical time `ms`.
+    pub fn from_physical_time(ms: u64) -> Self {
+        Epoch(ms << EPOCH_PHYSICAL_SHIFT_BITS)
+    }
+
     pub fn physical_time(&self) -> u64 {
         self.0 >> EPOCH_PHYSICAL_SHIFT_BITS
     }
 
     fn physical_now() -> u64 {
-        UNIX_SINGULARITY_DATE_EPOCH
+        let now = UNIX_SINGULARITY_DATE_EPOCH
             .elapsed()
             .expect("system clock set earlier than singularity date!")
-            .as_millis() as u64
+            .as_millis() as u64;
+        #[cfg(madsim)]
+        let now = {
+            let skew_ms = SIMULATED_CLOCK_SKEW_MS.load(AtomicOrdering::Relaxed);
+            if skew_ms >= 0 {
+                now.saturating_add(skew_ms as u64)
+            } else {
+                now.saturating_sub(skew_ms.unsigned_abs())
+            }
+        };
+        now
+    }
+
+    /// Returns the epoch whose physical time is `ms` milliseconds earlier than this one.
+    #[must_use]
+    pub fn subtract_ms(&self, ms: u64) -> Self {
+        Epoch(self.physical_time().saturating_sub(ms) << EPOCH_PHYSICAL_SHIFT_BITS)
     }
 
     /// Returns the epoch in real system time.
@@ -98,6 +132,16 @@ mod tests {
         assert_eq!(singularity_st, *UNIX_SINGULARITY_DATE_EPOCH);
     }
 
+    #[test]
+    fn test_epoch_subtract_ms() {
+        let epoch = Epoch::now();
+        assert_eq!(
+            epoch.subtract_ms(1000).physical_time(),
+            epoch.physical_time() - 1000
+        );
+        assert_eq!(epoch.subtract_ms(u64::MAX), Epoch(0));
+    }
+
     #[test]
     fn test_epoch_generate() {
         let mut prev_epoch = Epoch::now();
@@ -0,0 +1,210 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Gap filling of time series, i.e. generating rows for the empty time buckets between the rows of
+//! a partition, shared by the batch and streaming gap filling executors.
+
+use num_traits::{CheckedAdd, FromPrimitive, ToPrimitive};
+use risingwave_pb::plan_common::GapFillStrategy;
+
+use crate::array::Row;
+use crate::error::{ErrorCode, Result};
+use crate::types::{Datum, Decimal, IntervalUnit, NaiveDateTimeWrapper, ScalarImpl};
+
+/// Parameters of a gap filling operator, on the rows of its input.
+#[derive(Debug, Clone)]
+pub struct GapFiller {
+    pub time_col: usize,
+    pub interval: IntervalUnit,
+    pub partition_by: Vec<usize>,
+    pub strategies: Vec<GapFillStrategy>,
+}
+
+impl GapFiller {
+    pub fn new(
+        time_col: usize,
+        interval: IntervalUnit,
+        partition_by: Vec<usize>,
+        strategies: Vec<GapFillStrategy>,
+    ) -> Self {
+        Self {
+            time_col,
+            interval,
+            partition_by,
+            strategies,
+        }
+    }
+
+    /// The time of a row, which is never NULL as it is the start of a tumble window.
+    pub fn time_of(&self, row: &Row) -> Result<NaiveDateTimeWrapper> {
+        match &row[self.time_col] {
+            Some(ScalarImpl::NaiveDateTime(time)) => Ok(*time),
+            other => Err(ErrorCode::InternalError(format!(
+                "gap filling expects a non-null timestamp, got {:?}",
+                other
+            ))
+            .into()),
+        }
+    }
+
+    /// Generates the rows for the empty buckets between `prev` and `next`, two adjacent rows of a
+    /// partition in time order.
+    pub fn fill(&self, prev: &Row, next: &Row) -> Result<Vec<Row>> {
+        let prev_time = self.time_of(prev)?;
+        let next_time = self.time_of(next)?;
+        let span = (next_time.0 - prev_time.0).num_milliseconds() as f64;
+
+        let mut rows = vec![];
+        for k in 1i32.. {
+            // Step from `prev` each time, so that month intervals don't drift with short months.
+            let time = self
+                .interval
+                .checked_mul_int(k)
+                .and_then(|offset| prev_time.checked_add(offset))
+                .ok_or_else(|| {
+                    ErrorCode::InternalError(format!(
+                        "overflow when filling the gaps after {}",
+                        prev_time
+                    ))
+                })?;
+            if time >= next_time {
+                break;
+            }
+            if k == 1 && time <= prev_time {
+                return Err(ErrorCode::InvalidInputSyntax(format!(
+                    "can't fill the gaps by a non-positive interval {}",
+                    self.interval
+                ))
+                .into());
+            }
+            let fraction = (time.0 - prev_time.0).num_milliseconds() as f64 / span;
+            let datums = (0..prev.size())
+                .map(|idx| {
+                    if idx == self.time_col {
+                        Some(ScalarImpl::NaiveDateTime(time))
+                    } else if self.partition_by.contains(&idx) {
+                        prev[idx].clone()
+                    } else {
+                        match self.strategies[idx] {
+                            GapFillStrategy::None => None,
+                            GapFillStrategy::Locf => prev[idx].clone(),
+                            GapFillStrategy::Interpolate => {
+                                interpolate(&prev[idx], &next[idx], fraction)
+                            }
+                        }
+                    }
+                })
+                .collect();
+            rows.push(Row(datums));
+        }
+        Ok(rows)
+    }
+}
+
+/// Linear interpolation between two numbers, or NULL if either of them is NULL. Integers are
+/// rounded to the nearest.
+fn interpolate(prev: &Datum, next: &Datum, fraction: f64) -> Datum {
+    let lerp = |a: f64, b: f64| a + (b - a) * fraction;
+    match (prev.as_ref()?, next.as_ref()?) {
+        (ScalarImpl::Int16(a), ScalarImpl::Int16(b)) => {
+            Some(ScalarImpl::Int16(lerp(*a as f64, *b as f64).round() as i16))
+        }
+        (ScalarImpl::Int32(a), ScalarImpl::Int32(b)) => {
+            Some(ScalarImpl::Int32(lerp(*a as f64, *b as f64).round() as i32))
+        }
+        (ScalarImpl::Int64(a), ScalarImpl::Int64(b)) => {
+            Some(ScalarImpl::Int64(lerp(*a as f64, *b as f64).round() as i64))
+        }
+        (ScalarImpl::Float32(a), ScalarImpl::Float32(b)) => Some(ScalarImpl::Float32(
+            (lerp(a.0 as f64, b.0 as f64) as f32).into(),
+        )),
+        (ScalarImpl::Float64(a), ScalarImpl::Float64(b)) => {
+            Some(ScalarImpl::Float64(lerp(a.0, b.0).into()))
+        }
+        (ScalarImpl::Decimal(a), ScalarImpl::Decimal(b)) => {
+            let value = lerp(a.to_f64()?, b.to_f64()?);
+            Decimal::from_f64(value).map(ScalarImpl::Decimal)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDateTime;
+
+    use super::*;
+
+    fn time(s: &str) -> Datum {
+        Some(ScalarImpl::NaiveDateTime(NaiveDateTimeWrapper::new(
+            NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap(),
+        )))
+    }
+
+    fn row(partition: i32, t: &str, value: Option<i64>) -> Row {
+        Row(vec![
+            Some(ScalarImpl::Int32(partition)),
+            time(t),
+            value.map(ScalarImpl::Int64),
+            value.map(ScalarImpl::Int64),
+            value.map(ScalarImpl::Int64),
+        ])
+    }
+
+    #[test]
+    fn test_fill_gaps() {
+        let filler = GapFiller::new(
+            1,
+            IntervalUnit::from_minutes(1),
+            vec![0],
+            vec![
+                GapFillStrategy::None,
+                GapFillStrategy::None,
+                GapFillStrategy::None,
+                GapFillStrategy::Locf,
+                GapFillStrategy::Interpolate,
+            ],
+        );
+        let prev = row(1, "2022-01-01 10:00:00", Some(10));
+        let next = row(1, "2022-01-01 10:03:00", Some(40));
+        let rows = filler.fill(&prev, &next).unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                Row(vec![
+                    Some(ScalarImpl::Int32(1)),
+                    time("2022-01-01 10:01:00"),
+                    None,
+                    Some(ScalarImpl::Int64(10)),
+                    Some(ScalarImpl::Int64(20)),
+                ]),
+                Row(vec![
+                    Some(ScalarImpl::Int32(1)),
+                    time("2022-01-01 10:02:00"),
+                    None,
+                    Some(ScalarImpl::Int64(10)),
+                    Some(ScalarImpl::Int64(30)),
+                ]),
+            ]
+        );
+
+        // Adjacent buckets have no gap, and NULL can't be interpolated.
+        let next = row(1, "2022-01-01 10:01:00", None);
+        assert!(filler.fill(&prev, &next).unwrap().is_empty());
+        let next = row(1, "2022-01-01 10:02:00", None);
+        let rows = filler.fill(&prev, &next).unwrap();
+        assert_eq!(rows[0][3], Some(ScalarImpl::Int64(10)));
+        assert_eq!(rows[0][4], None);
+    }
+}
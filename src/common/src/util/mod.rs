@@ -25,9 +25,11 @@ pub mod chunk_coalesce;
 pub mod compress;
 pub mod encoding_for_comparison;
 pub mod env_var;
+pub mod gap_fill;
 pub mod hash_util;
 pub mod ordered;
 pub mod prost;
+pub mod shutdown;
 pub mod sort_util;
 #[macro_use]
 pub mod try_match;
@@ -193,6 +193,7 @@ pub fn compare_two_row(
                 Bool,
                 Decimal,
                 Interval,
+                Uuid,
                 NaiveDate,
                 NaiveDateTime,
                 NaiveTime
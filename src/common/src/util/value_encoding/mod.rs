@@ -21,7 +21,7 @@ use chrono::{Datelike, Timelike};
 use crate::error::Result;
 use crate::types::{
     DataType, Datum, Decimal, IntervalUnit, NaiveDateTimeWrapper, NaiveDateWrapper,
-    NaiveTimeWrapper, OrderedF32, OrderedF64, ScalarImpl, ScalarRefImpl,
+    NaiveTimeWrapper, OrderedF32, OrderedF64, ScalarImpl, ScalarRefImpl, Uuid,
 };
 
 pub mod error;
@@ -79,6 +79,7 @@ fn serialize_value(value: ScalarRefImpl, mut buf: impl BufMut) {
         ScalarRefImpl::Bool(v) => buf.put_u8(v as u8),
         ScalarRefImpl::Decimal(v) => serialize_decimal(&v, buf),
         ScalarRefImpl::Interval(v) => serialize_interval(&v, buf),
+        ScalarRefImpl::Uuid(v) => buf.put_slice(v.as_bytes()),
         ScalarRefImpl::NaiveDate(v) => serialize_naivedate(v.0.num_days_from_ce(), buf),
         ScalarRefImpl::NaiveDateTime(v) => {
             serialize_naivedatetime(v.0.timestamp(), v.0.timestamp_subsec_nanos(), buf)
@@ -143,6 +144,7 @@ fn deserialize_value(ty: &DataType, mut data: impl Buf) -> Result<Datum> {
         DataType::Boolean => ScalarImpl::Bool(deserialize_bool(data)?),
         DataType::Decimal => ScalarImpl::Decimal(deserialize_decimal(data)?),
         DataType::Interval => ScalarImpl::Interval(deserialize_interval(data)?),
+        DataType::Uuid => ScalarImpl::Uuid(deserialize_uuid(data)),
         DataType::Time => ScalarImpl::NaiveTime(deserialize_naivetime(data)?),
         DataType::Timestamp => ScalarImpl::NaiveDateTime(deserialize_naivedatetime(data)?),
         DataType::Timestampz => ScalarImpl::Int64(data.get_i64_le()),
@@ -181,6 +183,12 @@ fn deserialize_interval(mut data: impl Buf) -> Result<IntervalUnit> {
     Ok(IntervalUnit::new(months, days, ms))
 }
 
+fn deserialize_uuid(mut data: impl Buf) -> Uuid {
+    let mut bytes = [0; 16];
+    data.copy_to_slice(&mut bytes);
+    Uuid::from_bytes(bytes)
+}
+
 fn deserialize_naivetime(mut data: impl Buf) -> Result<NaiveTimeWrapper> {
     let secs = data.get_u32_le();
     let nano = data.get_u32_le();
@@ -32,6 +32,7 @@
 #[macro_use]
 extern crate log;
 
+pub mod observer;
 pub mod rpc;
 pub mod server;
 
@@ -71,6 +72,21 @@ pub struct ComputeNodeOpts {
     /// Enable reporting tracing information to jaeger
     #[clap(long)]
     pub enable_jaeger_tracing: bool,
+
+    /// Join the cluster as a serving node of a read replica, which only serves batch queries of
+    /// the read replica frontends. No streaming actors or compaction tasks run on it.
+    #[clap(long)]
+    pub read_replica: bool,
+
+    /// The resource group to label the compute node with. Only the streaming jobs created with the
+    /// same `resource_group` are scheduled to it.
+    #[clap(long, default_value = "default")]
+    pub resource_group: String,
+
+    /// Print the config after applying the defaults, the config file and the environment variable
+    /// overrides, and exit.
+    #[clap(long)]
+    pub print_effective_config: bool,
 }
 
 use std::future::Future;
@@ -83,6 +99,12 @@ pub fn start(opts: ComputeNodeOpts) -> Pin<Box<dyn Future<Output = ()> + Send>>
     // WARNING: don't change the function signature. Making it `async fn` will cause
     // slow compile in release mode.
     Box::pin(async move {
+        if opts.print_effective_config {
+            let config = risingwave_common::config::load_config(&opts.config_path).unwrap();
+            println!("{}", config.to_toml_string());
+            return;
+        }
+
         tracing::info!("meta address: {}", opts.meta_address.clone());
 
         let listen_address = opts.host.parse().unwrap();
@@ -0,0 +1,109 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use risingwave_common::error::Result;
+use risingwave_common::util::addr::HostAddr;
+use risingwave_pb::common::WorkerType;
+use risingwave_pb::meta::subscribe_response::Info;
+use risingwave_pb::meta::{SubscribeResponse, SystemParams};
+use risingwave_rpc_client::{MetaClient, NotificationStream};
+use risingwave_storage::hummock::SstableStoreRef;
+use tokio::task::JoinHandle;
+
+const RE_SUBSCRIBE_RETRY_INTERVAL: Duration = Duration::from_millis(100);
+
+/// `ObserverManager` receives meta's notifications and applies the system parameters changed by
+/// `ALTER SYSTEM` to the compute node.
+pub struct ObserverManager {
+    rx: Box<dyn NotificationStream>,
+    meta_client: MetaClient,
+    addr: HostAddr,
+    worker_type: WorkerType,
+    sstable_store: Option<SstableStoreRef>,
+}
+
+impl ObserverManager {
+    pub async fn new(
+        meta_client: MetaClient,
+        addr: HostAddr,
+        worker_type: WorkerType,
+        sstable_store: Option<SstableStoreRef>,
+    ) -> Result<Self> {
+        let rx = meta_client.subscribe(&addr, worker_type).await?;
+        Ok(Self {
+            rx,
+            meta_client,
+            addr,
+            worker_type,
+            sstable_store,
+        })
+    }
+
+    fn handle_notification(&self, resp: SubscribeResponse) {
+        match resp.info {
+            Some(Info::SystemParams(params)) => self.apply_system_params(&params),
+            _ => tracing::warn!("receive an unsupported notify {:?}", resp),
+        }
+    }
+
+    fn apply_system_params(&self, params: &SystemParams) {
+        tracing::info!("apply system params {:?}", params);
+        if let Some(sstable_store) = &self.sstable_store {
+            sstable_store.set_cache_capacity(
+                params.block_cache_capacity_mb as usize * (1 << 20),
+                params.meta_cache_capacity_mb as usize * (1 << 20),
+            );
+        }
+    }
+
+    /// `start` is used to spawn a new asynchronous task which receives meta's notification and
+    /// applies the changes to the compute node.
+    pub fn start(mut self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                match self.rx.next().await {
+                    Ok(Some(resp)) => self.handle_notification(resp),
+                    Ok(None) => {
+                        tracing::error!("Stream of notification terminated.");
+                        self.re_subscribe().await;
+                    }
+                    Err(_) => {}
+                }
+            }
+        })
+    }
+
+    /// `re_subscribe` is used to re-subscribe to the meta's notification. The current parameters
+    /// are sent again by meta on subscription.
+    async fn re_subscribe(&mut self) {
+        loop {
+            match self
+                .meta_client
+                .subscribe(&self.addr, self.worker_type)
+                .await
+            {
+                Ok(rx) => {
+                    tracing::debug!("re-subscribe success");
+                    self.rx = rx;
+                    break;
+                }
+                Err(_) => {
+                    tokio::time::sleep(RE_SUBSCRIBE_RETRY_INTERVAL).await;
+                }
+            }
+        }
+    }
+}
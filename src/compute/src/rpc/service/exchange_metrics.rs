@@ -13,11 +13,15 @@
 // limitations under the License.
 
 use prometheus::core::{AtomicU64, GenericCounterVec};
-use prometheus::{register_int_counter_vec_with_registry, Registry};
+use prometheus::{
+    register_int_counter_vec_with_registry, register_int_gauge_vec_with_registry, IntGaugeVec,
+    Registry,
+};
 
 pub struct ExchangeServiceMetrics {
     pub registry: Registry,
     pub stream_exchange_bytes: GenericCounterVec<AtomicU64>,
+    pub stream_exchange_buffered_bytes: IntGaugeVec,
 }
 
 impl ExchangeServiceMetrics {
@@ -30,9 +34,18 @@ impl ExchangeServiceMetrics {
         )
         .unwrap();
 
+        let stream_exchange_buffered_bytes = register_int_gauge_vec_with_registry!(
+            "stream_exchange_buffered_size",
+            "Size of messages that have been sent but not yet consumed by downstream Actor",
+            &["up_actor_id", "down_actor_id"],
+            registry
+        )
+        .unwrap();
+
         Self {
             registry,
             stream_exchange_bytes,
+            stream_exchange_buffered_bytes,
         }
     }
 
@@ -15,17 +15,24 @@
 use std::net::SocketAddr;
 use std::sync::Arc;
 
+use prometheus::IntGauge;
 use risingwave_batch::task::BatchManager;
+use risingwave_common::array::compression::compress_columns;
 use risingwave_common::error::{ErrorCode, Result, RwError};
+use risingwave_pb::common::buffer::CompressionType;
+use risingwave_pb::data::stream_message::StreamMessage;
 use risingwave_pb::task_service::exchange_service_server::ExchangeService;
 use risingwave_pb::task_service::{
-    GetDataRequest, GetDataResponse, GetStreamRequest, GetStreamResponse,
+    get_data_with_permits_request, get_stream_with_permits_request, GetDataRequest,
+    GetDataResponse, GetDataWithPermitsRequest, GetStreamRequest, GetStreamResponse,
+    GetStreamWithPermitsRequest,
 };
+use risingwave_rpc_client::permits::Permits;
 use risingwave_stream::executor::Message;
 use risingwave_stream::task::LocalStreamManager;
 use tokio::sync::mpsc::Receiver;
 use tokio_stream::wrappers::ReceiverStream;
-use tonic::{Request, Response, Status};
+use tonic::{Request, Response, Status, Streaming};
 
 use crate::rpc::service::exchange_metrics::ExchangeServiceMetrics;
 
@@ -40,11 +47,14 @@ pub struct ExchangeServiceImpl {
 }
 
 type ExchangeDataStream = ReceiverStream<std::result::Result<GetDataResponse, Status>>;
+type StreamDataStream = ReceiverStream<std::result::Result<GetStreamResponse, Status>>;
 
 #[async_trait::async_trait]
 impl ExchangeService for ExchangeServiceImpl {
     type GetDataStream = ExchangeDataStream;
-    type GetStreamStream = ReceiverStream<std::result::Result<GetStreamResponse, Status>>;
+    type GetDataWithPermitsStream = ExchangeDataStream;
+    type GetStreamStream = StreamDataStream;
+    type GetStreamWithPermitsStream = StreamDataStream;
 
     #[cfg_attr(coverage, no_coverage)]
     async fn get_data(
@@ -54,17 +64,31 @@ impl ExchangeService for ExchangeServiceImpl {
         let peer_addr = request
             .remote_addr()
             .ok_or_else(|| Status::unavailable("connection unestablished"))?;
-        let pb_task_output_id = request
-            .into_inner()
-            .task_output_id
-            .expect("Failed to get task output id.");
-        let (tx, rx) = tokio::sync::mpsc::channel(EXCHANGE_BUFFER_SIZE);
-        if let Err(e) = self.batch_mgr.get_data(tx, peer_addr, &pb_task_output_id) {
-            error!("Failed to serve exchange RPC from {}: {}", peer_addr, e);
-            return Err(e.into());
-        }
+        self.get_data_impl(peer_addr, request.into_inner(), None)
+    }
 
-        Ok(Response::new(ReceiverStream::new(rx)))
+    #[cfg_attr(coverage, no_coverage)]
+    async fn get_data_with_permits(
+        &self,
+        request: Request<Streaming<GetDataWithPermitsRequest>>,
+    ) -> std::result::Result<Response<Self::GetDataWithPermitsStream>, Status> {
+        let peer_addr = request
+            .remote_addr()
+            .ok_or_else(|| Status::unavailable("connection unestablished"))?;
+        let mut requests = request.into_inner();
+        let get = match requests.message().await?.and_then(|req| req.value) {
+            Some(get_data_with_permits_request::Value::Get(get)) => get,
+            _ => return Err(Status::invalid_argument("the first request must be get")),
+        };
+        let permits = Permits::new(get.permits);
+        permits.receive_from(requests, |req| match req.value {
+            Some(get_data_with_permits_request::Value::AddPermits(permits)) => Some(permits),
+            _ => None,
+        });
+        let req = get
+            .request
+            .ok_or_else(|| Status::invalid_argument("get data request is missing"))?;
+        self.get_data_impl(peer_addr, req, Some(permits))
     }
 
     async fn get_stream(
@@ -74,19 +98,34 @@ impl ExchangeService for ExchangeServiceImpl {
         let peer_addr = request
             .remote_addr()
             .ok_or_else(|| Status::unavailable("get_stream connection unestablished"))?;
-        let req = request.into_inner();
-        let up_down_ids = (req.up_fragment_id, req.down_fragment_id);
-        let receiver = self.stream_mgr.take_receiver(up_down_ids)?;
-        match self.get_stream_impl(peer_addr, receiver, up_down_ids).await {
-            Ok(resp) => Ok(resp),
-            Err(e) => {
-                error!(
-                    "Failed to server stream exchange RPC from {}: {}",
-                    peer_addr, e
-                );
-                Err(e.into())
-            }
-        }
+        self.get_stream_inner(peer_addr, request.into_inner(), |_| None)
+            .await
+    }
+
+    async fn get_stream_with_permits(
+        &self,
+        request: Request<Streaming<GetStreamWithPermitsRequest>>,
+    ) -> std::result::Result<Response<Self::GetStreamWithPermitsStream>, Status> {
+        let peer_addr = request
+            .remote_addr()
+            .ok_or_else(|| Status::unavailable("get_stream connection unestablished"))?;
+        let mut requests = request.into_inner();
+        let get = match requests.message().await?.and_then(|req| req.value) {
+            Some(get_stream_with_permits_request::Value::Get(get)) => get,
+            _ => return Err(Status::invalid_argument("the first request must be get")),
+        };
+        let req = get
+            .request
+            .ok_or_else(|| Status::invalid_argument("get stream request is missing"))?;
+        self.get_stream_inner(peer_addr, req, |buffered_bytes| {
+            let permits = Permits::new(get.permits).with_buffered_bytes(buffered_bytes);
+            permits.receive_from(requests, |req| match req.value {
+                Some(get_stream_with_permits_request::Value::AddPermits(permits)) => Some(permits),
+                _ => None,
+            });
+            Some(permits)
+        })
+        .await
     }
 }
 
@@ -103,12 +142,66 @@ impl ExchangeServiceImpl {
         }
     }
 
+    fn get_data_impl(
+        &self,
+        peer_addr: SocketAddr,
+        req: GetDataRequest,
+        permits: Option<Permits>,
+    ) -> std::result::Result<Response<ExchangeDataStream>, Status> {
+        let compression = req.compression();
+        let pb_task_output_id = req.task_output_id.expect("Failed to get task output id.");
+        let (tx, rx) = tokio::sync::mpsc::channel(EXCHANGE_BUFFER_SIZE);
+        if let Err(e) =
+            self.batch_mgr
+                .get_data(tx, peer_addr, &pb_task_output_id, compression, permits)
+        {
+            error!("Failed to serve exchange RPC from {}: {}", peer_addr, e);
+            return Err(e.into());
+        }
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+
+    /// Serves a stream exchange RPC. `make_permits` is given the gauge of the buffered bytes of
+    /// the channel, and returns the permits granted by the receiver, or `None` if the channel is
+    /// not flow-controlled.
+    async fn get_stream_inner(
+        &self,
+        peer_addr: SocketAddr,
+        req: GetStreamRequest,
+        make_permits: impl FnOnce(IntGauge) -> Option<Permits>,
+    ) -> std::result::Result<Response<StreamDataStream>, Status> {
+        let up_down_ids = (req.up_fragment_id, req.down_fragment_id);
+        let compression = req.compression();
+        let receiver = self.stream_mgr.take_receiver(up_down_ids)?;
+        let permits = make_permits(
+            self.metrics
+                .stream_exchange_buffered_bytes
+                .with_label_values(&[&up_down_ids.0.to_string(), &up_down_ids.1.to_string()]),
+        );
+        match self
+            .get_stream_impl(peer_addr, receiver, up_down_ids, compression, permits)
+            .await
+        {
+            Ok(resp) => Ok(resp),
+            Err(e) => {
+                error!(
+                    "Failed to server stream exchange RPC from {}: {}",
+                    peer_addr, e
+                );
+                Err(e.into())
+            }
+        }
+    }
+
     async fn get_stream_impl(
         &self,
         peer_addr: SocketAddr,
         mut receiver: Receiver<Message>,
         up_down_ids: (u32, u32),
-    ) -> Result<Response<<Self as ExchangeService>::GetStreamStream>> {
+        compression: CompressionType,
+        permits: Option<Permits>,
+    ) -> Result<Response<StreamDataStream>> {
         let (tx, rx) = tokio::sync::mpsc::channel(EXCHANGE_BUFFER_SIZE);
         let metrics = self.metrics.clone();
         tracing::trace!(target: "events::compute::exchange", peer_addr = %peer_addr, "serve stream exchange RPC");
@@ -121,7 +214,14 @@ impl ExchangeServiceImpl {
                     // the sender is closed, we close the receiver and stop forwarding message
                     None => break,
                     Some(msg) => {
-                        let res = match msg.to_protobuf() {
+                        let res = match msg.to_protobuf().and_then(|mut stream_msg| {
+                            if let Some(StreamMessage::StreamChunk(chunk)) =
+                                &mut stream_msg.stream_message
+                            {
+                                compress_columns(&mut chunk.columns, compression)?;
+                            }
+                            Ok(stream_msg)
+                        }) {
                             Ok(stream_msg) => Ok(GetStreamResponse {
                                 message: Some(stream_msg),
                             }),
@@ -133,6 +233,14 @@ impl ExchangeServiceImpl {
                             Err(_) => 0,
                         };
 
+                        // Wait for the receiver to consume the messages in flight, so that a slow
+                        // receiver cannot make the messages pile up here.
+                        if let Some(permits) = &permits
+                            && !permits.acquire(bytes).await
+                        {
+                            break;
+                        }
+
                         let _ = match tx.send(res).await.map_err(|e| {
                             RwError::from(ErrorCode::InternalError(format!(
                                 "failed to send stream data: {}",
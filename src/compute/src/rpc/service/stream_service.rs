@@ -154,7 +154,7 @@ impl StreamService for StreamServiceImpl {
         let req = request.into_inner();
         let collect_result = self
             .mgr
-            .collect_barrier_and_sync(req.prev_epoch, true)
+            .collect_barrier_and_sync(req.prev_epoch, req.checkpoint)
             .await;
 
         Ok(Response::new(BarrierCompleteResponse {
@@ -169,6 +169,7 @@ impl StreamService for StreamServiceImpl {
                     sst: Some(sst),
                 })
                 .collect_vec(),
+            actor_stats: collect_result.actor_stats,
         }))
     }
 
@@ -218,6 +219,35 @@ impl StreamService for StreamServiceImpl {
 
         Ok(Response::new(DropSourceResponse { status: None }))
     }
+
+    #[cfg_attr(coverage, no_coverage)]
+    async fn restore_tables(
+        &self,
+        request: Request<RestoreTablesRequest>,
+    ) -> Result<Response<RestoreTablesResponse>, Status> {
+        let req = request.into_inner();
+        let table_ids = req.table_ids.into_iter().map(TableId::new).collect_vec();
+        let synced_sstables = self
+            .mgr
+            .restore_tables(
+                &table_ids,
+                req.savepoint_epoch,
+                req.current_epoch,
+                req.write_epoch,
+            )
+            .await?;
+
+        Ok(Response::new(RestoreTablesResponse {
+            status: None,
+            sycned_sstables: synced_sstables
+                .into_iter()
+                .map(|(compaction_group_id, sst)| GroupedSstableInfo {
+                    compaction_group_id,
+                    sst: Some(sst),
+                })
+                .collect_vec(),
+        }))
+    }
 }
 
 impl StreamServiceImpl {
@@ -245,6 +275,9 @@ impl StreamServiceImpl {
                     .source_manager()
                     .create_table_source(&id, columns)?;
             }
+            Info::ExternalTable(_) => {
+                // External tables are scanned by batch queries directly and have no source here.
+            }
         };
 
         Ok(())
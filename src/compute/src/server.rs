@@ -19,15 +19,16 @@ use std::time::Duration;
 use risingwave_batch::executor::monitor::BatchMetrics;
 use risingwave_batch::rpc::service::task_service::BatchServiceImpl;
 use risingwave_batch::task::{BatchEnvironment, BatchManager};
-use risingwave_common::config::ComputeNodeConfig;
-use risingwave_common::service::MetricsManager;
+use risingwave_common::config::RwConfig;
+use risingwave_common::service::{GrpcMetrics, GrpcMiddlewareLayer, MetricsManager};
 use risingwave_common::util::addr::HostAddr;
+use risingwave_common::util::shutdown::wait_for_shutdown_signal;
 use risingwave_pb::common::WorkerType;
 use risingwave_pb::stream_service::stream_service_server::StreamServiceServer;
 use risingwave_pb::task_service::exchange_service_server::ExchangeServiceServer;
 use risingwave_pb::task_service::task_service_server::TaskServiceServer;
-use risingwave_rpc_client::MetaClient;
-use risingwave_source::MemSourceManager;
+use risingwave_rpc_client::{ComputeClientPool, MetaClient};
+use risingwave_source::{DmlChannelConfig, MemSourceManager};
 use risingwave_storage::hummock::compaction_executor::CompactionExecutor;
 use risingwave_storage::hummock::compactor::Compactor;
 use risingwave_storage::hummock::hummock_meta_client::MonitoredHummockMetaClient;
@@ -36,17 +37,18 @@ use risingwave_storage::monitor::{
 };
 use risingwave_storage::StateStoreImpl;
 use risingwave_stream::executor::monitor::StreamingMetrics;
-use risingwave_stream::task::{LocalStreamManager, StreamEnvironment};
+use risingwave_stream::task::{GlobalMemoryManager, LocalStreamManager, StreamEnvironment};
 use tokio::sync::oneshot::Sender;
 use tokio::task::JoinHandle;
 
+use crate::observer::ObserverManager;
 use crate::rpc::service::exchange_metrics::ExchangeServiceMetrics;
 use crate::rpc::service::exchange_service::ExchangeServiceImpl;
 use crate::rpc::service::stream_service::StreamServiceImpl;
 use crate::ComputeNodeOpts;
 
-fn load_config(opts: &ComputeNodeOpts) -> ComputeNodeConfig {
-    risingwave_common::config::load_config(&opts.config_path)
+fn load_config(opts: &ComputeNodeOpts) -> RwConfig {
+    risingwave_common::config::load_config(&opts.config_path).unwrap()
 }
 
 fn get_compile_mode() -> &'static str {
@@ -74,8 +76,13 @@ pub async fn compute_node_serve(
     let mut meta_client = MetaClient::new(&opts.meta_address).await.unwrap();
 
     // Register to the cluster. We're not ready to serve until activate is called.
+    let worker_type = if opts.read_replica {
+        WorkerType::ServingNode
+    } else {
+        WorkerType::ComputeNode
+    };
     let worker_id = meta_client
-        .register(&client_addr, WorkerType::ComputeNode)
+        .register_in_resource_group(&client_addr, worker_type, &opts.resource_group)
         .await
         .unwrap();
     info!("Assigned worker node id {}", worker_id);
@@ -95,6 +102,7 @@ pub async fn compute_node_serve(
     let storage_config = Arc::new(config.storage.clone());
     let state_store_metrics = Arc::new(StateStoreMetrics::new(registry.clone()));
     let object_store_metrics = Arc::new(ObjectStoreMetrics::new(registry.clone()));
+    let grpc_metrics = Arc::new(GrpcMetrics::new(&registry, "compute"));
     let hummock_meta_client = Arc::new(MonitoredHummockMetaClient::new(
         meta_client.clone(),
         hummock_metrics.clone(),
@@ -109,9 +117,11 @@ pub async fn compute_node_serve(
     .await
     .unwrap();
     if let StateStoreImpl::HummockStateStore(storage) = &state_store {
-        if opts.state_store.starts_with("hummock+memory")
-            || opts.state_store.starts_with("hummock+disk")
-            || storage_config.disable_remote_compactor
+        // The primary cluster compacts the shared state store for read replicas.
+        if !opts.read_replica
+            && (opts.state_store.starts_with("hummock+memory")
+                || opts.state_store.starts_with("hummock+disk")
+                || storage_config.disable_remote_compactor)
         {
             tracing::info!("start embedded compactor");
             // todo: set shutdown_sender in HummockStorage.
@@ -127,15 +137,42 @@ pub async fn compute_node_serve(
         monitor_cache(storage.inner().sstable_store(), &registry).unwrap();
     }
 
+    // Apply the system parameters set by `ALTER SYSTEM`, and watch for their changes.
+    let sstable_store = match &state_store {
+        StateStoreImpl::HummockStateStore(storage) => Some(storage.inner().sstable_store()),
+        _ => None,
+    };
+    ObserverManager::new(
+        meta_client.clone(),
+        client_addr.clone(),
+        worker_type,
+        sstable_store,
+    )
+    .await
+    .unwrap()
+    .start();
+
     // Initialize the managers.
     let batch_mgr = Arc::new(BatchManager::new());
+    // Connections to other compute nodes are shared by batch exchanges and streaming remote inputs.
+    let compute_client_pool = Arc::new(ComputeClientPool::new(u64::MAX));
     let stream_mgr = Arc::new(LocalStreamManager::new(
         client_addr.clone(),
         state_store.clone(),
         streaming_metrics.clone(),
         config.streaming.clone(),
+        compute_client_pool.clone(),
+    ));
+    // Shrink the executor caches under memory pressure.
+    if let Some(sub_task) =
+        GlobalMemoryManager::new(&config.streaming, streaming_metrics.clone()).start()
+    {
+        sub_tasks.push(sub_task);
+    }
+    let source_mgr = Arc::new(MemSourceManager::with_dml_config(
+        worker_id,
+        DmlChannelConfig::new(&config.batch),
     ));
-    let source_mgr = Arc::new(MemSourceManager::new(worker_id));
 
     // Initialize batch environment.
     let batch_config = Arc::new(config.batch.clone());
@@ -147,6 +184,7 @@ pub async fn compute_node_serve(
         worker_id,
         state_store.clone(),
         batch_metrics.clone(),
+        compute_client_pool,
     );
 
     // Initialize the streaming environment.
@@ -163,28 +201,57 @@ pub async fn compute_node_serve(
     let batch_srv = BatchServiceImpl::new(batch_mgr.clone(), batch_env);
     let exchange_srv =
         ExchangeServiceImpl::new(batch_mgr, stream_mgr.clone(), exchange_srv_metrics);
-    let stream_srv = StreamServiceImpl::new(stream_mgr, stream_env.clone());
+    let stream_srv = StreamServiceImpl::new(stream_mgr.clone(), stream_env.clone());
 
+    let graceful_shutdown_timeout =
+        Duration::from_millis(config.server.graceful_shutdown_timeout_ms as u64);
+    let shutdown_meta_client = meta_client.clone();
+    let shutdown_addr = client_addr.clone();
+    let read_replica = opts.read_replica;
     let (shutdown_send, mut shutdown_recv) = tokio::sync::oneshot::channel::<()>();
     let join_handle = tokio::spawn(async move {
         tonic::transport::Server::builder()
+            .layer(GrpcMiddlewareLayer::new(grpc_metrics))
             .add_service(TaskServiceServer::new(batch_srv))
             .add_service(ExchangeServiceServer::new(exchange_srv))
             .add_service(StreamServiceServer::new(stream_srv))
             .serve_with_shutdown(listen_addr, async move {
-                tokio::select! {
-                    _ = tokio::signal::ctrl_c() => {},
-                    _ = &mut shutdown_recv => {
-                        for (join_handle, shutdown_sender) in sub_tasks {
-                            if let Err(err) = shutdown_sender.send(()) {
-                                tracing::warn!("Failed to send shutdown: {:?}", err);
-                                continue;
-                            }
-                            if let Err(err) = join_handle.await {
-                                tracing::warn!("Failed to join shutdown: {:?}", err);
-                            }
-                        }
-                    },
+                let graceful = tokio::select! {
+                    _ = wait_for_shutdown_signal() => true,
+                    _ = &mut shutdown_recv => false,
+                };
+                // Serving nodes have no streaming state to persist.
+                if graceful && !read_replica {
+                    // Keep serving until the state is persisted by the next checkpoint, so that
+                    // recovery after the restart doesn't need to replay from an older epoch.
+                    if let Err(err) = shutdown_meta_client.cordon(&shutdown_addr).await {
+                        tracing::warn!("Failed to cordon: {:?}", err);
+                    }
+                    if tokio::time::timeout(
+                        graceful_shutdown_timeout,
+                        stream_mgr.wait_next_checkpoint(),
+                    )
+                    .await
+                    .is_err()
+                    {
+                        tracing::warn!("Timeout waiting for the next checkpoint, shutting down");
+                    }
+                }
+                for (join_handle, shutdown_sender) in sub_tasks {
+                    if let Err(err) = shutdown_sender.send(()) {
+                        tracing::warn!("Failed to send shutdown: {:?}", err);
+                        continue;
+                    }
+                    if let Err(err) = join_handle.await {
+                        tracing::warn!("Failed to join shutdown: {:?}", err);
+                    }
+                }
+                if graceful {
+                    // Unregister so that meta recovers the streaming jobs without waiting for the
+                    // heartbeat to expire.
+                    if let Err(err) = shutdown_meta_client.unregister(shutdown_addr).await {
+                        tracing::warn!("Failed to unregister: {:?}", err);
+                    }
                 }
             })
             .await
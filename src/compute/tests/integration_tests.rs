@@ -33,8 +33,8 @@ use risingwave_common::test_prelude::DataChunkTestExt;
 use risingwave_common::types::{DataType, IntoOrdered};
 use risingwave_common::util::sort_util::{OrderPair, OrderType};
 use risingwave_pb::data::data_type::TypeName;
-use risingwave_pb::plan_common::ColumnDesc as ProstColumnDesc;
-use risingwave_source::{MemSourceManager, SourceManager};
+use risingwave_pb::plan_common::{ColumnDesc as ProstColumnDesc, HandleConflictBehavior};
+use risingwave_source::{DmlPriority, MemSourceManager, SourceManager};
 use risingwave_storage::memory::MemoryStateStore;
 use risingwave_storage::table::cell_based_table::CellBasedTable;
 use risingwave_storage::table::state_table::StateTable;
@@ -160,6 +160,7 @@ async fn test_table_v2_materialize() -> Result<()> {
         all_column_ids.clone(),
         2,
         vec![0usize],
+        HandleConflictBehavior::NoCheck,
     )
     .boxed()
     .execute();
@@ -178,6 +179,7 @@ async fn test_table_v2_materialize() -> Result<()> {
     let insert = Box::new(InsertExecutor::new(
         source_table_id,
         source_manager.clone(),
+        DmlPriority::High,
         insert_inner,
     ));
 
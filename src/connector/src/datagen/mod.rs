@@ -15,6 +15,7 @@
 pub mod enumerator;
 pub mod source;
 pub mod split;
+pub mod tpch;
 
 use std::collections::HashMap;
 
@@ -0,0 +1,567 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Generator of TPC-H data.
+//!
+//! The value domains follow the TPC-H specification closely enough for all 22 queries to return
+//! non-empty results, but the data is not meant to be validated against the official answer set.
+//! Like the random fields of datagen, every row is derived from a seeded rng of its offset, so
+//! any range of a table can be generated independently and the result is reproducible.
+
+use chrono::{Duration, NaiveDate};
+use rand::distributions::Alphanumeric;
+use rand::prelude::SliceRandom;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde_json::{json, Value};
+
+const REGIONS: [&str; 5] = ["AFRICA", "AMERICA", "ASIA", "EUROPE", "MIDDLE EAST"];
+
+/// Nation names with their region keys.
+const NATIONS: [(&str, i32); 25] = [
+    ("ALGERIA", 0),
+    ("ARGENTINA", 1),
+    ("BRAZIL", 1),
+    ("CANADA", 1),
+    ("EGYPT", 4),
+    ("ETHIOPIA", 0),
+    ("FRANCE", 3),
+    ("GERMANY", 3),
+    ("INDIA", 2),
+    ("INDONESIA", 2),
+    ("IRAN", 4),
+    ("IRAQ", 4),
+    ("JAPAN", 2),
+    ("JORDAN", 4),
+    ("KENYA", 0),
+    ("MOROCCO", 0),
+    ("MOZAMBIQUE", 0),
+    ("PERU", 1),
+    ("CHINA", 2),
+    ("ROMANIA", 3),
+    ("SAUDI ARABIA", 4),
+    ("VIETNAM", 2),
+    ("RUSSIA", 3),
+    ("UNITED KINGDOM", 3),
+    ("UNITED STATES", 1),
+];
+
+const COLORS: [&str; 32] = [
+    "almond",
+    "antique",
+    "aquamarine",
+    "azure",
+    "beige",
+    "bisque",
+    "black",
+    "blanched",
+    "blue",
+    "blush",
+    "brown",
+    "burlywood",
+    "chartreuse",
+    "chiffon",
+    "chocolate",
+    "coral",
+    "cornflower",
+    "cream",
+    "cyan",
+    "dark",
+    "forest",
+    "frosted",
+    "green",
+    "honeydew",
+    "ivory",
+    "khaki",
+    "lace",
+    "lavender",
+    "lemon",
+    "linen",
+    "navy",
+    "red",
+];
+const TYPE_SYLLABLE_1: [&str; 6] = ["STANDARD", "SMALL", "MEDIUM", "LARGE", "ECONOMY", "PROMO"];
+const TYPE_SYLLABLE_2: [&str; 5] = ["ANODIZED", "BURNISHED", "PLATED", "POLISHED", "BRUSHED"];
+const TYPE_SYLLABLE_3: [&str; 5] = ["TIN", "NICKEL", "BRASS", "STEEL", "COPPER"];
+const CONTAINER_SYLLABLE_1: [&str; 5] = ["SM", "LG", "MED", "JUMBO", "WRAP"];
+const CONTAINER_SYLLABLE_2: [&str; 8] = ["CASE", "BOX", "BAG", "JAR", "PKG", "PACK", "CAN", "DRUM"];
+const SEGMENTS: [&str; 5] = [
+    "AUTOMOBILE",
+    "BUILDING",
+    "FURNITURE",
+    "MACHINERY",
+    "HOUSEHOLD",
+];
+const PRIORITIES: [&str; 5] = ["1-URGENT", "2-HIGH", "3-MEDIUM", "4-NOT SPECIFIED", "5-LOW"];
+const INSTRUCTIONS: [&str; 4] = [
+    "DELIVER IN PERSON",
+    "COLLECT COD",
+    "NONE",
+    "TAKE BACK RETURN",
+];
+const MODES: [&str; 7] = ["REG AIR", "AIR", "RAIL", "SHIP", "TRUCK", "MAIL", "FOB"];
+const WORDS: [&str; 16] = [
+    "furiously",
+    "quickly",
+    "carefully",
+    "blithely",
+    "final",
+    "regular",
+    "express",
+    "pending",
+    "special",
+    "ironic",
+    "requests",
+    "deposits",
+    "accounts",
+    "packages",
+    "Customer",
+    "Complaints",
+];
+
+/// Tables of TPC-H, in an order that respects the foreign keys between them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TpchTable {
+    Region,
+    Nation,
+    Supplier,
+    Part,
+    PartSupp,
+    Customer,
+    Orders,
+    LineItem,
+}
+
+impl TpchTable {
+    pub const ALL: [TpchTable; 8] = [
+        TpchTable::Region,
+        TpchTable::Nation,
+        TpchTable::Supplier,
+        TpchTable::Part,
+        TpchTable::PartSupp,
+        TpchTable::Customer,
+        TpchTable::Orders,
+        TpchTable::LineItem,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TpchTable::Region => "region",
+            TpchTable::Nation => "nation",
+            TpchTable::Supplier => "supplier",
+            TpchTable::Part => "part",
+            TpchTable::PartSupp => "partsupp",
+            TpchTable::Customer => "customer",
+            TpchTable::Orders => "orders",
+            TpchTable::LineItem => "lineitem",
+        }
+    }
+
+    pub fn columns(&self) -> &'static [&'static str] {
+        match self {
+            TpchTable::Region => &["r_regionkey", "r_name", "r_comment"],
+            TpchTable::Nation => &["n_nationkey", "n_name", "n_regionkey", "n_comment"],
+            TpchTable::Supplier => &[
+                "s_suppkey",
+                "s_name",
+                "s_address",
+                "s_nationkey",
+                "s_phone",
+                "s_acctbal",
+                "s_comment",
+            ],
+            TpchTable::Part => &[
+                "p_partkey",
+                "p_name",
+                "p_mfgr",
+                "p_brand",
+                "p_type",
+                "p_size",
+                "p_container",
+                "p_retailprice",
+                "p_comment",
+            ],
+            TpchTable::PartSupp => &[
+                "ps_partkey",
+                "ps_suppkey",
+                "ps_availqty",
+                "ps_supplycost",
+                "ps_comment",
+            ],
+            TpchTable::Customer => &[
+                "c_custkey",
+                "c_name",
+                "c_address",
+                "c_nationkey",
+                "c_phone",
+                "c_acctbal",
+                "c_mktsegment",
+                "c_comment",
+            ],
+            TpchTable::Orders => &[
+                "o_orderkey",
+                "o_custkey",
+                "o_orderstatus",
+                "o_totalprice",
+                "o_orderdate",
+                "o_orderpriority",
+                "o_clerk",
+                "o_shippriority",
+                "o_comment",
+            ],
+            TpchTable::LineItem => &[
+                "l_orderkey",
+                "l_partkey",
+                "l_suppkey",
+                "l_linenumber",
+                "l_quantity",
+                "l_extendedprice",
+                "l_discount",
+                "l_tax",
+                "l_returnflag",
+                "l_linestatus",
+                "l_shipdate",
+                "l_commitdate",
+                "l_receiptdate",
+                "l_shipinstruct",
+                "l_shipmode",
+                "l_comment",
+            ],
+        }
+    }
+}
+
+/// A generated row. Dates are strings in `YYYY-MM-DD`, and decimals are numbers with at most 2
+/// fractional digits.
+pub type TpchRow = Vec<Value>;
+
+pub struct TpchGenerator {
+    scale_factor: f64,
+    seed: u64,
+}
+
+impl TpchGenerator {
+    pub fn new(scale_factor: f64, seed: u64) -> Self {
+        assert!(scale_factor > 0.0, "scale factor must be positive");
+        Self { scale_factor, seed }
+    }
+
+    fn scaled(&self, base: u64) -> u64 {
+        ((base as f64 * self.scale_factor) as u64).max(1)
+    }
+
+    fn supplier_count(&self) -> u64 {
+        self.scaled(10_000)
+    }
+
+    fn part_count(&self) -> u64 {
+        self.scaled(200_000)
+    }
+
+    fn customer_count(&self) -> u64 {
+        self.scaled(150_000)
+    }
+
+    fn order_count(&self) -> u64 {
+        self.scaled(1_500_000)
+    }
+
+    /// Number of rows to generate for `table`. `lineitem` rows are generated together with their
+    /// order, so the number of orders is returned for it.
+    pub fn row_count(&self, table: TpchTable) -> u64 {
+        match table {
+            TpchTable::Region => REGIONS.len() as u64,
+            TpchTable::Nation => NATIONS.len() as u64,
+            TpchTable::Supplier => self.supplier_count(),
+            TpchTable::Part => self.part_count(),
+            TpchTable::PartSupp => self.part_count() * 4,
+            TpchTable::Customer => self.customer_count(),
+            TpchTable::Orders | TpchTable::LineItem => self.order_count(),
+        }
+    }
+
+    fn rng(&self, table: TpchTable, offset: u64) -> StdRng {
+        StdRng::seed_from_u64((offset ^ self.seed).wrapping_add((table as u64) << 56))
+    }
+
+    /// Generates the rows of `table` at `offset`. Every table yields one row per offset, except
+    /// `lineitem` which yields all lines of the order at `offset`.
+    pub fn generate(&self, table: TpchTable, offset: u64) -> Vec<TpchRow> {
+        match table {
+            TpchTable::Region => vec![self.region(offset)],
+            TpchTable::Nation => vec![self.nation(offset)],
+            TpchTable::Supplier => vec![self.supplier(offset)],
+            TpchTable::Part => vec![self.part(offset)],
+            TpchTable::PartSupp => vec![self.partsupp(offset)],
+            TpchTable::Customer => vec![self.customer(offset)],
+            TpchTable::Orders => vec![self.order(offset).0],
+            TpchTable::LineItem => self.order(offset).1,
+        }
+    }
+
+    fn region(&self, offset: u64) -> TpchRow {
+        let mut rng = self.rng(TpchTable::Region, offset);
+        vec![
+            json!(offset),
+            json!(REGIONS[offset as usize]),
+            json!(text(&mut rng, 31, 115)),
+        ]
+    }
+
+    fn nation(&self, offset: u64) -> TpchRow {
+        let mut rng = self.rng(TpchTable::Nation, offset);
+        let (name, region) = NATIONS[offset as usize];
+        vec![
+            json!(offset),
+            json!(name),
+            json!(region),
+            json!(text(&mut rng, 31, 114)),
+        ]
+    }
+
+    fn supplier(&self, offset: u64) -> TpchRow {
+        let mut rng = self.rng(TpchTable::Supplier, offset);
+        let key = offset + 1;
+        let nation = rng.gen_range(0..NATIONS.len() as u64);
+        vec![
+            json!(key),
+            json!(format!("Supplier#{:09}", key)),
+            json!(address(&mut rng)),
+            json!(nation),
+            json!(phone(&mut rng, nation)),
+            json!(cents(rng.gen_range(-99_999..=999_999))),
+            json!(text(&mut rng, 25, 100)),
+        ]
+    }
+
+    fn part(&self, offset: u64) -> TpchRow {
+        let mut rng = self.rng(TpchTable::Part, offset);
+        let key = offset + 1;
+        let name = COLORS
+            .choose_multiple(&mut rng, 5)
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(" ");
+        let mfgr = rng.gen_range(1..=5);
+        let brand = rng.gen_range(1..=5);
+        vec![
+            json!(key),
+            json!(name),
+            json!(format!("Manufacturer#{}", mfgr)),
+            json!(format!("Brand#{}{}", mfgr, brand)),
+            json!(format!(
+                "{} {} {}",
+                pick(&mut rng, &TYPE_SYLLABLE_1),
+                pick(&mut rng, &TYPE_SYLLABLE_2),
+                pick(&mut rng, &TYPE_SYLLABLE_3)
+            )),
+            json!(rng.gen_range(1..=50)),
+            json!(format!(
+                "{} {}",
+                pick(&mut rng, &CONTAINER_SYLLABLE_1),
+                pick(&mut rng, &CONTAINER_SYLLABLE_2)
+            )),
+            json!(cents(retail_price_cents(key))),
+            json!(text(&mut rng, 5, 22)),
+        ]
+    }
+
+    /// The `i`-th (0 to 3) supplier of a part, as defined by the specification.
+    fn part_supplier(&self, part: u64, i: u64) -> u64 {
+        let s = self.supplier_count();
+        (part + (i * (s / 4 + (part - 1) / s))) % s + 1
+    }
+
+    fn partsupp(&self, offset: u64) -> TpchRow {
+        let mut rng = self.rng(TpchTable::PartSupp, offset);
+        let part = offset / 4 + 1;
+        vec![
+            json!(part),
+            json!(self.part_supplier(part, offset % 4)),
+            json!(rng.gen_range(1..=9999)),
+            json!(cents(rng.gen_range(100..=100_000))),
+            json!(text(&mut rng, 49, 198)),
+        ]
+    }
+
+    fn customer(&self, offset: u64) -> TpchRow {
+        let mut rng = self.rng(TpchTable::Customer, offset);
+        let key = offset + 1;
+        let nation = rng.gen_range(0..NATIONS.len() as u64);
+        vec![
+            json!(key),
+            json!(format!("Customer#{:09}", key)),
+            json!(address(&mut rng)),
+            json!(nation),
+            json!(phone(&mut rng, nation)),
+            json!(cents(rng.gen_range(-99_999..=999_999))),
+            json!(pick(&mut rng, &SEGMENTS)),
+            json!(text(&mut rng, 29, 116)),
+        ]
+    }
+
+    /// Generates an order together with its lines, since the status and total price of an order
+    /// are derived from its lines.
+    fn order(&self, offset: u64) -> (TpchRow, Vec<TpchRow>) {
+        let mut rng = self.rng(TpchTable::Orders, offset);
+        let key = offset + 1;
+        let start = NaiveDate::from_ymd(1992, 1, 1);
+        let current = NaiveDate::from_ymd(1995, 6, 17);
+        let order_date = start + Duration::days(rng.gen_range(0..=2405));
+
+        let mut total_cents = 0;
+        let mut lines = vec![];
+        for line_number in 1..=rng.gen_range(1..=7) {
+            let part = rng.gen_range(1..=self.part_count());
+            let supplier = self.part_supplier(part, rng.gen_range(0..4));
+            let quantity = rng.gen_range(1..=50);
+            let price = quantity * retail_price_cents(part);
+            let discount = rng.gen_range(0..=10);
+            let tax = rng.gen_range(0..=8);
+            // Round half up as the price is in cents.
+            total_cents += (price * (100 - discount) * (100 + tax) + 5000) / 10000;
+
+            let ship_date = order_date + Duration::days(rng.gen_range(1..=121));
+            let commit_date = order_date + Duration::days(rng.gen_range(30..=90));
+            let receipt_date = ship_date + Duration::days(rng.gen_range(1..=30));
+            let return_flag = if receipt_date <= current {
+                *["R", "A"].choose(&mut rng).unwrap()
+            } else {
+                "N"
+            };
+            let line_status = if ship_date > current { "O" } else { "F" };
+            lines.push(vec![
+                json!(key),
+                json!(part),
+                json!(supplier),
+                json!(line_number),
+                json!(quantity),
+                json!(cents(price as i64)),
+                json!(cents(discount as i64)),
+                json!(cents(tax as i64)),
+                json!(return_flag),
+                json!(line_status),
+                json!(date(ship_date)),
+                json!(date(commit_date)),
+                json!(date(receipt_date)),
+                json!(pick(&mut rng, &INSTRUCTIONS)),
+                json!(pick(&mut rng, &MODES)),
+                json!(text(&mut rng, 10, 43)),
+            ]);
+        }
+
+        let status = if lines.iter().all(|line| line[9] == "F") {
+            "F"
+        } else if lines.iter().all(|line| line[9] == "O") {
+            "O"
+        } else {
+            "P"
+        };
+        let clerks = self.scaled(1000);
+        let order = vec![
+            json!(key),
+            json!(rng.gen_range(1..=self.customer_count())),
+            json!(status),
+            json!(cents(total_cents as i64)),
+            json!(date(order_date)),
+            json!(pick(&mut rng, &PRIORITIES)),
+            json!(format!("Clerk#{:09}", rng.gen_range(1..=clerks))),
+            json!(0),
+            json!(text(&mut rng, 19, 78)),
+        ];
+        (order, lines)
+    }
+}
+
+fn retail_price_cents(part: u64) -> u64 {
+    90000 + ((part / 10) % 20001) + 100 * (part % 1000)
+}
+
+fn cents(cents: i64) -> f64 {
+    cents as f64 / 100.0
+}
+
+fn date(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+fn pick(rng: &mut StdRng, values: &[&'static str]) -> &'static str {
+    values.choose(rng).unwrap()
+}
+
+fn address(rng: &mut StdRng) -> String {
+    let len = rng.gen_range(10..=40);
+    rng.sample_iter(&Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
+}
+
+fn phone(rng: &mut StdRng, nation: u64) -> String {
+    format!(
+        "{}-{}-{}-{}",
+        nation + 10,
+        rng.gen_range(100..=999),
+        rng.gen_range(100..=999),
+        rng.gen_range(1000..=9999)
+    )
+}
+
+/// Random words with a length in `[min, max]`.
+fn text(rng: &mut StdRng, min: usize, max: usize) -> String {
+    let len = rng.gen_range(min..=max);
+    let mut text = String::with_capacity(len + 16);
+    while text.len() < len {
+        if !text.is_empty() {
+            text.push(' ');
+        }
+        text.push_str(pick(rng, &WORDS));
+    }
+    text.truncate(max);
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deterministic() {
+        let generator = TpchGenerator::new(0.01, 42);
+        for table in TpchTable::ALL {
+            for offset in [0, generator.row_count(table) - 1] {
+                let rows = generator.generate(table, offset);
+                assert_eq!(rows, generator.generate(table, offset));
+                for row in rows {
+                    assert_eq!(row.len(), table.columns().len(), "{:?}", table);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_order_consistent_with_lines() {
+        let generator = TpchGenerator::new(0.01, 0);
+        for offset in 0..100 {
+            let order = &generator.generate(TpchTable::Orders, offset)[0];
+            let lines = generator.generate(TpchTable::LineItem, offset);
+            assert!((1..=7).contains(&lines.len()));
+            assert!(lines.iter().all(|line| line[0] == order[0]));
+            let part_count = generator.row_count(TpchTable::Part);
+            assert!(lines
+                .iter()
+                .all(|line| (1..=part_count).contains(&line[1].as_u64().unwrap())));
+        }
+    }
+}
@@ -0,0 +1,245 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Snapshots of Delta Lake tables, replayed from the JSON commits of their transaction logs.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use anyhow::{anyhow, bail, Result};
+use itertools::Itertools;
+use risingwave_object_store::object::ObjectStore;
+use serde::Deserialize;
+
+use super::LakeFile;
+
+const DELTA_LOG_DIR: &str = "_delta_log";
+
+#[derive(Debug, Default, Deserialize)]
+struct Action {
+    add: Option<AddAction>,
+    remove: Option<RemoveAction>,
+    #[serde(rename = "metaData")]
+    metadata: Option<MetadataAction>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AddAction {
+    path: String,
+    #[serde(default)]
+    partition_values: HashMap<String, Option<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RemoveAction {
+    path: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MetadataAction {
+    #[serde(default)]
+    partition_columns: Vec<String>,
+}
+
+/// The data files of a Delta table at one committed version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeltaSnapshot {
+    pub version: u64,
+    pub partition_columns: Vec<String>,
+    pub files: Vec<LakeFile>,
+}
+
+/// Loads the snapshot of the Delta table under `prefix` at `version`, or at the latest version if
+/// it's `None`.
+pub async fn load_snapshot(
+    store: &dyn ObjectStore,
+    prefix: &str,
+    version: Option<u64>,
+) -> Result<DeltaSnapshot> {
+    let log_prefix = format!("{}{}/", prefix, DELTA_LOG_DIR);
+    // Commit files are named by their zero-padded versions, so they are listed in order.
+    let commits = store
+        .list(&log_prefix)
+        .await?
+        .into_iter()
+        .filter_map(|path| {
+            let version = path
+                .strip_prefix(&log_prefix)?
+                .strip_suffix(".json")?
+                .parse::<u64>()
+                .ok()?;
+            Some((version, path))
+        })
+        .collect_vec();
+    match commits.first() {
+        None => bail!("no Delta transaction log found in {}", log_prefix),
+        Some((first, _)) if *first != 0 => bail!(
+            "the transaction log of {} starts at version {}, and checkpoints are not supported",
+            prefix,
+            first
+        ),
+        _ => {}
+    }
+
+    let mut added = BTreeMap::new();
+    let mut partition_columns = vec![];
+    let mut snapshot_version = 0;
+    for (commit_version, path) in commits {
+        if let Some(version) = version
+            && commit_version > version
+        {
+            break;
+        }
+        let data = store.read(&path, None).await?;
+        for line in std::str::from_utf8(&data)?.lines() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let action: Action = serde_json::from_str(line)
+                .map_err(|e| anyhow!("failed to parse Delta action in {}: {}", path, e))?;
+            if let Some(metadata) = action.metadata {
+                partition_columns = metadata.partition_columns;
+            }
+            if let Some(remove) = action.remove {
+                added.remove(&remove.path);
+            }
+            if let Some(add) = action.add {
+                added.insert(add.path, add.partition_values);
+            }
+        }
+        snapshot_version = commit_version;
+    }
+    if let Some(version) = version
+        && version != snapshot_version
+    {
+        bail!("version {} of Delta table {} not found", version, prefix);
+    }
+
+    let files = added
+        .into_iter()
+        .map(|(path, mut partition_values)| {
+            if path.contains("://") {
+                bail!("data file {} outside of the table is not supported", path);
+            }
+            let partitions = partition_columns
+                .iter()
+                .map(|column| match partition_values.remove(column).flatten() {
+                    Some(value) => Ok((column.to_lowercase(), value)),
+                    None => Err(anyhow!(
+                        "null partition value of {} in {} is not supported",
+                        column,
+                        path
+                    )),
+                })
+                .collect::<Result<_>>()?;
+            Ok(LakeFile {
+                path: format!("{}{}", prefix, urlencoding::decode(&path)?),
+                partitions,
+            })
+        })
+        .collect::<Result<_>>()?;
+    Ok(DeltaSnapshot {
+        version: snapshot_version,
+        partition_columns: partition_columns
+            .iter()
+            .map(|column| column.to_lowercase())
+            .collect(),
+        files,
+    })
+}
+
+/// Lists the Delta tables directly under `prefix`, as `(name, prefix)` pairs ordered by name.
+pub async fn list_tables(store: &dyn ObjectStore, prefix: &str) -> Result<Vec<(String, String)>> {
+    let tables: BTreeSet<_> = store
+        .list(prefix)
+        .await?
+        .iter()
+        .filter_map(|path| {
+            let mut segments = path.strip_prefix(prefix)?.split('/');
+            match (segments.next(), segments.next()) {
+                (Some(name), Some(DELTA_LOG_DIR)) if !name.is_empty() => Some(name.to_string()),
+                _ => None,
+            }
+        })
+        .collect();
+    Ok(tables
+        .into_iter()
+        .map(|name| {
+            let table_prefix = format!("{}{}/", prefix, name);
+            (name.to_lowercase(), table_prefix)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use risingwave_object_store::object::InMemObjectStore;
+
+    use super::*;
+
+    async fn commit(store: &InMemObjectStore, version: u64, actions: &[&str]) {
+        store
+            .upload(
+                &format!("lake/t/_delta_log/{:020}.json", version),
+                Bytes::from(actions.join("\n")),
+            )
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_load_snapshot() {
+        let store = InMemObjectStore::new(false);
+        commit(
+            &store,
+            0,
+            &[
+                r#"{"metaData":{"id":"1","partitionColumns":["dt"]}}"#,
+                r#"{"add":{"path":"dt=1/a.parquet","partitionValues":{"dt":"1"}}}"#,
+            ],
+        )
+        .await;
+        commit(
+            &store,
+            1,
+            &[
+                r#"{"remove":{"path":"dt=1/a.parquet"}}"#,
+                r#"{"add":{"path":"dt=1/b%20c.parquet","partitionValues":{"dt":"1"}}}"#,
+            ],
+        )
+        .await;
+
+        let latest = load_snapshot(&store, "lake/t/", None).await.unwrap();
+        assert_eq!(
+            latest,
+            DeltaSnapshot {
+                version: 1,
+                partition_columns: vec!["dt".to_string()],
+                files: vec![LakeFile {
+                    path: "lake/t/dt=1/b c.parquet".to_string(),
+                    partitions: vec![("dt".to_string(), "1".to_string())],
+                }],
+            }
+        );
+
+        let first = load_snapshot(&store, "lake/t/", Some(0)).await.unwrap();
+        assert_eq!(first.files[0].path, "lake/t/dt=1/a.parquet");
+        assert!(load_snapshot(&store, "lake/t/", Some(2)).await.is_err());
+
+        let tables = list_tables(&store, "lake/").await.unwrap();
+        assert_eq!(tables, vec![("t".to_string(), "lake/t/".to_string())]);
+    }
+}
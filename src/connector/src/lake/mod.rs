@@ -0,0 +1,301 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Ad hoc access to Parquet files in a data lake, laid out in Hive-style partition directories
+//! such as `s3://bucket/events/dt=2022-07-01/part-0.parquet`.
+
+use std::path::{Component, Path};
+use std::sync::Arc;
+
+use anyhow::{anyhow, bail, Result};
+use itertools::Itertools;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use risingwave_common::array::arrow::from_arrow_schema;
+use risingwave_common::array::column::Column;
+use risingwave_common::array::{DataChunk, Utf8Array};
+use risingwave_common::catalog::{Field, Schema};
+use risingwave_common::types::DataType;
+use risingwave_object_store::object::{parse_object_store, ObjectStore};
+
+pub mod delta;
+pub mod unload;
+
+const PARQUET_SUFFIX: &str = ".parquet";
+
+/// The location of an external table, split into the object store holding it and the prefix of
+/// its files within the store.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LakeLocation {
+    store_url: String,
+    prefix: String,
+}
+
+impl LakeLocation {
+    /// Parses `s3://bucket/path` or `disk:///path`.
+    pub fn parse(location: &str) -> Result<Self> {
+        if let Some(path) = location.strip_prefix("s3://") {
+            let (bucket, prefix) = path.split_once('/').unwrap_or((path, ""));
+            if bucket.is_empty() {
+                bail!("missing bucket in location: {}", location);
+            }
+            let prefix = prefix.trim_matches('/');
+            Ok(Self {
+                store_url: format!("s3://{}", bucket),
+                prefix: if prefix.is_empty() {
+                    String::new()
+                } else {
+                    format!("{}/", prefix)
+                },
+            })
+        } else if location.starts_with("disk://") {
+            Ok(Self {
+                store_url: location.trim_end_matches('/').to_string(),
+                prefix: String::new(),
+            })
+        } else {
+            bail!(
+                "unsupported location: {}, expected s3://bucket/path or disk:///path",
+                location
+            )
+        }
+    }
+
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Checks that a `disk://` location is under one of the local directories `roots`, so that
+    /// the files of the nodes can't be accessed through arbitrary paths. S3 locations are always
+    /// allowed.
+    pub fn check_disk_roots(&self, roots: &[String]) -> Result<()> {
+        let path = match self.store_url.strip_prefix("disk://") {
+            Some(path) => Path::new(path),
+            None => return Ok(()),
+        };
+        if !path.is_absolute() || path.components().any(|c| c == Component::ParentDir) {
+            bail!(
+                "disk location must be an absolute path without `..`: {}",
+                path.display()
+            );
+        }
+        if !roots.iter().any(|root| path.starts_with(root)) {
+            bail!(
+                "disk location {} is not under any of `lake_disk_roots` {:?}",
+                path.display(),
+                roots
+            );
+        }
+        Ok(())
+    }
+
+    pub async fn connect(&self) -> Box<dyn ObjectStore> {
+        parse_object_store(&self.store_url, false).await
+    }
+}
+
+/// A Parquet file of an external table, with the partition values parsed from its `key=value`
+/// directories.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LakeFile {
+    pub path: String,
+    pub partitions: Vec<(String, String)>,
+}
+
+impl LakeFile {
+    pub fn partition_value(&self, column: &str) -> Option<&str> {
+        self.partitions
+            .iter()
+            .find(|(name, _)| name == column)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Lists the Parquet files under `prefix`, in ascending order of their paths.
+pub async fn list_files(store: &dyn ObjectStore, prefix: &str) -> Result<Vec<LakeFile>> {
+    let paths = store.list(prefix).await?;
+    Ok(paths
+        .into_iter()
+        .filter(|path| path.ends_with(PARQUET_SUFFIX))
+        .map(|path| {
+            let relative = path.strip_prefix(prefix).unwrap_or(&path);
+            let partitions = relative
+                .split('/')
+                .rev()
+                .skip(1)
+                .filter_map(|segment| segment.split_once('='))
+                .map(|(name, value)| (name.to_lowercase(), value.to_string()))
+                .collect_vec()
+                .into_iter()
+                .rev()
+                .collect();
+            LakeFile { path, partitions }
+        })
+        .collect())
+}
+
+/// Infers the schema of the table from one of its files. Partition columns follow the columns
+/// stored in the file, as `VARCHAR`.
+pub async fn infer_schema(store: &dyn ObjectStore, file: &LakeFile) -> Result<Schema> {
+    let data = store.read(&file.path, None).await?;
+    let reader = ParquetRecordBatchReaderBuilder::try_new(data)?;
+    let mut schema = from_arrow_schema(reader.schema())?;
+    for (name, _) in &file.partitions {
+        if schema.fields.iter().any(|field| &field.name == name) {
+            bail!("partition column {} is also stored in {}", name, file.path);
+        }
+        schema
+            .fields
+            .push(Field::with_name(DataType::Varchar, name.clone()));
+    }
+    Ok(schema)
+}
+
+/// Reads `file` into chunks of at most `chunk_size` rows laid out as `schema`. Columns are matched
+/// by name, and partition columns are filled with the value from the path of the file.
+pub async fn read_file(
+    store: &dyn ObjectStore,
+    file: &LakeFile,
+    schema: &Schema,
+    chunk_size: usize,
+) -> Result<Vec<DataChunk>> {
+    let data = store.read(&file.path, None).await?;
+    let reader = ParquetRecordBatchReaderBuilder::try_new(data)?
+        .with_batch_size(chunk_size)
+        .build()?;
+    let mut chunks = vec![];
+    for batch in reader {
+        let batch = batch?;
+        let file_schema = from_arrow_schema(&batch.schema())?;
+        let chunk = DataChunk::from_record_batch(&batch)?;
+        let columns = schema
+            .fields()
+            .iter()
+            .map(|field| {
+                if let Some(value) = file.partition_value(&field.name) {
+                    let array = Utf8Array::from_slice(&vec![Some(value); chunk.cardinality()])?;
+                    return Ok(Column::new(Arc::new(array.into())));
+                }
+                let (idx, file_field) = file_schema
+                    .fields()
+                    .iter()
+                    .find_position(|file_field| file_field.name == field.name)
+                    .ok_or_else(|| anyhow!("column {} not found in {}", field.name, file.path))?;
+                if file_field.data_type != field.data_type {
+                    bail!(
+                        "column {} of {} has type {:?}, expected {:?}",
+                        field.name,
+                        file.path,
+                        file_field.data_type,
+                        field.data_type
+                    );
+                }
+                Ok(chunk.column_at(idx).clone())
+            })
+            .collect::<Result<Vec<_>>>()?;
+        chunks.push(DataChunk::new(columns, chunk.cardinality()));
+    }
+    Ok(chunks)
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use parquet::arrow::ArrowWriter;
+    use risingwave_common::array::DataChunkTestExt;
+    use risingwave_object_store::object::InMemObjectStore;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_location() {
+        let location = LakeLocation::parse("s3://bucket/events/").unwrap();
+        assert_eq!(location.store_url, "s3://bucket");
+        assert_eq!(location.prefix(), "events/");
+        assert_eq!(LakeLocation::parse("s3://bucket").unwrap().prefix(), "");
+        assert!(LakeLocation::parse("s3:///events").is_err());
+        assert!(LakeLocation::parse("hdfs://events").is_err());
+    }
+
+    #[test]
+    fn test_check_disk_roots() {
+        let roots = vec!["/data/lake".to_string()];
+        let check = |location: &str| {
+            LakeLocation::parse(location)
+                .unwrap()
+                .check_disk_roots(&roots)
+        };
+        check("s3://bucket/events/").unwrap();
+        check("disk:///data/lake/events/").unwrap();
+        assert!(check("disk:///data/lakehouse/events").is_err());
+        assert!(check("disk:///data/lake/../../etc").is_err());
+        assert!(check("disk://data/lake/events").is_err());
+        assert!(LakeLocation::parse("disk:///data/lake")
+            .unwrap()
+            .check_disk_roots(&[])
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_read_partitioned_table() {
+        let schema = Schema::new(vec![
+            Field::with_name(DataType::Int32, "v1"),
+            Field::with_name(DataType::Varchar, "v2"),
+        ]);
+        let chunk = DataChunk::from_pretty(
+            "i T
+             1 a
+             2 b",
+        );
+        let batch = chunk.to_record_batch(&schema).unwrap();
+        let mut buf = vec![];
+        let mut writer = ArrowWriter::try_new(&mut buf, batch.schema(), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let store = InMemObjectStore::new(false);
+        store
+            .upload("t/dt=1/part-0.parquet", Bytes::from(buf))
+            .await
+            .unwrap();
+        store.upload("t/_SUCCESS", Bytes::new()).await.unwrap();
+
+        let files = list_files(&store, "t/").await.unwrap();
+        assert_eq!(
+            files,
+            vec![LakeFile {
+                path: "t/dt=1/part-0.parquet".to_string(),
+                partitions: vec![("dt".to_string(), "1".to_string())],
+            }]
+        );
+
+        let table_schema = infer_schema(&store, &files[0]).await.unwrap();
+        assert_eq!(
+            table_schema.fields()[2],
+            Field::with_name(DataType::Varchar, "dt")
+        );
+
+        let chunks = read_file(&store, &files[0], &table_schema, 1024)
+            .await
+            .unwrap();
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(
+            chunks[0],
+            DataChunk::from_pretty(
+                "i T T
+                 1 a 1
+                 2 b 1",
+            )
+        );
+    }
+}
@@ -0,0 +1,257 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Encoding of the files written by `UNLOAD`.
+
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use bytes::Bytes;
+use parquet::arrow::ArrowWriter;
+use parquet::file::properties::WriterProperties;
+use risingwave_common::array::arrow::to_arrow_schema;
+use risingwave_common::array::DataChunk;
+use risingwave_common::catalog::Schema;
+use risingwave_common::types::ScalarRefImpl;
+use risingwave_object_store::object::{BoxedStreamingUploader, ObjectStore};
+
+/// Rows of a Parquet row group. Each row group is uploaded as soon as it's complete, so this
+/// bounds the memory buffered by a file.
+const PARQUET_ROW_GROUP_SIZE: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnloadFormat {
+    Parquet,
+    Csv,
+}
+
+impl UnloadFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            UnloadFormat::Parquet => "parquet",
+            UnloadFormat::Csv => "csv",
+        }
+    }
+}
+
+/// A `Write` that can be drained while being owned by the `ArrowWriter`.
+#[derive(Clone, Default)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn take(&self) -> Vec<u8> {
+        std::mem::take(&mut *self.0.lock().unwrap())
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+enum Encoder {
+    Parquet {
+        writer: ArrowWriter<SharedBuf>,
+        buf: SharedBuf,
+    },
+    Csv,
+}
+
+/// Writes chunks into a file of the object store with a streaming upload, so that only the
+/// encoded data not yet uploaded is held in memory.
+pub struct PartWriter {
+    schema: Schema,
+    uploader: BoxedStreamingUploader,
+    encoder: Encoder,
+    rows: usize,
+}
+
+impl PartWriter {
+    pub async fn new(
+        store: &dyn ObjectStore,
+        path: &str,
+        format: UnloadFormat,
+        schema: Schema,
+    ) -> Result<Self> {
+        let mut uploader = store.streaming_upload(path).await?;
+        let encoder = match format {
+            UnloadFormat::Parquet => {
+                let buf = SharedBuf::default();
+                let props = WriterProperties::builder()
+                    .set_max_row_group_size(PARQUET_ROW_GROUP_SIZE)
+                    .build();
+                let writer = ArrowWriter::try_new(
+                    buf.clone(),
+                    Arc::new(to_arrow_schema(&schema)?),
+                    Some(props),
+                )?;
+                Encoder::Parquet { writer, buf }
+            }
+            UnloadFormat::Csv => {
+                let header = schema
+                    .fields()
+                    .iter()
+                    .map(|field| csv_escape(&field.name))
+                    .collect::<Vec<_>>()
+                    .join(",");
+                uploader
+                    .write_bytes(Bytes::from(format!("{}\n", header)))
+                    .await?;
+                Encoder::Csv
+            }
+        };
+        Ok(Self {
+            schema,
+            uploader,
+            encoder,
+            rows: 0,
+        })
+    }
+
+    /// The number of rows written.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub async fn write(&mut self, chunk: &DataChunk) -> Result<()> {
+        let data = match &mut self.encoder {
+            Encoder::Parquet { writer, buf } => {
+                writer.write(&chunk.to_record_batch(&self.schema)?)?;
+                buf.take()
+            }
+            Encoder::Csv => {
+                let mut data = String::new();
+                for row in chunk.rows() {
+                    for (i, datum) in row.values().enumerate() {
+                        if i > 0 {
+                            data.push(',');
+                        }
+                        if let Some(scalar) = datum {
+                            data.push_str(&csv_escape(&csv_value_format(scalar)));
+                        }
+                    }
+                    data.push('\n');
+                }
+                data.into_bytes()
+            }
+        };
+        self.rows += chunk.cardinality();
+        if !data.is_empty() {
+            self.uploader.write_bytes(Bytes::from(data)).await?;
+        }
+        Ok(())
+    }
+
+    /// Completes the file, after which it's visible in the object store. Returns the number of
+    /// rows written.
+    pub async fn finish(mut self) -> Result<usize> {
+        if let Encoder::Parquet { writer, buf } = self.encoder {
+            writer.close()?;
+            self.uploader.write_bytes(Bytes::from(buf.take())).await?;
+        }
+        self.uploader.finish().await?;
+        Ok(self.rows)
+    }
+}
+
+/// Nulls are written as empty fields, and booleans as `true` or `false`.
+fn csv_value_format(scalar: ScalarRefImpl<'_>) -> String {
+    match scalar {
+        ScalarRefImpl::Bool(b) => b.to_string(),
+        _ => scalar.to_string(),
+    }
+}
+
+/// Quotes `value` if needed, following RFC 4180.
+fn csv_escape(value: &str) -> String {
+    if value.contains(|c| matches!(c, ',' | '"' | '\n' | '\r')) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use risingwave_common::array::DataChunkTestExt;
+    use risingwave_common::catalog::Field;
+    use risingwave_common::types::DataType;
+    use risingwave_object_store::object::InMemObjectStore;
+
+    use super::*;
+
+    fn schema() -> Schema {
+        Schema::new(vec![
+            Field::with_name(DataType::Int32, "v1"),
+            Field::with_name(DataType::Varchar, "v2"),
+        ])
+    }
+
+    #[tokio::test]
+    async fn test_csv() {
+        let store = InMemObjectStore::new(false);
+        let mut writer = PartWriter::new(&store, "part.csv", UnloadFormat::Csv, schema())
+            .await
+            .unwrap();
+        writer
+            .write(&DataChunk::from_pretty(
+                "i T
+                 1 a,b
+                 . c",
+            ))
+            .await
+            .unwrap();
+        assert_eq!(writer.finish().await.unwrap(), 2);
+        assert_eq!(
+            store.read("part.csv", None).await.unwrap(),
+            Bytes::from("v1,v2\n1,\"a,b\"\n,c\n")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_parquet() {
+        let store = InMemObjectStore::new(false);
+        let mut writer = PartWriter::new(&store, "part.parquet", UnloadFormat::Parquet, schema())
+            .await
+            .unwrap();
+        for _ in 0..2 {
+            writer
+                .write(&DataChunk::from_pretty(
+                    "i T
+                     1 a
+                     2 b",
+                ))
+                .await
+                .unwrap();
+        }
+        assert_eq!(writer.finish().await.unwrap(), 4);
+
+        let data = store.read("part.parquet", None).await.unwrap();
+        let rows: usize = ParquetRecordBatchReaderBuilder::try_new(data)
+            .unwrap()
+            .build()
+            .unwrap()
+            .map(|batch| batch.unwrap().num_rows())
+            .sum();
+        assert_eq!(rows, 4);
+    }
+}
@@ -41,6 +41,7 @@ pub mod datagen;
 mod filesystem;
 pub mod kafka;
 pub mod kinesis;
+pub mod lake;
 mod nexmark;
 mod pulsar;
 
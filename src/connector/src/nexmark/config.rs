@@ -58,6 +58,8 @@ pub struct NexmarkConfig {
     pub hot_auction_ratio: usize,
     /// Ratio of bids for 'hot' bidders compared to all other people.
     pub hot_bidder_ratio: usize,
+    /// Ratio of bids from 'hot' channels compared to all other channels.
+    pub hot_channel_ratio: usize,
     /// Event id of first event to be generated.
     /// Event ids are unique over all generators, and are used as a seed to
     /// generate each event's data.
@@ -134,6 +136,12 @@ impl NexmarkConfig {
         let hot_seller_ratio = properties.hot_seller_ratio.unwrap_or(4);
         let hot_auction_ratio = properties.hot_auction_ratio.unwrap_or(2);
         let hot_bidder_ratio = properties.hot_bidder_ratio.unwrap_or(4);
+        let hot_channel_ratio = properties.hot_channel_ratio.unwrap_or(10);
+        // A bid is on a hot channel with a probability of `1 - 1 / hot_channel_ratio`.
+        anyhow::ensure!(
+            hot_channel_ratio > 0,
+            "nexmark.hot.channel.ratio must be positive"
+        );
         let first_event_id = properties.hot_first_event_id.unwrap_or(0);
         let first_event_number = properties.first_event_number.unwrap_or(0);
         let num_categories = properties.num_categories.unwrap_or(5);
@@ -224,6 +232,7 @@ impl NexmarkConfig {
             hot_seller_ratio,
             hot_auction_ratio,
             hot_bidder_ratio,
+            hot_channel_ratio,
             first_event_id,
             first_event_number,
             base_time,
@@ -310,4 +319,13 @@ mod tests {
         assert_eq!(config.active_people, 1000);
         Ok(())
     }
+
+    #[test]
+    fn test_zero_hot_channel_ratio() {
+        let properties = NexmarkProperties {
+            hot_channel_ratio: Some(0),
+            ..Default::default()
+        };
+        assert!(NexmarkConfig::from(properties).is_err());
+    }
 }
@@ -66,6 +66,8 @@ pub struct NexmarkProperties {
     pub hot_auction_ratio: Option<usize>,
     #[serde(rename = "nexmark.hot.bidder.ratio")]
     pub hot_bidder_ratio: Option<usize>,
+    #[serde(rename = "nexmark.hot.channel.ratio")]
+    pub hot_channel_ratio: Option<usize>,
     #[serde(rename = "nexmark.first.event.id")]
     pub hot_first_event_id: Option<usize>,
     #[serde(rename = "nexmark.first.event.number")]
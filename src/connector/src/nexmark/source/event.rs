@@ -40,6 +40,16 @@ use crate::nexmark::config::NexmarkConfig;
 
 const MIN_STRING_LENGTH: usize = 3;
 
+/// Number of distinct channels other than the hot ones.
+const CHANNEL_NUMBER: usize = 10_000;
+const HOT_CHANNELS: [&str; 4] = ["Google", "Facebook", "Baidu", "Apple"];
+const HOT_URLS: [&str; 4] = [
+    "https://www.nexmark.com/googl/item.htm?query=1",
+    "https://www.nexmark.com/faceb/item.htm?query=1",
+    "https://www.nexmark.com/baidu/item.htm?query=1",
+    "https://www.nexmark.com/apple/item.htm?query=1",
+];
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum EventType {
     Person,
@@ -296,6 +306,11 @@ pub struct Bid {
     pub bidder: Id,
     /// The price in cents that the person bid for.
     pub price: usize,
+    /// The channel that the bid is placed through, e.g. a search engine.
+    pub channel: String,
+    /// The url of the page that the bid is placed on. For channels other than the hot ones, the
+    /// channel id is encoded in the `channel_id` parameter.
+    pub url: String,
     /// A millisecond timestamp for the event origin.
     pub b_date_time: String,
 }
@@ -312,10 +327,28 @@ impl Bid {
         } else {
             Person::next_id(id, rng, nex)
         };
+        let price = rng.gen_price();
+        let (channel, url) = if 0 < rng.gen_range(0..nex.hot_channel_ratio) {
+            let index = rng.gen_range(0..HOT_CHANNELS.len());
+            (HOT_CHANNELS[index].to_string(), HOT_URLS[index].to_string())
+        } else {
+            let channel_id = rng.gen_range(0..CHANNEL_NUMBER);
+            (
+                format!("channel-{}", channel_id),
+                format!(
+                    "https://www.nexmark.com/{}/{}/item.htm?query=1&channel_id={}",
+                    rng.gen_string(5).replace(' ', "_"),
+                    rng.gen_string(5).replace(' ', "_"),
+                    channel_id
+                ),
+            )
+        };
         Bid {
             auction: auction + nex.first_auction_id,
             bidder: bidder + nex.first_person_id,
-            price: rng.gen_price(),
+            price,
+            channel,
+            url,
             b_date_time: milli_ts_to_timestamp_string(time),
         }
     }
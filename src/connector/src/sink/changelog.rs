@@ -0,0 +1,85 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The changelog output format, where every change is emitted as an appended row carrying its
+//! operation and commit epoch, rather than applied as an upsert.
+
+use std::sync::Arc;
+
+use itertools::Itertools;
+use risingwave_common::array::column::Column;
+use risingwave_common::array::{ArrayResult, DataChunk, I64Array, Op, StreamChunk, Utf8Array};
+use risingwave_common::catalog::{Field, Schema};
+use risingwave_common::types::DataType;
+
+pub const CHANGELOG_OP_COLUMN: &str = "_op";
+pub const CHANGELOG_EPOCH_COLUMN: &str = "_epoch";
+
+pub fn op_name(op: Op) -> &'static str {
+    match op {
+        Op::Insert => "insert",
+        Op::Delete => "delete",
+        Op::UpdateDelete => "update_before",
+        Op::UpdateInsert => "update_after",
+    }
+}
+
+/// The schema of the changelog of `schema`, with the op and epoch columns appended.
+pub fn changelog_schema(schema: &Schema) -> Schema {
+    let mut fields = schema.fields().to_vec();
+    fields.push(Field::with_name(DataType::Varchar, CHANGELOG_OP_COLUMN));
+    fields.push(Field::with_name(DataType::Int64, CHANGELOG_EPOCH_COLUMN));
+    Schema::new(fields)
+}
+
+/// Converts the visible changes of `chunk` committed in `epoch` to rows of [`changelog_schema`].
+pub fn to_changelog_chunk(chunk: StreamChunk, epoch: u64) -> ArrayResult<DataChunk> {
+    let (data, ops) = chunk.compact()?.into_parts();
+    let cardinality = data.cardinality();
+    let op_column = Utf8Array::from_slice(&ops.iter().map(|op| Some(op_name(*op))).collect_vec())?;
+    let epoch_column = I64Array::from_slice(&vec![Some(epoch as i64); cardinality])?;
+    let (mut columns, _) = data.into_parts();
+    columns.push(Column::new(Arc::new(op_column.into())));
+    columns.push(Column::new(Arc::new(epoch_column.into())));
+    Ok(DataChunk::new(columns, cardinality))
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::{DataChunkTestExt, StreamChunkTestExt};
+
+    use super::*;
+
+    #[test]
+    fn test_to_changelog_chunk() {
+        let chunk = StreamChunk::from_pretty(
+            "  i
+            +  1
+            U- 2
+            U+ 3
+            -  4 D
+            -  5",
+        );
+        assert_eq!(
+            to_changelog_chunk(chunk, 7).unwrap(),
+            DataChunk::from_pretty(
+                "i T             I
+                 1 insert        7
+                 2 update_before 7
+                 3 update_after  7
+                 5 delete        7"
+            )
+        );
+    }
+}
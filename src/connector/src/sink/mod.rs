@@ -12,39 +12,58 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod changelog;
 pub mod mysql;
+pub mod parquet;
 pub mod redis;
 
+use std::collections::HashMap;
+
 use async_trait::async_trait;
-use risingwave_common::array::StreamChunk;
+use risingwave_common::array::{ArrayError, StreamChunk};
 use risingwave_common::catalog::Schema;
 use risingwave_common::error::{ErrorCode, RwError};
+use risingwave_object_store::object::ObjectError;
 use thiserror::Error;
 
 use crate::sink::mysql::{MySQLConfig, MySQLSink};
+use crate::sink::parquet::{ParquetConfig, ParquetSink};
 use crate::sink::redis::{RedisConfig, RedisSink};
 
 #[async_trait]
 pub trait Sink {
     async fn write_batch(&mut self, chunk: StreamChunk, schema: &Schema) -> Result<()>;
+
+    /// Called before the chunks of `epoch` are written.
+    async fn begin_epoch(&mut self, _epoch: u64) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called once all chunks of `epoch` have been written, when its checkpoint is taken.
+    async fn commit(&mut self, _epoch: u64, _schema: &Schema) -> Result<()> {
+        Ok(())
+    }
 }
 
 pub enum SinkConfig {
     Mysql(MySQLConfig),
     Redis(RedisConfig),
+    Parquet(ParquetConfig),
 }
 
 pub enum SinkImpl {
     MySQL(MySQLSink),
     Redis(RedisSink),
+    Parquet(ParquetSink),
 }
 
 impl SinkImpl {
-    fn new(cfg: SinkConfig) -> Self {
-        match cfg {
+    async fn new(cfg: SinkConfig) -> Result<Self> {
+        Ok(match cfg {
             SinkConfig::Mysql(cfg) => SinkImpl::MySQL(MySQLSink::new(cfg)),
             SinkConfig::Redis(cfg) => SinkImpl::Redis(RedisSink::new(cfg)),
-        }
+            SinkConfig::Parquet(cfg) => SinkImpl::Parquet(ParquetSink::new(cfg).await?),
+        })
     }
 }
 
@@ -54,6 +73,46 @@ impl Sink for SinkImpl {
         match self {
             SinkImpl::MySQL(sink) => sink.write_batch(chunk, schema).await,
             SinkImpl::Redis(sink) => sink.write_batch(chunk, schema).await,
+            SinkImpl::Parquet(sink) => sink.write_batch(chunk, schema).await,
+        }
+    }
+
+    async fn begin_epoch(&mut self, epoch: u64) -> Result<()> {
+        match self {
+            SinkImpl::MySQL(sink) => sink.begin_epoch(epoch).await,
+            SinkImpl::Redis(sink) => sink.begin_epoch(epoch).await,
+            SinkImpl::Parquet(sink) => sink.begin_epoch(epoch).await,
+        }
+    }
+
+    async fn commit(&mut self, epoch: u64, schema: &Schema) -> Result<()> {
+        match self {
+            SinkImpl::MySQL(sink) => sink.commit(epoch, schema).await,
+            SinkImpl::Redis(sink) => sink.commit(epoch, schema).await,
+            SinkImpl::Parquet(sink) => sink.commit(epoch, schema).await,
+        }
+    }
+}
+
+/// How the changes of the input are written to a sink.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SinkFormat {
+    /// Only inserts are accepted.
+    AppendOnly,
+    /// Every change is appended with its operation and commit epoch. See [`changelog`].
+    Changelog,
+}
+
+impl SinkFormat {
+    /// Parses the `format` property.
+    pub fn from_properties(properties: &HashMap<String, String>) -> Result<Self> {
+        match properties.get("format").map(|s| s.as_str()) {
+            Some("append_only") | None => Ok(SinkFormat::AppendOnly),
+            Some("changelog") => Ok(SinkFormat::Changelog),
+            Some(other) => Err(SinkError::Config(format!(
+                "invalid format: {}, expected append_only or changelog",
+                other
+            ))),
         }
     }
 }
@@ -64,6 +123,16 @@ pub type Result<T> = std::result::Result<T, SinkError>;
 pub enum SinkError {
     #[error(transparent)]
     MySQL(#[from] mysql_async::Error),
+    #[error(transparent)]
+    Parquet(#[from] ::parquet::errors::ParquetError),
+    #[error(transparent)]
+    ObjectStore(#[from] ObjectError),
+    #[error(transparent)]
+    Array(#[from] ArrayError),
+    #[error("invalid sink config: {0}")]
+    Config(String),
+    #[error(transparent)]
+    Internal(#[from] anyhow::Error),
 }
 
 impl From<SinkError> for RwError {
@@ -91,6 +91,7 @@ impl TryFrom<Datum> for MySQLValue {
                 ScalarImpl::NaiveDate(v) => Ok(MySQLValue(format!("{}", v).into())),
                 ScalarImpl::NaiveTime(v) => Ok(MySQLValue(format!("{}", v).into())),
                 ScalarImpl::NaiveDateTime(v) => Ok(MySQLValue(format!("{}", v).into())),
+                ScalarImpl::Uuid(v) => Ok(MySQLValue(format!("{}", v).into())),
                 // ScalarImpl::Interval(v) => Ok(MySQLValue(Value::NULL)),
                 _ => unimplemented!(),
             }
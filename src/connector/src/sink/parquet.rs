@@ -0,0 +1,378 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use bytes::Bytes;
+use itertools::Itertools;
+use parquet::arrow::ArrowWriter;
+use risingwave_common::array::{DataChunk, Op, Row, StreamChunk};
+use risingwave_common::catalog::Schema;
+use risingwave_common::types::ScalarRefImpl;
+use risingwave_object_store::object::ObjectStore;
+
+use crate::lake::LakeLocation;
+use crate::sink::changelog::{changelog_schema, to_changelog_chunk};
+use crate::sink::{Result, Sink, SinkError, SinkFormat};
+
+pub const PARQUET_SINK: &str = "parquet";
+
+/// The directory of rows whose partition column is null, following Hive.
+const DEFAULT_PARTITION: &str = "__HIVE_DEFAULT_PARTITION__";
+const SUCCESS_MARKER: &str = "_SUCCESS";
+const MANIFEST_DIR: &str = "_manifests";
+const DEFAULT_MAX_ROWS_PER_FILE: usize = 100_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PartitionGranularity {
+    Day,
+    Hour,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParquetConfig {
+    /// `s3://bucket/path` or `disk:///path`.
+    pub location: String,
+    /// The `TIMESTAMP` or `DATE` column the files are partitioned by.
+    pub partition_column: String,
+    pub granularity: PartitionGranularity,
+    /// A file is rolled once it has this many rows, even if the epoch hasn't been committed.
+    pub max_rows_per_file: usize,
+    pub format: SinkFormat,
+}
+
+impl ParquetConfig {
+    pub fn from_properties(properties: &HashMap<String, String>) -> Result<Self> {
+        let get = |key: &str| {
+            properties
+                .get(key)
+                .cloned()
+                .ok_or_else(|| SinkError::Config(format!("{} is required", key)))
+        };
+        let granularity = match properties.get("partition.granularity").map(|s| s.as_str()) {
+            Some("day") => PartitionGranularity::Day,
+            Some("hour") | None => PartitionGranularity::Hour,
+            Some(other) => {
+                return Err(SinkError::Config(format!(
+                    "invalid partition.granularity: {}, expected day or hour",
+                    other
+                )))
+            }
+        };
+        let max_rows_per_file = match properties.get("file.max_rows") {
+            Some(rows) => rows
+                .parse()
+                .ok()
+                .filter(|rows| *rows > 0)
+                .ok_or_else(|| SinkError::Config(format!("invalid file.max_rows: {}", rows)))?,
+            None => DEFAULT_MAX_ROWS_PER_FILE,
+        };
+        Ok(Self {
+            location: get("location")?,
+            partition_column: get("partition.column")?,
+            granularity,
+            max_rows_per_file,
+            format: SinkFormat::from_properties(properties)?,
+        })
+    }
+}
+
+/// `ParquetSink` writes append-only input as Parquet files in Hive-style directories by the hour
+/// or day of a time column, e.g. `dt=2022-07-01/hour=13/part-<epoch>-<seq>.parquet`. In the
+/// changelog format, any input is accepted and written with its op and epoch columns.
+///
+/// Files only become visible on commit: the files of an epoch are uploaded when its checkpoint is
+/// committed, followed by a manifest of the epoch under `_manifests/` and a `_SUCCESS` marker in
+/// each partition written. Since file names are derived from the epoch, re-committing an epoch
+/// after recovery overwrites its files instead of duplicating them.
+pub struct ParquetSink {
+    cfg: ParquetConfig,
+    prefix: String,
+    store: Box<dyn ObjectStore>,
+    /// Rows of the current epoch not yet rolled into a file, by partition directory.
+    buffers: BTreeMap<String, Vec<Row>>,
+    /// Files rolled in the current epoch, as `(partition directory, content)`.
+    rolled: Vec<(String, Bytes)>,
+    epoch: u64,
+}
+
+impl ParquetSink {
+    pub async fn new(cfg: ParquetConfig) -> Result<Self> {
+        let location = LakeLocation::parse(&cfg.location).map_err(SinkError::Internal)?;
+        let store = location.connect().await;
+        Ok(Self {
+            prefix: location.prefix().to_string(),
+            cfg,
+            store,
+            buffers: BTreeMap::new(),
+            rolled: vec![],
+            epoch: 0,
+        })
+    }
+
+    #[cfg(test)]
+    fn with_store(cfg: ParquetConfig, store: Box<dyn ObjectStore>) -> Self {
+        Self {
+            prefix: String::new(),
+            cfg,
+            store,
+            buffers: BTreeMap::new(),
+            rolled: vec![],
+            epoch: 0,
+        }
+    }
+
+    /// The schema of the written files.
+    fn file_schema(&self, schema: &Schema) -> Schema {
+        match self.cfg.format {
+            SinkFormat::AppendOnly => schema.clone(),
+            SinkFormat::Changelog => changelog_schema(schema),
+        }
+    }
+
+    fn partition_dir(&self, datum: Option<ScalarRefImpl<'_>>) -> Result<String> {
+        let (date, hour) = match datum {
+            None => return Ok(format!("dt={}/", DEFAULT_PARTITION)),
+            Some(ScalarRefImpl::NaiveDateTime(ts)) => (ts.0.date(), ts.0.format("%H").to_string()),
+            Some(ScalarRefImpl::NaiveDate(date))
+                if self.cfg.granularity == PartitionGranularity::Day =>
+            {
+                (date.0, String::new())
+            }
+            Some(other) => {
+                return Err(SinkError::Config(format!(
+                    "cannot partition by {} of {:?}",
+                    self.cfg.partition_column, other
+                )))
+            }
+        };
+        Ok(match self.cfg.granularity {
+            PartitionGranularity::Day => format!("dt={}/", date.format("%Y-%m-%d")),
+            PartitionGranularity::Hour => {
+                format!("dt={}/hour={}/", date.format("%Y-%m-%d"), hour)
+            }
+        })
+    }
+
+    /// Encodes `rows` of the file schema into a file.
+    fn roll(&mut self, partition: String, rows: Vec<Row>, schema: &Schema) -> Result<()> {
+        let chunk = DataChunk::from_rows(&rows, &schema.data_types())?;
+        let batch = chunk.to_record_batch(schema)?;
+        let mut buf = vec![];
+        let mut writer = ArrowWriter::try_new(&mut buf, batch.schema(), None)?;
+        writer.write(&batch)?;
+        writer.close()?;
+        self.rolled.push((partition, Bytes::from(buf)));
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Sink for ParquetSink {
+    async fn write_batch(&mut self, chunk: StreamChunk, schema: &Schema) -> Result<()> {
+        let (chunk, schema) = match self.cfg.format {
+            SinkFormat::AppendOnly => (chunk, schema.clone()),
+            SinkFormat::Changelog => {
+                let data = to_changelog_chunk(chunk, self.epoch)?;
+                let ops = vec![Op::Insert; data.cardinality()];
+                (StreamChunk::from_parts(ops, data), changelog_schema(schema))
+            }
+        };
+        let partition_idx = schema
+            .fields()
+            .iter()
+            .position(|field| field.name == self.cfg.partition_column)
+            .ok_or_else(|| {
+                SinkError::Config(format!(
+                    "partition column {} not found",
+                    self.cfg.partition_column
+                ))
+            })?;
+        let (data, ops) = chunk.compact()?.into_parts();
+        for (row, op) in data.rows().zip_eq(ops) {
+            if op != Op::Insert {
+                return Err(SinkError::Internal(anyhow!(
+                    "parquet sink only accepts append-only input"
+                )));
+            }
+            let partition = self.partition_dir(row.value_at(partition_idx))?;
+            let buffer = self.buffers.entry(partition.clone()).or_default();
+            buffer.push(row.to_owned_row());
+            if buffer.len() >= self.cfg.max_rows_per_file {
+                let rows = std::mem::take(buffer);
+                self.roll(partition, rows, &schema)?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn begin_epoch(&mut self, epoch: u64) -> Result<()> {
+        self.epoch = epoch;
+        Ok(())
+    }
+
+    async fn commit(&mut self, epoch: u64, schema: &Schema) -> Result<()> {
+        let schema = self.file_schema(schema);
+        for (partition, rows) in std::mem::take(&mut self.buffers) {
+            if !rows.is_empty() {
+                self.roll(partition, rows, &schema)?;
+            }
+        }
+        if self.rolled.is_empty() {
+            return Ok(());
+        }
+
+        let mut paths = vec![];
+        for (seq, (partition, data)) in std::mem::take(&mut self.rolled).into_iter().enumerate() {
+            let path = format!(
+                "{}{}part-{:020}-{:05}.parquet",
+                self.prefix, partition, epoch, seq
+            );
+            self.store.upload(&path, data).await?;
+            paths.push((partition, path));
+        }
+
+        let manifest = serde_json::json!({
+            "epoch": epoch,
+            "files": paths.iter().map(|(_, path)| path).collect_vec(),
+        });
+        self.store
+            .upload(
+                &format!("{}{}/{:020}.json", self.prefix, MANIFEST_DIR, epoch),
+                Bytes::from(manifest.to_string()),
+            )
+            .await?;
+        for partition in paths.iter().map(|(partition, _)| partition).dedup() {
+            self.store
+                .upload(
+                    &format!("{}{}{}", self.prefix, partition, SUCCESS_MARKER),
+                    Bytes::new(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::{DataChunkTestExt, StreamChunkTestExt};
+    use risingwave_common::catalog::Field;
+    use risingwave_common::types::DataType;
+    use risingwave_object_store::object::InMemObjectStore;
+
+    use super::*;
+    use crate::lake::{list_files, read_file};
+
+    #[tokio::test]
+    async fn test_hourly_partitions() {
+        let cfg = ParquetConfig::from_properties(
+            &[
+                ("location", "s3://bucket/events"),
+                ("partition.column", "ts"),
+                ("file.max_rows", "2"),
+            ]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect(),
+        )
+        .unwrap();
+        assert_eq!(cfg.granularity, PartitionGranularity::Hour);
+
+        let store = InMemObjectStore::new(false);
+        let mut sink = ParquetSink::with_store(cfg, Box::new(store.clone()));
+        let schema = Schema::new(vec![
+            Field::with_name(DataType::Int32, "v"),
+            Field::with_name(DataType::Timestamp, "ts"),
+        ]);
+        let chunk = StreamChunk::from_pretty(
+            " i  TS
+            + 1 2022-07-01T12:10:00
+            + 2 2022-07-01T12:20:00
+            + 3 2022-07-01T12:30:00
+            + 4 2022-07-01T13:00:00",
+        );
+        sink.write_batch(chunk, &schema).await.unwrap();
+        assert_eq!(sink.rolled.len(), 1);
+        // Nothing is visible before the epoch is committed.
+        assert!(store.list("").await.unwrap().is_empty());
+
+        sink.commit(1, &schema).await.unwrap();
+        assert_eq!(
+            store.list("").await.unwrap(),
+            vec![
+                "_manifests/00000000000000000001.json",
+                "dt=2022-07-01/hour=12/_SUCCESS",
+                "dt=2022-07-01/hour=12/part-00000000000000000001-00000.parquet",
+                "dt=2022-07-01/hour=12/part-00000000000000000001-00001.parquet",
+                "dt=2022-07-01/hour=13/_SUCCESS",
+                "dt=2022-07-01/hour=13/part-00000000000000000001-00002.parquet",
+            ]
+        );
+
+        let files = list_files(&store, "").await.unwrap();
+        let mut rows = 0;
+        for file in &files {
+            let chunks = read_file(&store, file, &schema, 1024).await.unwrap();
+            rows += chunks.iter().map(|c| c.cardinality()).sum::<usize>();
+        }
+        assert_eq!(rows, 4);
+
+        let delete = StreamChunk::from_pretty(
+            " i  TS
+            - 1 2022-07-01T12:10:00",
+        );
+        assert!(sink.write_batch(delete, &schema).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_changelog() {
+        let cfg = ParquetConfig {
+            location: "s3://bucket/events".to_string(),
+            partition_column: "ts".to_string(),
+            granularity: PartitionGranularity::Day,
+            max_rows_per_file: DEFAULT_MAX_ROWS_PER_FILE,
+            format: SinkFormat::Changelog,
+        };
+        let store = InMemObjectStore::new(false);
+        let mut sink = ParquetSink::with_store(cfg, Box::new(store.clone()));
+        let schema = Schema::new(vec![
+            Field::with_name(DataType::Int32, "v"),
+            Field::with_name(DataType::Timestamp, "ts"),
+        ]);
+        sink.begin_epoch(5).await.unwrap();
+        let chunk = StreamChunk::from_pretty(
+            "  i TS
+            U- 1 2022-07-01T12:10:00
+            U+ 2 2022-07-01T12:10:00",
+        );
+        sink.write_batch(chunk, &schema).await.unwrap();
+        sink.commit(5, &schema).await.unwrap();
+
+        let files = list_files(&store, "").await.unwrap();
+        let chunks = read_file(&store, &files[0], &changelog_schema(&schema), 1024)
+            .await
+            .unwrap();
+        assert_eq!(
+            chunks[0],
+            DataChunk::from_pretty(
+                "i TS                  T             I
+                 1 2022-07-01T12:10:00 update_before 5
+                 2 2022-07-01T12:10:00 update_after  5"
+            )
+        );
+    }
+}
@@ -13,5 +13,7 @@
 // limitations under the License.
 
 pub mod bench;
+pub mod catalog;
 pub mod hummock;
+pub mod stream;
 pub mod table;
@@ -0,0 +1,39 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::Result;
+use risingwave_common::catalog::DEFAULT_DATABASE_NAME;
+use risingwave_frontend::catalog::dump::dump_catalog;
+
+use crate::common::MetaServiceOpts;
+
+/// Dumps the DDL to recreate the logical schema of `database` on another cluster to `output`, or
+/// to stdout if not specified. Defaults to the default database.
+pub async fn dump(database: Option<String>, output: Option<String>) -> Result<()> {
+    let database = database.unwrap_or_else(|| DEFAULT_DATABASE_NAME.to_string());
+    let meta = MetaServiceOpts::from_env()?.create_meta_client().await?;
+    let snapshot = meta.get_catalog().await?;
+    let ddl = dump_catalog(&snapshot, &database)?
+        .into_iter()
+        .map(|statement| format!("{};\n", statement))
+        .collect::<String>();
+    match output {
+        Some(path) => {
+            tokio::fs::write(&path, ddl).await?;
+            println!("dumped the catalog of \"{}\" to {}", database, path);
+        }
+        None => print!("{}", ddl),
+    }
+    Ok(())
+}
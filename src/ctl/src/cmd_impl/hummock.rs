@@ -20,3 +20,7 @@ mod sst_dump;
 pub use sst_dump::*;
 mod trigger_manual_compaction;
 pub use trigger_manual_compaction::*;
+mod migrate;
+pub use migrate::*;
+mod failover;
+pub use failover::*;
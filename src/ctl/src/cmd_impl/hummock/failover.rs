@@ -0,0 +1,44 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use risingwave_meta::hummock::replication::read_latest_replicated_version;
+use risingwave_object_store::object::object_metrics::ObjectStoreMetrics;
+use risingwave_object_store::object::{parse_object_store, ObjectStoreImpl};
+
+use crate::common::MetaServiceOpts;
+
+/// Resets the Hummock version on meta node to the latest checkpoint replicated to `replica`. All
+/// compute nodes, compactors and frontends must be stopped beforehand, and restarted with the
+/// replica as their state store afterwards.
+pub async fn failover(replica: String, data_directory: String) -> anyhow::Result<()> {
+    let replica = ObjectStoreImpl::new(
+        parse_object_store(&replica, false).await,
+        Arc::new(ObjectStoreMetrics::unused()),
+    );
+    let version = read_latest_replicated_version(&replica, &data_directory)
+        .await?
+        .ok_or_else(|| anyhow!("no checkpoint has been replicated to the object store"))?;
+    println!(
+        "Latest replicated checkpoint: version {}, epoch {}",
+        version.id, version.max_committed_epoch
+    );
+
+    let meta_client = MetaServiceOpts::from_env()?.create_meta_client().await?;
+    let version = meta_client.reset_current_version(version).await?;
+    println!("Reset the current version to version {}", version.id);
+    Ok(())
+}
@@ -0,0 +1,74 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_rpc_client::HummockMetaClient;
+use risingwave_storage::hummock::VERSION;
+use risingwave_storage::monitor::StoreLocalStatistic;
+
+use crate::common::HummockServiceOpts;
+
+/// Rewrites SSTs of older format versions in the current version, by triggering manual compaction
+/// on the levels containing them. Compaction always writes SSTs of the current version, so the
+/// command should be re-run until no outdated SST is reported.
+pub async fn migrate(dry_run: bool) -> anyhow::Result<()> {
+    let hummock_opts = HummockServiceOpts::from_env()?;
+    let (meta_client, hummock) = hummock_opts.create_hummock_store().await?;
+    let sstable_store = &*hummock.inner().sstable_store();
+
+    let version = meta_client.pin_version(u64::MAX).await?;
+    let mut outdated_levels = vec![];
+    for (compaction_group_id, levels) in &version.levels {
+        for level in &levels.levels {
+            let mut outdated_count = 0;
+            for sstable_info in &level.table_infos {
+                let sstable = sstable_store
+                    .sstable(sstable_info.id, &mut StoreLocalStatistic::default())
+                    .await?;
+                if sstable.value().meta.version < VERSION {
+                    outdated_count += 1;
+                }
+            }
+            if outdated_count > 0 {
+                println!(
+                    "Compaction group {}, level {}: {} of {} SSTs are of older format versions",
+                    compaction_group_id,
+                    level.level_idx,
+                    outdated_count,
+                    level.table_infos.len()
+                );
+                outdated_levels.push((*compaction_group_id, level.level_idx));
+            }
+        }
+    }
+    meta_client.unpin_version(&[version.id]).await?;
+
+    if outdated_levels.is_empty() {
+        println!("All SSTs are of the current format version {}", VERSION);
+        return Ok(());
+    }
+    if dry_run {
+        return Ok(());
+    }
+    for (compaction_group_id, level) in outdated_levels {
+        // Table id 0 makes the compaction include all SSTs of the level.
+        meta_client
+            .trigger_manual_compaction(compaction_group_id, 0, level)
+            .await?;
+        println!(
+            "Triggered compaction of compaction group {}, level {}",
+            compaction_group_id, level
+        );
+    }
+    Ok(())
+}
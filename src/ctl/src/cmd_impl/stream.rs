@@ -0,0 +1,70 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{anyhow, Result};
+use prost::Message;
+use risingwave_common::catalog::TableId;
+use risingwave_connector::state::SourceStateHandler;
+use risingwave_connector::SplitImpl;
+use risingwave_pb::ddl_service::StreamingJobSnapshot;
+use risingwave_pb::source::ConnectorSplit;
+use risingwave_storage::Keyspace;
+
+use crate::common::{HummockServiceOpts, MetaServiceOpts};
+
+/// Exports the materialized view `mv_name` to `path`, with its sources resuming from the offsets
+/// persisted in the state store.
+pub async fn export(mv_name: String, path: String) -> Result<()> {
+    let hummock_opts = HummockServiceOpts::from_env()?;
+    let (meta, hummock) = hummock_opts.create_hummock_store().await?;
+    let mv = meta
+        .list_materialize_view()
+        .await?
+        .into_iter()
+        .find(|table| table.name == mv_name && !table.is_internal)
+        .ok_or_else(|| anyhow!("mv not found"))?;
+    let mut snapshot = meta.export_streaming_job(TableId::new(mv.id)).await?;
+
+    // Meta only knows the splits as they were assigned, so take the offsets from the state store.
+    for (source_id, splits) in &mut snapshot.source_splits {
+        let state_handler = SourceStateHandler::new(Keyspace::table_root(
+            hummock.clone(),
+            &TableId::new(*source_id),
+        ));
+        for split in &mut splits.splits {
+            let assigned = SplitImpl::try_from(&*split)?;
+            if let Some(recovered) = state_handler
+                .try_recover_from_state_store(&assigned, u64::MAX)
+                .await?
+            {
+                *split = ConnectorSplit::from(&recovered);
+            }
+        }
+    }
+
+    tokio::fs::write(&path, snapshot.encode_to_vec()).await?;
+    println!("exported \"{}\" to {}", mv_name, path);
+    Ok(())
+}
+
+/// Imports a materialized view exported by [`export`] into the cluster.
+pub async fn import(path: String) -> Result<()> {
+    let snapshot = StreamingJobSnapshot::decode(tokio::fs::read(&path).await?.as_slice())?;
+    let meta_opts = MetaServiceOpts::from_env()?;
+    let meta = meta_opts.create_meta_client().await?;
+    let name = snapshot.materialized_view.as_ref().unwrap().name.clone();
+    let (table_id, _) = meta.import_streaming_job(snapshot).await?;
+    println!("imported \"{}\" as table {}", name, table_id);
+    Ok(())
+}
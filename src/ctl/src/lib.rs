@@ -41,9 +41,22 @@ enum Commands {
     /// Commands for Tables
     #[clap(subcommand)]
     Table(TableCommands),
+    /// Commands for Streaming Jobs
+    #[clap(subcommand)]
+    Stream(StreamCommands),
     /// Commands for Benchmarks
     #[clap(subcommand)]
     Bench(BenchCommands),
+    /// dump the DDL to recreate the logical schema of a database on another cluster, with the
+    /// secrets redacted
+    DumpCatalog {
+        /// name of the database to dump, the default database if not specified
+        #[clap(short, long = "database")]
+        database: Option<String>,
+        /// file to write the DDL to, stdout if not specified
+        #[clap(short, long = "output")]
+        output: Option<String>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -70,6 +83,21 @@ enum HummockCommands {
         #[clap(short, long = "level", default_value_t = 1)]
         level: u32,
     },
+    /// rewrite SSTs of older format versions into the current format version
+    Migrate {
+        /// only report SSTs of older format versions
+        #[clap(long = "dry-run")]
+        dry_run: bool,
+    },
+    /// reset the Hummock version on meta node to the latest checkpoint replicated to another
+    /// object store, so that the cluster can be restarted on that object store
+    Failover {
+        /// url of the object store that checkpoints are replicated to, e.g. `s3://backup-bucket`
+        replica: String,
+        /// directory of the SSTs in the object store
+        #[clap(long = "data-directory", default_value = "hummock_001")]
+        data_directory: String,
+    },
 }
 
 #[derive(Subcommand)]
@@ -81,6 +109,22 @@ enum TableCommands {
     },
 }
 
+#[derive(Subcommand)]
+enum StreamCommands {
+    /// export the full definition of a materialized view, to be imported into another cluster
+    Export {
+        /// name of the materialized view to export
+        mv_name: String,
+        /// file to write the definition to
+        path: String,
+    },
+    /// import a materialized view exported from another cluster
+    Import {
+        /// file to read the definition from
+        path: String,
+    },
+}
+
 pub async fn start(opts: CliOpts) -> Result<()> {
     match opts.command {
         Commands::Hummock(HummockCommands::ListVersion) => {
@@ -102,10 +146,26 @@ pub async fn start(opts: CliOpts) -> Result<()> {
             ))
             .await??
         }
+        Commands::Hummock(HummockCommands::Migrate { dry_run }) => {
+            tokio::spawn(cmd_impl::hummock::migrate(dry_run)).await??
+        }
+        Commands::Hummock(HummockCommands::Failover {
+            replica,
+            data_directory,
+        }) => tokio::spawn(cmd_impl::hummock::failover(replica, data_directory)).await??,
         Commands::Table(TableCommands::Scan { mv_name }) => {
             tokio::spawn(cmd_impl::table::scan(mv_name)).await??
         }
+        Commands::Stream(StreamCommands::Export { mv_name, path }) => {
+            tokio::spawn(cmd_impl::stream::export(mv_name, path)).await??
+        }
+        Commands::Stream(StreamCommands::Import { path }) => {
+            tokio::spawn(cmd_impl::stream::import(path)).await??
+        }
         Commands::Bench(cmd) => tokio::spawn(cmd_impl::bench::do_bench(cmd)).await??,
+        Commands::DumpCatalog { database, output } => {
+            tokio::spawn(cmd_impl::catalog::dump(database, output)).await??
+        }
     }
     Ok(())
 }
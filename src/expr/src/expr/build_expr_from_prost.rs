@@ -12,26 +12,31 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use risingwave_common::array::DataChunk;
-use risingwave_common::types::{DataType, ToOwnedDatum};
+use risingwave_common::array::{BoolArray, DataChunk, F64Array, Utf8Array};
+use risingwave_common::types::{DataType, NaiveDateTimeWrapper, ToOwnedDatum};
 use risingwave_pb::expr::expr_node::RexNode;
 use risingwave_pb::expr::ExprNode;
 
 use crate::expr::expr_binary_bytes::{
-    new_ltrim_characters, new_repeat, new_rtrim_characters, new_substr_start, new_to_char,
-    new_trim_characters,
+    new_ltrim_characters, new_repeat, new_rtrim_characters, new_st_geohash, new_st_point,
+    new_substr_start, new_to_char, new_trim_characters,
 };
 use crate::expr::expr_binary_nonnull::{new_binary_expr, new_like_default};
 use crate::expr::expr_binary_nullable::new_nullable_binary_expr;
 use crate::expr::expr_case::{CaseExpression, WhenClause};
 use crate::expr::expr_in::InExpression;
+use crate::expr::expr_literal::LiteralExpression;
 use crate::expr::expr_ternary_bytes::{
     new_replace_expr, new_split_part_expr, new_substr_start_end, new_translate_expr,
 };
 use crate::expr::expr_unary::{
     new_length_default, new_ltrim_expr, new_rtrim_expr, new_trim_expr, new_unary_expr,
 };
-use crate::expr::{build_from_prost as expr_build_from_prost, BoxedExpression};
+use crate::expr::template::TernaryExpression;
+use crate::expr::{
+    build_from_prost as expr_build_from_prost, BoxedExpression, GenRandomUuidExpression,
+};
+use crate::vector_op::geo::st_dwithin;
 use crate::{bail, ensure, Result};
 
 fn get_children_and_return_type(prost: &ExprNode) -> Result<(Vec<ExprNode>, DataType)> {
@@ -84,6 +89,43 @@ pub fn build_repeat_expr(prost: &ExprNode) -> Result<BoxedExpression> {
     Ok(new_repeat(left_expr, right_expr, ret_type))
 }
 
+pub fn build_st_point_expr(prost: &ExprNode) -> Result<BoxedExpression> {
+    let (children, ret_type) = get_children_and_return_type(prost)?;
+    ensure!(children.len() == 2);
+    let x_expr = expr_build_from_prost(&children[0])?;
+    let y_expr = expr_build_from_prost(&children[1])?;
+    Ok(new_st_point(x_expr, y_expr, ret_type))
+}
+
+pub fn build_st_geohash_expr(prost: &ExprNode) -> Result<BoxedExpression> {
+    let (children, ret_type) = get_children_and_return_type(prost)?;
+    ensure!(children.len() == 2);
+    let geom_expr = expr_build_from_prost(&children[0])?;
+    let precision_expr = expr_build_from_prost(&children[1])?;
+    Ok(new_st_geohash(geom_expr, precision_expr, ret_type))
+}
+
+pub fn build_st_dwithin_expr(prost: &ExprNode) -> Result<BoxedExpression> {
+    let (children, ret_type) = get_children_and_return_type(prost)?;
+    ensure!(children.len() == 3);
+    let left_expr = expr_build_from_prost(&children[0])?;
+    let right_expr = expr_build_from_prost(&children[1])?;
+    let distance_expr = expr_build_from_prost(&children[2])?;
+    Ok(Box::new(TernaryExpression::<
+        Utf8Array,
+        Utf8Array,
+        F64Array,
+        BoolArray,
+        _,
+    >::new(
+        left_expr,
+        right_expr,
+        distance_expr,
+        ret_type,
+        st_dwithin,
+    )))
+}
+
 pub fn build_substr_expr(prost: &ExprNode) -> Result<BoxedExpression> {
     let (children, ret_type) = get_children_and_return_type(prost)?;
     let child = expr_build_from_prost(&children[0])?;
@@ -255,6 +297,23 @@ pub fn build_to_char_expr(prost: &ExprNode) -> Result<BoxedExpression> {
     Ok(new_to_char(data_expr, tmpl_expr, ret_type))
 }
 
+/// `NOW()` is evaluated once when the expression is built, so that it returns the same timestamp
+/// for all rows of a query.
+pub fn build_now_expr(prost: &ExprNode) -> Result<BoxedExpression> {
+    let (children, ret_type) = get_children_and_return_type(prost)?;
+    ensure!(children.is_empty());
+    ensure!(ret_type == DataType::Timestamp);
+    let now = NaiveDateTimeWrapper::new(chrono::Utc::now().naive_utc());
+    Ok(Box::new(LiteralExpression::new(ret_type, Some(now.into()))))
+}
+
+pub fn build_gen_random_uuid_expr(prost: &ExprNode) -> Result<BoxedExpression> {
+    let (children, ret_type) = get_children_and_return_type(prost)?;
+    ensure!(children.is_empty());
+    ensure!(ret_type == DataType::Uuid);
+    Ok(Box::new(GenRandomUuidExpression))
+}
+
 #[cfg(test)]
 mod tests {
     use std::vec;
@@ -472,4 +531,22 @@ mod tests {
         };
         assert!(build_binary_expr_prost(&expr).is_ok());
     }
+
+    #[test]
+    fn test_build_now_expr() {
+        let p = ExprNode {
+            expr_type: Type::Now as i32,
+            return_type: Some(ProstDataType {
+                type_name: TypeName::Timestamp as i32,
+                ..Default::default()
+            }),
+            rex_node: Some(RexNode::FuncCall(FunctionCall { children: vec![] })),
+        };
+        let expr = build_now_expr(&p).unwrap();
+        let res = expr.eval(&DataChunk::new_dummy(2)).unwrap();
+        // All rows get the same timestamp.
+        assert_eq!(res.len(), 2);
+        assert!(res.datum_at(0).is_some());
+        assert_eq!(res.datum_at(0), res.datum_at(1));
+    }
 }
@@ -215,6 +215,18 @@ macro_rules! interval {
 
 pub(crate) use interval;
 
+#[macro_export]
+macro_rules! uuid {
+    ($macro:ident) => {
+        $macro! {
+            risingwave_common::types::DataType::Uuid,
+            risingwave_common::array::UuidArray
+        }
+    };
+}
+
+pub(crate) use uuid;
+
 /// Get the type match pattern out of the type macro. e.g., `DataType::Decimal { .. }`.
 #[macro_export]
 macro_rules! type_match_pattern {
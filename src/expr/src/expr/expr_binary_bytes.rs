@@ -14,13 +14,14 @@
 
 //! For expression that only accept two arguments + 1 bytes writer as input.
 
-use risingwave_common::array::{I32Array, NaiveDateTimeArray, Utf8Array};
+use risingwave_common::array::{F64Array, I32Array, NaiveDateTimeArray, Utf8Array};
 use risingwave_common::types::DataType;
 
 use super::Expression;
 use crate::expr::template::BinaryBytesExpression;
 use crate::expr::BoxedExpression;
 use crate::vector_op::concat_op::concat_op;
+use crate::vector_op::geo::{st_geohash, st_point};
 use crate::vector_op::repeat::repeat;
 use crate::vector_op::substr::*;
 use crate::vector_op::to_char::to_char_timestamp;
@@ -79,6 +80,29 @@ pub fn new_repeat(
         .boxed()
 }
 
+pub fn new_st_point(
+    expr_ia1: BoxedExpression,
+    expr_ia2: BoxedExpression,
+    return_type: DataType,
+) -> BoxedExpression {
+    BinaryBytesExpression::<F64Array, F64Array, _>::new(expr_ia1, expr_ia2, return_type, st_point)
+        .boxed()
+}
+
+pub fn new_st_geohash(
+    expr_ia1: BoxedExpression,
+    expr_ia2: BoxedExpression,
+    return_type: DataType,
+) -> BoxedExpression {
+    BinaryBytesExpression::<Utf8Array, I32Array, _>::new(
+        expr_ia1,
+        expr_ia2,
+        return_type,
+        st_geohash,
+    )
+    .boxed()
+}
+
 macro_rules! impl_utf8_utf8 {
     ($({ $func_name:ident, $method:ident }),*) => {
         $(pub fn $func_name(
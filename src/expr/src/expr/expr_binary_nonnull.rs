@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use risingwave_common::array::{
-    Array, BoolArray, DecimalArray, I32Array, IntervalArray, ListArray, NaiveDateArray,
+    Array, BoolArray, DecimalArray, F64Array, I32Array, IntervalArray, ListArray, NaiveDateArray,
     NaiveDateTimeArray, StructArray, Utf8Array,
 };
 use risingwave_common::types::*;
@@ -26,7 +26,12 @@ use crate::for_all_cmp_variants;
 use crate::vector_op::arithmetic_op::*;
 use crate::vector_op::bitwise_op::*;
 use crate::vector_op::cmp::*;
+use crate::vector_op::distance::{
+    check_vector_dims, cosine_distance, inner_product, l2_distance, negative_inner_product,
+};
 use crate::vector_op::extract::{extract_from_date, extract_from_timestamp};
+use crate::vector_op::fulltext::ts_match;
+use crate::vector_op::geo::{st_contains, st_distance, st_distance_sphere};
 use crate::vector_op::like::like_default;
 use crate::vector_op::position::position;
 use crate::vector_op::round::round_digits;
@@ -475,6 +480,60 @@ pub fn new_binary_expr(
         )),
         Type::TumbleStart => new_tumble_start(l, r, ret),
         Type::ConcatOp => new_concat_op(l, r, ret),
+        Type::TsMatch => Box::new(BinaryExpression::<Utf8Array, Utf8Array, BoolArray, _>::new(
+            l, r, ret, ts_match,
+        )),
+        Type::L2Distance => Box::new(BinaryExpression::<ListArray, ListArray, F64Array, _>::new(
+            l,
+            r,
+            ret,
+            l2_distance,
+        )),
+        Type::CheckVectorDims => {
+            Box::new(BinaryExpression::<ListArray, I32Array, ListArray, _>::new(
+                l,
+                r,
+                ret,
+                check_vector_dims,
+            ))
+        }
+        Type::CosineDistance => Box::new(
+            BinaryExpression::<ListArray, ListArray, F64Array, _>::new(l, r, ret, cosine_distance),
+        ),
+        Type::InnerProduct => Box::new(BinaryExpression::<ListArray, ListArray, F64Array, _>::new(
+            l,
+            r,
+            ret,
+            inner_product,
+        )),
+        Type::NegativeInnerProduct => {
+            Box::new(BinaryExpression::<ListArray, ListArray, F64Array, _>::new(
+                l,
+                r,
+                ret,
+                negative_inner_product,
+            ))
+        }
+        Type::StDistance => Box::new(BinaryExpression::<Utf8Array, Utf8Array, F64Array, _>::new(
+            l,
+            r,
+            ret,
+            st_distance,
+        )),
+        Type::StDistanceSphere => {
+            Box::new(BinaryExpression::<Utf8Array, Utf8Array, F64Array, _>::new(
+                l,
+                r,
+                ret,
+                st_distance_sphere,
+            ))
+        }
+        Type::StContains => Box::new(BinaryExpression::<Utf8Array, Utf8Array, BoolArray, _>::new(
+            l,
+            r,
+            ret,
+            st_contains,
+        )),
 
         tp => {
             unimplemented!(
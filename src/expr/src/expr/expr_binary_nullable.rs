@@ -112,6 +112,7 @@ fn build_array_access_expr(
         DataType::Timestamp => array_access_expression!(NaiveDateTimeArray),
         DataType::Timestampz => array_access_expression!(PrimitiveArray::<i64>),
         DataType::Interval => array_access_expression!(IntervalArray),
+        DataType::Uuid => array_access_expression!(UuidArray),
         DataType::Struct { .. } => array_access_expression!(StructArray),
         DataType::List { .. } => array_access_expression!(ListArray),
     }
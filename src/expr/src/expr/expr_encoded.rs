@@ -0,0 +1,203 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+use std::sync::Arc;
+
+use itertools::Itertools;
+use risingwave_common::array::encoding::EncodedUtf8Array;
+use risingwave_common::array::{
+    Array, ArrayBuilder, ArrayRef, BytesGuard, BytesWriter, DataChunk, Row, Utf8Array,
+    Utf8ArrayBuilder,
+};
+use risingwave_common::types::{DataType, Datum, Scalar};
+
+use crate::expr::{BoxedExpression, Expression};
+use crate::Result;
+
+/// Evaluates a `VARCHAR -> VARCHAR` function on the dictionary or run-length encoded form of its
+/// input, so that the function is called once per distinct value or per run rather than once per
+/// row. Falls back to evaluating row by row if no encoding pays off.
+pub struct EncodedUtf8Expression<F> {
+    child: BoxedExpression,
+    return_type: DataType,
+    func: F,
+}
+
+impl<F> fmt::Debug for EncodedUtf8Expression<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EncodedUtf8Expression")
+            .field("func", &std::any::type_name::<F>())
+            .field("child", &self.child)
+            .field("return_type", &self.return_type)
+            .finish()
+    }
+}
+
+impl<F> EncodedUtf8Expression<F>
+where
+    F: Fn(&str, BytesWriter) -> Result<BytesGuard> + Send + Sync,
+{
+    pub fn new(child: BoxedExpression, return_type: DataType, func: F) -> Self {
+        Self {
+            child,
+            return_type,
+            func,
+        }
+    }
+
+    fn eval_value(&self, value: &str) -> Result<String> {
+        let builder = Utf8ArrayBuilder::new(1)?;
+        let array = (self.func)(value, builder.writer())?
+            .into_inner()
+            .finish()?;
+        Ok(array.value_at(0).map(str::to_owned).unwrap_or_default())
+    }
+
+    fn eval_rows<'a>(
+        &self,
+        values: impl Iterator<Item = Option<&'a str>>,
+        capacity: usize,
+    ) -> Result<Utf8Array> {
+        let mut builder = Utf8ArrayBuilder::new(capacity)?;
+        for value in values {
+            match value {
+                Some(value) => {
+                    let writer = builder.writer();
+                    builder = (self.func)(value, writer)?.into_inner();
+                }
+                None => builder.append(None)?,
+            }
+        }
+        Ok(builder.finish()?)
+    }
+}
+
+impl<F> Expression for EncodedUtf8Expression<F>
+where
+    F: Fn(&str, BytesWriter) -> Result<BytesGuard> + Send + Sync,
+{
+    fn return_type(&self) -> DataType {
+        self.return_type.clone()
+    }
+
+    fn eval(&self, input: &DataChunk) -> Result<ArrayRef> {
+        let child = self.child.eval(input)?;
+        let array: &Utf8Array = child.as_ref().into();
+        let output = match input.visibility() {
+            Some(visibility) => self.eval_rows(
+                array
+                    .iter()
+                    .zip_eq(visibility.iter())
+                    .filter(|(_, visible)| *visible)
+                    .map(|(value, _)| value),
+                input.cardinality(),
+            )?,
+            None => match EncodedUtf8Array::try_encode(array)? {
+                Some(encoded) => encoded.map(|value| self.eval_value(value))?.decode()?,
+                None => self.eval_rows(array.iter(), array.len())?,
+            },
+        };
+        Ok(Arc::new(output.into()))
+    }
+
+    fn eval_row(&self, input: &Row) -> Result<Datum> {
+        let datum = self.child.eval_row(input)?;
+        datum
+            .map(|value| Ok(self.eval_value(value.as_utf8())?.to_scalar_value()))
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::column::Column;
+    use risingwave_common::array::ArrayImpl;
+    use risingwave_common::buffer::Bitmap;
+
+    use super::*;
+    use crate::expr::InputRefExpression;
+    use crate::vector_op::upper::upper;
+
+    #[test]
+    fn test_encoded_upper() {
+        let expr = EncodedUtf8Expression::new(
+            Box::new(InputRefExpression::new(DataType::Varchar, 0)),
+            DataType::Varchar,
+            upper,
+        );
+
+        // Sorted, low-cardinality and distinct inputs are evaluated as run-length encoded,
+        // dictionary encoded and plain arrays respectively.
+        let inputs = [
+            vec![
+                Some("a"),
+                Some("a"),
+                Some("a"),
+                Some("a"),
+                None,
+                None,
+                None,
+                None,
+            ],
+            vec![
+                Some("a"),
+                Some("b"),
+                None,
+                Some("a"),
+                Some("b"),
+                Some("a"),
+                Some("b"),
+                Some("a"),
+            ],
+            vec![Some("a"), Some("b"), None, Some("c")],
+        ];
+        for input in inputs {
+            let array = Utf8Array::from_slice(&input).unwrap();
+            let chunk = DataChunk::new(
+                vec![Column::new(Arc::new(ArrayImpl::Utf8(array)))],
+                input.len(),
+            );
+            let expected = input.iter().map(|v| v.map(str::to_uppercase)).collect_vec();
+
+            let result = expr.eval(&chunk).unwrap();
+            assert_eq!(
+                result
+                    .as_utf8()
+                    .iter()
+                    .map(|v| v.map(str::to_owned))
+                    .collect_vec(),
+                expected
+            );
+
+            let visibility =
+                Bitmap::try_from((0..input.len()).map(|i| i % 2 == 0).collect_vec()).unwrap();
+            let result = expr.eval(&chunk.with_visibility(visibility)).unwrap();
+            assert_eq!(
+                result
+                    .as_utf8()
+                    .iter()
+                    .map(|v| v.map(str::to_owned))
+                    .collect_vec(),
+                expected.into_iter().step_by(2).collect_vec()
+            );
+        }
+
+        let row = Row::new(vec![Some("b".to_string().to_scalar_value())]);
+        assert_eq!(
+            expr.eval_row(&row).unwrap(),
+            Some("B".to_string().to_scalar_value())
+        );
+    }
+}
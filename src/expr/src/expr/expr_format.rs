@@ -0,0 +1,176 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+use risingwave_common::array::{
+    Array, ArrayBuilder, ArrayImpl, ArrayRef, DataChunk, Row, Utf8ArrayBuilder,
+};
+use risingwave_common::types::{DataType, Datum, Scalar};
+use risingwave_pb::expr::expr_node::{RexNode, Type};
+use risingwave_pb::expr::ExprNode;
+
+use crate::expr::{build_from_prost as expr_build_from_prost, BoxedExpression, Expression};
+use crate::vector_op::format::format;
+use crate::{bail, ensure, ExprError, Result};
+
+/// `FormatExpression` evaluates `format(formatstr, args...)`, where the arguments have been cast
+/// to strings.
+#[derive(Debug)]
+pub struct FormatExpression {
+    return_type: DataType,
+    fmt_expr: BoxedExpression,
+    arg_exprs: Vec<BoxedExpression>,
+}
+
+impl Expression for FormatExpression {
+    fn return_type(&self) -> DataType {
+        self.return_type.clone()
+    }
+
+    fn eval(&self, input: &DataChunk) -> Result<ArrayRef> {
+        let fmt_column = self.fmt_expr.eval(input)?;
+        let fmt_column = fmt_column.as_utf8();
+
+        let arg_columns = self
+            .arg_exprs
+            .iter()
+            .map(|c| c.eval(input))
+            .collect::<Result<Vec<_>>>()?;
+        let arg_columns_ref = arg_columns.iter().map(|c| c.as_utf8()).collect::<Vec<_>>();
+
+        let row_len = input.cardinality();
+        let mut builder = Utf8ArrayBuilder::new(row_len)?;
+
+        for row_idx in 0..row_len {
+            let fmt = match fmt_column.value_at(row_idx) {
+                Some(fmt) => fmt,
+                None => {
+                    builder.append(None)?;
+                    continue;
+                }
+            };
+            let args = arg_columns_ref
+                .iter()
+                .map(|c| c.value_at(row_idx))
+                .collect::<Vec<_>>();
+            builder.append(Some(format(fmt, &args)?.as_str()))?;
+        }
+        Ok(Arc::new(ArrayImpl::from(builder.finish()?)))
+    }
+
+    fn eval_row(&self, input: &Row) -> Result<Datum> {
+        let fmt = match self.fmt_expr.eval_row(input)? {
+            Some(fmt) => fmt,
+            None => return Ok(None),
+        };
+        let args = self
+            .arg_exprs
+            .iter()
+            .map(|c| c.eval_row(input))
+            .collect::<Result<Vec<_>>>()?;
+        let args = args
+            .iter()
+            .map(|arg| arg.as_ref().map(|arg| arg.as_utf8().as_str()))
+            .collect::<Vec<_>>();
+        Ok(Some(format(fmt.as_utf8(), &args)?.to_scalar_value()))
+    }
+}
+
+impl FormatExpression {
+    pub fn new(
+        return_type: DataType,
+        fmt_expr: BoxedExpression,
+        arg_exprs: Vec<BoxedExpression>,
+    ) -> Self {
+        FormatExpression {
+            return_type,
+            fmt_expr,
+            arg_exprs,
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a ExprNode> for FormatExpression {
+    type Error = ExprError;
+
+    fn try_from(prost: &'a ExprNode) -> Result<Self> {
+        ensure!(prost.get_expr_type().unwrap() == Type::Format);
+
+        let ret_type = DataType::from(prost.get_return_type().unwrap());
+        let RexNode::FuncCall(func_call_node) = prost.get_rex_node().unwrap() else {
+            bail!("Expected RexNode::FuncCall");
+        };
+
+        let children = &func_call_node.children;
+        ensure!(!children.is_empty());
+        let fmt_expr = expr_build_from_prost(&children[0])?;
+
+        let arg_exprs = children[1..]
+            .iter()
+            .map(expr_build_from_prost)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(FormatExpression::new(ret_type, fmt_expr, arg_exprs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::DataChunkTestExt;
+    use risingwave_pb::data::data_type::TypeName;
+    use risingwave_pb::data::DataType as ProstDataType;
+    use risingwave_pb::expr::FunctionCall;
+
+    use super::*;
+    use crate::expr::test_utils::make_input_ref;
+
+    #[test]
+    fn test_eval_format_expr() {
+        let expr = FormatExpression::try_from(&ExprNode {
+            expr_type: Type::Format as i32,
+            return_type: Some(ProstDataType {
+                type_name: TypeName::Varchar as i32,
+                ..Default::default()
+            }),
+            rex_node: Some(RexNode::FuncCall(FunctionCall {
+                children: vec![
+                    make_input_ref(0, TypeName::Varchar),
+                    make_input_ref(1, TypeName::Varchar),
+                    make_input_ref(2, TypeName::Varchar),
+                ],
+            })),
+        })
+        .unwrap();
+
+        let chunk = DataChunk::from_pretty(
+            "T     T T
+             %s-%L a .
+             .     a b",
+        );
+        let actual = expr.eval(&chunk).unwrap();
+        let actual = actual.as_utf8().iter().collect::<Vec<_>>();
+        assert_eq!(actual, vec![Some("a-NULL"), None]);
+
+        let row = Row(vec![
+            Some("%2$s%1$s".to_string().to_scalar_value()),
+            Some("a".to_string().to_scalar_value()),
+            Some("b".to_string().to_scalar_value()),
+        ]);
+        assert_eq!(
+            expr.eval_row(&row).unwrap(),
+            Some("ba".to_string().to_scalar_value())
+        );
+    }
+}
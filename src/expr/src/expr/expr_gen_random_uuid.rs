@@ -0,0 +1,63 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use risingwave_common::array::{ArrayBuilder, ArrayRef, DataChunk, Row, UuidArrayBuilder};
+use risingwave_common::types::{DataType, Datum, Uuid};
+
+use crate::expr::Expression;
+use crate::Result;
+
+/// `gen_random_uuid()` generates a new random (version 4) UUID for each row. Unlike `NOW()`, it
+/// can't be evaluated into a literal when the expression is built.
+#[derive(Debug)]
+pub struct GenRandomUuidExpression;
+
+impl Expression for GenRandomUuidExpression {
+    fn return_type(&self) -> DataType {
+        DataType::Uuid
+    }
+
+    fn eval(&self, input: &DataChunk) -> Result<ArrayRef> {
+        let cardinality = input.cardinality();
+        let mut builder = UuidArrayBuilder::new(cardinality)?;
+        for _ in 0..cardinality {
+            builder.append(Some(Uuid::new_v4()))?;
+        }
+        Ok(Arc::new(builder.finish()?.into()))
+    }
+
+    fn eval_row(&self, _input: &Row) -> Result<Datum> {
+        Ok(Some(Uuid::new_v4().into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::Array;
+
+    use super::*;
+
+    #[test]
+    fn test_gen_random_uuid() {
+        let expr = GenRandomUuidExpression;
+        let chunk = DataChunk::new_dummy(3);
+        let array = expr.eval(&chunk).unwrap();
+        let array = array.as_uuid();
+        assert_eq!(array.len(), 3);
+        assert_ne!(array.value_at(0), array.value_at(1));
+        assert!(array.iter().all(|v| v.is_some()));
+    }
+}
@@ -0,0 +1,124 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use risingwave_common::array::column::Column;
+use risingwave_common::array::{ArrayRef, DataChunk, ListValue, Row};
+use risingwave_common::types::{DataType, Datum, ScalarImpl, ToOwnedDatum};
+use risingwave_pb::expr::expr_node::Type;
+
+use crate::expr::expr_unary::new_unary_expr;
+use crate::expr::{BoxedExpression, Expression, InputRefExpression};
+use crate::Result;
+
+/// `ListCastExpression` casts a list to a list of another element type, by casting the elements of
+/// each list with the cast between the element types.
+#[derive(Debug)]
+pub struct ListCastExpression {
+    return_type: DataType,
+    child: BoxedExpression,
+    source_elem_type: DataType,
+    /// Casts the first column of a chunk of the elements of a list.
+    elem_cast: BoxedExpression,
+}
+
+impl ListCastExpression {
+    pub fn new(return_type: DataType, child: BoxedExpression) -> Result<Self> {
+        let (DataType::List { datatype: source }, DataType::List { datatype: target }) =
+            (child.return_type(), return_type.clone())
+        else {
+            unreachable!("list cast between non-list types");
+        };
+        let elem_cast = new_unary_expr(
+            Type::Cast,
+            *target,
+            Box::new(InputRefExpression::new((*source).clone(), 0)),
+        )?;
+        Ok(Self {
+            return_type,
+            child,
+            source_elem_type: *source,
+            elem_cast,
+        })
+    }
+
+    fn cast_datum(&self, datum: Datum) -> Result<Datum> {
+        let Some(ScalarImpl::List(list)) = datum else {
+            return Ok(None);
+        };
+        let values = list.values();
+        let mut builder = self.source_elem_type.create_array_builder(values.len())?;
+        for value in values {
+            builder.append_datum(value)?;
+        }
+        let elems = DataChunk::new(vec![Column::new(Arc::new(builder.finish()?))], values.len());
+        let casted = self.elem_cast.eval(&elems)?;
+        let values = casted.iter().map(|datum| datum.to_owned_datum()).collect();
+        Ok(Some(ScalarImpl::List(ListValue::new(values))))
+    }
+}
+
+impl Expression for ListCastExpression {
+    fn return_type(&self) -> DataType {
+        self.return_type.clone()
+    }
+
+    fn eval(&self, input: &DataChunk) -> Result<ArrayRef> {
+        let array = self.child.eval(input)?;
+        let mut builder = self.return_type.create_array_builder(array.len())?;
+        for datum in array.iter() {
+            builder.append_datum(&self.cast_datum(datum.to_owned_datum())?)?;
+        }
+        Ok(Arc::new(builder.finish()?))
+    }
+
+    fn eval_row(&self, input: &Row) -> Result<Datum> {
+        self.cast_datum(self.child.eval_row(input)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::types::OrderedF64;
+
+    use super::*;
+    use crate::expr::LiteralExpression;
+
+    #[test]
+    fn test_list_cast() {
+        let list = ListValue::new(vec![Some(ScalarImpl::Int32(1)), None]);
+        let child = Box::new(LiteralExpression::new(
+            DataType::List {
+                datatype: Box::new(DataType::Int32),
+            },
+            Some(ScalarImpl::List(list)),
+        ));
+        let expr = ListCastExpression::new(
+            DataType::List {
+                datatype: Box::new(DataType::Float64),
+            },
+            child,
+        )
+        .unwrap();
+        let casted = expr.eval_row(&Row(vec![])).unwrap();
+        assert_eq!(
+            casted,
+            Some(ScalarImpl::List(ListValue::new(vec![
+                Some(ScalarImpl::Float64(OrderedF64::from(1.0))),
+                None
+            ])))
+        );
+    }
+}
@@ -0,0 +1,304 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+use risingwave_common::array::{Array, ArrayRef, DataChunk, Row};
+use risingwave_common::types::{DataType, Datum, ScalarImpl};
+use risingwave_pb::expr::expr_node::{RexNode, Type};
+use risingwave_pb::expr::ExprNode;
+
+use crate::expr::{build_from_prost as expr_build_from_prost, BoxedExpression, Expression};
+use crate::vector_op::regexp::{regexp_match, regexp_replace, regexp_substr, RegexpContext};
+use crate::{bail, ExprError, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RegexpKind {
+    /// `regexp_match(text, pattern [, flags])`
+    Match,
+    /// `regexp_replace(text, pattern, replacement [, flags])`
+    Replace,
+    /// `substring(text from pattern)`
+    Substr,
+}
+
+/// `RegexpExpression` evaluates the regular expression functions. The pattern is compiled once if
+/// it and the flags are constants, which is the common case, and compiled for each row otherwise.
+#[derive(Debug)]
+pub struct RegexpExpression {
+    kind: RegexpKind,
+    return_type: DataType,
+    text: BoxedExpression,
+    pattern: BoxedExpression,
+    replacement: Option<BoxedExpression>,
+    flags: Option<BoxedExpression>,
+    ctx: Option<RegexpContext>,
+}
+
+impl RegexpExpression {
+    fn eval_datums(
+        &self,
+        text: Option<&str>,
+        pattern: Option<&str>,
+        replacement: Option<&str>,
+        flags: Option<&str>,
+    ) -> Result<Datum> {
+        let (Some(text), Some(pattern)) = (text, pattern) else {
+            return Ok(None);
+        };
+        if (self.replacement.is_some() && replacement.is_none())
+            || (self.flags.is_some() && flags.is_none())
+        {
+            return Ok(None);
+        }
+        let compiled;
+        let ctx = match &self.ctx {
+            Some(ctx) => ctx,
+            None => {
+                compiled = Self::compile(self.kind, pattern, flags)?;
+                &compiled
+            }
+        };
+        let datum = match self.kind {
+            RegexpKind::Match => regexp_match(text, ctx).map(ScalarImpl::List),
+            RegexpKind::Replace => Some(ScalarImpl::Utf8(regexp_replace(
+                text,
+                ctx,
+                replacement.unwrap(),
+            ))),
+            RegexpKind::Substr => regexp_substr(text, ctx).map(ScalarImpl::Utf8),
+        };
+        Ok(datum)
+    }
+
+    fn compile(kind: RegexpKind, pattern: &str, flags: Option<&str>) -> Result<RegexpContext> {
+        let ctx = RegexpContext::new(pattern, flags.unwrap_or(""))?;
+        if ctx.global && kind != RegexpKind::Replace {
+            return Err(ExprError::InvalidParam {
+                name: "flags",
+                reason: "regexp_match() does not support the \"global\" option".to_string(),
+            });
+        }
+        Ok(ctx)
+    }
+}
+
+impl Expression for RegexpExpression {
+    fn return_type(&self) -> DataType {
+        self.return_type.clone()
+    }
+
+    fn eval(&self, input: &DataChunk) -> Result<ArrayRef> {
+        let eval_utf8 = |expr: &BoxedExpression| expr.eval(input);
+        let text = eval_utf8(&self.text)?;
+        let pattern = eval_utf8(&self.pattern)?;
+        let replacement = self.replacement.as_ref().map(eval_utf8).transpose()?;
+        let flags = self.flags.as_ref().map(eval_utf8).transpose()?;
+
+        let row_len = input.cardinality();
+        let mut builder = self.return_type.create_array_builder(row_len)?;
+        for row_idx in 0..row_len {
+            let datum = self.eval_datums(
+                text.as_utf8().value_at(row_idx),
+                pattern.as_utf8().value_at(row_idx),
+                replacement
+                    .as_ref()
+                    .and_then(|a| a.as_utf8().value_at(row_idx)),
+                flags.as_ref().and_then(|a| a.as_utf8().value_at(row_idx)),
+            )?;
+            builder.append_datum(&datum)?;
+        }
+        Ok(Arc::new(builder.finish()?))
+    }
+
+    fn eval_row(&self, input: &Row) -> Result<Datum> {
+        let text = self.text.eval_row(input)?;
+        let pattern = self.pattern.eval_row(input)?;
+        let replacement = self
+            .replacement
+            .as_ref()
+            .map(|e| e.eval_row(input))
+            .transpose()?
+            .flatten();
+        let flags = self
+            .flags
+            .as_ref()
+            .map(|e| e.eval_row(input))
+            .transpose()?
+            .flatten();
+        self.eval_datums(
+            text.as_ref().map(|v| v.as_utf8().as_str()),
+            pattern.as_ref().map(|v| v.as_utf8().as_str()),
+            replacement.as_ref().map(|v| v.as_utf8().as_str()),
+            flags.as_ref().map(|v| v.as_utf8().as_str()),
+        )
+    }
+}
+
+impl<'a> TryFrom<&'a ExprNode> for RegexpExpression {
+    type Error = ExprError;
+
+    fn try_from(prost: &'a ExprNode) -> Result<Self> {
+        let kind = match prost.get_expr_type().unwrap() {
+            Type::RegexpMatch => RegexpKind::Match,
+            Type::RegexpReplace => RegexpKind::Replace,
+            Type::RegexpSubstr => RegexpKind::Substr,
+            _ => bail!("Expected regular expression functions"),
+        };
+        let return_type = DataType::from(prost.get_return_type().unwrap());
+        let RexNode::FuncCall(func_call_node) = prost.get_rex_node().unwrap() else {
+            bail!("Expected RexNode::FuncCall");
+        };
+        let children = &func_call_node.children;
+        let has_flags = match (kind, children.len()) {
+            (RegexpKind::Match, 2) | (RegexpKind::Substr, 2) | (RegexpKind::Replace, 3) => false,
+            (RegexpKind::Match, 3) | (RegexpKind::Replace, 4) => true,
+            _ => bail!("Unexpected number of arguments: {}", children.len()),
+        };
+
+        let text = expr_build_from_prost(&children[0])?;
+        let pattern = expr_build_from_prost(&children[1])?;
+        let replacement = match kind {
+            RegexpKind::Replace => Some(expr_build_from_prost(&children[2])?),
+            _ => None,
+        };
+        let flags = match has_flags {
+            true => Some(expr_build_from_prost(children.last().unwrap())?),
+            false => None,
+        };
+
+        // Compile the pattern in advance if it's a constant.
+        let is_const = |node: &ExprNode| node.get_expr_type().unwrap() == Type::ConstantValue;
+        let ctx = if is_const(&children[1]) && (!has_flags || is_const(children.last().unwrap())) {
+            let empty = Row(vec![]);
+            let pattern = pattern.eval_row(&empty)?;
+            let flags = match &flags {
+                Some(flags) => flags.eval_row(&empty)?,
+                None => Some(ScalarImpl::Utf8(String::new())),
+            };
+            match (pattern, flags) {
+                (Some(pattern), Some(flags)) => Some(Self::compile(
+                    kind,
+                    pattern.as_utf8(),
+                    Some(flags.as_utf8().as_str()),
+                )?),
+                _ => None,
+            }
+        } else {
+            None
+        };
+
+        Ok(Self {
+            kind,
+            return_type,
+            text,
+            pattern,
+            replacement,
+            flags,
+            ctx,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::{DataChunkTestExt, ListValue};
+    use risingwave_pb::data::data_type::TypeName;
+    use risingwave_pb::data::DataType as ProstDataType;
+    use risingwave_pb::expr::expr_node::Type;
+    use risingwave_pb::expr::{ConstantValue, ExprNode, FunctionCall};
+
+    use super::*;
+    use crate::expr::test_utils::make_input_ref;
+
+    fn make_string_literal(s: &str) -> ExprNode {
+        ExprNode {
+            expr_type: Type::ConstantValue as i32,
+            return_type: Some(ProstDataType {
+                type_name: TypeName::Varchar as i32,
+                ..Default::default()
+            }),
+            rex_node: Some(RexNode::Constant(ConstantValue {
+                body: s.as_bytes().to_vec(),
+            })),
+        }
+    }
+
+    fn make_regexp_expr(kind: Type, children: Vec<ExprNode>, ret: ProstDataType) -> ExprNode {
+        ExprNode {
+            expr_type: kind as i32,
+            return_type: Some(ret),
+            rex_node: Some(RexNode::FuncCall(FunctionCall { children })),
+        }
+    }
+
+    #[test]
+    fn test_regexp_replace_expr() {
+        let expr = RegexpExpression::try_from(&make_regexp_expr(
+            Type::RegexpReplace,
+            vec![
+                make_input_ref(0, TypeName::Varchar),
+                make_string_literal("o+"),
+                make_input_ref(1, TypeName::Varchar),
+                make_string_literal("g"),
+            ],
+            ProstDataType {
+                type_name: TypeName::Varchar as i32,
+                ..Default::default()
+            },
+        ))
+        .unwrap();
+        assert!(expr.ctx.is_some());
+
+        let chunk = DataChunk::from_pretty(
+            "T      T
+             foobar 0
+             boo    .
+             .      0",
+        );
+        let actual = expr.eval(&chunk).unwrap();
+        let actual = actual.as_utf8().iter().collect::<Vec<_>>();
+        assert_eq!(actual, vec![Some("f0bar"), None, None]);
+    }
+
+    #[test]
+    fn test_regexp_match_expr() {
+        let expr = RegexpExpression::try_from(&make_regexp_expr(
+            Type::RegexpMatch,
+            vec![
+                make_input_ref(0, TypeName::Varchar),
+                make_input_ref(1, TypeName::Varchar),
+            ],
+            DataType::List {
+                datatype: Box::new(DataType::Varchar),
+            }
+            .to_protobuf(),
+        ))
+        .unwrap();
+        assert!(expr.ctx.is_none());
+
+        let row = Row(vec![
+            Some(ScalarImpl::Utf8("foobar".to_string())),
+            Some(ScalarImpl::Utf8("(o+)(b)".to_string())),
+        ]);
+        assert_eq!(
+            expr.eval_row(&row).unwrap(),
+            Some(ScalarImpl::List(ListValue::new(vec![
+                Some(ScalarImpl::Utf8("oo".to_string())),
+                Some(ScalarImpl::Utf8("b".to_string())),
+            ])))
+        );
+    }
+}
@@ -19,7 +19,9 @@ use risingwave_common::types::*;
 use risingwave_pb::expr::expr_node::Type as ProstType;
 
 use super::template::{UnaryBytesExpression, UnaryExpression};
+use crate::expr::expr_encoded::EncodedUtf8Expression;
 use crate::expr::expr_is_null::{IsNotNullExpression, IsNullExpression};
+use crate::expr::expr_list_cast::ListCastExpression;
 use crate::expr::template::UnaryNullableExpression;
 use crate::expr::BoxedExpression;
 use crate::vector_op::arithmetic_op::{decimal_abs, general_abs, general_neg};
@@ -28,6 +30,9 @@ use crate::vector_op::bitwise_op::general_bitnot;
 use crate::vector_op::cast::*;
 use crate::vector_op::cmp::{is_false, is_not_false, is_not_true, is_true};
 use crate::vector_op::conjunction;
+use crate::vector_op::distance::ivf_list;
+use crate::vector_op::fulltext::{to_tsquery, to_tsvector};
+use crate::vector_op::geo::{st_as_binary, st_as_text, st_geom_from_text, st_geom_from_wkb};
 use crate::vector_op::length::length_default;
 use crate::vector_op::lower::lower;
 use crate::vector_op::ltrim::ltrim;
@@ -84,6 +89,7 @@ macro_rules! gen_cast {
             { varchar, float64, str_parse },
             { varchar, decimal, str_parse },
             { varchar, boolean, str_to_bool },
+            { varchar, uuid, str_to_uuid },
 
             { boolean, varchar, general_to_string },
             { int16, varchar, general_to_string },
@@ -92,6 +98,7 @@ macro_rules! gen_cast {
             { float32, varchar, general_to_string },
             { float64, varchar, general_to_string },
             { decimal, varchar, general_to_string },
+            { uuid, varchar, general_to_string },
 
             { boolean, int32, general_cast },
             { int32, boolean, int32_to_bool },
@@ -210,6 +217,9 @@ pub fn new_unary_expr(
     use crate::expr::data_types::*;
 
     let expr: BoxedExpression = match (expr_type, return_type.clone(), child_expr.return_type()) {
+        (ProstType::Cast, DataType::List { .. }, DataType::List { .. }) => {
+            Box::new(ListCastExpression::new(return_type, child_expr)?)
+        }
         (ProstType::Cast, _, _) => gen_cast! { child_expr, return_type, },
         (ProstType::BoolOut, _, DataType::Boolean) => {
             Box::new(UnaryExpression::<BoolArray, Utf8Array, _>::new(
@@ -255,20 +265,49 @@ pub fn new_unary_expr(
         }
         (ProstType::IsNull, _, _) => Box::new(IsNullExpression::new(child_expr)),
         (ProstType::IsNotNull, _, _) => Box::new(IsNotNullExpression::new(child_expr)),
-        (ProstType::Upper, _, _) => Box::new(UnaryBytesExpression::<Utf8Array, _>::new(
+        (ProstType::Upper, _, _) => {
+            Box::new(EncodedUtf8Expression::new(child_expr, return_type, upper))
+        }
+        (ProstType::Lower, _, _) => {
+            Box::new(EncodedUtf8Expression::new(child_expr, return_type, lower))
+        }
+        (ProstType::Md5, _, _) => {
+            Box::new(EncodedUtf8Expression::new(child_expr, return_type, md5))
+        }
+        (ProstType::ToTsvector, _, _) => Box::new(UnaryBytesExpression::<Utf8Array, _>::new(
+            child_expr,
+            return_type,
+            to_tsvector,
+        )),
+        (ProstType::ToTsquery, _, _) => Box::new(UnaryBytesExpression::<Utf8Array, _>::new(
+            child_expr,
+            return_type,
+            to_tsquery,
+        )),
+        (ProstType::StGeomFromText, _, _) => Box::new(UnaryBytesExpression::<Utf8Array, _>::new(
+            child_expr,
+            return_type,
+            st_geom_from_text,
+        )),
+        (ProstType::StAsText, _, _) => Box::new(UnaryBytesExpression::<Utf8Array, _>::new(
+            child_expr,
+            return_type,
+            st_as_text,
+        )),
+        (ProstType::StGeomFromWkb, _, _) => Box::new(UnaryBytesExpression::<Utf8Array, _>::new(
             child_expr,
             return_type,
-            upper,
+            st_geom_from_wkb,
         )),
-        (ProstType::Lower, _, _) => Box::new(UnaryBytesExpression::<Utf8Array, _>::new(
+        (ProstType::StAsBinary, _, _) => Box::new(UnaryBytesExpression::<Utf8Array, _>::new(
             child_expr,
             return_type,
-            lower,
+            st_as_binary,
         )),
-        (ProstType::Md5, _, _) => Box::new(UnaryBytesExpression::<Utf8Array, _>::new(
+        (ProstType::IvfList, _, _) => Box::new(UnaryExpression::<ListArray, I32Array, _>::new(
             child_expr,
             return_type,
-            md5,
+            ivf_list,
         )),
         (ProstType::Ascii, _, _) => Box::new(UnaryExpression::<Utf8Array, I32Array, _>::new(
             child_expr,
@@ -21,12 +21,17 @@ pub mod expr_binary_nullable;
 mod expr_case;
 mod expr_coalesce;
 mod expr_concat_ws;
+mod expr_encoded;
 mod expr_field;
+mod expr_format;
+mod expr_gen_random_uuid;
 mod expr_in;
 mod expr_input_ref;
 mod expr_is_null;
+mod expr_list_cast;
 mod expr_literal;
 mod expr_nested_construct;
+mod expr_regexp;
 mod expr_ternary_bytes;
 pub mod expr_unary;
 mod template;
@@ -36,6 +41,7 @@ use std::slice;
 use std::sync::Arc;
 
 pub use agg::AggKind;
+pub use expr_gen_random_uuid::GenRandomUuidExpression;
 pub use expr_input_ref::InputRefExpression;
 pub use expr_literal::*;
 use risingwave_common::array::{ArrayRef, DataChunk, Row};
@@ -47,7 +53,9 @@ use crate::expr::build_expr_from_prost::*;
 use crate::expr::expr_coalesce::CoalesceExpression;
 use crate::expr::expr_concat_ws::ConcatWsExpression;
 use crate::expr::expr_field::FieldExpression;
+use crate::expr::expr_format::FormatExpression;
 use crate::expr::expr_nested_construct::NestedConstructExpression;
+use crate::expr::expr_regexp::RegexpExpression;
 use crate::ExprError;
 
 pub type ExpressionRef = Arc<dyn Expression>;
@@ -82,13 +90,19 @@ pub fn build_from_prost(prost: &ExprNode) -> Result<BoxedExpression> {
     match prost.get_expr_type().unwrap() {
         Cast | Upper | Lower | Md5 | Not | IsTrue | IsNotTrue | IsFalse | IsNotFalse | IsNull
         | IsNotNull | Neg | Ascii | Abs | Ceil | Floor | Round | BitwiseNot | CharLength
-        | BoolOut => build_unary_expr_prost(prost),
+        | BoolOut | ToTsvector | ToTsquery | IvfList | StGeomFromText | StAsText
+        | StGeomFromWkb | StAsBinary => build_unary_expr_prost(prost),
         Equal | NotEqual | LessThan | LessThanOrEqual | GreaterThan | GreaterThanOrEqual | Add
         | Subtract | Multiply | Divide | Modulus | Extract | RoundDigit | TumbleStart
         | Position | BitwiseShiftLeft | BitwiseShiftRight | BitwiseAnd | BitwiseOr | BitwiseXor
-        | ConcatOp => build_binary_expr_prost(prost),
+        | ConcatOp | TsMatch | L2Distance | CosineDistance | InnerProduct | CheckVectorDims
+        | NegativeInnerProduct | StDistance | StDistanceSphere | StContains => {
+            build_binary_expr_prost(prost)
+        }
         And | Or | IsDistinctFrom | ArrayAccess => build_nullable_binary_expr_prost(prost),
         ToChar => build_to_char_expr(prost),
+        Now => build_now_expr(prost),
+        GenRandomUuid => build_gen_random_uuid_expr(prost),
         Coalesce => CoalesceExpression::try_from(prost).map(Expression::boxed),
         Substr => build_substr_expr(prost),
         Length => build_length_expr(prost),
@@ -98,8 +112,15 @@ pub fn build_from_prost(prost: &ExprNode) -> Result<BoxedExpression> {
         Ltrim => build_ltrim_expr(prost),
         Rtrim => build_rtrim_expr(prost),
         Repeat => build_repeat_expr(prost),
+        StPoint => build_st_point_expr(prost),
+        StGeoHash => build_st_geohash_expr(prost),
+        StDWithin => build_st_dwithin_expr(prost),
         ConcatWs => ConcatWsExpression::try_from(prost).map(Expression::boxed),
         SplitPart => build_split_part_expr(prost),
+        RegexpMatch | RegexpReplace | RegexpSubstr => {
+            RegexpExpression::try_from(prost).map(Expression::boxed)
+        }
+        Format => FormatExpression::try_from(prost).map(Expression::boxed),
         ConstantValue => LiteralExpression::try_from(prost).map(Expression::boxed),
         InputRef => InputRefExpression::try_from(prost).map(Expression::boxed),
         Case => build_case_expr(prost),
@@ -385,6 +385,7 @@ macro_rules! for_all_cmp_variants {
             { interval, interval, interval, $general_f },
             { date, date, date, $general_f },
             { boolean, boolean, boolean, $general_f },
+            { uuid, uuid, uuid, $general_f },
             { timestamp, date, timestamp, $general_f },
             { date, timestamp, timestamp, $general_f }
         }
@@ -18,7 +18,7 @@ use std::str::FromStr;
 use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime};
 use num_traits::ToPrimitive;
 use risingwave_common::types::{
-    Decimal, NaiveDateTimeWrapper, NaiveDateWrapper, NaiveTimeWrapper, OrderedF32, OrderedF64,
+    Decimal, NaiveDateTimeWrapper, NaiveDateWrapper, NaiveTimeWrapper, OrderedF32, OrderedF64, Uuid,
 };
 
 use crate::{ExprError, Result};
@@ -34,6 +34,13 @@ const PARSE_ERROR_STR_TO_TIMESTAMP: &str = "Can't cast string to timestamp (expe
 const PARSE_ERROR_STR_TO_TIME: &str =
     "Can't cast string to time (expected format is HH:MM:SS[.MS] or HH:MM)";
 const PARSE_ERROR_STR_TO_DATE: &str = "Can't cast string to date (expected format is YYYY-MM-DD)";
+const PARSE_ERROR_STR_TO_UUID: &str =
+    "Can't cast string to uuid (expected format is xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx)";
+
+#[inline(always)]
+pub fn str_to_uuid(elem: &str) -> Result<Uuid> {
+    Uuid::from_str(elem).map_err(|_| ExprError::Parse(PARSE_ERROR_STR_TO_UUID))
+}
 
 #[inline(always)]
 pub fn str_to_date(elem: &str) -> Result<NaiveDateWrapper> {
@@ -0,0 +1,235 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Distances between vectors for similarity search. There is no dedicated type, so a vector is a
+//! list of numbers, e.g. `VECTOR(3)` is `DOUBLE PRECISION[]` whose values are checked to have 3
+//! dimensions on insert, and vectors of different dimensions fail to be compared when computing
+//! the distance.
+
+use num_traits::ToPrimitive;
+use risingwave_common::array::{ListRef, ListValue};
+use risingwave_common::types::{OrderedF64, ScalarRef, ScalarRefImpl};
+
+use crate::{ExprError, Result};
+
+/// The number of random hyperplanes splitting the space into the lists of an IVF index, which has
+/// `2 ^ IVF_HYPERPLANES` lists.
+pub const IVF_HYPERPLANES: usize = 4;
+
+/// Converts a list of numbers to a vector. Null elements are not allowed.
+pub fn to_vector(list: ListRef<'_>) -> Result<Vec<f64>> {
+    list.values_ref()
+        .into_iter()
+        .map(|datum| {
+            let value = match datum {
+                Some(ScalarRefImpl::Int16(v)) => Some(v as f64),
+                Some(ScalarRefImpl::Int32(v)) => Some(v as f64),
+                Some(ScalarRefImpl::Int64(v)) => Some(v as f64),
+                Some(ScalarRefImpl::Float32(v)) => Some(v.0 as f64),
+                Some(ScalarRefImpl::Float64(v)) => Some(v.0),
+                Some(ScalarRefImpl::Decimal(v)) => v.to_f64(),
+                _ => None,
+            };
+            value.ok_or_else(|| ExprError::InvalidParam {
+                name: "vector",
+                reason: format!("invalid vector element {:?}", datum),
+            })
+        })
+        .collect()
+}
+
+fn to_vectors(l: ListRef<'_>, r: ListRef<'_>) -> Result<(Vec<f64>, Vec<f64>)> {
+    let (l, r) = (to_vector(l)?, to_vector(r)?);
+    if l.len() != r.len() {
+        return Err(ExprError::InvalidParam {
+            name: "vector",
+            reason: format!("different vector dimensions {} and {}", l.len(), r.len()),
+        });
+    }
+    Ok((l, r))
+}
+
+fn dot(l: &[f64], r: &[f64]) -> f64 {
+    l.iter().zip(r).map(|(l, r)| l * r).sum()
+}
+
+/// `l <-> r`: the Euclidean distance.
+#[inline(always)]
+pub fn l2_distance(l: ListRef<'_>, r: ListRef<'_>) -> Result<OrderedF64> {
+    let (l, r) = to_vectors(l, r)?;
+    let distance = l
+        .iter()
+        .zip(&r)
+        .map(|(l, r)| (l - r) * (l - r))
+        .sum::<f64>();
+    Ok(distance.sqrt().into())
+}
+
+/// `l <=> r`: one minus the cosine of the angle between the vectors, which is NaN for a zero
+/// vector.
+#[inline(always)]
+pub fn cosine_distance(l: ListRef<'_>, r: ListRef<'_>) -> Result<OrderedF64> {
+    let (l, r) = to_vectors(l, r)?;
+    let similarity = dot(&l, &r) / (dot(&l, &l).sqrt() * dot(&r, &r).sqrt());
+    Ok((1.0 - similarity).into())
+}
+
+#[inline(always)]
+pub fn inner_product(l: ListRef<'_>, r: ListRef<'_>) -> Result<OrderedF64> {
+    let (l, r) = to_vectors(l, r)?;
+    Ok(dot(&l, &r).into())
+}
+
+/// `l <#> r`: the negative inner product, so that a smaller value means more similar like the
+/// other distances.
+#[inline(always)]
+pub fn negative_inner_product(l: ListRef<'_>, r: ListRef<'_>) -> Result<OrderedF64> {
+    let (l, r) = to_vectors(l, r)?;
+    Ok((-dot(&l, &r)).into())
+}
+
+/// Returns the vector if it has `dims` dimensions, which is checked on the values inserted into a
+/// `VECTOR(dims)` column.
+#[inline(always)]
+pub fn check_vector_dims(v: ListRef<'_>, dims: i32) -> Result<ListValue> {
+    let len = to_vector(v)?.len();
+    if len as i32 != dims {
+        return Err(ExprError::InvalidParam {
+            name: "vector",
+            reason: format!("expected {} dimensions, not {}", dims, len),
+        });
+    }
+    Ok(v.to_owned_scalar())
+}
+
+/// Returns the list of an IVF index the vector belongs to.
+#[inline(always)]
+pub fn ivf_list(v: ListRef<'_>) -> Result<i32> {
+    Ok(ivf_list_of(&to_vector(v)?))
+}
+
+/// The lists of an IVF index are the regions split by [`IVF_HYPERPLANES`] hyperplanes through the
+/// origin, and a vector belongs to the list given by the sides of the hyperplanes it is on. Unlike
+/// the clusters of k-means, the hyperplanes are pseudo-random and fixed for each dimension, so an
+/// index needs no training and can be maintained incrementally. Similar vectors are likely to be
+/// on the same sides of the hyperplanes, i.e. in the same list.
+pub fn ivf_list_of(v: &[f64]) -> i32 {
+    (0..IVF_HYPERPLANES).fold(0, |list, plane| {
+        list | (((projection(v, plane) >= 0.0) as i32) << plane)
+    })
+}
+
+/// Returns the `probes` lists of an IVF index nearest to the vector, starting from the list it
+/// belongs to. The distance to another list is the sum of the distances to the hyperplanes the
+/// vector has to cross to get there, so a search probing them misses fewer near vectors close to
+/// the boundaries of its list.
+pub fn ivf_nearest_lists(v: &[f64], probes: usize) -> Vec<i32> {
+    let projections = (0..IVF_HYPERPLANES)
+        .map(|plane| projection(v, plane))
+        .collect::<Vec<_>>();
+    let list = ivf_list_of(v);
+    let mut lists = (0..1 << IVF_HYPERPLANES)
+        .map(|other: i32| {
+            let distance = (0..IVF_HYPERPLANES)
+                .filter(|plane| ((list ^ other) >> plane) & 1 == 1)
+                .map(|plane| projections[plane].abs())
+                .sum::<f64>();
+            (OrderedF64::from(distance), other)
+        })
+        .collect::<Vec<_>>();
+    lists.sort();
+    lists
+        .into_iter()
+        .take(probes)
+        .map(|(_, list)| list)
+        .collect()
+}
+
+/// The signed distance from the vector to a hyperplane, scaled by the norm of its normal vector.
+fn projection(v: &[f64], plane: usize) -> f64 {
+    v.iter()
+        .enumerate()
+        .map(|(dim, x)| x * hyperplane_component(plane, dim))
+        .sum()
+}
+
+/// A component of the normal vector of a hyperplane in `[-1, 1)`, generated by `SplitMix64`.
+fn hyperplane_component(plane: usize, dim: usize) -> f64 {
+    let mut z = ((plane as u64) << 32 | dim as u64).wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^= z >> 31;
+    (z >> 11) as f64 / (1u64 << 52) as f64 - 1.0
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::types::ScalarImpl;
+
+    use super::*;
+
+    fn list(values: &[f64]) -> ListValue {
+        ListValue::new(
+            values
+                .iter()
+                .map(|v| Some(ScalarImpl::Float64((*v).into())))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_distance() {
+        let (l, r) = (list(&[1.0, 0.0, 2.0]), list(&[0.0, 3.0, 2.0]));
+        let l = ListRef::ValueRef { val: &l };
+        let r = ListRef::ValueRef { val: &r };
+        assert_eq!(l2_distance(l, r).unwrap(), 10f64.sqrt().into());
+        assert_eq!(inner_product(l, r).unwrap(), 4.0.into());
+        assert_eq!(negative_inner_product(l, r).unwrap(), (-4.0).into());
+        let cosine = cosine_distance(l, r).unwrap().0;
+        assert!((cosine - (1.0 - 4.0 / (5f64.sqrt() * 13f64.sqrt()))).abs() < 1e-9);
+
+        let ints = ListValue::new(vec![Some(ScalarImpl::Int32(1)), Some(ScalarImpl::Int32(0))]);
+        let ints = ListRef::ValueRef { val: &ints };
+        assert!(l2_distance(l, ints).is_err());
+        let null = ListValue::new(vec![None]);
+        assert!(to_vector(ListRef::ValueRef { val: &null }).is_err());
+    }
+
+    #[test]
+    fn test_ivf_list() {
+        let v = [0.3, -1.2, 4.5, 0.7];
+        let list = ivf_list_of(&v);
+        assert!((0..1 << IVF_HYPERPLANES).contains(&list));
+        // The list only depends on the direction of the vector.
+        assert_eq!(ivf_list_of(&v.map(|x| x * 10.0)), list);
+        assert_eq!(
+            ivf_list_of(&v.map(|x| -x)),
+            (1 << IVF_HYPERPLANES) - 1 - list
+        );
+
+        assert_eq!(ivf_nearest_lists(&v, 1), vec![list]);
+        let mut all = ivf_nearest_lists(&v, usize::MAX);
+        assert_eq!(all[0], list);
+        all.sort_unstable();
+        assert_eq!(all, (0..1 << IVF_HYPERPLANES).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_check_vector_dims() {
+        let v = list(&[1.0, 2.0]);
+        let v = ListRef::ValueRef { val: &v };
+        assert_eq!(check_vector_dims(v, 2).unwrap(), list(&[1.0, 2.0]));
+        assert!(check_vector_dims(v, 3).is_err());
+    }
+}
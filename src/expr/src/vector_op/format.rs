@@ -0,0 +1,165 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{ExprError, Result};
+
+/// `format(formatstr, args...)` formats the arguments like `sprintf` in C, as in PostgreSQL. A
+/// format specifier is `%[position][flags][width]type`, where:
+/// - `position` is `n$` referring to the `n`-th argument, or the next argument if omitted.
+/// - `flags` can only be `-`, which left-justifies the output.
+/// - `width` is the minimum number of characters of the output.
+/// - `type` is `s` for a string, `I` for an SQL identifier, `L` for an SQL literal, or `%` for a
+///   literal `%`.
+pub fn format(fmt: &str, args: &[Option<&str>]) -> Result<String> {
+    let mut result = String::with_capacity(fmt.len());
+    let mut chars = fmt.chars().peekable();
+    let mut next_arg = 0;
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            result.push(c);
+            continue;
+        }
+        if chars.peek() == Some(&'%') {
+            chars.next();
+            result.push('%');
+            continue;
+        }
+
+        let mut number = read_number(&mut chars);
+        if let Some(position) = number {
+            if chars.peek() == Some(&'$') {
+                chars.next();
+                if position == 0 {
+                    return Err(invalid_format(
+                        "format specifies argument 0, but arguments are numbered from 1",
+                    ));
+                }
+                next_arg = position - 1;
+                number = None;
+            }
+        }
+        let mut left_justify = false;
+        if number.is_none() && chars.peek() == Some(&'-') {
+            chars.next();
+            left_justify = true;
+        }
+        let width = number.or_else(|| read_number(&mut chars)).unwrap_or(0);
+
+        let Some(ty) = chars.next() else {
+            return Err(invalid_format("unterminated format() type specifier"));
+        };
+        if !matches!(ty, 's' | 'I' | 'L') {
+            return Err(invalid_format(&format!(
+                "unrecognized format() type specifier \"{}\"",
+                ty
+            )));
+        }
+        let Some(arg) = args.get(next_arg) else {
+            return Err(invalid_format("too few arguments for format()"));
+        };
+        next_arg += 1;
+
+        let formatted = match (ty, arg) {
+            ('s', Some(arg)) => arg.to_string(),
+            ('s', None) => String::new(),
+            ('I', Some(arg)) => quote_ident(arg),
+            ('I', None) => {
+                return Err(invalid_format(
+                    "null values cannot be formatted as an SQL identifier",
+                ))
+            }
+            ('L', Some(arg)) => quote_literal(arg),
+            ('L', None) => "NULL".to_string(),
+            _ => unreachable!(),
+        };
+        let padding = " ".repeat(width.saturating_sub(formatted.chars().count()));
+        if left_justify {
+            result.push_str(&formatted);
+            result.push_str(&padding);
+        } else {
+            result.push_str(&padding);
+            result.push_str(&formatted);
+        }
+    }
+    Ok(result)
+}
+
+fn read_number(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) -> Option<usize> {
+    let mut number = None;
+    while let Some(digit) = chars.peek().and_then(|c| c.to_digit(10)) {
+        chars.next();
+        number = Some(number.unwrap_or(0) * 10 + digit as usize);
+    }
+    number
+}
+
+fn invalid_format(reason: &str) -> ExprError {
+    ExprError::InvalidParam {
+        name: "formatstr",
+        reason: reason.to_string(),
+    }
+}
+
+/// Quotes `s` as an SQL identifier if it is not a lower-case identifier.
+fn quote_ident(s: &str) -> String {
+    let is_plain = s
+        .chars()
+        .next()
+        .map_or(false, |c| c.is_ascii_lowercase() || c == '_')
+        && s.chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if is_plain {
+        s.to_string()
+    } else {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    }
+}
+
+/// Quotes `s` as an SQL string literal, using the escape string syntax if it has backslashes.
+fn quote_literal(s: &str) -> String {
+    let quoted = s.replace('\'', "''");
+    if quoted.contains('\\') {
+        format!("E'{}'", quoted.replace('\\', "\\\\"))
+    } else {
+        format!("'{}'", quoted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format() {
+        assert_eq!(
+            format("Hello %s, %1$s and %%", &[Some("World")]).unwrap(),
+            "Hello World, World and %"
+        );
+        assert_eq!(
+            format(
+                "INSERT INTO %I VALUES(%L, %L)",
+                &[Some("Foo bar"), Some("O'Reilly"), None]
+            )
+            .unwrap(),
+            "INSERT INTO \"Foo bar\" VALUES('O''Reilly', NULL)"
+        );
+        assert_eq!(
+            format("|%5s|%-5s|%3$s|", &[Some("a"), Some("b"), None]).unwrap(),
+            "|    a|b    ||"
+        );
+        assert!(format("%s %s", &[Some("a")]).is_err());
+        assert!(format("%d", &[Some("1")]).is_err());
+        assert!(format("%I", &[None]).is_err());
+    }
+}
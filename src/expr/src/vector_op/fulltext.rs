@@ -0,0 +1,239 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Full-text search with the `simple` configuration of Postgres: a document is split into words at
+//! non-alphanumeric characters, and each word is lowercased into a lexeme, without stemming or
+//! stop words. There is no dedicated type, so a `tsvector` is a `varchar` of the sorted distinct
+//! lexemes like `'cat' 'fat'`, and a `tsquery` is a `varchar` like `'fat' & ( 'cat' | !'rat' )`.
+//! Splitting a `tsvector` again yields the same lexemes, so `@@` accepts a plain text as well.
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+use itertools::Itertools;
+use risingwave_common::array::{BytesGuard, BytesWriter};
+
+use crate::{ExprError, Result};
+
+/// Returns the sorted distinct lexemes of the text.
+pub fn lexemes(text: &str) -> BTreeSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+#[inline(always)]
+pub fn to_tsvector(s: &str, writer: BytesWriter) -> Result<BytesGuard> {
+    let tsvector = lexemes(s)
+        .iter()
+        .map(|lexeme| format!("'{}'", lexeme))
+        .join(" ");
+    writer.write_ref(&tsvector).map_err(Into::into)
+}
+
+#[inline(always)]
+pub fn to_tsquery(s: &str, writer: BytesWriter) -> Result<BytesGuard> {
+    let query = TsQuery::parse(s)?;
+    writer.write_ref(&query.to_string()).map_err(Into::into)
+}
+
+/// `tsvector @@ tsquery`: whether the lexemes of the document satisfy the query.
+#[inline(always)]
+pub fn ts_match(document: &str, query: &str) -> Result<bool> {
+    Ok(TsQuery::parse(query)?.matches(&lexemes(document)))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TsQuery {
+    Term(String),
+    And(Box<TsQuery>, Box<TsQuery>),
+    Or(Box<TsQuery>, Box<TsQuery>),
+    Not(Box<TsQuery>),
+}
+
+impl TsQuery {
+    /// Parses a query of terms combined by `&`, `|`, `!` and parentheses, where `!` binds tightest
+    /// and `|` loosest. The terms are normalized into lexemes, and quotes around them are ignored.
+    pub fn parse(s: &str) -> Result<Self> {
+        let mut parser = QueryParser {
+            input: s,
+            chars: s.char_indices().peekable(),
+        };
+        let query = parser.parse_or()?;
+        match parser.peek() {
+            None => Ok(query),
+            Some(c) => Err(parser.error(format!("unexpected \"{}\"", c))),
+        }
+    }
+
+    pub fn matches(&self, lexemes: &BTreeSet<String>) -> bool {
+        match self {
+            Self::Term(term) => lexemes.contains(term),
+            Self::And(l, r) => l.matches(lexemes) && r.matches(lexemes),
+            Self::Or(l, r) => l.matches(lexemes) || r.matches(lexemes),
+            Self::Not(q) => !q.matches(lexemes),
+        }
+    }
+
+    /// Returns the lexemes that every matching document must contain.
+    pub fn required_terms(&self) -> Vec<&str> {
+        match self {
+            Self::Term(term) => vec![term.as_str()],
+            Self::And(l, r) => {
+                let mut terms = l.required_terms();
+                terms.extend(r.required_terms());
+                terms
+            }
+            Self::Or(..) | Self::Not(_) => vec![],
+        }
+    }
+}
+
+impl fmt::Display for TsQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Term(term) => write!(f, "'{}'", term),
+            Self::Not(q) if matches!(**q, Self::Term(_)) => write!(f, "!{}", q),
+            Self::Not(q) => write!(f, "!( {} )", q),
+            Self::And(l, r) => {
+                let fmt_child = |f: &mut fmt::Formatter<'_>, q: &TsQuery| match q {
+                    Self::Or(..) => write!(f, "( {} )", q),
+                    _ => write!(f, "{}", q),
+                };
+                fmt_child(f, l)?;
+                write!(f, " & ")?;
+                fmt_child(f, r)
+            }
+            Self::Or(l, r) => write!(f, "{} | {}", l, r),
+        }
+    }
+}
+
+struct QueryParser<'a> {
+    input: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+}
+
+impl QueryParser<'_> {
+    fn error(&self, reason: String) -> ExprError {
+        ExprError::InvalidParam {
+            name: "tsquery",
+            reason: format!("{} in \"{}\"", reason, self.input),
+        }
+    }
+
+    /// Peeks the next character, skipping whitespaces and quotes.
+    fn peek(&mut self) -> Option<char> {
+        while let Some(&(_, c)) = self.chars.peek() {
+            if c.is_whitespace() || c == '\'' {
+                self.chars.next();
+            } else {
+                return Some(c);
+            }
+        }
+        None
+    }
+
+    fn parse_or(&mut self) -> Result<TsQuery> {
+        let mut query = self.parse_and()?;
+        while self.peek() == Some('|') {
+            self.chars.next();
+            query = TsQuery::Or(Box::new(query), Box::new(self.parse_and()?));
+        }
+        Ok(query)
+    }
+
+    fn parse_and(&mut self) -> Result<TsQuery> {
+        let mut query = self.parse_unary()?;
+        while self.peek() == Some('&') {
+            self.chars.next();
+            query = TsQuery::And(Box::new(query), Box::new(self.parse_unary()?));
+        }
+        Ok(query)
+    }
+
+    fn parse_unary(&mut self) -> Result<TsQuery> {
+        match self.peek() {
+            Some('!') => {
+                self.chars.next();
+                Ok(TsQuery::Not(Box::new(self.parse_unary()?)))
+            }
+            Some('(') => {
+                self.chars.next();
+                let query = self.parse_or()?;
+                if self.peek() != Some(')') {
+                    return Err(self.error("missing \")\"".to_string()));
+                }
+                self.chars.next();
+                Ok(query)
+            }
+            Some(c) if c.is_alphanumeric() => {
+                let mut term = String::new();
+                while let Some(&(_, c)) = self.chars.peek().filter(|(_, c)| c.is_alphanumeric()) {
+                    term.extend(c.to_lowercase());
+                    self.chars.next();
+                }
+                Ok(TsQuery::Term(term))
+            }
+            Some(c) => Err(self.error(format!("unexpected \"{}\"", c))),
+            None => Err(self.error("unexpected end".to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::{Array, ArrayBuilder, Utf8ArrayBuilder};
+
+    use super::*;
+
+    #[test]
+    fn test_to_tsvector() {
+        let builder = Utf8ArrayBuilder::new(1).unwrap();
+        let guard = to_tsvector(
+            "A fat cat sat on a mat, and ate a FAT rat.",
+            builder.writer(),
+        )
+        .unwrap();
+        let array = guard.into_inner().finish().unwrap();
+        assert_eq!(
+            array.value_at(0).unwrap(),
+            "'a' 'and' 'ate' 'cat' 'fat' 'mat' 'on' 'rat' 'sat'"
+        );
+    }
+
+    #[test]
+    fn test_tsquery() {
+        let query = TsQuery::parse("Fat & (cat | !'Rat') & !(dog & mouse)").unwrap();
+        assert_eq!(
+            query.to_string(),
+            "'fat' & ( 'cat' | !'rat' ) & !( 'dog' & 'mouse' )"
+        );
+        // The normalized query parses to itself.
+        assert_eq!(TsQuery::parse(&query.to_string()).unwrap(), query);
+        assert_eq!(query.required_terms(), vec!["fat"]);
+
+        assert!(ts_match("The fat cat", &query.to_string()).unwrap());
+        assert!(!ts_match("The fat rat", "fat & (cat | !rat)").unwrap());
+        assert!(ts_match("The fat dog", "fat & (cat | !rat)").unwrap());
+        assert!(ts_match("'cat' 'fat'", "cat & fat").unwrap());
+
+        for invalid in ["", "fat &", "(fat", "fat cat", "fat & |"] {
+            assert!(TsQuery::parse(invalid).is_err(), "{}", invalid);
+        }
+    }
+}
@@ -0,0 +1,650 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A subset of the geospatial functions of PostGIS. There is no dedicated type, so a geometry is a
+//! string in the well-known text (WKT) format, e.g. `POINT(1 2)` or `POLYGON((0 0,1 0,1 1,0 0))`,
+//! and `GEOMETRY` and `GEOGRAPHY` are both aliases of `VARCHAR`. Only points, line strings and
+//! polygons are supported, and all functions except [`st_distance_sphere`] work on the Cartesian
+//! plane.
+//!
+//! The WKT arguments are parsed on every call, and WKB is only accepted and produced as hex
+//! strings, since there is no `BYTEA`. See `docs/geospatial.md` for the other limitations.
+
+use std::fmt::{self, Write};
+
+use risingwave_common::array::{BytesGuard, BytesWriter};
+use risingwave_common::types::OrderedF64;
+
+use crate::{ExprError, Result};
+
+/// The mean radius of the earth in meters, used by [`st_distance_sphere`].
+const EARTH_RADIUS: f64 = 6_371_008.8;
+
+/// The tolerance of a point lying on a line.
+const EPSILON: f64 = 1e-9;
+
+const GEOHASH_BASE32: &[u8] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+/// The WKB type codes of the supported geometries.
+const WKB_POINT: u32 = 1;
+const WKB_LINE_STRING: u32 = 2;
+const WKB_POLYGON: u32 = 3;
+
+type Coord = (f64, f64);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Geometry {
+    Point(Coord),
+    LineString(Vec<Coord>),
+    /// The first ring is the exterior, and the others are holes. Every ring is closed.
+    Polygon(Vec<Vec<Coord>>),
+}
+
+fn invalid(reason: impl Into<String>) -> ExprError {
+    ExprError::InvalidParam {
+        name: "geometry",
+        reason: reason.into(),
+    }
+}
+
+impl Geometry {
+    /// Parses a geometry in the WKT format. Keywords are case-insensitive.
+    pub fn from_wkt(wkt: &str) -> Result<Self> {
+        let wkt = wkt.trim();
+        let (kind, body) = match (wkt.find('('), wkt.strip_suffix(')')) {
+            (Some(open), Some(rest)) => (wkt[..open].trim().to_uppercase(), &rest[open + 1..]),
+            _ => return Err(invalid(format!("invalid WKT {}", wkt))),
+        };
+        match kind.as_str() {
+            "POINT" => Ok(Self::Point(parse_coord(body)?)),
+            "LINESTRING" => Self::line_string(parse_coords(body)?),
+            "POLYGON" => {
+                let rings = body
+                    .split(')')
+                    .map(|ring| ring.trim().trim_start_matches(',').trim())
+                    .filter(|ring| !ring.is_empty())
+                    .map(|ring| match ring.strip_prefix('(') {
+                        Some(ring) => parse_coords(ring),
+                        None => Err(invalid(format!("invalid polygon ring {}", ring))),
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                Self::polygon(rings)
+            }
+            _ => Err(invalid(format!("unsupported geometry type {}", kind))),
+        }
+    }
+
+    /// Parses a geometry in the WKB format, encoded in hex. Both byte orders are accepted.
+    pub fn from_wkb_hex(hex: &str) -> Result<Self> {
+        let hex = hex.trim();
+        if hex.len() % 2 != 0 || !hex.is_ascii() {
+            return Err(invalid(format!("invalid hex WKB {}", hex)));
+        }
+        let bytes = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16))
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|_| invalid(format!("invalid hex WKB {}", hex)))?;
+        let mut reader = WkbReader {
+            bytes: &bytes,
+            little_endian: true,
+        };
+        let geometry = reader.read_geometry()?;
+        if !reader.bytes.is_empty() {
+            return Err(invalid("trailing bytes after WKB"));
+        }
+        Ok(geometry)
+    }
+
+    /// Encodes the geometry in the little-endian WKB format, in hex.
+    pub fn to_wkb_hex(&self) -> String {
+        fn put_u32(out: &mut String, v: u32) {
+            v.to_le_bytes()
+                .iter()
+                .for_each(|b| write!(out, "{:02x}", b).unwrap());
+        }
+        fn put_coords(out: &mut String, coords: &[Coord]) {
+            for (x, y) in coords {
+                for v in [x, y] {
+                    v.to_le_bytes()
+                        .iter()
+                        .for_each(|b| write!(out, "{:02x}", b).unwrap());
+                }
+            }
+        }
+
+        let mut out = "01".to_string();
+        match self {
+            Self::Point(p) => {
+                put_u32(&mut out, WKB_POINT);
+                put_coords(&mut out, &[*p]);
+            }
+            Self::LineString(points) => {
+                put_u32(&mut out, WKB_LINE_STRING);
+                put_u32(&mut out, points.len() as u32);
+                put_coords(&mut out, points);
+            }
+            Self::Polygon(rings) => {
+                put_u32(&mut out, WKB_POLYGON);
+                put_u32(&mut out, rings.len() as u32);
+                for ring in rings {
+                    put_u32(&mut out, ring.len() as u32);
+                    put_coords(&mut out, ring);
+                }
+            }
+        }
+        out
+    }
+
+    fn line_string(points: Vec<Coord>) -> Result<Self> {
+        if points.len() < 2 {
+            return Err(invalid("a line string must have at least 2 points"));
+        }
+        Ok(Self::LineString(points))
+    }
+
+    fn polygon(rings: Vec<Vec<Coord>>) -> Result<Self> {
+        if rings.is_empty() {
+            return Err(invalid("a polygon must have at least 1 ring"));
+        }
+        for ring in &rings {
+            if ring.len() < 4 || ring.first() != ring.last() {
+                return Err(invalid(
+                    "a polygon ring must be closed and have at least 4 points",
+                ));
+            }
+        }
+        Ok(Self::Polygon(rings))
+    }
+
+    fn vertices(&self) -> Vec<Coord> {
+        match self {
+            Self::Point(p) => vec![*p],
+            Self::LineString(points) => points.clone(),
+            Self::Polygon(rings) => rings.concat(),
+        }
+    }
+
+    /// The segments of the geometry, where a point is a segment of zero length.
+    fn segments(&self) -> Vec<(Coord, Coord)> {
+        match self {
+            Self::Point(p) => vec![(*p, *p)],
+            Self::LineString(points) => points.windows(2).map(|w| (w[0], w[1])).collect(),
+            Self::Polygon(rings) => rings
+                .iter()
+                .flat_map(|ring| ring.windows(2).map(|w| (w[0], w[1])))
+                .collect(),
+        }
+    }
+
+    fn on_boundary(&self, p: Coord) -> bool {
+        self.segments()
+            .iter()
+            .any(|s| point_segment_distance(p, *s) <= EPSILON)
+    }
+
+    /// Whether `p` is in the interior of the polygon, excluding its boundary.
+    fn interior_contains(&self, p: Coord) -> bool {
+        match self {
+            Self::Polygon(rings) => {
+                !self.on_boundary(p)
+                    && ring_contains(&rings[0], p)
+                    && !rings[1..].iter().any(|hole| ring_contains(hole, p))
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether `p` is in the interior or on the boundary of the geometry.
+    fn covers_point(&self, p: Coord) -> bool {
+        self.on_boundary(p) || self.interior_contains(p)
+    }
+
+    /// The minimum Cartesian distance between the geometries, which is 0 if they intersect.
+    pub fn distance(&self, other: &Self) -> f64 {
+        if other.vertices().iter().any(|p| self.interior_contains(*p))
+            || self.vertices().iter().any(|p| other.interior_contains(*p))
+        {
+            return 0.0;
+        }
+        let other_segments = other.segments();
+        self.segments()
+            .iter()
+            .flat_map(|s| other_segments.iter().map(|t| segment_distance(*s, *t)))
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    /// Whether no point of `other` lies outside the geometry, and the interiors of the geometries
+    /// intersect. Only polygons can contain line strings and polygons.
+    pub fn contains(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Point(p), _) => other.vertices().iter().all(|q| q == p),
+            (Self::LineString(_), Self::Point(p)) => {
+                // The end points are the boundary of a line string, not its interior.
+                let points = self.vertices();
+                self.on_boundary(*p) && points.first() != Some(p) && points.last() != Some(p)
+            }
+            (Self::LineString(_), Self::LineString(_)) => other.segments().iter().all(|(a, b)| {
+                [*a, *b, midpoint(*a, *b)]
+                    .iter()
+                    .all(|p| self.on_boundary(*p))
+            }),
+            (Self::LineString(_), Self::Polygon(_)) => false,
+            (Self::Polygon(_), Self::Point(p)) => self.interior_contains(*p),
+            (Self::Polygon(rings), _) => {
+                let segments = self.segments();
+                let inside = other.segments().iter().all(|(a, b)| {
+                    [*a, *b, midpoint(*a, *b)]
+                        .iter()
+                        .all(|p| self.covers_point(*p))
+                        && !segments
+                            .iter()
+                            .any(|t| segments_cross_properly((*a, *b), *t))
+                });
+                // A polygon must not contain any hole of this polygon.
+                let no_hole_inside = rings[1..]
+                    .iter()
+                    .flatten()
+                    .all(|p| !other.interior_contains(*p));
+                let interiors_intersect = other
+                    .segments()
+                    .iter()
+                    .any(|(a, b)| self.interior_contains(midpoint(*a, *b)))
+                    || other.vertices().iter().any(|p| self.interior_contains(*p));
+                inside && no_hole_inside && interiors_intersect
+            }
+        }
+    }
+
+    /// The center of the bounding box of the geometry.
+    fn center(&self) -> Coord {
+        let vertices = self.vertices();
+        let (mut min_x, mut min_y) = (f64::INFINITY, f64::INFINITY);
+        let (mut max_x, mut max_y) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+        for (x, y) in vertices {
+            min_x = min_x.min(x);
+            min_y = min_y.min(y);
+            max_x = max_x.max(x);
+            max_y = max_y.max(y);
+        }
+        ((min_x + max_x) / 2.0, (min_y + max_y) / 2.0)
+    }
+}
+
+impl fmt::Display for Geometry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn write_coords(f: &mut fmt::Formatter<'_>, coords: &[Coord]) -> fmt::Result {
+            for (i, (x, y)) in coords.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ",")?;
+                }
+                write!(f, "{} {}", x, y)?;
+            }
+            Ok(())
+        }
+
+        match self {
+            Self::Point((x, y)) => write!(f, "POINT({} {})", x, y),
+            Self::LineString(points) => {
+                write!(f, "LINESTRING(")?;
+                write_coords(f, points)?;
+                write!(f, ")")
+            }
+            Self::Polygon(rings) => {
+                write!(f, "POLYGON(")?;
+                for (i, ring) in rings.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "(")?;
+                    write_coords(f, ring)?;
+                    write!(f, ")")?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+fn parse_coord(s: &str) -> Result<Coord> {
+    let mut values = s.split_whitespace().map(|v| v.parse::<f64>());
+    match (values.next(), values.next(), values.next()) {
+        (Some(Ok(x)), Some(Ok(y)), None) if x.is_finite() && y.is_finite() => Ok((x, y)),
+        _ => Err(invalid(format!("invalid coordinate {}", s.trim()))),
+    }
+}
+
+fn parse_coords(s: &str) -> Result<Vec<Coord>> {
+    s.split(',').map(parse_coord).collect()
+}
+
+struct WkbReader<'a> {
+    bytes: &'a [u8],
+    little_endian: bool,
+}
+
+impl WkbReader<'_> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N]> {
+        if self.bytes.len() < N {
+            return Err(invalid("unexpected end of WKB"));
+        }
+        let (head, rest) = self.bytes.split_at(N);
+        self.bytes = rest;
+        Ok(head.try_into().unwrap())
+    }
+
+    fn read_u32(&mut self) -> Result<u32> {
+        let bytes = self.take::<4>()?;
+        Ok(if self.little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    fn read_coords(&mut self, n: usize) -> Result<Vec<Coord>> {
+        (0..n)
+            .map(|_| {
+                let mut read_f64 = || -> Result<f64> {
+                    let bytes = self.take::<8>()?;
+                    Ok(if self.little_endian {
+                        f64::from_le_bytes(bytes)
+                    } else {
+                        f64::from_be_bytes(bytes)
+                    })
+                };
+                Ok((read_f64()?, read_f64()?))
+            })
+            .collect()
+    }
+
+    fn read_geometry(&mut self) -> Result<Geometry> {
+        self.little_endian = match self.take::<1>()? {
+            [0] => false,
+            [1] => true,
+            [b] => return Err(invalid(format!("invalid WKB byte order {}", b))),
+        };
+        match self.read_u32()? {
+            WKB_POINT => Ok(Geometry::Point(self.read_coords(1)?[0])),
+            WKB_LINE_STRING => {
+                let n = self.read_u32()? as usize;
+                Geometry::line_string(self.read_coords(n)?)
+            }
+            WKB_POLYGON => {
+                let rings = (0..self.read_u32()?)
+                    .map(|_| {
+                        let n = self.read_u32()? as usize;
+                        self.read_coords(n)
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                Geometry::polygon(rings)
+            }
+            t => Err(invalid(format!("unsupported WKB geometry type {}", t))),
+        }
+    }
+}
+
+fn midpoint(a: Coord, b: Coord) -> Coord {
+    ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0)
+}
+
+fn cross(o: Coord, a: Coord, b: Coord) -> f64 {
+    (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+}
+
+fn point_segment_distance(p: Coord, (a, b): (Coord, Coord)) -> f64 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let len2 = dx * dx + dy * dy;
+    let t = if len2 == 0.0 {
+        0.0
+    } else {
+        (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / len2).clamp(0.0, 1.0)
+    };
+    let (x, y) = (a.0 + t * dx, a.1 + t * dy);
+    ((p.0 - x).powi(2) + (p.1 - y).powi(2)).sqrt()
+}
+
+/// Whether the segments cross at a single point in the interior of both.
+fn segments_cross_properly((a, b): (Coord, Coord), (c, d): (Coord, Coord)) -> bool {
+    let (d1, d2) = (cross(a, b, c), cross(a, b, d));
+    let (d3, d4) = (cross(c, d, a), cross(c, d, b));
+    d1 * d2 < 0.0 && d3 * d4 < 0.0
+}
+
+fn segment_distance(s: (Coord, Coord), t: (Coord, Coord)) -> f64 {
+    if segments_cross_properly(s, t) {
+        return 0.0;
+    }
+    [
+        point_segment_distance(s.0, t),
+        point_segment_distance(s.1, t),
+        point_segment_distance(t.0, s),
+        point_segment_distance(t.1, s),
+    ]
+    .into_iter()
+    .fold(f64::INFINITY, f64::min)
+}
+
+/// Whether `p` is inside the closed ring, by casting a ray to the right.
+fn ring_contains(ring: &[Coord], p: Coord) -> bool {
+    let mut inside = false;
+    for w in ring.windows(2) {
+        let (a, b) = (w[0], w[1]);
+        if (a.1 > p.1) != (b.1 > p.1) && p.0 < a.0 + (p.1 - a.1) * (b.0 - a.0) / (b.1 - a.1) {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+/// Encodes a longitude and latitude in a geohash of `precision` characters.
+pub fn geohash((lon, lat): Coord, precision: usize) -> String {
+    let (mut lon_range, mut lat_range) = ((-180.0, 180.0), (-90.0, 90.0));
+    let mut hash = String::with_capacity(precision);
+    let mut even = true;
+    let (mut bits, mut ch) = (0, 0);
+    while hash.len() < precision {
+        let (range, value) = if even {
+            (&mut lon_range, lon)
+        } else {
+            (&mut lat_range, lat)
+        };
+        let mid = (range.0 + range.1) / 2.0;
+        ch <<= 1;
+        if value >= mid {
+            ch |= 1;
+            range.0 = mid;
+        } else {
+            range.1 = mid;
+        }
+        even = !even;
+        bits += 1;
+        if bits == 5 {
+            hash.push(GEOHASH_BASE32[ch] as char);
+            bits = 0;
+            ch = 0;
+        }
+    }
+    hash
+}
+
+/// `ST_GeomFromText(wkt)`: validates and normalizes a geometry in the WKT format.
+pub fn st_geom_from_text(wkt: &str, writer: BytesWriter) -> Result<BytesGuard> {
+    let geometry = Geometry::from_wkt(wkt)?;
+    writer.write_ref(&geometry.to_string()).map_err(Into::into)
+}
+
+/// `ST_AsText(geom)`.
+pub fn st_as_text(geom: &str, writer: BytesWriter) -> Result<BytesGuard> {
+    st_geom_from_text(geom, writer)
+}
+
+/// `ST_GeomFromWKB(hex)`: decodes a geometry in the WKB format, encoded in hex.
+pub fn st_geom_from_wkb(hex: &str, writer: BytesWriter) -> Result<BytesGuard> {
+    let geometry = Geometry::from_wkb_hex(hex)?;
+    writer.write_ref(&geometry.to_string()).map_err(Into::into)
+}
+
+/// `ST_AsBinary(geom)`: encodes a geometry in the WKB format, in hex since there is no `BYTEA`.
+pub fn st_as_binary(geom: &str, writer: BytesWriter) -> Result<BytesGuard> {
+    let geometry = Geometry::from_wkt(geom)?;
+    writer.write_ref(&geometry.to_wkb_hex()).map_err(Into::into)
+}
+
+/// `ST_Point(x, y)`.
+pub fn st_point(x: OrderedF64, y: OrderedF64, writer: BytesWriter) -> Result<BytesGuard> {
+    if !x.0.is_finite() || !y.0.is_finite() {
+        return Err(invalid(format!("invalid coordinate {} {}", x.0, y.0)));
+    }
+    writer
+        .write_ref(&Geometry::Point((x.0, y.0)).to_string())
+        .map_err(Into::into)
+}
+
+/// `ST_Distance(a, b)`: the minimum Cartesian distance between the geometries.
+pub fn st_distance(a: &str, b: &str) -> Result<OrderedF64> {
+    let (a, b) = (Geometry::from_wkt(a)?, Geometry::from_wkt(b)?);
+    Ok(a.distance(&b).into())
+}
+
+/// `ST_DistanceSphere(a, b)`: the great-circle distance in meters between two points of longitude
+/// and latitude in degrees.
+pub fn st_distance_sphere(a: &str, b: &str) -> Result<OrderedF64> {
+    let (Geometry::Point((lon1, lat1)), Geometry::Point((lon2, lat2))) =
+        (Geometry::from_wkt(a)?, Geometry::from_wkt(b)?)
+    else {
+        return Err(invalid("ST_DistanceSphere only supports points"));
+    };
+    let (lat1, lat2) = (lat1.to_radians(), lat2.to_radians());
+    let (d_lat, d_lon) = (lat2 - lat1, (lon2 - lon1).to_radians());
+    let h = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.0).sin().powi(2);
+    Ok((2.0 * EARTH_RADIUS * h.sqrt().min(1.0).asin()).into())
+}
+
+/// `ST_Contains(a, b)`.
+pub fn st_contains(a: &str, b: &str) -> Result<bool> {
+    let (a, b) = (Geometry::from_wkt(a)?, Geometry::from_wkt(b)?);
+    Ok(a.contains(&b))
+}
+
+/// `ST_DWithin(a, b, distance)`: whether the Cartesian distance between the geometries is at most
+/// `distance`, e.g. for geofencing.
+pub fn st_dwithin(a: &str, b: &str, distance: OrderedF64) -> Result<bool> {
+    Ok(st_distance(a, b)? <= distance)
+}
+
+/// `ST_GeoHash(geom, precision)`: the geohash of a point, or of the center of the bounding box of
+/// other geometries, which buckets nearby geometries together by a common prefix.
+pub fn st_geohash(geom: &str, precision: i32, writer: BytesWriter) -> Result<BytesGuard> {
+    if !(1..=20).contains(&precision) {
+        return Err(ExprError::InvalidParam {
+            name: "precision",
+            reason: format!(
+                "geohash precision must be between 1 and 20, got {}",
+                precision
+            ),
+        });
+    }
+    let center = Geometry::from_wkt(geom)?.center();
+    writer
+        .write_ref(&geohash(center, precision as usize))
+        .map_err(Into::into)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn geom(wkt: &str) -> Geometry {
+        Geometry::from_wkt(wkt).unwrap()
+    }
+
+    #[test]
+    fn test_wkt_and_wkb() {
+        let square = geom("polygon (( 0 0, 4 0, 4 4, 0 4, 0 0 ), (1 1, 2 1, 2 2, 1 1))");
+        assert_eq!(
+            square.to_string(),
+            "POLYGON((0 0,4 0,4 4,0 4,0 0),(1 1,2 1,2 2,1 1))"
+        );
+        assert_eq!(
+            geom("LINESTRING(0 0, 1.5 -2)").to_string(),
+            "LINESTRING(0 0,1.5 -2)"
+        );
+
+        let point = geom("POINT(1 2)");
+        assert_eq!(
+            point.to_wkb_hex(),
+            "0101000000000000000000f03f0000000000000040"
+        );
+        // Big endian.
+        assert_eq!(
+            Geometry::from_wkb_hex("00000000013ff00000000000004000000000000000").unwrap(),
+            point
+        );
+        assert_eq!(
+            Geometry::from_wkb_hex(&square.to_wkb_hex()).unwrap(),
+            square
+        );
+
+        assert!(Geometry::from_wkt("POINT(1)").is_err());
+        assert!(Geometry::from_wkt("LINESTRING(0 0)").is_err());
+        assert!(Geometry::from_wkt("POLYGON((0 0, 1 0, 1 1))").is_err());
+        assert!(Geometry::from_wkt("CIRCLE(0 0, 1)").is_err());
+        assert!(Geometry::from_wkb_hex("0101").is_err());
+    }
+
+    #[test]
+    fn test_distance_and_contains() {
+        let fence = "POLYGON((0 0,4 0,4 4,0 4,0 0),(1 1,2 1,2 2,1 2,1 1))";
+        assert_eq!(st_distance("POINT(0 0)", "POINT(3 4)").unwrap(), 5.0.into());
+        assert_eq!(st_distance("POINT(3 3)", fence).unwrap(), 0.0.into());
+        assert_eq!(st_distance("POINT(6 2)", fence).unwrap(), 2.0.into());
+        assert_eq!(st_distance("POINT(1.5 1.25)", fence).unwrap(), 0.25.into());
+        assert_eq!(
+            st_distance("LINESTRING(-1 2,5 2)", "LINESTRING(2 -1,2 5)").unwrap(),
+            0.0.into()
+        );
+
+        assert!(st_contains(fence, "POINT(3 3)").unwrap());
+        // In the hole, on the boundary and outside.
+        assert!(!st_contains(fence, "POINT(1.5 1.5)").unwrap());
+        assert!(!st_contains(fence, "POINT(4 2)").unwrap());
+        assert!(!st_contains(fence, "POINT(5 5)").unwrap());
+        assert!(st_contains(fence, "LINESTRING(3 0.5,3 3.5)").unwrap());
+        assert!(!st_contains(fence, "LINESTRING(0.5 1.5,3 1.5)").unwrap());
+        assert!(st_contains(fence, "POLYGON((2.5 2.5,3.5 2.5,3.5 3.5,2.5 2.5))").unwrap());
+        assert!(!st_contains(fence, "POLYGON((0.5 0.5,3 0.5,3 3,0.5 3,0.5 0.5))").unwrap());
+        assert!(st_contains("LINESTRING(0 0,2 2)", "POINT(1 1)").unwrap());
+        assert!(!st_contains("LINESTRING(0 0,2 2)", "POINT(0 0)").unwrap());
+
+        assert!(st_dwithin("POINT(6 2)", fence, 2.0.into()).unwrap());
+        assert!(!st_dwithin("POINT(6 2)", fence, 1.9.into()).unwrap());
+    }
+
+    #[test]
+    fn test_distance_sphere_and_geohash() {
+        // From London to Paris.
+        let distance = st_distance_sphere("POINT(-0.1278 51.5074)", "POINT(2.3522 48.8566)")
+            .unwrap()
+            .0;
+        assert!((distance - 343_556.0).abs() < 1_000.0);
+        assert!(st_distance_sphere("POINT(0 0)", "LINESTRING(0 0,1 1)").is_err());
+
+        assert_eq!(geohash((-5.6, 42.6), 5), "ezs42");
+        assert_eq!(
+            geom("POLYGON((-6 42,-5 42,-5 43,-6 42))").center(),
+            (-5.5, 42.5)
+        );
+    }
+}
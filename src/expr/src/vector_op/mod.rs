@@ -21,13 +21,18 @@ pub mod cast;
 pub mod cmp;
 pub mod concat_op;
 pub mod conjunction;
+pub mod distance;
 pub mod extract;
+pub mod format;
+pub mod fulltext;
+pub mod geo;
 pub mod length;
 pub mod like;
 pub mod lower;
 pub mod ltrim;
 pub mod md5;
 pub mod position;
+pub mod regexp;
 pub mod repeat;
 pub mod replace;
 pub mod round;
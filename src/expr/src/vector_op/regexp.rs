@@ -0,0 +1,194 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! POSIX regular expression functions of PostgreSQL, e.g. `regexp_match` and `regexp_replace`.
+
+use regex::{Regex, RegexBuilder};
+use risingwave_common::array::ListValue;
+use risingwave_common::types::{Datum, ScalarImpl};
+
+use crate::{ExprError, Result};
+
+/// A compiled regular expression together with the flags of a function call.
+#[derive(Debug, Clone)]
+pub struct RegexpContext {
+    pub regex: Regex,
+    /// Whether to replace all the matches instead of the first one, given by the `g` flag.
+    pub global: bool,
+}
+
+impl RegexpContext {
+    /// Compiles `pattern` with the flags supported by PostgreSQL:
+    /// - `i`: case-insensitive matching, and `c` for case-sensitive matching (the default).
+    /// - `n`: newline-sensitive matching, where `.` doesn't match newlines and `^`, `$` match at
+    ///   the beginning and the end of each line.
+    /// - `x`: ignore whitespaces and comments in the pattern.
+    /// - `g`: replace all the matches. Only allowed in `regexp_replace`.
+    pub fn new(pattern: &str, flags: &str) -> Result<Self> {
+        let mut builder = RegexBuilder::new(pattern);
+        // Unlike most regex engines, `.` matches newlines by default in PostgreSQL.
+        builder.dot_matches_new_line(true);
+        let mut global = false;
+        for flag in flags.chars() {
+            match flag {
+                'i' => {
+                    builder.case_insensitive(true);
+                }
+                'c' => {
+                    builder.case_insensitive(false);
+                }
+                'n' => {
+                    builder.dot_matches_new_line(false).multi_line(true);
+                }
+                'x' => {
+                    builder.ignore_whitespace(true);
+                }
+                'g' => global = true,
+                _ => {
+                    return Err(ExprError::InvalidParam {
+                        name: "flags",
+                        reason: format!("invalid regular expression option: \"{}\"", flag),
+                    })
+                }
+            }
+        }
+        let regex = builder.build().map_err(|e| ExprError::InvalidParam {
+            name: "pattern",
+            reason: e.to_string(),
+        })?;
+        Ok(Self { regex, global })
+    }
+}
+
+/// Returns the captured substrings of the first match, or the whole match if the pattern has no
+/// parenthesized subexpressions. Returns `None` if there is no match.
+pub fn regexp_match(text: &str, ctx: &RegexpContext) -> Option<ListValue> {
+    let captures = ctx.regex.captures(text)?;
+    let values: Vec<Datum> = if captures.len() == 1 {
+        vec![Some(ScalarImpl::Utf8(captures[0].to_string()))]
+    } else {
+        captures
+            .iter()
+            .skip(1)
+            .map(|m| m.map(|m| ScalarImpl::Utf8(m.as_str().to_string())))
+            .collect()
+    };
+    Some(ListValue::new(values))
+}
+
+/// Replaces the first match, or all the matches with the `g` flag, with `replacement`, in which
+/// `\n` refers to the `n`-th parenthesized subexpression and `\&` refers to the whole match.
+pub fn regexp_replace(text: &str, ctx: &RegexpContext, replacement: &str) -> String {
+    let replacement = to_regex_replacement(replacement);
+    if ctx.global {
+        ctx.regex
+            .replace_all(text, replacement.as_str())
+            .into_owned()
+    } else {
+        ctx.regex.replace(text, replacement.as_str()).into_owned()
+    }
+}
+
+/// `SUBSTRING(text FROM pattern)` returns the substring of the first parenthesized subexpression,
+/// or the whole match if the pattern has none.
+pub fn regexp_substr(text: &str, ctx: &RegexpContext) -> Option<String> {
+    let captures = ctx.regex.captures(text)?;
+    let m = if captures.len() == 1 {
+        captures.get(0)
+    } else {
+        captures.get(1)
+    };
+    m.map(|m| m.as_str().to_string())
+}
+
+/// Translates a replacement string of PostgreSQL into the syntax of the `regex` crate.
+fn to_regex_replacement(replacement: &str) -> String {
+    let mut result = String::with_capacity(replacement.len());
+    let mut chars = replacement.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '$' => result.push_str("$$"),
+            '\\' => match chars.peek() {
+                Some(&d) if d.is_ascii_digit() => {
+                    chars.next();
+                    result.push_str(&format!("${{{}}}", d));
+                }
+                Some('&') => {
+                    chars.next();
+                    result.push_str("${0}");
+                }
+                Some('\\') => {
+                    chars.next();
+                    result.push('\\');
+                }
+                _ => result.push('\\'),
+            },
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf8_list(values: &[Option<&str>]) -> ListValue {
+        ListValue::new(
+            values
+                .iter()
+                .map(|v| v.map(|v| ScalarImpl::Utf8(v.to_string())))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_regexp_match() {
+        let ctx = RegexpContext::new("(b)(x)?(c)", "").unwrap();
+        assert_eq!(
+            regexp_match("abcbc", &ctx),
+            Some(utf8_list(&[Some("b"), None, Some("c")]))
+        );
+        let ctx = RegexpContext::new("B.C", "i").unwrap();
+        assert_eq!(
+            regexp_match("ab\ncd", &ctx),
+            Some(utf8_list(&[Some("b\nc")]))
+        );
+        let ctx = RegexpContext::new("B.C", "in").unwrap();
+        assert_eq!(regexp_match("ab\ncd", &ctx), None);
+        assert!(RegexpContext::new("a", "z").is_err());
+        assert!(RegexpContext::new("(a", "").is_err());
+    }
+
+    #[test]
+    fn test_regexp_replace() {
+        let ctx = RegexpContext::new("(\\w+)@(\\w+)", "").unwrap();
+        assert_eq!(
+            regexp_replace("a@b c@d", &ctx, "\\2 at \\1 ($5)"),
+            "b at a ($5) c@d"
+        );
+        let ctx = RegexpContext::new("[aeiou]", "g").unwrap();
+        assert_eq!(regexp_replace("regexp", &ctx, "<\\&>"), "r<e>g<e>xp");
+    }
+
+    #[test]
+    fn test_regexp_substr() {
+        let ctx = RegexpContext::new("o.b", "").unwrap();
+        assert_eq!(regexp_substr("foobar", &ctx), Some("oob".to_string()));
+        let ctx = RegexpContext::new("o(.)b", "").unwrap();
+        assert_eq!(regexp_substr("foobar", &ctx), Some("o".to_string()));
+        let ctx = RegexpContext::new("x", "").unwrap();
+        assert_eq!(regexp_substr("foobar", &ctx), None);
+    }
+}
@@ -0,0 +1,380 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Structured audit and slow query log of the frontend.
+//!
+//! After a statement finishes, [`AuditLogger::log`] checks it against [`AuditLogConfig`]. Slow
+//! queries, DDLs and failed statements are written as one JSON object per line to the
+//! `risingwave_audit` tracing target, and the most recent records are kept in memory so that they
+//! can be queried from the `rw_audit_log` system table. Passwords and secret options in the
+//! recorded SQL are redacted by [`redact_sql`].
+
+use std::borrow::Cow;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use parking_lot::Mutex;
+use risingwave_common::config::AuditLogConfig;
+use risingwave_sqlparser::ast::{AstString, CreateUserOption, SqlOption, Statement, Value};
+use serde::{Deserialize, Serialize};
+
+use crate::catalog::dump::{is_secret, REDACTED};
+use crate::scheduler::Query;
+
+/// Tracing target of the audit log, so that it can be routed to a separate file by the logging
+/// configuration.
+pub const AUDIT_LOG_TARGET: &str = "risingwave_audit";
+
+/// Why a statement is recorded in the audit log.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditLogKind {
+    SlowQuery,
+    Ddl,
+    Failed,
+}
+
+impl AuditLogKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditLogKind::SlowQuery => "slow_query",
+            AuditLogKind::Ddl => "ddl",
+            AuditLogKind::Failed => "failed",
+        }
+    }
+}
+
+/// Stats of one stage of a batch query.
+#[derive(Clone, Debug, Serialize)]
+pub struct StageAuditInfo {
+    pub stage_id: u32,
+    pub parallelism: u32,
+    pub has_table_scan: bool,
+}
+
+/// Plan related information of a batch query, collected by the query handler.
+#[derive(Clone, Debug, Serialize)]
+pub struct QueryAuditInfo {
+    pub query_id: String,
+    pub plan_hash: u64,
+    pub stages: Vec<StageAuditInfo>,
+}
+
+impl QueryAuditInfo {
+    pub fn new(query: &Query, explained_plan: &str) -> Self {
+        let mut hasher = DefaultHasher::new();
+        explained_plan.hash(&mut hasher);
+        let mut stages = query
+            .stage_graph
+            .stages
+            .values()
+            .map(|stage| StageAuditInfo {
+                stage_id: stage.id,
+                parallelism: stage.parallelism,
+                has_table_scan: stage.has_table_scan,
+            })
+            .collect::<Vec<_>>();
+        stages.sort_by_key(|stage| stage.stage_id);
+        Self {
+            query_id: query.query_id().id.clone(),
+            plan_hash: hasher.finish(),
+            stages,
+        }
+    }
+}
+
+/// One line of the audit log.
+#[derive(Clone, Debug, Serialize)]
+pub struct AuditLogRecord {
+    pub kind: AuditLogKind,
+    /// Milliseconds since unix epoch when the statement finished.
+    pub logged_at_ms: u64,
+    pub user: String,
+    pub database: String,
+    pub duration_ms: u64,
+    pub sql: String,
+    pub error: Option<String>,
+    #[serde(flatten)]
+    pub query: Option<QueryAuditInfo>,
+}
+
+/// Information of a finished statement, which is passed to [`AuditLogger::log`].
+pub struct StatementAudit<'a> {
+    pub user: &'a str,
+    pub database: &'a str,
+    pub sql: &'a str,
+    pub is_ddl: bool,
+    pub duration: Duration,
+    pub error: Option<String>,
+    pub query: Option<QueryAuditInfo>,
+}
+
+pub struct AuditLogger {
+    config: AuditLogConfig,
+    /// Most recent records, capped by `system_table_capacity`.
+    records: Mutex<VecDeque<AuditLogRecord>>,
+}
+
+pub type AuditLoggerRef = Arc<AuditLogger>;
+
+impl AuditLogger {
+    pub fn new(config: AuditLogConfig) -> Self {
+        Self {
+            records: Mutex::new(VecDeque::with_capacity(config.system_table_capacity)),
+            config,
+        }
+    }
+
+    /// Returns the reason to record the statement, or `None` if it should not be recorded.
+    /// Failures take precedence over DDLs, and DDLs over slow queries.
+    fn classify(&self, audit: &StatementAudit<'_>) -> Option<AuditLogKind> {
+        if audit.error.is_some() && self.config.log_failed_statements {
+            Some(AuditLogKind::Failed)
+        } else if audit.is_ddl && self.config.log_ddl {
+            Some(AuditLogKind::Ddl)
+        } else if self.config.slow_query_threshold_ms > 0
+            && audit.duration >= Duration::from_millis(self.config.slow_query_threshold_ms)
+        {
+            Some(AuditLogKind::SlowQuery)
+        } else {
+            None
+        }
+    }
+
+    /// Records the statement if it matches the config, and returns the reason it's recorded for.
+    pub fn log(&self, audit: StatementAudit<'_>) -> Option<AuditLogKind> {
+        let kind = self.classify(&audit)?;
+        let record = AuditLogRecord {
+            kind,
+            logged_at_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+            user: audit.user.to_string(),
+            database: audit.database.to_string(),
+            duration_ms: audit.duration.as_millis() as u64,
+            sql: audit.sql.to_string(),
+            error: audit.error,
+            query: audit.query,
+        };
+        match serde_json::to_string(&record) {
+            Ok(line) => tracing::info!(target: AUDIT_LOG_TARGET, "{}", line),
+            Err(e) => tracing::warn!("failed to serialize audit log record: {}", e),
+        }
+
+        let capacity = self.config.system_table_capacity;
+        if capacity > 0 {
+            let mut records = self.records.lock();
+            if records.len() >= capacity {
+                records.pop_front();
+            }
+            records.push_back(record);
+        }
+        Some(kind)
+    }
+
+    /// Returns the records kept in memory, from the oldest to the newest.
+    pub fn records(&self) -> Vec<AuditLogRecord> {
+        self.records.lock().iter().cloned().collect()
+    }
+}
+
+impl Default for AuditLogger {
+    fn default() -> Self {
+        Self::new(AuditLogConfig::default())
+    }
+}
+
+/// Whether the statement changes the catalog.
+pub fn is_ddl(stmt: &Statement) -> bool {
+    matches!(
+        stmt,
+        Statement::CreateSource { .. }
+            | Statement::CreateTable { .. }
+            | Statement::CreateDatabase { .. }
+            | Statement::CreateSchema { .. }
+            | Statement::CreateUser(_)
+            | Statement::CreateView { .. }
+            | Statement::CreateIndex { .. }
+            | Statement::CreateSubscription { .. }
+            | Statement::CreateSink { .. }
+            | Statement::AlterTable { .. }
+            | Statement::AlterSystem { .. }
+            | Statement::CreateExternalTable { .. }
+            | Statement::CreateExternalSchema { .. }
+            | Statement::Comment { .. }
+            | Statement::Drop(_)
+            | Statement::Grant { .. }
+            | Statement::Revoke { .. }
+            | Statement::AlterUser { .. }
+            | Statement::AlterMaterializedView { .. }
+            | Statement::SavepointMaterializedView { .. }
+    )
+}
+
+/// Returns the SQL of the statement to be recorded, with the passwords of users and the values of
+/// secret options redacted as [`REDACTED`] like in the catalog dump. The statement is rendered
+/// again only if it may hold secrets, otherwise `sql` is returned as is.
+pub fn redact_sql<'a>(stmt: &Statement, sql: &'a str) -> Cow<'a, str> {
+    if !matches!(
+        stmt,
+        Statement::CreateUser(_)
+            | Statement::CreateTable { .. }
+            | Statement::CreateView { .. }
+            | Statement::CreateSource { .. }
+            | Statement::CreateSink { .. }
+            | Statement::CreateSubscription { .. }
+    ) {
+        return Cow::Borrowed(sql);
+    }
+    let mut stmt = stmt.clone();
+    let options: &mut [SqlOption] = match &mut stmt {
+        Statement::CreateUser(stmt) => {
+            for option in &mut stmt.with_options.0 {
+                match option {
+                    CreateUserOption::Password(Some(password))
+                    | CreateUserOption::EncryptedPassword(password) => {
+                        *password = AstString(REDACTED.to_string())
+                    }
+                    _ => {}
+                }
+            }
+            &mut []
+        }
+        Statement::CreateTable {
+            table_properties,
+            with_options,
+            ..
+        } => {
+            redact_options(table_properties);
+            with_options
+        }
+        Statement::CreateView { with_options, .. } => with_options,
+        Statement::CreateSource { stmt, .. } => &mut stmt.with_properties.0,
+        Statement::CreateSink { stmt } => &mut stmt.with_properties.0,
+        Statement::CreateSubscription { stmt } => &mut stmt.with_properties.0,
+        _ => unreachable!(),
+    };
+    redact_options(options);
+    Cow::Owned(stmt.to_string())
+}
+
+fn redact_options(options: &mut [SqlOption]) {
+    for option in options {
+        if is_secret(&option.name.value) {
+            option.value = Value::SingleQuotedString(REDACTED.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_sqlparser::parser::Parser;
+
+    use super::*;
+
+    fn audit(is_ddl: bool, duration_ms: u64, error: Option<&str>) -> StatementAudit<'static> {
+        StatementAudit {
+            user: "root",
+            database: "dev",
+            sql: "select 1",
+            is_ddl,
+            duration: Duration::from_millis(duration_ms),
+            error: error.map(str::to_string),
+            query: None,
+        }
+    }
+
+    #[test]
+    fn test_classify() {
+        let logger = AuditLogger::new(AuditLogConfig {
+            slow_query_threshold_ms: 100,
+            log_ddl: true,
+            log_failed_statements: true,
+            system_table_capacity: 2,
+            replay_capture_location: String::new(),
+        });
+        assert_eq!(logger.classify(&audit(false, 10, None)), None);
+        assert_eq!(
+            logger.classify(&audit(false, 100, None)),
+            Some(AuditLogKind::SlowQuery)
+        );
+        assert_eq!(
+            logger.classify(&audit(true, 10, None)),
+            Some(AuditLogKind::Ddl)
+        );
+        assert_eq!(
+            logger.classify(&audit(true, 10, Some("err"))),
+            Some(AuditLogKind::Failed)
+        );
+    }
+
+    #[test]
+    fn test_records_capacity() {
+        let logger = AuditLogger::new(AuditLogConfig {
+            slow_query_threshold_ms: 0,
+            log_ddl: true,
+            log_failed_statements: false,
+            system_table_capacity: 2,
+            replay_capture_location: String::new(),
+        });
+        for duration_ms in 0..3 {
+            logger.log(audit(true, duration_ms, Some("ignored")));
+        }
+        let records = logger.records();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].duration_ms, 1);
+        assert_eq!(records[1].kind, AuditLogKind::Ddl);
+    }
+
+    #[test]
+    fn test_is_ddl() {
+        let ddl = |sql: &str| is_ddl(&Parser::parse_sql(sql).unwrap().remove(0));
+        assert!(ddl("CREATE TABLE t (v INT)"));
+        assert!(ddl("CREATE SINK snk FROM mv WITH ('sink' = 'mysql')"));
+        assert!(ddl("ALTER TABLE t ADD COLUMN v2 INT"));
+        assert!(ddl("ALTER SYSTEM SET barrier_interval_ms = 500"));
+        assert!(ddl("CREATE EXTERNAL TABLE lake STORED AS PARQUET LOCATION 's3://bucket/events/'"));
+        assert!(ddl("CREATE EXTERNAL SCHEMA lake STORED AS DELTA LOCATION 's3://bucket/wh/'"));
+        assert!(ddl("COMMENT ON TABLE t IS 'events'"));
+        assert!(ddl("ALTER TABLE events DROP PARTITION '2022-07-01 00:00:00'"));
+        assert!(ddl("DROP TABLE t"));
+        assert!(!ddl("INSERT INTO t VALUES (1)"));
+        assert!(!ddl("SELECT * FROM t"));
+    }
+
+    #[test]
+    fn test_redact_sql() {
+        let redact = |sql: &str| {
+            let stmt = Parser::parse_sql(sql).unwrap().remove(0);
+            redact_sql(&stmt, sql).into_owned()
+        };
+        assert_eq!(
+            redact("CREATE USER alice WITH LOGIN PASSWORD 'secret'"),
+            "CREATE USER alice WITH LOGIN PASSWORD '[REDACTED]'"
+        );
+        assert_eq!(
+            redact(
+                "CREATE SOURCE s WITH ('kafka.topic' = 'events', \
+                 'properties.sasl.password' = 'secret') ROW FORMAT JSON"
+            ),
+            "CREATE SOURCE s WITH ('kafka.topic' = 'events', \
+             'properties.sasl.password' = '[REDACTED]') ROW FORMAT JSON"
+        );
+        assert_eq!(redact("select 'secret'"), "select 'secret'");
+    }
+}
@@ -0,0 +1,361 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Auto-prepare: statements only differing in their constants, e.g. point queries issued by a
+//! serving workload, share a generic plan, into which the constants of each execution are bound.
+//! It applies to the statements of the extended query protocol as well, since their parameters are
+//! substituted into the statement as constants.
+//!
+//! The constants are found from the tokens of the statement. The first time a statement is
+//! generalized, it's planned again with different constants, i.e. a probe, and the two plans are
+//! compared. The plan is only generic if both plans have the same shape, and every constant that
+//! differs between them is traced back to a constant of the statement. This rejects the statements
+//! whose constants decide the plan, like `LIMIT 10` or contradicting point lookups, which are
+//! always planned as they are until the next DDL.
+
+use std::sync::Arc;
+
+use itertools::Itertools;
+use risingwave_common::types::{DataType, ScalarImpl};
+use risingwave_pb::batch_plan::plan_node::NodeBody;
+use risingwave_pb::expr::expr_node::RexNode;
+use risingwave_pb::expr::ExprNode;
+use risingwave_sqlparser::ast::Statement;
+use risingwave_sqlparser::parser::Parser;
+use risingwave_sqlparser::tokenizer::{Token, Tokenizer};
+
+use crate::binder::expr::number_literal;
+use crate::expr::{Expr, Literal};
+use crate::plan_cache::CachedPlan;
+use crate::scheduler::{ExecutionPlanNode, Query, StageId};
+
+/// Where a parameter is bound in a generic plan: the `index`-th constant of the node at `path`,
+/// i.e. the child indices from the root, in stage `stage_id`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParamSlot {
+    stage_id: StageId,
+    path: Vec<usize>,
+    index: usize,
+    param: usize,
+}
+
+/// A statement with its constants parameterized.
+pub struct ParameterizedSql {
+    /// The statement with each constant replaced by `$n`.
+    pub generic_sql: String,
+    tokens: Vec<Token>,
+    /// The token index and the bound value of each constant.
+    params: Vec<(usize, Literal)>,
+}
+
+/// Parameterizes the numbers and strings in the statement. Returns `None` if there are none.
+pub fn parameterize(sql: &str) -> Option<ParameterizedSql> {
+    let tokens = Tokenizer::new(sql).tokenize().ok()?;
+    let mut generic_tokens = vec![];
+    let mut params = vec![];
+    for (idx, token) in tokens.iter().enumerate() {
+        let literal = match token {
+            Token::Whitespace(_) => continue,
+            Token::Number(s, false) => number_literal(s).ok(),
+            Token::SingleQuotedString(s) => Some(Literal::new(
+                Some(ScalarImpl::Utf8(s.clone())),
+                DataType::Varchar,
+            )),
+            _ => None,
+        };
+        match literal {
+            Some(literal) => {
+                params.push((idx, literal));
+                generic_tokens.push(format!("${}", params.len()));
+            }
+            None => generic_tokens.push(token.to_string()),
+        }
+    }
+    if params.is_empty() {
+        return None;
+    }
+    Some(ParameterizedSql {
+        generic_sql: generic_tokens.join(" "),
+        tokens,
+        params,
+    })
+}
+
+impl ParameterizedSql {
+    /// Returns the statement with a different value for each constant, which has the same type as
+    /// the original one. Returns `None` if it fails to parse.
+    pub fn probe_statement(&self) -> Option<Statement> {
+        let mut tokens = self.tokens.clone();
+        for (i, (idx, literal)) in self.params.iter().enumerate() {
+            tokens[*idx] = probe_token(&tokens[*idx], literal, i + 1)?;
+        }
+        Parser::new(tokens).parse_statement().ok()
+    }
+
+    /// Compares the plan of the statement with the plan of [`Self::probe_statement`], and returns
+    /// where the parameters are bound in the plan, or `None` if the plan depends on the constants.
+    pub fn find_param_slots(&self, query: &Query, probe: &Query) -> Option<Vec<ParamSlot>> {
+        let probe_params = self.probe_statement_params()?;
+        let (graph, probe_graph) = (&query.stage_graph, &probe.stage_graph);
+        if graph.root_stage_id != probe_graph.root_stage_id
+            || graph.stages.len() != probe_graph.stages.len()
+        {
+            return None;
+        }
+        let mut slots = vec![];
+        for (stage_id, stage) in &graph.stages {
+            let probe_stage = probe_graph.stages.get(stage_id)?;
+            if graph.get_child_stages(stage_id) != probe_graph.get_child_stages(stage_id)
+                || stage.exchange_info != probe_stage.exchange_info
+                || stage.parallelism != probe_stage.parallelism
+                || stage.has_table_scan != probe_stage.has_table_scan
+            {
+                return None;
+            }
+            let mut diff = PlanDiff {
+                stage_id: *stage_id,
+                path: vec![],
+                params: &self.params,
+                probe_params: &probe_params,
+                slots: &mut slots,
+            };
+            diff.diff_node(&stage.root, &probe_stage.root)?;
+        }
+        // Every constant must be bound somewhere, or it might have been folded into the plan.
+        if (0..self.params.len()).any(|param| slots.iter().all(|slot| slot.param != param)) {
+            return None;
+        }
+        Some(slots)
+    }
+
+    fn probe_statement_params(&self) -> Option<Vec<Literal>> {
+        self.params
+            .iter()
+            .enumerate()
+            .map(
+                |(i, (idx, literal))| match probe_token(&self.tokens[*idx], literal, i + 1)? {
+                    Token::Number(s, _) => number_literal(&s).ok(),
+                    Token::SingleQuotedString(s) => {
+                        Some(Literal::new(Some(ScalarImpl::Utf8(s)), DataType::Varchar))
+                    }
+                    _ => unreachable!(),
+                },
+            )
+            .collect()
+    }
+
+    /// Binds the constants of the statement into the generic plan. Returns `None` if they don't
+    /// have the types of the parameters, e.g. `1` of a parameter planned as `2147483648`.
+    pub fn bind(&self, plan: &CachedPlan) -> Option<Arc<CachedPlan>> {
+        if self.params.len() != plan.param_types.len()
+            || self
+                .params
+                .iter()
+                .zip_eq(&plan.param_types)
+                .any(|((_, literal), param_type)| &literal.return_type() != param_type)
+        {
+            return None;
+        }
+        let mut query = plan.query.clone_with_new_id();
+        for slot in &plan.param_slots {
+            let stage = query.stage_graph.stages.get_mut(&slot.stage_id).unwrap();
+            let stage = Arc::get_mut(stage).unwrap();
+            let mut node = Arc::make_mut(&mut stage.root);
+            for child in &slot.path {
+                node = Arc::make_mut(&mut node.children[*child]);
+            }
+            let mut constants = vec![];
+            visit_constants(&mut node.node, &mut |expr| constants.push(expr));
+            *constants[slot.index] = self.params[slot.param].1.to_expr_proto();
+        }
+        Some(Arc::new(CachedPlan {
+            query,
+            schema: plan.schema.clone(),
+            explained_plan: plan.explained_plan.clone(),
+            hints: plan.hints.clone(),
+            worker_node_count: plan.worker_node_count,
+            param_types: plan.param_types.clone(),
+            param_slots: plan.param_slots.clone(),
+        }))
+    }
+
+    pub fn param_types(&self) -> Vec<DataType> {
+        self.params
+            .iter()
+            .map(|(_, literal)| literal.return_type())
+            .collect()
+    }
+}
+
+/// Returns a token of a different value of the same type for the `n`-th constant, which is also
+/// different from the ones of the other constants of the same value.
+fn probe_token(token: &Token, literal: &Literal, n: usize) -> Option<Token> {
+    let number = |s: String| Some(Token::Number(s, false));
+    match (token, literal.get_data()) {
+        (_, Some(ScalarImpl::Int32(v))) => {
+            let n = n as i32;
+            number(v.checked_add(n).unwrap_or(v - n).to_string())
+        }
+        (_, Some(ScalarImpl::Int64(v))) => {
+            let n = n as i64;
+            number(v.checked_add(n).unwrap_or(v - n).to_string())
+        }
+        (Token::Number(s, _), Some(ScalarImpl::Decimal(_))) if s.contains('.') => {
+            number(format!("{}{}", s, n))
+        }
+        (Token::Number(s, _), Some(ScalarImpl::Decimal(_))) => number(format!("{}.{}", s, n)),
+        (Token::SingleQuotedString(s), _) => {
+            Some(Token::SingleQuotedString(format!("{}{}", s, "_".repeat(n))))
+        }
+        _ => None,
+    }
+}
+
+struct PlanDiff<'a> {
+    stage_id: StageId,
+    path: Vec<usize>,
+    params: &'a [(usize, Literal)],
+    probe_params: &'a [Literal],
+    slots: &'a mut Vec<ParamSlot>,
+}
+
+impl PlanDiff<'_> {
+    fn diff_node(&mut self, node: &ExecutionPlanNode, probe: &ExecutionPlanNode) -> Option<()> {
+        if node.plan_node_type != probe.plan_node_type
+            || node.schema != probe.schema
+            || node.source_stage_id != probe.source_stage_id
+            || node.children.len() != probe.children.len()
+        {
+            return None;
+        }
+        let (mut body, mut probe_body) = (node.node.clone(), probe.node.clone());
+        let constants = take_constants(&mut body);
+        let probe_constants = take_constants(&mut probe_body);
+        if body != probe_body || constants.len() != probe_constants.len() {
+            return None;
+        }
+        for (index, (constant, probe_constant)) in
+            constants.iter().zip_eq(&probe_constants).enumerate()
+        {
+            if constant == probe_constant {
+                continue;
+            }
+            let param = (0..self.params.len())
+                .filter(|param| {
+                    &self.params[*param].1.to_expr_proto() == constant
+                        && &self.probe_params[*param].to_expr_proto() == probe_constant
+                })
+                .exactly_one()
+                .ok()?;
+            self.slots.push(ParamSlot {
+                stage_id: self.stage_id,
+                path: self.path.clone(),
+                index,
+                param,
+            });
+        }
+        for (i, (child, probe_child)) in node.children.iter().zip_eq(&probe.children).enumerate() {
+            self.path.push(i);
+            self.diff_node(child, probe_child)?;
+            self.path.pop();
+        }
+        Some(())
+    }
+}
+
+/// Takes all the constants out of the plan node, leaving the rest of the node to be compared.
+fn take_constants(body: &mut NodeBody) -> Vec<ExprNode> {
+    let mut constants = vec![];
+    visit_constants(body, &mut |expr| constants.push(std::mem::take(expr)));
+    constants
+}
+
+/// Visits the constants of the expressions in the plan node in a fixed order. Constants of other
+/// kinds of nodes are left in the node, so that plans differing in them are never generic.
+fn visit_constants<'a>(body: &'a mut NodeBody, f: &mut impl FnMut(&'a mut ExprNode)) {
+    let exprs: Vec<&mut ExprNode> = match body {
+        NodeBody::Project(node) => node.select_list.iter_mut().collect(),
+        NodeBody::Filter(node) => node.search_condition.iter_mut().collect(),
+        NodeBody::HashJoin(node) => node.condition.iter_mut().collect(),
+        NodeBody::NestedLoopJoin(node) => node.join_cond.iter_mut().collect(),
+        NodeBody::Values(node) => node
+            .tuples
+            .iter_mut()
+            .flat_map(|tuple| tuple.cells.iter_mut())
+            .collect(),
+        NodeBody::RowSeqScan(node) => match &mut node.scan_range {
+            Some(range) => range
+                .eq_conds
+                .iter_mut()
+                .chain(
+                    range
+                        .lower_bound
+                        .iter_mut()
+                        .flat_map(|b| b.value.iter_mut()),
+                )
+                .chain(
+                    range
+                        .upper_bound
+                        .iter_mut()
+                        .flat_map(|b| b.value.iter_mut()),
+                )
+                .collect(),
+            None => vec![],
+        },
+        _ => vec![],
+    };
+    for expr in exprs {
+        visit_expr_constants(expr, f);
+    }
+}
+
+fn visit_expr_constants<'a>(expr: &'a mut ExprNode, f: &mut impl FnMut(&'a mut ExprNode)) {
+    match &mut expr.rex_node {
+        Some(RexNode::Constant(_)) => f(expr),
+        Some(RexNode::FuncCall(call)) => {
+            for child in &mut call.children {
+                visit_expr_constants(child, f);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parameterize() {
+        let sql = parameterize("SELECT * FROM t WHERE k = 42 AND s = 'a''b'").unwrap();
+        assert_eq!(sql.generic_sql, "SELECT * FROM t WHERE k = $1 AND s = $2");
+        assert_eq!(sql.param_types(), vec![DataType::Int32, DataType::Varchar]);
+        assert_eq!(
+            parameterize("select * from t where k = 7")
+                .unwrap()
+                .generic_sql,
+            "select * from t where k = $1"
+        );
+        assert!(parameterize("SELECT * FROM t").is_none());
+    }
+
+    #[test]
+    fn test_probe_statement() {
+        let sql = parameterize("SELECT 1, 1, 2147483647, 1.5, 'a'").unwrap();
+        assert_eq!(
+            sql.probe_statement().unwrap().to_string(),
+            "SELECT 2, 3, 2147483644, 1.54, 'a_____'"
+        );
+    }
+}
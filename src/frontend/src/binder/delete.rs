@@ -35,7 +35,8 @@ impl Binder {
         source_name: ObjectName,
         selection: Option<Expr>,
     ) -> Result<BoundDelete> {
-        let (schema_name, table_name) = Self::resolve_table_name(source_name.clone())?;
+        let (schema_name, table_name) =
+            Self::resolve_table_name(&self.db_name, source_name.clone())?;
         let table_source = self.bind_table_source(source_name)?;
         if table_source.append_only {
             return Err(ErrorCode::BindError(
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use itertools::Itertools;
 use risingwave_common::error::{ErrorCode, Result};
 use risingwave_common::types::DataType;
 use risingwave_sqlparser::ast::{BinaryOperator, Expr};
@@ -49,13 +50,85 @@ impl Binder {
             BinaryOperator::PGBitwiseXor => ExprType::BitwiseXor,
             BinaryOperator::PGBitwiseShiftLeft => ExprType::BitwiseShiftLeft,
             BinaryOperator::PGBitwiseShiftRight => ExprType::BitwiseShiftRight,
+            BinaryOperator::PGTsMatch => ExprType::TsMatch,
+            BinaryOperator::PGL2Distance => ExprType::L2Distance,
+            BinaryOperator::PGNegativeInnerProduct => ExprType::NegativeInnerProduct,
+            // `<=>` is the cosine distance of vectors in pgvector.
+            BinaryOperator::Spaceship => ExprType::CosineDistance,
             BinaryOperator::Concat => return self.bind_concat_op(bound_left, bound_right),
 
             _ => return Err(ErrorCode::NotImplemented(format!("{:?}", op), 112.into()).into()),
         };
+        if let (ExprImpl::FunctionCall(left), ExprImpl::FunctionCall(right)) =
+            (&bound_left, &bound_right)
+            && left.get_expr_type() == ExprType::Row
+            && right.get_expr_type() == ExprType::Row
+            && matches!(
+                func_type,
+                ExprType::Equal
+                    | ExprType::NotEqual
+                    | ExprType::LessThan
+                    | ExprType::LessThanOrEqual
+                    | ExprType::GreaterThan
+                    | ExprType::GreaterThanOrEqual
+            )
+        {
+            return Self::bind_row_comparison(
+                func_type,
+                left.inputs().to_vec(),
+                right.inputs().to_vec(),
+            );
+        }
         Ok(FunctionCall::new(func_type, vec![bound_left, bound_right])?.into())
     }
 
+    /// Expands a comparison of two rows into comparisons of their fields. An ordering comparison
+    /// is lexicographic, e.g. `(a, b) > (x, y)` becomes `a >= x AND (a > x OR b > y)`, so that
+    /// the keyset predicate of a paginated query bounds a range scan along the primary key.
+    fn bind_row_comparison(
+        func_type: ExprType,
+        left: Vec<ExprImpl>,
+        right: Vec<ExprImpl>,
+    ) -> Result<ExprImpl> {
+        if left.len() != right.len() || left.is_empty() {
+            return Err(ErrorCode::BindError(
+                "unequal number of entries in row expressions".to_string(),
+            )
+            .into());
+        }
+        let (strict, non_strict) = match func_type {
+            ExprType::Equal | ExprType::NotEqual => {
+                let conjunction = match func_type {
+                    ExprType::Equal => ExprType::And,
+                    _ => ExprType::Or,
+                };
+                let mut fields = left.into_iter().zip_eq(right);
+                let (l, r) = fields.next().unwrap();
+                let mut expr: ExprImpl = FunctionCall::new(func_type, vec![l, r])?.into();
+                for (l, r) in fields {
+                    let field: ExprImpl = FunctionCall::new(func_type, vec![l, r])?.into();
+                    expr = FunctionCall::new(conjunction, vec![expr, field])?.into();
+                }
+                return Ok(expr);
+            }
+            ExprType::LessThan | ExprType::LessThanOrEqual => {
+                (ExprType::LessThan, ExprType::LessThanOrEqual)
+            }
+            _ => (ExprType::GreaterThan, ExprType::GreaterThanOrEqual),
+        };
+        // Built from the last field, which is compared with the original operator.
+        let mut fields = left.into_iter().zip_eq(right).rev();
+        let (l, r) = fields.next().unwrap();
+        let mut expr: ExprImpl = FunctionCall::new(func_type, vec![l, r])?.into();
+        for (l, r) in fields {
+            let strict_cmp = FunctionCall::new(strict, vec![l.clone(), r.clone()])?.into();
+            let or = FunctionCall::new(ExprType::Or, vec![strict_cmp, expr])?.into();
+            let non_strict_cmp = FunctionCall::new(non_strict, vec![l, r])?.into();
+            expr = FunctionCall::new(ExprType::And, vec![non_strict_cmp, or])?.into();
+        }
+        Ok(expr)
+    }
+
     /// Apply a NOT on top of LIKE.
     fn bind_not_like(&mut self, left: ExprImpl, right: ExprImpl) -> Result<ExprImpl> {
         Ok(FunctionCall::new(
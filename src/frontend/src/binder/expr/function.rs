@@ -64,6 +64,37 @@ impl Binder {
                 "ltrim" => ExprType::Ltrim,
                 "rtrim" => ExprType::Rtrim,
                 "md5" => ExprType::Md5,
+                "to_tsvector" => ExprType::ToTsvector,
+                "to_tsquery" => ExprType::ToTsquery,
+                "l2_distance" => ExprType::L2Distance,
+                "cosine_distance" => ExprType::CosineDistance,
+                "inner_product" => ExprType::InnerProduct,
+                "st_geomfromtext" => ExprType::StGeomFromText,
+                "st_astext" => ExprType::StAsText,
+                "st_geomfromwkb" => ExprType::StGeomFromWkb,
+                "st_asbinary" => ExprType::StAsBinary,
+                "st_point" | "st_makepoint" => {
+                    inputs = Self::rewrite_args(inputs, &[DataType::Float64, DataType::Float64])?;
+                    ExprType::StPoint
+                }
+                "st_distance" => ExprType::StDistance,
+                "st_distancesphere" => ExprType::StDistanceSphere,
+                "st_contains" => ExprType::StContains,
+                "st_dwithin" => {
+                    inputs = Self::rewrite_args(
+                        inputs,
+                        &[DataType::Varchar, DataType::Varchar, DataType::Float64],
+                    )?;
+                    ExprType::StDWithin
+                }
+                "st_geohash" => {
+                    // Without the precision, the geohash is as precise as a point can be encoded.
+                    if inputs.len() == 1 {
+                        inputs.push(ExprImpl::literal_int(20));
+                    }
+                    inputs = Self::rewrite_args(inputs, &[DataType::Varchar, DataType::Int32])?;
+                    ExprType::StGeoHash
+                }
                 "to_char" => ExprType::ToChar,
                 "nullif" => {
                     inputs = Self::rewrite_nullif_to_case_when(inputs)?;
@@ -75,6 +106,11 @@ impl Binder {
                 }
                 "concat_ws" => ExprType::ConcatWs,
                 "split_part" => ExprType::SplitPart,
+                "regexp_match" => ExprType::RegexpMatch,
+                "regexp_replace" => ExprType::RegexpReplace,
+                "format" => ExprType::Format,
+                "locf" => ExprType::Locf,
+                "interpolate" => ExprType::Interpolate,
                 "coalesce" => ExprType::Coalesce,
                 "round" => {
                     inputs = Self::rewrite_round_args(inputs);
@@ -104,6 +140,8 @@ impl Binder {
                 "char_length" => ExprType::CharLength,
                 "character_length" => ExprType::CharLength,
                 "repeat" => ExprType::Repeat,
+                "now" => ExprType::Now,
+                "gen_random_uuid" => ExprType::GenRandomUuid,
                 _ => {
                     return Err(ErrorCode::NotImplemented(
                         format!("unsupported function: {:?}", function_name),
@@ -195,6 +233,19 @@ impl Binder {
         }
     }
 
+    /// Casts the arguments to the types the function takes, e.g. the integer coordinates of
+    /// `ST_Point` to `DOUBLE PRECISION`. The arguments are kept if the number of them is wrong.
+    fn rewrite_args(inputs: Vec<ExprImpl>, types: &[DataType]) -> Result<Vec<ExprImpl>> {
+        if inputs.len() != types.len() {
+            return Ok(inputs);
+        }
+        inputs
+            .into_iter()
+            .zip_eq(types)
+            .map(|(input, ty)| input.cast_implicit(ty.clone()))
+            .try_collect()
+    }
+
     fn rewrite_two_bool_inputs(mut inputs: Vec<ExprImpl>) -> Result<Vec<ExprImpl>> {
         if inputs.len() != 2 {
             return Err(
@@ -30,6 +30,8 @@ mod function;
 mod subquery;
 mod value;
 
+pub(crate) use value::{number_literal, parse_interval, TimeStrToken};
+
 impl Binder {
     pub(super) fn bind_expr(&mut self, expr: Expr) -> Result<ExprImpl> {
         match expr {
@@ -241,6 +243,10 @@ impl Binder {
                 None => ExprImpl::literal_int(1),
             },
         ];
+        // `SUBSTRING(text FROM pattern)` extracts the substring matching a regular expression.
+        if substring_for.is_none() && args[1].return_type() == DataType::Varchar {
+            return FunctionCall::new(ExprType::RegexpSubstr, args).map(|f| f.into());
+        }
         if let Some(expr) = substring_for {
             args.push(self.bind_expr(*expr)?);
         }
@@ -388,9 +394,25 @@ pub fn bind_data_type(data_type: &AstDataType) -> Result<DataType> {
         AstDataType::Timestamp(false) => DataType::Timestamp,
         AstDataType::Timestamp(true) => DataType::Timestampz,
         AstDataType::Interval => DataType::Interval,
+        AstDataType::Uuid => DataType::Uuid,
         AstDataType::Array(datatype) => DataType::List {
             datatype: Box::new(bind_data_type(datatype)?),
         },
+        // The dimension is not a part of the type. It's kept as the type name of a column, and
+        // checked on insert, see `ColumnDesc::vector_dims`.
+        AstDataType::Vector(_) => DataType::List {
+            datatype: Box::new(DataType::Float64),
+        },
+        // Geometries are in the WKT format, and geographies are geometries of longitude and
+        // latitude.
+        AstDataType::Custom(name)
+            if matches!(
+                name.to_string().to_lowercase().as_str(),
+                "geometry" | "geography"
+            ) =>
+        {
+            DataType::Varchar
+        }
         AstDataType::Char(..) => {
             return Err(ErrorCode::NotImplemented(
                 "CHAR is not supported, please use VARCHAR instead\n".to_string(),
@@ -21,6 +21,20 @@ use risingwave_sqlparser::ast::{DateTimeField, Expr, Value};
 use crate::binder::Binder;
 use crate::expr::{align_types, Expr as _, ExprImpl, ExprType, FunctionCall, Literal};
 
+/// Binds a number to the narrowest of `INT`, `BIGINT` and `DECIMAL` holding it.
+pub(crate) fn number_literal(s: &str) -> Result<Literal> {
+    let (data, data_type) = if let Ok(int_32) = s.parse::<i32>() {
+        (Some(ScalarImpl::Int32(int_32)), DataType::Int32)
+    } else if let Ok(int_64) = s.parse::<i64>() {
+        (Some(ScalarImpl::Int64(int_64)), DataType::Int64)
+    } else {
+        // Notice: when the length of decimal exceeds 29(>= 30), it will be rounded up.
+        let decimal = str_parse::<Decimal>(s)?;
+        (Some(ScalarImpl::Decimal(decimal)), DataType::Decimal)
+    };
+    Ok(Literal::new(data, data_type))
+}
+
 impl Binder {
     pub fn bind_value(&mut self, value: Value) -> Result<Literal> {
         match value {
@@ -51,16 +65,7 @@ impl Binder {
     }
 
     fn bind_number(&mut self, s: String, _b: bool) -> Result<Literal> {
-        let (data, data_type) = if let Ok(int_32) = s.parse::<i32>() {
-            (Some(ScalarImpl::Int32(int_32)), DataType::Int32)
-        } else if let Ok(int_64) = s.parse::<i64>() {
-            (Some(ScalarImpl::Int64(int_64)), DataType::Int64)
-        } else {
-            // Notice: when the length of decimal exceeds 29(>= 30), it will be rounded up.
-            let decimal = str_parse::<Decimal>(&s)?;
-            (Some(ScalarImpl::Decimal(decimal)), DataType::Decimal)
-        };
-        Ok(Literal::new(data, data_type))
+        number_literal(&s)
     }
 
     fn bind_interval(
@@ -13,8 +13,9 @@
 // limitations under the License.
 
 use itertools::Itertools;
+use risingwave_common::catalog::ColumnDesc;
 use risingwave_common::error::{ErrorCode, Result};
-use risingwave_common::types::DataType;
+use risingwave_common::types::{DataType, ScalarImpl};
 use risingwave_sqlparser::ast::{Ident, ObjectName, Query, SetExpr};
 
 use super::{BoundQuery, BoundSetExpr};
@@ -109,6 +110,7 @@ impl Binder {
                 (bound, cast_exprs)
             }
         };
+        let cast_exprs = Self::check_vector_dims(&table_source.columns, &source, cast_exprs);
 
         let insert = BoundInsert {
             table_source,
@@ -142,6 +144,44 @@ impl Binder {
         Err(ErrorCode::BindError(msg.into()).into())
     }
 
+    /// Checks that the values inserted into `VECTOR(n)` columns have `n` dimensions, in the extra
+    /// `Project` of `cast_exprs`.
+    fn check_vector_dims(
+        columns: &[ColumnDesc],
+        source: &BoundQuery,
+        cast_exprs: Vec<ExprImpl>,
+    ) -> Vec<ExprImpl> {
+        if columns.iter().all(|c| c.vector_dims().is_none()) {
+            return cast_exprs;
+        }
+        let exprs = match cast_exprs.is_empty() {
+            true => source
+                .data_types()
+                .into_iter()
+                .enumerate()
+                .map(|(i, t)| InputRef::new(i, t).into())
+                .collect(),
+            false => cast_exprs,
+        };
+        exprs
+            .into_iter()
+            .zip_eq(columns)
+            .map(|(expr, column)| match column.vector_dims() {
+                Some(dims) => {
+                    let return_type = expr.return_type();
+                    let dims = Literal::new(Some(ScalarImpl::Int32(dims as i32)), DataType::Int32);
+                    FunctionCall::new_unchecked(
+                        ExprType::CheckVectorDims,
+                        vec![expr, dims.into()],
+                        return_type,
+                    )
+                    .into()
+                }
+                None => expr,
+            })
+            .collect()
+    }
+
     /// If expr is struct type function and some inputs are null,
     /// we need to change the data type of these fields to target type.
     pub fn change_null_struct_type(expr: ExprImpl, target: DataType) -> Result<ExprImpl> {
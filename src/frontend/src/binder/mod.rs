@@ -36,8 +36,9 @@ pub use expr::bind_data_type;
 pub use insert::BoundInsert;
 pub use query::BoundQuery;
 pub use relation::{
-    BoundBaseTable, BoundJoin, BoundSource, BoundSystemTable, BoundTableFunction, BoundTableSource,
-    BoundWindowTableFunction, FunctionType, Relation, WindowTableFunctionKind,
+    BoundBaseTable, BoundJoin, BoundMatchRecognize, BoundSource, BoundSystemTable,
+    BoundTableFunction, BoundTableSample, BoundTableSource, BoundWindowTableFunction, FunctionType,
+    Relation, WindowTableFunctionKind,
 };
 pub use select::BoundSelect;
 pub use set_expr::BoundSetExpr;
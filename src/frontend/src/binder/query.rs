@@ -17,10 +17,11 @@ use std::collections::HashMap;
 use risingwave_common::catalog::Schema;
 use risingwave_common::error::{ErrorCode, Result};
 use risingwave_common::types::DataType;
+use risingwave_common::util::sort_util::OrderType;
 use risingwave_sqlparser::ast::{Cte, Expr, OrderByExpr, Query, Value, With};
 
-use crate::binder::{Binder, BoundSetExpr};
-use crate::expr::ExprImpl;
+use crate::binder::{Binder, BoundSetExpr, Relation};
+use crate::expr::{ExprImpl, InputRef};
 use crate::optimizer::property::{Direction, FieldOrder};
 
 /// A validated sql query, including order and union.
@@ -68,6 +69,67 @@ impl Binder {
         result
     }
 
+    /// Bind a [`Query`] of a `SELECT` statement. A query selecting from a materialized view with
+    /// `ORDER BY` returns the rows in that order if it has no `ORDER BY` of its own, as if the
+    /// definition of the materialized view were inlined like a view in `PostgreSQL`.
+    pub(super) fn bind_top_query(&mut self, query: Query) -> Result<BoundQuery> {
+        self.push_context();
+        let result = self.bind_query_inner(query).map(Self::inherit_sort_key);
+        self.pop_context();
+        result
+    }
+
+    /// Orders the query by the sort key of the materialized view it selects from, which can be
+    /// provided by scanning the materialized view without sorting.
+    fn inherit_sort_key(mut query: BoundQuery) -> BoundQuery {
+        if !query.order.is_empty() {
+            return query;
+        }
+        let BoundSetExpr::Select(select) = &query.body else {
+            return query;
+        };
+        let Some(Relation::BaseTable(table)) = &select.from else {
+            return query;
+        };
+        if select.distinct
+            || !select.group_by.is_empty()
+            || select.select_items.iter().any(|item| item.has_agg_call())
+        {
+            return query;
+        }
+        // The columns of the only relation in the context are bound from 0 in the order of the
+        // table columns.
+        let catalog = &table.table_catalog;
+        let visible_output_num = query.schema().len();
+        for desc in catalog.sort_key() {
+            let index = catalog
+                .columns()
+                .iter()
+                .position(|c| c.column_id() == desc.column_desc.column_id)
+                .unwrap();
+            let output_index = select
+                .select_items
+                .iter()
+                .position(
+                    |item| matches!(item, ExprImpl::InputRef(input) if input.index() == index),
+                )
+                .unwrap_or_else(|| {
+                    query
+                        .extra_order_exprs
+                        .push(InputRef::new(index, desc.column_desc.data_type.clone()).into());
+                    visible_output_num + query.extra_order_exprs.len() - 1
+                });
+            query.order.push(FieldOrder {
+                index: output_index,
+                direct: match desc.order {
+                    OrderType::Ascending => Direction::Asc,
+                    OrderType::Descending => Direction::Desc,
+                },
+            });
+        }
+        query
+    }
+
     /// Bind a [`Query`] using the current [`BindContext`](super::BindContext).
     pub(super) fn bind_query_inner(&mut self, query: Query) -> Result<BoundQuery> {
         let limit = query.get_limit_value();
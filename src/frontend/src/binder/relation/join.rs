@@ -65,14 +65,27 @@ impl Binder {
     fn bind_table_with_joins(&mut self, table: TableWithJoins) -> Result<Relation> {
         let mut root = self.bind_table_factor(table.relation)?;
         for join in table.joins {
+            let is_asof = matches!(
+                join.join_operator,
+                JoinOperator::AsOfInner(_) | JoinOperator::AsOfLeftOuter(_)
+            );
             let (constraint, join_type) = match join.join_operator {
-                JoinOperator::Inner(constraint) => (constraint, JoinType::Inner),
-                JoinOperator::LeftOuter(constraint) => (constraint, JoinType::LeftOuter),
+                JoinOperator::Inner(constraint) | JoinOperator::AsOfInner(constraint) => {
+                    (constraint, JoinType::Inner)
+                }
+                JoinOperator::LeftOuter(constraint) | JoinOperator::AsOfLeftOuter(constraint) => {
+                    (constraint, JoinType::LeftOuter)
+                }
                 JoinOperator::RightOuter(constraint) => (constraint, JoinType::RightOuter),
                 JoinOperator::FullOuter(constraint) => (constraint, JoinType::FullOuter),
                 // Cross join equals to inner join with with no constraint.
                 JoinOperator::CrossJoin => (JoinConstraint::None, JoinType::Inner),
             };
+            if is_asof && !matches!(constraint, JoinConstraint::On(_)) {
+                return Err(
+                    ErrorCode::BindError("ASOF join requires an ON condition".to_string()).into(),
+                );
+            }
             let right: Relation;
             let cond: ExprImpl;
             if let JoinConstraint::Using(_col) = constraint.clone() {
@@ -92,13 +105,17 @@ impl Binder {
                 }
                 (cond, _) = self.bind_join_constraint(constraint, None)?;
             }
-            let join = BoundJoin {
+            let join = Box::new(BoundJoin {
                 join_type,
                 left: root,
                 right,
                 cond,
+            });
+            root = if is_asof {
+                Relation::AsOfJoin(join)
+            } else {
+                Relation::Join(join)
             };
-            root = Relation::Join(Box::new(join));
         }
 
         Ok(root)
@@ -174,5 +191,9 @@ fn get_table_name(table_factor: &TableFactor) -> Option<Vec<Ident>> {
             .as_ref()
             .map(|table_alias| vec![table_alias.name.clone()]),
         TableFactor::NestedJoin(table_with_joins) => get_table_name(&table_with_joins.relation),
+        TableFactor::MatchRecognize { alias, .. } => alias
+            .as_ref()
+            .map(|table_alias| vec![table_alias.name.clone()]),
+        TableFactor::TableSample { table, .. } => get_table_name(table),
     }
 }
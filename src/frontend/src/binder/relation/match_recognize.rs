@@ -0,0 +1,273 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use risingwave_common::catalog::Field;
+use risingwave_common::error::{ErrorCode, Result, RwError};
+use risingwave_common::types::DataType;
+use risingwave_sqlparser::ast::{
+    self, Expr, FunctionArg, FunctionArgExpr, Ident, MatchRecognize, TableAlias, TableFactor,
+};
+
+use super::{Binder, Relation};
+use crate::binder::bind_context::ColumnBinding;
+use crate::expr::{Expr as _, ExprImpl, InputRef};
+use crate::optimizer::plan_node::{Measure, MeasureKind, PatternQuantifier, PatternTerm};
+use crate::optimizer::property::{Direction, FieldOrder};
+
+/// `<table> MATCH_RECOGNIZE (...)`, which outputs the partition keys and the measures of each
+/// match, followed by a hidden column of the sequence number of the match in its partition.
+#[derive(Debug, Clone)]
+pub struct BoundMatchRecognize {
+    pub(crate) input: Relation,
+    pub(crate) partition_by: Vec<usize>,
+    pub(crate) order_by: Vec<FieldOrder>,
+    pub(crate) pattern: Vec<PatternTerm>,
+    pub(crate) measures: Vec<Measure>,
+}
+
+impl Binder {
+    pub(super) fn bind_match_recognize(
+        &mut self,
+        table: TableFactor,
+        match_recognize: MatchRecognize,
+        alias: Option<TableAlias>,
+    ) -> Result<BoundMatchRecognize> {
+        let MatchRecognize {
+            partition_by,
+            order_by,
+            measures,
+            pattern,
+            define,
+        } = match_recognize;
+
+        self.push_context();
+        let input = self.bind_table_factor(table)?;
+        let partition_by: Vec<usize> = partition_by
+            .into_iter()
+            .map(|expr| self.bind_match_recognize_column(expr, "PARTITION BY"))
+            .try_collect()?;
+        let order_by: Vec<FieldOrder> = order_by
+            .into_iter()
+            .map(|order_by_expr| {
+                let index = self.bind_match_recognize_column(order_by_expr.expr, "ORDER BY")?;
+                let direct = match order_by_expr.asc {
+                    None | Some(true) => Direction::Asc,
+                    Some(false) => Direction::Desc,
+                };
+                Ok::<_, RwError>(FieldOrder { index, direct })
+            })
+            .try_collect()?;
+        let input_columns = std::mem::take(&mut self.context.columns);
+        self.pop_context();
+
+        let mut symbols = HashMap::new();
+        for (term_idx, term) in pattern.iter().enumerate() {
+            if symbols
+                .insert(term.symbol.value.clone(), term_idx)
+                .is_some()
+            {
+                return Err(ErrorCode::NotImplemented(
+                    format!(
+                        "pattern variable \"{}\" appearing more than once in PATTERN",
+                        term.symbol.value
+                    ),
+                    None.into(),
+                )
+                .into());
+            }
+        }
+
+        let mut conditions = vec![None; pattern.len()];
+        for definition in define {
+            let symbol = definition.symbol.value;
+            let Some(&term_idx) = symbols.get(&symbol) else {
+                return Err(ErrorCode::BindError(format!(
+                    "pattern variable \"{}\" in DEFINE is not in PATTERN",
+                    symbol
+                ))
+                .into());
+            };
+            if conditions[term_idx].is_some() {
+                return Err(ErrorCode::BindError(format!(
+                    "pattern variable \"{}\" is defined more than once",
+                    symbol
+                ))
+                .into());
+            }
+            conditions[term_idx] =
+                Some(self.bind_pattern_condition(&input_columns, symbol, definition.condition)?);
+        }
+
+        let pattern = pattern
+            .into_iter()
+            .zip_eq(conditions)
+            .map(|(term, condition)| PatternTerm {
+                symbol: term.symbol.value,
+                quantifier: match term.quantifier {
+                    None => PatternQuantifier::One,
+                    Some(ast::PatternQuantifier::ZeroOrMore) => PatternQuantifier::ZeroOrMore,
+                    Some(ast::PatternQuantifier::OneOrMore) => PatternQuantifier::OneOrMore,
+                    Some(ast::PatternQuantifier::ZeroOrOne) => PatternQuantifier::ZeroOrOne,
+                },
+                // A pattern variable not in `DEFINE` accepts any row.
+                condition: condition.unwrap_or_else(|| ExprImpl::literal_bool(true)),
+            })
+            .collect_vec();
+
+        let measures: Vec<Measure> = measures
+            .into_iter()
+            .map(|measure| {
+                let (kind, term, column) =
+                    Self::resolve_measure(&input_columns, &symbols, measure.expr)?;
+                Ok::<_, RwError>(Measure {
+                    kind,
+                    term,
+                    column,
+                    name: measure.alias.value,
+                })
+            })
+            .try_collect()?;
+
+        let columns = partition_by
+            .iter()
+            .map(|&idx| (false, input_columns[idx].field.clone()))
+            .chain(measures.iter().map(|measure| {
+                let data_type = input_columns[measure.column].field.data_type.clone();
+                (false, Field::with_name(data_type, measure.name.clone()))
+            }))
+            .chain(std::iter::once((
+                true,
+                Field::with_name(DataType::Int64, "_match_number"),
+            )))
+            .collect_vec();
+        self.bind_context(columns, "match_recognize".to_string(), alias)?;
+
+        Ok(BoundMatchRecognize {
+            input,
+            partition_by,
+            order_by,
+            pattern,
+            measures,
+        })
+    }
+
+    fn bind_match_recognize_column(&mut self, expr: Expr, clause: &str) -> Result<usize> {
+        match self.bind_expr(expr)? {
+            ExprImpl::InputRef(input_ref) => Ok(input_ref.index()),
+            _ => Err(ErrorCode::NotImplemented(
+                format!("{} of MATCH_RECOGNIZE other than columns", clause),
+                None.into(),
+            )
+            .into()),
+        }
+    }
+
+    /// Binds the condition of a pattern variable on the current row, where the columns can be
+    /// qualified by the name of the pattern variable, e.g. `A.price > 10`.
+    fn bind_pattern_condition(
+        &mut self,
+        input_columns: &[ColumnBinding],
+        symbol: String,
+        condition: Expr,
+    ) -> Result<ExprImpl> {
+        self.push_context();
+        let result = self
+            .bind_context(
+                input_columns
+                    .iter()
+                    .map(|column| (column.is_hidden, column.field.clone())),
+                symbol.clone(),
+                None,
+            )
+            .and_then(|_| self.bind_expr(condition));
+        self.pop_context();
+        let condition = result?;
+        if condition.return_type() != DataType::Boolean {
+            return Err(ErrorCode::BindError(format!(
+                "the condition of pattern variable \"{}\" must be a boolean, not {:?}",
+                symbol,
+                condition.return_type()
+            ))
+            .into());
+        }
+        if condition.has_correlated_input_ref() {
+            return Err(ErrorCode::NotImplemented(
+                "conditions in DEFINE referring to other tables".to_string(),
+                None.into(),
+            )
+            .into());
+        }
+        Ok(condition)
+    }
+
+    /// Resolves a measure in the form of `FIRST(A.col)`, `LAST(A.col)` or `A.col`, which is the
+    /// same as `LAST(A.col)`.
+    fn resolve_measure(
+        input_columns: &[ColumnBinding],
+        symbols: &HashMap<String, usize>,
+        expr: Expr,
+    ) -> Result<(MeasureKind, usize, usize)> {
+        let (kind, idents) = match expr {
+            Expr::CompoundIdentifier(idents) => (MeasureKind::Last, idents),
+            Expr::Function(func) if func.name.0.len() == 1 && func.args.len() == 1 => {
+                let kind = match func.name.0[0].value.to_lowercase().as_str() {
+                    "first" => MeasureKind::First,
+                    "last" => MeasureKind::Last,
+                    _ => return Err(Self::unsupported_measure()),
+                };
+                match func.args.into_iter().next().unwrap() {
+                    FunctionArg::Unnamed(FunctionArgExpr::Expr(Expr::CompoundIdentifier(
+                        idents,
+                    ))) => (kind, idents),
+                    _ => return Err(Self::unsupported_measure()),
+                }
+            }
+            _ => return Err(Self::unsupported_measure()),
+        };
+        let [Ident { value: symbol, .. }, Ident { value: column, .. }] = &idents[..] else {
+            return Err(Self::unsupported_measure());
+        };
+        let Some(&term) = symbols.get(symbol) else {
+            return Err(ErrorCode::BindError(format!(
+                "pattern variable \"{}\" in MEASURES is not in PATTERN",
+                symbol
+            ))
+            .into());
+        };
+        let mut matched = input_columns
+            .iter()
+            .filter(|binding| &binding.field.name == column);
+        match (matched.next(), matched.next()) {
+            (Some(binding), None) => Ok((kind, term, binding.index)),
+            (Some(_), Some(_)) => Err(ErrorCode::BindError(format!(
+                "column \"{}\" in MEASURES is ambiguous",
+                column
+            ))
+            .into()),
+            (None, _) => Err(ErrorCode::ItemNotFound(format!("Invalid column: {}", column)).into()),
+        }
+    }
+
+    fn unsupported_measure() -> RwError {
+        ErrorCode::NotImplemented(
+            "MEASURES of MATCH_RECOGNIZE other than `FIRST(A.col)`, `LAST(A.col)` and `A.col`"
+                .to_string(),
+            None.into(),
+        )
+        .into()
+    }
+}
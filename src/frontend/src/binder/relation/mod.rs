@@ -23,15 +23,19 @@ use super::bind_context::ColumnBinding;
 use crate::binder::Binder;
 
 mod join;
+mod match_recognize;
 mod subquery;
 mod table_function;
 mod table_or_source;
+mod table_sample;
 mod window_table_function;
 
 pub use join::BoundJoin;
+pub use match_recognize::BoundMatchRecognize;
 pub use subquery::BoundSubquery;
 pub use table_function::BoundTableFunction;
 pub use table_or_source::{BoundBaseTable, BoundSource, BoundSystemTable, BoundTableSource};
+pub use table_sample::BoundTableSample;
 pub use window_table_function::{BoundWindowTableFunction, WindowTableFunctionKind};
 
 /// A validated item that refers to a table-like entity, including base table, subquery, join, etc.
@@ -43,8 +47,11 @@ pub enum Relation {
     SystemTable(Box<BoundSystemTable>),
     Subquery(Box<BoundSubquery>),
     Join(Box<BoundJoin>),
+    /// An `ASOF JOIN`, whose join type is either inner or left outer.
+    AsOfJoin(Box<BoundJoin>),
     WindowTableFunction(Box<BoundWindowTableFunction>),
     TableFunction(Box<BoundTableFunction>),
+    MatchRecognize(Box<BoundMatchRecognize>),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -90,9 +97,33 @@ impl Binder {
         Ok(name)
     }
 
-    /// return the (`schema_name`, `table_name`)
-    pub fn resolve_table_name(name: ObjectName) -> Result<(String, String)> {
-        Self::resolve_double_name(name.0, "empty table name", DEFAULT_SCHEMA_NAME)
+    /// return the (`schema_name`, `table_name`), where the name may be qualified by the current
+    /// database `db_name`. Relations of other databases can't be referenced, since databases are
+    /// isolated from each other.
+    pub fn resolve_table_name(db_name: &str, name: ObjectName) -> Result<(String, String)> {
+        match name.0.len() {
+            0..=2 => {}
+            3 if name.0[0].value == db_name => {}
+            3 => {
+                return Err(ErrorCode::NotImplemented(
+                    format!("cross-database references are not implemented: {}", name),
+                    None.into(),
+                )
+                .into())
+            }
+            _ => {
+                return Err(ErrorCode::BindError(format!(
+                    "improper qualified name (too many dotted names): {}",
+                    name
+                ))
+                .into())
+            }
+        }
+        let mut identifiers = name.0;
+        if identifiers.len() == 3 {
+            identifiers.remove(0);
+        }
+        Self::resolve_double_name(identifiers, "empty table name", DEFAULT_SCHEMA_NAME)
     }
 
     /// return the ( `database_name`, `schema_name`)
@@ -113,6 +144,11 @@ impl Binder {
         Self::resolve_single_name(name.0, "user name")
     }
 
+    /// return the `subscription_name`
+    pub fn resolve_subscription_name(name: ObjectName) -> Result<String> {
+        Self::resolve_single_name(name.0, "subscription name")
+    }
+
     /// Fill the [`BindContext`](super::BindContext) for table.
     pub(super) fn bind_context(
         &mut self,
@@ -179,7 +215,7 @@ impl Binder {
         alias: Option<TableAlias>,
     ) -> Result<Relation> {
         let has_schema_name = name.0.len() > 1;
-        let (schema_name, table_name) = Self::resolve_table_name(name)?;
+        let (schema_name, table_name) = Self::resolve_table_name(&self.db_name, name)?;
         if !has_schema_name
             && let Some(bound_query) = self.cte_to_relation.get(&table_name)
         {
@@ -240,6 +276,14 @@ impl Binder {
                     )))
                 }
             }
+            TableFactor::MatchRecognize {
+                table,
+                match_recognize,
+                alias,
+            } => Ok(Relation::MatchRecognize(Box::new(
+                self.bind_match_recognize(*table, *match_recognize, alias)?,
+            ))),
+            TableFactor::TableSample { table, sample } => self.bind_table_sample(*table, sample),
             _ => Err(ErrorCode::NotImplemented(
                 format!("unsupported table factor {:?}", table_factor),
                 None.into(),
@@ -248,3 +292,26 @@ impl Binder {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use risingwave_sqlparser::ast::{Ident, ObjectName};
+
+    use crate::binder::Binder;
+
+    fn object_name(parts: &[&str]) -> ObjectName {
+        ObjectName(parts.iter().map(|part| Ident::new(*part)).collect())
+    }
+
+    #[test]
+    fn test_resolve_table_name() {
+        let resolve = |parts: &[&str]| Binder::resolve_table_name("dev", object_name(parts));
+        let expected = ("public".to_string(), "t".to_string());
+        assert_eq!(resolve(&["t"]).unwrap(), expected);
+        assert_eq!(resolve(&["public", "t"]).unwrap(), expected);
+        assert_eq!(resolve(&["dev", "public", "t"]).unwrap(), expected);
+        // Databases are isolated from each other.
+        assert!(resolve(&["other", "public", "t"]).is_err());
+        assert!(resolve(&["a", "dev", "public", "t"]).is_err());
+    }
+}
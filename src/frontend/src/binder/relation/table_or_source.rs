@@ -18,6 +18,7 @@ use risingwave_common::catalog::{ColumnDesc, PG_CATALOG_SCHEMA_NAME};
 use risingwave_common::error::{ErrorCode, Result, RwError};
 use risingwave_sqlparser::ast::{ObjectName, TableAlias};
 
+use super::BoundTableSample;
 use crate::binder::{Binder, Relation};
 use crate::catalog::source_catalog::SourceCatalog;
 use crate::catalog::system_catalog::SystemCatalog;
@@ -30,6 +31,7 @@ pub struct BoundBaseTable {
     pub table_id: TableId,
     pub table_catalog: TableCatalog,
     pub table_indexes: Vec<Arc<TableCatalog>>,
+    pub sample: Option<BoundTableSample>,
 }
 
 /// `BoundTableSource` is used by DML statement on table source like insert, update.
@@ -109,6 +111,7 @@ impl Binder {
                     table_id,
                     table_catalog,
                     table_indexes,
+                    sample: None,
                 };
 
                 (Relation::BaseTable(Box::new(table)), columns)
@@ -176,11 +179,12 @@ impl Binder {
             table_id,
             table_catalog,
             table_indexes,
+            sample: None,
         })
     }
 
     pub(crate) fn bind_table_source(&mut self, name: ObjectName) -> Result<BoundTableSource> {
-        let (schema_name, source_name) = Self::resolve_table_name(name)?;
+        let (schema_name, source_name) = Self::resolve_table_name(&self.db_name, name)?;
         let source = self
             .catalog
             .get_source_by_name(&self.db_name, &schema_name, &source_name)?;
@@ -0,0 +1,106 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_pb::batch_plan::table_sample::Method;
+use risingwave_pb::batch_plan::TableSample;
+use risingwave_sqlparser::ast::{self, Expr, TableFactor, TableSampleMethod, Value};
+
+use super::{Binder, Relation};
+
+/// `TABLESAMPLE` of a base table, which makes a batch scan only read a sample of the table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoundTableSample {
+    pub method: Method,
+    /// The probability to keep a vnode or row in percent.
+    pub percentage: f64,
+    /// A random seed is used if not given by `REPEATABLE`.
+    pub seed: Option<u64>,
+}
+
+impl BoundTableSample {
+    pub fn to_protobuf(&self) -> TableSample {
+        TableSample {
+            method: self.method as i32,
+            percentage: self.percentage,
+            seed: self.seed.unwrap_or_else(rand::random),
+        }
+    }
+}
+
+impl std::fmt::Display for BoundTableSample {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let method = match self.method {
+            Method::System => "system",
+            Method::Bernoulli => "bernoulli",
+        };
+        write!(f, "{}({}%)", method, self.percentage)?;
+        if let Some(seed) = self.seed {
+            write!(f, " repeatable({})", seed)?;
+        }
+        Ok(())
+    }
+}
+
+impl Binder {
+    pub(super) fn bind_table_sample(
+        &mut self,
+        table: TableFactor,
+        sample: ast::TableSample,
+    ) -> Result<Relation> {
+        let mut relation = self.bind_table_factor(table)?;
+        let Relation::BaseTable(base_table) = &mut relation else {
+            return Err(ErrorCode::NotImplemented(
+                "TABLESAMPLE is only supported on tables and materialized views".to_string(),
+                None.into(),
+            )
+            .into());
+        };
+        let percentage = match &sample.percentage {
+            Expr::Value(Value::Number(number, _)) => number.parse::<f64>().ok(),
+            _ => None,
+        }
+        .filter(|percentage| (0.0..=100.0).contains(percentage))
+        .ok_or_else(|| {
+            ErrorCode::BindError(format!(
+                "TABLESAMPLE percentage must be a number between 0 and 100, got {}",
+                sample.percentage
+            ))
+        })?;
+        let seed = sample
+            .seed
+            .map(|seed| {
+                match &seed {
+                    Expr::Value(Value::Number(number, _)) => number.parse::<u64>().ok(),
+                    _ => None,
+                }
+                .ok_or_else(|| {
+                    ErrorCode::BindError(format!(
+                        "REPEATABLE seed must be a non-negative integer, got {}",
+                        seed
+                    ))
+                })
+            })
+            .transpose()?;
+        base_table.sample = Some(BoundTableSample {
+            method: match sample.method {
+                TableSampleMethod::System => Method::System,
+                TableSampleMethod::Bernoulli => Method::Bernoulli,
+            },
+            percentage,
+            seed,
+        });
+        Ok(relation)
+    }
+}
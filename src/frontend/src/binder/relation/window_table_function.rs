@@ -85,7 +85,9 @@ impl Binder {
             )
             .into());
         };
-        let Some(ExprImpl::InputRef(time_col)) = self.bind_function_arg(time_col_arg)?.into_iter().next() else {
+        let Some(ExprImpl::InputRef(time_col)) =
+            self.bind_function_arg(time_col_arg)?.into_iter().next()
+        else {
             return Err(ErrorCode::BindError(
                 "the 2st arg of window table function should be time_col".to_string(),
             )
@@ -116,7 +118,7 @@ impl Binder {
                 .into_iter(),
             ).collect::<Result<Vec<_>>>()?;
 
-        let (_, table_name) = Self::resolve_table_name(table_name)?;
+        let (_, table_name) = Self::resolve_table_name(&self.db_name, table_name)?;
         self.bind_context(columns, table_name, alias)?;
 
         let exprs: Vec<_> = args
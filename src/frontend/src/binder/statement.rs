@@ -53,7 +53,7 @@ impl Binder {
                 self.bind_update(table, assignments, selection)?.into(),
             )),
 
-            Statement::Query(q) => Ok(BoundStatement::Query(self.bind_query(*q)?.into())),
+            Statement::Query(q) => Ok(BoundStatement::Query(self.bind_top_query(*q)?.into())),
 
             _ => Err(ErrorCode::NotImplemented(
                 format!("unsupported statement {:?}", stmt),
@@ -77,11 +77,31 @@ pub trait CatalogWriter: Send + Sync {
 
     async fn drop_materialized_view(&self, table_id: TableId) -> Result<()>;
 
+    async fn replace_materialized_view(
+        &self,
+        table_id: TableId,
+        new_table_id: TableId,
+    ) -> Result<()>;
+
+    async fn alter_materialized_view(
+        &self,
+        table_id: TableId,
+        table: ProstTable,
+        graph: StreamFragmentGraph,
+    ) -> Result<()>;
+
     async fn drop_source(&self, source_id: u32) -> Result<()>;
 
     async fn drop_database(&self, database_id: u32) -> Result<()>;
 
     async fn drop_schema(&self, schema_id: u32) -> Result<()>;
+
+    async fn comment_on(
+        &self,
+        table_id: TableId,
+        column_id: Option<i32>,
+        description: String,
+    ) -> Result<()>;
 }
 
 #[derive(Clone)]
@@ -166,6 +186,31 @@ impl CatalogWriter for CatalogWriterImpl {
         self.wait_version(version).await
     }
 
+    async fn replace_materialized_view(
+        &self,
+        table_id: TableId,
+        new_table_id: TableId,
+    ) -> Result<()> {
+        let version = self
+            .meta_client
+            .replace_materialized_view(table_id, new_table_id)
+            .await?;
+        self.wait_version(version).await
+    }
+
+    async fn alter_materialized_view(
+        &self,
+        table_id: TableId,
+        table: ProstTable,
+        graph: StreamFragmentGraph,
+    ) -> Result<()> {
+        let (_, version) = self
+            .meta_client
+            .alter_materialized_view(table_id, table, graph)
+            .await?;
+        self.wait_version(version).await
+    }
+
     async fn drop_source(&self, source_id: u32) -> Result<()> {
         let version = self.meta_client.drop_source(source_id).await?;
         self.wait_version(version).await
@@ -180,6 +225,19 @@ impl CatalogWriter for CatalogWriterImpl {
         let version = self.meta_client.drop_database(database_id).await?;
         self.wait_version(version).await
     }
+
+    async fn comment_on(
+        &self,
+        table_id: TableId,
+        column_id: Option<i32>,
+        description: String,
+    ) -> Result<()> {
+        let version = self
+            .meta_client
+            .comment_on(table_id.table_id, column_id, description)
+            .await?;
+        self.wait_version(version).await
+    }
 }
 
 impl CatalogWriterImpl {
@@ -0,0 +1,765 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Renders the catalog of a database as the DDL to recreate its logical schema on another cluster,
+//! like `pg_dump --schema-only`.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use itertools::Itertools;
+use risingwave_common::catalog::{
+    DEFAULT_SCHEMA_NAME, DEFAULT_SUPPER_USER, DEFAULT_SUPPER_USER_FOR_PG,
+    FULLTEXT_INDEX_COLUMN_KEY, IVF_INDEX_COLUMN_KEY, PARTITION_INTERVAL_KEY,
+    PARTITION_INTERVAL_SECONDS_KEY, PARTITION_RETENTION_KEY, PARTITION_RETENTION_SECONDS_KEY,
+    PG_CATALOG_SCHEMA_NAME, RETENTION_KEY, RETENTION_SECONDS_KEY, WATERMARK_DELAY_KEY,
+    WATERMARK_DELAY_SECONDS_KEY,
+};
+use risingwave_common::error::Result;
+use risingwave_pb::catalog::source::Info;
+use risingwave_pb::catalog::table::OptionalAssociatedSourceId;
+use risingwave_pb::catalog::{Database, Source, Table};
+use risingwave_pb::data::data_type::TypeName;
+use risingwave_pb::data::DataType;
+use risingwave_pb::ddl_service::CatalogSnapshot;
+use risingwave_pb::plan_common::{
+    ColumnCatalog, ColumnDesc, ExternalTableFormat, HandleConflictBehavior, RowFormatType,
+};
+use risingwave_pb::user::grant_privilege::{Action, Object};
+use risingwave_pb::user::{GrantPrivilege, UserInfo};
+use risingwave_sqlparser::keywords::ALL_KEYWORDS;
+
+use super::source_catalog::{check_append_only, check_shared, WithOptions};
+use super::CatalogError;
+
+/// The placeholder of the redacted secrets, to be filled in before running the DDL.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// The values of the properties whose keys contain any of these are redacted.
+const SECRET_KEY_PATTERNS: &[&str] = &[
+    "password",
+    "passwd",
+    "secret",
+    "token",
+    "credential",
+    "access_key",
+    "private_key",
+    "api_key",
+    "sasl.jaas.config",
+];
+
+/// The properties normalized into seconds on creation, and the keys they are given with.
+const SECONDS_KEYS: &[(&str, &str)] = &[
+    (RETENTION_SECONDS_KEY, RETENTION_KEY),
+    (WATERMARK_DELAY_SECONDS_KEY, WATERMARK_DELAY_KEY),
+    (PARTITION_INTERVAL_SECONDS_KEY, PARTITION_INTERVAL_KEY),
+    (PARTITION_RETENTION_SECONDS_KEY, PARTITION_RETENTION_KEY),
+];
+
+const PROTOBUF_MESSAGE_KEY: &str = "proto.message";
+
+/// Renders the DDL statements to recreate the database `database_name` in `snapshot`, without the
+/// trailing semicolons. Each object is created after the ones it depends on: the users first, then
+/// the schemas, the relations in the order of their dependencies, and finally the grants. The
+/// values of secret properties and the passwords of the users are redacted as [`REDACTED`].
+pub fn dump_catalog(snapshot: &CatalogSnapshot, database_name: &str) -> Result<Vec<String>> {
+    let database = snapshot
+        .databases
+        .iter()
+        .find(|database| database.name == database_name)
+        .ok_or_else(|| CatalogError::NotFound("database", database_name.to_string()))?;
+    let dumper = Dumper {
+        database,
+        schemas: snapshot
+            .schemas
+            .iter()
+            .filter(|schema| schema.database_id == database.id)
+            .map(|schema| (schema.id, schema.name.as_str()))
+            .collect(),
+        sources: snapshot
+            .sources
+            .iter()
+            .filter(|source| source.database_id == database.id)
+            .map(|source| (source.id, source))
+            .collect(),
+        tables: snapshot
+            .tables
+            .iter()
+            .filter(|table| table.database_id == database.id && !table.is_internal)
+            .map(|table| (table.id, table))
+            .collect(),
+    };
+    let users = snapshot
+        .users
+        .iter()
+        .sorted_by(|a, b| a.name.cmp(&b.name))
+        .collect_vec();
+
+    let mut statements = vec![];
+    for user in &users {
+        if user.name != DEFAULT_SUPPER_USER && user.name != DEFAULT_SUPPER_USER_FOR_PG {
+            statements.extend(create_user(user));
+        }
+    }
+    for (_, schema_name) in dumper.schemas.iter().sorted() {
+        if *schema_name != DEFAULT_SCHEMA_NAME && *schema_name != PG_CATALOG_SCHEMA_NAME {
+            statements.push(format!("CREATE SCHEMA {}", quote_ident(schema_name)));
+        }
+    }
+    for relation in dumper.sorted_relations() {
+        statements.extend(dumper.create_relation(relation));
+    }
+    for user in &users {
+        for privilege in &user.grant_privileges {
+            statements.extend(dumper.grant(user, privilege));
+        }
+    }
+    Ok(statements)
+}
+
+#[derive(Clone, Copy)]
+enum Relation<'a> {
+    /// A source, or an external table.
+    Source(&'a Source),
+    /// A materialized view or an index, or a table or a materialized or shared source along with
+    /// the source it's associated with.
+    Table(&'a Table, Option<&'a Source>),
+}
+
+struct Dumper<'a> {
+    database: &'a Database,
+    schemas: HashMap<u32, &'a str>,
+    sources: HashMap<u32, &'a Source>,
+    tables: HashMap<u32, &'a Table>,
+}
+
+impl<'a> Dumper<'a> {
+    /// Sorts the relations so that each one comes after those it depends on. Otherwise they are in
+    /// the order of their ids, i.e. the order they were created in.
+    fn sorted_relations(&self) -> Vec<Relation<'a>> {
+        // The source associated with a table is created along with it.
+        let associated: HashMap<u32, u32> = self
+            .tables
+            .values()
+            .filter_map(|table| associated_source_id(table).map(|id| (id, table.id)))
+            .collect();
+        let mut relations = BTreeMap::new();
+        for source in self.sources.values() {
+            if !associated.contains_key(&source.id) {
+                relations.insert(source.id, Relation::Source(source));
+            }
+        }
+        for table in self.tables.values() {
+            let source = associated_source_id(table).and_then(|id| self.sources.get(&id).copied());
+            relations.insert(table.id, Relation::Table(table, source));
+        }
+
+        let mut dependencies: BTreeMap<u32, BTreeSet<u32>> = relations
+            .iter()
+            .map(|(id, relation)| {
+                let dependencies = match relation {
+                    Relation::Source(_) => BTreeSet::new(),
+                    Relation::Table(table, _) => table
+                        .dependent_relations
+                        .iter()
+                        .copied()
+                        .chain(table.is_index.then_some(table.index_on_id))
+                        .map(|id| associated.get(&id).copied().unwrap_or(id))
+                        .filter(|dependency| dependency != id && relations.contains_key(dependency))
+                        .collect(),
+                };
+                (*id, dependencies)
+            })
+            .collect();
+        let mut sorted = vec![];
+        while !dependencies.is_empty() {
+            // Fall back to the smallest id on a cycle, which can't be created in the first place.
+            let next = dependencies
+                .iter()
+                .find(|(_, dependencies)| dependencies.is_empty())
+                .or_else(|| dependencies.iter().next())
+                .map(|(id, _)| *id)
+                .unwrap();
+            dependencies.remove(&next);
+            for remaining in dependencies.values_mut() {
+                remaining.remove(&next);
+            }
+            sorted.push(relations[&next]);
+        }
+        sorted
+    }
+
+    fn create_relation(&self, relation: Relation<'_>) -> Vec<String> {
+        match relation {
+            Relation::Source(source) => vec![self.create_source(source, None)],
+            Relation::Table(table, Some(source)) => {
+                let mut statements = vec![self.create_source(source, Some(table))];
+                statements.extend(self.comments(table, "TABLE"));
+                statements
+            }
+            Relation::Table(table, None) if table.is_index => vec![self.create_index(table)],
+            Relation::Table(table, None) => {
+                let name = self.relation_name(table.schema_id, &table.name);
+                let mut statements = vec![if table.definition.is_empty() {
+                    format!("-- materialized view {} has no recorded definition", name)
+                } else {
+                    format!(
+                        "CREATE MATERIALIZED VIEW {}{} AS {}",
+                        name,
+                        with_clause(&table.properties),
+                        table.definition
+                    )
+                }];
+                statements.extend(self.comments(table, "MATERIALIZED VIEW"));
+                statements
+            }
+        }
+    }
+
+    /// Renders the source, with the table associated with it if any.
+    fn create_source(&self, source: &Source, table: Option<&Table>) -> String {
+        let name = self.relation_name(source.schema_id, &source.name);
+        let on_conflict = match table.map(|table| table.handle_pk_conflict_behavior()) {
+            Some(HandleConflictBehavior::Ignore) => " ON CONFLICT IGNORE",
+            _ => "",
+        };
+        match source.info.as_ref().unwrap() {
+            Info::StreamSource(info) => {
+                let materialized = table.is_some() && !check_shared(&info.properties);
+                let row_format = match info.row_format() {
+                    RowFormatType::Json => "JSON".to_string(),
+                    RowFormatType::Protobuf => format!(
+                        "PROTOBUF MESSAGE {} ROW SCHEMA LOCATION {}",
+                        quote_literal(
+                            info.properties
+                                .get(PROTOBUF_MESSAGE_KEY)
+                                .map_or("", String::as_str)
+                        ),
+                        quote_literal(&info.row_schema_location)
+                    ),
+                    RowFormatType::DebeziumJson => "DEBEZIUM JSON".to_string(),
+                    RowFormatType::Avro => format!(
+                        "AVRO ROW SCHEMA LOCATION {}",
+                        quote_literal(&info.row_schema_location)
+                    ),
+                };
+                // The columns of a Protobuf source are derived from its schema.
+                let columns = match info.row_format() {
+                    RowFormatType::Protobuf => String::new(),
+                    _ => column_defs(&info.columns, &info.pk_column_ids),
+                };
+                format!(
+                    "CREATE {}SOURCE {}{}{}{} ROW FORMAT {}",
+                    if materialized { "MATERIALIZED " } else { "" },
+                    name,
+                    columns,
+                    on_conflict,
+                    with_clause(&info.properties),
+                    row_format
+                )
+            }
+            Info::TableSource(info) => {
+                let mut properties = table
+                    .map_or(&info.properties, |table| &table.properties)
+                    .clone();
+                let append_only = check_append_only(&properties);
+                properties.remove(WithOptions::AppenOnly);
+                format!(
+                    "CREATE TABLE {}{}{}{}{}",
+                    name,
+                    column_defs(&info.columns, &info.pk_column_ids),
+                    if append_only { " APPEND ONLY" } else { "" },
+                    on_conflict,
+                    with_clause(&properties)
+                )
+            }
+            Info::ExternalTable(info) => format!(
+                "CREATE EXTERNAL TABLE {} STORED AS {} LOCATION {}",
+                name,
+                match info.format() {
+                    ExternalTableFormat::Parquet => "PARQUET",
+                    ExternalTableFormat::Delta => "DELTA",
+                },
+                quote_literal(&info.location)
+            ),
+        }
+    }
+
+    fn create_index(&self, index: &Table) -> String {
+        let name = self.relation_name(index.schema_id, &index.name);
+        let on = self
+            .tables
+            .get(&index.index_on_id)
+            .map(|table| self.relation_name(table.schema_id, &table.name))
+            .unwrap_or_default();
+        // The column of a full-text or IVF index is kept as it was given.
+        let (using, columns) = if let Some(column) = index.properties.get(FULLTEXT_INDEX_COLUMN_KEY)
+        {
+            (" USING FULLTEXT", column.clone())
+        } else if let Some(column) = index.properties.get(IVF_INDEX_COLUMN_KEY) {
+            (" USING IVF", column.clone())
+        } else {
+            let columns = index
+                .distribution_keys
+                .iter()
+                .filter_map(|idx| index.columns.get(*idx as usize)?.column_desc.as_ref())
+                .map(|column| quote_ident(&column.name))
+                .join(", ");
+            ("", columns)
+        };
+        format!("CREATE INDEX {} ON {}{} ({})", name, on, using, columns)
+    }
+
+    fn comments(&self, table: &Table, object_type: &str) -> Vec<String> {
+        let name = self.relation_name(table.schema_id, &table.name);
+        let mut statements = vec![];
+        if !table.description.is_empty() {
+            statements.push(format!(
+                "COMMENT ON {} {} IS {}",
+                object_type,
+                name,
+                quote_literal(&table.description)
+            ));
+        }
+        for column in visible_columns(&table.columns) {
+            if let Some(description) = table.column_descriptions.get(&column.column_id) {
+                statements.push(format!(
+                    "COMMENT ON COLUMN {}.{} IS {}",
+                    name,
+                    quote_ident(&column.name),
+                    quote_literal(description)
+                ));
+            }
+        }
+        statements
+    }
+
+    /// Renders the privilege granted to the user, grouped by whether they can grant it to others.
+    /// The privileges on the objects of other databases are skipped.
+    fn grant(&self, user: &UserInfo, privilege: &GrantPrivilege) -> Vec<String> {
+        let object = match privilege.object.as_ref() {
+            Some(Object::DatabaseId(id)) if *id == self.database.id => {
+                Some(format!("DATABASE {}", quote_ident(&self.database.name)))
+            }
+            Some(Object::SchemaId(id)) => self
+                .schemas
+                .get(id)
+                .map(|schema_name| format!("SCHEMA {}", quote_ident(schema_name))),
+            Some(Object::TableId(id)) => self.tables.get(id).map(|table| {
+                format!(
+                    "MATERIALIZED VIEW {}",
+                    self.relation_name(table.schema_id, &table.name)
+                )
+            }),
+            Some(Object::SourceId(id)) => self.sources.get(id).map(|source| {
+                format!(
+                    "SOURCE {}",
+                    self.relation_name(source.schema_id, &source.name)
+                )
+            }),
+            Some(Object::AllTablesSchemaId(id)) => self.schemas.get(id).map(|schema_name| {
+                format!(
+                    "ALL MATERIALIZED VIEWS IN SCHEMA {}",
+                    quote_ident(schema_name)
+                )
+            }),
+            Some(Object::AllSourcesSchemaId(id)) => self
+                .schemas
+                .get(id)
+                .map(|schema_name| format!("ALL SOURCES IN SCHEMA {}", quote_ident(schema_name))),
+            Some(Object::DatabaseId(_)) | None => None,
+        };
+        let Some(object) = object else {
+            return vec![];
+        };
+        [false, true]
+            .into_iter()
+            .filter_map(|with_grant_option| {
+                let actions = privilege
+                    .action_with_opts
+                    .iter()
+                    .filter(|action| action.with_grant_option == with_grant_option)
+                    .filter_map(|action| action_name(action.action()))
+                    .join(", ");
+                (!actions.is_empty()).then(|| {
+                    format!(
+                        "GRANT {} ON {} TO {}{}",
+                        actions,
+                        object,
+                        quote_ident(&user.name),
+                        if with_grant_option {
+                            " WITH GRANT OPTION"
+                        } else {
+                            ""
+                        }
+                    )
+                })
+            })
+            .collect()
+    }
+
+    fn relation_name(&self, schema_id: u32, name: &str) -> String {
+        let schema_name = self
+            .schemas
+            .get(&schema_id)
+            .copied()
+            .unwrap_or(DEFAULT_SCHEMA_NAME);
+        format!("{}.{}", quote_ident(schema_name), quote_ident(name))
+    }
+}
+
+fn create_user(user: &UserInfo) -> Vec<String> {
+    let name = quote_ident(&user.name);
+    let mut options = vec![
+        if user.is_supper {
+            "SUPERUSER"
+        } else {
+            "NOSUPERUSER"
+        }
+        .to_string(),
+        if user.can_create_db {
+            "CREATEDB"
+        } else {
+            "NOCREATEDB"
+        }
+        .to_string(),
+        if user.can_login { "LOGIN" } else { "NOLOGIN" }.to_string(),
+    ];
+    // Only the hash of the password is kept, which is redacted all the same.
+    if user.auth_info.is_some() {
+        options.push(format!("PASSWORD {}", quote_literal(REDACTED)));
+    }
+    let mut statements = vec![format!("CREATE USER {} WITH {}", name, options.join(" "))];
+    if let Some(limits) = &user.limits {
+        for (param, value) in [
+            ("statement_timeout", limits.statement_timeout_ms),
+            ("max_result_rows", limits.max_result_rows),
+            ("memory_limit", limits.memory_limit_bytes),
+        ] {
+            if value > 0 {
+                statements.push(format!("ALTER USER {} SET {} = {}", name, param, value));
+            }
+        }
+    }
+    statements
+}
+
+fn action_name(action: Action) -> Option<&'static str> {
+    match action {
+        Action::Unknown => None,
+        Action::Select => Some("SELECT"),
+        Action::Insert => Some("INSERT"),
+        Action::Update => Some("UPDATE"),
+        Action::Delete => Some("DELETE"),
+        Action::Create => Some("CREATE"),
+        Action::Connect => Some("CONNECT"),
+    }
+}
+
+fn associated_source_id(table: &Table) -> Option<u32> {
+    match table.optional_associated_source_id {
+        Some(OptionalAssociatedSourceId::AssociatedSourceId(id)) => Some(id),
+        None => None,
+    }
+}
+
+fn visible_columns(columns: &[ColumnCatalog]) -> impl Iterator<Item = &ColumnDesc> {
+    columns
+        .iter()
+        .filter(|column| !column.is_hidden)
+        .filter_map(|column| column.column_desc.as_ref())
+}
+
+/// Renders the column definitions, with the primary key unless it's the hidden row id.
+fn column_defs(columns: &[ColumnCatalog], pk_column_ids: &[i32]) -> String {
+    let columns = visible_columns(columns).collect_vec();
+    let mut defs = columns
+        .iter()
+        .map(|column| format!("{} {}", quote_ident(&column.name), column_type(column)))
+        .collect_vec();
+    let pk = pk_column_ids
+        .iter()
+        .map(|id| columns.iter().find(|column| column.column_id == *id))
+        .collect::<Option<Vec<_>>>();
+    if let Some(pk) = pk
+        && !pk.is_empty()
+    {
+        defs.push(format!(
+            "PRIMARY KEY ({})",
+            pk.iter().map(|column| quote_ident(&column.name)).join(", ")
+        ));
+    }
+    format!(" ({})", defs.join(", "))
+}
+
+fn column_type(column: &ColumnDesc) -> String {
+    render_type(column.column_type.as_ref().unwrap(), &column.field_descs)
+}
+
+fn render_type(data_type: &DataType, field_descs: &[ColumnDesc]) -> String {
+    match data_type.type_name() {
+        TypeName::Int16 => "SMALLINT".to_string(),
+        TypeName::Int32 => "INT".to_string(),
+        TypeName::Int64 => "BIGINT".to_string(),
+        TypeName::Float => "REAL".to_string(),
+        TypeName::Double => "DOUBLE".to_string(),
+        TypeName::Boolean => "BOOLEAN".to_string(),
+        TypeName::Varchar => "VARCHAR".to_string(),
+        TypeName::Date => "DATE".to_string(),
+        TypeName::Time => "TIME".to_string(),
+        TypeName::Timestamp => "TIMESTAMP".to_string(),
+        TypeName::Timestampz => "TIMESTAMP WITH TIME ZONE".to_string(),
+        TypeName::Decimal => "NUMERIC".to_string(),
+        TypeName::Interval => "INTERVAL".to_string(),
+        TypeName::Uuid => "UUID".to_string(),
+        TypeName::Struct => format!(
+            "STRUCT<{}>",
+            field_descs
+                .iter()
+                .map(|field| format!("{} {}", quote_ident(&field.name), column_type(field)))
+                .join(", ")
+        ),
+        TypeName::List => format!("{}[]", render_type(&data_type.field_type[0], field_descs)),
+    }
+}
+
+/// Renders the `WITH` clause of the properties, with the ones normalized into seconds given back
+/// as intervals, and the secrets redacted.
+fn with_clause(properties: &HashMap<String, String>) -> String {
+    if properties.is_empty() {
+        return String::new();
+    }
+    let options = properties
+        .iter()
+        .map(|(key, value)| {
+            match SECONDS_KEYS
+                .iter()
+                .find(|(seconds_key, _)| seconds_key == key)
+            {
+                Some((_, key)) => (key.to_string(), format!("{} seconds", value)),
+                None if is_secret(key) => (key.clone(), REDACTED.to_string()),
+                None => (key.clone(), value.clone()),
+            }
+        })
+        .sorted()
+        .map(|(key, value)| format!("{} = {}", quote_literal(&key), quote_literal(&value)))
+        .join(", ");
+    format!(" WITH ({})", options)
+}
+
+/// Whether the property holds a secret, which is redacted in the dump and the audit log.
+pub fn is_secret(key: &str) -> bool {
+    let key = key.to_lowercase();
+    SECRET_KEY_PATTERNS
+        .iter()
+        .any(|pattern| key.contains(pattern))
+}
+
+/// Quotes the identifier unless it's in lowercase and not a keyword, as it's stored when given
+/// without quotes.
+fn quote_ident(ident: &str) -> String {
+    let plain = ident.starts_with(|c: char| c.is_ascii_lowercase() || c == '_')
+        && ident
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        && !ALL_KEYWORDS.contains(&ident.to_uppercase().as_str());
+    if plain {
+        ident.to_string()
+    } else {
+        format!("\"{}\"", ident.replace('"', "\"\""))
+    }
+}
+
+fn quote_literal(literal: &str) -> String {
+    format!("'{}'", literal.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::types::DataType;
+    use risingwave_pb::catalog::{Schema, StreamSourceInfo, TableSourceInfo};
+    use risingwave_pb::user::grant_privilege::ActionWithGrantOption;
+    use risingwave_pb::user::AuthInfo;
+
+    use super::*;
+
+    fn column(column_id: i32, name: &str, data_type: DataType, is_hidden: bool) -> ColumnCatalog {
+        ColumnCatalog {
+            column_desc: Some(ColumnDesc {
+                column_type: Some(data_type.to_protobuf()),
+                column_id,
+                name: name.to_string(),
+                ..Default::default()
+            }),
+            is_hidden,
+        }
+    }
+
+    #[test]
+    fn test_dump_catalog() {
+        let columns = vec![
+            column(0, "_row_id", DataType::Int64, true),
+            column(1, "v1", DataType::Int32, false),
+            column(2, "Name", DataType::Varchar, false),
+        ];
+        let snapshot = CatalogSnapshot {
+            databases: vec![Database {
+                id: 1,
+                name: "dev".to_string(),
+                ..Default::default()
+            }],
+            schemas: vec![
+                Schema {
+                    id: 2,
+                    database_id: 1,
+                    name: DEFAULT_SCHEMA_NAME.to_string(),
+                    ..Default::default()
+                },
+                Schema {
+                    id: 3,
+                    database_id: 1,
+                    name: "analytics".to_string(),
+                    ..Default::default()
+                },
+            ],
+            sources: vec![
+                Source {
+                    id: 10,
+                    schema_id: 2,
+                    database_id: 1,
+                    name: "t".to_string(),
+                    info: Some(Info::TableSource(TableSourceInfo {
+                        columns: columns.clone(),
+                        pk_column_ids: vec![1],
+                        ..Default::default()
+                    })),
+                    ..Default::default()
+                },
+                // Created after the materialized view on it had been replaced.
+                Source {
+                    id: 20,
+                    schema_id: 2,
+                    database_id: 1,
+                    name: "s".to_string(),
+                    info: Some(Info::StreamSource(StreamSourceInfo {
+                        properties: [
+                            ("connector", "kafka"),
+                            ("kafka.topic", "events"),
+                            ("properties.sasl.password", "hunter2"),
+                        ]
+                        .into_iter()
+                        .map(|(k, v)| (k.to_string(), v.to_string()))
+                        .collect(),
+                        row_format: RowFormatType::Json as i32,
+                        columns: columns.clone(),
+                        pk_column_ids: vec![0],
+                        ..Default::default()
+                    })),
+                    ..Default::default()
+                },
+            ],
+            tables: vec![
+                Table {
+                    id: 11,
+                    schema_id: 2,
+                    database_id: 1,
+                    name: "t".to_string(),
+                    columns: columns.clone(),
+                    optional_associated_source_id: Some(
+                        OptionalAssociatedSourceId::AssociatedSourceId(10),
+                    ),
+                    properties: [("appendonly", "true"), (RETENTION_SECONDS_KEY, "86400")]
+                        .into_iter()
+                        .map(|(k, v)| (k.to_string(), v.to_string()))
+                        .collect(),
+                    description: "the table's rows".to_string(),
+                    column_descriptions: [(1, "the value".to_string())].into(),
+                    ..Default::default()
+                },
+                Table {
+                    id: 12,
+                    schema_id: 3,
+                    database_id: 1,
+                    name: "mv".to_string(),
+                    columns: columns[1..].to_vec(),
+                    dependent_relations: vec![10, 20],
+                    definition: "SELECT t.v1, s.\"Name\" FROM t JOIN s ON t.v1 = s.v1".to_string(),
+                    ..Default::default()
+                },
+                Table {
+                    id: 13,
+                    schema_id: 2,
+                    database_id: 1,
+                    name: "idx".to_string(),
+                    columns: columns[1..].to_vec(),
+                    distribution_keys: vec![1],
+                    is_index: true,
+                    index_on_id: 11,
+                    dependent_relations: vec![11],
+                    ..Default::default()
+                },
+                // The internal state table of the materialized view.
+                Table {
+                    id: 14,
+                    schema_id: 3,
+                    database_id: 1,
+                    name: "__internal_mv_1".to_string(),
+                    is_internal: true,
+                    ..Default::default()
+                },
+            ],
+            users: vec![
+                UserInfo {
+                    name: DEFAULT_SUPPER_USER.to_string(),
+                    is_supper: true,
+                    can_create_db: true,
+                    can_login: true,
+                    ..Default::default()
+                },
+                UserInfo {
+                    name: "alice".to_string(),
+                    can_login: true,
+                    auth_info: Some(AuthInfo::default()),
+                    grant_privileges: vec![GrantPrivilege {
+                        object: Some(Object::TableId(12)),
+                        action_with_opts: vec![ActionWithGrantOption {
+                            action: Action::Select as i32,
+                            with_grant_option: true,
+                        }],
+                    }],
+                    ..Default::default()
+                },
+            ],
+        };
+
+        assert_eq!(
+            dump_catalog(&snapshot, "dev").unwrap(),
+            vec![
+                "CREATE USER alice WITH NOSUPERUSER NOCREATEDB LOGIN PASSWORD '[REDACTED]'",
+                "CREATE SCHEMA analytics",
+                "CREATE TABLE public.t (v1 INT, \"Name\" VARCHAR, PRIMARY KEY (v1)) APPEND ONLY \
+                 WITH ('retention' = '86400 seconds')",
+                "COMMENT ON TABLE public.t IS 'the table''s rows'",
+                "COMMENT ON COLUMN public.t.v1 IS 'the value'",
+                "CREATE INDEX public.idx ON public.t (\"Name\")",
+                "CREATE SOURCE public.s (v1 INT, \"Name\" VARCHAR) WITH ('connector' = 'kafka', \
+                 'kafka.topic' = 'events', 'properties.sasl.password' = '[REDACTED]') \
+                 ROW FORMAT JSON",
+                "CREATE MATERIALIZED VIEW analytics.mv AS \
+                 SELECT t.v1, s.\"Name\" FROM t JOIN s ON t.v1 = s.v1",
+                "GRANT SELECT ON MATERIALIZED VIEW analytics.mv TO alice WITH GRANT OPTION",
+            ]
+        );
+        assert!(dump_catalog(&snapshot, "prod").is_err());
+    }
+}
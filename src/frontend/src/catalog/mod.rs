@@ -20,6 +20,7 @@ pub(crate) mod catalog_service;
 
 pub(crate) mod column_catalog;
 pub(crate) mod database_catalog;
+pub mod dump;
 pub(crate) mod pg_catalog;
 pub(crate) mod root_catalog;
 pub(crate) mod schema_catalog;
@@ -96,6 +97,11 @@ pub enum CatalogError {
 
 impl From<CatalogError> for RwError {
     fn from(e: CatalogError) -> Self {
-        ErrorCode::CatalogError(Box::new(e)).into()
+        let sqlstate = match &e {
+            CatalogError::NotFound(..) => "42704",
+            CatalogError::Duplicated(..) => "42710",
+            CatalogError::NotEmpty(..) => "2BP01",
+        };
+        RwError::from(ErrorCode::CatalogError(Box::new(e))).with_sqlstate(sqlstate)
     }
 }
@@ -13,8 +13,13 @@
 // limitations under the License.
 
 pub mod pg_cast;
+pub mod pg_description;
 pub mod pg_namespace;
 pub mod pg_type;
+pub mod rw_audit_log;
+pub mod rw_catalog_dump;
+pub mod rw_table_storage;
+pub mod rw_telemetry;
 
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -26,12 +31,20 @@ use risingwave_common::catalog::{ColumnDesc, SysCatalogReader, TableId, DEFAULT_
 use risingwave_common::error::{ErrorCode, Result};
 use risingwave_common::types::{DataType, ScalarImpl};
 
+use crate::audit_log::AuditLoggerRef;
 use crate::catalog::catalog_service::CatalogReader;
 use crate::catalog::column_catalog::ColumnCatalog;
+use crate::catalog::dump::dump_catalog;
 use crate::catalog::pg_catalog::pg_cast::*;
+use crate::catalog::pg_catalog::pg_description::*;
 use crate::catalog::pg_catalog::pg_namespace::*;
 use crate::catalog::pg_catalog::pg_type::*;
+use crate::catalog::pg_catalog::rw_audit_log::*;
+use crate::catalog::pg_catalog::rw_catalog_dump::*;
+use crate::catalog::pg_catalog::rw_table_storage::*;
+use crate::catalog::pg_catalog::rw_telemetry::*;
 use crate::catalog::system_catalog::SystemCatalog;
+use crate::meta_client::FrontendMetaClient;
 use crate::scheduler::worker_node_manager::WorkerNodeManagerRef;
 use crate::session::AuthContext;
 use crate::user::user_service::UserInfoReader;
@@ -44,9 +57,11 @@ pub struct SysCatalogReaderImpl {
     user_info_reader: UserInfoReader,
     // Read cluster info.
     worker_node_manager: WorkerNodeManagerRef,
-    // TODO: Read from meta.
-    // meta_client: MetaClient,
+    // Read storage stats from meta.
+    meta_client: Arc<dyn FrontendMetaClient>,
     auth_context: Arc<AuthContext>,
+    // Read recent audit log records.
+    audit_logger: AuditLoggerRef,
 }
 
 impl SysCatalogReaderImpl {
@@ -54,13 +69,17 @@ impl SysCatalogReaderImpl {
         catalog_reader: CatalogReader,
         user_info_reader: UserInfoReader,
         worker_node_manager: WorkerNodeManagerRef,
+        meta_client: Arc<dyn FrontendMetaClient>,
         auth_context: Arc<AuthContext>,
+        audit_logger: AuditLoggerRef,
     ) -> Self {
         Self {
             catalog_reader,
             user_info_reader,
             worker_node_manager,
+            meta_client,
             auth_context,
+            audit_logger,
         }
     }
 }
@@ -75,6 +94,16 @@ impl SysCatalogReader for SysCatalogReaderImpl {
             Ok(PG_CAST_DATA_ROWS.clone())
         } else if table_name == PG_NAMESPACE_TABLE_NAME {
             self.read_namespace()
+        } else if table_name == PG_DESCRIPTION_TABLE_NAME {
+            self.read_description()
+        } else if table_name == RW_AUDIT_LOG_TABLE_NAME {
+            self.read_audit_log()
+        } else if table_name == RW_TABLE_STORAGE_TABLE_NAME {
+            self.read_table_storage().await
+        } else if table_name == RW_TELEMETRY_TABLE_NAME {
+            self.read_telemetry().await
+        } else if table_name == RW_CATALOG_DUMP_TABLE_NAME {
+            self.read_catalog_dump().await
         } else {
             Err(ErrorCode::ItemNotFound(format!("Invalid system table: {}", table_name)).into())
         }
@@ -96,6 +125,161 @@ impl SysCatalogReaderImpl {
             })
             .collect_vec())
     }
+
+    fn read_description(&self) -> Result<Vec<Row>> {
+        let reader = self.catalog_reader.read_guard();
+        let database = reader.get_database_by_name(&self.auth_context.database)?;
+        let mut rows = vec![];
+        for table in database
+            .iter_schemas()
+            .flat_map(|schema| schema.iter_all_tables())
+            .sorted_by_key(|table| table.id().table_id)
+        {
+            let row = |objsubid: usize, description: &str| {
+                Row::new(vec![
+                    Some(ScalarImpl::Int32(table.id().table_id as i32)),
+                    Some(ScalarImpl::Int32(PG_CLASS_OID)),
+                    Some(ScalarImpl::Int32(objsubid as i32)),
+                    Some(ScalarImpl::Utf8(description.to_string())),
+                ])
+            };
+            if !table.description.is_empty() {
+                rows.push(row(0, &table.description));
+            }
+            for (idx, column) in table.columns().iter().filter(|c| !c.is_hidden).enumerate() {
+                if let Some(description) = table.column_description(column.column_id()) {
+                    rows.push(row(idx + 1, description));
+                }
+            }
+        }
+        Ok(rows)
+    }
+
+    fn read_audit_log(&self) -> Result<Vec<Row>> {
+        self.check_superuser(RW_AUDIT_LOG_TABLE_NAME)?;
+        Ok(self
+            .audit_logger
+            .records()
+            .into_iter()
+            .map(|record| {
+                let query = record.query.as_ref();
+                Row::new(vec![
+                    Some(ScalarImpl::Int64(record.logged_at_ms as i64)),
+                    Some(ScalarImpl::Utf8(record.kind.as_str().to_string())),
+                    query.map(|q| ScalarImpl::Utf8(q.query_id.clone())),
+                    Some(ScalarImpl::Utf8(record.user)),
+                    Some(ScalarImpl::Utf8(record.database)),
+                    Some(ScalarImpl::Int64(record.duration_ms as i64)),
+                    query.map(|q| ScalarImpl::Utf8(format!("{:016x}", q.plan_hash))),
+                    query.map(|q| ScalarImpl::Int32(q.stages.len() as i32)),
+                    Some(ScalarImpl::Utf8(record.sql)),
+                    record.error.map(ScalarImpl::Utf8),
+                ])
+            })
+            .collect_vec())
+    }
+
+    async fn read_table_storage(&self) -> Result<Vec<Row>> {
+        let table_stats = self.meta_client.get_table_storage_stats().await?;
+        let table_names: HashMap<u32, (String, String)> = {
+            let reader = self.catalog_reader.read_guard();
+            let database = reader.get_database_by_name(&self.auth_context.database)?;
+            database
+                .iter_schemas()
+                .flat_map(|schema| {
+                    schema.iter_all_tables().map(|table| {
+                        (
+                            table.id().table_id,
+                            (schema.name(), table.name().to_string()),
+                        )
+                    })
+                })
+                .collect()
+        };
+        Ok(table_stats
+            .into_iter()
+            .map(|stats| {
+                let names = table_names.get(&stats.table_id);
+                Row::new(vec![
+                    Some(ScalarImpl::Int32(stats.table_id as i32)),
+                    names.map(|(schema_name, _)| ScalarImpl::Utf8(schema_name.clone())),
+                    names.map(|(_, table_name)| ScalarImpl::Utf8(table_name.clone())),
+                    Some(ScalarImpl::Int64(stats.total_size as i64)),
+                    Some(ScalarImpl::Int64(stats.sstable_count as i64)),
+                ])
+            })
+            .collect_vec())
+    }
+
+    async fn read_telemetry(&self) -> Result<Vec<Row>> {
+        let report = self.meta_client.get_telemetry_report().await?;
+        let entry = |category: &str, name: &str, value: String| {
+            Row::new(vec![
+                Some(ScalarImpl::Utf8(category.to_string())),
+                Some(ScalarImpl::Utf8(name.to_string())),
+                Some(ScalarImpl::Utf8(value)),
+            ])
+        };
+        let mut rows = vec![
+            entry("report", "tracking_id", report.tracking_id.clone()),
+            entry(
+                "report",
+                "report_time_ms",
+                report.report_time_ms.to_string(),
+            ),
+            entry("report", "reported", report.reported.to_string()),
+            entry("report", "endpoint", report.endpoint.clone()),
+        ];
+        let sorted = |map: HashMap<String, u64>| map.into_iter().sorted().collect_vec();
+        rows.extend(
+            sorted(report.node_counts)
+                .into_iter()
+                .map(|(name, count)| entry("node_count", &name, count.to_string())),
+        );
+        rows.extend(
+            sorted(report.feature_usage)
+                .into_iter()
+                .map(|(name, count)| entry("feature_usage", &name, count.to_string())),
+        );
+        rows.extend(
+            sorted(report.error_counts)
+                .into_iter()
+                .map(|(name, count)| entry("error_count", &name, count.to_string())),
+        );
+        Ok(rows)
+    }
+
+    /// Returns an error unless the user is a superuser, which is required to read `table_name`.
+    fn check_superuser(&self, table_name: &str) -> Result<()> {
+        let is_super = self
+            .user_info_reader
+            .read_guard()
+            .get_user_by_name(&self.auth_context.user_name)
+            .map_or(false, |user| user.is_supper);
+        if !is_super {
+            return Err(ErrorCode::PermissionDenied(format!(
+                "must be superuser to read {}",
+                table_name
+            ))
+            .into());
+        }
+        Ok(())
+    }
+
+    async fn read_catalog_dump(&self) -> Result<Vec<Row>> {
+        self.check_superuser(RW_CATALOG_DUMP_TABLE_NAME)?;
+        let snapshot = self.meta_client.get_catalog().await?;
+        Ok(dump_catalog(&snapshot, &self.auth_context.database)?
+            .into_iter()
+            .enumerate()
+            .map(|(seq, statement)| {
+                Row::new(vec![
+                    Some(ScalarImpl::Int32(seq as i32)),
+                    Some(ScalarImpl::Utf8(statement)),
+                ])
+            })
+            .collect_vec())
+    }
 }
 
 // TODO: support struct column and type name when necessary.
@@ -135,6 +319,11 @@ lazy_static::lazy_static! {
             (PG_TYPE_TABLE_NAME.to_string(), def_sys_catalog!(1, PG_TYPE_TABLE_NAME, PG_TYPE_COLUMNS)),
             (PG_NAMESPACE_TABLE_NAME.to_string(), def_sys_catalog!(2, PG_NAMESPACE_TABLE_NAME, PG_NAMESPACE_COLUMNS)),
             (PG_CAST_TABLE_NAME.to_string(), def_sys_catalog!(3, PG_CAST_TABLE_NAME, PG_CAST_COLUMNS)),
+            (RW_AUDIT_LOG_TABLE_NAME.to_string(), def_sys_catalog!(4, RW_AUDIT_LOG_TABLE_NAME, RW_AUDIT_LOG_COLUMNS)),
+            (RW_TABLE_STORAGE_TABLE_NAME.to_string(), def_sys_catalog!(5, RW_TABLE_STORAGE_TABLE_NAME, RW_TABLE_STORAGE_COLUMNS)),
+            (RW_TELEMETRY_TABLE_NAME.to_string(), def_sys_catalog!(6, RW_TELEMETRY_TABLE_NAME, RW_TELEMETRY_COLUMNS)),
+            (PG_DESCRIPTION_TABLE_NAME.to_string(), def_sys_catalog!(7, PG_DESCRIPTION_TABLE_NAME, PG_DESCRIPTION_COLUMNS)),
+            (RW_CATALOG_DUMP_TABLE_NAME.to_string(), def_sys_catalog!(8, RW_CATALOG_DUMP_TABLE_NAME, RW_CATALOG_DUMP_COLUMNS)),
         ].into();
 }
 
@@ -0,0 +1,31 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_common::types::DataType;
+
+use crate::catalog::pg_catalog::PgCatalogColumnsDef;
+
+/// The catalog `pg_description` stores the comments of tables, materialized views and their
+/// columns set by `COMMENT ON`. `objsubid` is the 1-based position of the column among the
+/// visible ones, or 0 for the relation itself. Ref: [`https://www.postgresql.org/docs/current/catalog-pg-description.html`]
+pub const PG_DESCRIPTION_TABLE_NAME: &str = "pg_description";
+pub const PG_DESCRIPTION_COLUMNS: &[PgCatalogColumnsDef] = &[
+    (DataType::Int32, "objoid"),
+    (DataType::Int32, "classoid"),
+    (DataType::Int32, "objsubid"),
+    (DataType::Varchar, "description"),
+];
+
+/// The oid of `pg_class` in PostgreSQL, which is the `classoid` of the comments on relations.
+pub const PG_CLASS_OID: i32 = 1259;
@@ -39,6 +39,7 @@ pub const PG_TYPE_DATA: &[(i32, &str)] = &[
     (1184, "timestamptz"),
     (1186, "interval"),
     (1700, "numeric"),
+    (2950, "uuid"),
 ];
 
 fn build_pg_type_rows() -> Vec<Row> {
@@ -0,0 +1,34 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_common::types::DataType;
+
+use crate::catalog::pg_catalog::PgCatalogColumnsDef;
+
+/// The catalog `rw_audit_log` stores the recent records of the audit log in this frontend, which
+/// includes slow queries, DDLs and failed statements. See [`crate::audit_log`]. Only superusers can
+/// read it, since it holds the statements of all the users.
+pub const RW_AUDIT_LOG_TABLE_NAME: &str = "rw_audit_log";
+pub const RW_AUDIT_LOG_COLUMNS: &[PgCatalogColumnsDef] = &[
+    (DataType::Int64, "logged_at_ms"),
+    (DataType::Varchar, "kind"),
+    (DataType::Varchar, "query_id"),
+    (DataType::Varchar, "user_name"),
+    (DataType::Varchar, "database_name"),
+    (DataType::Int64, "duration_ms"),
+    (DataType::Varchar, "plan_hash"),
+    (DataType::Int32, "stage_count"),
+    (DataType::Varchar, "sql"),
+    (DataType::Varchar, "error"),
+];
@@ -0,0 +1,24 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_common::types::DataType;
+
+use crate::catalog::pg_catalog::PgCatalogColumnsDef;
+
+/// The catalog `rw_catalog_dump` shows the DDL statements to recreate the logical schema of the
+/// current database on another cluster, in the order to run them, with the secrets redacted. See
+/// [`crate::catalog::dump::dump_catalog`]. Only superusers can read it, since it lists all users.
+pub const RW_CATALOG_DUMP_TABLE_NAME: &str = "rw_catalog_dump";
+pub const RW_CATALOG_DUMP_COLUMNS: &[PgCatalogColumnsDef] =
+    &[(DataType::Int32, "seq"), (DataType::Varchar, "statement")];
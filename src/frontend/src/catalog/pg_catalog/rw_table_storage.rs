@@ -0,0 +1,29 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_common::types::DataType;
+
+use crate::catalog::pg_catalog::PgCatalogColumnsDef;
+
+/// The catalog `rw_table_storage` stores the estimated storage usage of each state table, fetched
+/// from meta. Tables outside the current database and internal state tables of streaming operators
+/// only have their ids shown.
+pub const RW_TABLE_STORAGE_TABLE_NAME: &str = "rw_table_storage";
+pub const RW_TABLE_STORAGE_COLUMNS: &[PgCatalogColumnsDef] = &[
+    (DataType::Int32, "table_id"),
+    (DataType::Varchar, "schema_name"),
+    (DataType::Varchar, "table_name"),
+    (DataType::Int64, "total_size"),
+    (DataType::Int64, "sstable_count"),
+];
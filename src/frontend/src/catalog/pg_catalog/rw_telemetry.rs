@@ -0,0 +1,27 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_common::types::DataType;
+
+use crate::catalog::pg_catalog::PgCatalogColumnsDef;
+
+/// The catalog `rw_telemetry` shows the telemetry report built by meta, flattened into
+/// `(category, name, value)` entries, so that users can inspect exactly what is (or would be)
+/// reported before opting in.
+pub const RW_TELEMETRY_TABLE_NAME: &str = "rw_telemetry";
+pub const RW_TELEMETRY_COLUMNS: &[PgCatalogColumnsDef] = &[
+    (DataType::Varchar, "category"),
+    (DataType::Varchar, "name"),
+    (DataType::Varchar, "value"),
+];
@@ -56,6 +56,17 @@ impl SchemaCatalog {
             .unwrap();
     }
 
+    /// Update the table in place, which may have been renamed.
+    pub fn update_table(&mut self, prost: &ProstTable) {
+        let name = prost.name.clone();
+        let id = prost.id.into();
+        let table: TableCatalog = prost.into();
+
+        let old_name = self.table_name_by_id.insert(id, name.clone()).unwrap();
+        self.table_by_name.remove(&old_name).unwrap();
+        self.table_by_name.try_insert(name, table).unwrap();
+    }
+
     pub fn drop_table(&mut self, id: TableId) {
         let name = self.table_name_by_id.remove(&id).unwrap();
         self.table_by_name.remove(&name).unwrap();
@@ -76,6 +87,11 @@ impl SchemaCatalog {
         self.source_by_name.remove(&name).unwrap();
     }
 
+    /// Iterate all tables, materialized views, indexes and materialized sources.
+    pub fn iter_all_tables(&self) -> impl Iterator<Item = &TableCatalog> {
+        self.table_by_name.values()
+    }
+
     pub fn iter_table(&self) -> impl Iterator<Item = &TableCatalog> {
         self.table_by_name
             .iter()
@@ -88,11 +104,23 @@ impl SchemaCatalog {
             .map(|(_, v)| v)
     }
 
-    /// Iterate all materialized views, excluding the indexs.
+    /// Iterate all materialized views, excluding the indexs and internal tables.
     pub fn iter_mv(&self) -> impl Iterator<Item = &TableCatalog> {
         self.table_by_name
             .iter()
-            .filter(|(_, v)| v.associated_source_id.is_none() && v.is_index_on.is_none())
+            .filter(|(_, v)| {
+                v.associated_source_id.is_none()
+                    && v.is_index_on.is_none()
+                    && v.is_internal_of.is_none()
+            })
+            .map(|(_, v)| v)
+    }
+
+    /// Iterate the internal state tables of the materialized view `mv_id`.
+    pub fn iter_internal_table(&self, mv_id: TableId) -> impl Iterator<Item = &TableCatalog> {
+        self.table_by_name
+            .iter()
+            .filter(move |(_, v)| v.is_internal_of == Some(mv_id))
             .map(|(_, v)| v)
     }
 
@@ -16,6 +16,7 @@ use std::collections::HashMap;
 use itertools::Itertools;
 use risingwave_pb::catalog::source::Info;
 use risingwave_pb::catalog::Source as ProstSource;
+use risingwave_pb::plan_common::{ExternalTableFormat, RowFormatType};
 use risingwave_pb::stream_plan::source_node::SourceType;
 
 use super::column_catalog::ColumnCatalog;
@@ -24,11 +25,11 @@ use super::{ColumnId, SourceId, TABLE_SOURCE_PK_COLID};
 #[expect(non_snake_case, non_upper_case_globals)]
 pub mod WithOptions {
     pub const AppenOnly: &str = "appendonly";
-    pub const Connector: &str = "connector";
+    /// A shared source is read by a single reader fragment, which keeps the rows in the source's
+    /// own table for the materialized views on it to backfill from.
+    pub const Shared: &str = "shared";
 }
 
-pub const KAFKA_CONNECTOR: &str = "kafka";
-
 /// this struct `SourceCatalog` is used in frontend and compared with `ProstSource` it only maintain
 /// information which will be used during optimization.
 #[derive(Clone, Debug)]
@@ -40,6 +41,16 @@ pub struct SourceCatalog {
     pub source_type: SourceType,
     pub append_only: bool,
     pub owner: String,
+    /// Set if the source is an external table of Parquet files, which only batch queries can scan.
+    pub external: Option<ExternalTableDesc>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExternalTableDesc {
+    pub location: String,
+    pub format: ExternalTableFormat,
+    /// The columns parsed from Hive-style `key=value` directories of the files.
+    pub partition_columns: Vec<String>,
 }
 
 impl SourceCatalog {
@@ -69,7 +80,7 @@ impl From<&ProstSource> for SourceCatalog {
     fn from(prost: &ProstSource) -> Self {
         let id = prost.id;
         let name = prost.name.clone();
-        let (source_type, prost_columns, pk_col_ids, with_options) = match &prost.info {
+        let (source_type, prost_columns, pk_col_ids, append_only) = match &prost.info {
             Some(Info::StreamSource(source)) => (
                 SourceType::Source,
                 source.columns.clone(),
@@ -78,19 +89,39 @@ impl From<&ProstSource> for SourceCatalog {
                     .iter()
                     .map(|id| ColumnId::new(*id))
                     .collect(),
-                source.properties.clone(),
+                // A connector source only inserts rows, unless it's in a changelog format.
+                source.row_format() != RowFormatType::DebeziumJson
+                    || check_append_only(&source.properties),
             ),
             Some(Info::TableSource(source)) => (
                 SourceType::Table,
                 source.columns.clone(),
-                vec![TABLE_SOURCE_PK_COLID],
-                source.properties.clone(),
+                if source.pk_column_ids.is_empty() {
+                    vec![TABLE_SOURCE_PK_COLID]
+                } else {
+                    source
+                        .pk_column_ids
+                        .iter()
+                        .map(|id| ColumnId::new(*id))
+                        .collect()
+                },
+                check_append_only(&source.properties),
             ),
+            Some(Info::ExternalTable(table)) => {
+                (SourceType::Source, table.columns.clone(), vec![], true)
+            }
             None => unreachable!(),
         };
+        let external = match &prost.info {
+            Some(Info::ExternalTable(table)) => Some(ExternalTableDesc {
+                location: table.location.clone(),
+                format: table.format(),
+                partition_columns: table.partition_columns.clone(),
+            }),
+            _ => None,
+        };
         let columns = prost_columns.into_iter().map(ColumnCatalog::from).collect();
 
-        let append_only = check_append_only(&with_options);
         let owner: String = prost.owner.clone();
 
         Self {
@@ -101,19 +132,23 @@ impl From<&ProstSource> for SourceCatalog {
             source_type,
             append_only,
             owner,
+            external,
         }
     }
 }
 
-fn check_append_only(with_options: &HashMap<String, String>) -> bool {
+pub(crate) fn check_append_only(with_options: &HashMap<String, String>) -> bool {
     if let Some(val) = with_options.get(WithOptions::AppenOnly) {
         if val.to_lowercase() == "true" {
             return true;
         }
     }
-    if let Some(val) = with_options.get(WithOptions::Connector) {
-        // Kafka source is append-only
-        if val.to_lowercase() == KAFKA_CONNECTOR {
+    false
+}
+
+pub(crate) fn check_shared(with_options: &HashMap<String, String>) -> bool {
+    if let Some(val) = with_options.get(WithOptions::Shared) {
+        if val.to_lowercase() == "true" {
             return true;
         }
     }
@@ -15,12 +15,15 @@
 use std::collections::{HashMap, HashSet};
 
 use itertools::Itertools;
-use risingwave_common::catalog::{ColumnDesc, OrderedColumnDesc, TableDesc};
+use risingwave_common::catalog::{
+    ColumnDesc, ColumnId, OrderedColumnDesc, TableDesc, WatermarkDesc, PARTITION_BY_KEY,
+    PARTITION_INTERVAL_SECONDS_KEY, WATERMARK_DELAY_SECONDS_KEY, WATERMARK_KEY,
+};
 use risingwave_common::util::compress::decompress_data;
 use risingwave_common::util::sort_util::OrderType;
 use risingwave_pb::catalog::table::OptionalAssociatedSourceId;
 use risingwave_pb::catalog::Table as ProstTable;
-use risingwave_pb::plan_common::OrderType as ProstOrderType;
+use risingwave_pb::plan_common::{HandleConflictBehavior, OrderType as ProstOrderType};
 
 use super::column_catalog::ColumnCatalog;
 use super::{DatabaseId, SchemaId};
@@ -40,6 +43,9 @@ pub struct TableCatalog {
     /// Keys used as materialize's storage key prefix, including MV order keys and pks.
     pub order_desc: Vec<OrderedColumnDesc>,
 
+    /// Number of leading keys of `order_desc` given by the `ORDER BY` of a materialized view.
+    pub sort_key_len: usize,
+
     /// Primary key columns indices.
     pub pks: Vec<usize>,
 
@@ -49,6 +55,9 @@ pub struct TableCatalog {
     /// If set to Some(TableId), then this table is an index on another table.
     pub is_index_on: Option<TableId>,
 
+    /// If set to Some(TableId), then this table is an internal state table of a materialized view.
+    pub is_internal_of: Option<TableId>,
+
     /// The appendonly attribute is derived from `StreamMaterialize` and `StreamTableScan` relies
     /// on this to derive an append-only stream plan
     pub appendonly: bool,
@@ -61,6 +70,22 @@ pub struct TableCatalog {
     pub vnode_mapping: Option<Vec<u32>>,
 
     pub properties: HashMap<String, String>,
+
+    /// The SQL definition of a materialized view, empty for the others.
+    pub definition: String,
+
+    /// How to handle a row inserted with an existing primary key.
+    pub handle_pk_conflict_behavior: HandleConflictBehavior,
+
+    /// The comment set by `COMMENT ON`, empty if not set.
+    pub description: String,
+
+    /// The comments of the columns, keyed by column id.
+    pub column_descriptions: HashMap<i32, String>,
+
+    /// Ids of the tables and sources a materialized view is built on, resolved by meta when it's
+    /// created.
+    pub dependent_relations: Vec<TableId>,
 }
 
 impl TableCatalog {
@@ -85,6 +110,12 @@ impl TableCatalog {
         self.order_desc.as_ref()
     }
 
+    /// The keys given by the `ORDER BY` of a materialized view, in which order its rows are
+    /// stored. Empty if the materialized view is not ordered.
+    pub fn sort_key(&self) -> &[OrderedColumnDesc] {
+        &self.order_desc[..self.sort_key_len]
+    }
+
     /// Get a [`TableDesc`] of the table.
     pub fn table_desc(&self) -> TableDesc {
         TableDesc {
@@ -94,9 +125,39 @@ impl TableCatalog {
             columns: self.columns.iter().map(|c| c.column_desc.clone()).collect(),
             distribution_keys: self.distribution_keys.clone(),
             appendonly: self.appendonly,
+            watermark: self.watermark(),
         }
     }
 
+    /// The event-time watermark declared by the `watermark` property of the table.
+    pub fn watermark(&self) -> Option<WatermarkDesc> {
+        let column = self.properties.get(WATERMARK_KEY)?;
+        let column_index = self
+            .columns
+            .iter()
+            .position(|c| c.name() == column.as_str())?;
+        let delay_seconds: u64 = self
+            .properties
+            .get(WATERMARK_DELAY_SECONDS_KEY)
+            .map_or(0, |s| s.parse().unwrap());
+        Some(WatermarkDesc {
+            column_index,
+            delay_ms: delay_seconds * 1000,
+        })
+    }
+
+    /// The partition column and the partition interval in seconds declared by the `partition_by`
+    /// property of the table.
+    pub fn partition(&self) -> Option<(&str, u32)> {
+        let column = self.properties.get(PARTITION_BY_KEY)?;
+        let interval_seconds = self
+            .properties
+            .get(PARTITION_INTERVAL_SECONDS_KEY)?
+            .parse()
+            .unwrap();
+        Some((column, interval_seconds))
+    }
+
     /// Get a reference to the table catalog's name.
     pub fn name(&self) -> &str {
         self.name.as_ref()
@@ -106,6 +167,13 @@ impl TableCatalog {
         self.distribution_keys.as_ref()
     }
 
+    /// Get the comment of the column `column_id`, if any.
+    pub fn column_description(&self, column_id: ColumnId) -> Option<&str> {
+        self.column_descriptions
+            .get(&column_id.get_id())
+            .map(String::as_str)
+    }
+
     pub fn to_prost(&self, schema_id: SchemaId, database_id: DatabaseId) -> ProstTable {
         let (order_column_ids, orders) = self
             .order_desc()
@@ -127,12 +195,18 @@ impl TableCatalog {
             order_column_ids,
             orders,
             pk: self.pks.iter().map(|x| *x as _).collect(),
-            dependent_relations: vec![],
+            dependent_relations: self
+                .dependent_relations
+                .iter()
+                .map(|id| id.table_id)
+                .collect(),
             optional_associated_source_id: self
                 .associated_source_id
                 .map(|source_id| OptionalAssociatedSourceId::AssociatedSourceId(source_id.into())),
             is_index: self.is_index_on.is_some(),
             index_on_id: self.is_index_on.unwrap_or_default().table_id(),
+            is_internal: self.is_internal_of.is_some(),
+            internal_of_id: self.is_internal_of.unwrap_or_default().table_id(),
             distribution_keys: self
                 .distribution_keys
                 .iter()
@@ -142,6 +216,11 @@ impl TableCatalog {
             owner: self.owner.clone(),
             mapping: None,
             properties: HashMap::default(),
+            definition: self.definition.clone(),
+            handle_pk_conflict_behavior: self.handle_pk_conflict_behavior as i32,
+            description: self.description.clone(),
+            column_descriptions: self.column_descriptions.clone(),
+            sort_key_len: self.sort_key_len as u32,
         }
     }
 }
@@ -192,12 +271,18 @@ impl From<ProstTable> for TableCatalog {
             associated_source_id: associated_source_id.map(Into::into),
             name,
             order_desc,
+            sort_key_len: tb.sort_key_len as usize,
             columns,
             is_index_on: if tb.is_index {
                 Some(tb.index_on_id.into())
             } else {
                 None
             },
+            is_internal_of: if tb.is_internal {
+                Some(tb.internal_of_id.into())
+            } else {
+                None
+            },
             distribution_keys: tb
                 .distribution_keys
                 .iter()
@@ -207,7 +292,16 @@ impl From<ProstTable> for TableCatalog {
             appendonly: tb.appendonly,
             owner: tb.owner,
             vnode_mapping: Some(vnode_mapping),
+            handle_pk_conflict_behavior: tb.handle_pk_conflict_behavior(),
             properties: tb.properties,
+            definition: tb.definition,
+            description: tb.description,
+            column_descriptions: tb.column_descriptions,
+            dependent_relations: tb
+                .dependent_relations
+                .into_iter()
+                .map(TableId::new)
+                .collect(),
         }
     }
 }
@@ -231,7 +325,7 @@ mod tests {
     use risingwave_pb::catalog::Table as ProstTable;
     use risingwave_pb::common::ParallelUnitMapping;
     use risingwave_pb::plan_common::{
-        ColumnCatalog as ProstColumnCatalog, ColumnDesc as ProstColumnDesc,
+        ColumnCatalog as ProstColumnCatalog, ColumnDesc as ProstColumnDesc, HandleConflictBehavior,
     };
 
     use crate::catalog::column_catalog::ColumnCatalog;
@@ -245,6 +339,9 @@ mod tests {
         let table: TableCatalog = ProstTable {
             is_index: false,
             index_on_id: 0,
+            is_internal: false,
+            internal_of_id: 0,
+            definition: String::new(),
             id: 0,
             schema_id: 0,
             database_id: 0,
@@ -290,6 +387,10 @@ mod tests {
                 data,
             }),
             properties: HashMap::from([(String::from("ttl"), String::from("300"))]),
+            handle_pk_conflict_behavior: HandleConflictBehavior::Overwrite as i32,
+            description: "test table".to_string(),
+            column_descriptions: HashMap::from([(1, "country".to_string())]),
+            sort_key_len: 0,
         }
         .into();
 
@@ -297,6 +398,7 @@ mod tests {
             table,
             TableCatalog {
                 is_index_on: None,
+                is_internal_of: None,
                 id: TableId::new(0),
                 associated_source_id: Some(TableId::new(233)),
                 name: "test".to_string(),
@@ -335,12 +437,20 @@ mod tests {
                     column_desc: row_id_column_desc(),
                     order: OrderType::Ascending
                 }],
+                sort_key_len: 0,
                 distribution_keys: vec![],
                 appendonly: false,
                 owner: risingwave_common::catalog::DEFAULT_SUPPER_USER.to_string(),
                 vnode_mapping: Some(mapping),
                 properties: HashMap::from([(String::from("ttl"), String::from("300"))]),
+                definition: String::new(),
+                handle_pk_conflict_behavior: HandleConflictBehavior::Overwrite,
+                description: "test table".to_string(),
+                column_descriptions: HashMap::from([(1, "country".to_string())]),
+                dependent_relations: vec![],
             }
         );
+        assert_eq!(table.column_description(ColumnId::new(1)), Some("country"));
+        assert_eq!(table.column_description(ColumnId::new(2)), None);
     }
 }
@@ -148,6 +148,61 @@ impl FunctionCall {
                     .try_collect()?;
                 Ok(DataType::Varchar)
             }
+            ExprType::Format => {
+                if inputs.is_empty() {
+                    return Err(ErrorCode::BindError(
+                        "Function `Format` takes at least 1 arguments (0 given)".to_string(),
+                    )
+                    .into());
+                }
+                inputs = inputs
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, input)| match i {
+                        // 0-th arg is the format string
+                        0 => input.cast_implicit(DataType::Varchar),
+                        // subsequent can be any type, using the output format
+                        _ => input.cast_output(),
+                    })
+                    .try_collect()?;
+                Ok(DataType::Varchar)
+            }
+            ExprType::RegexpMatch => {
+                let actual = inputs.len();
+                if !(2..=3).contains(&actual) {
+                    return Err(ErrorCode::BindError(format!(
+                        "Function `RegexpMatch` takes 2 or 3 arguments ({} given)",
+                        actual
+                    ))
+                    .into());
+                }
+                inputs = inputs
+                    .into_iter()
+                    .map(|input| input.cast_implicit(DataType::Varchar))
+                    .try_collect()?;
+                Ok(DataType::List {
+                    datatype: Box::new(DataType::Varchar),
+                })
+            }
+            ExprType::Locf | ExprType::Interpolate => {
+                if inputs.len() != 1 {
+                    return Err(ErrorCode::BindError(format!(
+                        "Function `{:?}` takes 1 argument ({} given)",
+                        func_type,
+                        inputs.len()
+                    ))
+                    .into());
+                }
+                let return_type = inputs[0].return_type();
+                if func_type == ExprType::Interpolate && !return_type.is_numeric() {
+                    return Err(ErrorCode::BindError(format!(
+                        "Function `Interpolate` takes a numeric argument ({:?} given)",
+                        return_type
+                    ))
+                    .into());
+                }
+                Ok(return_type)
+            }
             ExprType::ConcatOp => {
                 inputs = inputs
                     .into_iter()
@@ -15,9 +15,11 @@
 use enum_as_inner::EnumAsInner;
 use fixedbitset::FixedBitSet;
 use paste::paste;
+use risingwave_common::array::{ListRef, ListValue, Row};
 use risingwave_common::error::Result;
-use risingwave_common::types::{DataType, Scalar};
-use risingwave_expr::expr::AggKind;
+use risingwave_common::types::{DataType, Scalar, ScalarImpl};
+use risingwave_expr::expr::{build_from_prost, AggKind};
+use risingwave_expr::vector_op::distance::to_vector;
 use risingwave_pb::expr::ExprNode;
 
 mod agg_call;
@@ -260,8 +262,8 @@ impl ExprImpl {
         if let ExprImpl::FunctionCall(function_call) = self &&
         function_call.get_expr_type() == ExprType::Equal{
             match function_call.clone().decompose_as_binary() {
-                (_, ExprImpl::InputRef(x), ExprImpl::Literal(y)) => Some((*x, *y)),
-                (_, ExprImpl::Literal(x), ExprImpl::InputRef(y)) => Some((*y, *x)),
+                (_, ExprImpl::InputRef(x), y) => Some((*x, y.fold_literal_cast()?)),
+                (_, x, ExprImpl::InputRef(y)) => Some((*y, x.fold_literal_cast()?)),
                 _ => None,
             }
         } else {
@@ -270,16 +272,6 @@ impl ExprImpl {
     }
 
     pub fn as_comparison_const(&self) -> Option<(InputRef, ExprType, Literal)> {
-        fn reverse_comparison(comparison: ExprType) -> ExprType {
-            match comparison {
-                ExprType::LessThan => ExprType::GreaterThan,
-                ExprType::LessThanOrEqual => ExprType::GreaterThanOrEqual,
-                ExprType::GreaterThan => ExprType::LessThan,
-                ExprType::GreaterThanOrEqual => ExprType::LessThanOrEqual,
-                _ => unreachable!(),
-            }
-        }
-
         if let ExprImpl::FunctionCall(function_call) = self {
             match function_call.get_expr_type() {
                 ty @ (ExprType::LessThan
@@ -288,9 +280,9 @@ impl ExprImpl {
                 | ExprType::GreaterThanOrEqual) => {
                     let (_, op1, op2) = function_call.clone().decompose_as_binary();
                     match (op1, op2) {
-                        (ExprImpl::InputRef(x), ExprImpl::Literal(y)) => Some((*x, ty, *y)),
-                        (ExprImpl::Literal(x), ExprImpl::InputRef(y)) => {
-                            Some((*y, reverse_comparison(ty), *x))
+                        (ExprImpl::InputRef(x), y) => Some((*x, ty, y.fold_literal_cast()?)),
+                        (x, ExprImpl::InputRef(y)) => {
+                            Some((*y, reverse_comparison(ty), x.fold_literal_cast()?))
                         }
                         _ => None,
                     }
@@ -301,6 +293,241 @@ impl ExprImpl {
             None
         }
     }
+
+    /// Returns the literal of a literal or a cast of one, e.g. `'2022-07-01':Varchar::Timestamp`,
+    /// which is folded into a literal of the cast type.
+    fn fold_literal_cast(&self) -> Option<Literal> {
+        match self {
+            ExprImpl::Literal(literal) => Some(*literal.clone()),
+            ExprImpl::FunctionCall(function_call)
+                if function_call.get_expr_type() == ExprType::Cast
+                    && matches!(function_call.inputs(), [ExprImpl::Literal(_)]) =>
+            {
+                let datum = build_from_prost(&self.to_expr_proto())
+                    .ok()?
+                    .eval_row(&Row::new(vec![]))
+                    .ok()?;
+                Some(Literal::new(datum, self.return_type()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the two columns compared by the expression and the comparison, where the first
+    /// column has the smaller index, e.g. `$3 > $1` becomes `($1, LessThan, $3)`.
+    pub fn as_comparison_cond(&self) -> Option<(InputRef, ExprType, InputRef)> {
+        let ExprImpl::FunctionCall(function_call) = self else {
+            return None;
+        };
+        let ty = function_call.get_expr_type();
+        if !matches!(
+            ty,
+            ExprType::LessThan
+                | ExprType::LessThanOrEqual
+                | ExprType::GreaterThan
+                | ExprType::GreaterThanOrEqual
+        ) {
+            return None;
+        }
+        match function_call.clone().decompose_as_binary() {
+            (_, ExprImpl::InputRef(x), ExprImpl::InputRef(y)) => {
+                if x.index() < y.index() {
+                    Some((*x, ty, *y))
+                } else {
+                    Some((*y, reverse_comparison(ty), *x))
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Checks whether the expression contains `NOW()`, which can only be used in the comparisons
+    /// accepted by [`ExprImpl::as_now_comparison`] in a streaming job.
+    pub fn has_now(&self) -> bool {
+        struct Has {
+            has: bool,
+        }
+
+        impl ExprVisitor for Has {
+            fn visit_function_call(&mut self, func_call: &FunctionCall) {
+                if func_call.get_expr_type() == ExprType::Now {
+                    self.has = true;
+                } else {
+                    func_call
+                        .inputs()
+                        .iter()
+                        .for_each(|expr| self.visit_expr(expr));
+                }
+            }
+        }
+
+        let mut visitor = Has { has: false };
+        visitor.visit_expr(self);
+        visitor.has
+    }
+
+    /// Checks whether the expression contains `locf()` or `interpolate()`, which can only be used
+    /// at the top of a select item of a query grouped by a tumble window.
+    pub fn has_gap_fill_strategy(&self) -> bool {
+        struct Has {
+            has: bool,
+        }
+
+        impl ExprVisitor for Has {
+            fn visit_function_call(&mut self, func_call: &FunctionCall) {
+                if matches!(
+                    func_call.get_expr_type(),
+                    ExprType::Locf | ExprType::Interpolate
+                ) {
+                    self.has = true;
+                } else {
+                    func_call
+                        .inputs()
+                        .iter()
+                        .for_each(|expr| self.visit_expr(expr));
+                }
+            }
+        }
+
+        let mut visitor = Has { has: false };
+        visitor.visit_expr(self);
+        visitor.has
+    }
+
+    /// Returns the column, the comparison and the bound if this compares a column with a bound that
+    /// never goes down, i.e. `NOW()` plus or minus a constant, e.g. `v > NOW() - INTERVAL '1
+    /// hour'`. The comparison is reversed if the column is on the right.
+    pub fn as_now_comparison(&self) -> Option<(InputRef, ExprType, ExprImpl)> {
+        let ExprImpl::FunctionCall(function_call) = self else {
+            return None;
+        };
+        let ty = function_call.get_expr_type();
+        if !matches!(
+            ty,
+            ExprType::LessThan
+                | ExprType::LessThanOrEqual
+                | ExprType::GreaterThan
+                | ExprType::GreaterThanOrEqual
+        ) {
+            return None;
+        }
+        match function_call.clone().decompose_as_binary() {
+            (_, ExprImpl::InputRef(x), y) if y.is_now_offset() => Some((*x, ty, y)),
+            (_, x, ExprImpl::InputRef(y)) if x.is_now_offset() => {
+                Some((*y, reverse_comparison(ty), x))
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether this is `NOW()` plus or minus a constant.
+    fn is_now_offset(&self) -> bool {
+        let ExprImpl::FunctionCall(function_call) = self else {
+            return false;
+        };
+        let is_const = |expr: &ExprImpl| {
+            matches!(expr, ExprImpl::Literal(literal) if literal.get_data().is_some())
+        };
+        match function_call.get_expr_type() {
+            ExprType::Now => true,
+            ExprType::Add => {
+                let (_, x, y) = function_call.clone().decompose_as_binary();
+                (x.is_now_offset() && is_const(&y)) || (is_const(&x) && y.is_now_offset())
+            }
+            ExprType::Subtract => {
+                let (_, x, y) = function_call.clone().decompose_as_binary();
+                x.is_now_offset() && is_const(&y)
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the text column and the query if this is a full-text match of a column with a
+    /// constant query, e.g. `to_tsvector(v) @@ to_tsquery('fat & rat')`. The conversions of both
+    /// sides are optional.
+    pub fn as_ts_match_const(&self) -> Option<(InputRef, String)> {
+        let ExprImpl::FunctionCall(function_call) = self else {
+            return None;
+        };
+        if function_call.get_expr_type() != ExprType::TsMatch {
+            return None;
+        }
+        let unwrap = |expr: ExprImpl, ty: ExprType| match expr {
+            ExprImpl::FunctionCall(func) if func.get_expr_type() == ty => {
+                func.decompose_as_unary().1
+            }
+            expr => expr,
+        };
+        let (_, doc, query) = function_call.clone().decompose_as_binary();
+        match (
+            unwrap(doc, ExprType::ToTsvector),
+            unwrap(query, ExprType::ToTsquery),
+        ) {
+            (ExprImpl::InputRef(column), ExprImpl::Literal(query)) => match query.get_data() {
+                Some(ScalarImpl::Utf8(query)) => Some((*column, query.clone())),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns the vector column and the constant vector if this is a distance between them, e.g.
+    /// `v <-> ARRAY[1.0, 2.0]`, whose ascending order is from the nearest vector to the farthest.
+    pub fn as_vector_distance_const(&self) -> Option<(InputRef, Vec<f64>)> {
+        let ExprImpl::FunctionCall(function_call) = self else {
+            return None;
+        };
+        if !matches!(
+            function_call.get_expr_type(),
+            ExprType::L2Distance | ExprType::CosineDistance | ExprType::NegativeInnerProduct
+        ) {
+            return None;
+        }
+        // The distances are symmetric.
+        match function_call.clone().decompose_as_binary() {
+            (_, ExprImpl::InputRef(column), vector) | (_, vector, ExprImpl::InputRef(column)) => {
+                Some((*column, vector.as_const_vector()?))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the vector if this is a constant list of numbers.
+    fn as_const_vector(&self) -> Option<Vec<f64>> {
+        let values = match self {
+            ExprImpl::Literal(literal) => match literal.get_data() {
+                Some(ScalarImpl::List(list)) => list.values().to_vec(),
+                _ => return None,
+            },
+            ExprImpl::FunctionCall(function_call)
+                if function_call.get_expr_type() == ExprType::Array =>
+            {
+                function_call
+                    .inputs()
+                    .iter()
+                    .map(|input| match input {
+                        ExprImpl::Literal(literal) => Some(literal.get_data().clone()),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<_>>>()?
+            }
+            _ => return None,
+        };
+        to_vector(ListRef::ValueRef {
+            val: &ListValue::new(values),
+        })
+        .ok()
+    }
+}
+
+fn reverse_comparison(comparison: ExprType) -> ExprType {
+    match comparison {
+        ExprType::LessThan => ExprType::GreaterThan,
+        ExprType::LessThanOrEqual => ExprType::GreaterThanOrEqual,
+        ExprType::GreaterThan => ExprType::LessThan,
+        ExprType::GreaterThanOrEqual => ExprType::LessThanOrEqual,
+        _ => unreachable!(),
+    }
 }
 
 impl Expr for ExprImpl {
@@ -85,9 +85,15 @@ impl From<&CastContext> for String {
     }
 }
 
-/// Checks whether casting from `source` to `target` is ok in `allows` context.
+/// Checks whether casting from `source` to `target` is ok in `allows` context. A list can be cast
+/// to another list if its elements can.
 pub fn cast_ok(source: &DataType, target: &DataType, allows: CastContext) -> bool {
-    cast_ok_base(source.into(), target.into(), allows)
+    match (source, target) {
+        (DataType::List { datatype: source }, DataType::List { datatype: target }) => {
+            source == target || cast_ok(source, target, allows)
+        }
+        _ => cast_ok_base(source.into(), target.into(), allows),
+    }
 }
 
 pub fn cast_ok_base(source: DataTypeName, target: DataTypeName, allows: CastContext) -> bool {
@@ -129,6 +135,7 @@ fn build_cast_map() -> HashMap<(DataTypeName, DataTypeName), CastContext> {
         T::Timestampz,
         T::Time,
         T::Interval,
+        T::Uuid,
     ] {
         m.insert((t, T::Varchar), CastContext::Assign);
         // Casting from string is explicit-only in PG.
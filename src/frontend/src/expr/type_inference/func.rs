@@ -124,6 +124,7 @@ fn build_type_derive_map() -> FuncSigMap {
         T::Timestampz,
         T::Time,
         T::Interval,
+        T::Uuid,
     ];
     let num_types = [
         T::Int16,
@@ -163,7 +164,7 @@ fn build_type_derive_map() -> FuncSigMap {
     build_binary_cmp_funcs(&mut map, cmp_exprs, &[T::Date, T::Timestamp, T::Timestampz]);
     build_binary_cmp_funcs(&mut map, cmp_exprs, &[T::Time, T::Interval]);
     for e in cmp_exprs {
-        for t in [T::Boolean, T::Varchar] {
+        for t in [T::Boolean, T::Varchar, T::Uuid] {
             map.insert(*e, vec![t, t], T::Boolean);
         }
     }
@@ -241,9 +242,20 @@ fn build_type_derive_map() -> FuncSigMap {
     for t in [T::Timestamp, T::Date] {
         map.insert(E::TumbleStart, vec![t, T::Interval], T::Timestamp);
     }
+    map.insert(E::Now, vec![], T::Timestamp);
+    map.insert(E::GenRandomUuid, vec![], T::Uuid);
 
     // string expressions
-    for e in [E::Trim, E::Ltrim, E::Rtrim, E::Lower, E::Upper, E::Md5] {
+    for e in [
+        E::Trim,
+        E::Ltrim,
+        E::Rtrim,
+        E::Lower,
+        E::Upper,
+        E::Md5,
+        E::ToTsvector,
+        E::ToTsquery,
+    ] {
         map.insert(e, vec![T::Varchar], T::Varchar);
     }
     for e in [E::Trim, E::Ltrim, E::Rtrim] {
@@ -253,14 +265,49 @@ fn build_type_derive_map() -> FuncSigMap {
         map.insert(e, vec![T::Varchar, T::Int32], T::Varchar);
     }
     map.insert(E::Substr, vec![T::Varchar, T::Int32, T::Int32], T::Varchar);
-    for e in [E::Replace, E::Translate] {
+    for e in [E::Replace, E::Translate, E::RegexpReplace] {
         map.insert(e, vec![T::Varchar, T::Varchar, T::Varchar], T::Varchar);
     }
+    map.insert(E::RegexpReplace, vec![T::Varchar; 4], T::Varchar);
+    map.insert(E::RegexpSubstr, vec![T::Varchar, T::Varchar], T::Varchar);
     for e in [E::Length, E::Ascii, E::CharLength] {
         map.insert(e, vec![T::Varchar], T::Int32);
     }
     map.insert(E::Position, vec![T::Varchar, T::Varchar], T::Int32);
     map.insert(E::Like, vec![T::Varchar, T::Varchar], T::Boolean);
+    map.insert(E::TsMatch, vec![T::Varchar, T::Varchar], T::Boolean);
+
+    // vector expressions
+    for e in [
+        E::L2Distance,
+        E::CosineDistance,
+        E::InnerProduct,
+        E::NegativeInnerProduct,
+    ] {
+        map.insert(e, vec![T::List, T::List], T::Float64);
+    }
+    map.insert(E::IvfList, vec![T::List], T::Int32);
+
+    // geospatial expressions, where geometries are in the WKT format
+    for e in [
+        E::StGeomFromText,
+        E::StAsText,
+        E::StGeomFromWkb,
+        E::StAsBinary,
+    ] {
+        map.insert(e, vec![T::Varchar], T::Varchar);
+    }
+    map.insert(E::StPoint, vec![T::Float64, T::Float64], T::Varchar);
+    for e in [E::StDistance, E::StDistanceSphere] {
+        map.insert(e, vec![T::Varchar, T::Varchar], T::Float64);
+    }
+    map.insert(E::StContains, vec![T::Varchar, T::Varchar], T::Boolean);
+    map.insert(
+        E::StDWithin,
+        vec![T::Varchar, T::Varchar, T::Float64],
+        T::Boolean,
+    );
+    map.insert(E::StGeoHash, vec![T::Varchar, T::Int32], T::Varchar);
     map.insert(
         E::SplitPart,
         vec![T::Varchar, T::Varchar, T::Int32],
@@ -40,6 +40,7 @@ pub enum DataTypeName {
     Timestampz,
     Time,
     Interval,
+    Uuid,
     Struct,
     List,
 }
@@ -60,6 +61,7 @@ impl From<&DataType> for DataTypeName {
             DataType::Timestampz => DataTypeName::Timestampz,
             DataType::Time => DataTypeName::Time,
             DataType::Interval => DataTypeName::Interval,
+            DataType::Uuid => DataTypeName::Uuid,
             DataType::Struct { .. } => DataTypeName::Struct,
             DataType::List { .. } => DataTypeName::List,
         }
@@ -88,6 +90,7 @@ impl From<DataTypeName> for DataType {
             DataTypeName::Timestampz => DataType::Timestampz,
             DataTypeName::Time => DataType::Time,
             DataTypeName::Interval => DataType::Interval,
+            DataTypeName::Uuid => DataType::Uuid,
             DataTypeName::Struct | DataTypeName::List => {
                 panic!("Functions returning struct or list can not be inferred. Please use `FunctionCall::new_unchecked`.")
             }
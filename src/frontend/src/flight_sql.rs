@@ -0,0 +1,331 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Arrow Flight SQL endpoint of the frontend, served alongside pgwire.
+//!
+//! Only statement queries are supported for now. `GetFlightInfo` with a `CommandStatementQuery`
+//! plans the query and returns its schema together with a ticket carrying the SQL text, and
+//! `DoGet` with the ticket executes the query and streams the result as Arrow record batches.
+//!
+//! Every call authenticates with the `authorization: Basic` header, and connects to the database
+//! given by the `database` header, or `dev` if absent. Queries are audited the same as the ones
+//! from pgwire once their results are streamed, and are subject to the statement timeout and the
+//! row limit of the session.
+//!
+//! Arrow Flight is served with its own versions of tonic and prost, so the service is not available
+//! in the simulation.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use arrow::ipc::writer::IpcWriteOptions;
+use arrow_flight::flight_service_server::{FlightService, FlightServiceServer};
+use arrow_flight::sql::{
+    CommandStatementQuery, ProstAnyExt, ProstMessageExt, TicketStatementQuery,
+};
+use arrow_flight::utils::flight_data_from_arrow_batch;
+use arrow_flight::{
+    Action, ActionType, Criteria, Empty, FlightData, FlightDescriptor, FlightEndpoint, FlightInfo,
+    HandshakeRequest, HandshakeResponse, IpcMessage, PutResult, SchemaAsIpc, SchemaResult, Ticket,
+};
+use flight_prost::Message;
+use flight_prost_types::Any;
+use flight_tonic::metadata::MetadataMap;
+use flight_tonic::{Request, Response, Status, Streaming};
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use futures_async_stream::{for_await, try_stream};
+use pgwire::pg_server::{Session, SessionManager};
+use risingwave_common::array::arrow::to_arrow_schema;
+use risingwave_common::catalog::DEFAULT_DATABASE_NAME;
+use risingwave_common::error::RwError;
+use risingwave_sqlparser::ast::Statement;
+
+use crate::handler::query::{execute_query, infer_query_schema, parse_single_query};
+use crate::session::{OptimizerContext, SessionImpl, SessionManagerImpl};
+use crate::user::user_authentication::{authenticate, decode_basic_auth};
+
+const AUTHORIZATION_HEADER: &str = "authorization";
+const DATABASE_HEADER: &str = "database";
+
+type BoxedFlightStream<T> = BoxStream<'static, Result<T, Status>>;
+
+fn internal_error(e: impl ToString) -> Status {
+    Status::internal(e.to_string())
+}
+
+/// Decodes a `google.protobuf.Any` message of type `M`.
+fn decode_any<M: ProstMessageExt>(bytes: &[u8]) -> Result<M, Status> {
+    let any = Any::decode(bytes)
+        .map_err(|e| Status::invalid_argument(format!("invalid command: {}", e)))?;
+    any.unpack()
+        .map_err(|e| Status::invalid_argument(format!("invalid command: {}", e)))?
+        .ok_or_else(|| Status::unimplemented(format!("unsupported command: {}", any.type_url)))
+}
+
+fn encode_any<M: ProstMessageExt>(message: &M) -> Vec<u8> {
+    message.as_any().encode_to_vec()
+}
+
+/// Returns the user name and the password in the `authorization: Basic` header.
+fn basic_auth(metadata: &MetadataMap) -> Result<(String, String), Status> {
+    let header = metadata
+        .get(AUTHORIZATION_HEADER)
+        .and_then(|value| value.to_str().ok());
+    decode_basic_auth(header).map_err(Status::unauthenticated)
+}
+
+/// Parses `sql` which must be a single query.
+fn parse_query(sql: &str) -> Result<Statement, Status> {
+    parse_single_query(sql).map_err(|e| Status::invalid_argument(e.to_string()))
+}
+
+pub struct FlightSqlServiceImpl {
+    session_mgr: Arc<SessionManagerImpl>,
+}
+
+impl FlightSqlServiceImpl {
+    pub fn new(session_mgr: Arc<SessionManagerImpl>) -> Self {
+        Self { session_mgr }
+    }
+
+    fn connect(&self, metadata: &MetadataMap) -> Result<Arc<SessionImpl>, Status> {
+        let (user, password) = basic_auth(metadata)?;
+        let database = match metadata.get(DATABASE_HEADER) {
+            Some(database) => database
+                .to_str()
+                .map_err(|_| Status::invalid_argument("invalid database header"))?,
+            None => DEFAULT_DATABASE_NAME,
+        };
+        let session = self
+            .session_mgr
+            .connect(database, &user)
+            .map_err(|e| Status::unauthenticated(e.to_string()))?;
+        if !authenticate(session.user_authenticator(), &user, &password) {
+            return Err(Status::unauthenticated(format!(
+                "password authentication failed for user {}",
+                user
+            )));
+        }
+        Ok(session)
+    }
+}
+
+/// Executes the query and streams its result as Arrow Flight data. The chunks are converted as
+/// they arrive instead of being buffered in the frontend, so only the statement timeout and the
+/// row limit of the session apply.
+#[try_stream(ok = FlightData, error = RwError)]
+async fn query_flight_data(session: Arc<SessionImpl>, sql: String, stmt: Statement) {
+    let context = OptimizerContext::new(session, Arc::from(sql.as_str()));
+    let (data_stream, schema) = execute_query(context, stmt).await?;
+    let arrow_schema = to_arrow_schema(&schema)?;
+    let options = IpcWriteOptions::default();
+    let schema_data: FlightData = SchemaAsIpc::new(&arrow_schema, &options).into();
+    yield schema_data;
+
+    #[for_await]
+    for chunk in data_stream {
+        let batch = chunk?.to_record_batch(&schema)?;
+        let (dictionaries, batch) = flight_data_from_arrow_batch(&batch, &options);
+        for dictionary in dictionaries {
+            yield dictionary;
+        }
+        yield batch;
+    }
+}
+
+/// Same as [`query_flight_data`], but the query is audited once the stream completes or fails.
+#[try_stream(ok = FlightData, error = Status)]
+async fn flight_data_stream(session: Arc<SessionImpl>, sql: String, stmt: Statement) {
+    let audited = session.begin_audited(&stmt, &sql);
+    let mut error = None;
+    #[for_await]
+    for data in query_flight_data(session, sql, stmt) {
+        match data {
+            Ok(data) => yield data,
+            Err(e) => {
+                error = Some(e);
+                break;
+            }
+        }
+    }
+    audited.finish(error.as_ref().map(|e| e.to_string()));
+    if let Some(e) = error {
+        return Err(internal_error(e));
+    }
+}
+
+#[async_trait::async_trait]
+impl FlightService for FlightSqlServiceImpl {
+    type DoActionStream = BoxedFlightStream<arrow_flight::Result>;
+    type DoExchangeStream = BoxedFlightStream<FlightData>;
+    type DoGetStream = BoxedFlightStream<FlightData>;
+    type DoPutStream = BoxedFlightStream<PutResult>;
+    type HandshakeStream = BoxedFlightStream<HandshakeResponse>;
+    type ListActionsStream = BoxedFlightStream<ActionType>;
+    type ListFlightsStream = BoxedFlightStream<FlightInfo>;
+
+    /// Checks the credentials, and returns the same authorization header to be used as the token
+    /// of the following calls.
+    async fn handshake(
+        &self,
+        request: Request<Streaming<HandshakeRequest>>,
+    ) -> Result<Response<Self::HandshakeStream>, Status> {
+        self.connect(request.metadata())?;
+        let token = request
+            .metadata()
+            .get(AUTHORIZATION_HEADER)
+            .unwrap()
+            .clone();
+        let mut response = Response::new(futures::stream::empty::<Result<_, Status>>().boxed());
+        response.metadata_mut().insert(AUTHORIZATION_HEADER, token);
+        Ok(response)
+    }
+
+    async fn list_flights(
+        &self,
+        _request: Request<Criteria>,
+    ) -> Result<Response<Self::ListFlightsStream>, Status> {
+        Err(Status::unimplemented("list_flights"))
+    }
+
+    async fn get_flight_info(
+        &self,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        let session = self.connect(request.metadata())?;
+        let descriptor = request.into_inner();
+        let command: CommandStatementQuery = decode_any(&descriptor.cmd)?;
+        let stmt = parse_query(&command.query)?;
+
+        let context = OptimizerContext::new(session, Arc::from(command.query.as_str()));
+        let schema = infer_query_schema(context, stmt).map_err(internal_error)?;
+        let arrow_schema = to_arrow_schema(&schema).map_err(internal_error)?;
+        let IpcMessage(schema) = SchemaAsIpc::new(&arrow_schema, &IpcWriteOptions::default())
+            .try_into()
+            .map_err(internal_error)?;
+
+        // The query is stateless, so the ticket simply carries the SQL text.
+        let ticket = encode_any(&TicketStatementQuery {
+            statement_handle: command.query.into_bytes(),
+        });
+        Ok(Response::new(FlightInfo {
+            schema,
+            flight_descriptor: Some(descriptor),
+            endpoint: vec![FlightEndpoint {
+                ticket: Some(Ticket { ticket }),
+                location: vec![],
+            }],
+            total_records: -1,
+            total_bytes: -1,
+        }))
+    }
+
+    async fn get_schema(
+        &self,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<SchemaResult>, Status> {
+        Err(Status::unimplemented("get_schema"))
+    }
+
+    async fn do_get(
+        &self,
+        request: Request<Ticket>,
+    ) -> Result<Response<Self::DoGetStream>, Status> {
+        let session = self.connect(request.metadata())?;
+        let ticket: TicketStatementQuery = decode_any(&request.get_ref().ticket)?;
+        let sql = String::from_utf8(ticket.statement_handle)
+            .map_err(|_| Status::invalid_argument("invalid statement handle"))?;
+        let stmt = parse_query(&sql)?;
+        Ok(Response::new(
+            flight_data_stream(session, sql, stmt).boxed(),
+        ))
+    }
+
+    async fn do_put(
+        &self,
+        _request: Request<Streaming<FlightData>>,
+    ) -> Result<Response<Self::DoPutStream>, Status> {
+        Err(Status::unimplemented("do_put"))
+    }
+
+    async fn do_action(
+        &self,
+        _request: Request<Action>,
+    ) -> Result<Response<Self::DoActionStream>, Status> {
+        Err(Status::unimplemented("do_action"))
+    }
+
+    async fn list_actions(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<Self::ListActionsStream>, Status> {
+        Err(Status::unimplemented("list_actions"))
+    }
+
+    async fn do_exchange(
+        &self,
+        _request: Request<Streaming<FlightData>>,
+    ) -> Result<Response<Self::DoExchangeStream>, Status> {
+        Err(Status::unimplemented("do_exchange"))
+    }
+}
+
+pub async fn flight_sql_serve(
+    addr: SocketAddr,
+    session_mgr: Arc<SessionManagerImpl>,
+) -> Result<(), flight_tonic::transport::Error> {
+    tracing::info!("flight sql service listening on {}", addr);
+    flight_tonic::transport::Server::builder()
+        .add_service(FlightServiceServer::new(FlightSqlServiceImpl::new(
+            session_mgr,
+        )))
+        .serve(addr)
+        .await
+        .map_err(|e| {
+            tracing::error!("flight sql service failed: {}", e);
+            e
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_basic_auth() {
+        let mut metadata = MetadataMap::new();
+        assert!(basic_auth(&metadata).is_err());
+        metadata.insert(
+            AUTHORIZATION_HEADER,
+            format!("Basic {}", base64::encode("root:pass:word"))
+                .parse()
+                .unwrap(),
+        );
+        assert_eq!(
+            basic_auth(&metadata).unwrap(),
+            ("root".to_string(), "pass:word".to_string())
+        );
+    }
+
+    #[test]
+    fn test_ticket_round_trip() {
+        let ticket = encode_any(&TicketStatementQuery {
+            statement_handle: b"select 1".to_vec(),
+        });
+        let decoded: TicketStatementQuery = decode_any(&ticket).unwrap();
+        assert_eq!(decoded.statement_handle, b"select 1");
+        assert!(decode_any::<CommandStatementQuery>(&ticket).is_err());
+    }
+}
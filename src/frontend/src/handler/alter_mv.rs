@@ -0,0 +1,113 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pgwire::pg_response::{PgResponse, StatementType};
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_sqlparser::ast::{Ident, ObjectName, Query};
+
+use super::create_mv::{gen_create_mv_plan, REPLACING_MV_PREFIX};
+use crate::binder::Binder;
+use crate::session::OptimizerContext;
+use crate::stream_fragmenter::StreamFragmenter;
+
+/// Replaces the query of the materialized view `name`, keeping its properties. Unlike `CREATE OR
+/// REPLACE MATERIALIZED VIEW`, the meta node reuses the states of the aggregations that compute the
+/// same as the old query, so that they're not rebuilt from the upstreams.
+pub async fn handle_alter_mv_as_query(
+    context: OptimizerContext,
+    name: ObjectName,
+    query: Box<Query>,
+) -> Result<PgResponse> {
+    let session = context.session_ctx.clone();
+    let (schema_name, table_name) = Binder::resolve_table_name(session.database(), name)?;
+
+    let (old_table_id, properties) = {
+        let reader = session.env().catalog_reader().read_guard();
+        let table = reader.get_table_by_name(session.database(), &schema_name, &table_name)?;
+        if table.associated_source_id().is_some()
+            || table.is_index_on.is_some()
+            || table.is_internal_of.is_some()
+        {
+            return Err(ErrorCode::InvalidInputSyntax(format!(
+                "\"{}\" is not a materialized view",
+                table_name
+            ))
+            .into());
+        }
+        (table.id(), table.properties.clone())
+    };
+
+    // Planned under a temporary name, which the new table gives up for the old one's when it
+    // takes over.
+    let (table, graph) = {
+        let replacing_name = ObjectName(vec![
+            Ident::new(schema_name),
+            Ident::new(format!("{}{}", REPLACING_MV_PREFIX, table_name)),
+        ]);
+        let (plan, table) =
+            gen_create_mv_plan(&session, context.into(), query, replacing_name, properties)?;
+        let graph = StreamFragmenter::build_graph(plan.to_stream_prost());
+
+        (table, graph)
+    };
+
+    let catalog_writer = session.env().catalog_writer();
+    catalog_writer
+        .alter_materialized_view(old_table_id, table, graph)
+        .await?;
+
+    Ok(PgResponse::empty_result(
+        StatementType::ALTER_MATERIALIZED_VIEW,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::catalog::{DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME};
+
+    use crate::test_utils::LocalFrontend;
+
+    #[tokio::test]
+    async fn test_alter_mv_as_query_handler() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend
+            .run_sql("create table t (v1 int, v2 int);")
+            .await
+            .unwrap();
+        frontend
+            .run_sql(
+                "create materialized view mv as select v1, count(*) as cnt from t group by v1;",
+            )
+            .await
+            .unwrap();
+        frontend
+            .run_sql("alter materialized view mv as select v1, count(*) as cnt, sum(v2) as s from t group by v1;")
+            .await
+            .unwrap();
+
+        let session = frontend.session_ref();
+        let catalog_reader = session.env().catalog_reader().read_guard();
+        let mv = catalog_reader
+            .get_table_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "mv")
+            .unwrap();
+        assert!(mv.columns().iter().any(|c| c.name() == "s"));
+        drop(catalog_reader);
+
+        // Only materialized views can be altered.
+        assert!(frontend
+            .run_sql("alter materialized view t as select v1 from t;")
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,40 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pgwire::pg_response::{PgResponse, StatementType};
+use risingwave_common::error::Result;
+use risingwave_sqlparser::ast::{Ident, SetVariableValue, Value};
+
+use crate::session::OptimizerContext;
+
+/// Sets a cluster-wide system parameter. The parameter is validated and persisted by meta, and
+/// takes effect on all nodes without restarting them.
+pub(super) async fn handle_alter_system(
+    context: OptimizerContext,
+    param: Ident,
+    value: SetVariableValue,
+) -> Result<PgResponse> {
+    let value = match value {
+        SetVariableValue::Literal(Value::SingleQuotedString(s)) => s,
+        value => value.to_string(),
+    };
+    context
+        .session_ctx
+        .env()
+        .meta_client()
+        .set_system_param(param.value, value)
+        .await?;
+
+    Ok(PgResponse::empty_result(StatementType::ALTER_SYSTEM))
+}
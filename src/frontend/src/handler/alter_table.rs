@@ -0,0 +1,108 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pgwire::pg_response::PgResponse;
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_common::types::NaiveDateTimeWrapper;
+use risingwave_expr::vector_op::cast::str_to_timestamp;
+use risingwave_sqlparser::ast::{
+    AlterTableOperation, BinaryOperator, DataType, Expr, Ident, ObjectName, Statement, Value,
+};
+
+use super::dml;
+use crate::binder::Binder;
+use crate::session::OptimizerContext;
+
+pub async fn handle_alter_table(
+    context: OptimizerContext,
+    table_name: ObjectName,
+    operation: AlterTableOperation,
+) -> Result<PgResponse> {
+    match operation {
+        AlterTableOperation::DropPartition { value } => {
+            handle_drop_partition(context, table_name, value).await
+        }
+        _ => {
+            Err(ErrorCode::NotImplemented(format!("ALTER TABLE {}", operation), None.into()).into())
+        }
+    }
+}
+
+/// Drops the partition of a partitioned table containing the timestamp `value`, by deleting the
+/// rows of its time range. As the rows are ordered by the partition column, the deletion only scans
+/// the partition, and the materialized views on the table see the rows deleted.
+async fn handle_drop_partition(
+    context: OptimizerContext,
+    table_name: ObjectName,
+    value: Value,
+) -> Result<PgResponse> {
+    let session = context.session_ctx.clone();
+    let (schema_name, name) = Binder::resolve_table_name(session.database(), table_name.clone())?;
+    let (column, interval_seconds) = {
+        let catalog_reader = session.env().catalog_reader().read_guard();
+        let table = catalog_reader.get_table_by_name(session.database(), &schema_name, &name)?;
+        let Some((column, interval_seconds)) = table.partition() else {
+            return Err(ErrorCode::InvalidInputSyntax(format!(
+                "table \"{}\" is not partitioned",
+                name
+            ))
+            .into());
+        };
+        if table.appendonly {
+            return Err(ErrorCode::InvalidInputSyntax(format!(
+                "partitions of append-only table \"{}\" can only expire by partition_retention",
+                name
+            ))
+            .into());
+        }
+        (column.to_string(), interval_seconds as i64)
+    };
+
+    let Value::SingleQuotedString(timestamp) = value else {
+        return Err(ErrorCode::InvalidInputSyntax(format!(
+            "expected a timestamp string for the partition, got {}",
+            value
+        ))
+        .into());
+    };
+    let seconds = str_to_timestamp(&timestamp)?.0.timestamp();
+    let start = seconds.div_euclid(interval_seconds) * interval_seconds;
+    let bound = |seconds: i64| -> Result<Box<Expr>> {
+        let timestamp = NaiveDateTimeWrapper::with_secs_nsecs(seconds, 0)
+            .map_err(|e| ErrorCode::InvalidInputSyntax(e.to_string()))?;
+        Ok(Box::new(Expr::TypedString {
+            data_type: DataType::Timestamp(false),
+            value: timestamp.to_string(),
+        }))
+    };
+    let column = || Box::new(Expr::Identifier(Ident::with_quote('"', column.clone())));
+    let selection = Expr::BinaryOp {
+        left: Box::new(Expr::BinaryOp {
+            left: column(),
+            op: BinaryOperator::GtEq,
+            right: bound(start)?,
+        }),
+        op: BinaryOperator::And,
+        right: Box::new(Expr::BinaryOp {
+            left: column(),
+            op: BinaryOperator::Lt,
+            right: bound(start + interval_seconds)?,
+        }),
+    };
+    let delete = Statement::Delete {
+        table_name,
+        selection: Some(selection),
+    };
+    dml::handle_dml(context, delete).await
+}
@@ -0,0 +1,130 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pgwire::pg_response::{PgResponse, StatementType};
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_pb::user::update_user_request::UpdateField;
+use risingwave_sqlparser::ast::{Ident, ObjectName, SetVariableValue, Value};
+
+use crate::binder::Binder;
+use crate::catalog::CatalogError;
+use crate::session::OptimizerContext;
+
+/// Sets or resets (with `value` of `None`) a limit of the user, which applies to all the following
+/// statements of its sessions, see [`crate::scheduler::QueryLimits`]. Only superusers can alter the
+/// limits.
+pub async fn handle_alter_user(
+    context: OptimizerContext,
+    user_name: ObjectName,
+    param: Ident,
+    value: Option<SetVariableValue>,
+) -> Result<PgResponse> {
+    let session = context.session_ctx;
+    let user_name = Binder::resolve_user_name(user_name)?;
+    let value = match value {
+        Some(SetVariableValue::Literal(Value::Number(v, _))) => v.parse::<u64>().ok(),
+        Some(_) => None,
+        None => Some(0),
+    }
+    .ok_or_else(|| {
+        ErrorCode::InvalidParameterValue(format!("{} must be a non-negative integer", param.value))
+    })?;
+
+    let mut user = {
+        let user_reader = session.env().user_info_reader();
+        let reader = user_reader.read_guard();
+        if let Some(current_user) = reader.get_user_by_name(session.user_name())
+            && !current_user.is_supper
+        {
+            return Err(ErrorCode::PermissionDenied(
+                "must be superuser to alter the limits of users".to_string(),
+            )
+            .into());
+        }
+        reader
+            .get_user_by_name(&user_name)
+            .cloned()
+            .ok_or_else(|| CatalogError::NotFound("user", user_name.clone()))?
+    };
+
+    let limits = user.limits.get_or_insert_with(Default::default);
+    match param.value.to_ascii_lowercase().as_str() {
+        "statement_timeout" => limits.statement_timeout_ms = value,
+        "max_result_rows" => limits.max_result_rows = value,
+        "memory_limit" => limits.memory_limit_bytes = value,
+        _ => {
+            return Err(ErrorCode::UnrecognizedConfigurationParameter(param.value).into());
+        }
+    }
+
+    let user_info_writer = session.env().user_info_writer();
+    user_info_writer
+        .update_user(user, vec![UpdateField::Limits])
+        .await?;
+    Ok(PgResponse::empty_result(StatementType::ALTER_USER))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::LocalFrontend;
+
+    #[tokio::test]
+    async fn test_alter_user() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        let session = frontend.session_ref();
+        let user_info_reader = session.env().user_info_reader();
+
+        frontend.run_sql("CREATE USER user").await.unwrap();
+        frontend
+            .run_sql("ALTER USER user SET max_result_rows = 100")
+            .await
+            .unwrap();
+        frontend
+            .run_sql("ALTER USER user SET statement_timeout TO 60000")
+            .await
+            .unwrap();
+        frontend
+            .run_sql("ALTER USER user RESET statement_timeout")
+            .await
+            .unwrap();
+        let limits = user_info_reader
+            .read_guard()
+            .get_user_by_name("user")
+            .unwrap()
+            .limits
+            .clone()
+            .unwrap();
+        assert_eq!(limits.max_result_rows, 100);
+        assert_eq!(limits.statement_timeout_ms, 0);
+
+        assert!(frontend
+            .run_sql("ALTER USER user SET max_result_rows = -1")
+            .await
+            .is_err());
+        assert!(frontend
+            .run_sql("ALTER USER user SET unknown_limit = 1")
+            .await
+            .is_err());
+
+        // The session can't raise the limits of its user.
+        frontend
+            .run_sql("ALTER USER root SET max_result_rows = 10")
+            .await
+            .unwrap();
+        session.set_config("max_result_rows", "100").unwrap();
+        assert_eq!(session.query_limits().max_result_rows, Some(10));
+        session.set_config("max_result_rows", "5").unwrap();
+        assert_eq!(session.query_limits().max_result_rows, Some(5));
+    }
+}
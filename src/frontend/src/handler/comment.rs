@@ -0,0 +1,155 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pgwire::pg_response::{PgResponse, StatementType};
+use risingwave_common::error::{ErrorCode, Result, RwError};
+use risingwave_sqlparser::ast::{CommentObject, ObjectName};
+
+use crate::binder::Binder;
+use crate::catalog::CatalogError;
+use crate::session::OptimizerContext;
+
+/// Sets the comment of a table, a materialized view or one of their columns. A `NULL` or empty
+/// comment removes it.
+pub async fn handle_comment(
+    context: OptimizerContext,
+    object_type: CommentObject,
+    object_name: ObjectName,
+    comment: Option<String>,
+) -> Result<PgResponse> {
+    let session = context.session_ctx;
+    let (table_name, column_name) = match object_type {
+        CommentObject::Column => {
+            let mut identifiers = object_name.0;
+            let column_name = identifiers.pop().filter(|_| !identifiers.is_empty());
+            let column_name = column_name
+                .ok_or_else(|| ErrorCode::BindError("column name must be qualified".to_string()))?;
+            (ObjectName(identifiers), Some(column_name.value))
+        }
+        CommentObject::Table | CommentObject::MaterializedView => (object_name, None),
+    };
+    let (schema_name, table_name) = Binder::resolve_table_name(session.database(), table_name)?;
+
+    let (table_id, column_id) = {
+        let catalog_reader = session.env().catalog_reader().read_guard();
+        let table =
+            catalog_reader.get_table_by_name(session.database(), &schema_name, &table_name)?;
+        let is_mv = table.associated_source_id().is_none();
+        if table.is_index_on.is_some() || table.is_internal_of.is_some() {
+            return Err(RwError::from(ErrorCode::InvalidInputSyntax(format!(
+                "\"{}\" is not a table or materialized view",
+                table_name
+            ))));
+        }
+        match object_type {
+            CommentObject::Table if is_mv => {
+                return Err(RwError::from(ErrorCode::InvalidInputSyntax(
+                    "Use `COMMENT ON MATERIALIZED VIEW` to comment on a materialized view."
+                        .to_owned(),
+                )));
+            }
+            CommentObject::MaterializedView if !is_mv => {
+                return Err(RwError::from(ErrorCode::InvalidInputSyntax(
+                    "Use `COMMENT ON TABLE` to comment on a table.".to_owned(),
+                )));
+            }
+            _ => {}
+        }
+        let column_id = match column_name {
+            Some(column_name) => Some(
+                table
+                    .columns()
+                    .iter()
+                    .find(|c| !c.is_hidden && c.name() == column_name)
+                    .ok_or_else(|| CatalogError::NotFound("column", column_name.clone()))?
+                    .column_id()
+                    .get_id(),
+            ),
+            None => None,
+        };
+        (table.id(), column_id)
+    };
+
+    let catalog_writer = session.env().catalog_writer();
+    catalog_writer
+        .comment_on(table_id, column_id, comment.unwrap_or_default())
+        .await?;
+
+    Ok(PgResponse::empty_result(StatementType::COMMENT))
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::catalog::{DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME};
+
+    use crate::test_utils::LocalFrontend;
+
+    #[tokio::test]
+    async fn test_comment_handler() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend
+            .run_sql("create table t (v1 int, v2 int);")
+            .await
+            .unwrap();
+        frontend
+            .run_sql("create materialized view mv as select v1 from t;")
+            .await
+            .unwrap();
+
+        frontend
+            .run_sql("comment on table t is 'table t'")
+            .await
+            .unwrap();
+        frontend
+            .run_sql("comment on column t.v2 is 'column v2'")
+            .await
+            .unwrap();
+        frontend
+            .run_sql("comment on materialized view mv is 'mv'")
+            .await
+            .unwrap();
+        assert!(frontend
+            .run_sql("comment on table mv is 'mv'")
+            .await
+            .is_err());
+        assert!(frontend
+            .run_sql("comment on column t.v3 is 'v3'")
+            .await
+            .is_err());
+
+        let session = frontend.session_ref();
+        let catalog_reader = session.env().catalog_reader().read_guard();
+        let table = catalog_reader
+            .get_table_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "t")
+            .unwrap();
+        let v2 = table.columns().iter().find(|c| c.name() == "v2").unwrap();
+        assert_eq!(table.description, "table t");
+        assert_eq!(table.column_description(v2.column_id()), Some("column v2"));
+        let mv = catalog_reader
+            .get_table_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "mv")
+            .unwrap();
+        assert_eq!(mv.description, "mv");
+        drop(catalog_reader);
+
+        frontend
+            .run_sql("comment on column t.v2 is null")
+            .await
+            .unwrap();
+        let catalog_reader = session.env().catalog_reader().read_guard();
+        let table = catalog_reader
+            .get_table_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "t")
+            .unwrap();
+        assert!(table.column_descriptions.is_empty());
+    }
+}
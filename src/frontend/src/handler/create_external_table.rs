@@ -0,0 +1,229 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pgwire::pg_response::{PgResponse, StatementType};
+use risingwave_common::catalog::{ColumnDesc, ColumnId};
+use risingwave_common::error::{ErrorCode, Result, ToRwResult};
+use risingwave_connector::lake::{self, delta, LakeLocation};
+use risingwave_pb::catalog::source::Info;
+use risingwave_pb::catalog::ExternalTableInfo;
+use risingwave_pb::plan_common::ExternalTableFormat as ProstExternalTableFormat;
+use risingwave_sqlparser::ast::{
+    CreateExternalSchemaStatement, CreateExternalTableStatement, ExternalTableFormat, Ident,
+    ObjectName,
+};
+
+use super::create_source::make_prost_source;
+use super::util::relation_exists;
+use crate::binder::Binder;
+use crate::catalog::column_catalog::ColumnCatalog;
+use crate::catalog::CatalogError;
+use crate::session::{OptimizerContext, SessionImpl};
+
+/// Parses the location of external tables. Their files are read with the credentials of the
+/// nodes, so only superusers can create external tables, and `disk://` locations must be under the
+/// configured `lake_disk_roots`.
+fn parse_location(session: &SessionImpl, location: &str) -> Result<LakeLocation> {
+    session.check_superuser("create external tables")?;
+    let lake_location = LakeLocation::parse(location).to_rw_result()?;
+    lake_location
+        .check_disk_roots(session.env().lake_disk_roots())
+        .to_rw_result()?;
+    Ok(lake_location)
+}
+
+/// Reads the layout of the external table at `location`. The schema is inferred from its first
+/// file, followed by its partition columns as `VARCHAR`.
+async fn bind_external_table(
+    session: &SessionImpl,
+    location: String,
+    format: ExternalTableFormat,
+) -> Result<ExternalTableInfo> {
+    let lake_location = parse_location(session, &location)?;
+    let store = lake_location.connect().await;
+    let (format, files) = match format {
+        ExternalTableFormat::Parquet => (
+            ProstExternalTableFormat::Parquet,
+            lake::list_files(store.as_ref(), lake_location.prefix())
+                .await
+                .to_rw_result()?,
+        ),
+        ExternalTableFormat::Delta => (
+            ProstExternalTableFormat::Delta,
+            delta::load_snapshot(store.as_ref(), lake_location.prefix(), None)
+                .await
+                .to_rw_result()?
+                .files,
+        ),
+        ExternalTableFormat::Iceberg => {
+            return Err(ErrorCode::NotImplemented(
+                "external tables stored as Iceberg".to_string(),
+                None.into(),
+            )
+            .into())
+        }
+    };
+    let first_file = files.first().ok_or_else(|| {
+        ErrorCode::InvalidInputSyntax(format!("no Parquet files found in {}", location))
+    })?;
+    let schema = lake::infer_schema(store.as_ref(), first_file)
+        .await
+        .to_rw_result()?;
+
+    let columns = schema
+        .fields()
+        .iter()
+        .enumerate()
+        .map(|(idx, field)| {
+            ColumnCatalog {
+                column_desc: ColumnDesc {
+                    data_type: field.data_type.clone(),
+                    column_id: ColumnId::new(idx as i32),
+                    name: field.name.clone(),
+                    field_descs: vec![],
+                    type_name: String::new(),
+                },
+                is_hidden: false,
+            }
+            .to_protobuf()
+        })
+        .collect();
+    let partition_columns = first_file
+        .partitions
+        .iter()
+        .map(|(name, _)| name.clone())
+        .collect();
+
+    Ok(ExternalTableInfo {
+        location,
+        columns,
+        partition_columns,
+        format: format as i32,
+    })
+}
+
+/// Creates an external table over the Parquet files or the Delta table under a location.
+pub async fn handle_create_external_table(
+    context: OptimizerContext,
+    stmt: CreateExternalTableStatement,
+) -> Result<PgResponse> {
+    let session = context.session_ctx;
+
+    if stmt.if_not_exists && relation_exists(&session, &stmt.table_name)? {
+        return Ok(PgResponse::empty_result_with_notice(
+            StatementType::CREATE_TABLE,
+            format!("relation {} exists, skipping", stmt.table_name),
+        ));
+    }
+
+    let info = bind_external_table(&session, stmt.location.0, stmt.format).await?;
+    let source = make_prost_source(&session, stmt.table_name, Info::ExternalTable(info))?;
+    let catalog_writer = session.env().catalog_writer();
+    catalog_writer.create_source(source).await?;
+
+    Ok(PgResponse::empty_result(StatementType::CREATE_TABLE))
+}
+
+/// Attaches a Delta Lake warehouse as a schema, with an external table for each Delta table
+/// directly under the location.
+pub async fn handle_create_external_schema(
+    context: OptimizerContext,
+    stmt: CreateExternalSchemaStatement,
+) -> Result<PgResponse> {
+    let session = context.session_ctx;
+    let (database_name, schema_name) =
+        Binder::resolve_schema_name(session.database(), stmt.schema_name)?;
+
+    if stmt.format != ExternalTableFormat::Delta {
+        return Err(ErrorCode::NotImplemented(
+            format!("external schemas stored as {}", stmt.format),
+            None.into(),
+        )
+        .into());
+    }
+
+    let db_id = {
+        let catalog_reader = session.env().catalog_reader();
+        let reader = catalog_reader.read_guard();
+        if reader
+            .get_schema_by_name(&database_name, &schema_name)
+            .is_ok()
+        {
+            return if stmt.if_not_exists {
+                Ok(PgResponse::empty_result_with_notice(
+                    StatementType::CREATE_SCHEMA,
+                    format!("schema {} exists, skipping", schema_name),
+                ))
+            } else {
+                Err(CatalogError::Duplicated("schema", schema_name).into())
+            };
+        }
+        reader.get_database_by_name(&database_name)?.id()
+    };
+
+    // Bind all tables before creating anything, so that a broken table fails the whole statement.
+    let location = stmt.location.0;
+    let lake_location = parse_location(&session, &location)?;
+    let store = lake_location.connect().await;
+    let tables = delta::list_tables(store.as_ref(), lake_location.prefix())
+        .await
+        .to_rw_result()?;
+    let mut infos = Vec::with_capacity(tables.len());
+    for (name, _) in tables {
+        let table_location = format!("{}/{}", location.trim_end_matches('/'), name);
+        infos.push((
+            name,
+            bind_external_table(&session, table_location, ExternalTableFormat::Delta).await?,
+        ));
+    }
+
+    let catalog_writer = session.env().catalog_writer();
+    catalog_writer
+        .create_schema(db_id, &schema_name, session.user_name().to_string())
+        .await?;
+    for (name, info) in infos {
+        let table_name = ObjectName(vec![Ident::new(schema_name.clone()), Ident::new(name)]);
+        let source = make_prost_source(&session, table_name, Info::ExternalTable(info))?;
+        catalog_writer.create_source(source).await?;
+    }
+
+    Ok(PgResponse::empty_result(StatementType::CREATE_SCHEMA))
+}
+
+#[cfg(test)]
+mod tests {
+    use pgwire::pg_server::Session;
+
+    use crate::test_utils::LocalFrontend;
+
+    #[tokio::test]
+    async fn test_external_table_location_access() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend.run_sql("create user u;").await.unwrap();
+
+        let err = frontend
+            .session_user_ref("u")
+            .run_statement("create external table t stored as parquet location 's3://bucket/t';")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("must be superuser"));
+
+        // No `lake_disk_roots` are configured.
+        let err = frontend
+            .run_sql("create external table t stored as parquet location 'disk:///etc';")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("lake_disk_roots"));
+    }
+}
@@ -18,25 +18,64 @@ use std::rc::Rc;
 use fixedbitset::FixedBitSet;
 use itertools::Itertools;
 use pgwire::pg_response::{PgResponse, StatementType};
+use risingwave_common::catalog::{
+    FULLTEXT_INDEX_COLUMN_KEY, IVF_INDEX_COLUMN_KEY, IVF_LIST_COLUMN_NAME, LEXEME_COLUMN_NAME,
+};
 use risingwave_common::error::{ErrorCode, Result, RwError};
+use risingwave_common::types::DataType;
 use risingwave_pb::catalog::Table as ProstTable;
-use risingwave_sqlparser::ast::{ObjectName, OrderByExpr};
+use risingwave_sqlparser::ast::{Ident, ObjectName, OrderByExpr};
 
 use crate::binder::Binder;
 use crate::catalog::check_schema_writable;
-use crate::optimizer::plan_node::{LogicalScan, StreamTableScan};
+use crate::expr::{ExprType, FunctionCall, InputRef};
+use crate::optimizer::plan_node::{
+    LogicalProject, LogicalScan, StreamProject, StreamTableScan, StreamTokenize,
+};
 use crate::optimizer::property::{FieldOrder, Order, RequiredDist};
 use crate::optimizer::{PlanRef, PlanRoot};
 use crate::session::{OptimizerContext, OptimizerContextRef, SessionImpl};
 use crate::stream_fragmenter::StreamFragmenter;
 
+/// The method of an index given by `USING method`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IndexMethod {
+    /// The rows of the table ordered by the indexed columns.
+    Btree,
+    /// A row for each lexeme of a text column, ordered by the lexeme.
+    Fulltext,
+    /// The rows of the table ordered by the IVF list of a vector column.
+    Ivf,
+}
+
+impl IndexMethod {
+    fn from_ident(method: Option<Ident>) -> Result<Self> {
+        let Some(method) = method else {
+            return Ok(Self::Btree);
+        };
+        match method.value.to_lowercase().as_str() {
+            "btree" => Ok(Self::Btree),
+            "fulltext" => Ok(Self::Fulltext),
+            "ivf" => Ok(Self::Ivf),
+            _ => Err(ErrorCode::NotImplemented(
+                format!("index method {} not supported", method),
+                None.into(),
+            )
+            .into()),
+        }
+    }
+}
+
 pub(crate) fn gen_create_index_plan(
     session: &SessionImpl,
     context: OptimizerContextRef,
     index_name: ObjectName,
     table_name: ObjectName,
+    using: Option<Ident>,
     columns: Vec<OrderByExpr>,
 ) -> Result<(PlanRef, ProstTable)> {
+    let method = IndexMethod::from_ident(using)?;
+
     let columns = columns
         .iter()
         .map(|column| {
@@ -68,7 +107,7 @@ pub(crate) fn gen_create_index_plan(
         })
         .try_collect::<_, Vec<_>, _>()?;
 
-    let (schema_name, table_name) = Binder::resolve_table_name(table_name)?;
+    let (schema_name, table_name) = Binder::resolve_table_name(session.database(), table_name)?;
     let catalog_reader = session.env().catalog_reader();
     let table = catalog_reader
         .read_guard()
@@ -93,29 +132,85 @@ pub(crate) fn gen_create_index_plan(
         })
         .try_collect::<_, Vec<_>, RwError>()?;
 
+    if method != IndexMethod::Btree {
+        if arrange_keys.len() != 1 {
+            return Err(ErrorCode::InvalidInputSyntax(format!(
+                "a {:?} index must be on exactly one column",
+                method
+            ))
+            .into());
+        }
+        let column = &table_desc.columns[arrange_keys[0]];
+        let type_ok = match method {
+            IndexMethod::Fulltext => column.data_type == DataType::Varchar,
+            IndexMethod::Ivf => matches!(column.data_type, DataType::List { .. }),
+            IndexMethod::Btree => unreachable!(),
+        };
+        if !type_ok {
+            return Err(ErrorCode::InvalidInputSyntax(format!(
+                "a {:?} index can't be on column {} of type {:?}",
+                method, column.name, column.data_type
+            ))
+            .into());
+        }
+    }
+
     // Manually assemble the materialization plan for the index MV.
     let materialize = {
-        let mut required_cols = FixedBitSet::with_capacity(table_desc.columns.len());
-        required_cols.toggle_range(..);
-        required_cols.toggle(0);
-        let mut out_names: Vec<String> =
-            table_desc.columns.iter().map(|c| c.name.clone()).collect();
-        out_names.remove(0);
-
-        let scan_node = StreamTableScan::new(LogicalScan::create(
+        let scan_node: PlanRef = StreamTableScan::new(LogicalScan::create(
             table_name,
             false,
-            table_desc,
+            table_desc.clone(),
             // indexes are only used by DeltaJoin rule, and we don't need to provide them here.
             vec![],
             context,
-        ));
+        ))
+        .into();
+
+        let mut out_names: Vec<String> =
+            table_desc.columns.iter().map(|c| c.name.clone()).collect();
+        let extra_col_idx = out_names.len();
+        // A full-text index is ordered by the lexemes of the column, with a row for each of them,
+        // and an IVF index is ordered by the list of the vector.
+        let (plan, order_keys) = match method {
+            IndexMethod::Btree => (scan_node, arrange_keys),
+            IndexMethod::Fulltext => {
+                out_names.push(LEXEME_COLUMN_NAME.to_string());
+                let tokenize = StreamTokenize::new(scan_node, arrange_keys[0]);
+                (tokenize.into(), vec![extra_col_idx])
+            }
+            IndexMethod::Ivf => {
+                out_names.push(IVF_LIST_COLUMN_NAME.to_string());
+                let column = &table_desc.columns[arrange_keys[0]];
+                let mut exprs = scan_node
+                    .schema()
+                    .fields()
+                    .iter()
+                    .enumerate()
+                    .map(|(i, f)| InputRef::new(i, f.data_type()).into())
+                    .collect_vec();
+                exprs.push(
+                    FunctionCall::new(
+                        ExprType::IvfList,
+                        vec![InputRef::new(arrange_keys[0], column.data_type.clone()).into()],
+                    )?
+                    .into(),
+                );
+                let project = StreamProject::new(LogicalProject::new(scan_node, exprs));
+                (project.into(), vec![extra_col_idx])
+            }
+        };
+
+        let mut required_cols = FixedBitSet::with_capacity(out_names.len());
+        required_cols.toggle_range(..);
+        required_cols.toggle(0);
+        out_names.remove(0);
 
         PlanRoot::new(
-            scan_node.into(),
+            plan,
             RequiredDist::AnyShard,
             Order::new(
-                arrange_keys
+                order_keys
                     .iter()
                     .map(|id| FieldOrder::ascending(*id))
                     .collect(),
@@ -126,7 +221,8 @@ pub(crate) fn gen_create_index_plan(
         .gen_create_index_plan(index_name.to_string(), table.id())?
     };
 
-    let (index_schema_name, index_table_name) = Binder::resolve_table_name(index_name)?;
+    let (index_schema_name, index_table_name) =
+        Binder::resolve_table_name(session.database(), index_name)?;
     check_schema_writable(&index_schema_name)?;
     let (index_database_id, index_schema_id) = session
         .env()
@@ -138,9 +234,23 @@ pub(crate) fn gen_create_index_plan(
             &index_table_name,
         )?;
 
-    let index_table = materialize
+    let mut index_table = materialize
         .table()
         .to_prost(index_schema_id, index_database_id);
+    match method {
+        IndexMethod::Btree => {}
+        IndexMethod::Fulltext => {
+            index_table.properties.insert(
+                FULLTEXT_INDEX_COLUMN_KEY.to_string(),
+                columns[0].to_string(),
+            );
+        }
+        IndexMethod::Ivf => {
+            index_table
+                .properties
+                .insert(IVF_INDEX_COLUMN_KEY.to_string(), columns[0].to_string());
+        }
+    }
 
     Ok((materialize.into(), index_table))
 }
@@ -149,6 +259,7 @@ pub async fn handle_create_index(
     context: OptimizerContext,
     name: ObjectName,
     table_name: ObjectName,
+    using: Option<Ident>,
     columns: Vec<OrderByExpr>,
 ) -> Result<PgResponse> {
     let session = context.session_ctx.clone();
@@ -159,6 +270,7 @@ pub async fn handle_create_index(
             context.into(),
             name.clone(),
             table_name.clone(),
+            using,
             columns,
         )?;
         let plan = plan.to_stream_prost();
@@ -13,20 +13,29 @@
 // limitations under the License.
 
 use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Arc;
 
+use itertools::Itertools;
 use pgwire::pg_response::{PgResponse, StatementType};
+use risingwave_common::catalog::{CHECKPOINT_FREQUENCY_KEY, RESOURCE_GROUP_KEY};
 use risingwave_common::error::{ErrorCode, Result};
+use risingwave_common::session_config::MV_REUSE;
 use risingwave_pb::catalog::Table as ProstTable;
-use risingwave_sqlparser::ast::{ObjectName, Query, WithProperties};
+use risingwave_sqlparser::ast::{Ident, ObjectName, Query, Statement, WithProperties};
+use risingwave_sqlparser::parser::Parser;
 
 use super::util::handle_with_properties;
 use crate::binder::{Binder, BoundSetExpr};
-use crate::catalog::check_schema_writable;
+use crate::catalog::table_catalog::TableCatalog;
+use crate::catalog::{check_schema_writable, TableId};
+use crate::optimizer::plan_node::{LogicalProject, LogicalScan};
 use crate::optimizer::property::RequiredDist;
 use crate::optimizer::PlanRef;
 use crate::planner::Planner;
 use crate::session::{OptimizerContext, OptimizerContextRef, SessionImpl};
 use crate::stream_fragmenter::StreamFragmenter;
+use crate::utils::ColIndexMapping;
 
 /// Generate create MV plan, return plan and mv table info.
 pub fn gen_create_mv_plan(
@@ -36,14 +45,34 @@ pub fn gen_create_mv_plan(
     name: ObjectName,
     properties: HashMap<String, String>,
 ) -> Result<(PlanRef, ProstTable)> {
-    let (schema_name, table_name) = Binder::resolve_table_name(name)?;
+    let (schema_name, table_name) = Binder::resolve_table_name(session.database(), name)?;
     check_schema_writable(&schema_name)?;
+    if let Some(frequency) = properties.get(CHECKPOINT_FREQUENCY_KEY) {
+        if !matches!(frequency.parse::<u32>(), Ok(frequency) if frequency > 0) {
+            return Err(ErrorCode::InvalidParameterValue(format!(
+                "{} must be a positive integer, got {}",
+                CHECKPOINT_FREQUENCY_KEY, frequency
+            ))
+            .into());
+        }
+    }
+    if properties
+        .get(RESOURCE_GROUP_KEY)
+        .map_or(false, |group| group.is_empty())
+    {
+        return Err(ErrorCode::InvalidParameterValue(format!(
+            "{} must not be empty",
+            RESOURCE_GROUP_KEY
+        ))
+        .into());
+    }
     let (database_id, schema_id) = session
         .env()
         .catalog_reader()
         .read_guard()
         .check_relation_name_duplicated(session.database(), &schema_name, &table_name)?;
 
+    let definition = query.to_string();
     let bound = {
         let mut binder = Binder::new(
             session.env().catalog_reader().read_guard(),
@@ -63,17 +92,162 @@ pub fn gen_create_mv_plan(
         }
     }
 
-    let mut plan_root = Planner::new(context).plan_query(bound)?;
+    let reuse = context
+        .inner()
+        .session_ctx
+        .get_config(MV_REUSE)
+        .map_or(false, |c| c.is_set(false));
+    let mut plan_root = Planner::new(context.clone()).plan_query(bound)?;
+    if reuse {
+        let reusable_mvs = plan_reusable_mvs(&context.inner().session_ctx);
+        plan_root.set_plan(reuse_mvs(plan_root.plan().clone(), &reusable_mvs)?);
+    }
+    let dependent_relations = scanned_relations(plan_root.plan());
     plan_root.set_required_dist(RequiredDist::Any);
     let materialize = plan_root.gen_create_mv_plan(table_name)?;
     let mut table = materialize.table().to_prost(schema_id, database_id);
     let plan: PlanRef = materialize.into();
     table.owner = session.user_name().to_string();
     table.properties = properties;
+    table.definition = definition;
+    table.dependent_relations = dependent_relations
+        .into_iter()
+        .map(|id| id.table_id)
+        .unique()
+        .collect();
 
     Ok((plan, table))
 }
 
+/// Plans the definitions of the materialized views in the current database, and returns them
+/// along with the fingerprints of the logical plans. The ones failing to plan, e.g., because of
+/// dropped dependencies, and the ones without stateful operators are skipped, as there's nothing to
+/// gain from reusing them.
+fn plan_reusable_mvs(session: &Arc<SessionImpl>) -> Vec<(TableCatalog, String)> {
+    let mvs = {
+        let catalog_reader = session.env().catalog_reader().read_guard();
+        let Ok(database) = catalog_reader.get_database_by_name(session.database()) else {
+            return vec![];
+        };
+        database
+            .iter_schemas()
+            .flat_map(|schema| schema.iter_mv())
+            .filter(|mv| !mv.definition.is_empty())
+            .cloned()
+            .collect_vec()
+    };
+
+    let plan_definition = |definition: &str| -> Result<PlanRef> {
+        let statement = Parser::parse_sql(definition)
+            .map_err(|e| ErrorCode::InternalError(e.to_string()))?
+            .pop();
+        let Some(Statement::Query(query)) = statement else {
+            return Err(ErrorCode::InternalError(format!("not a query: {}", definition)).into());
+        };
+        let bound = Binder::new(
+            session.env().catalog_reader().read_guard(),
+            session.database().to_string(),
+        )
+        .bind_query(*query)?;
+        let context = OptimizerContext::new(session.clone(), Arc::from(definition));
+        Ok(Planner::new(context.into()).plan_query(bound)?.as_subplan())
+    };
+
+    mvs.into_iter()
+        .filter_map(|mv| {
+            let plan = plan_definition(&mv.definition).ok()?;
+            if !has_stateful_operator(&plan) {
+                return None;
+            }
+            let scanned = scanned_relations(&plan);
+            // The definition is bound again in the current catalog. Skip the materialized view if
+            // its names now resolve to other relations than the ones it's built on.
+            if !scanned.iter().all(|id| mv.dependent_relations.contains(id)) {
+                return None;
+            }
+            Some((mv, plan_fingerprint(&plan)))
+        })
+        .collect()
+}
+
+/// Explains the plan like `explain_to_string`, but with the ids of the scanned tables and sources,
+/// so that plans on relations of the same name in different schemas are told apart.
+fn plan_fingerprint(plan: &PlanRef) -> String {
+    let node = if let Some(scan) = plan.as_logical_scan() {
+        format!("{} {{ table_id: {} }}", plan, scan.table_desc().table_id)
+    } else if let Some(source) = plan.as_logical_source() {
+        format!("{} {{ source_id: {} }}", plan, source.source_catalog().id)
+    } else {
+        plan.to_string()
+    };
+    format!(
+        "{}({})",
+        node,
+        plan.inputs().iter().map(plan_fingerprint).join(", ")
+    )
+}
+
+/// The ids of the tables and sources scanned by the plan.
+fn scanned_relations(plan: &PlanRef) -> Vec<TableId> {
+    let mut relations = plan
+        .inputs()
+        .iter()
+        .flat_map(scanned_relations)
+        .collect_vec();
+    if let Some(scan) = plan.as_logical_scan() {
+        relations.push(scan.table_desc().table_id);
+    } else if let Some(source) = plan.as_logical_source() {
+        relations.push(TableId::new(source.source_catalog().id));
+    }
+    relations
+}
+
+fn has_stateful_operator(plan: &PlanRef) -> bool {
+    plan.as_logical_agg().is_some()
+        || plan.as_logical_join().is_some()
+        || plan.as_logical_top_n().is_some()
+        || plan.inputs().iter().any(has_stateful_operator)
+}
+
+/// Replaces the subtrees of `plan` computing the same results as the `reusable_mvs` with scans on
+/// them. So the new materialized view is built on the changelog of the existing ones instead of
+/// recomputing from the sources, and they can't be dropped before the new one.
+fn reuse_mvs(plan: PlanRef, reusable_mvs: &[(TableCatalog, String)]) -> Result<PlanRef> {
+    if !reusable_mvs.is_empty() && has_stateful_operator(&plan) {
+        let fingerprint = plan_fingerprint(&plan);
+        if let Some((mv, _)) = reusable_mvs.iter().find(|(_, p)| *p == fingerprint) {
+            let scan = LogicalScan::create(
+                mv.name().to_string(),
+                false,
+                Rc::new(mv.table_desc()),
+                vec![],
+                plan.ctx(),
+            );
+            let visible_columns = mv
+                .columns()
+                .iter()
+                .enumerate()
+                .filter(|(_, c)| !c.is_hidden)
+                .map(|(i, _)| i)
+                .collect_vec();
+            let reused: PlanRef = LogicalProject::with_mapping(
+                scan.into(),
+                ColIndexMapping::with_remaining_columns(&visible_columns, mv.columns().len()),
+            )
+            .into();
+            if reused.schema().data_types() == plan.schema().data_types() {
+                return Ok(reused);
+            }
+        }
+    }
+    let inputs: Vec<_> = plan
+        .inputs()
+        .into_iter()
+        .map(|input| reuse_mvs(input, reusable_mvs))
+        .try_collect()?;
+    Ok(plan.clone_with_inputs(&inputs))
+}
+
 pub async fn handle_create_mv(
     context: OptimizerContext,
     name: ObjectName,
@@ -106,6 +280,77 @@ pub async fn handle_create_mv(
     ))
 }
 
+/// The prefix of the name under which the new materialized view is built by
+/// `CREATE OR REPLACE MATERIALIZED VIEW`.
+pub(super) const REPLACING_MV_PREFIX: &str = "__rw_replacing_";
+
+/// Replaces the materialized view `name` with a new definition, or creates it if not exists. The
+/// new one is built and backfilled under a temporary name first, then takes over the name while
+/// the old one is dropped in one step, so that queries never see a missing or half-built
+/// materialized view.
+pub async fn handle_create_or_replace_mv(
+    context: OptimizerContext,
+    name: ObjectName,
+    query: Box<Query>,
+    with_options: WithProperties,
+) -> Result<PgResponse> {
+    let session = context.session_ctx.clone();
+    let (schema_name, table_name) = Binder::resolve_table_name(session.database(), name.clone())?;
+
+    let old_table_id = {
+        let reader = session.env().catalog_reader().read_guard();
+        match reader.get_table_by_name(session.database(), &schema_name, &table_name) {
+            Ok(table) => {
+                if table.associated_source_id().is_some()
+                    || table.is_index_on.is_some()
+                    || table.is_internal_of.is_some()
+                {
+                    return Err(ErrorCode::InvalidInputSyntax(format!(
+                        "\"{}\" is not a materialized view",
+                        table_name
+                    ))
+                    .into());
+                }
+                Some(table.id())
+            }
+            Err(_) => None,
+        }
+    };
+    let Some(old_table_id) = old_table_id else {
+        return handle_create_mv(context, name, query, with_options).await;
+    };
+
+    let replacing_name = ObjectName(vec![
+        Ident::new(schema_name.clone()),
+        Ident::new(format!("{}{}", REPLACING_MV_PREFIX, table_name)),
+    ]);
+    handle_create_mv(context, replacing_name, query, with_options).await?;
+    let new_table_id = session
+        .env()
+        .catalog_reader()
+        .read_guard()
+        .get_table_by_name(
+            session.database(),
+            &schema_name,
+            &format!("{}{}", REPLACING_MV_PREFIX, table_name),
+        )?
+        .id();
+
+    let catalog_writer = session.env().catalog_writer();
+    if let Err(err) = catalog_writer
+        .replace_materialized_view(old_table_id, new_table_id)
+        .await
+    {
+        // Leave the old materialized view untouched, e.g., when others depend on it.
+        catalog_writer.drop_materialized_view(new_table_id).await?;
+        return Err(err);
+    }
+
+    Ok(PgResponse::empty_result(
+        StatementType::CREATE_MATERIALIZED_VIEW,
+    ))
+}
+
 #[cfg(test)]
 pub mod tests {
     use std::collections::HashMap;
@@ -211,4 +456,107 @@ pub mod tests {
             "Bind error: An alias must be specified for an expression"
         );
     }
+
+    #[tokio::test]
+    async fn test_checkpoint_frequency() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+
+        let sql = "create table t(x varchar)";
+        frontend.run_sql(sql).await.unwrap();
+
+        let sql = "create materialized view mv1 with (checkpoint_frequency = 0) as select x from t";
+        let err = frontend.run_sql(sql).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid Parameter Value: checkpoint_frequency must be a positive integer, got 0"
+        );
+
+        let sql =
+            "create materialized view mv1 with (checkpoint_frequency = 10) as select x from t";
+        frontend.run_sql(sql).await.unwrap();
+        let table = frontend
+            .session_ref()
+            .env()
+            .catalog_reader()
+            .read_guard()
+            .get_table_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "mv1")
+            .unwrap()
+            .clone();
+        assert_eq!(table.properties["checkpoint_frequency"], "10");
+    }
+
+    #[tokio::test]
+    async fn test_resource_group() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+
+        let sql = "create table t(x varchar)";
+        frontend.run_sql(sql).await.unwrap();
+
+        let sql = "create materialized view mv1 with (resource_group = '') as select x from t";
+        let err = frontend.run_sql(sql).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid Parameter Value: resource_group must not be empty"
+        );
+
+        let sql =
+            "create materialized view mv1 with (resource_group = 'reporting') as select x from t";
+        frontend.run_sql(sql).await.unwrap();
+        let table = frontend
+            .session_ref()
+            .env()
+            .catalog_reader()
+            .read_guard()
+            .get_table_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "mv1")
+            .unwrap()
+            .clone();
+        assert_eq!(table.properties["resource_group"], "reporting");
+    }
+
+    #[tokio::test]
+    async fn test_create_or_replace_mv() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend
+            .run_sql("create table t(x int, y int)")
+            .await
+            .unwrap();
+
+        let get_mv = || {
+            frontend
+                .session_ref()
+                .env()
+                .catalog_reader()
+                .read_guard()
+                .get_table_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "mv")
+                .unwrap()
+                .clone()
+        };
+
+        let sql = "create or replace materialized view mv as select x from t";
+        frontend.run_sql(sql).await.unwrap();
+        let old_mv = get_mv();
+        assert_eq!(old_mv.definition, "SELECT x FROM t");
+
+        frontend
+            .run_sql("comment on materialized view mv is 'mv'")
+            .await
+            .unwrap();
+        let sql = "create or replace materialized view mv as select y from t";
+        frontend.run_sql(sql).await.unwrap();
+        let new_mv = get_mv();
+        assert_ne!(new_mv.id(), old_mv.id());
+        assert_eq!(new_mv.definition, "SELECT y FROM t");
+        assert_eq!(new_mv.description, "mv");
+
+        let session = frontend.session_ref();
+        let catalog_reader = session.env().catalog_reader().read_guard();
+        let schema = catalog_reader
+            .get_schema_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME)
+            .unwrap();
+        assert_eq!(schema.iter_mv().count(), 1);
+        drop(catalog_reader);
+
+        let sql = "create or replace materialized view t as select y from t";
+        assert!(frontend.run_sql(sql).await.is_err());
+    }
 }
@@ -12,20 +12,28 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+
 use itertools::Itertools;
 use pgwire::pg_response::{PgResponse, StatementType};
-use risingwave_common::error::Result;
+use risingwave_common::error::{ErrorCode, Result};
 use risingwave_pb::catalog::source::Info;
 use risingwave_pb::catalog::{Source as ProstSource, StreamSourceInfo};
-use risingwave_pb::plan_common::{ColumnCatalog as ProstColumnCatalog, RowFormatType};
+use risingwave_pb::plan_common::{
+    ColumnCatalog as ProstColumnCatalog, HandleConflictBehavior, RowFormatType,
+};
 use risingwave_source::ProtobufParser;
 use risingwave_sqlparser::ast::{CreateSourceStatement, ObjectName, ProtobufSchema, SourceSchema};
 
-use super::create_table::{bind_sql_columns, gen_materialized_source_plan};
+use super::create_table::{
+    bind_sql_columns, bind_sql_foreign_keys, bind_sql_pk_column_ids, check_watermark_column,
+    gen_materialized_source_plan, normalize_table_properties, resolve_conflict_behavior,
+};
 use super::util::handle_with_properties;
 use crate::binder::Binder;
-use crate::catalog::check_schema_writable;
 use crate::catalog::column_catalog::ColumnCatalog;
+use crate::catalog::source_catalog::{check_append_only, check_shared, WithOptions};
+use crate::catalog::{check_schema_writable, TABLE_SOURCE_PK_COLID};
 use crate::session::{OptimizerContext, SessionImpl};
 use crate::stream_fragmenter::StreamFragmenter;
 
@@ -34,7 +42,7 @@ pub(crate) fn make_prost_source(
     name: ObjectName,
     source_info: Info,
 ) -> Result<ProstSource> {
-    let (schema_name, name) = Binder::resolve_table_name(name)?;
+    let (schema_name, name) = Binder::resolve_table_name(session.database(), name)?;
     check_schema_writable(&schema_name)?;
 
     let (database_id, schema_id) = session
@@ -67,46 +75,113 @@ fn extract_protobuf_table_schema(schema: &ProtobufSchema) -> Result<Vec<ProstCol
         .collect_vec())
 }
 
+/// Marks the table of a shared source as append-only, since it has no primary key to apply updates
+/// and deletes by. Sources in a changelog format, e.g. CDC sources, can't be shared for the same
+/// reason, until their tables are keyed by the primary keys of the upstream.
+fn mark_shared_append_only(
+    row_format: RowFormatType,
+    with_properties: &mut HashMap<String, String>,
+) -> Result<()> {
+    match row_format {
+        RowFormatType::Json | RowFormatType::Protobuf | RowFormatType::Avro => {
+            with_properties.insert(WithOptions::AppenOnly.to_string(), "true".to_string());
+            Ok(())
+        }
+        RowFormatType::DebeziumJson => Err(ErrorCode::InvalidInputSyntax(format!(
+            "sources of row format {:?} can't be shared yet, since they carry updates and deletes",
+            row_format
+        ))
+        .into()),
+    }
+}
+
 pub async fn handle_create_source(
     context: OptimizerContext,
     is_materialized: bool,
     stmt: CreateSourceStatement,
 ) -> Result<PgResponse> {
-    let with_properties = handle_with_properties("create_source", stmt.with_properties.0)?;
+    let mut with_properties = handle_with_properties("create_source", stmt.with_properties.0)?;
 
-    let source = match &stmt.source_schema {
+    let (row_format, row_schema_location, columns) = match &stmt.source_schema {
         SourceSchema::Protobuf(protobuf_schema) => {
             let mut columns = vec![ColumnCatalog::row_id_column().to_protobuf()];
             columns.extend(extract_protobuf_table_schema(protobuf_schema)?.into_iter());
-            StreamSourceInfo {
-                properties: with_properties.clone(),
-                row_format: RowFormatType::Protobuf as i32,
-                row_schema_location: protobuf_schema.row_schema_location.0.clone(),
-                row_id_index: 0,
+            (
+                RowFormatType::Protobuf,
+                protobuf_schema.row_schema_location.0.clone(),
                 columns,
-                pk_column_ids: vec![0],
-            }
+            )
         }
-        SourceSchema::Json => StreamSourceInfo {
-            properties: with_properties.clone(),
-            row_format: RowFormatType::Json as i32,
-            row_schema_location: "".to_string(),
-            row_id_index: 0,
-            columns: bind_sql_columns(stmt.columns)?,
-            pk_column_ids: vec![0],
+        SourceSchema::Json => (
+            RowFormatType::Json,
+            "".to_string(),
+            bind_sql_columns(stmt.columns.clone())?,
+        ),
+    };
+
+    // A shared source keeps the rows of its single reader fragment in its own table like a
+    // materialized source, so that each materialized view on it backfills the historical rows from
+    // the table through a chain, instead of opening its own consumer and re-reading the connector.
+    let is_shared = !is_materialized && check_shared(&with_properties);
+    if is_shared {
+        mark_shared_append_only(row_format, &mut with_properties)?;
+    }
+    if is_materialized || is_shared {
+        with_properties = normalize_table_properties(with_properties)?;
+    }
+
+    // Only a materialized source deduplicates the rows by its primary key and checks its foreign
+    // keys, so a plain source always uses the hidden row id.
+    let (pk_column_ids, conflict_behavior, foreign_keys) = if is_materialized {
+        let pk_column_ids = bind_sql_pk_column_ids(&columns, &stmt.columns, &stmt.constraints)?;
+        let conflict_behavior = resolve_conflict_behavior(
+            !pk_column_ids.is_empty(),
+            check_append_only(&with_properties),
+            stmt.on_conflict,
+        )?;
+        let foreign_keys = bind_sql_foreign_keys(
+            &context.session_ctx,
+            &columns,
+            &stmt.columns,
+            &stmt.constraints,
+        )?;
+        (pk_column_ids, conflict_behavior, foreign_keys)
+    } else if stmt.on_conflict.is_some() {
+        return Err(ErrorCode::InvalidInputSyntax(
+            "ON CONFLICT is only supported on materialized sources".to_string(),
+        )
+        .into());
+    } else {
+        (vec![], HandleConflictBehavior::NoCheck, vec![])
+    };
+
+    check_watermark_column(&with_properties, &columns)?;
+
+    let source = StreamSourceInfo {
+        properties: with_properties.clone(),
+        row_format: row_format as i32,
+        row_schema_location,
+        row_id_index: 0,
+        columns,
+        pk_column_ids: if pk_column_ids.is_empty() {
+            vec![TABLE_SOURCE_PK_COLID.get_id()]
+        } else {
+            pk_column_ids
         },
     };
 
     let session = context.session_ctx.clone();
     let source = make_prost_source(&session, stmt.source_name, Info::StreamSource(source))?;
     let catalog_writer = session.env().catalog_writer();
-    if is_materialized {
+    if is_materialized || is_shared {
         let (graph, table) = {
             let (plan, table) = gen_materialized_source_plan(
                 context.into(),
                 source.clone(),
                 session.user_name().to_string(),
                 with_properties.clone(),
+                conflict_behavior,
+                foreign_keys,
             )?;
             let plan = plan.to_stream_prost();
             let graph = StreamFragmenter::build_graph(plan);
@@ -127,9 +202,11 @@ pub async fn handle_create_source(
 pub mod tests {
     use std::collections::HashMap;
 
+    use itertools::Itertools;
     use risingwave_common::catalog::{DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME};
     use risingwave_common::types::DataType;
 
+    use super::*;
     use crate::catalog::row_id_column_name;
     use crate::test_utils::{create_proto_file, LocalFrontend, PROTO_FILE_DATA};
 
@@ -187,4 +264,46 @@ pub mod tests {
         };
         assert_eq!(columns, expected_columns);
     }
+
+    #[tokio::test]
+    async fn test_create_shared_source() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend
+            .run_sql("CREATE SOURCE s (v INT) WITH (shared = 'true') ROW FORMAT JSON")
+            .await
+            .unwrap();
+
+        let session = frontend.session_ref();
+        let catalog_reader = session.env().catalog_reader();
+        let table = catalog_reader
+            .read_guard()
+            .get_table_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "s")
+            .unwrap()
+            .clone();
+        assert_eq!(table.properties["appendonly"], "true");
+
+        // The materialized views on a shared source backfill from its table instead of reading
+        // the connector.
+        let plan = frontend
+            .run_sql("EXPLAIN CREATE MATERIALIZED VIEW mv AS SELECT v FROM s")
+            .await
+            .unwrap()
+            .values()
+            .iter()
+            .map(|row| row.values()[0].clone().unwrap())
+            .join("\n");
+        assert!(plan.contains("StreamTableScan { table: s"), "{}", plan);
+        assert!(!plan.contains("StreamSource"), "{}", plan);
+    }
+
+    #[test]
+    fn test_shared_source_row_format() {
+        let mut with_properties = HashMap::new();
+        mark_shared_append_only(RowFormatType::Json, &mut with_properties).unwrap();
+        assert_eq!(with_properties["appendonly"], "true");
+
+        let mut with_properties = HashMap::new();
+        mark_shared_append_only(RowFormatType::DebeziumJson, &mut with_properties).unwrap_err();
+        assert!(with_properties.is_empty());
+    }
 }
@@ -18,24 +18,36 @@ use std::rc::Rc;
 use fixedbitset::FixedBitSet;
 use itertools::Itertools;
 use pgwire::pg_response::{PgResponse, StatementType};
-use risingwave_common::catalog::{ColumnDesc, ColumnId};
-use risingwave_common::error::Result;
+use risingwave_common::catalog::{
+    ColumnDesc, ColumnId, DEFAULT_PARTITION_INTERVAL_SECONDS, PARTITION_BY_KEY,
+    PARTITION_INTERVAL_KEY, PARTITION_INTERVAL_SECONDS_KEY, PARTITION_RETENTION_KEY,
+    PARTITION_RETENTION_SECONDS_KEY, RETENTION_KEY, RETENTION_SECONDS_KEY, WATERMARK_DELAY_KEY,
+    WATERMARK_DELAY_SECONDS_KEY, WATERMARK_KEY,
+};
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_common::types::DataType;
 use risingwave_pb::catalog::source::Info;
 use risingwave_pb::catalog::{Source as ProstSource, Table as ProstTable, TableSourceInfo};
-use risingwave_pb::plan_common::ColumnCatalog;
-use risingwave_sqlparser::ast::{ColumnDef, DataType as AstDataType, ObjectName, SqlOption};
+use risingwave_pb::plan_common::{ColumnCatalog, HandleConflictBehavior, JoinType};
+use risingwave_sqlparser::ast::{
+    ColumnDef, ColumnOption, DataType as AstDataType, DateTimeField, Ident, ObjectName, OnConflict,
+    SqlOption, TableConstraint,
+};
 
 use super::create_source::make_prost_source;
 use super::util::handle_with_properties;
-use crate::binder::expr::{bind_data_type, bind_struct_field};
+use crate::binder::expr::{bind_data_type, bind_struct_field, parse_interval, TimeStrToken};
+use crate::binder::Binder;
+use crate::catalog::source_catalog::{check_append_only, WithOptions};
+use crate::catalog::table_catalog::TableCatalog;
 use crate::catalog::{check_valid_column_name, row_id_column_desc};
-use crate::optimizer::plan_node::{LogicalSource, StreamSource};
-use crate::optimizer::property::{Order, RequiredDist};
+use crate::expr::{ExprType, FunctionCall, InputRef};
+use crate::optimizer::plan_node::{LogicalJoin, LogicalScan, LogicalSource, StreamSource};
+use crate::optimizer::property::{FieldOrder, Order, RequiredDist};
 use crate::optimizer::{PlanRef, PlanRoot};
 use crate::session::{OptimizerContext, OptimizerContextRef, SessionImpl};
 use crate::stream_fragmenter::StreamFragmenter;
-
-// FIXME: store PK columns in ProstTableSourceInfo as Catalog information, and then remove this
+use crate::utils::Condition;
 
 /// Binds the column schemas declared in CREATE statement into `ColumnCatalog`.
 pub fn bind_sql_columns(columns: Vec<ColumnDef>) -> Result<Vec<ColumnCatalog>> {
@@ -59,7 +71,10 @@ pub fn bind_sql_columns(columns: Vec<ColumnDef>) -> Result<Vec<ColumnCatalog>> {
                 column_id: ColumnId::new((i + 1) as i32),
                 name: column.name.value,
                 field_descs,
-                type_name: "".to_string(),
+                type_name: match column.data_type {
+                    AstDataType::Vector(_) => column.data_type.to_string(),
+                    _ => "".to_string(),
+                },
             });
         }
         column_descs
@@ -76,19 +91,470 @@ pub fn bind_sql_columns(columns: Vec<ColumnDef>) -> Result<Vec<ColumnCatalog>> {
     Ok(columns_catalog)
 }
 
+/// Binds the primary key declared in CREATE statement, either on a column or as a table
+/// constraint, into the ids of `columns`. Returns an empty vector if there is no primary key, in
+/// which case the hidden row id is used.
+pub(crate) fn bind_sql_pk_column_ids(
+    columns: &[ColumnCatalog],
+    column_defs: &[ColumnDef],
+    constraints: &[TableConstraint],
+) -> Result<Vec<i32>> {
+    let column_pks = column_defs
+        .iter()
+        .filter(|column| {
+            column
+                .options
+                .iter()
+                .any(|option| matches!(option.option, ColumnOption::Unique { is_primary: true }))
+        })
+        .map(|column| vec![column.name.clone()]);
+    let constraint_pks = constraints
+        .iter()
+        .filter_map(|constraint| match constraint {
+            TableConstraint::Unique {
+                columns,
+                is_primary: true,
+                ..
+            } => Some(columns.clone()),
+            _ => None,
+        });
+    let pks = column_pks.chain(constraint_pks).collect_vec();
+    if pks.len() > 1 {
+        return Err(ErrorCode::InvalidInputSyntax(
+            "multiple primary keys are not allowed".to_string(),
+        )
+        .into());
+    }
+    let Some(pk) = pks.into_iter().next() else {
+        return Ok(vec![]);
+    };
+
+    let mut pk_column_ids: Vec<i32> = Vec::with_capacity(pk.len());
+    for name in pk {
+        let column = columns
+            .iter()
+            .filter(|c| !c.is_hidden)
+            .filter_map(|c| c.column_desc.as_ref())
+            .find(|c| c.name == name.value)
+            .ok_or_else(|| {
+                ErrorCode::ItemNotFound(format!("primary key column \"{}\"", name.value))
+            })?;
+        if pk_column_ids.contains(&column.column_id) {
+            return Err(ErrorCode::InvalidInputSyntax(format!(
+                "column \"{}\" appears twice in primary key",
+                name.value
+            ))
+            .into());
+        }
+        pk_column_ids.push(column.column_id);
+    }
+    Ok(pk_column_ids)
+}
+
+/// A foreign key of a table. The ingested rows referencing no row of the referenced table are
+/// dropped.
+pub(crate) struct ForeignKey {
+    /// Indices of the referencing columns in the table.
+    columns: Vec<usize>,
+    referenced_table: TableCatalog,
+    /// Indices of the referenced columns in `referenced_table`.
+    referenced_columns: Vec<usize>,
+}
+
+/// Binds the foreign keys declared in CREATE statement, either on a column or as a table
+/// constraint, against the referenced tables. A foreign key without referenced columns references
+/// the primary key of the table.
+pub(crate) fn bind_sql_foreign_keys(
+    session: &SessionImpl,
+    columns: &[ColumnCatalog],
+    column_defs: &[ColumnDef],
+    constraints: &[TableConstraint],
+) -> Result<Vec<ForeignKey>> {
+    let column_fks = column_defs.iter().flat_map(|column| {
+        column
+            .options
+            .iter()
+            .filter_map(|option| match &option.option {
+                ColumnOption::ForeignKey {
+                    foreign_table,
+                    referred_columns,
+                    ..
+                } => Some((
+                    vec![column.name.clone()],
+                    foreign_table.clone(),
+                    referred_columns.clone(),
+                )),
+                _ => None,
+            })
+    });
+    let constraint_fks = constraints
+        .iter()
+        .filter_map(|constraint| match constraint {
+            TableConstraint::ForeignKey {
+                columns,
+                foreign_table,
+                referred_columns,
+                ..
+            } => Some((
+                columns.clone(),
+                foreign_table.clone(),
+                referred_columns.clone(),
+            )),
+            _ => None,
+        });
+
+    // The name, type and hiddenness of the columns of the table.
+    let table_columns = columns
+        .iter()
+        .map(|c| {
+            let desc = c.column_desc.as_ref().unwrap();
+            let data_type: DataType = desc.column_type.as_ref().unwrap().into();
+            (desc.name.clone(), data_type, c.is_hidden)
+        })
+        .collect_vec();
+
+    let catalog_reader = session.env().catalog_reader().read_guard();
+    column_fks
+        .chain(constraint_fks)
+        .map(|(fk_columns, foreign_table, referred_columns)| {
+            let (schema_name, table_name) =
+                Binder::resolve_table_name(session.database(), foreign_table)?;
+            let referenced_table = catalog_reader
+                .get_table_by_name(session.database(), &schema_name, &table_name)?
+                .clone();
+            let referenced_table_columns = referenced_table
+                .columns()
+                .iter()
+                .map(|c| (c.name().to_string(), c.data_type().clone(), c.is_hidden))
+                .collect_vec();
+
+            let fk_columns: Vec<usize> = fk_columns
+                .iter()
+                .map(|name| find_column(&table_columns, name))
+                .try_collect()?;
+            let referenced_columns: Vec<usize> = if referred_columns.is_empty() {
+                if referenced_table
+                    .pks
+                    .iter()
+                    .any(|idx| referenced_table.columns[*idx].is_hidden)
+                {
+                    return Err(ErrorCode::InvalidInputSyntax(format!(
+                        "there is no primary key for referenced table \"{}\"",
+                        table_name
+                    ))
+                    .into());
+                }
+                referenced_table.pks.clone()
+            } else {
+                referred_columns
+                    .iter()
+                    .map(|name| find_column(&referenced_table_columns, name))
+                    .try_collect()?
+            };
+
+            if fk_columns.len() != referenced_columns.len() {
+                return Err(ErrorCode::InvalidInputSyntax(
+                    "number of referencing and referenced columns for foreign key disagree"
+                        .to_string(),
+                )
+                .into());
+            }
+            for (&column, &referenced_column) in fk_columns.iter().zip_eq(&referenced_columns) {
+                let (name, data_type, _) = &table_columns[column];
+                let (referenced_name, referenced_type, _) =
+                    &referenced_table_columns[referenced_column];
+                if data_type != referenced_type {
+                    return Err(ErrorCode::InvalidInputSyntax(format!(
+                        "foreign key column \"{}\" of type {:?} can't reference column \"{}\" of type {:?}",
+                        name, data_type, referenced_name, referenced_type
+                    ))
+                    .into());
+                }
+            }
+
+            Ok(ForeignKey {
+                columns: fk_columns,
+                referenced_table,
+                referenced_columns,
+            })
+        })
+        .try_collect()
+}
+
+/// Finds the visible column named `name` in the name, type and hiddenness of the columns.
+fn find_column(columns: &[(String, DataType, bool)], name: &Ident) -> Result<usize> {
+    columns
+        .iter()
+        .position(|(column_name, _, is_hidden)| !is_hidden && *column_name == name.value)
+        .ok_or_else(|| ErrorCode::ItemNotFound(format!("column \"{}\"", name.value)).into())
+}
+
+/// Drops the rows of `input` referencing no row of the referenced tables with semi joins, which
+/// look up the referenced tables by the foreign keys. A row is held back until the row it
+/// references arrives, and retracted once the referenced row is deleted. Note that a row with a
+/// null foreign key is dropped as well.
+fn gen_foreign_key_check(input: PlanRef, foreign_keys: Vec<ForeignKey>) -> Result<PlanRef> {
+    let ctx = input.ctx();
+    let mut plan = input;
+    for fk in foreign_keys {
+        let left_len = plan.schema().len();
+        let right: PlanRef = LogicalScan::create(
+            fk.referenced_table.name().to_string(),
+            false,
+            Rc::new(fk.referenced_table.table_desc()),
+            vec![],
+            ctx.clone(),
+        )
+        .into();
+        let conjunctions = fk
+            .columns
+            .iter()
+            .zip_eq(fk.referenced_columns.iter())
+            .map(|(&left_idx, &right_idx)| {
+                let data_type = plan.schema().fields()[left_idx].data_type.clone();
+                FunctionCall::new(
+                    ExprType::Equal,
+                    vec![
+                        InputRef::new(left_idx, data_type.clone()).into(),
+                        InputRef::new(left_len + right_idx, data_type).into(),
+                    ],
+                )
+                .map(Into::into)
+            })
+            .try_collect()?;
+        plan = LogicalJoin::new(plan, right, JoinType::LeftSemi, Condition { conjunctions }).into();
+    }
+    // A semi join outputs the columns of its left side, so the columns are not changed.
+    let (plan, _) = plan.logical_rewrite_for_stream()?;
+    plan.to_stream()
+}
+
+/// Resolves how to handle a row inserted with an existing primary key. A table with the hidden row
+/// id never sees a conflict, and a user-defined primary key overwrites the existing row unless
+/// specified otherwise.
+pub(crate) fn resolve_conflict_behavior(
+    has_pk: bool,
+    append_only: bool,
+    on_conflict: Option<OnConflict>,
+) -> Result<HandleConflictBehavior> {
+    if !has_pk {
+        return match on_conflict {
+            None => Ok(HandleConflictBehavior::NoCheck),
+            Some(_) => Err(ErrorCode::InvalidInputSyntax(
+                "ON CONFLICT requires a primary key".to_string(),
+            )
+            .into()),
+        };
+    }
+    match on_conflict {
+        Some(OnConflict::Ignore) => Ok(HandleConflictBehavior::Ignore),
+        // Overwriting a row retracts the existing one, which an append-only stream can't do.
+        _ if append_only => Err(ErrorCode::InvalidInputSyntax(
+            "append-only tables with a primary key only support ON CONFLICT IGNORE".to_string(),
+        )
+        .into()),
+        None | Some(OnConflict::Overwrite) | Some(OnConflict::DoUpdateFull) => {
+            Ok(HandleConflictBehavior::Overwrite)
+        }
+    }
+}
+
+/// Resolves the properties of a table from its `APPEND ONLY` clause and `WITH` options.
+pub(crate) fn resolve_table_properties(
+    append_only: bool,
+    with_options: Vec<SqlOption>,
+) -> Result<HashMap<String, String>> {
+    let mut properties = handle_with_properties("create_table", with_options)?;
+    if append_only {
+        properties.insert(WithOptions::AppenOnly.to_string(), "true".to_string());
+    }
+    normalize_table_properties(properties)
+}
+
+/// Normalizes the properties of a table, or a materialized or shared source. The retention of an
+/// append-only table is normalized into seconds, which meta passes to compaction to expire old
+/// rows, and so are the delay of its watermark and the interval and retention of its partitions.
+pub(crate) fn normalize_table_properties(
+    mut properties: HashMap<String, String>,
+) -> Result<HashMap<String, String>> {
+    if let Some(retention) = properties.remove(RETENTION_KEY) {
+        if !check_append_only(&properties) {
+            return Err(ErrorCode::InvalidParameterValue(format!(
+                "{} is only supported on append-only tables",
+                RETENTION_KEY
+            ))
+            .into());
+        }
+        properties.insert(
+            RETENTION_SECONDS_KEY.to_string(),
+            parse_interval_seconds(RETENTION_KEY, &retention)?.to_string(),
+        );
+    }
+    if properties.contains_key(WATERMARK_KEY) && !check_append_only(&properties) {
+        return Err(ErrorCode::InvalidParameterValue(format!(
+            "{} is only supported on append-only tables",
+            WATERMARK_KEY
+        ))
+        .into());
+    }
+    if let Some(delay) = properties.remove(WATERMARK_DELAY_KEY) {
+        if !properties.contains_key(WATERMARK_KEY) {
+            return Err(ErrorCode::InvalidParameterValue(format!(
+                "{} requires {}",
+                WATERMARK_DELAY_KEY, WATERMARK_KEY
+            ))
+            .into());
+        }
+        properties.insert(
+            WATERMARK_DELAY_SECONDS_KEY.to_string(),
+            parse_interval_seconds(WATERMARK_DELAY_KEY, &delay)?.to_string(),
+        );
+    }
+    if properties.contains_key(PARTITION_BY_KEY) {
+        let interval_seconds = match properties.remove(PARTITION_INTERVAL_KEY) {
+            Some(interval) => parse_interval_seconds(PARTITION_INTERVAL_KEY, &interval)?,
+            None => DEFAULT_PARTITION_INTERVAL_SECONDS,
+        };
+        properties.insert(
+            PARTITION_INTERVAL_SECONDS_KEY.to_string(),
+            interval_seconds.to_string(),
+        );
+    }
+    for key in [PARTITION_INTERVAL_KEY, PARTITION_RETENTION_KEY] {
+        if properties.contains_key(key) && !properties.contains_key(PARTITION_BY_KEY) {
+            return Err(ErrorCode::InvalidParameterValue(format!(
+                "{} requires {}",
+                key, PARTITION_BY_KEY
+            ))
+            .into());
+        }
+    }
+    if let Some(retention) = properties.remove(PARTITION_RETENTION_KEY) {
+        if !check_append_only(&properties) {
+            return Err(ErrorCode::InvalidParameterValue(format!(
+                "{} is only supported on append-only tables",
+                PARTITION_RETENTION_KEY
+            ))
+            .into());
+        }
+        properties.insert(
+            PARTITION_RETENTION_SECONDS_KEY.to_string(),
+            parse_interval_seconds(PARTITION_RETENTION_KEY, &retention)?.to_string(),
+        );
+    }
+    Ok(properties)
+}
+
+/// Parses the interval property `key` like `'7 days'` or `'1 day 12 hours'` into seconds.
+fn parse_interval_seconds(key: &str, interval: &str) -> Result<u32> {
+    let invalid = || {
+        ErrorCode::InvalidParameterValue(format!(
+            "{} must be a positive interval in days, hours, minutes or seconds, got '{}'",
+            key, interval
+        ))
+    };
+    let mut seconds: u64 = 0;
+    for tokens in parse_interval(interval)?.chunks(2) {
+        let [TimeStrToken::Num(num), TimeStrToken::TimeUnit(unit)] = tokens else {
+            return Err(invalid().into());
+        };
+        let unit_seconds = match unit {
+            DateTimeField::Day => 24 * 3600,
+            DateTimeField::Hour => 3600,
+            DateTimeField::Minute => 60,
+            DateTimeField::Second => 1,
+            DateTimeField::Year | DateTimeField::Month => return Err(invalid().into()),
+        };
+        seconds = u64::try_from(*num)
+            .ok()
+            .and_then(|num| num.checked_mul(unit_seconds))
+            .and_then(|s| seconds.checked_add(s))
+            .ok_or_else(invalid)?;
+    }
+    match u32::try_from(seconds) {
+        Ok(seconds) if seconds > 0 => Ok(seconds),
+        _ => Err(invalid().into()),
+    }
+}
+
+/// Checks that the `watermark` property, if any, names a timestamp column.
+pub(crate) fn check_watermark_column(
+    properties: &HashMap<String, String>,
+    columns: &[ColumnCatalog],
+) -> Result<()> {
+    let Some(watermark) = properties.get(WATERMARK_KEY) else {
+        return Ok(());
+    };
+    let column = columns
+        .iter()
+        .filter_map(|c| c.column_desc.as_ref())
+        .find(|c| &c.name == watermark)
+        .map(ColumnDesc::from)
+        .ok_or_else(|| ErrorCode::ItemNotFound(format!("watermark column \"{}\"", watermark)))?;
+    if column.data_type != DataType::Timestamp {
+        return Err(ErrorCode::InvalidParameterValue(format!(
+            "watermark column \"{}\" must be of type timestamp",
+            watermark
+        ))
+        .into());
+    }
+    Ok(())
+}
+
+/// Finds the `partition_by` column in the output of the source of a table, which leads the order
+/// key of the table. It must be a timestamp column, and part of the primary key if any, so that
+/// the rows of a key never move across partitions.
+fn find_partition_column(source_node: &PlanRef, column: &str) -> Result<usize> {
+    let index = source_node
+        .schema()
+        .fields()
+        .iter()
+        .position(|f| f.name == column)
+        .ok_or_else(|| ErrorCode::ItemNotFound(format!("partition column \"{}\"", column)))?;
+    if source_node.schema().fields()[index].data_type != DataType::Timestamp {
+        return Err(ErrorCode::InvalidParameterValue(format!(
+            "partition column \"{}\" must be of type timestamp",
+            column
+        ))
+        .into());
+    }
+    // The hidden row id, always the first column, is the primary key of a table without one.
+    let pk_indices = source_node.pk_indices();
+    if pk_indices != [0] && !pk_indices.contains(&index) {
+        return Err(ErrorCode::InvalidParameterValue(format!(
+            "partition column \"{}\" must be part of the primary key",
+            column
+        ))
+        .into());
+    }
+    Ok(index)
+}
+
 pub(crate) fn gen_create_table_plan(
     session: &SessionImpl,
     context: OptimizerContextRef,
     table_name: ObjectName,
     columns: Vec<ColumnDef>,
+    constraints: Vec<TableConstraint>,
+    on_conflict: Option<OnConflict>,
     properties: HashMap<String, String>,
 ) -> Result<(PlanRef, ProstSource, ProstTable)> {
+    let column_catalogs = bind_sql_columns(columns.clone())?;
+    let pk_column_ids = bind_sql_pk_column_ids(&column_catalogs, &columns, &constraints)?;
+    let foreign_keys = bind_sql_foreign_keys(session, &column_catalogs, &columns, &constraints)?;
+    check_watermark_column(&properties, &column_catalogs)?;
+    let conflict_behavior = resolve_conflict_behavior(
+        !pk_column_ids.is_empty(),
+        check_append_only(&properties),
+        on_conflict,
+    )?;
     let source = make_prost_source(
         session,
         table_name,
         Info::TableSource(TableSourceInfo {
-            columns: bind_sql_columns(columns)?,
+            columns: column_catalogs,
             properties: properties.clone(),
+            pk_column_ids,
         }),
     )?;
     let (plan, table) = gen_materialized_source_plan(
@@ -96,42 +562,62 @@ pub(crate) fn gen_create_table_plan(
         source.clone(),
         session.user_name().to_string(),
         properties,
+        conflict_behavior,
+        foreign_keys,
     )?;
     Ok((plan, source, table))
 }
 
 /// Generate a stream plan with `StreamSource` + `StreamMaterialize`, it resembles a
-/// `CREATE MATERIALIZED VIEW AS SELECT * FROM <source>`.
+/// `CREATE MATERIALIZED VIEW AS SELECT * FROM <source>`. The rows violating the foreign keys are
+/// dropped before the materialization. The rows of a partitioned table are ordered by the partition
+/// column, so that scans filtering the column only read the matching partitions.
 pub(crate) fn gen_materialized_source_plan(
     context: OptimizerContextRef,
     source: ProstSource,
     owner: String,
     properties: HashMap<String, String>,
+    conflict_behavior: HandleConflictBehavior,
+    foreign_keys: Vec<ForeignKey>,
 ) -> Result<(PlanRef, ProstTable)> {
     let materialize = {
         // Manually assemble the materialization plan for the table.
-        let source_node: PlanRef =
-            StreamSource::new(LogicalSource::new(Rc::new((&source).into()), context)).into();
+        let logical_source = LogicalSource::new(Rc::new((&source).into()), context);
+        let source_node: PlanRef = if foreign_keys.is_empty() {
+            StreamSource::new(logical_source).into()
+        } else {
+            gen_foreign_key_check(logical_source.into(), foreign_keys)?
+        };
         let mut required_cols = FixedBitSet::with_capacity(source_node.schema().len());
         required_cols.toggle_range(..);
         required_cols.toggle(0);
         let mut out_names = source_node.schema().names();
         out_names.remove(0);
+        let order = match properties.get(PARTITION_BY_KEY) {
+            Some(column) => Order::new(vec![FieldOrder::ascending(find_partition_column(
+                &source_node,
+                column,
+            )?)]),
+            None => Order::any(),
+        };
 
         PlanRoot::new(
             source_node,
             RequiredDist::Any,
-            Order::any(),
+            order,
             required_cols,
             out_names,
         )
         .gen_create_mv_plan(source.name.clone())?
+        .with_conflict_behavior(conflict_behavior)
     };
     let mut table = materialize
         .table()
         .to_prost(source.schema_id, source.database_id);
     table.owner = owner;
     table.properties = properties;
+    // Unlike the `ORDER BY` of a materialized view, the partition order isn't provided to queries.
+    table.sort_key_len = 0;
     Ok((materialize.into(), table))
 }
 
@@ -139,6 +625,9 @@ pub async fn handle_create_table(
     context: OptimizerContext,
     table_name: ObjectName,
     columns: Vec<ColumnDef>,
+    constraints: Vec<TableConstraint>,
+    append_only: bool,
+    on_conflict: Option<OnConflict>,
     with_options: Vec<SqlOption>,
 ) -> Result<PgResponse> {
     let session = context.session_ctx.clone();
@@ -149,7 +638,9 @@ pub async fn handle_create_table(
             context.into(),
             table_name.clone(),
             columns,
-            handle_with_properties("create_table", with_options)?,
+            constraints,
+            on_conflict,
+            resolve_table_properties(append_only, with_options)?,
         )?;
         let plan = plan.to_stream_prost();
         let graph = StreamFragmenter::build_graph(plan);
@@ -176,8 +667,9 @@ mod tests {
     use std::collections::HashMap;
 
     use itertools::Itertools;
-    use risingwave_common::catalog::{DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME};
+    use risingwave_common::catalog::{WatermarkDesc, DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME};
     use risingwave_common::types::DataType;
+    use risingwave_pb::plan_common::HandleConflictBehavior;
 
     use crate::catalog::row_id_column_name;
     use crate::test_utils::LocalFrontend;
@@ -231,4 +723,246 @@ mod tests {
 
         assert_eq!(columns, expected_columns);
     }
+
+    #[tokio::test]
+    async fn test_create_table_with_retention() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+
+        let sql = "create table t1 (v int) with (retention = '7 days')";
+        let err = frontend.run_sql(sql).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid Parameter Value: retention is only supported on append-only tables"
+        );
+
+        let sql = "create table t1 (v int) append only with (retention = '1 month')";
+        let err = frontend.run_sql(sql).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid Parameter Value: retention must be a positive interval in days, hours, minutes or seconds, got '1 month'"
+        );
+
+        let sql = "create table t1 (v int) append only with (retention = '1 day 12 hours')";
+        frontend.run_sql(sql).await.unwrap();
+
+        let session = frontend.session_ref();
+        let catalog_reader = session.env().catalog_reader();
+        let source = catalog_reader
+            .read_guard()
+            .get_source_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "t1")
+            .unwrap()
+            .clone();
+        assert!(source.append_only);
+        let table = catalog_reader
+            .read_guard()
+            .get_table_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "t1")
+            .unwrap()
+            .clone();
+        assert_eq!(table.properties["retention_seconds"], "129600");
+        assert!(!table.properties.contains_key("retention"));
+    }
+
+    #[tokio::test]
+    async fn test_create_table_with_watermark() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+
+        let sql = "create table t1 (v int, ts timestamp) with (watermark = 'ts')";
+        let err = frontend.run_sql(sql).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid Parameter Value: watermark is only supported on append-only tables"
+        );
+
+        let sql = "create table t1 (v int, ts timestamp) append only with (watermark = 'v')";
+        let err = frontend.run_sql(sql).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid Parameter Value: watermark column \"v\" must be of type timestamp"
+        );
+
+        let sql = "create table t1 (v int, ts timestamp) append only with (watermark = 'ts', watermark_delay = '5 minutes')";
+        frontend.run_sql(sql).await.unwrap();
+
+        let session = frontend.session_ref();
+        let catalog_reader = session.env().catalog_reader();
+        let table = catalog_reader
+            .read_guard()
+            .get_table_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "t1")
+            .unwrap()
+            .clone();
+        assert_eq!(table.properties["watermark_delay_seconds"], "300");
+        let ts_index = table.columns.iter().position(|c| c.name() == "ts");
+        assert_eq!(
+            table.table_desc().watermark,
+            Some(WatermarkDesc {
+                column_index: ts_index.unwrap(),
+                delay_ms: 300 * 1000
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_table_with_partition() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+
+        let sql = "create table t1 (v int, ts timestamp) with (partition_by = 'v')";
+        let err = frontend.run_sql(sql).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid Parameter Value: partition column \"v\" must be of type timestamp"
+        );
+
+        let sql = "create table t1 (v int primary key, ts timestamp) with (partition_by = 'ts')";
+        let err = frontend.run_sql(sql).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid Parameter Value: partition column \"ts\" must be part of the primary key"
+        );
+
+        let sql = "create table t1 (v int, ts timestamp) with (partition_by = 'ts', partition_retention = '30 days')";
+        let err = frontend.run_sql(sql).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid Parameter Value: partition_retention is only supported on append-only tables"
+        );
+
+        let sql = "create table t1 (v int, ts timestamp) append only with (partition_retention = '30 days')";
+        let err = frontend.run_sql(sql).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid Parameter Value: partition_retention requires partition_by"
+        );
+
+        let sql = "create table t1 (v int, ts timestamp) append only with (partition_by = 'ts', partition_interval = '1 hour', partition_retention = '30 days')";
+        frontend.run_sql(sql).await.unwrap();
+
+        let session = frontend.session_ref();
+        let catalog_reader = session.env().catalog_reader();
+        let table = catalog_reader
+            .read_guard()
+            .get_table_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "t1")
+            .unwrap()
+            .clone();
+        assert_eq!(table.properties["partition_interval_seconds"], "3600");
+        assert_eq!(table.properties["partition_retention_seconds"], "2592000");
+        // The partition column leads the order key, followed by the row id.
+        assert_eq!(table.order_desc[0].column_desc.name, "ts");
+        assert_eq!(table.order_desc.len(), 2);
+        assert!(table.sort_key().is_empty());
+
+        let sql = "create table t2 (v int, ts timestamp) with (partition_by = 'ts')";
+        frontend.run_sql(sql).await.unwrap();
+        let table = catalog_reader
+            .read_guard()
+            .get_table_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "t2")
+            .unwrap()
+            .clone();
+        assert_eq!(table.properties["partition_interval_seconds"], "86400");
+    }
+
+    #[tokio::test]
+    async fn test_create_table_with_pk() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+
+        for (sql, err) in [
+            (
+                "create table t1 (v int) on conflict ignore",
+                "Invalid input syntax: ON CONFLICT requires a primary key",
+            ),
+            (
+                "create table t1 (v int primary key) append only",
+                "Invalid input syntax: append-only tables with a primary key only support ON CONFLICT IGNORE",
+            ),
+            (
+                "create table t1 (v int primary key, w int, primary key (w))",
+                "Invalid input syntax: multiple primary keys are not allowed",
+            ),
+            (
+                "create table t1 (v int, primary key (w))",
+                "Item not found: primary key column \"w\"",
+            ),
+        ] {
+            assert_eq!(frontend.run_sql(sql).await.unwrap_err().to_string(), err);
+        }
+
+        let sql = "create table t1 (v int, w int, primary key (w)) append only on conflict ignore";
+        frontend.run_sql(sql).await.unwrap();
+        let sql = "create table t2 (v int primary key, w int)";
+        frontend.run_sql(sql).await.unwrap();
+
+        let session = frontend.session_ref();
+        let catalog_reader = session.env().catalog_reader();
+        for (name, pk, behavior) in [
+            ("t1", "w", HandleConflictBehavior::Ignore),
+            ("t2", "v", HandleConflictBehavior::Overwrite),
+        ] {
+            let source = catalog_reader
+                .read_guard()
+                .get_source_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, name)
+                .unwrap()
+                .clone();
+            let table = catalog_reader
+                .read_guard()
+                .get_table_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, name)
+                .unwrap()
+                .clone();
+            let pk_columns = table
+                .pks
+                .iter()
+                .map(|idx| table.columns[*idx].name())
+                .collect_vec();
+            assert_eq!(pk_columns, vec![pk]);
+            assert_eq!(source.pk_col_ids.len(), 1);
+            assert_eq!(table.handle_pk_conflict_behavior, behavior);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_table_with_foreign_key() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend
+            .run_sql("create table t_ref (k int primary key, v varchar)")
+            .await
+            .unwrap();
+        frontend
+            .run_sql("create table t_no_pk (k int)")
+            .await
+            .unwrap();
+
+        for (sql, err) in [
+            (
+                "create table t (k int references t_no_pk)",
+                "Invalid input syntax: there is no primary key for referenced table \"t_no_pk\"",
+            ),
+            (
+                "create table t (k int references t_ref (x))",
+                "Item not found: column \"x\"",
+            ),
+            (
+                "create table t (k int references t_ref (v))",
+                "Invalid input syntax: foreign key column \"k\" of type Int32 can't reference column \"v\" of type Varchar",
+            ),
+            (
+                "create table t (k int, v varchar, foreign key (k, v) references t_ref (k))",
+                "Invalid input syntax: number of referencing and referenced columns for foreign key disagree",
+            ),
+        ] {
+            assert_eq!(frontend.run_sql(sql).await.unwrap_err().to_string(), err);
+        }
+
+        frontend
+            .run_sql("create table t (id int, k int references t_ref)")
+            .await
+            .unwrap();
+        let session = frontend.session_ref();
+        let catalog_reader = session.env().catalog_reader();
+        let table = catalog_reader
+            .read_guard()
+            .get_table_by_name(DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, "t")
+            .unwrap()
+            .clone();
+        // The rows are materialized as they are after the check.
+        let columns = table.columns.iter().map(|c| c.name()).collect_vec();
+        assert_eq!(columns, vec![row_id_column_name().as_str(), "id", "k"]);
+    }
 }
@@ -27,13 +27,13 @@ use crate::session::OptimizerContext;
 
 pub fn handle_describe(context: OptimizerContext, table_name: ObjectName) -> Result<PgResponse> {
     let session = context.session_ctx;
-    let (schema_name, table_name) = Binder::resolve_table_name(table_name)?;
+    let (schema_name, table_name) = Binder::resolve_table_name(session.database(), table_name)?;
 
     let catalog_reader = session.env().catalog_reader().read_guard();
 
     // For Source, it doesn't have table catalog so use get source to get column descs.
-    let (columns, indices): (Vec<ColumnDesc>, Vec<TableCatalog>) = {
-        let (catalogs, indices) = match catalog_reader
+    let (columns, indices, table): (Vec<ColumnDesc>, Vec<TableCatalog>, Option<&TableCatalog>) = {
+        let (catalogs, indices, table) = match catalog_reader
             .get_schema_by_name(session.database(), &schema_name)?
             .get_table_by_name(&table_name)
         {
@@ -45,12 +45,14 @@ pub fn handle_describe(context: OptimizerContext, table_name: ObjectName) -> Res
                     .filter(|x| x.is_index_on == Some(table.id))
                     .cloned()
                     .collect_vec(),
+                Some(table),
             ),
             None => (
                 &catalog_reader
                     .get_source_by_name(session.database(), &schema_name, &table_name)?
                     .columns,
                 vec![],
+                None,
             ),
         };
         (
@@ -60,11 +62,31 @@ pub fn handle_describe(context: OptimizerContext, table_name: ObjectName) -> Res
                 .map(|c| c.column_desc.clone())
                 .collect(),
             indices,
+            table,
         )
     };
 
+    // Comments of the columns set by `COMMENT ON`.
+    let descriptions = columns
+        .iter()
+        .flat_map(|c| c.flatten())
+        .map(|c| {
+            table
+                .and_then(|table| table.column_description(c.column_id))
+                .map(str::to_string)
+        })
+        .collect_vec();
+
     // Convert all column descs to rows
-    let mut rows = col_descs_to_rows(columns);
+    let mut rows = col_descs_to_rows(columns)
+        .into_iter()
+        .zip_eq(descriptions)
+        .map(|(row, description)| {
+            let mut values = row.values().to_vec();
+            values.push(description);
+            Row::new(values)
+        })
+        .collect_vec();
 
     // Convert all indexs to rows
     rows.extend(indices.iter().map(|i| {
@@ -76,6 +98,7 @@ pub fn handle_describe(context: OptimizerContext, table_name: ObjectName) -> Res
         Row::new(vec![
             Some(i.name.clone()),
             Some(format!("index({})", display_comma_separated(&s))),
+            None,
         ])
     }));
 
@@ -87,6 +110,7 @@ pub fn handle_describe(context: OptimizerContext, table_name: ObjectName) -> Res
         vec![
             PgFieldDescriptor::new("Name".to_owned(), TypeOid::Varchar),
             PgFieldDescriptor::new("Type".to_owned(), TypeOid::Varchar),
+            PgFieldDescriptor::new("Description".to_owned(), TypeOid::Varchar),
         ],
         true,
     ))
@@ -112,6 +136,11 @@ mod tests {
             .await
             .unwrap();
 
+        frontend
+            .run_sql("comment on column t.v2 is 'column v2';")
+            .await
+            .unwrap();
+
         let sql = "describe t";
         let pg_response = frontend.run_sql(sql).await.unwrap();
 
@@ -132,5 +161,11 @@ mod tests {
         };
 
         assert_eq!(columns, expected_columns);
+
+        let descriptions = pg_response
+            .iter()
+            .filter_map(|row| Some((row.index(0).as_deref()?, row.index(2).as_deref()?)))
+            .collect::<HashMap<&str, &str>>();
+        assert_eq!(descriptions, maplit::hashmap! { "v2" => "column v2" });
     }
 }
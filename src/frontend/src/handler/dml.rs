@@ -12,14 +12,19 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashSet;
+
 use futures_async_stream::for_await;
+use pgwire::pg_field_descriptor::PgFieldDescriptor;
 use pgwire::pg_response::{PgResponse, StatementType};
-use risingwave_common::error::Result;
+use pgwire::types::Row;
+use risingwave_common::error::{ErrorCode, Result};
 use risingwave_common::session_config::IMPLICIT_FLUSH;
-use risingwave_sqlparser::ast::Statement;
+use risingwave_sqlparser::ast::{SetExpr, Statement};
 
 use crate::binder::Binder;
 use crate::handler::util::{to_pg_field, to_pg_rows};
+use crate::insert_batcher::InsertBatcher;
 use crate::planner::Planner;
 use crate::scheduler::{ExecutionContext, ExecutionContextRef};
 use crate::session::{OptimizerContext, SessionImpl};
@@ -28,6 +33,50 @@ pub async fn handle_dml(context: OptimizerContext, stmt: Statement) -> Result<Pg
     let stmt_type = to_statement_type(&stmt);
     let session = context.session_ctx.clone();
 
+    check_storage_quota(&session).await?;
+    // Statements reading tables should see the previous writes of this session.
+    if reads_tables(&stmt) {
+        session.flush_pending_writes().await?;
+    }
+
+    let insert_batcher = session.env().insert_batcher_ref();
+    if insert_batcher.enabled() && InsertBatcher::batchable(&stmt) {
+        // The batch is flushed as a whole, and its epoch is recorded in the session.
+        let rows_count = insert_batcher.insert(session.clone(), stmt).await?;
+        return Ok(PgResponse::new(
+            stmt_type,
+            rows_count as i32,
+            vec![],
+            vec![],
+            true,
+        ));
+    }
+    let (rows_count, rows, pg_descs) = execute_dml(context, stmt).await?;
+
+    // Implicitly flush the writes. Otherwise, they're flushed before the next read of this
+    // session.
+    let implicit_flush = session
+        .get_config(IMPLICIT_FLUSH)
+        .map(|flag| flag.is_set(false))
+        .unwrap_or(false);
+    if implicit_flush {
+        flush_for_write(&session, stmt_type).await?;
+    } else {
+        session.mark_pending_writes();
+    }
+
+    Ok(PgResponse::new(stmt_type, rows_count, rows, pg_descs, true))
+}
+
+/// Executes the DML statement, and returns the number of affected rows, with the rows and their
+/// descriptors returned by the compute node.
+pub(crate) async fn execute_dml(
+    context: OptimizerContext,
+    stmt: Statement,
+) -> Result<(i32, Vec<Row>, Vec<PgFieldDescriptor>)> {
+    let stmt_type = to_statement_type(&stmt);
+    let session = context.session_ctx.clone();
+
     let bound = {
         let mut binder = Binder::new(
             session.env().catalog_reader().read_guard(),
@@ -70,27 +119,71 @@ pub async fn handle_dml(context: OptimizerContext, stmt: Statement) -> Result<Pg
         _ => unreachable!(),
     };
 
-    // Implicitly flush the writes.
-    if let Some(flag) = session.get_config(IMPLICIT_FLUSH) {
-        if flag.is_set(false) {
-            flush_for_write(&session, stmt_type).await?;
-        }
+    Ok((rows_count, rows, pg_descs))
+}
+
+/// Checks the storage usage of the current database against the configured quota. Depending on
+/// the config, a database exceeding its quota either rejects the statement or logs an alert.
+async fn check_storage_quota(session: &SessionImpl) -> Result<()> {
+    let config = session.env().storage_quota_config();
+    if config.database_storage_quota_mb == 0 {
+        return Ok(());
     }
 
-    Ok(PgResponse::new(stmt_type, rows_count, rows, pg_descs, true))
+    let table_stats = session
+        .env()
+        .meta_client()
+        .get_table_storage_stats()
+        .await?;
+    let table_ids: HashSet<u32> = {
+        let reader = session.env().catalog_reader().read_guard();
+        reader
+            .get_database_by_name(session.database())?
+            .iter_schemas()
+            .flat_map(|schema| schema.iter_all_tables().map(|table| table.id().table_id))
+            .collect()
+    };
+    let usage: u64 = table_stats
+        .iter()
+        .filter(|stats| table_ids.contains(&stats.table_id))
+        .map(|stats| stats.total_size)
+        .sum();
+    let quota = config.database_storage_quota_mb << 20;
+    if usage <= quota {
+        return Ok(());
+    }
+
+    let msg = format!(
+        "database \"{}\" uses {} bytes, exceeding its quota of {} MB",
+        session.database(),
+        usage,
+        config.database_storage_quota_mb
+    );
+    if config.reject_writes_over_quota {
+        return Err(ErrorCode::StorageQuotaExceeded(msg).into());
+    }
+    tracing::warn!("Storage quota exceeded: {}", msg);
+    Ok(())
 }
 
 async fn flush_for_write(session: &SessionImpl, stmt_type: StatementType) -> Result<()> {
     match stmt_type {
         StatementType::INSERT | StatementType::DELETE | StatementType::UPDATE => {
-            let client = session.env().meta_client();
-            client.flush().await?;
+            session.flush_writes().await?;
         }
         _ => {}
     }
     Ok(())
 }
 
+/// Returns whether the statement reads tables besides the one it writes to.
+fn reads_tables(stmt: &Statement) -> bool {
+    match stmt {
+        Statement::Insert { source, .. } => !matches!(source.body, SetExpr::Values(_)),
+        _ => true,
+    }
+}
+
 fn to_statement_type(stmt: &Statement) -> StatementType {
     use StatementType::*;
 
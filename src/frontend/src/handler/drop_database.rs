@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use pgwire::pg_response::{PgResponse, StatementType};
-use risingwave_common::error::{ErrorCode, Result};
+use risingwave_common::error::{ErrorCode, Result, RwError};
 use risingwave_sqlparser::ast::{DropMode, ObjectName};
 
 use crate::binder::Binder;
@@ -32,6 +32,12 @@ pub async fn handle_drop_database(
     if mode.is_some() {
         return Err(ErrorCode::BindError("Drop database not support drop mode".to_string()).into());
     }
+    if database_name == session.database() {
+        return Err(RwError::from(ErrorCode::PermissionDenied(
+            "cannot drop the currently open database".to_string(),
+        ))
+        .with_sqlstate("55006"));
+    }
     let database = {
         let reader = catalog_reader.read_guard();
         match reader.get_database_by_name(&database_name) {
@@ -90,6 +96,7 @@ mod tests {
             .unwrap();
 
         assert!(frontend.run_sql("DROP DATABASE database").await.is_err());
+        assert!(frontend.run_sql("DROP DATABASE dev").await.is_err());
 
         frontend
             .run_sql("DROP SCHEMA database.schema")
@@ -25,7 +25,7 @@ pub async fn handle_drop_index(
     table_name: ObjectName,
 ) -> Result<PgResponse> {
     let session = context.session_ctx;
-    let (schema_name, table_name) = Binder::resolve_table_name(table_name)?;
+    let (schema_name, table_name) = Binder::resolve_table_name(session.database(), table_name)?;
 
     let catalog_reader = session.env().catalog_reader();
 
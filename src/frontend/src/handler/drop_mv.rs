@@ -25,7 +25,7 @@ pub async fn handle_drop_mv(
     table_name: ObjectName,
 ) -> Result<PgResponse> {
     let session = context.session_ctx;
-    let (schema_name, table_name) = Binder::resolve_table_name(table_name)?;
+    let (schema_name, table_name) = Binder::resolve_table_name(session.database(), table_name)?;
 
     let catalog_reader = session.env().catalog_reader();
 
@@ -48,6 +48,12 @@ pub async fn handle_drop_mv(
                 "Use `DROP INDEX` to drop an index.".to_owned(),
             )));
         }
+        // Internal tables are dropped together with their materialized view.
+        if table.is_internal_of.is_some() {
+            return Err(RwError::from(ErrorCode::InvalidInputSyntax(
+                "Cannot drop an internal table.".to_owned(),
+            )));
+        }
         table.id()
     };
 
@@ -22,7 +22,7 @@ use crate::session::OptimizerContext;
 
 pub async fn handle_drop_source(context: OptimizerContext, name: ObjectName) -> Result<PgResponse> {
     let session = context.session_ctx;
-    let (schema_name, source_name) = Binder::resolve_table_name(name)?;
+    let (schema_name, source_name) = Binder::resolve_table_name(session.database(), name)?;
 
     let catalog_reader = session.env().catalog_reader();
     let source = catalog_reader
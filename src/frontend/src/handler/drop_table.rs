@@ -45,10 +45,26 @@ pub async fn handle_drop_table(
     table_name: ObjectName,
 ) -> Result<PgResponse> {
     let session = context.session_ctx;
-    let (schema_name, table_name) = Binder::resolve_table_name(table_name)?;
+    let (schema_name, table_name) = Binder::resolve_table_name(session.database(), table_name)?;
 
     let catalog_reader = session.env().catalog_reader();
 
+    let external_source_id = catalog_reader
+        .read_guard()
+        .get_source_by_name(session.database(), &schema_name, &table_name)
+        .ok()
+        .filter(|source| source.external.is_some())
+        .map(|source| source.id);
+    if let Some(source_id) = external_source_id {
+        // External tables are backed by a source only.
+        session
+            .env()
+            .catalog_writer()
+            .drop_source(source_id)
+            .await?;
+        return Ok(PgResponse::empty_result(StatementType::DROP_TABLE));
+    }
+
     check_source(catalog_reader, session.clone(), &schema_name, &table_name)?;
 
     let (source_id, table_id) = {
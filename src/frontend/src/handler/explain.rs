@@ -12,26 +12,56 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use itertools::Itertools;
 use pgwire::pg_field_descriptor::{PgFieldDescriptor, TypeOid};
 use pgwire::pg_response::{PgResponse, StatementType};
 use pgwire::types::Row;
-use risingwave_common::error::Result;
-use risingwave_sqlparser::ast::Statement;
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_pb::common::ParallelUnitType;
+use risingwave_pb::stream_plan::stream_fragment_graph::StreamFragmentEdge;
+use risingwave_pb::stream_plan::stream_node::NodeBody;
+use risingwave_pb::stream_plan::{DispatcherType, FragmentType, StreamFragmentGraph, StreamNode};
+use risingwave_sqlparser::ast::{ExplainFormat, Statement};
 
 use super::create_index::gen_create_index_plan;
 use super::create_mv::gen_create_mv_plan;
-use super::create_table::gen_create_table_plan;
+use super::create_table::{gen_create_table_plan, resolve_table_properties};
 use super::util::handle_with_properties;
 use crate::binder::Binder;
 use crate::planner::Planner;
-use crate::session::OptimizerContext;
+use crate::session::{OptimizerContext, SessionImpl};
+use crate::stream_fragmenter::StreamFragmenter;
 
+#[allow(clippy::too_many_arguments)]
 pub(super) fn handle_explain(
-    context: OptimizerContext,
+    mut context: OptimizerContext,
     stmt: Statement,
     _verbose: bool,
+    format: ExplainFormat,
+    trace: bool,
+    streaming: bool,
+    distributed: bool,
 ) -> Result<PgResponse> {
+    context.explain_trace = trace;
     let session = context.session_ctx.clone();
+    let creates_streaming_job = matches!(
+        stmt,
+        Statement::CreateView {
+            or_replace: false,
+            materialized: true,
+            ..
+        } | Statement::CreateTable { .. }
+            | Statement::CreateIndex { .. }
+    );
+    if streaming && !creates_streaming_job {
+        return Err(ErrorCode::NotImplemented(
+            "EXPLAIN (STREAMING) of statements other than CREATE MATERIALIZED VIEW, CREATE TABLE \
+             and CREATE INDEX"
+                .to_string(),
+            None.into(),
+        )
+        .into());
+    }
     // bind, plan, optimize, and serialize here
     let mut planner = Planner::new(context.into());
 
@@ -57,6 +87,9 @@ pub(super) fn handle_explain(
         Statement::CreateTable {
             name,
             columns,
+            constraints,
+            append_only,
+            on_conflict,
             with_options,
             ..
         } => {
@@ -65,7 +98,9 @@ pub(super) fn handle_explain(
                 planner.ctx(),
                 name,
                 columns,
-                handle_with_properties("explain create_table", with_options)?,
+                constraints,
+                on_conflict,
+                resolve_table_properties(append_only, with_options)?,
             )?
             .0
         }
@@ -73,9 +108,10 @@ pub(super) fn handle_explain(
         Statement::CreateIndex {
             name,
             table_name,
+            using,
             columns,
             ..
-        } => gen_create_index_plan(&*session, planner.ctx(), name, table_name, columns)?.0,
+        } => gen_create_index_plan(&*session, planner.ctx(), name, table_name, using, columns)?.0,
 
         stmt => {
             let bound = {
@@ -90,13 +126,49 @@ pub(super) fn handle_explain(
         }
     };
 
-    let output = plan.explain_to_string()?;
-
-    let rows = output
-        .lines()
+    // The optimizer trace goes before the final plan.
+    let mut rows = planner
+        .ctx()
+        .take_trace()
+        .iter()
+        .flat_map(|s| s.lines())
         .map(|s| Row::new(vec![Some(s.into())]))
         .collect::<Vec<_>>();
 
+    if distributed && creates_streaming_job {
+        let graph = StreamFragmenter::build_graph(plan.to_stream_prost());
+        let parallelism = hash_parallel_unit_count(&session);
+        rows.extend(match format {
+            ExplainFormat::Text => explain_fragment_graph(&graph, parallelism)
+                .into_iter()
+                .map(|s| Row::new(vec![Some(s)]))
+                .collect::<Vec<_>>(),
+            ExplainFormat::Json => vec![Row::new(vec![Some(
+                fragment_graph_to_json(&graph, parallelism).to_string(),
+            )])],
+            format => {
+                return Err(ErrorCode::NotImplemented(
+                    format!("EXPLAIN (DISTRIBUTED) in format {}", format),
+                    None.into(),
+                )
+                .into())
+            }
+        });
+    } else {
+        rows.extend(match format {
+            ExplainFormat::Text => plan
+                .explain_to_string()?
+                .lines()
+                .map(|s| Row::new(vec![Some(s.into())]))
+                .collect::<Vec<_>>(),
+            // Structured formats are returned in a single row, so that they can be consumed as a
+            // whole by tools.
+            ExplainFormat::Json => vec![Row::new(vec![Some(plan.explain_to_json()?)])],
+            ExplainFormat::Yaml => vec![Row::new(vec![Some(plan.explain_to_yaml()?)])],
+            ExplainFormat::Dot => vec![Row::new(vec![Some(plan.explain_to_dot()?)])],
+        });
+    }
+
     Ok(PgResponse::new(
         StatementType::EXPLAIN,
         rows.len() as i32,
@@ -108,3 +180,283 @@ pub(super) fn handle_explain(
         true,
     ))
 }
+
+/// The number of actors of each non-singleton fragment, which meta sets to the number of hash
+/// parallel units in the cluster.
+fn hash_parallel_unit_count(session: &SessionImpl) -> u32 {
+    session
+        .env()
+        .worker_node_manager()
+        .list_worker_nodes()
+        .iter()
+        .flat_map(|worker| &worker.parallel_units)
+        .filter(|unit| unit.r#type == ParallelUnitType::Hash as i32)
+        .count() as u32
+}
+
+fn fragment_actor_count(fragment_is_singleton: bool, parallelism: u32) -> u32 {
+    if fragment_is_singleton {
+        1
+    } else {
+        parallelism
+    }
+}
+
+fn fragment_type_name(fragment_type: i32) -> &'static str {
+    match FragmentType::from_i32(fragment_type) {
+        Some(FragmentType::Source) => "source",
+        Some(FragmentType::Sink) => "sink",
+        _ => "other",
+    }
+}
+
+fn format_edge(edge: &StreamFragmentEdge) -> String {
+    let strategy = edge.dispatch_strategy.as_ref().unwrap();
+    let dispatcher = DispatcherType::from_i32(strategy.r#type).unwrap_or(DispatcherType::Invalid);
+    if strategy.column_indices.is_empty() {
+        format!("Fragment {} ({:?})", edge.upstream_id, dispatcher)
+    } else {
+        format!(
+            "Fragment {} ({:?} on [{}])",
+            edge.upstream_id,
+            dispatcher,
+            strategy.column_indices.iter().join(", ")
+        )
+    }
+}
+
+/// Explains the fragments of `graph` in the order of their ids, starting from the one with the
+/// materialize node. Each operator is on its own line, and an exchange is followed by the
+/// fragment it receives from.
+fn explain_fragment_graph(graph: &StreamFragmentGraph, parallelism: u32) -> Vec<String> {
+    fn explain_node(
+        node: &StreamNode,
+        depth: usize,
+        fragment_id: u32,
+        graph: &StreamFragmentGraph,
+        lines: &mut Vec<String>,
+        linked: &mut Vec<u64>,
+    ) {
+        let mut line = format!("{}{}", "  ".repeat(depth), node.identity);
+        if let Some(NodeBody::Exchange(_)) = &node.node_body
+            && let Some(edge) = graph
+                .edges
+                .iter()
+                .find(|edge| edge.downstream_id == fragment_id && edge.link_id == node.operator_id)
+        {
+            line.push_str(&format!(" <- {}", format_edge(edge)));
+            linked.push(edge.link_id);
+        }
+        lines.push(line);
+        for input in &node.input {
+            explain_node(input, depth + 1, fragment_id, graph, lines, linked);
+        }
+    }
+
+    let mut lines = vec![];
+    let mut total_actors = 0;
+    for (id, fragment) in graph.fragments.iter().sorted_by_key(|(id, _)| **id) {
+        let actors = fragment_actor_count(fragment.is_singleton, parallelism);
+        total_actors += actors;
+        lines.push(format!(
+            "Fragment {} ({}, actors: {}{}, state tables: {})",
+            id,
+            fragment_type_name(fragment.fragment_type),
+            actors,
+            if fragment.is_singleton {
+                " singleton"
+            } else {
+                ""
+            },
+            fragment.table_ids_cnt
+        ));
+        let mut linked = vec![];
+        explain_node(
+            fragment.node.as_ref().unwrap(),
+            1,
+            *id,
+            graph,
+            &mut lines,
+            &mut linked,
+        );
+        // Inputs not linked to an exchange, e.g. the virtual links of delta joins.
+        for edge in graph
+            .edges
+            .iter()
+            .filter(|edge| edge.downstream_id == *id && !linked.contains(&edge.link_id))
+            .sorted_by_key(|edge| edge.upstream_id)
+        {
+            lines.push(format!("  Input: {}", format_edge(edge)));
+        }
+    }
+    lines.push(format!(
+        "Total: {} fragments, {} actors, {} state tables",
+        graph.fragments.len(),
+        total_actors,
+        graph.table_ids_cnt
+    ));
+    lines
+}
+
+fn fragment_graph_to_json(graph: &StreamFragmentGraph, parallelism: u32) -> serde_json::Value {
+    fn collect_operators(node: &StreamNode, operators: &mut Vec<String>) {
+        operators.push(node.identity.clone());
+        for input in &node.input {
+            collect_operators(input, operators);
+        }
+    }
+
+    let fragments = graph
+        .fragments
+        .iter()
+        .sorted_by_key(|(id, _)| **id)
+        .map(|(id, fragment)| {
+            let mut operators = vec![];
+            collect_operators(fragment.node.as_ref().unwrap(), &mut operators);
+            let inputs = graph
+                .edges
+                .iter()
+                .filter(|edge| edge.downstream_id == *id)
+                .sorted_by_key(|edge| edge.upstream_id)
+                .map(|edge| {
+                    let strategy = edge.dispatch_strategy.as_ref().unwrap();
+                    serde_json::json!({
+                        "fragment": edge.upstream_id,
+                        "dispatcher": format!(
+                            "{:?}",
+                            DispatcherType::from_i32(strategy.r#type)
+                                .unwrap_or(DispatcherType::Invalid)
+                        ),
+                        "columns": strategy.column_indices,
+                    })
+                })
+                .collect_vec();
+            serde_json::json!({
+                "id": id,
+                "type": fragment_type_name(fragment.fragment_type),
+                "actors": fragment_actor_count(fragment.is_singleton, parallelism),
+                "singleton": fragment.is_singleton,
+                "state_tables": fragment.table_ids_cnt,
+                "operators": operators,
+                "inputs": inputs,
+            })
+        })
+        .collect_vec();
+    serde_json::json!({
+        "fragments": fragments,
+        "actors": graph
+            .fragments
+            .values()
+            .map(|fragment| fragment_actor_count(fragment.is_singleton, parallelism))
+            .sum::<u32>(),
+        "state_tables": graph.table_ids_cnt,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::LocalFrontend;
+
+    #[tokio::test]
+    async fn test_explain_format() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend
+            .run_sql("create table t (v1 int, v2 int)")
+            .await
+            .unwrap();
+
+        let sql = "explain (format json) select v1 from t where v2 > 1";
+        let rows = frontend.run_sql(sql).await.unwrap().values();
+        assert_eq!(rows.len(), 1);
+        let json: serde_json::Value =
+            serde_json::from_str(rows[0].values()[0].as_ref().unwrap()).unwrap();
+        assert_eq!(json["id"], 0);
+        assert_eq!(json["children"][0]["id"], 1);
+        assert!(json["distribution"].is_string());
+
+        let sql = "explain (format dot) select v1 from t";
+        let rows = frontend.run_sql(sql).await.unwrap().values();
+        assert!(rows[0].values()[0]
+            .as_ref()
+            .unwrap()
+            .starts_with("digraph plan {"));
+    }
+
+    #[tokio::test]
+    async fn test_explain_trace() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend
+            .run_sql("create table t (v1 int, v2 int)")
+            .await
+            .unwrap();
+
+        let sql = "select v1 from t where v2 > 1";
+        let plan = frontend
+            .run_sql(format!("explain {}", sql))
+            .await
+            .unwrap()
+            .values();
+        let traced = frontend
+            .run_sql(format!("explain (trace) {}", sql))
+            .await
+            .unwrap()
+            .values();
+        let traced = traced
+            .iter()
+            .map(|row| row.values()[0].clone().unwrap())
+            .collect::<Vec<_>>();
+        assert!(traced.contains(&"Predicate Push-down:".to_string()));
+        assert!(traced.contains(&"To Batch Distributed Plan:".to_string()));
+        // The final plan is the same as the one without trace.
+        assert_eq!(
+            traced[traced.len() - plan.len()..],
+            plan.iter()
+                .map(|row| row.values()[0].clone().unwrap())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_explain_distributed() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend
+            .run_sql("create table t (v1 int, v2 int)")
+            .await
+            .unwrap();
+
+        let rows = frontend
+            .run_sql(
+                "explain (streaming, distributed) create materialized view mv as \
+                 select v1, count(*) from t group by v1",
+            )
+            .await
+            .unwrap()
+            .values()
+            .iter()
+            .map(|row| row.values()[0].clone().unwrap())
+            .collect::<Vec<_>>();
+        assert!(rows[0].starts_with("Fragment 0"));
+        assert!(rows.iter().any(|row| row.contains("<- Fragment")));
+        assert!(rows.last().unwrap().starts_with("Total: "));
+
+        let json = frontend
+            .run_sql(
+                "explain (distributed, format json) create materialized view mv as \
+                 select v1, count(*) from t group by v1",
+            )
+            .await
+            .unwrap()
+            .values()[0]
+            .values()[0]
+            .clone()
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert!(json["fragments"].as_array().unwrap().len() > 1);
+
+        // Queries have no streaming plan.
+        assert!(frontend
+            .run_sql("explain (streaming) select * from t")
+            .await
+            .is_err());
+    }
+}
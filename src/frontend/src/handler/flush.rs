@@ -18,8 +18,7 @@ use risingwave_common::error::Result;
 use crate::session::OptimizerContext;
 
 pub(super) async fn handle_flush(context: OptimizerContext) -> Result<PgResponse> {
-    let client = context.session_ctx.env().meta_client();
-    client.flush().await?;
+    context.session_ctx.flush_writes().await?;
 
     Ok(PgResponse::empty_result(StatementType::FLUSH))
 }
@@ -114,7 +114,8 @@ fn make_prost_privilege(
         }
         GrantObjects::Mviews(tables) => {
             for name in tables {
-                let (schema_name, table_name) = Binder::resolve_table_name(name)?;
+                let (schema_name, table_name) =
+                    Binder::resolve_table_name(session.database(), name)?;
                 let table =
                     reader.get_table_by_name(session.database(), &schema_name, &table_name)?;
                 grant_objs.push(ProstObject::TableId(table.id().table_id));
@@ -122,7 +123,8 @@ fn make_prost_privilege(
         }
         GrantObjects::Sources(sources) => {
             for name in sources {
-                let (schema_name, table_name) = Binder::resolve_table_name(name)?;
+                let (schema_name, table_name) =
+                    Binder::resolve_table_name(session.database(), name)?;
                 let source =
                     reader.get_source_by_name(session.database(), &schema_name, &table_name)?;
                 grant_objs.push(ProstObject::SourceId(source.id));
@@ -191,7 +193,10 @@ pub async fn handle_grant_privilege(
         grantees,
         with_grant_option,
         granted_by,
-    } = stmt else { return Err(ErrorCode::BindError("Invalid grant statement".to_string()).into()); };
+    } = stmt
+    else {
+        return Err(ErrorCode::BindError("Invalid grant statement".to_string()).into());
+    };
     let users = grantees.into_iter().map(|g| g.value).collect::<Vec<_>>();
     {
         let user_reader = session.env().user_info_reader();
@@ -230,7 +235,10 @@ pub async fn handle_revoke_privilege(
         granted_by,
         revoke_grant_option,
         cascade: _,
-    } = stmt else { return Err(ErrorCode::BindError("Invalid revoke statement".to_string()).into()); };
+    } = stmt
+    else {
+        return Err(ErrorCode::BindError("Invalid revoke statement".to_string()).into());
+    };
     // TODO: support cascade and restrict option, this requires to record granted_by in each
     // actions.
     let users = grantees.into_iter().map(|g| g.value).collect::<Vec<_>>();
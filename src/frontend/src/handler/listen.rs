@@ -0,0 +1,168 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use itertools::Itertools;
+use pgwire::pg_response::{PgResponse, StatementType};
+use pgwire::pg_server::Notification;
+use risingwave_common::array::{DataChunk, Op, Row};
+use risingwave_common::catalog::Schema;
+use risingwave_common::error::Result;
+use risingwave_connector::sink::changelog::op_name;
+use risingwave_sqlparser::ast::{Expr, ObjectName};
+use serde_json::json;
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::binder::Binder;
+use crate::handler::subscription::query_rows;
+use crate::handler::util::to_pg_rows;
+use crate::session::{OptimizerContext, SessionImpl};
+use crate::subscription::diff_snapshot;
+
+/// How often a listened materialized view is checked for changes.
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Listens on the changes of a materialized view, optionally only the rows matching `selection`.
+/// Each changed row is raised as a notification on the channel named after the view, with a JSON
+/// payload like `{"op": "insert", "row": {"v1": "1"}}`.
+pub async fn handle_listen(
+    context: OptimizerContext,
+    channel: ObjectName,
+    selection: Option<Expr>,
+) -> Result<PgResponse> {
+    let session = context.session_ctx.clone();
+    let channel_name = channel.to_string();
+    let (schema_name, mv_name) = Binder::resolve_table_name(session.database(), channel)?;
+    let sql = match selection {
+        Some(selection) => format!(
+            "SELECT * FROM {}.{} WHERE {}",
+            schema_name, mv_name, selection
+        ),
+        None => format!("SELECT * FROM {}.{}", schema_name, mv_name),
+    };
+
+    // Take the starting point in place, which also validates the query.
+    let (schema, rows) = query_rows(context, &sql).await?;
+    let mut snapshot = HashMap::new();
+    diff_snapshot(&mut snapshot, rows);
+
+    let task = tokio::spawn(watch_changes(
+        Arc::downgrade(&session),
+        channel_name.clone(),
+        sql,
+        schema,
+        snapshot,
+        session.listeners().sender(),
+    ));
+    session.listeners().listen(channel_name, task);
+    Ok(PgResponse::empty_result(StatementType::LISTEN))
+}
+
+pub fn handle_unlisten(
+    context: OptimizerContext,
+    channel: Option<ObjectName>,
+) -> Result<PgResponse> {
+    let channel = channel.map(|channel| channel.to_string());
+    context.session_ctx.listeners().unlisten(channel.as_deref());
+    Ok(PgResponse::empty_result(StatementType::UNLISTEN))
+}
+
+/// Polls the query of a channel and raises a notification for every changed row, until the
+/// session is gone.
+async fn watch_changes(
+    session: Weak<SessionImpl>,
+    channel: String,
+    sql: String,
+    schema: Schema,
+    mut snapshot: HashMap<Row, usize>,
+    sender: UnboundedSender<Notification>,
+) {
+    let mut interval = tokio::time::interval(WATCH_INTERVAL);
+    loop {
+        interval.tick().await;
+        let Some(session) = session.upgrade() else {
+            return;
+        };
+        let context = OptimizerContext::new(session, Arc::from(sql.as_str()));
+        let rows = match query_rows(context, &sql).await {
+            Ok((_, rows)) => rows,
+            Err(e) => {
+                tracing::warn!("failed to check changes of channel {}: {}", channel, e);
+                continue;
+            }
+        };
+        let changes = diff_snapshot(&mut snapshot, rows);
+        if changes.is_empty() {
+            continue;
+        }
+        for payload in to_payloads(&schema, changes) {
+            let notification = Notification {
+                channel: channel.clone(),
+                payload,
+            };
+            if sender.send(notification).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+fn to_payloads(schema: &Schema, changes: Vec<(Op, Row)>) -> Vec<String> {
+    let (ops, rows): (Vec<_>, Vec<_>) = changes.into_iter().unzip();
+    let chunk = match DataChunk::from_rows(&rows, &schema.data_types()) {
+        Ok(chunk) => chunk,
+        Err(e) => {
+            tracing::warn!("failed to format changes: {}", e);
+            return vec![];
+        }
+    };
+    ops.into_iter()
+        .zip_eq(to_pg_rows(chunk))
+        .map(|(op, row)| {
+            let row = schema
+                .names()
+                .into_iter()
+                .zip_eq(row.values().iter().map(|value| json!(value)))
+                .collect::<serde_json::Map<_, _>>();
+            json!({ "op": op_name(op), "row": row }).to_string()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::catalog::Field;
+    use risingwave_common::types::{DataType, ScalarImpl};
+
+    use super::*;
+
+    #[test]
+    fn test_to_payloads() {
+        let schema = Schema::new(vec![Field::with_name(DataType::Int32, "v1")]);
+        let changes = vec![
+            (Op::Insert, Row(vec![Some(ScalarImpl::Int32(1))])),
+            (Op::Delete, Row(vec![None])),
+        ];
+        assert_eq!(
+            to_payloads(&schema, changes),
+            vec![
+                r#"{"op":"insert","row":{"v1":"1"}}"#.to_string(),
+                r#"{"op":"delete","row":{"v1":null}}"#.to_string(),
+            ]
+        );
+    }
+}
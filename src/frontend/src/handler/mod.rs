@@ -15,16 +15,28 @@
 use std::sync::Arc;
 
 use pgwire::pg_response::PgResponse;
-use pgwire::pg_response::StatementType::{ABORT, START_TRANSACTION};
+use pgwire::pg_response::StatementType::{
+    self, ABORT, CREATE_MATERIALIZED_VIEW, CREATE_SOURCE, CREATE_TABLE, DROP_INDEX,
+    DROP_MATERIALIZED_VIEW, DROP_SOURCE, DROP_TABLE, START_TRANSACTION,
+};
 use risingwave_common::error::{ErrorCode, Result};
-use risingwave_sqlparser::ast::{DropStatement, ObjectType, Statement, WithProperties};
+use risingwave_sqlparser::ast::{
+    AlterMaterializedViewOperation, DropStatement, ObjectName, ObjectType, Statement,
+    WithProperties,
+};
 
 use crate::session::{OptimizerContext, SessionImpl};
 
+mod alter_mv;
+mod alter_system;
+mod alter_table;
+mod alter_user;
+mod comment;
 mod create_database;
+pub mod create_external_table;
 pub mod create_index;
 pub mod create_mv;
-mod create_schema;
+pub mod create_schema;
 pub mod create_source;
 pub mod create_table;
 pub mod create_user;
@@ -40,31 +52,108 @@ pub mod drop_user;
 mod explain;
 mod flush;
 pub mod handle_privilege;
+pub mod listen;
 pub mod query;
+mod reconfigure;
+mod replay;
+mod savepoint;
 mod set;
 mod show;
+mod subscription;
+mod unload;
 pub mod util;
 
+/// A notice to skip `CREATE ... IF NOT EXISTS` as the relation `name` already exists.
+fn relation_exists_notice(stmt_type: StatementType, name: &ObjectName) -> PgResponse {
+    PgResponse::empty_result_with_notice(stmt_type, format!("relation {} exists, skipping", name))
+}
+
+/// Whether the statement can be executed by a read replica, which must not change the catalog or
+/// the data.
+fn is_read_only(stmt: &Statement) -> bool {
+    matches!(
+        stmt,
+        Statement::Query(_)
+            | Statement::Explain { .. }
+            | Statement::Describe { .. }
+            | Statement::ShowObjects(_)
+            | Statement::SetVariable { .. }
+            | Statement::StartTransaction { .. }
+            | Statement::Abort { .. }
+            | Statement::Replay { .. }
+    )
+}
+
 pub(super) async fn handle(
     session: Arc<SessionImpl>,
     stmt: Statement,
     sql: &str,
 ) -> Result<PgResponse> {
+    if session.env().is_read_replica() && !is_read_only(&stmt) {
+        return Err(ErrorCode::PermissionDenied(format!(
+            "cannot execute \"{}\" in a read replica",
+            stmt
+        ))
+        .into());
+    }
     let context = OptimizerContext::new(session.clone(), Arc::from(sql));
     match stmt {
         Statement::Explain {
-            statement, verbose, ..
-        } => explain::handle_explain(context, *statement, verbose),
+            statement,
+            verbose,
+            format,
+            trace,
+            streaming,
+            distributed,
+            ..
+        } => explain::handle_explain(
+            context,
+            *statement,
+            verbose,
+            format,
+            trace,
+            streaming,
+            distributed,
+        ),
         Statement::CreateSource {
             is_materialized,
             stmt,
-        } => create_source::handle_create_source(context, is_materialized, stmt).await,
+        } => {
+            if stmt.if_not_exists && util::relation_exists(&session, &stmt.source_name)? {
+                return Ok(relation_exists_notice(CREATE_SOURCE, &stmt.source_name));
+            }
+            create_source::handle_create_source(context, is_materialized, stmt).await
+        }
+        Statement::CreateExternalTable { stmt } => {
+            create_external_table::handle_create_external_table(context, stmt).await
+        }
+        Statement::CreateExternalSchema { stmt } => {
+            create_external_table::handle_create_external_schema(context, stmt).await
+        }
         Statement::CreateTable {
             name,
             columns,
+            constraints,
+            append_only,
+            on_conflict,
             with_options,
+            if_not_exists,
             ..
-        } => create_table::handle_create_table(context, name, columns, with_options).await,
+        } => {
+            if if_not_exists && util::relation_exists(&session, &name)? {
+                return Ok(relation_exists_notice(CREATE_TABLE, &name));
+            }
+            create_table::handle_create_table(
+                context,
+                name,
+                columns,
+                constraints,
+                append_only,
+                on_conflict,
+                with_options,
+            )
+            .await
+        }
         Statement::CreateDatabase {
             db_name,
             if_not_exists,
@@ -79,6 +168,11 @@ pub(super) async fn handle(
         Statement::Grant { .. } => handle_privilege::handle_grant_privilege(context, stmt).await,
         Statement::Revoke { .. } => handle_privilege::handle_revoke_privilege(context, stmt).await,
         Statement::Describe { name } => describe::handle_describe(context, name),
+        Statement::Comment {
+            object_type,
+            object_name,
+            comment,
+        } => comment::handle_comment(context, object_type, object_name, comment).await,
         Statement::ShowObjects(show_object) => show::handle_show_object(context, show_object),
         Statement::Drop(DropStatement {
             object_type,
@@ -86,6 +180,27 @@ pub(super) async fn handle(
             if_exists,
             drop_mode,
         }) => match object_type {
+            ObjectType::Table
+            | ObjectType::MaterializedView
+            | ObjectType::Index
+            | ObjectType::Source
+                if if_exists && !util::relation_exists(&session, &object_name)? =>
+            {
+                let stmt_type = match object_type {
+                    ObjectType::Table => DROP_TABLE,
+                    ObjectType::MaterializedView => DROP_MATERIALIZED_VIEW,
+                    ObjectType::Index => DROP_INDEX,
+                    _ => DROP_SOURCE,
+                };
+                Ok(PgResponse::empty_result_with_notice(
+                    stmt_type,
+                    format!(
+                        "NOTICE: {} {} does not exist, skipping",
+                        object_type.to_string().to_lowercase(),
+                        object_name
+                    ),
+                ))
+            }
             ObjectType::Table => drop_table::handle_drop_table(context, object_name).await,
             ObjectType::MaterializedView => drop_mv::handle_drop_mv(context, object_name).await,
             ObjectType::Index => drop_index::handle_drop_index(context, object_name).await,
@@ -106,6 +221,9 @@ pub(super) async fn handle(
             ObjectType::User => {
                 drop_user::handle_drop_user(context, object_name, if_exists, drop_mode.into()).await
             }
+            ObjectType::Subscription => {
+                subscription::handle_drop_subscription(context, object_name, if_exists)
+            }
             _ => Err(
                 ErrorCode::InvalidInputSyntax(format!("DROP {} is unsupported", object_type))
                     .into(),
@@ -117,13 +235,84 @@ pub(super) async fn handle(
         }
         Statement::CreateView {
             materialized: true,
-            or_replace: false,
+            or_replace,
+            if_not_exists,
             name,
             query,
             with_options,
             ..
-        } => create_mv::handle_create_mv(context, name, query, WithProperties(with_options)).await,
+        } => {
+            let with_options = WithProperties(with_options);
+            if or_replace {
+                if if_not_exists {
+                    return Err(ErrorCode::InvalidInputSyntax(
+                        "OR REPLACE and IF NOT EXISTS cannot be specified together".to_string(),
+                    )
+                    .into());
+                }
+                create_mv::handle_create_or_replace_mv(context, name, query, with_options).await
+            } else if if_not_exists && util::relation_exists(&session, &name)? {
+                Ok(relation_exists_notice(CREATE_MATERIALIZED_VIEW, &name))
+            } else {
+                create_mv::handle_create_mv(context, name, query, with_options).await
+            }
+        }
         Statement::Flush => flush::handle_flush(context).await,
+        Statement::CreateSubscription { stmt } => {
+            subscription::handle_create_subscription(context, stmt).await
+        }
+        Statement::DeclareCursor {
+            cursor_name,
+            subscription,
+        } => subscription::handle_declare_cursor(context, cursor_name, subscription),
+        Statement::Fetch { cursor_name, count } => {
+            subscription::handle_fetch(context, cursor_name, count).await
+        }
+        Statement::CloseCursor { cursor_name } => {
+            subscription::handle_close_cursor(context, cursor_name)
+        }
+        Statement::Listen { channel, selection } => {
+            listen::handle_listen(context, channel, selection).await
+        }
+        Statement::Unlisten { channel } => listen::handle_unlisten(context, channel),
+        Statement::Unload {
+            query,
+            location,
+            format,
+        } => unload::handle_unload(context, query, location, format).await,
+        Statement::Replay { location } => replay::handle_replay(context, location).await,
+        Statement::AlterSystem { param, value } => {
+            alter_system::handle_alter_system(context, param, value).await
+        }
+        Statement::AlterUser { name, param, value } => {
+            alter_user::handle_alter_user(context, name, param, value).await
+        }
+        Statement::AlterTable { name, operation } => {
+            alter_table::handle_alter_table(context, name, operation).await
+        }
+        Statement::AlterMaterializedView { name, operation } => match operation {
+            AlterMaterializedViewOperation::RestoreFromSavepoint => {
+                savepoint::handle_restore_savepoint(context, name).await
+            }
+            AlterMaterializedViewOperation::ReleaseSavepoint => {
+                savepoint::handle_release_savepoint(context, name).await
+            }
+            AlterMaterializedViewOperation::AsQuery { query } => {
+                alter_mv::handle_alter_mv_as_query(context, name, query).await
+            }
+            AlterMaterializedViewOperation::Pause => {
+                reconfigure::handle_pause(context, name, true).await
+            }
+            AlterMaterializedViewOperation::Resume => {
+                reconfigure::handle_pause(context, name, false).await
+            }
+            AlterMaterializedViewOperation::SetParam { param, value } => {
+                reconfigure::handle_set_param(context, name, param, value).await
+            }
+        },
+        Statement::SavepointMaterializedView { name } => {
+            savepoint::handle_savepoint(context, name).await
+        }
         Statement::SetVariable {
             local: _,
             variable,
@@ -132,6 +321,7 @@ pub(super) async fn handle(
         Statement::CreateIndex {
             name,
             table_name,
+            using,
             columns,
             unique,
             if_not_exists,
@@ -141,14 +331,10 @@ pub(super) async fn handle(
                     ErrorCode::NotImplemented("create unique index".into(), None.into()).into(),
                 );
             }
-            if if_not_exists {
-                return Err(ErrorCode::NotImplemented(
-                    "create if_not_exists index".into(),
-                    None.into(),
-                )
-                .into());
+            if if_not_exists && util::relation_exists(&session, &name)? {
+                return Ok(relation_exists_notice(CREATE_TABLE, &name));
             }
-            create_index::handle_create_index(context, name, table_name, columns).await
+            create_index::handle_create_index(context, name, table_name, using, columns).await
         }
         // Ignore `StartTransaction` and `Abort` temporarily.Its not final implementation.
         // 1. Fully support transaction is too hard and gives few benefits to us.
@@ -12,54 +12,66 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::Arc;
+use std::time::Instant;
+
+use futures::{stream, StreamExt};
 use futures_async_stream::for_await;
 use log::debug;
 use pgwire::pg_field_descriptor::PgFieldDescriptor;
 use pgwire::pg_response::{PgResponse, StatementType};
 use risingwave_batch::executor::BoxedDataChunkStream;
-use risingwave_common::error::Result;
+use risingwave_common::catalog::Schema;
+use risingwave_common::error::{ErrorCode, Result};
 use risingwave_common::session_config::QUERY_MODE;
 use risingwave_sqlparser::ast::Statement;
+use risingwave_sqlparser::parser::Parser;
 use tracing::info;
 
-use crate::binder::{Binder, BoundStatement};
+use crate::audit_log::QueryAuditInfo;
+use crate::auto_prepare::{parameterize, ParamSlot, ParameterizedSql};
+use crate::binder::Binder;
 use crate::config::QueryMode;
 use crate::handler::util::{to_pg_field, to_pg_rows};
+use crate::plan_cache::{CachedPlan, PlanCacheKey};
 use crate::planner::Planner;
+use crate::replay::ReplayCapture;
+use crate::result_cache::ResultCacheKey;
 use crate::scheduler::{
     BatchPlanFragmenter, ExecutionContext, ExecutionContextRef, LocalQueryExecution,
+    PinnedHummockSnapshot, Query,
 };
-use crate::session::OptimizerContext;
+use crate::session::{OptimizerContext, SessionImpl};
 
 pub async fn handle_query(context: OptimizerContext, stmt: Statement) -> Result<PgResponse> {
     let stmt_type = to_statement_type(&stmt);
     let session = context.session_ctx.clone();
+    let limits = session.query_limits();
+    let (data_stream, schema) = execute_query(context, stmt).await?;
+    let pg_descs = schema
+        .fields()
+        .iter()
+        .map(to_pg_field)
+        .collect::<Vec<PgFieldDescriptor>>();
 
-    let bound = {
-        let mut binder = Binder::new(
-            session.env().catalog_reader().read_guard(),
-            session.database().to_string(),
-        );
-        binder.bind(stmt)?
-    };
-
-    let query_mode = session
-        .get_config(QUERY_MODE)
-        .map(|entry| entry.get_val(QueryMode::default()))
-        .unwrap_or_default();
-
-    debug!("query_mode:{:?}", query_mode);
-
-    let (data_stream, pg_descs) = match query_mode {
-        QueryMode::Local => local_execute(context, bound)?,
-        QueryMode::Distributed => distribute_execute(context, bound).await?,
-    };
-
+    let start = Instant::now();
     let mut rows = vec![];
+    let mut bytes = 0;
     #[for_await]
     for chunk in data_stream {
-        rows.extend(to_pg_rows(chunk?));
+        let chunk_rows = to_pg_rows(chunk?);
+        bytes += chunk_rows
+            .iter()
+            .flat_map(|row| row.values())
+            .map(|value| value.as_ref().map_or(0, |v| v.len()) as u64)
+            .sum::<u64>();
+        limits.check_memory(bytes)?;
+        rows.extend(chunk_rows);
     }
+    session
+        .env()
+        .frontend_metrics()
+        .observe_stage("execute", start);
 
     let rows_count = match stmt_type {
         StatementType::SELECT => rows.len() as i32,
@@ -69,85 +81,368 @@ pub async fn handle_query(context: OptimizerContext, stmt: Statement) -> Result<
     Ok(PgResponse::new(stmt_type, rows_count, rows, pg_descs, true))
 }
 
-fn to_statement_type(stmt: &Statement) -> StatementType {
-    use StatementType::*;
+/// Plans and schedules a batch query, returning the stream of result chunks and their schema. It's
+/// shared by pgwire and other result transports such as Arrow Flight. The statement timeout and the
+/// row limit of the session are enforced on the query.
+pub async fn execute_query(
+    context: OptimizerContext,
+    stmt: Statement,
+) -> Result<(BoxedDataChunkStream, Schema)> {
+    let limits = context.session_ctx.query_limits();
+    let start = Instant::now();
+    let (data_stream, schema) = limits
+        .with_timeout(start, schedule_query(context, stmt))
+        .await?;
+    Ok((limits.enforce(data_stream, start), schema))
+}
 
-    match stmt {
-        Statement::Query(_) => SELECT,
-        _ => unreachable!(),
+/// Same as [`execute_query`], but reads the snapshot at `epoch` and bypasses the result cache, so
+/// that several executions of the query see the same data, e.g. the pages of the HTTP gateway. The
+/// snapshot must be kept pinned by the caller.
+pub async fn execute_query_at_epoch(
+    context: OptimizerContext,
+    stmt: Statement,
+    epoch: u64,
+) -> Result<(BoxedDataChunkStream, Schema)> {
+    let limits = context.session_ctx.query_limits();
+    let start = Instant::now();
+    let (data_stream, schema) = limits
+        .with_timeout(start, schedule_query_at_epoch(context, stmt, epoch))
+        .await?;
+    Ok((limits.enforce(data_stream, start), schema))
+}
+
+/// Parses `sql` which must be a single query, as taken by the result transports other than pgwire.
+pub fn parse_single_query(sql: &str) -> Result<Statement> {
+    let mut stmts =
+        Parser::parse_sql(sql).map_err(|e| ErrorCode::InvalidInputSyntax(e.to_string()))?;
+    match stmts.as_slice() {
+        [Statement::Query(_)] => Ok(stmts.swap_remove(0)),
+        _ => Err(
+            ErrorCode::InvalidInputSyntax("only a single query is supported".to_string()).into(),
+        ),
     }
 }
 
-async fn distribute_execute(
+async fn schedule_query(
     context: OptimizerContext,
-    stmt: BoundStatement,
-) -> Result<(BoxedDataChunkStream, Vec<PgFieldDescriptor>)> {
+    stmt: Statement,
+) -> Result<(BoxedDataChunkStream, Schema)> {
     let session = context.session_ctx.clone();
-    // Subblock to make sure PlanRef (an Rc) is dropped before `await` below.
-    let (query, pg_descs) = {
-        let root = Planner::new(context.into()).plan(stmt)?;
+    // Make the previous writes of this session visible to the query.
+    session.flush_pending_writes().await?;
 
-        let pg_descs = root
-            .schema()
-            .fields()
-            .iter()
-            .map(to_pg_field)
-            .collect::<Vec<PgFieldDescriptor>>();
+    let query_mode = session
+        .get_config(QUERY_MODE)
+        .map(|entry| entry.get_val(QueryMode::default()))
+        .unwrap_or_default();
 
-        let plan = root.gen_batch_query_plan()?;
+    debug!("query_mode:{:?}", query_mode);
 
-        info!(
-            "Generated distributed plan: {:?}",
-            plan.explain_to_string()?
-        );
+    let env = session.env().clone();
+    let result_cache = env.result_cache_ref();
+    let result_cache_key = result_cache
+        .enabled()
+        .then(|| ResultCacheKey::new(&session, &stmt));
+    let plan = plan_query(context, stmt, &query_mode)?;
+    session.set_query_audit_info(QueryAuditInfo::new(&plan.query, &plan.explained_plan));
+    let query = plan.query.clone_with_new_id();
+    let schema = plan.schema.clone();
+    let catalog_version = env.catalog_reader().read_guard().version();
+    let replay_capture = env.replay_capturer().enabled().then(|| ReplayCapture {
+        query_id: query.query_id().id.clone(),
+        session_config: session.config_values(),
+        catalog_version,
+        epoch: None,
+        plan: plan.explained_plan.clone(),
+    });
+
+    // Queries without any table scan, e.g. `SELECT now()`, don't read a snapshot, and their
+    // results are never cached.
+    let has_table_scan = !query.stages_with_table_scan().is_empty();
+    let result_cache_key = result_cache_key.filter(|_| has_table_scan);
+    if result_cache_key.is_none() && !(replay_capture.is_some() && has_table_scan) {
+        if let Some(capture) = replay_capture {
+            session.set_replay_capture(capture);
+        }
+        let data_stream = match query_mode {
+            QueryMode::Local => local_execute(session, query, None),
+            QueryMode::Distributed => distribute_execute(session, query, None).await?,
+        };
+        return Ok((data_stream, schema));
+    }
 
-        let plan_fragmenter = BatchPlanFragmenter::new(session.env().worker_node_manager_ref());
-        let query = plan_fragmenter.split(plan)?;
-        info!("Generated query after plan fragmenter: {:?}", &query);
-        (query, pg_descs)
+    // Pin the snapshot before the lookup, so that the query reads the epoch its result is cached
+    // at. Captured queries pin it here as well to record the epoch they read.
+    let pinned_snapshot = env
+        .hummock_snapshot_manager()
+        .acquire(query.query_id(), session.min_read_epoch())
+        .await?;
+    let epoch = pinned_snapshot.epoch();
+    if let Some(mut capture) = replay_capture {
+        capture.epoch = Some(epoch);
+        session.set_replay_capture(capture);
+    }
+    let Some(key) = result_cache_key else {
+        let data_stream = match query_mode {
+            QueryMode::Local => local_execute(session, query, Some(pinned_snapshot)),
+            QueryMode::Distributed => {
+                distribute_execute(session, query, Some(pinned_snapshot)).await?
+            }
+        };
+        return Ok((data_stream, schema));
     };
+    let cached = result_cache.get(&key, epoch, catalog_version);
+    env.frontend_metrics()
+        .result_cache_lookup_count
+        .with_label_values(&[if cached.is_some() { "hit" } else { "miss" }])
+        .inc();
+    if let Some(chunks) = cached {
+        pinned_snapshot.unpin().await?;
+        return Ok((stream::iter(chunks.into_iter().map(Ok)).boxed(), schema));
+    }
 
-    let execution_context: ExecutionContextRef = ExecutionContext::new(session.clone()).into();
-    let query_manager = execution_context.session().env().query_manager().clone();
-    Ok((
-        Box::pin(query_manager.schedule(execution_context, query).await?),
-        pg_descs,
-    ))
+    let data_stream = match query_mode {
+        QueryMode::Local => local_execute(session, query, Some(pinned_snapshot)),
+        QueryMode::Distributed => distribute_execute(session, query, Some(pinned_snapshot)).await?,
+    };
+    let data_stream = result_cache.cache_stream(data_stream, key, epoch, catalog_version);
+    Ok((data_stream, schema))
+}
+
+async fn schedule_query_at_epoch(
+    context: OptimizerContext,
+    stmt: Statement,
+    epoch: u64,
+) -> Result<(BoxedDataChunkStream, Schema)> {
+    let session = context.session_ctx.clone();
+    let query_mode = session
+        .get_config(QUERY_MODE)
+        .map(|entry| entry.get_val(QueryMode::default()))
+        .unwrap_or_default();
+    let plan = plan_query(context, stmt, &query_mode)?;
+    session.set_query_audit_info(QueryAuditInfo::new(&plan.query, &plan.explained_plan));
+    let query = plan.query.clone_with_new_id();
+    let pinned_snapshot = session
+        .env()
+        .hummock_snapshot_manager()
+        .acquire_pinned(query.query_id(), epoch)
+        .await
+        .ok_or_else(|| {
+            ErrorCode::InternalError(format!("snapshot of epoch {} is not pinned", epoch))
+        })?;
+    let data_stream = match query_mode {
+        QueryMode::Local => local_execute(session, query, Some(pinned_snapshot)),
+        QueryMode::Distributed => distribute_execute(session, query, Some(pinned_snapshot)).await?,
+    };
+    Ok((data_stream, plan.schema.clone()))
 }
 
-fn local_execute(
+/// Binds, optimizes and fragments the query, or takes its plan from the plan cache if possible.
+/// A statement only differing from a cached one in its constants has them bound into the generic
+/// plan, see [`crate::auto_prepare`].
+fn plan_query(
     context: OptimizerContext,
-    stmt: BoundStatement,
-) -> Result<(BoxedDataChunkStream, Vec<PgFieldDescriptor>)> {
+    stmt: Statement,
+    query_mode: &QueryMode,
+) -> Result<Arc<CachedPlan>> {
     let session = context.session_ctx.clone();
+    let env = session.env();
+    let plan_cache = env.plan_cache();
+    let cache_key = plan_cache
+        .enabled()
+        .then(|| PlanCacheKey::new(&session, &stmt));
+    let parameterized = (cache_key.is_some() && plan_cache.auto_prepare_enabled())
+        .then(|| parameterize(&stmt.to_string()))
+        .flatten();
+    let generic_key = parameterized
+        .as_ref()
+        .map(|sql| PlanCacheKey::generic(&session, &sql.generic_sql));
+    let catalog_version = env.catalog_reader().read_guard().version();
+    let worker_node_count = env.worker_node_manager().worker_node_count();
+    if let Some(key) = &cache_key {
+        let generic_plan = || {
+            let generic = plan_cache.get(
+                generic_key.as_ref()?,
+                catalog_version,
+                &context.hints,
+                worker_node_count,
+            )?;
+            parameterized.as_ref()?.bind(&generic)
+        };
+        let (plan, result) =
+            match plan_cache.get(key, catalog_version, &context.hints, worker_node_count) {
+                Some(plan) => (Some(plan), "hit"),
+                None => match generic_plan() {
+                    Some(plan) => (Some(plan), "generic_hit"),
+                    None => (None, "miss"),
+                },
+            };
+        env.frontend_metrics()
+            .plan_cache_lookup_count
+            .with_label_values(&[result])
+            .inc();
+        if let Some(plan) = plan {
+            return Ok(plan);
+        }
+    }
+    let hints = context.hints.clone();
+    let sql = context.sql.clone();
+    let (query, schema, explained_plan) = build_plan(context, stmt, query_mode)?;
 
-    // Subblock to make sure PlanRef (an Rc) is dropped before `await` below.
-    let (query, pg_descs) = {
-        let root = Planner::new(context.into()).plan(stmt)?;
+    let mut param_types = vec![];
+    let mut param_slots = vec![];
+    if let Some(parameterized) = &parameterized
+        && let Some(generic_key) = &generic_key
+        && !plan_cache.is_rejected(generic_key, catalog_version)
+    {
+        let probe_context = OptimizerContext::new(session.clone(), sql);
+        match generalize(parameterized, &query, probe_context, query_mode) {
+            Some(slots) => {
+                param_types = parameterized.param_types();
+                param_slots = slots;
+            }
+            None => plan_cache.reject(generic_key.clone(), catalog_version),
+        }
+    }
 
-        let pg_descs = root
-            .schema()
-            .fields()
-            .iter()
-            .map(to_pg_field)
-            .collect::<Vec<PgFieldDescriptor>>();
+    let plan = Arc::new(CachedPlan {
+        query,
+        schema,
+        explained_plan,
+        hints,
+        worker_node_count,
+        param_types,
+        param_slots,
+    });
+    if let Some(key) = cache_key {
+        plan_cache.put(key, catalog_version, plan.clone());
+        if !plan.param_slots.is_empty()
+            && let Some(generic_key) = generic_key
+        {
+            plan_cache.put(generic_key, catalog_version, plan.clone());
+        }
+    }
+    Ok(plan)
+}
+
+/// Binds, optimizes and fragments the query, returning the query with its schema and explained
+/// plan.
+pub(super) fn build_plan(
+    context: OptimizerContext,
+    stmt: Statement,
+    query_mode: &QueryMode,
+) -> Result<(Query, Schema, String)> {
+    let session = context.session_ctx.clone();
+    let env = session.env();
+
+    let start = Instant::now();
+    let bound = {
+        let mut binder = Binder::new(
+            env.catalog_reader().read_guard(),
+            session.database().to_string(),
+        );
+        binder.bind(stmt)?
+    };
+    env.frontend_metrics().observe_stage("bind", start);
+
+    let start = Instant::now();
+    let root = Planner::new(context.into()).plan(bound)?;
+    let schema = root.schema().clone();
+    let plan = match query_mode {
+        QueryMode::Local => root.gen_batch_local_plan()?,
+        QueryMode::Distributed => root.gen_batch_query_plan()?,
+    };
+    let explained_plan = plan.explain_to_string()?;
+    match query_mode {
+        QueryMode::Local => info!("Generated local execution plan: {:?}", explained_plan),
+        QueryMode::Distributed => info!("Generated distributed plan: {:?}", explained_plan),
+    }
 
-        let plan = root.gen_batch_local_plan()?;
+    let plan_fragmenter = BatchPlanFragmenter::new(env.worker_node_manager_ref());
+    let query = plan_fragmenter.split(plan)?;
+    info!("Generated query after plan fragmenter: {:?}", &query);
+    env.frontend_metrics().observe_stage("plan", start);
+    Ok((query, schema, explained_plan))
+}
+
+/// Plans the statement again with different constants, and returns where its constants are bound
+/// in `query` if the plan is generic.
+fn generalize(
+    parameterized: &ParameterizedSql,
+    query: &Query,
+    probe_context: OptimizerContext,
+    query_mode: &QueryMode,
+) -> Option<Vec<ParamSlot>> {
+    let probe_stmt = parameterized.probe_statement()?;
+    let (probe, _, _) = build_plan(probe_context, probe_stmt, query_mode).ok()?;
+    parameterized.find_param_slots(query, &probe)
+}
 
-        info!(
-            "Generated local execution plan: {:?}",
-            plan.explain_to_string()?
+/// Returns the schema of the query result without executing the query.
+pub fn infer_query_schema(context: OptimizerContext, stmt: Statement) -> Result<Schema> {
+    let session = context.session_ctx.clone();
+    let bound = {
+        let mut binder = Binder::new(
+            session.env().catalog_reader().read_guard(),
+            session.database().to_string(),
         );
+        binder.bind(stmt)?
+    };
+    let root = Planner::new(context.into()).plan(bound)?;
+    Ok(root.schema().clone())
+}
 
-        let plan_fragmenter = BatchPlanFragmenter::new(session.env().worker_node_manager_ref());
-        let query = plan_fragmenter.split(plan)?;
-        info!("Generated query after plan fragmenter: {:?}", &query);
-        (query, pg_descs)
+fn to_statement_type(stmt: &Statement) -> StatementType {
+    use StatementType::*;
+
+    match stmt {
+        Statement::Query(_) => SELECT,
+        _ => unreachable!(),
+    }
+}
+
+/// Schedules the query, reading `pinned_snapshot` if given, or a snapshot pinned on scheduling.
+pub(super) async fn distribute_execute(
+    session: Arc<SessionImpl>,
+    query: Query,
+    pinned_snapshot: Option<PinnedHummockSnapshot>,
+) -> Result<BoxedDataChunkStream> {
+    let execution_context: ExecutionContextRef = ExecutionContext::new(session.clone()).into();
+    let query_manager = session.env().query_manager().clone();
+    let start = Instant::now();
+    let data_stream: BoxedDataChunkStream = match pinned_snapshot {
+        Some(pinned_snapshot) => Box::pin(
+            query_manager
+                .schedule_with_snapshot(query, pinned_snapshot)
+                .await?,
+        ),
+        None => Box::pin(query_manager.schedule(execution_context, query).await?),
     };
+    session
+        .env()
+        .frontend_metrics()
+        .observe_stage("schedule", start);
+    Ok(data_stream)
+}
 
+pub(super) fn local_execute(
+    session: Arc<SessionImpl>,
+    query: Query,
+    pinned_snapshot: Option<PinnedHummockSnapshot>,
+) -> BoxedDataChunkStream {
     let front_env = session.env();
 
     // TODO: Passing sql here
-    let execution = LocalQueryExecution::new(query, front_env.clone(), "", session.auth_context());
-    Ok((Box::pin(execution.run()), pg_descs))
+    let mut execution = LocalQueryExecution::new(
+        query,
+        front_env.clone(),
+        "",
+        session.auth_context(),
+        session.min_read_epoch(),
+    );
+    if let Some(pinned_snapshot) = pinned_snapshot {
+        execution = execution.with_pinned_snapshot(pinned_snapshot);
+    }
+    Box::pin(execution.run())
 }
@@ -0,0 +1,145 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pgwire::pg_response::{PgResponse, StatementType};
+use risingwave_common::catalog::TableId;
+use risingwave_common::error::{ErrorCode, Result, RwError};
+use risingwave_pb::data::config_change::Change;
+use risingwave_pb::data::ConfigChange;
+use risingwave_sqlparser::ast::{Ident, ObjectName, SetVariableValue, Value};
+
+use crate::binder::Binder;
+use crate::session::{OptimizerContext, SessionImpl};
+
+/// Resolves the streaming job to reconfigure, i.e. a materialized view, or a table or materialized
+/// source, whose source actors are paused or throttled.
+fn resolve_streaming_job(session: &SessionImpl, name: ObjectName) -> Result<TableId> {
+    let (schema_name, table_name) = Binder::resolve_table_name(session.database(), name)?;
+    let catalog_reader = session.env().catalog_reader().read_guard();
+    let table = catalog_reader.get_table_by_name(session.database(), &schema_name, &table_name)?;
+    if table.is_index_on.is_some() || table.is_internal_of.is_some() {
+        return Err(RwError::from(ErrorCode::InvalidInputSyntax(format!(
+            "\"{}\" is not a materialized view",
+            table_name
+        ))));
+    }
+    Ok(table.id())
+}
+
+async fn reconfigure(
+    context: OptimizerContext,
+    name: ObjectName,
+    change: Change,
+) -> Result<PgResponse> {
+    let session = context.session_ctx;
+    let table_id = resolve_streaming_job(&session, name)?;
+    session
+        .env()
+        .meta_client()
+        .reconfigure(
+            table_id.table_id,
+            ConfigChange {
+                actor_ids: vec![],
+                change: Some(change),
+            },
+        )
+        .await?;
+    Ok(PgResponse::empty_result(
+        StatementType::ALTER_MATERIALIZED_VIEW,
+    ))
+}
+
+/// Pauses or resumes reading from the sources of the streaming job, from the next barrier on.
+pub async fn handle_pause(
+    context: OptimizerContext,
+    name: ObjectName,
+    paused: bool,
+) -> Result<PgResponse> {
+    reconfigure(context, name, Change::PauseSource(paused)).await
+}
+
+/// Handles `ALTER MATERIALIZED VIEW <name> SET <param> = <value>`. Only `source_rate_limit`, the
+/// maximum rows per second read by each source actor, is supported. `DEFAULT` removes the limit.
+pub async fn handle_set_param(
+    context: OptimizerContext,
+    name: ObjectName,
+    param: Ident,
+    value: SetVariableValue,
+) -> Result<PgResponse> {
+    if param.value.to_lowercase() != "source_rate_limit" {
+        return Err(ErrorCode::InvalidParameterValue(format!(
+            "unrecognized parameter \"{}\"",
+            param
+        ))
+        .into());
+    }
+    let limit = match &value {
+        SetVariableValue::Ident(ident) if ident.value.to_lowercase() == "default" => 0,
+        SetVariableValue::Literal(Value::Number(n, _)) => n.parse::<u32>().map_err(|_| {
+            ErrorCode::InvalidParameterValue(format!("invalid source_rate_limit: {}", n))
+        })?,
+        value => {
+            return Err(ErrorCode::InvalidParameterValue(format!(
+                "invalid source_rate_limit: {}",
+                value
+            ))
+            .into())
+        }
+    };
+    reconfigure(context, name, Change::SourceRateLimit(limit)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::LocalFrontend;
+
+    #[tokio::test]
+    async fn test_reconfigure_handler() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend
+            .run_sql("create table t (v1 smallint);")
+            .await
+            .unwrap();
+        frontend
+            .run_sql("create materialized view mv as select v1 from t;")
+            .await
+            .unwrap();
+
+        frontend
+            .run_sql("alter materialized view mv pause;")
+            .await
+            .unwrap();
+        frontend
+            .run_sql("alter materialized view mv resume;")
+            .await
+            .unwrap();
+        frontend
+            .run_sql("alter materialized view mv set source_rate_limit = 1000;")
+            .await
+            .unwrap();
+        frontend
+            .run_sql("alter materialized view mv set source_rate_limit to default;")
+            .await
+            .unwrap();
+
+        assert!(frontend
+            .run_sql("alter materialized view mv set source_rate_limit = -1;")
+            .await
+            .is_err());
+        assert!(frontend
+            .run_sql("alter materialized view mv set parallelism = 4;")
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,250 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Instant;
+
+use futures_async_stream::for_await;
+use pgwire::pg_field_descriptor::{PgFieldDescriptor, TypeOid};
+use pgwire::pg_response::{PgResponse, StatementType};
+use pgwire::types::Row;
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_common::session_config::QUERY_MODE;
+use risingwave_sqlparser::ast::Statement;
+use risingwave_sqlparser::parser::Parser;
+
+use super::query::{build_plan, distribute_execute, local_execute};
+use crate::config::QueryMode;
+use crate::replay::{load_bundle, ReplayBundle};
+use crate::session::{OptimizerContext, SessionImpl};
+
+/// The outcome of replaying a query, compared with the captured one.
+#[derive(Default)]
+struct ReplayOutcome {
+    epoch: Option<u64>,
+    plan: Option<String>,
+    rows: Option<usize>,
+    error: Option<String>,
+}
+
+/// Restores the session config when dropped, so that the config the query is replayed with doesn't
+/// stay in the session even if the replay fails or is cancelled.
+struct ConfigGuard {
+    session: Arc<SessionImpl>,
+    config: BTreeMap<String, String>,
+}
+
+impl ConfigGuard {
+    fn new(session: Arc<SessionImpl>) -> Self {
+        let config = session.config_values();
+        Self { session, config }
+    }
+}
+
+impl Drop for ConfigGuard {
+    fn drop(&mut self) {
+        for (key, value) in &self.config {
+            // The keys are taken from the session, so they're always known.
+            let _ = self.session.set_config(key, value);
+        }
+    }
+}
+
+/// Plans and executes the query captured in the bundle at `location` again, with the session
+/// config it was captured with. The query reads the latest snapshot of this cluster, and bypasses
+/// the plan cache and the result cache. Returns the captured and replayed epoch, duration, row
+/// count, error and plan side by side.
+///
+/// Bundles contain the SQL and the plans of queries of any user, so only superusers can replay
+/// them.
+pub async fn handle_replay(context: OptimizerContext, location: String) -> Result<PgResponse> {
+    let session = context.session_ctx.clone();
+    session.check_superuser("replay captured queries")?;
+    let bundle = load_bundle(&location).await?;
+    if bundle.database != session.database() {
+        return Err(ErrorCode::InvalidInputSyntax(format!(
+            "query {} is captured in database \"{}\", connect to it to replay the query",
+            bundle.query_id, bundle.database
+        ))
+        .into());
+    }
+    let stmt = match Parser::parse_sql(&bundle.sql)
+        .map_err(|e| ErrorCode::InvalidInputSyntax(e.to_string()))?
+        .as_slice()
+    {
+        [stmt @ Statement::Query(_)] => stmt.clone(),
+        _ => {
+            return Err(ErrorCode::InvalidInputSyntax(format!(
+                "captured statement is not a query: {}",
+                bundle.sql
+            ))
+            .into())
+        }
+    };
+
+    // Plan the query with the captured session config, and restore the current one afterwards.
+    // Parameters unknown to this cluster are ignored.
+    let config_guard = ConfigGuard::new(session.clone());
+    for (key, value) in &bundle.session_config {
+        let _ = session.set_config(key, value);
+    }
+    let start = Instant::now();
+    let mut outcome = ReplayOutcome::default();
+    if let Err(e) = replay_query(
+        OptimizerContext::new(session.clone(), Arc::from(bundle.sql.as_str())),
+        stmt,
+        &mut outcome,
+    )
+    .await
+    {
+        outcome.error = Some(e.to_string());
+    }
+    let duration = start.elapsed();
+    drop(config_guard);
+
+    let rows = comparison_rows(&bundle, &outcome, duration.as_millis() as u64);
+    Ok(PgResponse::new(
+        StatementType::REPLAY,
+        rows.len() as i32,
+        rows,
+        vec![
+            PgFieldDescriptor::new("item".to_string(), TypeOid::Varchar),
+            PgFieldDescriptor::new("captured".to_string(), TypeOid::Varchar),
+            PgFieldDescriptor::new("replayed".to_string(), TypeOid::Varchar),
+        ],
+        true,
+    ))
+}
+
+/// Executes the query, filling `outcome` as the replay proceeds, so that the plan is reported
+/// even if the execution fails.
+async fn replay_query(
+    context: OptimizerContext,
+    stmt: Statement,
+    outcome: &mut ReplayOutcome,
+) -> Result<()> {
+    let session = context.session_ctx.clone();
+    session.flush_pending_writes().await?;
+    let query_mode = session
+        .get_config(QUERY_MODE)
+        .map(|entry| entry.get_val(QueryMode::default()))
+        .unwrap_or_default();
+    let limits = session.query_limits();
+    let start = Instant::now();
+
+    let (query, _, explained_plan) = build_plan(context, stmt, &query_mode)?;
+    outcome.plan = Some(explained_plan);
+    let pinned_snapshot = session
+        .env()
+        .hummock_snapshot_manager()
+        .acquire(query.query_id(), session.min_read_epoch())
+        .await?;
+    outcome.epoch = Some(pinned_snapshot.epoch());
+    let data_stream = match query_mode {
+        QueryMode::Local => local_execute(session, query, Some(pinned_snapshot)),
+        QueryMode::Distributed => {
+            limits
+                .with_timeout(
+                    start,
+                    distribute_execute(session, query, Some(pinned_snapshot)),
+                )
+                .await?
+        }
+    };
+    let mut rows = 0;
+    #[for_await]
+    for chunk in limits.enforce(data_stream, start) {
+        rows += chunk?.cardinality();
+    }
+    outcome.rows = Some(rows);
+    Ok(())
+}
+
+fn comparison_rows(bundle: &ReplayBundle, outcome: &ReplayOutcome, duration_ms: u64) -> Vec<Row> {
+    let row = |item: &str, captured: Option<String>, replayed: Option<String>| {
+        Row::new(vec![Some(item.to_string()), captured, replayed])
+    };
+    let plan_changed = outcome
+        .plan
+        .as_ref()
+        .map(|plan| (plan != &bundle.plan).to_string());
+    vec![
+        row("query_id", Some(bundle.query_id.clone()), None),
+        row("sql", Some(bundle.sql.clone()), Some(bundle.sql.clone())),
+        row(
+            "epoch",
+            bundle.epoch.map(|epoch| epoch.to_string()),
+            outcome.epoch.map(|epoch| epoch.to_string()),
+        ),
+        row(
+            "duration_ms",
+            Some(bundle.duration_ms.to_string()),
+            Some(duration_ms.to_string()),
+        ),
+        row("rows", None, outcome.rows.map(|rows| rows.to_string())),
+        row("error", bundle.error.clone(), outcome.error.clone()),
+        row("plan_changed", None, plan_changed),
+        row("plan", Some(bundle.plan.clone()), outcome.plan.clone()),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use pgwire::pg_server::Session;
+
+    use super::*;
+    use crate::test_utils::LocalFrontend;
+
+    #[tokio::test]
+    async fn test_replay_rejects_invalid_locations() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        // Unsupported location.
+        assert!(frontend
+            .run_sql("replay 'hdfs://namenode/replay/q1.json';")
+            .await
+            .is_err());
+        // Missing bundle.
+        let dir = tempfile::tempdir().unwrap();
+        assert!(frontend
+            .run_sql(format!("replay 'disk://{}/q1.json';", dir.path().display()))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_replay_requires_superuser() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend.run_sql("create user u;").await.unwrap();
+        let err = frontend
+            .session_user_ref("u")
+            .run_statement("replay 's3://bucket/replay/q1.json';")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("must be superuser"));
+    }
+
+    #[tokio::test]
+    async fn test_config_guard_restores_config() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        let session = frontend.session_ref();
+        let config = session.config_values();
+        {
+            let _guard = ConfigGuard::new(session.clone());
+            session.set_config(QUERY_MODE, "local").unwrap();
+            assert_ne!(session.config_values(), config);
+        }
+        assert_eq!(session.config_values(), config);
+    }
+}
@@ -0,0 +1,121 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pgwire::pg_response::{PgResponse, StatementType};
+use risingwave_common::catalog::TableId;
+use risingwave_common::error::{ErrorCode, Result, RwError};
+use risingwave_sqlparser::ast::ObjectName;
+
+use crate::binder::Binder;
+use crate::session::{OptimizerContext, SessionImpl};
+
+/// Resolves the materialized view that a savepoint statement refers to.
+fn resolve_mv(session: &SessionImpl, name: ObjectName) -> Result<TableId> {
+    let (schema_name, table_name) = Binder::resolve_table_name(session.database(), name)?;
+    let catalog_reader = session.env().catalog_reader().read_guard();
+    let table = catalog_reader.get_table_by_name(session.database(), &schema_name, &table_name)?;
+    if table.associated_source_id().is_some()
+        || table.is_index_on.is_some()
+        || table.is_internal_of.is_some()
+    {
+        return Err(RwError::from(ErrorCode::InvalidInputSyntax(format!(
+            "\"{}\" is not a materialized view",
+            table_name
+        ))));
+    }
+    Ok(table.id())
+}
+
+pub async fn handle_savepoint(context: OptimizerContext, name: ObjectName) -> Result<PgResponse> {
+    let session = context.session_ctx;
+    let table_id = resolve_mv(&session, name)?;
+    let epoch = session
+        .env()
+        .meta_client()
+        .savepoint(table_id.table_id)
+        .await?;
+    Ok(PgResponse::empty_result_with_notice(
+        StatementType::SAVEPOINT,
+        format!("savepoint taken at epoch {}", epoch),
+    ))
+}
+
+pub async fn handle_restore_savepoint(
+    context: OptimizerContext,
+    name: ObjectName,
+) -> Result<PgResponse> {
+    let session = context.session_ctx;
+    let table_id = resolve_mv(&session, name)?;
+    session
+        .env()
+        .meta_client()
+        .restore_savepoint(table_id.table_id)
+        .await?;
+    Ok(PgResponse::empty_result(
+        StatementType::ALTER_MATERIALIZED_VIEW,
+    ))
+}
+
+pub async fn handle_release_savepoint(
+    context: OptimizerContext,
+    name: ObjectName,
+) -> Result<PgResponse> {
+    let session = context.session_ctx;
+    let table_id = resolve_mv(&session, name)?;
+    session
+        .env()
+        .meta_client()
+        .release_savepoint(table_id.table_id)
+        .await?;
+    Ok(PgResponse::empty_result(
+        StatementType::ALTER_MATERIALIZED_VIEW,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::LocalFrontend;
+
+    #[tokio::test]
+    async fn test_savepoint_handler() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend
+            .run_sql("create table t (v1 smallint);")
+            .await
+            .unwrap();
+        frontend
+            .run_sql("create materialized view mv as select v1 from t;")
+            .await
+            .unwrap();
+
+        frontend
+            .run_sql("savepoint materialized view mv;")
+            .await
+            .unwrap();
+        frontend
+            .run_sql("alter materialized view mv restore from savepoint;")
+            .await
+            .unwrap();
+        frontend
+            .run_sql("alter materialized view mv release savepoint;")
+            .await
+            .unwrap();
+
+        // Only materialized views have savepoints.
+        assert!(frontend
+            .run_sql("savepoint materialized view t;")
+            .await
+            .is_err());
+    }
+}
@@ -21,6 +21,7 @@ use risingwave_common::error::Result;
 use risingwave_sqlparser::ast::{Ident, ObjectName, ShowObject};
 
 use crate::binder::Binder;
+use crate::catalog::CatalogError;
 use crate::handler::util::col_descs_to_rows;
 use crate::session::{OptimizerContext, SessionImpl};
 
@@ -28,7 +29,7 @@ pub fn get_columns_from_table(
     session: &SessionImpl,
     table_name: ObjectName,
 ) -> Result<Vec<ColumnDesc>> {
-    let (schema_name, table_name) = Binder::resolve_table_name(table_name)?;
+    let (schema_name, table_name) = Binder::resolve_table_name(session.database(), table_name)?;
 
     let catalog_reader = session.env().catalog_reader().read_guard();
     let catalogs = match catalog_reader
@@ -84,6 +85,19 @@ pub fn handle_show_object(context: OptimizerContext, command: ShowObject) -> Res
             .iter_materialized_source()
             .map(|t| t.name.clone())
             .collect(),
+        ShowObject::InternalTable { mview } => {
+            let (schema_name, mview_name) = Binder::resolve_table_name(session.database(), mview)?;
+            let schema = catalog_reader.get_schema_by_name(session.database(), &schema_name)?;
+            let mview = schema
+                .iter_mv()
+                .find(|t| t.name == mview_name)
+                .ok_or_else(|| CatalogError::NotFound("materialized view", mview_name.clone()))?;
+            schema
+                .iter_internal_table(mview.id)
+                .map(|t| t.name.clone())
+                .sorted()
+                .collect()
+        }
         ShowObject::Sink { _schema } => todo!(),
         ShowObject::Columns { table } => {
             let columns = get_columns_from_table(&session, table)?;
@@ -0,0 +1,173 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use futures_async_stream::for_await;
+use itertools::Itertools;
+use pgwire::pg_field_descriptor::PgFieldDescriptor;
+use pgwire::pg_response::{PgResponse, StatementType};
+use risingwave_common::array::{DataChunk, Row};
+use risingwave_common::catalog::Schema;
+use risingwave_common::error::{ErrorCode, Result, RwError};
+use risingwave_connector::sink::changelog::changelog_schema;
+use risingwave_sqlparser::ast::{CreateSubscriptionStatement, Ident, ObjectName};
+use risingwave_sqlparser::parser::Parser;
+
+use crate::binder::Binder;
+use crate::handler::query::execute_query;
+use crate::handler::util::{handle_with_properties, to_pg_field, to_pg_rows};
+use crate::session::OptimizerContext;
+
+/// The property that sets how long the changes of a subscription are retained.
+const RETENTION_PROPERTY: &str = "retention";
+const DEFAULT_RETENTION: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Reads the whole content of the materialized view `mv_name`.
+async fn read_mv(context: OptimizerContext, mv_name: &str) -> Result<(Schema, Vec<Row>)> {
+    query_rows(context, &format!("SELECT * FROM {}", mv_name)).await
+}
+
+/// Runs the batch query `sql` and collects all the result rows.
+pub(super) async fn query_rows(context: OptimizerContext, sql: &str) -> Result<(Schema, Vec<Row>)> {
+    let stmt = Parser::parse_sql(sql)
+        .map_err(|e| ErrorCode::InternalError(e.to_string()))?
+        .into_iter()
+        .exactly_one()
+        .map_err(|_| ErrorCode::InternalError("expect exactly one statement".to_string()))?;
+    let (data_stream, schema) = execute_query(context, stmt).await?;
+    let mut rows = vec![];
+    #[for_await]
+    for chunk in data_stream {
+        rows.extend(chunk?.rows().map(|r| r.to_owned_row()));
+    }
+    Ok((schema, rows))
+}
+
+pub async fn handle_create_subscription(
+    context: OptimizerContext,
+    stmt: CreateSubscriptionStatement,
+) -> Result<PgResponse> {
+    let session = context.session_ctx.clone();
+    let subscription_name = Binder::resolve_subscription_name(stmt.subscription_name)?;
+    let (schema_name, mv_name) =
+        Binder::resolve_table_name(session.database(), stmt.materialized_view)?;
+    {
+        let reader = session.env().catalog_reader().read_guard();
+        let table = reader.get_table_by_name(session.database(), &schema_name, &mv_name)?;
+        if table.associated_source_id().is_some() || table.is_index_on.is_some() {
+            return Err(RwError::from(ErrorCode::InvalidInputSyntax(format!(
+                "{} is not a materialized view",
+                mv_name
+            ))));
+        }
+    }
+
+    let properties = handle_with_properties("create_subscription", stmt.with_properties.0)?;
+    let retention = match properties.get(RETENTION_PROPERTY) {
+        Some(retention) => humantime::parse_duration(retention).map_err(|e| {
+            ErrorCode::InvalidInputSyntax(format!("invalid retention {}: {}", retention, e))
+        })?,
+        None => DEFAULT_RETENTION,
+    };
+
+    let mv_name = format!("{}.{}", schema_name, mv_name);
+    let (schema, rows) = read_mv(context, &mv_name).await?;
+    let result = session.env().subscription_manager().create_subscription(
+        subscription_name,
+        mv_name,
+        retention,
+        schema,
+        rows,
+    );
+    match result {
+        Err(_) if stmt.if_not_exists => {}
+        result => result?,
+    }
+
+    Ok(PgResponse::empty_result(StatementType::CREATE_SUBSCRIPTION))
+}
+
+pub fn handle_drop_subscription(
+    context: OptimizerContext,
+    subscription_name: ObjectName,
+    if_exists: bool,
+) -> Result<PgResponse> {
+    let subscription_name = Binder::resolve_subscription_name(subscription_name)?;
+    match context
+        .session_ctx
+        .env()
+        .subscription_manager()
+        .drop_subscription(&subscription_name)
+    {
+        Err(_) if if_exists => {}
+        result => result?,
+    }
+    Ok(PgResponse::empty_result(StatementType::DROP_SUBSCRIPTION))
+}
+
+pub fn handle_declare_cursor(
+    context: OptimizerContext,
+    cursor_name: Ident,
+    subscription_name: ObjectName,
+) -> Result<PgResponse> {
+    let subscription_name = Binder::resolve_subscription_name(subscription_name)?;
+    context
+        .session_ctx
+        .env()
+        .subscription_manager()
+        .declare_cursor(cursor_name.value, &subscription_name)?;
+    Ok(PgResponse::empty_result(StatementType::DECLARE_CURSOR))
+}
+
+/// Pulls the latest changes of the subscribed MV into the changelog, then returns the next
+/// `count` changes after the cursor.
+pub async fn handle_fetch(
+    context: OptimizerContext,
+    cursor_name: Ident,
+    count: u64,
+) -> Result<PgResponse> {
+    let session = context.session_ctx.clone();
+    let manager = session.env().subscription_manager();
+    let cursor_name = cursor_name.value;
+    let (subscription_name, mv_name) = manager.cursor_target(&cursor_name)?;
+    let (_, rows) = read_mv(context, &mv_name).await?;
+    manager.refresh(&subscription_name, rows)?;
+
+    let (schema, rows) = manager.fetch(&cursor_name, count)?;
+    let schema = changelog_schema(&schema);
+    let chunk = DataChunk::from_rows(&rows, &schema.data_types())?;
+    let pg_descs = schema
+        .fields()
+        .iter()
+        .map(to_pg_field)
+        .collect::<Vec<PgFieldDescriptor>>();
+    let rows = to_pg_rows(chunk);
+    Ok(PgResponse::new(
+        StatementType::FETCH,
+        rows.len() as i32,
+        rows,
+        pg_descs,
+        true,
+    ))
+}
+
+pub fn handle_close_cursor(context: OptimizerContext, cursor_name: Ident) -> Result<PgResponse> {
+    context
+        .session_ctx
+        .env()
+        .subscription_manager()
+        .close_cursor(&cursor_name.value)?;
+    Ok(PgResponse::empty_result(StatementType::CLOSE_CURSOR))
+}
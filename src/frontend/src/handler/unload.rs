@@ -0,0 +1,201 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use bytes::Bytes;
+use futures_async_stream::for_await;
+use pgwire::pg_field_descriptor::{PgFieldDescriptor, TypeOid};
+use pgwire::pg_response::{PgResponse, StatementType};
+use pgwire::types::Row;
+use risingwave_common::array::{Array, DataChunk};
+use risingwave_common::error::{ErrorCode, Result, ToRwResult};
+use risingwave_connector::lake::LakeLocation;
+use risingwave_pb::batch_plan::unload_node;
+use risingwave_sqlparser::ast::{Query, Statement, UnloadFormat};
+
+use super::query::distribute_execute;
+use crate::binder::Binder;
+use crate::optimizer::plan_node::{BatchUnload, PlanTreeNodeUnary};
+use crate::optimizer::PlanRef;
+use crate::planner::Planner;
+use crate::scheduler::BatchPlanFragmenter;
+use crate::session::OptimizerContext;
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// Places the [`BatchUnload`] right below the exchange gathering the results, so that each task
+/// of the stage below writes its own files in parallel. If the results are gathered in order, or
+/// processed after being gathered, e.g. by a `LIMIT`, the files are written by the root task.
+fn add_unload(
+    plan: PlanRef,
+    location: String,
+    format: unload_node::Format,
+    column_names: Vec<String>,
+) -> PlanRef {
+    // The projection pruning the output columns is stateless, so it can be done before the
+    // exchange.
+    if let Some(project) = plan.as_batch_project()
+        && let Some(exchange) = project.input().as_batch_exchange()
+        && exchange.order().is_any()
+    {
+        let project = project.clone_with_input(exchange.input());
+        let unload = BatchUnload::new(project.into(), location, format, column_names);
+        return exchange.clone_with_input(unload.into()).into();
+    }
+    if let Some(exchange) = plan.as_batch_exchange()
+        && exchange.order().is_any()
+    {
+        let unload = BatchUnload::new(exchange.input(), location, format, column_names);
+        return exchange.clone_with_input(unload.into()).into();
+    }
+    BatchUnload::new(plan, location, format, column_names).into()
+}
+
+/// Writes the results of `query` to part files under `location`, and then a manifest listing
+/// them. The query is always executed in distributed mode. Returns the path and the row count of
+/// each file.
+///
+/// Files are written with the credentials of the nodes, so only superusers can unload, and
+/// `disk://` locations must be under the configured `lake_disk_roots`.
+pub async fn handle_unload(
+    context: OptimizerContext,
+    query: Box<Query>,
+    location: String,
+    format: UnloadFormat,
+) -> Result<PgResponse> {
+    let session = context.session_ctx.clone();
+    session.check_superuser("unload query results")?;
+    let lake_location = LakeLocation::parse(&location).to_rw_result()?;
+    lake_location
+        .check_disk_roots(session.env().lake_disk_roots())
+        .to_rw_result()?;
+    // Make the previous writes of this session visible to the query.
+    session.flush_pending_writes().await?;
+
+    let query = {
+        let env = session.env();
+        let bound = {
+            let mut binder = Binder::new(
+                env.catalog_reader().read_guard(),
+                session.database().to_string(),
+            );
+            binder.bind(Statement::Query(query))?
+        };
+        let root = Planner::new(context.into()).plan(bound)?;
+        let column_names = root.schema().names();
+        let format = match format {
+            UnloadFormat::Parquet => unload_node::Format::Parquet,
+            UnloadFormat::Csv => unload_node::Format::Csv,
+        };
+        let plan = add_unload(
+            root.gen_batch_query_plan()?,
+            location.clone(),
+            format,
+            column_names,
+        );
+        BatchPlanFragmenter::new(env.worker_node_manager_ref()).split(plan)?
+    };
+
+    let mut files = vec![];
+    #[for_await]
+    for chunk in distribute_execute(session.clone(), query, None).await? {
+        let chunk: DataChunk = chunk?;
+        let paths = chunk.column_at(0).array_ref().as_utf8();
+        let rows = chunk.column_at(1).array_ref().as_int64();
+        for (path, rows) in paths.iter().zip(rows.iter()) {
+            files.push((path.unwrap().to_string(), rows.unwrap()));
+        }
+    }
+    files.sort();
+
+    let manifest = serde_json::json!({
+        "files": files
+            .iter()
+            .map(|(path, rows)| serde_json::json!({ "path": path, "rows": rows }))
+            .collect::<Vec<_>>(),
+        "rows": files.iter().map(|(_, rows)| rows).sum::<i64>(),
+    });
+    lake_location
+        .connect()
+        .await
+        .upload(
+            &format!("{}{}", lake_location.prefix(), MANIFEST_FILE),
+            Bytes::from(manifest.to_string()),
+        )
+        .await
+        .map_err(|e| ErrorCode::InternalError(format!("failed to upload manifest: {}", e)))?;
+
+    let rows = files
+        .into_iter()
+        .map(|(path, rows)| Row::new(vec![Some(path), Some(rows.to_string())]))
+        .collect::<Vec<_>>();
+    Ok(PgResponse::new(
+        StatementType::UNLOAD,
+        rows.len() as i32,
+        rows,
+        vec![
+            PgFieldDescriptor::new("path".to_string(), TypeOid::Varchar),
+            PgFieldDescriptor::new("rows".to_string(), TypeOid::BigInt),
+        ],
+        true,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use pgwire::pg_server::Session;
+
+    use crate::test_utils::LocalFrontend;
+
+    #[tokio::test]
+    async fn test_unload_rejects_invalid_statements() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend
+            .run_sql("create table t (v1 int, v2 int);")
+            .await
+            .unwrap();
+
+        // Unsupported location.
+        assert!(frontend
+            .run_sql("unload (select * from t) to 'hdfs://namenode/out' format csv;")
+            .await
+            .is_err());
+        // Unknown column.
+        assert!(frontend
+            .run_sql("unload (select v3 from t) to 's3://bucket/out' format parquet;")
+            .await
+            .is_err());
+        // Disk location not under `lake_disk_roots`.
+        assert!(frontend
+            .run_sql("unload (select * from t) to 'disk:///etc/out' format csv;")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unload_requires_superuser() {
+        let frontend = LocalFrontend::new(Default::default()).await;
+        frontend
+            .run_sql("create table t (v1 int, v2 int);")
+            .await
+            .unwrap();
+        frontend.run_sql("create user u;").await.unwrap();
+
+        let err = frontend
+            .session_user_ref("u")
+            .run_statement("unload (select * from t) to 's3://bucket/out' format csv;")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("must be superuser"));
+    }
+}
@@ -23,7 +23,10 @@ use risingwave_common::catalog::{ColumnDesc, Field};
 use risingwave_common::error::ErrorCode::ProtocolError;
 use risingwave_common::error::{Result, RwError};
 use risingwave_common::types::{DataType, ScalarRefImpl};
-use risingwave_sqlparser::ast::{SqlOption, Value};
+use risingwave_sqlparser::ast::{ObjectName, SqlOption, Value};
+
+use crate::binder::Binder;
+use crate::session::SessionImpl;
 
 /// Format scalars according to postgres convention.
 fn pg_value_format(d: ScalarRefImpl) -> String {
@@ -63,6 +66,19 @@ pub fn to_pg_rows(chunk: DataChunk) -> Vec<Row> {
         .collect_vec()
 }
 
+/// Returns whether a relation, i.e., a table, materialized view, index or source named `name`
+/// exists. Used to skip `CREATE ... IF NOT EXISTS` and `DROP ... IF EXISTS`.
+pub fn relation_exists(session: &SessionImpl, name: &ObjectName) -> Result<bool> {
+    let (schema_name, relation_name) =
+        Binder::resolve_table_name(session.database(), name.clone())?;
+    let reader = session.env().catalog_reader().read_guard();
+    let Ok(schema) = reader.get_schema_by_name(session.database(), &schema_name) else {
+        return Ok(false);
+    };
+    Ok(schema.get_table_by_name(&relation_name).is_some()
+        || schema.get_source_by_name(&relation_name).is_some())
+}
+
 /// Convert column descs to rows which conclude name and type
 pub fn col_descs_to_rows(columns: Vec<ColumnDesc>) -> Vec<Row> {
     columns
@@ -103,6 +119,7 @@ pub fn data_type_to_type_oid(data_type: DataType) -> TypeOid {
         DataType::Timestampz => TypeOid::Timestampz,
         DataType::Decimal => TypeOid::Decimal,
         DataType::Interval => TypeOid::Varchar,
+        DataType::Uuid => TypeOid::Uuid,
         DataType::Struct { .. } => TypeOid::Varchar,
         DataType::List { .. } => TypeOid::Varchar,
     }
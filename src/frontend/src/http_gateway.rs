@@ -0,0 +1,413 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! HTTP query gateway of the frontend, for clients without a Postgres driver such as serverless
+//! functions and browsers.
+//!
+//! `POST /query` takes a JSON body like `{"sql": "select * from mv", "page_size": 100}`. The
+//! result is returned as JSON with the values in Postgres text format, or as an Arrow IPC stream
+//! if the `accept` header is `application/vnd.apache.arrow.stream`.
+//!
+//! When `page_size` is set and more rows are available, the response carries a page token, in the
+//! `next_page_token` field of the JSON body or the `x-next-page-token` header. Passing it back as
+//! `page_token` with the same SQL returns the following page. Each page executes the query again,
+//! on the snapshot of the first page whose epoch is in the token, so the pages of a changing MV
+//! are consistent. The gateway keeps the snapshot pinned until no page of it is requested for
+//! [`PAGE_SNAPSHOT_TTL`], after which its tokens expire.
+//!
+//! Requests authenticate with the `authorization: Basic` header, and connect to the `database` of
+//! the body, or `dev` if absent. Queries are audited the same as the ones from pgwire.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use arrow::ipc::writer::StreamWriter;
+use axum::extract::Extension;
+use axum::http::header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::{Json, Router};
+use futures_async_stream::for_await;
+use itertools::Itertools;
+use parking_lot::Mutex;
+use pgwire::pg_server::{Session, SessionManager};
+use risingwave_common::array::{DataChunk, Row};
+use risingwave_common::catalog::{Schema, DEFAULT_DATABASE_NAME};
+use risingwave_common::error::{ErrorCode, Result as RwResult};
+use risingwave_sqlparser::ast::Statement;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::handler::query::{execute_query, execute_query_at_epoch, parse_single_query};
+use crate::handler::util::to_pg_rows;
+use crate::scheduler::{PinnedHummockSnapshot, QueryId};
+use crate::session::{OptimizerContext, SessionImpl, SessionManagerImpl};
+use crate::user::user_authentication::{authenticate, decode_basic_auth};
+
+const ARROW_STREAM_CONTENT_TYPE: &str = "application/vnd.apache.arrow.stream";
+const NEXT_PAGE_TOKEN_HEADER: &str = "x-next-page-token";
+
+/// How long the snapshot of a paged query is kept pinned after the last request of its pages.
+pub const PAGE_SNAPSHOT_TTL: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Deserialize)]
+pub struct QueryRequest {
+    pub sql: String,
+    pub database: Option<String>,
+    pub page_size: Option<usize>,
+    pub page_token: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ColumnInfo {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub data_type: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QueryResponse {
+    pub columns: Vec<ColumnInfo>,
+    pub rows: Vec<Vec<Option<String>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_page_token: Option<String>,
+}
+
+pub struct GatewayError(StatusCode, String);
+
+type Result<T> = std::result::Result<T, GatewayError>;
+
+impl GatewayError {
+    fn bad_request(e: impl ToString) -> Self {
+        Self(StatusCode::BAD_REQUEST, e.to_string())
+    }
+
+    fn unauthorized(e: impl ToString) -> Self {
+        Self(StatusCode::UNAUTHORIZED, e.to_string())
+    }
+
+    fn internal(e: impl ToString) -> Self {
+        Self(StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+    }
+}
+
+impl IntoResponse for GatewayError {
+    fn into_response(self) -> Response {
+        (self.0, Json(json!({ "error": self.1 }))).into_response()
+    }
+}
+
+/// A page token is the epoch of the snapshot and the offset of the page, bound to the hash of the
+/// SQL it was issued for.
+fn encode_page_token(sql: &str, epoch: u64, offset: usize) -> String {
+    base64::encode(format!("{}:{}:{}", sql_hash(sql), epoch, offset))
+}
+
+/// Returns the epoch and the offset in the page token.
+fn decode_page_token(sql: &str, token: &str) -> Result<(u64, usize)> {
+    let invalid = || GatewayError::bad_request("invalid page token");
+    let token = base64::decode(token)
+        .ok()
+        .and_then(|token| String::from_utf8(token).ok())
+        .ok_or_else(invalid)?;
+    let (hash, epoch, offset) = token.split(':').collect_tuple().ok_or_else(invalid)?;
+    if hash != sql_hash(sql).to_string() {
+        return Err(GatewayError::bad_request(
+            "page token was issued for a different query",
+        ));
+    }
+    let epoch = epoch.parse().map_err(|_| invalid())?;
+    let offset = offset.parse().map_err(|_| invalid())?;
+    Ok((epoch, offset))
+}
+
+fn sql_hash(sql: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    sql.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Returns the user name and the password in the `authorization: Basic` header.
+fn basic_auth(headers: &HeaderMap) -> Result<(String, String)> {
+    let header = headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok());
+    decode_basic_auth(header).map_err(GatewayError::unauthorized)
+}
+
+/// Snapshots kept pinned for the following pages of paged queries, by their epochs.
+#[derive(Default)]
+pub struct PageSnapshots {
+    snapshots: Mutex<HashMap<u64, (PinnedHummockSnapshot, Instant)>>,
+}
+
+impl PageSnapshots {
+    /// Pins the latest snapshot for the first page of a query, and returns its epoch.
+    async fn pin_latest(&self, session: &SessionImpl) -> Result<u64> {
+        let snapshot = session
+            .env()
+            .hummock_snapshot_manager()
+            .acquire(&QueryId::default(), session.min_read_epoch())
+            .await
+            .map_err(GatewayError::internal)?;
+        let epoch = snapshot.epoch();
+        let mut snapshots = self.snapshots.lock();
+        Self::expire(&mut snapshots);
+        // The new snapshot is dropped, i.e. unpinned, if one of the epoch is already kept.
+        let (_, last_used) = snapshots.entry(epoch).or_insert((snapshot, Instant::now()));
+        *last_used = Instant::now();
+        Ok(epoch)
+    }
+
+    /// Keeps the snapshot at `epoch` pinned for a following page, or returns an error if it has
+    /// expired.
+    fn touch(&self, epoch: u64) -> Result<()> {
+        let mut snapshots = self.snapshots.lock();
+        Self::expire(&mut snapshots);
+        let (_, last_used) = snapshots.get_mut(&epoch).ok_or_else(|| {
+            GatewayError::bad_request("page token has expired, run the query again")
+        })?;
+        *last_used = Instant::now();
+        Ok(())
+    }
+
+    fn expire(snapshots: &mut HashMap<u64, (PinnedHummockSnapshot, Instant)>) {
+        snapshots.retain(|_, (_, last_used)| last_used.elapsed() < PAGE_SNAPSHOT_TTL);
+    }
+}
+
+fn connect(
+    session_mgr: &SessionManagerImpl,
+    headers: &HeaderMap,
+    database: Option<&str>,
+) -> Result<Arc<SessionImpl>> {
+    let (user, password) = basic_auth(headers)?;
+    let session = session_mgr
+        .connect(database.unwrap_or(DEFAULT_DATABASE_NAME), &user)
+        .map_err(GatewayError::unauthorized)?;
+    if !authenticate(session.user_authenticator(), &user, &password) {
+        return Err(GatewayError::unauthorized(format!(
+            "password authentication failed for user {}",
+            user
+        )));
+    }
+    Ok(session)
+}
+
+/// Executes the query and returns the rows of the requested page, and the token of the next page
+/// if there are more rows. Paged queries read the snapshot pinned for their first page.
+async fn execute_page(
+    session: Arc<SessionImpl>,
+    page_snapshots: &PageSnapshots,
+    request: &QueryRequest,
+) -> Result<(Schema, Vec<Row>, Option<String>)> {
+    let stmt = parse_single_query(&request.sql).map_err(GatewayError::bad_request)?;
+    let page = match &request.page_token {
+        Some(token) => {
+            let (epoch, offset) = decode_page_token(&request.sql, token)?;
+            page_snapshots.touch(epoch)?;
+            Some((epoch, offset))
+        }
+        None if request.page_size.is_some() => {
+            Some((page_snapshots.pin_latest(&session).await?, 0))
+        }
+        None => None,
+    };
+    let offset = page.map_or(0, |(_, offset)| offset);
+    let limit = request.page_size.unwrap_or(usize::MAX);
+
+    let (schema, rows, has_more) = session
+        .handle_audited(stmt, &request.sql, |session, stmt| {
+            collect_page(
+                session,
+                &request.sql,
+                stmt,
+                page.map(|(epoch, _)| epoch),
+                offset,
+                limit,
+            )
+        })
+        .await
+        .map_err(GatewayError::bad_request)?;
+    let next_page_token = page
+        .filter(|_| has_more)
+        .map(|(epoch, _)| encode_page_token(&request.sql, epoch, offset + rows.len()));
+    Ok((schema, rows, next_page_token))
+}
+
+/// Executes the query, on the snapshot at `epoch` if given, and collects at most `limit` rows after
+/// the first `offset` ones, which are checked against the memory limit of the session as they're
+/// buffered. Returns whether there are more rows.
+async fn collect_page(
+    session: Arc<SessionImpl>,
+    sql: &str,
+    stmt: Statement,
+    epoch: Option<u64>,
+    offset: usize,
+    limit: usize,
+) -> RwResult<(Schema, Vec<Row>, bool)> {
+    let limits = session.query_limits();
+    let context = OptimizerContext::new(session, Arc::from(sql));
+    let (data_stream, schema) = match epoch {
+        Some(epoch) => execute_query_at_epoch(context, stmt, epoch).await?,
+        None => execute_query(context, stmt).await?,
+    };
+    let mut skipped = 0;
+    let mut rows = vec![];
+    let mut has_more = false;
+    let mut bytes = 0;
+    #[for_await]
+    for chunk in data_stream {
+        let chunk = chunk?;
+        let buffered = rows.len();
+        for row in chunk.rows() {
+            if skipped < offset {
+                skipped += 1;
+            } else if rows.len() < limit {
+                rows.push(row.to_owned_row());
+            } else {
+                has_more = true;
+                break;
+            }
+        }
+        // The buffered rows are estimated to take their share of the heap size of the chunk.
+        let cardinality = chunk.cardinality().max(1);
+        bytes += (chunk.estimated_heap_size() * (rows.len() - buffered) / cardinality) as u64;
+        limits.check_memory(bytes)?;
+        if has_more {
+            break;
+        }
+    }
+    Ok((schema, rows, has_more))
+}
+
+fn to_json_response(
+    schema: &Schema,
+    chunk: DataChunk,
+    next_page_token: Option<String>,
+) -> Response {
+    let columns = schema
+        .fields()
+        .iter()
+        .map(|field| ColumnInfo {
+            name: field.name.clone(),
+            data_type: format!("{:?}", field.data_type),
+        })
+        .collect();
+    let rows = to_pg_rows(chunk)
+        .into_iter()
+        .map(|row| row.values().to_vec())
+        .collect();
+    Json(QueryResponse {
+        columns,
+        rows,
+        next_page_token,
+    })
+    .into_response()
+}
+
+fn to_arrow_response(
+    schema: &Schema,
+    chunk: DataChunk,
+    next_page_token: Option<String>,
+) -> Result<Response> {
+    let batch = chunk
+        .to_record_batch(schema)
+        .map_err(GatewayError::internal)?;
+    let mut writer =
+        StreamWriter::try_new(vec![], &batch.schema()).map_err(GatewayError::internal)?;
+    writer.write(&batch).map_err(GatewayError::internal)?;
+    writer.finish().map_err(GatewayError::internal)?;
+    let body = writer.into_inner().map_err(GatewayError::internal)?;
+
+    let mut headers = HeaderMap::new();
+    headers.insert(CONTENT_TYPE, ARROW_STREAM_CONTENT_TYPE.parse().unwrap());
+    if let Some(token) = next_page_token {
+        headers.insert(NEXT_PAGE_TOKEN_HEADER, token.parse().unwrap());
+    }
+    Ok((headers, body).into_response())
+}
+
+async fn query(
+    Extension(session_mgr): Extension<Arc<SessionManagerImpl>>,
+    Extension(page_snapshots): Extension<Arc<PageSnapshots>>,
+    headers: HeaderMap,
+    Json(request): Json<QueryRequest>,
+) -> Result<Response> {
+    let session = connect(&session_mgr, &headers, request.database.as_deref())?;
+    let (schema, rows, next_page_token) = execute_page(session, &page_snapshots, &request).await?;
+    let chunk =
+        DataChunk::from_rows(&rows, &schema.data_types()).map_err(GatewayError::internal)?;
+
+    let wants_arrow = headers
+        .get(ACCEPT)
+        .and_then(|value| value.to_str().ok())
+        .map_or(false, |value| value.contains(ARROW_STREAM_CONTENT_TYPE));
+    if wants_arrow {
+        to_arrow_response(&schema, chunk, next_page_token)
+    } else {
+        Ok(to_json_response(&schema, chunk, next_page_token))
+    }
+}
+
+pub async fn http_gateway_serve(
+    addr: SocketAddr,
+    session_mgr: Arc<SessionManagerImpl>,
+) -> RwResult<()> {
+    tracing::info!("http query gateway listening on {}", addr);
+    let app = Router::new()
+        .route("/query", post(query))
+        .layer(Extension(session_mgr))
+        .layer(Extension(Arc::new(PageSnapshots::default())));
+    axum::Server::bind(&addr)
+        .serve(app.into_make_service())
+        .await
+        .map_err(|e| {
+            tracing::error!("http query gateway failed: {}", e);
+            ErrorCode::InternalError(e.to_string())
+        })?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_token() {
+        let token = encode_page_token("select 1", 7, 42);
+        assert_eq!(decode_page_token("select 1", &token).ok(), Some((7, 42)));
+        assert!(decode_page_token("select 2", &token).is_err());
+        assert!(decode_page_token("select 1", "garbage").is_err());
+    }
+
+    #[test]
+    fn test_basic_auth() {
+        let mut headers = HeaderMap::new();
+        assert!(basic_auth(&headers).is_err());
+        headers.insert(
+            AUTHORIZATION,
+            format!("Basic {}", base64::encode("root:pass"))
+                .parse()
+                .unwrap(),
+        );
+        let (user, password) = basic_auth(&headers).ok().unwrap();
+        assert_eq!((user.as_str(), password.as_str()), ("root", "pass"));
+    }
+}
@@ -0,0 +1,259 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Group commit of small inserts, so that thousands of single-row inserts per second from many
+//! sessions don't each pay for planning, scheduling and a DML chunk of their own.
+//!
+//! An `INSERT ... VALUES` statement of constants is held for a short delay, during which the
+//! concurrent ones into the same table, with the same columns, user and session configurations,
+//! are coalesced into it. The first statement of a batch leads it: once the delay passes or the
+//! batch is full, the rows of all statements are inserted by a single statement on behalf of the
+//! leader's session and flushed. Each statement is answered with the count of its own rows and the
+//! epoch committing them, which its session reads no older than. The batch is inserted as a whole:
+//! if the coalesced insert fails, every statement fails with the same error. The batching is
+//! disabled by default.
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use risingwave_common::error::{internal_error, Result};
+use risingwave_sqlparser::ast::{Expr, ObjectName, Query, SetExpr, Statement, Values};
+use tokio::sync::{oneshot, Notify};
+
+use crate::handler::dml::execute_dml;
+use crate::plan_cache::PlanCacheKey;
+use crate::session::{OptimizerContext, SessionImpl};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct InsertBatchKey {
+    /// The statement without rows, which identifies the table and columns.
+    statement: PlanCacheKey,
+    /// Inserts are only coalesced for the same user, whose privileges were checked.
+    user_name: String,
+}
+
+/// Rows of a statement in the batch, and where to send the count of inserted rows with the epoch
+/// committing them.
+type PendingInsert = (Vec<Vec<Expr>>, oneshot::Sender<Result<(usize, u64)>>);
+
+struct PendingBatch {
+    inserts: Vec<PendingInsert>,
+    rows: usize,
+    /// Notified once the batch is full, so that it's sent without waiting for the delay.
+    full: Arc<Notify>,
+}
+
+pub struct InsertBatcher {
+    max_delay: Duration,
+    max_rows: usize,
+    batches: Mutex<HashMap<InsertBatchKey, PendingBatch>>,
+}
+
+pub type InsertBatcherRef = Arc<InsertBatcher>;
+
+impl Default for InsertBatcher {
+    /// A disabled batcher.
+    fn default() -> Self {
+        Self::new(Duration::ZERO, 0)
+    }
+}
+
+impl InsertBatcher {
+    pub fn new(max_delay: Duration, max_rows: usize) -> Self {
+        Self {
+            max_delay,
+            max_rows,
+            batches: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        !self.max_delay.is_zero()
+    }
+
+    /// Whether the statement can be coalesced with others, i.e. an `INSERT ... VALUES` whose
+    /// values are constants, which are bound the same way in any session.
+    pub fn batchable(stmt: &Statement) -> bool {
+        let Statement::Insert { source, .. } = stmt else {
+            return false;
+        };
+        let Query {
+            with: None,
+            body: SetExpr::Values(Values(rows)),
+            order_by,
+            limit: None,
+            offset: None,
+            fetch: None,
+        } = source.as_ref()
+        else {
+            return false;
+        };
+        order_by.is_empty() && rows.iter().flatten().all(is_constant)
+    }
+
+    /// Inserts the rows of the batchable statement together with the concurrent ones into the
+    /// same table, and returns the number of its rows inserted. The rows are flushed, and the
+    /// following reads of the session see them.
+    pub async fn insert(
+        self: &Arc<Self>,
+        session: Arc<SessionImpl>,
+        stmt: Statement,
+    ) -> Result<usize> {
+        let Statement::Insert {
+            table_name,
+            columns,
+            mut source,
+        } = stmt
+        else {
+            unreachable!("only inserts are batched")
+        };
+        let body = std::mem::replace(&mut source.body, SetExpr::Values(Values(vec![])));
+        let SetExpr::Values(Values(rows)) = body else {
+            unreachable!("only inserts of values are batched")
+        };
+        let template = Statement::Insert {
+            table_name,
+            columns,
+            source,
+        };
+        let key = InsertBatchKey {
+            statement: PlanCacheKey::new(&session, &template),
+            user_name: session.user_name().to_string(),
+        };
+
+        let (tx, rx) = oneshot::channel();
+        let leader = {
+            let mut batches = self.batches.lock();
+            match batches.entry(key.clone()) {
+                Entry::Occupied(mut entry) => {
+                    let batch = entry.get_mut();
+                    batch.rows += rows.len();
+                    batch.inserts.push((rows, tx));
+                    if batch.rows >= self.max_rows {
+                        batch.full.notify_one();
+                    }
+                    None
+                }
+                Entry::Vacant(entry) => {
+                    let full = Arc::new(Notify::new());
+                    if rows.len() >= self.max_rows {
+                        full.notify_one();
+                    }
+                    entry.insert(PendingBatch {
+                        rows: rows.len(),
+                        inserts: vec![(rows, tx)],
+                        full: full.clone(),
+                    });
+                    Some(full)
+                }
+            }
+        };
+        // The batch is sent by a separate task, so that the others aren't stuck if the leader's
+        // statement is cancelled.
+        if let Some(full) = leader {
+            let this = self.clone();
+            let session = session.clone();
+            tokio::spawn(async move {
+                tokio::select! {
+                    _ = tokio::time::sleep(this.max_delay) => {}
+                    _ = full.notified() => {}
+                }
+                let batch = this.batches.lock().remove(&key).unwrap();
+                Self::send_batch(session, template, batch.inserts).await;
+            });
+        }
+        let (rows_count, epoch) = rx
+            .await
+            .map_err(|_| internal_error("batched insert is dropped"))??;
+        session.advance_write_epoch(epoch);
+        Ok(rows_count)
+    }
+
+    async fn send_batch(
+        session: Arc<SessionImpl>,
+        template: Statement,
+        inserts: Vec<PendingInsert>,
+    ) {
+        let rows = inserts
+            .iter()
+            .flat_map(|(rows, _)| rows.iter().cloned())
+            .collect();
+        // Retrying the statements one by one after a failure could insert the rows written
+        // before it twice, so they all fail instead.
+        let result = async {
+            execute_insert(&session, &template, rows).await?;
+            session.env().meta_client().flush().await
+        }
+        .await;
+        for (rows, tx) in inserts {
+            let _ = tx.send(result.clone().map(|epoch| (rows.len(), epoch)));
+        }
+    }
+}
+
+/// Executes the template insert with the given rows.
+async fn execute_insert(
+    session: &Arc<SessionImpl>,
+    template: &Statement,
+    rows: Vec<Vec<Expr>>,
+) -> Result<usize> {
+    let mut stmt = template.clone();
+    if let Statement::Insert { source, .. } = &mut stmt {
+        source.body = SetExpr::Values(Values(rows));
+    }
+    let context = OptimizerContext::new(session.clone(), stmt.to_string().into());
+    let (rows_count, ..) = execute_dml(context, stmt).await?;
+    Ok(rows_count as usize)
+}
+
+fn is_constant(expr: &Expr) -> bool {
+    match expr {
+        Expr::Value(_) | Expr::TypedString { .. } => true,
+        Expr::UnaryOp { expr, .. } | Expr::Nested(expr) | Expr::Cast { expr, .. } => {
+            is_constant(expr)
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_sqlparser::parser::Parser;
+
+    use super::*;
+
+    fn parse(sql: &str) -> Statement {
+        Parser::parse_sql(sql).unwrap().remove(0)
+    }
+
+    #[test]
+    fn test_batchable() {
+        assert!(InsertBatcher::batchable(&parse(
+            "insert into t values (1, 'a'), (-2, null)"
+        )));
+        assert!(InsertBatcher::batchable(&parse(
+            "insert into t (v1) values (cast('1' as int))"
+        )));
+        assert!(!InsertBatcher::batchable(&parse(
+            "insert into t values (now())"
+        )));
+        assert!(!InsertBatcher::batchable(&parse(
+            "insert into t select * from s"
+        )));
+        assert!(!InsertBatcher::batchable(&parse("delete from t")));
+    }
+}
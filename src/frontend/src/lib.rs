@@ -38,18 +38,30 @@
 #![feature(map_first_last)]
 #![feature(lint_reasons)]
 
+pub mod audit_log;
+pub mod auto_prepare;
 #[macro_use]
 pub mod catalog;
 pub mod binder;
 pub mod expr;
+#[cfg(not(madsim))]
+pub mod flight_sql;
 pub mod handler;
+pub mod http_gateway;
+pub mod insert_batcher;
+pub mod listener;
+pub mod monitor;
 pub mod observer;
 pub mod optimizer;
+pub mod plan_cache;
 pub mod planner;
+pub mod replay;
+pub mod result_cache;
 #[expect(dead_code)]
 pub mod scheduler;
 pub mod session;
 pub mod stream_fragmenter;
+pub mod subscription;
 pub mod utils;
 extern crate log;
 mod meta_client;
@@ -62,10 +74,14 @@ pub mod user;
 
 use std::ffi::OsString;
 use std::iter;
+use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use clap::Parser;
-use pgwire::pg_server::pg_serve;
+use pgwire::pg_server::pg_serve_with_shutdown;
+use risingwave_common::service::MetricsManager;
+use risingwave_common::util::shutdown::wait_for_shutdown_signal;
 use session::SessionManagerImpl;
 
 #[derive(Parser, Clone, Debug)]
@@ -88,6 +104,30 @@ pub struct FrontendOpts {
     /// No given `config_path` means to use default config.
     #[clap(long, default_value = "")]
     pub config_path: String,
+
+    #[clap(long, default_value = "127.0.0.1:2222")]
+    pub prometheus_listener_addr: String,
+
+    #[clap(long, default_value = "0")]
+    pub metrics_level: u32,
+
+    /// Address of the Arrow Flight SQL service. The service is disabled if not specified.
+    #[clap(long)]
+    pub flight_sql_addr: Option<SocketAddr>,
+
+    /// Address of the HTTP query gateway. The gateway is disabled if not specified.
+    #[clap(long)]
+    pub http_gateway_addr: Option<SocketAddr>,
+
+    /// Run as a frontend of a read replica. It serves read-only queries with the serving nodes
+    /// only, so that ad-hoc queries don't affect the streaming jobs on the compute nodes.
+    #[clap(long)]
+    pub read_replica: bool,
+
+    /// Print the config after applying the defaults, the config file and the environment variable
+    /// overrides, and exit.
+    #[clap(long)]
+    pub print_effective_config: bool,
 }
 
 impl Default for FrontendOpts {
@@ -104,7 +144,49 @@ pub fn start(opts: FrontendOpts) -> Pin<Box<dyn Future<Output = ()> + Send>> {
     // WARNING: don't change the function signature. Making it `async fn` will cause
     // slow compile in release mode.
     Box::pin(async move {
+        if opts.print_effective_config {
+            let config = risingwave_common::config::load_config(&opts.config_path).unwrap();
+            println!("{}", config.to_toml_string());
+            return;
+        }
+
         let session_mgr = Arc::new(SessionManagerImpl::new(&opts).await.unwrap());
-        pg_serve(&opts.host, session_mgr).await.unwrap();
+        #[cfg(not(madsim))]
+        if let Some(flight_sql_addr) = opts.flight_sql_addr {
+            tokio::spawn(flight_sql::flight_sql_serve(
+                flight_sql_addr,
+                session_mgr.clone(),
+            ));
+        }
+        #[cfg(madsim)]
+        if opts.flight_sql_addr.is_some() {
+            tracing::warn!("flight sql service is not available in the simulation");
+        }
+        if let Some(http_gateway_addr) = opts.http_gateway_addr {
+            tokio::spawn(http_gateway::http_gateway_serve(
+                http_gateway_addr,
+                session_mgr.clone(),
+            ));
+        }
+        if opts.metrics_level > 0 {
+            MetricsManager::boot_metrics_service(
+                opts.prometheus_listener_addr.clone(),
+                Arc::new(session_mgr.env().frontend_metrics().registry().clone()),
+            );
+        }
+        let drain_timeout = Duration::from_millis(
+            session_mgr
+                .env()
+                .server_config()
+                .graceful_shutdown_timeout_ms as u64,
+        );
+        pg_serve_with_shutdown(
+            &opts.host,
+            session_mgr,
+            wait_for_shutdown_signal(),
+            drain_timeout,
+        )
+        .await
+        .unwrap();
     })
 }
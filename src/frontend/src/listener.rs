@@ -0,0 +1,110 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `LISTEN` channels of a session. Each channel is backed by a task that watches a materialized
+//! view for changes and raises a notification per changed row, which pgwire pushes to the client
+//! while the connection is idle.
+
+use std::collections::HashMap;
+
+use parking_lot::Mutex;
+use pgwire::pg_server::Notification;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::task::JoinHandle;
+
+pub struct SessionListeners {
+    /// The tasks watching the channels, by channel name.
+    tasks: Mutex<HashMap<String, JoinHandle<()>>>,
+    tx: UnboundedSender<Notification>,
+    rx: tokio::sync::Mutex<UnboundedReceiver<Notification>>,
+}
+
+impl Default for SessionListeners {
+    fn default() -> Self {
+        let (tx, rx) = unbounded_channel();
+        Self {
+            tasks: Mutex::new(HashMap::new()),
+            tx,
+            rx: tokio::sync::Mutex::new(rx),
+        }
+    }
+}
+
+impl SessionListeners {
+    /// The sender for the watching tasks to raise notifications.
+    pub fn sender(&self) -> UnboundedSender<Notification> {
+        self.tx.clone()
+    }
+
+    /// Starts listening on `channel` with the given watching task, replacing the previous one.
+    pub fn listen(&self, channel: String, task: JoinHandle<()>) {
+        if let Some(previous) = self.tasks.lock().insert(channel, task) {
+            previous.abort();
+        }
+    }
+
+    /// Stops listening on `channel`, or all channels if it's `None`.
+    pub fn unlisten(&self, channel: Option<&str>) {
+        let mut tasks = self.tasks.lock();
+        match channel {
+            Some(channel) => {
+                if let Some(task) = tasks.remove(channel) {
+                    task.abort();
+                }
+            }
+            None => tasks.drain().for_each(|(_, task)| task.abort()),
+        }
+    }
+
+    pub async fn next(&self) -> Notification {
+        self.rx
+            .lock()
+            .await
+            .recv()
+            .await
+            .expect("the sender is kept by the listeners")
+    }
+}
+
+impl Drop for SessionListeners {
+    fn drop(&mut self) {
+        self.unlisten(None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_unlisten_stops_task() {
+        let listeners = SessionListeners::default();
+        let sender = listeners.sender();
+        let task = tokio::spawn(async move {
+            loop {
+                sender
+                    .send(Notification {
+                        channel: "mv".to_string(),
+                        payload: String::new(),
+                    })
+                    .unwrap();
+                tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+            }
+        });
+        listeners.listen("mv".to_string(), task);
+        assert_eq!(listeners.next().await.channel, "mv");
+        listeners.unlisten(Some("mv"));
+        assert!(listeners.tasks.lock().is_empty());
+    }
+}
@@ -12,6 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use risingwave_pb::data::ConfigChange;
+use risingwave_pb::ddl_service::CatalogSnapshot;
+use risingwave_pb::hummock::TableStorageStats;
+use risingwave_pb::meta::TelemetryReport;
 use risingwave_rpc_client::error::Result;
 use risingwave_rpc_client::{HummockMetaClient, MetaClient};
 
@@ -24,11 +28,27 @@ use risingwave_rpc_client::{HummockMetaClient, MetaClient};
 pub trait FrontendMetaClient: Send + Sync {
     async fn pin_snapshot(&self, last_pinned: u64) -> Result<u64>;
 
-    async fn flush(&self) -> Result<()>;
+    async fn flush(&self) -> Result<u64>;
 
     async fn unpin_snapshot(&self, epoch: u64) -> Result<()>;
 
     async fn unpin_snapshot_before(&self, epoch: u64) -> Result<()>;
+
+    async fn get_table_storage_stats(&self) -> Result<Vec<TableStorageStats>>;
+
+    async fn set_system_param(&self, param: String, value: String) -> Result<()>;
+
+    async fn savepoint(&self, table_id: u32) -> Result<u64>;
+
+    async fn restore_savepoint(&self, table_id: u32) -> Result<()>;
+
+    async fn release_savepoint(&self, table_id: u32) -> Result<()>;
+
+    async fn reconfigure(&self, table_id: u32, change: ConfigChange) -> Result<()>;
+
+    async fn get_telemetry_report(&self) -> Result<TelemetryReport>;
+
+    async fn get_catalog(&self) -> Result<CatalogSnapshot>;
 }
 
 pub struct FrontendMetaClientImpl(pub MetaClient);
@@ -39,7 +59,7 @@ impl FrontendMetaClient for FrontendMetaClientImpl {
         self.0.pin_snapshot(last_pinned).await
     }
 
-    async fn flush(&self) -> Result<()> {
+    async fn flush(&self) -> Result<u64> {
         self.0.flush().await
     }
 
@@ -50,4 +70,36 @@ impl FrontendMetaClient for FrontendMetaClientImpl {
     async fn unpin_snapshot_before(&self, epoch: u64) -> Result<()> {
         self.0.unpin_snapshot_before(epoch).await
     }
+
+    async fn get_table_storage_stats(&self) -> Result<Vec<TableStorageStats>> {
+        self.0.get_table_storage_stats().await
+    }
+
+    async fn set_system_param(&self, param: String, value: String) -> Result<()> {
+        self.0.set_system_param(param, value).await.map(|_| ())
+    }
+
+    async fn savepoint(&self, table_id: u32) -> Result<u64> {
+        self.0.savepoint(table_id).await
+    }
+
+    async fn restore_savepoint(&self, table_id: u32) -> Result<()> {
+        self.0.restore_savepoint(table_id).await
+    }
+
+    async fn release_savepoint(&self, table_id: u32) -> Result<()> {
+        self.0.release_savepoint(table_id).await
+    }
+
+    async fn reconfigure(&self, table_id: u32, change: ConfigChange) -> Result<()> {
+        self.0.reconfigure(table_id, change).await
+    }
+
+    async fn get_telemetry_report(&self) -> Result<TelemetryReport> {
+        self.0.get_telemetry_report().await
+    }
+
+    async fn get_catalog(&self) -> Result<CatalogSnapshot> {
+        self.0.get_catalog().await
+    }
 }
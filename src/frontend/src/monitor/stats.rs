@@ -0,0 +1,166 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Instant;
+
+use prometheus::{
+    exponential_buckets, histogram_opts, register_histogram_vec_with_registry,
+    register_int_counter_vec_with_registry, register_int_gauge_with_registry, HistogramVec,
+    IntCounterVec, IntGauge, Registry,
+};
+use risingwave_sqlparser::ast::Statement;
+
+use crate::audit_log::is_ddl;
+
+pub struct FrontendMetrics {
+    registry: Registry,
+
+    /// num of statements handled, by statement type
+    pub statement_count: IntCounterVec,
+    /// num of statements failed, by statement type
+    pub statement_error_count: IntCounterVec,
+    /// end-to-end latency of statements, by statement type
+    pub statement_latency: HistogramVec,
+    /// latency of each stage of a statement: parse, bind, plan, schedule and execute
+    pub stage_latency: HistogramVec,
+    /// num of sessions currently connected
+    pub active_sessions: IntGauge,
+    /// num of plan cache lookups, by result: hit or miss
+    pub plan_cache_lookup_count: IntCounterVec,
+    /// num of result cache lookups, by result: hit or miss
+    pub result_cache_lookup_count: IntCounterVec,
+}
+
+impl FrontendMetrics {
+    pub fn new(registry: Registry) -> Self {
+        let statement_count = register_int_counter_vec_with_registry!(
+            "frontend_statement_count",
+            "num of statements handled",
+            &["stmt_type"],
+            registry
+        )
+        .unwrap();
+
+        let statement_error_count = register_int_counter_vec_with_registry!(
+            "frontend_statement_error_count",
+            "num of statements failed",
+            &["stmt_type"],
+            registry
+        )
+        .unwrap();
+
+        let opts = histogram_opts!(
+            "frontend_statement_duration_seconds",
+            "end-to-end latency of statements",
+            exponential_buckets(0.0001, 2.0, 20).unwrap() // max 52s
+        );
+        let statement_latency =
+            register_histogram_vec_with_registry!(opts, &["stmt_type"], registry).unwrap();
+
+        let opts = histogram_opts!(
+            "frontend_stage_duration_seconds",
+            "latency of each stage of a statement",
+            exponential_buckets(0.0001, 2.0, 20).unwrap() // max 52s
+        );
+        let stage_latency =
+            register_histogram_vec_with_registry!(opts, &["stage"], registry).unwrap();
+
+        let active_sessions = register_int_gauge_with_registry!(
+            "frontend_active_sessions",
+            "num of sessions currently connected",
+            registry
+        )
+        .unwrap();
+
+        let plan_cache_lookup_count = register_int_counter_vec_with_registry!(
+            "frontend_plan_cache_lookup_count",
+            "num of plan cache lookups",
+            &["result"],
+            registry
+        )
+        .unwrap();
+
+        let result_cache_lookup_count = register_int_counter_vec_with_registry!(
+            "frontend_result_cache_lookup_count",
+            "num of result cache lookups",
+            &["result"],
+            registry
+        )
+        .unwrap();
+
+        Self {
+            registry,
+            statement_count,
+            statement_error_count,
+            statement_latency,
+            stage_latency,
+            active_sessions,
+            plan_cache_lookup_count,
+            result_cache_lookup_count,
+        }
+    }
+
+    /// Create a new `FrontendMetrics` instance used in tests or other places.
+    pub fn unused() -> Self {
+        Self::new(Registry::new())
+    }
+
+    pub fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    /// Records the time elapsed since `start` as the latency of `stage`.
+    pub fn observe_stage(&self, stage: &str, start: Instant) {
+        self.stage_latency
+            .with_label_values(&[stage])
+            .observe(start.elapsed().as_secs_f64());
+    }
+}
+
+/// Returns the label of the statement in the per-statement-type metrics. DDLs share a single label
+/// to keep the cardinality low.
+pub fn statement_label(stmt: &Statement) -> &'static str {
+    match stmt {
+        Statement::Query(_) => "select",
+        Statement::Insert { .. } => "insert",
+        Statement::Update { .. } => "update",
+        Statement::Delete { .. } => "delete",
+        Statement::Explain { .. } => "explain",
+        Statement::Fetch { .. } => "fetch",
+        Statement::Flush => "flush",
+        Statement::SetVariable { .. } => "set",
+        Statement::ShowObjects(_) | Statement::ShowVariable { .. } | Statement::Describe { .. } => {
+            "show"
+        }
+        stmt if is_ddl(stmt) => "ddl",
+        _ => "other",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_sqlparser::parser::Parser;
+
+    use super::*;
+
+    #[test]
+    fn test_statement_label() {
+        let label = |sql: &str| statement_label(&Parser::parse_sql(sql).unwrap()[0]);
+        assert_eq!(label("SELECT 1"), "select");
+        assert_eq!(label("INSERT INTO t VALUES (1)"), "insert");
+        assert_eq!(label("CREATE TABLE t (v INT)"), "ddl");
+        assert_eq!(label("SHOW TABLES"), "show");
+        assert_eq!(label("START TRANSACTION"), "other");
+    }
+}
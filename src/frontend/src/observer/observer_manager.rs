@@ -45,6 +45,9 @@ pub(crate) struct ObserverManager {
     user_info_manager: Arc<RwLock<UserInfoManager>>,
     user_info_updated_tx: Sender<UserInfoVersion>,
     hummock_snapshot_manager: HummockSnapshotManagerRef,
+    /// Type of the workers that batch tasks are scheduled to. Serving nodes for read replicas, and
+    /// compute nodes otherwise.
+    batch_worker_type: WorkerType,
 }
 
 const RE_SUBSCRIBE_RETRY_INTERVAL: Duration = Duration::from_millis(100);
@@ -60,6 +63,7 @@ impl ObserverManager {
         user_info_manager: Arc<RwLock<UserInfoManager>>,
         user_info_updated_tx: Sender<UserInfoVersion>,
         hummock_snapshot_manager: HummockSnapshotManagerRef,
+        read_replica: bool,
     ) -> Self {
         let rx = meta_client
             .subscribe(&addr, WorkerType::Frontend)
@@ -75,6 +79,11 @@ impl ObserverManager {
             user_info_manager,
             user_info_updated_tx,
             hummock_snapshot_manager,
+            batch_worker_type: if read_replica {
+                WorkerType::ServingNode
+            } else {
+                WorkerType::ComputeNode
+            },
         }
     }
 
@@ -100,7 +109,13 @@ impl ObserverManager {
                 for user in snapshot.users {
                     user_guard.create_user(user)
                 }
-                self.worker_node_manager.refresh_worker_node(snapshot.nodes);
+                let nodes = snapshot
+                    .nodes
+                    .into_iter()
+                    .filter(|node| node.r#type == self.batch_worker_type as i32)
+                    .collect();
+                self.worker_node_manager
+                    .refresh_worker_node(nodes, resp.version);
             }
             _ => {
                 return Err(ErrorCode::InternalError(format!(
@@ -111,7 +126,9 @@ impl ObserverManager {
             }
         }
         catalog_guard.set_version(resp.version);
+        user_guard.set_version(resp.version);
         self.catalog_updated_tx.send(resp.version).unwrap();
+        self.user_info_updated_tx.send(resp.version).unwrap();
         Ok(())
     }
 
@@ -134,6 +151,7 @@ impl ObserverManager {
             },
             Info::Table(table) => match resp.operation() {
                 Operation::Add => catalog_guard.create_table(table),
+                Operation::Update => catalog_guard.update_table(table),
                 Operation::Delete => {
                     catalog_guard.drop_table(table.database_id, table.schema_id, table.id.into())
                 }
@@ -192,8 +210,9 @@ impl ObserverManager {
             Info::Database(_) | Info::Schema(_) | Info::Table(_) | Info::Source(_) => {
                 self.handle_catalog_notification(resp);
             }
+            Info::Node(node) if node.r#type != self.batch_worker_type as i32 => {}
             Info::Node(node) => {
-                self.update_worker_node_manager(resp.operation(), node.clone());
+                self.update_worker_node_manager(resp.operation(), node.clone(), resp.version);
             }
             Info::User(_) => {
                 self.handle_user_notification(resp);
@@ -209,6 +228,9 @@ impl ObserverManager {
                     .update_snapshot_status(hummock_snapshot.epoch)
                     .await;
             }
+            Info::SystemParams(_) => {
+                panic!("system params are only sent to compute nodes {:?}", resp)
+            }
         }
     }
 
@@ -262,18 +284,24 @@ impl ObserverManager {
     }
 
     /// `update_worker_node_manager` is called in `start` method.
-    /// It calls `add_worker_node` and `remove_worker_node` of `WorkerNodeManager`.
-    fn update_worker_node_manager(&self, operation: Operation, node: WorkerNode) {
+    /// It applies the worker membership delta to `WorkerNodeManager`, skipping stale ones.
+    fn update_worker_node_manager(&self, operation: Operation, node: WorkerNode, version: u64) {
         tracing::debug!(
-            "Update worker nodes, operation: {:?}, node: {:?}",
+            "Update worker nodes, operation: {:?}, node: {:?}, version: {}",
             operation,
-            node
+            node,
+            version
         );
 
-        match operation {
-            Operation::Add => self.worker_node_manager.add_worker_node(node),
-            Operation::Delete => self.worker_node_manager.remove_worker_node(node),
-            _ => (),
+        if !self
+            .worker_node_manager
+            .apply_delta(operation, node, version)
+        {
+            tracing::warn!(
+                "ignore stale worker node notification, version: {}, current version: {}",
+                version,
+                self.worker_node_manager.version()
+            );
         }
     }
 }
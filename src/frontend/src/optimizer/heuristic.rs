@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::rc::Rc;
+
 use itertools::Itertools;
 
 use crate::optimizer::rule::BoxedRule;
@@ -39,6 +41,14 @@ impl HeuristicOptimizer {
     fn optimize_node(&self, mut plan: PlanRef) -> PlanRef {
         for rule in &self.rules {
             if let Some(applied) = rule.apply(plan.clone()) {
+                if !Rc::ptr_eq(&plan, &applied) {
+                    plan.ctx().trace(format!(
+                        "apply {}:\n  before: {}\n  after: {}",
+                        rule.description(),
+                        plan,
+                        applied
+                    ));
+                }
                 plan = applied;
             }
         }
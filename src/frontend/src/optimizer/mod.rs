@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod plan_hint;
 pub mod plan_node;
 pub use plan_node::PlanRef;
 pub mod property;
@@ -96,6 +97,17 @@ impl PlanRoot {
         &self.schema
     }
 
+    /// Get a reference to the plan root's plan.
+    pub fn plan(&self) -> &PlanRef {
+        &self.plan
+    }
+
+    /// Replace the plan with an equivalent one, which must have the same output data types.
+    pub fn set_plan(&mut self, plan: PlanRef) {
+        assert_eq!(plan.schema().data_types(), self.plan.schema().data_types());
+        self.plan = plan;
+    }
+
     /// Transform the [`PlanRoot`] back to a [`PlanRef`] suitable to be used as a subplan, for
     /// example as insert source or subquery. This ignores Order but retains post-Order pruning
     /// (`out_fields`).
@@ -115,6 +127,8 @@ impl PlanRoot {
     /// Apply logical optimization to the plan.
     pub fn gen_optimized_logical_plan(&self) -> PlanRef {
         let mut plan = self.plan.clone();
+        let ctx = plan.ctx();
+        ctx.trace_plan("Begin", &plan);
 
         // Subquery Unnesting.
         plan = {
@@ -126,9 +140,11 @@ impl PlanRoot {
             let heuristic_optimizer = HeuristicOptimizer::new(ApplyOrder::TopDown, rules);
             heuristic_optimizer.optimize(plan)
         };
+        ctx.trace_plan("Subquery Unnesting", &plan);
 
         // Predicate Push-down
         plan = plan.predicate_pushdown(Condition::true_cond());
+        ctx.trace_plan("Predicate Push-down", &plan);
 
         // Merge inner joins and intermediate filters into multijoin
         // This rule assumes that filters have already been pushed down near to
@@ -138,6 +154,7 @@ impl PlanRoot {
             let heuristic_optimizer = HeuristicOptimizer::new(ApplyOrder::BottomUp, rules);
             heuristic_optimizer.optimize(plan)
         };
+        ctx.trace_plan("Merge Into Multi Join", &plan);
 
         // Reorder multijoin into left-deep join tree.
         plan = {
@@ -145,10 +162,12 @@ impl PlanRoot {
             let heuristic_optimizer = HeuristicOptimizer::new(ApplyOrder::TopDown, rules);
             heuristic_optimizer.optimize(plan)
         };
+        ctx.trace_plan("Reorder Multi Join", &plan);
 
         // Predicate Push-down: apply filter pushdown rules again since we pullup all join
         // conditions into a filter above the multijoin.
         plan = plan.predicate_pushdown(Condition::true_cond());
+        ctx.trace_plan("Predicate Push-down", &plan);
 
         // Prune Columns
         //
@@ -158,6 +177,7 @@ impl PlanRoot {
         // use `self.out_fields` as `required_cols` here.
         let required_cols = (0..self.plan.schema().len()).collect_vec();
         plan = plan.prune_col(&required_cols);
+        ctx.trace_plan("Prune Columns", &plan);
 
         plan = {
             let rules = vec![
@@ -168,6 +188,7 @@ impl PlanRoot {
             let heuristic_optimizer = HeuristicOptimizer::new(ApplyOrder::BottomUp, rules);
             heuristic_optimizer.optimize(plan)
         };
+        ctx.trace_plan("Project Remove", &plan);
 
         plan
     }
@@ -179,9 +200,11 @@ impl PlanRoot {
 
         // Convert to physical plan node
         plan = plan.to_batch_with_order_required(&self.required_order)?;
+        plan.ctx().trace_plan("To Batch Physical Plan", &plan);
 
         // Convert to distributed plan
         plan = plan.to_distributed_with_required(&self.required_order, &self.required_dist)?;
+        plan.ctx().trace_plan("To Batch Distributed Plan", &plan);
 
         // Add Project if the any position of `self.out_fields` is set to zero.
         if self.out_fields.count_ones(..) != self.out_fields.len() {
@@ -204,9 +227,11 @@ impl PlanRoot {
 
         // Convert to physical plan node
         plan = plan.to_batch_with_order_required(&self.required_order)?;
+        plan.ctx().trace_plan("To Batch Physical Plan", &plan);
 
         // Convert to physical plan node
         plan = plan.to_local_with_order_required(&self.required_order)?;
+        plan.ctx().trace_plan("To Batch Local Plan", &plan);
 
         // We remark that since the `to_local_with_order_required` does not enforce single
         // distribution, we enforce at the root if needed.
@@ -249,6 +274,7 @@ impl PlanRoot {
                 .enforce_if_not_satisfies(self.plan.clone(), &Order::any()),
             _ => unreachable!(),
         }?;
+        plan.ctx().trace_plan("To Stream Plan", &plan);
 
         // Rewrite joins with index to delta join
         let plan = {
@@ -256,6 +282,7 @@ impl PlanRoot {
             let heuristic_optimizer = HeuristicOptimizer::new(ApplyOrder::BottomUp, rules);
             heuristic_optimizer.optimize(plan)
         };
+        plan.ctx().trace_plan("Delta Join", &plan);
 
         Ok(plan)
     }
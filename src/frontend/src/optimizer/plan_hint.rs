@@ -0,0 +1,161 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Optimizer hints given in a `/*+ ... */` comment of the query, e.g.
+//!
+//! ```sql
+//! SELECT /*+ HASH_JOIN(a b), BROADCAST(b), PARALLELISM(4) */ * FROM a JOIN b ON a.k = b.k;
+//! ```
+//!
+//! Tables are referred to by their names. Malformed or unknown hints are ignored with a warning,
+//! so that a query never fails because of its hints.
+
+use std::collections::HashSet;
+
+use crate::optimizer::PlanRef;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PlanHints {
+    /// `HASH_JOIN(t1 t2 ...)`: use hash join for joins between the tables.
+    pub hash_join: Vec<HashSet<String>>,
+    /// `NESTED_LOOP_JOIN(t1 t2 ...)`: use nested-loop join for joins between the tables.
+    pub nested_loop_join: Vec<HashSet<String>>,
+    /// `BROADCAST(t ...)`: broadcast the join side reading the tables instead of shuffling both.
+    pub broadcast: HashSet<String>,
+    /// `PARALLELISM(n)`: the number of tasks of each distributed batch stage.
+    pub parallelism: Option<u32>,
+}
+
+impl PlanHints {
+    /// Parses the hints from all `/*+ ... */` comments in `sql`.
+    pub fn parse(sql: &str) -> Self {
+        let mut hints = Self::default();
+        let mut rest = sql;
+        while let Some(start) = rest.find("/*+") {
+            rest = &rest[start + 3..];
+            let end = rest.find("*/").unwrap_or(rest.len());
+            hints.parse_comment(&rest[..end]);
+            rest = &rest[end..];
+        }
+        hints
+    }
+
+    fn parse_comment(&mut self, comment: &str) {
+        let mut rest = comment.trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+        while !rest.is_empty() {
+            let (Some(open), Some(close)) = (rest.find('('), rest.find(')')) else {
+                tracing::warn!("ignore malformed plan hint: {}", rest);
+                return;
+            };
+            if open > close {
+                tracing::warn!("ignore malformed plan hint: {}", rest);
+                return;
+            }
+            let name = rest[..open].trim();
+            let args = rest[open + 1..close]
+                .split(|c: char| c.is_whitespace() || c == ',')
+                .filter(|arg| !arg.is_empty())
+                .map(|arg| arg.to_lowercase())
+                .collect::<Vec<_>>();
+            self.add_hint(name, args);
+            rest = rest[close + 1..].trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+        }
+    }
+
+    fn add_hint(&mut self, name: &str, args: Vec<String>) {
+        match name.to_uppercase().as_str() {
+            "HASH_JOIN" if args.len() >= 2 => self.hash_join.push(args.into_iter().collect()),
+            "NESTED_LOOP_JOIN" if args.len() >= 2 => {
+                self.nested_loop_join.push(args.into_iter().collect())
+            }
+            "BROADCAST" if !args.is_empty() => self.broadcast.extend(args),
+            "PARALLELISM" if args.len() == 1 => match args[0].parse() {
+                Ok(parallelism) if parallelism > 0 => self.parallelism = Some(parallelism),
+                _ => tracing::warn!("ignore invalid parallelism hint: {}", args[0]),
+            },
+            _ => tracing::warn!("ignore unknown plan hint: {}({})", name, args.join(" ")),
+        }
+    }
+
+    /// Whether the join between `left` and `right` is hinted with `HASH_JOIN`.
+    pub fn prefer_hash_join(&self, left: &PlanRef, right: &PlanRef) -> bool {
+        Self::match_join(&self.hash_join, left, right)
+    }
+
+    /// Whether the join between `left` and `right` is hinted with `NESTED_LOOP_JOIN`.
+    pub fn prefer_nested_loop_join(&self, left: &PlanRef, right: &PlanRef) -> bool {
+        Self::match_join(&self.nested_loop_join, left, right)
+    }
+
+    /// Whether `plan` only reads tables hinted with `BROADCAST`.
+    pub fn prefer_broadcast(&self, plan: &PlanRef) -> bool {
+        let tables = scanned_tables(plan);
+        !tables.is_empty() && tables.is_subset(&self.broadcast)
+    }
+
+    /// A join hint matches a join if both sides read some of the hinted tables, and all the tables
+    /// read by the join are hinted.
+    fn match_join(join_hints: &[HashSet<String>], left: &PlanRef, right: &PlanRef) -> bool {
+        let left = scanned_tables(left);
+        let right = scanned_tables(right);
+        join_hints.iter().any(|tables| {
+            !left.is_disjoint(tables)
+                && !right.is_disjoint(tables)
+                && left.is_subset(tables)
+                && right.is_subset(tables)
+        })
+    }
+}
+
+/// Collects the names of the tables scanned in the plan.
+fn scanned_tables(plan: &PlanRef) -> HashSet<String> {
+    fn visit(plan: &PlanRef, tables: &mut HashSet<String>) {
+        if let Some(scan) = plan.as_logical_scan() {
+            tables.insert(scan.table_name().to_lowercase());
+        } else if let Some(scan) = plan.as_batch_seq_scan() {
+            tables.insert(scan.logical().table_name().to_lowercase());
+        }
+        for input in plan.inputs() {
+            visit(&input, tables);
+        }
+    }
+
+    let mut tables = HashSet::new();
+    visit(plan, &mut tables);
+    tables
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_plan_hints() {
+        let hints = PlanHints::parse(
+            "SELECT /*+ HASH_JOIN(a b), BROADCAST(B) PARALLELISM(4) */ * FROM a JOIN b ON a.k = b.k",
+        );
+        assert_eq!(
+            hints.hash_join,
+            vec![HashSet::from(["a".to_string(), "b".to_string()])]
+        );
+        assert_eq!(hints.broadcast, HashSet::from(["b".to_string()]));
+        assert_eq!(hints.parallelism, Some(4));
+
+        // Plain comments, unknown and malformed hints are ignored.
+        let hints = PlanHints::parse("SELECT /* HASH_JOIN(a b) */ /*+ FOO(a) PARALLELISM(x) */ 1");
+        assert_eq!(hints, PlanHints::default());
+        let hints = PlanHints::parse("SELECT /*+ BROADCAST(a */ 1");
+        assert_eq!(hints, PlanHints::default());
+    }
+}
@@ -0,0 +1,139 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use risingwave_common::error::Result;
+use risingwave_pb::batch_plan::plan_node::NodeBody;
+use risingwave_pb::batch_plan::AsOfJoinNode;
+
+use super::{
+    LogicalAsOfJoin, PlanBase, PlanRef, PlanTreeNodeBinary, ToBatchProst, ToDistributedBatch,
+};
+use crate::optimizer::plan_node::ToLocalBatch;
+use crate::optimizer::property::{Distribution, Order, RequiredDist};
+
+/// `BatchAsOfJoin` implements [`super::LogicalAsOfJoin`] by sorting the rows of each equal key of
+/// the right side by time, and finding the latest one for each left row with a binary search.
+#[derive(Debug, Clone)]
+pub struct BatchAsOfJoin {
+    pub base: PlanBase,
+    logical: LogicalAsOfJoin,
+}
+
+impl BatchAsOfJoin {
+    pub fn new(logical: LogicalAsOfJoin) -> Self {
+        let ctx = logical.base.ctx.clone();
+        let dist = match (
+            logical.left().distribution(),
+            logical.right().distribution(),
+        ) {
+            (Distribution::Single, Distribution::Single) => Distribution::Single,
+            (left @ Distribution::HashShard(_), Distribution::HashShard(_)) => logical
+                .l2o_col_mapping()
+                .rewrite_provided_distribution(left),
+            (_, _) => unreachable!(),
+        };
+        let base = PlanBase::new_batch(ctx, logical.schema().clone(), dist, Order::any());
+        Self { base, logical }
+    }
+}
+
+impl fmt::Display for BatchAsOfJoin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.logical.fmt_with_name(f, "BatchAsOfJoin")
+    }
+}
+
+impl PlanTreeNodeBinary for BatchAsOfJoin {
+    fn left(&self) -> PlanRef {
+        self.logical.left()
+    }
+
+    fn right(&self) -> PlanRef {
+        self.logical.right()
+    }
+
+    fn clone_with_left_right(&self, left: PlanRef, right: PlanRef) -> Self {
+        Self::new(self.logical.clone_with_left_right(left, right))
+    }
+}
+
+impl_plan_tree_node_for_binary! { BatchAsOfJoin }
+
+impl ToDistributedBatch for BatchAsOfJoin {
+    fn to_distributed(&self) -> Result<PlanRef> {
+        if self.logical.eq_keys().is_empty() {
+            let left = self
+                .left()
+                .to_distributed_with_required(&Order::any(), &RequiredDist::single())?;
+            let right = self
+                .right()
+                .to_distributed_with_required(&Order::any(), &RequiredDist::single())?;
+            return Ok(self.clone_with_left_right(left, right).into());
+        }
+
+        let right = self.right().to_distributed_with_required(
+            &Order::any(),
+            &RequiredDist::shard_by_key(
+                self.right().schema().len(),
+                &self.logical.right_eq_indexes(),
+            ),
+        )?;
+        let left_dist = self
+            .logical
+            .r2l_eq_columns_mapping()
+            .rewrite_required_distribution(&RequiredDist::PhysicalDist(
+                right.distribution().clone(),
+            ));
+        let left = self
+            .left()
+            .to_distributed_with_required(&Order::any(), &left_dist)?;
+        Ok(self.clone_with_left_right(left, right).into())
+    }
+}
+
+impl ToBatchProst for BatchAsOfJoin {
+    fn to_batch_prost_body(&self) -> NodeBody {
+        NodeBody::AsOfJoin(AsOfJoinNode {
+            join_type: self.logical.join_type() as i32,
+            left_key: self
+                .logical
+                .left_eq_indexes()
+                .into_iter()
+                .map(|a| a as i32)
+                .collect(),
+            right_key: self
+                .logical
+                .right_eq_indexes()
+                .into_iter()
+                .map(|a| a as i32)
+                .collect(),
+            left_asof_key: self.logical.left_asof_key() as i32,
+            right_asof_key: self.logical.right_asof_key() as i32,
+            inclusive: self.logical.inclusive(),
+        })
+    }
+}
+
+impl ToLocalBatch for BatchAsOfJoin {
+    fn to_local(&self) -> Result<PlanRef> {
+        let right = RequiredDist::single()
+            .enforce_if_not_satisfies(self.right().to_local()?, &Order::any())?;
+        let left = RequiredDist::single()
+            .enforce_if_not_satisfies(self.left().to_local()?, &Order::any())?;
+
+        Ok(self.clone_with_left_right(left, right).into())
+    }
+}
@@ -0,0 +1,108 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use risingwave_common::error::Result;
+use risingwave_pb::batch_plan::plan_node::NodeBody;
+use risingwave_pb::batch_plan::{ExternalScanNode, PartitionFilter};
+
+use super::{
+    LogicalSource, PlanBase, PlanRef, PlanTreeNodeLeaf, ToBatchProst, ToDistributedBatch,
+    ToLocalBatch,
+};
+use crate::optimizer::property::{Distribution, Order};
+
+/// `BatchExternalScan` reads the Parquet files of an external table, skipping the partitions
+/// excluded by its partition filters. A Delta table is read at its latest snapshot.
+#[derive(Debug, Clone)]
+pub struct BatchExternalScan {
+    pub base: PlanBase,
+    logical: LogicalSource,
+}
+
+impl PlanTreeNodeLeaf for BatchExternalScan {}
+impl_plan_tree_node_for_leaf!(BatchExternalScan);
+
+impl BatchExternalScan {
+    pub fn new(logical: LogicalSource) -> Self {
+        let ctx = logical.base.ctx.clone();
+        let base = PlanBase::new_batch(
+            ctx,
+            logical.schema().clone(),
+            Distribution::Single,
+            Order::any(),
+        );
+        Self { base, logical }
+    }
+
+    #[must_use]
+    pub fn logical(&self) -> &LogicalSource {
+        &self.logical
+    }
+}
+
+impl fmt::Display for BatchExternalScan {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "BatchExternalScan {{ table: {}, columns: [{}]",
+            self.logical.source_catalog.name,
+            self.logical.column_names().join(", ")
+        )?;
+        let partition_filters = self.logical.partition_filters_display();
+        if !partition_filters.is_empty() {
+            write!(f, ", partition_filters: [{}]", partition_filters.join(", "))?;
+        }
+        write!(f, " }}")
+    }
+}
+
+impl ToDistributedBatch for BatchExternalScan {
+    fn to_distributed(&self) -> Result<PlanRef> {
+        Ok(self.clone().into())
+    }
+}
+
+impl ToLocalBatch for BatchExternalScan {
+    fn to_local(&self) -> Result<PlanRef> {
+        Ok(self.clone().into())
+    }
+}
+
+impl ToBatchProst for BatchExternalScan {
+    fn to_batch_prost_body(&self) -> NodeBody {
+        let source_catalog = &self.logical.source_catalog;
+        let fields = self.logical.schema().fields();
+        let external = source_catalog.external.as_ref().unwrap();
+        NodeBody::ExternalScan(ExternalScanNode {
+            location: external.location.clone(),
+            column_descs: source_catalog
+                .columns
+                .iter()
+                .map(|c| c.column_desc.to_protobuf())
+                .collect(),
+            partition_filters: self
+                .logical
+                .partition_filters()
+                .iter()
+                .map(|(idx, values)| PartitionFilter {
+                    column: fields[*idx].name.clone(),
+                    values: values.clone(),
+                })
+                .collect(),
+            format: external.format as i32,
+        })
+    }
+}
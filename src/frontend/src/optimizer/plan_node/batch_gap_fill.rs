@@ -0,0 +1,106 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use risingwave_common::error::Result;
+use risingwave_pb::batch_plan::plan_node::NodeBody;
+use risingwave_pb::batch_plan::GapFillNode;
+
+use super::{
+    LogicalGapFill, PlanBase, PlanRef, PlanTreeNodeUnary, ToBatchProst, ToDistributedBatch,
+};
+use crate::optimizer::plan_node::ToLocalBatch;
+use crate::optimizer::property::{Order, RequiredDist};
+
+/// `BatchGapFill` implements [`super::LogicalGapFill`] by sorting the rows of each partition by
+/// time, and filling the gaps between the adjacent rows.
+#[derive(Debug, Clone)]
+pub struct BatchGapFill {
+    pub base: PlanBase,
+    logical: LogicalGapFill,
+}
+
+impl BatchGapFill {
+    pub fn new(logical: LogicalGapFill) -> Self {
+        let ctx = logical.base.ctx.clone();
+        let dist = logical.input().distribution().clone();
+        let base = PlanBase::new_batch(ctx, logical.schema().clone(), dist, Order::any());
+        BatchGapFill { base, logical }
+    }
+}
+
+impl fmt::Display for BatchGapFill {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.logical.fmt_with_name(f, "BatchGapFill")
+    }
+}
+
+impl PlanTreeNodeUnary for BatchGapFill {
+    fn input(&self) -> PlanRef {
+        self.logical.input()
+    }
+
+    fn clone_with_input(&self, input: PlanRef) -> Self {
+        Self::new(self.logical.clone_with_input(input))
+    }
+}
+
+impl_plan_tree_node_for_unary! { BatchGapFill }
+
+impl ToDistributedBatch for BatchGapFill {
+    fn to_distributed(&self) -> Result<PlanRef> {
+        let required_dist = if self.logical.partition_by().is_empty() {
+            RequiredDist::single()
+        } else {
+            RequiredDist::shard_by_key(self.input().schema().len(), self.logical.partition_by())
+        };
+        let new_input = self
+            .input()
+            .to_distributed_with_required(&Order::any(), &required_dist)?;
+        Ok(self.clone_with_input(new_input).into())
+    }
+}
+
+impl ToBatchProst for BatchGapFill {
+    fn to_batch_prost_body(&self) -> NodeBody {
+        NodeBody::GapFill(GapFillNode {
+            time_col: self.logical.time_col() as u32,
+            interval: Some(self.logical.interval().into()),
+            partition_by: self
+                .logical
+                .partition_by()
+                .iter()
+                .map(|&idx| idx as u32)
+                .collect(),
+            strategies: self
+                .logical
+                .strategies()
+                .iter()
+                .map(|&strategy| strategy as i32)
+                .collect(),
+        })
+    }
+}
+
+impl ToLocalBatch for BatchGapFill {
+    fn to_local(&self) -> Result<PlanRef> {
+        let new_input = self.input().to_local()?;
+
+        let new_input =
+            RequiredDist::single().enforce_if_not_satisfies(new_input, &Order::any())?;
+
+        Ok(self.clone_with_input(new_input).into())
+    }
+}
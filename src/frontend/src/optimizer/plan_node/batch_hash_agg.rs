@@ -42,6 +42,7 @@ impl BatchHashAgg {
                 .i2o_col_mapping()
                 .rewrite_provided_distribution(input_dist),
             Distribution::SomeShard => Distribution::SomeShard,
+            Distribution::Broadcast => unreachable!(),
         };
         let base = PlanBase::new_batch(ctx, logical.schema().clone(), dist, Order::any());
         BatchHashAgg { base, logical }
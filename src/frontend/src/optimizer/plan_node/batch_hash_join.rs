@@ -17,6 +17,7 @@ use std::fmt;
 use risingwave_common::error::Result;
 use risingwave_pb::batch_plan::plan_node::NodeBody;
 use risingwave_pb::batch_plan::HashJoinNode;
+use risingwave_pb::plan_common::JoinType;
 
 use super::{
     EqJoinPredicate, LogicalJoin, PlanBase, PlanRef, PlanTreeNodeBinary, ToBatchProst,
@@ -69,6 +70,8 @@ impl BatchHashJoin {
             (Distribution::HashShard(_), Distribution::HashShard(_)) => {
                 l2o_mapping.rewrite_provided_distribution(left)
             }
+            // The right side is replicated to every partition of the left side.
+            (_, Distribution::Broadcast) => l2o_mapping.rewrite_provided_distribution(left),
             (_, _) => unreachable!(),
         }
     }
@@ -77,6 +80,15 @@ impl BatchHashJoin {
     pub fn eq_join_predicate(&self) -> &EqJoinPredicate {
         &self.eq_join_predicate
     }
+
+    /// Broadcasting the right side is only correct if each output row is produced by probing the
+    /// left side, i.e. no unmatched right rows need to be emitted.
+    fn can_broadcast_right(&self) -> bool {
+        matches!(
+            self.logical.join_type(),
+            JoinType::Inner | JoinType::LeftOuter | JoinType::LeftSemi | JoinType::LeftAnti
+        )
+    }
 }
 
 impl fmt::Display for BatchHashJoin {
@@ -122,6 +134,16 @@ impl_plan_tree_node_for_binary! { BatchHashJoin }
 
 impl ToDistributedBatch for BatchHashJoin {
     fn to_distributed(&self) -> Result<PlanRef> {
+        let ctx = self.base.ctx.clone();
+        if self.can_broadcast_right() && ctx.inner().hints.prefer_broadcast(&self.right()) {
+            let left = self.left().to_distributed()?;
+            let right = self.right().to_distributed_with_required(
+                &Order::any(),
+                &RequiredDist::PhysicalDist(Distribution::Broadcast),
+            )?;
+            return Ok(self.clone_with_left_right(left, right).into());
+        }
+
         let right = self.right().to_distributed_with_required(
             &Order::any(),
             &RequiredDist::shard_by_key(
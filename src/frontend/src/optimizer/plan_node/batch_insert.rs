@@ -19,7 +19,9 @@ use risingwave_pb::batch_plan::plan_node::NodeBody;
 use risingwave_pb::batch_plan::InsertNode;
 use risingwave_pb::plan_common::TableRefId;
 
-use super::{LogicalInsert, PlanRef, PlanTreeNodeUnary, ToBatchProst, ToDistributedBatch};
+use super::{
+    LogicalInsert, PlanRef, PlanTreeNode, PlanTreeNodeUnary, ToBatchProst, ToDistributedBatch,
+};
 use crate::optimizer::plan_node::{PlanBase, ToLocalBatch};
 use crate::optimizer::property::{Distribution, Order};
 
@@ -42,6 +44,19 @@ impl BatchInsert {
         );
         BatchInsert { base, logical }
     }
+
+    /// Whether the inserted rows are read from relations instead of given by `VALUES`.
+    fn is_bulk(&self) -> bool {
+        fn reads_relations(plan: &PlanRef) -> bool {
+            let inputs = plan.inputs();
+            if inputs.is_empty() {
+                plan.as_batch_values().is_none()
+            } else {
+                inputs.iter().any(reads_relations)
+            }
+        }
+        reads_relations(&self.input())
+    }
 }
 
 impl fmt::Display for BatchInsert {
@@ -78,6 +93,7 @@ impl ToBatchProst for BatchInsert {
             }
             .into(),
             column_ids: vec![], // unused
+            bulk: self.is_bulk(),
         })
     }
 }
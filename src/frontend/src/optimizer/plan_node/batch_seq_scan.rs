@@ -22,6 +22,7 @@ use risingwave_pb::batch_plan::{RowSeqScanNode, SysRowSeqScanNode};
 use risingwave_pb::plan_common::ColumnDesc as ProstColumnDesc;
 
 use super::{PlanBase, PlanRef, ToBatchProst, ToDistributedBatch};
+use crate::binder::BoundTableSample;
 use crate::catalog::ColumnId;
 use crate::expr::Literal;
 use crate::optimizer::plan_node::{LogicalScan, ToLocalBatch};
@@ -34,13 +35,22 @@ pub struct BatchSeqScan {
     pub base: PlanBase,
     logical: LogicalScan,
     scan_range: ScanRange,
+    /// Whether to scan the table in descending order of the primary key.
+    reverse: bool,
+    /// The maximum number of rows to read, if any.
+    limit: Option<u64>,
 }
 
 impl BatchSeqScan {
-    pub fn new_inner(logical: LogicalScan, dist: Distribution, scan_range: ScanRange) -> Self {
+    fn new_inner(
+        logical: LogicalScan,
+        dist: Distribution,
+        scan_range: ScanRange,
+        order: Order,
+        reverse: bool,
+    ) -> Self {
         let ctx = logical.base.ctx.clone();
-        // TODO: derive from input
-        let base = PlanBase::new_batch(ctx, logical.schema().clone(), dist, Order::any());
+        let base = PlanBase::new_batch(ctx, logical.schema().clone(), dist, order);
 
         {
             // validate scan_range
@@ -57,23 +67,59 @@ impl BatchSeqScan {
             base,
             logical,
             scan_range,
+            reverse,
+            limit: None,
         }
     }
 
     pub fn new(logical: LogicalScan, scan_range: ScanRange) -> Self {
-        Self::new_inner(logical, Distribution::Single, scan_range)
+        Self::new_inner(
+            logical,
+            Distribution::Single,
+            scan_range,
+            Order::any(),
+            false,
+        )
+    }
+
+    /// Create a [`BatchSeqScan`] which yields rows in the order of the primary key, or in the
+    /// reversed order if `reverse` is set.
+    pub fn new_with_order(logical: LogicalScan, scan_range: ScanRange, reverse: bool) -> Self {
+        let order = if reverse {
+            logical.storage_order().reverse()
+        } else {
+            logical.storage_order()
+        };
+        Self::new_inner(logical, Distribution::Single, scan_range, order, reverse)
+    }
+
+    /// Stop after reading `limit` rows, which is only valid if all rows in the scan range are
+    /// returned, i.e. no rows are filtered out after the scan.
+    pub fn clone_with_limit(&self, limit: u64) -> Self {
+        Self {
+            limit: Some(limit),
+            ..self.clone()
+        }
     }
 
     pub fn clone_with_dist(&self) -> Self {
-        Self::new_inner(
+        let scan = Self::new_inner(
             self.logical.clone(),
-            if self.logical.is_sys_table() {
+            // An ordered scan is done by a single task, otherwise the order would be lost
+            // when gathering the results.
+            if self.logical.is_sys_table() || !self.order().is_any() {
                 Distribution::Single
             } else {
                 Distribution::SomeShard
             },
             self.scan_range.clone(),
-        )
+            self.order().clone(),
+            self.reverse,
+        );
+        Self {
+            limit: self.limit,
+            ..scan
+        }
     }
 
     /// Get a reference to the batch seq scan's logical.
@@ -114,12 +160,25 @@ impl fmt::Display for BatchSeqScan {
             }
         }
 
+        let reverse_str = if self.reverse { ", reverse: true" } else { "" };
+        let limit_str = match self.limit {
+            Some(limit) => format!(", limit: {}", limit),
+            None => "".to_string(),
+        };
+        let sample_str = match self.logical.sample() {
+            Some(sample) => format!(", sample: {}", sample),
+            None => "".to_string(),
+        };
+
         if self.scan_range.is_full_table_scan() {
             write!(
                 f,
-                "BatchScan {{ table: {}, columns: [{}] }}",
+                "BatchScan {{ table: {}, columns: [{}]{}{}{} }}",
                 self.logical.table_name(),
-                self.logical.column_names().join(", ")
+                self.logical.column_names().join(", "),
+                reverse_str,
+                limit_str,
+                sample_str
             )
         } else {
             let order_names = self.logical.order_names();
@@ -138,10 +197,13 @@ impl fmt::Display for BatchSeqScan {
 
             write!(
                 f,
-                "BatchScan {{ table: {}, columns: [{}], scan_range: [{}] }}",
+                "BatchScan {{ table: {}, columns: [{}], scan_range: [{}]{}{}{} }}",
                 self.logical.table_name(),
                 self.logical.column_names().join(", "),
-                range_str.join(", ")
+                range_str.join(", "),
+                reverse_str,
+                limit_str,
+                sample_str
             )
         }
     }
@@ -177,6 +239,9 @@ impl ToBatchProst for BatchSeqScan {
                     .map(ColumnId::get_id)
                     .collect(),
                 scan_range: Some(self.scan_range.to_protobuf()),
+                reverse: self.reverse,
+                sample: self.logical.sample().map(BoundTableSample::to_protobuf),
+                limit: self.limit.unwrap_or_default(),
             })
         }
     }
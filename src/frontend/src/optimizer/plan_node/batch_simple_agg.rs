@@ -36,6 +36,7 @@ impl BatchSimpleAgg {
         let input_dist = input.distribution();
         match input_dist {
             Distribution::Single | Distribution::SomeShard | Distribution::HashShard(_) => {}
+            Distribution::Broadcast => unreachable!(),
         };
         let base = PlanBase::new_batch(
             ctx,
@@ -0,0 +1,110 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use risingwave_common::catalog::{Field, Schema};
+use risingwave_common::error::Result;
+use risingwave_common::types::DataType;
+use risingwave_pb::batch_plan::plan_node::NodeBody;
+use risingwave_pb::batch_plan::{unload_node, UnloadNode};
+
+use super::{PlanBase, PlanRef, PlanTreeNodeUnary, ToBatchProst, ToDistributedBatch, ToLocalBatch};
+use crate::optimizer::property::Order;
+
+/// `BatchUnload` writes the rows of its input to part files under `location`, and outputs the
+/// path and the row count of each written file. Each task of its stage writes its own files.
+#[derive(Debug, Clone)]
+pub struct BatchUnload {
+    pub base: PlanBase,
+    input: PlanRef,
+    location: String,
+    format: unload_node::Format,
+    /// The names of the input columns in the written files.
+    column_names: Vec<String>,
+}
+
+impl BatchUnload {
+    pub fn new(
+        input: PlanRef,
+        location: String,
+        format: unload_node::Format,
+        column_names: Vec<String>,
+    ) -> Self {
+        let ctx = input.ctx();
+        let schema = Schema::new(vec![
+            Field::with_name(DataType::Varchar, "path"),
+            Field::with_name(DataType::Int64, "rows"),
+        ]);
+        let base = PlanBase::new_batch(ctx, schema, input.distribution().clone(), Order::any());
+        Self {
+            base,
+            input,
+            location,
+            format,
+            column_names,
+        }
+    }
+}
+
+impl fmt::Display for BatchUnload {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "BatchUnload {{ location: {}, format: {:?} }}",
+            self.location, self.format
+        )
+    }
+}
+
+impl PlanTreeNodeUnary for BatchUnload {
+    fn input(&self) -> PlanRef {
+        self.input.clone()
+    }
+
+    fn clone_with_input(&self, input: PlanRef) -> Self {
+        Self::new(
+            input,
+            self.location.clone(),
+            self.format,
+            self.column_names.clone(),
+        )
+    }
+}
+
+impl_plan_tree_node_for_unary! { BatchUnload }
+
+impl ToDistributedBatch for BatchUnload {
+    fn to_distributed(&self) -> Result<PlanRef> {
+        let new_input = self.input().to_distributed()?;
+        Ok(self.clone_with_input(new_input).into())
+    }
+}
+
+impl ToBatchProst for BatchUnload {
+    fn to_batch_prost_body(&self) -> NodeBody {
+        NodeBody::Unload(UnloadNode {
+            location: self.location.clone(),
+            format: self.format as i32,
+            column_names: self.column_names.clone(),
+        })
+    }
+}
+
+impl ToLocalBatch for BatchUnload {
+    fn to_local(&self) -> Result<PlanRef> {
+        let new_input = self.input().to_local()?;
+        Ok(self.clone_with_input(new_input).into())
+    }
+}
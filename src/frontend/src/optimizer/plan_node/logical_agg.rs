@@ -19,20 +19,22 @@ use fixedbitset::FixedBitSet;
 use itertools::Itertools;
 use risingwave_common::catalog::{ColumnDesc, ColumnId, Field, OrderedColumnDesc, Schema, TableId};
 use risingwave_common::error::{ErrorCode, Result};
+use risingwave_common::session_config::TWO_PHASE_AGG;
 use risingwave_common::types::DataType;
 use risingwave_common::util::sort_util::OrderType;
 use risingwave_expr::expr::AggKind;
 use risingwave_pb::expr::AggCall as ProstAggCall;
+use risingwave_pb::plan_common::HandleConflictBehavior;
 
 use super::{
     BatchHashAgg, BatchSimpleAgg, ColPrunable, PlanBase, PlanRef, PlanTreeNodeUnary,
-    PredicatePushdown, StreamHashAgg, StreamSimpleAgg, ToBatch, ToStream,
+    PredicatePushdown, StreamHashAgg, StreamLocalAgg, StreamSimpleAgg, ToBatch, ToStream,
 };
 use crate::catalog::column_catalog::ColumnCatalog;
 use crate::catalog::table_catalog::TableCatalog;
 use crate::expr::{AggCall, Expr, ExprImpl, ExprRewriter, ExprType, FunctionCall, InputRef};
 use crate::optimizer::plan_node::{gen_filter_and_pushdown, LogicalProject};
-use crate::optimizer::property::RequiredDist;
+use crate::optimizer::property::{Order, RequiredDist};
 use crate::utils::{ColIndexMapping, Condition, Substitute};
 
 /// Aggregation Call
@@ -183,13 +185,20 @@ impl LogicalAgg {
                 name: String::new(),
                 columns,
                 order_desc,
+                sort_key_len: 0,
                 pks: internal_pk_indices,
                 is_index_on: None,
+                is_internal_of: None,
                 distribution_keys: base.dist.dist_column_indices().to_vec(),
                 appendonly: false,
                 owner: risingwave_common::catalog::DEFAULT_SUPPER_USER.to_string(),
                 vnode_mapping: None,
                 properties: HashMap::default(),
+                definition: String::new(),
+                description: String::new(),
+                column_descriptions: HashMap::default(),
+                dependent_relations: vec![],
+                handle_pk_conflict_behavior: HandleConflictBehavior::NoCheck,
             });
         }
         (table_catalogs, column_mapping)
@@ -340,7 +349,10 @@ impl ExprRewriter for ExprHandler {
     /// Or all `InputRef`s appears in it must refer to a group column.
     fn rewrite_function_call(&mut self, func_call: FunctionCall) -> ExprImpl {
         let expr: ExprImpl = func_call.into();
-        if !expr.has_subquery() && let Some(index) = self.expr_index.get(&expr) && *index < self.group_key_len {
+        if !expr.has_subquery()
+            && let Some(index) = self.expr_index.get(&expr)
+            && *index < self.group_key_len
+        {
             InputRef::new(*index, expr.return_type()).into()
         } else {
             let (func_type, inputs, ret) = expr.into_function_call().unwrap().decompose();
@@ -355,7 +367,9 @@ impl ExprRewriter for ExprHandler {
     /// When there is an `InputRef` (outside of agg call), it must refers to a group column.
     fn rewrite_input_ref(&mut self, input_ref: InputRef) -> ExprImpl {
         let expr = input_ref.into();
-        if let Some(index) = self.expr_index.get(&expr) && *index < self.group_key_len {
+        if let Some(index) = self.expr_index.get(&expr)
+            && *index < self.group_key_len
+        {
             InputRef::new(*index, expr.return_type()).into()
         } else {
             self.error = Some(ErrorCode::InvalidInputSyntax(
@@ -478,6 +492,56 @@ impl LogicalAgg {
     }
 
     /// Get a reference to the logical agg's agg calls.
+    /// Whether the agg can be split into a local agg before the shuffle and a global agg after it,
+    /// i.e. two-phase agg is enabled in the session, and all the agg calls can be combined from
+    /// their partial results.
+    fn can_two_phase_agg(&self, stream_input: &PlanRef) -> bool {
+        let enabled = self
+            .ctx()
+            .inner()
+            .session_ctx
+            .get_config(TWO_PHASE_AGG)
+            .map_or(false, |config| config.is_set(false));
+        enabled
+            && self.agg_calls.iter().all(|agg_call| {
+                !agg_call.distinct
+                    && match agg_call.agg_kind {
+                        AggKind::Count | AggKind::RowCount | AggKind::Sum => true,
+                        // The partial extremes of an epoch can't be retracted.
+                        AggKind::Min | AggKind::Max => stream_input.append_only(),
+                        _ => false,
+                    }
+            })
+    }
+
+    /// Generates a local agg on `stream_input`, and a global agg combining its partial results
+    /// after the shuffle.
+    fn to_two_phase_stream_agg(&self, stream_input: PlanRef) -> Result<PlanRef> {
+        let local_agg: PlanRef = StreamLocalAgg::new(self.clone_with_input(stream_input)).into();
+
+        // The local agg outputs the group keys first, followed by the partial results.
+        let group_key_len = self.group_keys().len();
+        let global_group_keys = (0..group_key_len).collect_vec();
+        let global_agg_calls = self
+            .agg_calls()
+            .iter()
+            .enumerate()
+            .map(|(i, agg_call)| agg_call.partial_to_total_agg_call(group_key_len + i))
+            .collect_vec();
+        let required_dist = if global_group_keys.is_empty() {
+            RequiredDist::single()
+        } else {
+            RequiredDist::shard_by_key(local_agg.schema().len(), &global_group_keys)
+        };
+        let exchange = required_dist.enforce_if_not_satisfies(local_agg, &Order::any())?;
+        let global_agg = LogicalAgg::new(global_agg_calls, global_group_keys, exchange);
+        if group_key_len == 0 {
+            Ok(StreamSimpleAgg::new(global_agg).into())
+        } else {
+            Ok(StreamHashAgg::new(global_agg).into())
+        }
+    }
+
     pub fn agg_calls(&self) -> &[PlanAggCall] {
         self.agg_calls.as_ref()
     }
@@ -692,21 +756,22 @@ impl ToBatch for LogicalAgg {
 
 impl ToStream for LogicalAgg {
     fn to_stream(&self) -> Result<PlanRef> {
+        let required_dist = if self.group_keys().is_empty() {
+            RequiredDist::single()
+        } else {
+            RequiredDist::shard_by_key(self.input().schema().len(), self.group_keys())
+        };
+        let input = self.input().to_stream()?;
+        // Two-phase agg only pays off when the input has to be shuffled anyway.
+        if !input.distribution().satisfies(&required_dist) && self.can_two_phase_agg(&input) {
+            return self.to_two_phase_stream_agg(input);
+        }
+
+        let input = required_dist.enforce_if_not_satisfies(input, &Order::any())?;
         if self.group_keys().is_empty() {
-            Ok(StreamSimpleAgg::new(
-                self.clone_with_input(
-                    self.input()
-                        .to_stream_with_dist_required(&RequiredDist::single())?,
-                ),
-            )
-            .into())
+            Ok(StreamSimpleAgg::new(self.clone_with_input(input)).into())
         } else {
-            Ok(StreamHashAgg::new(
-                self.clone_with_input(self.input().to_stream_with_dist_required(
-                    &RequiredDist::shard_by_key(self.input().schema().len(), self.group_keys()),
-                )?),
-            )
-            .into())
+            Ok(StreamHashAgg::new(self.clone_with_input(input)).into())
         }
     }
 
@@ -126,24 +126,27 @@ impl PredicatePushdown for LogicalApply {
     }
 }
 
+/// The error of an `Apply` left by the subquery unnesting, i.e. the correlated subquery is not
+/// supported yet.
+fn unnest_error() -> RwError {
+    RwError::from(ErrorCode::PlanError(
+        "LogicalApply should be unnested".to_string(),
+    ))
+    .with_hint("Try to rewrite the correlated subquery with joins.")
+}
+
 impl ToBatch for LogicalApply {
     fn to_batch(&self) -> Result<PlanRef> {
-        Err(RwError::from(ErrorCode::InternalError(
-            "LogicalApply should be unnested".to_string(),
-        )))
+        Err(unnest_error())
     }
 }
 
 impl ToStream for LogicalApply {
     fn to_stream(&self) -> Result<PlanRef> {
-        Err(RwError::from(ErrorCode::InternalError(
-            "LogicalApply should be unnested".to_string(),
-        )))
+        Err(unnest_error())
     }
 
     fn logical_rewrite_for_stream(&self) -> Result<(PlanRef, ColIndexMapping)> {
-        Err(RwError::from(ErrorCode::InternalError(
-            "LogicalApply should be unnested".to_string(),
-        )))
+        Err(unnest_error())
     }
 }
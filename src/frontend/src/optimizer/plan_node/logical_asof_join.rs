@@ -0,0 +1,379 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use fixedbitset::FixedBitSet;
+use itertools::Itertools;
+use risingwave_common::catalog::Schema;
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_pb::plan_common::JoinType;
+
+use super::{
+    BatchAsOfJoin, ColPrunable, LogicalFilter, LogicalProject, PlanBase, PlanRef,
+    PlanTreeNodeBinary, PredicatePushdown, StreamAsOfJoin, ToBatch, ToStream,
+};
+use crate::expr::{Expr, ExprImpl, ExprType, InputRefDisplay};
+use crate::optimizer::property::RequiredDist;
+use crate::utils::{ColIndexMapping, Condition};
+
+/// `LogicalAsOfJoin` matches each left row with the latest right row of the same equal keys, whose
+/// time is no later than the left row's, i.e. `ASOF JOIN ... ON l.k = r.k AND l.ts >= r.ts`. The
+/// output has all the columns of both sides, and the unmatched left rows of an `ASOF LEFT JOIN`
+/// are padded with NULLs. Since each left row has at most one match, the primary key of the left
+/// side is also the primary key of the output.
+#[derive(Debug, Clone)]
+pub struct LogicalAsOfJoin {
+    pub base: PlanBase,
+    left: PlanRef,
+    right: PlanRef,
+    /// Either [`JoinType::Inner`] or [`JoinType::LeftOuter`].
+    join_type: JoinType,
+    /// The equal columns, each indexed by the schema of its side.
+    eq_keys: Vec<(usize, usize)>,
+    left_asof_key: usize,
+    right_asof_key: usize,
+    /// Whether a right row at the same time as the left row matches, i.e. `>=` rather than `>`.
+    inclusive: bool,
+}
+
+impl LogicalAsOfJoin {
+    pub fn new(
+        left: PlanRef,
+        right: PlanRef,
+        join_type: JoinType,
+        eq_keys: Vec<(usize, usize)>,
+        (left_asof_key, right_asof_key): (usize, usize),
+        inclusive: bool,
+    ) -> Self {
+        assert!(matches!(join_type, JoinType::Inner | JoinType::LeftOuter));
+        let ctx = left.ctx();
+        let schema = Schema {
+            fields: left
+                .schema()
+                .fields()
+                .iter()
+                .chain(right.schema().fields())
+                .cloned()
+                .collect(),
+        };
+        let pk_indices = left.pk_indices().to_vec();
+        let base = PlanBase::new_logical(ctx, schema, pk_indices);
+        Self {
+            base,
+            left,
+            right,
+            join_type,
+            eq_keys,
+            left_asof_key,
+            right_asof_key,
+            inclusive,
+        }
+    }
+
+    /// Creates an ASOF join from the `ON` clause, which must consist of equalities and exactly one
+    /// comparison `l.ts >= r.ts` or `l.ts > r.ts` between the two sides. The conditions on the
+    /// right side alone filter the right rows before the join, and so do those on the left side
+    /// of an inner join.
+    pub fn create(
+        left: PlanRef,
+        right: PlanRef,
+        join_type: JoinType,
+        on_clause: ExprImpl,
+    ) -> Result<PlanRef> {
+        let left_len = left.schema().len();
+        let right_len = right.schema().len();
+        let (left_cond, right_cond, on) =
+            Condition::with_expr(on_clause).split(left_len, right_len);
+        if join_type == JoinType::LeftOuter && !left_cond.always_true() {
+            return Err(ErrorCode::NotImplemented(
+                "ASOF LEFT JOIN with conditions on the left side only".to_string(),
+                None.into(),
+            )
+            .into());
+        }
+        let left = LogicalFilter::create(left, left_cond);
+        let mut r2i =
+            ColIndexMapping::with_shift_offset(left_len + right_len, -(left_len as isize));
+        let right = LogicalFilter::create(right, right_cond.rewrite_expr(&mut r2i));
+
+        let (eq_keys, other_cond) = on.split_eq_keys(left_len, right_len);
+        let invalid_cond = || {
+            ErrorCode::InvalidInputSyntax(
+                "the ON clause of an ASOF join must consist of equalities and exactly one \
+                 comparison `left_time >= right_time` or `left_time > right_time`"
+                    .to_string(),
+            )
+        };
+        let [cond] = other_cond.conjunctions.as_slice() else {
+            return Err(invalid_cond().into());
+        };
+        let (left_time, ty, right_time) = cond.as_comparison_cond().ok_or_else(invalid_cond)?;
+        let inclusive = match ty {
+            ExprType::GreaterThanOrEqual => true,
+            ExprType::GreaterThan => false,
+            _ => return Err(invalid_cond().into()),
+        };
+        if left_time.index() >= left_len || right_time.index() < left_len {
+            return Err(invalid_cond().into());
+        }
+        if left_time.return_type() != right_time.return_type() {
+            return Err(ErrorCode::InvalidInputSyntax(format!(
+                "the times of an ASOF join must be of the same type, not {:?} and {:?}",
+                left_time.return_type(),
+                right_time.return_type()
+            ))
+            .into());
+        }
+
+        let eq_keys = eq_keys
+            .into_iter()
+            .map(|(l, r)| (l.index(), r.index() - left_len))
+            .collect();
+        Ok(Self::new(
+            left,
+            right,
+            join_type,
+            eq_keys,
+            (left_time.index(), right_time.index() - left_len),
+            inclusive,
+        )
+        .into())
+    }
+
+    pub fn join_type(&self) -> JoinType {
+        self.join_type
+    }
+
+    pub fn eq_keys(&self) -> &[(usize, usize)] {
+        &self.eq_keys
+    }
+
+    pub fn left_eq_indexes(&self) -> Vec<usize> {
+        self.eq_keys.iter().map(|(l, _)| *l).collect()
+    }
+
+    pub fn right_eq_indexes(&self) -> Vec<usize> {
+        self.eq_keys.iter().map(|(_, r)| *r).collect()
+    }
+
+    pub fn left_asof_key(&self) -> usize {
+        self.left_asof_key
+    }
+
+    pub fn right_asof_key(&self) -> usize {
+        self.right_asof_key
+    }
+
+    pub fn inclusive(&self) -> bool {
+        self.inclusive
+    }
+
+    /// The mapping from the columns of the left side to the output columns.
+    pub fn l2o_col_mapping(&self) -> ColIndexMapping {
+        ColIndexMapping::identity_or_none(self.left.schema().len(), self.schema().len())
+    }
+
+    /// The mapping from the equal columns of the right side to those of the left side.
+    pub fn r2l_eq_columns_mapping(&self) -> ColIndexMapping {
+        let mut map = vec![None; self.right.schema().len()];
+        for &(l, r) in &self.eq_keys {
+            map[r] = Some(l);
+        }
+        ColIndexMapping::with_target_size(map, self.left.schema().len())
+    }
+
+    pub fn fmt_with_name(&self, f: &mut fmt::Formatter, name: &str) -> fmt::Result {
+        let left_len = self.left.schema().len();
+        let predicate = self
+            .eq_keys
+            .iter()
+            .map(|&(l, r)| format!("{} = {}", InputRefDisplay(l), InputRefDisplay(r + left_len)))
+            .chain(std::iter::once(format!(
+                "{} {} {}",
+                InputRefDisplay(self.left_asof_key),
+                if self.inclusive { ">=" } else { ">" },
+                InputRefDisplay(self.right_asof_key + left_len)
+            )))
+            .join(" AND ");
+        write!(
+            f,
+            "{} {{ type: {:?}, predicate: {} }}",
+            name, self.join_type, predicate
+        )
+    }
+}
+
+impl fmt::Display for LogicalAsOfJoin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_with_name(f, "LogicalAsOfJoin")
+    }
+}
+
+impl PlanTreeNodeBinary for LogicalAsOfJoin {
+    fn left(&self) -> PlanRef {
+        self.left.clone()
+    }
+
+    fn right(&self) -> PlanRef {
+        self.right.clone()
+    }
+
+    fn clone_with_left_right(&self, left: PlanRef, right: PlanRef) -> Self {
+        Self::new(
+            left,
+            right,
+            self.join_type,
+            self.eq_keys.clone(),
+            (self.left_asof_key, self.right_asof_key),
+            self.inclusive,
+        )
+    }
+
+    #[must_use]
+    fn rewrite_with_left_right(
+        &self,
+        left: PlanRef,
+        left_col_change: ColIndexMapping,
+        right: PlanRef,
+        right_col_change: ColIndexMapping,
+    ) -> (Self, ColIndexMapping) {
+        let eq_keys = self
+            .eq_keys
+            .iter()
+            .map(|&(l, r)| (left_col_change.map(l), right_col_change.map(r)))
+            .collect();
+        let new_left_len = left.schema().len();
+        let join = Self::new(
+            left,
+            right,
+            self.join_type,
+            eq_keys,
+            (
+                left_col_change.map(self.left_asof_key),
+                right_col_change.map(self.right_asof_key),
+            ),
+            self.inclusive,
+        );
+        let map = (0..left_col_change.source_size())
+            .map(|i| left_col_change.try_map(i))
+            .chain(
+                (0..right_col_change.source_size())
+                    .map(|i| right_col_change.try_map(i).map(|i| i + new_left_len)),
+            )
+            .collect();
+        let out_col_change = ColIndexMapping::with_target_size(map, join.schema().len());
+        (join, out_col_change)
+    }
+}
+
+impl_plan_tree_node_for_binary! { LogicalAsOfJoin }
+
+impl ColPrunable for LogicalAsOfJoin {
+    fn prune_col(&self, required_cols: &[usize]) -> PlanRef {
+        let left_len = self.left.schema().len();
+        let right_len = self.right.schema().len();
+        let mut required = FixedBitSet::with_capacity(left_len + right_len);
+        required.extend(required_cols.iter().copied());
+        for &(l, r) in &self.eq_keys {
+            required.insert(l);
+            required.insert(r + left_len);
+        }
+        required.insert(self.left_asof_key);
+        required.insert(self.right_asof_key + left_len);
+        let (left_required_cols, right_required_cols): (Vec<_>, Vec<_>) =
+            required.ones().partition(|&i| i < left_len);
+        let right_required_cols = right_required_cols
+            .into_iter()
+            .map(|i| i - left_len)
+            .collect_vec();
+
+        let (join, out_col_change) = self.rewrite_with_left_right(
+            self.left.prune_col(&left_required_cols),
+            ColIndexMapping::with_remaining_columns(&left_required_cols, left_len),
+            self.right.prune_col(&right_required_cols),
+            ColIndexMapping::with_remaining_columns(&right_required_cols, right_len),
+        );
+        let required_cols = required_cols
+            .iter()
+            .map(|&i| out_col_change.map(i))
+            .collect_vec();
+        if required_cols.iter().copied().eq(0..join.schema().len()) {
+            join.into()
+        } else {
+            let src_size = join.schema().len();
+            LogicalProject::with_mapping(
+                join.into(),
+                ColIndexMapping::with_remaining_columns(&required_cols, src_size),
+            )
+            .into()
+        }
+    }
+}
+
+impl PredicatePushdown for LogicalAsOfJoin {
+    /// Only the predicates on the left side can be pushed down, since filtering the right rows
+    /// would change which of them is the latest.
+    fn predicate_pushdown(&self, predicate: Condition) -> PlanRef {
+        let left_len = self.left.schema().len();
+        let right_len = self.right.schema().len();
+        let (left_predicate, right_predicate, others) = predicate.split(left_len, right_len);
+        let new_left = self.left.predicate_pushdown(left_predicate);
+        let new_right = self.right.predicate_pushdown(Condition::true_cond());
+        let new_join = self.clone_with_left_right(new_left, new_right);
+        LogicalFilter::create(new_join.into(), right_predicate.and(others))
+    }
+}
+
+impl ToBatch for LogicalAsOfJoin {
+    fn to_batch(&self) -> Result<PlanRef> {
+        let left = self.left.to_batch()?;
+        let right = self.right.to_batch()?;
+        Ok(BatchAsOfJoin::new(self.clone_with_left_right(left, right)).into())
+    }
+}
+
+impl ToStream for LogicalAsOfJoin {
+    fn to_stream(&self) -> Result<PlanRef> {
+        let (left, right) = if self.eq_keys.is_empty() {
+            (
+                self.left
+                    .to_stream_with_dist_required(&RequiredDist::single())?,
+                self.right
+                    .to_stream_with_dist_required(&RequiredDist::single())?,
+            )
+        } else {
+            let right = self
+                .right
+                .to_stream_with_dist_required(&RequiredDist::shard_by_key(
+                    self.right.schema().len(),
+                    &self.right_eq_indexes(),
+                ))?;
+            let left_dist = self.r2l_eq_columns_mapping().rewrite_required_distribution(
+                &RequiredDist::PhysicalDist(right.distribution().clone()),
+            );
+            let left = self.left.to_stream_with_dist_required(&left_dist)?;
+            (left, right)
+        };
+        Ok(StreamAsOfJoin::new(self.clone_with_left_right(left, right)).into())
+    }
+
+    fn logical_rewrite_for_stream(&self) -> Result<(PlanRef, ColIndexMapping)> {
+        let (left, left_col_change) = self.left.logical_rewrite_for_stream()?;
+        let (right, right_col_change) = self.right.logical_rewrite_for_stream()?;
+        let (join, out_col_change) =
+            self.rewrite_with_left_right(left, left_col_change, right, right_col_change);
+        Ok((join.into(), out_col_change))
+    }
+}
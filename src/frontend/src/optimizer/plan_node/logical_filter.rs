@@ -19,11 +19,13 @@ use itertools::Itertools;
 
 use super::{
     ColPrunable, CollectInputRef, LogicalProject, PlanBase, PlanRef, PlanTreeNodeUnary,
-    PredicatePushdown, ToBatch, ToStream,
+    PredicatePushdown, StreamDynamicFilter, StreamExchange, StreamNow, StreamProject, ToBatch,
+    ToStream,
 };
-use crate::expr::{assert_input_ref, ExprImpl};
+use crate::expr::{assert_input_ref, ExprImpl, ExprRewriter, ExprType, FunctionCall, InputRef};
 use crate::optimizer::plan_node::{BatchFilter, StreamFilter};
-use crate::risingwave_common::error::Result;
+use crate::optimizer::property::Distribution;
+use crate::risingwave_common::error::{ErrorCode, Result};
 use crate::utils::{ColIndexMapping, Condition};
 
 /// `LogicalFilter` iterates over its input and returns elements for which `predicate` evaluates to
@@ -161,8 +163,33 @@ impl ToBatch for LogicalFilter {
 impl ToStream for LogicalFilter {
     fn to_stream(&self) -> Result<PlanRef> {
         let new_input = self.input().to_stream()?;
-        let new_logical = self.clone_with_input(new_input);
-        Ok(StreamFilter::new(new_logical).into())
+        // `NOW()` goes up over time in a streaming job, so the comparisons with it are evaluated by
+        // dynamic filters against a `StreamNow`, instead of a `StreamFilter`.
+        let [predicate, now_predicate] = self
+            .predicate
+            .clone()
+            .group_by::<_, 2>(|expr| expr.has_now() as usize);
+        let mut plan = if predicate.always_true() {
+            new_input
+        } else {
+            StreamFilter::new(LogicalFilter::new(new_input, predicate)).into()
+        };
+        for expr in now_predicate.conjunctions {
+            let Some((input_ref, comparator, bound)) = expr.as_now_comparison() else {
+                return Err(ErrorCode::NotImplemented(
+                    "NOW() in a streaming job other than comparisons like `v > NOW() - INTERVAL '1 hour'`"
+                        .to_string(),
+                    None.into(),
+                )
+                .into());
+            };
+            let now = StreamNow::new(self.base.ctx.clone()).into();
+            let bound = RewriteNow.rewrite_expr(bound);
+            let bound = StreamProject::new(LogicalProject::new(now, vec![bound])).into();
+            let bound = StreamExchange::new(bound, Distribution::Broadcast).into();
+            plan = StreamDynamicFilter::new(input_ref.index(), comparator, plan, bound).into();
+        }
+        Ok(plan)
     }
 
     fn logical_rewrite_for_stream(&self) -> Result<(PlanRef, ColIndexMapping)> {
@@ -172,6 +199,24 @@ impl ToStream for LogicalFilter {
     }
 }
 
+/// Replaces `NOW()` with the output column of a `StreamNow`.
+struct RewriteNow;
+
+impl ExprRewriter for RewriteNow {
+    fn rewrite_function_call(&mut self, func_call: FunctionCall) -> ExprImpl {
+        let (func_type, inputs, ret) = func_call.decompose();
+        if func_type == ExprType::Now {
+            InputRef::new(0, ret).into()
+        } else {
+            let inputs = inputs
+                .into_iter()
+                .map(|expr| self.rewrite_expr(expr))
+                .collect();
+            FunctionCall::new_unchecked(func_type, inputs, ret).into()
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
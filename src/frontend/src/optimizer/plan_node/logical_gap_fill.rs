@@ -0,0 +1,241 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use itertools::Itertools;
+use risingwave_common::error::Result;
+use risingwave_common::types::IntervalUnit;
+pub use risingwave_pb::plan_common::GapFillStrategy;
+
+use super::{
+    gen_filter_and_pushdown, BatchGapFill, ColPrunable, LogicalProject, PlanBase, PlanRef,
+    PlanTreeNodeUnary, PredicatePushdown, StreamGapFill, ToBatch, ToStream,
+};
+use crate::expr::InputRefDisplay;
+use crate::optimizer::property::RequiredDist;
+use crate::utils::{ColIndexMapping, Condition};
+
+/// `LogicalGapFill` generates a row for each empty time bucket between the buckets of each
+/// partition of its input, where `time_col` steps by `interval`. The partition keys of a generated
+/// row are copied from the buckets around it, and the other columns are filled by their
+/// [`GapFillStrategy`], or NULL.
+///
+/// The input is expected to have at most one row for each partition and bucket, e.g. the output of
+/// an aggregation grouped by a tumble window, so the output has the primary key of `partition_by`
+/// and `time_col`.
+#[derive(Debug, Clone)]
+pub struct LogicalGapFill {
+    pub base: PlanBase,
+    input: PlanRef,
+    time_col: usize,
+    interval: IntervalUnit,
+    partition_by: Vec<usize>,
+    strategies: Vec<GapFillStrategy>,
+}
+
+impl LogicalGapFill {
+    pub fn new(
+        input: PlanRef,
+        time_col: usize,
+        interval: IntervalUnit,
+        partition_by: Vec<usize>,
+        strategies: Vec<GapFillStrategy>,
+    ) -> Self {
+        assert_eq!(strategies.len(), input.schema().len());
+        let ctx = input.ctx();
+        let schema = input.schema().clone();
+        let pk_indices = partition_by
+            .iter()
+            .copied()
+            .chain(std::iter::once(time_col))
+            .collect();
+        let base = PlanBase::new_logical(ctx, schema, pk_indices);
+        Self {
+            base,
+            input,
+            time_col,
+            interval,
+            partition_by,
+            strategies,
+        }
+    }
+
+    pub fn create(
+        input: PlanRef,
+        time_col: usize,
+        interval: IntervalUnit,
+        partition_by: Vec<usize>,
+        strategies: Vec<GapFillStrategy>,
+    ) -> PlanRef {
+        Self::new(input, time_col, interval, partition_by, strategies).into()
+    }
+
+    pub fn time_col(&self) -> usize {
+        self.time_col
+    }
+
+    pub fn interval(&self) -> IntervalUnit {
+        self.interval
+    }
+
+    pub fn partition_by(&self) -> &[usize] {
+        &self.partition_by
+    }
+
+    pub fn strategies(&self) -> &[GapFillStrategy] {
+        &self.strategies
+    }
+
+    pub fn fmt_with_name(&self, f: &mut fmt::Formatter, name: &str) -> fmt::Result {
+        let fill = self
+            .strategies
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, strategy)| match strategy {
+                GapFillStrategy::None => None,
+                GapFillStrategy::Locf => Some(format!("locf({})", InputRefDisplay(idx))),
+                GapFillStrategy::Interpolate => {
+                    Some(format!("interpolate({})", InputRefDisplay(idx)))
+                }
+            })
+            .collect_vec();
+        f.debug_struct(name)
+            .field("time_col", &InputRefDisplay(self.time_col))
+            .field("interval", &format_args!("{}", self.interval))
+            .field(
+                "partition_by",
+                &self
+                    .partition_by
+                    .iter()
+                    .copied()
+                    .map(InputRefDisplay)
+                    .collect_vec(),
+            )
+            .field("fill", &format_args!("[{}]", fill.iter().format(", ")))
+            .finish()
+    }
+}
+
+impl PlanTreeNodeUnary for LogicalGapFill {
+    fn input(&self) -> PlanRef {
+        self.input.clone()
+    }
+
+    fn clone_with_input(&self, input: PlanRef) -> Self {
+        Self::new(
+            input,
+            self.time_col,
+            self.interval,
+            self.partition_by.clone(),
+            self.strategies.clone(),
+        )
+    }
+
+    #[must_use]
+    fn rewrite_with_input(
+        &self,
+        input: PlanRef,
+        input_col_change: ColIndexMapping,
+    ) -> (Self, ColIndexMapping) {
+        let time_col = input_col_change.map(self.time_col);
+        let partition_by = self
+            .partition_by
+            .iter()
+            .map(|&idx| input_col_change.map(idx))
+            .collect();
+        // The columns added to the input, e.g. by `logical_rewrite_for_stream`, are left NULL.
+        let mut strategies = vec![GapFillStrategy::None; input.schema().len()];
+        for (idx, strategy) in self.strategies.iter().enumerate() {
+            if let Some(new_idx) = input_col_change.try_map(idx) {
+                strategies[new_idx] = *strategy;
+            }
+        }
+        let new = Self::new(input, time_col, self.interval, partition_by, strategies);
+        (new, input_col_change)
+    }
+}
+
+impl_plan_tree_node_for_unary! { LogicalGapFill }
+
+impl fmt::Display for LogicalGapFill {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_with_name(f, "LogicalGapFill")
+    }
+}
+
+impl ColPrunable for LogicalGapFill {
+    fn prune_col(&self, required_cols: &[usize]) -> PlanRef {
+        let input_col_num = self.input.schema().len();
+        let input_required_cols = required_cols
+            .iter()
+            .chain(&self.partition_by)
+            .chain(std::iter::once(&self.time_col))
+            .copied()
+            .sorted()
+            .dedup()
+            .collect_vec();
+        let mapping = ColIndexMapping::with_remaining_columns(&input_required_cols, input_col_num);
+        let new_input = self.input.prune_col(&input_required_cols);
+        let (new, out_col_change) = self.rewrite_with_input(new_input, mapping);
+
+        if input_required_cols == required_cols {
+            new.into()
+        } else {
+            let required_cols = required_cols
+                .iter()
+                .map(|&idx| out_col_change.map(idx))
+                .collect_vec();
+            let src_size = new.schema().len();
+            LogicalProject::with_mapping(
+                new.into(),
+                ColIndexMapping::with_remaining_columns(&required_cols, src_size),
+            )
+            .into()
+        }
+    }
+}
+
+impl PredicatePushdown for LogicalGapFill {
+    fn predicate_pushdown(&self, predicate: Condition) -> PlanRef {
+        // A filter on the input would change the buckets around the gaps.
+        gen_filter_and_pushdown(self, predicate, Condition::true_cond())
+    }
+}
+
+impl ToBatch for LogicalGapFill {
+    fn to_batch(&self) -> Result<PlanRef> {
+        let new_input = self.input().to_batch()?;
+        let new_logical = self.clone_with_input(new_input);
+        Ok(BatchGapFill::new(new_logical).into())
+    }
+}
+
+impl ToStream for LogicalGapFill {
+    fn to_stream(&self) -> Result<PlanRef> {
+        let required_dist = if self.partition_by.is_empty() {
+            RequiredDist::single()
+        } else {
+            RequiredDist::shard_by_key(self.input.schema().len(), &self.partition_by)
+        };
+        let input = self.input().to_stream_with_dist_required(&required_dist)?;
+        Ok(StreamGapFill::new(self.clone_with_input(input)).into())
+    }
+
+    fn logical_rewrite_for_stream(&self) -> Result<(PlanRef, ColIndexMapping)> {
+        let (input, input_col_change) = self.input.logical_rewrite_for_stream()?;
+        let (gap_fill, out_col_change) = self.rewrite_with_input(input, input_col_change);
+        Ok((gap_fill.into(), out_col_change))
+    }
+}
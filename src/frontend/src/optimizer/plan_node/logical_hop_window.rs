@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeMap;
 use std::fmt;
 
 use fixedbitset::FixedBitSet;
@@ -143,6 +144,32 @@ impl LogicalHopWindow {
         self.input.schema().len() + 2
     }
 
+    /// Derives the watermarks of the output columns from those of the input columns, both as maps
+    /// from column index to the delay in milliseconds. The windows of an event time `t` start
+    /// within `(t - size, t]`, so `window_start` lags a further window size behind the time
+    /// column while `window_end` does not.
+    pub fn derive_watermark_columns(&self, input: &BTreeMap<usize, u64>) -> BTreeMap<usize, u64> {
+        let i2o = self.i2o_col_mapping();
+        let mut output: BTreeMap<_, _> = input
+            .iter()
+            .filter_map(|(&idx, &delay)| Some((i2o.try_map(idx)?, delay)))
+            .collect();
+        if let Some(&delay) = input.get(&self.time_col.index())
+            && self.window_size.get_months() == 0
+        {
+            let size_ms = self.window_size.get_days() as u64 * 24 * 3600 * 1000
+                + self.window_size.get_ms() as u64;
+            let internal2output = self.internal2output_col_mapping();
+            if let Some(start) = internal2output.try_map(self.window_start_col_idx()) {
+                output.insert(start, delay + size_ms);
+            }
+            if let Some(end) = internal2output.try_map(self.window_end_col_idx()) {
+                output.insert(end, delay);
+            }
+        }
+        output
+    }
+
     fn output2internal_col_mapping(&self) -> ColIndexMapping {
         self.internal2output_col_mapping().inverse()
     }
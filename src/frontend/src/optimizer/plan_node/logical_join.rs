@@ -667,11 +667,18 @@ impl ToBatch for LogicalJoin {
             self.on.clone(),
         );
 
+        let ctx = self.base.ctx.clone();
+        let hints = &ctx.inner().hints;
+        let use_nested_loop_join = hints.prefer_nested_loop_join(&self.left, &self.right);
+        if !predicate.has_eq() && hints.prefer_hash_join(&self.left, &self.right) {
+            tracing::warn!("ignore HASH_JOIN hint on a join without equal conditions");
+        }
+
         let left = self.left().to_batch()?;
         let right = self.right().to_batch()?;
         let logical_join = self.clone_with_left_right(left, right);
 
-        if predicate.has_eq() {
+        if predicate.has_eq() && !use_nested_loop_join {
             // Convert to Hash Join for equal joins
             // For inner joins, pull non-equal conditions to a filter operator on top of it
             let pull_filter = self.join_type == JoinType::Inner && predicate.has_non_eq();
@@ -705,7 +712,7 @@ impl ToBatch for LogicalJoin {
                 Ok(BatchHashJoin::new(logical_join, predicate).into())
             }
         } else {
-            // Convert to Nested-loop Join for non-equal joins
+            // Convert to Nested-loop Join for non-equal joins, or when hinted
             Ok(BatchNestedLoopJoin::new(logical_join).into())
         }
     }
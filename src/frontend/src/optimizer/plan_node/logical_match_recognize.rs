@@ -0,0 +1,313 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use fixedbitset::FixedBitSet;
+use itertools::Itertools;
+use risingwave_common::catalog::{Field, Schema};
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_common::types::DataType;
+pub use risingwave_pb::stream_plan::match_recognize_node::measure::Kind as MeasureKind;
+pub use risingwave_pb::stream_plan::match_recognize_node::pattern_term::Quantifier as PatternQuantifier;
+
+use super::{
+    gen_filter_and_pushdown, ColPrunable, LogicalProject, PlanBase, PlanRef, PlanTreeNodeUnary,
+    PredicatePushdown, StreamMatchRecognize, ToBatch, ToStream,
+};
+use crate::expr::{ExprImpl, ExprRewriter, InputRefDisplay};
+use crate::optimizer::property::{Order, RequiredDist};
+use crate::utils::{ColIndexMapping, Condition};
+
+/// A pattern variable of `MATCH_RECOGNIZE`, with its quantifier in `PATTERN` and the condition on
+/// the rows it accepts in `DEFINE`.
+#[derive(Debug, Clone)]
+pub struct PatternTerm {
+    pub symbol: String,
+    pub quantifier: PatternQuantifier,
+    pub condition: ExprImpl,
+}
+
+impl fmt::Display for PatternTerm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let quantifier = match self.quantifier {
+            PatternQuantifier::One => "",
+            PatternQuantifier::ZeroOrMore => "*",
+            PatternQuantifier::OneOrMore => "+",
+            PatternQuantifier::ZeroOrOne => "?",
+        };
+        write!(f, "{}{}: {:?}", self.symbol, quantifier, self.condition)
+    }
+}
+
+/// A measure of `MATCH_RECOGNIZE`, i.e. the value of `column` in the first or the last row of a
+/// match accepted by the pattern term `term`.
+#[derive(Debug, Clone)]
+pub struct Measure {
+    pub kind: MeasureKind,
+    pub term: usize,
+    pub column: usize,
+    pub name: String,
+}
+
+/// `LogicalMatchRecognize` finds the rows matching `pattern` in each partition of its input, and
+/// outputs the partition keys, the measures and the sequence number of each match, which makes up
+/// the primary key together with the partition keys.
+///
+/// The rows are matched in the order they arrive, which `order_by` is expected to follow.
+#[derive(Debug, Clone)]
+pub struct LogicalMatchRecognize {
+    pub base: PlanBase,
+    input: PlanRef,
+    partition_by: Vec<usize>,
+    order_by: Order,
+    pattern: Vec<PatternTerm>,
+    measures: Vec<Measure>,
+}
+
+impl LogicalMatchRecognize {
+    pub fn new(
+        input: PlanRef,
+        partition_by: Vec<usize>,
+        order_by: Order,
+        pattern: Vec<PatternTerm>,
+        measures: Vec<Measure>,
+    ) -> Self {
+        let ctx = input.ctx();
+        let input_schema = input.schema();
+        let schema: Schema = partition_by
+            .iter()
+            .map(|&idx| input_schema[idx].clone())
+            .chain(measures.iter().map(|measure| {
+                Field::with_name(
+                    input_schema[measure.column].data_type(),
+                    measure.name.clone(),
+                )
+            }))
+            .chain(std::iter::once(Field::with_name(
+                DataType::Int64,
+                "_match_number",
+            )))
+            .collect();
+        let pk_indices = (0..partition_by.len())
+            .chain(std::iter::once(schema.len() - 1))
+            .collect();
+        let base = PlanBase::new_logical(ctx, schema, pk_indices);
+        Self {
+            base,
+            input,
+            partition_by,
+            order_by,
+            pattern,
+            measures,
+        }
+    }
+
+    pub fn create(
+        input: PlanRef,
+        partition_by: Vec<usize>,
+        order_by: Order,
+        pattern: Vec<PatternTerm>,
+        measures: Vec<Measure>,
+    ) -> PlanRef {
+        Self::new(input, partition_by, order_by, pattern, measures).into()
+    }
+
+    pub fn partition_by(&self) -> &[usize] {
+        &self.partition_by
+    }
+
+    pub fn pattern(&self) -> &[PatternTerm] {
+        &self.pattern
+    }
+
+    pub fn measures(&self) -> &[Measure] {
+        &self.measures
+    }
+
+    /// Maps the partition keys of the input to the output.
+    pub fn i2o_col_mapping(&self) -> ColIndexMapping {
+        let mut map = vec![None; self.input.schema().len()];
+        for (out_idx, &in_idx) in self.partition_by.iter().enumerate() {
+            map[in_idx] = Some(out_idx);
+        }
+        ColIndexMapping::with_target_size(map, self.schema().len())
+    }
+
+    pub fn fmt_with_name(&self, f: &mut fmt::Formatter, name: &str) -> fmt::Result {
+        let measures = self
+            .measures
+            .iter()
+            .map(|measure| {
+                format!(
+                    "{}({}.{})",
+                    match measure.kind {
+                        MeasureKind::First => "FIRST",
+                        MeasureKind::Last => "LAST",
+                    },
+                    self.pattern[measure.term].symbol,
+                    InputRefDisplay(measure.column)
+                )
+            })
+            .collect_vec();
+        f.debug_struct(name)
+            .field(
+                "partition_by",
+                &self
+                    .partition_by
+                    .iter()
+                    .copied()
+                    .map(InputRefDisplay)
+                    .collect_vec(),
+            )
+            .field("order_by", &format_args!("{}", self.order_by))
+            .field(
+                "pattern",
+                &format_args!("[{}]", self.pattern.iter().format(", ")),
+            )
+            .field("measures", &measures)
+            .finish()
+    }
+}
+
+impl PlanTreeNodeUnary for LogicalMatchRecognize {
+    fn input(&self) -> PlanRef {
+        self.input.clone()
+    }
+
+    fn clone_with_input(&self, input: PlanRef) -> Self {
+        Self::new(
+            input,
+            self.partition_by.clone(),
+            self.order_by.clone(),
+            self.pattern.clone(),
+            self.measures.clone(),
+        )
+    }
+
+    #[must_use]
+    fn rewrite_with_input(
+        &self,
+        input: PlanRef,
+        mut input_col_change: ColIndexMapping,
+    ) -> (Self, ColIndexMapping) {
+        let partition_by = self
+            .partition_by
+            .iter()
+            .map(|&idx| input_col_change.map(idx))
+            .collect();
+        let order_by = input_col_change
+            .rewrite_required_order(&self.order_by)
+            .unwrap();
+        let pattern = self
+            .pattern
+            .iter()
+            .map(|term| PatternTerm {
+                condition: input_col_change.rewrite_expr(term.condition.clone()),
+                ..term.clone()
+            })
+            .collect();
+        let measures = self
+            .measures
+            .iter()
+            .map(|measure| Measure {
+                column: input_col_change.map(measure.column),
+                ..measure.clone()
+            })
+            .collect();
+        let new = Self::new(input, partition_by, order_by, pattern, measures);
+        // The output columns don't depend on the positions of the input columns.
+        let out_col_change = ColIndexMapping::identity(new.schema().len());
+        (new, out_col_change)
+    }
+}
+
+impl_plan_tree_node_for_unary! { LogicalMatchRecognize }
+
+impl fmt::Display for LogicalMatchRecognize {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_with_name(f, "LogicalMatchRecognize")
+    }
+}
+
+impl ColPrunable for LogicalMatchRecognize {
+    fn prune_col(&self, required_cols: &[usize]) -> PlanRef {
+        let input_col_num = self.input.schema().len();
+        let input_required_cols = {
+            let mut tmp = FixedBitSet::with_capacity(input_col_num);
+            self.pattern
+                .iter()
+                .for_each(|term| tmp.union_with(&term.condition.collect_input_refs(input_col_num)));
+            tmp.extend(self.partition_by.iter().copied());
+            tmp.extend(self.order_by.field_order.iter().map(|fo| fo.index));
+            tmp.extend(self.measures.iter().map(|measure| measure.column));
+            tmp.ones().collect_vec()
+        };
+        let mapping = ColIndexMapping::with_remaining_columns(&input_required_cols, input_col_num);
+        let new_input = self.input.prune_col(&input_required_cols);
+        let (new, _) = self.rewrite_with_input(new_input, mapping);
+
+        if required_cols.iter().copied().eq(0..self.schema().len()) {
+            new.into()
+        } else {
+            let src_size = new.schema().len();
+            LogicalProject::with_mapping(
+                new.into(),
+                ColIndexMapping::with_remaining_columns(required_cols, src_size),
+            )
+            .into()
+        }
+    }
+}
+
+impl PredicatePushdown for LogicalMatchRecognize {
+    fn predicate_pushdown(&self, predicate: Condition) -> PlanRef {
+        gen_filter_and_pushdown(self, predicate, Condition::true_cond())
+    }
+}
+
+impl ToBatch for LogicalMatchRecognize {
+    fn to_batch(&self) -> Result<PlanRef> {
+        Err(
+            ErrorCode::NotImplemented("MATCH_RECOGNIZE in batch queries".to_string(), None.into())
+                .into(),
+        )
+    }
+}
+
+impl ToStream for LogicalMatchRecognize {
+    fn to_stream(&self) -> Result<PlanRef> {
+        let required_dist = if self.partition_by.is_empty() {
+            RequiredDist::single()
+        } else {
+            RequiredDist::shard_by_key(self.input.schema().len(), &self.partition_by)
+        };
+        let input = self.input().to_stream_with_dist_required(&required_dist)?;
+        // A row can't be taken back from the matches it completed.
+        if !input.append_only() {
+            return Err(ErrorCode::NotImplemented(
+                "MATCH_RECOGNIZE on a non-append-only input".to_string(),
+                None.into(),
+            )
+            .into());
+        }
+        Ok(StreamMatchRecognize::new(self.clone_with_input(input)).into())
+    }
+
+    fn logical_rewrite_for_stream(&self) -> Result<(PlanRef, ColIndexMapping)> {
+        let (input, input_col_change) = self.input.logical_rewrite_for_stream()?;
+        let (match_recognize, out_col_change) = self.rewrite_with_input(input, input_col_change);
+        Ok((match_recognize.into(), out_col_change))
+    }
+}
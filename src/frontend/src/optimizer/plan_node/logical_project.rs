@@ -18,7 +18,7 @@ use std::string::String;
 use fixedbitset::FixedBitSet;
 use itertools::Itertools;
 use risingwave_common::catalog::{Field, Schema};
-use risingwave_common::error::Result;
+use risingwave_common::error::{ErrorCode, Result};
 
 use super::{
     gen_filter_and_pushdown, BatchProject, ColPrunable, PlanBase, PlanRef, PlanTreeNodeUnary,
@@ -272,6 +272,13 @@ impl ToBatch for LogicalProject {
 
 impl ToStream for LogicalProject {
     fn to_stream_with_dist_required(&self, required_dist: &RequiredDist) -> Result<PlanRef> {
+        if self.exprs.iter().any(|expr| expr.has_now()) {
+            return Err(ErrorCode::NotImplemented(
+                "NOW() in the select list of a streaming job".to_string(),
+                None.into(),
+            )
+            .into());
+        }
         let input_required = if required_dist.satisfies(&RequiredDist::AnyShard) {
             RequiredDist::Any
         } else {
@@ -18,19 +18,38 @@ use std::rc::Rc;
 
 use fixedbitset::FixedBitSet;
 use itertools::Itertools;
-use risingwave_common::catalog::{ColumnDesc, Schema, TableDesc};
+use risingwave_common::catalog::{
+    ColumnDesc, Schema, TableDesc, IVF_LIST_COLUMN_NAME, LEXEME_COLUMN_NAME,
+};
 use risingwave_common::error::{ErrorCode, Result, RwError};
+use risingwave_common::types::{DataType, ScalarImpl};
+use risingwave_common::util::sort_util::OrderType;
+use risingwave_expr::vector_op::distance::ivf_nearest_lists;
+use risingwave_expr::vector_op::fulltext::TsQuery;
 
 use super::{
     BatchFilter, BatchProject, ColPrunable, PlanBase, PlanRef, PredicatePushdown, StreamTableScan,
     ToBatch, ToStream,
 };
+use crate::binder::BoundTableSample;
 use crate::catalog::ColumnId;
-use crate::expr::{CollectInputRef, ExprImpl, InputRef};
+use crate::expr::{CollectInputRef, ExprImpl, ExprType, FunctionCall, InputRef, Literal};
 use crate::optimizer::plan_node::{BatchSeqScan, LogicalFilter, LogicalProject};
+use crate::optimizer::property::{Direction, FieldOrder, Order};
 use crate::session::OptimizerContextRef;
 use crate::utils::{ColIndexMapping, Condition, ScanRange};
 
+/// An index on a column of a table for some kind of predicates or orders of the column, e.g. a
+/// full-text index has a row for each lexeme of the column of each row of the table, ordered by
+/// the lexeme.
+#[derive(Debug, Clone)]
+pub struct ColumnIndex {
+    pub name: String,
+    /// The index of the indexed column in the table.
+    pub column_idx: usize,
+    pub index: Rc<TableDesc>,
+}
+
 /// `LogicalScan` returns contents of a table or other equivalent object
 #[derive(Debug, Clone)]
 pub struct LogicalScan {
@@ -44,6 +63,13 @@ pub struct LogicalScan {
     table_desc: Rc<TableDesc>,
     // Descriptors of all indexes on this table
     indexes: Vec<(String, Rc<TableDesc>)>,
+    /// Full-text indexes on this table, used for full-text match predicates in batch queries.
+    fulltext_indexes: Vec<ColumnIndex>,
+    /// IVF indexes on vector columns of this table, used for nearest neighbor searches in batch
+    /// queries.
+    ivf_indexes: Vec<ColumnIndex>,
+    /// Only read a sample of the table in batch queries.
+    sample: Option<BoundTableSample>,
     /// The pushed down predicates. It refers to column indexes of the table.
     predicate: Condition,
 }
@@ -56,6 +82,9 @@ impl LogicalScan {
         output_col_idx: Vec<usize>, // the column index in the table
         table_desc: Rc<TableDesc>,
         indexes: Vec<(String, Rc<TableDesc>)>,
+        fulltext_indexes: Vec<ColumnIndex>,
+        ivf_indexes: Vec<ColumnIndex>,
+        sample: Option<BoundTableSample>,
         ctx: OptimizerContextRef,
         predicate: Condition, // refers to column indexes of the table
     ) -> Self {
@@ -108,6 +137,9 @@ impl LogicalScan {
             output_col_idx,
             table_desc,
             indexes,
+            fulltext_indexes,
+            ivf_indexes,
+            sample,
             predicate,
         }
     }
@@ -126,11 +158,36 @@ impl LogicalScan {
             (0..table_desc.columns.len()).into_iter().collect(),
             table_desc,
             indexes,
+            vec![],
+            vec![],
+            None,
             ctx,
             Condition::true_cond(),
         )
     }
 
+    /// Sets the full-text indexes on this table.
+    pub fn with_fulltext_indexes(mut self, fulltext_indexes: Vec<ColumnIndex>) -> Self {
+        self.fulltext_indexes = fulltext_indexes;
+        self
+    }
+
+    /// Sets the IVF indexes on this table.
+    pub fn with_ivf_indexes(mut self, ivf_indexes: Vec<ColumnIndex>) -> Self {
+        self.ivf_indexes = ivf_indexes;
+        self
+    }
+
+    /// Sets the `TABLESAMPLE` of this scan.
+    pub fn with_sample(mut self, sample: Option<BoundTableSample>) -> Self {
+        self.sample = sample;
+        self
+    }
+
+    pub fn sample(&self) -> Option<&BoundTableSample> {
+        self.sample.as_ref()
+    }
+
     pub(super) fn column_names(&self) -> Vec<String> {
         self.output_col_idx
             .iter()
@@ -159,6 +216,11 @@ impl LogicalScan {
         self.table_desc.as_ref()
     }
 
+    /// Get the indices of the output columns in the table.
+    pub fn output_col_idx(&self) -> &[usize] {
+        &self.output_col_idx
+    }
+
     /// Get the descs of the output columns.
     pub fn column_descs(&self) -> Vec<ColumnDesc> {
         self.output_col_idx
@@ -175,6 +237,71 @@ impl LogicalScan {
             .collect()
     }
 
+    /// The order in which the rows are stored, i.e. the order of the primary key, in terms of the
+    /// output columns. Only the longest prefix of the primary key present in the output is kept.
+    pub fn storage_order(&self) -> Order {
+        let id_to_op_idx = self
+            .output_col_idx
+            .iter()
+            .enumerate()
+            .map(|(op_idx, tb_idx)| (self.table_desc.columns[*tb_idx].column_id, op_idx))
+            .collect::<HashMap<_, _>>();
+        let field_order = self
+            .table_desc
+            .order_desc
+            .iter()
+            .map_while(|desc| {
+                let index = *id_to_op_idx.get(&desc.column_desc.column_id)?;
+                let direct = match desc.order {
+                    OrderType::Ascending => Direction::Asc,
+                    OrderType::Descending => Direction::Desc,
+                };
+                Some(FieldOrder { index, direct })
+            })
+            .collect();
+        Order::new(field_order)
+    }
+
+    /// Try to convert to a batch scan which yields rows in `required_order` by reading the table
+    /// along its primary key, forward or backward, so that no sort is needed on top of it.
+    /// Returns `None` if the primary key cannot provide the order.
+    pub fn to_batch_with_scan_order(&self, required_order: &Order) -> Option<PlanRef> {
+        if self.is_sys_table || required_order.is_any() {
+            return None;
+        }
+        let storage_order = self.storage_order();
+        let reverse = if storage_order.satisfies(required_order) {
+            false
+        } else if storage_order.reverse().satisfies(required_order) {
+            true
+        } else {
+            return None;
+        };
+
+        if self.predicate.always_true() {
+            return Some(
+                BatchSeqScan::new_with_order(self.clone(), ScanRange::full_table_scan(), reverse)
+                    .into(),
+            );
+        }
+        let (scan_range, predicate) = self.predicate.clone().split_to_scan_range(
+            &self.table_desc.order_column_ids(),
+            self.table_desc.columns.len(),
+        );
+        let mut scan = self.clone();
+        scan.predicate = predicate;
+        let (scan, predicate, project_expr) = scan.predicate_pull_up();
+        // The projection would change the column indices the order refers to.
+        if project_expr.is_some() {
+            return None;
+        }
+        let mut plan: PlanRef = BatchSeqScan::new_with_order(scan, scan_range, reverse).into();
+        if !predicate.always_true() {
+            plan = BatchFilter::new(LogicalFilter::new(plan, predicate)).into();
+        }
+        Some(plan)
+    }
+
     /// Get all indexes on this table
     pub fn indexes(&self) -> &[(String, Rc<TableDesc>)] {
         &self.indexes
@@ -217,11 +344,108 @@ impl LogicalScan {
             new_required_col_idx,
             index.clone(),
             vec![],
+            vec![],
+            vec![],
+            self.sample.clone(),
             self.ctx(),
             self.predicate.clone(),
         )
     }
 
+    /// Converts to a scan on the index, which keeps the output columns and the predicate, and only
+    /// reads the rows whose `key_column` of the index is one of `keys`.
+    fn to_index_scan_on_keys(
+        &self,
+        index: &ColumnIndex,
+        key_column: &str,
+        keys: Vec<ScalarImpl>,
+        key_type: DataType,
+    ) -> Option<LogicalScan> {
+        let key_idx = index
+            .index
+            .columns
+            .iter()
+            .position(|c| c.name == key_column)?;
+        let id_to_index_idx = index
+            .index
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(idx, desc)| (desc.column_id, idx))
+            .collect::<HashMap<_, _>>();
+        let output_col_idx = self
+            .output_col_idx
+            .iter()
+            .map(|i| id_to_index_idx[&self.table_desc.columns[*i].column_id])
+            .collect();
+        let func_type = match keys.len() {
+            1 => ExprType::Equal,
+            _ => ExprType::In,
+        };
+        let inputs = std::iter::once(InputRef::new(key_idx, key_type.clone()).into())
+            .chain(
+                keys.into_iter()
+                    .map(|key| Literal::new(Some(key), key_type.clone()).into()),
+            )
+            .collect();
+        let key_in = FunctionCall::new(func_type, inputs).unwrap();
+        // The predicate refers to the same columns in the index as in the table.
+        let predicate = self
+            .predicate
+            .clone()
+            .and(Condition::with_expr(key_in.into()));
+        Some(Self::new(
+            index.name.clone(),
+            false,
+            output_col_idx,
+            index.index.clone(),
+            vec![],
+            vec![],
+            vec![],
+            self.sample.clone(),
+            self.ctx(),
+            predicate,
+        ))
+    }
+
+    /// Try to convert to a scan on a full-text index, if a conjunction of the predicate is a
+    /// full-text match on a column with a full-text index. The index scan only reads the rows of
+    /// a lexeme required by the query, and keeps the whole predicate to filter them.
+    fn to_fulltext_index_scan(&self) -> Option<LogicalScan> {
+        self.predicate.conjunctions.iter().find_map(|expr| {
+            let (column, query) = expr.as_ts_match_const()?;
+            let index = self
+                .fulltext_indexes
+                .iter()
+                .find(|index| index.column_idx == column.index())?;
+            let query = TsQuery::parse(&query).ok()?;
+            let lexeme = query.required_terms().first()?.to_string();
+            self.to_index_scan_on_keys(
+                index,
+                LEXEME_COLUMN_NAME,
+                vec![ScalarImpl::Utf8(lexeme)],
+                DataType::Varchar,
+            )
+        })
+    }
+
+    /// Try to convert to a scan on an IVF index to find the nearest vectors, if `distance` is a
+    /// distance between a column with an IVF index and a constant vector. The index scan only
+    /// reads the `probes` lists nearest to the constant vector, so the search is approximate.
+    pub fn to_ivf_index_scan(&self, distance: &ExprImpl, probes: usize) -> Option<LogicalScan> {
+        let (column, vector) = distance.as_vector_distance_const()?;
+        let column_idx = self.output_col_idx[column.index()];
+        let index = self
+            .ivf_indexes
+            .iter()
+            .find(|index| index.column_idx == column_idx)?;
+        let lists = ivf_nearest_lists(&vector, probes)
+            .into_iter()
+            .map(ScalarImpl::Int32)
+            .collect();
+        self.to_index_scan_on_keys(index, IVF_LIST_COLUMN_NAME, lists, DataType::Int32)
+    }
+
     /// a vec of `InputRef` corresponding to `output_col_idx`, which can represent a pulled project.
     fn output_idx_to_input_ref(&self) -> Vec<ExprImpl> {
         let output_idx = self
@@ -253,6 +477,9 @@ impl LogicalScan {
             self.required_col_idx.clone(),
             self.table_desc.clone(),
             self.indexes.clone(),
+            self.fulltext_indexes.clone(),
+            self.ivf_indexes.clone(),
+            self.sample.clone(),
             self.ctx(),
             Condition::true_cond(),
         );
@@ -271,6 +498,9 @@ impl LogicalScan {
             self.required_col_idx.clone(),
             self.table_desc.clone(),
             self.indexes.clone(),
+            self.fulltext_indexes.clone(),
+            self.ivf_indexes.clone(),
+            self.sample.clone(),
             self.base.ctx.clone(),
             predicate,
         )
@@ -283,6 +513,9 @@ impl LogicalScan {
             output_col_idx,
             self.table_desc.clone(),
             self.indexes.clone(),
+            self.fulltext_indexes.clone(),
+            self.ivf_indexes.clone(),
+            self.sample.clone(),
             self.base.ctx.clone(),
             self.predicate.clone(),
         )
@@ -293,12 +526,17 @@ impl_plan_tree_node_for_leaf! {LogicalScan}
 
 impl fmt::Display for LogicalScan {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let sample_str = match &self.sample {
+            Some(sample) => format!(", sample: {}", sample),
+            None => "".to_string(),
+        };
         if self.predicate.always_true() {
             write!(
                 f,
-                "LogicalScan {{ table: {}, columns: [{}] }}",
+                "LogicalScan {{ table: {}, columns: [{}]{} }}",
                 self.table_name,
                 self.column_names().join(", "),
+                sample_str,
             )
         } else {
             let required_col_names = self
@@ -309,11 +547,12 @@ impl fmt::Display for LogicalScan {
 
             write!(
                 f,
-                "LogicalScan {{ table: {}, output_columns: [{}], required_columns: [{}], predicate: {} }}",
+                "LogicalScan {{ table: {}, output_columns: [{}], required_columns: [{}], predicate: {}{} }}",
                 self.table_name,
                 self.column_names().join(", "),
                 required_col_names.join(", "),
                 self.predicate,
+                sample_str,
             )
         }
     }
@@ -346,6 +585,9 @@ impl PredicatePushdown for LogicalScan {
 
 impl ToBatch for LogicalScan {
     fn to_batch(&self) -> Result<PlanRef> {
+        if let Some(index_scan) = self.to_fulltext_index_scan() {
+            return index_scan.to_batch();
+        }
         if self.predicate.always_true() {
             Ok(BatchSeqScan::new(self.clone(), ScanRange::full_table_scan()).into())
         } else {
@@ -368,6 +610,18 @@ impl ToBatch for LogicalScan {
             Ok(plan)
         }
     }
+
+    fn to_batch_with_order_required(&self, required_order: &Order) -> Result<PlanRef> {
+        // Rows are read in the order of the primary key, e.g. the `ORDER BY` of a materialized
+        // view, without sorting them if it provides the required order.
+        if self.to_fulltext_index_scan().is_none()
+            && let Some(plan) = self.to_batch_with_scan_order(required_order)
+        {
+            return Ok(plan);
+        }
+        let ret = self.to_batch()?;
+        required_order.enforce_if_not_satisfies(ret)
+    }
 }
 
 impl ToStream for LogicalScan {
@@ -378,6 +632,12 @@ impl ToStream for LogicalScan {
                 None.into(),
             )));
         }
+        if self.sample.is_some() {
+            return Err(RwError::from(ErrorCode::NotImplemented(
+                "TABLESAMPLE in streaming queries".to_string(),
+                None.into(),
+            )));
+        }
         if self.predicate.always_true() {
             Ok(StreamTableScan::new(self.clone()).into())
         } else {
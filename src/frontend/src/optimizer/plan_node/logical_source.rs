@@ -16,14 +16,17 @@ use std::collections::HashMap;
 use std::fmt;
 use std::rc::Rc;
 
+use itertools::Itertools;
 use risingwave_common::catalog::Schema;
 use risingwave_common::error::{ErrorCode, Result, RwError};
+use risingwave_common::types::ScalarImpl;
 
 use super::{
-    ColPrunable, LogicalFilter, LogicalProject, PlanBase, PlanRef, PredicatePushdown, StreamSource,
-    ToBatch, ToStream,
+    BatchExternalScan, ColPrunable, LogicalFilter, LogicalProject, PlanBase, PlanRef,
+    PredicatePushdown, StreamSource, ToBatch, ToStream,
 };
 use crate::catalog::source_catalog::SourceCatalog;
+use crate::expr::{ExprImpl, ExprType};
 use crate::session::OptimizerContextRef;
 use crate::utils::{ColIndexMapping, Condition};
 
@@ -32,6 +35,9 @@ use crate::utils::{ColIndexMapping, Condition};
 pub struct LogicalSource {
     pub base: PlanBase,
     pub source_catalog: Rc<SourceCatalog>,
+    /// The values partition columns of an external table are restricted to by the pushed down
+    /// predicates, as `(column index, values)`. Files of other partitions need not be read.
+    partition_filters: Vec<(usize, Vec<String>)>,
 }
 
 impl LogicalSource {
@@ -57,9 +63,49 @@ impl LogicalSource {
         LogicalSource {
             base,
             source_catalog,
+            partition_filters: vec![],
         }
     }
 
+    pub fn partition_filters(&self) -> &[(usize, Vec<String>)] {
+        &self.partition_filters
+    }
+
+    pub(super) fn partition_filters_display(&self) -> Vec<String> {
+        self.partition_filters
+            .iter()
+            .map(|(idx, values)| {
+                format!(
+                    "{} IN ({})",
+                    self.schema().fields()[*idx].name,
+                    values.iter().map(|value| format!("'{}'", value)).join(", ")
+                )
+            })
+            .collect()
+    }
+
+    /// Records the conditions of `predicate` that restrict a partition column of the external
+    /// table to constant values.
+    fn with_partition_filters(&self, predicate: &Condition) -> Self {
+        let mut source = self.clone();
+        let Some(external) = &self.source_catalog.external else {
+            return source;
+        };
+        for expr in &predicate.conjunctions {
+            let Some((idx, values)) = as_partition_filter(expr) else {
+                continue;
+            };
+            let is_partition_column = external
+                .partition_columns
+                .contains(&self.schema().fields()[idx].name);
+            let filter = (idx, values);
+            if is_partition_column && !source.partition_filters.contains(&filter) {
+                source.partition_filters.push(filter);
+            }
+        }
+        source
+    }
+
     pub(super) fn column_names(&self) -> Vec<String> {
         self.schema()
             .fields()
@@ -75,14 +121,51 @@ impl LogicalSource {
 
 impl_plan_tree_node_for_leaf! {LogicalSource}
 
+/// Matches `column = 'value'` and `column IN ('value', ...)` on a varchar column.
+fn as_partition_filter(expr: &ExprImpl) -> Option<(usize, Vec<String>)> {
+    let ExprImpl::FunctionCall(call) = expr else {
+        return None;
+    };
+    let (column, literals) = match call.get_expr_type() {
+        ExprType::Equal => {
+            let (column, literal) = expr.as_eq_const()?;
+            (column, vec![ExprImpl::from(literal)])
+        }
+        ExprType::In => match call.inputs().split_first()? {
+            (ExprImpl::InputRef(column), literals) => (*column.clone(), literals.to_vec()),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    let values = literals
+        .iter()
+        .map(|literal| match literal {
+            ExprImpl::Literal(literal) => match literal.get_data() {
+                Some(ScalarImpl::Utf8(value)) => Some(value.clone()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some((column.index(), values))
+}
+
 impl fmt::Display for LogicalSource {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "LogicalSource {{ source: {}, columns: [{}] }}",
+            "LogicalSource {{ source: {}, columns: [{}]",
             self.source_catalog.name,
             self.column_names().join(", ")
-        )
+        )?;
+        if !self.partition_filters.is_empty() {
+            write!(
+                f,
+                ", partition_filters: [{}]",
+                self.partition_filters_display().join(", ")
+            )?;
+        }
+        write!(f, " }}")
     }
 }
 
@@ -95,12 +178,16 @@ impl ColPrunable for LogicalSource {
 
 impl PredicatePushdown for LogicalSource {
     fn predicate_pushdown(&self, predicate: Condition) -> PlanRef {
-        LogicalFilter::create(self.clone().into(), predicate)
+        // The filter is kept even when it prunes partitions, since pruning is only per file.
+        LogicalFilter::create(self.with_partition_filters(&predicate).into(), predicate)
     }
 }
 
 impl ToBatch for LogicalSource {
     fn to_batch(&self) -> Result<PlanRef> {
+        if self.source_catalog.external.is_some() {
+            return Ok(BatchExternalScan::new(self.clone()).into());
+        }
         Err(RwError::from(ErrorCode::NotImplemented(
             "there is no batch source operator".to_string(),
             None.into(),
@@ -110,6 +197,13 @@ impl ToBatch for LogicalSource {
 
 impl ToStream for LogicalSource {
     fn to_stream(&self) -> Result<PlanRef> {
+        if self.source_catalog.external.is_some() {
+            return Err(ErrorCode::NotImplemented(
+                "streaming queries on external tables".to_string(),
+                None.into(),
+            )
+            .into());
+        }
         Ok(StreamSource::new(self.clone()).into())
     }
 
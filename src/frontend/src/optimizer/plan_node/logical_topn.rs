@@ -22,8 +22,11 @@ use super::{
     gen_filter_and_pushdown, ColPrunable, PlanBase, PlanRef, PlanTreeNodeUnary, PredicatePushdown,
     ToBatch, ToStream,
 };
-use crate::optimizer::plan_node::{BatchTopN, LogicalProject, StreamTopN};
-use crate::optimizer::property::{FieldOrder, Order, RequiredDist};
+use crate::optimizer::plan_node::{
+    BatchLimit, BatchTopN, LogicalLimit, LogicalProject, StreamTopN,
+};
+use crate::optimizer::property::{Direction, FieldOrder, Order, RequiredDist};
+use crate::planner::LIMIT_ALL_COUNT;
 use crate::utils::{ColIndexMapping, Condition};
 
 /// `LogicalTopN` sorts the input data and fetches up to `limit` rows from `offset`
@@ -174,6 +177,42 @@ impl ToBatch for LogicalTopN {
     }
 
     fn to_batch_with_order_required(&self, required_order: &Order) -> Result<PlanRef> {
+        // If the primary key of the scanned table provides the order, read the table along it
+        // (backward if needed) and stop after the first rows, instead of sorting the whole input.
+        if let Some(scan) = self.input().as_logical_scan()
+            && let Some(mut input) = scan.to_batch_with_scan_order(self.topn_order())
+        {
+            // The offset rows are skipped after being read, but no more rows are needed if all
+            // rows read are returned by the scan.
+            let limit = self.limit().saturating_add(self.offset());
+            if limit > 0
+                && limit < LIMIT_ALL_COUNT
+                && let Some(seq_scan) = input.as_batch_seq_scan()
+                && seq_scan.logical().sample().is_none()
+            {
+                input = seq_scan.clone_with_limit(limit as u64).into();
+            }
+            let ret = BatchLimit::new(LogicalLimit::new(input, self.limit(), self.offset())).into();
+            return required_order.enforce_if_not_satisfies(ret);
+        }
+
+        // The nearest vectors of a constant vector can be searched approximately in the nearest
+        // lists of an IVF index, if enabled by the session.
+        let ivf_probes = self.ctx().inner().session_ctx.ivf_probes();
+        if ivf_probes > 0
+            && let Some(project) = self.input().as_logical_project()
+            && let Some(scan) = project.input().as_logical_scan()
+            && let Some(first) = self.topn_order().field_order.first()
+            && first.direct == Direction::Asc
+            && let Some(index_scan) =
+                scan.to_ivf_index_scan(&project.exprs()[first.index], ivf_probes)
+        {
+            let project = project.clone_with_input(index_scan.into());
+            return self
+                .clone_with_input(project.into())
+                .to_batch_with_order_required(required_order);
+        }
+
         let new_input = self.input().to_batch()?;
         let new_logical = self.clone_with_input(new_input);
         let ret = BatchTopN::new(new_logical).into();
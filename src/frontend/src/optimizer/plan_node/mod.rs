@@ -38,6 +38,7 @@ use risingwave_common::catalog::Schema;
 use risingwave_common::error::{ErrorCode, Result};
 use risingwave_pb::batch_plan::PlanNode as BatchPlanProst;
 use risingwave_pb::stream_plan::StreamNode as StreamPlanProst;
+use serde::Serialize;
 
 use super::property::{Distribution, Order};
 
@@ -70,6 +71,20 @@ pub type PlanRef = Rc<dyn PlanNode>;
 #[derive(Clone, Debug, Copy)]
 pub struct PlanNodeId(pub i32);
 
+/// A plan node in the structured output of `EXPLAIN`, with its properties annotated.
+#[derive(Debug, Serialize)]
+pub struct ExplainNode {
+    pub id: usize,
+    pub name: String,
+    pub detail: String,
+    pub schema: Vec<String>,
+    pub pk_indices: Vec<usize>,
+    pub order: String,
+    pub distribution: String,
+    pub append_only: bool,
+    pub children: Vec<ExplainNode>,
+}
+
 #[derive(Debug, PartialEq)]
 pub enum Convention {
     Logical,
@@ -95,6 +110,67 @@ impl dyn PlanNode {
         Ok(output)
     }
 
+    /// Explain the whole plan tree into a structured form, which can be serialized to JSON or
+    /// YAML. Nodes are numbered in pre-order, so the ids only depend on the shape of the plan.
+    pub fn explain_to_node(&self) -> ExplainNode {
+        fn build(plan: &dyn PlanNode, next_id: &mut usize) -> ExplainNode {
+            let id = *next_id;
+            *next_id += 1;
+            ExplainNode {
+                id,
+                name: format!("{:?}", plan.node_type()),
+                detail: plan.to_string(),
+                schema: plan.schema().names(),
+                pk_indices: plan.pk_indices().to_vec(),
+                order: plan.order().to_string(),
+                distribution: format!("{:?}", plan.distribution()),
+                append_only: plan.append_only(),
+                children: plan
+                    .inputs()
+                    .iter()
+                    .map(|input| build(&**input, next_id))
+                    .collect(),
+            }
+        }
+        build(self, &mut 0)
+    }
+
+    /// Explain the plan node and return a JSON string.
+    pub fn explain_to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(&self.explain_to_node())
+            .map_err(|e| ErrorCode::InternalError(format!("failed to explain: {}", e)).into())
+    }
+
+    /// Explain the plan node and return a YAML string.
+    pub fn explain_to_yaml(&self) -> Result<String> {
+        serde_yaml::to_string(&self.explain_to_node())
+            .map_err(|e| ErrorCode::InternalError(format!("failed to explain: {}", e)).into())
+    }
+
+    /// Explain the plan node and return a graph in the DOT language of Graphviz.
+    pub fn explain_to_dot(&self) -> Result<String> {
+        fn write_node(node: &ExplainNode, output: &mut String) {
+            let label = format!(
+                "{}\\ndistribution: {}\\norder: {}",
+                node.detail, node.distribution, node.order
+            );
+            output.push_str(&format!(
+                "  {} [label=\"{}\"];\n",
+                node.id,
+                label.replace('"', "\\\"")
+            ));
+            for child in &node.children {
+                output.push_str(&format!("  {} -> {};\n", node.id, child.id));
+                write_node(child, output);
+            }
+        }
+
+        let mut output = "digraph plan {\n  node [shape=box];\n".to_string();
+        write_node(&self.explain_to_node(), &mut output);
+        output.push_str("}\n");
+        Ok(output)
+    }
+
     pub fn id(&self) -> PlanNodeId {
         self.plan_base().id
     }
@@ -205,9 +281,12 @@ pub use to_prost::*;
 mod predicate_pushdown;
 pub use predicate_pushdown::*;
 
+mod batch_asof_join;
 mod batch_delete;
 mod batch_exchange;
+mod batch_external_scan;
 mod batch_filter;
+mod batch_gap_fill;
 mod batch_hash_agg;
 mod batch_hash_join;
 mod batch_hop_window;
@@ -220,16 +299,20 @@ mod batch_simple_agg;
 mod batch_sort;
 mod batch_table_function;
 mod batch_topn;
+mod batch_unload;
 mod batch_update;
 mod batch_values;
 mod logical_agg;
 mod logical_apply;
+mod logical_asof_join;
 mod logical_delete;
 mod logical_filter;
+mod logical_gap_fill;
 mod logical_hop_window;
 mod logical_insert;
 mod logical_join;
 mod logical_limit;
+mod logical_match_recognize;
 mod logical_multi_join;
 mod logical_project;
 mod logical_scan;
@@ -238,23 +321,33 @@ mod logical_table_function;
 mod logical_topn;
 mod logical_update;
 mod logical_values;
+mod stream_asof_join;
 mod stream_delta_join;
+mod stream_dynamic_filter;
 mod stream_exchange;
 mod stream_filter;
+mod stream_gap_fill;
 mod stream_hash_agg;
 mod stream_hash_join;
 mod stream_hop_window;
 mod stream_index_scan;
+mod stream_local_agg;
+mod stream_match_recognize;
 mod stream_materialize;
+mod stream_now;
 mod stream_project;
 mod stream_simple_agg;
 mod stream_source;
 mod stream_table_scan;
+mod stream_tokenize;
 mod stream_topn;
 
+pub use batch_asof_join::BatchAsOfJoin;
 pub use batch_delete::BatchDelete;
 pub use batch_exchange::BatchExchange;
+pub use batch_external_scan::BatchExternalScan;
 pub use batch_filter::BatchFilter;
+pub use batch_gap_fill::BatchGapFill;
 pub use batch_hash_agg::BatchHashAgg;
 pub use batch_hash_join::BatchHashJoin;
 pub use batch_hop_window::BatchHopWindow;
@@ -267,36 +360,49 @@ pub use batch_simple_agg::BatchSimpleAgg;
 pub use batch_sort::BatchSort;
 pub use batch_table_function::BatchTableFunction;
 pub use batch_topn::BatchTopN;
+pub use batch_unload::BatchUnload;
 pub use batch_update::BatchUpdate;
 pub use batch_values::BatchValues;
 pub use logical_agg::{LogicalAgg, PlanAggCall};
 pub use logical_apply::LogicalApply;
+pub use logical_asof_join::LogicalAsOfJoin;
 pub use logical_delete::LogicalDelete;
 pub use logical_filter::LogicalFilter;
+pub use logical_gap_fill::{GapFillStrategy, LogicalGapFill};
 pub use logical_hop_window::LogicalHopWindow;
 pub use logical_insert::LogicalInsert;
 pub use logical_join::LogicalJoin;
 pub use logical_limit::LogicalLimit;
+pub use logical_match_recognize::{
+    LogicalMatchRecognize, Measure, MeasureKind, PatternQuantifier, PatternTerm,
+};
 pub use logical_multi_join::LogicalMultiJoin;
 pub use logical_project::LogicalProject;
-pub use logical_scan::LogicalScan;
+pub use logical_scan::{ColumnIndex, LogicalScan};
 pub use logical_source::LogicalSource;
 pub use logical_table_function::LogicalTableFunction;
 pub use logical_topn::LogicalTopN;
 pub use logical_update::LogicalUpdate;
 pub use logical_values::LogicalValues;
+pub use stream_asof_join::StreamAsOfJoin;
 pub use stream_delta_join::StreamDeltaJoin;
+pub use stream_dynamic_filter::StreamDynamicFilter;
 pub use stream_exchange::StreamExchange;
 pub use stream_filter::StreamFilter;
+pub use stream_gap_fill::StreamGapFill;
 pub use stream_hash_agg::StreamHashAgg;
 pub use stream_hash_join::StreamHashJoin;
 pub use stream_hop_window::StreamHopWindow;
 pub use stream_index_scan::StreamIndexScan;
+pub use stream_local_agg::StreamLocalAgg;
+pub use stream_match_recognize::StreamMatchRecognize;
 pub use stream_materialize::StreamMaterialize;
+pub use stream_now::StreamNow;
 pub use stream_project::StreamProject;
 pub use stream_simple_agg::StreamSimpleAgg;
 pub use stream_source::StreamSource;
 pub use stream_table_scan::StreamTableScan;
+pub use stream_tokenize::StreamTokenize;
 pub use stream_topn::StreamTopN;
 
 use crate::session::OptimizerContextRef;
@@ -334,6 +440,9 @@ macro_rules! for_all_plan_nodes {
             , { Logical, HopWindow }
             , { Logical, TableFunction }
             , { Logical, MultiJoin }
+            , { Logical, MatchRecognize }
+            , { Logical, GapFill }
+            , { Logical, AsOfJoin }
             // , { Logical, Sort } we don't need a LogicalSort, just require the Order
             , { Batch, SimpleAgg }
             , { Batch, HashAgg }
@@ -352,6 +461,10 @@ macro_rules! for_all_plan_nodes {
             , { Batch, TopN }
             , { Batch, HopWindow }
             , { Batch, TableFunction }
+            , { Batch, AsOfJoin }
+            , { Batch, GapFill }
+            , { Batch, ExternalScan }
+            , { Batch, Unload }
             , { Stream, Project }
             , { Stream, Filter }
             , { Stream, TableScan }
@@ -360,11 +473,18 @@ macro_rules! for_all_plan_nodes {
             , { Stream, Exchange }
             , { Stream, HashAgg }
             , { Stream, SimpleAgg }
+            , { Stream, LocalAgg }
             , { Stream, Materialize }
             , { Stream, TopN }
             , { Stream, HopWindow }
             , { Stream, DeltaJoin }
             , { Stream, IndexScan }
+            , { Stream, DynamicFilter }
+            , { Stream, Now }
+            , { Stream, MatchRecognize }
+            , { Stream, AsOfJoin }
+            , { Stream, Tokenize }
+            , { Stream, GapFill }
         }
     };
 }
@@ -391,6 +511,9 @@ macro_rules! for_logical_plan_nodes {
             , { Logical, HopWindow }
             , { Logical, TableFunction }
             , { Logical, MultiJoin }
+            , { Logical, MatchRecognize }
+            , { Logical, GapFill }
+            , { Logical, AsOfJoin }
             // , { Logical, Sort} not sure if we will support Order by clause in subquery/view/MV
             // if we dont support thatk, we don't need LogicalSort, just require the Order at the top of query
         }
@@ -420,6 +543,10 @@ macro_rules! for_batch_plan_nodes {
             , { Batch, Update }
             , { Batch, HopWindow }
             , { Batch, TableFunction }
+            , { Batch, AsOfJoin }
+            , { Batch, GapFill }
+            , { Batch, ExternalScan }
+            , { Batch, Unload }
         }
     };
 }
@@ -438,11 +565,18 @@ macro_rules! for_stream_plan_nodes {
             , { Stream, Source }
             , { Stream, HashAgg }
             , { Stream, SimpleAgg }
+            , { Stream, LocalAgg }
             , { Stream, Materialize }
             , { Stream, TopN }
             , { Stream, HopWindow }
             , { Stream, DeltaJoin }
             , { Stream, IndexScan }
+            , { Stream, DynamicFilter }
+            , { Stream, Now }
+            , { Stream, MatchRecognize }
+            , { Stream, AsOfJoin }
+            , { Stream, Tokenize }
+            , { Stream, GapFill }
         }
     };
 }
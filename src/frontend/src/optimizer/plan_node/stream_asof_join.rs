@@ -0,0 +1,176 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use itertools::Itertools;
+use risingwave_common::catalog::{ColumnDesc, DatabaseId, OrderedColumnDesc, SchemaId, TableId};
+use risingwave_common::util::sort_util::OrderType;
+use risingwave_pb::plan_common::HandleConflictBehavior;
+use risingwave_pb::stream_plan::stream_node::NodeBody as ProstStreamNode;
+use risingwave_pb::stream_plan::AsOfJoinNode;
+
+use super::{LogicalAsOfJoin, PlanBase, PlanRef, PlanTreeNodeBinary, ToStreamProst};
+use crate::catalog::column_catalog::ColumnCatalog;
+use crate::catalog::table_catalog::TableCatalog;
+
+/// [`StreamAsOfJoin`] implements [`super::LogicalAsOfJoin`] incrementally. Both sides are kept in
+/// internal tables ordered by the equal keys and then the time in descending order, so that the
+/// latest right row of a left row, and the left rows whose match is changed by a right row, can
+/// be found with a prefix scan.
+#[derive(Debug, Clone)]
+pub struct StreamAsOfJoin {
+    pub base: PlanBase,
+    logical: LogicalAsOfJoin,
+}
+
+impl StreamAsOfJoin {
+    pub fn new(logical: LogicalAsOfJoin) -> Self {
+        let ctx = logical.base.ctx.clone();
+        let dist = logical
+            .l2o_col_mapping()
+            .rewrite_provided_distribution(logical.left().distribution());
+        // The match of a left row changes as the right side changes.
+        let base = PlanBase::new_stream(
+            ctx,
+            logical.schema().clone(),
+            logical.base.pk_indices.to_vec(),
+            dist,
+            false,
+        );
+        Self { base, logical }
+    }
+
+    /// The internal table keeping the rows of one side, ordered by the equal keys, the time in
+    /// descending order and then the primary key of the side.
+    fn infer_internal_table_catalog(
+        input: &PlanRef,
+        eq_keys: Vec<usize>,
+        asof_key: usize,
+    ) -> TableCatalog {
+        let base = input.plan_base();
+        let columns = base
+            .schema
+            .fields()
+            .iter()
+            .map(|field| ColumnCatalog {
+                column_desc: ColumnDesc::from_field_without_column_id(field),
+                is_hidden: false,
+            })
+            .collect_vec();
+        let mut pks = eq_keys;
+        pks.push(asof_key);
+        for &idx in base.pk_indices.iter() {
+            if !pks.contains(&idx) {
+                pks.push(idx);
+            }
+        }
+        let order_desc = pks
+            .iter()
+            .map(|&idx| OrderedColumnDesc {
+                column_desc: columns[idx].column_desc.clone(),
+                order: if idx == asof_key {
+                    OrderType::Descending
+                } else {
+                    OrderType::Ascending
+                },
+            })
+            .collect();
+        TableCatalog {
+            id: TableId::placeholder(),
+            associated_source_id: None,
+            name: String::new(),
+            columns,
+            order_desc,
+            sort_key_len: 0,
+            pks,
+            distribution_keys: base.dist.dist_column_indices().to_vec(),
+            is_index_on: None,
+            is_internal_of: None,
+            appendonly: false,
+            owner: risingwave_common::catalog::DEFAULT_SUPPER_USER.to_string(),
+            vnode_mapping: None,
+            properties: HashMap::default(),
+            definition: String::new(),
+            description: String::new(),
+            column_descriptions: HashMap::default(),
+            dependent_relations: vec![],
+            handle_pk_conflict_behavior: HandleConflictBehavior::NoCheck,
+        }
+    }
+}
+
+impl fmt::Display for StreamAsOfJoin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.logical.fmt_with_name(f, "StreamAsOfJoin")
+    }
+}
+
+impl PlanTreeNodeBinary for StreamAsOfJoin {
+    fn left(&self) -> PlanRef {
+        self.logical.left()
+    }
+
+    fn right(&self) -> PlanRef {
+        self.logical.right()
+    }
+
+    fn clone_with_left_right(&self, left: PlanRef, right: PlanRef) -> Self {
+        Self::new(self.logical.clone_with_left_right(left, right))
+    }
+}
+
+impl_plan_tree_node_for_binary! { StreamAsOfJoin }
+
+impl ToStreamProst for StreamAsOfJoin {
+    fn to_stream_prost_body(&self) -> ProstStreamNode {
+        let left_table = Self::infer_internal_table_catalog(
+            &self.left(),
+            self.logical.left_eq_indexes(),
+            self.logical.left_asof_key(),
+        );
+        let right_table = Self::infer_internal_table_catalog(
+            &self.right(),
+            self.logical.right_eq_indexes(),
+            self.logical.right_asof_key(),
+        );
+        ProstStreamNode::AsOfJoin(AsOfJoinNode {
+            join_type: self.logical.join_type() as i32,
+            left_key: self
+                .logical
+                .left_eq_indexes()
+                .into_iter()
+                .map(|a| a as i32)
+                .collect(),
+            right_key: self
+                .logical
+                .right_eq_indexes()
+                .into_iter()
+                .map(|a| a as i32)
+                .collect(),
+            left_asof_key: self.logical.left_asof_key() as i32,
+            right_asof_key: self.logical.right_asof_key() as i32,
+            inclusive: self.logical.inclusive(),
+            left_table: Some(left_table.to_prost(
+                SchemaId::placeholder() as u32,
+                DatabaseId::placeholder() as u32,
+            )),
+            right_table: Some(right_table.to_prost(
+                SchemaId::placeholder() as u32,
+                DatabaseId::placeholder() as u32,
+            )),
+        })
+    }
+}
@@ -0,0 +1,154 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use itertools::Itertools;
+use risingwave_common::catalog::{ColumnDesc, DatabaseId, OrderedColumnDesc, SchemaId, TableId};
+use risingwave_common::util::sort_util::OrderType;
+use risingwave_pb::plan_common::HandleConflictBehavior;
+use risingwave_pb::stream_plan::stream_node::NodeBody as ProstStreamNode;
+use risingwave_pb::stream_plan::DynamicFilterNode;
+
+use super::{PlanBase, PlanRef, PlanTreeNodeBinary, ToStreamProst};
+use crate::catalog::column_catalog::ColumnCatalog;
+use crate::catalog::table_catalog::TableCatalog;
+use crate::expr::{ExprType, InputRefDisplay};
+
+/// [`StreamDynamicFilter`] filters the left input by comparing a column with the single row of the
+/// right input, which is broadcast to all its actors and never goes down, e.g. the bound of
+/// `v > NOW() - INTERVAL '1 hour'`. The rows affected by a new bound are emitted or retracted
+/// incrementally, so the left rows are kept in an internal table ordered by the compared column.
+#[derive(Debug, Clone)]
+pub struct StreamDynamicFilter {
+    pub base: PlanBase,
+    left_index: usize,
+    comparator: ExprType,
+    left: PlanRef,
+    right: PlanRef,
+}
+
+impl StreamDynamicFilter {
+    pub fn new(left_index: usize, comparator: ExprType, left: PlanRef, right: PlanRef) -> Self {
+        assert_eq!(right.schema().len(), 1);
+        assert_eq!(
+            left.schema().fields()[left_index].data_type(),
+            right.schema().fields()[0].data_type()
+        );
+        // Rows are retracted or emitted later as the bound goes up.
+        let base = PlanBase::new_stream(
+            left.ctx(),
+            left.schema().clone(),
+            left.pk_indices().to_vec(),
+            left.distribution().clone(),
+            false,
+        );
+        Self {
+            base,
+            left_index,
+            comparator,
+            left,
+            right,
+        }
+    }
+
+    /// The internal table keeping the rows of the left input, ordered by the compared column.
+    fn infer_left_internal_table_catalog(&self) -> TableCatalog {
+        let base = self.left.plan_base();
+        let columns = base
+            .schema
+            .fields()
+            .iter()
+            .map(|field| ColumnCatalog {
+                column_desc: ColumnDesc::from_field_without_column_id(field),
+                is_hidden: false,
+            })
+            .collect_vec();
+        let pks = std::iter::once(self.left_index)
+            .chain(
+                base.pk_indices
+                    .iter()
+                    .copied()
+                    .filter(|&idx| idx != self.left_index),
+            )
+            .collect_vec();
+        let order_desc = pks
+            .iter()
+            .map(|&idx| OrderedColumnDesc {
+                column_desc: columns[idx].column_desc.clone(),
+                order: OrderType::Ascending,
+            })
+            .collect();
+        TableCatalog {
+            id: TableId::placeholder(),
+            associated_source_id: None,
+            name: String::new(),
+            columns,
+            order_desc,
+            sort_key_len: 0,
+            pks,
+            distribution_keys: base.dist.dist_column_indices().to_vec(),
+            is_index_on: None,
+            is_internal_of: None,
+            appendonly: false,
+            owner: risingwave_common::catalog::DEFAULT_SUPPER_USER.to_string(),
+            vnode_mapping: None,
+            properties: HashMap::default(),
+            definition: String::new(),
+            description: String::new(),
+            column_descriptions: HashMap::default(),
+            dependent_relations: vec![],
+            handle_pk_conflict_behavior: HandleConflictBehavior::NoCheck,
+        }
+    }
+}
+
+impl fmt::Display for StreamDynamicFilter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("StreamDynamicFilter")
+            .field("left_key", &InputRefDisplay(self.left_index))
+            .field("comparator", &self.comparator)
+            .finish()
+    }
+}
+
+impl PlanTreeNodeBinary for StreamDynamicFilter {
+    fn left(&self) -> PlanRef {
+        self.left.clone()
+    }
+
+    fn right(&self) -> PlanRef {
+        self.right.clone()
+    }
+
+    fn clone_with_left_right(&self, left: PlanRef, right: PlanRef) -> Self {
+        Self::new(self.left_index, self.comparator, left, right)
+    }
+}
+
+impl_plan_tree_node_for_binary! { StreamDynamicFilter }
+
+impl ToStreamProst for StreamDynamicFilter {
+    fn to_stream_prost_body(&self) -> ProstStreamNode {
+        ProstStreamNode::DynamicFilter(DynamicFilterNode {
+            left_key: self.left_index as u32,
+            comparator: self.comparator as i32,
+            left_table: Some(self.infer_left_internal_table_catalog().to_prost(
+                SchemaId::placeholder() as u32,
+                DatabaseId::placeholder() as u32,
+            )),
+        })
+    }
+}
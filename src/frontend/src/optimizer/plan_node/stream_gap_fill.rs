@@ -0,0 +1,133 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use itertools::Itertools;
+use risingwave_common::catalog::{ColumnDesc, DatabaseId, OrderedColumnDesc, SchemaId, TableId};
+use risingwave_common::util::sort_util::OrderType;
+use risingwave_pb::plan_common::HandleConflictBehavior;
+use risingwave_pb::stream_plan::stream_node::NodeBody as ProstStreamNode;
+use risingwave_pb::stream_plan::GapFillNode;
+
+use super::{LogicalGapFill, PlanBase, PlanRef, PlanTreeNodeUnary, ToStreamProst};
+use crate::catalog::column_catalog::ColumnCatalog;
+use crate::catalog::table_catalog::TableCatalog;
+
+/// [`StreamGapFill`] keeps the input rows of each partition in an internal table ordered by time.
+/// When a bucket changes, the rows generated for the gaps around it are retracted and generated
+/// again.
+#[derive(Debug, Clone)]
+pub struct StreamGapFill {
+    pub base: PlanBase,
+    logical: LogicalGapFill,
+}
+
+impl StreamGapFill {
+    pub fn new(logical: LogicalGapFill) -> Self {
+        let ctx = logical.base.ctx.clone();
+        let pk_indices = logical.base.pk_indices.to_vec();
+        let dist = logical.input().distribution().clone();
+        let base = PlanBase::new_stream(ctx, logical.schema().clone(), pk_indices, dist, false);
+        Self { base, logical }
+    }
+
+    /// The internal table keeping the input rows, keyed by the partition keys and the time.
+    fn infer_internal_table_catalog(&self) -> TableCatalog {
+        let columns = self
+            .logical
+            .input()
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| ColumnCatalog {
+                column_desc: ColumnDesc::from_field_without_column_id(field),
+                is_hidden: false,
+            })
+            .collect_vec();
+        let pks = self.base.pk_indices.clone();
+        let order_desc = pks
+            .iter()
+            .map(|&idx| OrderedColumnDesc {
+                column_desc: columns[idx].column_desc.clone(),
+                order: OrderType::Ascending,
+            })
+            .collect();
+        TableCatalog {
+            id: TableId::placeholder(),
+            associated_source_id: None,
+            name: String::new(),
+            columns,
+            order_desc,
+            sort_key_len: 0,
+            pks,
+            distribution_keys: self.base.dist.dist_column_indices().to_vec(),
+            is_index_on: None,
+            is_internal_of: None,
+            appendonly: false,
+            owner: risingwave_common::catalog::DEFAULT_SUPPER_USER.to_string(),
+            vnode_mapping: None,
+            properties: HashMap::default(),
+            definition: String::new(),
+            description: String::new(),
+            column_descriptions: HashMap::default(),
+            dependent_relations: vec![],
+            handle_pk_conflict_behavior: HandleConflictBehavior::NoCheck,
+        }
+    }
+}
+
+impl fmt::Display for StreamGapFill {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.logical.fmt_with_name(f, "StreamGapFill")
+    }
+}
+
+impl PlanTreeNodeUnary for StreamGapFill {
+    fn input(&self) -> PlanRef {
+        self.logical.input()
+    }
+
+    fn clone_with_input(&self, input: PlanRef) -> Self {
+        Self::new(self.logical.clone_with_input(input))
+    }
+}
+
+impl_plan_tree_node_for_unary! { StreamGapFill }
+
+impl ToStreamProst for StreamGapFill {
+    fn to_stream_prost_body(&self) -> ProstStreamNode {
+        ProstStreamNode::GapFill(GapFillNode {
+            time_col: self.logical.time_col() as u32,
+            interval: Some(self.logical.interval().into()),
+            partition_by: self
+                .logical
+                .partition_by()
+                .iter()
+                .map(|&idx| idx as u32)
+                .collect(),
+            strategies: self
+                .logical
+                .strategies()
+                .iter()
+                .map(|&strategy| strategy as i32)
+                .collect(),
+            state_table: Some(self.infer_internal_table_catalog().to_prost(
+                SchemaId::placeholder() as u32,
+                DatabaseId::placeholder() as u32,
+            )),
+        })
+    }
+}
@@ -21,7 +21,7 @@ use risingwave_pb::stream_plan::stream_node::NodeBody as ProstStreamNode;
 use super::logical_agg::PlanAggCall;
 use super::{LogicalAgg, PlanBase, PlanRef, PlanTreeNodeUnary, ToStreamProst};
 use crate::expr::InputRefDisplay;
-use crate::optimizer::property::Distribution;
+use crate::optimizer::property::{derive_watermark_columns, Distribution};
 
 #[derive(Debug, Clone)]
 pub struct StreamHashAgg {
@@ -41,6 +41,7 @@ impl StreamHashAgg {
                 .i2o_col_mapping()
                 .rewrite_provided_distribution(input_dist),
             Distribution::SomeShard => Distribution::SomeShard,
+            Distribution::Broadcast => unreachable!(),
         };
         // Hash agg executor might change the append-only behavior of the stream.
         let base = PlanBase::new_stream(ctx, logical.schema().clone(), pk_indices, dist, false);
@@ -93,6 +94,7 @@ impl ToStreamProst for StreamHashAgg {
     fn to_stream_prost_body(&self) -> ProstStreamNode {
         use risingwave_pb::stream_plan::*;
         let (internal_tables, column_mapping) = self.logical.infer_internal_table_catalog();
+        let watermarks = derive_watermark_columns(&self.input());
         ProstStreamNode::HashAgg(HashAggNode {
             distribution_keys: self
                 .distribution_keys()
@@ -118,6 +120,15 @@ impl ToStreamProst for StreamHashAgg {
                 .map(|(k, v)| (k as u32, v))
                 .collect(),
             is_append_only: self.input().append_only(),
+            watermark_cleanup: self.distribution_keys().iter().enumerate().find_map(
+                |(key_index, idx)| {
+                    Some(WatermarkCleanup {
+                        key_index: key_index as u32,
+                        delay_ms: *watermarks.get(idx)?,
+                    })
+                },
+            ),
+            reemit: false,
         })
     }
 }
@@ -19,16 +19,16 @@ use itertools::Itertools;
 use risingwave_common::catalog::{ColumnDesc, DatabaseId, OrderedColumnDesc, SchemaId, TableId};
 use risingwave_common::session_config::DELTA_JOIN;
 use risingwave_common::util::sort_util::OrderType;
-use risingwave_pb::plan_common::JoinType;
+use risingwave_pb::plan_common::{HandleConflictBehavior, JoinType};
 use risingwave_pb::stream_plan::stream_node::NodeBody;
-use risingwave_pb::stream_plan::HashJoinNode;
+use risingwave_pb::stream_plan::{HashJoinNode, WatermarkCleanup};
 
 use super::{LogicalJoin, PlanBase, PlanRef, PlanTreeNodeBinary, StreamDeltaJoin, ToStreamProst};
 use crate::catalog::column_catalog::ColumnCatalog;
 use crate::catalog::table_catalog::TableCatalog;
 use crate::expr::Expr;
 use crate::optimizer::plan_node::EqJoinPredicate;
-use crate::optimizer::property::Distribution;
+use crate::optimizer::property::{derive_watermark_columns, Distribution};
 use crate::utils::ColIndexMapping;
 
 /// [`StreamHashJoin`] implements [`super::LogicalJoin`] with hash table. It builds a hash table
@@ -101,6 +101,10 @@ impl StreamHashJoin {
         }
     }
 
+    pub fn logical(&self) -> &LogicalJoin {
+        &self.logical
+    }
+
     /// Get join type
     pub fn join_type(&self) -> JoinType {
         self.logical.join_type()
@@ -187,6 +191,20 @@ impl_plan_tree_node_for_binary! { StreamHashJoin }
 
 impl ToStreamProst for StreamHashJoin {
     fn to_stream_prost_body(&self) -> NodeBody {
+        // Only an inner join never matches the rows behind a watermark again, so that the other
+        // side's state behind it can be purged.
+        let watermark_cleanup = |input: PlanRef, key_indices: Vec<usize>| {
+            if self.join_type() != JoinType::Inner {
+                return None;
+            }
+            let watermarks = derive_watermark_columns(&input);
+            key_indices.iter().enumerate().find_map(|(key_index, idx)| {
+                Some(WatermarkCleanup {
+                    key_index: key_index as u32,
+                    delay_ms: *watermarks.get(idx)?,
+                })
+            })
+        };
         NodeBody::HashJoin(HashJoinNode {
             join_type: self.logical.join_type() as i32,
             left_key: self
@@ -234,6 +252,14 @@ impl ToStreamProst for StreamHashJoin {
                 .map(|&x| x as u32)
                 .collect(),
             is_append_only: self.is_append_only,
+            left_watermark: watermark_cleanup(
+                self.left(),
+                self.eq_join_predicate.left_eq_indexes(),
+            ),
+            right_watermark: watermark_cleanup(
+                self.right(),
+                self.eq_join_predicate.right_eq_indexes(),
+            ),
         })
     }
 }
@@ -263,12 +289,19 @@ fn infer_internal_table_catalog(input: PlanRef) -> TableCatalog {
         name: String::new(),
         columns,
         order_desc,
+        sort_key_len: 0,
         pks: pk_indices.clone(),
         distribution_keys: base.dist.dist_column_indices().to_vec(),
         is_index_on: None,
+        is_internal_of: None,
         appendonly: input.append_only(),
         owner: risingwave_common::catalog::DEFAULT_SUPPER_USER.to_string(),
         vnode_mapping: None,
         properties: HashMap::default(),
+        definition: String::new(),
+        description: String::new(),
+        column_descriptions: HashMap::default(),
+        dependent_relations: vec![],
+        handle_pk_conflict_behavior: HandleConflictBehavior::NoCheck,
     }
 }
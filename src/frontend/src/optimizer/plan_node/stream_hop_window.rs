@@ -41,6 +41,10 @@ impl StreamHopWindow {
         );
         Self { base, logical }
     }
+
+    pub fn logical(&self) -> &LogicalHopWindow {
+        &self.logical
+    }
 }
 
 impl fmt::Display for StreamHopWindow {
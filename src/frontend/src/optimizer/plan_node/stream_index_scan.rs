@@ -44,7 +44,7 @@ impl StreamIndexScan {
             logical.schema().clone(),
             logical.base.pk_indices.clone(),
             Distribution::HashShard(logical.map_distribution_keys()),
-            false, // TODO: determine the `append-only` field of table scan
+            logical.table_desc().appendonly,
         );
         Self {
             base,
@@ -102,6 +102,7 @@ impl StreamIndexScan {
                 .iter()
                 .map(|k| *k as u32)
                 .collect_vec(),
+            base_epoch: 0,
         };
 
         let pk_indices = self.base.pk_indices.iter().map(|x| *x as u32).collect_vec();
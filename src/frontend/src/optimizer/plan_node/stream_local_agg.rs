@@ -0,0 +1,131 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use itertools::Itertools;
+use risingwave_pb::stream_plan::stream_node::NodeBody as ProstStreamNode;
+
+use super::logical_agg::PlanAggCall;
+use super::{LogicalAgg, PlanBase, PlanRef, PlanTreeNodeUnary, ToStreamProst};
+use crate::expr::InputRefDisplay;
+use crate::optimizer::property::Distribution;
+
+/// The local phase of a two-phase streaming aggregation. It aggregates the input in memory without
+/// any state, and emits the partial results of each epoch to the global [`super::StreamHashAgg`]
+/// or [`super::StreamSimpleAgg`] downstream, which combines them after the shuffle.
+#[derive(Debug, Clone)]
+pub struct StreamLocalAgg {
+    pub base: PlanBase,
+    logical: LogicalAgg,
+}
+
+impl StreamLocalAgg {
+    pub fn new(logical: LogicalAgg) -> Self {
+        let ctx = logical.base.ctx.clone();
+        let pk_indices = logical.base.pk_indices.to_vec();
+        let input = logical.input();
+        let input_dist = input.distribution();
+        let dist = match input_dist {
+            Distribution::Single => Distribution::Single,
+            Distribution::HashShard(_) => logical
+                .i2o_col_mapping()
+                .rewrite_provided_distribution(input_dist),
+            Distribution::SomeShard => Distribution::SomeShard,
+            Distribution::Broadcast => unreachable!(),
+        };
+        let base = PlanBase::new_stream(
+            ctx,
+            logical.schema().clone(),
+            pk_indices,
+            dist,
+            input.append_only(),
+        );
+        StreamLocalAgg { base, logical }
+    }
+
+    pub fn agg_calls(&self) -> &[PlanAggCall] {
+        self.logical.agg_calls()
+    }
+
+    pub fn group_keys(&self) -> &[usize] {
+        self.logical.group_keys()
+    }
+}
+
+impl fmt::Display for StreamLocalAgg {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.group_keys().is_empty() {
+            f.debug_struct("StreamLocalSimpleAgg")
+                .field("aggs", &self.agg_calls())
+                .finish()
+        } else {
+            f.debug_struct("StreamLocalHashAgg")
+                .field(
+                    "group_keys",
+                    &self
+                        .group_keys()
+                        .iter()
+                        .copied()
+                        .map(InputRefDisplay)
+                        .collect_vec(),
+                )
+                .field("aggs", &self.agg_calls())
+                .finish()
+        }
+    }
+}
+
+impl PlanTreeNodeUnary for StreamLocalAgg {
+    fn input(&self) -> PlanRef {
+        self.logical.input()
+    }
+
+    fn clone_with_input(&self, input: PlanRef) -> Self {
+        Self::new(self.logical.clone_with_input(input))
+    }
+}
+impl_plan_tree_node_for_unary! { StreamLocalAgg }
+
+impl ToStreamProst for StreamLocalAgg {
+    fn to_stream_prost_body(&self) -> ProstStreamNode {
+        use risingwave_pb::stream_plan::*;
+        let agg_calls = self
+            .agg_calls()
+            .iter()
+            .map(PlanAggCall::to_protobuf)
+            .collect_vec();
+        let is_append_only = self.input().append_only();
+        if self.group_keys().is_empty() {
+            ProstStreamNode::LocalSimpleAgg(SimpleAggNode {
+                agg_calls,
+                distribution_keys: vec![],
+                internal_tables: vec![],
+                column_mapping: Default::default(),
+                is_append_only,
+                reemit: false,
+            })
+        } else {
+            ProstStreamNode::LocalHashAgg(HashAggNode {
+                distribution_keys: self.group_keys().iter().map(|idx| *idx as u32).collect(),
+                agg_calls,
+                internal_tables: vec![],
+                column_mapping: Default::default(),
+                is_append_only,
+                watermark_cleanup: None,
+                reemit: false,
+            })
+        }
+    }
+}
@@ -0,0 +1,164 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use itertools::Itertools;
+use risingwave_common::catalog::{
+    ColumnDesc, DatabaseId, Field, OrderedColumnDesc, SchemaId, TableId,
+};
+use risingwave_common::types::DataType;
+use risingwave_common::util::sort_util::OrderType;
+use risingwave_pb::plan_common::HandleConflictBehavior;
+use risingwave_pb::stream_plan::match_recognize_node::{
+    Measure as ProstMeasure, PatternTerm as ProstPatternTerm,
+};
+use risingwave_pb::stream_plan::stream_node::NodeBody as ProstStreamNode;
+use risingwave_pb::stream_plan::MatchRecognizeNode;
+
+use super::{LogicalMatchRecognize, PlanBase, PlanRef, PlanTreeNodeUnary, ToStreamProst};
+use crate::catalog::column_catalog::ColumnCatalog;
+use crate::catalog::table_catalog::TableCatalog;
+use crate::expr::Expr;
+
+/// [`StreamMatchRecognize`] runs the pattern of `MATCH_RECOGNIZE` as an NFA over each partition of
+/// an append-only input. The partial matches of each partition are kept in an internal table, and
+/// a row is emitted once a match completes.
+#[derive(Debug, Clone)]
+pub struct StreamMatchRecognize {
+    pub base: PlanBase,
+    logical: LogicalMatchRecognize,
+}
+
+impl StreamMatchRecognize {
+    pub fn new(logical: LogicalMatchRecognize) -> Self {
+        let ctx = logical.base.ctx.clone();
+        let pk_indices = logical.base.pk_indices.to_vec();
+        let dist = logical
+            .i2o_col_mapping()
+            .rewrite_provided_distribution(logical.input().distribution());
+        let base = PlanBase::new_stream(ctx, logical.schema().clone(), pk_indices, dist, true);
+        Self { base, logical }
+    }
+
+    /// The internal table keeping the partial matches of each partition, keyed by the partition
+    /// keys and the NFA state of each partial match. See `MatchRecognizeExecutor` for the layout.
+    fn infer_internal_table_catalog(&self) -> TableCatalog {
+        let input_schema = self.logical.input().schema().clone();
+        let partition_by = self.logical.partition_by();
+        let columns = partition_by
+            .iter()
+            .map(|&idx| input_schema[idx].clone())
+            .chain([
+                Field::with_name(DataType::Int32, "slot"),
+                Field::with_name(DataType::Int64, "seq"),
+                Field::with_name(DataType::Int64, "match_count"),
+            ])
+            .chain(
+                self.logical
+                    .measures()
+                    .iter()
+                    .map(|measure| input_schema[measure.column].clone()),
+            )
+            .map(|field| ColumnCatalog {
+                column_desc: ColumnDesc::from_field_without_column_id(&field),
+                is_hidden: false,
+            })
+            .collect_vec();
+        let pks = (0..=partition_by.len()).collect_vec();
+        let order_desc = pks
+            .iter()
+            .map(|&idx| OrderedColumnDesc {
+                column_desc: columns[idx].column_desc.clone(),
+                order: OrderType::Ascending,
+            })
+            .collect();
+        TableCatalog {
+            id: TableId::placeholder(),
+            associated_source_id: None,
+            name: String::new(),
+            columns,
+            order_desc,
+            sort_key_len: 0,
+            pks,
+            distribution_keys: self.base.dist.dist_column_indices().to_vec(),
+            is_index_on: None,
+            is_internal_of: None,
+            appendonly: false,
+            owner: risingwave_common::catalog::DEFAULT_SUPPER_USER.to_string(),
+            vnode_mapping: None,
+            properties: HashMap::default(),
+            definition: String::new(),
+            description: String::new(),
+            column_descriptions: HashMap::default(),
+            dependent_relations: vec![],
+            handle_pk_conflict_behavior: HandleConflictBehavior::NoCheck,
+        }
+    }
+}
+
+impl fmt::Display for StreamMatchRecognize {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.logical.fmt_with_name(f, "StreamMatchRecognize")
+    }
+}
+
+impl PlanTreeNodeUnary for StreamMatchRecognize {
+    fn input(&self) -> PlanRef {
+        self.logical.input()
+    }
+
+    fn clone_with_input(&self, input: PlanRef) -> Self {
+        Self::new(self.logical.clone_with_input(input))
+    }
+}
+
+impl_plan_tree_node_for_unary! { StreamMatchRecognize }
+
+impl ToStreamProst for StreamMatchRecognize {
+    fn to_stream_prost_body(&self) -> ProstStreamNode {
+        ProstStreamNode::MatchRecognize(MatchRecognizeNode {
+            partition_by: self
+                .logical
+                .partition_by()
+                .iter()
+                .map(|&idx| idx as u32)
+                .collect(),
+            pattern: self
+                .logical
+                .pattern()
+                .iter()
+                .map(|term| ProstPatternTerm {
+                    quantifier: term.quantifier as i32,
+                    condition: Some(term.condition.to_expr_proto()),
+                })
+                .collect(),
+            measures: self
+                .logical
+                .measures()
+                .iter()
+                .map(|measure| ProstMeasure {
+                    kind: measure.kind as i32,
+                    term: measure.term as u32,
+                    column: measure.column as u32,
+                })
+                .collect(),
+            state_table: Some(self.infer_internal_table_catalog().to_prost(
+                SchemaId::placeholder() as u32,
+                DatabaseId::placeholder() as u32,
+            )),
+        })
+    }
+}
@@ -22,7 +22,7 @@ use risingwave_common::error::ErrorCode::InternalError;
 use risingwave_common::error::Result;
 use risingwave_common::util::sort_util::OrderType;
 use risingwave_pb::expr::InputRefExpr;
-use risingwave_pb::plan_common::ColumnOrder;
+use risingwave_pb::plan_common::{ColumnOrder, HandleConflictBehavior};
 use risingwave_pb::stream_plan::stream_node::NodeBody as ProstStreamNode;
 
 use super::{PlanRef, PlanTreeNodeUnary, ToStreamProst};
@@ -61,7 +61,11 @@ impl StreamMaterialize {
 
     #[must_use]
     pub fn new(input: PlanRef, table: TableCatalog) -> Self {
-        let base = Self::derive_plan_base(&input).unwrap();
+        let mut base = Self::derive_plan_base(&input).unwrap();
+        // Overwriting a row on primary key conflict retracts the existing one.
+        if table.handle_pk_conflict_behavior == HandleConflictBehavior::Overwrite {
+            base.append_only = false;
+        }
         Self { base, input, table }
     }
 
@@ -132,6 +136,13 @@ impl StreamMaterialize {
 
         let mut in_order = FixedBitSet::with_capacity(schema.len());
         let mut order_desc = vec![];
+        // The rows of a materialized view with `ORDER BY` are stored in that order, so that they
+        // can be read in order without sorting.
+        let sort_key_len = if is_index_on.is_none() {
+            user_order_by.field_order.len()
+        } else {
+            0
+        };
 
         for field in &user_order_by.field_order {
             let idx = field.index;
@@ -159,18 +170,37 @@ impl StreamMaterialize {
             name: mv_name,
             columns,
             order_desc,
+            sort_key_len,
             pks: pk_indices.clone(),
             is_index_on,
+            is_internal_of: None,
             distribution_keys: base.dist.dist_column_indices().to_vec(),
             appendonly: input.append_only(),
             owner: risingwave_common::catalog::DEFAULT_SUPPER_USER.to_string(),
             vnode_mapping: None,
             properties: HashMap::default(),
+            definition: String::new(),
+            description: String::new(),
+            column_descriptions: HashMap::default(),
+            dependent_relations: vec![],
+            handle_pk_conflict_behavior: HandleConflictBehavior::NoCheck,
         };
 
         Ok(Self { base, input, table })
     }
 
+    /// Set how to handle a row inserted with an existing primary key, which is only possible for
+    /// tables and materialized sources with a user-defined primary key.
+    #[must_use]
+    pub fn with_conflict_behavior(mut self, behavior: HandleConflictBehavior) -> Self {
+        self.table.handle_pk_conflict_behavior = behavior;
+        if behavior == HandleConflictBehavior::Overwrite {
+            self.base.append_only = false;
+            self.table.appendonly = false;
+        }
+        self
+    }
+
     /// Get a reference to the stream materialize's table.
     #[must_use]
     pub fn table(&self) -> &TableCatalog {
@@ -274,6 +304,7 @@ impl ToStreamProst for StreamMaterialize {
                 .iter()
                 .map(|idx| *idx as u32)
                 .collect_vec(),
+            handle_pk_conflict_behavior: self.table().handle_pk_conflict_behavior as i32,
         })
     }
 }
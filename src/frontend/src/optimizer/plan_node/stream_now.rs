@@ -0,0 +1,54 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use risingwave_common::catalog::{Field, Schema};
+use risingwave_common::types::DataType;
+use risingwave_pb::stream_plan::stream_node::NodeBody as ProstStreamNode;
+use risingwave_pb::stream_plan::NowNode;
+
+use super::{PlanBase, ToStreamProst};
+use crate::optimizer::property::Distribution;
+use crate::session::OptimizerContextRef;
+
+/// [`StreamNow`] produces the current timestamp as a single row, which is updated at every barrier
+/// with the time of the new epoch. It is the bound of the [`super::StreamDynamicFilter`] evaluating
+/// a comparison with `NOW()` in a streaming job.
+#[derive(Debug, Clone)]
+pub struct StreamNow {
+    pub base: PlanBase,
+}
+
+impl StreamNow {
+    pub fn new(ctx: OptimizerContextRef) -> Self {
+        let schema = Schema::new(vec![Field::with_name(DataType::Timestamp, "now")]);
+        let base = PlanBase::new_stream(ctx, schema, vec![], Distribution::Single, false);
+        Self { base }
+    }
+}
+
+impl_plan_tree_node_for_leaf! { StreamNow }
+
+impl fmt::Display for StreamNow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "StreamNow")
+    }
+}
+
+impl ToStreamProst for StreamNow {
+    fn to_stream_prost_body(&self) -> ProstStreamNode {
+        ProstStreamNode::Now(NowNode {})
+    }
+}
@@ -104,6 +104,7 @@ impl ToStreamProst for StreamSimpleAgg {
                 .map(|(k, v)| (k as u32, v))
                 .collect(),
             is_append_only: self.input().append_only(),
+            reemit: false,
         })
     }
 }
@@ -109,6 +109,7 @@ impl StreamTableScan {
                 .iter()
                 .map(|k| *k as u32)
                 .collect_vec(),
+            base_epoch: 0,
         };
 
         let pk_indices = self.base.pk_indices.iter().map(|x| *x as u32).collect_vec();
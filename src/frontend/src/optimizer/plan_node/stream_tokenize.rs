@@ -0,0 +1,91 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use risingwave_common::catalog::{Field, Schema, LEXEME_COLUMN_NAME};
+use risingwave_common::types::DataType;
+use risingwave_pb::stream_plan::stream_node::NodeBody as ProstStreamNode;
+use risingwave_pb::stream_plan::TokenizeNode;
+
+use super::{PlanBase, PlanRef, PlanTreeNodeUnary, ToStreamProst};
+use crate::expr::InputRefDisplay;
+
+/// `StreamTokenize` emits each input row once for each distinct lexeme of the text in
+/// `column_idx`, with the lexeme appended as the last column. It maintains the inverted index of a
+/// full-text index.
+#[derive(Debug, Clone)]
+pub struct StreamTokenize {
+    pub base: PlanBase,
+    input: PlanRef,
+    column_idx: usize,
+}
+
+impl StreamTokenize {
+    pub fn new(input: PlanRef, column_idx: usize) -> Self {
+        assert_eq!(
+            input.schema().fields()[column_idx].data_type,
+            DataType::Varchar
+        );
+        let ctx = input.ctx();
+        let mut fields = input.schema().fields().to_vec();
+        fields.push(Field::with_name(DataType::Varchar, LEXEME_COLUMN_NAME));
+        // A row is identified by the input pk and the lexeme.
+        let mut pk_indices = input.pk_indices().to_vec();
+        pk_indices.push(fields.len() - 1);
+        let base = PlanBase::new_stream(
+            ctx,
+            Schema { fields },
+            pk_indices,
+            input.distribution().clone(),
+            input.append_only(),
+        );
+        StreamTokenize {
+            base,
+            input,
+            column_idx,
+        }
+    }
+
+    pub fn column_idx(&self) -> usize {
+        self.column_idx
+    }
+}
+
+impl fmt::Display for StreamTokenize {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("StreamTokenize")
+            .field("column", &InputRefDisplay(self.column_idx))
+            .finish()
+    }
+}
+
+impl PlanTreeNodeUnary for StreamTokenize {
+    fn input(&self) -> PlanRef {
+        self.input.clone()
+    }
+
+    fn clone_with_input(&self, input: PlanRef) -> Self {
+        Self::new(input, self.column_idx)
+    }
+}
+impl_plan_tree_node_for_unary! { StreamTokenize }
+
+impl ToStreamProst for StreamTokenize {
+    fn to_stream_prost_body(&self) -> ProstStreamNode {
+        ProstStreamNode::Tokenize(TokenizeNode {
+            column_idx: self.column_idx as u32,
+        })
+    }
+}
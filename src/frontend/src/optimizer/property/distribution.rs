@@ -45,7 +45,7 @@
 use fixedbitset::FixedBitSet;
 use risingwave_common::error::Result;
 use risingwave_pb::batch_plan::exchange_info::{
-    Distribution as DistributionProst, DistributionMode, HashInfo,
+    BroadcastInfo, Distribution as DistributionProst, DistributionMode, HashInfo,
 };
 use risingwave_pb::batch_plan::ExchangeInfo;
 
@@ -65,6 +65,8 @@ pub enum Distribution {
     /// records with the same hash values must be on the same partition.
     /// `usize` is the index of column used as the distribution key.
     HashShard(Vec<usize>),
+    /// Records are replicated to all partitions. Only used for the broadcast side of batch joins.
+    Broadcast,
 }
 
 /// the distribution property requirement.
@@ -88,6 +90,7 @@ impl Distribution {
             mode: match self {
                 Distribution::Single => DistributionMode::Single,
                 Distribution::HashShard(_) => DistributionMode::Hash,
+                Distribution::Broadcast => DistributionMode::Broadcast,
                 // TODO: add round robin DistributionMode
                 Distribution::SomeShard => DistributionMode::Single,
             } as i32,
@@ -97,6 +100,9 @@ impl Distribution {
                     output_count,
                     keys: keys.iter().map(|num| *num as u32).collect(),
                 })),
+                Distribution::Broadcast => Some(DistributionProst::BroadcastInfo(BroadcastInfo {
+                    count: output_count,
+                })),
                 // TODO: add round robin distribution
                 Distribution::SomeShard => None,
             },
@@ -124,7 +130,9 @@ impl Distribution {
     /// valid.
     pub fn dist_column_indices(&self) -> &[usize] {
         match self {
-            Distribution::Single | Distribution::SomeShard => Default::default(),
+            Distribution::Single | Distribution::SomeShard | Distribution::Broadcast => {
+                Default::default()
+            }
             Distribution::HashShard(dists) => dists,
         }
     }
@@ -27,3 +27,5 @@ pub(crate) mod order;
 pub use order::*;
 mod distribution;
 pub use distribution::*;
+mod watermark;
+pub use watermark::*;
@@ -151,6 +151,14 @@ impl Direction {
             _ => self == other,
         }
     }
+
+    pub fn reverse(&self) -> Direction {
+        match self {
+            Direction::Asc => Direction::Desc,
+            Direction::Desc => Direction::Asc,
+            Direction::Any => Direction::Any,
+        }
+    }
 }
 
 const ANY_ORDER: Order = Order {
@@ -193,6 +201,19 @@ impl Order {
     pub fn is_any(&self) -> bool {
         self.field_order.is_empty()
     }
+
+    /// Returns the order with the direction of every field reversed.
+    pub fn reverse(&self) -> Self {
+        Self::new(
+            self.field_order
+                .iter()
+                .map(|f| FieldOrder {
+                    index: f.index,
+                    direct: f.direct.reverse(),
+                })
+                .collect(),
+        )
+    }
 }
 
 #[cfg(test)]
@@ -266,4 +287,28 @@ mod tests {
         assert!(!o4.satisfies(&o2));
         assert!(!o4.satisfies(&o3));
     }
+
+    #[test]
+    fn test_order_reverse() {
+        let o1 = Order {
+            field_order: vec![FieldOrder::ascending(0), FieldOrder::descending(1)],
+        };
+        let o2 = Order {
+            field_order: vec![FieldOrder::descending(0)],
+        };
+        let o3 = Order {
+            field_order: vec![
+                FieldOrder::descending(0),
+                FieldOrder {
+                    index: 1,
+                    direct: Direction::Any,
+                },
+            ],
+        };
+
+        assert!(!o1.satisfies(&o2));
+        assert!(o1.reverse().satisfies(&o2));
+        assert!(o1.reverse().satisfies(&o3));
+        assert!(o1.reverse().reverse().satisfies(&o1));
+    }
 }
@@ -0,0 +1,92 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Event-time watermarks carried by the columns of a stream plan.
+
+use std::collections::BTreeMap;
+
+use itertools::Itertools;
+
+use crate::optimizer::plan_node::{
+    PlanNodeType, PlanTreeNode, PlanTreeNodeBinary, PlanTreeNodeUnary,
+};
+use crate::optimizer::PlanRef;
+
+/// Derives the output columns of a stream `plan` carrying an event-time watermark, as a map from
+/// column index to how long the watermark lags behind the largest value seen, in milliseconds.
+///
+/// Watermarks originate from the `watermark` property of append-only tables and pass through the
+/// operators keeping the event-time columns. Late rows are only droppable from append-only
+/// streams, so no watermark is derived for the others.
+pub fn derive_watermark_columns(plan: &PlanRef) -> BTreeMap<usize, u64> {
+    if !plan.append_only() {
+        return BTreeMap::new();
+    }
+    match plan.node_type() {
+        PlanNodeType::StreamTableScan => {
+            let scan = plan.as_stream_table_scan().unwrap().logical();
+            scan.table_desc()
+                .watermark
+                .and_then(|watermark| {
+                    let idx = scan
+                        .output_col_idx()
+                        .iter()
+                        .position(|&idx| idx == watermark.column_index)?;
+                    Some((idx, watermark.delay_ms))
+                })
+                .into_iter()
+                .collect()
+        }
+        PlanNodeType::StreamFilter | PlanNodeType::StreamExchange => {
+            derive_watermark_columns(&plan.inputs()[0])
+        }
+        PlanNodeType::StreamProject => {
+            let project = plan.as_stream_project().unwrap().as_logical();
+            let i2o = project.i2o_col_mapping();
+            derive_watermark_columns(&project.input())
+                .into_iter()
+                .filter_map(|(idx, delay)| Some((i2o.try_map(idx)?, delay)))
+                .collect()
+        }
+        PlanNodeType::StreamHopWindow => {
+            let hop_window = plan.as_stream_hop_window().unwrap().logical();
+            hop_window.derive_watermark_columns(&derive_watermark_columns(&hop_window.input()))
+        }
+        PlanNodeType::StreamHashJoin => {
+            // A row of one side may match a row of the other side arbitrarily older, unless both
+            // are equal on a join key behind the watermarks of both sides.
+            let hash_join = plan.as_stream_hash_join().unwrap();
+            let join = hash_join.logical();
+            let left = derive_watermark_columns(&join.left());
+            let right = derive_watermark_columns(&join.right());
+            let l2o = join.l2i_col_mapping().composite(&join.i2o_col_mapping());
+            let r2o = join.r2i_col_mapping().composite(&join.i2o_col_mapping());
+            let predicate = hash_join.eq_join_predicate();
+            let mut output = BTreeMap::new();
+            for (l, r) in predicate
+                .left_eq_indexes()
+                .into_iter()
+                .zip_eq(predicate.right_eq_indexes())
+            {
+                if let (Some(&delay_l), Some(&delay_r)) = (left.get(&l), right.get(&r)) {
+                    let delay = delay_l.max(delay_r);
+                    output.extend(l2o.try_map(l).map(|idx| (idx, delay)));
+                    output.extend(r2o.try_map(r).map(|idx| (idx, delay)));
+                }
+            }
+            output
+        }
+        _ => BTreeMap::new(),
+    }
+}
@@ -16,18 +16,28 @@ use std::collections::HashSet;
 use std::rc::Rc;
 
 use itertools::Itertools;
+use risingwave_common::session_config::DELTA_JOIN_WITHOUT_INDEX;
 use risingwave_pb::plan_common::JoinType;
 
 use super::super::plan_node::*;
 use super::{BoxedRule, Rule};
 
-/// Use index scan and delta joins for supported queries.
+/// Use index scan and delta joins for supported queries. Each side of the join is looked up in an
+/// arrangement of the other side on the join key, which is an index, or the table or materialized
+/// view itself if it's already distributed and ordered by the join key and
+/// `RW_ENABLE_DELTA_JOIN_WITHOUT_INDEX` is set. So the join shares the state of the arrangements
+/// instead of keeping a private copy of both inputs.
 pub struct IndexDeltaJoinRule {}
 
 impl Rule for IndexDeltaJoinRule {
     fn apply(&self, plan: PlanRef) -> Option<PlanRef> {
         let join = plan.as_stream_hash_join()?;
+        let ctx = plan.ctx();
         if join.eq_join_predicate().has_non_eq() || join.join_type() != JoinType::Inner {
+            ctx.trace(format!(
+                "reject delta join for {}: only inner joins with equal conditions are supported",
+                join
+            ));
             return Some(plan);
         }
 
@@ -44,17 +54,31 @@ impl Rule for IndexDeltaJoinRule {
             }
         }
 
-        let input_left_dyn = match_through_exchange(Rc::clone(&join.inputs()[0]))?;
+        let (Some(input_left_dyn), Some(input_right_dyn)) = (
+            match_through_exchange(Rc::clone(&join.inputs()[0])),
+            match_through_exchange(Rc::clone(&join.inputs()[1])),
+        ) else {
+            ctx.trace(format!(
+                "reject delta join for {}: both inputs must be table scans",
+                join
+            ));
+            return None;
+        };
         let input_left = input_left_dyn.as_stream_table_scan()?;
-        let input_right_dyn = match_through_exchange(Rc::clone(&join.inputs()[1]))?;
         let input_right = input_right_dyn.as_stream_table_scan()?;
         let left_indices = join.eq_join_predicate().left_eq_indexes();
         let right_indices = join.eq_join_predicate().right_eq_indexes();
-
-        fn match_indexes(join_indices: &[usize], table_scan: &StreamTableScan) -> Option<PlanRef> {
-            if table_scan.logical().indexes().is_empty() {
-                return None;
-            }
+        let without_index = ctx
+            .inner()
+            .session_ctx
+            .get_config(DELTA_JOIN_WITHOUT_INDEX)
+            .map_or(false, |config| config.is_set(false));
+
+        fn match_indexes(
+            join_indices: &[usize],
+            table_scan: &StreamTableScan,
+            without_index: bool,
+        ) -> Option<PlanRef> {
             let column_descs = table_scan.logical().column_descs();
             // We assume column id of create index's MV is exactly the same as corresponding columns
             // in the original table. We generate a list of column ids, and match them against
@@ -64,7 +88,17 @@ impl Rule for IndexDeltaJoinRule {
                 .map(|idx| column_descs[*idx].column_id)
                 .collect_vec();
 
-            for (name, index) in table_scan.logical().indexes() {
+            // If enabled, try the table itself first, so that no index is needed if it's already
+            // arranged by the join key, e.g., a materialized view grouped by the join key.
+            let table = (
+                table_scan.logical().table_name().to_string(),
+                Rc::new(table_scan.logical().table_desc().clone()),
+            );
+            let table = without_index.then_some(&table);
+            for (name, index) in table
+                .into_iter()
+                .chain(table_scan.logical().indexes().iter())
+            {
                 // 1. Check if distribution keys are the same.
                 // We don't assume the hash function we are using satisfies commutativity
                 // `Hash(A, B) == Hash(B, A)`, so we consider order of each item in distribution
@@ -105,8 +139,8 @@ impl Rule for IndexDeltaJoinRule {
             None
         }
 
-        if let Some(left) = match_indexes(&left_indices, input_left) {
-            if let Some(right) = match_indexes(&right_indices, input_right) {
+        if let Some(left) = match_indexes(&left_indices, input_left, without_index) {
+            if let Some(right) = match_indexes(&right_indices, input_right, without_index) {
                 // We already ensured that index and join use the same distribution, so we directly
                 // replace the children with stream index scan without inserting any exchanges.
 
@@ -116,9 +150,19 @@ impl Rule for IndexDeltaJoinRule {
                         .into(),
                 )
             } else {
+                ctx.trace(format!(
+                    "reject delta join for {}: no index on {} matches the join keys",
+                    join,
+                    input_right.logical().table_name()
+                ));
                 Some(plan)
             }
         } else {
+            ctx.trace(format!(
+                "reject delta join for {}: no index on {} matches the join keys",
+                join,
+                input_left.logical().table_name()
+            ));
             Some(plan)
         }
     }
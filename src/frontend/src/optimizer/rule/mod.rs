@@ -21,6 +21,12 @@ use super::PlanRef;
 pub trait Rule: Send + Sync {
     /// return err(()) if not match
     fn apply(&self, plan: PlanRef) -> Option<PlanRef>;
+
+    /// The name of the rule shown in the optimizer trace.
+    fn description(&self) -> &str {
+        let name = std::any::type_name::<Self>();
+        name.rsplit("::").next().unwrap_or(name)
+    }
 }
 
 pub(super) type BoxedRule = Box<dyn Rule>;
@@ -0,0 +1,275 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Cache of optimized batch plans, so that repeated queries, e.g. issued by dashboards at a high
+//! rate, skip binding and planning.
+//!
+//! Plans are keyed by the normalized statement text together with the database and the session
+//! configurations, and are only valid for the catalog version they are planned at. Any DDL bumps
+//! the catalog version and invalidates the whole cache.
+//!
+//! Generic plans of statements with their constants parameterized are cached as well, see
+//! [`crate::auto_prepare`].
+
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use risingwave_common::catalog::Schema;
+use risingwave_common::collection::evictable::EvictableHashMap;
+use risingwave_common::types::DataType;
+use risingwave_sqlparser::ast::Statement;
+
+use crate::auto_prepare::ParamSlot;
+use crate::optimizer::plan_hint::PlanHints;
+use crate::scheduler::Query;
+use crate::session::SessionImpl;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PlanCacheKey {
+    database: String,
+    /// The statement printed back from its AST, so that differences in whitespace, comments and
+    /// keyword case don't matter.
+    normalized_sql: String,
+    /// Session configurations, which may affect the plan, e.g. the query mode.
+    config: String,
+    /// Whether the statement has its constants parameterized.
+    generic: bool,
+}
+
+impl PlanCacheKey {
+    pub fn new(session: &SessionImpl, stmt: &Statement) -> Self {
+        Self {
+            database: session.database().to_string(),
+            normalized_sql: stmt.to_string(),
+            config: session.config_snapshot(),
+            generic: false,
+        }
+    }
+
+    /// The key of the generic plan of a statement, whose constants are parameterized in
+    /// `generic_sql`.
+    pub fn generic(session: &SessionImpl, generic_sql: &str) -> Self {
+        Self {
+            database: session.database().to_string(),
+            normalized_sql: generic_sql.to_string(),
+            config: session.config_snapshot(),
+            generic: true,
+        }
+    }
+
+    #[cfg(test)]
+    pub fn for_test(sql: &str) -> Self {
+        Self {
+            database: "dev".to_string(),
+            normalized_sql: sql.to_string(),
+            config: "".to_string(),
+            generic: false,
+        }
+    }
+}
+
+/// A fragmented batch plan ready to be scheduled.
+pub struct CachedPlan {
+    pub query: Query,
+    pub schema: Schema,
+    pub explained_plan: String,
+    /// Hints are given in comments and hence not part of the key.
+    pub hints: PlanHints,
+    /// The parallelism of the stages depends on the number of workers.
+    pub worker_node_count: usize,
+    /// Types of the parameters of a generic plan, and where they're bound. Both are empty for
+    /// plans of a specific statement.
+    pub param_types: Vec<DataType>,
+    pub param_slots: Vec<ParamSlot>,
+}
+
+struct PlanCacheCore {
+    catalog_version: u64,
+    plans: EvictableHashMap<PlanCacheKey, Arc<CachedPlan>>,
+    /// Statements which can't be generalized, so that they're not probed again.
+    rejected: EvictableHashMap<PlanCacheKey, ()>,
+}
+
+pub struct PlanCache {
+    capacity: usize,
+    auto_prepare: bool,
+    core: Mutex<PlanCacheCore>,
+}
+
+pub type PlanCacheRef = Arc<PlanCache>;
+
+impl PlanCache {
+    pub fn new(capacity: usize, auto_prepare: bool) -> Self {
+        Self {
+            capacity,
+            auto_prepare,
+            core: Mutex::new(PlanCacheCore {
+                catalog_version: 0,
+                plans: EvictableHashMap::new(capacity),
+                rejected: EvictableHashMap::new(capacity),
+            }),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.capacity > 0
+    }
+
+    pub fn auto_prepare_enabled(&self) -> bool {
+        self.enabled() && self.auto_prepare
+    }
+
+    /// Returns the cached plan of `key` planned at `catalog_version`, if it's still valid for the
+    /// given hints and workers.
+    pub fn get(
+        &self,
+        key: &PlanCacheKey,
+        catalog_version: u64,
+        hints: &PlanHints,
+        worker_node_count: usize,
+    ) -> Option<Arc<CachedPlan>> {
+        let mut core = self.core.lock();
+        core.invalidate_if_stale(catalog_version);
+        let plan = core.plans.get(key)?.clone();
+        if &plan.hints == hints && plan.worker_node_count == worker_node_count {
+            Some(plan)
+        } else {
+            core.plans.pop(key);
+            None
+        }
+    }
+
+    /// Caches the plan of `key` planned at `catalog_version`. It's ignored if the catalog has
+    /// changed since then.
+    pub fn put(&self, key: PlanCacheKey, catalog_version: u64, plan: Arc<CachedPlan>) {
+        if !self.enabled() {
+            return;
+        }
+        let mut core = self.core.lock();
+        core.invalidate_if_stale(catalog_version);
+        if core.catalog_version == catalog_version {
+            core.plans.put(key, plan);
+            core.plans.evict_to_target_cap();
+        }
+    }
+
+    /// Records that the statement of the generic `key` can't be generalized at `catalog_version`.
+    pub fn reject(&self, key: PlanCacheKey, catalog_version: u64) {
+        let mut core = self.core.lock();
+        core.invalidate_if_stale(catalog_version);
+        if core.catalog_version == catalog_version {
+            core.rejected.put(key, ());
+            core.rejected.evict_to_target_cap();
+        }
+    }
+
+    pub fn is_rejected(&self, key: &PlanCacheKey, catalog_version: u64) -> bool {
+        let mut core = self.core.lock();
+        core.invalidate_if_stale(catalog_version);
+        core.rejected.contains(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.core.lock().plans.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl PlanCacheCore {
+    /// Drops all the plans if they're planned at an older catalog version.
+    fn invalidate_if_stale(&mut self, catalog_version: u64) {
+        if catalog_version > self.catalog_version {
+            self.plans.clear();
+            self.rejected.clear();
+            self.catalog_version = catalog_version;
+        }
+    }
+}
+
+impl Default for PlanCache {
+    fn default() -> Self {
+        Self::new(0, false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimizer::plan_node::{BatchValues, LogicalValues};
+    use crate::optimizer::PlanRef;
+    use crate::scheduler::worker_node_manager::WorkerNodeManager;
+    use crate::scheduler::BatchPlanFragmenter;
+    use crate::session::OptimizerContext;
+
+    fn key(sql: &str) -> PlanCacheKey {
+        PlanCacheKey::for_test(sql)
+    }
+
+    async fn plan() -> CachedPlan {
+        let ctx = OptimizerContext::mock().await;
+        let values: PlanRef =
+            BatchValues::new(LogicalValues::new(vec![], Schema::new(vec![]), ctx)).into();
+        let query = BatchPlanFragmenter::new(Arc::new(WorkerNodeManager::mock(vec![])))
+            .split(values)
+            .unwrap();
+        CachedPlan {
+            query,
+            schema: Schema::new(vec![]),
+            explained_plan: "".to_string(),
+            hints: PlanHints::default(),
+            worker_node_count: 0,
+            param_types: vec![],
+            param_slots: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_plan_cache() {
+        let cache = PlanCache::new(2, false);
+        let hints = PlanHints::default();
+        cache.put(key("a"), 1, Arc::new(plan().await));
+        assert!(cache.get(&key("a"), 1, &hints, 0).is_some());
+        assert!(cache.get(&key("b"), 1, &hints, 0).is_none());
+
+        // Plans are invalidated by changes of hints or workers.
+        let other_hints = PlanHints {
+            parallelism: Some(4),
+            ..Default::default()
+        };
+        assert!(cache.get(&key("a"), 1, &other_hints, 0).is_none());
+        cache.put(key("a"), 1, Arc::new(plan().await));
+        assert!(cache.get(&key("a"), 1, &hints, 2).is_none());
+
+        // The least recently used plan is evicted.
+        cache.put(key("a"), 1, Arc::new(plan().await));
+        cache.put(key("b"), 1, Arc::new(plan().await));
+        cache.put(key("c"), 1, Arc::new(plan().await));
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(&key("a"), 1, &hints, 0).is_none());
+
+        // DDLs invalidate all the plans, and plans of older catalogs are not cached.
+        assert!(cache.get(&key("c"), 2, &hints, 0).is_none());
+        assert!(cache.is_empty());
+        cache.put(key("a"), 1, Arc::new(plan().await));
+        assert!(cache.is_empty());
+
+        // Rejections are invalidated by DDLs as well.
+        cache.reject(key("a"), 2);
+        assert!(cache.is_rejected(&key("a"), 2));
+        assert!(!cache.is_rejected(&key("a"), 3));
+    }
+}
@@ -28,6 +28,8 @@ mod statement;
 mod update;
 mod values;
 
+pub use query::LIMIT_ALL_COUNT;
+
 /// `Planner` converts a bound statement to a [`crate::optimizer::plan_node::PlanNode`] tree
 pub struct Planner {
     ctx: OptimizerContextRef,
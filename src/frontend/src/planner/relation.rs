@@ -15,19 +15,21 @@
 use std::rc::Rc;
 
 use itertools::Itertools;
-use risingwave_common::catalog::{Field, Schema};
+use risingwave_common::catalog::{Field, Schema, FULLTEXT_INDEX_COLUMN_KEY, IVF_INDEX_COLUMN_KEY};
 use risingwave_common::error::{ErrorCode, Result};
 use risingwave_common::types::ScalarImpl;
 
 use crate::binder::{
-    BoundBaseTable, BoundJoin, BoundSource, BoundSystemTable, BoundTableFunction,
-    BoundWindowTableFunction, FunctionType, Relation, WindowTableFunctionKind,
+    BoundBaseTable, BoundJoin, BoundMatchRecognize, BoundSource, BoundSystemTable,
+    BoundTableFunction, BoundWindowTableFunction, FunctionType, Relation, WindowTableFunctionKind,
 };
+use crate::catalog::table_catalog::TableCatalog;
 use crate::expr::{ExprImpl, ExprType, FunctionCall, InputRef};
 use crate::optimizer::plan_node::{
-    LogicalHopWindow, LogicalJoin, LogicalProject, LogicalScan, LogicalSource,
-    LogicalTableFunction, PlanRef,
+    ColumnIndex, LogicalAsOfJoin, LogicalHopWindow, LogicalJoin, LogicalMatchRecognize,
+    LogicalProject, LogicalScan, LogicalSource, LogicalTableFunction, PlanRef,
 };
+use crate::optimizer::property::Order;
 use crate::planner::Planner;
 
 impl Planner {
@@ -38,12 +40,14 @@ impl Planner {
             // TODO: order is ignored in the subquery
             Relation::Subquery(q) => Ok(self.plan_query(q.query)?.as_subplan()),
             Relation::Join(join) => self.plan_join(*join),
+            Relation::AsOfJoin(join) => self.plan_asof_join(*join),
             Relation::WindowTableFunction(tf) => self.plan_window_table_function(*tf),
             Relation::Source(s) => self.plan_source(*s),
             Relation::TableFunction(gs) => match gs.func_type {
                 FunctionType::Generate => self.plan_generate_series_function(*gs),
                 FunctionType::Unnest => self.plan_unnest_function(*gs),
             },
+            Relation::MatchRecognize(mr) => self.plan_match_recognize(*mr),
         }
     }
 
@@ -59,18 +63,37 @@ impl Planner {
     }
 
     pub(super) fn plan_base_table(&mut self, base_table: BoundBaseTable) -> Result<PlanRef> {
-        Ok(LogicalScan::create(
-            base_table.name,
-            false,
-            Rc::new(base_table.table_catalog.table_desc()),
-            base_table
-                .table_indexes
-                .iter()
-                .map(|x| (x.name.clone(), Rc::new(x.table_desc())))
-                .collect(),
-            self.ctx(),
+        let table_desc = Rc::new(base_table.table_catalog.table_desc());
+        let column_index = |index: &TableCatalog, key: &str| {
+            let column = index.properties.get(key)?;
+            let column_idx = table_desc.columns.iter().position(|c| &c.name == column)?;
+            Some(ColumnIndex {
+                name: index.name.clone(),
+                column_idx,
+                index: Rc::new(index.table_desc()),
+            })
+        };
+        // Full-text and IVF indexes don't have the same rows as the table, or the order of any
+        // columns, so they can't replace a scan on the table.
+        let mut indexes = vec![];
+        let mut fulltext_indexes = vec![];
+        let mut ivf_indexes = vec![];
+        for index in &base_table.table_indexes {
+            if let Some(index) = column_index(index, FULLTEXT_INDEX_COLUMN_KEY) {
+                fulltext_indexes.push(index);
+            } else if let Some(index) = column_index(index, IVF_INDEX_COLUMN_KEY) {
+                ivf_indexes.push(index);
+            } else {
+                indexes.push((index.name.clone(), Rc::new(index.table_desc())));
+            }
+        }
+        Ok(
+            LogicalScan::create(base_table.name, false, table_desc, indexes, self.ctx())
+                .with_fulltext_indexes(fulltext_indexes)
+                .with_ivf_indexes(ivf_indexes)
+                .with_sample(base_table.sample)
+                .into(),
         )
-        .into())
     }
 
     pub(super) fn plan_source(&mut self, source: BoundSource) -> Result<PlanRef> {
@@ -85,6 +108,26 @@ impl Planner {
         Ok(LogicalJoin::create(left, right, join_type, on_clause))
     }
 
+    pub(super) fn plan_asof_join(&mut self, join: BoundJoin) -> Result<PlanRef> {
+        let left = self.plan_relation(join.left)?;
+        let right = self.plan_relation(join.right)?;
+        LogicalAsOfJoin::create(left, right, join.join_type, join.cond)
+    }
+
+    pub(super) fn plan_match_recognize(
+        &mut self,
+        match_recognize: BoundMatchRecognize,
+    ) -> Result<PlanRef> {
+        let input = self.plan_relation(match_recognize.input)?;
+        Ok(LogicalMatchRecognize::create(
+            input,
+            match_recognize.partition_by,
+            Order::new(match_recognize.order_by),
+            match_recognize.pattern,
+            match_recognize.measures,
+        ))
+    }
+
     pub(super) fn plan_window_table_function(
         &mut self,
         table_function: BoundWindowTableFunction,
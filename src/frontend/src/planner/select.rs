@@ -15,7 +15,7 @@
 use itertools::Itertools;
 use risingwave_common::catalog::Schema;
 use risingwave_common::error::{ErrorCode, Result};
-use risingwave_common::types::DataType;
+use risingwave_common::types::{DataType, IntervalUnit, ScalarImpl};
 use risingwave_pb::plan_common::JoinType;
 
 use crate::binder::BoundSelect;
@@ -24,7 +24,8 @@ use crate::expr::{
 };
 pub use crate::optimizer::plan_node::LogicalFilter;
 use crate::optimizer::plan_node::{
-    LogicalAgg, LogicalApply, LogicalJoin, LogicalProject, LogicalValues, PlanAggCall, PlanRef,
+    GapFillStrategy, LogicalAgg, LogicalApply, LogicalGapFill, LogicalJoin, LogicalProject,
+    LogicalValues, PlanAggCall, PlanRef, PlanTreeNodeUnary,
 };
 use crate::planner::Planner;
 use crate::utils::Condition;
@@ -51,6 +52,22 @@ impl Planner {
         }
         select_items.extend(extra_order_exprs);
 
+        // Strip `locf()` and `interpolate()` off the select items. They are applied to the output.
+        let gap_fill_strategies: Vec<_> = select_items
+            .iter_mut()
+            .map(Self::strip_gap_fill_strategy)
+            .try_collect()?;
+        if group_by
+            .iter()
+            .chain(&having)
+            .any(|e| e.has_gap_fill_strategy())
+        {
+            return Err(ErrorCode::InvalidInputSyntax(
+                "locf() and interpolate() can only be used in the select list".into(),
+            )
+            .into());
+        }
+
         // Plan the FROM clause.
         let mut root = match from {
             None => self.create_dummy_values(),
@@ -60,6 +77,16 @@ impl Planner {
         if let Some(where_clause) = where_clause {
             root = self.plan_where(root, where_clause)?;
         }
+        let gap_fill = if gap_fill_strategies
+            .iter()
+            .any(|strategy| *strategy != GapFillStrategy::None)
+        {
+            let (time_col, interval, partition_by) =
+                Self::resolve_gap_fill_keys(&root, &select_items, &group_by)?;
+            Some((time_col, interval, partition_by, gap_fill_strategies))
+        } else {
+            None
+        };
         // Plan the SELECT clause.
         // TODO: select-agg, group-by, having can also contain subquery exprs.
         let has_agg_call = select_items.iter().any(|expr| expr.has_agg_call());
@@ -77,6 +104,10 @@ impl Planner {
         }
         root = LogicalProject::create(root, select_items);
 
+        if let Some((time_col, interval, partition_by, strategies)) = gap_fill {
+            root = LogicalGapFill::create(root, time_col, interval, partition_by, strategies);
+        }
+
         if distinct {
             let group_keys = (0..root.schema().fields().len()).collect();
             root = LogicalAgg::new(vec![], group_keys, root).into();
@@ -85,6 +116,88 @@ impl Planner {
         Ok(root)
     }
 
+    /// Strips `locf()` or `interpolate()` off the top of a select item, and returns how the item is
+    /// filled for the empty buckets.
+    fn strip_gap_fill_strategy(expr: &mut ExprImpl) -> Result<GapFillStrategy> {
+        let strategy = match expr {
+            ExprImpl::FunctionCall(func) => match func.get_expr_type() {
+                ExprType::Locf => GapFillStrategy::Locf,
+                ExprType::Interpolate => GapFillStrategy::Interpolate,
+                _ => GapFillStrategy::None,
+            },
+            _ => GapFillStrategy::None,
+        };
+        if strategy != GapFillStrategy::None {
+            let ExprImpl::FunctionCall(func) = expr else {
+                unreachable!()
+            };
+            *expr = func.inputs()[0].clone();
+        }
+        if expr.has_gap_fill_strategy() {
+            return Err(ErrorCode::InvalidInputSyntax(
+                "locf() and interpolate() can't be nested in other expressions".into(),
+            )
+            .into());
+        }
+        Ok(strategy)
+    }
+
+    /// Finds the select items of the group keys of a gap filled query, where exactly one of them
+    /// is the start of a tumble window, whose size is the interval between the buckets. The
+    /// others partition the buckets.
+    fn resolve_gap_fill_keys(
+        input: &PlanRef,
+        select_items: &[ExprImpl],
+        group_by: &[ExprImpl],
+    ) -> Result<(usize, IntervalUnit, Vec<usize>)> {
+        let mut time_col = None;
+        let mut partition_by = vec![];
+        for key in group_by {
+            let Some(idx) = select_items.iter().position(|item| item == key) else {
+                return Err(ErrorCode::InvalidInputSyntax(
+                    "the group keys of a query with locf() or interpolate() must appear in select list"
+                        .into(),
+                )
+                .into());
+            };
+            match Self::tumble_window_size(input, key) {
+                Some(interval) if time_col.is_none() => time_col = Some((idx, interval)),
+                _ => partition_by.push(idx),
+            }
+        }
+        let Some((time_col, interval)) = time_col else {
+            return Err(ErrorCode::InvalidInputSyntax(
+                "locf() and interpolate() require the query to be grouped by the start of a tumble window"
+                    .into(),
+            )
+            .into());
+        };
+        Ok((time_col, interval, partition_by))
+    }
+
+    /// Returns the window size if `expr` on `plan` is the start of a tumble window, looking through
+    /// the filters and projections on it.
+    fn tumble_window_size(plan: &PlanRef, expr: &ExprImpl) -> Option<IntervalUnit> {
+        match expr {
+            ExprImpl::FunctionCall(func) if func.get_expr_type() == ExprType::TumbleStart => {
+                match func.inputs()[1].as_literal()?.get_data() {
+                    Some(ScalarImpl::Interval(interval)) => Some(*interval),
+                    _ => None,
+                }
+            }
+            ExprImpl::InputRef(input_ref) => {
+                if let Some(project) = plan.as_logical_project() {
+                    Self::tumble_window_size(&project.input(), &project.exprs()[input_ref.index()])
+                } else if let Some(filter) = plan.as_logical_filter() {
+                    Self::tumble_window_size(&filter.input(), expr)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
     /// Helper to create a dummy node as child of [`LogicalProject`].
     /// For example, `select 1+2, 3*4` will be `Project([1+2, 3+4]) - Values([[]])`.
     fn create_dummy_values(&self) -> PlanRef {
@@ -0,0 +1,239 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Capture and replay of batch queries, to debug issues of the optimizer and executors reported
+//! from production.
+//!
+//! If `replay_capture_location` is configured, the query handler keeps the plan and the epoch of
+//! each batch query in a [`ReplayCapture`]. When the query is recorded as a slow or failed query
+//! in the audit log, the capture is uploaded by the [`ReplayCapturer`] as a JSON
+//! [`ReplayBundle`] named after the query id. `REPLAY 'location'` on a repro cluster plans and
+//! executes the SQL of a bundle again with its session config, and compares the outcome with the
+//! captured one.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bytes::Bytes;
+use risingwave_common::error::{ErrorCode, Result, ToRwResult};
+use risingwave_connector::lake::LakeLocation;
+use serde::{Deserialize, Serialize};
+
+use crate::audit_log::AuditLogKind;
+
+/// Version of the bundle format, bumped on incompatible changes.
+pub const REPLAY_BUNDLE_VERSION: u32 = 1;
+
+/// A captured batch query, uploaded to the object store as JSON.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReplayBundle {
+    pub version: u32,
+    pub query_id: String,
+    pub kind: AuditLogKind,
+    /// Milliseconds since unix epoch when the query finished.
+    pub captured_at_ms: u64,
+    pub user: String,
+    pub database: String,
+    pub sql: String,
+    /// The session config the query is planned with.
+    pub session_config: BTreeMap<String, String>,
+    pub catalog_version: u64,
+    /// The epoch of the snapshot read by the query, or `None` if it doesn't scan any table.
+    pub epoch: Option<u64>,
+    /// The explained batch plan.
+    pub plan: String,
+    pub duration_ms: u64,
+    pub error: Option<String>,
+}
+
+/// Plan related information of a batch query, collected by the query handler.
+#[derive(Clone, Debug)]
+pub struct ReplayCapture {
+    pub query_id: String,
+    pub session_config: BTreeMap<String, String>,
+    pub catalog_version: u64,
+    pub epoch: Option<u64>,
+    pub plan: String,
+}
+
+/// Information of a finished statement, which is passed to [`ReplayCapturer::capture`].
+pub struct StatementReplay<'a> {
+    pub kind: AuditLogKind,
+    pub user: &'a str,
+    pub database: &'a str,
+    pub sql: &'a str,
+    pub duration_ms: u64,
+    pub error: Option<String>,
+    pub capture: ReplayCapture,
+}
+
+/// Uploads the bundles of slow and failed queries to `replay_capture_location`.
+#[derive(Default)]
+pub struct ReplayCapturer {
+    location: Option<LakeLocation>,
+}
+
+pub type ReplayCapturerRef = Arc<ReplayCapturer>;
+
+impl ReplayCapturer {
+    pub fn new(location: &str) -> Self {
+        if location.is_empty() {
+            return Self::default();
+        }
+        match LakeLocation::parse(location) {
+            Ok(location) => Self {
+                location: Some(location),
+            },
+            Err(e) => {
+                tracing::warn!("invalid replay capture location, capturing disabled: {}", e);
+                Self::default()
+            }
+        }
+    }
+
+    /// Whether the query handler should collect a [`ReplayCapture`] of each batch query.
+    pub fn enabled(&self) -> bool {
+        self.location.is_some()
+    }
+
+    /// Uploads the bundle of a slow or failed query in background. Other statements are ignored.
+    pub fn capture(&self, replay: StatementReplay<'_>) {
+        let Some(location) = self.location.clone() else {
+            return;
+        };
+        if !matches!(replay.kind, AuditLogKind::SlowQuery | AuditLogKind::Failed) {
+            return;
+        }
+        let bundle = ReplayBundle {
+            version: REPLAY_BUNDLE_VERSION,
+            query_id: replay.capture.query_id,
+            kind: replay.kind,
+            captured_at_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+            user: replay.user.to_string(),
+            database: replay.database.to_string(),
+            sql: replay.sql.to_string(),
+            session_config: replay.capture.session_config,
+            catalog_version: replay.capture.catalog_version,
+            epoch: replay.capture.epoch,
+            plan: replay.capture.plan,
+            duration_ms: replay.duration_ms,
+            error: replay.error,
+        };
+        tokio::spawn(async move {
+            let path = format!("{}{}.json", location.prefix(), bundle.query_id);
+            let body = match serde_json::to_vec_pretty(&bundle) {
+                Ok(body) => body,
+                Err(e) => {
+                    tracing::warn!("failed to serialize replay bundle: {}", e);
+                    return;
+                }
+            };
+            match location
+                .connect()
+                .await
+                .upload(&path, Bytes::from(body))
+                .await
+            {
+                Ok(()) => tracing::info!("captured query {} to {}", bundle.query_id, path),
+                Err(e) => tracing::warn!("failed to upload replay bundle {}: {}", path, e),
+            }
+        });
+    }
+}
+
+/// Downloads the bundle at `location`, e.g. `s3://bucket/replay/<query_id>.json`.
+pub async fn load_bundle(location: &str) -> Result<ReplayBundle> {
+    let (dir, file) = location.rsplit_once('/').ok_or_else(|| {
+        ErrorCode::InvalidInputSyntax(format!("invalid replay bundle location: {}", location))
+    })?;
+    let dir = LakeLocation::parse(dir).to_rw_result()?;
+    let path = format!("{}{}", dir.prefix(), file);
+    let body = dir
+        .connect()
+        .await
+        .read(&path, None)
+        .await
+        .map_err(|e| ErrorCode::InternalError(format!("failed to read {}: {}", location, e)))?;
+    let bundle: ReplayBundle = serde_json::from_slice(&body).map_err(|e| {
+        ErrorCode::InvalidInputSyntax(format!("invalid replay bundle {}: {}", location, e))
+    })?;
+    if bundle.version != REPLAY_BUNDLE_VERSION {
+        return Err(ErrorCode::NotImplemented(
+            format!("replay bundle of version {}", bundle.version),
+            None.into(),
+        )
+        .into());
+    }
+    Ok(bundle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replay(kind: AuditLogKind) -> StatementReplay<'static> {
+        StatementReplay {
+            kind,
+            user: "root",
+            database: "dev",
+            sql: "select * from t",
+            duration_ms: 1000,
+            error: None,
+            capture: ReplayCapture {
+                query_id: "q1".to_string(),
+                session_config: BTreeMap::from([("query_mode".to_string(), "local".to_string())]),
+                catalog_version: 3,
+                epoch: Some(42),
+                plan: "BatchScan { table: t }".to_string(),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_capture_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let location = format!("disk://{}", dir.path().display());
+        let capturer = ReplayCapturer::new(&location);
+        assert!(capturer.enabled());
+
+        // DDLs are not captured.
+        capturer.capture(replay(AuditLogKind::Ddl));
+        capturer.capture(replay(AuditLogKind::SlowQuery));
+        let bundle_location = format!("{}/q1.json", location);
+        // The bundle is uploaded in background.
+        let mut bundle = None;
+        for _ in 0..100 {
+            if let Ok(loaded) = load_bundle(&bundle_location).await {
+                bundle = Some(loaded);
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        let bundle = bundle.unwrap();
+        assert_eq!(bundle.kind, AuditLogKind::SlowQuery);
+        assert_eq!(bundle.sql, "select * from t");
+        assert_eq!(bundle.epoch, Some(42));
+        assert_eq!(bundle.session_config["query_mode"], "local");
+    }
+
+    #[test]
+    fn test_capture_disabled() {
+        assert!(!ReplayCapturer::new("").enabled());
+        assert!(!ReplayCapturer::new("hdfs://namenode/replay").enabled());
+    }
+}
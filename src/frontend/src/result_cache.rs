@@ -0,0 +1,209 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Cache of batch query results, so that a storm of identical queries, e.g. dashboards refreshed
+//! by many viewers at once, is served from the frontend instead of scanning the tables again.
+//!
+//! Results are keyed by the normalized statement, the database, the session configurations and
+//! the user, and are only valid for the epoch they are read at. Once a newer epoch is committed,
+//! the next query pins it and misses the cache, since the frontend doesn't know which tables the
+//! epoch has changed. Results also expire after the TTL, and results of too many rows are never
+//! cached. The cache is disabled by default.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use futures_async_stream::try_stream;
+use parking_lot::Mutex;
+use risingwave_batch::executor::BoxedDataChunkStream;
+use risingwave_common::array::DataChunk;
+use risingwave_common::collection::evictable::EvictableHashMap;
+use risingwave_common::error::RwError;
+use risingwave_sqlparser::ast::Statement;
+
+use crate::plan_cache::PlanCacheKey;
+use crate::session::SessionImpl;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ResultCacheKey {
+    statement: PlanCacheKey,
+    /// Results are never shared among users, whose privileges may differ.
+    user_name: String,
+}
+
+impl ResultCacheKey {
+    pub fn new(session: &SessionImpl, stmt: &Statement) -> Self {
+        Self {
+            statement: PlanCacheKey::new(session, stmt),
+            user_name: session.user_name().to_string(),
+        }
+    }
+}
+
+struct CachedResult {
+    epoch: u64,
+    catalog_version: u64,
+    cached_at: Instant,
+    chunks: Vec<DataChunk>,
+}
+
+pub struct ResultCache {
+    capacity: usize,
+    max_rows: usize,
+    ttl: Duration,
+    results: Mutex<EvictableHashMap<ResultCacheKey, CachedResult>>,
+}
+
+pub type ResultCacheRef = Arc<ResultCache>;
+
+impl ResultCache {
+    pub fn new(capacity: usize, max_rows: usize, ttl: Duration) -> Self {
+        Self {
+            capacity,
+            max_rows,
+            ttl,
+            results: Mutex::new(EvictableHashMap::new(capacity)),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.capacity > 0
+    }
+
+    /// Returns the cached result of `key` if it's read at `epoch` and `catalog_version`, and hasn't
+    /// expired.
+    pub fn get(
+        &self,
+        key: &ResultCacheKey,
+        epoch: u64,
+        catalog_version: u64,
+    ) -> Option<Vec<DataChunk>> {
+        let mut results = self.results.lock();
+        let result = results.get(key)?;
+        if result.epoch == epoch
+            && result.catalog_version == catalog_version
+            && result.cached_at.elapsed() < self.ttl
+        {
+            Some(result.chunks.clone())
+        } else {
+            results.pop(key);
+            None
+        }
+    }
+
+    /// Caches the result of `key` read at `epoch` and `catalog_version`.
+    pub fn put(
+        &self,
+        key: ResultCacheKey,
+        epoch: u64,
+        catalog_version: u64,
+        chunks: Vec<DataChunk>,
+    ) {
+        if !self.enabled() {
+            return;
+        }
+        let mut results = self.results.lock();
+        results.put(
+            key,
+            CachedResult {
+                epoch,
+                catalog_version,
+                cached_at: Instant::now(),
+                chunks,
+            },
+        );
+        results.evict_to_target_cap();
+    }
+
+    pub fn len(&self) -> usize {
+        self.results.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Passes through the result chunks of the query, and caches them once the query finishes if
+    /// they don't exceed the row limit.
+    #[try_stream(boxed, ok = DataChunk, error = RwError)]
+    pub async fn cache_stream(
+        self: Arc<Self>,
+        stream: BoxedDataChunkStream,
+        key: ResultCacheKey,
+        epoch: u64,
+        catalog_version: u64,
+    ) {
+        let mut chunks = Some(vec![]);
+        let mut rows = 0;
+        #[for_await]
+        for chunk in stream {
+            let chunk = chunk?;
+            rows += chunk.cardinality();
+            if rows > self.max_rows {
+                chunks = None;
+            } else if let Some(chunks) = &mut chunks {
+                chunks.push(chunk.clone());
+            }
+            yield chunk;
+        }
+        if let Some(chunks) = chunks {
+            self.put(key, epoch, catalog_version, chunks);
+        }
+    }
+}
+
+impl Default for ResultCache {
+    fn default() -> Self {
+        Self::new(0, 0, Duration::ZERO)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::{DataChunk, DataChunkTestExt};
+
+    use super::*;
+
+    #[test]
+    fn test_result_cache() {
+        let cache = ResultCache::new(1, 100, Duration::from_secs(60));
+        let key = |user: &str| ResultCacheKey {
+            statement: PlanCacheKey::for_test("select * from t"),
+            user_name: user.to_string(),
+        };
+        let chunk = DataChunk::from_pretty(
+            "i
+             1
+             2",
+        );
+        cache.put(key("root"), 1, 1, vec![chunk]);
+        assert_eq!(cache.get(&key("root"), 1, 1).unwrap().len(), 1);
+        assert!(cache.get(&key("other"), 1, 1).is_none());
+
+        // A newer epoch invalidates the result.
+        assert!(cache.get(&key("root"), 2, 1).is_none());
+        assert!(cache.is_empty());
+
+        // Results are evicted by the capacity.
+        cache.put(key("root"), 2, 1, vec![]);
+        cache.put(key("other"), 2, 1, vec![]);
+        assert_eq!(cache.len(), 1);
+        assert!(cache.get(&key("other"), 2, 1).is_some());
+
+        // Results expire after the TTL.
+        let cache = ResultCache::new(1, 100, Duration::ZERO);
+        cache.put(key("root"), 1, 1, vec![]);
+        assert!(cache.get(&key("root"), 1, 1).is_none());
+    }
+}
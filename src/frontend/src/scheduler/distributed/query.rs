@@ -12,11 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashMap;
 use std::mem;
 use std::sync::Arc;
 
 use anyhow::anyhow;
+use madsim::collections::HashMap;
 use risingwave_common::bail;
 use risingwave_pb::batch_plan::{TaskId as TaskIdProst, TaskOutputId as TaskOutputIdProst};
 use risingwave_rpc_client::ComputeClientPoolRef;
@@ -32,7 +32,9 @@ use crate::scheduler::distributed::StageEvent::Scheduled;
 use crate::scheduler::distributed::StageExecution;
 use crate::scheduler::plan_fragmenter::{Query, StageId, ROOT_TASK_ID, ROOT_TASK_OUTPUT_ID};
 use crate::scheduler::worker_node_manager::WorkerNodeManagerRef;
-use crate::scheduler::{HummockSnapshotManagerRef, SchedulerError, SchedulerResult};
+use crate::scheduler::{
+    HummockSnapshotManagerRef, PinnedHummockSnapshot, SchedulerError, SchedulerResult,
+};
 
 /// Message sent to a `QueryRunner` to control its execution.
 #[derive(Debug)]
@@ -90,6 +92,9 @@ struct QueryRunner {
     root_stage_sender: Option<oneshot::Sender<SchedulerResult<QueryResultFetcher>>>,
 
     epoch: u64,
+    /// Will be set to `None` after all stages with table scan are scheduled. Dropping it unpins
+    /// the snapshot if the query fails before that.
+    pinned_snapshot: Option<PinnedHummockSnapshot>,
     hummock_snapshot_manager: HummockSnapshotManagerRef,
     compute_client_pool: ComputeClientPoolRef,
 }
@@ -97,13 +102,14 @@ struct QueryRunner {
 impl QueryExecution {
     pub fn new(
         query: Query,
-        epoch: u64,
+        pinned_snapshot: PinnedHummockSnapshot,
         worker_node_manager: WorkerNodeManagerRef,
         hummock_snapshot_manager: HummockSnapshotManagerRef,
         compute_client_pool: ComputeClientPoolRef,
     ) -> Self {
         let query = Arc::new(query);
         let (sender, receiver) = channel(100);
+        let epoch = pinned_snapshot.epoch();
 
         let stage_executions = {
             let mut stage_executions: HashMap<StageId, Arc<StageExecution>> =
@@ -141,6 +147,7 @@ impl QueryExecution {
             msg_sender: sender,
             scheduled_stages_count: 0,
             epoch,
+            pinned_snapshot: Some(pinned_snapshot),
             hummock_snapshot_manager,
             compute_client_pool,
         };
@@ -242,9 +249,9 @@ impl QueryRunner {
                         // thus they all successfully pinned a HummockVersion.
                         // So we can now unpin their epoch.
                         info!("Query {:?} has scheduled all of its stages that have table scan (iterator creation).", self.query.query_id);
-                        self.hummock_snapshot_manager
-                            .unpin_snapshot(self.epoch, self.query.query_id())
-                            .await?;
+                        if let Some(pinned_snapshot) = self.pinned_snapshot.take() {
+                            pinned_snapshot.unpin().await?;
+                        }
                     }
 
                     if self.scheduled_stages_count == self.stage_executions.len() {
@@ -372,13 +379,19 @@ mod tests {
     async fn test_query_should_not_hang_with_empty_worker() {
         let worker_node_manager = Arc::new(WorkerNodeManager::mock(vec![]));
         let compute_client_pool = Arc::new(ComputeClientPool::new(1024));
+        let hummock_snapshot_manager = Arc::new(HummockSnapshotManager::new(Arc::new(
+            MockFrontendMetaClient {},
+        )));
+        let query = create_query().await;
+        let pinned_snapshot = hummock_snapshot_manager
+            .acquire(query.query_id(), 0)
+            .await
+            .unwrap();
         let query_execution = QueryExecution::new(
-            create_query().await,
-            100,
+            query,
+            pinned_snapshot,
             worker_node_manager,
-            Arc::new(HummockSnapshotManager::new(Arc::new(
-                MockFrontendMetaClient {},
-            ))),
+            hummock_snapshot_manager,
             compute_client_pool,
         );
 
@@ -420,6 +433,7 @@ mod tests {
                 ],
                 distribution_keys: vec![],
                 appendonly: false,
+                watermark: None,
             }),
             vec![],
             ctx,
@@ -489,6 +503,7 @@ mod tests {
             }),
             state: risingwave_pb::common::worker_node::State::Running as i32,
             parallel_units: generate_parallel_units(0, 0),
+            resource_group: "default".to_string(),
         };
         let worker2 = WorkerNode {
             id: 1,
@@ -499,6 +514,7 @@ mod tests {
             }),
             state: risingwave_pb::common::worker_node::State::Running as i32,
             parallel_units: generate_parallel_units(8, 1),
+            resource_group: "default".to_string(),
         };
         let worker3 = WorkerNode {
             id: 2,
@@ -509,6 +525,7 @@ mod tests {
             }),
             state: risingwave_pb::common::worker_node::State::Running as i32,
             parallel_units: generate_parallel_units(16, 2),
+            resource_group: "default".to_string(),
         };
         let workers = vec![worker1, worker2, worker3];
         let worker_node_manager = Arc::new(WorkerNodeManager::mock(workers));
@@ -17,9 +17,11 @@ use std::fmt::{Debug, Formatter};
 use futures::StreamExt;
 use futures_async_stream::try_stream;
 use log::debug;
+use prost::Message;
 use risingwave_common::array::DataChunk;
 use risingwave_common::error::RwError;
 use risingwave_pb::batch_plan::{PlanNode as BatchPlanProst, TaskId, TaskOutputId};
+use risingwave_pb::common::buffer::CompressionType;
 use risingwave_pb::common::HostAddress;
 use risingwave_rpc_client::ComputeClientPoolRef;
 use uuid::Uuid;
@@ -28,7 +30,8 @@ use super::QueryExecution;
 use crate::scheduler::plan_fragmenter::{Query, QueryId};
 use crate::scheduler::worker_node_manager::WorkerNodeManagerRef;
 use crate::scheduler::{
-    DataChunkStream, ExecutionContextRef, HummockSnapshotManagerRef, SchedulerResult,
+    DataChunkStream, ExecutionContextRef, HummockSnapshotManagerRef, PinnedHummockSnapshot,
+    SchedulerResult,
 };
 
 pub struct QueryResultFetcher {
@@ -67,7 +70,7 @@ impl QueryManager {
     /// This is kept for dml only.
     pub async fn schedule_single(
         &self,
-        _context: ExecutionContextRef,
+        context: ExecutionContextRef,
         plan: BatchPlanProst,
     ) -> SchedulerResult<impl DataChunkStream> {
         let worker_node_addr = self.worker_node_manager.next_random()?.host.unwrap();
@@ -91,17 +94,18 @@ impl QueryManager {
             output_id: 0,
         };
 
-        let epoch = self
+        let pinned_snapshot = self
             .hummock_snapshot_manager
-            .get_epoch(query_id.clone())
+            .acquire(&query_id, context.session().min_read_epoch())
             .await?;
+        let epoch = pinned_snapshot.epoch();
 
         let creat_task_resp = compute_client
             .create_task(task_id.clone(), plan, epoch)
             .await;
-        self.hummock_snapshot_manager
-            .unpin_snapshot(epoch, &query_id)
-            .await?;
+        // The task has created its iterators, which pin a Hummock version, so the snapshot can be
+        // unpinned now.
+        pinned_snapshot.unpin().await?;
         creat_task_resp?;
 
         let query_result_fetcher = QueryResultFetcher::new(
@@ -117,32 +121,33 @@ impl QueryManager {
 
     pub async fn schedule(
         &self,
-        _context: ExecutionContextRef,
+        context: ExecutionContextRef,
         query: Query,
     ) -> SchedulerResult<impl DataChunkStream> {
-        let query_id = query.query_id().clone();
-        let epoch = self
+        let pinned_snapshot = self
             .hummock_snapshot_manager
-            .get_epoch(query_id.clone())
+            .acquire(query.query_id(), context.session().min_read_epoch())
             .await?;
+        self.schedule_with_snapshot(query, pinned_snapshot).await
+    }
 
+    /// Schedules the query to read the snapshot pinned by the caller for it.
+    pub async fn schedule_with_snapshot(
+        &self,
+        query: Query,
+        pinned_snapshot: PinnedHummockSnapshot,
+    ) -> SchedulerResult<impl DataChunkStream> {
+        // The snapshot is unpinned by the query runner, either after all the scans are scheduled
+        // or when the query fails.
         let query_execution = QueryExecution::new(
             query,
-            epoch,
+            pinned_snapshot,
             self.worker_node_manager.clone(),
             self.hummock_snapshot_manager.clone(),
             self.compute_client_pool.clone(),
         );
 
-        let query_result_fetcher = match query_execution.start().await {
-            Ok(query_result_fetcher) => query_result_fetcher,
-            Err(e) => {
-                self.hummock_snapshot_manager
-                    .unpin_snapshot(epoch, &query_id)
-                    .await?;
-                return Err(e);
-            }
-        };
+        let query_result_fetcher = query_execution.start().await?;
 
         Ok(query_result_fetcher.run())
     }
@@ -171,13 +176,17 @@ impl QueryResultFetcher {
             "Starting to run query result fetcher, task output id: {:?}, task_host: {:?}",
             self.task_output_id, self.task_host
         );
-        let compute_client = self
+        let (mut stream, permit_sender) = self
             .compute_client_pool
-            .get_client_for_addr((&self.task_host).into())
+            .retry((&self.task_host).into(), |client| {
+                let task_output_id = self.task_output_id.clone();
+                async move { client.get_data(task_output_id, CompressionType::None).await }
+            })
             .await?;
-        let mut stream = compute_client.get_data(self.task_output_id.clone()).await?;
         while let Some(response) = stream.next().await {
-            yield DataChunk::from_protobuf(response?.get_record_batch()?)?;
+            let response = response?;
+            permit_sender.add_permits(response.encoded_len());
+            yield DataChunk::from_protobuf(response.get_record_batch()?)?;
         }
     }
 }
@@ -12,13 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashMap;
 use std::mem;
 use std::sync::Arc;
 
 use anyhow::anyhow;
 use arc_swap::ArcSwap;
 use futures::{stream, StreamExt};
+use madsim::collections::HashMap;
 use risingwave_pb::batch_plan::plan_node::NodeBody;
 use risingwave_pb::batch_plan::{
     ExchangeNode, ExchangeSource, MergeSortExchangeNode, PlanFragment, PlanNode as PlanNodeProst,
@@ -214,7 +214,14 @@ impl StageExecution {
     ///
     /// When this method is called, all tasks should have been scheduled, and their `worker_node`
     /// should have been set.
-    fn all_exchange_sources_for(&self, output_id: u32) -> Vec<ExchangeSource> {
+    ///
+    /// Sources placed on `consumer_addr` are tagged as co-located, so that the consumer fetches
+    /// them through the local channel.
+    fn all_exchange_sources_for(
+        &self,
+        output_id: u32,
+        consumer_addr: &HostAddress,
+    ) -> Vec<ExchangeSource> {
         self.tasks
             .iter()
             .map(|(task_id, status_holder)| {
@@ -227,9 +234,11 @@ impl StageExecution {
                     output_id,
                 };
 
+                let host = status_holder.inner.load_full().location.clone().unwrap();
                 ExchangeSource {
                     task_output_id: Some(task_output_id),
-                    host: Some(status_holder.inner.load_full().location.clone().unwrap()),
+                    co_located: &host == consumer_addr,
+                    host: Some(host),
                     local_execute_plan: None,
                 }
             })
@@ -295,8 +304,14 @@ impl StageRunner {
                 stage_id: self.stage.id,
                 task_id: id,
             };
-            let plan_fragment = self.create_plan_fragment(id);
-            futures.push(async { self.schedule_task(task_id, plan_fragment).await });
+            // The worker is picked before the plan is created, so that exchange sources on the
+            // same worker can be tagged as co-located.
+            let worker_node_addr = self.worker_node_manager.next_random()?.host.unwrap();
+            let plan_fragment = self.create_plan_fragment(id, &worker_node_addr);
+            futures.push(async {
+                self.schedule_task(task_id, plan_fragment, worker_node_addr)
+                    .await
+            });
         }
         let mut buffered = stream::iter(futures).buffer_unordered(TASK_SCHEDULING_PARALLELISM);
         while let Some(result) = buffered.next().await {
@@ -309,9 +324,8 @@ impl StageRunner {
         &self,
         task_id: TaskIdProst,
         plan_fragment: PlanFragment,
+        worker_node_addr: HostAddress,
     ) -> SchedulerResult<()> {
-        let worker_node_addr = self.worker_node_manager.next_random()?.host.unwrap();
-
         let compute_client = self
             .compute_client_pool
             .get_client_for_addr((&worker_node_addr).into())
@@ -332,8 +346,12 @@ impl StageRunner {
         Ok(())
     }
 
-    fn create_plan_fragment(&self, task_id: TaskId) -> PlanFragment {
-        let plan_node_prost = self.convert_plan_node(&self.stage.root, task_id);
+    fn create_plan_fragment(
+        &self,
+        task_id: TaskId,
+        worker_node_addr: &HostAddress,
+    ) -> PlanFragment {
+        let plan_node_prost = self.convert_plan_node(&self.stage.root, task_id, worker_node_addr);
         let exchange_info = self.stage.exchange_info.clone();
 
         PlanFragment {
@@ -346,6 +364,7 @@ impl StageRunner {
         &self,
         execution_plan_node: &ExecutionPlanNode,
         task_id: TaskId,
+        worker_node_addr: &HostAddress,
     ) -> PlanNodeProst {
         match execution_plan_node.plan_node_type {
             PlanNodeType::BatchExchange => {
@@ -356,7 +375,9 @@ impl StageRunner {
                     .find(|child_stage| {
                         child_stage.stage.id == execution_plan_node.source_stage_id.unwrap()
                     })
-                    .map(|child_stage| child_stage.all_exchange_sources_for(task_id))
+                    .map(|child_stage| {
+                        child_stage.all_exchange_sources_for(task_id, worker_node_addr)
+                    })
                     .unwrap();
 
                 match &execution_plan_node.node {
@@ -392,7 +413,7 @@ impl StageRunner {
                 let children = execution_plan_node
                     .children
                     .iter()
-                    .map(|e| self.convert_plan_node(e, task_id))
+                    .map(|e| self.convert_plan_node(e, task_id, worker_node_addr))
                     .collect();
 
                 PlanNodeProst {
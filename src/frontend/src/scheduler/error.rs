@@ -35,6 +35,10 @@ pub enum SchedulerError {
 
 impl From<SchedulerError> for RwError {
     fn from(s: SchedulerError) -> Self {
-        ErrorCode::SchedulerError(Box::new(s)).into()
+        match s {
+            // Report the error raised by the compute node as is, with its causal chain.
+            SchedulerError::RpcError(e) => e.into(),
+            s => ErrorCode::SchedulerError(Box::new(s)).into(),
+        }
     }
 }
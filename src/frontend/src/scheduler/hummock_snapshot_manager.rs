@@ -38,9 +38,51 @@ impl HummockSnapshotManager {
         }
     }
 
-    pub async fn get_epoch(&self, query_id: QueryId) -> SchedulerResult<u64> {
+    /// Pins a snapshot no older than `min_epoch` for the batch query `query_id`. All stages of the
+    /// query should read the returned epoch, and the snapshot is unpinned once the returned guard
+    /// is unpinned or dropped. If the frontend dies, meta releases its pinned snapshots after its
+    /// lease expires.
+    pub async fn acquire(
+        self: &Arc<Self>,
+        query_id: &QueryId,
+        min_epoch: u64,
+    ) -> SchedulerResult<PinnedHummockSnapshot> {
+        let epoch = self.get_epoch(query_id.clone(), min_epoch).await?;
+        Ok(PinnedHummockSnapshot {
+            snapshot_manager: self.clone(),
+            query_id: query_id.clone(),
+            epoch,
+            unpinned: false,
+        })
+    }
+
+    /// Pins the snapshot at `epoch` for the batch query `query_id`, if it's still pinned by other
+    /// queries, e.g. the snapshot kept by the HTTP gateway for the following pages of a query.
+    /// Returns `None` otherwise, as the snapshot may have been released.
+    pub async fn acquire_pinned(
+        self: &Arc<Self>,
+        query_id: &QueryId,
+        epoch: u64,
+    ) -> Option<PinnedHummockSnapshot> {
+        let mut core_guard = self.core.lock().await;
+        let query_ids = core_guard.epoch_to_query_ids.get_mut(&epoch)?;
+        if query_ids.is_empty() {
+            return None;
+        }
+        query_ids.insert(query_id.clone());
+        Some(PinnedHummockSnapshot {
+            snapshot_manager: self.clone(),
+            query_id: query_id.clone(),
+            epoch,
+            unpinned: false,
+        })
+    }
+
+    pub async fn get_epoch(&self, query_id: QueryId, min_epoch: u64) -> SchedulerResult<u64> {
         let mut core_guard = self.core.lock().await;
-        if core_guard.is_outdated {
+        // The last pinned snapshot may lag behind `min_epoch` if the notification of the newly
+        // committed epoch hasn't arrived yet. Pin a new one from meta in this case.
+        if core_guard.is_outdated || core_guard.last_pinned < min_epoch {
             let epoch = self
                 .meta_client
                 .pin_snapshot(core_guard.last_pinned)
@@ -48,7 +90,7 @@ impl HummockSnapshotManager {
                 .map_err(|_e| PinSnapshot(query_id.clone(), core_guard.last_pinned))?;
             core_guard.is_outdated = false;
             core_guard.last_pinned = epoch;
-            core_guard.epoch_to_query_ids.insert(epoch, HashSet::new());
+            core_guard.epoch_to_query_ids.entry(epoch).or_default();
         }
         let last_pinned = core_guard.last_pinned;
         core_guard
@@ -98,10 +140,15 @@ impl HummockSnapshotManager {
                     tokio::spawn(
                         async move { meta_client.unpin_snapshot_before(epoch_inner).await },
                     );
-                if let Err(join_error) = handle.await && join_error.is_panic() {
+                if let Err(join_error) = handle.await
+                    && join_error.is_panic()
+                {
                     error!("Request meta to unpin snapshot panic {:?}!", join_error);
                 } else {
-                    tracing::info!("Unpin epoch RPC succeed in frontend manager {:?}", epoch_inner);
+                    tracing::info!(
+                        "Unpin epoch RPC succeed in frontend manager {:?}",
+                        epoch_inner
+                    );
                 }
             });
         }
@@ -117,6 +164,48 @@ impl HummockSnapshotManager {
     }
 }
 
+/// A snapshot pinned for a batch query by [`HummockSnapshotManager::acquire`]. It's unpinned in
+/// background when dropped without being unpinned explicitly, e.g. the query fails or is
+/// cancelled.
+pub struct PinnedHummockSnapshot {
+    snapshot_manager: HummockSnapshotManagerRef,
+    query_id: QueryId,
+    epoch: u64,
+    unpinned: bool,
+}
+
+impl PinnedHummockSnapshot {
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    pub async fn unpin(mut self) -> SchedulerResult<()> {
+        self.unpinned = true;
+        self.snapshot_manager
+            .unpin_snapshot(self.epoch, &self.query_id)
+            .await
+    }
+}
+
+impl Drop for PinnedHummockSnapshot {
+    fn drop(&mut self) {
+        if self.unpinned {
+            return;
+        }
+        let snapshot_manager = self.snapshot_manager.clone();
+        let query_id = self.query_id.clone();
+        let epoch = self.epoch;
+        tokio::spawn(async move {
+            if let Err(e) = snapshot_manager.unpin_snapshot(epoch, &query_id).await {
+                error!(
+                    "Failed to unpin snapshot {} of query {:?}: {:?}",
+                    epoch, query_id, e
+                );
+            }
+        });
+    }
+}
+
 #[derive(Default)]
 struct HummockSnapshotManagerCore {
     is_outdated: bool,
@@ -135,3 +224,174 @@ impl HummockSnapshotManagerCore {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+
+    use risingwave_pb::data::ConfigChange;
+    use risingwave_pb::ddl_service::CatalogSnapshot;
+    use risingwave_pb::hummock::TableStorageStats;
+    use risingwave_pb::meta::TelemetryReport;
+    use risingwave_rpc_client::error::Result as RpcResult;
+
+    use super::HummockSnapshotManager;
+    use crate::meta_client::FrontendMetaClient;
+    use crate::scheduler::plan_fragmenter::QueryId;
+    use crate::test_utils::MockFrontendMetaClient;
+
+    /// Commits a new epoch on each flush.
+    #[derive(Default)]
+    struct FlushingMetaClient {
+        committed_epoch: AtomicU64,
+    }
+
+    #[async_trait::async_trait]
+    impl FrontendMetaClient for FlushingMetaClient {
+        async fn pin_snapshot(&self, _epoch: u64) -> RpcResult<u64> {
+            Ok(self.committed_epoch.load(Ordering::Relaxed))
+        }
+
+        async fn flush(&self) -> RpcResult<u64> {
+            Ok(self.committed_epoch.fetch_add(1, Ordering::Relaxed) + 1)
+        }
+
+        async fn unpin_snapshot(&self, _epoch: u64) -> RpcResult<()> {
+            Ok(())
+        }
+
+        async fn unpin_snapshot_before(&self, _epoch: u64) -> RpcResult<()> {
+            Ok(())
+        }
+
+        async fn get_table_storage_stats(&self) -> RpcResult<Vec<TableStorageStats>> {
+            Ok(vec![])
+        }
+
+        async fn set_system_param(&self, _param: String, _value: String) -> RpcResult<()> {
+            Ok(())
+        }
+
+        async fn savepoint(&self, _table_id: u32) -> RpcResult<u64> {
+            Ok(self.committed_epoch.load(Ordering::Relaxed))
+        }
+
+        async fn restore_savepoint(&self, _table_id: u32) -> RpcResult<()> {
+            Ok(())
+        }
+
+        async fn release_savepoint(&self, _table_id: u32) -> RpcResult<()> {
+            Ok(())
+        }
+
+        async fn reconfigure(&self, _table_id: u32, _change: ConfigChange) -> RpcResult<()> {
+            Ok(())
+        }
+
+        async fn get_telemetry_report(&self) -> RpcResult<TelemetryReport> {
+            Ok(TelemetryReport::default())
+        }
+
+        async fn get_catalog(&self) -> RpcResult<CatalogSnapshot> {
+            Ok(CatalogSnapshot::default())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pinned_snapshot() {
+        let manager = Arc::new(HummockSnapshotManager::new(Arc::new(
+            MockFrontendMetaClient {},
+        )));
+        let query_1 = QueryId {
+            id: "query_1".to_string(),
+        };
+        let query_2 = QueryId {
+            id: "query_2".to_string(),
+        };
+
+        // Queries share the pinned snapshot before it's outdated.
+        let snapshot_1 = manager.acquire(&query_1, 0).await.unwrap();
+        let snapshot_2 = manager.acquire(&query_2, 0).await.unwrap();
+        assert_eq!(snapshot_1.epoch(), snapshot_2.epoch());
+        assert_eq!(manager.core.lock().await.epoch_to_query_ids.len(), 1);
+
+        snapshot_1.unpin().await.unwrap();
+        assert_eq!(manager.core.lock().await.epoch_to_query_ids.len(), 1);
+
+        // The snapshot is unpinned in background when dropped.
+        drop(snapshot_2);
+        for _ in 0..100 {
+            if manager.core.lock().await.epoch_to_query_ids.is_empty() {
+                break;
+            }
+            tokio::task::yield_now().await;
+        }
+        let core = manager.core.lock().await;
+        assert!(core.epoch_to_query_ids.is_empty());
+        assert!(core.is_outdated);
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_no_older_than_min_epoch() {
+        let meta_client = Arc::new(FlushingMetaClient::default());
+        let manager = Arc::new(HummockSnapshotManager::new(meta_client.clone()));
+        let query_1 = QueryId {
+            id: "query_1".to_string(),
+        };
+        let query_2 = QueryId {
+            id: "query_2".to_string(),
+        };
+        let query_3 = QueryId {
+            id: "query_3".to_string(),
+        };
+
+        let snapshot_1 = manager.acquire(&query_1, 0).await.unwrap();
+        assert_eq!(snapshot_1.epoch(), 0);
+
+        // The committed epoch isn't notified yet, so the pinned snapshot is reused unless the
+        // query requires a newer one.
+        let write_epoch = meta_client.flush().await.unwrap();
+        let snapshot_2 = manager.acquire(&query_2, 0).await.unwrap();
+        assert_eq!(snapshot_2.epoch(), 0);
+        let snapshot_3 = manager.acquire(&query_3, write_epoch).await.unwrap();
+        assert_eq!(snapshot_3.epoch(), write_epoch);
+
+        let core = manager.core.lock().await;
+        assert_eq!(core.last_pinned, write_epoch);
+        assert_eq!(core.epoch_to_query_ids.len(), 2);
+        assert_eq!(core.epoch_to_query_ids[&0].len(), 2);
+        assert_eq!(core.epoch_to_query_ids[&write_epoch].len(), 1);
+        drop(core);
+
+        snapshot_1.unpin().await.unwrap();
+        snapshot_2.unpin().await.unwrap();
+        snapshot_3.unpin().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_acquire_pinned_snapshot() {
+        let meta_client = Arc::new(FlushingMetaClient::default());
+        let manager = Arc::new(HummockSnapshotManager::new(meta_client.clone()));
+        let query_1 = QueryId {
+            id: "query_1".to_string(),
+        };
+        let query_2 = QueryId {
+            id: "query_2".to_string(),
+        };
+
+        let snapshot_1 = manager.acquire(&query_1, 0).await.unwrap();
+        let write_epoch = meta_client.flush().await.unwrap();
+        assert!(manager
+            .acquire_pinned(&query_2, write_epoch)
+            .await
+            .is_none());
+
+        // The snapshot is kept pinned by the second query after the first one unpins it.
+        let snapshot_2 = manager.acquire_pinned(&query_2, 0).await.unwrap();
+        snapshot_1.unpin().await.unwrap();
+        assert_eq!(manager.core.lock().await.epoch_to_query_ids[&0].len(), 1);
+        snapshot_2.unpin().await.unwrap();
+        assert!(manager.acquire_pinned(&query_1, 0).await.is_none());
+    }
+}
@@ -13,10 +13,10 @@
 // limitations under the License.
 
 //! Local execution for batch query.
-use std::collections::HashMap;
 use std::sync::Arc;
 
 use futures_async_stream::try_stream;
+use madsim::collections::HashMap;
 use risingwave_batch::executor::ExecutorBuilder;
 use risingwave_batch::task::TaskId;
 use risingwave_common::array::DataChunk;
@@ -35,7 +35,7 @@ use uuid::Uuid;
 use crate::optimizer::plan_node::PlanNodeType;
 use crate::scheduler::plan_fragmenter::{ExecutionPlanNode, Query, StageId};
 use crate::scheduler::task_context::FrontendBatchTaskContext;
-use crate::scheduler::SchedulerResult;
+use crate::scheduler::{PinnedHummockSnapshot, SchedulerResult};
 use crate::session::{AuthContext, FrontendEnv};
 
 pub struct LocalQueryExecution {
@@ -43,6 +43,10 @@ pub struct LocalQueryExecution {
     query: Query,
     front_env: FrontendEnv,
     epoch: Option<u64>,
+    /// The snapshot read by the query should be no older than this epoch.
+    min_epoch: u64,
+    /// The snapshot pinned by the caller to read, instead of pinning one on start.
+    pinned_snapshot: Option<PinnedHummockSnapshot>,
 
     auth_context: Arc<AuthContext>,
 }
@@ -53,16 +57,25 @@ impl LocalQueryExecution {
         front_env: FrontendEnv,
         sql: S,
         auth_context: Arc<AuthContext>,
+        min_epoch: u64,
     ) -> Self {
         Self {
             sql: sql.into(),
             query,
             front_env,
             epoch: None,
+            min_epoch,
+            pinned_snapshot: None,
             auth_context,
         }
     }
 
+    /// Reads the given snapshot, which is unpinned once the query finishes.
+    pub fn with_pinned_snapshot(mut self, pinned_snapshot: PinnedHummockSnapshot) -> Self {
+        self.pinned_snapshot = Some(pinned_snapshot);
+        self
+    }
+
     #[try_stream(ok = DataChunk, error = RwError)]
     pub async fn run(mut self) {
         debug!(
@@ -81,11 +94,18 @@ impl LocalQueryExecution {
             task_id: 0,
         };
 
-        let epoch = self
-            .front_env
-            .hummock_snapshot_manager()
-            .get_epoch(query_id)
-            .await?;
+        // The snapshot is pinned until the query finishes, so that all the scans read the same
+        // epoch.
+        let pinned_snapshot = match self.pinned_snapshot.take() {
+            Some(pinned_snapshot) => pinned_snapshot,
+            None => {
+                self.front_env
+                    .hummock_snapshot_manager()
+                    .acquire(&query_id, self.min_epoch)
+                    .await?
+            }
+        };
+        let epoch = pinned_snapshot.epoch();
         self.epoch = Some(epoch);
         let plan_fragment = self.create_plan_fragment()?;
         let plan_node = plan_fragment.root.unwrap();
@@ -96,6 +116,7 @@ impl LocalQueryExecution {
         for chunk in executor.execute() {
             yield chunk?;
         }
+        pinned_snapshot.unpin().await?;
     }
 
     /// Convert query to plan fragment.
@@ -213,6 +234,7 @@ impl LocalQueryExecution {
                                 }),
                                 host: Some(worker_node.host.as_ref().unwrap().clone()),
                                 local_execute_plan: Some(Plan(local_execute_plan.clone())),
+                                co_located: false,
                             };
                             exchange_source
                         }),
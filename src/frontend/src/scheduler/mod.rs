@@ -27,9 +27,11 @@ pub use distributed::QueryManager;
 mod hummock_snapshot_manager;
 pub use hummock_snapshot_manager::*;
 mod plan_fragmenter;
-pub use plan_fragmenter::BatchPlanFragmenter;
+pub use plan_fragmenter::{BatchPlanFragmenter, ExecutionPlanNode, Query, QueryId, StageId};
 mod local;
 pub use local::*;
+mod query_limits;
+pub use query_limits::QueryLimits;
 mod error;
 mod task_context;
 pub mod worker_node_manager;
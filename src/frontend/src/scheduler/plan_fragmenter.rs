@@ -12,10 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::{HashMap, HashSet};
 use std::fmt::{Debug, Formatter};
 use std::sync::Arc;
 
+use madsim::collections::{HashMap, HashSet};
 use risingwave_pb::batch_plan::plan_node::NodeBody;
 use risingwave_pb::batch_plan::ExchangeInfo;
 use risingwave_pb::plan_common::Field as FieldProst;
@@ -144,6 +144,37 @@ impl Query {
         &self.query_id
     }
 
+    /// Returns a copy of the query with a new query id, so that a cached query can be scheduled
+    /// again.
+    pub fn clone_with_new_id(&self) -> Self {
+        let query_id = QueryId::default();
+        let stages = self
+            .stage_graph
+            .stages
+            .iter()
+            .map(|(stage_id, stage)| {
+                let stage = QueryStage {
+                    query_id: query_id.clone(),
+                    id: stage.id,
+                    root: stage.root.clone(),
+                    exchange_info: stage.exchange_info.clone(),
+                    parallelism: stage.parallelism,
+                    has_table_scan: stage.has_table_scan,
+                };
+                (*stage_id, Arc::new(stage))
+            })
+            .collect();
+        Self {
+            query_id,
+            stage_graph: StageGraph {
+                root_stage_id: self.stage_graph.root_stage_id,
+                stages,
+                child_edges: self.stage_graph.child_edges.clone(),
+                parent_edges: self.stage_graph.parent_edges.clone(),
+            },
+        }
+    }
+
     pub fn stages_with_table_scan(&self) -> HashSet<StageId> {
         self.stage_graph
             .stages
@@ -327,7 +358,10 @@ impl BatchPlanFragmenter {
         self.next_stage_id += 1;
         let parallelism = match root.distribution() {
             Distribution::Single => 1,
-            _ => self.worker_node_manager.worker_node_count(),
+            _ => match root.ctx().inner().hints.parallelism {
+                Some(parallelism) => parallelism as usize,
+                None => self.worker_node_manager.worker_node_count(),
+            },
         };
 
         let mut builder = QueryStageBuilder::new(
@@ -393,10 +427,10 @@ impl BatchPlanFragmenter {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::{HashMap, HashSet};
     use std::rc::Rc;
     use std::sync::Arc;
 
+    use madsim::collections::{HashMap, HashSet};
     use risingwave_common::catalog::{ColumnDesc, TableDesc};
     use risingwave_common::types::DataType;
     use risingwave_pb::batch_plan::plan_node::NodeBody;
@@ -454,6 +488,7 @@ mod tests {
                 ],
                 distribution_keys: vec![],
                 appendonly: false,
+                watermark: None,
             }),
             vec![],
             ctx,
@@ -530,6 +565,7 @@ mod tests {
             }),
             state: risingwave_pb::common::worker_node::State::Running as i32,
             parallel_units: generate_parallel_units(0, 0),
+            resource_group: "default".to_string(),
         };
         let worker2 = WorkerNode {
             id: 1,
@@ -540,6 +576,7 @@ mod tests {
             }),
             state: risingwave_pb::common::worker_node::State::Running as i32,
             parallel_units: generate_parallel_units(8, 1),
+            resource_group: "default".to_string(),
         };
         let worker3 = WorkerNode {
             id: 2,
@@ -550,6 +587,7 @@ mod tests {
             }),
             state: risingwave_pb::common::worker_node::State::Running as i32,
             parallel_units: generate_parallel_units(16, 2),
+            resource_group: "default".to_string(),
         };
         let workers = vec![worker1, worker2, worker3];
         let worker_node_manager = Arc::new(WorkerNodeManager::mock(workers));
@@ -632,6 +670,20 @@ mod tests {
         assert_eq!(scan_node2.root.source_stage_id, None);
         assert_eq!(1, scan_node2.root.children.len());
         assert!(scan_node2.has_table_scan);
+
+        // A cloned query has the same stages under a new query id.
+        let cloned = query.clone_with_new_id();
+        assert_ne!(cloned.query_id, query.query_id);
+        assert_eq!(cloned.stage_graph.stages.len(), 4);
+        assert_eq!(
+            cloned.stage_graph.child_edges,
+            query.stage_graph.child_edges
+        );
+        assert!(cloned
+            .stage_graph
+            .stages
+            .values()
+            .all(|stage| stage.query_id == cloned.query_id));
     }
 
     fn generate_parallel_units(start_id: u32, node_id: u32) -> Vec<ParallelUnit> {
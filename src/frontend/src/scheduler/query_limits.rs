@@ -0,0 +1,153 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use futures::StreamExt;
+use futures_async_stream::try_stream;
+use risingwave_batch::executor::BoxedDataChunkStream;
+use risingwave_common::array::DataChunk;
+use risingwave_common::error::{ErrorCode, Result, RwError};
+use risingwave_pb::user::UserLimits;
+
+/// Limits on the resources used by a batch query, the stricter ones of the session configurations
+/// and the limits of the user set by `ALTER USER ... SET`, so that one user can't monopolize the
+/// cluster or the frontend.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct QueryLimits {
+    pub statement_timeout: Option<Duration>,
+    pub max_result_rows: Option<u64>,
+    pub memory_limit_bytes: Option<u64>,
+}
+
+impl QueryLimits {
+    pub fn new(session: &UserLimits, user: &UserLimits) -> Self {
+        // 0 means unlimited.
+        let stricter = |a: u64, b: u64| [a, b].into_iter().filter(|v| *v > 0).min();
+        Self {
+            statement_timeout: stricter(session.statement_timeout_ms, user.statement_timeout_ms)
+                .map(Duration::from_millis),
+            max_result_rows: stricter(session.max_result_rows, user.max_result_rows),
+            memory_limit_bytes: stricter(session.memory_limit_bytes, user.memory_limit_bytes),
+        }
+    }
+
+    /// Runs `fut` with the statement timeout counted from `start`.
+    pub async fn with_timeout<T>(
+        &self,
+        start: Instant,
+        fut: impl Future<Output = Result<T>>,
+    ) -> Result<T> {
+        match self.statement_timeout {
+            Some(timeout) => {
+                let remaining = timeout.saturating_sub(start.elapsed());
+                tokio::time::timeout(remaining, fut).await.map_err(|_| {
+                    ErrorCode::ResourceLimitExceeded(format!(
+                        "statement timeout of {}ms",
+                        timeout.as_millis()
+                    ))
+                })?
+            }
+            None => fut.await,
+        }
+    }
+
+    /// Enforces the statement timeout and the row limit on the result of the query started at
+    /// `start`. The query is cancelled once the returned stream is dropped on an error.
+    #[try_stream(boxed, ok = DataChunk, error = RwError)]
+    pub async fn enforce(self, mut stream: BoxedDataChunkStream, start: Instant) {
+        let mut rows = 0;
+        while let Some(chunk) = self
+            .with_timeout(start, async { Ok(stream.next().await) })
+            .await?
+        {
+            let chunk = chunk?;
+            rows += chunk.cardinality() as u64;
+            if let Some(max_result_rows) = self.max_result_rows
+                && rows > max_result_rows
+            {
+                return Err(ErrorCode::ResourceLimitExceeded(format!(
+                    "more than {} result rows",
+                    max_result_rows
+                ))
+                .into());
+            }
+            yield chunk;
+        }
+    }
+
+    /// Checks the bytes of the result buffered in the frontend against the memory limit.
+    pub fn check_memory(&self, bytes: u64) -> Result<()> {
+        match self.memory_limit_bytes {
+            Some(limit) if bytes > limit => Err(ErrorCode::ResourceLimitExceeded(format!(
+                "result of more than {} bytes",
+                limit
+            ))
+            .into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::stream;
+    use risingwave_common::array::DataChunkTestExt;
+
+    use super::*;
+
+    #[test]
+    fn test_query_limits() {
+        let session = UserLimits {
+            statement_timeout_ms: 1000,
+            max_result_rows: 0,
+            memory_limit_bytes: 0,
+        };
+        let user = UserLimits {
+            statement_timeout_ms: 2000,
+            max_result_rows: 10,
+            memory_limit_bytes: 0,
+        };
+        let limits = QueryLimits::new(&session, &user);
+        assert_eq!(
+            limits,
+            QueryLimits {
+                statement_timeout: Some(Duration::from_secs(1)),
+                max_result_rows: Some(10),
+                memory_limit_bytes: None,
+            }
+        );
+        assert!(limits.check_memory(u64::MAX).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_enforce_max_result_rows() {
+        let limits = QueryLimits {
+            max_result_rows: Some(3),
+            ..Default::default()
+        };
+        let chunk = || {
+            DataChunk::from_pretty(
+                "i
+                 1
+                 2",
+            )
+        };
+        let stream = stream::iter(vec![Ok(chunk()), Ok(chunk())]).boxed();
+        let results: Vec<_> = limits.enforce(stream, Instant::now()).collect().await;
+        assert!(results[0].is_ok());
+        assert!(results[1].is_err());
+    }
+}
@@ -19,6 +19,7 @@ use risingwave_batch::task::{BatchTaskContext, TaskOutput, TaskOutputId};
 use risingwave_common::catalog::SysCatalogReaderRef;
 use risingwave_common::error::Result;
 use risingwave_common::util::addr::{is_local_address, HostAddr};
+use risingwave_rpc_client::ComputeClientPoolRef;
 use risingwave_source::SourceManagerRef;
 
 use crate::catalog::pg_catalog::SysCatalogReaderImpl;
@@ -47,7 +48,9 @@ impl BatchTaskContext for FrontendBatchTaskContext {
             self.env.catalog_reader().clone(),
             self.env.user_info_reader().clone(),
             self.env.worker_node_manager_ref(),
+            self.env.meta_client_ref(),
             self.auth_context.clone(),
+            self.env.audit_logger_ref(),
         )))
     }
 
@@ -55,6 +58,10 @@ impl BatchTaskContext for FrontendBatchTaskContext {
         is_local_address(self.env.server_address(), peer_addr)
     }
 
+    fn client_pool(&self) -> ComputeClientPoolRef {
+        self.env.compute_client_pool_ref()
+    }
+
     fn source_manager_ref(&self) -> Option<SourceManagerRef> {
         todo!()
     }
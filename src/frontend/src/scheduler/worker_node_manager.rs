@@ -17,12 +17,23 @@ use std::sync::{Arc, RwLock};
 use rand::distributions::{Distribution as RandDistribution, Uniform};
 use risingwave_common::bail;
 use risingwave_pb::common::WorkerNode;
+use risingwave_pb::meta::subscribe_response::Operation;
 
 use crate::scheduler::SchedulerResult;
 
-/// `WorkerNodeManager` manages live worker nodes.
+pub type NotificationVersion = u64;
+
+/// `WorkerNodeManager` manages live worker nodes. It's a local cache of the worker topology kept
+/// up to date by meta's notifications, so that scheduling doesn't need to ask meta per query.
 pub struct WorkerNodeManager {
-    worker_nodes: RwLock<Vec<WorkerNode>>,
+    inner: RwLock<WorkerNodeManagerInner>,
+}
+
+struct WorkerNodeManagerInner {
+    worker_nodes: Vec<WorkerNode>,
+    /// The notification version of the last applied change. Changes with a version not newer than
+    /// it are stale and ignored.
+    version: NotificationVersion,
 }
 
 pub type WorkerNodeManagerRef = Arc<WorkerNodeManager>;
@@ -35,36 +46,81 @@ impl Default for WorkerNodeManager {
 
 impl WorkerNodeManager {
     pub fn new() -> Self {
-        let worker_nodes = RwLock::new(Vec::new());
-        Self { worker_nodes }
+        Self::mock(Vec::new())
     }
 
     /// Used in tests.
     pub fn mock(worker_nodes: Vec<WorkerNode>) -> Self {
-        let worker_nodes = RwLock::new(worker_nodes);
-        Self { worker_nodes }
+        let inner = RwLock::new(WorkerNodeManagerInner {
+            worker_nodes,
+            version: 0,
+        });
+        Self { inner }
     }
 
     pub fn list_worker_nodes(&self) -> Vec<WorkerNode> {
-        self.worker_nodes.read().unwrap().clone()
+        self.inner.read().unwrap().worker_nodes.clone()
+    }
+
+    /// Get the version of the cached worker topology.
+    pub fn version(&self) -> NotificationVersion {
+        self.inner.read().unwrap().version
     }
 
+    /// Adds or replaces the worker node with the same id.
     pub fn add_worker_node(&self, node: WorkerNode) {
-        self.worker_nodes.write().unwrap().push(node);
+        let mut write_guard = self.inner.write().unwrap();
+        let worker_nodes = &mut write_guard.worker_nodes;
+        match worker_nodes.iter().position(|x| x.id == node.id) {
+            Some(idx) => worker_nodes[idx] = node,
+            None => worker_nodes.push(node),
+        }
     }
 
     pub fn remove_worker_node(&self, node: WorkerNode) {
-        self.worker_nodes.write().unwrap().retain(|x| *x != node);
+        self.inner
+            .write()
+            .unwrap()
+            .worker_nodes
+            .retain(|x| x.id != node.id);
+    }
+
+    /// Applies a worker membership delta notified with `version`. Returns false if the delta is
+    /// stale, i.e. already covered by the cached topology.
+    pub fn apply_delta(
+        &self,
+        operation: Operation,
+        node: WorkerNode,
+        version: NotificationVersion,
+    ) -> bool {
+        let mut write_guard = self.inner.write().unwrap();
+        if version <= write_guard.version {
+            return false;
+        }
+        let worker_nodes = &mut write_guard.worker_nodes;
+        match operation {
+            Operation::Add => match worker_nodes.iter().position(|x| x.id == node.id) {
+                Some(idx) => worker_nodes[idx] = node,
+                None => worker_nodes.push(node),
+            },
+            Operation::Delete => worker_nodes.retain(|x| x.id != node.id),
+            _ => (),
+        }
+        write_guard.version = version;
+        true
     }
 
-    pub fn refresh_worker_node(&self, nodes: Vec<WorkerNode>) {
-        let mut write_guard = self.worker_nodes.write().unwrap();
-        *write_guard = nodes;
+    /// Replaces the cached topology with a snapshot from meta taken at `version`.
+    pub fn refresh_worker_node(&self, nodes: Vec<WorkerNode>, version: NotificationVersion) {
+        let mut write_guard = self.inner.write().unwrap();
+        write_guard.worker_nodes = nodes;
+        write_guard.version = version;
     }
 
     /// Get a random worker node.
     pub fn next_random(&self) -> SchedulerResult<WorkerNode> {
-        let current_nodes = self.worker_nodes.read().unwrap();
+        let read_guard = self.inner.read().unwrap();
+        let current_nodes = &read_guard.worker_nodes;
         let mut rng = rand::thread_rng();
         if current_nodes.is_empty() {
             tracing::error!("No worker node available.");
@@ -76,7 +132,7 @@ impl WorkerNodeManager {
     }
 
     pub fn worker_node_count(&self) -> usize {
-        self.worker_nodes.read().unwrap().len()
+        self.inner.read().unwrap().worker_nodes.len()
     }
 }
 
@@ -101,6 +157,7 @@ mod tests {
                 host: Some(HostAddr::try_from("127.0.0.1:1234").unwrap().to_protobuf()),
                 state: worker_node::State::Running as i32,
                 parallel_units: vec![],
+                resource_group: "default".to_string(),
             },
             WorkerNode {
                 id: 2,
@@ -108,6 +165,7 @@ mod tests {
                 host: Some(HostAddr::try_from("127.0.0.1:1235").unwrap().to_protobuf()),
                 state: worker_node::State::Running as i32,
                 parallel_units: vec![],
+                resource_group: "default".to_string(),
             },
         ];
         worker_nodes
@@ -116,11 +174,27 @@ mod tests {
         assert_eq!(manager.worker_node_count(), 2);
         assert_eq!(manager.list_worker_nodes(), worker_nodes);
 
-        manager.remove_worker_node(worker_nodes[0].clone());
+        // Evicted nodes are notified with the `Deleting` state.
+        manager.remove_worker_node(WorkerNode {
+            state: worker_node::State::Deleting as i32,
+            ..worker_nodes[0].clone()
+        });
         assert_eq!(manager.worker_node_count(), 1);
         assert_eq!(
             manager.list_worker_nodes(),
             worker_nodes.as_slice()[1..].to_vec()
         );
+
+        // Deltas older than the snapshot are ignored.
+        manager.refresh_worker_node(worker_nodes.clone(), 10);
+        assert!(!manager.apply_delta(Operation::Delete, worker_nodes[0].clone(), 9));
+        assert_eq!(manager.worker_node_count(), 2);
+        assert!(manager.apply_delta(Operation::Delete, worker_nodes[0].clone(), 11));
+        assert_eq!(manager.worker_node_count(), 1);
+        assert_eq!(manager.version(), 11);
+
+        // Re-adding a node replaces the cached one instead of duplicating it.
+        assert!(manager.apply_delta(Operation::Add, worker_nodes[1].clone(), 12));
+        assert_eq!(manager.worker_node_count(), 1);
     }
 }
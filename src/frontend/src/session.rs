@@ -12,46 +12,66 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::Formatter;
+use std::future::Future;
 use std::io::{Error, ErrorKind};
 use std::marker::Sync;
-use std::path::PathBuf;
-use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicI32, AtomicU64, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use parking_lot::RwLock;
+use itertools::Itertools;
+use parking_lot::{Mutex, RwLock};
+use pgwire::error::PgError;
 use pgwire::pg_field_descriptor::PgFieldDescriptor;
 use pgwire::pg_response::PgResponse;
-use pgwire::pg_server::{BoxedError, Session, SessionManager, UserAuthenticator};
+use pgwire::pg_server::{BoxedError, Notification, Session, SessionManager, UserAuthenticator};
 use rand::RngCore;
 #[cfg(test)]
 use risingwave_common::catalog::{DEFAULT_DATABASE_NAME, DEFAULT_SUPPER_USER};
-use risingwave_common::config::FrontendConfig;
+use risingwave_common::config::{RwConfig, ServerConfig, StorageQuotaConfig};
 use risingwave_common::error::{ErrorCode, Result, RwError};
-use risingwave_common::session_config::{DELTA_JOIN, IMPLICIT_FLUSH, QUERY_MODE};
+use risingwave_common::session_config::{
+    DELTA_JOIN, DELTA_JOIN_WITHOUT_INDEX, IMPLICIT_FLUSH, IVF_PROBES, MAX_RESULT_ROWS,
+    MEMORY_LIMIT, MV_REUSE, QUERY_MODE, STATEMENT_TIMEOUT, TWO_PHASE_AGG,
+};
 use risingwave_common::util::addr::HostAddr;
 use risingwave_pb::common::WorkerType;
 use risingwave_pb::user::auth_info::EncryptionType;
-use risingwave_rpc_client::{ComputeClientPool, MetaClient};
+use risingwave_pb::user::UserLimits;
+use risingwave_rpc_client::{ComputeClientPool, ComputeClientPoolRef, MetaClient};
 use risingwave_sqlparser::ast::Statement;
 use risingwave_sqlparser::parser::Parser;
 use tokio::sync::oneshot::Sender;
 use tokio::sync::watch;
 use tokio::task::JoinHandle;
 
+use crate::audit_log::{
+    is_ddl, redact_sql, AuditLogger, AuditLoggerRef, QueryAuditInfo, StatementAudit,
+};
 use crate::binder::Binder;
 use crate::catalog::catalog_service::{CatalogReader, CatalogWriter, CatalogWriterImpl};
 use crate::catalog::root_catalog::Catalog;
 use crate::handler::handle;
 use crate::handler::util::to_pg_field;
+use crate::insert_batcher::{InsertBatcher, InsertBatcherRef};
+use crate::listener::SessionListeners;
 use crate::meta_client::{FrontendMetaClient, FrontendMetaClientImpl};
+use crate::monitor::{statement_label, FrontendMetrics};
 use crate::observer::observer_manager::ObserverManager;
+use crate::optimizer::plan_hint::PlanHints;
 use crate::optimizer::plan_node::PlanNodeId;
+use crate::optimizer::PlanRef;
+use crate::plan_cache::{PlanCache, PlanCacheRef};
 use crate::planner::Planner;
+use crate::replay::{ReplayCapture, ReplayCapturer, ReplayCapturerRef, StatementReplay};
+use crate::result_cache::{ResultCache, ResultCacheRef};
 use crate::scheduler::worker_node_manager::{WorkerNodeManager, WorkerNodeManagerRef};
-use crate::scheduler::{HummockSnapshotManager, HummockSnapshotManagerRef, QueryManager};
+use crate::scheduler::{
+    HummockSnapshotManager, HummockSnapshotManagerRef, QueryLimits, QueryManager,
+};
+use crate::subscription::{SubscriptionManager, SubscriptionManagerRef};
 use crate::test_utils::MockUserInfoWriter;
 use crate::user::user_authentication::md5_hash_with_salt;
 use crate::user::user_manager::UserInfoManager;
@@ -64,6 +84,12 @@ pub struct OptimizerContext {
     pub next_id: AtomicI32,
     /// For debugging purposes, store the SQL string in Context
     pub sql: Arc<str>,
+    /// Optimizer hints given in the SQL comments.
+    pub hints: PlanHints,
+    /// Whether to record the optimizer trace, set by `EXPLAIN (TRACE)`.
+    pub explain_trace: bool,
+    /// The applied optimizer rules and intermediate plans, recorded if `explain_trace` is set.
+    pub optimizer_trace: parking_lot::Mutex<Vec<String>>,
 }
 
 #[derive(Clone, Debug)]
@@ -92,14 +118,41 @@ impl OptimizerContextRef {
         let next_id = self.inner.next_id.fetch_add(1, Ordering::Relaxed);
         PlanNodeId(next_id)
     }
+
+    pub fn is_explain_trace(&self) -> bool {
+        self.inner.explain_trace
+    }
+
+    /// Records a step of the optimizer if `EXPLAIN (TRACE)` is requested.
+    pub fn trace(&self, message: impl Into<String>) {
+        if self.inner.explain_trace {
+            self.inner.optimizer_trace.lock().push(message.into());
+        }
+    }
+
+    /// Records the plan after an optimization stage if `EXPLAIN (TRACE)` is requested.
+    pub fn trace_plan(&self, stage: &str, plan: &PlanRef) {
+        if self.inner.explain_trace {
+            let plan = plan.explain_to_string().unwrap_or_default();
+            self.trace(format!("{}:\n{}", stage, plan));
+        }
+    }
+
+    pub fn take_trace(&self) -> Vec<String> {
+        std::mem::take(&mut *self.inner.optimizer_trace.lock())
+    }
 }
 
 impl OptimizerContext {
     pub fn new(session_ctx: Arc<SessionImpl>, sql: Arc<str>) -> Self {
+        let hints = PlanHints::parse(&sql);
         Self {
             session_ctx,
             next_id: AtomicI32::new(0),
             sql,
+            hints,
+            explain_trace: false,
+            optimizer_trace: Default::default(),
         }
     }
 
@@ -110,6 +163,9 @@ impl OptimizerContext {
             session_ctx: Arc::new(SessionImpl::mock()),
             next_id: AtomicI32::new(0),
             sql: Arc::from(""),
+            hints: PlanHints::default(),
+            explain_trace: false,
+            optimizer_trace: Default::default(),
         }
         .into()
     }
@@ -125,13 +181,8 @@ impl std::fmt::Debug for OptimizerContext {
     }
 }
 
-fn load_config(opts: &FrontendOpts) -> FrontendConfig {
-    if opts.config_path.is_empty() {
-        return FrontendConfig::default();
-    }
-
-    let config_path = PathBuf::from(opts.config_path.to_owned());
-    FrontendConfig::init(config_path).unwrap()
+fn load_config(opts: &FrontendOpts) -> RwConfig {
+    risingwave_common::config::load_config(&opts.config_path).unwrap()
 }
 
 /// The global environment for the frontend server.
@@ -147,7 +198,22 @@ pub struct FrontendEnv {
     worker_node_manager: WorkerNodeManagerRef,
     query_manager: QueryManager,
     hummock_snapshot_manager: HummockSnapshotManagerRef,
+    compute_client_pool: ComputeClientPoolRef,
     server_addr: HostAddr,
+    audit_logger: AuditLoggerRef,
+    replay_capturer: ReplayCapturerRef,
+    subscription_manager: SubscriptionManagerRef,
+    frontend_metrics: Arc<FrontendMetrics>,
+    plan_cache: PlanCacheRef,
+    result_cache: ResultCacheRef,
+    insert_batcher: InsertBatcherRef,
+    storage_quota_config: StorageQuotaConfig,
+    server_config: ServerConfig,
+    /// Local directories that `disk://` locations of data lakes must be under, see
+    /// [`risingwave_common::config::BatchConfig::lake_disk_roots`].
+    lake_disk_roots: Vec<String>,
+    /// Whether the frontend belongs to a read replica, see [`FrontendOpts::read_replica`].
+    read_replica: bool,
 }
 
 impl FrontendEnv {
@@ -174,9 +240,10 @@ impl FrontendEnv {
         let query_manager = QueryManager::new(
             worker_node_manager.clone(),
             hummock_snapshot_manager.clone(),
-            compute_client_pool,
+            compute_client_pool.clone(),
         );
         let server_addr = HostAddr::try_from("127.0.0.1:4565").unwrap();
+        let audit_logger = Arc::new(AuditLogger::default());
         Self {
             meta_client,
             catalog_writer,
@@ -186,7 +253,19 @@ impl FrontendEnv {
             worker_node_manager,
             query_manager,
             hummock_snapshot_manager,
+            compute_client_pool,
             server_addr,
+            audit_logger,
+            replay_capturer: Arc::new(ReplayCapturer::default()),
+            subscription_manager: Arc::new(SubscriptionManager::default()),
+            frontend_metrics: Arc::new(FrontendMetrics::unused()),
+            plan_cache: Arc::new(PlanCache::default()),
+            result_cache: Arc::new(ResultCache::default()),
+            insert_batcher: Arc::new(InsertBatcher::default()),
+            storage_quota_config: StorageQuotaConfig::default(),
+            server_config: ServerConfig::default(),
+            lake_disk_roots: vec![],
+            read_replica: false,
         }
     }
 
@@ -230,7 +309,7 @@ impl FrontendEnv {
         let query_manager = QueryManager::new(
             worker_node_manager.clone(),
             hummock_snapshot_manager.clone(),
-            compute_client_pool,
+            compute_client_pool.clone(),
         );
 
         let user_info_manager = Arc::new(RwLock::new(UserInfoManager::default()));
@@ -250,10 +329,17 @@ impl FrontendEnv {
             user_info_manager,
             user_info_updated_tx,
             hummock_snapshot_manager.clone(),
+            opts.read_replica,
         )
         .await;
         let observer_join_handle = observer_manager.start().await?;
 
+        let audit_logger = Arc::new(AuditLogger::new(config.audit_log.clone()));
+        let replay_capturer = Arc::new(ReplayCapturer::new(
+            &config.audit_log.replay_capture_location,
+        ));
+        let frontend_metrics = Arc::new(FrontendMetrics::new(prometheus::Registry::new()));
+
         meta_client.activate(&frontend_address).await?;
 
         Ok((
@@ -266,7 +352,29 @@ impl FrontendEnv {
                 meta_client: frontend_meta_client,
                 query_manager,
                 hummock_snapshot_manager,
+                compute_client_pool,
                 server_addr: frontend_address,
+                audit_logger,
+                replay_capturer,
+                subscription_manager: Arc::new(SubscriptionManager::default()),
+                frontend_metrics,
+                plan_cache: Arc::new(PlanCache::new(
+                    config.batch.plan_cache_capacity,
+                    config.batch.plan_cache_auto_prepare,
+                )),
+                result_cache: Arc::new(ResultCache::new(
+                    config.batch.result_cache_capacity,
+                    config.batch.result_cache_max_rows,
+                    Duration::from_millis(config.batch.result_cache_ttl_ms),
+                )),
+                insert_batcher: Arc::new(InsertBatcher::new(
+                    Duration::from_millis(config.batch.insert_batch_max_delay_ms),
+                    config.batch.insert_batch_max_rows,
+                )),
+                storage_quota_config: config.storage_quota.clone(),
+                server_config: config.server.clone(),
+                lake_disk_roots: config.batch.lake_disk_roots.clone(),
+                read_replica: opts.read_replica,
             },
             observer_join_handle,
             heartbeat_join_handle,
@@ -294,6 +402,10 @@ impl FrontendEnv {
         &self.user_info_reader
     }
 
+    pub fn is_read_replica(&self) -> bool {
+        self.read_replica
+    }
+
     pub fn worker_node_manager(&self) -> &WorkerNodeManager {
         &*self.worker_node_manager
     }
@@ -318,9 +430,57 @@ impl FrontendEnv {
         &self.hummock_snapshot_manager
     }
 
+    pub fn compute_client_pool_ref(&self) -> ComputeClientPoolRef {
+        self.compute_client_pool.clone()
+    }
+
     pub fn server_address(&self) -> &HostAddr {
         &self.server_addr
     }
+
+    pub fn audit_logger(&self) -> &AuditLogger {
+        &*self.audit_logger
+    }
+
+    pub fn audit_logger_ref(&self) -> AuditLoggerRef {
+        self.audit_logger.clone()
+    }
+
+    pub fn replay_capturer(&self) -> &ReplayCapturer {
+        &*self.replay_capturer
+    }
+
+    pub fn subscription_manager(&self) -> &SubscriptionManager {
+        &*self.subscription_manager
+    }
+
+    pub fn frontend_metrics(&self) -> &FrontendMetrics {
+        &*self.frontend_metrics
+    }
+
+    pub fn plan_cache(&self) -> &PlanCache {
+        &*self.plan_cache
+    }
+
+    pub fn result_cache_ref(&self) -> ResultCacheRef {
+        self.result_cache.clone()
+    }
+
+    pub fn insert_batcher_ref(&self) -> InsertBatcherRef {
+        self.insert_batcher.clone()
+    }
+
+    pub fn storage_quota_config(&self) -> &StorageQuotaConfig {
+        &self.storage_quota_config
+    }
+
+    pub fn server_config(&self) -> &ServerConfig {
+        &self.server_config
+    }
+
+    pub fn lake_disk_roots(&self) -> &[String] {
+        &self.lake_disk_roots
+    }
 }
 
 pub struct AuthContext {
@@ -337,6 +497,64 @@ impl AuthContext {
     }
 }
 
+/// A statement being handled by a session, see [`SessionImpl::begin_audited`].
+pub struct AuditedStatement {
+    session: Arc<SessionImpl>,
+    is_ddl: bool,
+    stmt_label: &'static str,
+    sql: String,
+    start: Instant,
+}
+
+impl AuditedStatement {
+    /// Records the metrics, audit log and replay capture of the statement, which failed with
+    /// `error` if any.
+    pub fn finish(self, error: Option<String>) {
+        let session = &self.session;
+        let metrics = session.env.frontend_metrics();
+        let stmt_label = self.stmt_label;
+        let duration = self.start.elapsed();
+        metrics
+            .statement_count
+            .with_label_values(&[stmt_label])
+            .inc();
+        metrics
+            .statement_latency
+            .with_label_values(&[stmt_label])
+            .observe(duration.as_secs_f64());
+        if error.is_some() {
+            metrics
+                .statement_error_count
+                .with_label_values(&[stmt_label])
+                .inc();
+        }
+        let sql = self.sql.as_str();
+        let kind = session.env.audit_logger().log(StatementAudit {
+            user: session.user_name(),
+            database: session.database(),
+            sql,
+            is_ddl: self.is_ddl,
+            duration,
+            error: error.clone(),
+            query: session.query_audit_info.lock().take(),
+        });
+        let capture = session.replay_capture.lock().take();
+        if let Some(kind) = kind
+            && let Some(capture) = capture
+        {
+            session.env.replay_capturer().capture(StatementReplay {
+                kind,
+                user: session.user_name(),
+                database: session.database(),
+                sql,
+                duration_ms: duration.as_millis() as u64,
+                error,
+                capture,
+            });
+        }
+    }
+}
+
 pub struct SessionImpl {
     env: FrontendEnv,
     auth_context: Arc<AuthContext>,
@@ -344,6 +562,19 @@ pub struct SessionImpl {
     user_authenticator: UserAuthenticator,
     /// Stores the value of configurations.
     config_map: RwLock<HashMap<String, ConfigEntry>>,
+    /// Plan information of the running batch query, reported to the audit log when the
+    /// statement finishes.
+    query_audit_info: Mutex<Option<QueryAuditInfo>>,
+    /// Plan and epoch of the running batch query, uploaded for replaying if the query turns out
+    /// to be slow or failed. Only collected if capturing is enabled.
+    replay_capture: Mutex<Option<ReplayCapture>>,
+    /// Whether this session has issued writes that are not flushed yet.
+    has_pending_writes: AtomicBool,
+    /// The committed epoch containing all the flushed writes of this session. Batch queries of
+    /// this session read a snapshot no older than it, so that they see these writes.
+    write_epoch: AtomicU64,
+    /// The channels this session is listening on.
+    listeners: SessionListeners,
 }
 
 #[derive(Clone)]
@@ -373,7 +604,17 @@ fn build_default_session_config_map() -> HashMap<String, String> {
     let mut m = HashMap::new();
     m.insert(IMPLICIT_FLUSH.to_ascii_lowercase(), "false".to_string());
     m.insert(DELTA_JOIN.to_ascii_lowercase(), "false".to_string());
+    m.insert(
+        DELTA_JOIN_WITHOUT_INDEX.to_ascii_lowercase(),
+        "false".to_string(),
+    );
+    m.insert(TWO_PHASE_AGG.to_ascii_lowercase(), "false".to_string());
+    m.insert(MV_REUSE.to_ascii_lowercase(), "true".to_string());
     m.insert(QUERY_MODE.to_ascii_lowercase(), "distributed".to_string());
+    m.insert(STATEMENT_TIMEOUT.to_ascii_lowercase(), "0".to_string());
+    m.insert(MAX_RESULT_ROWS.to_ascii_lowercase(), "0".to_string());
+    m.insert(MEMORY_LIMIT.to_ascii_lowercase(), "0".to_string());
+    m.insert(IVF_PROBES.to_ascii_lowercase(), "0".to_string());
     m
 }
 
@@ -389,24 +630,37 @@ impl SessionImpl {
         auth_context: Arc<AuthContext>,
         user_authenticator: UserAuthenticator,
     ) -> Self {
+        env.frontend_metrics().active_sessions.inc();
         Self {
             env,
             auth_context,
             user_authenticator,
             config_map: Self::init_config_map(),
+            query_audit_info: Mutex::new(None),
+            replay_capture: Mutex::new(None),
+            has_pending_writes: AtomicBool::new(false),
+            write_epoch: AtomicU64::new(0),
+            listeners: SessionListeners::default(),
         }
     }
 
     #[cfg(test)]
     pub fn mock() -> Self {
+        let env = FrontendEnv::mock();
+        env.frontend_metrics().active_sessions.inc();
         Self {
-            env: FrontendEnv::mock(),
+            env,
             auth_context: Arc::new(AuthContext::new(
                 DEFAULT_DATABASE_NAME.to_string(),
                 DEFAULT_SUPPER_USER.to_string(),
             )),
             user_authenticator: UserAuthenticator::None,
             config_map: Self::init_config_map(),
+            query_audit_info: Mutex::new(None),
+            replay_capture: Mutex::new(None),
+            has_pending_writes: AtomicBool::new(false),
+            write_epoch: AtomicU64::new(0),
+            listeners: SessionListeners::default(),
         }
     }
 
@@ -414,6 +668,10 @@ impl SessionImpl {
         &self.env
     }
 
+    pub fn listeners(&self) -> &SessionListeners {
+        &self.listeners
+    }
+
     pub fn auth_context(&self) -> Arc<AuthContext> {
         self.auth_context.clone()
     }
@@ -447,6 +705,149 @@ impl SessionImpl {
         reader.get(&key).cloned()
     }
 
+    /// Returns all the configuration values in this session.
+    pub fn config_values(&self) -> BTreeMap<String, String> {
+        self.config_map
+            .read()
+            .iter()
+            .map(|(key, entry)| (key.clone(), entry.str_val.clone()))
+            .collect()
+    }
+
+    /// Handles a single parsed statement with `handler`, recording its metrics, audit log and
+    /// replay capture. It's shared by pgwire and other transports such as Arrow Flight, so that
+    /// their statements are audited the same way.
+    pub async fn handle_audited<T, Fut>(
+        self: Arc<Self>,
+        stmt: Statement,
+        sql: &str,
+        handler: impl FnOnce(Arc<Self>, Statement) -> Fut,
+    ) -> Result<T>
+    where
+        Fut: Future<Output = Result<T>>,
+    {
+        let audited = self.begin_audited(&stmt, sql);
+        let rsp = handler(self.clone(), stmt).await;
+        audited.finish(rsp.as_ref().err().map(|e| e.to_string()));
+        rsp
+    }
+
+    /// Starts to handle a statement whose result is streamed, and is audited by
+    /// [`AuditedStatement::finish`] once the stream completes or fails.
+    pub fn begin_audited(self: &Arc<Self>, stmt: &Statement, sql: &str) -> AuditedStatement {
+        self.query_audit_info.lock().take();
+        self.replay_capture.lock().take();
+        AuditedStatement {
+            session: self.clone(),
+            is_ddl: is_ddl(stmt),
+            stmt_label: statement_label(stmt),
+            sql: redact_sql(stmt, sql).into_owned(),
+            start: Instant::now(),
+        }
+    }
+
+    /// Returns an error unless the user of the session is a superuser, which is required to
+    /// `action`.
+    pub fn check_superuser(&self, action: &str) -> Result<()> {
+        let is_super = self
+            .env
+            .user_info_reader()
+            .read_guard()
+            .get_user_by_name(self.user_name())
+            .map_or(false, |user| user.is_supper);
+        if !is_super {
+            return Err(
+                ErrorCode::PermissionDenied(format!("must be superuser to {}", action)).into(),
+            );
+        }
+        Ok(())
+    }
+
+    /// Returns the number of lists of an IVF index to probe in a nearest neighbor search, or 0 if
+    /// the approximate search is disabled.
+    pub fn ivf_probes(&self) -> usize {
+        self.get_config(IVF_PROBES)
+            .and_then(|entry| entry.str_val.parse().ok())
+            .unwrap_or(0)
+    }
+
+    /// Returns the limits on the queries of this session, the stricter ones of the session
+    /// configurations and the limits of the user.
+    pub fn query_limits(&self) -> QueryLimits {
+        let config = |key| {
+            self.get_config(key)
+                .and_then(|entry| entry.str_val.parse().ok())
+                .unwrap_or(0)
+        };
+        let session_limits = UserLimits {
+            statement_timeout_ms: config(STATEMENT_TIMEOUT),
+            max_result_rows: config(MAX_RESULT_ROWS),
+            memory_limit_bytes: config(MEMORY_LIMIT),
+        };
+        let user_limits = self
+            .env
+            .user_info_reader()
+            .read_guard()
+            .get_user_by_name(self.user_name())
+            .and_then(|user| user.limits.clone())
+            .unwrap_or_default();
+        QueryLimits::new(&session_limits, &user_limits)
+    }
+
+    /// Renders all the configuration values of this session, sorted by name.
+    pub fn config_snapshot(&self) -> String {
+        self.config_map
+            .read()
+            .iter()
+            .map(|(key, entry)| format!("{}={}", key, entry.str_val))
+            .sorted()
+            .join(",")
+    }
+
+    /// Records that this session has issued writes without flushing them. They're flushed before
+    /// the next read of this session.
+    pub fn mark_pending_writes(&self) {
+        self.has_pending_writes.store(true, Ordering::Relaxed);
+    }
+
+    /// Flushes all the writes issued so far, and waits until they're committed.
+    pub async fn flush_writes(&self) -> Result<()> {
+        let epoch = self.env.meta_client().flush().await?;
+        self.advance_write_epoch(epoch);
+        self.has_pending_writes.store(false, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Records that the writes of this session are committed in `epoch`, e.g. by a batched insert
+    /// flushed on its behalf.
+    pub fn advance_write_epoch(&self, epoch: u64) {
+        self.write_epoch.fetch_max(epoch, Ordering::Relaxed);
+    }
+
+    /// Flushes the writes of this session if any of them are not flushed yet, so that the
+    /// following reads of this session see them.
+    pub async fn flush_pending_writes(&self) -> Result<()> {
+        if self.has_pending_writes.load(Ordering::Relaxed) {
+            self.flush_writes().await?;
+        }
+        Ok(())
+    }
+
+    /// Returns the minimal epoch that batch queries of this session should read.
+    pub fn min_read_epoch(&self) -> u64 {
+        self.write_epoch.load(Ordering::Relaxed)
+    }
+
+    /// Set the plan information of the running batch query for the audit log.
+    pub fn set_query_audit_info(&self, info: QueryAuditInfo) {
+        *self.query_audit_info.lock() = Some(info);
+    }
+
+    /// Set the capture of the running batch query for replaying.
+    pub fn set_replay_capture(&self, capture: ReplayCapture) {
+        *self.replay_capture.lock() = Some(capture);
+    }
+
     fn init_config_map() -> RwLock<HashMap<String, ConfigEntry>> {
         let mut map = HashMap::new();
         for (key, value) in &*DEFAULT_SESSION_CONFIG_MAP {
@@ -456,6 +857,12 @@ impl SessionImpl {
     }
 }
 
+impl Drop for SessionImpl {
+    fn drop(&mut self) {
+        self.env.frontend_metrics().active_sessions.dec();
+    }
+}
+
 pub struct SessionManagerImpl {
     env: FrontendEnv,
     observer_join_handle: JoinHandle<()>,
@@ -474,9 +881,12 @@ impl SessionManager for SessionManagerImpl {
         let catalog_reader = self.env.catalog_reader();
         let reader = catalog_reader.read_guard();
         if reader.get_database_by_name(database).is_err() {
-            return Err(Box::new(Error::new(
-                ErrorKind::InvalidInput,
-                format!("Not found database name: {}", database),
+            return Err(Box::new(PgError::new(
+                SQLSTATE_INVALID_CATALOG_NAME,
+                Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("database \"{}\" does not exist", database),
+                ),
             )));
         }
         let user_reader = self.env.user_info_reader();
@@ -523,9 +933,12 @@ impl SessionManager for SessionManagerImpl {
             )
             .into())
         } else {
-            Err(Box::new(Error::new(
-                ErrorKind::InvalidInput,
-                format!("Role {} does not exist", user_name),
+            Err(Box::new(PgError::new(
+                SQLSTATE_INVALID_AUTHORIZATION,
+                Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Role {} does not exist", user_name),
+                ),
             )))
         }
     }
@@ -543,6 +956,10 @@ impl SessionManagerImpl {
         })
     }
 
+    pub fn env(&self) -> &FrontendEnv {
+        &self.env
+    }
+
     /// Used in unit test. Called before `LocalMeta::stop`.
     pub fn terminate(&self) {
         self.observer_join_handle.abort();
@@ -550,17 +967,38 @@ impl SessionManagerImpl {
     }
 }
 
+/// SQLSTATE code of the errors from the parser.
+const SQLSTATE_SYNTAX_ERROR: &str = "42601";
+/// SQLSTATE code of connecting to a database that does not exist.
+const SQLSTATE_INVALID_CATALOG_NAME: &str = "3D000";
+/// SQLSTATE code of connecting as a role that does not exist.
+const SQLSTATE_INVALID_AUTHORIZATION: &str = "28000";
+
+/// Attach the SQLSTATE code and the hint of `err` to be reported to the client.
+fn to_pg_error(err: RwError) -> PgError {
+    let sqlstate = err.sqlstate().to_string();
+    let hint = err.hint().map(str::to_string);
+    PgError {
+        sqlstate,
+        hint,
+        error: Box::new(err),
+    }
+}
+
 #[async_trait::async_trait]
 impl Session for SessionImpl {
     async fn run_statement(
         self: Arc<Self>,
         sql: &str,
     ) -> std::result::Result<PgResponse, BoxedError> {
+        let metrics = self.env.frontend_metrics();
         // Parse sql.
+        let start = Instant::now();
         let mut stmts = Parser::parse_sql(sql).map_err(|e| {
             tracing::error!("failed to parse sql:\n{}:\n{}", sql, e);
-            e
+            PgError::new(SQLSTATE_SYNTAX_ERROR, e)
         })?;
+        metrics.observe_stage("parse", start);
         if stmts.is_empty() {
             return Ok(PgResponse::empty_result(
                 pgwire::pg_response::StatementType::EMPTY,
@@ -573,9 +1011,12 @@ impl Session for SessionImpl {
             ));
         }
         let stmt = stmts.swap_remove(0);
-        let rsp = handle(self, stmt, sql).await.map_err(|e| {
+        let rsp = self
+            .handle_audited(stmt, sql, |session, stmt| handle(session, stmt, sql))
+            .await;
+        let rsp = rsp.map_err(|e| {
             tracing::error!("failed to handle sql:\n{}:\n{}", sql, e);
-            e
+            to_pg_error(e)
         })?;
         Ok(rsp)
     }
@@ -587,7 +1028,7 @@ impl Session for SessionImpl {
         // Parse sql.
         let mut stmts = Parser::parse_sql(sql).map_err(|e| {
             tracing::error!("failed to parse sql:\n{}:\n{}", sql, e);
-            e
+            PgError::new(SQLSTATE_SYNTAX_ERROR, e)
         })?;
         if stmts.is_empty() {
             return Ok(vec![]);
@@ -601,7 +1042,7 @@ impl Session for SessionImpl {
         let stmt = stmts.swap_remove(0);
         let rsp = infer(self, stmt, sql).map_err(|e| {
             tracing::error!("failed to handle sql:\n{}:\n{}", sql, e);
-            e
+            to_pg_error(e)
         })?;
         Ok(rsp)
     }
@@ -609,6 +1050,10 @@ impl Session for SessionImpl {
     fn user_authenticator(&self) -> &UserAuthenticator {
         &self.user_authenticator
     }
+
+    async fn next_notification(&self) -> Notification {
+        self.listeners.next().await
+    }
 }
 
 /// Returns row description of the statement
@@ -182,6 +182,12 @@ impl StreamFragmenter {
         match stream_node.get_node_body()? {
             NodeBody::Source(_) => current_fragment.fragment_type = FragmentType::Source,
 
+            // `NOW()` is a source generating its rows from the barriers injected into it.
+            NodeBody::Now(_) => {
+                current_fragment.fragment_type = FragmentType::Source;
+                current_fragment.is_singleton = true;
+            }
+
             NodeBody::Materialize(_) => current_fragment.fragment_type = FragmentType::Sink,
 
             // TODO: Force singleton for TopN as a workaround. We should implement two phase TopN.
@@ -347,6 +353,33 @@ impl StreamFragmenter {
                 top_n_node.table_id = state.gen_table_id();
             }
 
+            NodeBody::DynamicFilter(dynamic_filter_node) => {
+                if let Some(left_table) = &mut dynamic_filter_node.left_table {
+                    left_table.id = state.gen_table_id();
+                }
+            }
+
+            NodeBody::AsOfJoin(as_of_join_node) => {
+                if let Some(left_table) = &mut as_of_join_node.left_table {
+                    left_table.id = state.gen_table_id();
+                }
+                if let Some(right_table) = &mut as_of_join_node.right_table {
+                    right_table.id = state.gen_table_id();
+                }
+            }
+
+            NodeBody::MatchRecognize(match_recognize_node) => {
+                if let Some(state_table) = &mut match_recognize_node.state_table {
+                    state_table.id = state.gen_table_id();
+                }
+            }
+
+            NodeBody::GapFill(gap_fill_node) => {
+                if let Some(state_table) = &mut gap_fill_node.state_table {
+                    state_table.id = state.gen_table_id();
+                }
+            }
+
             NodeBody::AppendOnlyTopN(append_only_top_n_node) => {
                 append_only_top_n_node.table_id = state.gen_table_id();
             }
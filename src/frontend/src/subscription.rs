@@ -0,0 +1,338 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Subscriptions on materialized views, which let clients pull the incremental changes of an MV
+//! through cursors at their own pace.
+//!
+//! The changelog of a subscription is retained in the memory of the frontend node that created
+//! it. Each refresh diffs the current content of the MV against the last seen snapshot, and
+//! changes older than the retention of the subscription are pruned. A cursor remembers the
+//! sequence number of the next change it will return, so it can be resumed from any session
+//! connected to the same frontend until the changes it needs are pruned.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+
+use itertools::Itertools;
+use parking_lot::Mutex;
+use risingwave_common::array::{Op, Row};
+use risingwave_common::catalog::Schema;
+use risingwave_common::error::ErrorCode::{InternalError, InvalidInputSyntax};
+use risingwave_common::error::Result;
+use risingwave_common::types::ScalarImpl;
+use risingwave_common::util::epoch::Epoch;
+use risingwave_connector::sink::changelog::op_name;
+
+use crate::catalog::CatalogError;
+
+pub type SubscriptionManagerRef = Arc<SubscriptionManager>;
+
+/// A change of a subscribed MV, numbered by `seq` in the order it was observed.
+#[derive(Clone, Debug)]
+struct ChangeLogEntry {
+    seq: u64,
+    epoch: Epoch,
+    op: Op,
+    row: Row,
+}
+
+/// Replaces the multiset `snapshot` with `rows`, returning the rows deleted and inserted.
+pub fn diff_snapshot(snapshot: &mut HashMap<Row, usize>, rows: Vec<Row>) -> Vec<(Op, Row)> {
+    let mut current: HashMap<Row, usize> = HashMap::new();
+    for row in rows {
+        *current.entry(row).or_default() += 1;
+    }
+
+    let mut changes = vec![];
+    for (row, count) in &*snapshot {
+        let new_count = current.get(row).copied().unwrap_or(0);
+        for _ in new_count..*count {
+            changes.push((Op::Delete, row.clone()));
+        }
+    }
+    for (row, count) in &current {
+        let old_count = snapshot.get(row).copied().unwrap_or(0);
+        for _ in old_count..*count {
+            changes.push((Op::Insert, row.clone()));
+        }
+    }
+    *snapshot = current;
+    changes
+}
+
+struct Subscription {
+    mv_name: String,
+    retention: Duration,
+    schema: Schema,
+    /// The multiset of rows of the MV as of the last refresh.
+    snapshot: HashMap<Row, usize>,
+    log: VecDeque<ChangeLogEntry>,
+    next_seq: u64,
+}
+
+impl Subscription {
+    /// The sequence number of the earliest change still retained.
+    fn first_seq(&self) -> u64 {
+        self.log.front().map(|e| e.seq).unwrap_or(self.next_seq)
+    }
+
+    fn apply_snapshot(&mut self, rows: Vec<Row>, epoch: Epoch) {
+        for (op, row) in diff_snapshot(&mut self.snapshot, rows) {
+            self.log.push_back(ChangeLogEntry {
+                seq: self.next_seq,
+                epoch,
+                op,
+                row,
+            });
+            self.next_seq += 1;
+        }
+
+        let retained_since = epoch.subtract_ms(self.retention.as_millis() as u64);
+        while let Some(entry) = self.log.front()
+            && entry.epoch < retained_since
+        {
+            self.log.pop_front();
+        }
+    }
+}
+
+struct Cursor {
+    subscription: String,
+    next_seq: u64,
+}
+
+#[derive(Default)]
+struct SubscriptionManagerCore {
+    subscriptions: HashMap<String, Subscription>,
+    cursors: HashMap<String, Cursor>,
+}
+
+/// Keeps the subscriptions and cursors of the frontend node.
+#[derive(Default)]
+pub struct SubscriptionManager {
+    core: Mutex<SubscriptionManagerCore>,
+}
+
+impl SubscriptionManager {
+    /// Creates a subscription on `mv_name`, starting from the given content of the MV.
+    pub fn create_subscription(
+        &self,
+        name: String,
+        mv_name: String,
+        retention: Duration,
+        schema: Schema,
+        rows: Vec<Row>,
+    ) -> Result<()> {
+        let mut core = self.core.lock();
+        if core.subscriptions.contains_key(&name) {
+            return Err(CatalogError::Duplicated("subscription", name).into());
+        }
+        let mut subscription = Subscription {
+            mv_name,
+            retention,
+            schema,
+            snapshot: HashMap::new(),
+            log: VecDeque::new(),
+            next_seq: 0,
+        };
+        subscription.apply_snapshot(rows, Epoch::now());
+        // The initial content is the starting point rather than changes.
+        subscription.log.clear();
+        core.subscriptions.insert(name, subscription);
+        Ok(())
+    }
+
+    /// Drops a subscription along with the cursors declared on it.
+    pub fn drop_subscription(&self, name: &str) -> Result<()> {
+        let mut core = self.core.lock();
+        if core.subscriptions.remove(name).is_none() {
+            return Err(CatalogError::NotFound("subscription", name.to_string()).into());
+        }
+        core.cursors.retain(|_, cursor| cursor.subscription != name);
+        Ok(())
+    }
+
+    /// Declares a cursor on `subscription`, starting from the earliest retained change.
+    pub fn declare_cursor(&self, name: String, subscription: &str) -> Result<()> {
+        let mut core = self.core.lock();
+        if core.cursors.contains_key(&name) {
+            return Err(CatalogError::Duplicated("cursor", name).into());
+        }
+        let next_seq = core
+            .subscriptions
+            .get(subscription)
+            .ok_or_else(|| CatalogError::NotFound("subscription", subscription.to_string()))?
+            .first_seq();
+        core.cursors.insert(
+            name,
+            Cursor {
+                subscription: subscription.to_string(),
+                next_seq,
+            },
+        );
+        Ok(())
+    }
+
+    pub fn close_cursor(&self, name: &str) -> Result<()> {
+        match self.core.lock().cursors.remove(name) {
+            Some(_) => Ok(()),
+            None => Err(CatalogError::NotFound("cursor", name.to_string()).into()),
+        }
+    }
+
+    /// Returns the names of the subscription and MV that the cursor reads.
+    pub fn cursor_target(&self, cursor: &str) -> Result<(String, String)> {
+        let core = self.core.lock();
+        let subscription = &core
+            .cursors
+            .get(cursor)
+            .ok_or_else(|| CatalogError::NotFound("cursor", cursor.to_string()))?
+            .subscription;
+        let mv_name = core
+            .subscriptions
+            .get(subscription)
+            .ok_or_else(|| InternalError(format!("subscription {} not found", subscription)))?
+            .mv_name
+            .clone();
+        Ok((subscription.clone(), mv_name))
+    }
+
+    /// Records the changes between the last seen content of the MV and `rows`.
+    pub fn refresh(&self, subscription: &str, rows: Vec<Row>) -> Result<()> {
+        let mut core = self.core.lock();
+        let subscription = core
+            .subscriptions
+            .get_mut(subscription)
+            .ok_or_else(|| CatalogError::NotFound("subscription", subscription.to_string()))?;
+        subscription.apply_snapshot(rows, Epoch::now());
+        Ok(())
+    }
+
+    /// Returns at most `count` changes after the position of the cursor and advances it. Each row
+    /// is the changed row followed by its op name and epoch, as in the changelog sink format.
+    pub fn fetch(&self, cursor: &str, count: u64) -> Result<(Schema, Vec<Row>)> {
+        let mut core = self.core.lock();
+        let core = &mut *core;
+        let cursor = core
+            .cursors
+            .get_mut(cursor)
+            .ok_or_else(|| CatalogError::NotFound("cursor", cursor.to_string()))?;
+        let subscription = core
+            .subscriptions
+            .get(&cursor.subscription)
+            .ok_or_else(|| {
+                InternalError(format!("subscription {} not found", cursor.subscription))
+            })?;
+        if cursor.next_seq < subscription.first_seq() {
+            return Err(InvalidInputSyntax(format!(
+                "changes of subscription {} requested by the cursor are beyond the retention of {}",
+                cursor.subscription,
+                humantime::format_duration(subscription.retention)
+            ))
+            .into());
+        }
+
+        let rows = subscription
+            .log
+            .iter()
+            .skip_while(|entry| entry.seq < cursor.next_seq)
+            .take(count as usize)
+            .map(|entry| {
+                let mut values = entry.row.0.clone();
+                values.push(Some(ScalarImpl::Utf8(op_name(entry.op).to_string())));
+                values.push(Some(ScalarImpl::Int64(entry.epoch.0 as i64)));
+                Row(values)
+            })
+            .collect_vec();
+        cursor.next_seq += rows.len() as u64;
+        Ok((subscription.schema.clone(), rows))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::catalog::Field;
+    use risingwave_common::types::DataType;
+
+    use super::*;
+
+    fn row(v: i32) -> Row {
+        Row(vec![Some(ScalarImpl::Int32(v))])
+    }
+
+    fn fetch_ops(manager: &SubscriptionManager, cursor: &str, count: u64) -> Vec<(i32, String)> {
+        let (_, rows) = manager.fetch(cursor, count).unwrap();
+        rows.into_iter()
+            .map(|r| match (&r.0[0], &r.0[1]) {
+                (Some(ScalarImpl::Int32(v)), Some(ScalarImpl::Utf8(op))) => (*v, op.clone()),
+                _ => unreachable!(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_subscription_cursor() {
+        let manager = SubscriptionManager::default();
+        let schema = Schema::new(vec![Field::unnamed(DataType::Int32)]);
+        manager
+            .create_subscription(
+                "sub".into(),
+                "mv".into(),
+                Duration::from_secs(3600),
+                schema,
+                vec![row(1), row(2)],
+            )
+            .unwrap();
+        manager.declare_cursor("c".into(), "sub").unwrap();
+        assert!(fetch_ops(&manager, "c", 10).is_empty());
+
+        manager
+            .refresh("sub", vec![row(2), row(3), row(3)])
+            .unwrap();
+        let mut changes = fetch_ops(&manager, "c", 2);
+        changes.extend(fetch_ops(&manager, "c", 10));
+        changes.sort();
+        assert_eq!(
+            changes,
+            vec![
+                (1, "delete".to_string()),
+                (3, "insert".to_string()),
+                (3, "insert".to_string())
+            ]
+        );
+        assert!(fetch_ops(&manager, "c", 10).is_empty());
+
+        // A new cursor replays all retained changes.
+        manager.declare_cursor("c2".into(), "sub").unwrap();
+        assert_eq!(fetch_ops(&manager, "c2", 10).len(), 3);
+
+        manager.drop_subscription("sub").unwrap();
+        assert!(manager.fetch("c", 1).is_err());
+    }
+
+    #[test]
+    fn test_retention() {
+        let manager = SubscriptionManager::default();
+        let schema = Schema::new(vec![Field::unnamed(DataType::Int32)]);
+        manager
+            .create_subscription("sub".into(), "mv".into(), Duration::ZERO, schema, vec![])
+            .unwrap();
+        manager.declare_cursor("c".into(), "sub").unwrap();
+        manager.refresh("sub", vec![row(1)]).unwrap();
+        std::thread::sleep(Duration::from_millis(5));
+        manager.refresh("sub", vec![row(2)]).unwrap();
+        assert!(manager.fetch("c", 10).is_err());
+    }
+}
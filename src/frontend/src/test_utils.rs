@@ -30,7 +30,12 @@ use risingwave_pb::catalog::{
     Database as ProstDatabase, Schema as ProstSchema, Source as ProstSource, Table as ProstTable,
 };
 use risingwave_pb::common::ParallelUnitMapping;
+use risingwave_pb::data::ConfigChange;
+use risingwave_pb::ddl_service::CatalogSnapshot;
+use risingwave_pb::hummock::TableStorageStats;
+use risingwave_pb::meta::TelemetryReport;
 use risingwave_pb::stream_plan::StreamFragmentGraph;
+use risingwave_pb::user::update_user_request::UpdateField;
 use risingwave_pb::user::{GrantPrivilege, UserInfo};
 use risingwave_rpc_client::error::Result as RpcResult;
 use risingwave_sqlparser::ast::Statement;
@@ -116,11 +121,16 @@ impl LocalFrontend {
     }
 
     pub fn session_ref(&self) -> Arc<SessionImpl> {
+        self.session_user_ref(DEFAULT_SUPPER_USER)
+    }
+
+    /// Returns a session of `user_name`, who must have been created, e.g. by `CREATE USER`.
+    pub fn session_user_ref(&self, user_name: &str) -> Arc<SessionImpl> {
         Arc::new(SessionImpl::new(
             self.env.clone(),
             Arc::new(AuthContext::new(
                 DEFAULT_DATABASE_NAME.to_string(),
-                DEFAULT_SUPPER_USER.to_string(),
+                user_name.to_string(),
             )),
             UserAuthenticator::None,
         ))
@@ -220,6 +230,32 @@ impl CatalogWriter for MockCatalogWriter {
         Ok(())
     }
 
+    async fn replace_materialized_view(
+        &self,
+        table_id: TableId,
+        new_table_id: TableId,
+    ) -> Result<()> {
+        let table = self.get_table(table_id);
+        let mut new_table = self.get_table(new_table_id);
+        self.drop_materialized_view(table_id).await?;
+        new_table.name = table.name;
+        new_table.description = table.description;
+        self.catalog.write().update_table(&new_table);
+        Ok(())
+    }
+
+    async fn alter_materialized_view(
+        &self,
+        table_id: TableId,
+        table: ProstTable,
+        graph: StreamFragmentGraph,
+    ) -> Result<()> {
+        self.create_materialized_view(table, graph).await?;
+        // The new table is assigned with the last generated id.
+        let new_table_id = TableId::new(self.id.load(Ordering::SeqCst));
+        self.replace_materialized_view(table_id, new_table_id).await
+    }
+
     async fn drop_source(&self, source_id: u32) -> Result<()> {
         let (database_id, schema_id) = self.drop_table_or_source_id(source_id);
         self.catalog
@@ -238,6 +274,26 @@ impl CatalogWriter for MockCatalogWriter {
         self.catalog.write().drop_schema(database_id, schema_id);
         Ok(())
     }
+
+    async fn comment_on(
+        &self,
+        table_id: TableId,
+        column_id: Option<i32>,
+        description: String,
+    ) -> Result<()> {
+        let mut table = self.get_table(table_id);
+        match column_id {
+            Some(column_id) if description.is_empty() => {
+                table.column_descriptions.remove(&column_id);
+            }
+            Some(column_id) => {
+                table.column_descriptions.insert(column_id, description);
+            }
+            None => table.description = description,
+        }
+        self.catalog.write().update_table(&table);
+        Ok(())
+    }
 }
 
 impl MockCatalogWriter {
@@ -310,6 +366,35 @@ impl MockCatalogWriter {
         Ok(source.id)
     }
 
+    fn get_table(&self, table_id: TableId) -> ProstTable {
+        let schema_id = *self
+            .table_id_to_schema_id
+            .read()
+            .get(&table_id.table_id)
+            .unwrap();
+        let database_id = self.get_database_id_by_schema(schema_id);
+        let catalog = self.catalog.read();
+        let mut table = catalog
+            .get_all_database_names()
+            .into_iter()
+            .find_map(|db_name| {
+                catalog
+                    .get_database_by_name(&db_name)
+                    .unwrap()
+                    .iter_schemas()
+                    .flat_map(|schema| schema.iter_all_tables())
+                    .find(|table| table.id() == table_id)
+                    .map(|table| table.to_prost(schema_id, database_id))
+            })
+            .unwrap();
+        table.mapping = Some(ParallelUnitMapping {
+            table_id: table.id,
+            original_indices: [0, 10, 20].to_vec(),
+            data: [1, 2, 3].to_vec(),
+        });
+        table
+    }
+
     fn get_database_id_by_schema(&self, schema_id: u32) -> DatabaseId {
         *self
             .schema_id_to_database_id
@@ -335,6 +420,26 @@ impl UserInfoWriter for MockUserInfoWriter {
         Ok(())
     }
 
+    async fn update_user(
+        &self,
+        update_user: UserInfo,
+        update_fields: Vec<UpdateField>,
+    ) -> Result<()> {
+        let mut user_info = self.user_info.write();
+        let mut user = user_info
+            .get_user_by_name(&update_user.name)
+            .cloned()
+            .unwrap();
+        for field in update_fields {
+            match field {
+                UpdateField::Limits => user.limits = update_user.limits.clone(),
+                UpdateField::Unknown => unreachable!(),
+            }
+        }
+        user_info.update_user(user);
+        Ok(())
+    }
+
     /// In `MockUserInfoWriter`, we don't support expand privilege with `GrantAllTables` and
     /// `GrantAllSources` when grant privilege to user.
     async fn grant_privilege(
@@ -423,8 +528,8 @@ impl FrontendMetaClient for MockFrontendMetaClient {
         Ok(0)
     }
 
-    async fn flush(&self) -> RpcResult<()> {
-        Ok(())
+    async fn flush(&self) -> RpcResult<u64> {
+        Ok(0)
     }
 
     async fn unpin_snapshot(&self, _epoch: u64) -> RpcResult<()> {
@@ -434,6 +539,38 @@ impl FrontendMetaClient for MockFrontendMetaClient {
     async fn unpin_snapshot_before(&self, _epoch: u64) -> RpcResult<()> {
         Ok(())
     }
+
+    async fn get_table_storage_stats(&self) -> RpcResult<Vec<TableStorageStats>> {
+        Ok(vec![])
+    }
+
+    async fn set_system_param(&self, _param: String, _value: String) -> RpcResult<()> {
+        Ok(())
+    }
+
+    async fn savepoint(&self, _table_id: u32) -> RpcResult<u64> {
+        Ok(0)
+    }
+
+    async fn restore_savepoint(&self, _table_id: u32) -> RpcResult<()> {
+        Ok(())
+    }
+
+    async fn release_savepoint(&self, _table_id: u32) -> RpcResult<()> {
+        Ok(())
+    }
+
+    async fn reconfigure(&self, _table_id: u32, _change: ConfigChange) -> RpcResult<()> {
+        Ok(())
+    }
+
+    async fn get_telemetry_report(&self) -> RpcResult<TelemetryReport> {
+        Ok(TelemetryReport::default())
+    }
+
+    async fn get_catalog(&self) -> RpcResult<CatalogSnapshot> {
+        Ok(CatalogSnapshot::default())
+    }
 }
 pub static PROTO_FILE_DATA: &str = r#"
     syntax = "proto3";
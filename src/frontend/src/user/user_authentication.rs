@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use md5;
+use pgwire::pg_server::UserAuthenticator;
 use risingwave_pb::user::auth_info::EncryptionType;
 use risingwave_pb::user::AuthInfo;
 use sha2::{Digest, Sha256};
@@ -112,10 +113,49 @@ pub fn md5_hash(name: &str, password: &str) -> Vec<u8> {
     format!("{:x}", ctx.compute()).into_bytes()
 }
 
+/// Authenticates the password sent in clear text, as by Flight and HTTP clients. The MD5 challenge
+/// of pgwire is computed here instead of by the client.
+pub fn authenticate(authenticator: &UserAuthenticator, user: &str, password: &str) -> bool {
+    match authenticator {
+        UserAuthenticator::MD5WithSalt { salt, .. } => {
+            authenticator.authenticate(&md5_hash_with_salt(&md5_hash(user, password), salt))
+        }
+        _ => authenticator.authenticate(password.as_bytes()),
+    }
+}
+
+/// Returns the user name and the password in the value of an `authorization: Basic` header, or
+/// the reason why it's rejected.
+pub fn decode_basic_auth(header: Option<&str>) -> Result<(String, String), &'static str> {
+    let credentials = header
+        .and_then(|value| value.strip_prefix("Basic "))
+        .ok_or("missing basic authorization header")?;
+    let credentials = base64::decode(credentials)
+        .ok()
+        .and_then(|credentials| String::from_utf8(credentials).ok())
+        .ok_or("invalid basic authorization header")?;
+    let (user, password) = credentials
+        .split_once(':')
+        .ok_or("invalid basic authorization header")?;
+    Ok((user.to_string(), password.to_string()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_decode_basic_auth() {
+        assert!(decode_basic_auth(None).is_err());
+        assert!(decode_basic_auth(Some("Bearer token")).is_err());
+        assert!(decode_basic_auth(Some("Basic garbage")).is_err());
+        let header = format!("Basic {}", base64::encode("root:pass:word"));
+        assert_eq!(
+            decode_basic_auth(Some(&header)).unwrap(),
+            ("root".to_string(), "pass:word".to_string())
+        );
+    }
+
     #[test]
     fn test_encrypt_password() {
         let (user_name, password) = ("foo", "bar");
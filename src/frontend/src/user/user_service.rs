@@ -18,6 +18,7 @@ use parking_lot::lock_api::ArcRwLockReadGuard;
 use parking_lot::{RawRwLock, RwLock};
 use risingwave_common::error::ErrorCode::InternalError;
 use risingwave_common::error::{Result, RwError};
+use risingwave_pb::user::update_user_request::UpdateField;
 use risingwave_pb::user::{GrantPrivilege, UserInfo};
 use risingwave_rpc_client::MetaClient;
 use tokio::sync::watch::Receiver;
@@ -45,6 +46,9 @@ pub trait UserInfoWriter: Send + Sync {
 
     async fn drop_user(&self, user_name: &str) -> Result<()>;
 
+    async fn update_user(&self, user_info: UserInfo, update_fields: Vec<UpdateField>)
+        -> Result<()>;
+
     async fn grant_privilege(
         &self,
         users: Vec<UserName>,
@@ -78,6 +82,18 @@ impl UserInfoWriter for UserInfoWriterImpl {
         self.wait_version(version).await
     }
 
+    async fn update_user(
+        &self,
+        user_info: UserInfo,
+        update_fields: Vec<UpdateField>,
+    ) -> Result<()> {
+        let version = self
+            .meta_client
+            .update_user(user_info, update_fields)
+            .await?;
+        self.wait_version(version).await
+    }
+
     async fn grant_privilege(
         &self,
         users: Vec<UserName>,
@@ -25,7 +25,7 @@ use anyhow::{anyhow, Result};
 pub use resolve_id::*;
 use risingwave_frontend::binder::Binder;
 use risingwave_frontend::handler::{
-    create_index, create_mv, create_source, create_table, drop_table,
+    create_index, create_mv, create_schema, create_source, create_table, drop_table,
 };
 use risingwave_frontend::optimizer::PlanRef;
 use risingwave_frontend::planner::Planner;
@@ -138,13 +138,19 @@ pub struct TestCaseResult {
 impl TestCaseResult {
     /// Convert a result to test case
     pub fn as_test_case(self, original_test_case: &TestCase) -> Result<TestCase> {
-        if original_test_case.binder_error.is_none() && let Some(ref err) = self.binder_error {
+        if original_test_case.binder_error.is_none()
+            && let Some(ref err) = self.binder_error
+        {
             return Err(anyhow!("unexpected binder error: {}", err));
         }
-        if original_test_case.planner_error.is_none() && let Some(ref err) = self.planner_error {
+        if original_test_case.planner_error.is_none()
+            && let Some(ref err) = self.planner_error
+        {
             return Err(anyhow!("unexpected planner error: {}", err));
         }
-        if original_test_case.optimizer_error.is_none() && let Some(ref err) = self.optimizer_error {
+        if original_test_case.optimizer_error.is_none()
+            && let Some(ref err) = self.optimizer_error
+        {
             return Err(anyhow!("unexpected optimizer error: {}", err));
         }
 
@@ -266,10 +272,22 @@ impl TestCase {
                 Statement::CreateTable {
                     name,
                     columns,
+                    constraints,
+                    append_only,
+                    on_conflict,
                     with_options,
                     ..
                 } => {
-                    create_table::handle_create_table(context, name, columns, with_options).await?;
+                    create_table::handle_create_table(
+                        context,
+                        name,
+                        columns,
+                        constraints,
+                        append_only,
+                        on_conflict,
+                        with_options,
+                    )
+                    .await?;
                 }
                 Statement::CreateSource {
                     is_materialized,
@@ -277,14 +295,24 @@ impl TestCase {
                 } => {
                     create_source::handle_create_source(context, is_materialized, stmt).await?;
                 }
+                Statement::CreateSchema {
+                    schema_name,
+                    if_not_exists,
+                    ..
+                } => {
+                    create_schema::handle_create_schema(context, schema_name, if_not_exists)
+                        .await?;
+                }
                 Statement::CreateIndex {
                     name,
                     table_name,
+                    using,
                     columns,
                     // TODO: support unique and if_not_exist in planner test
                     ..
                 } => {
-                    create_index::handle_create_index(context, name, table_name, columns).await?;
+                    create_index::handle_create_index(context, name, table_name, using, columns)
+                        .await?;
                 }
                 Statement::CreateView {
                     materialized: true,
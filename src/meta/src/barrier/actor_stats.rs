@@ -0,0 +1,213 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use parking_lot::Mutex;
+use risingwave_pb::stream_service::barrier_complete_response::ActorStats;
+
+use crate::model::{ActorId, FragmentId};
+use crate::rpc::metrics::MetaMetrics;
+
+/// Statistics of a fragment in an epoch, rolled up from the statistics of its actors.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct FragmentStats {
+    /// The materialized view that the fragment belongs to.
+    pub table_id: u32,
+    pub input_rows: u64,
+    pub output_rows: u64,
+    pub output_blocking_ns: u64,
+    /// The actor that took the longest to process the barrier, with its latency in ns.
+    pub slowest_actor: Option<(ActorId, u64)>,
+}
+
+/// Statistics of a materialized view in an epoch, rolled up from the statistics of all its actors.
+/// The rows are summed over all fragments, which reflects how much work the materialized view
+/// costs rather than how many rows it outputs.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct MviewStats {
+    pub input_rows: u64,
+    pub output_rows: u64,
+    pub output_blocking_ns: u64,
+}
+
+/// Roll up the statistics reported by actors into per-fragment and per-materialized-view
+/// statistics.
+pub fn rollup_actor_stats<'a>(
+    actor_stats: impl IntoIterator<Item = &'a ActorStats>,
+) -> (HashMap<FragmentId, FragmentStats>, HashMap<u32, MviewStats>) {
+    let mut fragments: HashMap<FragmentId, FragmentStats> = HashMap::new();
+    let mut mviews: HashMap<u32, MviewStats> = HashMap::new();
+
+    for stats in actor_stats {
+        let fragment = fragments.entry(stats.fragment_id).or_default();
+        fragment.table_id = stats.table_id;
+        fragment.input_rows += stats.input_rows;
+        fragment.output_rows += stats.output_rows;
+        fragment.output_blocking_ns += stats.output_blocking_ns;
+        if fragment
+            .slowest_actor
+            .map_or(true, |(_, latency)| stats.processing_latency_ns > latency)
+        {
+            fragment.slowest_actor = Some((stats.actor_id, stats.processing_latency_ns));
+        }
+
+        let mview = mviews.entry(stats.table_id).or_default();
+        mview.input_rows += stats.input_rows;
+        mview.output_rows += stats.output_rows;
+        mview.output_blocking_ns += stats.output_blocking_ns;
+    }
+
+    (fragments, mviews)
+}
+
+/// Roll up the statistics reported by actors on the completion of a barrier, and report them to
+/// the metrics, so that the throughput cost of each materialized view and the slowest actor of
+/// each fragment can be observed without aggregating the metrics of all compute nodes.
+pub fn report_actor_stats<'a>(
+    metrics: &MetaMetrics,
+    actor_stats: impl IntoIterator<Item = &'a ActorStats>,
+) {
+    let (fragments, mviews) = rollup_actor_stats(actor_stats);
+
+    for (fragment_id, stats) in fragments {
+        let table_id = stats.table_id.to_string();
+        let fragment_id = fragment_id.to_string();
+        let labels = [table_id.as_str(), fragment_id.as_str()];
+        metrics
+            .fragment_input_rows
+            .with_label_values(&labels)
+            .inc_by(stats.input_rows);
+        metrics
+            .fragment_output_rows
+            .with_label_values(&labels)
+            .inc_by(stats.output_rows);
+        metrics
+            .fragment_output_blocking_duration_ns
+            .with_label_values(&labels)
+            .inc_by(stats.output_blocking_ns);
+        if let Some((actor_id, latency_ns)) = stats.slowest_actor {
+            metrics
+                .fragment_slowest_actor_id
+                .with_label_values(&labels)
+                .set(actor_id as i64);
+            metrics
+                .fragment_slowest_actor_latency
+                .with_label_values(&labels)
+                .set(latency_ns as f64 / 1e9);
+        }
+    }
+
+    for (table_id, stats) in mviews {
+        let table_id = table_id.to_string();
+        metrics
+            .mview_input_rows
+            .with_label_values(&[&table_id])
+            .inc_by(stats.input_rows);
+        metrics
+            .mview_output_rows
+            .with_label_values(&[&table_id])
+            .inc_by(stats.output_rows);
+        metrics
+            .mview_output_blocking_duration_ns
+            .with_label_values(&[&table_id])
+            .inc_by(stats.output_blocking_ns);
+    }
+}
+
+/// Accumulates the time the actors of each fragment are blocked on sending to downstream actors,
+/// until it's taken by the parallelism controller to evaluate the backpressure of the fragments.
+#[derive(Debug, Default)]
+pub struct BlockingTimeRecorder {
+    blocking_ns: Mutex<HashMap<FragmentId, u64>>,
+}
+
+impl BlockingTimeRecorder {
+    pub fn record<'a>(&self, actor_stats: impl IntoIterator<Item = &'a ActorStats>) {
+        let mut blocking_ns = self.blocking_ns.lock();
+        for stats in actor_stats {
+            *blocking_ns.entry(stats.fragment_id).or_default() += stats.output_blocking_ns;
+        }
+    }
+
+    /// Returns the blocking time of each fragment in ns since the last call.
+    pub fn take(&self) -> HashMap<FragmentId, u64> {
+        std::mem::take(&mut *self.blocking_ns.lock())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn actor_stats(
+        actor_id: ActorId,
+        fragment_id: FragmentId,
+        table_id: u32,
+        rows: u64,
+        latency_ns: u64,
+    ) -> ActorStats {
+        ActorStats {
+            actor_id,
+            fragment_id,
+            table_id,
+            input_rows: rows,
+            output_rows: rows / 2,
+            processing_latency_ns: latency_ns,
+            output_blocking_ns: 10,
+        }
+    }
+
+    #[test]
+    fn test_rollup_actor_stats() {
+        let stats = vec![
+            actor_stats(1, 1, 100, 10, 300),
+            actor_stats(2, 1, 100, 20, 500),
+            actor_stats(3, 2, 100, 30, 100),
+            actor_stats(4, 3, 200, 40, 200),
+        ];
+        let (fragments, mviews) = rollup_actor_stats(&stats);
+
+        assert_eq!(
+            fragments[&1],
+            FragmentStats {
+                table_id: 100,
+                input_rows: 30,
+                output_rows: 15,
+                output_blocking_ns: 20,
+                slowest_actor: Some((2, 500)),
+            }
+        );
+        assert_eq!(fragments[&2].slowest_actor, Some((3, 100)));
+        assert_eq!(fragments[&3].table_id, 200);
+
+        assert_eq!(
+            mviews[&100],
+            MviewStats {
+                input_rows: 60,
+                output_rows: 30,
+                output_blocking_ns: 30,
+            }
+        );
+        assert_eq!(mviews[&200].input_rows, 40);
+
+        report_actor_stats(&MetaMetrics::new(), &stats);
+
+        let recorder = BlockingTimeRecorder::default();
+        recorder.record(&stats);
+        recorder.record(&stats[..1]);
+        assert_eq!(recorder.take(), HashMap::from([(1, 30), (2, 10), (3, 10)]));
+        assert!(recorder.take().is_empty());
+    }
+}
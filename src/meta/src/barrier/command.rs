@@ -12,16 +12,20 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use futures::future::try_join_all;
 use risingwave_common::catalog::TableId;
 use risingwave_common::error::{Result, RwError};
 use risingwave_common::util::epoch::Epoch;
 use risingwave_connector::SplitImpl;
-use risingwave_pb::common::ActorInfo;
+use risingwave_hummock_sdk::HummockEpoch;
+use risingwave_pb::common::{ActorInfo, ParallelUnit};
 use risingwave_pb::data::barrier::Mutation;
-use risingwave_pb::data::{AddMutation, DispatcherMutation, StopMutation};
+use risingwave_pb::data::{
+    AddMutation, ConfigChange, ConfigMutation, DispatcherMutation, StopMutation,
+};
+use risingwave_pb::meta::table_fragments::Fragment;
 use risingwave_pb::source::{ConnectorSplit, ConnectorSplits};
 use risingwave_pb::stream_service::DropActorsRequest;
 use risingwave_rpc_client::StreamClientPoolRef;
@@ -64,6 +68,23 @@ pub enum Command {
         dispatches: HashMap<(ActorId, DispatcherId), Vec<ActorInfo>>,
         source_state: HashMap<ActorId, Vec<SplitImpl>>,
     },
+
+    /// `RestoreSavepoint` command rolls the given tables back to the savepoint. It doesn't
+    /// generate a barrier. Instead, the barrier manager enters recovery, and the tables are
+    /// restored after all actors are stopped and before they're rebuilt.
+    RestoreSavepoint {
+        table_ids: Vec<TableId>,
+        savepoint_epoch: HummockEpoch,
+    },
+
+    /// `Reschedule` command replaces the actors of a fragment with the given ones. Like
+    /// `RestoreSavepoint`, it doesn't generate a barrier. Instead, the barrier manager enters
+    /// recovery, and all actors are rebuilt from the updated table fragments.
+    Reschedule {
+        table_id: TableId,
+        fragment: Fragment,
+        actor_locations: BTreeMap<ActorId, ParallelUnit>,
+    },
 }
 
 impl Command {
@@ -71,6 +92,16 @@ impl Command {
         Self::Plain(None)
     }
 
+    /// A barrier that applies the config changes to the actors they target.
+    pub fn reconfigure(changes: Vec<ConfigChange>) -> Self {
+        Self::Plain(Some(Mutation::Config(ConfigMutation { changes })))
+    }
+
+    /// Whether this is a periodic barrier without any mutation.
+    pub fn is_periodic(&self) -> bool {
+        matches!(self, Command::Plain(None))
+    }
+
     pub fn creating_table_id(&self) -> Option<TableId> {
         match self {
             Command::CreateMaterializedView {
@@ -95,6 +126,10 @@ pub struct CommandContext<'a, S: MetaStore> {
     pub prev_epoch: &'a Epoch,
     pub curr_epoch: &'a Epoch,
 
+    /// Whether the writes of `prev_epoch` and the previous epochs not checkpointed yet are synced
+    /// and committed after this barrier is collected.
+    pub checkpoint: bool,
+
     command: Command,
 }
 
@@ -106,6 +141,7 @@ impl<'a, S: MetaStore> CommandContext<'a, S> {
         prev_epoch: &'a Epoch,
         curr_epoch: &'a Epoch,
         command: Command,
+        checkpoint: bool,
     ) -> Self {
         Self {
             fragment_manager,
@@ -113,6 +149,7 @@ impl<'a, S: MetaStore> CommandContext<'a, S> {
             info,
             prev_epoch,
             curr_epoch,
+            checkpoint,
             command,
         }
     }
@@ -127,6 +164,8 @@ where
         let mutation = match &self.command {
             Command::Plain(mutation) => mutation.clone(),
 
+            Command::RestoreSavepoint { .. } | Command::Reschedule { .. } => None,
+
             Command::DropMaterializedView(table_id) => {
                 let actors = self.fragment_manager.get_table_actor_ids(table_id).await?;
                 Some(Mutation::Stop(StopMutation { actors }))
@@ -187,7 +226,7 @@ where
     /// Do some stuffs after barriers are collected, for the given command.
     pub async fn post_collect(&self) -> Result<()> {
         match &self.command {
-            Command::Plain(_) => {}
+            Command::Plain(_) | Command::RestoreSavepoint { .. } | Command::Reschedule { .. } => {}
 
             Command::DropMaterializedView(table_id) => {
                 // Tell compute nodes to drop actors.
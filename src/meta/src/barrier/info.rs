@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use risingwave_pb::common::WorkerNode;
 
@@ -23,8 +23,9 @@ use crate::stream::ActorInfos;
 /// [`BarrierActorInfo`] resolves the actor info read from meta store for
 /// [`crate::barrier::GlobalBarrierManager`].
 pub struct BarrierActorInfo {
-    /// node_id => node
-    pub node_map: HashMap<WorkerId, WorkerNode>,
+    /// node_id => node. Ordered so that barriers are injected to and collected from nodes in a
+    /// deterministic order.
+    pub node_map: BTreeMap<WorkerId, WorkerNode>,
 
     /// node_id => actors
     pub actor_map: HashMap<WorkerId, Vec<ActorId>>,
@@ -43,7 +44,7 @@ impl BarrierActorInfo {
         let node_map = all_nodes
             .into_iter()
             .map(|node| (node.id, node))
-            .collect::<HashMap<_, _>>();
+            .collect::<BTreeMap<_, _>>();
 
         Self {
             node_map,
@@ -12,20 +12,24 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, VecDeque};
 use std::iter::once;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
+use fail::fail_point;
 use futures::future::try_join_all;
 use itertools::Itertools;
 use risingwave_common::catalog::TableId;
-use risingwave_common::error::{ErrorCode, Result, RwError};
+use risingwave_common::error::{internal_error, ErrorCode, Result, RwError};
 use risingwave_common::util::epoch::INVALID_EPOCH;
 use risingwave_hummock_sdk::{HummockEpoch, LocalSstableInfo};
 use risingwave_pb::common::worker_node::State::Running;
-use risingwave_pb::common::WorkerType;
+use risingwave_pb::common::{ParallelUnit, WorkerType};
 use risingwave_pb::data::Barrier;
+use risingwave_pb::meta::table_fragments::Fragment;
+use risingwave_pb::meta::SystemParams;
 use risingwave_pb::stream_service::{
     BarrierCompleteRequest, BarrierCompleteResponse, InjectBarrierRequest,
 };
@@ -35,6 +39,8 @@ use tokio::sync::{oneshot, watch, RwLock};
 use tokio::task::JoinHandle;
 use uuid::Uuid;
 
+use self::actor_stats::report_actor_stats;
+pub use self::actor_stats::BlockingTimeRecorder;
 pub use self::command::Command;
 use self::command::CommandContext;
 use self::info::BarrierActorInfo;
@@ -42,17 +48,19 @@ use self::notifier::Notifier;
 use self::progress::CreateMviewProgressTracker;
 use crate::cluster::{ClusterManagerRef, META_NODE_ID};
 use crate::hummock::HummockManagerRef;
-use crate::manager::{CatalogManagerRef, MetaSrvEnv};
-use crate::model::BarrierManagerState;
+use crate::manager::{record_error, CatalogManagerRef, MetaSrvEnv};
+use crate::model::{ActorId, BarrierManagerState};
 use crate::rpc::metrics::MetaMetrics;
 use crate::storage::MetaStore;
 use crate::stream::FragmentManagerRef;
 
+mod actor_stats;
 mod command;
 mod info;
 mod notifier;
 mod progress;
 mod recovery;
+mod savepoint;
 
 type Scheduled = (Command, SmallVec<[Notifier; 1]>);
 
@@ -142,8 +150,12 @@ impl ScheduledBarriers {
 /// barrier manager and meta store, some actions like "drop materialized view" or "create mv on mv"
 /// must be done in barrier manager transactional using [`Command`].
 pub struct GlobalBarrierManager<S: MetaStore> {
-    /// The maximal interval for sending a barrier.
-    interval: Duration,
+    /// The maximal interval for sending a barrier, which can be changed at runtime.
+    interval_tx: watch::Sender<Duration>,
+    interval_rx: watch::Receiver<Duration>,
+
+    /// Number of barriers between two checkpoints, if not specified by the streaming jobs.
+    checkpoint_frequency: AtomicUsize,
 
     /// Enable recovery or not when failover.
     enable_recovery: bool,
@@ -161,6 +173,9 @@ pub struct GlobalBarrierManager<S: MetaStore> {
 
     metrics: Arc<MetaMetrics>,
 
+    /// The time actors of each fragment are blocked on sending to downstream actors.
+    blocking_time: Arc<BlockingTimeRecorder>,
+
     env: MetaSrvEnv<S>,
 }
 
@@ -179,14 +194,19 @@ where
     ) -> Self {
         let enable_recovery = env.opts.enable_recovery;
         let interval = env.opts.checkpoint_interval;
+        let checkpoint_frequency = env.opts.checkpoint_frequency;
         tracing::info!(
-            "Starting barrier manager with: interval={:?}, enable_recovery={}",
+            "Starting barrier manager with: interval={:?}, checkpoint_frequency={}, enable_recovery={}",
             interval,
+            checkpoint_frequency,
             enable_recovery
         );
+        let (interval_tx, interval_rx) = watch::channel(interval);
 
         Self {
-            interval,
+            interval_tx,
+            interval_rx,
+            checkpoint_frequency: AtomicUsize::new(checkpoint_frequency.max(1)),
             enable_recovery,
             cluster_manager,
             catalog_manager,
@@ -194,10 +214,44 @@ where
             scheduled_barriers: ScheduledBarriers::new(),
             hummock_manager,
             metrics,
+            blocking_time: Arc::new(BlockingTimeRecorder::default()),
             env,
         }
     }
 
+    /// Returns the recorder of the time actors are blocked on their downstream actors, which
+    /// measures the backpressure of the fragments.
+    pub fn blocking_time_recorder(&self) -> Arc<BlockingTimeRecorder> {
+        self.blocking_time.clone()
+    }
+
+    /// Replaces the actors of the fragment with the ones of the given fragment, which are
+    /// scheduled to `actor_locations`. All actors are stopped and rebuilt, and the progress since
+    /// the last checkpoint is replayed.
+    pub async fn reschedule_fragment(
+        &self,
+        table_id: TableId,
+        fragment: Fragment,
+        actor_locations: BTreeMap<ActorId, ParallelUnit>,
+    ) -> Result<()> {
+        let (tx, rx) = oneshot::channel();
+        self.scheduled_barriers
+            .push((
+                Command::Reschedule {
+                    table_id,
+                    fragment,
+                    actor_locations,
+                },
+                once(Notifier {
+                    collected: Some(tx),
+                    ..Default::default()
+                })
+                .collect(),
+            ))
+            .await;
+        rx.await.unwrap()
+    }
+
     pub async fn start(barrier_manager: BarrierManagerRef<S>) -> (JoinHandle<()>, Sender<()>) {
         let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel();
         let join_handle = tokio::spawn(async move {
@@ -207,20 +261,67 @@ where
         (join_handle, shutdown_tx)
     }
 
+    /// Start a worker to apply the barrier interval and checkpoint frequency whenever they are
+    /// changed by `ALTER SYSTEM`.
+    pub async fn start_system_params_watcher(
+        barrier_manager: BarrierManagerRef<S>,
+        mut params_rx: watch::Receiver<SystemParams>,
+    ) -> (JoinHandle<()>, Sender<()>) {
+        let (shutdown_tx, mut shutdown_rx) = tokio::sync::oneshot::channel();
+        let join_handle = tokio::spawn(async move {
+            loop {
+                let (interval, checkpoint_frequency) = {
+                    let params = params_rx.borrow();
+                    (
+                        Duration::from_millis(params.barrier_interval_ms as u64),
+                        params.checkpoint_frequency as usize,
+                    )
+                };
+                if interval != barrier_manager.interval() {
+                    barrier_manager.set_interval(interval);
+                }
+                barrier_manager.set_checkpoint_frequency(checkpoint_frequency);
+
+                tokio::select! {
+                    changed = params_rx.changed() => {
+                        if changed.is_err() {
+                            return;
+                        }
+                    }
+                    _ = &mut shutdown_rx => {
+                        return;
+                    }
+                }
+            }
+        });
+
+        (join_handle, shutdown_tx)
+    }
+
     /// Start an infinite loop to take scheduled barriers and send them.
     async fn run(&self, mut shutdown_rx: Receiver<()>) {
         let mut tracker = CreateMviewProgressTracker::default();
         let mut state = BarrierManagerState::create(self.env.meta_store()).await;
+        // Epochs must be greater than the ones sent before, which may be generated by a previous
+        // leader before the physical time bound was persisted.
+        let epoch_generator = self.env.epoch_generator();
+        epoch_generator.observe(state.prev_epoch).await;
+        let max_committed_epoch = self
+            .hummock_manager
+            .get_current_version()
+            .await
+            .max_committed_epoch;
+        epoch_generator.observe(max_committed_epoch.into()).await;
 
         if self.enable_recovery {
             // handle init, here we simply trigger a recovery process to achieve the consistency. We
             // may need to avoid this when we have more state persisted in meta store.
-            let new_epoch = state.prev_epoch.next();
+            let new_epoch = epoch_generator.generate().await.unwrap();
             assert!(new_epoch > state.prev_epoch);
             state.prev_epoch = new_epoch;
 
             let (new_epoch, actors_to_track, create_mview_progress) =
-                self.recovery(state.prev_epoch).await;
+                self.recovery(state.prev_epoch, None).await;
             tracker.add(new_epoch, actors_to_track, vec![]);
             for progress in create_mview_progress {
                 tracker.update(progress);
@@ -229,8 +330,10 @@ where
             state.update(self.env.meta_store()).await.unwrap();
         }
 
-        let mut min_interval = tokio::time::interval(self.interval);
-        min_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let mut interval_rx = self.interval_rx.clone();
+        let mut min_interval = Self::new_min_interval(*interval_rx.borrow());
+        // Number of barriers collected since the last checkpoint.
+        let mut uncheckpointed_barriers = 0;
         loop {
             tokio::select! {
                 biased;
@@ -239,6 +342,13 @@ where
                     tracing::info!("Barrier manager is shutting down");
                     return;
                 }
+                // The interval is changed.
+                Ok(()) = interval_rx.changed() => {
+                    let interval = *interval_rx.borrow();
+                    tracing::info!("Barrier interval is changed to {:?}", interval);
+                    min_interval = Self::new_min_interval(interval);
+                    continue;
+                }
                 // there's barrier scheduled.
                 _ = self.scheduled_barriers.wait_one() => {}
                 // Wait for the minimal interval,
@@ -246,6 +356,47 @@ where
             }
             // Get a barrier to send.
             let (command, notifiers) = self.scheduled_barriers.pop_or_default().await;
+            // Some commands are applied by rebuilding all actors in recovery.
+            let restore = match &command {
+                Command::RestoreSavepoint {
+                    table_ids,
+                    savepoint_epoch,
+                } => Some(Some((table_ids.as_slice(), *savepoint_epoch))),
+                Command::Reschedule { .. } => Some(None),
+                _ => None,
+            };
+            if let Some(restore) = restore {
+                let mut notifiers = notifiers;
+                notifiers.iter_mut().for_each(Notifier::notify_to_send);
+                if let Command::Reschedule {
+                    table_id,
+                    fragment,
+                    actor_locations,
+                } = &command
+                    && let Err(e) = self
+                        .fragment_manager
+                        .reschedule_fragment(table_id, fragment.clone(), actor_locations)
+                        .await
+                {
+                    notifiers
+                        .into_iter()
+                        .for_each(|notifier| notifier.notify_collection_failed(e.clone()));
+                    continue;
+                }
+                let (new_epoch, actors_to_track, create_mview_progress) =
+                    self.recovery(state.prev_epoch, restore).await;
+                notifiers.iter_mut().for_each(Notifier::notify_collected);
+                tracker = CreateMviewProgressTracker::default(); // Reset progress tracker
+                tracker.add(new_epoch, actors_to_track, vec![]);
+                for progress in create_mview_progress {
+                    tracker.update(progress);
+                }
+
+                state.prev_epoch = new_epoch;
+                uncheckpointed_barriers = 0;
+                state.update(self.env.meta_store()).await.unwrap();
+                continue;
+            }
             let info = self.resolve_actor_info(command.creating_table_id()).await;
             // When there's no actors exist in the cluster, we don't need to send the barrier. This
             // is an advance optimization. Besides if another barrier comes immediately,
@@ -256,8 +407,22 @@ where
                 notifiers.iter_mut().for_each(Notifier::notify_collected);
                 continue;
             }
-            let new_epoch = state.prev_epoch.next();
+            let mut notifiers = notifiers;
+            let new_epoch = match epoch_generator.generate().await {
+                Ok(new_epoch) => new_epoch,
+                Err(e) => {
+                    notifiers
+                        .into_iter()
+                        .for_each(|notifier| notifier.notify_collection_failed(e.clone()));
+                    continue;
+                }
+            };
             assert!(new_epoch > state.prev_epoch);
+            // Barriers with mutations or waited by someone are always checkpoints, so that their
+            // effects are committed once they're collected.
+            let checkpoint = !command.is_periodic()
+                || !notifiers.is_empty()
+                || uncheckpointed_barriers + 1 >= self.effective_checkpoint_frequency().await;
             let command_ctx = CommandContext::new(
                 self.fragment_manager.clone(),
                 self.env.stream_client_pool_ref(),
@@ -265,15 +430,25 @@ where
                 &state.prev_epoch,
                 &new_epoch,
                 command,
+                checkpoint,
             );
 
-            let mut notifiers = notifiers;
             notifiers.iter_mut().for_each(Notifier::notify_to_send);
             match self.run_inner(&command_ctx).await {
                 Ok(responses) => {
                     // Notify about collected first.
+                    notifiers.iter_mut().for_each(|notifier| {
+                        notifier.notify_collected_epoch(command_ctx.prev_epoch.0)
+                    });
                     notifiers.iter_mut().for_each(Notifier::notify_collected);
 
+                    report_actor_stats(
+                        &self.metrics,
+                        responses.iter().flat_map(|r| r.actor_stats.iter()),
+                    );
+                    self.blocking_time
+                        .record(responses.iter().flat_map(|r| r.actor_stats.iter()));
+
                     // Then try to finish the barrier for Create MVs.
                     let actors_to_track = command_ctx.actors_to_track();
                     tracker.add(new_epoch, actors_to_track, notifiers);
@@ -282,15 +457,21 @@ where
                     }
 
                     state.prev_epoch = new_epoch;
+                    if checkpoint {
+                        uncheckpointed_barriers = 0;
+                    } else {
+                        uncheckpointed_barriers += 1;
+                    }
                 }
                 Err(e) => {
+                    record_error("barrier_failure");
                     notifiers
                         .into_iter()
                         .for_each(|notifier| notifier.notify_collection_failed(e.clone()));
                     if self.enable_recovery {
                         // If failed, enter recovery mode.
                         let (new_epoch, actors_to_track, create_mview_progress) =
-                            self.recovery(new_epoch).await;
+                            self.recovery(new_epoch, None).await;
                         tracker = CreateMviewProgressTracker::default(); // Reset progress tracker
                         tracker.add(new_epoch, actors_to_track, vec![]);
                         for progress in create_mview_progress {
@@ -298,6 +479,7 @@ where
                         }
 
                         state.prev_epoch = new_epoch;
+                        uncheckpointed_barriers = 0;
                     } else {
                         panic!("failed to execute barrier: {:?}", e);
                     }
@@ -317,8 +499,8 @@ where
 
         // Wait for all barriers collected
         let result = self.inject_barrier(command_context).await;
-        // Commit this epoch to Hummock
-        if command_context.prev_epoch.0 != INVALID_EPOCH {
+        // Commit this epoch to Hummock, together with the previous epochs not checkpointed yet.
+        if command_context.checkpoint && command_context.prev_epoch.0 != INVALID_EPOCH {
             match &result {
                 Ok(resps) => {
                     // We must ensure all epochs are committed in ascending order, because
@@ -334,6 +516,9 @@ where
                             )
                         })
                         .collect_vec();
+                    fail_point!("commit_epoch_err", |_| Err(internal_error(
+                        "commit_epoch_err"
+                    )));
                     self.hummock_manager
                         .commit_epoch(command_context.prev_epoch.0, synced_ssts)
                         .await?;
@@ -382,6 +567,9 @@ where
                     span: vec![],
                 };
                 async move {
+                    fail_point!("inject_barrier_err", |_| Err(internal_error(
+                        "inject_barrier_err"
+                    )));
                     let mut client = self.env.stream_client_pool().get(node).await?;
 
                     let request = InjectBarrierRequest {
@@ -416,12 +604,17 @@ where
             } else {
                 let request_id = Uuid::new_v4().to_string();
                 let prev_epoch = command_context.prev_epoch.0;
+                let checkpoint = command_context.checkpoint;
                 async move {
+                    fail_point!("collect_barrier_err", |_| Err(internal_error(
+                        "collect_barrier_err"
+                    )));
                     let mut client = self.env.stream_client_pool().get(node).await?;
 
                     let request = BarrierCompleteRequest {
                         request_id,
                         prev_epoch,
+                        checkpoint,
                     };
                     tracing::trace!(
                         target: "events::meta::barrier::barrier_complete",
@@ -442,6 +635,46 @@ where
         try_join_all(collect_futures).await
     }
 
+    fn new_min_interval(interval: Duration) -> tokio::time::Interval {
+        let mut min_interval = tokio::time::interval(interval);
+        min_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        min_interval
+    }
+
+    /// Changes the maximal interval for sending a barrier. It takes effect immediately.
+    pub fn set_interval(&self, interval: Duration) {
+        self.interval_tx.send(interval).ok();
+    }
+
+    pub fn interval(&self) -> Duration {
+        *self.interval_rx.borrow()
+    }
+
+    /// Changes the number of barriers between two checkpoints for the streaming jobs not
+    /// specifying their own.
+    pub fn set_checkpoint_frequency(&self, checkpoint_frequency: usize) {
+        self.checkpoint_frequency
+            .store(checkpoint_frequency.max(1), Ordering::Relaxed);
+    }
+
+    pub fn checkpoint_frequency(&self) -> usize {
+        self.checkpoint_frequency.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of barriers between two checkpoints. As writes of all streaming jobs are
+    /// committed together, a checkpoint is made once any job requires it, i.e. the minimal
+    /// frequency among all the jobs.
+    async fn effective_checkpoint_frequency(&self) -> usize {
+        let default = self.checkpoint_frequency();
+        self.fragment_manager
+            .list_checkpoint_frequencies()
+            .await
+            .into_iter()
+            .map(|frequency| frequency.map_or(default, |frequency| frequency as usize))
+            .min()
+            .unwrap_or(default)
+    }
+
     /// Resolve actor information from cluster and fragment manager.
     async fn resolve_actor_info(&self, creating_table_id: Option<TableId>) -> BarrierActorInfo {
         let all_nodes = self
@@ -485,7 +718,14 @@ where
 
     /// Run a command and return when it's completely finished.
     pub async fn run_command(&self, command: Command) -> Result<()> {
+        self.run_command_with_epoch(command).await.map(|_| ())
+    }
+
+    /// Run a command and return when it's completely finished, with the `prev_epoch` of its
+    /// barrier, i.e. the epoch of the last data before the command takes effect.
+    pub async fn run_command_with_epoch(&self, command: Command) -> Result<HummockEpoch> {
         let (collect_tx, collect_rx) = oneshot::channel();
+        let (epoch_tx, epoch_rx) = oneshot::channel();
         let (finish_tx, finish_rx) = oneshot::channel();
 
         let is_create_mv = matches!(command, Command::CreateMaterializedView { .. });
@@ -494,6 +734,7 @@ where
             command,
             Notifier {
                 collected: Some(collect_tx),
+                collected_epoch: Some(epoch_tx),
                 finished: Some(finish_tx),
                 ..Default::default()
             },
@@ -501,6 +742,8 @@ where
         .await?;
 
         collect_rx.await.unwrap()?; // Throw the error if it occurs when collecting this barrier.
+                                    // No barrier is sent if there's no actor in the cluster.
+        let prev_epoch = epoch_rx.await.unwrap_or(INVALID_EPOCH);
 
         // TODO: refactor this
         if is_create_mv {
@@ -518,7 +761,7 @@ where
             finish_rx.await.unwrap(); // Wait for this command to be finished.
         }
 
-        Ok(())
+        Ok(prev_epoch)
     }
 
     /// Wait for the next barrier to collect. Note that the barrier flowing in our stream graph is
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use risingwave_common::error::{Result, RwError};
+use risingwave_hummock_sdk::HummockEpoch;
 use tokio::sync::oneshot;
 
 /// Used for notifying the status of a scheduled command/barrier.
@@ -24,6 +25,10 @@ pub(super) struct Notifier {
     /// Get notified when scheduled barrier is collected or failed.
     pub collected: Option<oneshot::Sender<Result<()>>>,
 
+    /// Get notified of the `prev_epoch` of the scheduled barrier when it's collected, i.e. the
+    /// epoch of the last data before it.
+    pub collected_epoch: Option<oneshot::Sender<HummockEpoch>>,
+
     /// Get notified when scheduled barrier is finished.
     pub finished: Option<oneshot::Sender<()>>,
 }
@@ -43,6 +48,13 @@ impl Notifier {
         }
     }
 
+    /// Notify the `prev_epoch` of the barrier when we have collected it from all actors.
+    pub fn notify_collected_epoch(&mut self, prev_epoch: HummockEpoch) {
+        if let Some(tx) = self.collected_epoch.take() {
+            tx.send(prev_epoch).ok();
+        }
+    }
+
     /// Notify when we failed to collect a barrier. This function consumes `self`.
     pub fn notify_collection_failed(self, err: RwError) {
         if let Some(tx) = self.collected {
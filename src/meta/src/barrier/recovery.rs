@@ -17,15 +17,18 @@ use std::iter::Map;
 use std::time::Duration;
 
 use futures::future::try_join_all;
+use itertools::Itertools;
 use log::{debug, error};
+use risingwave_common::catalog::TableId;
 use risingwave_common::error::{ErrorCode, Result, RwError};
 use risingwave_common::util::epoch::Epoch;
+use risingwave_hummock_sdk::HummockEpoch;
 use risingwave_pb::common::ActorInfo;
 use risingwave_pb::data::Epoch as ProstEpoch;
 use risingwave_pb::stream_service::barrier_complete_response::CreateMviewProgress;
 use risingwave_pb::stream_service::{
-    BroadcastActorInfoTableRequest, BuildActorsRequest, ForceStopActorsRequest, SyncSourcesRequest,
-    UpdateActorsRequest,
+    BroadcastActorInfoTableRequest, BuildActorsRequest, ForceStopActorsRequest,
+    RestoreTablesRequest, SyncSourcesRequest, UpdateActorsRequest,
 };
 use tokio_retry::strategy::{jitter, ExponentialBackoff};
 use uuid::Uuid;
@@ -55,8 +58,13 @@ where
             .map(jitter)
     }
 
-    /// Recovery the whole cluster from the latest epoch.
-    pub(crate) async fn recovery(&self, prev_epoch: Epoch) -> RecoveryResult {
+    /// Recovery the whole cluster from the latest epoch. If `restore` is given, the tables are
+    /// rolled back to the savepoint before the actors are rebuilt.
+    pub(crate) async fn recovery(
+        &self,
+        prev_epoch: Epoch,
+        restore: Option<(&[TableId], HummockEpoch)>,
+    ) -> RecoveryResult {
         // Abort buffered schedules, they might be dirty already.
         self.scheduled_barriers.abort().await;
 
@@ -64,12 +72,25 @@ where
         let retry_strategy = Self::get_retry_strategy();
         let (new_epoch, responses) = tokio_retry::Retry::spawn(retry_strategy, || async {
             let info = self.resolve_actor_info(None).await;
-            let mut new_epoch = prev_epoch.next();
+            // Epochs generated by failed attempts may have been sent, so each attempt generates
+            // new ones.
+            let mut new_epoch = self.env.epoch_generator().generate().await?;
 
             // Reset all compute nodes, stop and drop existing actors.
             self.reset_compute_nodes(&info, &prev_epoch, &new_epoch)
                 .await;
 
+            if let Some((table_ids, savepoint_epoch)) = restore {
+                if let Err(err) = self
+                    .restore_tables(&info, table_ids, savepoint_epoch, &new_epoch)
+                    .await
+                {
+                    error!("restore_tables failed: {}", err);
+                    return Err(err);
+                }
+                new_epoch = self.env.epoch_generator().generate().await?;
+            }
+
             // Refresh sources in local source manger of compute node.
             if let Err(err) = self.sync_sources(&info).await {
                 error!("sync_sources failed: {}", err);
@@ -87,15 +108,23 @@ where
             }
 
             let prev_epoch = new_epoch;
-            new_epoch = prev_epoch.next();
-            // checkpoint, used as init barrier to initialize all executors.
+            new_epoch = self.env.epoch_generator().generate().await?;
+            // checkpoint, used as init barrier to initialize all executors. It also reapplies the
+            // config changes to the rebuilt actors.
+            let config_changes = self.fragment_manager.list_config_changes().await;
+            let command = if config_changes.is_empty() {
+                Command::checkpoint()
+            } else {
+                Command::reconfigure(config_changes)
+            };
             let command_ctx = CommandContext::new(
                 self.fragment_manager.clone(),
                 self.env.stream_client_pool_ref(),
                 &info,
                 &prev_epoch,
                 &new_epoch,
-                Command::checkpoint(),
+                command,
+                true,
             );
 
             match self.inject_barrier(&command_ctx).await {
@@ -126,6 +155,54 @@ where
         )
     }
 
+    /// Roll the tables back to the savepoint on one of the compute nodes, and commit the restored
+    /// data at `epoch`. Restoring again is a no-op, as the committed data equals the savepoint.
+    async fn restore_tables(
+        &self,
+        info: &BarrierActorInfo,
+        table_ids: &[TableId],
+        savepoint_epoch: HummockEpoch,
+        epoch: &Epoch,
+    ) -> Result<()> {
+        let node = info.node_map.values().next().ok_or_else(|| {
+            RwError::from(ErrorCode::InternalError(
+                "no compute node to restore the tables".to_string(),
+            ))
+        })?;
+        let current_epoch = self
+            .hummock_manager
+            .get_current_version()
+            .await
+            .max_committed_epoch;
+        let client = self.env.stream_client_pool().get(node).await?;
+        let response = client
+            .to_owned()
+            .restore_tables(RestoreTablesRequest {
+                table_ids: table_ids.iter().map(TableId::table_id).collect(),
+                savepoint_epoch,
+                current_epoch,
+                write_epoch: epoch.0,
+            })
+            .await?
+            .into_inner();
+        let synced_ssts = response
+            .sycned_sstables
+            .into_iter()
+            .map(|grouped| {
+                (
+                    grouped.compaction_group_id,
+                    grouped.sst.expect("field not None"),
+                )
+            })
+            .collect_vec();
+        self.hummock_manager
+            .commit_epoch(epoch.0, synced_ssts)
+            .await?;
+        debug!("tables restored to savepoint {}", savepoint_epoch);
+
+        Ok(())
+    }
+
     /// Sync all sources in compute nodes, the local source manager in compute nodes may be dirty
     /// already.
     async fn sync_sources(&self, info: &BarrierActorInfo) -> Result<()> {
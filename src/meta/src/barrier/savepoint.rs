@@ -0,0 +1,166 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::iter::once;
+
+use itertools::Itertools;
+use risingwave_common::catalog::TableId;
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_hummock_sdk::HummockEpoch;
+use tokio::sync::oneshot;
+
+use super::notifier::Notifier;
+use super::{Command, GlobalBarrierManager};
+use crate::model::TableFragments;
+use crate::storage::MetaStore;
+
+impl<S> GlobalBarrierManager<S>
+where
+    S: MetaStore,
+{
+    /// Takes a savepoint of the streaming job `table_id` at the next checkpoint, replacing the
+    /// previous one if any. Compaction keeps the data visible to the savepoint, of all tables in
+    /// the cluster, until the savepoint is released or the job is dropped.
+    pub async fn savepoint(&self, table_id: &TableId) -> Result<HummockEpoch> {
+        let table_fragments = self
+            .fragment_manager
+            .select_table_fragments_by_table_id(table_id)
+            .await?;
+        Self::check_restorable(&table_fragments)?;
+
+        // All writes issued before are committed once the barrier is collected.
+        self.wait_for_next_barrier_to_collect().await?;
+        let savepoint_epoch = self
+            .hummock_manager
+            .pin_savepoint(table_id.table_id())
+            .await;
+        self.fragment_manager
+            .update_savepoint_epoch(table_id, Some(savepoint_epoch))
+            .await?;
+
+        Ok(savepoint_epoch)
+    }
+
+    /// Releases the savepoint of the streaming job `table_id`.
+    pub async fn release_savepoint(&self, table_id: &TableId) -> Result<()> {
+        self.fragment_manager
+            .update_savepoint_epoch(table_id, None)
+            .await?;
+        self.unpin_savepoint(table_id).await;
+        Ok(())
+    }
+
+    /// Pins the max committed epoch as a savepoint of the streaming job `table_id` without
+    /// persisting it, which keeps the states adopted by the job being created from compaction.
+    pub async fn pin_savepoint(&self, table_id: &TableId) -> HummockEpoch {
+        self.hummock_manager
+            .pin_savepoint(table_id.table_id())
+            .await
+    }
+
+    /// Unpins the savepoint of the dropped streaming job `table_id`.
+    pub async fn unpin_savepoint(&self, table_id: &TableId) {
+        self.hummock_manager
+            .unpin_savepoint(table_id.table_id())
+            .await;
+    }
+
+    /// Rolls the streaming job `table_id` back to its savepoint, including the materialized view,
+    /// the internal states and the split offsets of its sources. All actors in the cluster are
+    /// rebuilt in a recovery, during which the tables are restored. Returns when it's finished.
+    ///
+    /// It's rejected if other streaming jobs read the materialized view or the sources of the job,
+    /// as their states would be inconsistent with the restored ones.
+    pub async fn restore_savepoint(&self, table_id: &TableId) -> Result<()> {
+        if !self.enable_recovery {
+            return Err(ErrorCode::NotImplemented(
+                "restoring savepoints with recovery disabled".to_string(),
+                None.into(),
+            )
+            .into());
+        }
+        let all_table_fragments = self.fragment_manager.list_table_fragments().await?;
+        let table_fragments = all_table_fragments
+            .iter()
+            .find(|table_fragments| table_fragments.table_id() == *table_id)
+            .ok_or_else(|| ErrorCode::ItemNotFound(format!("streaming job {}", table_id)))?;
+        let savepoint_epoch = table_fragments
+            .savepoint_epoch()
+            .ok_or_else(|| ErrorCode::ItemNotFound("savepoint".to_string()))?;
+        Self::check_restorable(table_fragments)?;
+
+        let source_ids = table_fragments.stream_source_ids();
+        for other in &all_table_fragments {
+            if other.table_id() == *table_id {
+                continue;
+            }
+            if other.dependent_table_ids().contains(table_id) {
+                return Err(ErrorCode::NotImplemented(
+                    "restoring savepoints of materialized views read by other materialized views"
+                        .to_string(),
+                    None.into(),
+                )
+                .into());
+            }
+            if !other.stream_source_ids().is_disjoint(&source_ids) {
+                return Err(ErrorCode::NotImplemented(
+                    "restoring savepoints of materialized views sharing sources with others"
+                        .to_string(),
+                    None.into(),
+                )
+                .into());
+            }
+        }
+
+        let table_ids = once(*table_id)
+            .chain(
+                table_fragments
+                    .internal_table_ids()
+                    .into_iter()
+                    .map(TableId::new),
+            )
+            .chain(source_ids.into_iter().map(TableId::new))
+            .unique()
+            .collect();
+        let (tx, rx) = oneshot::channel();
+        self.scheduled_barriers
+            .push((
+                Command::RestoreSavepoint {
+                    table_ids,
+                    savepoint_epoch,
+                },
+                once(Notifier {
+                    collected: Some(tx),
+                    ..Default::default()
+                })
+                .collect(),
+            ))
+            .await;
+        rx.await.unwrap()
+    }
+
+    /// Only the jobs reading sources directly can be restored, since the sources replay from the
+    /// restored split offsets. The changes of upstream tables since the savepoint can't be
+    /// replayed.
+    fn check_restorable(table_fragments: &TableFragments) -> Result<()> {
+        if !table_fragments.chain_actor_ids().is_empty() {
+            return Err(ErrorCode::NotImplemented(
+                "savepoints of materialized views on tables or materialized views".to_string(),
+                None.into(),
+            )
+            .into());
+        }
+        Ok(())
+    }
+}
@@ -14,13 +14,14 @@
 
 use std::cmp;
 use std::collections::hash_map::Entry;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 use std::ops::Add;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 
 use itertools::Itertools;
+use risingwave_common::catalog::DEFAULT_RESOURCE_GROUP;
 use risingwave_common::error::{internal_error, ErrorCode, Result};
 use risingwave_common::try_match_expand;
 use risingwave_common::types::ParallelUnitId;
@@ -31,7 +32,7 @@ use tokio::sync::oneshot::Sender;
 use tokio::sync::{RwLock, RwLockReadGuard};
 use tokio::task::JoinHandle;
 
-use crate::manager::{IdCategory, LocalNotification, MetaSrvEnv};
+use crate::manager::{record_error, IdCategory, LocalNotification, MetaSrvEnv};
 use crate::model::{MetadataModel, Worker, INVALID_EXPIRE_AT};
 use crate::storage::MetaStore;
 
@@ -58,6 +59,15 @@ impl Hash for WorkerKey {
     }
 }
 
+/// Whether frontends schedule batch tasks to workers of the type, and are thus notified of their
+/// membership changes.
+fn is_batch_worker(worker_type: WorkerType) -> bool {
+    matches!(
+        worker_type,
+        WorkerType::ComputeNode | WorkerType::ServingNode
+    )
+}
+
 /// The id preserved for the meta node. Note that there's no such entry in cluster manager.
 pub const META_NODE_ID: u32 = 0;
 
@@ -100,6 +110,24 @@ where
         host_address: HostAddress,
         r#type: WorkerType,
     ) -> Result<(WorkerNode, bool)> {
+        self.add_worker_node_in_resource_group(host_address, r#type, DEFAULT_RESOURCE_GROUP)
+            .await
+    }
+
+    /// Same as [`Self::add_worker_node`], but labels the worker with the resource group. An empty
+    /// resource group means the default one. The resource group of a registered worker is kept,
+    /// and the node must restart after its lease expires to change it.
+    pub async fn add_worker_node_in_resource_group(
+        &self,
+        host_address: HostAddress,
+        r#type: WorkerType,
+        resource_group: &str,
+    ) -> Result<(WorkerNode, bool)> {
+        let resource_group = if resource_group.is_empty() {
+            DEFAULT_RESOURCE_GROUP
+        } else {
+            resource_group
+        };
         let mut core = self.core.write().await;
         match core.get_worker_by_host(host_address.clone()) {
             Some(worker) => Ok((worker.to_protobuf(), false)),
@@ -126,6 +154,7 @@ where
                     host: Some(host_address.clone()),
                     state: State::Starting as i32,
                     parallel_units,
+                    resource_group: resource_group.to_string(),
                 };
 
                 let worker = Worker::from_protobuf(worker_node.clone());
@@ -153,7 +182,7 @@ where
         core.update_worker_node(worker.clone());
 
         // Notify frontends of new compute node.
-        if worker.worker_node.r#type == WorkerType::ComputeNode as i32 {
+        if is_batch_worker(worker.worker_type()) {
             self.env
                 .notification_manager()
                 .notify_frontend(Operation::Add, Info::Node(worker.worker_node))
@@ -176,7 +205,7 @@ where
         core.delete_worker_node(worker);
 
         // Notify frontends to delete compute node.
-        if worker_type == WorkerType::ComputeNode {
+        if is_batch_worker(worker_type) {
             self.env
                 .notification_manager()
                 .notify_frontend(Operation::Delete, Info::Node(worker_node.clone()))
@@ -192,16 +221,66 @@ where
         Ok(())
     }
 
+    /// Invoked when a worker node starts to shut down gracefully. The worker keeps running its
+    /// actors and tasks, but no new ones will be scheduled to it.
+    pub async fn cordon_worker_node(&self, host_address: HostAddress) -> Result<()> {
+        let mut core = self.core.write().await;
+        let mut worker = core.get_worker_by_host_checked(host_address)?;
+        if worker.is_cordoned() {
+            return Ok(());
+        }
+        worker.set_cordoned();
+        core.update_worker_node(worker.clone());
+        tracing::info!("Cordoned worker {}", worker.worker_id());
+
+        // Notify frontends to stop scheduling batch tasks to the compute node.
+        if is_batch_worker(worker.worker_type())
+            && worker.worker_node.state == State::Running as i32
+        {
+            self.env
+                .notification_manager()
+                .notify_frontend(Operation::Delete, Info::Node(worker.worker_node))
+                .await;
+        }
+
+        Ok(())
+    }
+
+    /// Evicts a worker whose heartbeat lease has expired. The worker is marked as `Deleting` first,
+    /// so that it's no longer used by meta and frontends even if the deletion fails and has to be
+    /// retried in the next check.
+    async fn evict_worker_node(&self, host_address: HostAddress) -> Result<()> {
+        {
+            let mut core = self.core.write().await;
+            let mut worker = core.get_worker_by_host_checked(host_address.clone())?;
+            if worker.worker_node.state != State::Deleting as i32 {
+                worker.worker_node.state = State::Deleting as i32;
+                worker.insert(self.env.meta_store()).await?;
+                core.update_worker_node(worker.clone());
+
+                if is_batch_worker(worker.worker_type()) {
+                    self.env
+                        .notification_manager()
+                        .notify_frontend(Operation::Delete, Info::Node(worker.worker_node))
+                        .await;
+                }
+            }
+        }
+        self.delete_worker_node(host_address).await
+    }
+
     /// Invoked when it receives a heartbeat from a worker node.
     pub async fn heartbeat(&self, worker_id: WorkerId) -> Result<()> {
         tracing::trace!(target: "events::meta::server_heartbeat", worker_id = worker_id, "receive heartbeat");
         let mut core = self.core.write().await;
 
-        if let Some(worker) = core.get_worker_by_id(worker_id) {
-            core.update_worker_ttl(worker.key().unwrap(), self.max_heartbeat_interval);
-            Ok(())
-        } else {
-            Err(ErrorCode::UnknownWorker.into())
+        match core.get_worker_by_id(worker_id) {
+            // A worker being evicted must restart and register again.
+            Some(worker) if worker.worker_node.state != State::Deleting as i32 => {
+                core.update_worker_ttl(worker.key().unwrap(), self.max_heartbeat_interval);
+                Ok(())
+            }
+            _ => Err(ErrorCode::UnknownWorker.into()),
         }
     }
 
@@ -247,8 +326,9 @@ where
                 // 2. Delete expired workers.
                 for worker in workers_to_init_or_delete {
                     let key = worker.key().expect("illegal key");
-                    match cluster_manager.delete_worker_node(key.clone()).await {
+                    match cluster_manager.evict_worker_node(key.clone()).await {
                         Ok(_) => {
+                            record_error("worker_expired");
                             cluster_manager
                                 .env
                                 .notification_manager()
@@ -294,6 +374,13 @@ where
         core.list_worker_node(worker_type, worker_state)
     }
 
+    /// Get running nodes with the specified type that are not cordoned, i.e. nodes that new
+    /// actors can be scheduled to.
+    pub async fn list_schedulable_worker_node(&self, worker_type: WorkerType) -> Vec<WorkerNode> {
+        let core = self.core.read().await;
+        core.list_schedulable_worker_node(worker_type)
+    }
+
     pub async fn list_parallel_units(
         &self,
         parallel_unit_type: Option<ParallelUnitType>,
@@ -310,6 +397,17 @@ where
         core.get_parallel_unit_count(parallel_unit_type)
     }
 
+    /// Lists the parallel units of the workers in the resource group, in the same order as
+    /// [`Self::list_parallel_units`].
+    pub async fn list_resource_group_parallel_units(
+        &self,
+        parallel_unit_type: ParallelUnitType,
+        resource_group: &str,
+    ) -> Vec<ParallelUnit> {
+        let core = self.core.read().await;
+        core.list_resource_group_parallel_units(parallel_unit_type, resource_group)
+    }
+
     async fn generate_cn_parallel_units(
         &self,
         parallel_degree: usize,
@@ -454,6 +552,15 @@ impl ClusterManagerCore {
             .collect::<Vec<_>>()
     }
 
+    fn list_schedulable_worker_node(&self, worker_type: WorkerType) -> Vec<WorkerNode> {
+        self.workers
+            .values()
+            .filter(|worker| !worker.is_cordoned())
+            .map(|worker| worker.to_protobuf())
+            .filter(|w| w.r#type == worker_type as i32 && w.state == State::Running as i32)
+            .collect_vec()
+    }
+
     fn list_parallel_units(
         &self,
         parallel_unit_type: Option<ParallelUnitType>,
@@ -469,6 +576,23 @@ impl ClusterManagerCore {
         }
     }
 
+    fn list_resource_group_parallel_units(
+        &self,
+        parallel_unit_type: ParallelUnitType,
+        resource_group: &str,
+    ) -> Vec<ParallelUnit> {
+        let worker_ids = self
+            .workers
+            .values()
+            .filter(|worker| worker.worker_node.resource_group == resource_group)
+            .map(|worker| worker.worker_id())
+            .collect::<HashSet<_>>();
+        self.list_parallel_units(Some(parallel_unit_type))
+            .into_iter()
+            .filter(|parallel_unit| worker_ids.contains(&parallel_unit.worker_node_id))
+            .collect()
+    }
+
     fn get_parallel_unit_count(&self, parallel_unit_type: Option<ParallelUnitType>) -> usize {
         match parallel_unit_type {
             Some(ParallelUnitType::Single) => self.single_parallel_units.len(),
@@ -545,6 +669,78 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_cordon_worker_node() -> Result<()> {
+        let env = MetaSrvEnv::for_test().await;
+        let cluster_manager = ClusterManager::new(env, Duration::new(0, 0)).await?;
+
+        for port in [5000, 5001] {
+            let host = HostAddress {
+                host: "localhost".to_string(),
+                port,
+            };
+            cluster_manager
+                .add_worker_node(host.clone(), WorkerType::ComputeNode)
+                .await?;
+            cluster_manager.activate_worker_node(host).await?;
+        }
+        let host_to_cordon = HostAddress {
+            host: "localhost".to_string(),
+            port: 5000,
+        };
+        cluster_manager
+            .cordon_worker_node(host_to_cordon.clone())
+            .await?;
+
+        // The cordoned node is still running, but not schedulable any more.
+        assert_eq!(
+            cluster_manager
+                .list_worker_node(WorkerType::ComputeNode, Some(State::Running))
+                .await
+                .len(),
+            2
+        );
+        let schedulable = cluster_manager
+            .list_schedulable_worker_node(WorkerType::ComputeNode)
+            .await;
+        assert_eq!(schedulable.len(), 1);
+        assert_ne!(schedulable[0].host, Some(host_to_cordon));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_serving_node() -> Result<()> {
+        let env = MetaSrvEnv::for_test().await;
+        let cluster_manager = ClusterManager::new(env, Duration::new(0, 0)).await?;
+
+        let host = HostAddress {
+            host: "localhost".to_string(),
+            port: 5000,
+        };
+        let (worker_node, _) = cluster_manager
+            .add_worker_node(host.clone(), WorkerType::ServingNode)
+            .await?;
+        cluster_manager.activate_worker_node(host).await?;
+
+        // Serving nodes are never scheduled with streaming actors.
+        assert!(worker_node.parallel_units.is_empty());
+        assert_cluster_manager(&cluster_manager, 0, 0).await;
+        assert!(cluster_manager
+            .list_schedulable_worker_node(WorkerType::ComputeNode)
+            .await
+            .is_empty());
+        assert_eq!(
+            cluster_manager
+                .list_worker_node(WorkerType::ServingNode, Some(State::Running))
+                .await
+                .len(),
+            1
+        );
+
+        Ok(())
+    }
+
     async fn assert_cluster_manager(
         cluster_manager: &ClusterManager<MemStore>,
         single_parallel_count: usize,
@@ -53,6 +53,8 @@ impl CompactionConfigBuilder {
                     "Zstd".to_string(),
                     "Zstd".to_string(),
                 ],
+                // use the default level of each algorithm
+                compression_level: vec![],
             },
         }
     }
@@ -95,4 +97,5 @@ builder_field! {
     level0_tier_compact_file_number: u64,
     compaction_mode: i32,
     compression_algorithm: Vec<String>,
+    compression_level: Vec<u32>,
 }
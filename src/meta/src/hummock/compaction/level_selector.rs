@@ -232,12 +232,18 @@ impl DynamicLevelSelector {
             input.target_file_size = self.config.target_file_size_base
                 << (input.target_level.level_idx as usize - base_level);
         }
-        if input.target_level.level_idx == 0 {
-            input.compression_algorithm = self.config.compression_algorithm[0].clone();
+        let idx = if input.target_level.level_idx == 0 {
+            0
         } else {
-            let idx = input.target_level.level_idx as usize - base_level + 1;
-            input.compression_algorithm = self.config.compression_algorithm[idx].clone();
-        }
+            input.target_level.level_idx as usize - base_level + 1
+        };
+        input.compression_algorithm = self.config.compression_algorithm[idx].clone();
+        input.compression_level = self
+            .config
+            .compression_level
+            .get(idx)
+            .copied()
+            .unwrap_or_default();
     }
 }
 
@@ -462,6 +468,7 @@ pub mod tests {
             .min_compaction_bytes(1)
             .max_bytes_for_level_base(100)
             .level0_tigger_file_numer(8)
+            .compression_level(vec![0, 0, 1, 2, 3, 19, 19])
             .build();
         let selector = DynamicLevelSelector::new(
             Arc::new(config.clone()),
@@ -493,6 +500,7 @@ pub mod tests {
             config.target_file_size_base * 4
         );
         assert_eq!(compaction.compression_algorithm.as_str(), "Lz4",);
+        assert_eq!(compaction.compression_level, 3);
         // no compaction need to be scheduled because we do not calculate the size of pending files
         // to score.
         let compaction = selector.pick_compaction(2, &levels, &mut levels_handlers);
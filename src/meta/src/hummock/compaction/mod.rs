@@ -19,7 +19,7 @@ mod min_overlap_compaction_picker;
 mod overlap_strategy;
 mod prost_type;
 mod tier_compaction_picker;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fmt::{Debug, Formatter};
 use std::sync::Arc;
 
@@ -77,6 +77,7 @@ pub struct SearchResult {
     target_level: Level,
     split_ranges: Vec<KeyRange>,
     compression_algorithm: String,
+    compression_level: u32,
     target_file_size: u64,
 }
 
@@ -151,7 +152,10 @@ impl CompactStatus {
             vnode_mappings: vec![],
             compaction_group_id,
             existing_table_ids: vec![],
+            table_retention_seconds: HashMap::new(),
+            table_partition_retention: HashMap::new(),
             compression_algorithm,
+            compression_level: ret.compression_level,
             target_file_size: ret.target_file_size,
         };
         Some(compact_task)
@@ -118,6 +118,7 @@ impl CompactionPicker for TierCompactionPicker {
                 },
                 split_ranges: vec![],
                 compression_algorithm: "".to_string(),
+                compression_level: 0,
                 target_file_size: 0,
             });
         }
@@ -223,6 +224,7 @@ impl CompactionPicker for LevelCompactionPicker {
             split_ranges: splits,
             target_file_size: self.config.target_file_size_base,
             compression_algorithm: "".to_string(),
+            compression_level: 0,
         })
     }
 }
@@ -12,13 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
 
 use itertools::Itertools;
 use risingwave_hummock_sdk::compaction_group::{Prefix, StaticCompactionGroupId};
 use risingwave_hummock_sdk::CompactionGroupId;
-use risingwave_pb::hummock::CompactionConfig;
+use risingwave_pb::hummock::{CompactionConfig, PartitionRetention};
 use tokio::sync::RwLock;
 
 use crate::hummock::compaction::compaction_config::CompactionConfigBuilder;
@@ -94,10 +94,35 @@ impl<S: MetaStore> CompactionGroupManager<S> {
                 CompactionGroupId::from(StaticCompactionGroupId::StateDefault),
             ));
         }
+        let table_retention_seconds = table_fragments
+            .retention_seconds()
+            .map(|retention_seconds| {
+                (
+                    Prefix::from(table_fragments.table_id().table_id),
+                    retention_seconds,
+                )
+            })
+            .into_iter()
+            .collect();
+        let table_partition_retention = table_fragments
+            .partition_retention()
+            .map(|partition_retention| {
+                (
+                    Prefix::from(table_fragments.table_id().table_id),
+                    partition_retention.clone(),
+                )
+            })
+            .into_iter()
+            .collect();
         self.inner
             .write()
             .await
-            .register(&pairs, self.env.meta_store())
+            .register(
+                &pairs,
+                &table_retention_seconds,
+                &table_partition_retention,
+                self.env.meta_store(),
+            )
             .await
     }
 
@@ -126,6 +151,8 @@ impl<S: MetaStore> CompactionGroupManager<S> {
                     source_id.into(),
                     StaticCompactionGroupId::StateDefault.into(),
                 )],
+                &HashMap::new(),
+                &HashMap::new(),
                 self.env.meta_store(),
             )
             .await
@@ -234,9 +261,14 @@ impl CompactionGroupManagerInner {
         Ok(())
     }
 
+    /// Registers `pairs` to compaction groups. Members in `table_retention_seconds` expire their
+    /// keys older than the given seconds in compaction, and members in `table_partition_retention`
+    /// their expired partitions.
     async fn register<S: MetaStore>(
         &mut self,
         pairs: &[(Prefix, CompactionGroupId)],
+        table_retention_seconds: &HashMap<Prefix, u32>,
+        table_partition_retention: &HashMap<Prefix, PartitionRetention>,
         meta_store: &S,
     ) -> Result<()> {
         let mut compaction_groups = VarTransaction::new(&mut self.compaction_groups);
@@ -245,6 +277,16 @@ impl CompactionGroupManagerInner {
                 .get_mut(compaction_group_id)
                 .ok_or(Error::InvalidCompactionGroup(*compaction_group_id))?;
             compaction_group.member_prefixes.insert(*prefix);
+            if let Some(retention_seconds) = table_retention_seconds.get(prefix) {
+                compaction_group
+                    .table_retention_seconds
+                    .insert(u32::from(*prefix), *retention_seconds);
+            }
+            if let Some(partition_retention) = table_partition_retention.get(prefix) {
+                compaction_group
+                    .table_partition_retention
+                    .insert(u32::from(*prefix), partition_retention.clone());
+            }
         }
         let mut trx = Transaction::default();
         compaction_groups.apply_to_txn(&mut trx)?;
@@ -274,6 +316,12 @@ impl CompactionGroupManagerInner {
                 .get_mut(&compaction_group_id)
                 .ok_or(Error::InvalidCompactionGroup(compaction_group_id))?;
             compaction_group.member_prefixes.remove(prefix);
+            compaction_group
+                .table_retention_seconds
+                .remove(&u32::from(*prefix));
+            compaction_group
+                .table_partition_retention
+                .remove(&u32::from(*prefix));
         }
         let mut trx = Transaction::default();
         compaction_groups.apply_to_txn(&mut trx)?;
@@ -299,10 +347,12 @@ impl CompactionGroupManagerInner {
 #[cfg(test)]
 mod tests {
 
+    use std::collections::HashMap;
     use std::ops::Deref;
 
     use risingwave_common::catalog::TableId;
     use risingwave_hummock_sdk::compaction_group::{Prefix, StaticCompactionGroupId};
+    use risingwave_hummock_sdk::CompactionGroupId;
 
     use crate::hummock::compaction_group::manager::{
         CompactionGroupManager, CompactionGroupManagerInner,
@@ -336,6 +386,8 @@ mod tests {
                     Prefix::from(1u32),
                     StaticCompactionGroupId::StateDefault.into(),
                 )],
+                &HashMap::new(),
+                &HashMap::new(),
                 env.meta_store(),
             )
             .await
@@ -348,6 +400,8 @@ mod tests {
                     Prefix::from(2u32),
                     StaticCompactionGroupId::MaterializedView.into(),
                 )],
+                &HashMap::from([(Prefix::from(2u32), 3600)]),
+                &HashMap::new(),
                 env.meta_store(),
             )
             .await
@@ -360,6 +414,14 @@ mod tests {
         let inner = compaction_group_manager.inner;
         assert_eq!(inner.read().await.index.len(), 2);
         assert_eq!(registered_number(inner.read().await.deref()), 2);
+        let retention_of_2 = |inner: &CompactionGroupManagerInner| {
+            inner.compaction_groups
+                [&CompactionGroupId::from(StaticCompactionGroupId::MaterializedView)]
+                .table_retention_seconds
+                .get(&2)
+                .copied()
+        };
+        assert_eq!(retention_of_2(inner.read().await.deref()), Some(3600));
 
         // Test unregister
         inner
@@ -370,6 +432,7 @@ mod tests {
             .unwrap();
         assert_eq!(inner.read().await.index.len(), 1);
         assert_eq!(registered_number(inner.read().await.deref()), 1);
+        assert_eq!(retention_of_2(inner.read().await.deref()), None);
 
         // Test init
         let compaction_group_manager = CompactionGroupManager::new(env.clone()).await.unwrap();
@@ -15,12 +15,12 @@
 pub mod manager;
 
 use std::borrow::Borrow;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use itertools::Itertools;
 use risingwave_hummock_sdk::compaction_group::Prefix;
 use risingwave_hummock_sdk::CompactionGroupId;
-use risingwave_pb::hummock::CompactionConfig;
+use risingwave_pb::hummock::{CompactionConfig, PartitionRetention};
 
 use crate::model::MetadataModel;
 
@@ -29,6 +29,10 @@ pub struct CompactionGroup {
     group_id: CompactionGroupId,
     member_prefixes: HashSet<Prefix>,
     compaction_config: CompactionConfig,
+    /// Retention in seconds of member tables that expire old rows.
+    table_retention_seconds: HashMap<u32, u32>,
+    /// Partition retention of member tables that expire old partitions.
+    table_partition_retention: HashMap<u32, PartitionRetention>,
 }
 
 impl CompactionGroup {
@@ -37,6 +41,8 @@ impl CompactionGroup {
             group_id,
             member_prefixes: Default::default(),
             compaction_config,
+            table_retention_seconds: Default::default(),
+            table_partition_retention: Default::default(),
         }
     }
 
@@ -51,6 +57,14 @@ impl CompactionGroup {
     pub fn compaction_config(&self) -> &CompactionConfig {
         &self.compaction_config
     }
+
+    pub fn table_retention_seconds(&self) -> &HashMap<u32, u32> {
+        &self.table_retention_seconds
+    }
+
+    pub fn table_partition_retention(&self) -> &HashMap<u32, PartitionRetention> {
+        &self.table_partition_retention
+    }
 }
 
 impl From<&risingwave_pb::hummock::CompactionGroup> for CompactionGroup {
@@ -70,6 +84,8 @@ impl From<&risingwave_pb::hummock::CompactionGroup> for CompactionGroup {
                 .as_ref()
                 .cloned()
                 .unwrap(),
+            table_retention_seconds: compaction_group.table_retention_seconds.clone(),
+            table_partition_retention: compaction_group.table_partition_retention.clone(),
         }
     }
 }
@@ -80,6 +96,8 @@ impl From<&CompactionGroup> for risingwave_pb::hummock::CompactionGroup {
             id: compaction_group.group_id,
             member_prefixes: compaction_group.member_prefixes.iter().map_into().collect(),
             compaction_config: Some(compaction_group.compaction_config.clone()),
+            table_retention_seconds: compaction_group.table_retention_seconds.clone(),
+            table_partition_retention: compaction_group.table_partition_retention.clone(),
         }
     }
 }
@@ -19,8 +19,10 @@ use std::time::Duration;
 use parking_lot::Mutex;
 use risingwave_hummock_sdk::compact::compact_task_to_string;
 use risingwave_hummock_sdk::CompactionGroupId;
+use risingwave_pb::meta::SystemParams;
 use tokio::sync::mpsc::UnboundedSender;
 use tokio::sync::oneshot::Receiver;
+use tokio::sync::watch;
 
 use crate::hummock::error::Error;
 use crate::hummock::{CompactorManagerRef, HummockManagerRef};
@@ -69,6 +71,8 @@ where
 {
     hummock_manager: HummockManagerRef<S>,
     compactor_manager: CompactorManagerRef,
+    /// Used to get the limit on concurrent compaction tasks, which can be changed at runtime.
+    system_params_rx: watch::Receiver<SystemParams>,
 }
 
 impl<S> CompactionScheduler<S>
@@ -78,10 +82,12 @@ where
     pub fn new(
         hummock_manager: HummockManagerRef<S>,
         compactor_manager: CompactorManagerRef,
+        system_params_rx: watch::Receiver<SystemParams>,
     ) -> Self {
         Self {
             hummock_manager,
             compactor_manager,
+            system_params_rx,
         }
     }
 
@@ -118,6 +124,19 @@ where
         compaction_group: CompactionGroupId,
         request_channel: Arc<CompactionRequestChannel>,
     ) -> bool {
+        // 0. Postpone picking if there are too many running tasks. The compaction group will be
+        // scheduled again on later commits.
+        let max_concurrent_tasks = self
+            .system_params_rx
+            .borrow()
+            .max_concurrent_compaction_tasks as usize;
+        if max_concurrent_tasks > 0
+            && self.hummock_manager.assigned_compact_task_num().await >= max_concurrent_tasks
+        {
+            request_channel.unschedule(compaction_group);
+            return false;
+        }
+
         // 1. Pick a compaction task.
         let compact_task = self
             .hummock_manager
@@ -183,8 +183,11 @@ mod tests {
             vnode_mappings: vec![],
             compaction_group_id: StaticCompactionGroupId::StateDefault.into(),
             existing_table_ids: vec![],
+            table_retention_seconds: Default::default(),
+            table_partition_retention: Default::default(),
             target_file_size: 1,
             compression_algorithm: 0,
+            compression_level: 0,
         }
     }
 
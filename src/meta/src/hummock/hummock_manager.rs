@@ -32,7 +32,7 @@ use risingwave_pb::hummock::hummock_version::Levels;
 use risingwave_pb::hummock::{
     CompactTask, CompactTaskAssignment, HummockPinnedSnapshot, HummockPinnedVersion,
     HummockSnapshot, HummockStaleSstables, HummockVersion, Level, LevelType, SstableIdInfo,
-    SstableInfo,
+    SstableInfo, TableStorageStats,
 };
 use risingwave_pb::meta::subscribe_response::{Info, Operation};
 use risingwave_pb::meta::MetaLeaderInfo;
@@ -48,6 +48,7 @@ use crate::hummock::model::{
     sstable_id_info, CurrentHummockVersionId, HummockPinnedSnapshotExt, HummockPinnedVersionExt,
     INVALID_TIMESTAMP,
 };
+use crate::hummock::replication::VersionHookRef;
 use crate::hummock::CompactorManagerRef;
 use crate::manager::{IdCategory, MetaSrvEnv};
 use crate::model::{MetadataModel, ValTransaction, VarTransaction};
@@ -75,6 +76,14 @@ pub struct HummockManager<S: MetaStore> {
     compaction_scheduler: parking_lot::RwLock<Option<CompactionRequestChannelRef>>,
 
     compactor_manager: CompactorManagerRef,
+
+    /// Ids of dropped tables whose keys are still in the current version, mapped to the max
+    /// committed epoch when meta first found them no longer registered in any compaction group.
+    /// Compaction keeps their keys until no pinned snapshot can read them.
+    dropped_tables: parking_lot::Mutex<BTreeMap<u32, HummockEpoch>>,
+
+    /// Hooks called with each committed version, e.g. to replicate it.
+    version_hooks: parking_lot::RwLock<Vec<VersionHookRef>>,
 }
 
 pub type HummockManagerRef<S> = Arc<HummockManager<S>>;
@@ -140,6 +149,9 @@ struct Versioning {
     pinned_snapshots: BTreeMap<HummockContextId, HummockPinnedSnapshot>,
     stale_sstables: BTreeMap<HummockVersionId, HummockStaleSstables>,
     sstable_id_infos: BTreeMap<HummockSSTableId, SstableIdInfo>,
+    /// Epochs of the savepoints by the streaming jobs. They're persisted with the jobs rather than
+    /// by hummock, and pinned again on restarting.
+    savepoints: BTreeMap<u32, HummockEpoch>,
 }
 
 impl Versioning {
@@ -152,6 +164,19 @@ impl Versioning {
     pub fn current_version(&self) -> HummockVersion {
         self.current_version_ref().clone()
     }
+
+    /// Returns the min pinned snapshot or savepoint, or the max committed epoch if there is none.
+    /// Data invisible to this epoch can be safely removed by compaction.
+    fn watermark(&self) -> HummockEpoch {
+        self.pinned_snapshots
+            .values()
+            .flat_map(|v| v.snapshot_id.clone())
+            .chain(self.savepoints.values().cloned())
+            .fold(
+                self.current_version_ref().max_committed_epoch,
+                std::cmp::min,
+            )
+    }
 }
 
 impl<S> HummockManager<S>
@@ -174,6 +199,8 @@ where
             compaction_group_manager,
             compaction_scheduler: parking_lot::RwLock::new(None),
             compactor_manager,
+            dropped_tables: parking_lot::Mutex::new(BTreeMap::new()),
+            version_hooks: parking_lot::RwLock::new(vec![]),
         };
 
         instance.load_meta_store_state().await?;
@@ -467,6 +494,27 @@ where
         })
     }
 
+    /// Pins the max committed epoch as the savepoint of the streaming job `table_id`, replacing the
+    /// previous one if any. Compaction keeps the data visible to the savepoint until it's unpinned.
+    pub async fn pin_savepoint(&self, table_id: u32) -> HummockEpoch {
+        let mut versioning_guard = self.versioning.write().await;
+        let epoch = versioning_guard.current_version_ref().max_committed_epoch;
+        versioning_guard.savepoints.insert(table_id, epoch);
+        epoch
+    }
+
+    /// Pins the savepoints persisted with the streaming jobs, on restarting.
+    pub async fn repin_savepoints(
+        &self,
+        savepoints: impl IntoIterator<Item = (u32, HummockEpoch)>,
+    ) {
+        self.versioning.write().await.savepoints.extend(savepoints);
+    }
+
+    pub async fn unpin_savepoint(&self, table_id: u32) {
+        self.versioning.write().await.savepoints.remove(&table_id);
+    }
+
     pub async fn unpin_snapshot(
         &self,
         context_id: HummockContextId,
@@ -600,21 +648,19 @@ where
                     .compaction_group_manager
                     .internal_table_ids_by_compaction_group_id(compaction_group_id)
                     .await?;
+                let (table_retention_seconds, table_partition_retention) = self
+                    .compaction_group_manager
+                    .compaction_group(compaction_group_id)
+                    .await
+                    .map(|group| {
+                        (
+                            group.table_retention_seconds().clone(),
+                            group.table_partition_retention().clone(),
+                        )
+                    })
+                    .unwrap_or_default();
 
-                compact_task.watermark = {
-                    let versioning_guard = self.versioning.read().await;
-                    let current_version_id = versioning_guard.current_version_id.id();
-                    let max_committed_epoch = versioning_guard
-                        .hummock_versions
-                        .get(&current_version_id)
-                        .unwrap()
-                        .max_committed_epoch;
-                    versioning_guard
-                        .pinned_snapshots
-                        .values()
-                        .flat_map(|v| v.snapshot_id.clone())
-                        .fold(max_committed_epoch, std::cmp::min)
-                };
+                compact_task.watermark = self.versioning.read().await.watermark();
 
                 // to get all relational table_id from sst_info
                 let table_ids = compact_task
@@ -629,10 +675,35 @@ where
                     })
                     .collect::<HashSet<u32>>();
 
-                for table_id in table_ids {
-                    // to found exist table_id from
-                    if existing_table_ids_from_meta.contains(&table_id) {
-                        compact_task.existing_table_ids.push(table_id);
+                {
+                    let mut dropped_tables = self.dropped_tables.lock();
+                    for table_id in table_ids {
+                        // to found exist table_id from
+                        if existing_table_ids_from_meta.contains(&table_id) {
+                            compact_task.existing_table_ids.push(table_id);
+                            if let Some(retention_seconds) = table_retention_seconds.get(&table_id)
+                            {
+                                compact_task
+                                    .table_retention_seconds
+                                    .insert(table_id, *retention_seconds);
+                            }
+                            if let Some(partition_retention) =
+                                table_partition_retention.get(&table_id)
+                            {
+                                compact_task
+                                    .table_partition_retention
+                                    .insert(table_id, partition_retention.clone());
+                            }
+                            continue;
+                        }
+                        // The table has been dropped. Keep its keys until all snapshots that may
+                        // still read it are unpinned.
+                        let dropped_epoch = *dropped_tables
+                            .entry(table_id)
+                            .or_insert(current_version.max_committed_epoch);
+                        if compact_task.watermark <= dropped_epoch {
+                            compact_task.existing_table_ids.push(table_id);
+                        }
                     }
                 }
 
@@ -790,6 +861,7 @@ where
                 version_stale_sstables,
                 sstable_id_infos
             )?;
+            self.call_version_hooks(versioning.current_version_ref());
         } else {
             // The compaction task is cancelled.
             commit_multi_var!(
@@ -911,6 +983,7 @@ where
 
         // Update metrics
         trigger_commit_stat(&self.metrics, versioning.current_version_ref());
+        self.call_version_hooks(versioning.current_version_ref());
 
         tracing::trace!("new committed epoch {}", epoch);
 
@@ -936,6 +1009,87 @@ where
         Ok(())
     }
 
+    pub fn add_version_hook(&self, hook: VersionHookRef) {
+        self.version_hooks.write().push(hook);
+    }
+
+    fn call_version_hooks(&self, version: &HummockVersion) {
+        for hook in self.version_hooks.read().iter() {
+            hook.on_version_committed(version);
+        }
+    }
+
+    /// Replaces all the versions with `version`, e.g. the one replicated to another object store
+    /// that the cluster fails over onto. All the other nodes must have been stopped, so that no
+    /// version or snapshot is pinned and no compaction task is assigned.
+    pub async fn reset_current_version(
+        &self,
+        mut version: HummockVersion,
+    ) -> Result<HummockVersion> {
+        let compaction_guard = self.compaction.read().await;
+        let mut versioning_guard = self.versioning.write().await;
+        if !versioning_guard.pinned_versions.is_empty()
+            || !versioning_guard.pinned_snapshots.is_empty()
+            || !compaction_guard.compact_task_assignment.is_empty()
+        {
+            return Err(Error::InternalError(
+                "hummock is still in use, stop all the other nodes before resetting the version"
+                    .to_string(),
+            ));
+        }
+        let versioning = versioning_guard.deref_mut();
+        let mut current_version_id = VarTransaction::new(&mut versioning.current_version_id);
+        let mut hummock_versions = VarTransaction::new(&mut versioning.hummock_versions);
+        let mut stale_sstables = VarTransaction::new(&mut versioning.stale_sstables);
+        let mut sstable_id_infos = VarTransaction::new(&mut versioning.sstable_id_infos);
+        current_version_id.increase();
+        version.id = current_version_id.id();
+
+        // The SSTs of the old versions are not in the new object store.
+        hummock_versions.clear();
+        hummock_versions.insert(version.id, version.clone());
+        stale_sstables.clear();
+        sstable_id_infos.clear();
+        let now = sstable_id_info::get_timestamp_now();
+        for levels in version.levels.values() {
+            for sst in levels.levels.iter().flat_map(|level| &level.table_infos) {
+                sstable_id_infos.insert(
+                    sst.id,
+                    SstableIdInfo {
+                        id: sst.id,
+                        id_create_timestamp: now,
+                        meta_create_timestamp: now,
+                        meta_delete_timestamp: INVALID_TIMESTAMP,
+                    },
+                );
+            }
+        }
+        commit_multi_var!(
+            self,
+            None,
+            current_version_id,
+            hummock_versions,
+            stale_sstables,
+            sstable_id_infos
+        )?;
+        trigger_commit_stat(&self.metrics, versioning.current_version_ref());
+        self.call_version_hooks(versioning.current_version_ref());
+        tracing::info!(
+            "reset to version {} at epoch {}",
+            version.id,
+            version.max_committed_epoch
+        );
+
+        drop(versioning_guard);
+        drop(compaction_guard);
+        #[cfg(test)]
+        {
+            self.check_state_consistency().await;
+        }
+
+        Ok(version)
+    }
+
     pub async fn get_new_table_id(&self) -> Result<HummockSSTableId> {
         // TODO id_gen_manager generates u32, we need u64
         let sstable_id = get_remote_sst_id(
@@ -1082,10 +1236,12 @@ where
             let num_ssts_to_delete = ssts_to_delete.id.len();
             for idx in (0..num_ssts_to_delete).rev() {
                 let sst_id = ssts_to_delete.id[idx];
-                if !ssts_in_use.contains(&sst_id) && let Some(mut sst_id_info) = sstable_id_infos.get_mut(&sst_id) {
+                if !ssts_in_use.contains(&sst_id)
+                    && let Some(mut sst_id_info) = sstable_id_infos.get_mut(&sst_id)
+                {
                     sst_id_info.meta_delete_timestamp = sstable_id_info::get_timestamp_now();
-                    // We don't want to repetitively set the delete timestamp of these that have been set,
-                    // so we remove these ones.
+                    // We don't want to repetitively set the delete timestamp of these that have
+                    // been set, so we remove these ones.
                     ssts_to_delete.id.swap_remove(idx);
                 }
             }
@@ -1316,11 +1472,124 @@ where
         Ok(marked)
     }
 
+    /// Returns the number of compaction tasks assigned to compactors and not yet reported.
+    pub async fn assigned_compact_task_num(&self) -> usize {
+        self.compaction.read().await.compact_task_assignment.len()
+    }
+
     /// Gets current version without pinning it.
     pub async fn get_current_version(&self) -> HummockVersion {
         self.versioning.read().await.current_version()
     }
 
+    /// Triggers manual compaction on the SSTs containing keys of dropped tables, once no pinned
+    /// snapshot can read these tables. The compaction filter removes their keys, and SSTs left
+    /// stale are deleted by the vacuum afterwards, which honors pinned versions.
+    ///
+    /// Returns ids of the dropped tables being reclaimed.
+    pub async fn reclaim_dropped_tables(&self) -> Result<Vec<u32>> {
+        let existing_table_ids = self
+            .compaction_group_manager
+            .internal_table_ids_by_compaction_group_id(StaticCompactionGroupId::StateDefault.into())
+            .await?;
+        let (watermark, current_version) = {
+            let versioning_guard = self.versioning.read().await;
+            (
+                versioning_guard.watermark(),
+                versioning_guard.current_version(),
+            )
+        };
+        // TODO #2065: reclaim all compaction groups.
+        let levels = current_version
+            .get_compaction_group_levels(StaticCompactionGroupId::StateDefault.into());
+
+        let (reclaimable, levels_to_compact) = {
+            let mut dropped_tables = self.dropped_tables.lock();
+            let mut table_ids_in_version = HashSet::new();
+            for level in levels {
+                for sst in &level.table_infos {
+                    for table_id in &sst.table_ids {
+                        if !existing_table_ids.contains(table_id) {
+                            dropped_tables
+                                .entry(*table_id)
+                                .or_insert(current_version.max_committed_epoch);
+                        }
+                        table_ids_in_version.insert(*table_id);
+                    }
+                }
+            }
+            // Tables without any keys left are fully reclaimed, or have been registered again.
+            dropped_tables.retain(|table_id, _| {
+                table_ids_in_version.contains(table_id) && !existing_table_ids.contains(table_id)
+            });
+            let reclaimable: HashSet<u32> = dropped_tables
+                .iter()
+                .filter(|(_, dropped_epoch)| watermark > **dropped_epoch)
+                .map(|(table_id, _)| *table_id)
+                .collect();
+            let levels_to_compact = levels
+                .iter()
+                .filter(|level| {
+                    level.table_infos.iter().any(|sst| {
+                        sst.table_ids
+                            .iter()
+                            .any(|table_id| reclaimable.contains(table_id))
+                    })
+                })
+                .map(|level| level.level_idx as usize)
+                .collect_vec();
+            (reclaimable, levels_to_compact)
+        };
+
+        for level in levels_to_compact {
+            let option = ManualCompactionOption {
+                internal_table_id: reclaimable.clone(),
+                level,
+                ..Default::default()
+            };
+            if let Err(e) = self
+                .trigger_manual_compaction(StaticCompactionGroupId::StateDefault.into(), option)
+                .await
+            {
+                tracing::debug!(
+                    "Failed to reclaim dropped tables in level {}: {:#?}",
+                    level,
+                    e
+                );
+            }
+        }
+        Ok(reclaimable.into_iter().sorted().collect_vec())
+    }
+
+    /// Gets the estimated storage usage of each table in the current version.
+    ///
+    /// The stats follow the SSTs referenced by the current version, so they are updated whenever
+    /// an epoch is committed or a compaction task is reported. SSTs only record which tables they
+    /// contain, hence the size of an SST shared by several tables is attributed to them evenly.
+    pub async fn get_table_storage_stats(&self) -> Vec<TableStorageStats> {
+        let versioning_guard = self.versioning.read().await;
+        let mut stats: BTreeMap<u32, TableStorageStats> = BTreeMap::new();
+        for levels in versioning_guard.current_version_ref().levels.values() {
+            for sst in levels.levels.iter().flat_map(|level| level.table_infos.iter()) {
+                if sst.table_ids.is_empty() {
+                    continue;
+                }
+                let table_count = sst.table_ids.len() as u64;
+                let share = sst.file_size / table_count;
+                let remainder = sst.file_size % table_count;
+                for (idx, table_id) in sst.table_ids.iter().enumerate() {
+                    let entry = stats.entry(*table_id).or_insert_with(|| TableStorageStats {
+                        table_id: *table_id,
+                        ..Default::default()
+                    });
+                    entry.total_size += share + if idx == 0 { remainder } else { 0 };
+                    entry.sstable_count += 1;
+                }
+            }
+        }
+        stats.into_values().collect()
+    }
+
     pub fn set_compaction_scheduler(&self, sender: CompactionRequestChannelRef) {
         *self.compaction_scheduler.write() = Some(sender);
     }
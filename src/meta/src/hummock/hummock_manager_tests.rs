@@ -1032,3 +1032,205 @@ async fn test_trigger_manual_compaction() {
         assert!(result.is_err());
     }
 }
+
+#[tokio::test]
+async fn test_get_table_storage_stats() {
+    let (_, hummock_manager, _cluster_manager, _) = setup_compute_env(80).await;
+    let epoch = 1;
+    assert!(hummock_manager.get_table_storage_stats().await.is_empty());
+
+    let mut ssts = generate_test_tables(
+        epoch,
+        vec![
+            hummock_manager.get_new_table_id().await.unwrap(),
+            hummock_manager.get_new_table_id().await.unwrap(),
+        ],
+    );
+    // The SSTs contain tables [1, 2] and [2, 3] respectively.
+    ssts[0].file_size = 10;
+    ssts[1].file_size = 7;
+    hummock_manager
+        .commit_epoch(epoch, to_local_sstable_info(&ssts))
+        .await
+        .unwrap();
+
+    let stats = hummock_manager
+        .get_table_storage_stats()
+        .await
+        .into_iter()
+        .map(|stats| (stats.table_id, stats.total_size, stats.sstable_count))
+        .collect_vec();
+    assert_eq!(stats, vec![(1, 5, 1), (2, 9, 2), (3, 3, 1)]);
+}
+
+#[tokio::test]
+async fn test_dropped_table_hold() {
+    let (_, hummock_manager, _cluster_manager, worker_node) = setup_compute_env(80).await;
+    let context_id = worker_node.id;
+    let epoch = 1;
+    // The SSTs contain tables [1, 2] and [2, 3] respectively, none of which is registered to any
+    // compaction group, i.e. they are dropped.
+    let ssts = generate_test_tables(
+        epoch,
+        vec![
+            hummock_manager.get_new_table_id().await.unwrap(),
+            hummock_manager.get_new_table_id().await.unwrap(),
+        ],
+    );
+    hummock_manager
+        .commit_epoch(epoch, to_local_sstable_info(&ssts))
+        .await
+        .unwrap();
+    let snapshot = hummock_manager
+        .pin_snapshot(context_id, u64::MAX)
+        .await
+        .unwrap();
+    assert_eq!(snapshot.epoch, epoch);
+
+    // Keys of the dropped tables are kept for the pinned snapshot.
+    let compact_task = hummock_manager
+        .get_compact_task(StaticCompactionGroupId::StateDefault.into())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        compact_task
+            .existing_table_ids
+            .iter()
+            .cloned()
+            .sorted()
+            .collect_vec(),
+        vec![1, 2, 3]
+    );
+    assert!(hummock_manager
+        .reclaim_dropped_tables()
+        .await
+        .unwrap()
+        .is_empty());
+
+    // The dropped tables can be reclaimed after the snapshot is unpinned.
+    let epoch = epoch + 1;
+    let ssts = generate_test_tables(
+        epoch,
+        vec![hummock_manager.get_new_table_id().await.unwrap()],
+    );
+    hummock_manager
+        .commit_epoch(epoch, to_local_sstable_info(&ssts))
+        .await
+        .unwrap();
+    hummock_manager
+        .unpin_snapshot(context_id, vec![snapshot])
+        .await
+        .unwrap();
+    assert_eq!(
+        hummock_manager.reclaim_dropped_tables().await.unwrap(),
+        vec![1, 2, 3]
+    );
+}
+
+#[tokio::test]
+async fn test_reset_current_version() {
+    let (_env, hummock_manager, _cluster_manager, worker_node) = setup_compute_env(80).await;
+    let context_id = worker_node.id;
+
+    let epoch: u64 = 1;
+    let original_tables = generate_test_tables(epoch, get_sst_ids(&hummock_manager, 2).await);
+    hummock_manager
+        .commit_epoch(epoch, to_local_sstable_info(&original_tables))
+        .await
+        .unwrap();
+    let replicated_version = hummock_manager.get_current_version().await;
+    hummock_manager
+        .commit_epoch(
+            epoch + 1,
+            to_local_sstable_info(&generate_test_tables(
+                epoch + 1,
+                get_sst_ids(&hummock_manager, 1).await,
+            )),
+        )
+        .await
+        .unwrap();
+
+    // The version is in use.
+    hummock_manager
+        .pin_version(context_id, u64::MAX)
+        .await
+        .unwrap();
+    assert!(hummock_manager
+        .reset_current_version(replicated_version.clone())
+        .await
+        .is_err());
+    hummock_manager
+        .release_contexts(vec![context_id])
+        .await
+        .unwrap();
+
+    let old_version_id = hummock_manager.get_current_version().await.id;
+    let version = hummock_manager
+        .reset_current_version(replicated_version)
+        .await
+        .unwrap();
+    assert!(version.id > old_version_id);
+    assert_eq!(version.max_committed_epoch, epoch);
+    assert_eq!(hummock_manager.get_current_version().await, version);
+    assert_eq!(
+        hummock_manager.list_version_ids_asc().await.unwrap(),
+        vec![version.id]
+    );
+    assert_eq!(
+        get_sorted_sstable_ids(&original_tables),
+        hummock_manager
+            .list_sstable_id_infos(None)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|info| info.id)
+            .sorted()
+            .collect_vec()
+    );
+}
+
+#[tokio::test]
+async fn test_savepoint_hold() {
+    let (_env, hummock_manager, _cluster_manager, worker_node) = setup_compute_env(80).await;
+    let context_id = worker_node.id;
+    let epoch = 1;
+    let ssts = generate_test_tables(epoch, get_sst_ids(&hummock_manager, 2).await);
+    hummock_manager
+        .commit_epoch(epoch, to_local_sstable_info(&ssts))
+        .await
+        .unwrap();
+    assert_eq!(hummock_manager.pin_savepoint(1000).await, epoch);
+    let epoch = epoch + 1;
+    let ssts = generate_test_tables(epoch, get_sst_ids(&hummock_manager, 2).await);
+    hummock_manager
+        .commit_epoch(epoch, to_local_sstable_info(&ssts))
+        .await
+        .unwrap();
+
+    // Data visible to the savepoint is kept by compaction.
+    let mut compact_task = hummock_manager
+        .get_compact_task(StaticCompactionGroupId::StateDefault.into())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(compact_task.watermark, epoch - 1);
+    hummock_manager
+        .assign_compaction_task(&compact_task, context_id, async { true })
+        .await
+        .unwrap();
+    compact_task.task_status = false;
+    assert!(hummock_manager
+        .report_compact_task(&compact_task)
+        .await
+        .unwrap());
+
+    // And can be removed after the savepoint is unpinned.
+    hummock_manager.unpin_savepoint(1000).await;
+    let compact_task = hummock_manager
+        .get_compact_task(StaticCompactionGroupId::StateDefault.into())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(compact_task.watermark, epoch);
+}
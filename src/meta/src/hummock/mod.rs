@@ -25,6 +25,7 @@ mod metrics_utils;
 #[cfg(any(test, feature = "test"))]
 pub mod mock_hummock_meta_client;
 mod model;
+pub mod replication;
 #[cfg(any(test, feature = "test"))]
 pub mod test_utils;
 mod utils;
@@ -160,6 +161,9 @@ where
                     return;
                 }
             }
+            if let Err(err) = vacuum.vacuum_dropped_tables().await {
+                tracing::warn!("Vacuum dropped tables error {}", err);
+            }
             if let Err(err) = vacuum.vacuum_version_metadata().await {
                 tracing::warn!("Vacuum tracked data error {}", err);
             }
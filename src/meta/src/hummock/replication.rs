@@ -0,0 +1,335 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Replication of the committed hummock versions and their SSTs to a second object store, e.g. a
+//! bucket in another region, so that the cluster can fail over onto it. See
+//! `docs/object-store-replication.md` for the failover procedure.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use bytes::Bytes;
+use itertools::Itertools;
+use prost::Message;
+use risingwave_hummock_sdk::{HummockSSTableId, HummockVersionId};
+use risingwave_object_store::object::object_metrics::ObjectStoreMetrics;
+use risingwave_object_store::object::{
+    parse_object_store, ObjectError, ObjectResult, ObjectStoreImpl, ObjectStoreRef,
+};
+use risingwave_pb::hummock::HummockVersion;
+use tokio::sync::oneshot::Sender;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+use crate::hummock::HummockManager;
+use crate::storage::MetaStore;
+
+/// Receives each hummock version once it's committed to the meta store, e.g. after a checkpoint
+/// or a compaction. It's called with the versioning lock held, so it must return quickly.
+pub trait VersionHook: Send + Sync {
+    fn on_version_committed(&self, version: &HummockVersion);
+}
+
+pub type VersionHookRef = Arc<dyn VersionHook>;
+
+/// Directory under the data directory of the replica where the replicated versions are written.
+pub const REPLICATED_VERSION_DIR: &str = "replicated_versions";
+
+/// The path of a replicated version. The id is zero-padded so that the versions are listed in
+/// order.
+pub fn replicated_version_path(data_directory: &str, version_id: HummockVersionId) -> String {
+    format!(
+        "{}/{}/{:020}",
+        data_directory, REPLICATED_VERSION_DIR, version_id
+    )
+}
+
+/// The paths of the data and the meta of an SST, following `SstableStore`. The meta is written
+/// last, so an SST is complete in the replica if its meta is there.
+fn sst_paths(data_directory: &str, sst_id: HummockSSTableId) -> [String; 2] {
+    [
+        format!("{}/{}.data", data_directory, sst_id),
+        format!("{}/{}.meta", data_directory, sst_id),
+    ]
+}
+
+fn sst_ids(version: &HummockVersion) -> HashSet<HummockSSTableId> {
+    version
+        .levels
+        .values()
+        .flat_map(|levels| &levels.levels)
+        .flat_map(|level| &level.table_infos)
+        .map(|sst| sst.id)
+        .collect()
+}
+
+/// Copies the committed versions and their new SSTs to the replica object store asynchronously.
+///
+/// A version is written to the replica only after all its SSTs are there, so the replica always
+/// holds a complete checkpoint. Versions committed while a previous one is being copied are
+/// skipped in favor of the latest. Only the latest replicated version and its SSTs are kept.
+pub struct ObjectStoreReplicator {
+    latest_version: watch::Sender<Option<HummockVersion>>,
+}
+
+impl VersionHook for ObjectStoreReplicator {
+    fn on_version_committed(&self, version: &HummockVersion) {
+        let _ = self.latest_version.send(Some(version.clone()));
+    }
+}
+
+impl ObjectStoreReplicator {
+    /// Starts replicating the versions of `hummock_manager` from the object store of the state
+    /// store `state_store`, e.g. `hummock+s3://bucket`, to the object store `replica`, e.g.
+    /// `s3://bucket-in-another-region`.
+    pub async fn start<S: MetaStore>(
+        hummock_manager: &HummockManager<S>,
+        state_store: &str,
+        replica: &str,
+        data_directory: String,
+    ) -> (JoinHandle<()>, Sender<()>) {
+        let primary = state_store
+            .strip_prefix("hummock+")
+            .unwrap_or_else(|| panic!("cannot replicate state store {}", state_store));
+        let new_object_store = |store| {
+            Arc::new(ObjectStoreImpl::new(
+                store,
+                Arc::new(ObjectStoreMetrics::unused()),
+            ))
+        };
+        let primary = new_object_store(parse_object_store(primary, false).await);
+        let replica = new_object_store(parse_object_store(replica, false).await);
+
+        let (latest_version, mut version_rx) = watch::channel(None);
+        let replicator = Arc::new(Self { latest_version });
+        replicator.on_version_committed(&hummock_manager.get_current_version().await);
+        hummock_manager.add_version_hook(replicator);
+
+        let (shutdown_tx, mut shutdown_rx) = tokio::sync::oneshot::channel();
+        let join_handle = tokio::spawn(async move {
+            let mut worker = ReplicationWorker {
+                primary,
+                replica,
+                data_directory,
+                replicated_ssts: None,
+            };
+            loop {
+                tokio::select! {
+                    changed = version_rx.changed() => {
+                        if changed.is_err() {
+                            return;
+                        }
+                    }
+                    _ = &mut shutdown_rx => {
+                        tracing::info!("Object store replication is shutting down");
+                        return;
+                    }
+                }
+                let version = version_rx.borrow().clone().unwrap();
+                if let Err(err) = worker.replicate(&version).await {
+                    // The SSTs of an outdated version may have been vacuumed. Retry with the next.
+                    tracing::warn!("Failed to replicate version {}: {}", version.id, err);
+                }
+            }
+        });
+        (join_handle, shutdown_tx)
+    }
+}
+
+struct ReplicationWorker {
+    primary: ObjectStoreRef,
+    replica: ObjectStoreRef,
+    data_directory: String,
+    /// The SSTs complete in the replica, listed from the replica on the first replication.
+    replicated_ssts: Option<HashSet<HummockSSTableId>>,
+}
+
+impl ReplicationWorker {
+    async fn list_replicated_ssts(&self) -> ObjectResult<HashSet<HummockSSTableId>> {
+        let prefix = format!("{}/", self.data_directory);
+        Ok(self
+            .replica
+            .list(&prefix)
+            .await?
+            .iter()
+            .filter_map(|path| path.rsplit('/').next()?.strip_suffix(".meta")?.parse().ok())
+            .collect())
+    }
+
+    async fn replicate(&mut self, version: &HummockVersion) -> ObjectResult<()> {
+        let mut replicated_ssts = match self.replicated_ssts.take() {
+            Some(replicated_ssts) => replicated_ssts,
+            None => self.list_replicated_ssts().await?,
+        };
+        let result = self.replicate_inner(version, &mut replicated_ssts).await;
+        self.replicated_ssts = Some(replicated_ssts);
+        result
+    }
+
+    async fn replicate_inner(
+        &self,
+        version: &HummockVersion,
+        replicated_ssts: &mut HashSet<HummockSSTableId>,
+    ) -> ObjectResult<()> {
+        let sst_ids = sst_ids(version);
+        for &sst_id in sst_ids.difference(replicated_ssts).sorted() {
+            for path in sst_paths(&self.data_directory, sst_id) {
+                let object = self.primary.read(&path, None).await?;
+                self.replica.upload(&path, object).await?;
+            }
+            replicated_ssts.insert(sst_id);
+        }
+
+        let version_path = replicated_version_path(&self.data_directory, version.id);
+        self.replica
+            .upload(&version_path, Bytes::from(version.encode_to_vec()))
+            .await?;
+        tracing::debug!(
+            "replicated version {} at epoch {}",
+            version.id,
+            version.max_committed_epoch
+        );
+
+        // Remove the older versions, and then the SSTs no longer referenced.
+        let version_dir = format!("{}/{}/", self.data_directory, REPLICATED_VERSION_DIR);
+        for path in self.replica.list(&version_dir).await? {
+            if path < version_path {
+                self.replica.delete(&path).await?;
+            }
+        }
+        let stale_ssts = replicated_ssts.difference(&sst_ids).copied().collect_vec();
+        for sst_id in stale_ssts {
+            // Delete the meta first, so that a partially deleted SST is no longer complete.
+            for path in sst_paths(&self.data_directory, sst_id).iter().rev() {
+                self.replica.delete(path).await?;
+            }
+            replicated_ssts.remove(&sst_id);
+        }
+        Ok(())
+    }
+}
+
+/// Reads the latest version replicated to `replica`.
+pub async fn read_latest_replicated_version(
+    replica: &ObjectStoreImpl,
+    data_directory: &str,
+) -> ObjectResult<Option<HummockVersion>> {
+    let version_dir = format!("{}/{}/", data_directory, REPLICATED_VERSION_DIR);
+    let Some(path) = replica.list(&version_dir).await?.pop() else {
+        return Ok(None);
+    };
+    let bytes = replica.read(&path, None).await?;
+    let version = HummockVersion::decode(bytes).map_err(|e| {
+        ObjectError::internal(format!("invalid replicated version {}: {}", path, e))
+    })?;
+    Ok(Some(version))
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_object_store::object::InMemObjectStore;
+    use risingwave_pb::hummock::hummock_version::Levels;
+    use risingwave_pb::hummock::{Level, SstableInfo};
+
+    use super::*;
+
+    fn version(id: HummockVersionId, sst_ids: &[HummockSSTableId]) -> HummockVersion {
+        let level = Level {
+            table_infos: sst_ids
+                .iter()
+                .map(|&id| SstableInfo {
+                    id,
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        };
+        HummockVersion {
+            id,
+            levels: [(
+                0,
+                Levels {
+                    levels: vec![level],
+                },
+            )]
+            .into_iter()
+            .collect(),
+            max_committed_epoch: id,
+            safe_epoch: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replicate() {
+        let new_object_store = || {
+            Arc::new(ObjectStoreImpl::new(
+                Box::new(InMemObjectStore::new(false)),
+                Arc::new(ObjectStoreMetrics::unused()),
+            ))
+        };
+        let primary = new_object_store();
+        for sst_id in 1..=3 {
+            for path in sst_paths("hummock", sst_id) {
+                primary
+                    .upload(&path, Bytes::from(path.clone()))
+                    .await
+                    .unwrap();
+            }
+        }
+        let replica = new_object_store();
+        let mut worker = ReplicationWorker {
+            primary,
+            replica: replica.clone(),
+            data_directory: "hummock".to_string(),
+            replicated_ssts: None,
+        };
+
+        worker.replicate(&version(1, &[1, 2])).await.unwrap();
+        assert_eq!(
+            read_latest_replicated_version(&replica, "hummock")
+                .await
+                .unwrap()
+                .unwrap(),
+            version(1, &[1, 2])
+        );
+
+        // SST 1 is compacted into SST 3.
+        worker.replicate(&version(2, &[2, 3])).await.unwrap();
+        assert_eq!(
+            replica.list("hummock/").await.unwrap(),
+            vec![
+                "hummock/2.data".to_string(),
+                "hummock/2.meta".to_string(),
+                "hummock/3.data".to_string(),
+                "hummock/3.meta".to_string(),
+                replicated_version_path("hummock", 2),
+            ]
+        );
+        assert_eq!(
+            replica.read("hummock/3.data", None).await.unwrap(),
+            Bytes::from("hummock/3.data")
+        );
+
+        // SST 4 doesn't exist in the primary, so version 3 is not replicated.
+        assert!(worker.replicate(&version(3, &[4])).await.is_err());
+        assert_eq!(
+            read_latest_replicated_version(&replica, "hummock")
+                .await
+                .unwrap()
+                .unwrap()
+                .id,
+            2
+        );
+    }
+}
@@ -116,6 +116,16 @@ where
         Ok(vacuum_count as u64)
     }
 
+    /// Triggers compaction to remove keys of dropped tables that are no longer readable by any
+    /// pinned snapshot. Return ids of these dropped tables.
+    pub async fn vacuum_dropped_tables(&self) -> risingwave_common::error::Result<Vec<u32>> {
+        let table_ids = self.hummock_manager.reclaim_dropped_tables().await?;
+        if !table_ids.is_empty() {
+            tracing::debug!("Try to reclaim dropped tables {:?}", table_ids);
+        }
+        Ok(table_ids)
+    }
+
     /// Qualified SSTs and their metadata(aka `SstableIdInfo`) are deleted.
     /// Return number of SSTs to delete.
     /// Two types of SSTs can be deleted:
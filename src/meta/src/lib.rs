@@ -53,7 +53,8 @@ pub mod test_utils;
 use std::time::Duration;
 
 use clap::{ArgEnum, Parser};
-use risingwave_common::config::ComputeNodeConfig;
+use risingwave_common::config::RwConfig;
+use risingwave_common::types::VIRTUAL_NODE_COUNT;
 
 use crate::manager::MetaOpts;
 use crate::rpc::server::{rpc_serve, AddressInfo, MetaStoreBackend};
@@ -103,10 +104,34 @@ pub struct MetaNodeOpts {
 
     #[clap(long, default_value = "10")]
     meta_leader_lease_secs: u64,
+
+    /// Endpoint that telemetry reports are sent to when the `telemetry_enabled` system parameter
+    /// is set, e.g. a local collector. Reports are only kept locally if not specified.
+    #[clap(long)]
+    telemetry_endpoint: Option<String>,
+
+    /// Number of virtual nodes that data is distributed over. Must be a power of two between 256
+    /// and 32768. It only takes effect when the cluster is created, and can't be changed later.
+    #[clap(long, default_value_t = VIRTUAL_NODE_COUNT)]
+    vnode_count: usize,
+
+    /// State store url of the cluster, e.g. `hummock+s3://bucket`. Only required for replication.
+    #[clap(long)]
+    state_store: Option<String>,
+
+    /// Object store that committed checkpoints of the state store are replicated to, e.g.
+    /// `s3://backup-bucket`. Replication is disabled if not specified.
+    #[clap(long)]
+    replica_object_store: Option<String>,
+
+    /// Print the config after applying the defaults, the config file and the environment variable
+    /// overrides, and exit.
+    #[clap(long)]
+    pub print_effective_config: bool,
 }
 
-fn load_config(opts: &MetaNodeOpts) -> ComputeNodeConfig {
-    risingwave_common::config::load_config(&opts.config_path)
+fn load_config(opts: &MetaNodeOpts) -> RwConfig {
+    risingwave_common::config::load_config(&opts.config_path).unwrap()
 }
 
 use std::future::Future;
@@ -118,7 +143,13 @@ pub fn start(opts: MetaNodeOpts) -> Pin<Box<dyn Future<Output = ()> + Send>> {
     // WARNING: don't change the function signature. Making it `async fn` will cause
     // slow compile in release mode.
     Box::pin(async move {
-        let compute_config = load_config(&opts);
+        if opts.print_effective_config {
+            let config = risingwave_common::config::load_config(&opts.config_path).unwrap();
+            println!("{}", config.to_toml_string());
+            return;
+        }
+
+        let config = load_config(&opts);
         let meta_addr = opts.host.unwrap_or_else(|| opts.listen_addr.clone());
         let listen_addr = opts.listen_addr.parse().unwrap();
         let dashboard_addr = opts.dashboard_host.map(|x| x.parse().unwrap());
@@ -135,7 +166,7 @@ pub fn start(opts: MetaNodeOpts) -> Pin<Box<dyn Future<Output = ()> + Send>> {
         };
         let max_heartbeat_interval = Duration::from_millis(opts.max_heartbeat_interval as u64);
         let checkpoint_interval =
-            Duration::from_millis(compute_config.streaming.checkpoint_interval_ms as u64);
+            Duration::from_millis(config.streaming.checkpoint_interval_ms as u64);
 
         tracing::info!("Meta server listening at {}", listen_addr);
         let add_info = AddressInfo {
@@ -153,6 +184,15 @@ pub fn start(opts: MetaNodeOpts) -> Pin<Box<dyn Future<Output = ()> + Send>> {
             MetaOpts {
                 enable_recovery: !opts.disable_recovery,
                 checkpoint_interval,
+                checkpoint_frequency: config.streaming.checkpoint_frequency,
+                block_cache_capacity_mb: config.storage.block_cache_capacity_mb,
+                meta_cache_capacity_mb: config.storage.meta_cache_capacity_mb,
+                telemetry_endpoint: opts.telemetry_endpoint,
+                vnode_count: opts.vnode_count,
+                state_store: opts.state_store,
+                replica_object_store: opts.replica_object_store,
+                data_directory: config.storage.data_directory,
+                auto_parallelism: config.auto_parallelism,
             },
         )
         .await
@@ -22,7 +22,6 @@ use itertools::Itertools;
 use risingwave_common::catalog::{
     DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, DEFAULT_SUPPER_USER, PG_CATALOG_SCHEMA_NAME,
 };
-use risingwave_common::ensure;
 use risingwave_common::error::ErrorCode::{CatalogError, InternalError};
 use risingwave_common::error::{Result, RwError};
 use risingwave_pb::catalog::table::OptionalAssociatedSourceId;
@@ -115,6 +114,38 @@ where
         core.get_catalog().await
     }
 
+    /// Checks that the schema and the relations a new relation depends on still exist. Frontends
+    /// bind DDL statements against their local catalog cache, which may lag behind meta when
+    /// another frontend has just dropped them, so meta re-validates under the catalog lock.
+    async fn check_dependencies(
+        &self,
+        database_id: DatabaseId,
+        schema_id: SchemaId,
+        dependent_relations: &[RelationId],
+    ) -> Result<()> {
+        let store = self.env.meta_store();
+        match Schema::select(store, &schema_id).await? {
+            Some(schema) if schema.database_id == database_id => {}
+            _ => {
+                return Err(CatalogError(
+                    anyhow!("schema {} has been dropped concurrently", schema_id).into(),
+                )
+                .into())
+            }
+        }
+        for &relation_id in dependent_relations {
+            if Table::select(store, &relation_id).await?.is_none()
+                && Source::select(store, &relation_id).await?.is_none()
+            {
+                return Err(CatalogError(
+                    anyhow!("relation {} has been dropped concurrently", relation_id).into(),
+                )
+                .into());
+            }
+        }
+        Ok(())
+    }
+
     pub async fn create_database(&self, database: &Database) -> Result<NotificationVersion> {
         let mut core = self.core.lock().await;
         if !core.has_database(database) {
@@ -195,6 +226,19 @@ where
 
     pub async fn create_schema(&self, schema: &Schema) -> Result<NotificationVersion> {
         let mut core = self.core.lock().await;
+        if Database::select(self.env.meta_store(), &schema.database_id)
+            .await?
+            .is_none()
+        {
+            return Err(CatalogError(
+                anyhow!(
+                    "database {} has been dropped concurrently",
+                    schema.database_id
+                )
+                .into(),
+            )
+            .into());
+        }
         if !core.has_schema(schema) {
             schema.insert(self.env.meta_store()).await?;
             core.add_schema(schema);
@@ -236,6 +280,12 @@ where
 
     pub async fn start_create_table_procedure(&self, table: &Table) -> Result<()> {
         let mut core = self.core.lock().await;
+        self.check_dependencies(
+            table.database_id,
+            table.schema_id,
+            &table.dependent_relations,
+        )
+        .await?;
         let key = (table.database_id, table.schema_id, table.name.clone());
         if !core.has_table(table) && !core.has_in_progress_creation(&key) {
             core.mark_creating(&key);
@@ -250,17 +300,32 @@ where
         }
     }
 
+    /// Finishes creating `table`, together with its `internal_tables` that expose the state of
+    /// its streaming job.
     pub async fn finish_create_table_procedure(
         &self,
+        internal_tables: Vec<Table>,
         table: &Table,
     ) -> Result<NotificationVersion> {
         let mut core = self.core.lock().await;
         let key = (table.database_id, table.schema_id, table.name.clone());
         if !core.has_table(table) && core.has_in_progress_creation(&key) {
             core.unmark_creating(&key);
-            table.insert(self.env.meta_store()).await?;
-            core.add_table(table);
+            let mut transaction = Transaction::default();
+            for internal_table in &internal_tables {
+                internal_table.upsert_in_transaction(&mut transaction)?;
+            }
+            table.upsert_in_transaction(&mut transaction)?;
+            self.env.meta_store().txn(transaction).await?;
 
+            for internal_table in internal_tables {
+                core.add_table(&internal_table);
+                self.env
+                    .notification_manager()
+                    .notify_frontend(Operation::Add, Info::Table(internal_table))
+                    .await;
+            }
+            core.add_table(table);
             let version = self
                 .env
                 .notification_manager()
@@ -293,6 +358,12 @@ where
 
     pub async fn create_table(&self, table: &Table) -> Result<NotificationVersion> {
         let mut core = self.core.lock().await;
+        self.check_dependencies(
+            table.database_id,
+            table.schema_id,
+            &table.dependent_relations,
+        )
+        .await?;
         if !core.has_table(table) {
             table.insert(self.env.meta_store()).await?;
             core.add_table(table);
@@ -329,11 +400,29 @@ where
                 )
                 .into()),
                 None => {
-                    Table::delete(self.env.meta_store(), &table_id).await?;
+                    let internal_tables = Table::list(self.env.meta_store())
+                        .await?
+                        .into_iter()
+                        .filter(|t| t.is_internal && t.internal_of_id == table_id)
+                        .collect_vec();
+                    let mut transaction = Transaction::default();
+                    for internal_table in &internal_tables {
+                        internal_table.delete_in_transaction(&mut transaction)?;
+                    }
+                    table.delete_in_transaction(&mut transaction)?;
+                    self.env.meta_store().txn(transaction).await?;
+
                     core.drop_table(&table);
                     for &dependent_relation_id in &table.dependent_relations {
                         core.decrease_ref_count(dependent_relation_id);
                     }
+                    for internal_table in internal_tables {
+                        core.drop_table(&internal_table);
+                        self.env
+                            .notification_manager()
+                            .notify_frontend(Operation::Delete, Info::Table(internal_table))
+                            .await;
+                    }
 
                     let version = self
                         .env
@@ -351,8 +440,143 @@ where
         }
     }
 
+    /// Replaces the table `table_id` with `new_table_id`, which takes over the name and the comment
+    /// of the former. The old table is dropped along with its internal tables in the same
+    /// transaction, so that the name is never missing nor resolved to a half-built table.
+    pub async fn replace_table(
+        &self,
+        table_id: TableId,
+        new_table_id: TableId,
+        adopted_table_ids: &HashSet<TableId>,
+    ) -> Result<NotificationVersion> {
+        let mut core = self.core.lock().await;
+        let table = Table::select(self.env.meta_store(), &table_id)
+            .await?
+            .ok_or_else(|| RwError::from(InternalError("table doesn't exist".to_string())))?;
+        let new_table = Table::select(self.env.meta_store(), &new_table_id)
+            .await?
+            .ok_or_else(|| RwError::from(InternalError("table doesn't exist".to_string())))?;
+        if let Some(ref_count) = core.get_ref_count(table_id) {
+            return Err(CatalogError(
+                anyhow!(
+                    "Fail to replace table `{}` because {} other relation(s) depend on it.",
+                    table.name,
+                    ref_count
+                )
+                .into(),
+            )
+            .into());
+        }
+
+        // The internal tables adopted by the new table are moved over instead of dropped. Their
+        // names stay valid, as the new table is renamed to the replaced one.
+        let (adopted_tables, internal_tables): (Vec<_>, Vec<_>) =
+            Table::list(self.env.meta_store())
+                .await?
+                .into_iter()
+                .filter(|t| t.is_internal && t.internal_of_id == table_id)
+                .partition(|t| adopted_table_ids.contains(&t.id));
+        let adopted_tables = adopted_tables
+            .into_iter()
+            .map(|t| Table {
+                internal_of_id: new_table_id,
+                ..t
+            })
+            .collect_vec();
+        let renamed_table = Table {
+            name: table.name.clone(),
+            description: table.description.clone(),
+            ..new_table.clone()
+        };
+        let mut transaction = Transaction::default();
+        for internal_table in &internal_tables {
+            internal_table.delete_in_transaction(&mut transaction)?;
+        }
+        for adopted_table in &adopted_tables {
+            adopted_table.upsert_in_transaction(&mut transaction)?;
+        }
+        table.delete_in_transaction(&mut transaction)?;
+        renamed_table.upsert_in_transaction(&mut transaction)?;
+        self.env.meta_store().txn(transaction).await?;
+
+        core.drop_table(&table);
+        for &dependent_relation_id in &table.dependent_relations {
+            core.decrease_ref_count(dependent_relation_id);
+        }
+        core.drop_table(&new_table);
+        core.add_table(&renamed_table);
+        for internal_table in internal_tables {
+            core.drop_table(&internal_table);
+            self.env
+                .notification_manager()
+                .notify_frontend(Operation::Delete, Info::Table(internal_table))
+                .await;
+        }
+        for adopted_table in adopted_tables {
+            self.env
+                .notification_manager()
+                .notify_frontend(Operation::Update, Info::Table(adopted_table))
+                .await;
+        }
+        self.env
+            .notification_manager()
+            .notify_frontend(Operation::Delete, Info::Table(table))
+            .await;
+        let version = self
+            .env
+            .notification_manager()
+            .notify_frontend(Operation::Update, Info::Table(renamed_table))
+            .await;
+
+        Ok(version)
+    }
+
+    /// Sets the comment of the table `table_id`, or of its column `column_id` if specified. An
+    /// empty `description` removes the comment.
+    pub async fn comment_on(
+        &self,
+        table_id: TableId,
+        column_id: Option<i32>,
+        description: String,
+    ) -> Result<NotificationVersion> {
+        let _core = self.core.lock().await;
+        let mut table = Table::select(self.env.meta_store(), &table_id)
+            .await?
+            .ok_or_else(|| RwError::from(InternalError("table doesn't exist".to_string())))?;
+        match column_id {
+            Some(column_id) => {
+                if !table.columns.iter().any(|c| {
+                    c.column_desc
+                        .as_ref()
+                        .map_or(false, |desc| desc.column_id == column_id)
+                }) {
+                    return Err(RwError::from(InternalError(
+                        "column doesn't exist".to_string(),
+                    )));
+                }
+                if description.is_empty() {
+                    table.column_descriptions.remove(&column_id);
+                } else {
+                    table.column_descriptions.insert(column_id, description);
+                }
+            }
+            None => table.description = description,
+        }
+        table.insert(self.env.meta_store()).await?;
+
+        let version = self
+            .env
+            .notification_manager()
+            .notify_frontend(Operation::Update, Info::Table(table))
+            .await;
+
+        Ok(version)
+    }
+
     pub async fn start_create_source_procedure(&self, source: &Source) -> Result<()> {
         let mut core = self.core.lock().await;
+        self.check_dependencies(source.database_id, source.schema_id, &[])
+            .await?;
         let key = (source.database_id, source.schema_id, source.name.clone());
         if !core.has_source(source) && !core.has_in_progress_creation(&key) {
             core.mark_creating(&key);
@@ -462,6 +686,12 @@ where
         mview: &Table,
     ) -> Result<()> {
         let mut core = self.core.lock().await;
+        self.check_dependencies(
+            source.database_id,
+            source.schema_id,
+            &mview.dependent_relations,
+        )
+        .await?;
         let source_key = (source.database_id, source.schema_id, source.name.clone());
         let mview_key = (mview.database_id, mview.schema_id, mview.name.clone());
         if !core.has_source(source)
@@ -471,7 +701,10 @@ where
         {
             core.mark_creating(&source_key);
             core.mark_creating(&mview_key);
-            ensure!(mview.dependent_relations.is_empty());
+            // The tables referenced by foreign keys of the materialized source.
+            for &dependent_relation_id in &mview.dependent_relations {
+                core.increase_ref_count(dependent_relation_id);
+            }
             Ok(())
         } else {
             Err(RwError::from(InternalError(
@@ -527,6 +760,8 @@ where
         mview: &Table,
     ) -> Result<()> {
         let mut core = self.core.lock().await;
+        self.check_dependencies(source.database_id, source.schema_id, &[])
+            .await?;
         let source_key = (source.database_id, source.schema_id, source.name.clone());
         let mview_key = (mview.database_id, mview.schema_id, mview.name.clone());
         if !core.has_source(source)
@@ -536,6 +771,9 @@ where
         {
             core.unmark_creating(&source_key);
             core.unmark_creating(&mview_key);
+            for &dependent_relation_id in &mview.dependent_relations {
+                core.decrease_ref_count(dependent_relation_id);
+            }
             Ok(())
         } else {
             Err(RwError::from(InternalError(
@@ -640,6 +878,10 @@ where
             .map(|s| s.id)
             .collect())
     }
+
+    pub async fn get_source(&self, source_id: SourceId) -> Result<Option<Source>> {
+        self.core.lock().await.get_source(source_id).await
+    }
 }
 
 type DatabaseKey = String;
@@ -817,3 +1059,118 @@ where
         self.in_progress_creation_tracker.remove(&relation.clone());
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_concurrent_ddl_conflicts() -> Result<()> {
+        let env = MetaSrvEnv::for_test().await;
+        let catalog_manager = CatalogManager::new(env).await?;
+        let (databases, schemas, _, _) = catalog_manager.get_catalog().await?;
+        let database_id = databases[0].id;
+        let schema_id = schemas
+            .iter()
+            .find(|s| s.name == DEFAULT_SCHEMA_NAME)
+            .unwrap()
+            .id;
+
+        let t = Table {
+            id: 1,
+            database_id,
+            schema_id,
+            name: "t".to_string(),
+            ..Default::default()
+        };
+        catalog_manager.create_table(&t).await?;
+
+        // Another frontend may still see `t` after it's dropped.
+        let mv = Table {
+            id: 2,
+            database_id,
+            schema_id,
+            name: "mv".to_string(),
+            dependent_relations: vec![t.id],
+            ..Default::default()
+        };
+        catalog_manager.drop_table(t.id).await?;
+        assert!(catalog_manager
+            .start_create_table_procedure(&mv)
+            .await
+            .is_err());
+
+        // Creating the same relation from two frontends at the same time.
+        let t2 = Table {
+            id: 3,
+            database_id,
+            schema_id,
+            name: "t2".to_string(),
+            ..Default::default()
+        };
+        catalog_manager.start_create_table_procedure(&t2).await?;
+        assert!(catalog_manager
+            .start_create_table_procedure(&Table {
+                id: 4,
+                ..t2.clone()
+            })
+            .await
+            .is_err());
+        catalog_manager
+            .finish_create_table_procedure(vec![], &t2)
+            .await?;
+
+        // Relations in a dropped schema are rejected.
+        assert!(catalog_manager
+            .create_table(&Table {
+                id: 5,
+                schema_id: schema_id + 100,
+                ..t2
+            })
+            .await
+            .is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_internal_tables() -> Result<()> {
+        let env = MetaSrvEnv::for_test().await;
+        let catalog_manager = CatalogManager::new(env).await?;
+        let (databases, schemas, _, _) = catalog_manager.get_catalog().await?;
+        let database_id = databases[0].id;
+        let schema_id = schemas
+            .iter()
+            .find(|s| s.name == DEFAULT_SCHEMA_NAME)
+            .unwrap()
+            .id;
+
+        let mv = Table {
+            id: 1,
+            database_id,
+            schema_id,
+            name: "mv".to_string(),
+            ..Default::default()
+        };
+        let internal_table = Table {
+            id: 2,
+            name: "__internal_mv_2".to_string(),
+            is_internal: true,
+            internal_of_id: mv.id,
+            ..mv.clone()
+        };
+        catalog_manager.start_create_table_procedure(&mv).await?;
+        catalog_manager
+            .finish_create_table_procedure(vec![internal_table], &mv)
+            .await?;
+        let (_, _, tables, _) = catalog_manager.get_catalog().await?;
+        assert_eq!(tables.len(), 2);
+
+        // Internal tables are dropped together with the materialized view.
+        catalog_manager.drop_table(mv.id).await?;
+        let (_, _, tables, _) = catalog_manager.get_catalog().await?;
+        assert!(tables.is_empty());
+
+        Ok(())
+    }
+}
@@ -18,12 +18,14 @@ use std::time::Duration;
 
 #[cfg(any(test, feature = "test"))]
 use prost::Message;
+use risingwave_common::config::{AutoParallelismConfig, StorageConfig};
+use risingwave_common::types::{set_virtual_node_count, VIRTUAL_NODE_COUNT};
 use risingwave_pb::meta::MetaLeaderInfo;
 #[cfg(any(test, feature = "test"))]
 use risingwave_pb::meta::MetaLeaseInfo;
 use risingwave_rpc_client::{StreamClientPool, StreamClientPoolRef};
 
-use super::{HashMappingManager, HashMappingManagerRef};
+use super::{EpochGenerator, EpochGeneratorRef, HashMappingManager, HashMappingManagerRef};
 use crate::manager::{
     IdGeneratorManager, IdGeneratorManagerRef, NotificationManager, NotificationManagerRef,
 };
@@ -31,7 +33,10 @@ use crate::manager::{
 use crate::rpc::{META_CF_NAME, META_LEADER_KEY, META_LEASE_KEY};
 #[cfg(any(test, feature = "test"))]
 use crate::storage::MemStore;
-use crate::storage::MetaStore;
+use crate::storage::{self, MetaStore, DEFAULT_COLUMN_FAMILY};
+
+/// Key of the vnode count of the cluster in the meta store.
+const VNODE_COUNT_KEY: &[u8] = b"vnode_count";
 
 /// [`MetaSrvEnv`] is the global environment in Meta service. The instance will be shared by all
 /// kind of managers inside Meta.
@@ -43,6 +48,9 @@ where
     /// id generator manager.
     id_gen_manager: IdGeneratorManagerRef<S>,
 
+    /// epoch generator.
+    epoch_generator: EpochGeneratorRef<S>,
+
     /// meta store.
     meta_store: Arc<S>,
 
@@ -65,6 +73,26 @@ where
 pub struct MetaOpts {
     pub enable_recovery: bool,
     pub checkpoint_interval: Duration,
+    /// Number of barriers between two checkpoints, if not specified by the streaming jobs.
+    pub checkpoint_frequency: usize,
+    /// Initial capacity of the block cache on compute nodes, before changed by `ALTER SYSTEM`.
+    pub block_cache_capacity_mb: usize,
+    /// Initial capacity of the meta cache on compute nodes, before changed by `ALTER SYSTEM`.
+    pub meta_cache_capacity_mb: usize,
+    /// Where telemetry reports are sent if `telemetry_enabled` is set. Reports are only kept
+    /// locally if not specified.
+    pub telemetry_endpoint: Option<String>,
+    /// Number of virtual nodes used when the cluster is created. It's ignored once the cluster has
+    /// been created.
+    pub vnode_count: usize,
+    /// State store url of the cluster, which committed checkpoints are replicated from.
+    pub state_store: Option<String>,
+    /// Object store url that committed checkpoints are replicated to, if any.
+    pub replica_object_store: Option<String>,
+    /// Directory of the SSTs in the object stores.
+    pub data_directory: String,
+    /// Configs of tuning the parallelism of fragments according to their backpressure.
+    pub auto_parallelism: AutoParallelismConfig,
 }
 
 impl Default for MetaOpts {
@@ -72,6 +100,15 @@ impl Default for MetaOpts {
         Self {
             enable_recovery: false,
             checkpoint_interval: Duration::from_millis(100),
+            checkpoint_frequency: 1,
+            block_cache_capacity_mb: 256,
+            meta_cache_capacity_mb: 64,
+            telemetry_endpoint: None,
+            vnode_count: VIRTUAL_NODE_COUNT,
+            state_store: None,
+            replica_object_store: None,
+            data_directory: StorageConfig::default().data_directory,
+            auto_parallelism: AutoParallelismConfig::default(),
         }
     }
 }
@@ -80,10 +117,54 @@ impl MetaOpts {
         Self {
             enable_recovery,
             checkpoint_interval: Duration::from_millis(checkpoint_interval),
+            checkpoint_frequency: 1,
+            block_cache_capacity_mb: 256,
+            meta_cache_capacity_mb: 64,
+            telemetry_endpoint: None,
+            vnode_count: VIRTUAL_NODE_COUNT,
+            state_store: None,
+            replica_object_store: None,
+            data_directory: StorageConfig::default().data_directory,
+            auto_parallelism: AutoParallelismConfig::default(),
         }
     }
 }
 
+/// Sets the vnode count of this process to the one persisted on cluster creation. If the cluster
+/// is being created, `vnode_count` is used and persisted.
+async fn init_vnode_count<S: MetaStore>(meta_store: &S, vnode_count: usize) -> usize {
+    match meta_store
+        .get_cf(DEFAULT_COLUMN_FAMILY, VNODE_COUNT_KEY)
+        .await
+    {
+        Ok(bytes) => {
+            let persisted = u32::from_be_bytes(bytes.as_slice().try_into().unwrap()) as usize;
+            if persisted != vnode_count {
+                tracing::warn!(
+                    "vnode count {} is ignored since the cluster was created with {}",
+                    vnode_count,
+                    persisted
+                );
+            }
+            set_virtual_node_count(persisted).unwrap();
+            persisted
+        }
+        Err(storage::Error::ItemNotFound(_)) => {
+            set_virtual_node_count(vnode_count).expect("invalid vnode count");
+            meta_store
+                .put_cf(
+                    DEFAULT_COLUMN_FAMILY,
+                    VNODE_COUNT_KEY.to_vec(),
+                    (vnode_count as u32).to_be_bytes().to_vec(),
+                )
+                .await
+                .unwrap();
+            vnode_count
+        }
+        Err(e) => panic!("{:?}", e),
+    }
+}
+
 impl<S> MetaSrvEnv<S>
 where
     S: MetaStore,
@@ -91,12 +172,16 @@ where
     pub async fn new(opts: MetaOpts, meta_store: Arc<S>, info: MetaLeaderInfo) -> Self {
         // change to sync after refactor `IdGeneratorManager::new` sync.
         let id_gen_manager = Arc::new(IdGeneratorManager::new(meta_store.clone()).await);
+        let epoch_generator = Arc::new(EpochGenerator::new(meta_store.clone()).await);
+        let vnode_count = init_vnode_count(meta_store.as_ref(), opts.vnode_count).await;
+        tracing::info!("cluster vnode count: {}", vnode_count);
         let stream_client_pool = Arc::new(StreamClientPool::default());
         let notification_manager = Arc::new(NotificationManager::new());
         let hash_mapping_manager = Arc::new(HashMappingManager::new());
 
         Self {
             id_gen_manager,
+            epoch_generator,
             meta_store,
             notification_manager,
             hash_mapping_manager,
@@ -122,6 +207,14 @@ where
         self.id_gen_manager.deref()
     }
 
+    pub fn epoch_generator_ref(&self) -> EpochGeneratorRef<S> {
+        self.epoch_generator.clone()
+    }
+
+    pub fn epoch_generator(&self) -> &EpochGenerator<S> {
+        self.epoch_generator.deref()
+    }
+
     pub fn notification_manager_ref(&self) -> NotificationManagerRef {
         self.notification_manager.clone()
     }
@@ -187,12 +280,14 @@ impl MetaSrvEnv<MemStore> {
             .await
             .unwrap();
         let id_gen_manager = Arc::new(IdGeneratorManager::new(meta_store.clone()).await);
+        let epoch_generator = Arc::new(EpochGenerator::new(meta_store.clone()).await);
         let notification_manager = Arc::new(NotificationManager::new());
         let stream_client_pool = Arc::new(StreamClientPool::default());
         let hash_mapping_manager = Arc::new(HashMappingManager::new());
 
         Self {
             id_gen_manager,
+            epoch_generator,
             meta_store,
             notification_manager,
             hash_mapping_manager,
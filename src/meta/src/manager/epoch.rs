@@ -0,0 +1,130 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use risingwave_common::error::Result;
+use risingwave_common::util::epoch::Epoch;
+use tokio::sync::Mutex;
+
+use crate::storage::{self, MetaStore, DEFAULT_COLUMN_FAMILY};
+
+/// Key of the physical time bound of generated epochs in the meta store.
+const EPOCH_PHYSICAL_BOUND_KEY: &[u8] = b"epoch_physical_bound";
+
+/// How far ahead of the generated epochs the physical time bound is persisted, in milliseconds.
+/// The bound is only persisted once the epochs reach it, rather than for every epoch.
+pub const EPOCH_PREALLOCATE_INTERVAL_MS: u64 = 3000;
+
+pub type EpochGeneratorRef<S> = Arc<EpochGenerator<S>>;
+
+/// [`EpochGenerator`] generates the epochs of the cluster as hybrid logical clocks. The physical
+/// part follows the system clock of the meta node, and the logical part orders the epochs within
+/// the same millisecond, or while the clock is behind the latest epoch. So the epochs keep
+/// increasing even if the clock goes backwards.
+///
+/// The physical time of the generated epochs is bounded by a value persisted in the meta store.
+/// A new leader starts from the bound, so its epochs are greater than any epoch generated before
+/// the failover, even if its clock is behind the clock of the previous leader.
+pub struct EpochGenerator<S> {
+    meta_store: Arc<S>,
+    core: Mutex<EpochGeneratorCore>,
+}
+
+struct EpochGeneratorCore {
+    /// The latest epoch generated or observed.
+    latest: Epoch,
+    /// The physical time no generated epoch reaches, as persisted in the meta store.
+    physical_bound: u64,
+}
+
+impl<S> EpochGenerator<S>
+where
+    S: MetaStore,
+{
+    pub async fn new(meta_store: Arc<S>) -> Self {
+        let physical_bound = match meta_store
+            .get_cf(DEFAULT_COLUMN_FAMILY, EPOCH_PHYSICAL_BOUND_KEY)
+            .await
+        {
+            Ok(value) => u64::from_be_bytes(value.as_slice().try_into().unwrap()),
+            Err(storage::Error::ItemNotFound(_)) => 0,
+            Err(e) => panic!("{:?}", e),
+        };
+        Self {
+            meta_store,
+            core: Mutex::new(EpochGeneratorCore {
+                latest: Epoch::from_physical_time(physical_bound),
+                physical_bound,
+            }),
+        }
+    }
+
+    /// Generates an epoch greater than all the epochs generated or observed before.
+    pub async fn generate(&self) -> Result<Epoch> {
+        let mut core = self.core.lock().await;
+        let epoch = core.latest.next();
+        if epoch.physical_time() >= core.physical_bound {
+            let physical_bound = epoch.physical_time() + EPOCH_PREALLOCATE_INTERVAL_MS;
+            self.meta_store
+                .put_cf(
+                    DEFAULT_COLUMN_FAMILY,
+                    EPOCH_PHYSICAL_BOUND_KEY.to_vec(),
+                    physical_bound.to_be_bytes().to_vec(),
+                )
+                .await?;
+            core.physical_bound = physical_bound;
+        }
+        core.latest = epoch;
+        Ok(epoch)
+    }
+
+    /// Advances the clock to `epoch` if it's behind, e.g. to the epochs committed before the
+    /// physical time bound was persisted.
+    pub async fn observe(&self, epoch: Epoch) {
+        let mut core = self.core.lock().await;
+        core.latest = core.latest.max(epoch);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemStore;
+
+    #[tokio::test]
+    async fn test_epoch_generator() {
+        let meta_store = Arc::new(MemStore::default());
+        let epoch_generator = EpochGenerator::new(meta_store.clone()).await;
+        let mut prev_epoch = epoch_generator.generate().await.unwrap();
+        for _ in 0..100 {
+            let epoch = epoch_generator.generate().await.unwrap();
+            assert!(epoch > prev_epoch);
+            prev_epoch = epoch;
+        }
+
+        // Observed epochs ahead of the clock are followed.
+        let observed = Epoch::from_physical_time(prev_epoch.physical_time() + 60_000);
+        epoch_generator.observe(observed).await;
+        prev_epoch = epoch_generator.generate().await.unwrap();
+        assert!(prev_epoch > observed);
+
+        // A new leader starts from the persisted bound.
+        let epoch_generator = EpochGenerator::new(meta_store).await;
+        let epoch = epoch_generator.generate().await.unwrap();
+        assert!(
+            epoch.physical_time() >= prev_epoch.physical_time() + EPOCH_PREALLOCATE_INTERVAL_MS
+        );
+    }
+}
@@ -16,7 +16,7 @@ use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 
 use parking_lot::Mutex;
-use risingwave_common::types::{ParallelUnitId, VirtualNode, VIRTUAL_NODE_COUNT};
+use risingwave_common::types::{virtual_node_count, ParallelUnitId, VirtualNode};
 use risingwave_pb::common::ParallelUnit;
 
 use super::TableId;
@@ -143,11 +143,12 @@ impl HashMappingManagerCore {
         fragment_id: FragmentId,
         parallel_units: &[ParallelUnit],
     ) -> Vec<ParallelUnitId> {
-        let mut vnode_mapping = Vec::with_capacity(VIRTUAL_NODE_COUNT);
+        let vnode_count = virtual_node_count();
+        let mut vnode_mapping = Vec::with_capacity(vnode_count);
         let mut owner_mapping: HashMap<ParallelUnitId, Vec<VirtualNode>> = HashMap::new();
         let mut load_balancer: BTreeMap<usize, Vec<ParallelUnitId>> = BTreeMap::new();
-        let hash_shard_size = VIRTUAL_NODE_COUNT / parallel_units.len();
-        let mut one_more_count = VIRTUAL_NODE_COUNT % parallel_units.len();
+        let hash_shard_size = vnode_count / parallel_units.len();
+        let mut one_more_count = vnode_count % parallel_units.len();
         let mut init_bound = 0;
 
         parallel_units.iter().for_each(|parallel_unit| {
@@ -14,14 +14,20 @@
 
 mod catalog;
 mod env;
+mod epoch;
 mod hash_mapping;
 mod id;
 mod notification;
+mod system_param;
+mod telemetry;
 mod user;
 
 pub use catalog::*;
 pub use env::*;
+pub use epoch::*;
 pub use hash_mapping::*;
 pub use id::*;
 pub use notification::*;
+pub use system_param::*;
+pub use telemetry::*;
 pub use user::*;
@@ -0,0 +1,197 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::str::FromStr;
+use std::sync::Arc;
+
+use prost::Message;
+use risingwave_common::error::ErrorCode::{InvalidConfigValue, UnrecognizedConfigurationParameter};
+use risingwave_common::error::{Result, RwError};
+use risingwave_pb::meta::subscribe_response::{Info, Operation};
+use risingwave_pb::meta::SystemParams;
+use tokio::sync::{watch, Mutex, MutexGuard};
+
+use crate::manager::MetaSrvEnv;
+use crate::storage::{self, MetaStore, DEFAULT_COLUMN_FAMILY};
+
+const SYSTEM_PARAMS_KEY: &[u8] = b"system_params";
+
+pub const BARRIER_INTERVAL_MS: &str = "barrier_interval_ms";
+pub const CHECKPOINT_FREQUENCY: &str = "checkpoint_frequency";
+pub const BLOCK_CACHE_CAPACITY_MB: &str = "block_cache_capacity_mb";
+pub const META_CACHE_CAPACITY_MB: &str = "meta_cache_capacity_mb";
+pub const MAX_CONCURRENT_COMPACTION_TASKS: &str = "max_concurrent_compaction_tasks";
+pub const TELEMETRY_ENABLED: &str = "telemetry_enabled";
+
+/// `SystemParamManager` manages the cluster-wide parameters that can be changed at runtime by
+/// `ALTER SYSTEM SET`. The parameters are persisted in the meta store, so values set by users
+/// take precedence over the config file after restarts.
+///
+/// Components in meta subscribe to the changes with [`SystemParamManager::watch_params`], and
+/// compute nodes are notified through the notification service.
+pub struct SystemParamManager<S: MetaStore> {
+    env: MetaSrvEnv<S>,
+    params: Mutex<SystemParams>,
+    params_tx: watch::Sender<SystemParams>,
+}
+
+pub type SystemParamManagerRef<S> = Arc<SystemParamManager<S>>;
+
+impl<S: MetaStore> SystemParamManager<S> {
+    pub async fn new(env: MetaSrvEnv<S>) -> Result<Self> {
+        let params = match env
+            .meta_store()
+            .get_cf(DEFAULT_COLUMN_FAMILY, SYSTEM_PARAMS_KEY)
+            .await
+        {
+            Ok(bytes) => SystemParams::decode(bytes.as_slice())?,
+            Err(storage::Error::ItemNotFound(_)) => {
+                let opts = env.opts.as_ref();
+                SystemParams {
+                    barrier_interval_ms: opts.checkpoint_interval.as_millis() as u32,
+                    checkpoint_frequency: opts.checkpoint_frequency as u32,
+                    block_cache_capacity_mb: opts.block_cache_capacity_mb as u32,
+                    meta_cache_capacity_mb: opts.meta_cache_capacity_mb as u32,
+                    max_concurrent_compaction_tasks: 0,
+                    telemetry_enabled: false,
+                }
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let (params_tx, _) = watch::channel(params.clone());
+        Ok(Self {
+            env,
+            params: Mutex::new(params),
+            params_tx,
+        })
+    }
+
+    pub async fn get_params(&self) -> SystemParams {
+        self.params.lock().await.clone()
+    }
+
+    /// Used in `NotificationService::subscribe`, so that no change is missed between taking the
+    /// snapshot and registering the subscriber.
+    pub async fn get_params_guard(&self) -> MutexGuard<'_, SystemParams> {
+        self.params.lock().await
+    }
+
+    /// Returns a receiver which always sees the latest parameters.
+    pub fn watch_params(&self) -> watch::Receiver<SystemParams> {
+        self.params_tx.subscribe()
+    }
+
+    /// Validates and sets a parameter, then propagates the new parameters to meta components and
+    /// compute nodes.
+    pub async fn set_param(&self, name: &str, value: &str) -> Result<SystemParams> {
+        let mut guard = self.params.lock().await;
+        let mut params = guard.clone();
+        set_param_value(&mut params, name, value)?;
+
+        self.env
+            .meta_store()
+            .put_cf(
+                DEFAULT_COLUMN_FAMILY,
+                SYSTEM_PARAMS_KEY.to_vec(),
+                params.encode_to_vec(),
+            )
+            .await?;
+        *guard = params.clone();
+
+        self.env
+            .notification_manager()
+            .notify_compute(Operation::Update, Info::SystemParams(params.clone()))
+            .await;
+        self.params_tx.send_replace(params.clone());
+
+        Ok(params)
+    }
+}
+
+fn parse_param<T: FromStr>(name: &str, value: &str) -> Result<T> {
+    value.parse().map_err(|_| {
+        RwError::from(InvalidConfigValue {
+            config_entry: name.to_string(),
+            config_value: value.to_string(),
+        })
+    })
+}
+
+fn parse_positive_param(name: &str, value: &str) -> Result<u32> {
+    match parse_param(name, value)? {
+        0 => Err(RwError::from(InvalidConfigValue {
+            config_entry: name.to_string(),
+            config_value: value.to_string(),
+        })),
+        v => Ok(v),
+    }
+}
+
+fn set_param_value(params: &mut SystemParams, name: &str, value: &str) -> Result<()> {
+    match name {
+        BARRIER_INTERVAL_MS => params.barrier_interval_ms = parse_positive_param(name, value)?,
+        CHECKPOINT_FREQUENCY => params.checkpoint_frequency = parse_positive_param(name, value)?,
+        BLOCK_CACHE_CAPACITY_MB => {
+            params.block_cache_capacity_mb = parse_positive_param(name, value)?
+        }
+        META_CACHE_CAPACITY_MB => {
+            params.meta_cache_capacity_mb = parse_positive_param(name, value)?
+        }
+        MAX_CONCURRENT_COMPACTION_TASKS => {
+            params.max_concurrent_compaction_tasks = parse_param(name, value)?
+        }
+        TELEMETRY_ENABLED => params.telemetry_enabled = parse_param(name, value)?,
+        _ => {
+            return Err(RwError::from(UnrecognizedConfigurationParameter(
+                name.to_string(),
+            )))
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_set_param() -> Result<()> {
+        let env = MetaSrvEnv::for_test().await;
+        let manager = SystemParamManager::new(env.clone()).await?;
+        let params_rx = manager.watch_params();
+        assert_eq!(manager.get_params().await.barrier_interval_ms, 100);
+
+        manager.set_param(BARRIER_INTERVAL_MS, "500").await?;
+        manager.set_param(TELEMETRY_ENABLED, "true").await?;
+        let params = params_rx.borrow().clone();
+        assert_eq!(params.barrier_interval_ms, 500);
+        assert!(params.telemetry_enabled);
+
+        // Invalid settings are rejected and leave the parameters unchanged.
+        assert!(manager.set_param(BARRIER_INTERVAL_MS, "0").await.is_err());
+        assert!(manager
+            .set_param(CHECKPOINT_FREQUENCY, "abc")
+            .await
+            .is_err());
+        assert!(manager.set_param("no_such_param", "1").await.is_err());
+        assert_eq!(*params_rx.borrow(), params);
+        assert_eq!(manager.get_params().await, params);
+
+        // The parameters survive restarts.
+        let manager = SystemParamManager::new(env).await?;
+        assert_eq!(manager.get_params().await, params);
+
+        Ok(())
+    }
+}
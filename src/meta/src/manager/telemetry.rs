@@ -0,0 +1,289 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Opt-in telemetry of the cluster.
+//!
+//! When the `telemetry_enabled` system parameter is set, meta periodically builds a
+//! [`TelemetryReport`] of aggregate counters: the number of nodes of each type, the number of
+//! catalog objects of each kind, and the number of errors of each category. No names, queries or
+//! data are included. The report is sent as JSON to the configured endpoint, and the latest one is
+//! kept so that it can be inspected with the `rw_telemetry` system table.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::anyhow;
+use hyper::{Body, Method, Request};
+use parking_lot::Mutex;
+use risingwave_common::error::Result;
+use risingwave_pb::catalog::source::Info;
+use risingwave_pb::common::WorkerType;
+use risingwave_pb::meta::{SystemParams, TelemetryReport};
+use serde_json::json;
+use tokio::sync::oneshot::Sender;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+use crate::cluster::ClusterManagerRef;
+use crate::manager::{CatalogManagerRef, MetaSrvEnv};
+use crate::storage::{self, MetaStore, DEFAULT_COLUMN_FAMILY};
+
+const TRACKING_ID_KEY: &[u8] = b"telemetry_tracking_id";
+
+/// How often a report is built and sent when telemetry is enabled.
+pub const TELEMETRY_REPORT_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+lazy_static::lazy_static! {
+    static ref ERROR_COUNTS: Mutex<BTreeMap<&'static str, u64>> = Mutex::new(BTreeMap::new());
+}
+
+/// Counts an error of `category` for telemetry. It's cheap and always counted, whether telemetry
+/// is enabled or not.
+pub fn record_error(category: &'static str) {
+    *ERROR_COUNTS.lock().entry(category).or_default() += 1;
+}
+
+pub struct TelemetryManager<S: MetaStore> {
+    cluster_manager: ClusterManagerRef<S>,
+    catalog_manager: CatalogManagerRef<S>,
+    /// Where reports are sent. Reports are only kept locally if it's not set.
+    endpoint: Option<String>,
+    tracking_id: String,
+    last_report: Mutex<Option<TelemetryReport>>,
+}
+
+pub type TelemetryManagerRef<S> = Arc<TelemetryManager<S>>;
+
+impl<S: MetaStore> TelemetryManager<S> {
+    pub async fn new(
+        env: MetaSrvEnv<S>,
+        cluster_manager: ClusterManagerRef<S>,
+        catalog_manager: CatalogManagerRef<S>,
+        endpoint: Option<String>,
+    ) -> Result<Self> {
+        let tracking_id = match env
+            .meta_store()
+            .get_cf(DEFAULT_COLUMN_FAMILY, TRACKING_ID_KEY)
+            .await
+        {
+            Ok(bytes) => String::from_utf8_lossy(&bytes).to_string(),
+            Err(storage::Error::ItemNotFound(_)) => {
+                let tracking_id = uuid::Uuid::new_v4().to_string();
+                env.meta_store()
+                    .put_cf(
+                        DEFAULT_COLUMN_FAMILY,
+                        TRACKING_ID_KEY.to_vec(),
+                        tracking_id.clone().into_bytes(),
+                    )
+                    .await?;
+                tracking_id
+            }
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self {
+            cluster_manager,
+            catalog_manager,
+            endpoint,
+            tracking_id,
+            last_report: Mutex::new(None),
+        })
+    }
+
+    /// Returns the last report, or a report built now if none has been built yet, so that users can
+    /// see what would be reported before enabling telemetry.
+    pub async fn get_report(&self) -> Result<TelemetryReport> {
+        if let Some(report) = self.last_report.lock().clone() {
+            return Ok(report);
+        }
+        self.build_report().await
+    }
+
+    pub async fn build_report(&self) -> Result<TelemetryReport> {
+        let mut node_counts = HashMap::new();
+        for (worker_type, name) in [
+            (WorkerType::Frontend, "frontend"),
+            (WorkerType::ComputeNode, "compute_node"),
+            (WorkerType::Compactor, "compactor"),
+            (WorkerType::ServingNode, "serving_node"),
+        ] {
+            let count = self
+                .cluster_manager
+                .list_worker_node(worker_type, None)
+                .await
+                .len();
+            node_counts.insert(name.to_string(), count as u64);
+        }
+
+        let (databases, schemas, tables, sources) = self
+            .catalog_manager
+            .get_catalog_core_guard()
+            .await
+            .get_catalog()
+            .await?;
+        let mut feature_usage: HashMap<String, u64> = HashMap::new();
+        let mut count = |kind: &str| *feature_usage.entry(kind.to_string()).or_default() += 1;
+        databases.iter().for_each(|_| count("database"));
+        schemas.iter().for_each(|_| count("schema"));
+        for table in &tables {
+            if table.is_internal {
+                count("internal_table");
+            } else if table.is_index {
+                count("index");
+            } else if table.optional_associated_source_id.is_some() {
+                count("table");
+            } else {
+                count("materialized_view");
+            }
+        }
+        for source in &sources {
+            match source.info {
+                Some(Info::StreamSource(_)) => count("source"),
+                Some(Info::TableSource(_)) => {}
+                Some(Info::ExternalTable(_)) => count("external_table"),
+                None => {}
+            }
+        }
+
+        let error_counts = ERROR_COUNTS
+            .lock()
+            .iter()
+            .map(|(category, count)| (category.to_string(), *count))
+            .collect();
+
+        Ok(TelemetryReport {
+            tracking_id: self.tracking_id.clone(),
+            report_time_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_millis() as u64,
+            node_counts,
+            feature_usage,
+            error_counts,
+            reported: false,
+            endpoint: self.endpoint.clone().unwrap_or_default(),
+        })
+    }
+
+    async fn send_report(&self, report: &TelemetryReport) -> anyhow::Result<()> {
+        let endpoint = self
+            .endpoint
+            .as_ref()
+            .ok_or_else(|| anyhow!("no telemetry endpoint"))?;
+        let body = json!({
+            "tracking_id": report.tracking_id,
+            "report_time_ms": report.report_time_ms,
+            "node_counts": report.node_counts,
+            "feature_usage": report.feature_usage,
+            "error_counts": report.error_counts,
+        });
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri(endpoint)
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))?;
+        let response = hyper::Client::new().request(request).await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("telemetry endpoint returned {}", response.status()));
+        }
+        Ok(())
+    }
+
+    async fn report(&self) {
+        let mut report = match self.build_report().await {
+            Ok(report) => report,
+            Err(e) => {
+                tracing::warn!("failed to build telemetry report: {}", e);
+                return;
+            }
+        };
+        if self.endpoint.is_some() {
+            match self.send_report(&report).await {
+                Ok(()) => report.reported = true,
+                Err(e) => tracing::warn!("failed to send telemetry report: {}", e),
+            }
+        }
+        *self.last_report.lock() = Some(report);
+    }
+
+    /// Starts reporting every `interval` while telemetry is enabled.
+    pub async fn start_reporter(
+        telemetry_manager: TelemetryManagerRef<S>,
+        mut params_rx: watch::Receiver<SystemParams>,
+        interval: Duration,
+    ) -> (JoinHandle<()>, Sender<()>) {
+        let (shutdown_tx, mut shutdown_rx) = tokio::sync::oneshot::channel();
+        let join_handle = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {
+                        if params_rx.borrow().telemetry_enabled {
+                            telemetry_manager.report().await;
+                        }
+                    }
+                    changed = params_rx.changed() => {
+                        if changed.is_err() {
+                            return;
+                        }
+                        // Report right after telemetry is enabled.
+                        if params_rx.borrow().telemetry_enabled {
+                            ticker.reset();
+                            telemetry_manager.report().await;
+                        }
+                    }
+                    _ = &mut shutdown_rx => {
+                        return;
+                    }
+                }
+            }
+        });
+        (join_handle, shutdown_tx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::cluster::ClusterManager;
+    use crate::manager::CatalogManager;
+
+    #[tokio::test]
+    async fn test_build_report() -> Result<()> {
+        let env = MetaSrvEnv::for_test().await;
+        let cluster_manager =
+            Arc::new(ClusterManager::new(env.clone(), Duration::from_secs(3600)).await?);
+        let catalog_manager = Arc::new(CatalogManager::new(env.clone()).await?);
+        let manager = TelemetryManager::new(
+            env.clone(),
+            cluster_manager.clone(),
+            catalog_manager.clone(),
+            None,
+        )
+        .await?;
+
+        record_error("test_error");
+        let report = manager.get_report().await?;
+        assert!(!report.reported);
+        assert_eq!(report.node_counts["compute_node"], 0);
+        assert!(report.error_counts["test_error"] >= 1);
+
+        // The tracking id survives restarts.
+        let manager = TelemetryManager::new(env, cluster_manager, catalog_manager, None).await?;
+        assert_eq!(manager.get_report().await?.tracking_id, report.tracking_id);
+        Ok(())
+    }
+}
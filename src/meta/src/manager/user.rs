@@ -20,6 +20,7 @@ use risingwave_common::error::ErrorCode::InternalError;
 use risingwave_common::error::{Result, RwError};
 use risingwave_pb::meta::subscribe_response::{Info, Operation};
 use risingwave_pb::user::grant_privilege::{ActionWithGrantOption, Object};
+use risingwave_pb::user::update_user_request::UpdateField;
 use risingwave_pb::user::{GrantPrivilege, UserInfo};
 use tokio::sync::{Mutex, MutexGuard};
 
@@ -143,6 +144,39 @@ impl<S: MetaStore> UserManager<S> {
             .await;
         Ok(version)
     }
+
+    /// Updates the `update_fields` of the user to the ones of `update_user`.
+    pub async fn update_user(
+        &self,
+        update_user: &UserInfo,
+        update_fields: &[UpdateField],
+    ) -> Result<NotificationVersion> {
+        let mut core = self.core.lock().await;
+        let mut user = core
+            .get(&update_user.name)
+            .ok_or_else(|| InternalError(format!("User {} does not exist", update_user.name)))
+            .cloned()?;
+        for field in update_fields {
+            match field {
+                UpdateField::Limits => user.limits = update_user.limits.clone(),
+                UpdateField::Unknown => {
+                    return Err(RwError::from(InternalError(
+                        "Unknown field to update".to_string(),
+                    )))
+                }
+            }
+        }
+
+        user.insert(self.env.meta_store()).await?;
+        core.insert(user.name.clone(), user.clone());
+
+        let version = self
+            .env
+            .notification_manager()
+            .notify_frontend(Operation::Update, Info::User(user))
+            .await;
+        Ok(version)
+    }
 }
 
 // Defines privilege grant for a user.
@@ -349,6 +383,7 @@ impl<S: MetaStore> UserManager<S> {
 #[cfg(test)]
 mod tests {
     use risingwave_pb::user::grant_privilege::Action;
+    use risingwave_pb::user::UserLimits;
 
     use super::*;
 
@@ -389,6 +424,26 @@ mod tests {
         let users = user_manager.list_users().await?;
         assert_eq!(users.len(), 3);
 
+        // Only the given fields are updated.
+        let limits = UserLimits {
+            statement_timeout_ms: 1000,
+            ..Default::default()
+        };
+        user_manager
+            .update_user(
+                &UserInfo {
+                    name: test_user.to_string(),
+                    is_supper: true,
+                    limits: Some(limits.clone()),
+                    ..Default::default()
+                },
+                &[UpdateField::Limits],
+            )
+            .await?;
+        let user = user_manager.get_user(&test_user.to_string()).await?;
+        assert_eq!(user.limits, Some(limits));
+        assert!(!user.is_supper);
+
         let object = Object::TableId(0);
         // Grant Select/Insert without grant option.
         user_manager
@@ -26,6 +26,7 @@ pub const INVALID_EXPIRE_AT: u64 = 0;
 pub struct Worker {
     pub worker_node: WorkerNode,
     expire_at: u64,
+    cordoned: bool,
 }
 
 impl MetadataModel for Worker {
@@ -44,6 +45,7 @@ impl MetadataModel for Worker {
         Self {
             worker_node: prost,
             expire_at: INVALID_EXPIRE_AT,
+            cordoned: false,
         }
     }
 
@@ -68,4 +70,14 @@ impl Worker {
     pub fn set_expire_at(&mut self, expire_at: u64) {
         self.expire_at = expire_at;
     }
+
+    /// Whether the worker is shutting down. The flag is not persisted, as the worker will be
+    /// gone soon.
+    pub fn is_cordoned(&self) -> bool {
+        self.cordoned
+    }
+
+    pub fn set_cordoned(&mut self) {
+        self.cordoned = true;
+    }
 }
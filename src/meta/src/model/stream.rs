@@ -15,14 +15,22 @@
 use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 
 use itertools::Itertools;
-use risingwave_common::catalog::TableId;
-use risingwave_common::error::Result;
-use risingwave_common::types::ParallelUnitId;
+use risingwave_common::catalog::{TableId, DEFAULT_RESOURCE_GROUP};
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_common::types::{virtual_node_count, ParallelUnitId};
+use risingwave_hummock_sdk::HummockEpoch;
+use risingwave_pb::catalog::Table;
+use risingwave_pb::common::ParallelUnit;
+use risingwave_pb::data::ConfigChange;
+use risingwave_pb::hummock::PartitionRetention;
+use risingwave_pb::meta::table_fragments::fragment::FragmentDistributionType;
 use risingwave_pb::meta::table_fragments::{ActorState, ActorStatus, Fragment};
 use risingwave_pb::meta::TableFragments as ProstTableFragments;
 use risingwave_pb::stream_plan::source_node::SourceType;
 use risingwave_pb::stream_plan::stream_node::NodeBody;
-use risingwave_pb::stream_plan::{FragmentType, StreamActor, StreamNode};
+use risingwave_pb::stream_plan::{
+    ActorMapping, DispatcherType, FragmentType, StreamActor, StreamFragmentGraph, StreamNode,
+};
 
 use super::{ActorId, FragmentId};
 use crate::cluster::WorkerId;
@@ -49,6 +57,27 @@ pub struct TableFragments {
 
     /// Internal TableIds from all Fragment
     internal_table_ids: Vec<u32>,
+
+    /// Checkpoint the job every given number of barriers. `None` means the cluster default.
+    checkpoint_frequency: Option<u32>,
+
+    /// Expire rows of the table older than the given seconds. `None` means never.
+    retention_seconds: Option<u32>,
+
+    /// Expire the partitions of the partitioned table. `None` means never.
+    partition_retention: Option<PartitionRetention>,
+
+    /// The fragment graph planned by the frontend, from which the job can be recreated.
+    fragment_graph: Option<StreamFragmentGraph>,
+
+    /// The committed epoch the job can be restored to. `None` means no savepoint.
+    savepoint_epoch: Option<HummockEpoch>,
+
+    /// The resource group of the compute nodes the job is scheduled to.
+    resource_group: String,
+
+    /// The latest change of each kind applied to the source actors, without actor ids.
+    config_changes: Vec<ConfigChange>,
 }
 
 impl MetadataModel for TableFragments {
@@ -65,6 +94,13 @@ impl MetadataModel for TableFragments {
             fragments: self.fragments.clone().into_iter().collect(),
             actor_status: self.actor_status.clone().into_iter().collect(),
             internal_table_ids: self.internal_table_ids.clone(),
+            checkpoint_frequency: self.checkpoint_frequency.unwrap_or_default(),
+            retention_seconds: self.retention_seconds.unwrap_or_default(),
+            fragment_graph: self.fragment_graph.clone(),
+            partition_retention: self.partition_retention.clone(),
+            savepoint_epoch: self.savepoint_epoch.unwrap_or_default(),
+            resource_group: self.resource_group.clone(),
+            config_changes: self.config_changes.clone(),
         }
     }
 
@@ -74,6 +110,18 @@ impl MetadataModel for TableFragments {
             fragments: prost.fragments.into_iter().collect(),
             actor_status: prost.actor_status.into_iter().collect(),
             internal_table_ids: prost.internal_table_ids,
+            checkpoint_frequency: Some(prost.checkpoint_frequency).filter(|f| *f > 0),
+            retention_seconds: Some(prost.retention_seconds).filter(|r| *r > 0),
+            fragment_graph: prost.fragment_graph,
+            partition_retention: prost.partition_retention,
+            savepoint_epoch: Some(prost.savepoint_epoch).filter(|e| *e > 0),
+            // Jobs created before resource groups are in the default one.
+            resource_group: if prost.resource_group.is_empty() {
+                DEFAULT_RESOURCE_GROUP.to_string()
+            } else {
+                prost.resource_group
+            },
+            config_changes: prost.config_changes,
         }
     }
 
@@ -85,17 +133,92 @@ impl MetadataModel for TableFragments {
 impl TableFragments {
     pub fn new(
         table_id: TableId,
-        fragments: BTreeMap<FragmentId, Fragment>,
+        mut fragments: BTreeMap<FragmentId, Fragment>,
         internal_table_id_set: HashSet<u32>,
     ) -> Self {
+        // Tag the actors with the materialized view for their metrics.
+        for actor in fragments.values_mut().flat_map(|f| f.actors.iter_mut()) {
+            actor.table_id = table_id.table_id;
+        }
         Self {
             table_id,
             fragments,
             actor_status: BTreeMap::default(),
             internal_table_ids: Vec::from_iter(internal_table_id_set),
+            checkpoint_frequency: None,
+            retention_seconds: None,
+            partition_retention: None,
+            fragment_graph: None,
+            savepoint_epoch: None,
+            resource_group: DEFAULT_RESOURCE_GROUP.to_string(),
+            config_changes: vec![],
         }
     }
 
+    pub fn checkpoint_frequency(&self) -> Option<u32> {
+        self.checkpoint_frequency
+    }
+
+    pub fn set_checkpoint_frequency(&mut self, checkpoint_frequency: Option<u32>) {
+        self.checkpoint_frequency = checkpoint_frequency;
+    }
+
+    pub fn retention_seconds(&self) -> Option<u32> {
+        self.retention_seconds
+    }
+
+    pub fn set_retention_seconds(&mut self, retention_seconds: Option<u32>) {
+        self.retention_seconds = retention_seconds;
+    }
+
+    pub fn partition_retention(&self) -> Option<&PartitionRetention> {
+        self.partition_retention.as_ref()
+    }
+
+    pub fn set_partition_retention(&mut self, partition_retention: Option<PartitionRetention>) {
+        self.partition_retention = partition_retention;
+    }
+
+    pub fn savepoint_epoch(&self) -> Option<HummockEpoch> {
+        self.savepoint_epoch
+    }
+
+    pub fn set_savepoint_epoch(&mut self, savepoint_epoch: Option<HummockEpoch>) {
+        self.savepoint_epoch = savepoint_epoch;
+    }
+
+    pub fn resource_group(&self) -> &str {
+        &self.resource_group
+    }
+
+    pub fn set_resource_group(&mut self, resource_group: String) {
+        self.resource_group = resource_group;
+    }
+
+    pub fn config_changes(&self) -> &[ConfigChange] {
+        &self.config_changes
+    }
+
+    /// Records the change applied to the source actors, replacing the earlier one of the same
+    /// kind.
+    pub fn set_config_change(&mut self, change: &ConfigChange) {
+        let kind = change.change.as_ref().map(std::mem::discriminant);
+        self.config_changes
+            .retain(|c| c.change.as_ref().map(std::mem::discriminant) != kind);
+        self.config_changes.push(ConfigChange {
+            actor_ids: vec![],
+            change: change.change.clone(),
+        });
+    }
+
+    pub fn fragment_graph(&self) -> Option<&StreamFragmentGraph> {
+        self.fragment_graph.as_ref()
+    }
+
+    pub fn set_fragment_graph(&mut self, fragment_graph: StreamFragmentGraph) {
+        self.fragment_graph = Some(fragment_graph);
+    }
+
     pub fn fragments(&self) -> Vec<&Fragment> {
         self.fragments.values().collect_vec()
     }
@@ -105,6 +228,192 @@ impl TableFragments {
         self.actor_status = actor_status;
     }
 
+    /// Returns the fragment and the parallel unit of each actor.
+    pub fn fragment_parallel_units(&self) -> impl Iterator<Item = (FragmentId, &ParallelUnit)> {
+        self.fragments.values().flat_map(move |fragment| {
+            fragment.actors.iter().filter_map(move |actor| {
+                let actor_status = self.actor_status.get(&actor.actor_id)?;
+                Some((fragment.fragment_id, actor_status.parallel_unit.as_ref()?))
+            })
+        })
+    }
+
+    /// Moves the actors on the parallel units in `targets` to the target parallel units, and
+    /// returns whether any actor is moved. The vnode mappings of the fragments are updated to the
+    /// target parallel units.
+    pub fn migrate_parallel_units(
+        &mut self,
+        targets: &HashMap<ParallelUnitId, ParallelUnit>,
+    ) -> bool {
+        let mut migrated = false;
+        for actor_status in self.actor_status.values_mut() {
+            if let Some(parallel_unit) = actor_status.parallel_unit.as_mut()
+                && let Some(target) = targets.get(&parallel_unit.id)
+            {
+                *parallel_unit = target.clone();
+                migrated = true;
+            }
+        }
+        if migrated {
+            for fragment in self.fragments.values_mut() {
+                if let Some(vnode_mapping) = fragment.vnode_mapping.as_mut() {
+                    for parallel_unit_id in &mut vnode_mapping.data {
+                        if let Some(target) = targets.get(parallel_unit_id) {
+                            *parallel_unit_id = target.id;
+                        }
+                    }
+                }
+            }
+        }
+        migrated
+    }
+
+    /// Checks whether the fragment can be rescheduled to a different number of actors. Only
+    /// hash-distributed fragments connected to other fragments of the job by hash or broadcast
+    /// exchanges can, since the actors of sources, chains and no-shuffle exchanges are bound to
+    /// their splits or upstream actors.
+    pub fn check_reschedulable(&self, fragment_id: FragmentId) -> Result<()> {
+        let not_reschedulable = |reason: &str| -> Result<()> {
+            Err(ErrorCode::InvalidParameterValue(format!(
+                "fragment {} of table {} can't be rescheduled: {}",
+                fragment_id, self.table_id, reason
+            ))
+            .into())
+        };
+        let fragment = match self.fragments.get(&fragment_id) {
+            Some(fragment) => fragment,
+            None => return not_reschedulable("fragment not found"),
+        };
+        if fragment.distribution_type != FragmentDistributionType::Hash as i32 {
+            return not_reschedulable("singleton fragment");
+        }
+        let actor_ids: HashSet<_> = fragment.actors.iter().map(|a| a.actor_id).collect();
+        for actor in &fragment.actors {
+            let stream_node = actor.get_nodes()?;
+            if actor.same_worker_node_as_upstream
+                || Self::contains_chain(stream_node)
+                || Self::fetch_stream_source_id(stream_node).is_some()
+            {
+                return not_reschedulable("actors bound to upstream or source");
+            }
+        }
+        let own_actor_ids: HashSet<_> = self.actor_ids().into_iter().collect();
+        for actor in self.fragments.values().flat_map(|f| f.actors.iter()) {
+            for dispatcher in &actor.dispatcher {
+                let downstream_rescheduled = dispatcher
+                    .downstream_actor_id
+                    .iter()
+                    .any(|id| actor_ids.contains(id));
+                if downstream_rescheduled
+                    && dispatcher.r#type != DispatcherType::Hash as i32
+                    && dispatcher.r#type != DispatcherType::Broadcast as i32
+                {
+                    return not_reschedulable("input not exchanged by hash or broadcast");
+                }
+                if actor_ids.contains(&actor.actor_id)
+                    && !dispatcher
+                        .downstream_actor_id
+                        .iter()
+                        .all(|id| own_actor_ids.contains(id))
+                {
+                    return not_reschedulable("read by other materialized views");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Replaces the actors of the fragment with the ones of the given fragment, which are
+    /// scheduled to `actor_locations`. The dispatchers of upstream actors and the merge nodes of
+    /// downstream actors are updated to the new actors. The fragment must have been checked by
+    /// [`Self::check_reschedulable`].
+    pub fn reschedule_fragment(
+        &mut self,
+        fragment: Fragment,
+        actor_locations: &BTreeMap<ActorId, ParallelUnit>,
+    ) {
+        let old_fragment = self
+            .fragments
+            .insert(fragment.fragment_id, fragment.clone())
+            .unwrap();
+        let old_actor_ids: HashSet<_> = old_fragment.actors.iter().map(|a| a.actor_id).collect();
+        let new_actor_ids = fragment.actors.iter().map(|a| a.actor_id).collect_vec();
+
+        // The consistent hash mapping from vnodes to the new actors.
+        let hash_mapping = if new_actor_ids.len() == 1 {
+            ActorMapping {
+                original_indices: vec![virtual_node_count() as u64 - 1],
+                data: new_actor_ids.clone(),
+            }
+        } else {
+            let parallel_unit_actors: HashMap<_, _> = actor_locations
+                .iter()
+                .filter(|(actor_id, _)| new_actor_ids.contains(actor_id))
+                .map(|(&actor_id, parallel_unit)| (parallel_unit.id, actor_id))
+                .collect();
+            let vnode_mapping = fragment.vnode_mapping.as_ref().unwrap();
+            ActorMapping {
+                original_indices: vnode_mapping.original_indices.clone(),
+                data: vnode_mapping
+                    .data
+                    .iter()
+                    .map(|parallel_unit_id| parallel_unit_actors[parallel_unit_id])
+                    .collect(),
+            }
+        };
+
+        let replace = |actor_ids: &mut Vec<ActorId>| {
+            if actor_ids.iter().any(|id| old_actor_ids.contains(id)) {
+                actor_ids.retain(|id| !old_actor_ids.contains(id));
+                actor_ids.extend(new_actor_ids.iter().copied());
+            }
+        };
+        for other in self.fragments.values_mut() {
+            if other.fragment_id == fragment.fragment_id {
+                continue;
+            }
+            for actor in &mut other.actors {
+                for dispatcher in &mut actor.dispatcher {
+                    if dispatcher
+                        .downstream_actor_id
+                        .iter()
+                        .any(|id| old_actor_ids.contains(id))
+                    {
+                        dispatcher.downstream_actor_id = new_actor_ids.clone();
+                        if dispatcher.r#type == DispatcherType::Hash as i32 {
+                            dispatcher.hash_mapping = Some(hash_mapping.clone());
+                        }
+                    }
+                }
+                replace(&mut actor.upstream_actor_id);
+                if let Some(stream_node) = actor.nodes.as_mut() {
+                    Self::replace_merge_upstreams(stream_node, &replace);
+                }
+            }
+        }
+
+        self.actor_status
+            .retain(|actor_id, _| !old_actor_ids.contains(actor_id));
+        for actor_id in new_actor_ids {
+            self.actor_status.insert(
+                actor_id,
+                ActorStatus {
+                    parallel_unit: Some(actor_locations[&actor_id].clone()),
+                    state: ActorState::Running as i32,
+                },
+            );
+        }
+    }
+
+    fn replace_merge_upstreams(stream_node: &mut StreamNode, replace: &impl Fn(&mut Vec<ActorId>)) {
+        if let Some(NodeBody::Merge(merge)) = stream_node.node_body.as_mut() {
+            replace(&mut merge.upstream_actor_id);
+        }
+        for child in &mut stream_node.input {
+            Self::replace_merge_upstreams(child, replace);
+        }
+    }
+
     /// Returns the table id.
     pub fn table_id(&self) -> TableId {
         self.table_id
@@ -182,6 +491,21 @@ impl TableFragments {
         None
     }
 
+    /// Returns the upstream table of the chain node in the stream node, if any.
+    pub fn fetch_chain_table_id(stream_node: &StreamNode) -> Option<TableId> {
+        if let Some(NodeBody::Chain(chain)) = stream_node.node_body.as_ref() {
+            return Some(TableId::from(&chain.table_ref_id));
+        }
+
+        for child in &stream_node.input {
+            if let Some(table_id) = Self::fetch_chain_table_id(child) {
+                return Some(table_id);
+            }
+        }
+
+        None
+    }
+
     /// Returns actors that contains Chain node.
     pub fn chain_actor_ids(&self) -> Vec<ActorId> {
         self.fragments
@@ -196,6 +520,41 @@ impl TableFragments {
             .collect()
     }
 
+    fn set_chain_base_epoch_inner(
+        stream_node: &mut StreamNode,
+        chain_operator_ids: &HashSet<u64>,
+        base_epoch: HummockEpoch,
+    ) {
+        if let Some(NodeBody::Chain(_)) = stream_node.node_body
+            && chain_operator_ids.contains(&stream_node.operator_id)
+            && let Some(NodeBody::BatchPlan(batch_plan)) = stream_node.input[1].node_body.as_mut()
+        {
+            batch_plan.base_epoch = base_epoch;
+        }
+
+        for child in &mut stream_node.input {
+            Self::set_chain_base_epoch_inner(child, chain_operator_ids, base_epoch);
+        }
+    }
+
+    /// Makes the chains with the given operator ids backfill only the changes made to their
+    /// upstreams after `base_epoch`, instead of the whole snapshot.
+    pub fn set_chain_base_epoch(
+        &mut self,
+        chain_operator_ids: &HashSet<u64>,
+        base_epoch: HummockEpoch,
+    ) {
+        for fragment in self.fragments.values_mut() {
+            for actor in &mut fragment.actors {
+                Self::set_chain_base_epoch_inner(
+                    actor.nodes.as_mut().unwrap(),
+                    chain_operator_ids,
+                    base_epoch,
+                );
+            }
+        }
+    }
+
     /// Resolve dependent table
     fn resolve_dependent_table(stream_node: &StreamNode, table_ids: &mut HashSet<TableId>) {
         if let Some(NodeBody::Chain(chain)) = stream_node.node_body.as_ref() {
@@ -218,6 +577,17 @@ impl TableFragments {
         table_ids
     }
 
+    /// Returns the ids of the stream sources read by the job. The split states of the source
+    /// actors are kept in the keyspaces of the sources.
+    pub fn stream_source_ids(&self) -> HashSet<SourceId> {
+        self.fragments
+            .values()
+            .filter_map(|fragment| {
+                Self::fetch_stream_source_id(fragment.actors[0].nodes.as_ref().unwrap())
+            })
+            .collect()
+    }
+
     /// Returns states of actors group by node id.
     pub fn node_actor_states(&self) -> BTreeMap<WorkerId, Vec<(ActorId, ActorState)>> {
         let mut map = BTreeMap::default();
@@ -383,4 +753,124 @@ impl TableFragments {
     pub fn internal_table_ids(&self) -> Vec<u32> {
         self.internal_table_ids.clone()
     }
+
+    /// Resolve the catalogs of internal state tables carried by stream nodes.
+    fn resolve_internal_tables(stream_node: &StreamNode, tables: &mut BTreeMap<u32, Table>) {
+        let mut add = |table: &Table| {
+            tables.entry(table.id).or_insert_with(|| table.clone());
+        };
+        match stream_node.node_body.as_ref() {
+            Some(NodeBody::HashAgg(node)) => node.internal_tables.iter().for_each(&mut add),
+            Some(NodeBody::GlobalSimpleAgg(node)) | Some(NodeBody::LocalSimpleAgg(node)) => {
+                node.internal_tables.iter().for_each(&mut add)
+            }
+            Some(NodeBody::HashJoin(node)) => node
+                .left_table
+                .iter()
+                .chain(&node.right_table)
+                .for_each(&mut add),
+            Some(NodeBody::DynamicFilter(node)) => node.left_table.iter().for_each(&mut add),
+            Some(NodeBody::AsOfJoin(node)) => node
+                .left_table
+                .iter()
+                .chain(&node.right_table)
+                .for_each(&mut add),
+            Some(NodeBody::MatchRecognize(node)) => node.state_table.iter().for_each(&mut add),
+            Some(NodeBody::GapFill(node)) => node.state_table.iter().for_each(&mut add),
+            _ => {}
+        }
+
+        for child in &stream_node.input {
+            Self::resolve_internal_tables(child, tables);
+        }
+    }
+
+    /// Returns the catalogs of internal state tables whose schema is known, e.g. agg states and
+    /// join sides. They are ordered by table id.
+    pub fn internal_tables(&self) -> Vec<Table> {
+        let mut tables = BTreeMap::new();
+        self.fragments.values().for_each(|fragment| {
+            let actor = &fragment.actors[0];
+            Self::resolve_internal_tables(actor.nodes.as_ref().unwrap(), &mut tables);
+        });
+
+        tables.into_values().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::util::compress::{compress_data, decompress_data};
+    use risingwave_pb::common::{ParallelUnitMapping, ParallelUnitType};
+
+    use super::*;
+
+    fn parallel_unit(id: ParallelUnitId, worker_node_id: u32) -> ParallelUnit {
+        ParallelUnit {
+            id,
+            r#type: ParallelUnitType::Hash as i32,
+            worker_node_id,
+        }
+    }
+
+    #[test]
+    fn test_migrate_parallel_units() {
+        let (original_indices, data) = compress_data(&[0, 0, 1, 1]);
+        let fragment = Fragment {
+            fragment_id: 1,
+            actors: (1..=2)
+                .map(|actor_id| StreamActor {
+                    actor_id,
+                    fragment_id: 1,
+                    ..Default::default()
+                })
+                .collect(),
+            vnode_mapping: Some(ParallelUnitMapping {
+                table_id: 10,
+                original_indices,
+                data,
+            }),
+            ..Default::default()
+        };
+        let mut table_fragments = TableFragments::new(
+            TableId::new(10),
+            BTreeMap::from([(1, fragment)]),
+            HashSet::new(),
+        );
+        let actor_status = |parallel_unit| ActorStatus {
+            parallel_unit: Some(parallel_unit),
+            state: ActorState::Running as i32,
+        };
+        table_fragments.set_actor_status(BTreeMap::from([
+            (1, actor_status(parallel_unit(0, 1))),
+            (2, actor_status(parallel_unit(1, 2))),
+        ]));
+
+        // None of the actors is on the parallel units to migrate.
+        let targets = HashMap::from([(5, parallel_unit(6, 3))]);
+        assert!(!table_fragments.migrate_parallel_units(&targets));
+
+        let targets = HashMap::from([(1, parallel_unit(2, 1))]);
+        assert!(table_fragments.migrate_parallel_units(&targets));
+        assert_eq!(
+            table_fragments.actor_status[&1]
+                .get_parallel_unit()
+                .unwrap(),
+            &parallel_unit(0, 1)
+        );
+        assert_eq!(
+            table_fragments.actor_status[&2]
+                .get_parallel_unit()
+                .unwrap(),
+            &parallel_unit(2, 1)
+        );
+        let mapping = table_fragments.fragments[&1]
+            .vnode_mapping
+            .as_ref()
+            .unwrap();
+        assert_eq!(
+            decompress_data(&mapping.original_indices, &mapping.data),
+            vec![0, 0, 2, 2]
+        );
+    }
 }
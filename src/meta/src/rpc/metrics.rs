@@ -17,11 +17,12 @@ use std::sync::Arc;
 
 use hyper::{Body, Request, Response};
 use prometheus::{
-    exponential_buckets, histogram_opts, register_histogram_vec_with_registry,
-    register_histogram_with_registry, register_int_gauge_vec_with_registry,
-    register_int_gauge_with_registry, Encoder, Histogram, HistogramVec, IntGauge, IntGaugeVec,
-    Registry, TextEncoder,
+    exponential_buckets, histogram_opts, register_gauge_vec_with_registry,
+    register_histogram_with_registry, register_int_counter_vec_with_registry,
+    register_int_gauge_vec_with_registry, register_int_gauge_with_registry, Encoder, GaugeVec,
+    Histogram, IntCounterVec, IntGauge, IntGaugeVec, Registry, TextEncoder,
 };
+use risingwave_common::service::{pprof_profile_service, GrpcMetrics, PPROF_PROFILE_PATH};
 use tower::make::Shared;
 use tower::ServiceBuilder;
 use tower_http::add_extension::AddExtensionLayer;
@@ -29,8 +30,8 @@ use tower_http::add_extension::AddExtensionLayer;
 pub struct MetaMetrics {
     registry: Registry,
 
-    /// gRPC latency of meta services
-    pub grpc_latency: HistogramVec,
+    /// gRPC latency and status codes of meta services
+    pub grpc_metrics: Arc<GrpcMetrics>,
     /// latency of each barrier
     pub barrier_latency: Histogram,
 
@@ -46,18 +47,31 @@ pub struct MetaMetrics {
     pub level_file_size: IntGaugeVec,
     /// hummock version size
     pub version_size: IntGauge,
+
+    /// rows received by the actors of each fragment from upstream actors
+    pub fragment_input_rows: IntCounterVec,
+    /// rows sent by the actors of each fragment to downstream actors
+    pub fragment_output_rows: IntCounterVec,
+    /// time spent by the actors of each fragment sending to downstream actors
+    pub fragment_output_blocking_duration_ns: IntCounterVec,
+    /// the actor of each fragment that took the longest to process the last barrier
+    pub fragment_slowest_actor_id: IntGaugeVec,
+    /// the barrier processing latency of the slowest actor of each fragment
+    pub fragment_slowest_actor_latency: GaugeVec,
+    /// rows received by all actors of each materialized view
+    pub mview_input_rows: IntCounterVec,
+    /// rows sent by all actors of each materialized view
+    pub mview_output_rows: IntCounterVec,
+    /// time spent by all actors of each materialized view sending to downstream actors
+    pub mview_output_blocking_duration_ns: IntCounterVec,
+    /// the parallelism of each fragment proposed by the parallelism controller
+    pub fragment_proposed_parallelism: IntGaugeVec,
 }
 
 impl MetaMetrics {
     pub fn new() -> Self {
         let registry = prometheus::Registry::new();
-        let opts = histogram_opts!(
-            "meta_grpc_duration_seconds",
-            "gRPC latency of meta services",
-            exponential_buckets(0.0001, 2.0, 20).unwrap() // max 52s
-        );
-        let grpc_latency =
-            register_histogram_vec_with_registry!(opts, &["path"], registry).unwrap();
+        let grpc_metrics = Arc::new(GrpcMetrics::new(&registry, "meta"));
 
         let opts = histogram_opts!(
             "meta_barrier_duration_seconds",
@@ -106,10 +120,82 @@ impl MetaMetrics {
         )
         .unwrap();
 
+        let fragment_input_rows = register_int_counter_vec_with_registry!(
+            "stream_fragment_input_rows",
+            "rows received by the actors of each fragment from upstream actors",
+            &["table_id", "fragment_id"],
+            registry
+        )
+        .unwrap();
+
+        let fragment_output_rows = register_int_counter_vec_with_registry!(
+            "stream_fragment_output_rows",
+            "rows sent by the actors of each fragment to downstream actors",
+            &["table_id", "fragment_id"],
+            registry
+        )
+        .unwrap();
+
+        let fragment_output_blocking_duration_ns = register_int_counter_vec_with_registry!(
+            "stream_fragment_output_blocking_duration_ns",
+            "time (ns) spent by the actors of each fragment sending to downstream actors",
+            &["table_id", "fragment_id"],
+            registry
+        )
+        .unwrap();
+
+        let fragment_slowest_actor_id = register_int_gauge_vec_with_registry!(
+            "stream_fragment_slowest_actor_id",
+            "the actor of each fragment that took the longest to process the last barrier",
+            &["table_id", "fragment_id"],
+            registry
+        )
+        .unwrap();
+
+        let fragment_slowest_actor_latency = register_gauge_vec_with_registry!(
+            "stream_fragment_slowest_actor_latency",
+            "the barrier processing latency (s) of the slowest actor of each fragment",
+            &["table_id", "fragment_id"],
+            registry
+        )
+        .unwrap();
+
+        let mview_input_rows = register_int_counter_vec_with_registry!(
+            "stream_mview_input_rows",
+            "rows received by all actors of each materialized view",
+            &["table_id"],
+            registry
+        )
+        .unwrap();
+
+        let mview_output_rows = register_int_counter_vec_with_registry!(
+            "stream_mview_output_rows",
+            "rows sent by all actors of each materialized view",
+            &["table_id"],
+            registry
+        )
+        .unwrap();
+
+        let mview_output_blocking_duration_ns = register_int_counter_vec_with_registry!(
+            "stream_mview_output_blocking_duration_ns",
+            "time (ns) spent by all actors of each materialized view sending to downstream actors",
+            &["table_id"],
+            registry
+        )
+        .unwrap();
+
+        let fragment_proposed_parallelism = register_int_gauge_vec_with_registry!(
+            "stream_fragment_proposed_parallelism",
+            "the parallelism of each fragment proposed by the parallelism controller",
+            &["table_id", "fragment_id"],
+            registry
+        )
+        .unwrap();
+
         Self {
             registry,
 
-            grpc_latency,
+            grpc_metrics,
             barrier_latency,
 
             max_committed_epoch,
@@ -118,6 +204,16 @@ impl MetaMetrics {
             level_compact_cnt,
             level_file_size,
             version_size,
+
+            fragment_input_rows,
+            fragment_output_rows,
+            fragment_output_blocking_duration_ns,
+            fragment_slowest_actor_id,
+            fragment_slowest_actor_latency,
+            mview_input_rows,
+            mview_output_rows,
+            mview_output_blocking_duration_ns,
+            fragment_proposed_parallelism,
         }
     }
 
@@ -142,6 +238,9 @@ impl MetaMetrics {
     }
 
     async fn metrics_service(req: Request<Body>) -> Result<Response<Body>, hyper::Error> {
+        if req.uri().path() == PPROF_PROFILE_PATH {
+            return Ok(pprof_profile_service(&req).await);
+        }
         let meta_metrics = req.extensions().get::<Arc<MetaMetrics>>().unwrap();
         let encoder = TextEncoder::new();
         let mut buffer = vec![];
@@ -21,18 +21,20 @@ use itertools::Itertools;
 use prost::Message;
 use risingwave_common::error::ErrorCode::InternalError;
 use risingwave_common::error::{Result, RwError};
+use risingwave_common::service::GrpcMiddlewareLayer;
+use risingwave_common::util::shutdown::wait_for_shutdown_signal;
 use risingwave_pb::ddl_service::ddl_service_server::DdlServiceServer;
 use risingwave_pb::hummock::hummock_manager_service_server::HummockManagerServiceServer;
 use risingwave_pb::meta::cluster_service_server::ClusterServiceServer;
 use risingwave_pb::meta::heartbeat_service_server::HeartbeatServiceServer;
 use risingwave_pb::meta::notification_service_server::NotificationServiceServer;
 use risingwave_pb::meta::stream_manager_service_server::StreamManagerServiceServer;
+use risingwave_pb::meta::system_params_service_server::SystemParamsServiceServer;
 use risingwave_pb::meta::{MetaLeaderInfo, MetaLeaseInfo};
 use risingwave_pb::user::user_service_server::UserServiceServer;
 use tokio::sync::oneshot::Sender;
 use tokio::task::JoinHandle;
 
-use super::intercept::MetricsMiddlewareLayer;
 use super::service::notification_service::NotificationServiceImpl;
 use super::DdlServiceImpl;
 use crate::barrier::GlobalBarrierManager;
@@ -41,12 +43,16 @@ use crate::dashboard::DashboardService;
 use crate::hummock;
 use crate::hummock::compaction_group::manager::CompactionGroupManager;
 use crate::hummock::CompactionScheduler;
-use crate::manager::{CatalogManager, MetaOpts, MetaSrvEnv, UserManager};
+use crate::manager::{
+    CatalogManager, MetaOpts, MetaSrvEnv, SystemParamManager, TelemetryManager, UserManager,
+    TELEMETRY_REPORT_INTERVAL,
+};
 use crate::rpc::metrics::MetaMetrics;
 use crate::rpc::service::cluster_service::ClusterServiceImpl;
 use crate::rpc::service::heartbeat_service::HeartbeatServiceImpl;
 use crate::rpc::service::hummock_service::HummockServiceImpl;
 use crate::rpc::service::stream_service::StreamServiceImpl;
+use crate::rpc::service::system_params_service::SystemParamsServiceImpl;
 use crate::rpc::service::user_service::UserServiceImpl;
 use crate::rpc::{META_CF_NAME, META_LEADER_KEY, META_LEASE_KEY};
 use crate::storage::{Error, EtcdMetaStore, MemStore, MetaStore, Transaction};
@@ -320,6 +326,17 @@ pub async fn rpc_serve_with_store<S: MetaStore>(
 
     let catalog_manager = Arc::new(CatalogManager::new(env.clone()).await.unwrap());
     let user_manager = Arc::new(UserManager::new(env.clone()).await.unwrap());
+    let system_param_manager = Arc::new(SystemParamManager::new(env.clone()).await.unwrap());
+    let telemetry_manager = Arc::new(
+        TelemetryManager::new(
+            env.clone(),
+            cluster_manager.clone(),
+            catalog_manager.clone(),
+            env.opts.telemetry_endpoint.clone(),
+        )
+        .await
+        .unwrap(),
+    );
 
     let barrier_manager = Arc::new(GlobalBarrierManager::new(
         env.clone(),
@@ -350,6 +367,11 @@ pub async fn rpc_serve_with_store<S: MetaStore>(
         });
     }
 
+    // Savepoints are persisted with the streaming jobs, pin them in hummock again.
+    hummock_manager
+        .repin_savepoints(fragment_manager.list_savepoints().await)
+        .await;
+
     let stream_manager = Arc::new(
         GlobalStreamManager::new(
             env.clone(),
@@ -383,6 +405,7 @@ pub async fn rpc_serve_with_store<S: MetaStore>(
     let compaction_scheduler = Arc::new(CompactionScheduler::new(
         hummock_manager.clone(),
         compactor_manager.clone(),
+        system_param_manager.watch_params(),
     ));
     let vacuum_trigger = Arc::new(hummock::VacuumTrigger::new(
         hummock_manager.clone(),
@@ -400,7 +423,7 @@ pub async fn rpc_serve_with_store<S: MetaStore>(
     );
     let user_srv = UserServiceImpl::<S>::new(catalog_manager.clone(), user_manager.clone());
     let cluster_srv = ClusterServiceImpl::<S>::new(cluster_manager.clone());
-    let stream_srv = StreamServiceImpl::<S>::new(stream_manager);
+    let stream_srv = StreamServiceImpl::<S>::new(stream_manager.clone(), hummock_manager.clone());
     let hummock_srv = HummockServiceImpl::new(
         hummock_manager.clone(),
         compactor_manager.clone(),
@@ -409,34 +432,94 @@ pub async fn rpc_serve_with_store<S: MetaStore>(
         fragment_manager.clone(),
     );
     let notification_manager = env.notification_manager_ref();
-    let notification_srv =
-        NotificationServiceImpl::new(env, catalog_manager, cluster_manager.clone(), user_manager);
+    let system_params_srv =
+        SystemParamsServiceImpl::new(system_param_manager.clone(), telemetry_manager.clone());
+    let notification_srv = NotificationServiceImpl::new(
+        env.clone(),
+        catalog_manager,
+        cluster_manager.clone(),
+        user_manager,
+        system_param_manager.clone(),
+    );
 
     if let Some(prometheus_addr) = address_info.prometheus_addr {
         meta_metrics.boot_metrics_service(prometheus_addr);
     }
 
+    let replication_hummock_manager = hummock_manager.clone();
     let mut sub_tasks = hummock::start_hummock_workers(
         hummock_manager,
         compactor_manager,
         vacuum_trigger,
-        notification_manager,
+        notification_manager.clone(),
         compaction_scheduler,
     )
     .await;
     sub_tasks.push((lease_handle, lease_shutdown));
+    if let (Some(state_store), Some(replica)) = (
+        env.opts.state_store.as_ref(),
+        env.opts.replica_object_store.as_ref(),
+    ) {
+        sub_tasks.push(
+            hummock::replication::ObjectStoreReplicator::start(
+                &replication_hummock_manager,
+                state_store,
+                replica,
+                env.opts.data_directory.clone(),
+            )
+            .await,
+        );
+    }
     #[cfg(not(test))]
     {
+        if env.opts.auto_parallelism.mode
+            != risingwave_common::config::AutoParallelismMode::Disabled
+        {
+            sub_tasks.push(
+                crate::stream::start_auto_parallelism(
+                    stream_manager,
+                    fragment_manager.clone(),
+                    cluster_manager.clone(),
+                    barrier_manager.blocking_time_recorder(),
+                    meta_metrics.clone(),
+                    env.opts.auto_parallelism.clone(),
+                )
+                .await,
+            );
+        }
+        sub_tasks.push(
+            crate::stream::start_actor_migration(
+                fragment_manager,
+                cluster_manager.clone(),
+                notification_manager,
+            )
+            .await,
+        );
         sub_tasks.push(
             ClusterManager::start_heartbeat_checker(cluster_manager, Duration::from_secs(1)).await,
         );
+        sub_tasks.push(
+            GlobalBarrierManager::start_system_params_watcher(
+                barrier_manager.clone(),
+                system_param_manager.watch_params(),
+            )
+            .await,
+        );
         sub_tasks.push(GlobalBarrierManager::start(barrier_manager).await);
+        sub_tasks.push(
+            TelemetryManager::start_reporter(
+                telemetry_manager,
+                system_param_manager.watch_params(),
+                TELEMETRY_REPORT_INTERVAL,
+            )
+            .await,
+        );
     }
 
     let (shutdown_send, mut shutdown_recv) = tokio::sync::oneshot::channel();
     let join_handle = tokio::spawn(async move {
         tonic::transport::Server::builder()
-            .layer(MetricsMiddlewareLayer::new(meta_metrics.clone()))
+            .layer(GrpcMiddlewareLayer::new(meta_metrics.grpc_metrics.clone()))
             .add_service(HeartbeatServiceServer::new(heartbeat_srv))
             .add_service(ClusterServiceServer::new(cluster_srv))
             .add_service(StreamManagerServiceServer::new(stream_srv))
@@ -444,9 +527,10 @@ pub async fn rpc_serve_with_store<S: MetaStore>(
             .add_service(NotificationServiceServer::new(notification_srv))
             .add_service(DdlServiceServer::new(ddl_srv))
             .add_service(UserServiceServer::new(user_srv))
+            .add_service(SystemParamsServiceServer::new(system_params_srv))
             .serve_with_shutdown(address_info.listen_addr, async move {
                 tokio::select! {
-                    _ = tokio::signal::ctrl_c() => {},
+                    _ = wait_for_shutdown_signal() => {},
                     _ = &mut shutdown_recv => {
                         for (join_handle, shutdown_sender) in sub_tasks {
                             if let Err(err) = shutdown_sender.send(()) {
@@ -14,11 +14,12 @@
 
 use risingwave_common::error::tonic_err;
 use risingwave_common::try_match_expand;
+use risingwave_common::types::virtual_node_count;
 use risingwave_pb::meta::cluster_service_server::ClusterService;
 use risingwave_pb::meta::{
     ActivateWorkerNodeRequest, ActivateWorkerNodeResponse, AddWorkerNodeRequest,
-    AddWorkerNodeResponse, DeleteWorkerNodeRequest, DeleteWorkerNodeResponse, ListAllNodesRequest,
-    ListAllNodesResponse,
+    AddWorkerNodeResponse, CordonWorkerNodeRequest, CordonWorkerNodeResponse,
+    DeleteWorkerNodeRequest, DeleteWorkerNodeResponse, ListAllNodesRequest, ListAllNodesResponse,
 };
 use tonic::{Request, Response, Status};
 
@@ -53,11 +54,12 @@ where
         let host = try_match_expand!(req.host, Some, "AddWorkerNodeRequest::host is empty")?;
         let (worker_node, _added) = self
             .cluster_manager
-            .add_worker_node(host, worker_type)
+            .add_worker_node_in_resource_group(host, worker_type, &req.resource_group)
             .await?;
         Ok(Response::new(AddWorkerNodeResponse {
             status: None,
             node: Some(worker_node),
+            vnode_count: virtual_node_count() as u32,
         }))
     }
 
@@ -81,6 +83,16 @@ where
         Ok(Response::new(DeleteWorkerNodeResponse { status: None }))
     }
 
+    async fn cordon_worker_node(
+        &self,
+        request: Request<CordonWorkerNodeRequest>,
+    ) -> Result<Response<CordonWorkerNodeResponse>, Status> {
+        let req = request.into_inner();
+        let host = try_match_expand!(req.host, Some, "CordonWorkerNodeRequest::host is empty")?;
+        self.cluster_manager.cordon_worker_node(host).await?;
+        Ok(Response::new(CordonWorkerNodeResponse { status: None }))
+    }
+
     async fn list_all_nodes(
         &self,
         request: Request<ListAllNodesRequest>,
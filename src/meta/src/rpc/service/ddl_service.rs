@@ -12,19 +12,27 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
-use risingwave_common::catalog::CatalogVersion;
+use risingwave_common::catalog::{
+    CatalogVersion, CHECKPOINT_FREQUENCY_KEY, DEFAULT_RESOURCE_GROUP,
+    PARTITION_INTERVAL_SECONDS_KEY, PARTITION_RETENTION_SECONDS_KEY, RESOURCE_GROUP_KEY,
+    RETENTION_SECONDS_KEY,
+};
 use risingwave_common::error::{tonic_err, ErrorCode, Result as RwResult};
 use risingwave_common::util::compress::compress_data;
+use risingwave_connector::{SplitImpl, SplitMetaData};
 use risingwave_pb::catalog::table::OptionalAssociatedSourceId;
 use risingwave_pb::catalog::*;
 use risingwave_pb::common::{ParallelUnitMapping, ParallelUnitType};
 use risingwave_pb::ddl_service::ddl_service_server::DdlService;
 use risingwave_pb::ddl_service::*;
+use risingwave_pb::hummock::PartitionRetention;
 use risingwave_pb::plan_common::TableRefId;
+use risingwave_pb::source::{ConnectorSplit, ConnectorSplits};
 use risingwave_pb::stream_plan::stream_node::NodeBody;
 use risingwave_pb::stream_plan::{StreamFragmentGraph, StreamNode};
+use risingwave_pb::user::UserInfo;
 use tonic::{Request, Response, Status};
 
 use crate::cluster::ClusterManagerRef;
@@ -32,7 +40,9 @@ use crate::manager::{CatalogManagerRef, IdCategory, MetaSrvEnv, SourceId, TableI
 use crate::model::{FragmentId, TableFragments};
 use crate::storage::MetaStore;
 use crate::stream::{
-    ActorGraphBuilder, FragmentManagerRef, GlobalStreamManagerRef, SourceManagerRef,
+    adopt_all_states, adopt_states, ActorGraphBuilder, AdoptedStates,
+    CreateMaterializedViewContext, FragmentManagerRef, GlobalStreamManagerRef, RollbackJob,
+    SourceManagerRef,
 };
 
 #[derive(Clone)]
@@ -180,7 +190,9 @@ where
             .map_err(tonic_err)?;
 
         // QUESTION(patrick): why do we need to contact compute node on create source
-        if let Err(e) = self.source_manager.create_source(&source).await {
+        // External tables are read by batch scans directly, so compute nodes don't know them.
+        let is_external = matches!(source.info, Some(source::Info::ExternalTable(_)));
+        if !is_external && let Err(e) = self.source_manager.create_source(&source).await {
             self.catalog_manager
                 .cancel_create_source_procedure(&source)
                 .await
@@ -205,6 +217,16 @@ where
         request: Request<DropSourceRequest>,
     ) -> Result<Response<DropSourceResponse>, Status> {
         let source_id = request.into_inner().source_id;
+        let is_external = matches!(
+            self.catalog_manager
+                .get_source(source_id)
+                .await
+                .map_err(tonic_err)?,
+            Some(Source {
+                info: Some(source::Info::ExternalTable(_)),
+                ..
+            })
+        );
 
         // 1. Drop source in catalog. Ref count will be checked.
         let version = self
@@ -214,10 +236,12 @@ where
             .map_err(tonic_err)?;
 
         // 2. Drop source on compute nodes.
-        self.source_manager
-            .drop_source(source_id)
-            .await
-            .map_err(tonic_err)?;
+        if !is_external {
+            self.source_manager
+                .drop_source(source_id)
+                .await
+                .map_err(tonic_err)?;
+        }
 
         Ok(Response::new(DropSourceResponse {
             status: None,
@@ -230,85 +254,37 @@ where
         request: Request<CreateMaterializedViewRequest>,
     ) -> Result<Response<CreateMaterializedViewResponse>, Status> {
         let req = request.into_inner();
-        let mut mview = req.get_materialized_view().map_err(tonic_err)?.clone();
+        let mview = req.get_materialized_view().map_err(tonic_err)?.clone();
         let fragment_graph = req.get_fragment_graph().map_err(tonic_err)?.clone();
 
-        // 0. Generate an id from mview.
-        let id = self
-            .env
-            .id_gen_manager()
-            .generate::<{ IdCategory::Table }>()
-            .await
-            .map_err(tonic_err)? as u32;
-        mview.id = id;
-
-        // 1. Resolve the dependent relations.
-        {
-            // TODO: distinguish SourceId and TableId
-            fn resolve_dependent_relations(
-                stream_node: &StreamNode,
-                dependent_relations: &mut HashSet<TableId>,
-            ) -> RwResult<()> {
-                match stream_node.node_body.as_ref().unwrap() {
-                    NodeBody::Source(source_node) => {
-                        dependent_relations.insert(source_node.get_table_ref_id()?.table_id as u32);
-                    }
-                    NodeBody::Chain(chain_node) => {
-                        dependent_relations.insert(chain_node.get_table_ref_id()?.table_id as u32);
-                    }
-                    _ => {}
-                }
-                for child in &stream_node.input {
-                    resolve_dependent_relations(child, dependent_relations)?;
-                }
-                Ok(())
-            }
-
-            let mut dependent_relations = Default::default();
-            for fragment in fragment_graph.fragments.values() {
-                resolve_dependent_relations(
-                    fragment.node.as_ref().unwrap(),
-                    &mut dependent_relations,
-                )
-                .map_err(tonic_err)?;
-            }
-            assert!(
-                !dependent_relations.is_empty(),
-                "there should be at lease 1 dependent relation when creating materialized view"
-            );
-            mview.dependent_relations = dependent_relations.into_iter().collect();
-        }
-
-        // 2. Mark current mview as "creating" and add reference count to dependent relations.
-        self.catalog_manager
-            .start_create_table_procedure(&mview)
+        let (table_id, version) = self
+            .create_materialized_view_inner(mview, fragment_graph, HashMap::new(), None)
             .await
             .map_err(tonic_err)?;
 
-        // 3. Create mview in stream manager. The id in stream node will be filled.
-        if let Err(e) = self
-            .create_mview_on_compute_node(fragment_graph, id, None)
-            .await
-        {
-            self.catalog_manager
-                .cancel_create_table_procedure(&mview)
-                .await
-                .map_err(tonic_err)?;
-            return Err(e.into());
-        } else {
-            self.set_mview_mapping(&mut mview).map_err(tonic_err)?;
-        }
+        Ok(Response::new(CreateMaterializedViewResponse {
+            status: None,
+            table_id,
+            version,
+        }))
+    }
 
-        // 4. Finally, update the catalog.
-        let version = self
-            .catalog_manager
-            .finish_create_table_procedure(&mview)
+    async fn alter_materialized_view(
+        &self,
+        request: Request<AlterMaterializedViewRequest>,
+    ) -> Result<Response<AlterMaterializedViewResponse>, Status> {
+        let req = request.into_inner();
+        let mview = req.get_materialized_view().map_err(tonic_err)?.clone();
+        let fragment_graph = req.get_fragment_graph().map_err(tonic_err)?.clone();
+
+        let (table_id, version) = self
+            .alter_materialized_view_inner(req.table_id, mview, fragment_graph)
             .await
             .map_err(tonic_err)?;
 
-        Ok(Response::new(CreateMaterializedViewResponse {
+        Ok(Response::new(AlterMaterializedViewResponse {
             status: None,
-            table_id: id,
+            table_id,
             version,
         }))
     }
@@ -339,6 +315,32 @@ where
         }))
     }
 
+    async fn replace_materialized_view(
+        &self,
+        request: Request<ReplaceMaterializedViewRequest>,
+    ) -> Result<Response<ReplaceMaterializedViewResponse>, Status> {
+        use risingwave_common::catalog::TableId;
+
+        let req = request.into_inner();
+        // 1. Cut over to the new mview and drop the old one in catalog. Ref count will be checked.
+        let version = self
+            .catalog_manager
+            .replace_table(req.table_id, req.new_table_id, &HashSet::new())
+            .await
+            .map_err(tonic_err)?;
+
+        // 2. drop the old mview in stream manager
+        self.stream_manager
+            .drop_materialized_view(&TableId::new(req.table_id))
+            .await
+            .map_err(tonic_err)?;
+
+        Ok(Response::new(ReplaceMaterializedViewResponse {
+            status: None,
+            version,
+        }))
+    }
+
     async fn create_materialized_source(
         &self,
         request: Request<CreateMaterializedSourceRequest>,
@@ -390,21 +392,199 @@ where
             .map_err(tonic_err)?;
         Ok(Response::new(ListMaterializedViewResponse { tables }))
     }
+
+    async fn comment_on(
+        &self,
+        request: Request<CommentOnRequest>,
+    ) -> Result<Response<CommentOnResponse>, Status> {
+        let req = request.into_inner();
+        let column_id = req.optional_column_id.map(|id| match id {
+            comment_on_request::OptionalColumnId::ColumnId(id) => id,
+        });
+        let version = self
+            .catalog_manager
+            .comment_on(req.table_id, column_id, req.description)
+            .await
+            .map_err(tonic_err)?;
+        Ok(Response::new(CommentOnResponse {
+            status: None,
+            version,
+        }))
+    }
+
+    async fn export_streaming_job(
+        &self,
+        request: Request<ExportStreamingJobRequest>,
+    ) -> Result<Response<ExportStreamingJobResponse>, Status> {
+        let table_id = request.into_inner().table_id;
+        let snapshot = self
+            .export_streaming_job_inner(table_id)
+            .await
+            .map_err(tonic_err)?;
+        Ok(Response::new(ExportStreamingJobResponse {
+            status: None,
+            snapshot: Some(snapshot),
+        }))
+    }
+
+    async fn import_streaming_job(
+        &self,
+        request: Request<ImportStreamingJobRequest>,
+    ) -> Result<Response<ImportStreamingJobResponse>, Status> {
+        let snapshot = request.into_inner().snapshot.unwrap();
+        let (table_id, version) = self
+            .import_streaming_job_inner(snapshot)
+            .await
+            .map_err(tonic_err)?;
+        Ok(Response::new(ImportStreamingJobResponse {
+            status: None,
+            table_id,
+            version,
+        }))
+    }
+
+    async fn get_catalog(
+        &self,
+        _request: Request<GetCatalogRequest>,
+    ) -> Result<Response<GetCatalogResponse>, Status> {
+        use crate::model::MetadataModel;
+        let (databases, schemas, tables, sources) = self
+            .catalog_manager
+            .get_catalog()
+            .await
+            .map_err(tonic_err)?;
+        let users = UserInfo::list(self.env.meta_store())
+            .await
+            .map_err(tonic_err)?;
+        Ok(Response::new(GetCatalogResponse {
+            snapshot: Some(CatalogSnapshot {
+                databases,
+                schemas,
+                sources,
+                tables,
+                users,
+            }),
+        }))
+    }
 }
 
 impl<S> DdlServiceImpl<S>
 where
     S: MetaStore,
 {
+    /// Creates the streaming job of the materialized view `id`. If `replaced` is specified, the job
+    /// replaces the one of the given materialized view, adopting its states.
+    #[allow(clippy::too_many_arguments)]
     async fn create_mview_on_compute_node(
         &self,
         mut fragment_graph: StreamFragmentGraph,
         id: TableId,
         affiliated_source: Option<Source>,
+        checkpoint_frequency: Option<u32>,
+        retention_seconds: Option<u32>,
+        partition_retention: Option<PartitionRetention>,
+        resource_group: String,
+        initial_splits: HashMap<SourceId, BTreeMap<String, SplitImpl>>,
+        replaced: Option<(TableId, AdoptedStates)>,
     ) -> RwResult<()> {
         use risingwave_common::catalog::TableId;
 
-        use crate::stream::CreateMaterializedViewContext;
+        let mview_id = TableId::new(id);
+        let ctx = CreateMaterializedViewContext {
+            affiliated_source,
+            initial_splits,
+            adopted_table_ids: replaced
+                .as_ref()
+                .map(|(_, adopted)| adopted.table_ids.clone())
+                .unwrap_or_default(),
+            ..Default::default()
+        };
+        let (mut table_fragments, ctx) = self
+            .build_table_fragments(&mut fragment_graph, mview_id, &resource_group, ctx)
+            .await?;
+        table_fragments.set_checkpoint_frequency(checkpoint_frequency);
+        table_fragments.set_retention_seconds(retention_seconds);
+        table_fragments.set_partition_retention(partition_retention);
+        table_fragments.set_resource_group(resource_group);
+        table_fragments.set_fragment_graph(fragment_graph);
+
+        // Create on compute node.
+        match replaced {
+            Some((table_id, adopted)) => {
+                let old_table_id = TableId::new(table_id);
+                let rollback = self.build_rollback_job(&old_table_id).await?;
+                self.stream_manager
+                    .replace_materialized_view(
+                        &old_table_id,
+                        table_fragments,
+                        &adopted.chain_operator_ids,
+                        ctx,
+                        rollback,
+                    )
+                    .await?
+            }
+            None => {
+                self.stream_manager
+                    .create_materialized_view(table_fragments, ctx)
+                    .await?
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Builds the job rebuilding the materialized view `table_id` with all of its states, in case
+    /// the new job replacing it fails to be created. Returns `None` if the states can't be
+    /// located, in which case the view is left without a job on failure.
+    async fn build_rollback_job(
+        &self,
+        table_id: &risingwave_common::catalog::TableId,
+    ) -> RwResult<Option<RollbackJob>> {
+        let old_table_fragments = self
+            .fragment_manager
+            .select_table_fragments_by_table_id(table_id)
+            .await?;
+        let Some((mut fragment_graph, adopted)) = adopt_all_states(&old_table_fragments) else {
+            tracing::warn!(
+                "failed to locate the states of materialized view {}, it can't be rolled back",
+                table_id
+            );
+            return Ok(None);
+        };
+        let ctx = CreateMaterializedViewContext {
+            adopted_table_ids: adopted.table_ids,
+            ..Default::default()
+        };
+        let (mut table_fragments, ctx) = self
+            .build_table_fragments(
+                &mut fragment_graph,
+                *table_id,
+                old_table_fragments.resource_group(),
+                ctx,
+            )
+            .await?;
+        table_fragments.set_checkpoint_frequency(old_table_fragments.checkpoint_frequency());
+        table_fragments.set_retention_seconds(old_table_fragments.retention_seconds());
+        table_fragments.set_partition_retention(old_table_fragments.partition_retention().cloned());
+        table_fragments.set_resource_group(old_table_fragments.resource_group().to_string());
+        table_fragments.set_fragment_graph(fragment_graph);
+        Ok(Some(RollbackJob {
+            table_fragments,
+            chain_operator_ids: adopted.chain_operator_ids,
+            ctx,
+        }))
+    }
+
+    /// Builds the actors of the materialized view `mview_id` from its fragment graph, scheduled in
+    /// `resource_group`.
+    async fn build_table_fragments(
+        &self,
+        fragment_graph: &mut StreamFragmentGraph,
+        mview_id: risingwave_common::catalog::TableId,
+        resource_group: &str,
+        mut ctx: CreateMaterializedViewContext,
+    ) -> RwResult<(TableFragments, CreateMaterializedViewContext)> {
+        use risingwave_common::catalog::TableId;
 
         // Fill in the correct mview id for stream node.
         fn fill_mview_id(stream_node: &mut StreamNode, mview_id: TableId) -> usize {
@@ -420,7 +600,6 @@ where
             mview_count
         }
 
-        let mview_id = TableId::new(id);
         let mut mview_count = 0;
         for fragment in fragment_graph.fragments.values_mut() {
             mview_count += fill_mview_id(fragment.node.as_mut().unwrap(), mview_id);
@@ -434,24 +613,34 @@ where
         // Resolve fragments.
         let parallel_degree = self
             .cluster_manager
-            .get_parallel_unit_count(Some(ParallelUnitType::Hash))
-            .await;
-        let mut ctx = CreateMaterializedViewContext {
-            affiliated_source,
-            ..Default::default()
-        };
-
+            .list_resource_group_parallel_units(ParallelUnitType::Hash, resource_group)
+            .await
+            .len();
+        if parallel_degree == 0 {
+            return Err(ErrorCode::InvalidParameterValue(format!(
+                "no compute node in resource group {}",
+                resource_group
+            ))
+            .into());
+        }
         let mut actor_graph_builder =
-            ActorGraphBuilder::new(self.env.id_gen_manager_ref(), &fragment_graph, &mut ctx)
-                .await?;
-
-        // TODO(Kexiang): now simply use Count(ParallelUnit) - 1 as parallelism of each fragment
+            ActorGraphBuilder::new(self.env.id_gen_manager_ref(), fragment_graph, &mut ctx).await?;
+
+        // Fragments with chain nodes have an actor for each sink actor of their upstream, and the
+        // others have an actor for each hash parallel unit in the resource group.
+        let chain_tables = actor_graph_builder.list_fragment_chain_tables();
+        let upstream_parallel_units = self
+            .fragment_manager
+            .get_sink_parallel_unit_ids(&chain_tables.values().copied().collect())
+            .await?;
         let parallelisms: HashMap<FragmentId, u32> = actor_graph_builder
             .list_fragment_ids()
             .into_iter()
             .map(|(fragment_id, is_singleton)| {
                 if is_singleton {
                     (fragment_id, 1)
+                } else if let Some(table_id) = chain_tables.get(&fragment_id) {
+                    (fragment_id, upstream_parallel_units[table_id].len() as u32)
                 } else {
                     (fragment_id, parallel_degree as u32)
                 }
@@ -473,13 +662,111 @@ where
 
         let table_fragments =
             TableFragments::new(mview_id, graph, ctx.internal_table_id_set.clone());
+        Ok((table_fragments, ctx))
+    }
 
-        // Create on compute node.
-        self.stream_manager
-            .create_materialized_view(table_fragments, ctx)
+    async fn create_materialized_view_inner(
+        &self,
+        mut mview: Table,
+        fragment_graph: StreamFragmentGraph,
+        initial_splits: HashMap<SourceId, BTreeMap<String, SplitImpl>>,
+        replaced: Option<(TableId, AdoptedStates)>,
+    ) -> RwResult<(TableId, CatalogVersion)> {
+        // 0. Generate an id from mview.
+        let id = self
+            .env
+            .id_gen_manager()
+            .generate::<{ IdCategory::Table }>()
+            .await? as u32;
+        mview.id = id;
+
+        // 1. Resolve the dependent relations.
+        {
+            // TODO: distinguish SourceId and TableId
+            fn resolve_dependent_relations(
+                stream_node: &StreamNode,
+                dependent_relations: &mut HashSet<TableId>,
+            ) -> RwResult<()> {
+                match stream_node.node_body.as_ref().unwrap() {
+                    NodeBody::Source(source_node) => {
+                        dependent_relations.insert(source_node.get_table_ref_id()?.table_id as u32);
+                    }
+                    NodeBody::Chain(chain_node) => {
+                        dependent_relations.insert(chain_node.get_table_ref_id()?.table_id as u32);
+                    }
+                    _ => {}
+                }
+                for child in &stream_node.input {
+                    resolve_dependent_relations(child, dependent_relations)?;
+                }
+                Ok(())
+            }
+
+            let mut dependent_relations = Default::default();
+            for fragment in fragment_graph.fragments.values() {
+                resolve_dependent_relations(
+                    fragment.node.as_ref().unwrap(),
+                    &mut dependent_relations,
+                )?;
+            }
+            assert!(
+                !dependent_relations.is_empty(),
+                "there should be at lease 1 dependent relation when creating materialized view"
+            );
+            mview.dependent_relations = dependent_relations.into_iter().collect();
+        }
+
+        // 2. Mark current mview as "creating" and add reference count to dependent relations.
+        self.catalog_manager
+            .start_create_table_procedure(&mview)
             .await?;
 
-        Ok(())
+        // 3. Create mview in stream manager. The id in stream node will be filled.
+        let adopted_table_ids = replaced
+            .as_ref()
+            .map(|(_, adopted)| adopted.table_ids.values().copied().collect::<HashSet<_>>())
+            .unwrap_or_default();
+        let checkpoint_frequency = mview
+            .properties
+            .get(CHECKPOINT_FREQUENCY_KEY)
+            .and_then(|frequency| frequency.parse().ok());
+        let resource_group = mview
+            .properties
+            .get(RESOURCE_GROUP_KEY)
+            .cloned()
+            .unwrap_or_else(|| DEFAULT_RESOURCE_GROUP.to_string());
+        if let Err(e) = self
+            .create_mview_on_compute_node(
+                fragment_graph,
+                id,
+                None,
+                checkpoint_frequency,
+                None,
+                None,
+                resource_group,
+                initial_splits,
+                replaced,
+            )
+            .await
+        {
+            self.catalog_manager
+                .cancel_create_table_procedure(&mview)
+                .await?;
+            return Err(e);
+        } else {
+            self.set_mview_mapping(&mut mview)?;
+        }
+        // The catalogs of the adopted tables are moved over from the replaced mview.
+        let mut internal_tables = self.resolve_internal_tables(&mview).await?;
+        internal_tables.retain(|table| !adopted_table_ids.contains(&table.id));
+
+        // 4. Finally, update the catalog.
+        let version = self
+            .catalog_manager
+            .finish_create_table_procedure(internal_tables, &mview)
+            .await?;
+
+        Ok((id, version))
     }
 
     async fn create_materialized_source_inner(
@@ -496,6 +783,28 @@ where
             .await? as u32;
         source.id = source_id;
 
+        // Resolve the tables referenced by the foreign keys, which are read by chain nodes.
+        {
+            fn resolve_chain_upstreams(
+                stream_node: &StreamNode,
+                upstreams: &mut HashSet<TableId>,
+            ) -> RwResult<()> {
+                if let NodeBody::Chain(chain_node) = stream_node.node_body.as_ref().unwrap() {
+                    upstreams.insert(chain_node.get_table_ref_id()?.table_id as u32);
+                }
+                for child in &stream_node.input {
+                    resolve_chain_upstreams(child, upstreams)?;
+                }
+                Ok(())
+            }
+
+            let mut dependent_relations = HashSet::new();
+            for fragment in fragment_graph.fragments.values() {
+                resolve_chain_upstreams(fragment.node.as_ref().unwrap(), &mut dependent_relations)?;
+            }
+            mview.dependent_relations = dependent_relations.into_iter().collect();
+        }
+
         self.catalog_manager
             .start_create_materialized_source_procedure(&source, &mview)
             .await?;
@@ -544,10 +853,35 @@ where
             .await? as u32;
         mview.id = mview_id;
 
+        let retention_seconds = mview
+            .properties
+            .get(RETENTION_SECONDS_KEY)
+            .and_then(|retention| retention.parse().ok());
+        let partition_retention = mview
+            .properties
+            .get(PARTITION_RETENTION_SECONDS_KEY)
+            .and_then(|retention| {
+                Some(PartitionRetention {
+                    interval_seconds: mview.properties[PARTITION_INTERVAL_SECONDS_KEY]
+                        .parse()
+                        .ok()?,
+                    retention_seconds: retention.parse().ok()?,
+                })
+            });
         // Create mview on compute node.
         // Noted that this progress relies on the source just created, so we pass it here.
         if let Err(e) = self
-            .create_mview_on_compute_node(fragment_graph, mview_id, Some(source.clone()))
+            .create_mview_on_compute_node(
+                fragment_graph,
+                mview_id,
+                Some(source.clone()),
+                None,
+                retention_seconds,
+                partition_retention,
+                DEFAULT_RESOURCE_GROUP.to_string(),
+                HashMap::new(),
+                None,
+            )
             .await
         {
             self.catalog_manager
@@ -569,6 +903,278 @@ where
         Ok((source_id, mview_id, version))
     }
 
+    async fn export_streaming_job_inner(
+        &self,
+        table_id: TableId,
+    ) -> RwResult<StreamingJobSnapshot> {
+        let (databases, schemas, tables, sources) = self.catalog_manager.get_catalog().await?;
+        let mview = tables
+            .iter()
+            .find(|table| table.id == table_id)
+            .ok_or_else(|| ErrorCode::ItemNotFound(format!("table {}", table_id)))?;
+        if mview.optional_associated_source_id.is_some() || mview.is_index || mview.is_internal {
+            return Err(ErrorCode::InvalidInputSyntax(format!(
+                "\"{}\" is not a materialized view",
+                mview.name
+            ))
+            .into());
+        }
+
+        let schema_name = |schema_id| {
+            schemas
+                .iter()
+                .find(|schema| schema.id == schema_id)
+                .map(|schema| schema.name.clone())
+                .unwrap_or_default()
+        };
+        let dependent_relations = mview
+            .dependent_relations
+            .iter()
+            .map(|&relation_id| -> RwResult<_> {
+                let (schema_id, name) = tables
+                    .iter()
+                    .find(|table| table.id == relation_id)
+                    .map(|table| (table.schema_id, table.name.clone()))
+                    .or_else(|| {
+                        sources
+                            .iter()
+                            .find(|source| source.id == relation_id)
+                            .map(|source| (source.schema_id, source.name.clone()))
+                    })
+                    .ok_or_else(|| ErrorCode::ItemNotFound(format!("relation {}", relation_id)))?;
+                let relation_name = RelationName {
+                    schema_name: schema_name(schema_id),
+                    name,
+                };
+                Ok((relation_id, relation_name))
+            })
+            .collect::<RwResult<_>>()?;
+
+        let table_fragments = self
+            .fragment_manager
+            .select_table_fragments_by_table_id(&risingwave_common::catalog::TableId::new(table_id))
+            .await?;
+        let fragment_graph = table_fragments.fragment_graph().cloned().ok_or_else(|| {
+            ErrorCode::NotImplemented(
+                format!(
+                    "exporting \"{}\" created before fragment graphs were kept",
+                    mview.name
+                ),
+                None.into(),
+            )
+        })?;
+        let source_splits = self
+            .source_manager
+            .list_assigned_splits(&table_fragments)
+            .await
+            .into_iter()
+            .map(|(source_id, splits)| {
+                let splits = ConnectorSplits {
+                    splits: splits.iter().map(ConnectorSplit::from).collect(),
+                };
+                (source_id, splits)
+            })
+            .collect();
+
+        Ok(StreamingJobSnapshot {
+            database_name: databases
+                .iter()
+                .find(|database| database.id == mview.database_id)
+                .map(|database| database.name.clone())
+                .unwrap_or_default(),
+            schema_name: schema_name(mview.schema_id),
+            materialized_view: Some(mview.clone()),
+            internal_tables: tables
+                .iter()
+                .filter(|table| table.is_internal && table.internal_of_id == table_id)
+                .cloned()
+                .collect(),
+            fragment_graph: Some(fragment_graph),
+            dependent_relations,
+            source_splits,
+        })
+    }
+
+    /// Recreates a streaming job exported from another cluster. The upstream relations are
+    /// resolved by names, and the sources resume from the exported offsets of their splits.
+    async fn import_streaming_job_inner(
+        &self,
+        snapshot: StreamingJobSnapshot,
+    ) -> RwResult<(TableId, CatalogVersion)> {
+        fn remap_relation_ids(stream_node: &mut StreamNode, relation_ids: &HashMap<u32, u32>) {
+            let remap = |id: u32| relation_ids.get(&id).copied().unwrap_or(id);
+            let table_ref_id = match stream_node.node_body.as_mut().unwrap() {
+                NodeBody::Source(source_node) => source_node.table_ref_id.as_mut(),
+                NodeBody::Chain(chain_node) => chain_node.table_ref_id.as_mut(),
+                NodeBody::DeltaIndexJoin(delta_index_join) => {
+                    delta_index_join.left_table_id = remap(delta_index_join.left_table_id);
+                    delta_index_join.right_table_id = remap(delta_index_join.right_table_id);
+                    None
+                }
+                _ => None,
+            };
+            if let Some(table_ref_id) = table_ref_id {
+                table_ref_id.table_id = remap(table_ref_id.table_id as u32) as i32;
+            }
+            for input in &mut stream_node.input {
+                remap_relation_ids(input, relation_ids);
+            }
+        }
+
+        let (databases, schemas, tables, sources) = self.catalog_manager.get_catalog().await?;
+        let database_id = databases
+            .iter()
+            .find(|database| database.name == snapshot.database_name)
+            .map(|database| database.id)
+            .ok_or_else(|| {
+                ErrorCode::ItemNotFound(format!("database {}", snapshot.database_name))
+            })?;
+        let resolve_schema_id = |schema_name: &str| {
+            schemas
+                .iter()
+                .find(|schema| schema.database_id == database_id && schema.name == schema_name)
+                .map(|schema| schema.id)
+                .ok_or_else(|| ErrorCode::ItemNotFound(format!("schema {}", schema_name)))
+        };
+
+        let mut relation_ids = HashMap::new();
+        for (relation_id, relation_name) in &snapshot.dependent_relations {
+            let schema_id = resolve_schema_id(&relation_name.schema_name)?;
+            let new_relation_id = tables
+                .iter()
+                .find(|table| {
+                    table.schema_id == schema_id
+                        && table.name == relation_name.name
+                        && !table.is_internal
+                })
+                .map(|table| table.id)
+                .or_else(|| {
+                    sources
+                        .iter()
+                        .find(|source| {
+                            source.schema_id == schema_id && source.name == relation_name.name
+                        })
+                        .map(|source| source.id)
+                })
+                .ok_or_else(|| {
+                    ErrorCode::ItemNotFound(format!(
+                        "relation {}.{}",
+                        relation_name.schema_name, relation_name.name
+                    ))
+                })?;
+            relation_ids.insert(*relation_id, new_relation_id);
+        }
+
+        let mut fragment_graph = snapshot.fragment_graph.unwrap();
+        for fragment in fragment_graph.fragments.values_mut() {
+            remap_relation_ids(fragment.node.as_mut().unwrap(), &relation_ids);
+        }
+        for table_id in &mut fragment_graph.dependent_table_ids {
+            *table_id = relation_ids.get(table_id).copied().unwrap_or(*table_id);
+        }
+
+        let initial_splits = snapshot
+            .source_splits
+            .iter()
+            .filter_map(|(source_id, splits)| {
+                let source_id = *relation_ids.get(source_id)?;
+                let splits = splits
+                    .splits
+                    .iter()
+                    .map(|split| -> RwResult<_> {
+                        let split = SplitImpl::try_from(split)
+                            .map_err(|e| ErrorCode::ConnectorError(e.to_string()))?;
+                        Ok((split.id(), split))
+                    })
+                    .collect::<RwResult<BTreeMap<_, _>>>();
+                Some(splits.map(|splits| (source_id, splits)))
+            })
+            .collect::<RwResult<_>>()?;
+
+        let mut mview = snapshot.materialized_view.unwrap();
+        mview.database_id = database_id;
+        mview.schema_id = resolve_schema_id(&snapshot.schema_name)?;
+        mview.mapping = None;
+        self.create_materialized_view_inner(mview, fragment_graph, initial_splits, None)
+            .await
+    }
+
+    /// Replaces the query of the materialized view `table_id` with the new one of `mview`. The
+    /// states of the aggregations computing the same as the old query are adopted by the new job,
+    /// instead of being rebuilt from the upstreams. Check [`adopt_states`] for details.
+    async fn alter_materialized_view_inner(
+        &self,
+        table_id: TableId,
+        mview: Table,
+        mut fragment_graph: StreamFragmentGraph,
+    ) -> RwResult<(TableId, CatalogVersion)> {
+        let old_table_id = risingwave_common::catalog::TableId::new(table_id);
+        let all_table_fragments = self.fragment_manager.list_table_fragments().await?;
+        if all_table_fragments.iter().any(|table_fragments| {
+            table_fragments
+                .dependent_table_ids()
+                .contains(&old_table_id)
+        }) {
+            return Err(ErrorCode::NotImplemented(
+                "altering the query of materialized views read by other materialized views"
+                    .to_string(),
+                None.into(),
+            )
+            .into());
+        }
+        let old_table_fragments = all_table_fragments
+            .iter()
+            .find(|table_fragments| table_fragments.table_id() == old_table_id)
+            .ok_or_else(|| ErrorCode::ItemNotFound(format!("streaming job {}", table_id)))?;
+        let adopted = adopt_states(&mut fragment_graph, old_table_fragments);
+
+        if adopted.is_empty() {
+            // Nothing to adopt. Create the new job aside and cut over to it, like `REPLACE
+            // MATERIALIZED VIEW`.
+            let (new_table_id, _) = self
+                .create_materialized_view_inner(mview, fragment_graph, HashMap::new(), None)
+                .await?;
+            let version = match self
+                .catalog_manager
+                .replace_table(table_id, new_table_id, &HashSet::new())
+                .await
+            {
+                Ok(version) => version,
+                Err(e) => {
+                    self.catalog_manager.drop_table(new_table_id).await?;
+                    self.stream_manager
+                        .drop_materialized_view(&risingwave_common::catalog::TableId::new(
+                            new_table_id,
+                        ))
+                        .await?;
+                    return Err(e);
+                }
+            };
+            self.stream_manager
+                .drop_materialized_view(&old_table_id)
+                .await?;
+            return Ok((new_table_id, version));
+        }
+
+        // The old job is stopped before the new one starts, so that the adopted states are no
+        // longer written.
+        let adopted_table_ids = adopted.table_ids.values().copied().collect::<HashSet<_>>();
+        let (new_table_id, _) = self
+            .create_materialized_view_inner(
+                mview,
+                fragment_graph,
+                HashMap::new(),
+                Some((table_id, adopted)),
+            )
+            .await?;
+        let version = self
+            .catalog_manager
+            .replace_table(table_id, new_table_id, &adopted_table_ids)
+            .await?;
+
+        Ok((new_table_id, version))
+    }
+
     async fn drop_materialized_source_inner(
         &self,
         source_id: SourceId,
@@ -593,6 +1199,42 @@ where
     }
 
     /// Fill in mview's vnode mapping so that frontend will know the data distribution.
+    /// Build the catalogs of the internal state tables of `mview`, so that they can be listed by
+    /// `SHOW INTERNAL TABLES` and read for debugging.
+    async fn resolve_internal_tables(&self, mview: &Table) -> RwResult<Vec<Table>> {
+        let mview_id = risingwave_common::catalog::TableId::new(mview.id);
+        let table_fragments = self
+            .fragment_manager
+            .select_table_fragments_by_table_id(&mview_id)
+            .await?;
+        let mut internal_tables = table_fragments.internal_tables();
+        for table in &mut internal_tables {
+            table.name = format!("__internal_{}_{}", mview.name, table.id);
+            table.schema_id = mview.schema_id;
+            table.database_id = mview.database_id;
+            table.owner = mview.owner.clone();
+            table.is_internal = true;
+            table.internal_of_id = mview.id;
+            // Columns of agg states are unnamed, and join sides may contain duplicated names.
+            let mut column_names = HashSet::new();
+            for (i, column) in table.columns.iter_mut().enumerate() {
+                let column_desc = column.column_desc.as_mut().unwrap();
+                if column_desc.name.is_empty() || !column_names.insert(column_desc.name.clone()) {
+                    column_desc.name = format!("_col{}", i);
+                    column_names.insert(column_desc.name.clone());
+                }
+            }
+            // Singleton states, e.g. of simple aggregations, have no vnode mapping.
+            if self.set_mview_mapping(table).is_err() {
+                table.mapping = Some(ParallelUnitMapping {
+                    table_id: table.id,
+                    ..Default::default()
+                });
+            }
+        }
+        Ok(internal_tables)
+    }
+
     fn set_mview_mapping(&self, mview: &mut Table) -> RwResult<()> {
         let vnode_mapping = self
             .env
@@ -310,4 +310,33 @@ where
             Err(e) => Err(tonic_err(e)),
         }
     }
+
+    async fn get_table_storage_stats(
+        &self,
+        _request: Request<GetTableStorageStatsRequest>,
+    ) -> Result<Response<GetTableStorageStatsResponse>, Status> {
+        let table_stats = self.hummock_manager.get_table_storage_stats().await;
+        Ok(Response::new(GetTableStorageStatsResponse {
+            status: None,
+            table_stats,
+        }))
+    }
+
+    async fn reset_current_version(
+        &self,
+        request: Request<ResetCurrentVersionRequest>,
+    ) -> Result<Response<ResetCurrentVersionResponse>, Status> {
+        let req = request.into_inner();
+        let result = self
+            .hummock_manager
+            .reset_current_version(req.version.unwrap())
+            .await;
+        match result {
+            Ok(version) => Ok(Response::new(ResetCurrentVersionResponse {
+                status: None,
+                version: Some(version),
+            })),
+            Err(e) => Err(tonic_err(e)),
+        }
+    }
 }
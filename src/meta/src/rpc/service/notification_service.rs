@@ -23,7 +23,9 @@ use tokio_stream::wrappers::UnboundedReceiverStream;
 use tonic::{Request, Response, Status};
 
 use crate::cluster::{ClusterManagerRef, WorkerKey};
-use crate::manager::{CatalogManagerRef, MetaSrvEnv, Notification, UserInfoManagerRef};
+use crate::manager::{
+    CatalogManagerRef, MetaSrvEnv, Notification, SystemParamManagerRef, UserInfoManagerRef,
+};
 use crate::storage::MetaStore;
 pub struct NotificationServiceImpl<S: MetaStore> {
     env: MetaSrvEnv<S>,
@@ -31,6 +33,7 @@ pub struct NotificationServiceImpl<S: MetaStore> {
     catalog_manager: CatalogManagerRef<S>,
     cluster_manager: ClusterManagerRef<S>,
     user_manager: UserInfoManagerRef<S>,
+    system_param_manager: SystemParamManagerRef<S>,
 }
 
 impl<S> NotificationServiceImpl<S>
@@ -42,12 +45,14 @@ where
         catalog_manager: CatalogManagerRef<S>,
         cluster_manager: ClusterManagerRef<S>,
         user_manager: UserInfoManagerRef<S>,
+        system_param_manager: SystemParamManagerRef<S>,
     ) -> Self {
         Self {
             env,
             catalog_manager,
             cluster_manager,
             user_manager,
+            system_param_manager,
         }
     }
 }
@@ -71,7 +76,17 @@ where
         let (tx, rx) = mpsc::unbounded_channel();
 
         match worker_type {
-            WorkerType::ComputeNode => {
+            WorkerType::ComputeNode | WorkerType::ServingNode => {
+                // Hold the guard until the sender is inserted, so that no change of parameters is
+                // missed.
+                let params_guard = self.system_param_manager.get_params_guard().await;
+                tx.send(Ok(SubscribeResponse {
+                    status: None,
+                    operation: Operation::Snapshot as i32,
+                    info: Some(Info::SystemParams(params_guard.clone())),
+                    version: self.env.notification_manager().current_version().await,
+                }))
+                .unwrap();
                 self.env
                     .notification_manager()
                     .insert_compute_sender(WorkerKey(host_address), tx)
@@ -82,7 +97,12 @@ where
                 let (database, schema, table, source) = catalog_guard.get_catalog().await?;
 
                 let cluster_guard = self.cluster_manager.get_cluster_core_guard().await;
-                let nodes = cluster_guard.list_worker_node(WorkerType::ComputeNode, Some(Running));
+                let nodes = [WorkerType::ComputeNode, WorkerType::ServingNode]
+                    .into_iter()
+                    .flat_map(|worker_type| {
+                        cluster_guard.list_worker_node(worker_type, Some(Running))
+                    })
+                    .collect();
 
                 let user_guard = self.user_manager.get_user_core_guard().await;
                 let users = user_guard.values().cloned().collect::<Vec<_>>();
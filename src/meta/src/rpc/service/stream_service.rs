@@ -12,10 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use risingwave_common::catalog::TableId;
+use risingwave_pb::hummock::HummockSnapshot;
 use risingwave_pb::meta::stream_manager_service_server::StreamManagerService;
 use risingwave_pb::meta::*;
 use tonic::{Request, Response, Status};
 
+use crate::hummock::HummockManagerRef;
 use crate::storage::MetaStore;
 use crate::stream::GlobalStreamManagerRef;
 
@@ -27,15 +30,20 @@ where
     S: MetaStore,
 {
     global_stream_manager: GlobalStreamManagerRef<S>,
+    hummock_manager: HummockManagerRef<S>,
 }
 
 impl<S> StreamServiceImpl<S>
 where
     S: MetaStore,
 {
-    pub fn new(global_stream_manager: GlobalStreamManagerRef<S>) -> Self {
+    pub fn new(
+        global_stream_manager: GlobalStreamManagerRef<S>,
+        hummock_manager: HummockManagerRef<S>,
+    ) -> Self {
         StreamServiceImpl {
             global_stream_manager,
+            hummock_manager,
         }
     }
 }
@@ -50,6 +58,73 @@ where
         let _req = request.into_inner();
 
         self.global_stream_manager.flush().await?;
-        Ok(Response::new(FlushResponse { status: None }))
+        // The epoch of the collected barrier has been committed to hummock when `flush` returns,
+        // so the current committed epoch covers all the writes before the flush.
+        let snapshot = HummockSnapshot {
+            epoch: self
+                .hummock_manager
+                .get_current_version()
+                .await
+                .max_committed_epoch,
+        };
+        Ok(Response::new(FlushResponse {
+            status: None,
+            snapshot: Some(snapshot),
+        }))
+    }
+
+    #[cfg_attr(coverage, no_coverage)]
+    async fn savepoint(
+        &self,
+        request: Request<SavepointRequest>,
+    ) -> TonicResponse<SavepointResponse> {
+        let req = request.into_inner();
+        let savepoint_epoch = self
+            .global_stream_manager
+            .savepoint(&TableId::new(req.table_id))
+            .await?;
+        Ok(Response::new(SavepointResponse {
+            status: None,
+            savepoint_epoch,
+        }))
+    }
+
+    #[cfg_attr(coverage, no_coverage)]
+    async fn restore_savepoint(
+        &self,
+        request: Request<RestoreSavepointRequest>,
+    ) -> TonicResponse<RestoreSavepointResponse> {
+        let req = request.into_inner();
+        self.global_stream_manager
+            .restore_savepoint(&TableId::new(req.table_id))
+            .await?;
+        Ok(Response::new(RestoreSavepointResponse { status: None }))
+    }
+
+    #[cfg_attr(coverage, no_coverage)]
+    async fn release_savepoint(
+        &self,
+        request: Request<ReleaseSavepointRequest>,
+    ) -> TonicResponse<ReleaseSavepointResponse> {
+        let req = request.into_inner();
+        self.global_stream_manager
+            .release_savepoint(&TableId::new(req.table_id))
+            .await?;
+        Ok(Response::new(ReleaseSavepointResponse { status: None }))
+    }
+
+    #[cfg_attr(coverage, no_coverage)]
+    async fn reconfigure(
+        &self,
+        request: Request<ReconfigureRequest>,
+    ) -> TonicResponse<ReconfigureResponse> {
+        let req = request.into_inner();
+        let change = req
+            .change
+            .ok_or_else(|| Status::invalid_argument("missing config change"))?;
+        self.global_stream_manager
+            .reconfigure(&TableId::new(req.table_id), change)
+            .await?;
+        Ok(Response::new(ReconfigureResponse { status: None }))
     }
 }
@@ -0,0 +1,349 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use risingwave_common::catalog::TableId;
+use risingwave_common::config::{AutoParallelismConfig, AutoParallelismMode};
+use risingwave_pb::common::ParallelUnitType;
+use tokio::sync::oneshot::Sender;
+use tokio::task::JoinHandle;
+
+use crate::barrier::BlockingTimeRecorder;
+use crate::cluster::ClusterManagerRef;
+use crate::model::{FragmentId, TableFragments};
+use crate::rpc::metrics::MetaMetrics;
+use crate::storage::MetaStore;
+use crate::stream::{FragmentManagerRef, GlobalStreamManagerRef};
+
+/// The backpressure of a fragment in an evaluation window.
+#[derive(Debug, Clone)]
+pub struct FragmentBackpressure {
+    pub table_id: TableId,
+    pub fragment_id: FragmentId,
+    pub parallelism: usize,
+    /// The maximum parallelism allowed by the config and the resource group of the fragment.
+    pub max_parallelism: usize,
+    /// The largest fraction of time the actors of an upstream fragment are blocked on sending.
+    pub upstream_blocking_ratio: f64,
+    /// The fraction of time the actors of the fragment itself are blocked on sending.
+    pub blocking_ratio: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParallelismProposal {
+    pub table_id: TableId,
+    pub fragment_id: FragmentId,
+    pub from: usize,
+    pub to: usize,
+}
+
+/// Decides the parallelism changes of fragments from their backpressure.
+///
+/// A fragment is scaled out, by doubling its parallelism, if its upstream actors are blocked on
+/// sending for more than `scale_out_threshold` of the time, while its own actors are not. If its
+/// own actors are blocked too, the bottleneck is further downstream. A fragment is scaled in, by
+/// halving its parallelism, after its upstream actors are blocked for less than
+/// `scale_in_threshold` of the time in `scale_in_windows` consecutive evaluations.
+pub struct ParallelismTuner {
+    config: AutoParallelismConfig,
+    /// Number of consecutive evaluations each fragment has been quiet.
+    quiet_windows: HashMap<FragmentId, usize>,
+}
+
+impl ParallelismTuner {
+    pub fn new(config: AutoParallelismConfig) -> Self {
+        Self {
+            config,
+            quiet_windows: HashMap::new(),
+        }
+    }
+
+    /// Returns the parallelism changes of the given fragments, the most backpressured first.
+    pub fn evaluate(&mut self, fragments: &[FragmentBackpressure]) -> Vec<ParallelismProposal> {
+        let min_parallelism = self.config.min_parallelism.max(1);
+        let mut fragments = fragments.iter().collect::<Vec<_>>();
+        fragments.sort_by(|a, b| {
+            b.upstream_blocking_ratio
+                .total_cmp(&a.upstream_blocking_ratio)
+        });
+        self.quiet_windows
+            .retain(|fragment_id, _| fragments.iter().any(|f| f.fragment_id == *fragment_id));
+
+        let mut proposals = vec![];
+        for fragment in fragments {
+            let quiet_windows = self.quiet_windows.entry(fragment.fragment_id).or_default();
+            let to = if fragment.upstream_blocking_ratio > self.config.scale_out_threshold
+                && fragment.blocking_ratio <= self.config.scale_out_threshold
+            {
+                *quiet_windows = 0;
+                (fragment.parallelism * 2).min(fragment.max_parallelism)
+            } else if fragment.upstream_blocking_ratio < self.config.scale_in_threshold {
+                *quiet_windows += 1;
+                if *quiet_windows < self.config.scale_in_windows {
+                    continue;
+                }
+                *quiet_windows = 0;
+                (fragment.parallelism / 2).max(min_parallelism)
+            } else {
+                *quiet_windows = 0;
+                continue;
+            };
+            if to != fragment.parallelism {
+                proposals.push(ParallelismProposal {
+                    table_id: fragment.table_id,
+                    fragment_id: fragment.fragment_id,
+                    from: fragment.parallelism,
+                    to,
+                });
+            }
+        }
+        proposals
+    }
+}
+
+/// Computes the backpressure of the reschedulable fragments from the time their actors are blocked
+/// on sending in the last `elapsed`. `max_parallelism` returns the maximum parallelism of the
+/// fragments in a resource group.
+pub fn fragment_backpressure(
+    all_table_fragments: &[TableFragments],
+    blocking_ns: &HashMap<FragmentId, u64>,
+    elapsed: Duration,
+    max_parallelism: impl Fn(&str) -> usize,
+) -> Vec<FragmentBackpressure> {
+    let elapsed_ns = elapsed.as_nanos().max(1) as f64;
+    let mut backpressure = vec![];
+    for table_fragments in all_table_fragments {
+        let fragment_of: HashMap<_, _> = table_fragments
+            .fragments
+            .values()
+            .flat_map(|f| f.actors.iter().map(|a| (a.actor_id, f.fragment_id)))
+            .collect();
+        let blocking_ratio = |fragment_id: FragmentId| {
+            let actor_count = table_fragments.fragments[&fragment_id].actors.len().max(1);
+            blocking_ns.get(&fragment_id).copied().unwrap_or(0) as f64
+                / (actor_count as f64 * elapsed_ns)
+        };
+
+        let mut upstreams: HashMap<FragmentId, HashSet<FragmentId>> = HashMap::new();
+        for fragment in table_fragments.fragments.values() {
+            for downstream in fragment
+                .actors
+                .iter()
+                .flat_map(|a| a.dispatcher.iter())
+                .flat_map(|d| d.downstream_actor_id.iter())
+                .filter_map(|actor_id| fragment_of.get(actor_id))
+            {
+                upstreams
+                    .entry(*downstream)
+                    .or_default()
+                    .insert(fragment.fragment_id);
+            }
+        }
+
+        for fragment in table_fragments.fragments.values() {
+            let fragment_upstreams = match upstreams.get(&fragment.fragment_id) {
+                Some(fragment_upstreams) => fragment_upstreams,
+                None => continue,
+            };
+            if table_fragments
+                .check_reschedulable(fragment.fragment_id)
+                .is_err()
+            {
+                continue;
+            }
+            backpressure.push(FragmentBackpressure {
+                table_id: table_fragments.table_id(),
+                fragment_id: fragment.fragment_id,
+                parallelism: fragment.actors.len(),
+                max_parallelism: max_parallelism(table_fragments.resource_group()),
+                upstream_blocking_ratio: fragment_upstreams
+                    .iter()
+                    .map(|upstream| blocking_ratio(*upstream))
+                    .fold(0.0, f64::max),
+                blocking_ratio: blocking_ratio(fragment.fragment_id),
+            });
+        }
+    }
+    backpressure
+}
+
+/// Starts a task to tune the parallelism of fragments according to their backpressure. The
+/// changes are proposed by logs and metrics, and applied one at a time by rescheduling the
+/// fragments if the mode is `apply`.
+pub async fn start_auto_parallelism<S>(
+    stream_manager: GlobalStreamManagerRef<S>,
+    fragment_manager: FragmentManagerRef<S>,
+    cluster_manager: ClusterManagerRef<S>,
+    blocking_time: Arc<BlockingTimeRecorder>,
+    metrics: Arc<MetaMetrics>,
+    config: AutoParallelismConfig,
+) -> (JoinHandle<()>, Sender<()>)
+where
+    S: MetaStore,
+{
+    let (shutdown_tx, mut shutdown_rx) = tokio::sync::oneshot::channel();
+    let join_handle = tokio::spawn(async move {
+        let mut interval =
+            tokio::time::interval(Duration::from_secs(config.evaluation_interval_sec.max(1)));
+        let mut tuner = ParallelismTuner::new(config.clone());
+        let mut window_start = Instant::now();
+        blocking_time.take();
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {},
+                _ = &mut shutdown_rx => {
+                    return;
+                }
+            }
+            let elapsed = window_start.elapsed();
+            window_start = Instant::now();
+            let blocking_ns = blocking_time.take();
+            if blocking_ns.is_empty() {
+                continue;
+            }
+
+            let all_table_fragments = match fragment_manager.list_table_fragments().await {
+                Ok(all_table_fragments) => all_table_fragments,
+                Err(err) => {
+                    tracing::warn!("Failed to list table fragments: {}", err);
+                    continue;
+                }
+            };
+            let mut group_parallelism = HashMap::new();
+            for table_fragments in &all_table_fragments {
+                let group = table_fragments.resource_group();
+                if !group_parallelism.contains_key(group) {
+                    let parallel_units = cluster_manager
+                        .list_resource_group_parallel_units(ParallelUnitType::Hash, group)
+                        .await
+                        .len();
+                    let max_parallelism = match config.max_parallelism {
+                        0 => parallel_units,
+                        max_parallelism => max_parallelism.min(parallel_units),
+                    };
+                    group_parallelism.insert(group.to_string(), max_parallelism);
+                }
+            }
+            let backpressure =
+                fragment_backpressure(&all_table_fragments, &blocking_ns, elapsed, |group| {
+                    group_parallelism[group]
+                });
+
+            let proposals = tuner.evaluate(&backpressure);
+            for proposal in &proposals {
+                tracing::info!(
+                    "Propose to change the parallelism of fragment {} of table {} from {} to {}",
+                    proposal.fragment_id,
+                    proposal.table_id,
+                    proposal.from,
+                    proposal.to
+                );
+                metrics
+                    .fragment_proposed_parallelism
+                    .with_label_values(&[
+                        &proposal.table_id.to_string(),
+                        &proposal.fragment_id.to_string(),
+                    ])
+                    .set(proposal.to as i64);
+            }
+
+            // Apply one change at a time, since the actors of all jobs are rebuilt to apply it.
+            if config.mode == AutoParallelismMode::Apply
+                && let Some(proposal) = proposals.first()
+            {
+                if let Err(err) = stream_manager
+                    .reschedule_fragment(&proposal.table_id, proposal.fragment_id, proposal.to)
+                    .await
+                {
+                    tracing::warn!(
+                        "Failed to reschedule fragment {}: {}",
+                        proposal.fragment_id,
+                        err
+                    );
+                }
+                // The blocking time during the rescheduling is not representative.
+                window_start = Instant::now();
+                blocking_time.take();
+            }
+        }
+    });
+
+    (join_handle, shutdown_tx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn backpressure(
+        fragment_id: FragmentId,
+        parallelism: usize,
+        upstream_blocking_ratio: f64,
+        blocking_ratio: f64,
+    ) -> FragmentBackpressure {
+        FragmentBackpressure {
+            table_id: TableId::new(1),
+            fragment_id,
+            parallelism,
+            max_parallelism: 8,
+            upstream_blocking_ratio,
+            blocking_ratio,
+        }
+    }
+
+    fn proposal(fragment_id: FragmentId, from: usize, to: usize) -> ParallelismProposal {
+        ParallelismProposal {
+            table_id: TableId::new(1),
+            fragment_id,
+            from,
+            to,
+        }
+    }
+
+    #[test]
+    fn test_parallelism_tuner() {
+        let config = AutoParallelismConfig {
+            min_parallelism: 2,
+            scale_in_windows: 2,
+            ..Default::default()
+        };
+        let mut tuner = ParallelismTuner::new(config);
+
+        // Fragment 2 is the bottleneck, fragment 1 is blocked by fragment 2, and fragment 3 is
+        // already at the maximum parallelism.
+        let proposals = tuner.evaluate(&[
+            backpressure(1, 4, 0.6, 0.9),
+            backpressure(2, 4, 0.9, 0.1),
+            backpressure(3, 8, 0.7, 0.0),
+            backpressure(4, 3, 0.2, 0.0),
+        ]);
+        assert_eq!(proposals, vec![proposal(2, 4, 8)]);
+
+        // Quiet fragments are scaled in after 2 evaluations, down to the minimum parallelism.
+        let quiet = [backpressure(2, 8, 0.0, 0.0), backpressure(4, 3, 0.01, 0.0)];
+        assert!(tuner.evaluate(&quiet).is_empty());
+        assert_eq!(
+            tuner.evaluate(&quiet),
+            vec![proposal(4, 3, 2), proposal(2, 8, 4)]
+        );
+        assert!(tuner.evaluate(&quiet).is_empty());
+
+        // A busy window resets the count.
+        tuner.evaluate(&[backpressure(2, 4, 0.3, 0.0)]);
+        assert!(tuner.evaluate(&[backpressure(2, 4, 0.0, 0.0)]).is_empty());
+    }
+}
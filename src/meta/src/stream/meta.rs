@@ -18,18 +18,21 @@ use std::sync::Arc;
 
 use risingwave_common::catalog::TableId;
 use risingwave_common::error::ErrorCode::InternalError;
-use risingwave_common::error::{Result, RwError};
+use risingwave_common::error::{ErrorCode, Result, RwError};
 use risingwave_common::try_match_expand;
-use risingwave_common::types::{ParallelUnitId, VIRTUAL_NODE_COUNT};
+use risingwave_common::types::{virtual_node_count, ParallelUnitId};
 use risingwave_common::util::compress::decompress_data;
-use risingwave_pb::meta::table_fragments::ActorState;
+use risingwave_hummock_sdk::HummockEpoch;
+use risingwave_pb::common::ParallelUnit;
+use risingwave_pb::data::ConfigChange;
+use risingwave_pb::meta::table_fragments::{ActorState, Fragment};
 use risingwave_pb::stream_plan::{FragmentType, StreamActor};
 use tokio::sync::RwLock;
 
 use crate::cluster::WorkerId;
 use crate::hummock::compaction_group::manager::CompactionGroupManagerRef;
 use crate::manager::{HashMappingManagerRef, MetaSrvEnv};
-use crate::model::{ActorId, MetadataModel, TableFragments, Transactional};
+use crate::model::{ActorId, FragmentId, MetadataModel, TableFragments, Transactional};
 use crate::storage::{MetaStore, Transaction};
 use crate::stream::record_table_vnode_mappings;
 
@@ -44,6 +47,8 @@ pub struct FragmentManager<S: MetaStore> {
     core: RwLock<FragmentManagerCore>,
 
     compaction_group_manager: CompactionGroupManagerRef<S>,
+
+    hash_mapping_manager: HashMappingManagerRef,
 }
 
 pub struct ActorInfos {
@@ -82,12 +87,17 @@ where
             .map(|tf| (tf.table_id(), tf))
             .collect();
 
-        Self::restore_vnode_mappings(env.hash_mapping_manager_ref(), &table_fragments)?;
+        Self::restore_vnode_mappings(
+            env.hash_mapping_manager_ref(),
+            &table_fragments,
+            virtual_node_count(),
+        )?;
 
         Ok(Self {
             meta_store,
             core: RwLock::new(FragmentManagerCore { table_fragments }),
             compaction_group_manager,
+            hash_mapping_manager: env.hash_mapping_manager_ref(),
         })
     }
 
@@ -114,6 +124,29 @@ where
         }
     }
 
+    /// Replaces the actors of the fragment with the ones of the given fragment, which are
+    /// scheduled to `actor_locations`. Check [`TableFragments::reschedule_fragment`] for details.
+    pub async fn reschedule_fragment(
+        &self,
+        table_id: &TableId,
+        fragment: Fragment,
+        actor_locations: &BTreeMap<ActorId, ParallelUnit>,
+    ) -> Result<()> {
+        let map = &mut self.core.write().await.table_fragments;
+        let mut table_fragments = map.get(table_id).cloned().ok_or_else(|| {
+            RwError::from(InternalError(format!(
+                "table_fragment not exist: id={}",
+                table_id
+            )))
+        })?;
+        // Check again, since the job may be changed after the fragment is scheduled.
+        table_fragments.check_reschedulable(fragment.fragment_id)?;
+        table_fragments.reschedule_fragment(fragment, actor_locations);
+        table_fragments.insert(&*self.meta_store).await?;
+        map.insert(*table_id, table_fragments);
+        Ok(())
+    }
+
     pub async fn select_table_fragments_by_table_id(
         &self,
         table_id: &TableId,
@@ -300,6 +333,182 @@ where
         }
     }
 
+    /// Migrates the actors on a deleted worker to the given parallel units of other workers. All
+    /// actors on one parallel unit are moved to the same target, which no actor of their fragments
+    /// is on, so that each fragment keeps at most one actor per parallel unit and chain actors stay
+    /// with their upstream actors. The targets are picked in a round-robin manner, and the vnode
+    /// mappings are updated to them. Returns the ids of the affected tables.
+    pub async fn migrate_actors(
+        &self,
+        from: WorkerId,
+        to: &[ParallelUnit],
+    ) -> Result<Vec<TableId>> {
+        let map = &mut self.core.write().await.table_fragments;
+
+        // The parallel units used by each fragment, and the fragments on each parallel unit to
+        // migrate.
+        let mut used: HashMap<FragmentId, HashSet<ParallelUnitId>> = HashMap::new();
+        let mut to_migrate: BTreeMap<ParallelUnitId, HashSet<FragmentId>> = BTreeMap::new();
+        for (fragment_id, parallel_unit) in map
+            .values()
+            .flat_map(|table_fragments| table_fragments.fragment_parallel_units())
+        {
+            used.entry(fragment_id)
+                .or_default()
+                .insert(parallel_unit.id);
+            if parallel_unit.worker_node_id == from {
+                to_migrate
+                    .entry(parallel_unit.id)
+                    .or_default()
+                    .insert(fragment_id);
+            }
+        }
+        if to_migrate.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut targets: HashMap<ParallelUnitId, ParallelUnit> = HashMap::new();
+        let mut next = 0;
+        for (parallel_unit_id, fragment_ids) in to_migrate {
+            let target_idx = (0..to.len())
+                .map(|i| (next + i) % to.len())
+                .find(|&idx| {
+                    fragment_ids
+                        .iter()
+                        .all(|fragment_id| !used[fragment_id].contains(&to[idx].id))
+                })
+                .ok_or_else(|| {
+                    RwError::from(InternalError(format!(
+                        "no free parallel unit to migrate the actors on parallel unit {} to",
+                        parallel_unit_id
+                    )))
+                })?;
+            let target = &to[target_idx];
+            for fragment_id in &fragment_ids {
+                used.get_mut(fragment_id).unwrap().insert(target.id);
+            }
+            targets.insert(parallel_unit_id, target.clone());
+            next = target_idx + 1;
+        }
+
+        let mut transaction = Transaction::default();
+        let mut migrated_tables = vec![];
+        for table_fragments in map.values() {
+            let mut table_fragments = table_fragments.clone();
+            if table_fragments.migrate_parallel_units(&targets) {
+                table_fragments.upsert_in_transaction(&mut transaction)?;
+                migrated_tables.push(table_fragments);
+            }
+        }
+
+        self.meta_store.txn(transaction).await?;
+        Ok(migrated_tables
+            .into_iter()
+            .map(|table_fragments| {
+                for (fragment_id, fragment) in &table_fragments.fragments {
+                    if let Some(mapping) = fragment.vnode_mapping.as_ref() {
+                        let vnode_mapping =
+                            decompress_data(&mapping.original_indices, &mapping.data);
+                        self.hash_mapping_manager
+                            .set_fragment_hash_mapping(*fragment_id, vnode_mapping);
+                    }
+                }
+                let table_id = table_fragments.table_id();
+                map.insert(table_id, table_fragments);
+                table_id
+            })
+            .collect())
+    }
+
+    /// Sets or clears the savepoint of the streaming job.
+    pub async fn update_savepoint_epoch(
+        &self,
+        table_id: &TableId,
+        savepoint_epoch: Option<HummockEpoch>,
+    ) -> Result<()> {
+        let map = &mut self.core.write().await.table_fragments;
+        let table_fragments = map.get_mut(table_id).ok_or_else(|| {
+            RwError::from(InternalError(format!(
+                "table_fragment not exist: id={}",
+                table_id
+            )))
+        })?;
+        let mut updated = table_fragments.clone();
+        updated.set_savepoint_epoch(savepoint_epoch);
+        updated.insert(&*self.meta_store).await?;
+        *table_fragments = updated;
+        Ok(())
+    }
+
+    /// Records the change applied to the source actors of the streaming job, and returns the ids
+    /// of the source actors.
+    pub async fn update_config_change(
+        &self,
+        table_id: &TableId,
+        change: &ConfigChange,
+    ) -> Result<Vec<ActorId>> {
+        let map = &mut self.core.write().await.table_fragments;
+        let table_fragments = map.get_mut(table_id).ok_or_else(|| {
+            RwError::from(InternalError(format!(
+                "table_fragment not exist: id={}",
+                table_id
+            )))
+        })?;
+        let actor_ids = table_fragments.source_actor_ids();
+        if actor_ids.is_empty() {
+            return Err(ErrorCode::InvalidParameterValue(format!(
+                "streaming job {} has no source to reconfigure",
+                table_id
+            ))
+            .into());
+        }
+        let mut updated = table_fragments.clone();
+        updated.set_config_change(change);
+        updated.insert(&*self.meta_store).await?;
+        *table_fragments = updated;
+        Ok(actor_ids)
+    }
+
+    /// Returns the recorded config changes of all streaming jobs, targeting their current source
+    /// actors. They're reapplied to the actors rebuilt in recovery.
+    pub async fn list_config_changes(&self) -> Vec<ConfigChange> {
+        let map = &self.core.read().await.table_fragments;
+        map.values()
+            .flat_map(|table_fragments| {
+                let actor_ids = table_fragments.source_actor_ids();
+                table_fragments
+                    .config_changes()
+                    .iter()
+                    .map(move |change| ConfigChange {
+                        actor_ids: actor_ids.clone(),
+                        change: change.change.clone(),
+                    })
+            })
+            .collect()
+    }
+
+    /// Returns the savepoints of the streaming jobs, by table id. Used by
+    /// [`crate::hummock::HummockManager`] to pin them on restarting.
+    pub async fn list_savepoints(&self) -> Vec<(u32, HummockEpoch)> {
+        let map = &self.core.read().await.table_fragments;
+        map.values()
+            .filter_map(|fragments| {
+                fragments
+                    .savepoint_epoch()
+                    .map(|epoch| (fragments.table_id().table_id(), epoch))
+            })
+            .collect()
+    }
+
+    /// Returns the checkpoint frequency of each streaming job, or `None` if the job uses the
+    /// cluster default. Used in [`crate::barrier::GlobalBarrierManager`].
+    pub async fn list_checkpoint_frequencies(&self) -> Vec<Option<u32>> {
+        let map = &self.core.read().await.table_fragments;
+        map.values()
+            .map(|fragments| fragments.checkpoint_frequency())
+            .collect()
+    }
+
     /// Used in [`crate::barrier::GlobalBarrierManager`]
     pub async fn load_all_actors(&self, with_creating_table: Option<TableId>) -> ActorInfos {
         let mut actor_maps = HashMap::new();
@@ -478,15 +687,18 @@ where
         Ok(info)
     }
 
+    /// Restores the vnode mappings of all fragments, which must have `vnode_count` vnodes, i.e. the
+    /// vnode count the cluster is created with.
     fn restore_vnode_mappings(
         hash_mapping_manager: HashMappingManagerRef,
         table_fragments: &HashMap<TableId, TableFragments>,
+        vnode_count: usize,
     ) -> Result<()> {
         for fragments in table_fragments.values() {
             for (fragment_id, fragment) in &fragments.fragments {
                 let mapping = fragment.vnode_mapping.as_ref().unwrap();
                 let vnode_mapping = decompress_data(&mapping.original_indices, &mapping.data);
-                assert_eq!(vnode_mapping.len(), VIRTUAL_NODE_COUNT);
+                assert_eq!(vnode_mapping.len(), vnode_count);
                 hash_mapping_manager.set_fragment_hash_mapping(*fragment_id, vnode_mapping);
 
                 // Looking at the first actor is enough, since all actors in one fragment have
@@ -503,3 +715,204 @@ where
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use risingwave_common::types::MIN_VIRTUAL_NODE_COUNT;
+    use risingwave_common::util::compress::compress_data;
+    use risingwave_pb::common::{ParallelUnitMapping, ParallelUnitType};
+    use risingwave_pb::meta::table_fragments::ActorStatus;
+    use risingwave_pb::plan_common::TableRefId;
+    use risingwave_pb::stream_plan::stream_node::NodeBody;
+    use risingwave_pb::stream_plan::{MaterializeNode, StreamNode};
+
+    use super::*;
+    use crate::hummock::compaction_group::manager::CompactionGroupManager;
+    use crate::manager::HashMappingManager;
+    use crate::storage::MemStore;
+
+    fn parallel_unit(id: ParallelUnitId, worker_node_id: WorkerId) -> ParallelUnit {
+        ParallelUnit {
+            id,
+            r#type: ParallelUnitType::Hash as i32,
+            worker_node_id,
+        }
+    }
+
+    /// Creates the fragments of `table_id` with the actors on the given parallel units.
+    fn create_table_fragments(
+        table_id: u32,
+        fragments: &[(FragmentId, &[(ActorId, ParallelUnit)])],
+    ) -> TableFragments {
+        let mut actor_status = BTreeMap::new();
+        let fragments = fragments
+            .iter()
+            .map(|(fragment_id, actors)| {
+                let mapping = actors
+                    .iter()
+                    .map(|(_, parallel_unit)| parallel_unit.id)
+                    .collect_vec();
+                let (original_indices, data) = compress_data(&mapping);
+                let fragment = Fragment {
+                    fragment_id: *fragment_id,
+                    actors: actors
+                        .iter()
+                        .map(|(actor_id, parallel_unit)| {
+                            actor_status.insert(
+                                *actor_id,
+                                ActorStatus {
+                                    parallel_unit: Some(parallel_unit.clone()),
+                                    state: ActorState::Running as i32,
+                                },
+                            );
+                            StreamActor {
+                                actor_id: *actor_id,
+                                fragment_id: *fragment_id,
+                                ..Default::default()
+                            }
+                        })
+                        .collect(),
+                    vnode_mapping: Some(ParallelUnitMapping {
+                        table_id,
+                        original_indices,
+                        data,
+                    }),
+                    ..Default::default()
+                };
+                (*fragment_id, fragment)
+            })
+            .collect();
+        let mut table_fragments =
+            TableFragments::new(TableId::new(table_id), fragments, HashSet::new());
+        table_fragments.set_actor_status(actor_status);
+        table_fragments
+    }
+
+    #[tokio::test]
+    async fn test_migrate_actors() -> Result<()> {
+        let env = MetaSrvEnv::for_test().await;
+        let compaction_group_manager = Arc::new(CompactionGroupManager::new(env.clone()).await?);
+        let fragment_manager = FragmentManager::new(env.clone(), compaction_group_manager).await?;
+
+        // Each worker has two parallel units, and worker 3 with parallel units 4 and 5 is deleted.
+        // Table 11 reads table 10 by the chain actors of fragment 2, which are on the same
+        // parallel units as their upstream actors.
+        let pu = |id| parallel_unit(id, id / 2 + 1);
+        fragment_manager
+            .start_create_table_fragments(create_table_fragments(
+                10,
+                &[(1, &[(1, pu(0)), (2, pu(4)), (3, pu(5))])],
+            ))
+            .await?;
+        fragment_manager
+            .start_create_table_fragments(create_table_fragments(
+                11,
+                &[
+                    (2, &[(4, pu(0)), (5, pu(4)), (6, pu(5))]),
+                    (3, &[(7, pu(1)), (8, pu(4))]),
+                ],
+            ))
+            .await?;
+
+        let to = (0..4).map(pu).collect_vec();
+        let mut migrated = fragment_manager.migrate_actors(3, &to).await?;
+        migrated.sort_by_key(|table_id| table_id.table_id);
+        assert_eq!(migrated, vec![TableId::new(10), TableId::new(11)]);
+
+        let mut actor_parallel_units = HashMap::new();
+        for table_fragments in fragment_manager.list_table_fragments().await? {
+            for (actor_id, status) in table_fragments.to_protobuf().actor_status {
+                actor_parallel_units.insert(actor_id, status.get_parallel_unit().unwrap().id);
+            }
+        }
+        // Parallel unit 0 and 1 are used by fragment 1 and 3, so the actors on parallel unit 4
+        // are moved to 2, and the ones on parallel unit 5 to the next free one 3.
+        for (actor_id, parallel_unit_id) in [
+            (1, 0),
+            (2, 2),
+            (3, 3),
+            (4, 0),
+            (5, 2),
+            (6, 3),
+            (7, 1),
+            (8, 2),
+        ] {
+            assert_eq!(actor_parallel_units[&actor_id], parallel_unit_id);
+        }
+        assert_eq!(
+            env.hash_mapping_manager_ref()
+                .get_fragment_hash_mapping(&1)
+                .unwrap(),
+            vec![0, 2, 3]
+        );
+        assert_eq!(
+            env.hash_mapping_manager_ref()
+                .get_fragment_hash_mapping(&3)
+                .unwrap(),
+            vec![1, 2]
+        );
+
+        // Nothing is left on the deleted worker.
+        assert!(fragment_manager.migrate_actors(3, &to).await?.is_empty());
+        // Parallel unit 2 is used by all fragments on worker 1.
+        assert!(fragment_manager.migrate_actors(1, &[pu(2)]).await.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_restore_vnode_mappings_with_non_default_count() {
+        // A cluster created with fewer vnodes than the default is restarted.
+        let vnode_count = MIN_VIRTUAL_NODE_COUNT;
+        let mapping: Vec<ParallelUnitId> = (0..vnode_count as u32).map(|vnode| vnode % 3).collect();
+        let (original_indices, data) = compress_data(&mapping);
+        let fragment = Fragment {
+            fragment_id: 1,
+            actors: vec![StreamActor {
+                actor_id: 1,
+                fragment_id: 1,
+                nodes: Some(StreamNode {
+                    node_body: Some(NodeBody::Materialize(MaterializeNode {
+                        table_ref_id: Some(TableRefId {
+                            table_id: 10,
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    })),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }],
+            vnode_mapping: Some(ParallelUnitMapping {
+                table_id: 10,
+                original_indices,
+                data,
+            }),
+            ..Default::default()
+        };
+        let table_fragments = HashMap::from([(
+            TableId::new(10),
+            TableFragments::new(
+                TableId::new(10),
+                BTreeMap::from([(1, fragment)]),
+                HashSet::new(),
+            ),
+        )]);
+
+        let hash_mapping_manager = Arc::new(HashMappingManager::new());
+        FragmentManager::<MemStore>::restore_vnode_mappings(
+            hash_mapping_manager.clone(),
+            &table_fragments,
+            vnode_count,
+        )
+        .unwrap();
+        assert_eq!(
+            hash_mapping_manager.get_fragment_hash_mapping(&1).unwrap(),
+            mapping
+        );
+        assert_eq!(
+            hash_mapping_manager.get_table_hash_mapping(&10).unwrap(),
+            mapping
+        );
+    }
+}
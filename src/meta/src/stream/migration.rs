@@ -0,0 +1,106 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use itertools::Itertools;
+use risingwave_pb::common::WorkerType;
+use tokio::sync::oneshot::Sender;
+use tokio::task::JoinHandle;
+
+use crate::cluster::ClusterManagerRef;
+use crate::manager::{LocalNotification, NotificationManagerRef};
+use crate::storage::MetaStore;
+use crate::stream::FragmentManagerRef;
+
+const MIGRATION_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Starts a task to reschedule the actors on the compute nodes evicted from the cluster, e.g. when
+/// their heartbeat leases expire. The actors are moved to the free parallel units of the remaining
+/// nodes of the same resource group, so that the recovery of the barrier manager can rebuild them
+/// there instead of waiting for the dead nodes to come back. If there aren't enough free parallel
+/// units, the migration is retried, e.g. until a new node joins.
+pub async fn start_actor_migration<S>(
+    fragment_manager: FragmentManagerRef<S>,
+    cluster_manager: ClusterManagerRef<S>,
+    notification_manager: NotificationManagerRef,
+) -> (JoinHandle<()>, Sender<()>)
+where
+    S: MetaStore,
+{
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    notification_manager.insert_local_sender(tx).await;
+    let (shutdown_tx, mut shutdown_rx) = tokio::sync::oneshot::channel();
+    let join_handle = tokio::spawn(async move {
+        loop {
+            let worker_node = tokio::select! {
+                notification = rx.recv() => {
+                    match notification {
+                        None => {
+                            return;
+                        }
+                        Some(LocalNotification::WorkerDeletion(worker_node)) => worker_node
+                    }
+                }
+                _ = &mut shutdown_rx => {
+                    return;
+                }
+            };
+            if worker_node.r#type != WorkerType::ComputeNode as i32 {
+                continue;
+            }
+
+            // Retry until there are enough free parallel units to migrate the actors to.
+            loop {
+                let targets = cluster_manager
+                    .list_schedulable_worker_node(WorkerType::ComputeNode)
+                    .await
+                    .into_iter()
+                    .filter(|node| node.resource_group == worker_node.resource_group)
+                    .flat_map(|node| node.parallel_units)
+                    .collect_vec();
+                match fragment_manager
+                    .migrate_actors(worker_node.id, &targets)
+                    .await
+                {
+                    Ok(tables) => {
+                        if !tables.is_empty() {
+                            tracing::info!(
+                                "Migrated actors of tables {:?} from evicted worker {} to {:?}",
+                                tables,
+                                worker_node.id,
+                                targets.iter().map(|p| p.worker_node_id).unique().collect_vec()
+                            );
+                        }
+                        break;
+                    }
+                    Err(err) => {
+                        tracing::warn!(
+                            "Failed to migrate actors from evicted worker {}: {}. Will retry.",
+                            worker_node.id,
+                            err
+                        );
+                    }
+                }
+                tokio::select! {
+                    _ = tokio::time::sleep(MIGRATION_RETRY_INTERVAL) => {}
+                    _ = &mut shutdown_rx => {
+                        return;
+                    }
+                }
+            }
+        }
+    });
+    (join_handle, shutdown_tx)
+}
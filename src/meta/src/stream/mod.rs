@@ -12,21 +12,27 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod auto_parallelism;
 mod meta;
+mod migration;
 mod scheduler;
 mod source_manager;
+mod state_reuse;
 mod stream_graph;
 mod stream_manager;
 
 #[cfg(test)]
 mod test_fragmenter;
 
+pub use auto_parallelism::*;
 pub use meta::*;
+pub use migration::*;
 use risingwave_common::error::Result;
 use risingwave_pb::stream_plan::stream_node::NodeBody;
 use risingwave_pb::stream_plan::StreamNode;
 pub use scheduler::*;
 pub use source_manager::*;
+pub use state_reuse::*;
 pub use stream_graph::*;
 pub use stream_manager::*;
 
@@ -61,6 +67,24 @@ pub fn record_table_vnode_mappings(
             hash_mapping_manager
                 .set_fragment_state_table(fragment_id, node.right_table.as_ref().unwrap().id);
         }
+        NodeBody::DynamicFilter(node) => {
+            hash_mapping_manager
+                .set_fragment_state_table(fragment_id, node.left_table.as_ref().unwrap().id);
+        }
+        NodeBody::AsOfJoin(node) => {
+            hash_mapping_manager
+                .set_fragment_state_table(fragment_id, node.left_table.as_ref().unwrap().id);
+            hash_mapping_manager
+                .set_fragment_state_table(fragment_id, node.right_table.as_ref().unwrap().id);
+        }
+        NodeBody::MatchRecognize(node) => {
+            hash_mapping_manager
+                .set_fragment_state_table(fragment_id, node.state_table.as_ref().unwrap().id);
+        }
+        NodeBody::GapFill(node) => {
+            hash_mapping_manager
+                .set_fragment_state_table(fragment_id, node.state_table.as_ref().unwrap().id);
+        }
         _ => {}
     }
     let input_nodes = stream_node.get_input();
@@ -16,9 +16,10 @@ use std::collections::{BTreeMap, HashMap};
 use std::sync::atomic::{AtomicUsize, Ordering};
 
 use risingwave_common::buffer::BitmapBuilder;
+use risingwave_common::catalog::{TableId, DEFAULT_RESOURCE_GROUP};
 use risingwave_common::error::ErrorCode::InternalError;
 use risingwave_common::error::{internal_error, Result};
-use risingwave_common::types::VIRTUAL_NODE_COUNT;
+use risingwave_common::types::ParallelUnitId;
 use risingwave_common::util::compress::compress_data;
 use risingwave_pb::common::{ActorInfo, ParallelUnit, ParallelUnitMapping, ParallelUnitType};
 use risingwave_pb::meta::table_fragments::fragment::FragmentDistributionType;
@@ -27,7 +28,7 @@ use risingwave_pb::meta::table_fragments::Fragment;
 use super::record_table_vnode_mappings;
 use crate::cluster::{ClusterManagerRef, WorkerId, WorkerLocations};
 use crate::manager::HashMappingManagerRef;
-use crate::model::ActorId;
+use crate::model::{ActorId, TableFragments};
 use crate::storage::MetaStore;
 
 /// [`Scheduler`] defines schedule logic for mv actors.
@@ -44,6 +45,10 @@ pub struct ScheduledLocations {
     pub actor_locations: BTreeMap<ActorId, ParallelUnit>,
     /// worker location map.
     pub node_locations: WorkerLocations,
+    /// The resource group of the compute nodes to schedule the actors to.
+    pub resource_group: String,
+    /// The parallel units of the sink actors of the upstream materialized views, by table id.
+    pub upstream_parallel_units: HashMap<TableId, Vec<ParallelUnitId>>,
 }
 
 impl ScheduledLocations {
@@ -51,6 +56,8 @@ impl ScheduledLocations {
         Self {
             actor_locations: BTreeMap::new(),
             node_locations: HashMap::new(),
+            resource_group: DEFAULT_RESOURCE_GROUP.to_string(),
+            upstream_parallel_units: HashMap::new(),
         }
     }
 
@@ -145,9 +152,12 @@ where
     /// [`Self::schedule`] schedules input fragments to different parallel units (workers).
     /// The schedule procedure is two-fold:
     /// (1) For singleton fragments, we apply the round robin strategy. One single parallel unit in
-    /// the cluster is assigned to a singleton fragment once, and all the single parallel units take
-    /// turns.
-    /// (2) For normal fragments, we schedule them to all the hash parallel units in the cluster.
+    /// the resource group is assigned to a singleton fragment once, and all the single parallel
+    /// units take turns.
+    /// (2) For normal fragments, we schedule them to all the hash parallel units in the resource
+    /// group.
+    /// Fragments with chain nodes are scheduled to the parallel units of their upstream instead,
+    /// since each chain actor reads the upstream actor on the same parallel unit.
     pub async fn schedule(
         &self,
         fragment: &mut Fragment,
@@ -177,15 +187,16 @@ where
             } else {
                 // Choose one parallel unit to schedule from single parallel units.
                 let single_parallel_units = self
-                    .cluster_manager
-                    .list_parallel_units(Some(ParallelUnitType::Single))
-                    .await;
+                    .candidate_parallel_units(fragment, locations, ParallelUnitType::Single)
+                    .await?;
                 let single_idx = self
                     .single_rr
                     .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |idx| {
                         Some((idx + 1) % single_parallel_units.len())
                     })
-                    .map_err(|_| internal_error("failed to round robin id"))?;
+                    .map_err(|_| internal_error("failed to round robin id"))?
+                    // The counter is shared by resource groups of different sizes.
+                    % single_parallel_units.len();
 
                 // Build vnode mapping.
                 self.set_fragment_vnode_mapping(
@@ -202,11 +213,10 @@ where
         } else {
             // Normal fragment
 
-            // Find out all the hash parallel units in the cluster.
+            // Find out all the hash parallel units in the resource group.
             let mut parallel_units = self
-                .cluster_manager
-                .list_parallel_units(Some(ParallelUnitType::Hash))
-                .await;
+                .candidate_parallel_units(fragment, locations, ParallelUnitType::Hash)
+                .await?;
             // FIXME(Kexiang): select appropriate parallel_units, currently only support
             // `parallel_degree < parallel_units.size()`
             parallel_units.truncate(fragment.actors.len());
@@ -227,7 +237,7 @@ where
                 .for_each(|(vnode, parallel_unit)| {
                     vnode_bitmaps
                         .entry(*parallel_unit)
-                        .or_insert_with(|| BitmapBuilder::zeroed(VIRTUAL_NODE_COUNT))
+                        .or_insert_with(|| BitmapBuilder::zeroed(vnode_mapping.len()))
                         .set(vnode, true);
                 });
             let vnode_bitmaps = vnode_bitmaps
@@ -263,6 +273,48 @@ where
         Ok(())
     }
 
+    /// Returns the parallel units of the given type that the fragment can be scheduled to, which
+    /// are the ones in the resource group, or the ones of the upstream if the fragment has a chain
+    /// node.
+    async fn candidate_parallel_units(
+        &self,
+        fragment: &Fragment,
+        locations: &ScheduledLocations,
+        parallel_unit_type: ParallelUnitType,
+    ) -> Result<Vec<ParallelUnit>> {
+        let chain_table_id = fragment
+            .actors
+            .first()
+            .and_then(|actor| actor.nodes.as_ref())
+            .and_then(TableFragments::fetch_chain_table_id);
+        let parallel_units = match chain_table_id
+            .and_then(|table_id| locations.upstream_parallel_units.get(&table_id))
+        {
+            Some(upstream_parallel_units) => self
+                .cluster_manager
+                .list_parallel_units(Some(parallel_unit_type))
+                .await
+                .into_iter()
+                .filter(|parallel_unit| upstream_parallel_units.contains(&parallel_unit.id))
+                .collect(),
+            None => {
+                self.cluster_manager
+                    .list_resource_group_parallel_units(
+                        parallel_unit_type,
+                        &locations.resource_group,
+                    )
+                    .await
+            }
+        };
+        if parallel_units.is_empty() {
+            return Err(internal_error(format!(
+                "no {:?} parallel unit in resource group {} to schedule fragment {}",
+                parallel_unit_type, locations.resource_group, fragment.fragment_id
+            )));
+        }
+        Ok(parallel_units)
+    }
+
     /// `set_fragment_vnode_mapping` works by following steps:
     /// 1. Build a vnode mapping according to parallel units where the fragment is scheduled.
     /// 2. Set the vnode mapping into the fragment.
@@ -353,6 +405,7 @@ mod test {
                         upstream_actor_id: vec![],
                         same_worker_node_as_upstream: false,
                         vnode_bitmap: None,
+                        table_id: 0,
                     }],
                     vnode_mapping: None,
                 };
@@ -382,6 +435,7 @@ mod test {
                         upstream_actor_id: vec![],
                         same_worker_node_as_upstream: false,
                         vnode_bitmap: None,
+                        table_id: 0,
                     })
                     .collect_vec();
                 actor_id += node_count * 7;
@@ -460,4 +514,86 @@ mod test {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_schedule_resource_group() -> Result<()> {
+        let env = MetaSrvEnv::for_test().await;
+        let cluster_manager =
+            Arc::new(ClusterManager::new(env.clone(), Duration::from_secs(3600)).await?);
+
+        let mut reporting_workers = vec![];
+        for (port, resource_group) in [(0, ""), (1, "reporting"), (2, "reporting"), (3, "")] {
+            let host = HostAddress {
+                host: "127.0.0.1".to_string(),
+                port,
+            };
+            let (worker_node, _) = cluster_manager
+                .add_worker_node_in_resource_group(
+                    host.clone(),
+                    WorkerType::ComputeNode,
+                    resource_group,
+                )
+                .await?;
+            cluster_manager.activate_worker_node(host).await?;
+            if resource_group == "reporting" {
+                reporting_workers.push(worker_node.id);
+            }
+        }
+        let parallel_degree = DEFAULT_WORK_NODE_PARALLEL_DEGREE - 1;
+        assert_eq!(
+            cluster_manager
+                .list_resource_group_parallel_units(ParallelUnitType::Hash, "reporting")
+                .await
+                .len(),
+            2 * parallel_degree
+        );
+
+        let scheduler = Scheduler::new(cluster_manager, env.hash_mapping_manager_ref());
+        let mut locations = ScheduledLocations::new();
+        locations.resource_group = "reporting".to_string();
+
+        let mut fragment = Fragment {
+            fragment_id: 1,
+            fragment_type: 0,
+            distribution_type: FragmentDistributionType::Hash as i32,
+            actors: (1..=2 * parallel_degree as u32)
+                .map(|actor_id| StreamActor {
+                    actor_id,
+                    fragment_id: 1,
+                    nodes: Some(StreamNode {
+                        node_body: Some(NodeBody::Materialize(MaterializeNode {
+                            table_ref_id: Some(TableRefId {
+                                table_id: 1,
+                                ..Default::default()
+                            }),
+                            ..Default::default()
+                        })),
+                        ..Default::default()
+                    }),
+                    dispatcher: vec![],
+                    upstream_actor_id: vec![],
+                    same_worker_node_as_upstream: false,
+                    vnode_bitmap: None,
+                    table_id: 0,
+                })
+                .collect_vec(),
+            vnode_mapping: None,
+        };
+        scheduler.schedule(&mut fragment, &mut locations).await?;
+        assert_eq!(locations.actor_locations.len(), 2 * parallel_degree);
+        assert!(locations
+            .actor_locations
+            .values()
+            .all(|parallel_unit| reporting_workers.contains(&parallel_unit.worker_node_id)));
+
+        // No compute node is in the resource group.
+        locations.resource_group = "unknown".to_string();
+        fragment.fragment_id = 2;
+        assert!(scheduler
+            .schedule(&mut fragment, &mut locations)
+            .await
+            .is_err());
+
+        Ok(())
+    }
 }
@@ -13,8 +13,9 @@
 // limitations under the License.
 
 use std::borrow::BorrowMut;
+use std::cmp::Reverse;
 use std::collections::hash_map::Entry;
-use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet};
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -350,51 +351,65 @@ pub(crate) fn fetch_source_fragments(
     }
 }
 
-// todo use min heap to optimize
+/// Reassigns the splits of a source fragment to its actors after the splits discovered from the
+/// connector change, returning the new splits of the changed actors, or `None` if nothing changes.
+///
+/// Splits no longer discovered, e.g. the parent shards closed by a Kinesis reshard, are unassigned,
+/// and the new ones, e.g. the partitions added to a Kafka topic, go to the actors with the fewest
+/// splits. The splits being read are never moved to another actor, as their offsets are only
+/// durable in the source state after the barrier carrying the change.
 fn diff_splits(
-    mut prev_actor_splits: HashMap<ActorId, Vec<SplitImpl>>,
+    prev_actor_splits: HashMap<ActorId, Vec<SplitImpl>>,
     discovered_splits: &BTreeMap<String, SplitImpl>,
 ) -> Option<HashMap<ActorId, Vec<SplitImpl>>> {
+    if prev_actor_splits.is_empty() {
+        return None;
+    }
     let prev_split_ids: HashSet<_> = prev_actor_splits
         .values()
         .flat_map(|splits| splits.iter().map(SplitImpl::id))
         .collect();
 
-    if discovered_splits
-        .keys()
-        .all(|split_id| prev_split_ids.contains(split_id))
-    {
-        return None;
-    }
+    let mut changed_actors = HashSet::new();
+    let mut actor_splits: BTreeMap<_, _> = prev_actor_splits
+        .into_iter()
+        .map(|(actor_id, splits)| {
+            let prev_len = splits.len();
+            let splits = splits
+                .into_iter()
+                .filter(|split| discovered_splits.contains_key(&split.id()))
+                .collect_vec();
+            if splits.len() != prev_len {
+                changed_actors.insert(actor_id);
+            }
+            (actor_id, splits)
+        })
+        .collect();
 
-    let mut new_discovered_splits = HashSet::new();
+    // Pop the actor with the fewest splits, breaking ties by the smallest actor id.
+    let mut heap: BinaryHeap<_> = actor_splits
+        .iter()
+        .map(|(&actor_id, splits)| Reverse((splits.len(), actor_id)))
+        .collect();
     for (split_id, split) in discovered_splits {
-        if !prev_split_ids.contains(split_id) {
-            new_discovered_splits.insert(split.id());
+        if prev_split_ids.contains(split_id) {
+            continue;
         }
+        let Reverse((len, actor_id)) = heap.pop().unwrap();
+        actor_splits.get_mut(&actor_id).unwrap().push(split.clone());
+        changed_actors.insert(actor_id);
+        heap.push(Reverse((len + 1, actor_id)));
     }
 
-    let mut result = HashMap::new();
-
-    let mut actors = prev_actor_splits.keys().cloned().collect_vec();
-
-    // sort actors
-    actors.sort();
-
-    let actor_len = actors.len();
-
-    for (index, split_id) in new_discovered_splits.into_iter().enumerate() {
-        let target_actor_id = actors[index % actor_len];
-        let split = discovered_splits.get(&split_id).unwrap().clone();
-
-        result
-            .entry(target_actor_id)
-            .or_insert_with(|| prev_actor_splits.remove(&target_actor_id).unwrap());
-
-        result.get_mut(&target_actor_id).unwrap().push(split);
+    if changed_actors.is_empty() {
+        return None;
     }
-
-    Some(result)
+    Some(
+        actor_splits
+            .into_iter()
+            .filter(|(actor_id, _)| changed_actors.contains(actor_id))
+            .collect(),
+    )
 }
 
 impl<S> SourceManager<S>
@@ -505,10 +520,13 @@ where
         Ok(())
     }
 
+    /// Assigns the splits discovered from the connectors to the actors of a new streaming job. The
+    /// splits found in `initial_splits` start from the offsets given there.
     pub async fn pre_allocate_splits(
         &self,
         table_id: &TableId,
         source_fragments: HashMap<SourceId, BTreeSet<FragmentId>>,
+        initial_splits: &HashMap<SourceId, BTreeMap<String, SplitImpl>>,
     ) -> Result<HashMap<ActorId, Vec<SplitImpl>>> {
         let core = self.core.lock().await;
         let table_fragments = core
@@ -532,6 +550,14 @@ where
             }
 
             if let Some(splits) = &handle.splits.lock().await.splits {
+                let mut splits = splits.clone();
+                for (split_id, initial_split) in
+                    initial_splits.get(&source_id).into_iter().flatten()
+                {
+                    if let Some(split) = splits.get_mut(split_id) {
+                        *split = initial_split.clone();
+                    }
+                }
                 for fragment_id in fragments {
                     let empty_actor_splits = table_fragments
                         .fragments
@@ -544,7 +570,7 @@ where
                         .map(|actor| (actor.actor_id, vec![]))
                         .collect();
 
-                    assigned.extend(diff_splits(empty_actor_splits, splits).unwrap());
+                    assigned.extend(diff_splits(empty_actor_splits, &splits).unwrap_or_default());
                 }
             } else {
                 unreachable!();
@@ -554,6 +580,31 @@ where
         Ok(assigned)
     }
 
+    /// Lists the splits assigned to the actors of a streaming job, grouped by source.
+    pub async fn list_assigned_splits(
+        &self,
+        table_fragments: &TableFragments,
+    ) -> HashMap<SourceId, Vec<SplitImpl>> {
+        let mut source_fragments = HashMap::new();
+        fetch_source_fragments(&mut source_fragments, table_fragments);
+
+        let core = self.core.lock().await;
+        source_fragments
+            .into_iter()
+            .map(|(source_id, fragment_ids)| {
+                let splits = fragment_ids
+                    .iter()
+                    .filter_map(|fragment_id| table_fragments.fragments.get(fragment_id))
+                    .flat_map(|fragment| &fragment.actors)
+                    .filter_map(|actor| core.actor_splits.get(&actor.actor_id))
+                    .flatten()
+                    .cloned()
+                    .collect();
+                (source_id, splits)
+            })
+            .collect()
+    }
+
     async fn all_stream_clients(&self) -> Result<impl Iterator<Item = StreamClient>> {
         // FIXME: there is gap between the compute node activate itself and source ddl operation,
         // create/drop source(non-stateful source like TableSource) before the compute node
@@ -691,6 +742,10 @@ where
                     .collect(),
             })));
 
+            log::info!(
+                "reassigning source splits of actors {:?}",
+                diff.keys().collect_vec()
+            );
             log::debug!("pushing down mutation {:#?}", command);
 
             tokio_retry::Retry::spawn(FixedInterval::new(Self::SOURCE_RETRY_INTERVAL), || async {
@@ -722,3 +777,51 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use risingwave_connector::kafka::split::KafkaSplit;
+
+    use super::*;
+
+    fn kafka_splits(partitions: impl IntoIterator<Item = i32>) -> BTreeMap<String, SplitImpl> {
+        partitions
+            .into_iter()
+            .map(|partition| {
+                let split = SplitImpl::Kafka(KafkaSplit::new(partition, None, None, "t".into()));
+                (split.id(), split)
+            })
+            .collect()
+    }
+
+    fn split_ids(splits: &[SplitImpl]) -> Vec<String> {
+        splits.iter().map(SplitImpl::id).sorted().collect()
+    }
+
+    #[test]
+    fn test_diff_splits() {
+        let empty_actor_splits = (1..=3).map(|actor_id| (actor_id, vec![])).collect();
+        let assigned = diff_splits(empty_actor_splits, &kafka_splits(0..4)).unwrap();
+        let mut lens = assigned.values().map(Vec::len).collect_vec();
+        lens.sort_unstable();
+        assert_eq!(lens, vec![1, 1, 2]);
+
+        // Nothing changes without new or removed splits.
+        assert!(diff_splits(assigned.clone(), &kafka_splits(0..4)).is_none());
+
+        // New partitions go to the actors with the fewest splits, leaving the others untouched.
+        let changed = diff_splits(assigned.clone(), &kafka_splits(0..6)).unwrap();
+        assert_eq!(changed.len(), 2);
+        for (actor_id, splits) in &changed {
+            assert_eq!(assigned[actor_id].len(), 1);
+            assert_eq!(splits.len(), 2);
+            assert_eq!(split_ids(&splits[..1]), split_ids(&assigned[actor_id]));
+        }
+
+        // Removed splits are unassigned.
+        let changed = diff_splits(assigned.clone(), &kafka_splits(1..4)).unwrap();
+        assert_eq!(changed.len(), 1);
+        let (actor_id, splits) = changed.into_iter().next().unwrap();
+        assert_eq!(splits.len(), assigned[&actor_id].len() - 1);
+    }
+}
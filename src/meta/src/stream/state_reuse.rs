@@ -0,0 +1,527 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{HashMap, HashSet};
+
+use itertools::Itertools;
+use risingwave_pb::stream_plan::lookup_node::ArrangementTableId;
+use risingwave_pb::stream_plan::stream_node::NodeBody;
+use risingwave_pb::stream_plan::{StreamFragmentGraph as StreamFragmentGraphProto, StreamNode};
+
+use crate::model::TableFragments;
+
+/// The states of the replaced job adopted by the new job of `ALTER MATERIALIZED VIEW AS`.
+#[derive(Debug, Default)]
+pub struct AdoptedStates {
+    /// Ids of the adopted internal tables, by local ids in the new fragment graph.
+    pub table_ids: HashMap<u32, u32>,
+    /// Operator ids of the chains under the adopted aggregations, which backfill only the changes
+    /// made after the replaced job is stopped.
+    pub chain_operator_ids: HashSet<u64>,
+}
+
+impl AdoptedStates {
+    pub fn is_empty(&self) -> bool {
+        self.table_ids.is_empty()
+    }
+}
+
+/// Finds the aggregations in `new_graph` that compute the same as ones of the replaced job, and
+/// adopts the internal tables of their whole subplans. The adopted aggregations are marked to
+/// re-emit their states to the new downstream.
+///
+/// An aggregation is adopted only if its subplan, including the upstream fragments, equals the one
+/// of the replaced job except for the ids. Subplans with sources or arrangements are never adopted.
+pub fn adopt_states(
+    new_graph: &mut StreamFragmentGraphProto,
+    old_table_fragments: &TableFragments,
+) -> AdoptedStates {
+    let mut adopted = AdoptedStates::default();
+    let Some(old_graph) = old_table_fragments.fragment_graph() else {
+        return adopted;
+    };
+    let old_table_ids = actual_table_ids(old_table_fragments, old_graph);
+
+    let mut old_aggs = vec![];
+    for fragment in old_graph.fragments.values() {
+        collect_aggs(fragment.node.as_ref().unwrap(), &mut old_aggs);
+    }
+    let old_aggs = old_aggs
+        .into_iter()
+        .filter_map(|node| Some((node, masked_plan(old_graph, node)?)))
+        .collect_vec();
+
+    let mut frontiers = vec![];
+    if let Some(root) = new_graph.fragments.values().find(|fragment| {
+        !new_graph
+            .edges
+            .iter()
+            .any(|e| e.upstream_id == fragment.fragment_id)
+    }) {
+        find_frontiers(
+            new_graph,
+            root.node.as_ref().unwrap(),
+            &old_aggs,
+            &mut HashSet::new(),
+            &mut frontiers,
+        );
+    }
+
+    let mut reemit_operator_ids = HashSet::new();
+    for (new_node, old_node) in frontiers {
+        let mut table_ids = vec![];
+        let mut chain_operator_ids = HashSet::new();
+        pair_states(
+            new_graph,
+            new_node,
+            old_graph,
+            old_node,
+            &mut table_ids,
+            &mut chain_operator_ids,
+        );
+        let Some(table_ids) = table_ids
+            .into_iter()
+            .map(|(new_id, old_id)| Some((new_id, *old_table_ids.get(&old_id)?)))
+            .collect::<Option<Vec<_>>>()
+        else {
+            continue;
+        };
+        adopted.table_ids.extend(table_ids);
+        adopted.chain_operator_ids.extend(chain_operator_ids);
+        reemit_operator_ids.insert(new_node.operator_id);
+    }
+
+    for fragment in new_graph.fragments.values_mut() {
+        set_reemit(fragment.node.as_mut().unwrap(), &reemit_operator_ids);
+    }
+    adopted
+}
+
+/// Adopts all the states of the replaced job, so that it can be rebuilt from its own fragment graph
+/// if the new job fails to be created. The chains of the rebuilt job backfill only the changes made
+/// after the job is stopped, and no aggregation re-emits its states, since the materialized view
+/// still holds them. Returns `None` if some internal table of the job can't be located.
+pub fn adopt_all_states(
+    old_table_fragments: &TableFragments,
+) -> Option<(StreamFragmentGraphProto, AdoptedStates)> {
+    let mut old_graph = old_table_fragments.fragment_graph()?.clone();
+    let table_ids = actual_table_ids(old_table_fragments, &old_graph);
+    if table_ids.len() != old_graph.table_ids_cnt as usize {
+        return None;
+    }
+    let mut chain_operator_ids = HashSet::new();
+    for fragment in old_graph.fragments.values_mut() {
+        let node = fragment.node.as_mut().unwrap();
+        collect_chains(node, &mut chain_operator_ids);
+        // The job may itself have been created by an `ALTER`.
+        set_reemit(node, &HashSet::new());
+    }
+    Some((
+        old_graph,
+        AdoptedStates {
+            table_ids,
+            chain_operator_ids,
+        },
+    ))
+}
+
+fn internal_table_ids_mut(body: &mut NodeBody) -> Vec<&mut u32> {
+    match body {
+        NodeBody::HashJoin(node) => node
+            .left_table
+            .iter_mut()
+            .chain(&mut node.right_table)
+            .map(|table| &mut table.id)
+            .collect(),
+        NodeBody::Lookup(node) => match &mut node.arrangement_table_id {
+            Some(ArrangementTableId::TableId(table_id)) => vec![table_id],
+            _ => vec![],
+        },
+        NodeBody::Arrange(node) => vec![&mut node.table_id],
+        NodeBody::HashAgg(node) => node.internal_tables.iter_mut().map(|t| &mut t.id).collect(),
+        NodeBody::GlobalSimpleAgg(node) => {
+            node.internal_tables.iter_mut().map(|t| &mut t.id).collect()
+        }
+        NodeBody::TopN(node) | NodeBody::AppendOnlyTopN(node) => vec![&mut node.table_id],
+        NodeBody::DynamicFilter(node) => node.left_table.iter_mut().map(|t| &mut t.id).collect(),
+        NodeBody::AsOfJoin(node) => node
+            .left_table
+            .iter_mut()
+            .chain(&mut node.right_table)
+            .map(|table| &mut table.id)
+            .collect(),
+        NodeBody::MatchRecognize(node) => node.state_table.iter_mut().map(|t| &mut t.id).collect(),
+        NodeBody::GapFill(node) => node.state_table.iter_mut().map(|t| &mut t.id).collect(),
+        _ => vec![],
+    }
+}
+
+/// Returns the ids of the internal tables of a node, in the order they're assigned by the
+/// fragmenter.
+fn internal_table_ids(body: &NodeBody) -> Vec<u32> {
+    internal_table_ids_mut(&mut body.clone())
+        .into_iter()
+        .map(|id| *id)
+        .collect()
+}
+
+/// Returns the root of the fragment linked by the exchange `link_id`.
+fn upstream_root(graph: &StreamFragmentGraphProto, link_id: u64) -> Option<&StreamNode> {
+    let edge = graph.edges.iter().find(|edge| edge.link_id == link_id)?;
+    graph.fragments.get(&edge.upstream_id)?.node.as_ref()
+}
+
+/// Returns the plan rooted at `node`, with the exchanges replaced by their upstream fragments and
+/// the ids irrelevant to the result masked. Returns `None` if the states of the plan can't be
+/// adopted.
+fn masked_plan(graph: &StreamFragmentGraphProto, node: &StreamNode) -> Option<StreamNode> {
+    let mut body = node.node_body.clone()?;
+    match &mut body {
+        NodeBody::Source(_)
+        | NodeBody::Arrange(_)
+        | NodeBody::Lookup(_)
+        | NodeBody::DeltaIndexJoin(_) => return None,
+        NodeBody::HashAgg(node) => node.reemit = false,
+        NodeBody::GlobalSimpleAgg(node) => node.reemit = false,
+        NodeBody::Materialize(node) => node.table_ref_id = None,
+        _ => {}
+    }
+    for table_id in internal_table_ids_mut(&mut body) {
+        *table_id = 0;
+    }
+    let input = match &body {
+        NodeBody::Exchange(_) => vec![masked_plan(graph, upstream_root(graph, node.operator_id)?)?],
+        _ => node
+            .input
+            .iter()
+            .map(|input| masked_plan(graph, input))
+            .collect::<Option<_>>()?,
+    };
+    Some(StreamNode {
+        input,
+        pk_indices: node.pk_indices.clone(),
+        node_body: Some(body),
+        operator_id: 0,
+        identity: String::new(),
+        fields: node.fields.clone(),
+        append_only: node.append_only,
+    })
+}
+
+/// Collects the aggregations in a fragment.
+fn collect_aggs<'a>(node: &'a StreamNode, aggs: &mut Vec<&'a StreamNode>) {
+    if let Some(NodeBody::HashAgg(_) | NodeBody::GlobalSimpleAgg(_)) = node.node_body {
+        aggs.push(node);
+    }
+    for input in &node.input {
+        collect_aggs(input, aggs);
+    }
+}
+
+/// Collects the operator ids of the chains in a fragment.
+fn collect_chains(node: &StreamNode, chain_operator_ids: &mut HashSet<u64>) {
+    if let Some(NodeBody::Chain(_)) = node.node_body {
+        chain_operator_ids.insert(node.operator_id);
+    }
+    for input in &node.input {
+        collect_chains(input, chain_operator_ids);
+    }
+}
+
+/// Finds the topmost aggregations that compute the same as the old ones, paired with them.
+fn find_frontiers<'a, 'b>(
+    graph: &'a StreamFragmentGraphProto,
+    node: &'a StreamNode,
+    old_aggs: &[(&'b StreamNode, StreamNode)],
+    used: &mut HashSet<usize>,
+    frontiers: &mut Vec<(&'a StreamNode, &'b StreamNode)>,
+) {
+    if let Some(NodeBody::HashAgg(_) | NodeBody::GlobalSimpleAgg(_)) = node.node_body
+        && let Some(masked) = masked_plan(graph, node)
+        && let Some(idx) =
+            (0..old_aggs.len()).find(|i| !used.contains(i) && old_aggs[*i].1 == masked)
+    {
+        used.insert(idx);
+        frontiers.push((node, old_aggs[idx].0));
+        return;
+    }
+    if let Some(NodeBody::Exchange(_)) = node.node_body {
+        if let Some(upstream) = upstream_root(graph, node.operator_id) {
+            find_frontiers(graph, upstream, old_aggs, used, frontiers);
+        }
+        return;
+    }
+    for input in &node.input {
+        find_frontiers(graph, input, old_aggs, used, frontiers);
+    }
+}
+
+/// Pairs the internal tables of two plans with equal masked plans, by local ids, and collects the
+/// chains of the new plan.
+fn pair_states(
+    new_graph: &StreamFragmentGraphProto,
+    new_node: &StreamNode,
+    old_graph: &StreamFragmentGraphProto,
+    old_node: &StreamNode,
+    table_ids: &mut Vec<(u32, u32)>,
+    chain_operator_ids: &mut HashSet<u64>,
+) {
+    let (Some(new_body), Some(old_body)) = (&new_node.node_body, &old_node.node_body) else {
+        return;
+    };
+    table_ids.extend(
+        internal_table_ids(new_body)
+            .into_iter()
+            .zip_eq(internal_table_ids(old_body)),
+    );
+    match new_body {
+        NodeBody::Chain(_) => {
+            chain_operator_ids.insert(new_node.operator_id);
+        }
+        NodeBody::Exchange(_) => {
+            if let Some(new_upstream) = upstream_root(new_graph, new_node.operator_id)
+                && let Some(old_upstream) = upstream_root(old_graph, old_node.operator_id)
+            {
+                pair_states(
+                    new_graph,
+                    new_upstream,
+                    old_graph,
+                    old_upstream,
+                    table_ids,
+                    chain_operator_ids,
+                );
+            }
+        }
+        _ => {
+            for (new_input, old_input) in new_node.input.iter().zip_eq(&old_node.input) {
+                pair_states(
+                    new_graph,
+                    new_input,
+                    old_graph,
+                    old_input,
+                    table_ids,
+                    chain_operator_ids,
+                );
+            }
+        }
+    }
+}
+
+/// Returns the global ids of the internal tables of a job, by local ids in its fragment graph.
+fn actual_table_ids(
+    table_fragments: &TableFragments,
+    graph: &StreamFragmentGraphProto,
+) -> HashMap<u32, u32> {
+    fn pair(local: &StreamNode, actual: &StreamNode, table_ids: &mut HashMap<u32, u32>) {
+        let (Some(local_body), Some(actual_body)) = (&local.node_body, &actual.node_body) else {
+            return;
+        };
+        table_ids.extend(
+            internal_table_ids(local_body)
+                .into_iter()
+                .zip(internal_table_ids(actual_body)),
+        );
+        if let NodeBody::Exchange(_) | NodeBody::Chain(_) = local_body {
+            return;
+        }
+        for (local_input, actual_input) in local.input.iter().zip(&actual.input) {
+            pair(local_input, actual_input, table_ids);
+        }
+    }
+
+    // Fragments are assigned with consecutive global ids in the order of their local ids.
+    let mut table_ids = HashMap::new();
+    let Some(&fragment_id_offset) = table_fragments.fragments.keys().next() else {
+        return table_ids;
+    };
+    for (local_id, fragment) in &graph.fragments {
+        if let Some(actor) = table_fragments
+            .fragments
+            .get(&(local_id + fragment_id_offset))
+            .and_then(|fragment| fragment.actors.first())
+        {
+            pair(
+                fragment.node.as_ref().unwrap(),
+                actor.nodes.as_ref().unwrap(),
+                &mut table_ids,
+            );
+        }
+    }
+    table_ids
+}
+
+/// Marks exactly the aggregations in `operator_ids` to re-emit their states.
+fn set_reemit(node: &mut StreamNode, operator_ids: &HashSet<u64>) {
+    let reemit = operator_ids.contains(&node.operator_id);
+    match node.node_body.as_mut().unwrap() {
+        NodeBody::HashAgg(node) => node.reemit = reemit,
+        NodeBody::GlobalSimpleAgg(node) => node.reemit = reemit,
+        _ => {}
+    }
+    for input in &mut node.input {
+        set_reemit(input, operator_ids);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use risingwave_common::catalog::TableId;
+    use risingwave_pb::catalog::Table;
+    use risingwave_pb::meta::table_fragments::Fragment;
+    use risingwave_pb::plan_common::TableRefId;
+    use risingwave_pb::stream_plan::stream_fragment_graph::{StreamFragment, StreamFragmentEdge};
+    use risingwave_pb::stream_plan::{
+        ChainNode, ExchangeNode, HashAggNode, MaterializeNode, StreamActor,
+    };
+
+    use super::*;
+
+    /// Builds `Materialize <- HashAgg <- Exchange <- Chain` in two fragments, with the operator
+    /// ids starting from `operator_id`.
+    fn agg_graph(
+        mview_id: i32,
+        operator_id: u64,
+        distribution_keys: Vec<u32>,
+    ) -> StreamFragmentGraphProto {
+        let chain = StreamNode {
+            node_body: Some(NodeBody::Chain(ChainNode {
+                table_ref_id: Some(TableRefId {
+                    table_id: 1,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })),
+            operator_id,
+            ..Default::default()
+        };
+        let exchange = StreamNode {
+            node_body: Some(NodeBody::Exchange(ExchangeNode::default())),
+            operator_id: operator_id + 1,
+            ..Default::default()
+        };
+        let agg = StreamNode {
+            node_body: Some(NodeBody::HashAgg(HashAggNode {
+                distribution_keys,
+                internal_tables: vec![Table::default()],
+                ..Default::default()
+            })),
+            operator_id: operator_id + 2,
+            input: vec![exchange],
+            ..Default::default()
+        };
+        let materialize = StreamNode {
+            node_body: Some(NodeBody::Materialize(MaterializeNode {
+                table_ref_id: Some(TableRefId {
+                    table_id: mview_id,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })),
+            operator_id: operator_id + 3,
+            input: vec![agg],
+            ..Default::default()
+        };
+        StreamFragmentGraphProto {
+            fragments: [(0, materialize), (1, chain)]
+                .into_iter()
+                .map(|(fragment_id, node)| {
+                    let fragment = StreamFragment {
+                        fragment_id,
+                        node: Some(node),
+                        ..Default::default()
+                    };
+                    (fragment_id, fragment)
+                })
+                .collect(),
+            edges: vec![StreamFragmentEdge {
+                link_id: operator_id + 1,
+                upstream_id: 1,
+                downstream_id: 0,
+                ..Default::default()
+            }],
+            table_ids_cnt: 1,
+            ..Default::default()
+        }
+    }
+
+    fn old_table_fragments() -> TableFragments {
+        let old_graph = agg_graph(2, 0, vec![0]);
+        let fragments = old_graph
+            .fragments
+            .iter()
+            .map(|(local_id, fragment)| {
+                let mut node = fragment.node.clone().unwrap();
+                if let Some(NodeBody::HashAgg(agg)) = node.input[0].node_body.as_mut() {
+                    agg.internal_tables[0].id = 100;
+                }
+                let fragment = Fragment {
+                    fragment_id: local_id + 10,
+                    actors: vec![StreamActor {
+                        nodes: Some(node),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                };
+                (local_id + 10, fragment)
+            })
+            .collect::<BTreeMap<_, _>>();
+        let mut table_fragments =
+            TableFragments::new(TableId::new(2), fragments, HashSet::from([100]));
+        table_fragments.set_fragment_graph(old_graph);
+        table_fragments
+    }
+
+    #[test]
+    fn test_adopt_states() {
+        let old_table_fragments = old_table_fragments();
+
+        let mut new_graph = agg_graph(3, 10, vec![0]);
+        let adopted = adopt_states(&mut new_graph, &old_table_fragments);
+        assert_eq!(adopted.table_ids, HashMap::from([(0, 100)]));
+        assert_eq!(adopted.chain_operator_ids, HashSet::from([10]));
+        let root = new_graph.fragments[&0].node.as_ref().unwrap();
+        let Some(NodeBody::HashAgg(agg)) = &root.input[0].node_body else {
+            unreachable!()
+        };
+        assert!(agg.reemit);
+
+        // Grouped by another column.
+        let mut new_graph = agg_graph(3, 10, vec![1]);
+        let adopted = adopt_states(&mut new_graph, &old_table_fragments);
+        assert!(adopted.is_empty());
+        assert!(adopted.chain_operator_ids.is_empty());
+    }
+
+    #[test]
+    fn test_adopt_all_states() {
+        let mut old_table_fragments = old_table_fragments();
+
+        let (old_graph, adopted) = adopt_all_states(&old_table_fragments).unwrap();
+        assert_eq!(adopted.table_ids, HashMap::from([(0, 100)]));
+        assert_eq!(adopted.chain_operator_ids, HashSet::from([0]));
+        let root = old_graph.fragments[&0].node.as_ref().unwrap();
+        let Some(NodeBody::HashAgg(agg)) = &root.input[0].node_body else {
+            unreachable!()
+        };
+        assert!(!agg.reemit);
+
+        // Some internal table of the job can't be located.
+        let mut graph = agg_graph(2, 0, vec![0]);
+        graph.table_ids_cnt = 2;
+        old_table_fragments.set_fragment_graph(graph);
+        assert!(adopt_all_states(&old_table_fragments).is_none());
+    }
+}
@@ -34,7 +34,7 @@ use risingwave_pb::stream_plan::{
 use super::{BuildGraphInfo, CreateMaterializedViewContext, FragmentManagerRef};
 use crate::cluster::WorkerId;
 use crate::manager::{IdCategory, IdGeneratorManagerRef};
-use crate::model::{ActorId, FragmentId};
+use crate::model::{ActorId, FragmentId, TableFragments};
 use crate::storage::MetaStore;
 
 /// Id of an Actor, maybe local or global
@@ -337,6 +337,8 @@ impl StreamActorBuilder {
                     )| *same_worker_node,
                 ),
             vnode_bitmap: None,
+            // will be filled later when building the table fragments
+            table_id: 0,
         }
     }
 }
@@ -531,7 +533,6 @@ impl StreamGraphBuilder {
         actor_id: LocalActorId,
         upstream_actor_id: &mut HashMap<u64, OrderedActorLink>,
     ) -> Result<StreamNode> {
-        let table_id_offset = ctx.table_id_offset;
         match stream_node.get_node_body()? {
             NodeBody::Exchange(_) => {
                 panic!("ExchangeNode should be eliminated from the top of the plan node when converting fragments to actors: {:#?}", stream_node)
@@ -548,11 +549,11 @@ impl StreamGraphBuilder {
                         let mut left_table_id: u32 = 0;
                         let mut right_table_id: u32 = 0;
                         if let Some(table) = &mut node.left_table {
-                            left_table_id = table.id + table_id_offset;
+                            left_table_id = ctx.internal_table_id(table.id);
                             table.id = left_table_id;
                         }
                         if let Some(table) = &mut node.right_table {
-                            right_table_id = left_table_id + 1;
+                            right_table_id = ctx.internal_table_id(table.id);
                             table.id = right_table_id;
                         }
                         ctx.internal_table_id_set.insert(left_table_id);
@@ -563,13 +564,13 @@ impl StreamGraphBuilder {
                         if let Some(ArrangementTableId::TableId(table_id)) =
                             &mut node.arrangement_table_id
                         {
-                            *table_id += table_id_offset;
+                            *table_id = ctx.internal_table_id(*table_id);
                             ctx.internal_table_id_set.insert(*table_id);
                         }
                     }
 
                     NodeBody::Arrange(node) => {
-                        node.table_id += table_id_offset;
+                        node.table_id = ctx.internal_table_id(node.table_id);
                         ctx.internal_table_id_set.insert(node.table_id);
                     }
 
@@ -577,21 +578,50 @@ impl StreamGraphBuilder {
                         assert_eq!(node.internal_tables.len(), node.agg_calls.len());
                         // In-place update the table id. Convert from local to global.
                         for table in &mut node.internal_tables {
-                            table.id += table_id_offset;
+                            table.id = ctx.internal_table_id(table.id);
                             ctx.internal_table_id_set.insert(table.id);
                         }
                     }
 
                     NodeBody::TopN(node) | NodeBody::AppendOnlyTopN(node) => {
-                        node.table_id += table_id_offset;
+                        node.table_id = ctx.internal_table_id(node.table_id);
                         ctx.internal_table_id_set.insert(node.table_id);
                     }
 
-                    NodeBody::GlobalSimpleAgg(node) | NodeBody::LocalSimpleAgg(node) => {
+                    NodeBody::DynamicFilter(node) => {
+                        if let Some(table) = &mut node.left_table {
+                            table.id = ctx.internal_table_id(table.id);
+                            ctx.internal_table_id_set.insert(table.id);
+                        }
+                    }
+
+                    NodeBody::AsOfJoin(node) => {
+                        for table in node.left_table.iter_mut().chain(&mut node.right_table) {
+                            table.id = ctx.internal_table_id(table.id);
+                            ctx.internal_table_id_set.insert(table.id);
+                        }
+                    }
+
+                    NodeBody::MatchRecognize(node) => {
+                        if let Some(table) = &mut node.state_table {
+                            table.id = ctx.internal_table_id(table.id);
+                            ctx.internal_table_id_set.insert(table.id);
+                        }
+                    }
+
+                    NodeBody::GapFill(node) => {
+                        if let Some(table) = &mut node.state_table {
+                            table.id = ctx.internal_table_id(table.id);
+                            ctx.internal_table_id_set.insert(table.id);
+                        }
+                    }
+
+                    // The local phase of a two-phase agg is stateless and has no internal tables.
+                    NodeBody::GlobalSimpleAgg(node) => {
                         assert_eq!(node.internal_tables.len(), node.agg_calls.len());
                         // In-place update the table id. Convert from local to global.
                         for table in &mut node.internal_tables {
-                            table.id += table_id_offset;
+                            table.id = ctx.internal_table_id(table.id);
                             ctx.internal_table_id_set.insert(table.id);
                         }
                     }
@@ -758,6 +788,18 @@ impl ActorGraphBuilder {
             .collect_vec()
     }
 
+    /// Lists the upstream table of each fragment with a chain node.
+    pub fn list_fragment_chain_tables(&self) -> HashMap<FragmentId, TableId> {
+        self.fragment_graph
+            .fragments()
+            .iter()
+            .filter_map(|(id, fragment)| {
+                TableFragments::fetch_chain_table_id(fragment.node.as_ref().unwrap())
+                    .map(|table_id| (id.as_global_id(), table_id))
+            })
+            .collect()
+    }
+
     /// Build a stream graph by duplicating each fragment as parallel actors.
     async fn generate_graph_inner<S>(
         &self,
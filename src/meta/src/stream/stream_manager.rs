@@ -16,16 +16,21 @@ use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
 use std::time::Instant;
 
+use fail::fail_point;
 use itertools::Itertools;
-use log::{debug, info};
+use log::{debug, info, warn};
 use risingwave_common::catalog::TableId;
-use risingwave_common::error::{internal_error, Result};
-use risingwave_common::types::{ParallelUnitId, VIRTUAL_NODE_COUNT};
+use risingwave_common::error::{internal_error, ErrorCode, Result};
+use risingwave_common::types::{virtual_node_count, ParallelUnitId};
+use risingwave_common::util::epoch::INVALID_EPOCH;
+use risingwave_connector::SplitImpl;
+use risingwave_hummock_sdk::HummockEpoch;
 use risingwave_pb::catalog::Source;
-use risingwave_pb::common::{ActorInfo, ParallelUnitMapping, WorkerType};
+use risingwave_pb::common::{ActorInfo, ParallelUnitMapping, ParallelUnitType, WorkerType};
+use risingwave_pb::data::ConfigChange;
 use risingwave_pb::meta::table_fragments::{ActorState, ActorStatus};
 use risingwave_pb::stream_plan::stream_node::NodeBody;
-use risingwave_pb::stream_plan::{ActorMapping, DispatcherType, StreamNode};
+use risingwave_pb::stream_plan::{ActorMapping, DispatcherType, StreamActor, StreamNode};
 use risingwave_pb::stream_service::{
     BroadcastActorInfoTableRequest, BuildActorsRequest, HangingChannel, UpdateActorsRequest,
 };
@@ -35,8 +40,10 @@ use uuid::Uuid;
 use super::ScheduledLocations;
 use crate::barrier::{BarrierManagerRef, Command};
 use crate::cluster::{ClusterManagerRef, WorkerId};
-use crate::manager::{HashMappingManagerRef, MetaSrvEnv};
-use crate::model::{ActorId, DispatcherId, TableFragments};
+use crate::manager::{
+    HashMappingManagerRef, IdCategory, IdGeneratorManagerRef, MetaSrvEnv, SourceId,
+};
+use crate::model::{ActorId, DispatcherId, FragmentId, TableFragments};
 use crate::storage::MetaStore;
 use crate::stream::{fetch_source_fragments, FragmentManagerRef, Scheduler, SourceManagerRef};
 
@@ -59,6 +66,32 @@ pub struct CreateMaterializedViewContext {
     pub table_id_offset: u32,
     /// Internal TableID for MaterializedView.
     pub internal_table_id_set: HashSet<u32>,
+    /// Splits to start from instead of the ones discovered from the connectors, by source and
+    /// split id. Used when importing a job exported from another cluster.
+    pub initial_splits: HashMap<SourceId, BTreeMap<String, SplitImpl>>,
+    /// Ids of the internal tables adopted from the replaced job, by local ids. Used by `ALTER
+    /// MATERIALIZED VIEW AS` to reuse the states of compatible aggregations.
+    pub adopted_table_ids: HashMap<u32, u32>,
+}
+
+/// The job rebuilding a materialized view replaced by `ALTER MATERIALIZED VIEW AS` with all of its
+/// states, created if the new job fails to be created.
+pub struct RollbackJob {
+    pub table_fragments: TableFragments,
+    /// Operator ids of all the chains of the job, which backfill only the changes made after the
+    /// replaced job is stopped.
+    pub chain_operator_ids: HashSet<u64>,
+    pub ctx: CreateMaterializedViewContext,
+}
+
+impl CreateMaterializedViewContext {
+    /// Returns the global id of the internal table with `local_id` in the fragment graph.
+    pub fn internal_table_id(&self, local_id: u32) -> u32 {
+        self.adopted_table_ids
+            .get(&local_id)
+            .copied()
+            .unwrap_or(local_id + self.table_id_offset)
+    }
 }
 
 /// `GlobalStreamManager` manages all the streams in the system.
@@ -83,6 +116,9 @@ pub struct GlobalStreamManager<S: MetaStore> {
 
     /// Client Pool to stream service on compute nodes
     client_pool: StreamClientPoolRef,
+
+    /// Generates ids of the actors created by rescheduling.
+    id_gen_manager: IdGeneratorManagerRef<S>,
 }
 
 impl<S> GlobalStreamManager<S>
@@ -104,6 +140,7 @@ where
             source_manager,
             _hash_mapping_manager: env.hash_mapping_manager_ref(),
             client_pool: env.stream_client_pool_ref(),
+            id_gen_manager: env.id_gen_manager_ref(),
         })
     }
 
@@ -138,7 +175,11 @@ where
                 let Some(NodeBody::Chain(ref mut chain)) = stream_node.node_body else {
                     // If node is not chain node, recursively deal with input nodes
                     for input in &mut stream_node.input {
-                        self.resolve_chain_node_inner(input, actor_id, same_worker_node_as_upstream)?;
+                        self.resolve_chain_node_inner(
+                            input,
+                            actor_id,
+                            same_worker_node_as_upstream,
+                        )?;
                     }
                     return Ok(());
                 };
@@ -273,14 +314,13 @@ where
             affiliated_source: _,
             table_id_offset: _,
             internal_table_id_set: _,
+            initial_splits,
+            adopted_table_ids: _,
         }: CreateMaterializedViewContext,
     ) -> Result<()> {
         let nodes = self
             .cluster_manager
-            .list_worker_node(
-                WorkerType::ComputeNode,
-                Some(risingwave_pb::common::worker_node::State::Running),
-            )
+            .list_schedulable_worker_node(WorkerType::ComputeNode)
             .await;
         if nodes.is_empty() {
             return Err(internal_error("no available compute node in the cluster"));
@@ -288,6 +328,14 @@ where
 
         let mut locations = ScheduledLocations::new();
         locations.node_locations = nodes.into_iter().map(|node| (node.id, node)).collect();
+        locations.resource_group = table_fragments.resource_group().to_string();
+        locations.upstream_parallel_units = self
+            .fragment_manager
+            .get_sink_parallel_unit_ids(&dependent_table_ids)
+            .await?
+            .into_iter()
+            .map(|(table_id, parallel_units)| (table_id, parallel_units.into_keys().collect()))
+            .collect();
 
         let topological_order = table_fragments.generate_topological_order();
 
@@ -360,7 +408,7 @@ where
                             // implemented.
                             if downstream_actors.len() == 1 {
                                 dispatcher.hash_mapping = Some(ActorMapping {
-                                    original_indices: vec![VIRTUAL_NODE_COUNT as u64 - 1],
+                                    original_indices: vec![virtual_node_count() as u64 - 1],
                                     data: vec![downstream_actors[0]],
                                 });
                             } else {
@@ -556,7 +604,7 @@ where
         let table_id = table_fragments.table_id();
         let init_split_assignment = self
             .source_manager
-            .pre_allocate_splits(&table_id, source_fragments.clone())
+            .pre_allocate_splits(&table_id, source_fragments.clone(), &initial_splits)
             .await?;
 
         if let Err(err) = self
@@ -585,6 +633,17 @@ where
     /// Dropping materialized view is done by barrier manager. Check
     /// [`Command::DropMaterializedView`] for details.
     pub async fn drop_materialized_view(&self, table_id: &TableId) -> Result<()> {
+        self.drop_materialized_view_with_epoch(table_id)
+            .await
+            .map(|_| ())
+    }
+
+    /// Drops the materialized view like [`Self::drop_materialized_view`], and returns the epoch
+    /// that the states of its actors are last committed at.
+    pub async fn drop_materialized_view_with_epoch(
+        &self,
+        table_id: &TableId,
+    ) -> Result<HummockEpoch> {
         let table_fragments = self
             .fragment_manager
             .select_table_fragments_by_table_id(table_id)
@@ -593,8 +652,9 @@ where
         let mut source_fragments = HashMap::new();
         fetch_source_fragments(&mut source_fragments, &table_fragments);
 
-        self.barrier_manager
-            .run_command(Command::DropMaterializedView(*table_id))
+        let epoch = self
+            .barrier_manager
+            .run_command_with_epoch(Command::DropMaterializedView(*table_id))
             .await?;
 
         let mut actor_ids = HashSet::new();
@@ -610,8 +670,172 @@ where
         self.source_manager
             .drop_update(Some(source_fragments), Some(actor_ids))
             .await?;
+        if table_fragments.savepoint_epoch().is_some() {
+            self.barrier_manager.unpin_savepoint(table_id).await;
+        }
 
-        Ok(())
+        Ok(epoch)
+    }
+
+    /// Replaces the materialized view `table_id` with the new job `table_fragments`, which adopts
+    /// some internal tables of the old job. The old job is dropped first, so that the adopted
+    /// states are no longer written, and the chains in `base_epoch_chains` backfill only the
+    /// changes made after the states are last committed.
+    ///
+    /// The states are kept from compaction by a savepoint of the new job until it's created. If
+    /// creating the new job fails, the uncommitted writes to the adopted states are discarded, and
+    /// the old job is rebuilt with all of its states by `rollback`, whose chains backfill the
+    /// changes made in between the same way. Without `rollback`, the materialized view is left
+    /// without a job.
+    pub async fn replace_materialized_view(
+        &self,
+        table_id: &TableId,
+        mut table_fragments: TableFragments,
+        base_epoch_chains: &HashSet<u64>,
+        ctx: CreateMaterializedViewContext,
+        rollback: Option<RollbackJob>,
+    ) -> Result<()> {
+        let new_table_id = table_fragments.table_id();
+        self.barrier_manager.pin_savepoint(&new_table_id).await;
+        let result = async {
+            let base_epoch = self.drop_materialized_view_with_epoch(table_id).await?;
+            if base_epoch == INVALID_EPOCH {
+                return Err(internal_error(format!(
+                    "failed to collect the last epoch of streaming job {}",
+                    table_id
+                )));
+            }
+            table_fragments.set_chain_base_epoch(base_epoch_chains, base_epoch);
+            let created = async {
+                fail_point!("replace_mview_err", |_| Err(internal_error(
+                    "replace_mview_err"
+                )));
+                self.create_materialized_view(table_fragments, ctx).await
+            };
+            let Err(e) = created.await else {
+                return Ok(());
+            };
+            let Some(RollbackJob {
+                mut table_fragments,
+                chain_operator_ids,
+                ctx,
+            }) = rollback
+            else {
+                return Err(e);
+            };
+            warn!(
+                "failed to replace materialized view {}, rolling back: {}",
+                table_id, e
+            );
+            table_fragments.set_chain_base_epoch(&chain_operator_ids, base_epoch);
+            if let Err(rollback_err) = self.create_materialized_view(table_fragments, ctx).await {
+                return Err(internal_error(format!(
+                    "{}, and failed to roll back materialized view {}: {}",
+                    e, table_id, rollback_err
+                )));
+            }
+            Err(e)
+        }
+        .await;
+        self.barrier_manager.unpin_savepoint(&new_table_id).await;
+        result
+    }
+
+    /// Takes a savepoint of the materialized view. Check
+    /// [`crate::barrier::GlobalBarrierManager::savepoint`] for details.
+    pub async fn savepoint(&self, table_id: &TableId) -> Result<HummockEpoch> {
+        self.barrier_manager.savepoint(table_id).await
+    }
+
+    /// Rolls the materialized view back to its savepoint. Check
+    /// [`crate::barrier::GlobalBarrierManager::restore_savepoint`] for details.
+    pub async fn restore_savepoint(&self, table_id: &TableId) -> Result<()> {
+        self.barrier_manager.restore_savepoint(table_id).await
+    }
+
+    pub async fn release_savepoint(&self, table_id: &TableId) -> Result<()> {
+        self.barrier_manager.release_savepoint(table_id).await
+    }
+
+    /// Applies the config change to the source actors of the streaming job at the next barrier.
+    /// The change is recorded in the table fragments, so that it survives recovery.
+    pub async fn reconfigure(&self, table_id: &TableId, change: ConfigChange) -> Result<()> {
+        let actor_ids = self
+            .fragment_manager
+            .update_config_change(table_id, &change)
+            .await?;
+        self.barrier_manager
+            .run_command(Command::reconfigure(vec![ConfigChange {
+                actor_ids,
+                ..change
+            }]))
+            .await
+    }
+
+    /// Reschedules the fragment of the materialized view to `parallelism` actors on the parallel
+    /// units of its resource group. The vnodes are redistributed among the new actors, which take
+    /// over the states of their vnodes in the state store, so no state is moved.
+    pub async fn reschedule_fragment(
+        &self,
+        table_id: &TableId,
+        fragment_id: FragmentId,
+        parallelism: usize,
+    ) -> Result<()> {
+        let table_fragments = self
+            .fragment_manager
+            .select_table_fragments_by_table_id(table_id)
+            .await?;
+        table_fragments.check_reschedulable(fragment_id)?;
+        let mut fragment = table_fragments.fragments[&fragment_id].clone();
+        if fragment.actors.len() == parallelism {
+            return Ok(());
+        }
+        let parallel_units = self
+            .cluster_manager
+            .list_resource_group_parallel_units(
+                ParallelUnitType::Hash,
+                table_fragments.resource_group(),
+            )
+            .await;
+        if parallelism == 0 || parallelism > parallel_units.len() {
+            return Err(ErrorCode::InvalidParameterValue(format!(
+                "parallelism {} is out of range [1, {}] of resource group {}",
+                parallelism,
+                parallel_units.len(),
+                table_fragments.resource_group()
+            ))
+            .into());
+        }
+
+        // The new actors are copies of an old one with new ids.
+        let actor_id_offset = self
+            .id_gen_manager
+            .generate_interval::<{ IdCategory::Actor }>(parallelism as i32)
+            .await? as ActorId;
+        let template = fragment.actors[0].clone();
+        fragment.actors = (0..parallelism as ActorId)
+            .map(|i| StreamActor {
+                actor_id: actor_id_offset + i,
+                vnode_bitmap: None,
+                ..template.clone()
+            })
+            .collect();
+        let mut locations = ScheduledLocations::new();
+        locations.resource_group = table_fragments.resource_group().to_string();
+        self.scheduler
+            .schedule(&mut fragment, &mut locations)
+            .await?;
+
+        info!(
+            "reschedule fragment {} of table {} from {} to {} actors",
+            fragment_id,
+            table_id,
+            table_fragments.fragments[&fragment_id].actors.len(),
+            parallelism
+        );
+        self.barrier_manager
+            .reschedule_fragment(*table_id, fragment, locations.actor_locations)
+            .await
     }
 
     /// Flush means waiting for the next barrier to collect.
@@ -203,6 +203,7 @@ fn make_stream_node() -> StreamNode {
             internal_tables: vec![make_internal_table(true), make_internal_table(false)],
             column_mapping: HashMap::new(),
             is_append_only: false,
+            reemit: false,
         })),
         input: vec![filter_node],
         fields: vec![], // TODO: fill this later
@@ -236,6 +237,7 @@ fn make_stream_node() -> StreamNode {
             internal_tables: vec![make_internal_table(true), make_internal_table(false)],
             column_mapping: HashMap::new(),
             is_append_only: false,
+            reemit: false,
         })),
         fields: vec![], // TODO: fill this later
         input: vec![exchange_node_1],
@@ -282,6 +284,7 @@ fn make_stream_node() -> StreamNode {
             column_ids: vec![0_i32, 1_i32],
             column_orders: vec![make_column_order(1), make_column_order(2)],
             distribution_keys: Default::default(),
+            handle_pk_conflict_behavior: 0,
         })),
         fields: vec![], // TODO: fill this later
         operator_id: 7,
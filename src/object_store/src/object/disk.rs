@@ -26,7 +26,8 @@ use risingwave_common::cache::{CachableEntry, LruCache};
 use tokio::io::AsyncWriteExt;
 
 use crate::object::{
-    strip_path_local, BlockLocation, ObjectError, ObjectMetadata, ObjectResult, ObjectStore,
+    strip_path_local, BlockLocation, BoxedStreamingUploader, ObjectError, ObjectMetadata,
+    ObjectResult, ObjectStore, StreamingUploader,
 };
 
 pub(super) mod utils {
@@ -150,6 +151,33 @@ impl LocalDiskObjectStore {
     }
 }
 
+/// Writes the parts to a temporary file, which is renamed to the object path on finish.
+struct LocalDiskStreamingUploader {
+    file: tokio::fs::File,
+    tmp_path: PathBuf,
+    file_path: PathBuf,
+}
+
+#[async_trait::async_trait]
+impl StreamingUploader for LocalDiskStreamingUploader {
+    async fn write_bytes(&mut self, data: Bytes) -> ObjectResult<()> {
+        self.file
+            .write_all(&data)
+            .await
+            .map_err(|e| ObjectError::disk(format!("failed to write {:?}", self.tmp_path), e))
+    }
+
+    async fn finish(mut self: Box<Self>) -> ObjectResult<()> {
+        self.file
+            .flush()
+            .await
+            .map_err(|e| ObjectError::disk(format!("failed to flush {:?}", self.tmp_path), e))?;
+        tokio::fs::rename(&self.tmp_path, &self.file_path)
+            .await
+            .map_err(|e| ObjectError::disk(format!("failed to rename {:?}", self.tmp_path), e))
+    }
+}
+
 #[async_trait::async_trait]
 impl ObjectStore for LocalDiskObjectStore {
     async fn upload(&self, path: &str, obj: Bytes) -> ObjectResult<()> {
@@ -165,6 +193,21 @@ impl ObjectStore for LocalDiskObjectStore {
         Ok(())
     }
 
+    async fn streaming_upload(&self, path: &str) -> ObjectResult<BoxedStreamingUploader> {
+        let path = strip_path_local(path, self.is_local);
+        let file_path = self.new_file_path(path)?;
+        // Write to a temporary file first, so that a partial object is never visible.
+        let mut tmp_path = file_path.clone().into_os_string();
+        tmp_path.push(".tmp");
+        let tmp_path = PathBuf::from(tmp_path);
+        let file = utils::open_file(tmp_path.as_path(), false, true, true).await?;
+        Ok(Box::new(LocalDiskStreamingUploader {
+            file,
+            tmp_path,
+            file_path,
+        }))
+    }
+
     async fn read(&self, path: &str, block_loc: Option<BlockLocation>) -> ObjectResult<Bytes> {
         let path = strip_path_local(path, self.is_local);
         match block_loc {
@@ -253,6 +296,40 @@ impl ObjectStore for LocalDiskObjectStore {
             .map_err(|e| ObjectError::disk(format!("failed to delete {}", path), e))?;
         Ok(())
     }
+
+    async fn list(&self, prefix: &str) -> ObjectResult<Vec<String>> {
+        let prefix = strip_path_local(prefix, self.is_local).to_owned();
+        let root = PathBuf::from(&self.path_prefix);
+        utils::asyncify(move || {
+            let mut paths = vec![];
+            let mut dirs = vec![root.clone()];
+            while let Some(dir) = dirs.pop() {
+                let entries = match std::fs::read_dir(&dir) {
+                    Ok(entries) => entries,
+                    Err(e) if e.kind() == ErrorKind::NotFound => continue,
+                    Err(e) => {
+                        return Err(ObjectError::disk(format!("failed to list {:?}", dir), e))
+                    }
+                };
+                for entry in entries {
+                    let path = entry
+                        .map_err(|e| ObjectError::disk(format!("failed to list {:?}", dir), e))?
+                        .path();
+                    if path.is_dir() {
+                        dirs.push(path);
+                        continue;
+                    }
+                    match path.strip_prefix(&root).ok().and_then(|path| path.to_str()) {
+                        Some(path) if path.starts_with(&prefix) => paths.push(path.to_owned()),
+                        _ => {}
+                    }
+                }
+            }
+            paths.sort();
+            Ok(paths)
+        })
+        .await
+    }
 }
 
 #[cfg(test)]
@@ -480,4 +557,19 @@ mod tests {
             .await
             .is_err());
     }
+
+    #[tokio::test]
+    async fn test_list() {
+        let test_dir = TempDir::new().unwrap();
+        let test_root_path = test_dir.path().to_str().unwrap();
+        let store = LocalDiskObjectStore::new(test_root_path, false);
+        for path in ["t/dt=2/1.obj", "t/dt=1/1.obj", "u/1.obj"] {
+            store.upload(path, Bytes::from("123")).await.unwrap();
+        }
+        assert_eq!(
+            store.list("t/").await.unwrap(),
+            vec!["t/dt=1/1.obj", "t/dt=2/1.obj"]
+        );
+        assert!(store.list("v/").await.unwrap().is_empty());
+    }
 }
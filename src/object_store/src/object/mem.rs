@@ -13,21 +13,25 @@
 // limitations under the License.
 
 use std::collections::HashMap;
+use std::sync::Arc;
 
-use bytes::Bytes;
+use bytes::{BufMut, Bytes, BytesMut};
 use fail::fail_point;
 use futures::future::try_join_all;
 use itertools::Itertools;
 use tokio::sync::Mutex;
 
 use super::{ObjectError, ObjectResult};
-use crate::object::{strip_path_local, BlockLocation, ObjectMetadata, ObjectStore};
+use crate::object::{
+    strip_path_local, BlockLocation, BoxedStreamingUploader, ObjectMetadata, ObjectStore,
+    StreamingUploader,
+};
 
 /// In-memory object storage, useful for testing.
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct InMemObjectStore {
     is_local: bool,
-    objects: Mutex<HashMap<String, Bytes>>,
+    objects: Arc<Mutex<HashMap<String, Bytes>>>,
 }
 
 #[async_trait::async_trait]
@@ -45,6 +49,14 @@ impl ObjectStore for InMemObjectStore {
         }
     }
 
+    async fn streaming_upload(&self, path: &str) -> ObjectResult<BoxedStreamingUploader> {
+        Ok(Box::new(InMemStreamingUploader {
+            path: strip_path_local(path, self.is_local).to_string(),
+            buf: BytesMut::new(),
+            objects: self.objects.clone(),
+        }))
+    }
+
     async fn read(&self, path: &str, block: Option<BlockLocation>) -> ObjectResult<Bytes> {
         let path = strip_path_local(path, self.is_local);
         fail_point!("mem_read_err", |_| Err(ObjectError::internal(
@@ -80,13 +92,39 @@ impl ObjectStore for InMemObjectStore {
         self.objects.lock().await.remove(path);
         Ok(())
     }
+
+    async fn list(&self, prefix: &str) -> ObjectResult<Vec<String>> {
+        let prefix = strip_path_local(prefix, self.is_local);
+        Ok(self
+            .objects
+            .lock()
+            .await
+            .keys()
+            .filter(|path| path.starts_with(prefix))
+            .cloned()
+            .sorted()
+            .collect())
+    }
 }
 
 impl InMemObjectStore {
     pub fn new(is_local: bool) -> Self {
         Self {
             is_local,
-            objects: Mutex::new(HashMap::new()),
+            objects: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Returns an object store backed by the objects shared in the process, so that nodes
+    /// running in the same process, e.g. in simulation tests, see the same objects as if they
+    /// were using a remote object store.
+    pub fn shared() -> Self {
+        lazy_static::lazy_static! {
+            static ref SHARED_OBJECTS: Arc<Mutex<HashMap<String, Bytes>>> = Default::default();
+        }
+        Self {
+            is_local: false,
+            objects: SHARED_OBJECTS.clone(),
         }
     }
 
@@ -103,6 +141,36 @@ impl InMemObjectStore {
     }
 }
 
+/// Buffers the parts and inserts the object on finish.
+struct InMemStreamingUploader {
+    path: String,
+    buf: BytesMut,
+    objects: Arc<Mutex<HashMap<String, Bytes>>>,
+}
+
+#[async_trait::async_trait]
+impl StreamingUploader for InMemStreamingUploader {
+    async fn write_bytes(&mut self, data: Bytes) -> ObjectResult<()> {
+        self.buf.put(data);
+        Ok(())
+    }
+
+    async fn finish(self: Box<Self>) -> ObjectResult<()> {
+        fail_point!("mem_upload_err", |_| Err(ObjectError::internal(
+            "mem upload error"
+        )));
+        if self.buf.is_empty() {
+            Err(ObjectError::internal("upload empty object"))
+        } else {
+            self.objects
+                .lock()
+                .await
+                .insert(self.path, self.buf.freeze());
+            Ok(())
+        }
+    }
+}
+
 fn find_block(obj: &Bytes, block: BlockLocation) -> ObjectResult<Bytes> {
     if block.offset + block.size > obj.len() {
         Err(ObjectError::internal("bad block offset and size"))
@@ -158,4 +226,42 @@ mod tests {
         let metadata = obj_store.metadata("/abc").await.unwrap();
         assert_eq!(metadata.total_size, 6);
     }
+
+    #[tokio::test]
+    async fn test_shared() {
+        let block = Bytes::from("123456");
+
+        let writer = InMemObjectStore::shared();
+        writer.upload("/shared", block).await.unwrap();
+
+        let reader = InMemObjectStore::shared();
+        let metadata = reader.metadata("/shared").await.unwrap();
+        assert_eq!(metadata.total_size, 6);
+        reader.delete("/shared").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_list() {
+        let obj_store = InMemObjectStore::new(false);
+        for path in ["/b/2", "/a/1", "/b/1"] {
+            obj_store.upload(path, Bytes::from("123")).await.unwrap();
+        }
+        assert_eq!(obj_store.list("/b/").await.unwrap(), vec!["/b/1", "/b/2"]);
+        assert!(obj_store.list("/c").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_streaming_upload() {
+        let obj_store = InMemObjectStore::new(false);
+        let mut uploader = obj_store.streaming_upload("/abc").await.unwrap();
+        uploader.write_bytes(Bytes::from("123")).await.unwrap();
+        uploader.write_bytes(Bytes::from("456")).await.unwrap();
+        // Not visible until finished.
+        obj_store.metadata("/abc").await.unwrap_err();
+        uploader.finish().await.unwrap();
+        assert_eq!(
+            obj_store.read("/abc", None).await.unwrap(),
+            Bytes::from("123456")
+        );
+    }
 }
@@ -72,6 +72,20 @@ impl BlockLocation {
     }
 }
 
+/// Uploads an object in parts, so that large objects can be written without being buffered in
+/// memory as a whole. The object becomes visible only after [`StreamingUploader::finish`]
+/// succeeds; dropping the uploader before that abandons the upload.
+#[async_trait::async_trait]
+pub trait StreamingUploader: Send {
+    /// Appends `data` to the object.
+    async fn write_bytes(&mut self, data: Bytes) -> ObjectResult<()>;
+
+    /// Completes the upload.
+    async fn finish(self: Box<Self>) -> ObjectResult<()>;
+}
+
+pub type BoxedStreamingUploader = Box<dyn StreamingUploader>;
+
 /// The implementation must be thread-safe.
 /// For `path`, if the `path` starts with `LOCAL_OBJECT_STORE_PATH_PREFIX`, it indicates that the
 /// operation should be performed on the local object store.
@@ -80,6 +94,9 @@ pub trait ObjectStore: Send + Sync {
     /// Uploads the object to `ObjectStore`.
     async fn upload(&self, path: &str, obj: Bytes) -> ObjectResult<()>;
 
+    /// Starts uploading the object at `path` in parts.
+    async fn streaming_upload(&self, path: &str) -> ObjectResult<BoxedStreamingUploader>;
+
     /// If the `block_loc` is None, the whole object will be return.
     /// If objects are PUT using a multipart upload, it’s a good practice to GET them in the same
     /// part sizes (or at least aligned to part boundaries) for best performance.
@@ -93,6 +110,9 @@ pub trait ObjectStore: Send + Sync {
 
     /// Deletes blob permanently.
     async fn delete(&self, path: &str) -> ObjectResult<()>;
+
+    /// Lists the paths of all objects starting with `prefix`, in ascending order.
+    async fn list(&self, prefix: &str) -> ObjectResult<Vec<String>>;
 }
 
 pub struct HybridObjectStore {
@@ -124,6 +144,14 @@ impl ObjectStore for HybridObjectStore {
         }
     }
 
+    async fn streaming_upload(&self, path: &str) -> ObjectResult<BoxedStreamingUploader> {
+        if is_local_path(path) {
+            self.local.streaming_upload(path).await
+        } else {
+            self.remote.streaming_upload(path).await
+        }
+    }
+
     async fn read(&self, path: &str, block_loc: Option<BlockLocation>) -> ObjectResult<Bytes> {
         if is_local_path(path) {
             self.local.read(path, block_loc).await
@@ -155,6 +183,14 @@ impl ObjectStore for HybridObjectStore {
             self.remote.delete(path).await
         }
     }
+
+    async fn list(&self, prefix: &str) -> ObjectResult<Vec<String>> {
+        if is_local_path(prefix) {
+            self.local.list(prefix).await
+        } else {
+            self.remote.list(prefix).await
+        }
+    }
 }
 
 pub type ObjectStoreRef = Arc<ObjectStoreImpl>;
@@ -190,6 +226,15 @@ impl ObjectStoreImpl {
         Ok(())
     }
 
+    pub async fn streaming_upload(&self, path: &str) -> ObjectResult<BoxedStreamingUploader> {
+        let _timer = self
+            .object_store_metrics
+            .operation_latency
+            .with_label_values(&["streaming_upload_start"])
+            .start_timer();
+        self.inner.streaming_upload(path).await
+    }
+
     pub async fn read(&self, path: &str, block_loc: Option<BlockLocation>) -> ObjectResult<Bytes> {
         let _timer = self
             .object_store_metrics
@@ -241,6 +286,15 @@ impl ObjectStoreImpl {
             .start_timer();
         self.inner.delete(path).await
     }
+
+    pub async fn list(&self, prefix: &str) -> ObjectResult<Vec<String>> {
+        let _timer = self
+            .object_store_metrics
+            .operation_latency
+            .with_label_values(&["list"])
+            .start_timer();
+        self.inner.list(prefix).await
+    }
 }
 
 pub async fn parse_object_store(url: &str, is_local: bool) -> Box<dyn ObjectStore> {
@@ -267,6 +321,14 @@ pub async fn parse_object_store(url: &str, is_local: bool) -> Box<dyn ObjectStor
                 .to_owned();
             Box::new(LocalDiskObjectStore::new(path.as_str(), true))
         }
+        memory_shared if memory_shared.starts_with("memory-shared") => {
+            assert!(
+                !is_local,
+                "memory-shared cannot be used as local object store"
+            );
+            tracing::warn!("You're using Hummock shared in-memory object store. This should only be used in tests with multiple nodes in one process.");
+            Box::new(InMemObjectStore::shared())
+        }
         memory if memory.starts_with("memory") => {
             tracing::warn!("You're using Hummock in-memory object store. This should never be used in benchmarks and production environment.");
             Box::new(InMemObjectStore::new(is_local))
@@ -12,15 +12,24 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use aws_sdk_s3::model::{CompletedMultipartUpload, CompletedPart};
 use aws_sdk_s3::{Client, Endpoint, Region};
 use aws_smithy_http::body::SdkBody;
+use bytes::{BufMut, BytesMut};
 use fail::fail_point;
 use futures::future::try_join_all;
 use itertools::Itertools;
 
-use super::{BlockLocation, ObjectError, ObjectMetadata, ObjectResult};
+use super::{
+    BlockLocation, BoxedStreamingUploader, ObjectError, ObjectMetadata, ObjectResult,
+    StreamingUploader,
+};
 use crate::object::{Bytes, ObjectStore};
 
+/// The size of the parts of streaming uploads. S3 requires all parts but the last to be at
+/// least 5 MiB.
+const S3_PART_SIZE: usize = 8 * 1024 * 1024;
+
 /// Object store with S3 backend
 pub struct S3ObjectStore {
     client: Client,
@@ -43,6 +52,17 @@ impl ObjectStore for S3ObjectStore {
         Ok(())
     }
 
+    async fn streaming_upload(&self, path: &str) -> ObjectResult<BoxedStreamingUploader> {
+        Ok(Box::new(S3StreamingUploader {
+            client: self.client.clone(),
+            bucket: self.bucket.clone(),
+            key: path.to_string(),
+            upload_id: None,
+            parts: vec![],
+            buf: BytesMut::new(),
+        }))
+    }
+
     /// Amazon S3 doesn't support retrieving multiple ranges of data per GET request.
     async fn read(&self, path: &str, block_loc: Option<BlockLocation>) -> ObjectResult<Bytes> {
         fail_point!("s3_read_err", |_| Err(ObjectError::internal(
@@ -114,6 +134,170 @@ impl ObjectStore for S3ObjectStore {
             .await?;
         Ok(())
     }
+
+    async fn list(&self, prefix: &str) -> ObjectResult<Vec<String>> {
+        fail_point!("s3_list_err", |_| Err(ObjectError::internal(
+            "s3 list error"
+        )));
+        let mut paths = vec![];
+        let mut continuation_token = None;
+        loop {
+            let resp = self
+                .client
+                .list_objects_v2()
+                .bucket(&self.bucket)
+                .prefix(prefix)
+                .set_continuation_token(continuation_token)
+                .send()
+                .await?;
+            paths.extend(
+                resp.contents()
+                    .unwrap_or_default()
+                    .iter()
+                    .filter_map(|object| object.key().map(str::to_string)),
+            );
+            if !resp.is_truncated() {
+                break;
+            }
+            continuation_token = resp.next_continuation_token().map(str::to_string);
+        }
+        // S3 lists the keys in ascending order of UTF-8 bytes.
+        Ok(paths)
+    }
+}
+
+/// Uploads the object with a multipart upload in parts of [`S3_PART_SIZE`]. Objects smaller than
+/// a part are uploaded with a single `PutObject` request instead.
+///
+/// The multipart upload is aborted if a request fails, but not if the uploader is dropped
+/// before finishing, so the bucket should have a lifecycle rule that cleans up incomplete
+/// multipart uploads.
+struct S3StreamingUploader {
+    client: Client,
+    bucket: String,
+    key: String,
+    /// Set once the first part is uploaded.
+    upload_id: Option<String>,
+    parts: Vec<CompletedPart>,
+    buf: BytesMut,
+}
+
+impl S3StreamingUploader {
+    async fn upload_part(&mut self) -> ObjectResult<()> {
+        let upload_id = match &self.upload_id {
+            Some(upload_id) => upload_id.clone(),
+            None => {
+                let resp = self
+                    .client
+                    .create_multipart_upload()
+                    .bucket(&self.bucket)
+                    .key(&self.key)
+                    .send()
+                    .await?;
+                let upload_id = resp
+                    .upload_id()
+                    .ok_or_else(|| ObjectError::internal("no upload id of multipart upload"))?
+                    .to_string();
+                self.upload_id = Some(upload_id.clone());
+                upload_id
+            }
+        };
+        let part_number = self.parts.len() as i32 + 1;
+        let data = self.buf.split().freeze();
+        let resp = self
+            .client
+            .upload_part()
+            .bucket(&self.bucket)
+            .key(&self.key)
+            .upload_id(&upload_id)
+            .part_number(part_number)
+            .body(SdkBody::from(data).into())
+            .send()
+            .await?;
+        self.parts.push(
+            CompletedPart::builder()
+                .set_e_tag(resp.e_tag().map(str::to_string))
+                .part_number(part_number)
+                .build(),
+        );
+        Ok(())
+    }
+
+    async fn complete(&mut self) -> ObjectResult<()> {
+        let upload_id = match &self.upload_id {
+            Some(upload_id) => upload_id.clone(),
+            None => {
+                self.client
+                    .put_object()
+                    .bucket(&self.bucket)
+                    .key(&self.key)
+                    .body(SdkBody::from(self.buf.split().freeze()).into())
+                    .send()
+                    .await?;
+                return Ok(());
+            }
+        };
+        if !self.buf.is_empty() {
+            self.upload_part().await?;
+        }
+        self.client
+            .complete_multipart_upload()
+            .bucket(&self.bucket)
+            .key(&self.key)
+            .upload_id(upload_id)
+            .multipart_upload(
+                CompletedMultipartUpload::builder()
+                    .set_parts(Some(std::mem::take(&mut self.parts)))
+                    .build(),
+            )
+            .send()
+            .await?;
+        Ok(())
+    }
+
+    async fn abort(&self) {
+        if let Some(upload_id) = &self.upload_id {
+            if let Err(e) = self
+                .client
+                .abort_multipart_upload()
+                .bucket(&self.bucket)
+                .key(&self.key)
+                .upload_id(upload_id)
+                .send()
+                .await
+            {
+                tracing::warn!("failed to abort multipart upload of {}: {}", self.key, e);
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl StreamingUploader for S3StreamingUploader {
+    async fn write_bytes(&mut self, data: Bytes) -> ObjectResult<()> {
+        fail_point!("s3_upload_err", |_| Err(ObjectError::internal(
+            "s3 upload error"
+        )));
+        self.buf.put(data);
+        if self.buf.len() >= S3_PART_SIZE {
+            if let Err(e) = self.upload_part().await {
+                self.abort().await;
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    async fn finish(mut self: Box<Self>) -> ObjectResult<()> {
+        fail_point!("s3_upload_err", |_| Err(ObjectError::internal(
+            "s3 upload error"
+        )));
+        if let Err(e) = self.complete().await {
+            self.abort().await;
+            return Err(e);
+        }
+        Ok(())
+    }
 }
 
 impl S3ObjectStore {
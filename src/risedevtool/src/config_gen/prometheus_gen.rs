@@ -54,6 +54,14 @@ impl PrometheusGen {
             .map(|node| format!("\"{}:{}\"", node.address, node.exporter_port))
             .join(",");
 
+        let frontend_targets = config
+            .provide_frontend
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|node| format!("\"{}:{}\"", node.address, node.exporter_port))
+            .join(",");
+
         let etcd_targets = config
             .provide_etcd
             .as_ref()
@@ -98,6 +106,10 @@ scrape_configs:
     static_configs:
       - targets: [{compactor_targets}]
 
+  - job_name: frontend
+    static_configs:
+      - targets: [{frontend_targets}]
+
   - job_name: etcd
     static_configs:
       - targets: [{etcd_targets}]
@@ -72,6 +72,7 @@ pub struct FrontendConfig {
     #[serde(with = "string")]
     pub port: u16,
     pub listen_address: String,
+    pub exporter_port: u16,
 
     pub provide_meta_node: Option<Vec<MetaNodeConfig>>,
     pub user_managed: bool,
@@ -158,6 +159,7 @@ pub struct PrometheusConfig {
     pub provide_meta_node: Option<Vec<MetaNodeConfig>>,
     pub provide_minio: Option<Vec<MinioConfig>>,
     pub provide_compactor: Option<Vec<CompactorConfig>>,
+    pub provide_frontend: Option<Vec<FrontendConfig>>,
     pub provide_etcd: Option<Vec<EtcdConfig>>,
     pub provide_redpanda: Option<Vec<RedPandaConfig>>,
 }
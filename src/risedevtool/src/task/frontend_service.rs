@@ -33,8 +33,14 @@ impl FrontendService {
     fn frontend(&self) -> Result<Command> {
         let prefix_bin = env::var("PREFIX_BIN")?;
 
-        if let Ok(x) = env::var("ENABLE_ALL_IN_ONE") && x == "true" {
-            Ok(Command::new(Path::new(&prefix_bin).join("risingwave").join("frontend-node")))
+        if let Ok(x) = env::var("ENABLE_ALL_IN_ONE")
+            && x == "true"
+        {
+            Ok(Command::new(
+                Path::new(&prefix_bin)
+                    .join("risingwave")
+                    .join("frontend-node"),
+            ))
         } else {
             Ok(Command::new(Path::new(&prefix_bin).join("frontend")))
         }
@@ -43,7 +49,14 @@ impl FrontendService {
     /// Apply command args accroding to config
     pub fn apply_command_args(cmd: &mut Command, config: &FrontendConfig) -> Result<()> {
         cmd.arg("--host")
-            .arg(format!("{}:{}", config.listen_address, config.port));
+            .arg(format!("{}:{}", config.listen_address, config.port))
+            .arg("--prometheus-listener-addr")
+            .arg(format!(
+                "{}:{}",
+                config.listen_address, config.exporter_port
+            ))
+            .arg("--metrics-level")
+            .arg("1");
 
         let provide_meta_node = config.provide_meta_node.as_ref().unwrap();
         match provide_meta_node.len() {
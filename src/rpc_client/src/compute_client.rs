@@ -19,21 +19,27 @@ use risingwave_common::array::DataChunk;
 use risingwave_common::util::addr::HostAddr;
 use risingwave_pb::batch_plan::exchange_info::DistributionMode;
 use risingwave_pb::batch_plan::{ExchangeInfo, PlanFragment, PlanNode, TaskId, TaskOutputId};
+use risingwave_pb::common::buffer::CompressionType;
 use risingwave_pb::task_service::exchange_service_client::ExchangeServiceClient;
 use risingwave_pb::task_service::task_service_client::TaskServiceClient;
 use risingwave_pb::task_service::{
-    CreateTaskRequest, CreateTaskResponse, ExecuteRequest, GetDataRequest, GetDataResponse,
-    GetStreamRequest, GetStreamResponse,
+    get_data_with_permits_request, get_stream_with_permits_request, CreateTaskRequest,
+    CreateTaskResponse, ExecuteRequest, GetDataRequest, GetDataResponse, GetDataWithPermitsRequest,
+    GetStreamRequest, GetStreamResponse, GetStreamWithPermitsRequest,
 };
-use tonic::transport::{Channel, Endpoint};
+use tokio::sync::mpsc::unbounded_channel;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tonic::transport::Endpoint;
 use tonic::Streaming;
 
 use crate::error::Result;
+use crate::permits::{PermitSender, DEFAULT_EXCHANGE_PERMITS};
+use crate::{InterceptedChannel, RpcInterceptor};
 
 #[derive(Clone)]
 pub struct ComputeClient {
-    pub exchange_client: ExchangeServiceClient<Channel>,
-    pub task_client: TaskServiceClient<Channel>,
+    pub exchange_client: ExchangeServiceClient<InterceptedChannel>,
+    pub task_client: TaskServiceClient<InterceptedChannel>,
     pub addr: HostAddr,
 }
 
@@ -43,8 +49,10 @@ impl ComputeClient {
             .connect_timeout(Duration::from_secs(5))
             .connect()
             .await?;
-        let exchange_client = ExchangeServiceClient::new(channel.clone());
-        let task_client = TaskServiceClient::new(channel);
+        // Exchange streams may outlive any fixed deadline, so only request ids are attached here.
+        let exchange_client =
+            ExchangeServiceClient::with_interceptor(channel.clone(), RpcInterceptor::new());
+        let task_client = TaskServiceClient::with_interceptor(channel, RpcInterceptor::new());
         Ok(Self {
             exchange_client,
             task_client,
@@ -52,29 +60,71 @@ impl ComputeClient {
         })
     }
 
-    pub async fn get_data(&self, output_id: TaskOutputId) -> Result<Streaming<GetDataResponse>> {
-        Ok(self
+    /// Fetches the output of a batch task. Chunks are compressed by the sender with `compression`.
+    /// The returned [`PermitSender`] must be fed with the size of each consumed message, otherwise
+    /// the sender stops after sending [`DEFAULT_EXCHANGE_PERMITS`] bytes.
+    pub async fn get_data(
+        &self,
+        output_id: TaskOutputId,
+        compression: CompressionType,
+    ) -> Result<(
+        Streaming<GetDataResponse>,
+        PermitSender<GetDataWithPermitsRequest>,
+    )> {
+        let (tx, rx) = unbounded_channel();
+        tx.send(GetDataWithPermitsRequest {
+            value: Some(get_data_with_permits_request::Value::Get(
+                get_data_with_permits_request::Get {
+                    request: Some(GetDataRequest {
+                        task_output_id: Some(output_id),
+                        compression: compression as i32,
+                    }),
+                    permits: DEFAULT_EXCHANGE_PERMITS,
+                },
+            )),
+        })
+        .unwrap();
+        let response_stream = self
             .exchange_client
             .to_owned()
-            .get_data(GetDataRequest {
-                task_output_id: Some(output_id),
-            })
+            .get_data_with_permits(UnboundedReceiverStream::new(rx))
             .await?
-            .into_inner())
+            .into_inner();
+        let permit_sender = PermitSender::new(tx, DEFAULT_EXCHANGE_PERMITS, |permits| {
+            GetDataWithPermitsRequest {
+                value: Some(get_data_with_permits_request::Value::AddPermits(permits)),
+            }
+        });
+        Ok((response_stream, permit_sender))
     }
 
     pub async fn get_stream(
         &self,
         up_fragment_id: u32,
         down_fragment_id: u32,
-    ) -> Result<Streaming<GetStreamResponse>> {
-        Ok(self
+        compression: CompressionType,
+    ) -> Result<(
+        Streaming<GetStreamResponse>,
+        PermitSender<GetStreamWithPermitsRequest>,
+    )> {
+        let (tx, rx) = unbounded_channel();
+        tx.send(GetStreamWithPermitsRequest {
+            value: Some(get_stream_with_permits_request::Value::Get(
+                get_stream_with_permits_request::Get {
+                    request: Some(GetStreamRequest {
+                        up_fragment_id,
+                        down_fragment_id,
+                        compression: compression as i32,
+                    }),
+                    permits: DEFAULT_EXCHANGE_PERMITS,
+                },
+            )),
+        })
+        .unwrap();
+        let response_stream = self
             .exchange_client
             .to_owned()
-            .get_stream(GetStreamRequest {
-                up_fragment_id,
-                down_fragment_id,
-            })
+            .get_stream_with_permits(UnboundedReceiverStream::new(rx))
             .await
             .inspect_err(|_| {
                 tracing::error!(
@@ -84,7 +134,13 @@ impl ComputeClient {
                     down_fragment_id
                 )
             })?
-            .into_inner())
+            .into_inner();
+        let permit_sender = PermitSender::new(tx, DEFAULT_EXCHANGE_PERMITS, |permits| {
+            GetStreamWithPermitsRequest {
+                value: Some(get_stream_with_permits_request::Value::AddPermits(permits)),
+            }
+        });
+        Ok((response_stream, permit_sender))
     }
 
     // TODO: Remove this
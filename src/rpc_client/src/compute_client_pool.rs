@@ -12,34 +12,232 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::anyhow;
+use futures::future::try_join_all;
 use moka::future::Cache;
 use risingwave_common::util::addr::HostAddr;
+use tokio_retry::strategy::{jitter, ExponentialBackoff};
 
-use crate::error::Result;
+use crate::error::{Result, RpcError};
 use crate::ComputeClient;
 
+/// Number of connections established to each compute node by default. Requests to the same node
+/// are spread over them, so that a few heavy exchange streams don't stall the others.
+pub const DEFAULT_CONNECTIONS_PER_PEER: usize = 2;
+
+/// Policy of retrying connections and idempotent RPCs to compute nodes, e.g. when the peer is
+/// restarting.
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    /// Maximum retries after the first attempt. 0 means no retry.
+    pub max_retries: usize,
+    /// Base interval in ms of the exponential backoff.
+    pub base_interval_ms: u64,
+    /// Maximum interval in ms between two attempts.
+    pub max_interval_ms: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_interval_ms: 100,
+            max_interval_ms: 2000,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn no_retry() -> Self {
+        Self {
+            max_retries: 0,
+            ..Default::default()
+        }
+    }
+
+    /// Intervals to wait before each retry.
+    fn backoff(&self) -> impl Iterator<Item = Duration> {
+        ExponentialBackoff::from_millis(self.base_interval_ms)
+            .max_delay(Duration::from_millis(self.max_interval_ms))
+            .map(jitter)
+            .take(self.max_retries)
+    }
+}
+
+/// Connections to a compute node, used in a round-robin manner.
+struct PeerClients {
+    clients: Vec<ComputeClient>,
+    next: AtomicUsize,
+}
+
+impl PeerClients {
+    fn next_client(&self) -> ComputeClient {
+        let idx = self.next.fetch_add(1, Ordering::Relaxed) % self.clients.len();
+        self.clients[idx].clone()
+    }
+}
+
+/// [`ComputeClientPool`] maintains a bounded number of connections to each compute node, which is
+/// shared by the batch scheduler, batch exchanges and streaming remote inputs.
+///
+/// A node is considered unhealthy once an RPC to it fails with a connection error. Its
+/// connections are then dropped and re-established on the next use.
 #[derive(Clone)]
 pub struct ComputeClientPool {
-    cache: Cache<HostAddr, ComputeClient>,
+    cache: Cache<HostAddr, Arc<PeerClients>>,
+    connections_per_peer: usize,
+    retry_policy: RetryPolicy,
+}
+
+impl Default for ComputeClientPool {
+    fn default() -> Self {
+        Self::new(u64::MAX)
+    }
 }
 
 impl ComputeClientPool {
     pub fn new(cache_capacity: u64) -> Self {
         Self {
             cache: Cache::new(cache_capacity),
+            connections_per_peer: DEFAULT_CONNECTIONS_PER_PEER,
+            retry_policy: RetryPolicy::default(),
         }
     }
 
-    /// Get a compute client from the pool.
+    pub fn with_connections_per_peer(mut self, connections_per_peer: usize) -> Self {
+        assert!(connections_per_peer > 0);
+        self.connections_per_peer = connections_per_peer;
+        self
+    }
+
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Get a compute client from the pool. Connections are established if there's none to `addr`,
+    /// which is retried by the retry policy.
     pub async fn get_client_for_addr(&self, addr: HostAddr) -> Result<ComputeClient> {
+        let mut backoff = self.retry_policy.backoff();
+        loop {
+            match self.get_peer(addr.clone()).await {
+                Ok(peer) => return Ok(peer.next_client()),
+                Err(err) => match backoff.next() {
+                    Some(interval) => {
+                        tracing::warn!(
+                            "Failed to connect to compute node {}, retry after {:?}: {}",
+                            addr,
+                            interval,
+                            err
+                        );
+                        tokio::time::sleep(interval).await;
+                    }
+                    None => return Err(err),
+                },
+            }
+        }
+    }
+
+    /// Call the idempotent RPC `f` with a client to `addr`. It's retried by the retry policy if it
+    /// fails with a connection error, and the connections to `addr` are re-established before
+    /// the retry.
+    pub async fn retry<T, F, Fut>(&self, addr: HostAddr, mut f: F) -> Result<T>
+    where
+        F: FnMut(ComputeClient) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut backoff = self.retry_policy.backoff();
+        loop {
+            let client = self.get_client_for_addr(addr.clone()).await?;
+            match f(client).await {
+                Err(err) if err.is_connection_error() => {
+                    self.invalidate(&addr).await;
+                    match backoff.next() {
+                        Some(interval) => {
+                            tracing::warn!(
+                                "RPC to compute node {} failed, retry after {:?}: {}",
+                                addr,
+                                interval,
+                                err
+                            );
+                            tokio::time::sleep(interval).await;
+                        }
+                        None => return Err(err),
+                    }
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Drop the connections to `addr`, e.g. after they are found broken. New ones will be
+    /// established on the next use.
+    pub async fn invalidate(&self, addr: &HostAddr) {
+        self.cache.invalidate(addr).await;
+    }
+
+    async fn get_peer(&self, addr: HostAddr) -> Result<Arc<PeerClients>> {
+        let connections_per_peer = self.connections_per_peer;
         self.cache
-            .try_get_with(addr.clone(), async { ComputeClient::new(addr).await })
+            .try_get_with(addr.clone(), async move {
+                let clients = try_join_all(
+                    (0..connections_per_peer).map(|_| ComputeClient::new(addr.clone())),
+                )
+                .await?;
+                Ok::<_, RpcError>(Arc::new(PeerClients {
+                    clients,
+                    next: AtomicUsize::new(0),
+                }))
+            })
             .await
             .map_err(|e| anyhow!("failed to create compute client: {:?}", e).into())
     }
 }
 
 pub type ComputeClientPoolRef = Arc<ComputeClientPool>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_unconnectable_peer() {
+        let pool = ComputeClientPool::new(16).with_retry_policy(RetryPolicy {
+            max_retries: 2,
+            base_interval_ms: 10,
+            max_interval_ms: 10,
+        });
+        let addr: HostAddr = "127.0.0.1:1001".parse().unwrap();
+        assert!(pool.get_client_for_addr(addr.clone()).await.is_err());
+
+        let mut attempts = 0;
+        let res = pool
+            .retry(addr, |client| {
+                attempts += 1;
+                async move { Ok::<_, RpcError>(client.addr) }
+            })
+            .await;
+        assert!(res.is_err());
+        // The RPC is never issued without a connection.
+        assert_eq!(attempts, 0);
+    }
+
+    #[test]
+    fn test_retry_policy_backoff() {
+        assert_eq!(RetryPolicy::default().backoff().count(), 3);
+        assert_eq!(RetryPolicy::no_retry().backoff().count(), 0);
+        let policy = RetryPolicy {
+            max_retries: 5,
+            base_interval_ms: 100,
+            max_interval_ms: 200,
+        };
+        assert!(policy
+            .backoff()
+            .all(|interval| interval <= Duration::from_millis(200)));
+    }
+}
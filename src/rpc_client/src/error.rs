@@ -30,8 +30,24 @@ pub enum RpcError {
     Internal(#[from] anyhow::Error),
 }
 
+impl RpcError {
+    /// Whether the error is caused by a broken or unavailable connection, in which case the peer
+    /// may not have received the request, and a reconnection is required.
+    pub fn is_connection_error(&self) -> bool {
+        match self {
+            RpcError::TrasnportError(_) => true,
+            RpcError::GrpcStatus(status) => status.code() == tonic::Code::Unavailable,
+            RpcError::Internal(_) => false,
+        }
+    }
+}
+
 impl From<RpcError> for RwError {
     fn from(r: RpcError) -> Self {
-        ErrorCode::RpcError(r.into()).into()
+        match r {
+            // Keep the causal chain and the report of the error raised by another node.
+            RpcError::GrpcStatus(status) => status.into(),
+            r => ErrorCode::RpcError(r.into()).into(),
+        }
     }
 }
@@ -0,0 +1,103 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use risingwave_common::service::REQUEST_ID_HEADER;
+use tonic::codegen::InterceptedService;
+use tonic::metadata::MetadataValue;
+use tonic::service::Interceptor;
+use tonic::transport::Channel;
+use tonic::{Request, Status};
+
+/// Deadline of the RPCs that are expected to return quickly, e.g. the ones changing the cluster
+/// membership or the users. RPCs waiting for barriers or streaming data have no deadline.
+pub const DEFAULT_RPC_DEADLINE: Duration = Duration::from_secs(10);
+
+/// Channel with [`RpcInterceptor`], which all rpc clients are built on.
+pub type InterceptedChannel = InterceptedService<Channel, RpcInterceptor>;
+
+const GRPC_TIMEOUT_HEADER: &str = "grpc-timeout";
+
+/// Interceptor shared by all rpc clients, which assigns a request id to each request so that it
+/// can be correlated with the logs on the server, and sets the deadline of the request if it's
+/// not set by the caller.
+#[derive(Clone, Debug, Default)]
+pub struct RpcInterceptor {
+    deadline: Option<Duration>,
+}
+
+impl RpcInterceptor {
+    /// Create an interceptor without a deadline, for clients of long-running or streaming RPCs.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an interceptor that fails the requests not finished within `deadline` with
+    /// `DeadlineExceeded`.
+    pub fn with_deadline(deadline: Duration) -> Self {
+        Self {
+            deadline: Some(deadline),
+        }
+    }
+}
+
+impl Interceptor for RpcInterceptor {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        if !request.metadata().contains_key(REQUEST_ID_HEADER) {
+            let request_id = uuid::Uuid::new_v4().to_string();
+            request
+                .metadata_mut()
+                .insert(REQUEST_ID_HEADER, request_id.parse().unwrap());
+        }
+        // The deadline set by the caller takes precedence.
+        if let Some(deadline) = self.deadline {
+            if !request.metadata().contains_key(GRPC_TIMEOUT_HEADER) {
+                request.set_timeout(deadline);
+            }
+        }
+        Ok(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rpc_interceptor() {
+        let mut interceptor = RpcInterceptor::with_deadline(Duration::from_secs(1));
+        let request = interceptor.call(Request::new(())).unwrap();
+        assert!(request.metadata().contains_key(REQUEST_ID_HEADER));
+        assert!(request.metadata().contains_key(GRPC_TIMEOUT_HEADER));
+
+        // The request id and the deadline set by the caller are kept.
+        let mut request = Request::new(());
+        request
+            .metadata_mut()
+            .insert(REQUEST_ID_HEADER, MetadataValue::from_static("id"));
+        request.set_timeout(Duration::from_millis(10));
+        let timeout = request.metadata().get(GRPC_TIMEOUT_HEADER).unwrap().clone();
+        let request = interceptor.call(request).unwrap();
+        assert_eq!(request.metadata().get(REQUEST_ID_HEADER).unwrap(), "id");
+        assert_eq!(
+            request.metadata().get(GRPC_TIMEOUT_HEADER).unwrap(),
+            &timeout
+        );
+
+        let mut interceptor = RpcInterceptor::new();
+        let request = interceptor.call(Request::new(())).unwrap();
+        assert!(!request.metadata().contains_key(GRPC_TIMEOUT_HEADER));
+    }
+}
@@ -34,10 +34,15 @@ pub use meta_client::{GrpcMetaClient, MetaClient, NotificationStream};
 mod compute_client;
 pub use compute_client::{ComputeClient, ExchangeSource};
 mod compute_client_pool;
-pub use compute_client_pool::{ComputeClientPool, ComputeClientPoolRef};
+pub use compute_client_pool::{
+    ComputeClientPool, ComputeClientPoolRef, RetryPolicy, DEFAULT_CONNECTIONS_PER_PEER,
+};
 mod hummock_meta_client;
 pub use hummock_meta_client::HummockMetaClient;
 mod stream_client_pool;
 pub use stream_client_pool::{StreamClient, StreamClientPool, StreamClientPoolRef};
 
 pub mod error;
+mod interceptor;
+pub use interceptor::{InterceptedChannel, RpcInterceptor, DEFAULT_RPC_DEADLINE};
+pub mod permits;
@@ -17,12 +17,15 @@ use std::time::Duration;
 use async_trait::async_trait;
 use paste::paste;
 use risingwave_common::catalog::{CatalogVersion, TableId};
+use risingwave_common::types::set_virtual_node_count;
 use risingwave_common::util::addr::HostAddr;
 use risingwave_hummock_sdk::{HummockEpoch, HummockSSTableId, HummockVersionId, LocalSstableInfo};
 use risingwave_pb::catalog::{
     Database as ProstDatabase, Schema as ProstSchema, Source as ProstSource, Table as ProstTable,
 };
 use risingwave_pb::common::WorkerType;
+use risingwave_pb::data::ConfigChange;
+use risingwave_pb::ddl_service::comment_on_request::OptionalColumnId;
 use risingwave_pb::ddl_service::ddl_service_client::DdlServiceClient;
 use risingwave_pb::ddl_service::*;
 use risingwave_pb::hummock::hummock_manager_service_client::HummockManagerServiceClient;
@@ -31,8 +34,10 @@ use risingwave_pb::meta::cluster_service_client::ClusterServiceClient;
 use risingwave_pb::meta::heartbeat_service_client::HeartbeatServiceClient;
 use risingwave_pb::meta::notification_service_client::NotificationServiceClient;
 use risingwave_pb::meta::stream_manager_service_client::StreamManagerServiceClient;
+use risingwave_pb::meta::system_params_service_client::SystemParamsServiceClient;
 use risingwave_pb::meta::*;
 use risingwave_pb::stream_plan::StreamFragmentGraph;
+use risingwave_pb::user::update_user_request::UpdateField;
 use risingwave_pb::user::user_service_client::UserServiceClient;
 use risingwave_pb::user::*;
 use tokio::sync::mpsc::Receiver;
@@ -42,8 +47,9 @@ use tokio_retry::strategy::{jitter, ExponentialBackoff};
 use tonic::transport::{Channel, Endpoint};
 use tonic::{Status, Streaming};
 
-use crate::error::Result;
+use crate::error::{anyhow, Result};
 use crate::hummock_meta_client::HummockMetaClient;
+use crate::{InterceptedChannel, RpcInterceptor, DEFAULT_RPC_DEADLINE};
 
 type DatabaseId = u32;
 type SchemaId = u32;
@@ -87,11 +93,28 @@ impl MetaClient {
 
     /// Register the current node to the cluster and set the corresponding worker id.
     pub async fn register(&mut self, addr: &HostAddr, worker_type: WorkerType) -> Result<u32> {
+        self.register_in_resource_group(addr, worker_type, "").await
+    }
+
+    /// Register the current node to the cluster, labeled with the resource group. Only compute
+    /// nodes are scheduled by resource groups.
+    pub async fn register_in_resource_group(
+        &mut self,
+        addr: &HostAddr,
+        worker_type: WorkerType,
+        resource_group: &str,
+    ) -> Result<u32> {
         let request = AddWorkerNodeRequest {
             worker_type: worker_type as i32,
             host: Some(addr.to_protobuf()),
+            resource_group: resource_group.to_string(),
         };
         let resp = self.inner.add_worker_node(request).await?;
+        // Hash codes must be mapped to vnodes in the same way as the other nodes of the cluster.
+        if resp.vnode_count != 0 {
+            set_virtual_node_count(resp.vnode_count as usize)
+                .map_err(|e| anyhow!(e.to_string()))?;
+        }
         let worker_node = resp.node.expect("AddWorkerNodeResponse::node is empty");
         self.set_worker_id(worker_node.id);
         Ok(worker_node.id)
@@ -106,12 +129,14 @@ impl MetaClient {
         Ok(())
     }
 
-    /// Send heartbeat signal to meta service.
-    pub async fn send_heartbeat(&self, node_id: u32) -> Result<()> {
-        let request = HeartbeatRequest {
+    /// Send heartbeat signal to meta service. It fails with `DeadlineExceeded` if the meta service
+    /// doesn't respond within `timeout`.
+    pub async fn send_heartbeat(&self, node_id: u32, timeout: Duration) -> Result<()> {
+        let mut request = tonic::Request::new(HeartbeatRequest {
             node_id,
             worker_type: WorkerType::ComputeNode as i32,
-        };
+        });
+        request.set_timeout(timeout);
         self.inner.heartbeat(request).await?;
         Ok(())
     }
@@ -155,6 +180,60 @@ impl MetaClient {
         Ok(resp.version)
     }
 
+    pub async fn replace_materialized_view(
+        &self,
+        table_id: TableId,
+        new_table_id: TableId,
+    ) -> Result<CatalogVersion> {
+        let request = ReplaceMaterializedViewRequest {
+            table_id: table_id.table_id(),
+            new_table_id: new_table_id.table_id(),
+        };
+
+        let resp = self.inner.replace_materialized_view(request).await?;
+        Ok(resp.version)
+    }
+
+    /// Replaces the query of the materialized view `table_id`. Returns the id of the new table
+    /// that takes over its name.
+    pub async fn alter_materialized_view(
+        &self,
+        table_id: TableId,
+        table: ProstTable,
+        graph: StreamFragmentGraph,
+    ) -> Result<(TableId, CatalogVersion)> {
+        let request = AlterMaterializedViewRequest {
+            table_id: table_id.table_id(),
+            materialized_view: Some(table),
+            fragment_graph: Some(graph),
+        };
+
+        let resp = self.inner.alter_materialized_view(request).await?;
+        Ok((resp.table_id.into(), resp.version))
+    }
+
+    /// Exports the full definition of a streaming job, to be imported into another cluster.
+    pub async fn export_streaming_job(&self, table_id: TableId) -> Result<StreamingJobSnapshot> {
+        let request = ExportStreamingJobRequest {
+            table_id: table_id.table_id(),
+        };
+
+        let resp = self.inner.export_streaming_job(request).await?;
+        Ok(resp.snapshot.unwrap())
+    }
+
+    pub async fn import_streaming_job(
+        &self,
+        snapshot: StreamingJobSnapshot,
+    ) -> Result<(TableId, CatalogVersion)> {
+        let request = ImportStreamingJobRequest {
+            snapshot: Some(snapshot),
+        };
+
+        let resp = self.inner.import_streaming_job(request).await?;
+        Ok((resp.table_id.into(), resp.version))
+    }
+
     pub async fn create_source(&self, source: ProstSource) -> Result<(u32, CatalogVersion)> {
         let request = CreateSourceRequest {
             source: Some(source),
@@ -212,6 +291,21 @@ impl MetaClient {
         Ok(resp.version)
     }
 
+    pub async fn comment_on(
+        &self,
+        table_id: u32,
+        column_id: Option<i32>,
+        description: String,
+    ) -> Result<CatalogVersion> {
+        let request = CommentOnRequest {
+            table_id,
+            optional_column_id: column_id.map(OptionalColumnId::ColumnId),
+            description,
+        };
+        let resp = self.inner.comment_on(request).await?;
+        Ok(resp.version)
+    }
+
     // TODO: using UserInfoVersion instead as return type.
     pub async fn create_user(&self, user: UserInfo) -> Result<u64> {
         let request = CreateUserRequest { user: Some(user) };
@@ -227,6 +321,19 @@ impl MetaClient {
         Ok(resp.version)
     }
 
+    pub async fn update_user(
+        &self,
+        user: UserInfo,
+        update_fields: Vec<UpdateField>,
+    ) -> Result<u64> {
+        let request = UpdateUserRequest {
+            user: Some(user),
+            update_fields: update_fields.into_iter().map(|f| f as i32).collect(),
+        };
+        let resp = self.inner.update_user(request).await?;
+        Ok(resp.version)
+    }
+
     pub async fn grant_privilege(
         &self,
         users: Vec<String>,
@@ -257,6 +364,16 @@ impl MetaClient {
         Ok(resp.version)
     }
 
+    /// Cordon the current node before shutting down, so that no new actors or tasks are scheduled
+    /// to it.
+    pub async fn cordon(&self, addr: &HostAddr) -> Result<()> {
+        let request = CordonWorkerNodeRequest {
+            host: Some(addr.to_protobuf()),
+        };
+        self.inner.cordon_worker_node(request).await?;
+        Ok(())
+    }
+
     /// Unregister the current node to the cluster.
     pub async fn unregister(&self, addr: HostAddr) -> Result<()> {
         let request = DeleteWorkerNodeRequest {
@@ -284,22 +401,14 @@ impl MetaClient {
                     }
                 }
                 tracing::trace!(target: "events::meta::client_heartbeat", "heartbeat");
-                match tokio::time::timeout(
-                    // TODO: decide better min_interval for timeout
-                    min_interval * 3,
-                    meta_client.send_heartbeat(meta_client.worker_id()),
-                )
-                .await
+                // TODO: decide better min_interval for timeout
+                if let Err(err) = meta_client
+                    .send_heartbeat(meta_client.worker_id(), min_interval * 3)
+                    .await
                 {
-                    Ok(Ok(_)) => {}
-                    Ok(Err(err)) => {
-                        tracing::warn!("Failed to send_heartbeat: error {}", err);
-                        if err.to_string().contains("unknown worker") {
-                            panic!("Already removed by the meta node. Need to restart the worker");
-                        }
-                    }
-                    Err(err) => {
-                        tracing::warn!("Failed to send_heartbeat: timeout {}", err);
+                    tracing::warn!("Failed to send_heartbeat: error {}", err);
+                    if err.to_string().contains("unknown worker") {
+                        panic!("Already removed by the meta node. Need to restart the worker");
                     }
                 }
             }
@@ -313,11 +422,81 @@ impl MetaClient {
         Ok(resp.tables)
     }
 
-    pub async fn flush(&self) -> Result<()> {
+    /// Gets the whole catalog and the users, to dump the DDL of the logical schema.
+    pub async fn get_catalog(&self) -> Result<CatalogSnapshot> {
+        let request = GetCatalogRequest {};
+        let resp = self.inner.get_catalog(request).await?;
+        Ok(resp.snapshot.unwrap())
+    }
+
+    /// Flushes all the pending writes, and returns the committed epoch containing them.
+    pub async fn flush(&self) -> Result<HummockEpoch> {
         let request = FlushRequest::default();
-        self.inner.flush(request).await?;
+        let resp = self.inner.flush(request).await?;
+        Ok(resp.snapshot.unwrap().epoch)
+    }
+
+    /// Takes a savepoint of the materialized view, and returns its epoch.
+    pub async fn savepoint(&self, table_id: u32) -> Result<HummockEpoch> {
+        let request = SavepointRequest { table_id };
+        let resp = self.inner.savepoint(request).await?;
+        Ok(resp.savepoint_epoch)
+    }
+
+    pub async fn restore_savepoint(&self, table_id: u32) -> Result<()> {
+        let request = RestoreSavepointRequest { table_id };
+        self.inner.restore_savepoint(request).await?;
+        Ok(())
+    }
+
+    pub async fn release_savepoint(&self, table_id: u32) -> Result<()> {
+        let request = ReleaseSavepointRequest { table_id };
+        self.inner.release_savepoint(request).await?;
         Ok(())
     }
+
+    /// Applies the config change to the source actors of the streaming job.
+    pub async fn reconfigure(&self, table_id: u32, change: ConfigChange) -> Result<()> {
+        let request = ReconfigureRequest {
+            table_id,
+            change: Some(change),
+        };
+        self.inner.reconfigure(request).await?;
+        Ok(())
+    }
+
+    pub async fn get_table_storage_stats(&self) -> Result<Vec<TableStorageStats>> {
+        let request = GetTableStorageStatsRequest {};
+        let resp = self.inner.get_table_storage_stats(request).await?;
+        Ok(resp.table_stats)
+    }
+
+    /// Replaces the current hummock version, see [`ResetCurrentVersionRequest`].
+    pub async fn reset_current_version(&self, version: HummockVersion) -> Result<HummockVersion> {
+        let request = ResetCurrentVersionRequest {
+            version: Some(version),
+        };
+        let resp = self.inner.reset_current_version(request).await?;
+        Ok(resp.version.unwrap())
+    }
+
+    pub async fn get_system_params(&self) -> Result<SystemParams> {
+        let request = GetSystemParamsRequest {};
+        let resp = self.inner.get_system_params(request).await?;
+        Ok(resp.params.unwrap())
+    }
+
+    pub async fn set_system_param(&self, param: String, value: String) -> Result<SystemParams> {
+        let request = SetSystemParamRequest { param, value };
+        let resp = self.inner.set_system_param(request).await?;
+        Ok(resp.params.unwrap())
+    }
+
+    pub async fn get_telemetry_report(&self) -> Result<TelemetryReport> {
+        let request = GetTelemetryReportRequest {};
+        let resp = self.inner.get_telemetry_report(request).await?;
+        Ok(resp.report.unwrap())
+    }
 }
 
 #[async_trait]
@@ -446,13 +625,14 @@ impl HummockMetaClient for MetaClient {
 /// Client to meta server. Cloning the instance is lightweight.
 #[derive(Debug, Clone)]
 pub struct GrpcMetaClient {
-    pub cluster_client: ClusterServiceClient<Channel>,
-    pub heartbeat_client: HeartbeatServiceClient<Channel>,
-    pub ddl_client: DdlServiceClient<Channel>,
-    pub hummock_client: HummockManagerServiceClient<Channel>,
-    pub notification_client: NotificationServiceClient<Channel>,
-    pub stream_client: StreamManagerServiceClient<Channel>,
-    pub user_client: UserServiceClient<Channel>,
+    pub cluster_client: ClusterServiceClient<InterceptedChannel>,
+    pub heartbeat_client: HeartbeatServiceClient<InterceptedChannel>,
+    pub ddl_client: DdlServiceClient<InterceptedChannel>,
+    pub hummock_client: HummockManagerServiceClient<InterceptedChannel>,
+    pub notification_client: NotificationServiceClient<InterceptedChannel>,
+    pub stream_client: StreamManagerServiceClient<InterceptedChannel>,
+    pub user_client: UserServiceClient<InterceptedChannel>,
+    pub system_params_client: SystemParamsServiceClient<InterceptedChannel>,
 }
 
 impl GrpcMetaClient {
@@ -483,13 +663,26 @@ impl GrpcMetaClient {
         })
         .await?;
 
-        let cluster_client = ClusterServiceClient::new(channel.clone());
-        let heartbeat_client = HeartbeatServiceClient::new(channel.clone());
-        let ddl_client = DdlServiceClient::new(channel.clone());
-        let hummock_client = HummockManagerServiceClient::new(channel.clone());
-        let notification_client = NotificationServiceClient::new(channel.clone());
-        let stream_client = StreamManagerServiceClient::new(channel.clone());
-        let user_client = UserServiceClient::new(channel);
+        // DDLs and flushes wait for barriers, and hummock serves a streaming RPC, so there's no
+        // deadline for them.
+        let short_rpc_interceptor = RpcInterceptor::with_deadline(DEFAULT_RPC_DEADLINE);
+        let cluster_client =
+            ClusterServiceClient::with_interceptor(channel.clone(), short_rpc_interceptor.clone());
+        let heartbeat_client = HeartbeatServiceClient::with_interceptor(
+            channel.clone(),
+            short_rpc_interceptor.clone(),
+        );
+        let ddl_client = DdlServiceClient::with_interceptor(channel.clone(), RpcInterceptor::new());
+        let hummock_client =
+            HummockManagerServiceClient::with_interceptor(channel.clone(), RpcInterceptor::new());
+        let notification_client =
+            NotificationServiceClient::with_interceptor(channel.clone(), RpcInterceptor::new());
+        let stream_client =
+            StreamManagerServiceClient::with_interceptor(channel.clone(), RpcInterceptor::new());
+        let user_client =
+            UserServiceClient::with_interceptor(channel.clone(), short_rpc_interceptor.clone());
+        let system_params_client =
+            SystemParamsServiceClient::with_interceptor(channel, short_rpc_interceptor);
         Ok(Self {
             cluster_client,
             heartbeat_client,
@@ -498,6 +691,7 @@ impl GrpcMetaClient {
             notification_client,
             stream_client,
             user_client,
+            system_params_client,
         })
     }
 }
@@ -506,7 +700,10 @@ macro_rules! grpc_meta_client_impl {
     ([], $( { $client:ident, $fn_name:ident, $req:ty, $resp:ty }),*) => {
         $(paste! {
             impl GrpcMetaClient {
-                pub async fn [<$fn_name>](&self, request: $req) -> Result<$resp> {
+                pub async fn [<$fn_name>](
+                    &self,
+                    request: impl tonic::IntoRequest<$req>,
+                ) -> Result<$resp> {
                     Ok(self
                         .$client
                         .to_owned()
@@ -526,9 +723,14 @@ macro_rules! for_all_meta_rpc {
             ,{ cluster_client, add_worker_node, AddWorkerNodeRequest, AddWorkerNodeResponse }
             ,{ cluster_client, activate_worker_node, ActivateWorkerNodeRequest, ActivateWorkerNodeResponse }
             ,{ cluster_client, delete_worker_node, DeleteWorkerNodeRequest, DeleteWorkerNodeResponse }
+            ,{ cluster_client, cordon_worker_node, CordonWorkerNodeRequest, CordonWorkerNodeResponse }
             ,{ cluster_client, list_all_nodes, ListAllNodesRequest, ListAllNodesResponse }
             ,{ heartbeat_client, heartbeat, HeartbeatRequest, HeartbeatResponse }
             ,{ stream_client, flush, FlushRequest, FlushResponse }
+            ,{ stream_client, savepoint, SavepointRequest, SavepointResponse }
+            ,{ stream_client, restore_savepoint, RestoreSavepointRequest, RestoreSavepointResponse }
+            ,{ stream_client, release_savepoint, ReleaseSavepointRequest, ReleaseSavepointResponse }
+            ,{ stream_client, reconfigure, ReconfigureRequest, ReconfigureResponse }
             ,{ ddl_client, create_materialized_source, CreateMaterializedSourceRequest, CreateMaterializedSourceResponse }
             ,{ ddl_client, create_materialized_view, CreateMaterializedViewRequest, CreateMaterializedViewResponse }
             ,{ ddl_client, create_source, CreateSourceRequest, CreateSourceResponse }
@@ -540,6 +742,12 @@ macro_rules! for_all_meta_rpc {
             ,{ ddl_client, drop_database, DropDatabaseRequest, DropDatabaseResponse }
             ,{ ddl_client, drop_schema, DropSchemaRequest, DropSchemaResponse }
             ,{ ddl_client, list_materialized_view, ListMaterializedViewRequest, ListMaterializedViewResponse }
+            ,{ ddl_client, comment_on, CommentOnRequest, CommentOnResponse }
+            ,{ ddl_client, replace_materialized_view, ReplaceMaterializedViewRequest, ReplaceMaterializedViewResponse }
+            ,{ ddl_client, alter_materialized_view, AlterMaterializedViewRequest, AlterMaterializedViewResponse }
+            ,{ ddl_client, export_streaming_job, ExportStreamingJobRequest, ExportStreamingJobResponse }
+            ,{ ddl_client, import_streaming_job, ImportStreamingJobRequest, ImportStreamingJobResponse }
+            ,{ ddl_client, get_catalog, GetCatalogRequest, GetCatalogResponse }
             ,{ hummock_client, pin_version, PinVersionRequest, PinVersionResponse }
             ,{ hummock_client, unpin_version, UnpinVersionRequest, UnpinVersionResponse }
             ,{ hummock_client, pin_snapshot, PinSnapshotRequest, PinSnapshotResponse }
@@ -552,10 +760,16 @@ macro_rules! for_all_meta_rpc {
             ,{ hummock_client, get_compaction_groups, GetCompactionGroupsRequest, GetCompactionGroupsResponse }
             ,{ hummock_client, trigger_manual_compaction, TriggerManualCompactionRequest, TriggerManualCompactionResponse }
             ,{ hummock_client, list_sstable_id_infos, ListSstableIdInfosRequest, ListSstableIdInfosResponse }
+            ,{ hummock_client, get_table_storage_stats, GetTableStorageStatsRequest, GetTableStorageStatsResponse }
+            ,{ hummock_client, reset_current_version, ResetCurrentVersionRequest, ResetCurrentVersionResponse }
             ,{ user_client, create_user, CreateUserRequest, CreateUserResponse }
             ,{ user_client, drop_user, DropUserRequest, DropUserResponse }
+            ,{ user_client, update_user, UpdateUserRequest, UpdateUserResponse }
             ,{ user_client, grant_privilege, GrantPrivilegeRequest, GrantPrivilegeResponse }
             ,{ user_client, revoke_privilege, RevokePrivilegeRequest, RevokePrivilegeResponse }
+            ,{ system_params_client, get_system_params, GetSystemParamsRequest, GetSystemParamsResponse }
+            ,{ system_params_client, set_system_param, SetSystemParamRequest, SetSystemParamResponse }
+            ,{ system_params_client, get_telemetry_report, GetTelemetryReportRequest, GetTelemetryReportResponse }
         }
     };
 }
@@ -0,0 +1,157 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Credit-based flow control of exchange channels.
+//!
+//! The receiver grants the sender a window of permits in bytes when opening a channel, and returns
+//! the permits of each message after consuming it. The sender waits for permits before sending a
+//! message, so that no more than a window of messages is buffered in between, however slow the
+//! receiver is.
+
+use std::sync::Arc;
+
+use futures::{Stream, StreamExt};
+use prometheus::IntGauge;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::Semaphore;
+use tonic::Status;
+
+/// Flow-control window of an exchange channel in bytes.
+pub const DEFAULT_EXCHANGE_PERMITS: u32 = 4 << 20;
+
+/// Returns the permits taken by a message of `bytes`. A message larger than the window takes the
+/// whole window, so that it can still be sent.
+fn permits_for(bytes: usize, window: u32) -> u32 {
+    bytes.min(window as usize) as u32
+}
+
+/// Permits granted to the sender of an exchange channel.
+#[derive(Clone)]
+pub struct Permits {
+    window: u32,
+    semaphore: Arc<Semaphore>,
+    /// Bytes sent but not yet consumed by the receiver.
+    buffered_bytes: Option<IntGauge>,
+}
+
+impl Permits {
+    pub fn new(window: u32) -> Self {
+        Self {
+            window,
+            semaphore: Arc::new(Semaphore::new(window as usize)),
+            buffered_bytes: None,
+        }
+    }
+
+    pub fn with_buffered_bytes(mut self, gauge: IntGauge) -> Self {
+        self.buffered_bytes = Some(gauge);
+        self
+    }
+
+    /// Spawns a task to add the permits returned by the receiver through `requests`. Once the
+    /// receiver closes the request stream, the permits are closed so that the sender stops
+    /// instead of waiting forever.
+    pub fn receive_from<R, S, F>(&self, mut requests: S, add_permits: F)
+    where
+        R: Send + 'static,
+        S: Stream<Item = std::result::Result<R, Status>> + Send + Unpin + 'static,
+        F: Fn(R) -> Option<u32> + Send + 'static,
+    {
+        let this = self.clone();
+        tokio::spawn(async move {
+            while let Some(Ok(request)) = requests.next().await {
+                if let Some(permits) = add_permits(request) {
+                    this.add(permits);
+                }
+            }
+            this.semaphore.close();
+        });
+    }
+
+    fn add(&self, permits: u32) {
+        self.semaphore.add_permits(permits as usize);
+        if let Some(gauge) = &self.buffered_bytes {
+            gauge.sub(permits as i64);
+        }
+    }
+
+    /// Waits for the permits of a message of `bytes`. Returns false if the receiver is gone.
+    pub async fn acquire(&self, bytes: usize) -> bool {
+        let permits = permits_for(bytes, self.window);
+        match self.semaphore.acquire_many(permits).await {
+            Ok(permit) => {
+                permit.forget();
+                if let Some(gauge) = &self.buffered_bytes {
+                    gauge.add(permits as i64);
+                }
+                true
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+/// Returns permits to the sender of an exchange channel.
+pub struct PermitSender<R> {
+    tx: UnboundedSender<R>,
+    window: u32,
+    make_request: fn(u32) -> R,
+}
+
+impl<R> PermitSender<R> {
+    pub(crate) fn new(tx: UnboundedSender<R>, window: u32, make_request: fn(u32) -> R) -> Self {
+        Self {
+            tx,
+            window,
+            make_request,
+        }
+    }
+
+    /// Returns the permits of a consumed message of `bytes`.
+    pub fn add_permits(&self, bytes: usize) {
+        // The sender is gone if this fails, and there is no need to notify it any more.
+        let _ = self
+            .tx
+            .send((self.make_request)(permits_for(bytes, self.window)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::sync::mpsc::unbounded_channel;
+    use tokio_stream::wrappers::UnboundedReceiverStream;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_permits() {
+        let (tx, rx) = unbounded_channel();
+        let permits = Permits::new(100);
+        permits.receive_from(UnboundedReceiverStream::new(rx).map(Ok), Some);
+        let permit_sender = PermitSender::new(tx, 100, |permits| permits);
+
+        assert!(permits.acquire(60).await);
+        // Larger than the window, so it takes the whole window.
+        let blocked = tokio::time::timeout(Duration::from_millis(100), permits.acquire(1000));
+        assert!(blocked.await.is_err());
+
+        permit_sender.add_permits(60);
+        assert!(permits.acquire(1000).await);
+
+        drop(permit_sender);
+        assert!(!permits.acquire(1).await);
+    }
+}
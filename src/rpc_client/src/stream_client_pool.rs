@@ -20,11 +20,12 @@ use moka::future::Cache;
 use risingwave_common::util::addr::HostAddr;
 use risingwave_pb::common::WorkerNode;
 use risingwave_pb::stream_service::stream_service_client::StreamServiceClient;
-use tonic::transport::{Channel, Endpoint};
+use tonic::transport::Endpoint;
 
 use crate::error::{Result, RpcError};
+use crate::{InterceptedChannel, RpcInterceptor};
 
-pub type StreamClient = StreamServiceClient<Channel>;
+pub type StreamClient = StreamServiceClient<InterceptedChannel>;
 
 pub type WorkerId = u32;
 
@@ -49,16 +50,17 @@ impl StreamClientPool {
 
     /// Get the stream service client for the given node. If the connection is not established, a
     /// new client will be created and returned.
-    pub async fn get(&self, node: &WorkerNode) -> Result<StreamServiceClient<Channel>> {
+    pub async fn get(&self, node: &WorkerNode) -> Result<StreamClient> {
         self.clients
             .try_get_with(node.id, async {
                 let addr: HostAddr = node.get_host().unwrap().into();
                 let endpoint = Endpoint::from_shared(format!("http://{}", addr))?;
-                let client = StreamServiceClient::new(
+                let client = StreamServiceClient::with_interceptor(
                     endpoint
                         .connect_timeout(Duration::from_secs(5))
                         .connect()
                         .await?,
+                    RpcInterceptor::new(),
                 );
                 Ok::<_, RpcError>(client)
             })
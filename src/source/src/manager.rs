@@ -29,7 +29,7 @@ use risingwave_pb::catalog::StreamSourceInfo;
 use risingwave_pb::plan_common::RowFormatType;
 
 use crate::row_id::{RowId, RowIdGenerator};
-use crate::table_v2::TableSourceV2;
+use crate::table_v2::{DmlChannelConfig, TableSourceV2};
 use crate::{ConnectorSource, SourceFormat, SourceImpl, SourceParserImpl};
 
 pub type SourceRef = Arc<SourceImpl>;
@@ -103,6 +103,8 @@ pub struct MemSourceManager {
     sources: Mutex<HashMap<TableId, SourceDesc>>,
     /// Located worker id.
     worker_id: u32,
+    /// Limits of the changes buffered in table sources.
+    dml_config: DmlChannelConfig,
 }
 
 #[async_trait]
@@ -192,7 +194,7 @@ impl SourceManager for MemSourceManager {
         );
 
         let source_columns = columns.iter().map(SourceColumnDesc::from).collect();
-        let source = SourceImpl::TableV2(TableSourceV2::new(columns));
+        let source = SourceImpl::TableV2(TableSourceV2::with_config(columns, self.dml_config));
 
         // Table sources do not need columns and format
         let desc = SourceDesc {
@@ -237,9 +239,14 @@ impl SourceManager for MemSourceManager {
 
 impl MemSourceManager {
     pub fn new(worker_id: u32) -> Self {
+        Self::with_dml_config(worker_id, DmlChannelConfig::default())
+    }
+
+    pub fn with_dml_config(worker_id: u32, dml_config: DmlChannelConfig) -> Self {
         MemSourceManager {
             sources: Mutex::new(HashMap::new()),
             worker_id,
+            dml_config,
         }
     }
 
@@ -16,7 +16,7 @@ use num_traits::FromPrimitive;
 use risingwave_common::error::ErrorCode::{self, InternalError};
 use risingwave_common::error::{Result, RwError};
 use risingwave_common::types::{DataType, Decimal, ScalarImpl, ScalarRef};
-use risingwave_expr::vector_op::cast::{str_to_date, str_to_timestamp};
+use risingwave_expr::vector_op::cast::{str_to_date, str_to_timestamp, str_to_uuid};
 use serde_json::Value;
 
 use crate::SourceColumnDesc;
@@ -88,6 +88,10 @@ pub(crate) fn json_parse_value(
             None => Err(RwError::from(InternalError("parse error".to_string()))),
             Some(date_str) => Ok(ScalarImpl::NaiveDateTime(str_to_timestamp(date_str)?)),
         },
+        DataType::Uuid => match value.and_then(|v| v.as_str()) {
+            None => Err(RwError::from(InternalError("parse error".to_string()))),
+            Some(uuid_str) => Ok(ScalarImpl::Uuid(str_to_uuid(uuid_str)?)),
+        },
         _ => Err(ErrorCode::NotImplemented(
             "unsupported type for json_parse_value".to_string(),
             None.into(),
@@ -13,24 +13,68 @@
 // limitations under the License.
 
 use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::RwLock;
+use std::sync::{Arc, RwLock};
 
 use async_trait::async_trait;
 use rand::prelude::SliceRandom;
 use risingwave_common::array::StreamChunk;
 use risingwave_common::catalog::{ColumnDesc, ColumnId};
-use risingwave_common::error::Result;
-use tokio::sync::{mpsc, oneshot};
+use risingwave_common::config::BatchConfig;
+use risingwave_common::error::{ErrorCode, Result};
+use tokio::sync::{mpsc, oneshot, OwnedSemaphorePermit, Semaphore};
 
 use crate::{StreamChunkWithState, StreamSourceReader};
 
+/// Priority of the changes written to a table. High priority changes are taken by the reader
+/// before low priority ones, and they're buffered within separate quotas, so that interactive DML
+/// isn't blocked by bulk loads.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DmlPriority {
+    High,
+    Low,
+}
+
+/// Limits of the changes buffered in a [`TableSourceV2`].
+#[derive(Clone, Copy, Debug)]
+pub struct DmlChannelConfig {
+    /// Rows written but not yet taken by the reader, for each priority.
+    pub max_buffered_rows: usize,
+    /// Whether writes fail instead of waiting when the quota is exhausted.
+    pub fail_on_full: bool,
+}
+
+impl DmlChannelConfig {
+    pub fn new(config: &BatchConfig) -> Self {
+        Self {
+            max_buffered_rows: config.dml_channel_max_buffered_rows,
+            fail_on_full: config.dml_fail_on_full,
+        }
+    }
+}
+
+impl Default for DmlChannelConfig {
+    fn default() -> Self {
+        Self::new(&BatchConfig::default())
+    }
+}
+
+/// A chunk written to the table, with the notifier of its cardinality and the quota it holds until
+/// taken by the reader.
+type ChangeMessage = (StreamChunk, oneshot::Sender<usize>, OwnedSemaphorePermit);
+
+#[derive(Debug)]
+struct ChangesSender {
+    high: mpsc::UnboundedSender<ChangeMessage>,
+    low: mpsc::UnboundedSender<ChangeMessage>,
+}
+
 #[derive(Debug)]
 struct TableSourceV2Core {
     /// The senders of the changes channel.
     ///
     /// When a `StreamReader` is created, a channel will be created and the sender will be
     /// saved here. The insert statement will take one channel randomly.
-    changes_txs: Vec<mpsc::UnboundedSender<(StreamChunk, oneshot::Sender<usize>)>>,
+    changes_txs: Vec<ChangesSender>,
 }
 
 /// [`TableSourceV2`] is a special internal source to handle table updates from user,
@@ -48,10 +92,20 @@ pub struct TableSourceV2 {
 
     /// Current allocated row id.
     next_row_id: AtomicUsize,
+
+    config: DmlChannelConfig,
+
+    /// Quotas of the buffered rows of high and low priority respectively.
+    high_quota: Arc<Semaphore>,
+    low_quota: Arc<Semaphore>,
 }
 
 impl TableSourceV2 {
     pub fn new(column_descs: Vec<ColumnDesc>) -> Self {
+        Self::with_config(column_descs, DmlChannelConfig::default())
+    }
+
+    pub fn with_config(column_descs: Vec<ColumnDesc>, config: DmlChannelConfig) -> Self {
         let core = TableSourceV2Core {
             changes_txs: vec![],
         };
@@ -60,6 +114,9 @@ impl TableSourceV2 {
             core: RwLock::new(core),
             column_descs,
             next_row_id: 0.into(),
+            config,
+            high_quota: Arc::new(Semaphore::new(config.max_buffered_rows)),
+            low_quota: Arc::new(Semaphore::new(config.max_buffered_rows)),
         }
     }
 
@@ -74,20 +131,46 @@ impl TableSourceV2 {
     /// Asynchronously write stream chunk into table. Changes written here will be simply passed to
     /// the associated streaming task via channel, and then be materialized to storage there.
     ///
+    /// Waits until the rows of the chunk fit in the quota of `priority`, or fails if
+    /// [`DmlChannelConfig::fail_on_full`] is set.
+    ///
     /// Returns an oneshot channel which will be notified when the chunk is taken by some reader,
     /// and the `usize` represents the cardinality of this chunk.
-    pub fn write_chunk(&self, chunk: StreamChunk) -> Result<oneshot::Receiver<usize>> {
-        let tx = {
-            let core = self.core.read().unwrap();
-            core.changes_txs
-                .choose(&mut rand::thread_rng())
-                .expect("no table reader exists")
-                .clone()
+    pub async fn write_chunk(
+        &self,
+        chunk: StreamChunk,
+        priority: DmlPriority,
+    ) -> Result<oneshot::Receiver<usize>> {
+        let quota = match priority {
+            DmlPriority::High => &self.high_quota,
+            DmlPriority::Low => &self.low_quota,
+        };
+        // A chunk larger than the quota takes all of it.
+        let rows = chunk.cardinality().min(self.config.max_buffered_rows) as u32;
+        let permit = if self.config.fail_on_full {
+            quota.clone().try_acquire_many_owned(rows).map_err(|_| {
+                ErrorCode::ResourceLimitExceeded(format!(
+                    "more than {} rows written to the table are waiting to be processed",
+                    self.config.max_buffered_rows
+                ))
+            })?
+        } else {
+            // The semaphore is never closed.
+            quota.clone().acquire_many_owned(rows).await.unwrap()
         };
 
         let (notifier_tx, notifier_rx) = oneshot::channel();
-        tx.send((chunk, notifier_tx))
-            .expect("write chunk to table reader failed");
+        let message = (chunk, notifier_tx, permit);
+        let core = self.core.read().unwrap();
+        let tx = core
+            .changes_txs
+            .choose(&mut rand::thread_rng())
+            .expect("no table reader exists");
+        match priority {
+            DmlPriority::High => tx.high.send(message),
+            DmlPriority::Low => tx.low.send(message),
+        }
+        .expect("write chunk to table reader failed");
 
         Ok(notifier_rx)
     }
@@ -96,8 +179,12 @@ impl TableSourceV2 {
     /// the chunk.
     ///
     /// Returns the cardinality of this chunk.
-    pub async fn blocking_write_chunk(&self, chunk: StreamChunk) -> Result<usize> {
-        let rx = self.write_chunk(chunk)?;
+    pub async fn blocking_write_chunk(
+        &self,
+        chunk: StreamChunk,
+        priority: DmlPriority,
+    ) -> Result<usize> {
+        let rx = self.write_chunk(chunk, priority).await?;
         let written_cardinality = rx.await.unwrap();
         Ok(written_cardinality)
     }
@@ -114,8 +201,9 @@ pub struct TableV2BatchReader;
 /// structure of "`MView` on `MView`".
 #[derive(Debug)]
 pub struct TableV2StreamReader {
-    /// The receiver of the changes channel.
-    rx: mpsc::UnboundedReceiver<(StreamChunk, oneshot::Sender<usize>)>,
+    /// The receivers of the changes channel, of high and low priority respectively.
+    high_rx: mpsc::UnboundedReceiver<ChangeMessage>,
+    low_rx: mpsc::UnboundedReceiver<ChangeMessage>,
 
     /// Mappings from the source column to the column to be read.
     column_indices: Vec<usize>,
@@ -124,11 +212,12 @@ pub struct TableV2StreamReader {
 #[async_trait]
 impl StreamSourceReader for TableV2StreamReader {
     async fn next(&mut self) -> Result<StreamChunkWithState> {
-        let (chunk, notifier) = self
-            .rx
-            .recv()
-            .await
-            .expect("TableSourceV2 dropped before associated streaming task terminated");
+        let (chunk, notifier, permit) = tokio::select! {
+            biased;
+            Some(message) = self.high_rx.recv() => message,
+            Some(message) = self.low_rx.recv() => message,
+            else => panic!("TableSourceV2 dropped before associated streaming task terminated"),
+        };
 
         // Caveats: this function is an arm of `tokio::select`. We should ensure there's no `await`
         // after here.
@@ -142,8 +231,9 @@ impl StreamSourceReader for TableV2StreamReader {
             .collect();
         let chunk = StreamChunk::new(ops, selected_columns, bitmap);
 
-        // Notify about that we've taken the chunk.
+        // Notify about that we've taken the chunk, and release its quota.
         notifier.send(chunk.cardinality()).ok();
+        drop(permit);
 
         Ok(StreamChunkWithState {
             chunk,
@@ -166,10 +256,18 @@ impl TableSourceV2 {
             .collect();
 
         let mut core = self.core.write().unwrap();
-        let (tx, rx) = mpsc::unbounded_channel();
-        core.changes_txs.push(tx);
-
-        Ok(TableV2StreamReader { rx, column_indices })
+        let (high_tx, high_rx) = mpsc::unbounded_channel();
+        let (low_tx, low_rx) = mpsc::unbounded_channel();
+        core.changes_txs.push(ChangesSender {
+            high: high_tx,
+            low: low_tx,
+        });
+
+        Ok(TableV2StreamReader {
+            high_rx,
+            low_rx,
+            column_indices,
+        })
     }
 }
 
@@ -179,6 +277,7 @@ mod tests {
 
     use assert_matches::assert_matches;
     use itertools::Itertools;
+    use risingwave_common::array::column::Column;
     use risingwave_common::array::{Array, I64Array, Op};
     use risingwave_common::column_nonnull;
     use risingwave_common::types::DataType;
@@ -211,7 +310,10 @@ mod tests {
                     None,
                 );
                 tokio::spawn(async move {
-                    source.blocking_write_chunk(chunk).await.unwrap();
+                    source
+                        .blocking_write_chunk(chunk, DmlPriority::High)
+                        .await
+                        .unwrap();
                 })
             }};
         }
@@ -233,4 +335,54 @@ mod tests {
 
         Ok(())
     }
+
+    fn new_chunk(values: &[i64]) -> StreamChunk {
+        StreamChunk::new(
+            vec![Op::Insert; values.len()],
+            vec![Column::new(Arc::new(
+                I64Array::from_slice(&values.iter().map(|v| Some(*v)).collect_vec())
+                    .unwrap()
+                    .into(),
+            ))],
+            None,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_dml_priority_and_quota() -> Result<()> {
+        let source = TableSourceV2::with_config(
+            vec![ColumnDesc::unnamed(ColumnId::from(0), DataType::Int64)],
+            DmlChannelConfig {
+                max_buffered_rows: 2,
+                fail_on_full: true,
+            },
+        );
+        let mut reader = source.stream_reader(vec![ColumnId::from(0)]).await?;
+
+        let low = source
+            .write_chunk(new_chunk(&[1, 2]), DmlPriority::Low)
+            .await?;
+        // The quota of low priority is exhausted, but not the one of high priority.
+        assert!(source
+            .write_chunk(new_chunk(&[3]), DmlPriority::Low)
+            .await
+            .is_err());
+        let high = source
+            .write_chunk(new_chunk(&[4]), DmlPriority::High)
+            .await?;
+
+        // High priority changes are taken first.
+        assert_eq!(reader.next().await?.chunk.cardinality(), 1);
+        assert_eq!(high.await.unwrap(), 1);
+        assert_eq!(reader.next().await?.chunk.cardinality(), 2);
+        assert_eq!(low.await.unwrap(), 2);
+
+        // The quota is released once the chunks are taken.
+        source
+            .write_chunk(new_chunk(&[5, 6]), DmlPriority::Low)
+            .await?;
+        assert_eq!(reader.next().await?.chunk.cardinality(), 2);
+
+        Ok(())
+    }
 }
@@ -77,6 +77,8 @@ pub enum DataType {
     Array(Box<DataType>),
     /// Structs
     Struct(Vec<StructField>),
+    /// Vector of floating points with the dimension e.g. VECTOR(3)
+    Vector(u64),
 }
 
 impl fmt::Display for DataType {
@@ -123,6 +125,7 @@ impl fmt::Display for DataType {
             DataType::Struct(defs) => {
                 write!(f, "STRUCT<{}>", display_comma_separated(defs))
             }
+            DataType::Vector(dim) => write!(f, "VECTOR({})", dim),
         }
     }
 }
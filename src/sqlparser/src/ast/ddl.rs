@@ -20,7 +20,10 @@ use core::fmt;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use crate::ast::{display_comma_separated, display_separated, DataType, Expr, Ident, ObjectName};
+use crate::ast::{
+    display_comma_separated, display_separated, DataType, Expr, Ident, ObjectName, Query,
+    SetVariableValue, Value,
+};
 use crate::tokenizer::Token;
 
 /// An `ALTER TABLE` (`Statement::AlterTable`) operation
@@ -43,6 +46,11 @@ pub enum AlterTableOperation {
         if_exists: bool,
         cascade: bool,
     },
+    /// `DROP PARTITION <value>`, dropping the partition of a partitioned table containing the
+    /// timestamp `value`.
+    DropPartition {
+        value: Value,
+    },
     /// `RENAME [ COLUMN ] <old_column_name> TO <new_column_name>`
     RenameColumn {
         old_column_name: Ident,
@@ -77,6 +85,44 @@ pub enum AlterTableOperation {
     },
 }
 
+/// An `ALTER MATERIALIZED VIEW` (`Statement::AlterMaterializedView`) operation
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AlterMaterializedViewOperation {
+    /// `RESTORE FROM SAVEPOINT`
+    RestoreFromSavepoint,
+    /// `RELEASE SAVEPOINT`
+    ReleaseSavepoint,
+    /// `AS <query>`
+    AsQuery { query: Box<Query> },
+    /// `PAUSE`
+    Pause,
+    /// `RESUME`
+    Resume,
+    /// `SET <param> { = | TO } <value>`
+    SetParam {
+        param: Ident,
+        value: SetVariableValue,
+    },
+}
+
+impl fmt::Display for AlterMaterializedViewOperation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AlterMaterializedViewOperation::RestoreFromSavepoint => {
+                write!(f, "RESTORE FROM SAVEPOINT")
+            }
+            AlterMaterializedViewOperation::ReleaseSavepoint => write!(f, "RELEASE SAVEPOINT"),
+            AlterMaterializedViewOperation::AsQuery { query } => write!(f, "AS {}", query),
+            AlterMaterializedViewOperation::Pause => write!(f, "PAUSE"),
+            AlterMaterializedViewOperation::Resume => write!(f, "RESUME"),
+            AlterMaterializedViewOperation::SetParam { param, value } => {
+                write!(f, "SET {} = {}", param, value)
+            }
+        }
+    }
+}
+
 impl fmt::Display for AlterTableOperation {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -99,6 +145,7 @@ impl fmt::Display for AlterTableOperation {
                 column_name,
                 if *cascade { " CASCADE" } else { "" }
             ),
+            AlterTableOperation::DropPartition { value } => write!(f, "DROP PARTITION {}", value),
             AlterTableOperation::RenameColumn {
                 old_column_name,
                 new_column_name,
@@ -421,3 +468,25 @@ impl fmt::Display for ReferentialAction {
         })
     }
 }
+
+/// `ON CONFLICT { OVERWRITE | IGNORE | DO UPDATE FULL }`
+///
+/// Used in `CREATE TABLE` and `CREATE MATERIALIZED SOURCE` to handle the rows inserted with an
+/// existing primary key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum OnConflict {
+    Overwrite,
+    Ignore,
+    DoUpdateFull,
+}
+
+impl fmt::Display for OnConflict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            OnConflict::Overwrite => "OVERWRITE",
+            OnConflict::Ignore => "IGNORE",
+            OnConflict::DoUpdateFull => "DO UPDATE FULL",
+        })
+    }
+}
@@ -32,14 +32,15 @@ use serde::{Deserialize, Serialize};
 
 pub use self::data_type::{DataType, StructField};
 pub use self::ddl::{
-    AlterColumnOperation, AlterTableOperation, ColumnDef, ColumnOption, ColumnOptionDef,
-    ReferentialAction, TableConstraint,
+    AlterColumnOperation, AlterMaterializedViewOperation, AlterTableOperation, ColumnDef,
+    ColumnOption, ColumnOptionDef, OnConflict, ReferentialAction, TableConstraint,
 };
 pub use self::operator::{BinaryOperator, UnaryOperator};
 pub use self::query::{
-    Cte, Fetch, Join, JoinConstraint, JoinOperator, LateralView, Offset, OffsetRows, OrderByExpr,
-    Query, Select, SelectItem, SetExpr, SetOperator, TableAlias, TableFactor, TableWithJoins, Top,
-    Values, With,
+    Cte, Fetch, Join, JoinConstraint, JoinOperator, LateralView, MatchRecognize, Measure, Offset,
+    OffsetRows, OrderByExpr, PatternQuantifier, PatternTerm, Query, Select, SelectItem, SetExpr,
+    SetOperator, SymbolDefinition, TableAlias, TableFactor, TableSample, TableSampleMethod,
+    TableWithJoins, Top, Values, With,
 };
 pub use self::statement::*;
 pub use self::value::{DateTimeField, TrimWhereField, Value};
@@ -647,6 +648,7 @@ pub enum ShowObject {
     Sink { _schema: Option<Ident> },
     MaterializedSource { schema: Option<Ident> },
     Columns { table: ObjectName },
+    InternalTable { mview: ObjectName },
 }
 
 impl fmt::Display for ShowObject {
@@ -674,15 +676,37 @@ impl fmt::Display for ShowObject {
             }
             ShowObject::Sink { _schema } => write!(f, "SINKS{}", fmt_schema(_schema)),
             ShowObject::Columns { table } => write!(f, "COLUMNS FROM {}", table),
+            ShowObject::InternalTable { mview } => write!(f, "INTERNAL TABLES FROM {}", mview),
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ExplainFormat {
+    Text,
+    Json,
+    Yaml,
+    Dot,
+}
+
+impl fmt::Display for ExplainFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            ExplainFormat::Text => "TEXT",
+            ExplainFormat::Json => "JSON",
+            ExplainFormat::Yaml => "YAML",
+            ExplainFormat::Dot => "DOT",
+        })
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum CommentObject {
     Column,
     Table,
+    MaterializedView,
 }
 
 impl fmt::Display for CommentObject {
@@ -690,6 +714,7 @@ impl fmt::Display for CommentObject {
         match self {
             CommentObject::Column => f.write_str("COLUMN"),
             CommentObject::Table => f.write_str("TABLE"),
+            CommentObject::MaterializedView => f.write_str("MATERIALIZED VIEW"),
         }
     }
 }
@@ -742,6 +767,7 @@ pub enum Statement {
     CreateView {
         or_replace: bool,
         materialized: bool,
+        if_not_exists: bool,
         /// View name
         name: ObjectName,
         columns: Vec<Ident>,
@@ -758,6 +784,10 @@ pub enum Statement {
         /// Optional schema
         columns: Vec<ColumnDef>,
         constraints: Vec<TableConstraint>,
+        /// `APPEND ONLY`
+        append_only: bool,
+        /// `ON CONFLICT` behavior of the primary key
+        on_conflict: Option<OnConflict>,
         table_properties: Vec<SqlOption>,
         with_options: Vec<SqlOption>,
         query: Option<Box<Query>>,
@@ -768,6 +798,8 @@ pub enum Statement {
         /// index name
         name: ObjectName,
         table_name: ObjectName,
+        /// The index method of `USING method`, e.g. `FULLTEXT`
+        using: Option<Ident>,
         columns: Vec<OrderByExpr>,
         unique: bool,
         if_not_exists: bool,
@@ -779,12 +811,43 @@ pub enum Statement {
     },
     /// CREATE SINK
     CreateSink { stmt: CreateSinkStatement },
+    /// CREATE EXTERNAL TABLE
+    CreateExternalTable { stmt: CreateExternalTableStatement },
+    /// CREATE EXTERNAL SCHEMA
+    CreateExternalSchema { stmt: CreateExternalSchemaStatement },
+    /// CREATE SUBSCRIPTION
+    CreateSubscription { stmt: CreateSubscriptionStatement },
     /// ALTER TABLE
     AlterTable {
         /// Table name
         name: ObjectName,
         operation: AlterTableOperation,
     },
+    /// ALTER MATERIALIZED VIEW
+    AlterMaterializedView {
+        /// Materialized view name
+        name: ObjectName,
+        operation: AlterMaterializedViewOperation,
+    },
+    /// SAVEPOINT MATERIALIZED VIEW
+    SavepointMaterializedView {
+        /// Materialized view name
+        name: ObjectName,
+    },
+    /// ALTER SYSTEM SET
+    AlterSystem {
+        /// Name of the system parameter
+        param: Ident,
+        value: SetVariableValue,
+    },
+    /// ALTER USER SET / RESET
+    AlterUser {
+        /// User name
+        name: ObjectName,
+        param: Ident,
+        /// `None` for `RESET`
+        value: Option<SetVariableValue>,
+    },
     /// DESCRIBE TABLE OR SOURCE
     Describe {
         /// Table or Source name
@@ -883,11 +946,51 @@ pub enum Statement {
         analyze: bool,
         // Display additional information regarding the plan.
         verbose: bool,
+        /// The output format of the plan.
+        format: ExplainFormat,
+        /// Display the rules applied by the optimizer and the intermediate plans.
+        trace: bool,
+        /// Display the streaming plan of a statement creating a streaming job.
+        streaming: bool,
+        /// Display the fragment graph of a streaming job, with the actors of each fragment.
+        distributed: bool,
         /// A SQL query that specifies what to explain
         statement: Box<Statement>,
     },
     /// CREATE USER
     CreateUser(CreateUserStatement),
+    /// `DECLARE cursor_name SUBSCRIPTION CURSOR FOR subscription_name`
+    ///
+    /// Note: RisingWave specific statement.
+    DeclareCursor {
+        cursor_name: Ident,
+        subscription: ObjectName,
+    },
+    /// `FETCH [ count | NEXT ] [ FROM | IN ] cursor_name`
+    Fetch { cursor_name: Ident, count: u64 },
+    /// `CLOSE cursor_name`
+    CloseCursor { cursor_name: Ident },
+    /// `LISTEN channel [ WHERE expr ]`
+    ///
+    /// Note: the channel is a materialized view, and the WHERE clause is RisingWave specific.
+    Listen {
+        channel: ObjectName,
+        selection: Option<Expr>,
+    },
+    /// `UNLISTEN { channel | * }`
+    Unlisten { channel: Option<ObjectName> },
+    /// `UNLOAD (query) TO 'location' FORMAT { PARQUET | CSV }`
+    ///
+    /// Note: RisingWave specific statement.
+    Unload {
+        query: Box<Query>,
+        location: String,
+        format: UnloadFormat,
+    },
+    /// `REPLAY 'location'`, which replays a query captured in the bundle at `location`.
+    ///
+    /// Note: RisingWave specific statement.
+    Replay { location: String },
     /// FLUSH the current barrier.
     ///
     /// Note: RisingWave specific statement.
@@ -904,6 +1007,10 @@ impl fmt::Display for Statement {
                 describe_alias,
                 verbose,
                 analyze,
+                format,
+                trace,
+                streaming,
+                distributed,
                 statement,
             } => {
                 if *describe_alias {
@@ -912,12 +1019,35 @@ impl fmt::Display for Statement {
                     write!(f, "EXPLAIN ")?;
                 }
 
-                if *analyze {
-                    write!(f, "ANALYZE ")?;
-                }
+                if *format != ExplainFormat::Text || *trace || *streaming || *distributed {
+                    let mut options = vec![];
+                    if *analyze {
+                        options.push("ANALYZE".to_string());
+                    }
+                    if *verbose {
+                        options.push("VERBOSE".to_string());
+                    }
+                    if *trace {
+                        options.push("TRACE".to_string());
+                    }
+                    if *streaming {
+                        options.push("STREAMING".to_string());
+                    }
+                    if *distributed {
+                        options.push("DISTRIBUTED".to_string());
+                    }
+                    if *format != ExplainFormat::Text {
+                        options.push(format!("FORMAT {}", format));
+                    }
+                    write!(f, "({}) ", display_comma_separated(&options))?;
+                } else {
+                    if *analyze {
+                        write!(f, "ANALYZE ")?;
+                    }
 
-                if *verbose {
-                    write!(f, "VERBOSE ")?;
+                    if *verbose {
+                        write!(f, "VERBOSE ")?;
+                    }
                 }
 
                 write!(f, "{}", statement)
@@ -1025,13 +1155,15 @@ impl fmt::Display for Statement {
                 columns,
                 query,
                 materialized,
+                if_not_exists,
                 with_options,
             } => {
                 write!(
                     f,
-                    "CREATE {or_replace}{materialized}VIEW {name}",
+                    "CREATE {or_replace}{materialized}VIEW {if_not_exists}{name}",
                     or_replace = if *or_replace { "OR REPLACE " } else { "" },
                     materialized = if *materialized { "MATERIALIZED " } else { "" },
+                    if_not_exists = if *if_not_exists { "IF NOT EXISTS " } else { "" },
                     name = name
                 )?;
                 if !with_options.is_empty() {
@@ -1046,6 +1178,8 @@ impl fmt::Display for Statement {
                 name,
                 columns,
                 constraints,
+                append_only,
+                on_conflict,
                 table_properties,
                 with_options,
                 or_replace,
@@ -1079,6 +1213,12 @@ impl fmt::Display for Statement {
                     // PostgreSQL allows `CREATE TABLE t ();`, but requires empty parens
                     write!(f, " ()")?;
                 }
+                if *append_only {
+                    write!(f, " APPEND ONLY")?;
+                }
+                if let Some(on_conflict) = on_conflict {
+                    write!(f, " ON CONFLICT {}", on_conflict)?;
+                }
                 if !table_properties.is_empty() {
                     write!(f, " WITH ({})", display_comma_separated(table_properties))?;
                 }
@@ -1093,16 +1233,21 @@ impl fmt::Display for Statement {
             Statement::CreateIndex {
                 name,
                 table_name,
+                using,
                 columns,
                 unique,
                 if_not_exists,
             } => write!(
                 f,
-                "CREATE {unique}INDEX {if_not_exists}{name} ON {table_name}({columns})",
+                "CREATE {unique}INDEX {if_not_exists}{name} ON {table_name}{using}({columns})",
                 unique = if *unique { "UNIQUE " } else { "" },
                 if_not_exists = if *if_not_exists { "IF NOT EXISTS " } else { "" },
                 name = name,
                 table_name = table_name,
+                using = match using {
+                    Some(using) => format!(" USING {} ", using),
+                    None => "".to_string(),
+                },
                 columns = display_separated(columns, ",")
             ),
             Statement::CreateSource {
@@ -1119,9 +1264,27 @@ impl fmt::Display for Statement {
                 }
             ),
             Statement::CreateSink { stmt } => write!(f, "CREATE SINK {}", stmt,),
+            Statement::CreateExternalTable { stmt } => write!(f, "CREATE EXTERNAL TABLE {}", stmt),
+            Statement::CreateExternalSchema { stmt } => {
+                write!(f, "CREATE EXTERNAL SCHEMA {}", stmt)
+            }
+            Statement::CreateSubscription { stmt } => write!(f, "CREATE SUBSCRIPTION {}", stmt),
             Statement::AlterTable { name, operation } => {
                 write!(f, "ALTER TABLE {} {}", name, operation)
             }
+            Statement::AlterMaterializedView { name, operation } => {
+                write!(f, "ALTER MATERIALIZED VIEW {} {}", name, operation)
+            }
+            Statement::SavepointMaterializedView { name } => {
+                write!(f, "SAVEPOINT MATERIALIZED VIEW {}", name)
+            }
+            Statement::AlterSystem { param, value } => {
+                write!(f, "ALTER SYSTEM SET {} = {}", param, value)
+            }
+            Statement::AlterUser { name, param, value } => match value {
+                Some(value) => write!(f, "ALTER USER {} SET {} = {}", name, param, value),
+                None => write!(f, "ALTER USER {} RESET {}", name, param),
+            },
             Statement::Drop(stmt) => write!(f, "DROP {}", stmt),
             Statement::SetVariable {
                 local,
@@ -1273,6 +1436,45 @@ impl fmt::Display for Statement {
             Statement::CreateUser(statement) => {
                 write!(f, "CREATE USER {}", statement)
             }
+            Statement::DeclareCursor {
+                cursor_name,
+                subscription,
+            } => write!(
+                f,
+                "DECLARE {} SUBSCRIPTION CURSOR FOR {}",
+                cursor_name, subscription
+            ),
+            Statement::Fetch { cursor_name, count } => {
+                write!(f, "FETCH {} FROM {}", count, cursor_name)
+            }
+            Statement::CloseCursor { cursor_name } => write!(f, "CLOSE {}", cursor_name),
+            Statement::Listen { channel, selection } => {
+                write!(f, "LISTEN {}", channel)?;
+                if let Some(selection) = selection {
+                    write!(f, " WHERE {}", selection)?;
+                }
+                Ok(())
+            }
+            Statement::Unlisten { channel } => match channel {
+                Some(channel) => write!(f, "UNLISTEN {}", channel),
+                None => write!(f, "UNLISTEN *"),
+            },
+            Statement::Unload {
+                query,
+                location,
+                format,
+            } => write!(
+                f,
+                "UNLOAD ({}) TO '{}' FORMAT {}",
+                query,
+                value::escape_single_quote_string(location),
+                format
+            ),
+            Statement::Replay { location } => write!(
+                f,
+                "REPLAY '{}'",
+                value::escape_single_quote_string(location)
+            ),
             Statement::Flush => {
                 write!(f, "FLUSH")
             }
@@ -1569,6 +1771,7 @@ pub enum ObjectType {
     Sink,
     Database,
     User,
+    Subscription,
 }
 
 impl fmt::Display for ObjectType {
@@ -1584,6 +1787,7 @@ impl fmt::Display for ObjectType {
             ObjectType::Sink => "SINK",
             ObjectType::Database => "DATABASE",
             ObjectType::User => "USER",
+            ObjectType::Subscription => "SUBSCRIPTION",
         })
     }
 }
@@ -1610,9 +1814,11 @@ impl ParseTo for ObjectType {
             ObjectType::Database
         } else if parser.parse_keyword(Keyword::USER) {
             ObjectType::User
+        } else if parser.parse_keyword(Keyword::SUBSCRIPTION) {
+            ObjectType::Subscription
         } else {
             return parser.expected(
-                "TABLE, VIEW, INDEX, MATERIALIZED VIEW, SOURCE, MATERIALIZED SOURCE, SINK, SCHEMA, DATABASE or USER after DROP",
+                "TABLE, VIEW, INDEX, MATERIALIZED VIEW, SOURCE, MATERIALIZED SOURCE, SINK, SCHEMA, DATABASE, USER or SUBSCRIPTION after DROP",
                 parser.peek_token(),
             );
         };
@@ -86,6 +86,9 @@ pub enum BinaryOperator {
     PGRegexIMatch,
     PGRegexNotMatch,
     PGRegexNotIMatch,
+    PGTsMatch,
+    PGL2Distance,
+    PGNegativeInnerProduct,
 }
 
 impl fmt::Display for BinaryOperator {
@@ -121,6 +124,9 @@ impl fmt::Display for BinaryOperator {
             BinaryOperator::PGRegexIMatch => "~*",
             BinaryOperator::PGRegexNotMatch => "!~",
             BinaryOperator::PGRegexNotIMatch => "!~*",
+            BinaryOperator::PGTsMatch => "@@",
+            BinaryOperator::PGL2Distance => "<->",
+            BinaryOperator::PGNegativeInnerProduct => "<#>",
         })
     }
 }
@@ -336,6 +336,17 @@ pub enum TableFactor {
     /// The parser may also accept non-standard nesting of bare tables for some
     /// dialects, but the information about such nesting is stripped from AST.
     NestedJoin(Box<TableWithJoins>),
+    /// `<table> MATCH_RECOGNIZE (...) [ AS <alias> ]`
+    MatchRecognize {
+        table: Box<TableFactor>,
+        match_recognize: Box<MatchRecognize>,
+        alias: Option<TableAlias>,
+    },
+    /// `<table> TABLESAMPLE <method> (<percentage>) [ REPEATABLE (<seed>) ]`
+    TableSample {
+        table: Box<TableFactor>,
+        sample: TableSample,
+    },
 }
 
 impl fmt::Display for TableFactor {
@@ -373,10 +384,163 @@ impl fmt::Display for TableFactor {
                 Ok(())
             }
             TableFactor::NestedJoin(table_reference) => write!(f, "({})", table_reference),
+            TableFactor::MatchRecognize {
+                table,
+                match_recognize,
+                alias,
+            } => {
+                write!(f, "{} MATCH_RECOGNIZE ({})", table, match_recognize)?;
+                if let Some(alias) = alias {
+                    write!(f, " AS {}", alias)?;
+                }
+                Ok(())
+            }
+            TableFactor::TableSample { table, sample } => write!(f, "{} {}", table, sample),
         }
     }
 }
 
+/// The sampling method of `TABLESAMPLE`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TableSampleMethod {
+    /// Sample blocks of rows, each of which is kept with the given probability.
+    System,
+    /// Sample rows, each of which is kept with the given probability.
+    Bernoulli,
+}
+
+impl fmt::Display for TableSampleMethod {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            TableSampleMethod::System => "SYSTEM",
+            TableSampleMethod::Bernoulli => "BERNOULLI",
+        })
+    }
+}
+
+/// `TABLESAMPLE <method> (<percentage>) [ REPEATABLE (<seed>) ]`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TableSample {
+    pub method: TableSampleMethod,
+    pub percentage: Expr,
+    pub seed: Option<Expr>,
+}
+
+impl fmt::Display for TableSample {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "TABLESAMPLE {} ({})", self.method, self.percentage)?;
+        if let Some(seed) = &self.seed {
+            write!(f, " REPEATABLE ({})", seed)?;
+        }
+        Ok(())
+    }
+}
+
+/// The clauses of `MATCH_RECOGNIZE`, which finds the rows matching `pattern` in each partition.
+/// Only `ONE ROW PER MATCH` and `AFTER MATCH SKIP PAST LAST ROW` are supported, which are also the
+/// defaults, so they are not kept here.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MatchRecognize {
+    pub partition_by: Vec<Expr>,
+    pub order_by: Vec<OrderByExpr>,
+    pub measures: Vec<Measure>,
+    pub pattern: Vec<PatternTerm>,
+    pub define: Vec<SymbolDefinition>,
+}
+
+impl fmt::Display for MatchRecognize {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if !self.partition_by.is_empty() {
+            write!(
+                f,
+                "PARTITION BY {} ",
+                display_comma_separated(&self.partition_by)
+            )?;
+        }
+        if !self.order_by.is_empty() {
+            write!(f, "ORDER BY {} ", display_comma_separated(&self.order_by))?;
+        }
+        if !self.measures.is_empty() {
+            write!(f, "MEASURES {} ", display_comma_separated(&self.measures))?;
+        }
+        write!(f, "PATTERN ({})", display_separated(&self.pattern, " "))?;
+        if !self.define.is_empty() {
+            write!(f, " DEFINE {}", display_comma_separated(&self.define))?;
+        }
+        Ok(())
+    }
+}
+
+/// `<expr> AS <alias>` in `MEASURES`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Measure {
+    pub expr: Expr,
+    pub alias: Ident,
+}
+
+impl fmt::Display for Measure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} AS {}", self.expr, self.alias)
+    }
+}
+
+/// A pattern variable with an optional quantifier in `PATTERN`, e.g. `B+`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PatternTerm {
+    pub symbol: Ident,
+    pub quantifier: Option<PatternQuantifier>,
+}
+
+impl fmt::Display for PatternTerm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.symbol)?;
+        if let Some(quantifier) = &self.quantifier {
+            write!(f, "{}", quantifier)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PatternQuantifier {
+    /// `*`
+    ZeroOrMore,
+    /// `+`
+    OneOrMore,
+    /// `?`
+    ZeroOrOne,
+}
+
+impl fmt::Display for PatternQuantifier {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            PatternQuantifier::ZeroOrMore => "*",
+            PatternQuantifier::OneOrMore => "+",
+            PatternQuantifier::ZeroOrOne => "?",
+        })
+    }
+}
+
+/// `<symbol> AS <condition>` in `DEFINE`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SymbolDefinition {
+    pub symbol: Ident,
+    pub condition: Expr,
+}
+
+impl fmt::Display for SymbolDefinition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} AS {}", self.symbol, self.condition)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct TableAlias {
@@ -454,6 +618,20 @@ impl fmt::Display for Join {
                 suffix(constraint)
             ),
             JoinOperator::CrossJoin => write!(f, " CROSS JOIN {}", self.relation),
+            JoinOperator::AsOfInner(constraint) => write!(
+                f,
+                " {}ASOF JOIN {}{}",
+                prefix(constraint),
+                self.relation,
+                suffix(constraint)
+            ),
+            JoinOperator::AsOfLeftOuter(constraint) => write!(
+                f,
+                " {}ASOF LEFT JOIN {}{}",
+                prefix(constraint),
+                self.relation,
+                suffix(constraint)
+            ),
         }
     }
 }
@@ -466,6 +644,11 @@ pub enum JoinOperator {
     RightOuter(JoinConstraint),
     FullOuter(JoinConstraint),
     CrossJoin,
+    /// `ASOF JOIN`, which matches each left row with the latest right row satisfying the
+    /// constraint.
+    AsOfInner(JoinConstraint),
+    /// `ASOF LEFT JOIN`
+    AsOfLeftOuter(JoinConstraint),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
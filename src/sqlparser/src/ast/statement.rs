@@ -20,7 +20,8 @@ use serde::{Deserialize, Serialize};
 
 use super::ObjectType;
 use crate::ast::{
-    display_comma_separated, display_separated, ColumnDef, ObjectName, SqlOption, TableConstraint,
+    display_comma_separated, display_separated, ColumnDef, ObjectName, OnConflict, SqlOption,
+    TableConstraint,
 };
 use crate::keywords::Keyword;
 use crate::parser::{Parser, ParserError};
@@ -75,6 +76,7 @@ pub struct CreateSourceStatement {
     pub if_not_exists: bool,
     pub columns: Vec<ColumnDef>,
     pub constraints: Vec<TableConstraint>,
+    pub on_conflict: Option<OnConflict>,
     pub source_name: ObjectName,
     pub with_properties: WithProperties,
     pub source_schema: SourceSchema,
@@ -157,6 +159,7 @@ impl ParseTo for CreateSourceStatement {
 
         // parse columns
         let (columns, constraints) = p.parse_columns()?;
+        let on_conflict = p.parse_on_conflict()?;
 
         impl_parse_to!(with_properties: WithProperties, p);
         impl_parse_to!([Keyword::ROW, Keyword::FORMAT], p);
@@ -165,6 +168,7 @@ impl ParseTo for CreateSourceStatement {
             if_not_exists,
             columns,
             constraints,
+            on_conflict,
             source_name,
             with_properties,
             source_schema,
@@ -177,6 +181,9 @@ impl fmt::Display for CreateSourceStatement {
         let mut v: Vec<String> = vec![];
         impl_fmt_display!(if_not_exists => [Keyword::IF, Keyword::NOT, Keyword::EXISTS], v, self);
         impl_fmt_display!(source_name, v, self);
+        if let Some(on_conflict) = &self.on_conflict {
+            v.push(format!("ON CONFLICT {}", on_conflict));
+        }
         impl_fmt_display!(with_properties, v, self);
         impl_fmt_display!([Keyword::ROW, Keyword::FORMAT], v);
         impl_fmt_display!(source_schema, v, self);
@@ -230,6 +237,207 @@ impl fmt::Display for CreateSinkStatement {
     }
 }
 
+// sql_grammar!(CreateSubscriptionStatement {
+//     if_not_exists => [Keyword::IF, Keyword::NOT, Keyword::EXISTS],
+//     subscription_name: ObjectName,
+//     [Keyword::ON],
+//     materialized_view: ObjectName,
+//     with_properties: WithProperties,
+// });
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CreateSubscriptionStatement {
+    pub if_not_exists: bool,
+    pub subscription_name: ObjectName,
+    pub materialized_view: ObjectName,
+    pub with_properties: WithProperties,
+}
+
+impl ParseTo for CreateSubscriptionStatement {
+    fn parse_to(p: &mut Parser) -> Result<Self, ParserError> {
+        impl_parse_to!(if_not_exists => [Keyword::IF, Keyword::NOT, Keyword::EXISTS], p);
+        impl_parse_to!(subscription_name: ObjectName, p);
+        impl_parse_to!([Keyword::ON], p);
+        impl_parse_to!(materialized_view: ObjectName, p);
+        impl_parse_to!(with_properties: WithProperties, p);
+        Ok(Self {
+            if_not_exists,
+            subscription_name,
+            materialized_view,
+            with_properties,
+        })
+    }
+}
+
+impl fmt::Display for CreateSubscriptionStatement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut v: Vec<String> = vec![];
+        impl_fmt_display!(if_not_exists => [Keyword::IF, Keyword::NOT, Keyword::EXISTS], v, self);
+        impl_fmt_display!(subscription_name, v, self);
+        impl_fmt_display!([Keyword::ON], v);
+        impl_fmt_display!(materialized_view, v, self);
+        impl_fmt_display!(with_properties, v, self);
+        v.iter().join(" ").fmt(f)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ExternalTableFormat {
+    Parquet, // Keyword::PARQUET
+    Delta,   // Keyword::DELTA
+    Iceberg, // Keyword::ICEBERG
+}
+
+impl ParseTo for ExternalTableFormat {
+    fn parse_to(p: &mut Parser) -> Result<Self, ParserError> {
+        let format = if p.parse_keywords(&[Keyword::PARQUET]) {
+            ExternalTableFormat::Parquet
+        } else if p.parse_keywords(&[Keyword::DELTA]) {
+            ExternalTableFormat::Delta
+        } else if p.parse_keywords(&[Keyword::ICEBERG]) {
+            ExternalTableFormat::Iceberg
+        } else {
+            return p.expected("PARQUET | DELTA | ICEBERG after STORED AS", p.peek_token());
+        };
+        Ok(format)
+    }
+}
+
+impl fmt::Display for ExternalTableFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExternalTableFormat::Parquet => write!(f, "PARQUET"),
+            ExternalTableFormat::Delta => write!(f, "DELTA"),
+            ExternalTableFormat::Iceberg => write!(f, "ICEBERG"),
+        }
+    }
+}
+
+/// The format of the files written by `UNLOAD`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum UnloadFormat {
+    Parquet, // Keyword::PARQUET
+    Csv,     // Keyword::CSV
+}
+
+impl ParseTo for UnloadFormat {
+    fn parse_to(p: &mut Parser) -> Result<Self, ParserError> {
+        let format = if p.parse_keywords(&[Keyword::PARQUET]) {
+            UnloadFormat::Parquet
+        } else if p.parse_keywords(&[Keyword::CSV]) {
+            UnloadFormat::Csv
+        } else {
+            return p.expected("PARQUET | CSV after FORMAT", p.peek_token());
+        };
+        Ok(format)
+    }
+}
+
+impl fmt::Display for UnloadFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnloadFormat::Parquet => write!(f, "PARQUET"),
+            UnloadFormat::Csv => write!(f, "CSV"),
+        }
+    }
+}
+
+// sql_grammar!(CreateExternalTableStatement {
+//     if_not_exists => [Keyword::IF, Keyword::NOT, Keyword::EXISTS],
+//     table_name: Ident,
+//     [Keyword::STORED, Keyword::AS],
+//     format: ExternalTableFormat,
+//     [Keyword::LOCATION],
+//     location: AstString,
+// });
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CreateExternalTableStatement {
+    pub if_not_exists: bool,
+    pub table_name: ObjectName,
+    pub format: ExternalTableFormat,
+    pub location: AstString,
+}
+
+impl ParseTo for CreateExternalTableStatement {
+    fn parse_to(p: &mut Parser) -> Result<Self, ParserError> {
+        impl_parse_to!(if_not_exists => [Keyword::IF, Keyword::NOT, Keyword::EXISTS], p);
+        impl_parse_to!(table_name: ObjectName, p);
+        impl_parse_to!([Keyword::STORED, Keyword::AS], p);
+        impl_parse_to!(format: ExternalTableFormat, p);
+        impl_parse_to!([Keyword::LOCATION], p);
+        impl_parse_to!(location: AstString, p);
+        Ok(Self {
+            if_not_exists,
+            table_name,
+            format,
+            location,
+        })
+    }
+}
+
+impl fmt::Display for CreateExternalTableStatement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut v: Vec<String> = vec![];
+        impl_fmt_display!(if_not_exists => [Keyword::IF, Keyword::NOT, Keyword::EXISTS], v, self);
+        impl_fmt_display!(table_name, v, self);
+        impl_fmt_display!([Keyword::STORED, Keyword::AS], v);
+        impl_fmt_display!(format, v, self);
+        impl_fmt_display!([Keyword::LOCATION], v);
+        impl_fmt_display!(location, v, self);
+        v.iter().join(" ").fmt(f)
+    }
+}
+
+// sql_grammar!(CreateExternalSchemaStatement {
+//     if_not_exists => [Keyword::IF, Keyword::NOT, Keyword::EXISTS],
+//     schema_name: Ident,
+//     [Keyword::STORED, Keyword::AS],
+//     format: ExternalTableFormat,
+//     [Keyword::LOCATION],
+//     location: AstString,
+// });
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CreateExternalSchemaStatement {
+    pub if_not_exists: bool,
+    pub schema_name: ObjectName,
+    pub format: ExternalTableFormat,
+    pub location: AstString,
+}
+
+impl ParseTo for CreateExternalSchemaStatement {
+    fn parse_to(p: &mut Parser) -> Result<Self, ParserError> {
+        impl_parse_to!(if_not_exists => [Keyword::IF, Keyword::NOT, Keyword::EXISTS], p);
+        impl_parse_to!(schema_name: ObjectName, p);
+        impl_parse_to!([Keyword::STORED, Keyword::AS], p);
+        impl_parse_to!(format: ExternalTableFormat, p);
+        impl_parse_to!([Keyword::LOCATION], p);
+        impl_parse_to!(location: AstString, p);
+        Ok(Self {
+            if_not_exists,
+            schema_name,
+            format,
+            location,
+        })
+    }
+}
+
+impl fmt::Display for CreateExternalSchemaStatement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut v: Vec<String> = vec![];
+        impl_fmt_display!(if_not_exists => [Keyword::IF, Keyword::NOT, Keyword::EXISTS], v, self);
+        impl_fmt_display!(schema_name, v, self);
+        impl_fmt_display!([Keyword::STORED, Keyword::AS], v);
+        impl_fmt_display!(format, v, self);
+        impl_fmt_display!([Keyword::LOCATION], v);
+        impl_fmt_display!(location, v, self);
+        v.iter().join(" ").fmt(f)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct AstVec<T>(pub Vec<T>);
@@ -71,12 +71,14 @@ define_keywords!(
     ABS,
     ACTION,
     ADD,
+    AFTER,
     ALL,
     ALLOCATE,
     ALTER,
     ANALYZE,
     AND,
     ANY,
+    APPEND,
     ARE,
     ARRAY,
     ARRAY_AGG,
@@ -84,6 +86,7 @@ define_keywords!(
     AS,
     ASC,
     ASENSITIVE,
+    ASOF,
     ASYMMETRIC,
     AT,
     ATOMIC,
@@ -93,6 +96,7 @@ define_keywords!(
     BEGIN,
     BEGIN_FRAME,
     BEGIN_PARTITION,
+    BERNOULLI,
     BETWEEN,
     BIGINT,
     BINARY,
@@ -130,6 +134,7 @@ define_keywords!(
     COMMIT,
     COMMITTED,
     CONDITION,
+    CONFLICT,
     CONNECT,
     CONSTRAINT,
     CONTAINS,
@@ -170,7 +175,9 @@ define_keywords!(
     DECIMAL,
     DECLARE,
     DEFAULT,
+    DEFINE,
     DELETE,
+    DELTA,
     DENSE_RANK,
     DEREF,
     DESC,
@@ -179,6 +186,8 @@ define_keywords!(
     DIRECTORY,
     DISCONNECT,
     DISTINCT,
+    DISTRIBUTED,
+    DO,
     DOUBLE,
     DROP,
     DYNAMIC,
@@ -232,6 +241,7 @@ define_keywords!(
     HEADER,
     HOLD,
     HOUR,
+    ICEBERG,
     IDENTITY,
     IF,
     IGNORE,
@@ -245,6 +255,7 @@ define_keywords!(
     INSERT,
     INT,
     INTEGER,
+    INTERNAL,
     INTERSECT,
     INTERSECTION,
     INTERVAL,
@@ -263,6 +274,7 @@ define_keywords!(
     LEVEL,
     LIKE,
     LIMIT,
+    LISTEN,
     LN,
     LOCAL,
     LOCALTIME,
@@ -271,8 +283,10 @@ define_keywords!(
     LOGIN,
     LOWER,
     MATCH,
+    MATCH_RECOGNIZE,
     MATERIALIZED,
     MAX,
+    MEASURES,
     MEMBER,
     MERGE,
     MESSAGE,
@@ -311,6 +325,7 @@ define_keywords!(
     OFFSET,
     OLD,
     ON,
+    ONE,
     ONLY,
     OPEN,
     OPTION,
@@ -322,6 +337,7 @@ define_keywords!(
     OVER,
     OVERLAPS,
     OVERLAY,
+    OVERWRITE,
     OWNER,
     PARAMETER,
     PARQUET,
@@ -329,6 +345,10 @@ define_keywords!(
     PARTITIONED,
     PARTITIONS,
     PASSWORD,
+    PAST,
+    PATTERN,
+    PAUSE,
+    PER,
     PERCENT,
     PERCENTILE_CONT,
     PERCENTILE_DISC,
@@ -372,8 +392,12 @@ define_keywords!(
     REPAIR,
     REPEATABLE,
     REPLACE,
+    REPLAY,
+    RESET,
+    RESTORE,
     RESTRICT,
     RESULT,
+    RESUME,
     RETURN,
     RETURNS,
     REVOKE,
@@ -405,6 +429,7 @@ define_keywords!(
     SHOW,
     SIMILAR,
     SINK,
+    SKIP,
     SMALLINT,
     SNAPSHOT,
     SOME,
@@ -425,9 +450,11 @@ define_keywords!(
     STDDEV_SAMP,
     STDIN,
     STORED,
+    STREAMING,
     STRING,
     STRUCT,
     SUBMULTISET,
+    SUBSCRIPTION,
     SUBSTRING,
     SUBSTRING_REGEX,
     SUCCEEDS,
@@ -455,6 +482,7 @@ define_keywords!(
     TINYINT,
     TO,
     TOP,
+    TRACE,
     TRAILING,
     TRANSACTION,
     TRANSLATE,
@@ -474,6 +502,8 @@ define_keywords!(
     UNION,
     UNIQUE,
     UNKNOWN,
+    UNLISTEN,
+    UNLOAD,
     UNNEST,
     UPDATE,
     UPPER,
@@ -489,6 +519,7 @@ define_keywords!(
     VARYING,
     VAR_POP,
     VAR_SAMP,
+    VECTOR,
     VERBOSE,
     VERSIONING,
     VIEW,
@@ -532,6 +563,8 @@ pub const RESERVED_FOR_TABLE_ALIAS: &[Keyword] = &[
     Keyword::EXCEPT,
     Keyword::INTERSECT,
     // Reserved only as a table alias in the `FROM`/`JOIN` clauses:
+    Keyword::MATCH_RECOGNIZE,
+    Keyword::TABLESAMPLE,
     Keyword::ON,
     Keyword::JOIN,
     Keyword::INNER,
@@ -540,6 +573,7 @@ pub const RESERVED_FOR_TABLE_ALIAS: &[Keyword] = &[
     Keyword::LEFT,
     Keyword::RIGHT,
     Keyword::NATURAL,
+    Keyword::ASOF,
     Keyword::USING,
     Keyword::CLUSTER,
     // for MSSQL-specific OUTER APPLY (seems reserved in most dialects)
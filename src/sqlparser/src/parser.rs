@@ -201,6 +201,36 @@ impl Parser {
                 Keyword::PREPARE => Ok(self.parse_prepare()?),
                 Keyword::COMMENT => Ok(self.parse_comment()?),
                 Keyword::FLUSH => Ok(Statement::Flush),
+                Keyword::SAVEPOINT => {
+                    self.expect_keywords(&[Keyword::MATERIALIZED, Keyword::VIEW])?;
+                    Ok(Statement::SavepointMaterializedView {
+                        name: self.parse_object_name()?,
+                    })
+                }
+                Keyword::DECLARE => Ok(self.parse_declare_cursor()?),
+                Keyword::FETCH => Ok(self.parse_fetch_cursor()?),
+                Keyword::CLOSE => Ok(Statement::CloseCursor {
+                    cursor_name: self.parse_identifier()?,
+                }),
+                Keyword::LISTEN => Ok(Statement::Listen {
+                    channel: self.parse_object_name()?,
+                    selection: if self.parse_keyword(Keyword::WHERE) {
+                        Some(self.parse_expr()?)
+                    } else {
+                        None
+                    },
+                }),
+                Keyword::UNLISTEN => Ok(Statement::Unlisten {
+                    channel: if self.consume_token(&Token::Mul) {
+                        None
+                    } else {
+                        Some(self.parse_object_name()?)
+                    },
+                }),
+                Keyword::UNLOAD => Ok(self.parse_unload()?),
+                Keyword::REPLAY => Ok(Statement::Replay {
+                    location: self.parse_literal_string()?,
+                }),
                 _ => self.expected("an SQL statement", Token::Word(w)),
             },
             Token::LParen => {
@@ -211,6 +241,22 @@ impl Parser {
         }
     }
 
+    /// Parse `UNLOAD (query) TO 'location' FORMAT { PARQUET | CSV }`, after `UNLOAD`.
+    pub fn parse_unload(&mut self) -> Result<Statement, ParserError> {
+        self.expect_token(&Token::LParen)?;
+        let query = Box::new(self.parse_query()?);
+        self.expect_token(&Token::RParen)?;
+        self.expect_keyword(Keyword::TO)?;
+        let location = self.parse_literal_string()?;
+        self.expect_keyword(Keyword::FORMAT)?;
+        let format = UnloadFormat::parse_to(self)?;
+        Ok(Statement::Unload {
+            query,
+            location,
+            format,
+        })
+    }
+
     pub fn parse_truncate(&mut self) -> Result<Statement, ParserError> {
         let _ = self.parse_keyword(Keyword::TABLE);
         let table_name = self.parse_object_name()?;
@@ -968,6 +1014,9 @@ impl Parser {
             Token::TildeAsterisk => Some(BinaryOperator::PGRegexIMatch),
             Token::ExclamationMarkTilde => Some(BinaryOperator::PGRegexNotMatch),
             Token::ExclamationMarkTildeAsterisk => Some(BinaryOperator::PGRegexNotIMatch),
+            Token::AtAt => Some(BinaryOperator::PGTsMatch),
+            Token::LtMinusGt => Some(BinaryOperator::PGL2Distance),
+            Token::LtHashGt => Some(BinaryOperator::PGNegativeInnerProduct),
             Token::Word(w) => match w.keyword {
                 Keyword::AND => Some(BinaryOperator::And),
                 Keyword::OR => Some(BinaryOperator::Or),
@@ -1148,6 +1197,9 @@ impl Parser {
             | Token::TildeAsterisk
             | Token::ExclamationMarkTilde
             | Token::ExclamationMarkTildeAsterisk
+            | Token::AtAt
+            | Token::LtMinusGt
+            | Token::LtHashGt
             | Token::Spaceship => Ok(20),
             Token::Pipe => Ok(21),
             Token::Caret | Token::Sharp | Token::ShiftRight | Token::ShiftLeft => Ok(22),
@@ -1378,6 +1430,14 @@ impl Parser {
             self.parse_create_source(true, or_replace)
         } else if self.parse_keyword(Keyword::SINK) {
             self.parse_create_sink(or_replace)
+        } else if self.parse_keywords(&[Keyword::EXTERNAL, Keyword::TABLE]) {
+            self.parse_create_external_table(or_replace)
+        } else if self.parse_keywords(&[Keyword::EXTERNAL, Keyword::SCHEMA]) {
+            self.parse_create_external_schema()
+        } else if self.parse_keyword(Keyword::SUBSCRIPTION) {
+            Ok(Statement::CreateSubscription {
+                stmt: CreateSubscriptionStatement::parse_to(self)?,
+            })
         } else if or_replace {
             self.expected(
                 "[EXTERNAL] TABLE or [MATERIALIZED] VIEW after CREATE OR REPLACE",
@@ -1425,6 +1485,7 @@ impl Parser {
     ) -> Result<Statement, ParserError> {
         // Many dialects support `OR ALTER` right after `CREATE`, but we don't (yet).
         // ANSI SQL and Postgres support RECURSIVE here, but we don't support it either.
+        let if_not_exists = self.parse_keywords(&[Keyword::IF, Keyword::NOT, Keyword::EXISTS]);
         let name = self.parse_object_name()?;
         let columns = self.parse_parenthesized_column_list(Optional)?;
         let with_options = self.parse_options(Keyword::WITH)?;
@@ -1437,6 +1498,7 @@ impl Parser {
             query,
             materialized,
             or_replace,
+            if_not_exists,
             with_options,
         })
     }
@@ -1473,6 +1535,28 @@ impl Parser {
         })
     }
 
+    // CREATE [OR REPLACE]?
+    // EXTERNAL TABLE
+    // [IF NOT EXISTS]?
+    // <table_name: Ident>
+    // STORED AS PARQUET
+    // LOCATION <location: String>
+    pub fn parse_create_external_table(
+        &mut self,
+        _or_replace: bool,
+    ) -> Result<Statement, ParserError> {
+        Ok(Statement::CreateExternalTable {
+            stmt: CreateExternalTableStatement::parse_to(self)?,
+        })
+    }
+
+    // CREATE EXTERNAL SCHEMA [IF NOT EXISTS] name STORED AS format LOCATION 'location'
+    pub fn parse_create_external_schema(&mut self) -> Result<Statement, ParserError> {
+        Ok(Statement::CreateExternalSchema {
+            stmt: CreateExternalSchemaStatement::parse_to(self)?,
+        })
+    }
+
     // CREATE USER name [ [ WITH ] option [ ... ] ]
     // where option can be:
     //       SUPERUSER | NOSUPERUSER
@@ -1496,12 +1580,18 @@ impl Parser {
         let index_name = self.parse_object_name()?;
         self.expect_keyword(Keyword::ON)?;
         let table_name = self.parse_object_name()?;
+        let using = if self.parse_keyword(Keyword::USING) {
+            Some(self.parse_identifier()?)
+        } else {
+            None
+        };
         self.expect_token(&Token::LParen)?;
         let columns = self.parse_comma_separated(Parser::parse_order_by_expr)?;
         self.expect_token(&Token::RParen)?;
         Ok(Statement::CreateIndex {
             name: index_name,
             table_name,
+            using,
             columns,
             unique,
             if_not_exists,
@@ -1522,6 +1612,8 @@ impl Parser {
         };
         // parse optional column list (schema)
         let (columns, constraints) = self.parse_columns()?;
+        let append_only = self.parse_keywords(&[Keyword::APPEND, Keyword::ONLY]);
+        let on_conflict = self.parse_on_conflict()?;
 
         // PostgreSQL supports `WITH ( options )`, before `AS`
         let with_options = self.parse_with_properties()?;
@@ -1538,6 +1630,8 @@ impl Parser {
             temporary,
             columns,
             constraints,
+            append_only,
+            on_conflict,
             with_options,
             table_properties,
             or_replace,
@@ -1547,6 +1641,22 @@ impl Parser {
         })
     }
 
+    /// Parses the optional `ON CONFLICT { OVERWRITE | IGNORE | DO UPDATE FULL }` of a table.
+    pub fn parse_on_conflict(&mut self) -> Result<Option<OnConflict>, ParserError> {
+        if !self.parse_keywords(&[Keyword::ON, Keyword::CONFLICT]) {
+            return Ok(None);
+        }
+        if self.parse_keyword(Keyword::OVERWRITE) {
+            Ok(Some(OnConflict::Overwrite))
+        } else if self.parse_keyword(Keyword::IGNORE) {
+            Ok(Some(OnConflict::Ignore))
+        } else if self.parse_keywords(&[Keyword::DO, Keyword::UPDATE, Keyword::FULL]) {
+            Ok(Some(OnConflict::DoUpdateFull))
+        } else {
+            self.expected("OVERWRITE, IGNORE or DO UPDATE FULL", self.peek_token())
+        }
+    }
+
     pub fn parse_columns(&mut self) -> Result<(Vec<ColumnDef>, Vec<TableConstraint>), ParserError> {
         let mut columns = vec![];
         let mut constraints = vec![];
@@ -1758,10 +1868,85 @@ impl Parser {
     }
 
     pub fn parse_alter(&mut self) -> Result<Statement, ParserError> {
+        if self.parse_keyword(Keyword::SYSTEM) {
+            return self.parse_alter_system();
+        }
+        if self.parse_keyword(Keyword::USER) {
+            return self.parse_alter_user();
+        }
+        if self.parse_keywords(&[Keyword::MATERIALIZED, Keyword::VIEW]) {
+            return self.parse_alter_materialized_view();
+        }
         self.expect_keyword(Keyword::TABLE)?;
         self.parse_alter_table()
     }
 
+    /// Parses `ALTER SYSTEM SET <param> { = | TO } <value>`.
+    pub fn parse_alter_system(&mut self) -> Result<Statement, ParserError> {
+        self.expect_keyword(Keyword::SET)?;
+        let (param, value) = self.parse_param_assignment()?;
+        Ok(Statement::AlterSystem { param, value })
+    }
+
+    /// Parses `ALTER USER <name> SET <param> { = | TO } <value>` and
+    /// `ALTER USER <name> RESET <param>`.
+    pub fn parse_alter_user(&mut self) -> Result<Statement, ParserError> {
+        let name = self.parse_object_name()?;
+        let (param, value) = if self.parse_keyword(Keyword::RESET) {
+            (self.parse_identifier()?, None)
+        } else {
+            self.expect_keyword(Keyword::SET)?;
+            let (param, value) = self.parse_param_assignment()?;
+            (param, Some(value))
+        };
+        Ok(Statement::AlterUser { name, param, value })
+    }
+
+    /// Parses `ALTER MATERIALIZED VIEW <name> { RESTORE FROM SAVEPOINT | RELEASE SAVEPOINT | AS
+    /// <query> | PAUSE | RESUME | SET <param> { = | TO } <value> }`.
+    pub fn parse_alter_materialized_view(&mut self) -> Result<Statement, ParserError> {
+        let name = self.parse_object_name()?;
+        let operation =
+            if self.parse_keywords(&[Keyword::RESTORE, Keyword::FROM, Keyword::SAVEPOINT]) {
+                AlterMaterializedViewOperation::RestoreFromSavepoint
+            } else if self.parse_keywords(&[Keyword::RELEASE, Keyword::SAVEPOINT]) {
+                AlterMaterializedViewOperation::ReleaseSavepoint
+            } else if self.parse_keyword(Keyword::AS) {
+                AlterMaterializedViewOperation::AsQuery {
+                    query: Box::new(self.parse_query()?),
+                }
+            } else if self.parse_keyword(Keyword::PAUSE) {
+                AlterMaterializedViewOperation::Pause
+            } else if self.parse_keyword(Keyword::RESUME) {
+                AlterMaterializedViewOperation::Resume
+            } else if self.parse_keyword(Keyword::SET) {
+                let (param, value) = self.parse_param_assignment()?;
+                AlterMaterializedViewOperation::SetParam { param, value }
+            } else {
+                return self.expected(
+                "RESTORE FROM SAVEPOINT, RELEASE SAVEPOINT, AS, PAUSE, RESUME or SET after ALTER \
+                     MATERIALIZED VIEW",
+                self.peek_token(),
+            );
+            };
+        Ok(Statement::AlterMaterializedView { name, operation })
+    }
+
+    /// Parses `<param> { = | TO } <value>`.
+    fn parse_param_assignment(&mut self) -> Result<(Ident, SetVariableValue), ParserError> {
+        let param = self.parse_identifier()?;
+        if !self.consume_token(&Token::Eq) {
+            self.expect_keyword(Keyword::TO)?;
+        }
+        let token = self.peek_token();
+        let value = match (self.parse_value(), token) {
+            (Ok(value), _) => SetVariableValue::Literal(value),
+            (Err(_), Token::Word(ident)) => SetVariableValue::Ident(ident.to_ident()),
+            (Err(_), unexpected) => self.expected("parameter value", unexpected)?,
+        };
+        Ok((param, value))
+    }
+
     pub fn parse_alter_table(&mut self) -> Result<Statement, ParserError> {
         let _ = self.parse_keyword(Keyword::ONLY);
         let table_name = self.parse_object_name()?;
@@ -1799,6 +1984,9 @@ impl Parser {
             AlterTableOperation::ChangeOwner {
                 new_owner_name: owner_name,
             }
+        } else if self.parse_keywords(&[Keyword::DROP, Keyword::PARTITION]) {
+            let value = self.parse_value()?;
+            AlterTableOperation::DropPartition { value }
         } else if self.parse_keyword(Keyword::DROP) {
             let _ = self.parse_keyword(Keyword::COLUMN);
             let if_exists = self.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
@@ -2072,6 +2260,12 @@ impl Parser {
                 }
                 Keyword::STRUCT => Ok(DataType::Struct(self.parse_struct_data_type()?)),
                 Keyword::BYTEA => Ok(DataType::Bytea),
+                Keyword::VECTOR => {
+                    self.expect_token(&Token::LParen)?;
+                    let dim = self.parse_literal_uint()?;
+                    self.expect_token(&Token::RParen)?;
+                    Ok(DataType::Vector(dim))
+                }
                 Keyword::NUMERIC | Keyword::DECIMAL | Keyword::DEC => {
                     let (precision, scale) = self.parse_optional_precision_scale()?;
                     Ok(DataType::Decimal(precision, scale))
@@ -2296,18 +2490,87 @@ impl Parser {
     }
 
     pub fn parse_explain(&mut self, describe_alias: bool) -> Result<Statement, ParserError> {
-        let analyze = self.parse_keyword(Keyword::ANALYZE);
-        let verbose = self.parse_keyword(Keyword::VERBOSE);
+        let mut analyze = false;
+        let mut verbose = false;
+        let mut format = ExplainFormat::Text;
+        let mut trace = false;
+        let mut streaming = false;
+        let mut distributed = false;
+
+        // `EXPLAIN (option [, ...]) statement`. A parenthesized query is told apart by the first
+        // token after the parenthesis.
+        let is_option_list = self.peek_token() == Token::LParen
+            && matches!(
+                self.peek_nth_token(1),
+                Token::Word(w) if matches!(
+                    w.keyword,
+                    Keyword::ANALYZE
+                        | Keyword::VERBOSE
+                        | Keyword::TRACE
+                        | Keyword::STREAMING
+                        | Keyword::DISTRIBUTED
+                        | Keyword::FORMAT
+                )
+            );
+        if is_option_list {
+            self.expect_token(&Token::LParen)?;
+            loop {
+                match self.parse_one_of_keywords(&[
+                    Keyword::ANALYZE,
+                    Keyword::VERBOSE,
+                    Keyword::TRACE,
+                    Keyword::STREAMING,
+                    Keyword::DISTRIBUTED,
+                    Keyword::FORMAT,
+                ]) {
+                    Some(Keyword::ANALYZE) => analyze = true,
+                    Some(Keyword::VERBOSE) => verbose = true,
+                    Some(Keyword::TRACE) => trace = true,
+                    Some(Keyword::STREAMING) => streaming = true,
+                    Some(Keyword::DISTRIBUTED) => distributed = true,
+                    Some(Keyword::FORMAT) => format = self.parse_explain_format()?,
+                    _ => {
+                        return self.expected(
+                            "ANALYZE, VERBOSE, TRACE, STREAMING, DISTRIBUTED or FORMAT",
+                            self.peek_token(),
+                        )
+                    }
+                }
+                if !self.consume_token(&Token::Comma) {
+                    break;
+                }
+            }
+            self.expect_token(&Token::RParen)?;
+        } else {
+            analyze = self.parse_keyword(Keyword::ANALYZE);
+            verbose = self.parse_keyword(Keyword::VERBOSE);
+        }
 
         let statement = self.parse_statement()?;
         Ok(Statement::Explain {
             describe_alias,
             analyze,
             verbose,
+            format,
+            trace,
+            streaming,
+            distributed,
             statement: Box::new(statement),
         })
     }
 
+    fn parse_explain_format(&mut self) -> Result<ExplainFormat, ParserError> {
+        let token = self.peek_token();
+        let format = self.parse_identifier()?;
+        match format.value.to_uppercase().as_str() {
+            "TEXT" => Ok(ExplainFormat::Text),
+            "JSON" => Ok(ExplainFormat::Json),
+            "YAML" => Ok(ExplainFormat::Yaml),
+            "DOT" => Ok(ExplainFormat::Dot),
+            _ => self.expected("TEXT, JSON, YAML or DOT", token),
+        }
+    }
+
     /// Parse a query expression, i.e. a `SELECT` statement optionally
     /// preceeded with some `WITH` CTE declarations and optionally followed
     /// by `ORDER BY`. Unlike some other parse_... methods, this one doesn't
@@ -2640,6 +2903,15 @@ impl Parser {
                         return self.expected("from after columns", self.peek_token());
                     }
                 }
+                Keyword::INTERNAL => {
+                    if self.parse_keywords(&[Keyword::TABLES, Keyword::FROM]) {
+                        return Ok(Statement::ShowObjects(ShowObject::InternalTable {
+                            mview: self.parse_object_name()?,
+                        }));
+                    } else {
+                        return self.expected("TABLES FROM after INTERNAL", self.peek_token());
+                    }
+                }
                 _ => {}
             }
         }
@@ -2702,6 +2974,19 @@ impl Parser {
                             _ => unreachable!(),
                         }
                     }
+                    Keyword::ASOF => {
+                        let _ = self.next_token();
+                        let outer = self.parse_keyword(Keyword::LEFT);
+                        if outer {
+                            let _ = self.parse_keyword(Keyword::OUTER);
+                        }
+                        self.expect_keyword(Keyword::JOIN)?;
+                        if outer {
+                            JoinOperator::AsOfLeftOuter
+                        } else {
+                            JoinOperator::AsOfInner
+                        }
+                    }
                     Keyword::OUTER => {
                         return self.expected("LEFT, RIGHT, or FULL", self.peek_token());
                     }
@@ -2786,7 +3071,9 @@ impl Parser {
                 vec![]
             };
             let alias = self.parse_optional_table_alias(keywords::RESERVED_FOR_TABLE_ALIAS)?;
-            Ok(TableFactor::Table { name, alias, args })
+            let table =
+                self.parse_optional_table_sample(TableFactor::Table { name, alias, args })?;
+            self.parse_optional_match_recognize(table)
         }
     }
 
@@ -2797,7 +3084,7 @@ impl Parser {
         let subquery = Box::new(self.parse_query()?);
         self.expect_token(&Token::RParen)?;
         let alias = self.parse_optional_table_alias(keywords::RESERVED_FOR_TABLE_ALIAS)?;
-        Ok(TableFactor::Derived {
+        self.parse_optional_match_recognize(TableFactor::Derived {
             lateral: match lateral {
                 Lateral => true,
                 NotLateral => false,
@@ -2807,6 +3094,126 @@ impl Parser {
         })
     }
 
+    /// Parse `TABLESAMPLE <method> (<percentage>) [ REPEATABLE (<seed>) ]` following a table, if
+    /// any.
+    fn parse_optional_table_sample(
+        &mut self,
+        table: TableFactor,
+    ) -> Result<TableFactor, ParserError> {
+        if !self.parse_keyword(Keyword::TABLESAMPLE) {
+            return Ok(table);
+        }
+        let method = match self.expect_one_of_keywords(&[Keyword::SYSTEM, Keyword::BERNOULLI])? {
+            Keyword::SYSTEM => TableSampleMethod::System,
+            Keyword::BERNOULLI => TableSampleMethod::Bernoulli,
+            _ => unreachable!(),
+        };
+        self.expect_token(&Token::LParen)?;
+        let percentage = self.parse_expr()?;
+        self.expect_token(&Token::RParen)?;
+        let seed = if self.parse_keyword(Keyword::REPEATABLE) {
+            self.expect_token(&Token::LParen)?;
+            let seed = self.parse_expr()?;
+            self.expect_token(&Token::RParen)?;
+            Some(seed)
+        } else {
+            None
+        };
+        Ok(TableFactor::TableSample {
+            table: Box::new(table),
+            sample: TableSample {
+                method,
+                percentage,
+                seed,
+            },
+        })
+    }
+
+    /// Parse `MATCH_RECOGNIZE (...) [ AS <alias> ]` following a table, if any.
+    fn parse_optional_match_recognize(
+        &mut self,
+        table: TableFactor,
+    ) -> Result<TableFactor, ParserError> {
+        if !self.parse_keyword(Keyword::MATCH_RECOGNIZE) {
+            return Ok(table);
+        }
+        self.expect_token(&Token::LParen)?;
+        let partition_by = if self.parse_keywords(&[Keyword::PARTITION, Keyword::BY]) {
+            self.parse_comma_separated(Parser::parse_expr)?
+        } else {
+            vec![]
+        };
+        let order_by = if self.parse_keywords(&[Keyword::ORDER, Keyword::BY]) {
+            self.parse_comma_separated(Parser::parse_order_by_expr)?
+        } else {
+            vec![]
+        };
+        let measures = if self.parse_keyword(Keyword::MEASURES) {
+            self.parse_comma_separated(|parser| {
+                let expr = parser.parse_expr()?;
+                parser.expect_keyword(Keyword::AS)?;
+                let alias = parser.parse_identifier()?;
+                Ok(Measure { expr, alias })
+            })?
+        } else {
+            vec![]
+        };
+        if self.parse_keyword(Keyword::ONE) {
+            self.expect_keywords(&[Keyword::ROW, Keyword::PER, Keyword::MATCH])?;
+        }
+        if self.parse_keyword(Keyword::AFTER) {
+            self.expect_keywords(&[
+                Keyword::MATCH,
+                Keyword::SKIP,
+                Keyword::PAST,
+                Keyword::LAST,
+                Keyword::ROW,
+            ])?;
+        }
+        self.expect_keyword(Keyword::PATTERN)?;
+        self.expect_token(&Token::LParen)?;
+        let mut pattern = vec![];
+        while !self.consume_token(&Token::RParen) {
+            let symbol = self.parse_identifier()?;
+            let quantifier = if self.consume_token(&Token::Mul) {
+                Some(PatternQuantifier::ZeroOrMore)
+            } else if self.consume_token(&Token::Plus) {
+                Some(PatternQuantifier::OneOrMore)
+            } else if self.consume_token(&Token::Char('?')) {
+                Some(PatternQuantifier::ZeroOrOne)
+            } else {
+                None
+            };
+            pattern.push(PatternTerm { symbol, quantifier });
+        }
+        if pattern.is_empty() {
+            return parser_err!("MATCH_RECOGNIZE requires a non-empty PATTERN");
+        }
+        let define = if self.parse_keyword(Keyword::DEFINE) {
+            self.parse_comma_separated(|parser| {
+                let symbol = parser.parse_identifier()?;
+                parser.expect_keyword(Keyword::AS)?;
+                let condition = parser.parse_expr()?;
+                Ok(SymbolDefinition { symbol, condition })
+            })?
+        } else {
+            vec![]
+        };
+        self.expect_token(&Token::RParen)?;
+        let alias = self.parse_optional_table_alias(keywords::RESERVED_FOR_TABLE_ALIAS)?;
+        Ok(TableFactor::MatchRecognize {
+            table: Box::new(table),
+            match_recognize: Box::new(MatchRecognize {
+                partition_by,
+                order_by,
+                measures,
+                pattern,
+                define,
+            }),
+            alias,
+        })
+    }
+
     fn parse_join_constraint(&mut self, natural: bool) -> Result<JoinConstraint, ParserError> {
         if natural {
             Ok(JoinConstraint::Natural)
@@ -3237,6 +3644,31 @@ impl Parser {
         }
     }
 
+    /// Parse a `DECLARE name SUBSCRIPTION CURSOR FOR subscription` statement.
+    fn parse_declare_cursor(&mut self) -> Result<Statement, ParserError> {
+        let cursor_name = self.parse_identifier()?;
+        self.expect_keywords(&[Keyword::SUBSCRIPTION, Keyword::CURSOR, Keyword::FOR])?;
+        let subscription = self.parse_object_name()?;
+        Ok(Statement::DeclareCursor {
+            cursor_name,
+            subscription,
+        })
+    }
+
+    /// Parse a `FETCH [ count | NEXT ] [ FROM | IN ] cursor` statement.
+    fn parse_fetch_cursor(&mut self) -> Result<Statement, ParserError> {
+        let count = if self.parse_keyword(Keyword::NEXT) {
+            1
+        } else if let Token::Number(_, _) = self.peek_token() {
+            self.parse_literal_uint()?
+        } else {
+            1
+        };
+        let _ = self.parse_one_of_keywords(&[Keyword::FROM, Keyword::IN]);
+        let cursor_name = self.parse_identifier()?;
+        Ok(Statement::Fetch { cursor_name, count })
+    }
+
     fn parse_deallocate(&mut self) -> Result<Statement, ParserError> {
         let prepare = self.parse_keyword(Keyword::PREPARE);
         let name = self.parse_identifier()?;
@@ -3286,6 +3718,11 @@ impl Parser {
                 let object_name = self.parse_object_name()?;
                 (CommentObject::Table, object_name)
             }
+            Token::Word(w) if w.keyword == Keyword::MATERIALIZED => {
+                self.expect_keyword(Keyword::VIEW)?;
+                let object_name = self.parse_object_name()?;
+                (CommentObject::MaterializedView, object_name)
+            }
             _ => self.expected("comment object_type", token)?,
         };
 
@@ -134,6 +134,12 @@ pub enum Token {
     DoubleExclamationMark,
     /// AtSign `@` used for PostgreSQL abs operator
     AtSign,
+    /// `@@`, a full-text match operator in PostgreSQL
+    AtAt,
+    /// `<->`, a vector L2 distance operator of pgvector
+    LtMinusGt,
+    /// `<#>`, a vector negative inner product operator of pgvector
+    LtHashGt,
     /// `|/`, a square root math operator in PostgreSQL
     PGSquareRoot,
     /// `||/` , a cube root math operator in PostgreSQL
@@ -189,6 +195,9 @@ impl fmt::Display for Token {
             Token::ExclamationMarkTilde => f.write_str("!~"),
             Token::ExclamationMarkTildeAsterisk => f.write_str("!~*"),
             Token::AtSign => f.write_str("@"),
+            Token::AtAt => f.write_str("@@"),
+            Token::LtMinusGt => f.write_str("<->"),
+            Token::LtHashGt => f.write_str("<#>"),
             Token::ShiftLeft => f.write_str("<<"),
             Token::ShiftRight => f.write_str(">>"),
             Token::PGSquareRoot => f.write_str("|/"),
@@ -543,6 +552,21 @@ impl<'a> Tokenizer<'a> {
                         }
                         Some('>') => self.consume_and_return(chars, Token::Neq),
                         Some('<') => self.consume_and_return(chars, Token::ShiftLeft),
+                        // `<-` or `<#` without `>` is `<` followed by another token, e.g. `a<-1`.
+                        Some('-' | '#') => {
+                            let mut lookahead = chars.clone();
+                            match (lookahead.next(), lookahead.peek()) {
+                                (Some('-'), Some('>')) => {
+                                    chars.next();
+                                    self.consume_and_return(chars, Token::LtMinusGt)
+                                }
+                                (Some('#'), Some('>')) => {
+                                    chars.next();
+                                    self.consume_and_return(chars, Token::LtHashGt)
+                                }
+                                _ => Ok(Some(Token::Lt)),
+                            }
+                        }
                         _ => Ok(Some(Token::Lt)),
                     }
                 }
@@ -577,7 +601,13 @@ impl<'a> Tokenizer<'a> {
                     }
                 }
                 '#' => self.consume_and_return(chars, Token::Sharp),
-                '@' => self.consume_and_return(chars, Token::AtSign),
+                '@' => {
+                    chars.next(); // consume
+                    match chars.peek() {
+                        Some('@') => self.consume_and_return(chars, Token::AtAt),
+                        _ => Ok(Some(Token::AtSign)),
+                    }
+                }
                 other => self.consume_and_return(chars, Token::Char(other)),
             },
             None => Ok(None),
@@ -1736,6 +1736,10 @@ fn run_explain_analyze(query: &str, expected_verbose: bool, expected_analyze: bo
             describe_alias: _,
             analyze,
             verbose,
+            format: _,
+            trace: _,
+            streaming: _,
+            distributed: _,
             statement,
         } => {
             assert_eq!(verbose, expected_verbose);
@@ -2284,6 +2288,22 @@ fn parse_joins_on() {
         only(&verified_only_select("SELECT * FROM t1 FULL JOIN t2 ON c1 = c2").from).joins,
         vec![join_with_constraint("t2", None, JoinOperator::FullOuter)]
     );
+    assert_eq!(
+        only(&verified_only_select("SELECT * FROM t1 ASOF JOIN t2 ON c1 = c2").from).joins,
+        vec![join_with_constraint("t2", None, JoinOperator::AsOfInner)]
+    );
+    assert_eq!(
+        only(&verified_only_select("SELECT * FROM t1 ASOF LEFT JOIN t2 ON c1 = c2").from).joins,
+        vec![join_with_constraint(
+            "t2",
+            None,
+            JoinOperator::AsOfLeftOuter
+        )]
+    );
+    one_statement_parses_to(
+        "SELECT * FROM t1 ASOF LEFT OUTER JOIN t2 ON c1 = c2",
+        "SELECT * FROM t1 ASOF LEFT JOIN t2 ON c1 = c2",
+    );
 }
 
 #[test]
@@ -2758,12 +2778,14 @@ fn parse_create_view() {
             query,
             or_replace,
             materialized,
+            if_not_exists,
             with_options,
         } => {
             assert_eq!("myschema.myview", name.to_string());
             assert_eq!(Vec::<Ident>::new(), columns);
             assert_eq!("SELECT foo FROM bar", query.to_string());
             assert!(!materialized);
+            assert!(!if_not_exists);
             assert!(!or_replace);
             assert_eq!(with_options, vec![]);
         }
@@ -2805,12 +2827,14 @@ fn parse_create_view_with_columns() {
             with_options,
             query,
             materialized,
+            if_not_exists,
         } => {
             assert_eq!("v", name.to_string());
             assert_eq!(columns, vec![Ident::new("has"), Ident::new("cols")]);
             assert_eq!(with_options, vec![]);
             assert_eq!("SELECT 1, 2", query.to_string());
             assert!(!materialized);
+            assert!(!if_not_exists);
             assert!(!or_replace)
         }
         _ => unreachable!(),
@@ -2827,12 +2851,14 @@ fn parse_create_or_replace_view() {
             with_options,
             query,
             materialized,
+            if_not_exists,
         } => {
             assert_eq!("v", name.to_string());
             assert_eq!(columns, vec![]);
             assert_eq!(with_options, vec![]);
             assert_eq!("SELECT 1", query.to_string());
             assert!(!materialized);
+            assert!(!if_not_exists);
             assert!(or_replace)
         }
         _ => unreachable!(),
@@ -2854,12 +2880,14 @@ fn parse_create_or_replace_materialized_view() {
             with_options,
             query,
             materialized,
+            if_not_exists,
         } => {
             assert_eq!("v", name.to_string());
             assert_eq!(columns, vec![]);
             assert_eq!(with_options, vec![]);
             assert_eq!("SELECT 1", query.to_string());
             assert!(materialized);
+            assert!(!if_not_exists);
             assert!(or_replace)
         }
         _ => unreachable!(),
@@ -2876,12 +2904,14 @@ fn parse_create_materialized_view() {
             columns,
             query,
             materialized,
+            if_not_exists,
             with_options,
         } => {
             assert_eq!("myschema.myview", name.to_string());
             assert_eq!(Vec::<Ident>::new(), columns);
             assert_eq!("SELECT foo FROM bar", query.to_string());
             assert!(materialized);
+            assert!(!if_not_exists);
             assert_eq!(with_options, vec![]);
             assert!(!or_replace);
         }
@@ -2889,6 +2919,26 @@ fn parse_create_materialized_view() {
     }
 }
 
+#[test]
+fn parse_create_materialized_view_if_not_exists() {
+    let sql = "CREATE MATERIALIZED VIEW IF NOT EXISTS myview AS SELECT foo FROM bar";
+    match verified_stmt(sql) {
+        Statement::CreateView {
+            name,
+            materialized,
+            if_not_exists,
+            or_replace,
+            ..
+        } => {
+            assert_eq!("myview", name.to_string());
+            assert!(materialized);
+            assert!(if_not_exists);
+            assert!(!or_replace);
+        }
+        _ => unreachable!(),
+    }
+}
+
 #[test]
 fn parse_drop_table() {
     let sql = "DROP TABLE foo";
@@ -3364,12 +3414,14 @@ fn parse_create_index() {
         Statement::CreateIndex {
             name,
             table_name,
+            using,
             columns,
             unique,
             if_not_exists,
         } => {
             assert_eq!("idx_name", name.to_string());
             assert_eq!("test", table_name.to_string());
+            assert_eq!(None, using);
             assert_eq!(indexed_columns, columns);
             assert!(unique);
             assert!(if_not_exists)
@@ -689,6 +689,42 @@ fn parse_pg_regex_match_ops() {
     }
 }
 
+#[test]
+fn parse_pg_ts_match_op() {
+    let select = verified_only_select("SELECT body @@ to_tsquery('fat & rat')");
+    assert!(matches!(
+        &select.projection[0],
+        SelectItem::UnnamedExpr(Expr::BinaryOp {
+            op: BinaryOperator::PGTsMatch,
+            ..
+        })
+    ));
+    verified_stmt("CREATE INDEX idx ON t USING fulltext (body)");
+}
+
+#[test]
+fn parse_pg_vector_ops() {
+    verified_stmt("CREATE TABLE t (id INT, embedding VECTOR(3))");
+    for (sql, op) in [
+        ("SELECT a <-> b", BinaryOperator::PGL2Distance),
+        ("SELECT a <#> b", BinaryOperator::PGNegativeInnerProduct),
+        ("SELECT a <=> b", BinaryOperator::Spaceship),
+    ] {
+        let select = verified_only_select(sql);
+        assert_eq!(
+            select.projection[0],
+            SelectItem::UnnamedExpr(Expr::BinaryOp {
+                left: Box::new(Expr::Identifier(Ident::new("a"))),
+                op,
+                right: Box::new(Expr::Identifier(Ident::new("b"))),
+            })
+        );
+    }
+    // `<-` is still `<` followed by a negative number.
+    one_statement_parses_to("SELECT a<-1", "SELECT a < -1");
+    verified_stmt("CREATE INDEX idx ON t USING ivf (embedding)");
+}
+
 #[test]
 fn test_transaction_statement() {
     let statement = verified_stmt("SET TRANSACTION SNAPSHOT '000003A1-1'");
@@ -743,6 +779,19 @@ fn parse_comments() {
         _ => unreachable!(),
     }
 
+    match verified_stmt("COMMENT ON MATERIALIZED VIEW mv IS 'comment'") {
+        Statement::Comment {
+            object_type,
+            object_name,
+            comment: Some(comment),
+        } => {
+            assert_eq!("comment", comment);
+            assert_eq!("mv", object_name.to_string());
+            assert_eq!(CommentObject::MaterializedView, object_type);
+        }
+        _ => unreachable!(),
+    }
+
     match verified_stmt("COMMENT ON TABLE public.tab IS NULL") {
         Statement::Comment {
             object_type,
@@ -100,6 +100,7 @@ fn build_block_data(t: u32, i: u64) -> Bytes {
     let options = BlockBuilderOptions {
         capacity: BLOCK_CAPACITY,
         compression_algorithm: CompressionAlgorithm::None,
+        compression_level: 0,
         restart_interval: RESTART_INTERVAL,
     };
     let mut builder = BlockBuilder::new(options);
@@ -28,14 +28,7 @@
 mod rpc;
 mod server;
 
-use std::fs;
-use std::path::PathBuf;
-
 use clap::Parser;
-use risingwave_common::config::{ServerConfig, StorageConfig};
-use risingwave_common::error::ErrorCode::InternalError;
-use risingwave_common::error::{Result, RwError};
-use serde::{Deserialize, Serialize};
 
 use crate::server::compactor_serve;
 
@@ -69,32 +62,11 @@ pub struct CompactorOpts {
     /// No given `config_path` means to use default config.
     #[clap(long, default_value = "")]
     pub config_path: String,
-}
 
-#[derive(Clone, Debug, Serialize, Deserialize, Default)]
-pub struct CompactorConfig {
-    // For connection
-    #[serde(default)]
-    pub server: ServerConfig,
-
-    // Below for Hummock.
-    #[serde(default)]
-    pub storage: StorageConfig,
-}
-
-impl CompactorConfig {
-    pub fn init(path: PathBuf) -> Result<CompactorConfig> {
-        let config_str = fs::read_to_string(path.clone()).map_err(|e| {
-            RwError::from(InternalError(format!(
-                "failed to open config file '{}': {}",
-                path.to_string_lossy(),
-                e
-            )))
-        })?;
-        let config: CompactorConfig = toml::from_str(config_str.as_str())
-            .map_err(|e| RwError::from(InternalError(format!("parse error {}", e))))?;
-        Ok(config)
-    }
+    /// Print the config after applying the defaults, the config file and the environment variable
+    /// overrides, and exit.
+    #[clap(long)]
+    pub print_effective_config: bool,
 }
 
 use std::future::Future;
@@ -104,6 +76,12 @@ pub fn start(opts: CompactorOpts) -> Pin<Box<dyn Future<Output = ()> + Send>> {
     // WARNING: don't change the function signature. Making it `async fn` will cause
     // slow compile in release mode.
     Box::pin(async move {
+        if opts.print_effective_config {
+            let config = risingwave_common::config::load_config(&opts.config_path).unwrap();
+            println!("{}", config.to_toml_string());
+            return;
+        }
+
         tracing::info!("meta address: {}", opts.meta_address.clone());
 
         let listen_address = opts.host.parse().unwrap();
@@ -13,12 +13,12 @@
 // limitations under the License.
 
 use std::net::SocketAddr;
-use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
-use risingwave_common::service::MetricsManager;
+use risingwave_common::service::{GrpcMetrics, GrpcMiddlewareLayer, MetricsManager};
 use risingwave_common::util::addr::HostAddr;
+use risingwave_common::util::shutdown::wait_for_shutdown_signal;
 use risingwave_object_store::object::{parse_object_store, ObjectStoreImpl};
 use risingwave_pb::common::WorkerType;
 use risingwave_pb::hummock::compactor_service_server::CompactorServiceServer;
@@ -33,7 +33,7 @@ use tokio::sync::oneshot::Sender;
 use tokio::task::JoinHandle;
 
 use crate::rpc::CompactorServiceImpl;
-use crate::{CompactorConfig, CompactorOpts};
+use crate::CompactorOpts;
 
 /// Fetches and runs compaction tasks.
 pub async fn compactor_serve(
@@ -41,14 +41,7 @@ pub async fn compactor_serve(
     client_addr: HostAddr,
     opts: CompactorOpts,
 ) -> (JoinHandle<()>, Sender<()>) {
-    let config = {
-        if opts.config_path.is_empty() {
-            CompactorConfig::default()
-        } else {
-            let config_path = PathBuf::from(opts.config_path.to_owned());
-            CompactorConfig::init(config_path).unwrap()
-        }
-    };
+    let config = risingwave_common::config::load_config(&opts.config_path).unwrap();
     tracing::info!("Starting compactor with config {:?}", config);
 
     // Register to the cluster.
@@ -64,6 +57,7 @@ pub async fn compactor_serve(
     let registry = prometheus::Registry::new();
     let hummock_metrics = Arc::new(HummockMetrics::new(registry.clone()));
     let object_metrics = Arc::new(ObjectStoreMetrics::new(registry.clone()));
+    let grpc_metrics = Arc::new(GrpcMetrics::new(&registry, "compactor"));
     let hummock_meta_client = Arc::new(MonitoredHummockMetaClient::new(
         meta_client.clone(),
         hummock_metrics.clone(),
@@ -105,10 +99,11 @@ pub async fn compactor_serve(
     let (shutdown_send, mut shutdown_recv) = tokio::sync::oneshot::channel();
     let join_handle = tokio::spawn(async move {
         tonic::transport::Server::builder()
+            .layer(GrpcMiddlewareLayer::new(grpc_metrics))
             .add_service(CompactorServiceServer::new(CompactorServiceImpl {}))
             .serve_with_shutdown(listen_addr, async move {
                 tokio::select! {
-                    _ = tokio::signal::ctrl_c() => {},
+                    _ = wait_for_shutdown_signal() => {},
                     _ = &mut shutdown_recv => {
                         for (join_handle, shutdown_sender) in sub_tasks {
                             if let Err(err) = shutdown_sender.send(()) {
@@ -16,20 +16,23 @@ use std::collections::{HashMap, HashSet};
 use std::future::Future;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use bytes::{Bytes, BytesMut};
 use futures::future::{try_join_all, BoxFuture};
 use futures::{stream, FutureExt, StreamExt, TryFutureExt};
 use itertools::Itertools;
+use madsim::time::Instant;
 use risingwave_common::config::StorageConfig;
 use risingwave_common::util::compress::decompress_data;
 use risingwave_hummock_sdk::compact::compact_task_to_string;
 use risingwave_hummock_sdk::compaction_group::StaticCompactionGroupId;
-use risingwave_hummock_sdk::key::{get_epoch, get_table_id, Epoch, FullKey};
+use risingwave_hummock_sdk::key::{get_epoch, get_table_id, Epoch, FullKey, TABLE_PREFIX_LEN};
 use risingwave_hummock_sdk::key_range::KeyRange;
 use risingwave_hummock_sdk::{CompactionGroupId, HummockSSTableId, VersionedComparator};
-use risingwave_pb::hummock::{CompactTask, SstableInfo, SubscribeCompactTasksResponse, VacuumTask};
+use risingwave_pb::hummock::{
+    CompactTask, PartitionRetention, SstableInfo, SubscribeCompactTasksResponse, VacuumTask,
+};
 use risingwave_rpc_client::HummockMetaClient;
 use tokio::sync::oneshot::Sender;
 use tokio::task::JoinHandle;
@@ -126,6 +129,86 @@ impl CompactionFilter for StateCleanUpCompactionFilter {
     }
 }
 
+/// Drops the keys of tables older than their retention.
+#[derive(Clone)]
+pub struct RetentionCompactionFilter {
+    /// Keys of a table with an epoch smaller than its cutoff epoch are expired.
+    cutoff_epochs: HashMap<u32, Epoch>,
+}
+
+impl RetentionCompactionFilter {
+    fn new(table_retention_seconds: HashMap<u32, u32>) -> Self {
+        let now = risingwave_common::util::epoch::Epoch::now();
+        let cutoff_epochs = table_retention_seconds
+            .into_iter()
+            .map(|(table_id, retention_seconds)| {
+                (table_id, now.subtract_ms(retention_seconds as u64 * 1000).0)
+            })
+            .collect();
+        RetentionCompactionFilter { cutoff_epochs }
+    }
+}
+
+impl CompactionFilter for RetentionCompactionFilter {
+    fn filter(&self, key: &[u8]) -> bool {
+        match get_table_id(key).and_then(|table_id| self.cutoff_epochs.get(&table_id)) {
+            None => true,
+            Some(cutoff_epoch) => get_epoch(key) >= *cutoff_epoch,
+        }
+    }
+}
+
+/// Drops the keys of partitioned tables in the partitions ending before their retention.
+#[derive(Clone)]
+pub struct PartitionRetentionCompactionFilter {
+    /// Keys of a table with a partition timestamp in seconds smaller than its cutoff are expired.
+    cutoff_seconds: HashMap<u32, i64>,
+}
+
+impl PartitionRetentionCompactionFilter {
+    fn new(table_partition_retention: HashMap<u32, PartitionRetention>) -> Self {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        let cutoff_seconds = table_partition_retention
+            .into_iter()
+            .map(|(table_id, retention)| {
+                let interval = retention.interval_seconds.max(1) as i64;
+                let expired_end = now - retention.retention_seconds as i64;
+                // The partitions ending no later than `expired_end` start before the cutoff.
+                (table_id, expired_end.div_euclid(interval) * interval)
+            })
+            .collect();
+        PartitionRetentionCompactionFilter { cutoff_seconds }
+    }
+}
+
+impl CompactionFilter for PartitionRetentionCompactionFilter {
+    fn filter(&self, key: &[u8]) -> bool {
+        let cutoff = match get_table_id(key).and_then(|table_id| self.cutoff_seconds.get(&table_id))
+        {
+            None => return true,
+            Some(cutoff) => *cutoff,
+        };
+        // The key of a partitioned table starts with its partition column after the table prefix,
+        // encoded as a non-null tag followed by the memcomparable timestamp.
+        match key.get(TABLE_PREFIX_LEN) {
+            Some(1) => memcomparable::Deserializer::new(&key[TABLE_PREFIX_LEN + 1..])
+                .deserialize_naivedatetime()
+                .map_or(true, |(seconds, _)| seconds >= cutoff),
+            _ => true,
+        }
+    }
+}
+
+/// Retains a key only if both filters retain it.
+impl<A: CompactionFilter, B: CompactionFilter> CompactionFilter for (A, B) {
+    fn filter(&self, key: &[u8]) -> bool {
+        self.0.filter(key) && self.1.filter(key)
+    }
+}
+
 #[derive(Clone)]
 /// Implementation of Hummock compaction.
 pub struct Compactor {
@@ -242,8 +325,19 @@ impl Compactor {
             vnode_mappings: vec![],
             compaction_group_id: StaticCompactionGroupId::StateDefault.into(),
             existing_table_ids: vec![],
+            table_retention_seconds: HashMap::new(),
+            table_partition_retention: HashMap::new(),
             target_file_size: context.options.sstable_size_mb as u64 * (1 << 20),
-            compression_algorithm: 0,
+            compression_algorithm: match context
+                .options
+                .shared_buffer_compression_algorithm
+                .as_str()
+            {
+                "Lz4" => 1,
+                "Zstd" => 2,
+                _ => 0,
+            },
+            compression_level: context.options.shared_buffer_compression_level,
         };
 
         let sstable_store = context.sstable_store.clone();
@@ -441,8 +535,13 @@ impl Compactor {
             );
         }
 
-        let compaction_filter =
-            StateCleanUpCompactionFilter::new(HashSet::from_iter(compact_task.existing_table_ids));
+        let compaction_filter = (
+            StateCleanUpCompactionFilter::new(HashSet::from_iter(compact_task.existing_table_ids)),
+            (
+                RetentionCompactionFilter::new(compact_task.table_retention_seconds),
+                PartitionRetentionCompactionFilter::new(compact_task.table_partition_retention),
+            ),
+        );
 
         for (split_index, _) in compact_task.splits.iter().enumerate() {
             let compactor = compactor.clone();
@@ -584,6 +683,7 @@ impl Compactor {
                     1 => CompressionAlgorithm::Lz4,
                     _ => CompressionAlgorithm::Zstd,
                 };
+                options.compression_level = self.compact_task.compression_level;
                 let builder = SSTableBuilder::new(table_id, options);
                 get_id_time.fetch_add(cost, Ordering::Relaxed);
                 Ok(builder)
@@ -683,7 +783,10 @@ impl Compactor {
     async fn build_sst_iter(&self) -> HummockResult<BoxedForwardHummockIterator> {
         let mut table_iters: Vec<BoxedForwardHummockIterator> = Vec::new();
         let mut stats = StoreLocalStatistic::default();
-        let read_options = Arc::new(ReadOptions { prefetch: true });
+        let read_options = Arc::new(ReadOptions {
+            prefetch: true,
+            ..Default::default()
+        });
         for level in &self.compact_task.input_ssts {
             if level.table_infos.is_empty() {
                 continue;
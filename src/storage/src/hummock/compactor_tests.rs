@@ -21,12 +21,14 @@ mod tests {
     use rand::Rng;
     use risingwave_common::catalog::TableId;
     use risingwave_common::config::StorageConfig;
+    use risingwave_common::types::{serialize_datum_into, NaiveDateTimeWrapper, ScalarImpl};
+    use risingwave_common::util::epoch::Epoch;
     use risingwave_hummock_sdk::compaction_group::hummock_version_ext::HummockVersionExt;
     use risingwave_hummock_sdk::compaction_group::StaticCompactionGroupId;
     use risingwave_hummock_sdk::key::get_table_id;
     use risingwave_meta::hummock::test_utils::setup_compute_env;
     use risingwave_meta::hummock::MockHummockMetaClient;
-    use risingwave_pb::hummock::HummockVersion;
+    use risingwave_pb::hummock::{HummockVersion, PartitionRetention};
     use risingwave_rpc_client::HummockMetaClient;
 
     use crate::hummock::compactor::{get_remote_sstable_id_generator, Compactor, CompactorContext};
@@ -368,4 +370,199 @@ mod tests {
         }
         assert_eq!(key_count, scan_count);
     }
+
+    #[tokio::test]
+    async fn test_compaction_expire_by_retention() {
+        let (_env, hummock_manager_ref, _cluster_manager_ref, worker_node) =
+            setup_compute_env(8080).await;
+        let hummock_meta_client = Arc::new(MockHummockMetaClient::new(
+            hummock_manager_ref.clone(),
+            worker_node.id,
+        ));
+        let storage = get_hummock_storage(hummock_meta_client.clone()).await;
+        let compact_ctx = CompactorContext {
+            options: storage.options().clone(),
+            sstable_store: storage.sstable_store(),
+            hummock_meta_client: hummock_meta_client.clone(),
+            stats: Arc::new(StateStoreMetrics::unused()),
+            is_share_buffer_compact: false,
+            sstable_id_generator: get_remote_sstable_id_generator(hummock_meta_client.clone()),
+            compaction_executor: None,
+        };
+
+        // 1. add sstables, half of which are written two hours ago
+        let val = Bytes::from(b"0"[..].repeat(1 << 10)); // 1024 Byte value
+
+        let table_id = 2;
+        let kv_count = 128;
+        let now = Epoch::now();
+        let mut epoch = now.subtract_ms(2 * 3600 * 1000).0;
+        for index in 0..kv_count {
+            let keyspace = Keyspace::table_root(storage.clone(), &TableId::new(table_id));
+            let mut write_batch = keyspace.state_store().start_write_batch();
+            let mut local = write_batch.prefixify(&keyspace);
+            epoch = if index == kv_count / 2 {
+                now.0
+            } else {
+                epoch + 1
+            };
+
+            let ramdom_key = rand::thread_rng().gen::<[u8; 32]>();
+            local.put(ramdom_key, StorageValue::new_default_put(val.clone()));
+            write_batch.ingest(epoch).await.unwrap();
+
+            storage.sync(Some(epoch)).await.unwrap();
+            hummock_meta_client
+                .commit_epoch(
+                    epoch,
+                    storage.local_version_manager.get_uncommitted_ssts(epoch),
+                )
+                .await
+                .unwrap();
+        }
+
+        // 2. get compact task, and keep keys of the table in the last hour
+        let mut compact_task = hummock_manager_ref
+            .get_compact_task(StaticCompactionGroupId::StateDefault.into())
+            .await
+            .unwrap()
+            .unwrap();
+        compact_task.existing_table_ids.push(table_id);
+        compact_task.table_retention_seconds.insert(table_id, 3600);
+
+        hummock_manager_ref
+            .assign_compaction_task(&compact_task, worker_node.id, async { true })
+            .await
+            .unwrap();
+
+        // 3. compact
+        Compactor::compact(Arc::new(compact_ctx), compact_task.clone()).await;
+
+        // 4. get the latest version and check
+        let version: HummockVersion = hummock_manager_ref.get_current_version().await;
+        let mut key_count = 0;
+        for table_info in version
+            .get_compaction_group_levels(StaticCompactionGroupId::StateDefault.into())
+            .iter()
+            .flat_map(|level| level.table_infos.iter())
+        {
+            key_count += storage
+                .sstable_store()
+                .sstable(table_info.id, &mut StoreLocalStatistic::default())
+                .await
+                .unwrap()
+                .value()
+                .meta
+                .key_count;
+        }
+        assert_eq!((kv_count / 2) as u32, key_count);
+
+        // 5. only the recent keys are visible
+        storage
+            .local_version_manager()
+            .try_update_pinned_version(version);
+        let scan_result = storage
+            .scan::<_, Vec<u8>>(.., None, epoch + 1)
+            .await
+            .unwrap();
+        assert_eq!(key_count as usize, scan_result.len());
+    }
+
+    #[tokio::test]
+    async fn test_compaction_expire_by_partition_retention() {
+        let (_env, hummock_manager_ref, _cluster_manager_ref, worker_node) =
+            setup_compute_env(8080).await;
+        let hummock_meta_client = Arc::new(MockHummockMetaClient::new(
+            hummock_manager_ref.clone(),
+            worker_node.id,
+        ));
+        let storage = get_hummock_storage(hummock_meta_client.clone()).await;
+        let compact_ctx = CompactorContext {
+            options: storage.options().clone(),
+            sstable_store: storage.sstable_store(),
+            hummock_meta_client: hummock_meta_client.clone(),
+            stats: Arc::new(StateStoreMetrics::unused()),
+            is_share_buffer_compact: false,
+            sstable_id_generator: get_remote_sstable_id_generator(hummock_meta_client.clone()),
+            compaction_executor: None,
+        };
+
+        // 1. add sstables, half of which are in the partitions of two months ago
+        let val = Bytes::from(b"0"[..].repeat(1 << 10)); // 1024 Byte value
+
+        let table_id = 2;
+        let kv_count = 128;
+        let now_seconds = chrono::Utc::now().timestamp();
+        let mut epoch = Epoch::now().0;
+        for index in 0..kv_count {
+            let keyspace = Keyspace::table_root(storage.clone(), &TableId::new(table_id));
+            let mut write_batch = keyspace.state_store().start_write_batch();
+            let mut local = write_batch.prefixify(&keyspace);
+            epoch += 1;
+
+            let seconds = if index < kv_count / 2 {
+                now_seconds - 60 * 24 * 3600
+            } else {
+                now_seconds
+            };
+            let ts = NaiveDateTimeWrapper::with_secs_nsecs(seconds, 0).unwrap();
+            let mut serializer = memcomparable::Serializer::new(vec![]);
+            serialize_datum_into(&Some(ScalarImpl::NaiveDateTime(ts)), &mut serializer).unwrap();
+            let mut key = serializer.into_inner();
+            key.extend(rand::thread_rng().gen::<[u8; 16]>());
+            local.put(key, StorageValue::new_default_put(val.clone()));
+            write_batch.ingest(epoch).await.unwrap();
+
+            storage.sync(Some(epoch)).await.unwrap();
+            hummock_meta_client
+                .commit_epoch(
+                    epoch,
+                    storage.local_version_manager.get_uncommitted_ssts(epoch),
+                )
+                .await
+                .unwrap();
+        }
+
+        // 2. get compact task, and keep the daily partitions of the last 30 days
+        let mut compact_task = hummock_manager_ref
+            .get_compact_task(StaticCompactionGroupId::StateDefault.into())
+            .await
+            .unwrap()
+            .unwrap();
+        compact_task.existing_table_ids.push(table_id);
+        compact_task.table_partition_retention.insert(
+            table_id,
+            PartitionRetention {
+                interval_seconds: 24 * 3600,
+                retention_seconds: 30 * 24 * 3600,
+            },
+        );
+
+        hummock_manager_ref
+            .assign_compaction_task(&compact_task, worker_node.id, async { true })
+            .await
+            .unwrap();
+
+        // 3. compact
+        Compactor::compact(Arc::new(compact_ctx), compact_task.clone()).await;
+
+        // 4. get the latest version and check
+        let version: HummockVersion = hummock_manager_ref.get_current_version().await;
+        let mut key_count = 0;
+        for table_info in version
+            .get_compaction_group_levels(StaticCompactionGroupId::StateDefault.into())
+            .iter()
+            .flat_map(|level| level.table_infos.iter())
+        {
+            key_count += storage
+                .sstable_store()
+                .sstable(table_info.id, &mut StoreLocalStatistic::default())
+                .await
+                .unwrap()
+                .value()
+                .meta
+                .key_count;
+        }
+        assert_eq!((kv_count / 2) as u32, key_count);
+    }
 }
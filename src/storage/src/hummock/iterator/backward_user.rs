@@ -217,7 +217,12 @@ impl BackwardUserIterator {
                 let full_key = &key_with_epoch(end_key.clone(), 0);
                 self.iterator.seek(full_key).await?;
             }
-            Excluded(_) => unimplemented!("excluded begin key is not supported"),
+            Excluded(end_key) => {
+                // All versions of `end_key` are ordered after the one with the max epoch, so
+                // seeking to it skips the excluded key entirely.
+                let full_key = &key_with_epoch(end_key.clone(), Epoch::MAX);
+                self.iterator.seek(full_key).await?;
+            }
             Unbounded => self.iterator.rewind().await?,
         };
 
@@ -230,19 +235,24 @@ impl BackwardUserIterator {
     /// Resets the iterating position to the first position where the key >= provided key.
     pub async fn seek(&mut self, user_key: &[u8]) -> HummockResult<()> {
         // Handle range scan when key > end_key
-        let user_key = match &self.key_range.1 {
+        let full_key = match &self.key_range.1 {
             Included(end_key) => {
                 if end_key.as_slice() < user_key {
-                    end_key.clone()
+                    key_with_epoch(end_key.clone(), 0)
                 } else {
-                    Vec::from(user_key)
+                    key_with_epoch(Vec::from(user_key), 0)
                 }
             }
-            Excluded(_) => unimplemented!("excluded begin key is not supported"),
-            Unbounded => Vec::from(user_key),
+            Excluded(end_key) => {
+                if end_key.as_slice() <= user_key {
+                    key_with_epoch(end_key.clone(), Epoch::MAX)
+                } else {
+                    key_with_epoch(Vec::from(user_key), 0)
+                }
+            }
+            Unbounded => key_with_epoch(Vec::from(user_key), 0),
         };
-        let full_key = &key_with_epoch(user_key, 0);
-        self.iterator.seek(full_key).await?;
+        self.iterator.seek(&full_key).await?;
 
         // Handle multi-version
         self.reset();
@@ -659,6 +669,59 @@ mod tests {
         assert!(!bui.is_valid());
     }
 
+    // [left, right)
+    #[tokio::test]
+    async fn test_backward_user_range_exclusive_end() {
+        let sstable_store = mock_sstable_store();
+        // key=[idx, epoch], value
+        let kv_pairs = vec![
+            (1, 100, HummockValue::put(iterator_test_value_of(1))),
+            (2, 100, HummockValue::put(iterator_test_value_of(2))),
+            (3, 100, HummockValue::put(iterator_test_value_of(3))),
+            (5, 200, HummockValue::delete()),
+            (5, 100, HummockValue::put(iterator_test_value_of(5))),
+            (6, 100, HummockValue::put(iterator_test_value_of(6))),
+            (7, 200, HummockValue::put(iterator_test_value_of(7))),
+            (7, 100, HummockValue::put(iterator_test_value_of(7))),
+            (8, 100, HummockValue::put(iterator_test_value_of(8))),
+        ];
+        let table =
+            gen_iterator_test_sstable_from_kv_pair(0, kv_pairs, sstable_store.clone()).await;
+        let cache = create_small_table_cache();
+        let handle = cache.insert(table.id, table.id, 1, Box::new(table));
+        let backward_iters: Vec<BoxedBackwardHummockIterator> = vec![Box::new(
+            BackwardSSTableIterator::new(handle, sstable_store),
+        )];
+        let bmi = BackwardMergeIterator::new(backward_iters, Arc::new(StateStoreMetrics::unused()));
+
+        let begin_key = Included(user_key(iterator_test_key_of_epoch(2, 0).as_slice()).to_vec());
+        let end_key = Excluded(user_key(iterator_test_key_of_epoch(7, 0).as_slice()).to_vec());
+
+        let mut bui = BackwardUserIterator::new(bmi, (begin_key, end_key));
+
+        // ----- basic iterate -----
+        bui.rewind().await.unwrap();
+        assert_eq!(bui.key(), user_key(iterator_test_key_of(6).as_slice()));
+        bui.next().await.unwrap();
+        assert_eq!(bui.key(), user_key(iterator_test_key_of(3).as_slice()));
+        bui.next().await.unwrap();
+        assert_eq!(bui.key(), user_key(iterator_test_key_of(2).as_slice()));
+        bui.next().await.unwrap();
+        assert!(!bui.is_valid());
+
+        // ----- end-range iterate -----
+        bui.seek(user_key(iterator_test_key_of(7).as_slice()))
+            .await
+            .unwrap();
+        assert_eq!(bui.key(), user_key(iterator_test_key_of(6).as_slice()));
+
+        // ----- in-range iterate -----
+        bui.seek(user_key(iterator_test_key_of(5).as_slice()))
+            .await
+            .unwrap();
+        assert_eq!(bui.key(), user_key(iterator_test_key_of(3).as_slice()));
+    }
+
     // ..=right
     #[tokio::test]
     async fn test_backward_user_range_to_inclusive() {
@@ -62,6 +62,18 @@ impl<TI: SSTableIteratorType> ConcatIteratorInner<TI> {
         }
     }
 
+    /// Prefetches the table after `idx` which is going to be read by a sequential forward scan, so
+    /// that the scan does not stall at the boundary of tables.
+    fn prefetch_next_table(&self, idx: usize) {
+        let count = self.read_options.prefetch_block_count;
+        if count == 0 || !matches!(TI::Direction::direction(), DirectionEnum::Forward) {
+            return;
+        }
+        if let Some(table) = self.tables.get(idx + 1) {
+            self.sstable_store.prefetch_sstable(table.id, count);
+        }
+    }
+
     /// Seeks to a table, and then seeks to the key if `seek_key` is given.
     async fn seek_idx(&mut self, idx: usize, seek_key: Option<&[u8]>) -> HummockResult<()> {
         if idx >= self.tables.len() {
@@ -105,6 +117,7 @@ impl<TI: SSTableIteratorType> HummockIterator for ConcatIteratorInner<TI> {
             Ok(())
         } else {
             // seek to next table
+            self.prefetch_next_table(self.cur_idx + 1);
             self.seek_idx(self.cur_idx + 1, None).await
         }
     }
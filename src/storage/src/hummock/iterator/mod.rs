@@ -148,4 +148,7 @@ impl HummockIteratorDirection for Backward {
 #[derive(Default)]
 pub struct ReadOptions {
     pub prefetch: bool,
+    /// Number of blocks to fetch in background ahead of a sequential forward scan, including
+    /// those of the next SST in a level. 0 disables it.
+    pub prefetch_block_count: usize,
 }
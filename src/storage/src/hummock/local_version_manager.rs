@@ -18,6 +18,7 @@ use std::sync::{Arc, Weak};
 use std::time::Duration;
 
 use bytes::Bytes;
+use fail::fail_point;
 use itertools::Itertools;
 use parking_lot::RwLock;
 use risingwave_common::config::StorageConfig;
@@ -343,6 +344,9 @@ impl LocalVersionManager {
     }
 
     pub async fn sync_shared_buffer_epoch(&self, epoch: HummockEpoch) -> HummockResult<()> {
+        fail_point!("sync_shared_buffer_epoch_err", |_| Err(
+            HummockError::shared_buffer_error("sync_shared_buffer_epoch_err")
+        ));
         let task = {
             match self.local_version.read().new_upload_task(epoch, SyncEpoch) {
                 Some((order_index, task_data)) => {
@@ -14,6 +14,7 @@
 
 //! Hummock is the state store of the streaming system.
 
+use std::cmp::Ordering;
 use std::fmt;
 use std::sync::Arc;
 
@@ -59,13 +60,11 @@ pub use error::*;
 pub use risingwave_common::cache::{CachableEntry, LookupResult, LruCache};
 use value::*;
 
-use self::iterator::HummockIterator;
 use self::key::user_key;
 pub use self::sstable_store::*;
 pub use self::state_store::HummockStateStoreIter;
 use super::monitor::StateStoreMetrics;
 use crate::hummock::conflict_detector::ConflictDetector;
-use crate::hummock::iterator::ReadOptions;
 use crate::hummock::local_version_manager::LocalVersionManager;
 use crate::hummock::sstable_store::{SstableStoreRef, TableHolder};
 use crate::monitor::StoreLocalStatistic;
@@ -127,35 +126,53 @@ impl HummockStorage {
         Ok(instance)
     }
 
+    /// Point get on a single SST. Instead of creating an [`SSTableIterator`], it consults the
+    /// bloom filter, then seeks in at most two blocks fetched through the block cache, where
+    /// concurrent gets to the same block share a single fetch.
     async fn get_from_table(
         &self,
         table: TableHolder,
         internal_key: &[u8],
         key: &[u8],
-        read_options: Arc<ReadOptions>,
         stats: &mut StoreLocalStatistic,
     ) -> HummockResult<Option<Option<Bytes>>> {
-        if table.value().surely_not_have_user_key(key) {
+        let sst = table.value();
+        if sst.surely_not_have_user_key(key) {
             stats.bloom_filter_true_negative_count += 1;
             return Ok(None);
         }
         // Might have the key, take it as might positive.
         stats.bloom_filter_might_positive_count += 1;
-        let mut iter = SSTableIterator::create(table, self.sstable_store.clone(), read_options);
-        iter.seek(internal_key).await?;
-        // Iterator has seeked passed the borders.
-        if !iter.is_valid() {
-            return Ok(None);
-        }
 
-        // Iterator gets us the key, we tell if it's the key we want
-        // or key next to it.
-        let value = match user_key(iter.key()) == key {
-            true => Some(iter.value().into_user_value().map(Bytes::copy_from_slice)),
-            false => None,
-        };
-        iter.collect_local_statistic(stats);
-        Ok(value)
+        // Find the last block whose smallest key is not greater than `internal_key`.
+        let block_idx = sst
+            .meta
+            .block_metas
+            .partition_point(|block_meta| {
+                VersionedComparator::compare_key(&block_meta.smallest_key, internal_key)
+                    != Ordering::Greater
+            })
+            .saturating_sub(1);
+        // The first entry not less than `internal_key` is either in that block, or the first entry
+        // of the next block.
+        for idx in block_idx..std::cmp::min(block_idx + 2, sst.block_count()) {
+            let block = self
+                .sstable_store
+                .get(sst, idx as u64, CachePolicy::Fill, stats)
+                .await?;
+            let mut block_iter = BlockIterator::new(block);
+            block_iter.seek(internal_key);
+            if !block_iter.is_valid() {
+                continue;
+            }
+            // We got the key, or the key next to it.
+            if user_key(block_iter.key()) != key {
+                return Ok(None);
+            }
+            let value = HummockValue::from_slice(block_iter.value())?;
+            return Ok(Some(value.into_user_value().map(Bytes::copy_from_slice)));
+        }
+        Ok(None)
     }
 
     pub fn hummock_meta_client(&self) -> &Arc<dyn HummockMetaClient> {
@@ -180,3 +197,116 @@ impl fmt::Debug for HummockStorage {
         todo!()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::key::key_with_epoch;
+    use super::*;
+    use crate::hummock::test_utils::{
+        default_builder_opt_for_test, gen_test_sstable, mock_hummock_storage, test_value_of,
+    };
+
+    async fn get_from_sst(
+        storage: &HummockStorage,
+        sst_id: HummockSSTableId,
+        key: &[u8],
+        epoch: u64,
+    ) -> Option<Option<Bytes>> {
+        let mut stats = StoreLocalStatistic::default();
+        let table = storage
+            .sstable_store
+            .sstable(sst_id, &mut stats)
+            .await
+            .unwrap();
+        let internal_key = key_with_epoch(key.to_vec(), epoch);
+        storage
+            .get_from_table(table, &internal_key, key, &mut stats)
+            .await
+            .unwrap()
+    }
+
+    fn key_of(idx: usize) -> Vec<u8> {
+        format!("key_{:05}", idx).into_bytes()
+    }
+
+    #[tokio::test]
+    async fn test_get_from_table_across_blocks() {
+        let storage = mock_hummock_storage().await;
+        let opts = SSTableBuilderOptions {
+            block_capacity: 256,
+            ..default_builder_opt_for_test()
+        };
+        // Only the even keys are in the SST, so each missing odd key sits between two entries,
+        // including the last entry of a block and the first entry of the next block.
+        let sst = gen_test_sstable(
+            opts,
+            1,
+            (0..500).map(|i| {
+                (
+                    key_with_epoch(key_of(i * 2), 1),
+                    HummockValue::put(test_value_of(i)),
+                )
+            }),
+            storage.sstable_store(),
+        )
+        .await;
+        assert!(sst.block_count() > 10);
+
+        for block_meta in &sst.meta.block_metas {
+            let key = user_key(&block_meta.smallest_key);
+            assert!(get_from_sst(&storage, 1, key, 1).await.is_some());
+        }
+        for i in 0..500 {
+            assert_eq!(
+                get_from_sst(&storage, 1, &key_of(i * 2), 1).await,
+                Some(Some(Bytes::from(test_value_of(i))))
+            );
+            assert_eq!(get_from_sst(&storage, 1, &key_of(i * 2 + 1), 1).await, None);
+        }
+        // Keys out of the key range of the SST.
+        assert_eq!(get_from_sst(&storage, 1, b"key", 1).await, None);
+        assert_eq!(get_from_sst(&storage, 1, b"key_99999", 1).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_get_from_table_multiple_versions() {
+        let storage = mock_hummock_storage().await;
+        // The versions of a key are ordered by epoch descendingly. With a block capacity of 1, each
+        // version is in its own block.
+        let options = [
+            default_builder_opt_for_test(),
+            SSTableBuilderOptions {
+                block_capacity: 1,
+                ..default_builder_opt_for_test()
+            },
+        ];
+        for (sst_id, opts) in (1..).zip(options) {
+            let kvs = vec![
+                (b"a".to_vec(), 1, HummockValue::put(b"a1".to_vec())),
+                (b"b".to_vec(), 4, HummockValue::delete()),
+                (b"b".to_vec(), 3, HummockValue::put(b"b3".to_vec())),
+                (b"b".to_vec(), 2, HummockValue::put(b"b2".to_vec())),
+                (b"c".to_vec(), 2, HummockValue::put(b"c2".to_vec())),
+            ];
+            gen_test_sstable(
+                opts,
+                sst_id,
+                kvs.into_iter()
+                    .map(|(key, epoch, value)| (key_with_epoch(key, epoch), value)),
+                storage.sstable_store(),
+            )
+            .await;
+
+            let get = |key: &'static [u8], epoch| get_from_sst(&storage, sst_id, key, epoch);
+            assert_eq!(get(b"a", 5).await, Some(Some(Bytes::from("a1"))));
+            // The deletion at epoch 4 hides the older versions.
+            assert_eq!(get(b"b", 5).await, Some(None));
+            assert_eq!(get(b"b", 4).await, Some(None));
+            assert_eq!(get(b"b", 3).await, Some(Some(Bytes::from("b3"))));
+            assert_eq!(get(b"b", 2).await, Some(Some(Bytes::from("b2"))));
+            // No version is visible at epoch 1, and the seek lands on the next key.
+            assert_eq!(get(b"b", 1).await, None);
+            assert_eq!(get(b"c", 1).await, None);
+        }
+    }
+}
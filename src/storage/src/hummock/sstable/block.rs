@@ -17,26 +17,38 @@ use std::io::{Read, Write};
 use std::ops::Range;
 
 use bytes::{Buf, BufMut, Bytes, BytesMut};
+use lz4;
+use prost::encoding::{decode_varint, encode_varint, encoded_len_varint};
 use risingwave_hummock_sdk::VersionedComparator;
-use {lz4, zstd};
+use zstd;
 
 use super::utils::{bytes_diff, xxhash64_verify, CompressionAlgorithm};
+use super::VERSION;
 use crate::hummock::sstable::utils::xxhash64_checksum;
 use crate::hummock::{HummockError, HummockResult};
 
 pub const DEFAULT_BLOCK_SIZE: usize = 4 * 1024;
 pub const DEFAULT_RESTART_INTERVAL: usize = 16;
 pub const DEFAULT_ENTRY_SIZE: usize = 16;
+pub const DEFAULT_COMPRESSION_LEVEL: u32 = 4;
 
 pub struct Block {
     /// Uncompressed entries data.
     data: Bytes,
     /// Restart points.
     restart_points: Vec<u32>,
+    /// Format version of the SST that the block belongs to.
+    version: u32,
 }
 
 impl Block {
+    /// Decodes a block of the current format version.
     pub fn decode(buf: Bytes) -> HummockResult<Self> {
+        Self::decode_with_version(buf, VERSION)
+    }
+
+    /// Decodes a block of the SST of format `version`.
+    pub fn decode_with_version(buf: Bytes, version: u32) -> HummockResult<Self> {
         // Verify checksum.
         let xxhash64_checksum = (&buf[buf.len() - 8..]).get_u64_le();
         xxhash64_verify(&buf[..buf.len() - 8], xxhash64_checksum)?;
@@ -78,6 +90,7 @@ impl Block {
         Ok(Block {
             data: buf.slice(..data_len),
             restart_points,
+            version,
         })
     }
 
@@ -117,6 +130,10 @@ impl Block {
     pub fn data(&self) -> &Bytes {
         &self.data
     }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
 }
 
 /// [`KeyPrefix`] contains info for prefix compression.
@@ -127,30 +144,61 @@ pub struct KeyPrefix {
     value: usize,
     /// Used for calculating range, won't be encoded.
     offset: usize,
+    /// Encoded length, won't be encoded.
+    len: usize,
 }
 
 impl KeyPrefix {
-    pub fn encode(&self, buf: &mut impl BufMut) {
-        buf.put_u16(self.overlap as u16);
-        buf.put_u16(self.diff as u16);
-        buf.put_u32(self.value as u32);
-    }
-
-    pub fn decode(buf: &mut impl Buf, offset: usize) -> Self {
-        let overlap = buf.get_u16() as usize;
-        let diff = buf.get_u16() as usize;
-        let value = buf.get_u32() as usize;
+    fn new(overlap: usize, diff: usize, value: usize, offset: usize) -> Self {
+        let len = encoded_len_varint(overlap as u64)
+            + encoded_len_varint(diff as u64)
+            + encoded_len_varint(value as u64);
         Self {
             overlap,
             diff,
             value,
             offset,
+            len,
         }
     }
 
+    /// Lengths are encoded as varints, so that an entry of a short diff key and value only takes
+    /// 3 bytes of header.
+    pub fn encode(&self, buf: &mut impl BufMut) {
+        encode_varint(self.overlap as u64, buf);
+        encode_varint(self.diff as u64, buf);
+        encode_varint(self.value as u64, buf);
+    }
+
+    /// Decodes the prefix of a block of format `version`.
+    ///
+    /// # Panics
+    ///
+    /// Panic if the prefix is corrupted, which should have been caught by the block checksum.
+    pub fn decode(buf: &mut impl Buf, offset: usize, version: u32) -> Self {
+        if version == 1 {
+            // Version 1 encodes the lengths in fixed width.
+            let overlap = buf.get_u16() as usize;
+            let diff = buf.get_u16() as usize;
+            let value = buf.get_u32() as usize;
+            return Self {
+                overlap,
+                diff,
+                value,
+                offset,
+                len: 2 + 2 + 4,
+            };
+        }
+        let mut decode = || decode_varint(buf).expect("corrupted key prefix") as usize;
+        let overlap = decode();
+        let diff = decode();
+        let value = decode();
+        Self::new(overlap, diff, value, offset)
+    }
+
     /// Encoded length.
     fn len(&self) -> usize {
-        2 + 2 + 4
+        self.len
     }
 
     /// Gets overlap len.
@@ -179,6 +227,8 @@ pub struct BlockBuilderOptions {
     pub capacity: usize,
     /// Compression algorithm.
     pub compression_algorithm: CompressionAlgorithm,
+    /// Compression level of the algorithm. 0 means [`DEFAULT_COMPRESSION_LEVEL`].
+    pub compression_level: u32,
     /// Restart point interval.
     pub restart_interval: usize,
 }
@@ -188,6 +238,7 @@ impl Default for BlockBuilderOptions {
         Self {
             capacity: DEFAULT_BLOCK_SIZE,
             compression_algorithm: CompressionAlgorithm::None,
+            compression_level: 0,
             restart_interval: DEFAULT_RESTART_INTERVAL,
         }
     }
@@ -207,6 +258,8 @@ pub struct BlockBuilder {
     entry_count: usize,
     /// Compression algorithm.
     compression_algorithm: CompressionAlgorithm,
+    /// Compression level.
+    compression_level: u32,
 }
 
 impl BlockBuilder {
@@ -220,6 +273,7 @@ impl BlockBuilder {
             last_key: vec![],
             entry_count: 0,
             compression_algorithm: options.compression_algorithm,
+            compression_level: options.compression_level,
         }
     }
 
@@ -230,7 +284,7 @@ impl BlockBuilder {
     /// # Format
     ///
     /// ```plain
-    /// entry (kv pair): | overlap len (2B) | diff len (2B) | value len(4B) | diff key | value |
+    /// entry (kv pair): | overlap len (var) | diff len (var) | value len (var) | diff key | value |
     /// ```
     ///
     /// # Panics
@@ -253,12 +307,12 @@ impl BlockBuilder {
             bytes_diff(&self.last_key, key)
         };
 
-        let prefix = KeyPrefix {
-            overlap: key.len() - diff_key.len(),
-            diff: diff_key.len(),
-            value: value.len(),
-            offset: self.buf.len(),
-        };
+        let prefix = KeyPrefix::new(
+            key.len() - diff_key.len(),
+            diff_key.len(),
+            value.len(),
+            self.buf.len(),
+        );
 
         prefix.encode(&mut self.buf);
         self.buf.put_slice(diff_key);
@@ -286,11 +340,15 @@ impl BlockBuilder {
             self.buf.put_u32_le(*restart_point);
         }
         self.buf.put_u32_le(self.restart_points.len() as u32);
+        let level = match self.compression_level {
+            0 => DEFAULT_COMPRESSION_LEVEL,
+            level => level,
+        };
         let mut buf = match self.compression_algorithm {
             CompressionAlgorithm::None => self.buf,
             CompressionAlgorithm::Lz4 => {
                 let mut encoder = lz4::EncoderBuilder::new()
-                    .level(4)
+                    .level(level)
                     .build(BytesMut::with_capacity(self.buf.len()).writer())
                     .map_err(HummockError::encode_error)
                     .unwrap();
@@ -303,10 +361,12 @@ impl BlockBuilder {
                 writer.into_inner()
             }
             CompressionAlgorithm::Zstd => {
-                let mut encoder =
-                    zstd::Encoder::new(BytesMut::with_capacity(self.buf.len()).writer(), 4)
-                        .map_err(HummockError::encode_error)
-                        .unwrap();
+                let mut encoder = zstd::Encoder::new(
+                    BytesMut::with_capacity(self.buf.len()).writer(),
+                    level as i32,
+                )
+                .map_err(HummockError::encode_error)
+                .unwrap();
                 encoder
                     .write_all(&self.buf[..])
                     .map_err(HummockError::encode_error)
@@ -332,6 +392,8 @@ impl BlockBuilder {
 
 #[cfg(test)]
 mod tests {
+    use itertools::Itertools;
+
     use super::*;
     use crate::hummock::{BlockHolder, BlockIterator};
 
@@ -371,15 +433,101 @@ mod tests {
         assert!(!bi.is_valid());
     }
 
+    #[test]
+    fn test_key_prefix_enc_dec() {
+        let prefix = KeyPrefix::new(300, 5, 1 << 20, 10);
+        let mut buf = BytesMut::new();
+        prefix.encode(&mut buf);
+        assert_eq!(buf.len(), prefix.len());
+        assert_eq!(buf.len(), 2 + 1 + 3);
+
+        let decoded = KeyPrefix::decode(&mut &buf[..], 10, VERSION);
+        assert_eq!(decoded.overlap_len(), 300);
+        assert_eq!(decoded.diff_key_range(), 16..21);
+        assert_eq!(decoded.value_range(), 21..21 + (1 << 20));
+    }
+
+    #[test]
+    fn test_block_common_prefix() {
+        // Keys of the same table and vnode share a long prefix.
+        let prefix = [b't', 0, 0, 0, 1, 0, 42, 0, 0, 0, 0, 0, 0, 0, 0];
+        let keys = (0..64u16)
+            .map(|i| {
+                let mut user_key = prefix.to_vec();
+                user_key.extend_from_slice(&i.to_be_bytes());
+                full_key(&user_key, 1)
+            })
+            .collect_vec();
+
+        let mut builder = BlockBuilder::new(BlockBuilderOptions::default());
+        for key in &keys {
+            builder.add(key, b"v");
+        }
+        let buf = builder.build();
+        let raw_len: usize = keys.iter().map(|key| key.len() + 1).sum();
+        assert!(buf.len() < raw_len * 2 / 3);
+
+        let block = Box::new(Block::decode(buf).unwrap());
+        let mut bi = BlockIterator::new(BlockHolder::from_owned_block(block));
+        for key in &keys {
+            bi.seek(key);
+            assert!(bi.is_valid());
+            assert_eq!(&key[..], bi.key());
+        }
+        bi.seek_to_first();
+        for key in &keys {
+            assert_eq!(&key[..], bi.key());
+            bi.next();
+        }
+        assert!(!bi.is_valid());
+    }
+
+    #[test]
+    fn test_decode_version_1_block() {
+        // | overlap len (2B) | diff len (2B) | value len (4B) | diff key | value |
+        let k1 = full_key(b"k1", 1);
+        let k2 = full_key(b"k2", 2);
+        let mut buf = BytesMut::new();
+        for (overlap, diff_key, value) in [(0, &k1[..], b"v01"), (1, &k2[1..], b"v02")] {
+            buf.put_u16(overlap);
+            buf.put_u16(diff_key.len() as u16);
+            buf.put_u32(value.len() as u32);
+            buf.put_slice(diff_key);
+            buf.put_slice(value);
+        }
+        buf.put_u32_le(0);
+        buf.put_u32_le(1);
+        CompressionAlgorithm::None.encode(&mut buf);
+        let checksum = xxhash64_checksum(&buf);
+        buf.put_u64_le(checksum);
+
+        let block = Box::new(Block::decode_with_version(buf.freeze(), 1).unwrap());
+        let mut bi = BlockIterator::new(BlockHolder::from_owned_block(block));
+        bi.seek_to_first();
+        assert_eq!(&k1[..], bi.key());
+        assert_eq!(b"v01", bi.value());
+        bi.next();
+        assert_eq!(&k2[..], bi.key());
+        assert_eq!(b"v02", bi.value());
+        bi.next();
+        assert!(!bi.is_valid());
+
+        bi.seek(&k2);
+        assert_eq!(&k2[..], bi.key());
+    }
+
     #[test]
     fn test_compressed_block_enc_dec() {
-        inner_test_compressed(CompressionAlgorithm::Lz4);
-        inner_test_compressed(CompressionAlgorithm::Zstd);
+        inner_test_compressed(CompressionAlgorithm::Lz4, 0);
+        inner_test_compressed(CompressionAlgorithm::Lz4, 12);
+        inner_test_compressed(CompressionAlgorithm::Zstd, 0);
+        inner_test_compressed(CompressionAlgorithm::Zstd, 19);
     }
 
-    fn inner_test_compressed(algo: CompressionAlgorithm) {
+    fn inner_test_compressed(algo: CompressionAlgorithm, level: u32) {
         let options = BlockBuilderOptions {
             compression_algorithm: algo,
+            compression_level: level,
             ..Default::default()
         };
         let mut builder = BlockBuilder::new(options);
@@ -174,7 +174,11 @@ impl BlockIterator {
 
     /// Decodes [`KeyPrefix`] at given offset.
     fn decode_prefix_at(&self, offset: usize) -> KeyPrefix {
-        KeyPrefix::decode(&mut &self.block.data()[offset..], offset)
+        KeyPrefix::decode(
+            &mut &self.block.data()[offset..],
+            offset,
+            self.block.version(),
+        )
     }
 
     /// Searches the restart point index that the given `key` belongs to.
@@ -41,6 +41,8 @@ pub struct SSTableBuilderOptions {
     pub bloom_false_positive: f64,
     /// Compression algorithm.
     pub compression_algorithm: CompressionAlgorithm,
+    /// Compression level of the algorithm. 0 means the default level.
+    pub compression_level: u32,
 }
 
 impl From<&StorageConfig> for SSTableBuilderOptions {
@@ -51,6 +53,7 @@ impl From<&StorageConfig> for SSTableBuilderOptions {
             restart_interval: DEFAULT_RESTART_INTERVAL,
             bloom_false_positive: options.bloom_false_positive,
             compression_algorithm: CompressionAlgorithm::None,
+            compression_level: 0,
         }
     }
 }
@@ -63,6 +66,7 @@ impl Default for SSTableBuilderOptions {
             restart_interval: DEFAULT_RESTART_INTERVAL,
             bloom_false_positive: DEFAULT_BLOOM_FALSE_POSITIVE,
             compression_algorithm: CompressionAlgorithm::None,
+            compression_level: 0,
         }
     }
 }
@@ -110,6 +114,7 @@ impl SSTableBuilder {
                 capacity: self.options.block_capacity,
                 restart_interval: self.options.restart_interval,
                 compression_algorithm: self.options.compression_algorithm,
+                compression_level: self.options.compression_level,
             }));
             self.block_metas.push(BlockMeta {
                 offset: self.buf.len() as u32,
@@ -235,6 +240,7 @@ pub(super) mod tests {
             restart_interval: 16,
             bloom_false_positive: 0.1,
             compression_algorithm: CompressionAlgorithm::None,
+            compression_level: 0,
         };
 
         let b = SSTableBuilder::new(0, opt);
@@ -265,6 +271,7 @@ pub(super) mod tests {
             restart_interval: 16,
             bloom_false_positive: if with_blooms { 0.01 } else { 0.0 },
             compression_algorithm: CompressionAlgorithm::None,
+            compression_level: 0,
         };
 
         // build remote table
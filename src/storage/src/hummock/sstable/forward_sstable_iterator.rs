@@ -39,6 +39,9 @@ pub struct SSTableIterator {
     /// Current block index.
     cur_idx: usize,
 
+    /// Blocks before this index have been prefetched.
+    prefetched_until: usize,
+
     /// Reference to the sst
     pub sst: TableHolder,
 
@@ -56,6 +59,7 @@ impl SSTableIterator {
         Self {
             block_iter: None,
             cur_idx: 0,
+            prefetched_until: 0,
             sst: table,
             sstable_store,
             stats: StoreLocalStatistic::default(),
@@ -63,6 +67,22 @@ impl SSTableIterator {
         }
     }
 
+    /// Prefetches the blocks after `idx` which is going to be read, so that at least half of the
+    /// prefetch window is ahead of the scan.
+    fn prefetch_ahead(&mut self, idx: usize) {
+        let count = self.options.prefetch_block_count;
+        if count == 0 || idx + count / 2 < self.prefetched_until {
+            return;
+        }
+        let start = std::cmp::max(idx + 1, self.prefetched_until);
+        let end = std::cmp::min(idx + 1 + count, self.sst.value().block_count());
+        if start < end {
+            self.sstable_store
+                .prefetch_blocks(self.sst.value(), start, end);
+        }
+        self.prefetched_until = end;
+    }
+
     /// Seeks to a block, and then seeks to the key if `seek_key` is given.
     async fn seek_idx(&mut self, idx: usize, seek_key: Option<&[u8]>) -> HummockResult<()> {
         tracing::trace!(
@@ -122,6 +142,7 @@ impl HummockIterator for SSTableIterator {
             Ok(())
         } else {
             // seek to next block
+            self.prefetch_ahead(self.cur_idx + 1);
             self.seek_idx(self.cur_idx + 1, None).await
         }
     }
@@ -141,6 +162,7 @@ impl HummockIterator for SSTableIterator {
     }
 
     async fn rewind(&mut self) -> HummockResult<()> {
+        self.prefetched_until = 0;
         self.seek_idx(0, None).await
     }
 
@@ -159,6 +181,7 @@ impl HummockIterator for SSTableIterator {
             })
             .saturating_sub(1); // considering the boundary of 0
 
+        self.prefetched_until = 0;
         self.seek_idx(block_idx, Some(key)).await?;
         if !self.is_valid() {
             // seek to next block
@@ -317,7 +340,10 @@ mod tests {
         let mut sstable_iter = SSTableIterator::create(
             block_on(sstable_store.sstable(table.id, &mut stats)).unwrap(),
             sstable_store,
-            Arc::new(ReadOptions { prefetch: true }),
+            Arc::new(ReadOptions {
+                prefetch: true,
+                ..Default::default()
+            }),
         );
         let mut cnt = 0;
         sstable_iter.rewind().await.unwrap();
@@ -331,4 +357,37 @@ mod tests {
         }
         assert_eq!(cnt, TEST_KEYS_COUNT);
     }
+
+    #[tokio::test]
+    async fn test_prefetch_ahead_read() {
+        let sstable_store = mock_sstable_store();
+        let kv_iter =
+            (0..TEST_KEYS_COUNT).map(|i| (test_key_of(i), HummockValue::put(test_value_of(i))));
+        let (data, meta, _) = gen_test_sstable_data(default_builder_opt_for_test(), kv_iter);
+        let table = Sstable { id: 0, meta };
+        assert!(table.block_count() > 4);
+        sstable_store
+            .put(table.clone(), data, CachePolicy::NotFill)
+            .await
+            .unwrap();
+
+        let mut stats = StoreLocalStatistic::default();
+        let mut sstable_iter = SSTableIterator::create(
+            sstable_store.sstable(table.id, &mut stats).await.unwrap(),
+            sstable_store,
+            Arc::new(ReadOptions {
+                prefetch_block_count: 4,
+                ..Default::default()
+            }),
+        );
+        let mut cnt = 0;
+        sstable_iter.rewind().await.unwrap();
+        while sstable_iter.is_valid() {
+            assert_bytes_eq!(sstable_iter.key(), test_key_of(cnt));
+            cnt += 1;
+            sstable_iter.next().await.unwrap();
+        }
+        assert_eq!(cnt, TEST_KEYS_COUNT);
+        assert_eq!(sstable_iter.prefetched_until, table.block_count());
+    }
 }
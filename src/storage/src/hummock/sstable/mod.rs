@@ -45,7 +45,14 @@ use super::{HummockError, HummockResult};
 
 const DEFAULT_META_BUFFER_CAPACITY: usize = 4096;
 const MAGIC: u32 = 0x5785ab73;
-const VERSION: u32 = 1;
+/// Format version of the SSTs being built. History of versions:
+///
+/// - 1: lengths of block entries are encoded in fixed width.
+/// - 2: lengths of block entries are encoded as varints.
+pub const VERSION: u32 = 2;
+/// The earliest format version that can still be read. SSTs of older versions must be rewritten
+/// with `risectl hummock migrate` before upgrading.
+pub const MIN_SUPPORTED_VERSION: u32 = 1;
 
 #[derive(Clone, Debug)]
 /// [`Sstable`] is a handle for accessing SST.
@@ -175,7 +182,7 @@ impl SstableMeta {
         put_length_prefixed_slice(&mut buf, &self.largest_key);
         let checksum = xxhash64_checksum(&buf);
         buf.put_u64_le(checksum);
-        buf.put_u32_le(VERSION);
+        buf.put_u32_le(self.version);
         buf.put_u32_le(MAGIC);
         buf
     }
@@ -191,7 +198,7 @@ impl SstableMeta {
 
         cursor -= 4;
         let version = (&buf[cursor..cursor + 4]).get_u32_le();
-        if version != VERSION {
+        if !(MIN_SUPPORTED_VERSION..=VERSION).contains(&version) {
             return Err(HummockError::invalid_format_version(version));
         }
 
@@ -274,4 +281,26 @@ mod tests {
         let decoded_meta = SstableMeta::decode(&mut &buf[..]).unwrap();
         assert_eq!(decoded_meta, meta);
     }
+
+    #[test]
+    pub fn test_sstable_meta_version() {
+        let mut meta = SstableMeta {
+            block_metas: vec![],
+            bloom_filter: vec![],
+            estimated_size: 0,
+            key_count: 0,
+            smallest_key: vec![],
+            largest_key: vec![],
+            version: MIN_SUPPORTED_VERSION,
+        };
+        let buf = meta.encode_to_bytes();
+        let decoded_meta = SstableMeta::decode(&mut &buf[..]).unwrap();
+        assert_eq!(decoded_meta.version, MIN_SUPPORTED_VERSION);
+
+        for version in [MIN_SUPPORTED_VERSION - 1, VERSION + 1] {
+            meta.version = version;
+            let buf = meta.encode_to_bytes();
+            assert!(SstableMeta::decode(&mut &buf[..]).is_err());
+        }
+    }
 }
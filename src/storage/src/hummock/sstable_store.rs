@@ -15,11 +15,12 @@
 use std::clone::Clone;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
-use std::time::Instant;
 
 use bytes::Bytes;
 use fail::fail_point;
 use futures::future::{try_join_all, FutureExt};
+use itertools::Itertools;
+use madsim::time::Instant;
 use risingwave_hummock_sdk::{is_remote_sst_id, HummockSSTableId};
 use risingwave_object_store::object::{get_local_path, BlockLocation, ObjectStoreRef};
 use tokio::sync::oneshot::{channel, Sender};
@@ -141,6 +142,71 @@ impl SstableStore {
         }
     }
 
+    /// Fetches blocks `[start, end)` of `sst` with a single read in background, and fills them into
+    /// the block cache. Blocks already cached at both ends of the range are skipped.
+    pub fn prefetch_blocks(self: &Arc<Self>, sst: &Sstable, start: usize, end: usize) {
+        let end = std::cmp::min(end, sst.meta.block_metas.len());
+        let is_cached = |idx: usize| self.block_cache.get(sst.id, idx as u64).is_some();
+        let start = (start..end).find(|&idx| !is_cached(idx)).unwrap_or(end);
+        let end = (start..end)
+            .rev()
+            .find(|&idx| !is_cached(idx))
+            .map_or(start, |idx| idx + 1);
+        if start >= end {
+            return;
+        }
+
+        let block_metas = sst.meta.block_metas[start..end].to_vec();
+        let first_offset = block_metas[0].offset as usize;
+        let block_loc = BlockLocation {
+            offset: first_offset,
+            size: block_metas.iter().map(|meta| meta.len as usize).sum(),
+        };
+        let (sst_id, version) = (sst.id, sst.meta.version);
+        let this = self.clone();
+        tokio::spawn(async move {
+            let data_path = this.get_sst_data_path(sst_id);
+            let block_data = match this.store.read(&data_path, Some(block_loc)).await {
+                Ok(block_data) => block_data,
+                Err(e) => {
+                    tracing::warn!("failed to prefetch blocks of SST {}: {:?}", sst_id, e);
+                    return;
+                }
+            };
+            for (idx, block_meta) in (start..end).zip_eq(block_metas) {
+                let offset = block_meta.offset as usize - first_offset;
+                let block_data = block_data.slice(offset..offset + block_meta.len as usize);
+                match Block::decode_with_version(block_data, version) {
+                    Ok(block) => {
+                        this.block_cache.insert(sst_id, idx as u64, Box::new(block));
+                    }
+                    Err(e) => {
+                        tracing::warn!(
+                            "failed to decode prefetched block of SST {}: {:?}",
+                            sst_id,
+                            e
+                        );
+                        return;
+                    }
+                }
+            }
+        });
+    }
+
+    /// Loads the meta of SST `sst_id` and prefetches its first `block_count` blocks in background.
+    pub fn prefetch_sstable(self: &Arc<Self>, sst_id: HummockSSTableId, block_count: usize) {
+        let this = self.clone();
+        tokio::spawn(async move {
+            match this
+                .sstable(sst_id, &mut StoreLocalStatistic::default())
+                .await
+            {
+                Ok(sst) => this.prefetch_blocks(sst.value(), 0, block_count),
+                Err(e) => tracing::warn!("failed to prefetch SST {}: {:?}", sst_id, e),
+            }
+        });
+    }
+
     async fn put_meta(&self, sst: &Sstable) -> HummockResult<()> {
         let meta_path = self.get_sst_meta_path(sst.id);
         let meta = Bytes::from(sst.meta.encode_to_bytes());
@@ -204,7 +270,10 @@ impl SstableStore {
             .read(&data_path, Some(block_loc))
             .await
             .map_err(HummockError::object_io_error)?;
-        let block = Block::decode(block_data.slice(..block_meta.len as usize))?;
+        let block = Block::decode_with_version(
+            block_data.slice(..block_meta.len as usize),
+            sst.meta.version,
+        )?;
         let ret = self
             .block_cache
             .insert(sst.id, block_index as u64, Box::new(block));
@@ -213,7 +282,10 @@ impl SstableStore {
             let mut offset = block_meta.len as usize;
             for block_meta in &sst.meta.block_metas[(block_index + 1)..end_index] {
                 let end_offset = offset + block_meta.len as usize;
-                let block = Block::decode(block_data.slice(offset..end_offset))?;
+                let block = Block::decode_with_version(
+                    block_data.slice(offset..end_offset),
+                    sst.meta.version,
+                )?;
                 self.block_cache
                     .insert(sst.id, index_offset, Box::new(block));
                 offset = end_offset;
@@ -248,7 +320,7 @@ impl SstableStore {
                 .read(&data_path, Some(block_loc))
                 .await
                 .map_err(HummockError::object_io_error)?;
-            let block = Block::decode(block_data)?;
+            let block = Block::decode_with_version(block_data, sst.meta.version)?;
             Ok(Box::new(block))
         };
 
@@ -368,6 +440,12 @@ impl SstableStore {
         self.block_cache.clone()
     }
 
+    /// Resizes the block cache and the meta cache, e.g. when changed by `ALTER SYSTEM`.
+    pub fn set_cache_capacity(&self, block_cache_capacity: usize, meta_cache_capacity: usize) {
+        self.block_cache.set_capacity(block_cache_capacity);
+        self.meta_cache.set_capacity(meta_cache_capacity);
+    }
+
     #[cfg(test)]
     pub fn clear_block_cache(&self) {
         self.block_cache.clear();
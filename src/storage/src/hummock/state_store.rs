@@ -80,7 +80,10 @@ impl HummockStorage {
         B: AsRef<[u8]> + Send,
         T: HummockIteratorType,
     {
-        let read_options = Arc::new(ReadOptions::default());
+        let read_options = Arc::new(ReadOptions {
+            prefetch_block_count: self.options.scan_prefetch_block_count,
+            ..Default::default()
+        });
         let mut overlapped_iters = vec![];
 
         let (shared_buffer_data, pinned_version) = self.read_filter(epoch, &key_range)?;
@@ -198,8 +201,6 @@ impl HummockStorage {
 
         let mut table_counts = 0;
         let internal_key = key_with_epoch(key.to_vec(), epoch);
-        // TODO: may want to avoid use Arc in read options
-        let read_options = Arc::new(ReadOptions::default());
 
         // Query shared buffer. Return the value without iterating SSTs if found
         for (replicated_batches, uncommitted_data) in shared_buffer_data {
@@ -224,13 +225,7 @@ impl HummockStorage {
                                 .await?;
                             table_counts += 1;
                             if let Some(v) = self
-                                .get_from_table(
-                                    table,
-                                    &internal_key,
-                                    key,
-                                    read_options.clone(),
-                                    &mut stats,
-                                )
+                                .get_from_table(table, &internal_key, key, &mut stats)
                                 .await?
                             {
                                 return Ok(v);
@@ -254,7 +249,7 @@ impl HummockStorage {
                         .await?;
                     table_counts += 1;
                     if let Some(v) = self
-                        .get_from_table(table, &internal_key, key, read_options.clone(), &mut stats)
+                        .get_from_table(table, &internal_key, key, &mut stats)
                         .await?
                     {
                         return Ok(v);
@@ -53,6 +53,9 @@ pub fn default_config_for_test() -> StorageConfig {
         enable_local_spill: false,
         local_object_store: "memory".to_string(),
         share_buffer_upload_concurrency: 1,
+        shared_buffer_compression_algorithm: "None".to_string(),
+        shared_buffer_compression_level: 0,
+        scan_prefetch_block_count: 0,
     }
 }
 
@@ -115,6 +118,7 @@ pub fn default_builder_opt_for_test() -> SSTableBuilderOptions {
         restart_interval: DEFAULT_RESTART_INTERVAL,
         bloom_false_positive: 0.1,
         compression_algorithm: CompressionAlgorithm::None,
+        compression_level: 0,
     }
 }
 
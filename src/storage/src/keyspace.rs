@@ -154,6 +154,30 @@ impl<S: StateStore> Keyspace<S> {
         Ok(strip_prefix_iterator)
     }
 
+    /// Gets a backward iterator with the given `range` in this keyspace, which yields keys from
+    /// the end of the range to its start.
+    /// The returned iterator will iterate data from a snapshot corresponding to the given `epoch`.
+    ///
+    /// **Note**: the `range` should not be prepended with the prefix of this keyspace. Unlike
+    /// [`StateStore::backward_iter`], the `range` is given in ascending order.
+    pub async fn backward_iter_with_range<R, B>(
+        &self,
+        range: R,
+        epoch: u64,
+    ) -> StorageResult<StripPrefixIterator<S::Iter>>
+    where
+        R: RangeBounds<B> + Send,
+        B: AsRef<[u8]> + Send,
+    {
+        let (start, end) = prefixed_range(range, &self.prefix);
+        let iter = self.store.backward_iter((end, start), epoch).await?;
+        let strip_prefix_iterator = StripPrefixIterator {
+            iter,
+            prefix_len: self.prefix.len(),
+        };
+        Ok(strip_prefix_iterator)
+    }
+
     /// Gets the underlying state store.
     pub fn state_store(&self) -> S {
         self.store.clone()
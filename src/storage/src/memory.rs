@@ -161,15 +161,28 @@ impl StateStore for MemoryStateStore {
 
     fn backward_scan<R, B>(
         &self,
-        _key_range: R,
-        _limit: Option<usize>,
-        _epoch: u64,
+        key_range: R,
+        limit: Option<usize>,
+        epoch: u64,
     ) -> Self::BackwardScanFuture<'_, R, B>
     where
         R: RangeBounds<B> + Send,
         B: AsRef<[u8]> + Send,
     {
-        async move { unimplemented!() }
+        async move {
+            // The memory state store is only used in tests, so simply reverse the forward scan.
+            // Note that the `key_range` of a backward scan starts from the larger key.
+            let key_range = (
+                key_range.end_bound().map(|v| v.as_ref().to_vec()),
+                key_range.start_bound().map(|v| v.as_ref().to_vec()),
+            );
+            let mut data = self.scan(key_range, None, epoch).await?;
+            data.reverse();
+            if let Some(limit) = limit {
+                data.truncate(limit);
+            }
+            Ok(data)
+        }
     }
 
     fn ingest_batch(
@@ -208,12 +221,19 @@ impl StateStore for MemoryStateStore {
         }
     }
 
-    fn backward_iter<R, B>(&self, _key_range: R, _epoch: u64) -> Self::BackwardIterFuture<'_, R, B>
+    fn backward_iter<R, B>(&self, key_range: R, epoch: u64) -> Self::BackwardIterFuture<'_, R, B>
     where
         R: RangeBounds<B> + Send,
         B: AsRef<[u8]> + Send,
     {
-        async move { unimplemented!() }
+        async move {
+            Ok(MemoryStateStoreIter::new(
+                self.backward_scan(key_range, None, epoch)
+                    .await
+                    .unwrap()
+                    .into_iter(),
+            ))
+        }
     }
 
     fn wait_epoch(&self, _epoch: u64) -> Self::WaitEpochFuture<'_> {
@@ -322,4 +342,58 @@ mod tests {
         assert_eq!(state_store.get(b"b", 1).await.unwrap(), None);
         assert_eq!(state_store.get(b"c", 1).await.unwrap(), None);
     }
+
+    #[tokio::test]
+    async fn test_backward_scan() {
+        let state_store = MemoryStateStore::new();
+        state_store
+            .ingest_batch(
+                vec![
+                    (
+                        b"a".to_vec().into(),
+                        StorageValue::new_default_put(b"v1".to_vec()),
+                    ),
+                    (
+                        b"b".to_vec().into(),
+                        StorageValue::new_default_put(b"v1".to_vec()),
+                    ),
+                    (
+                        b"c".to_vec().into(),
+                        StorageValue::new_default_put(b"v1".to_vec()),
+                    ),
+                ],
+                0,
+            )
+            .await
+            .unwrap();
+        state_store
+            .ingest_batch(
+                vec![(b"b".to_vec().into(), StorageValue::new_default_delete())],
+                1,
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            state_store.backward_scan("c"..="a", None, 0).await.unwrap(),
+            vec![
+                (b"c".to_vec().into(), b"v1".to_vec().into()),
+                (b"b".to_vec().into(), b"v1".to_vec().into()),
+                (b"a".to_vec().into(), b"v1".to_vec().into())
+            ]
+        );
+        assert_eq!(
+            state_store
+                .backward_scan("c"..="a", Some(1), 1)
+                .await
+                .unwrap(),
+            vec![(b"c".to_vec().into(), b"v1".to_vec().into())]
+        );
+
+        let mut iter = state_store.backward_iter("c".."a", 1).await.unwrap();
+        assert_eq!(
+            iter.next().await.unwrap(),
+            Some((b"c".to_vec().into(), b"v1".to_vec().into()))
+        );
+        assert_eq!(iter.next().await.unwrap(), None);
+    }
 }
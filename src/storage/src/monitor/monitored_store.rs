@@ -14,10 +14,10 @@
 
 use std::ops::RangeBounds;
 use std::sync::Arc;
-use std::time::Instant;
 
 use bytes::Bytes;
 use futures::Future;
+use madsim::time::Instant;
 use risingwave_hummock_sdk::LocalSstableInfo;
 use tracing::error;
 
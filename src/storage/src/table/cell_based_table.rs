@@ -375,16 +375,19 @@ impl<S: StateStore> CellBasedTable<S> {
             self.mapping.clone(),
             encoded_key_range,
             epoch,
+            false,
         )
         .await?
         .into_stream())
     }
 
-    /// Get a [`BatchIter`] with given `encoded_key_range`.
+    /// Get a [`BatchIter`] with given `encoded_key_range`. If `reverse` is set, rows are yielded
+    /// in descending order of the primary key.
     pub(super) async fn batch_iter_with_encoded_key_range<R, B>(
         &self,
         encoded_key_range: R,
         epoch: u64,
+        reverse: bool,
     ) -> StorageResult<BatchIter<S>>
     where
         R: RangeBounds<B> + Send,
@@ -395,6 +398,7 @@ impl<S: StateStore> CellBasedTable<S> {
             self.mapping.clone(),
             encoded_key_range,
             epoch,
+            reverse,
         )
         .await?
         .into_stream())
@@ -402,7 +406,13 @@ impl<S: StateStore> CellBasedTable<S> {
 
     // The returned iterator will iterate data from a snapshot corresponding to the given `epoch`
     pub async fn batch_iter(&self, epoch: u64) -> StorageResult<BatchIter<S>> {
-        self.batch_iter_with_encoded_key_range::<_, &[u8]>(.., epoch)
+        self.batch_iter_with_encoded_key_range::<_, &[u8]>(.., epoch, false)
+            .await
+    }
+
+    /// The same as [`Self::batch_iter`], but yields rows in descending order of the primary key.
+    pub async fn batch_backward_iter(&self, epoch: u64) -> StorageResult<BatchIter<S>> {
+        self.batch_iter_with_encoded_key_range::<_, &[u8]>(.., epoch, true)
             .await
     }
 
@@ -424,12 +434,51 @@ impl<S: StateStore> CellBasedTable<S> {
         .into_stream())
     }
 
+    /// The same as [`Self::batch_dedup_pk_iter`], but yields rows in descending order of the
+    /// primary key.
+    pub async fn batch_backward_dedup_pk_iter(
+        &self,
+        epoch: u64,
+        pk_descs: &[OrderedColumnDesc],
+    ) -> StorageResult<BatchDedupPkIter<S>> {
+        Ok(DedupPkCellBasedIter::new(
+            self.batch_backward_iter(epoch).await?,
+            self.mapping.clone(),
+            pk_descs,
+        )
+        .await?
+        .into_stream())
+    }
+
     pub async fn batch_iter_with_pk_bounds(
         &self,
         epoch: u64,
         pk_prefix: Row,
         next_col_bounds: impl RangeBounds<Datum>,
     ) -> StorageResult<BatchIter<S>> {
+        let key_range = self.pk_bounds_to_encoded_key_range(&pk_prefix, next_col_bounds);
+        self.batch_iter_with_encoded_key_range(key_range, epoch, false)
+            .await
+    }
+
+    /// The same as [`Self::batch_iter_with_pk_bounds`], but yields rows in descending order of the
+    /// primary key.
+    pub async fn batch_backward_iter_with_pk_bounds(
+        &self,
+        epoch: u64,
+        pk_prefix: Row,
+        next_col_bounds: impl RangeBounds<Datum>,
+    ) -> StorageResult<BatchIter<S>> {
+        let key_range = self.pk_bounds_to_encoded_key_range(&pk_prefix, next_col_bounds);
+        self.batch_iter_with_encoded_key_range(key_range, epoch, true)
+            .await
+    }
+
+    fn pk_bounds_to_encoded_key_range(
+        &self,
+        pk_prefix: &Row,
+        next_col_bounds: impl RangeBounds<Datum>,
+    ) -> (Bound<Vec<u8>>, Bound<Vec<u8>>) {
         fn serialize_pk_bound(
             pk_serializer: &OrderedRowSerializer,
             pk_prefix: &Row,
@@ -479,13 +528,13 @@ impl<S: StateStore> CellBasedTable<S> {
 
         let start_key = serialize_pk_bound(
             &self.pk_serializer,
-            &pk_prefix,
+            pk_prefix,
             next_col_bounds.start_bound(),
             true,
         );
         let end_key = serialize_pk_bound(
             &self.pk_serializer,
-            &pk_prefix,
+            pk_prefix,
             next_col_bounds.end_bound(),
             false,
         );
@@ -496,8 +545,7 @@ impl<S: StateStore> CellBasedTable<S> {
             end_key
         );
 
-        self.batch_iter_with_encoded_key_range((start_key, end_key), epoch)
-            .await
+        (start_key, end_key)
     }
 
     pub async fn batch_iter_with_pk_prefix(
@@ -505,8 +553,26 @@ impl<S: StateStore> CellBasedTable<S> {
         epoch: u64,
         pk_prefix: Row,
     ) -> StorageResult<BatchIter<S>> {
+        let key_range = self.pk_prefix_to_encoded_key_range(&pk_prefix);
+        self.batch_iter_with_encoded_key_range(key_range, epoch, false)
+            .await
+    }
+
+    /// The same as [`Self::batch_iter_with_pk_prefix`], but yields rows in descending order of the
+    /// primary key.
+    pub async fn batch_backward_iter_with_pk_prefix(
+        &self,
+        epoch: u64,
+        pk_prefix: Row,
+    ) -> StorageResult<BatchIter<S>> {
+        let key_range = self.pk_prefix_to_encoded_key_range(&pk_prefix);
+        self.batch_iter_with_encoded_key_range(key_range, epoch, true)
+            .await
+    }
+
+    fn pk_prefix_to_encoded_key_range(&self, pk_prefix: &Row) -> (Bound<Vec<u8>>, Bound<Vec<u8>>) {
         let prefix_serializer = self.pk_serializer.prefix(pk_prefix.size());
-        let serialized_pk_prefix = serialize_pk(&pk_prefix, &prefix_serializer);
+        let serialized_pk_prefix = serialize_pk(pk_prefix, &prefix_serializer);
 
         let key_range = range_of_prefix(&serialized_pk_prefix);
 
@@ -515,8 +581,7 @@ impl<S: StateStore> CellBasedTable<S> {
             (key_range.start_bound(), key_range.end_bound())
         );
 
-        self.batch_iter_with_encoded_key_range(key_range, epoch)
-            .await
+        key_range
     }
 }
 
@@ -539,6 +604,7 @@ impl<S: StateStore, const ITER_TYPE: bool> CellBasedIter<S, ITER_TYPE> {
         table_descs: Arc<ColumnDescMapping>,
         encoded_key_range: R,
         epoch: u64,
+        reverse: bool,
     ) -> StorageResult<Self>
     where
         R: RangeBounds<B> + Send,
@@ -550,7 +616,13 @@ impl<S: StateStore, const ITER_TYPE: bool> CellBasedIter<S, ITER_TYPE> {
 
         let cell_based_row_deserializer = CellBasedRowDeserializer::new(table_descs);
 
-        let iter = keyspace.iter_with_range(encoded_key_range, epoch).await?;
+        let iter = if reverse {
+            keyspace
+                .backward_iter_with_range(encoded_key_range, epoch)
+                .await?
+        } else {
+            keyspace.iter_with_range(encoded_key_range, epoch).await?
+        };
         let iter = Self {
             iter,
             cell_based_row_deserializer,
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::collections::HashSet;
+use std::ops::Bound;
 
 use futures::{pin_mut, StreamExt};
 use itertools::Itertools;
@@ -1062,6 +1063,76 @@ async fn test_cell_based_table_iter() {
     assert!(res.is_none());
 }
 
+#[tokio::test]
+async fn test_cell_based_table_backward_iter() {
+    let state_store = MemoryStateStore::new();
+    let order_types = vec![OrderType::Ascending, OrderType::Descending];
+    let keyspace = Keyspace::table_root(state_store, &TableId::from(0x42));
+    let column_ids = vec![ColumnId::from(0), ColumnId::from(1), ColumnId::from(2)];
+    let column_descs = vec![
+        ColumnDesc::unnamed(column_ids[0], DataType::Int32),
+        ColumnDesc::unnamed(column_ids[1], DataType::Int32),
+        ColumnDesc::unnamed(column_ids[2], DataType::Int32),
+    ];
+    let pk_indices = vec![0_usize, 1_usize];
+    let mut state = StateTable::new(
+        keyspace.clone(),
+        column_descs.clone(),
+        order_types.clone(),
+        None,
+        pk_indices,
+    );
+    let table = state.cell_based_table().clone();
+    let epoch: u64 = 0;
+
+    let rows = (1..=3)
+        .map(|i: i32| {
+            Row(vec![
+                Some(i.into()),
+                Some((i * 11).into()),
+                Some((i * 111).into()),
+            ])
+        })
+        .collect_vec();
+    for row in &rows {
+        state.insert(row.clone()).unwrap();
+    }
+    state.commit(epoch).await.unwrap();
+
+    let epoch = u64::MAX;
+    let iter = table.batch_backward_iter(epoch).await.unwrap();
+    pin_mut!(iter);
+    for row in rows.iter().rev() {
+        assert_eq!(iter.next_row().await.unwrap().as_ref(), Some(row));
+    }
+    assert!(iter.next_row().await.unwrap().is_none());
+
+    // Scan `[1, 3)` of the first pk column backward.
+    let iter = table
+        .batch_backward_iter_with_pk_bounds(
+            epoch,
+            Row(vec![]),
+            (
+                Bound::Included(Some(1_i32.into())),
+                Bound::Excluded(Some(3_i32.into())),
+            ),
+        )
+        .await
+        .unwrap();
+    pin_mut!(iter);
+    assert_eq!(iter.next_row().await.unwrap().as_ref(), Some(&rows[1]));
+    assert_eq!(iter.next_row().await.unwrap().as_ref(), Some(&rows[0]));
+    assert!(iter.next_row().await.unwrap().is_none());
+
+    let iter = table
+        .batch_backward_iter_with_pk_prefix(epoch, Row(vec![Some(2_i32.into())]))
+        .await
+        .unwrap();
+    pin_mut!(iter);
+    assert_eq!(iter.next_row().await.unwrap().as_ref(), Some(&rows[1]));
+    assert!(iter.next_row().await.unwrap().is_none());
+}
+
 #[tokio::test]
 async fn test_multi_cell_based_table_iter() {
     let state_store = MemoryStateStore::new();
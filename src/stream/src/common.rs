@@ -13,4 +13,6 @@
 // limitations under the License.
 
 mod builder;
+mod hot_key;
 pub use builder::*;
+pub use hot_key::*;
@@ -0,0 +1,124 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// The number of candidates tracked by a [`HotKeyDetector`].
+const DEFAULT_CAPACITY: usize = 16;
+
+/// An epoch with fewer rows than this is too small to tell whether a key is hot.
+const MIN_ROWS_PER_EPOCH: u64 = 1024;
+
+/// A key is hot if it takes at least this share of the rows of an epoch.
+pub const HOT_KEY_RATIO_THRESHOLD: f64 = 0.5;
+
+/// [`HotKeyDetector`] finds the most frequent key of the rows processed by an executor in each
+/// epoch with a Misra-Gries sketch, which takes constant memory no matter how many distinct keys
+/// there are. The count of a tracked key is a lower bound of its real count, with an error of at
+/// most `total / (capacity + 1)`.
+///
+/// The detector only reports skew. Hot keys of aggregations are mitigated by splitting them into
+/// two phases with `RW_ENABLE_TWO_PHASE_AGG`, so that the rows of a key are combined in each
+/// upstream actor before the shuffle. Rebalancing hot keys automatically is not supported yet.
+pub struct HotKeyDetector<K> {
+    capacity: usize,
+    counters: HashMap<K, u64>,
+    total: u64,
+}
+
+impl<K: Hash + Eq + Clone> Default for HotKeyDetector<K> {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl<K: Hash + Eq + Clone> HotKeyDetector<K> {
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0);
+        Self {
+            capacity,
+            counters: HashMap::with_capacity(capacity + 1),
+            total: 0,
+        }
+    }
+
+    pub fn observe(&mut self, key: &K) {
+        self.total += 1;
+        if let Some(count) = self.counters.get_mut(key) {
+            *count += 1;
+        } else if self.counters.len() < self.capacity {
+            self.counters.insert(key.clone(), 1);
+        } else {
+            // The new key and one occurrence of every tracked key cancel each other out.
+            self.counters.retain(|_, count| {
+                *count -= 1;
+                *count > 0
+            });
+        }
+    }
+
+    /// Returns the hottest key of the epoch and the share of rows it takes, and resets the sketch
+    /// for the next epoch. Returns `None` if the epoch has too few rows.
+    pub fn finish_epoch(&mut self) -> Option<(K, f64)> {
+        let total = std::mem::take(&mut self.total);
+        let counters = std::mem::take(&mut self.counters);
+        if total < MIN_ROWS_PER_EPOCH {
+            return None;
+        }
+        counters
+            .into_iter()
+            .max_by_key(|(_, count)| *count)
+            .map(|(key, count)| (key, count as f64 / total as f64))
+    }
+
+    /// Same as [`HotKeyDetector::finish_epoch`], and additionally reports the share of the hottest
+    /// key to the `gauge`, warning if the key is hot. The key itself is never logged, since it
+    /// holds user data.
+    pub fn report(&mut self, gauge: &prometheus::Gauge, identity: &str) -> Option<(K, f64)> {
+        let hottest = self.finish_epoch();
+        let ratio = hottest.as_ref().map_or(0.0, |(_, ratio)| *ratio);
+        gauge.set(ratio);
+        if ratio >= HOT_KEY_RATIO_THRESHOLD {
+            tracing::warn!(
+                "a hot key takes {:.0}% of the rows of {} in this epoch",
+                ratio * 100.0,
+                identity,
+            );
+        }
+        hottest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hot_key_detector() {
+        let mut detector = HotKeyDetector::new(4);
+        for i in 0..2000u64 {
+            // Key 0 takes 70% of the rows, and the others are all distinct.
+            let key = if i % 10 < 7 { 0 } else { i };
+            detector.observe(&key);
+        }
+        let (key, ratio) = detector.finish_epoch().unwrap();
+        assert_eq!(key, 0);
+        assert!((HOT_KEY_RATIO_THRESHOLD..=0.7).contains(&ratio));
+
+        // The sketch is reset, and a small epoch reports nothing.
+        detector.observe(&1);
+        assert!(detector.finish_epoch().is_none());
+    }
+}
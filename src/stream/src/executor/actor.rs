@@ -19,6 +19,7 @@ use futures::pin_mut;
 use madsim::time::Instant;
 use parking_lot::Mutex;
 use risingwave_common::error::Result;
+use risingwave_pb::stream_service::barrier_complete_response::ActorStats;
 use tokio_stream::StreamExt;
 use tracing_futures::Instrument;
 
@@ -30,6 +31,8 @@ pub struct OperatorInfo {
     pub operator_id: u64,
     pub source_barrier_at: VecDeque<(u64, Instant)>,
     pub source_first_chunk_at: VecDeque<(u64, Instant)>,
+    /// Rows received from upstream actors in the epochs whose barriers have been received.
+    pub input_rows: u64,
 }
 
 impl OperatorInfo {
@@ -38,12 +41,16 @@ impl OperatorInfo {
             operator_id,
             source_barrier_at: VecDeque::new(),
             source_first_chunk_at: VecDeque::new(),
+            input_rows: 0,
         }
     }
 }
 
 pub struct OperatorInfoStatus {
     last_barrier_curr_epoch: Option<u64>,
+    /// Rows received in the current epoch, which are reported to the context on the barrier so
+    /// that the context is not locked for each chunk.
+    input_rows: u64,
     ctx: ActorContextRef,
     actor_context_position: usize,
 }
@@ -59,6 +66,7 @@ impl OperatorInfoStatus {
 
         Self {
             last_barrier_curr_epoch: None,
+            input_rows: 0,
             ctx,
             actor_context_position,
         }
@@ -71,9 +79,11 @@ impl OperatorInfoStatus {
                 let info = &mut ctx.info[self.actor_context_position];
                 info.source_barrier_at
                     .push_back((barrier.epoch.prev, Instant::now()));
+                info.input_rows += std::mem::take(&mut self.input_rows);
                 self.last_barrier_curr_epoch = Some(barrier.epoch.curr);
             }
-            Message::Chunk(_) => {
+            Message::Chunk(chunk) => {
+                self.input_rows += chunk.cardinality() as u64;
                 if let Some(epoch) = self.last_barrier_curr_epoch.take() {
                     let mut ctx = self.ctx.lock();
                     let info = &mut ctx.info[self.actor_context_position];
@@ -89,6 +99,11 @@ impl OperatorInfoStatus {
 #[derive(Default)]
 pub struct ActorContext {
     pub info: Vec<OperatorInfo>,
+    /// Rows sent to downstream actors in the epochs whose barriers have been dispatched.
+    pub output_rows: u64,
+    /// Time spent sending messages to downstream actors in the epochs whose barriers have been
+    /// dispatched.
+    pub output_blocking_ns: u64,
 }
 
 pub type ActorContextRef = Arc<Mutex<ActorContext>>;
@@ -103,6 +118,9 @@ impl ActorContext {
 pub struct Actor<C> {
     consumer: C,
     id: ActorId,
+    fragment_id: u32,
+    /// The materialized view this actor belongs to.
+    table_id: u32,
     context: Arc<SharedContext>,
     metrics: Arc<StreamingMetrics>,
     actor_context: Arc<Mutex<ActorContext>>,
@@ -115,6 +133,8 @@ where
     pub fn new(
         consumer: C,
         id: ActorId,
+        fragment_id: u32,
+        table_id: u32,
         context: Arc<SharedContext>,
         metrics: Arc<StreamingMetrics>,
         actor_context: Arc<Mutex<ActorContext>>,
@@ -122,6 +142,8 @@ where
         Self {
             consumer,
             id,
+            fragment_id,
+            table_id,
             context,
             metrics,
             actor_context,
@@ -140,6 +162,13 @@ where
         );
 
         let actor_id_string = self.id.to_string();
+        let fragment_id_string = self.fragment_id.to_string();
+        let table_id_string = self.table_id.to_string();
+        let actor_labels = [
+            actor_id_string.as_str(),
+            fragment_id_string.as_str(),
+            table_id_string.as_str(),
+        ];
         let operator_id_string = {
             let mut res = vec![];
             let ctx = self.actor_context.lock();
@@ -154,10 +183,12 @@ where
 
         // Drive the streaming task with an infinite loop
         while let Some(barrier) = stream.next().instrument(span).await.transpose()? {
-            {
+            let actor_stats = {
                 // Calculate metrics
                 let prev_epoch = barrier.epoch.prev;
                 let mut ctx = self.actor_context.lock();
+                let mut input_rows = 0;
+                let mut processing_latency = None;
                 for (idx, operator) in ctx.info.iter_mut().enumerate() {
                     let operator_id_string = &operator_id_string[idx];
                     if let Some(&(op_prev_epoch, _)) = operator.source_first_chunk_at.front() {
@@ -173,17 +204,55 @@ where
                     }
                     let (op_prev_epoch, time) = operator.source_barrier_at.pop_front().unwrap();
                     assert_eq!(op_prev_epoch, prev_epoch);
+                    let barrier_time = time.elapsed();
                     self.metrics
                         .actor_barrier_time
                         .with_label_values(&[&actor_id_string, operator_id_string])
-                        .set(time.elapsed().as_secs_f64());
+                        .set(barrier_time.as_secs_f64());
+                    // The barrier has been processed since it arrived at the first input.
+                    processing_latency = processing_latency.max(Some(barrier_time));
+                    input_rows += std::mem::take(&mut operator.input_rows);
+                }
+                let output_rows = std::mem::take(&mut ctx.output_rows);
+                let output_blocking_ns = std::mem::take(&mut ctx.output_blocking_ns);
+
+                self.metrics
+                    .actor_in_record_cnt
+                    .with_label_values(&actor_labels)
+                    .inc_by(input_rows);
+                self.metrics
+                    .actor_out_record_cnt
+                    .with_label_values(&actor_labels)
+                    .inc_by(output_rows);
+                self.metrics
+                    .actor_output_blocking_duration_ns
+                    .with_label_values(&actor_labels)
+                    .inc_by(output_blocking_ns);
+                // Barriers of source actors are injected into the actor directly.
+                if let Some(latency) = processing_latency {
+                    self.metrics
+                        .actor_processing_latency
+                        .with_label_values(&actor_labels)
+                        .observe(latency.as_secs_f64());
                 }
-            }
+
+                ActorStats {
+                    actor_id: self.id,
+                    fragment_id: self.fragment_id,
+                    table_id: self.table_id,
+                    input_rows,
+                    output_rows,
+                    processing_latency_ns: processing_latency.unwrap_or_default().as_nanos() as u64,
+                    output_blocking_ns,
+                }
+            };
 
             // Collect barriers to local barrier manager
-            self.context
-                .lock_barrier_manager()
-                .collect(self.id, &barrier)?;
+            {
+                let mut barrier_manager = self.context.lock_barrier_manager();
+                barrier_manager.update_actor_stats(actor_stats);
+                barrier_manager.collect(self.id, &barrier)?;
+            }
 
             // Then stop this actor if asked
             let to_stop = barrier.is_to_stop_actor(self.id);
@@ -0,0 +1,567 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use futures::{pin_mut, StreamExt};
+use futures_async_stream::try_stream;
+use itertools::Itertools;
+use risingwave_common::array::{Op, Row, StreamChunk};
+use risingwave_common::catalog::{ColumnDesc, ColumnId, Schema};
+use risingwave_common::types::ScalarImpl;
+use risingwave_common::util::sort_util::OrderType;
+use risingwave_storage::table::state_table::StateTable;
+use risingwave_storage::{Keyspace, StateStore};
+
+use super::barrier_align::*;
+use super::error::StreamExecutorError;
+use super::monitor::StreamingMetrics;
+use super::{
+    BoxedExecutor, BoxedMessageStream, Executor, ExecutorInfo, Message, PkIndices, PkIndicesRef,
+    StreamExecutorResult, PROCESSING_WINDOW_SIZE,
+};
+
+/// The columns of one side of an [`AsOfJoinExecutor`].
+pub struct AsOfJoinSide {
+    pub eq_keys: Vec<usize>,
+    pub asof_key: usize,
+}
+
+struct SideState<S: StateStore> {
+    eq_keys: Vec<usize>,
+    asof_key: usize,
+    state_table: StateTable<S>,
+}
+
+impl<S: StateStore> SideState<S> {
+    /// Same as the internal table inferred by the frontend: ordered by the equal keys, the time
+    /// in descending order, and then the primary key of the input.
+    fn new(
+        input: &BoxedExecutor,
+        side: AsOfJoinSide,
+        keyspace: Keyspace<S>,
+        dist_keys: Vec<usize>,
+    ) -> Self {
+        let column_descs = input
+            .schema()
+            .fields()
+            .iter()
+            .enumerate()
+            .map(|(idx, field)| ColumnDesc::unnamed(ColumnId::from(idx as i32), field.data_type()))
+            .collect_vec();
+        let mut state_pk_indices = side.eq_keys.clone();
+        state_pk_indices.push(side.asof_key);
+        for &idx in input.pk_indices() {
+            if !state_pk_indices.contains(&idx) {
+                state_pk_indices.push(idx);
+            }
+        }
+        let order_types = state_pk_indices
+            .iter()
+            .map(|&idx| {
+                if idx == side.asof_key {
+                    OrderType::Descending
+                } else {
+                    OrderType::Ascending
+                }
+            })
+            .collect();
+        let state_table = StateTable::new(
+            keyspace,
+            column_descs,
+            order_types,
+            Some(dist_keys),
+            state_pk_indices,
+        );
+        Self {
+            eq_keys: side.eq_keys,
+            asof_key: side.asof_key,
+            state_table,
+        }
+    }
+
+    /// Returns the equal keys and the time of the row, or `None` if any of them is NULL, in which
+    /// case the row never matches.
+    fn join_key(&self, row: &Row) -> Option<(Row, ScalarImpl)> {
+        let key = Row(self.eq_keys.iter().map(|&idx| row.0[idx].clone()).collect());
+        if key.0.iter().any(Option::is_none) {
+            return None;
+        }
+        let time = row.0[self.asof_key].clone()?;
+        Some((key, time))
+    }
+
+    /// Returns the rows of the equal keys, ordered by time in descending order.
+    async fn scan(&self, key: &Row, epoch: u64) -> StreamExecutorResult<Vec<Row>> {
+        let iter = self.state_table.iter_with_pk_prefix(key, epoch).await?;
+        pin_mut!(iter);
+        let mut rows = vec![];
+        while let Some(row) = iter.next().await {
+            rows.push(row?.into_owned());
+        }
+        Ok(rows)
+    }
+}
+
+/// [`AsOfJoinExecutor`] joins each left row with the latest right row of the same equal keys
+/// whose time is no later than that of the left row, i.e. `l.ts >= r.ts`, or earlier than it if
+/// not `inclusive`, i.e. `l.ts > r.ts`. The unmatched left rows are padded with NULLs in an outer
+/// join, and dropped in an inner join.
+///
+/// Both sides are kept in state tables ordered by the equal keys and then the time in descending
+/// order, so the match of a left row is the first right row of its equal keys not later than it.
+/// A change of the right side may change the matches of the left rows of the same equal keys,
+/// which are found by comparing the matches before and after the change.
+pub struct AsOfJoinExecutor<S: StateStore> {
+    input_l: Option<BoxedExecutor>,
+    input_r: Option<BoxedExecutor>,
+    state_l: SideState<S>,
+    state_r: SideState<S>,
+    /// The number of columns of the right input.
+    len_r: usize,
+    outer: bool,
+    inclusive: bool,
+    info: ExecutorInfo,
+    actor_id: u64,
+    metrics: Arc<StreamingMetrics>,
+}
+
+impl<S: StateStore> AsOfJoinExecutor<S> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        input_l: BoxedExecutor,
+        input_r: BoxedExecutor,
+        side_l: AsOfJoinSide,
+        side_r: AsOfJoinSide,
+        outer: bool,
+        inclusive: bool,
+        pk_indices: PkIndices,
+        executor_id: u64,
+        keyspace_l: Keyspace<S>,
+        keyspace_r: Keyspace<S>,
+        dist_keys_l: Vec<usize>,
+        dist_keys_r: Vec<usize>,
+        actor_id: u64,
+        metrics: Arc<StreamingMetrics>,
+    ) -> Self {
+        assert_eq!(side_l.eq_keys.len(), side_r.eq_keys.len());
+        let schema = Schema {
+            fields: input_l
+                .schema()
+                .fields()
+                .iter()
+                .chain(input_r.schema().fields())
+                .cloned()
+                .collect(),
+        };
+        let len_r = input_r.schema().len();
+        let state_l = SideState::new(&input_l, side_l, keyspace_l, dist_keys_l);
+        let state_r = SideState::new(&input_r, side_r, keyspace_r, dist_keys_r);
+        Self {
+            input_l: Some(input_l),
+            input_r: Some(input_r),
+            state_l,
+            state_r,
+            len_r,
+            outer,
+            inclusive,
+            info: ExecutorInfo {
+                schema,
+                pk_indices,
+                identity: format!("AsOfJoinExecutor {:X}", executor_id),
+            },
+            actor_id,
+            metrics,
+        }
+    }
+
+    /// Whether a right row at `time_r` may match a left row at `time_l`.
+    fn covers(&self, time_l: &ScalarImpl, time_r: &ScalarImpl) -> bool {
+        if self.inclusive {
+            time_l >= time_r
+        } else {
+            time_l > time_r
+        }
+    }
+
+    /// Finds the match of a left row at `time_l` among the right rows of its equal keys.
+    fn find_match<'a>(&self, rows_r: &'a [Row], time_l: &ScalarImpl) -> Option<&'a Row> {
+        rows_r.iter().find(|row| {
+            let time_r = row.0[self.state_r.asof_key].as_ref().unwrap();
+            self.covers(time_l, time_r)
+        })
+    }
+
+    fn concat(&self, row_l: &Row, row_r: Option<&Row>) -> Row {
+        let mut values = row_l.0.clone();
+        match row_r {
+            Some(row_r) => values.extend(row_r.0.iter().cloned()),
+            None => values.extend(std::iter::repeat(None).take(self.len_r)),
+        }
+        Row(values)
+    }
+
+    fn visible_rows(chunk: &StreamChunk) -> impl Iterator<Item = (Op, Row)> + '_ {
+        chunk.ops().iter().enumerate().filter_map(|(idx, op)| {
+            let visible = chunk
+                .visibility()
+                .as_ref()
+                .map(|x| x.is_set(idx).unwrap())
+                .unwrap_or(true);
+            visible.then(|| {
+                let row = Row(chunk
+                    .columns()
+                    .iter()
+                    .map(|x| x.array_ref().datum_at(idx))
+                    .collect_vec());
+                (*op, row)
+            })
+        })
+    }
+
+    async fn apply_left(
+        &mut self,
+        chunk: StreamChunk,
+        epoch: u64,
+    ) -> StreamExecutorResult<Vec<(Op, Row)>> {
+        let mut changes = vec![];
+        for (op, row) in Self::visible_rows(&chunk) {
+            let is_insert = matches!(op, Op::Insert | Op::UpdateInsert);
+            // The match may differ from that of the updated row, so an update is emitted as a
+            // delete and an insert.
+            let op = if is_insert { Op::Insert } else { Op::Delete };
+            let Some((key, time)) = self.state_l.join_key(&row) else {
+                if self.outer {
+                    changes.push((op, self.concat(&row, None)));
+                }
+                continue;
+            };
+            let rows_r = self.state_r.scan(&key, epoch).await?;
+            match self.find_match(&rows_r, &time) {
+                Some(row_r) => changes.push((op, self.concat(&row, Some(row_r)))),
+                None if self.outer => changes.push((op, self.concat(&row, None))),
+                None => {}
+            }
+            if is_insert {
+                self.state_l.state_table.insert(row)?;
+            } else {
+                self.state_l.state_table.delete(row)?;
+            }
+        }
+        Ok(changes)
+    }
+
+    async fn apply_right(
+        &mut self,
+        chunk: StreamChunk,
+        epoch: u64,
+    ) -> StreamExecutorResult<Vec<(Op, Row)>> {
+        let mut changes = vec![];
+        for (op, row) in Self::visible_rows(&chunk) {
+            let Some((key, time_r)) = self.state_r.join_key(&row) else {
+                continue;
+            };
+            let old_rows_r = self.state_r.scan(&key, epoch).await?;
+            if matches!(op, Op::Insert | Op::UpdateInsert) {
+                self.state_r.state_table.insert(row)?;
+            } else {
+                self.state_r.state_table.delete(row)?;
+            }
+            let new_rows_r = self.state_r.scan(&key, epoch).await?;
+
+            // Only the left rows covering the changed right row may change their matches. They
+            // come first since the left rows are also in descending order of time.
+            for row_l in self.state_l.scan(&key, epoch).await? {
+                let time_l = row_l.0[self.state_l.asof_key].as_ref().unwrap();
+                if !self.covers(time_l, &time_r) {
+                    break;
+                }
+                let old_match = self.find_match(&old_rows_r, time_l);
+                let new_match = self.find_match(&new_rows_r, time_l);
+                if old_match == new_match {
+                    continue;
+                }
+                match (old_match, new_match) {
+                    (None, Some(new_match)) if !self.outer => {
+                        changes.push((Op::Insert, self.concat(&row_l, Some(new_match))));
+                    }
+                    (Some(old_match), None) if !self.outer => {
+                        changes.push((Op::Delete, self.concat(&row_l, Some(old_match))));
+                    }
+                    (old_match, new_match) => {
+                        changes.push((Op::UpdateDelete, self.concat(&row_l, old_match)));
+                        changes.push((Op::UpdateInsert, self.concat(&row_l, new_match)));
+                    }
+                }
+            }
+        }
+        Ok(changes)
+    }
+
+    fn build_chunks(&self, changes: Vec<(Op, Row)>) -> StreamExecutorResult<Vec<StreamChunk>> {
+        let data_types = self.info.schema.data_types();
+        let chunks: Vec<StreamChunk> = changes
+            .chunks(PROCESSING_WINDOW_SIZE)
+            .map(|changes| StreamChunk::from_rows(changes, &data_types))
+            .try_collect()?;
+        Ok(chunks)
+    }
+
+    #[try_stream(ok = Message, error = StreamExecutorError)]
+    async fn into_stream(mut self) {
+        let input_l = self.input_l.take().unwrap();
+        let input_r = self.input_r.take().unwrap();
+        let aligned_stream = barrier_align(
+            input_l.execute(),
+            input_r.execute(),
+            self.actor_id,
+            self.metrics.clone(),
+        );
+        let mut epoch = 0;
+
+        #[for_await]
+        for msg in aligned_stream {
+            match msg? {
+                AlignedMessage::Left(chunk) => {
+                    let changes = self.apply_left(chunk, epoch).await?;
+                    for chunk in self.build_chunks(changes)? {
+                        yield Message::Chunk(chunk);
+                    }
+                }
+                AlignedMessage::Right(chunk) => {
+                    let changes = self.apply_right(chunk, epoch).await?;
+                    for chunk in self.build_chunks(changes)? {
+                        yield Message::Chunk(chunk);
+                    }
+                }
+                AlignedMessage::Barrier(barrier) => {
+                    self.state_l
+                        .state_table
+                        .commit_with_value_meta(barrier.epoch.prev)
+                        .await?;
+                    self.state_r
+                        .state_table
+                        .commit_with_value_meta(barrier.epoch.prev)
+                        .await?;
+                    epoch = barrier.epoch.curr;
+                    yield Message::Barrier(barrier);
+                }
+            }
+        }
+    }
+}
+
+impl<S: StateStore> Executor for AsOfJoinExecutor<S> {
+    fn execute(self: Box<Self>) -> BoxedMessageStream {
+        self.into_stream().boxed()
+    }
+
+    fn schema(&self) -> &Schema {
+        &self.info.schema
+    }
+
+    fn pk_indices(&self) -> PkIndicesRef {
+        &self.info.pk_indices
+    }
+
+    fn identity(&self) -> &str {
+        &self.info.identity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::stream_chunk::StreamChunkTestExt;
+    use risingwave_common::catalog::schema_test_utils;
+    use risingwave_storage::memory::MemoryStateStore;
+
+    use super::*;
+    use crate::executor::test_utils::{
+        create_in_memory_keyspace_agg, next_barrier, next_chunk, MessageSender, MockSource,
+    };
+
+    /// Both sides have the columns `(k, ts, id)`, where `id` is the primary key.
+    fn create_executor(
+        outer: bool,
+        inclusive: bool,
+    ) -> (MessageSender, MessageSender, BoxedMessageStream) {
+        let (tx_l, source_l) = MockSource::channel(schema_test_utils::iii(), vec![2]);
+        let (tx_r, source_r) = MockSource::channel(schema_test_utils::iii(), vec![2]);
+        let mut keyspaces = create_in_memory_keyspace_agg(2);
+        let keyspace_r = keyspaces.pop().unwrap();
+        let keyspace_l = keyspaces.pop().unwrap();
+        let executor = AsOfJoinExecutor::<MemoryStateStore>::new(
+            Box::new(source_l),
+            Box::new(source_r),
+            AsOfJoinSide {
+                eq_keys: vec![0],
+                asof_key: 1,
+            },
+            AsOfJoinSide {
+                eq_keys: vec![0],
+                asof_key: 1,
+            },
+            outer,
+            inclusive,
+            vec![2],
+            1,
+            keyspace_l,
+            keyspace_r,
+            vec![0],
+            vec![0],
+            1,
+            Arc::new(StreamingMetrics::unused()),
+        );
+        (tx_l, tx_r, Box::new(executor).execute())
+    }
+
+    fn push_barrier(tx_l: &mut MessageSender, tx_r: &mut MessageSender, epoch: u64) {
+        tx_l.push_barrier(epoch, false);
+        tx_r.push_barrier(epoch, false);
+    }
+
+    #[tokio::test]
+    async fn test_asof_left_join() {
+        let (mut tx_l, mut tx_r, mut asof_join) = create_executor(true, true);
+        push_barrier(&mut tx_l, &mut tx_r, 1);
+        next_barrier(&mut asof_join).await;
+
+        // No left rows are affected.
+        tx_r.push_chunk(StreamChunk::from_pretty(
+            " I  I   I
+            + 1 10 100
+            + 1 20 200",
+        ));
+        push_barrier(&mut tx_l, &mut tx_r, 2);
+        next_barrier(&mut asof_join).await;
+
+        tx_l.push_chunk(StreamChunk::from_pretty(
+            " I  I I
+            + 1 15 1
+            + 1 25 2
+            + 2  5 3
+            + 1  5 4
+            + . 20 5",
+        ));
+        push_barrier(&mut tx_l, &mut tx_r, 3);
+        assert_eq!(
+            next_chunk(&mut asof_join).await,
+            StreamChunk::from_pretty(
+                " I  I I I  I   I
+                + 1 15 1 1 10 100
+                + 1 25 2 1 20 200
+                + 2  5 3 .  .   .
+                + 1  5 4 .  .   .
+                + . 20 5 .  .   ."
+            )
+        );
+        next_barrier(&mut asof_join).await;
+
+        // A right row at the same time as a left row matches it.
+        tx_r.push_chunk(StreamChunk::from_pretty(
+            " I  I   I
+            + 1 15 300",
+        ));
+        push_barrier(&mut tx_l, &mut tx_r, 4);
+        assert_eq!(
+            next_chunk(&mut asof_join).await,
+            StreamChunk::from_pretty(
+                "  I  I I I  I   I
+                U- 1 15 1 1 10 100
+                U+ 1 15 1 1 15 300"
+            )
+        );
+        next_barrier(&mut asof_join).await;
+
+        // The left rows matching a deleted right row fall back to the previous one.
+        tx_r.push_chunk(StreamChunk::from_pretty(
+            " I  I   I
+            - 1 20 200",
+        ));
+        push_barrier(&mut tx_l, &mut tx_r, 5);
+        assert_eq!(
+            next_chunk(&mut asof_join).await,
+            StreamChunk::from_pretty(
+                "  I  I I I  I   I
+                U- 1 25 2 1 20 200
+                U+ 1 25 2 1 15 300"
+            )
+        );
+        next_barrier(&mut asof_join).await;
+
+        tx_l.push_chunk(StreamChunk::from_pretty(
+            " I  I I
+            - 1 25 2
+            + 1 12 6",
+        ));
+        push_barrier(&mut tx_l, &mut tx_r, 6);
+        assert_eq!(
+            next_chunk(&mut asof_join).await,
+            StreamChunk::from_pretty(
+                " I  I I I  I   I
+                - 1 25 2 1 15 300
+                + 1 12 6 1 10 100"
+            )
+        );
+        next_barrier(&mut asof_join).await;
+    }
+
+    #[tokio::test]
+    async fn test_asof_inner_join_strict() {
+        let (mut tx_l, mut tx_r, mut asof_join) = create_executor(false, false);
+        push_barrier(&mut tx_l, &mut tx_r, 1);
+        next_barrier(&mut asof_join).await;
+
+        tx_r.push_chunk(StreamChunk::from_pretty(
+            " I  I   I
+            + 1 10 100",
+        ));
+        push_barrier(&mut tx_l, &mut tx_r, 2);
+        next_barrier(&mut asof_join).await;
+
+        // A right row at the same time as a left row doesn't match it.
+        tx_l.push_chunk(StreamChunk::from_pretty(
+            " I  I I
+            + 1 10 1
+            + 1 11 2",
+        ));
+        push_barrier(&mut tx_l, &mut tx_r, 3);
+        assert_eq!(
+            next_chunk(&mut asof_join).await,
+            StreamChunk::from_pretty(
+                " I  I I I  I   I
+                + 1 11 2 1 10 100"
+            )
+        );
+        next_barrier(&mut asof_join).await;
+
+        // The unmatched left rows are retracted, and the newly matched ones are emitted.
+        tx_r.push_chunk(StreamChunk::from_pretty(
+            " I  I   I
+            - 1 10 100
+            + 1  5 200",
+        ));
+        push_barrier(&mut tx_l, &mut tx_r, 4);
+        assert_eq!(
+            next_chunk(&mut asof_join).await,
+            StreamChunk::from_pretty(
+                " I  I I I  I   I
+                - 1 11 2 1 10 100
+                + 1 11 2 1  5 200
+                + 1 10 1 1  5 200"
+            )
+        );
+        next_barrier(&mut asof_join).await;
+    }
+}
@@ -12,18 +12,19 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::cmp::Ordering;
+
 use futures::{pin_mut, StreamExt};
 use futures_async_stream::try_stream;
-use risingwave_common::array::{DataChunk, Op, StreamChunk};
+use risingwave_common::array::{DataChunk, Op, Row, StreamChunk};
 use risingwave_common::buffer::{Bitmap, BitmapBuilder};
 use risingwave_common::catalog::{OrderedColumnDesc, Schema};
-use risingwave_common::types::VIRTUAL_NODE_COUNT;
 use risingwave_common::util::hash_util::CRC32FastBuilder;
 use risingwave_storage::table::cell_based_table::CellBasedTable;
 use risingwave_storage::table::TableIter;
 use risingwave_storage::StateStore;
 
-use super::error::StreamExecutorError;
+use super::error::{StreamExecutorError, StreamExecutorResult};
 use super::{Executor, ExecutorInfo, Message};
 use crate::executor::BoxedMessageStream;
 
@@ -45,6 +46,10 @@ pub struct BatchQueryExecutor<S: StateStore> {
     /// public key field descriptors. Used to decode pk into datums
     /// for dedup pk encoding.
     pk_descs: Vec<OrderedColumnDesc>,
+
+    /// If set, only the changes since this epoch are scanned. See
+    /// [`BatchQueryExecutor::with_base_epoch`].
+    base_epoch: Option<u64>,
 }
 
 impl<S> BatchQueryExecutor<S>
@@ -68,9 +73,19 @@ where
             key_indices,
             hash_filter,
             pk_descs,
+            base_epoch: None,
         }
     }
 
+    /// Scans the changes of the table between `base_epoch` and the epoch to read, as deletes of
+    /// the rows only visible at the former and inserts of the rows only visible at the latter.
+    /// Used by the chains whose downstream states are adopted from the streaming job replaced by
+    /// `ALTER MATERIALIZED VIEW AS`, which has processed the table until `base_epoch`.
+    pub fn with_base_epoch(mut self, base_epoch: u64) -> Self {
+        self.base_epoch = Some(base_epoch);
+        self
+    }
+
     #[try_stream(ok = Message, error = StreamExecutorError)]
     async fn execute_inner(self, epoch: u64) {
         let iter = self
@@ -97,6 +112,77 @@ where
         }
     }
 
+    #[try_stream(ok = Message, error = StreamExecutorError)]
+    async fn execute_delta(self, base_epoch: u64, epoch: u64) {
+        let base_iter = self
+            .table
+            .batch_dedup_pk_iter(base_epoch, &self.pk_descs)
+            .await?;
+        let iter = self
+            .table
+            .batch_dedup_pk_iter(epoch, &self.pk_descs)
+            .await?;
+        pin_mut!(base_iter);
+        pin_mut!(iter);
+
+        // Both iterators are ordered by the encoded pk, so they're merged to find the differences.
+        let mut base_next = base_iter.next().await.transpose()?;
+        let mut next = iter.next().await.transpose()?;
+        let mut rows = Vec::with_capacity(self.batch_size);
+        loop {
+            let ordering = match (&base_next, &next) {
+                (None, None) => break,
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (Some((base_pk, _)), Some((pk, _))) => base_pk.cmp(pk),
+            };
+            match ordering {
+                Ordering::Less => {
+                    let (_, base_row) = base_next.take().unwrap();
+                    rows.push((Op::Delete, base_row));
+                    base_next = base_iter.next().await.transpose()?;
+                }
+                Ordering::Greater => {
+                    let (_, row) = next.take().unwrap();
+                    rows.push((Op::Insert, row));
+                    next = iter.next().await.transpose()?;
+                }
+                Ordering::Equal => {
+                    let (_, base_row) = base_next.take().unwrap();
+                    let (_, row) = next.take().unwrap();
+                    if base_row != row {
+                        rows.push((Op::Delete, base_row));
+                        rows.push((Op::Insert, row));
+                    }
+                    base_next = base_iter.next().await.transpose()?;
+                    next = iter.next().await.transpose()?;
+                }
+            }
+
+            if rows.len() >= self.batch_size {
+                if let Some(chunk) = self.delta_chunk(std::mem::take(&mut rows))? {
+                    yield Message::Chunk(chunk);
+                }
+            }
+        }
+        if let Some(chunk) = self.delta_chunk(rows)? {
+            yield Message::Chunk(chunk);
+        }
+    }
+
+    /// Builds a chunk of the changed rows belonging to this parallel unit.
+    fn delta_chunk(&self, rows: Vec<(Op, Row)>) -> StreamExecutorResult<Option<StreamChunk>> {
+        if rows.is_empty() {
+            return Ok(None);
+        }
+        let (data_chunk, ops) =
+            StreamChunk::from_rows(&rows, &self.schema().data_types())?.into_parts();
+        Ok(match self.filter_chunk(data_chunk) {
+            Some(data_chunk) => Some(StreamChunk::from_parts(ops, data_chunk).compact()?),
+            None => None,
+        })
+    }
+
     /// Now we use hash as a workaround for supporting parallelized chain.
     fn filter_chunk(&self, data_chunk: DataChunk) -> Option<DataChunk> {
         let hash_values = data_chunk
@@ -109,7 +195,7 @@ where
         for hv in &hash_values {
             new_visibility.append(
                 self.hash_filter
-                    .is_set((hv.0 % VIRTUAL_NODE_COUNT as u64) as usize)
+                    .is_set((hv.0 % self.hash_filter.len() as u64) as usize)
                     .unwrap_or(false),
             );
         }
@@ -143,7 +229,10 @@ where
     }
 
     fn execute_with_epoch(self: Box<Self>, epoch: u64) -> BoxedMessageStream {
-        self.execute_inner(epoch).boxed()
+        match self.base_epoch {
+            Some(base_epoch) => self.execute_delta(base_epoch, epoch).boxed(),
+            None => self.execute_inner(epoch).boxed(),
+        }
     }
 }
 
@@ -153,9 +242,13 @@ mod test {
     use std::vec;
 
     use futures_async_stream::for_await;
-    use risingwave_common::catalog::{ColumnDesc, ColumnId};
-    use risingwave_common::types::DataType;
+    use risingwave_common::array::stream_chunk::StreamChunkTestExt;
+    use risingwave_common::catalog::{ColumnDesc, ColumnId, TableId};
+    use risingwave_common::types::{DataType, VIRTUAL_NODE_COUNT};
     use risingwave_common::util::sort_util::OrderType;
+    use risingwave_storage::memory::MemoryStateStore;
+    use risingwave_storage::table::state_table::StateTable;
+    use risingwave_storage::Keyspace;
 
     use super::*;
     use crate::executor::mview::test_utils::gen_basic_table;
@@ -217,4 +310,67 @@ mod test {
 
         assert_eq!(batch_cnt, test_batch_count)
     }
+
+    #[tokio::test]
+    async fn test_base_epoch() {
+        let row = |v1: i32, v2: i32| Row(vec![Some(v1.into()), Some(v2.into())]);
+        let column_descs = vec![
+            ColumnDesc::unnamed(ColumnId::from(0), DataType::Int32),
+            ColumnDesc::unnamed(ColumnId::from(1), DataType::Int32),
+        ];
+        let keyspace = Keyspace::table_root(MemoryStateStore::new(), &TableId::from(0x42));
+        let mut state = StateTable::new(
+            keyspace,
+            column_descs.clone(),
+            vec![OrderType::Ascending],
+            None,
+            vec![0],
+        );
+        let table = state.cell_based_table().clone();
+        for i in 0..5 {
+            state.insert(row(i, i)).unwrap();
+        }
+        state.commit(1).await.unwrap();
+        state.delete(row(0, 0)).unwrap();
+        state.update(row(1, 1), row(1, 10)).unwrap();
+        state.insert(row(5, 5)).unwrap();
+        state.commit(2).await.unwrap();
+
+        let info = ExecutorInfo {
+            schema: table.schema().clone(),
+            pk_indices: vec![0],
+            identity: "BatchQuery".to_owned(),
+        };
+        let hash_filter = {
+            let mut builder = BitmapBuilder::with_capacity(VIRTUAL_NODE_COUNT);
+            for _ in 0..VIRTUAL_NODE_COUNT {
+                builder.append(true);
+            }
+            builder.finish()
+        };
+        let pk_descs = vec![OrderedColumnDesc {
+            column_desc: column_descs[0].clone(),
+            order: OrderType::Ascending,
+        }];
+        let executor = Box::new(
+            BatchQueryExecutor::new(table, None, info, vec![], hash_filter, pk_descs)
+                .with_base_epoch(1),
+        );
+
+        let chunks: Vec<_> = executor
+            .execute_with_epoch(2)
+            .map(|msg| msg.unwrap().into_chunk().unwrap())
+            .collect()
+            .await;
+        assert_eq!(
+            chunks,
+            vec![StreamChunk::from_pretty(
+                " i  i
+                - 0  0
+                - 1  1
+                + 1 10
+                + 5  5"
+            )]
+        );
+    }
 }
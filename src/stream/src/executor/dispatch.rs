@@ -17,19 +17,20 @@ use std::future::Future;
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use fail::fail_point;
 use futures::Stream;
 use futures_async_stream::try_stream;
 use itertools::Itertools;
 use madsim::collections::{HashMap, HashSet};
+use madsim::time::Instant;
 use risingwave_common::array::{Op, StreamChunk};
 use risingwave_common::error::{internal_error, Result};
-use risingwave_common::types::VIRTUAL_NODE_COUNT;
 use risingwave_common::util::addr::{is_local_address, HostAddr};
 use risingwave_common::util::hash_util::CRC32FastBuilder;
 use tokio::sync::mpsc::Sender;
 use tracing::event;
 
-use crate::executor::{Barrier, BoxedExecutor, Message, Mutation, StreamConsumer};
+use crate::executor::{ActorContextRef, Barrier, BoxedExecutor, Message, Mutation, StreamConsumer};
 use crate::task::{ActorId, DispatcherId, SharedContext};
 
 /// `Output` provides an interface for `Dispatcher` to send data into downstream actors.
@@ -66,6 +67,9 @@ impl LocalOutput {
 #[async_trait]
 impl Output for LocalOutput {
     async fn send(&mut self, message: Message) -> Result<()> {
+        fail_point!("stream_local_output_send_err", |_| Err(internal_error(
+            "stream_local_output_send_err"
+        )));
         // local channel should never fail
         self.ch
             .send(message)
@@ -107,6 +111,9 @@ impl Output for RemoteOutput {
             Message::Chunk(chk) => Message::Chunk(chk.compact()?),
             _ => message,
         };
+        fail_point!("stream_remote_output_send_err", |_| Err(internal_error(
+            "stream_remote_output_send_err"
+        )));
         // local channel should never fail
         self.ch
             .send(message)
@@ -141,6 +148,7 @@ pub fn new_output(
 pub struct DispatchExecutor {
     input: BoxedExecutor,
     inner: DispatchExecutorInner,
+    actor_context: ActorContextRef,
 }
 
 struct DispatchExecutorInner {
@@ -268,6 +276,7 @@ impl DispatchExecutor {
         dispatchers: Vec<DispatcherImpl>,
         actor_id: u32,
         context: Arc<SharedContext>,
+        actor_context: ActorContextRef,
     ) -> Self {
         Self {
             input,
@@ -276,6 +285,7 @@ impl DispatchExecutor {
                 actor_id,
                 context,
             },
+            actor_context,
         }
     }
 }
@@ -287,13 +297,26 @@ impl StreamConsumer for DispatchExecutor {
         #[try_stream]
         async move {
             let input = self.input.execute();
+            // Statistics of the current epoch, reported to the actor context on the barrier.
+            let mut output_rows = 0;
+            let mut output_blocking_ns = 0;
 
             #[for_await]
             for msg in input {
                 let msg: Message = msg?;
                 let barrier = msg.as_barrier().cloned();
+                if let Message::Chunk(chunk) = &msg {
+                    output_rows += chunk.cardinality() as u64;
+                }
+                let start_time = Instant::now();
                 self.inner.dispatch(msg).await?;
+                output_blocking_ns += start_time.elapsed().as_nanos() as u64;
                 if let Some(barrier) = barrier {
+                    {
+                        let mut ctx = self.actor_context.lock();
+                        ctx.output_rows += std::mem::take(&mut output_rows);
+                        ctx.output_blocking_ns += std::mem::take(&mut output_blocking_ns);
+                    }
                     yield barrier;
                 }
             }
@@ -525,7 +548,7 @@ impl Dispatcher for HashDataDispatcher {
                 .get_hash_values(&self.keys, hash_builder)
                 .unwrap()
                 .iter()
-                .map(|hash| *hash as usize % VIRTUAL_NODE_COUNT)
+                .map(|hash| *hash as usize % self.hash_mapping.len())
                 .collect::<Vec<_>>();
 
             let (ops, columns, visibility) = chunk.into_inner();
@@ -943,6 +966,7 @@ mod tests {
             ))],
             actor_id,
             ctx.clone(),
+            ActorContext::create(),
         ))
         .execute();
         pin_mut!(executor);
@@ -0,0 +1,516 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use futures::{pin_mut, StreamExt};
+use futures_async_stream::try_stream;
+use itertools::Itertools;
+use risingwave_common::array::{Op, Row, StreamChunk};
+use risingwave_common::catalog::{ColumnDesc, ColumnId, Schema};
+use risingwave_common::types::{Datum, ScalarImpl};
+use risingwave_common::util::sort_util::OrderType;
+use risingwave_pb::expr::expr_node::Type as ExprNodeType;
+use risingwave_storage::table::state_table::StateTable;
+use risingwave_storage::{Keyspace, StateStore};
+
+use super::barrier_align::*;
+use super::error::StreamExecutorError;
+use super::monitor::StreamingMetrics;
+use super::{
+    BoxedExecutor, BoxedMessageStream, Executor, ExecutorInfo, Message, PkIndices, PkIndicesRef,
+    StreamExecutorResult, PROCESSING_WINDOW_SIZE,
+};
+
+/// [`DynamicFilterExecutor`] filters the left input by comparing the column `key_l` with the bound
+/// given by the single row of the right input, e.g. `v > NOW() - INTERVAL '1 hour'`. The bound
+/// must never go down, so that a row changes its visibility at most once:
+///
+/// - For `>` and `>=`, a row is retracted once the bound catches up with it. The state table keeps
+///   the rows emitted so far.
+/// - For `<` and `<=`, a row is emitted once the bound passes it. The state table keeps the rows
+///   not emitted yet.
+///
+/// The state table is ordered by `key_l`, so the rows affected by a new bound are always a prefix
+/// of it. Rows with a NULL key never pass the filter, and are not kept at all.
+pub struct DynamicFilterExecutor<S: StateStore> {
+    source_l: Option<BoxedExecutor>,
+    source_r: Option<BoxedExecutor>,
+    key_l: usize,
+    comparator: ExprNodeType,
+    state_table: StateTable<S>,
+    info: ExecutorInfo,
+    actor_id: u64,
+    metrics: Arc<StreamingMetrics>,
+}
+
+impl<S: StateStore> DynamicFilterExecutor<S> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        source_l: BoxedExecutor,
+        source_r: BoxedExecutor,
+        key_l: usize,
+        pk_indices: PkIndices,
+        executor_id: u64,
+        comparator: ExprNodeType,
+        keyspace: Keyspace<S>,
+        dist_key_l: Vec<usize>,
+        actor_id: u64,
+        metrics: Arc<StreamingMetrics>,
+    ) -> Self {
+        assert!(matches!(
+            comparator,
+            ExprNodeType::GreaterThan
+                | ExprNodeType::GreaterThanOrEqual
+                | ExprNodeType::LessThan
+                | ExprNodeType::LessThanOrEqual
+        ));
+        let schema = source_l.schema().clone();
+        let column_descs = schema
+            .fields()
+            .iter()
+            .enumerate()
+            .map(|(idx, field)| ColumnDesc::unnamed(ColumnId::from(idx as i32), field.data_type()))
+            .collect_vec();
+        // Same as the internal table inferred by the frontend.
+        let state_pk_indices = std::iter::once(key_l)
+            .chain(
+                source_l
+                    .pk_indices()
+                    .iter()
+                    .copied()
+                    .filter(|&idx| idx != key_l),
+            )
+            .collect_vec();
+        let state_table = StateTable::new(
+            keyspace,
+            column_descs,
+            vec![OrderType::Ascending; state_pk_indices.len()],
+            Some(dist_key_l),
+            state_pk_indices,
+        );
+        Self {
+            source_l: Some(source_l),
+            source_r: Some(source_r),
+            key_l,
+            comparator,
+            state_table,
+            info: ExecutorInfo {
+                schema,
+                pk_indices,
+                identity: format!("DynamicFilterExecutor {:X}", executor_id),
+            },
+            actor_id,
+            metrics,
+        }
+    }
+
+    /// Whether the bound is a lower bound, i.e. rows are retracted as it goes up.
+    fn is_lower_bound(&self) -> bool {
+        matches!(
+            self.comparator,
+            ExprNodeType::GreaterThan | ExprNodeType::GreaterThanOrEqual
+        )
+    }
+
+    fn passes(&self, key: &ScalarImpl, bound: &Datum) -> bool {
+        let Some(bound) = bound else {
+            return false;
+        };
+        match self.comparator {
+            ExprNodeType::GreaterThan => key > bound,
+            ExprNodeType::GreaterThanOrEqual => key >= bound,
+            ExprNodeType::LessThan => key < bound,
+            ExprNodeType::LessThanOrEqual => key <= bound,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Filters a chunk of the left input with the current bound.
+    fn apply_left(
+        &mut self,
+        chunk: StreamChunk,
+        bound: &Datum,
+    ) -> StreamExecutorResult<Option<StreamChunk>> {
+        let mut changes = vec![];
+        for (idx, op) in chunk.ops().iter().enumerate() {
+            let visible = chunk
+                .visibility()
+                .as_ref()
+                .map(|x| x.is_set(idx).unwrap())
+                .unwrap_or(true);
+            if !visible {
+                continue;
+            }
+
+            let row = Row(chunk
+                .columns()
+                .iter()
+                .map(|x| x.array_ref().datum_at(idx))
+                .collect_vec());
+            let Some(key) = &row.0[self.key_l] else {
+                continue;
+            };
+            let passes = self.passes(key, bound);
+            let is_insert = matches!(op, Op::Insert | Op::UpdateInsert);
+            // An update may change the visibility of the row, so it's emitted as a delete and an
+            // insert.
+            let op = if is_insert { Op::Insert } else { Op::Delete };
+
+            match (self.is_lower_bound(), passes, is_insert) {
+                // The row is kept to be retracted later.
+                (true, true, true) => self.state_table.insert(row.clone())?,
+                (true, true, false) => self.state_table.delete(row.clone())?,
+                // The row will never pass.
+                (true, false, _) => continue,
+                // The row is passed, and never goes back.
+                (false, true, _) => {}
+                // The row is kept to be emitted later.
+                (false, false, true) => {
+                    self.state_table.insert(row)?;
+                    continue;
+                }
+                (false, false, false) => {
+                    self.state_table.delete(row)?;
+                    continue;
+                }
+            }
+            changes.push((op, row));
+        }
+
+        if changes.is_empty() {
+            return Ok(None);
+        }
+        let data_types = self.info.schema.data_types();
+        Ok(Some(StreamChunk::from_rows(&changes, &data_types)?))
+    }
+
+    /// Moves the bound up to `bound`, and returns the rows changing their visibility.
+    async fn advance_bound(
+        &mut self,
+        bound: &Datum,
+        epoch: u64,
+    ) -> StreamExecutorResult<Vec<StreamChunk>> {
+        let is_lower_bound = self.is_lower_bound();
+        let mut rows = vec![];
+        {
+            let state_table_iter = self.state_table.iter(epoch).await?;
+            pin_mut!(state_table_iter);
+            while let Some(row) = state_table_iter.next().await {
+                let row = row?.into_owned();
+                let key = row.0[self.key_l].as_ref().unwrap();
+                // Rows kept for a lower bound pass the filter, and rows kept for an upper bound
+                // don't. The first row that stays the same ends the affected prefix.
+                if self.passes(key, bound) == is_lower_bound {
+                    break;
+                }
+                rows.push(row);
+            }
+        }
+
+        let op = if is_lower_bound {
+            Op::Delete
+        } else {
+            Op::Insert
+        };
+        let mut changes = Vec::with_capacity(rows.len());
+        for row in rows {
+            self.state_table.delete(row.clone())?;
+            changes.push((op, row));
+        }
+        let data_types = self.info.schema.data_types();
+        let chunks: Vec<StreamChunk> = changes
+            .chunks(PROCESSING_WINDOW_SIZE)
+            .map(|changes| StreamChunk::from_rows(changes, &data_types))
+            .try_collect()?;
+        Ok(chunks)
+    }
+
+    #[try_stream(ok = Message, error = StreamExecutorError)]
+    async fn into_stream(mut self) {
+        let input_l = self.source_l.take().unwrap();
+        let input_r = self.source_r.take().unwrap();
+        let aligned_stream = barrier_align(
+            input_l.execute(),
+            input_r.execute(),
+            self.actor_id,
+            self.metrics.clone(),
+        );
+
+        // The left chunks are buffered until the first bound arrives, since the bound before
+        // recovery is unknown.
+        let mut bound: Option<Datum> = None;
+        let mut buffered_chunks = vec![];
+        let mut epoch = 0;
+
+        #[for_await]
+        for msg in aligned_stream {
+            match msg? {
+                AlignedMessage::Left(chunk) => match &bound {
+                    Some(bound) => {
+                        if let Some(chunk) = self.apply_left(chunk, bound)? {
+                            yield Message::Chunk(chunk);
+                        }
+                    }
+                    None => buffered_chunks.push(chunk),
+                },
+                AlignedMessage::Right(chunk) => {
+                    // The right input has a single row, whose latest value is the new bound.
+                    let Some(new_bound) = chunk
+                        .ops()
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, op)| matches!(op, Op::Insert | Op::UpdateInsert))
+                        .map(|(idx, _)| chunk.column_at(0).array_ref().datum_at(idx))
+                        .last()
+                    else {
+                        continue;
+                    };
+                    for chunk in self.advance_bound(&new_bound, epoch).await? {
+                        yield Message::Chunk(chunk);
+                    }
+                    for chunk in std::mem::take(&mut buffered_chunks) {
+                        if let Some(chunk) = self.apply_left(chunk, &new_bound)? {
+                            yield Message::Chunk(chunk);
+                        }
+                    }
+                    bound = Some(new_bound);
+                }
+                AlignedMessage::Barrier(barrier) => {
+                    self.state_table
+                        .commit_with_value_meta(barrier.epoch.prev)
+                        .await?;
+                    epoch = barrier.epoch.curr;
+                    yield Message::Barrier(barrier);
+                }
+            }
+        }
+    }
+}
+
+impl<S: StateStore> Executor for DynamicFilterExecutor<S> {
+    fn execute(self: Box<Self>) -> BoxedMessageStream {
+        self.into_stream().boxed()
+    }
+
+    fn schema(&self) -> &Schema {
+        &self.info.schema
+    }
+
+    fn pk_indices(&self) -> PkIndicesRef {
+        &self.info.pk_indices
+    }
+
+    fn identity(&self) -> &str {
+        &self.info.identity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::stream_chunk::StreamChunkTestExt;
+    use risingwave_common::catalog::{Field, Schema};
+    use risingwave_common::types::DataType;
+    use risingwave_storage::memory::MemoryStateStore;
+
+    use super::*;
+    use crate::executor::test_utils::{
+        create_in_memory_keyspace, next_barrier, next_chunk, MessageSender, MockSource,
+    };
+
+    fn create_executor(
+        comparator: ExprNodeType,
+    ) -> (MessageSender, MessageSender, BoxedMessageStream) {
+        let schema_l = Schema {
+            fields: vec![
+                Field::unnamed(DataType::Int64),
+                Field::unnamed(DataType::Int64),
+            ],
+        };
+        let schema_r = Schema {
+            fields: vec![Field::unnamed(DataType::Int64)],
+        };
+        let (tx_l, source_l) = MockSource::channel(schema_l, vec![1]);
+        let (tx_r, source_r) = MockSource::channel(schema_r, vec![]);
+        let executor = DynamicFilterExecutor::<MemoryStateStore>::new(
+            Box::new(source_l),
+            Box::new(source_r),
+            0,
+            vec![1],
+            1,
+            comparator,
+            create_in_memory_keyspace(),
+            vec![],
+            1,
+            Arc::new(StreamingMetrics::unused()),
+        );
+        (tx_l, tx_r, Box::new(executor).execute())
+    }
+
+    #[tokio::test]
+    async fn test_dynamic_filter_lower_bound() {
+        let (mut tx_l, mut tx_r, mut dynamic_filter) = create_executor(ExprNodeType::GreaterThan);
+
+        tx_l.push_barrier(1, false);
+        tx_r.push_barrier(1, false);
+        next_barrier(&mut dynamic_filter).await;
+
+        // The left rows are held back until the first bound arrives.
+        tx_l.push_chunk(StreamChunk::from_pretty(
+            " I  I
+            + 1 10
+            + 3 11
+            + 5 12",
+        ));
+        tx_r.push_chunk(StreamChunk::from_pretty(
+            " I
+            + 2",
+        ));
+        tx_l.push_barrier(2, false);
+        tx_r.push_barrier(2, false);
+        assert_eq!(
+            next_chunk(&mut dynamic_filter).await,
+            StreamChunk::from_pretty(
+                " I  I
+                + 3 11
+                + 5 12"
+            )
+        );
+        next_barrier(&mut dynamic_filter).await;
+
+        // The rows caught up by the bound are retracted.
+        tx_r.push_chunk(StreamChunk::from_pretty(
+            "  I
+            U- 2
+            U+ 4",
+        ));
+        tx_l.push_barrier(3, false);
+        tx_r.push_barrier(3, false);
+        assert_eq!(
+            next_chunk(&mut dynamic_filter).await,
+            StreamChunk::from_pretty(
+                " I  I
+                - 3 11"
+            )
+        );
+        next_barrier(&mut dynamic_filter).await;
+
+        tx_l.push_chunk(StreamChunk::from_pretty(
+            " I  I
+            - 5 12
+            + 4 13
+            + 6 14",
+        ));
+        tx_l.push_barrier(4, false);
+        tx_r.push_barrier(4, false);
+        assert_eq!(
+            next_chunk(&mut dynamic_filter).await,
+            StreamChunk::from_pretty(
+                " I  I
+                - 5 12
+                + 6 14"
+            )
+        );
+        next_barrier(&mut dynamic_filter).await;
+
+        tx_r.push_chunk(StreamChunk::from_pretty(
+            "  I
+            U- 4
+            U+ 6",
+        ));
+        tx_l.push_barrier(5, false);
+        tx_r.push_barrier(5, false);
+        assert_eq!(
+            next_chunk(&mut dynamic_filter).await,
+            StreamChunk::from_pretty(
+                " I  I
+                - 6 14"
+            )
+        );
+        next_barrier(&mut dynamic_filter).await;
+    }
+
+    #[tokio::test]
+    async fn test_dynamic_filter_upper_bound() {
+        let (mut tx_l, mut tx_r, mut dynamic_filter) = create_executor(ExprNodeType::LessThan);
+
+        tx_l.push_barrier(1, false);
+        tx_r.push_barrier(1, false);
+        next_barrier(&mut dynamic_filter).await;
+
+        tx_l.push_chunk(StreamChunk::from_pretty(
+            " I  I
+            + 1 10
+            + 3 11
+            + 5 12",
+        ));
+        tx_r.push_chunk(StreamChunk::from_pretty(
+            " I
+            + 2",
+        ));
+        tx_l.push_barrier(2, false);
+        tx_r.push_barrier(2, false);
+        assert_eq!(
+            next_chunk(&mut dynamic_filter).await,
+            StreamChunk::from_pretty(
+                " I  I
+                + 1 10"
+            )
+        );
+        next_barrier(&mut dynamic_filter).await;
+
+        // The rows passed by the bound are emitted.
+        tx_r.push_chunk(StreamChunk::from_pretty(
+            "  I
+            U- 2
+            U+ 4",
+        ));
+        tx_l.push_barrier(3, false);
+        tx_r.push_barrier(3, false);
+        assert_eq!(
+            next_chunk(&mut dynamic_filter).await,
+            StreamChunk::from_pretty(
+                " I  I
+                + 3 11"
+            )
+        );
+        next_barrier(&mut dynamic_filter).await;
+
+        // A row held back is deleted before it's emitted.
+        tx_l.push_chunk(StreamChunk::from_pretty(
+            " I  I
+            - 5 12
+            + 2 13",
+        ));
+        tx_l.push_barrier(4, false);
+        tx_r.push_barrier(4, false);
+        assert_eq!(
+            next_chunk(&mut dynamic_filter).await,
+            StreamChunk::from_pretty(
+                " I  I
+                + 2 13"
+            )
+        );
+        next_barrier(&mut dynamic_filter).await;
+
+        tx_r.push_chunk(StreamChunk::from_pretty(
+            "  I
+            U- 4
+            U+ 6",
+        ));
+        tx_l.push_barrier(5, false);
+        tx_r.push_barrier(5, false);
+        next_barrier(&mut dynamic_filter).await;
+    }
+}
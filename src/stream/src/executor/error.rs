@@ -48,6 +48,9 @@ enum StreamExecutorErrorInner {
     #[error("Source error: {0}")]
     SourceError(RwError),
 
+    #[error("Sink error: {0}")]
+    SinkError(BoxedError),
+
     #[error("Channel `{0}` closed")]
     ChannelClosed(String),
 
@@ -82,6 +85,10 @@ impl StreamExecutorError {
         StreamExecutorErrorInner::SourceError(error.into()).into()
     }
 
+    pub fn sink_error(error: impl Error) -> Self {
+        StreamExecutorErrorInner::SinkError(error.into()).into()
+    }
+
     pub fn channel_closed(name: impl Into<String>) -> Self {
         StreamExecutorErrorInner::ChannelClosed(name.into()).into()
     }
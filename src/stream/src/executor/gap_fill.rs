@@ -0,0 +1,364 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeMap, HashMap};
+
+use futures::{pin_mut, StreamExt};
+use futures_async_stream::try_stream;
+use itertools::Itertools;
+use risingwave_common::array::{Op, Row, StreamChunk};
+use risingwave_common::catalog::{ColumnDesc, ColumnId, Schema};
+use risingwave_common::types::NaiveDateTimeWrapper;
+use risingwave_common::util::gap_fill::GapFiller;
+use risingwave_common::util::sort_util::OrderType;
+use risingwave_storage::table::state_table::StateTable;
+use risingwave_storage::{Keyspace, StateStore};
+
+use super::error::StreamExecutorError;
+use super::{
+    BoxedExecutor, BoxedMessageStream, Executor, ExecutorInfo, Message, PkIndices, PkIndicesRef,
+    StreamExecutorResult,
+};
+
+/// The rows of a partition, keyed by time.
+type Partition = BTreeMap<NaiveDateTimeWrapper, Row>;
+
+/// [`GapFillExecutor`] generates a row for each empty time bucket between the input rows of each
+/// partition, and passes the input rows through.
+///
+/// The input rows are kept in the state table keyed by the partition keys and the time, and cached
+/// in memory by partition. When the rows of some buckets change, the output between the buckets
+/// around them is generated again before and after the change, and the difference is emitted.
+pub struct GapFillExecutor<S: StateStore> {
+    input: Option<BoxedExecutor>,
+    info: ExecutorInfo,
+    filler: GapFiller,
+    state_table: StateTable<S>,
+    partitions: HashMap<Row, Partition>,
+}
+
+impl<S: StateStore> GapFillExecutor<S> {
+    pub fn new(
+        input: BoxedExecutor,
+        filler: GapFiller,
+        keyspace: Keyspace<S>,
+        dist_keys: Vec<usize>,
+        pk_indices: PkIndices,
+        executor_id: u64,
+    ) -> Self {
+        let schema = input.schema().clone();
+
+        // Same as the internal table inferred by the frontend.
+        let column_descs = schema
+            .fields()
+            .iter()
+            .enumerate()
+            .map(|(idx, field)| ColumnDesc::unnamed(ColumnId::from(idx as i32), field.data_type()))
+            .collect_vec();
+        let state_pk_indices = filler
+            .partition_by
+            .iter()
+            .copied()
+            .chain(std::iter::once(filler.time_col))
+            .collect_vec();
+        let state_table = StateTable::new(
+            keyspace,
+            column_descs,
+            vec![OrderType::Ascending; state_pk_indices.len()],
+            Some(dist_keys),
+            state_pk_indices,
+        );
+
+        Self {
+            input: Some(input),
+            info: ExecutorInfo {
+                schema,
+                pk_indices,
+                identity: format!("GapFillExecutor {:X}", executor_id),
+            },
+            filler,
+            state_table,
+            partitions: HashMap::new(),
+        }
+    }
+
+    async fn load_partition(
+        &self,
+        partition_key: &Row,
+        epoch: u64,
+    ) -> StreamExecutorResult<Partition> {
+        let iter = self
+            .state_table
+            .iter_with_pk_prefix(partition_key, epoch)
+            .await?;
+        pin_mut!(iter);
+        let mut partition = Partition::new();
+        while let Some(row) = iter.next().await {
+            let row = row?.into_owned();
+            let time = self
+                .filler
+                .time_of(&row)
+                .map_err(StreamExecutorError::eval_error)?;
+            partition.insert(time, row);
+        }
+        Ok(partition)
+    }
+
+    /// The output of the rows of `partition` between `start` and `end` inclusively, i.e. the rows
+    /// themselves and the rows generated for the gaps between them.
+    fn output(
+        &self,
+        partition: &Partition,
+        start: NaiveDateTimeWrapper,
+        end: NaiveDateTimeWrapper,
+    ) -> StreamExecutorResult<Partition> {
+        let mut output = Partition::new();
+        let mut rows = partition.range(start..=end).peekable();
+        while let Some((&time, row)) = rows.next() {
+            if let Some((_, next)) = rows.peek() {
+                for filled in self
+                    .filler
+                    .fill(row, next)
+                    .map_err(StreamExecutorError::eval_error)?
+                {
+                    let filled_time = self
+                        .filler
+                        .time_of(&filled)
+                        .map_err(StreamExecutorError::eval_error)?;
+                    output.insert(filled_time, filled);
+                }
+            }
+            output.insert(time, row.clone());
+        }
+        Ok(output)
+    }
+
+    /// Applies the changes of a partition, and returns the changes of the output.
+    fn apply_changes(
+        &mut self,
+        partition: &mut Partition,
+        changes: Vec<(Op, NaiveDateTimeWrapper, Row)>,
+    ) -> StreamExecutorResult<Vec<(Op, Row)>> {
+        let first = changes.iter().map(|(_, time, _)| *time).min().unwrap();
+        let last = changes.iter().map(|(_, time, _)| *time).max().unwrap();
+        // The buckets around the changed ones are not changed, so the output beyond them is not
+        // affected.
+        let start = partition
+            .range(..first)
+            .next_back()
+            .map_or(first, |(time, _)| *time);
+        let end = partition
+            .range(last..)
+            .find(|(time, _)| **time > last)
+            .map_or(last, |(time, _)| *time);
+
+        let old_output = self.output(partition, start, end)?;
+        for (op, time, row) in changes {
+            match op {
+                Op::Insert | Op::UpdateInsert => {
+                    partition.insert(time, row.clone());
+                    self.state_table.insert(row)?;
+                }
+                Op::Delete | Op::UpdateDelete => {
+                    partition.remove(&time);
+                    self.state_table.delete(row)?;
+                }
+            }
+        }
+        let new_output = self.output(partition, start, end)?;
+
+        let mut output_changes = vec![];
+        for time in old_output.keys().chain(new_output.keys()).sorted().dedup() {
+            match (old_output.get(time), new_output.get(time)) {
+                (Some(old), Some(new)) if old != new => {
+                    output_changes.push((Op::UpdateDelete, old.clone()));
+                    output_changes.push((Op::UpdateInsert, new.clone()));
+                }
+                (Some(old), None) => output_changes.push((Op::Delete, old.clone())),
+                (None, Some(new)) => output_changes.push((Op::Insert, new.clone())),
+                _ => {}
+            }
+        }
+        Ok(output_changes)
+    }
+
+    #[try_stream(ok = Message, error = StreamExecutorError)]
+    async fn execute_inner(mut self) {
+        let input = self.input.take().unwrap().execute();
+        let data_types = self.info.schema.data_types();
+        let mut epoch = 0;
+
+        #[for_await]
+        for msg in input {
+            match msg? {
+                Message::Chunk(chunk) => {
+                    let mut changes: HashMap<Row, Vec<_>> = HashMap::new();
+                    for (op, row) in chunk.rows() {
+                        let row = row.to_owned_row();
+                        let time = self
+                            .filler
+                            .time_of(&row)
+                            .map_err(StreamExecutorError::eval_error)?;
+                        changes
+                            .entry(row.by_indices(&self.filler.partition_by))
+                            .or_default()
+                            .push((op, time, row));
+                    }
+
+                    let mut output = vec![];
+                    for (partition_key, changes) in changes {
+                        let mut partition = match self.partitions.remove(&partition_key) {
+                            Some(partition) => partition,
+                            None => self.load_partition(&partition_key, epoch).await?,
+                        };
+                        let result = self.apply_changes(&mut partition, changes);
+                        self.partitions.insert(partition_key, partition);
+                        output.extend(result?);
+                    }
+                    if !output.is_empty() {
+                        yield Message::Chunk(StreamChunk::from_rows(&output, &data_types)?);
+                    }
+                }
+                Message::Barrier(barrier) => {
+                    self.state_table
+                        .commit_with_value_meta(barrier.epoch.prev)
+                        .await?;
+                    epoch = barrier.epoch.curr;
+                    yield Message::Barrier(barrier);
+                }
+            }
+        }
+    }
+}
+
+impl<S: StateStore> Executor for GapFillExecutor<S> {
+    fn execute(self: Box<Self>) -> BoxedMessageStream {
+        self.execute_inner().boxed()
+    }
+
+    fn schema(&self) -> &Schema {
+        &self.info.schema
+    }
+
+    fn pk_indices(&self) -> PkIndicesRef {
+        &self.info.pk_indices
+    }
+
+    fn identity(&self) -> &str {
+        &self.info.identity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::stream_chunk::StreamChunkTestExt;
+    use risingwave_common::catalog::{Field, TableId};
+    use risingwave_common::types::{DataType, IntervalUnit};
+    use risingwave_pb::plan_common::GapFillStrategy;
+    use risingwave_storage::memory::MemoryStateStore;
+
+    use super::*;
+    use crate::executor::test_utils::{next_barrier, next_chunk, MessageSender, MockSource};
+
+    /// `(p, window_start, locf(v), interpolate(v))` filled by minute and partitioned by `p`.
+    fn create_executor(
+        keyspace: Keyspace<MemoryStateStore>,
+    ) -> (MessageSender, BoxedMessageStream) {
+        let schema = Schema::new(vec![
+            Field::unnamed(DataType::Int64),
+            Field::unnamed(DataType::Timestamp),
+            Field::unnamed(DataType::Int64),
+            Field::unnamed(DataType::Int64),
+        ]);
+        let (tx, source) = MockSource::channel(schema, vec![0, 1]);
+        let filler = GapFiller::new(
+            1,
+            IntervalUnit::from_minutes(1),
+            vec![0],
+            vec![
+                GapFillStrategy::None,
+                GapFillStrategy::None,
+                GapFillStrategy::Locf,
+                GapFillStrategy::Interpolate,
+            ],
+        );
+        let executor =
+            GapFillExecutor::new(Box::new(source), filler, keyspace, vec![0], vec![0, 1], 1);
+        (tx, Box::new(executor).execute())
+    }
+
+    fn chunk(pretty: &str) -> StreamChunk {
+        StreamChunk::from_pretty(&pretty.replace('^', "2022-2-2T"))
+    }
+
+    #[tokio::test]
+    async fn test_gap_fill() {
+        let keyspace = Keyspace::table_root(MemoryStateStore::new(), &TableId::from(0x2333));
+        let (mut tx, mut gap_fill) = create_executor(keyspace.clone());
+        tx.push_barrier(1, false);
+        next_barrier(&mut gap_fill).await;
+
+        tx.push_chunk(chunk(
+            " I TS        I  I
+            + 1 ^10:00:00 10 10
+            + 1 ^10:03:00 40 40",
+        ));
+        assert_eq!(
+            next_chunk(&mut gap_fill).await,
+            chunk(
+                " I TS        I  I
+                + 1 ^10:00:00 10 10
+                + 1 ^10:01:00 10 20
+                + 1 ^10:02:00 10 30
+                + 1 ^10:03:00 40 40"
+            )
+        );
+        tx.push_barrier(2, false);
+        next_barrier(&mut gap_fill).await;
+
+        // A bucket filling the gap replaces the generated row, and the rows after it are filled
+        // from it, even after the recovery.
+        let (mut tx, mut gap_fill) = create_executor(keyspace);
+        tx.push_barrier(3, false);
+        next_barrier(&mut gap_fill).await;
+        tx.push_chunk(chunk(
+            " I TS        I  I
+            + 1 ^10:01:00 70 70",
+        ));
+        assert_eq!(
+            next_chunk(&mut gap_fill).await,
+            chunk(
+                " I TS        I  I
+                U- 1 ^10:01:00 10 20
+                U+ 1 ^10:01:00 70 70
+                U- 1 ^10:02:00 10 30
+                U+ 1 ^10:02:00 70 55"
+            )
+        );
+
+        // The gaps after the last bucket are retracted with it.
+        tx.push_chunk(chunk(
+            " I TS        I  I
+            - 1 ^10:03:00 40 40",
+        ));
+        assert_eq!(
+            next_chunk(&mut gap_fill).await,
+            chunk(
+                " I TS        I  I
+                - 1 ^10:02:00 70 55
+                - 1 ^10:03:00 40 40"
+            )
+        );
+    }
+}
@@ -68,6 +68,10 @@ pub struct SimpleAggExecutor<S: StateStore> {
     #[allow(dead_code)]
     /// Indices of the columns on which key distribution depends.
     key_indices: Vec<usize>,
+
+    /// The state store, if the executor emits its state when it's created. See
+    /// [`SimpleAggExecutor::with_reemit`].
+    reemit: Option<S>,
 }
 
 impl<S: StateStore> Executor for SimpleAggExecutor<S> {
@@ -126,9 +130,18 @@ impl<S: StateStore> SimpleAggExecutor<S> {
             agg_calls,
             key_indices,
             state_tables,
+            reemit: None,
         })
     }
 
+    /// Emits the state as an insert after the first barrier, if the executor is created by it
+    /// rather than rebuilt in recovery. Used when the state is adopted from the streaming job
+    /// replaced by `ALTER MATERIALIZED VIEW AS`, so that the new downstream receives the result.
+    pub fn with_reemit(mut self, state_store: S) -> Self {
+        self.reemit = Some(state_store);
+        self
+    }
+
     #[allow(clippy::too_many_arguments)]
     async fn apply_chunk(
         agg_calls: &[AggCall],
@@ -241,6 +254,48 @@ impl<S: StateStore> SimpleAggExecutor<S> {
         Ok(Some(chunk))
     }
 
+    /// Reads the state at `epoch`, and emits its output as an insert.
+    async fn reemit_state(
+        schema: &Schema,
+        agg_calls: &[AggCall],
+        input_pk_indices: &[usize],
+        input_schema: &Schema,
+        epoch: u64,
+        state_tables: &[StateTable<S>],
+    ) -> StreamExecutorResult<Option<StreamChunk>> {
+        let input_pk_data_types = input_pk_indices
+            .iter()
+            .map(|idx| input_schema.fields[*idx].data_type.clone())
+            .collect();
+        let mut states = generate_managed_agg_state(
+            None,
+            agg_calls,
+            input_pk_data_types,
+            epoch,
+            None,
+            state_tables,
+        )
+        .await?;
+        // Build the changes as if the state was empty before.
+        states.prev_states = Some(vec![None; agg_calls.len()]);
+
+        let mut builders = schema.create_array_builders(1)?;
+        let mut new_ops = Vec::with_capacity(1);
+        states
+            .build_changes(&mut builders, &mut new_ops, epoch, state_tables)
+            .await?;
+        if new_ops.is_empty() {
+            return Ok(None);
+        }
+
+        let columns: Vec<Column> = builders
+            .into_iter()
+            .map(|builder| builder.finish().map(Into::into))
+            .try_collect()?;
+
+        Ok(Some(StreamChunk::new(new_ops, columns, None)))
+    }
+
     #[try_stream(ok = Message, error = StreamExecutorError)]
     async fn execute_inner(self) {
         let SimpleAggExecutor {
@@ -252,13 +307,34 @@ impl<S: StateStore> SimpleAggExecutor<S> {
             agg_calls,
             key_indices: _,
             mut state_tables,
+            reemit,
         } = self;
         let mut input = input.execute();
 
         let barrier = expect_first_barrier(&mut input).await?;
         let mut epoch = barrier.epoch.curr;
+        let reemit_epoch = barrier.is_add_output().then_some(barrier.epoch.prev);
         yield Message::Barrier(barrier);
 
+        if let Some(state_store) = &reemit
+            && let Some(reemit_epoch) = reemit_epoch
+        {
+            // The adopted state is committed by the replaced job before `reemit_epoch`.
+            state_store.wait_epoch(reemit_epoch).await?;
+            if let Some(chunk) = Self::reemit_state(
+                &info.schema,
+                &agg_calls,
+                &input_pk_indices,
+                &input_schema,
+                reemit_epoch,
+                &state_tables,
+            )
+            .await?
+            {
+                yield Message::Chunk(chunk);
+            }
+        }
+
         #[for_await]
         for msg in input {
             let msg = msg?;
@@ -15,16 +15,17 @@
 use std::marker::PhantomData;
 use std::sync::Arc;
 
-use futures::{stream, StreamExt};
+use futures::{pin_mut, stream, StreamExt};
 use futures_async_stream::try_stream;
 use iter_chunks::IterChunks;
 use itertools::Itertools;
 use madsim::collections::HashMap;
 use risingwave_common::array::column::Column;
-use risingwave_common::array::{StreamChunk, Vis};
+use risingwave_common::array::{DataChunk, Row, StreamChunk, Vis};
 use risingwave_common::buffer::Bitmap;
 use risingwave_common::catalog::Schema;
 use risingwave_common::collection::evictable::EvictableHashMap;
+use risingwave_common::config::default;
 use risingwave_common::error::Result;
 use risingwave_common::hash::{HashCode, HashKey};
 use risingwave_common::util::hash_util::CRC32FastBuilder;
@@ -35,12 +36,14 @@ use super::{
     expect_first_barrier, pk_input_arrays, Executor, PkDataTypes, PkIndicesRef,
     StreamExecutorResult,
 };
+use crate::common::HotKeyDetector;
 use crate::executor::aggregation::{
     agg_input_arrays, generate_agg_schema, generate_managed_agg_state, generate_state_table,
-    AggCall, AggState,
+    AggCall, AggState, ROW_COUNT_COLUMN,
 };
 use crate::executor::error::StreamExecutorError;
-use crate::executor::{BoxedMessageStream, Message, PkIndices, PROCESSING_WINDOW_SIZE};
+use crate::executor::monitor::StreamingMetrics;
+use crate::executor::{BoxedMessageStream, Message, PkIndices, Watermark, PROCESSING_WINDOW_SIZE};
 
 /// [`HashAggExecutor`] could process large amounts of data using a state backend. It works as
 /// follows:
@@ -84,6 +87,20 @@ struct HashAggExecutorExtra<S: StateStore> {
     key_indices: Vec<usize>,
 
     state_tables: Vec<StateTable<S>>,
+
+    /// Watermark of a group key column, with the index of the column in `key_indices`. Late rows
+    /// are dropped and groups older than the watermark are purged at barriers.
+    watermark: Option<(usize, Watermark)>,
+
+    /// Maximum number of groups whose states are fetched concurrently for a chunk.
+    state_fetch_concurrency: usize,
+
+    /// The state store and the vnodes owned by the executor, if it emits the states of all groups
+    /// when it's created. See [`HashAggExecutor::with_reemit`].
+    reemit: Option<(S, Option<Bitmap>)>,
+
+    actor_id: u64,
+    metrics: Arc<StreamingMetrics>,
 }
 
 impl<K: HashKey, S: StateStore> Executor for HashAggExecutor<K, S> {
@@ -105,6 +122,7 @@ impl<K: HashKey, S: StateStore> Executor for HashAggExecutor<K, S> {
 }
 
 impl<K: HashKey, S: StateStore> HashAggExecutor<K, S> {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         input: Box<dyn Executor>,
         agg_calls: Vec<AggCall>,
@@ -112,6 +130,8 @@ impl<K: HashKey, S: StateStore> HashAggExecutor<K, S> {
         pk_indices: PkIndices,
         executor_id: u64,
         key_indices: Vec<usize>,
+        actor_id: u64,
+        metrics: Arc<StreamingMetrics>,
     ) -> Result<Self> {
         let input_info = input.info();
         let schema = generate_agg_schema(input.as_ref(), &agg_calls, Some(&key_indices));
@@ -139,11 +159,65 @@ impl<K: HashKey, S: StateStore> HashAggExecutor<K, S> {
                 agg_calls,
                 key_indices,
                 state_tables,
+                watermark: None,
+                state_fetch_concurrency: default::state_fetch_concurrency(),
+                reemit: None,
+                actor_id,
+                metrics,
             },
             _phantom: PhantomData,
         })
     }
 
+    /// Purges the groups whose `key_index`-th group key falls behind the watermark of the input
+    /// column of that key, which is delayed by `delay_ms`.
+    pub fn with_watermark(mut self, key_index: usize, delay_ms: u64) -> Self {
+        let watermark = Watermark::new(self.extra.key_indices[key_index], delay_ms);
+        self.extra.watermark = Some((key_index, watermark));
+        self
+    }
+
+    /// Sets the maximum number of groups whose states are fetched concurrently for a chunk.
+    pub fn with_state_fetch_concurrency(mut self, concurrency: usize) -> Self {
+        self.extra.state_fetch_concurrency = concurrency;
+        self
+    }
+
+    /// Emits the states of all groups owned by `vnodes` as inserts after the first barrier, if the
+    /// executor is created by it rather than rebuilt in recovery. Used when the states are adopted
+    /// from the streaming job replaced by `ALTER MATERIALIZED VIEW AS`, so that the new downstream
+    /// receives the results of the groups.
+    pub fn with_reemit(mut self, state_store: S, vnodes: Option<Bitmap>) -> Self {
+        self.extra.reemit = Some((state_store, vnodes));
+        self
+    }
+
+    /// Removes the groups older than the watermark from the cache. Their rows are late and never
+    /// update them again.
+    fn purge_expired_groups(
+        extra: &HashAggExecutorExtra<S>,
+        state_map: &mut EvictableHashMap<K, Option<Box<AggState<S>>>>,
+    ) -> StreamExecutorResult<()> {
+        let Some((key_index, watermark)) = &extra.watermark else {
+            return Ok(());
+        };
+        let key_data_types = &extra.schema.data_types()[..extra.key_indices.len()];
+        let mut expired = vec![];
+        for (key, _) in state_map.iter() {
+            let key_row = key
+                .clone()
+                .deserialize(key_data_types.iter())
+                .map_err(StreamExecutorError::eval_error)?;
+            if watermark.is_late(&key_row[*key_index]) {
+                expired.push(key.clone());
+            }
+        }
+        for key in expired {
+            state_map.pop(&key);
+        }
+        Ok(())
+    }
+
     /// Get unique keys, hash codes and visibility map of each key in a batch.
     ///
     /// The returned order is the same as how we get distinct final columns from original columns.
@@ -151,10 +225,12 @@ impl<K: HashKey, S: StateStore> HashAggExecutor<K, S> {
     /// `keys` are Hash Keys of all the rows
     /// `key_hash_codes` are hash codes of the deserialized `keys`
     /// `visibility`, leave invisible ones out of aggregation
+    /// `hot_keys` observes the keys of all visible rows
     fn get_unique_keys(
         keys: Vec<K>,
         key_hash_codes: Vec<HashCode>,
         visibility: &Option<Bitmap>,
+        hot_keys: &mut HotKeyDetector<K>,
     ) -> Result<Vec<(K, HashCode, Bitmap)>> {
         let total_num_rows = keys.len();
         assert_eq!(key_hash_codes.len(), total_num_rows);
@@ -171,9 +247,12 @@ impl<K: HashKey, S: StateStore> HashAggExecutor<K, S> {
         for (row_idx, (key, hash_code)) in keys.iter().zip_eq(key_hash_codes.iter()).enumerate() {
             // if the visibility map has already shadowed this row,
             // then we pass
-            if let Some(vis_map) = visibility && !vis_map.is_set(row_idx)? {
+            if let Some(vis_map) = visibility
+                && !vis_map.is_set(row_idx)?
+            {
                 continue;
             }
+            hot_keys.observe(key);
             let vis_map = key_to_vis_maps.entry(key).or_insert_with(|| {
                 unique_key_and_hash_codes.push((key, hash_code));
                 vec![false; total_num_rows]
@@ -203,9 +282,11 @@ impl<K: HashKey, S: StateStore> HashAggExecutor<K, S> {
             ref input_schema,
             ref schema,
             ref mut state_tables,
+            ref state_fetch_concurrency,
             ..
         }: &mut HashAggExecutorExtra<S>,
         state_map: &mut EvictableHashMap<K, Option<Box<AggState<S>>>>,
+        hot_keys: &mut HotKeyDetector<K>,
         chunk: StreamChunk,
         epoch: u64,
     ) -> StreamExecutorResult<()> {
@@ -223,7 +304,7 @@ impl<K: HashKey, S: StateStore> HashAggExecutor<K, S> {
 
         // --- Find unique keys in this batch and generate visibility map for each key ---
         // TODO: this might be inefficient if there are not too many duplicated keys in one batch.
-        let unique_keys = Self::get_unique_keys(keys, hash_codes, &visibility)
+        let unique_keys = Self::get_unique_keys(keys, hash_codes, &visibility, hot_keys)
             .map_err(StreamExecutorError::eval_error)?;
 
         // --- Retrieve all aggregation inputs in advance ---
@@ -285,7 +366,9 @@ impl<K: HashKey, S: StateStore> HashAggExecutor<K, S> {
             });
         }
 
-        let mut buffered = stream::iter(futures).buffer_unordered(10).fuse();
+        let mut buffered = stream::iter(futures)
+            .buffer_unordered((*state_fetch_concurrency).max(1))
+            .fuse();
 
         while let Some(result) = buffered.next().await {
             let (key, state) = result?;
@@ -412,6 +495,91 @@ impl<K: HashKey, S: StateStore> HashAggExecutor<K, S> {
         }
     }
 
+    /// Reads the states of all groups owned by the executor at `epoch`, and emits their outputs as
+    /// inserts.
+    #[try_stream(ok = StreamChunk, error = StreamExecutorError)]
+    async fn reemit_states<'a>(
+        &HashAggExecutorExtra::<S> {
+            ref key_indices,
+            ref agg_calls,
+            ref input_pk_indices,
+            ref input_schema,
+            ref schema,
+            ref state_tables,
+            ref reemit,
+            ..
+        }: &'a HashAggExecutorExtra<S>,
+        epoch: u64,
+    ) {
+        let Some((state_store, vnodes)) = reemit else {
+            return Ok(());
+        };
+        // The adopted states are committed by the replaced job before `epoch`.
+        state_store.wait_epoch(epoch).await?;
+
+        let key_len = key_indices.len();
+        let key_data_types = &schema.data_types()[..key_len];
+        let key_column_indices = (0..key_len).collect_vec();
+        let input_pk_data_types: PkDataTypes = input_pk_indices
+            .iter()
+            .map(|idx| input_schema.fields[*idx].data_type.clone())
+            .collect();
+
+        // Every group has a row count, keyed by the group key.
+        let groups = state_tables[ROW_COUNT_COLUMN].iter(epoch).await?;
+        pin_mut!(groups);
+        let mut batches = groups.chunks(PROCESSING_WINDOW_SIZE);
+        while let Some(batch) = batches.next().await {
+            let keys: Vec<Row> = batch
+                .into_iter()
+                .map(|row| row.map(|row| Row(row.0[..key_len].to_vec())))
+                .try_collect()?;
+            let hash_codes = DataChunk::from_rows(&keys, key_data_types)?
+                .get_hash_values(&key_column_indices, CRC32FastBuilder)?;
+
+            let mut builders = schema.create_array_builders(keys.len())?;
+            let mut new_ops = Vec::with_capacity(keys.len());
+            for (key, hash_code) in keys.iter().zip_eq(hash_codes) {
+                if let Some(vnodes) = vnodes
+                    && !vnodes
+                        .is_set(hash_code.0 as usize % vnodes.len())
+                        .unwrap_or(false)
+                {
+                    continue;
+                }
+                let mut states = generate_managed_agg_state(
+                    Some(key),
+                    agg_calls,
+                    input_pk_data_types.clone(),
+                    epoch,
+                    Some(hash_code),
+                    state_tables,
+                )
+                .await?;
+                // Build the changes as if the group was empty before.
+                states.prev_states = Some(vec![None; agg_calls.len()]);
+                let appended = states
+                    .build_changes(&mut builders[key_len..], &mut new_ops, epoch, state_tables)
+                    .await?;
+                for _ in 0..appended {
+                    for (builder, datum) in builders[..key_len].iter_mut().zip_eq(&key.0) {
+                        builder.append_datum(datum)?;
+                    }
+                }
+            }
+            if new_ops.is_empty() {
+                continue;
+            }
+
+            let columns: Vec<Column> = builders
+                .into_iter()
+                .map(|builder| -> Result<_> { Ok(Column::new(Arc::new(builder.finish()?))) })
+                .try_collect()
+                .map_err(StreamExecutorError::eval_error)?;
+            yield StreamChunk::new(new_ops, columns, None);
+        }
+    }
+
     #[try_stream(ok = Message, error = StreamExecutorError)]
     async fn execute_inner(self) {
         let HashAggExecutor {
@@ -420,18 +588,32 @@ impl<K: HashKey, S: StateStore> HashAggExecutor<K, S> {
 
         // The cached states. `HashKey -> (prev_value, value)`.
         let mut state_map = EvictableHashMap::new(1 << 16);
+        let mut hot_keys = HotKeyDetector::default();
 
         let mut input = input.execute();
         let barrier = expect_first_barrier(&mut input).await?;
         let mut epoch = barrier.epoch.curr;
+        let reemit_epoch = barrier.is_add_output().then_some(barrier.epoch.prev);
         yield Message::Barrier(barrier);
 
+        if let Some(reemit_epoch) = reemit_epoch {
+            #[for_await]
+            for chunk in Self::reemit_states(&extra, reemit_epoch) {
+                yield Message::Chunk(chunk?);
+            }
+        }
+
         #[for_await]
         for msg in input {
             let msg = msg?;
             match msg {
                 Message::Chunk(chunk) => {
-                    Self::apply_chunk(&mut extra, &mut state_map, chunk, epoch).await?;
+                    let chunk = match &mut extra.watermark {
+                        Some((_, watermark)) => watermark.filter_late_rows(chunk),
+                        None => chunk,
+                    };
+                    Self::apply_chunk(&mut extra, &mut state_map, &mut hot_keys, chunk, epoch)
+                        .await?;
                 }
                 Message::Barrier(barrier) => {
                     let next_epoch = barrier.epoch.curr;
@@ -441,6 +623,14 @@ impl<K: HashKey, S: StateStore> HashAggExecutor<K, S> {
                     for chunk in Self::flush_data(&mut extra, &mut state_map, epoch) {
                         yield Message::Chunk(chunk?);
                     }
+                    Self::purge_expired_groups(&extra, &mut state_map)?;
+                    hot_keys.report(
+                        &extra
+                            .metrics
+                            .hot_key_ratio
+                            .with_label_values(&[&extra.actor_id.to_string(), "hash_agg"]),
+                        &extra.identity,
+                    );
 
                     yield Message::Barrier(barrier);
                     epoch = next_epoch;
@@ -468,6 +658,7 @@ mod tests {
     use risingwave_storage::{Keyspace, StateStore};
 
     use crate::executor::aggregation::{AggArgs, AggCall};
+    use crate::executor::monitor::StreamingMetrics;
     use crate::executor::test_utils::*;
     use crate::executor::{Executor, HashAggExecutor, Message, PkIndices};
 
@@ -494,6 +685,8 @@ mod tests {
                 args.pk_indices,
                 args.executor_id,
                 args.key_indices,
+                0,
+                Arc::new(StreamingMetrics::unused()),
             )?))
         }
     }
@@ -20,6 +20,7 @@ use itertools::Itertools;
 use madsim::collections::HashSet;
 use risingwave_common::array::{Array, ArrayRef, Op, Row, RowRef, StreamChunk};
 use risingwave_common::catalog::Schema;
+use risingwave_common::config::default;
 use risingwave_common::error::{internal_error, Result, RwError};
 use risingwave_common::hash::HashKey;
 use risingwave_common::types::{DataType, ToOwnedDatum};
@@ -30,7 +31,9 @@ use super::barrier_align::*;
 use super::error::StreamExecutorError;
 use super::managed_state::join::*;
 use super::monitor::StreamingMetrics;
-use super::{BoxedExecutor, BoxedMessageStream, Executor, Message, PkIndices, PkIndicesRef};
+use super::{
+    BoxedExecutor, BoxedMessageStream, Executor, Message, PkIndices, PkIndicesRef, Watermark,
+};
 use crate::common::StreamChunkBuilder;
 use crate::executor::PROCESSING_WINDOW_SIZE;
 
@@ -131,6 +134,9 @@ struct JoinSide<K: HashKey, S: StateStore> {
     col_types: Vec<DataType>,
     /// The start position for the side in output new columns
     start_pos: usize,
+    /// Watermark of a join key column, with the index of the column in `key_indices`. Late rows
+    /// of this side are dropped, and the other side's state behind it is purged at barriers.
+    watermark: Option<(usize, Watermark)>,
 }
 
 impl<K: HashKey, S: StateStore> std::fmt::Debug for JoinSide<K, S> {
@@ -195,6 +201,9 @@ pub struct HashJoinExecutor<K: HashKey, S: StateStore, const T: JoinTypePrimitiv
     /// Whether the logic can be optimized for append-only stream
     append_only_optimize: bool,
 
+    /// Maximum number of join keys whose states are fetched concurrently for a chunk.
+    state_fetch_concurrency: usize,
+
     actor_id: u64,
     metrics: Arc<StreamingMetrics>,
 }
@@ -454,6 +463,7 @@ impl<K: HashKey, S: StateStore, const T: JoinTypePrimitive> HashJoinExecutor<K,
                 col_types: col_l_datatypes,
                 pk_indices: pk_indices_l,
                 start_pos: 0,
+                watermark: None,
             },
             side_r: JoinSide {
                 ht: JoinHashMap::new(
@@ -471,6 +481,7 @@ impl<K: HashKey, S: StateStore, const T: JoinTypePrimitive> HashJoinExecutor<K,
                 col_types: col_r_datatypes,
                 pk_indices: pk_indices_r,
                 start_pos: side_l_column_n,
+                watermark: None,
             },
             pk_indices,
             output_indices,
@@ -479,11 +490,40 @@ impl<K: HashKey, S: StateStore, const T: JoinTypePrimitive> HashJoinExecutor<K,
             op_info,
             epoch: 0,
             append_only_optimize,
+            state_fetch_concurrency: default::state_fetch_concurrency(),
             actor_id,
             metrics,
         }
     }
 
+    /// Sets the maximum number of join keys whose states are fetched concurrently for a chunk.
+    pub fn with_state_fetch_concurrency(mut self, concurrency: usize) -> Self {
+        self.state_fetch_concurrency = concurrency;
+        self
+    }
+
+    /// Sets the watermarks of the join keys of each side, as `(key_index, delay_ms)`. Only valid
+    /// for inner joins, where the rows of a key behind a watermark can never match again.
+    pub fn with_watermarks(
+        mut self,
+        left: Option<(usize, u64)>,
+        right: Option<(usize, u64)>,
+    ) -> Self {
+        assert!(
+            T == JoinType::Inner || (left.is_none() && right.is_none()),
+            "watermark cleanup is only supported for inner joins"
+        );
+        for (side, watermark) in [(&mut self.side_l, left), (&mut self.side_r, right)] {
+            side.watermark = watermark.map(|(key_index, delay_ms)| {
+                (
+                    key_index,
+                    Watermark::new(side.key_indices[key_index], delay_ms),
+                )
+            });
+        }
+        self
+    }
+
     #[try_stream(ok = Message, error = StreamExecutorError)]
     async fn into_stream(mut self) {
         let input_l = self.input_l.take().unwrap();
@@ -498,6 +538,10 @@ impl<K: HashKey, S: StateStore, const T: JoinTypePrimitive> HashJoinExecutor<K,
         for msg in aligned_stream {
             match msg? {
                 AlignedMessage::Left(chunk) => {
+                    let chunk = match &mut self.side_l.watermark {
+                        Some((_, watermark)) => watermark.filter_late_rows(chunk),
+                        None => chunk,
+                    };
                     #[for_await]
                     for chunk in Self::eq_join_oneside::<{ SideType::Left }>(
                         &mut self.side_l,
@@ -506,6 +550,7 @@ impl<K: HashKey, S: StateStore, const T: JoinTypePrimitive> HashJoinExecutor<K,
                         &mut self.cond,
                         chunk,
                         self.append_only_optimize,
+                        self.state_fetch_concurrency,
                     ) {
                         yield chunk.map_err(StreamExecutorError::hash_join_error).map(
                             |v| match v {
@@ -518,6 +563,10 @@ impl<K: HashKey, S: StateStore, const T: JoinTypePrimitive> HashJoinExecutor<K,
                     }
                 }
                 AlignedMessage::Right(chunk) => {
+                    let chunk = match &mut self.side_r.watermark {
+                        Some((_, watermark)) => watermark.filter_late_rows(chunk),
+                        None => chunk,
+                    };
                     #[for_await]
                     for chunk in Self::eq_join_oneside::<{ SideType::Right }>(
                         &mut self.side_l,
@@ -526,6 +575,7 @@ impl<K: HashKey, S: StateStore, const T: JoinTypePrimitive> HashJoinExecutor<K,
                         &mut self.cond,
                         chunk,
                         self.append_only_optimize,
+                        self.state_fetch_concurrency,
                     ) {
                         yield chunk.map_err(StreamExecutorError::hash_join_error).map(
                             |v| match v {
@@ -552,6 +602,19 @@ impl<K: HashKey, S: StateStore, const T: JoinTypePrimitive> HashJoinExecutor<K,
     }
 
     async fn flush_data(&mut self) -> Result<()> {
+        // Rows behind the watermark of one side never arrive, so the keys of the other side behind
+        // it can no longer be matched.
+        if let Some((key_index, watermark)) = &self.side_l.watermark {
+            self.side_r
+                .ht
+                .purge_expired(|key| watermark.is_late(&key[*key_index]))?;
+        }
+        if let Some((key_index, watermark)) = &self.side_r.watermark {
+            self.side_l
+                .ht
+                .purge_expired(|key| watermark.is_late(&key[*key_index]))?;
+        }
+
         self.side_l.ht.flush().await?;
         self.side_r.ht.flush().await?;
 
@@ -608,6 +671,7 @@ impl<K: HashKey, S: StateStore, const T: JoinTypePrimitive> HashJoinExecutor<K,
         cond: &'a mut Option<RowExpression>,
         chunk: StreamChunk,
         append_only_optimize: bool,
+        state_fetch_concurrency: usize,
     ) {
         let chunk = chunk.compact()?;
         let (data_chunk, ops) = chunk.into_parts();
@@ -651,6 +715,11 @@ impl<K: HashKey, S: StateStore, const T: JoinTypePrimitive> HashJoinExecutor<K,
         };
 
         let keys = K::build(&side_update.key_indices, &data_chunk)?;
+        // Fetch the states of all keys in the chunk concurrently instead of one by one below.
+        side_match
+            .ht
+            .prefetch_states(&keys, state_fetch_concurrency)
+            .await?;
         for (idx, (row, op)) in data_chunk.rows().zip_eq(ops.iter()).enumerate() {
             let key = &keys[idx];
             let value = row.to_owned_row();
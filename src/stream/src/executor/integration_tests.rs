@@ -77,6 +77,8 @@ async fn test_merger_sum_aggr() {
         let actor = Actor::new(
             consumer,
             0,
+            0,
+            0,
             context,
             StreamingMetrics::unused().into(),
             ActorContext::create(),
@@ -121,11 +123,14 @@ async fn test_merger_sum_aggr() {
         ))],
         0,
         ctx,
+        ActorContext::create(),
     );
     let context = SharedContext::for_test().into();
     let actor = Actor::new(
         dispatcher,
         0,
+        0,
+        0,
         context,
         StreamingMetrics::unused().into(),
         ActorContext::create(),
@@ -179,6 +184,8 @@ async fn test_merger_sum_aggr() {
     let actor = Actor::new(
         consumer,
         0,
+        0,
+        0,
         context,
         StreamingMetrics::unused().into(),
         ActorContext::create(),
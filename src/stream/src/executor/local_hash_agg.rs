@@ -0,0 +1,306 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use futures::StreamExt;
+use futures_async_stream::try_stream;
+use itertools::Itertools;
+use risingwave_common::array::column::Column;
+use risingwave_common::array::{Op, Row, StreamChunk};
+use risingwave_common::buffer::Bitmap;
+use risingwave_common::catalog::Schema;
+use risingwave_common::error::Result;
+
+use super::aggregation::{
+    create_streaming_agg_state, generate_agg_schema, AggCall, StreamingAggStateImpl,
+};
+use super::error::StreamExecutorError;
+use super::*;
+
+/// [`LocalHashAggExecutor`] is the local phase of a two-phase hash aggregation. It aggregates the
+/// input by group keys in memory without any state, and emits the partial results of the groups
+/// changed in an epoch as inserts upon each barrier. The global [`HashAggExecutor`] downstream
+/// combines the partial results after the shuffle, so that a hot group key is shuffled at most once
+/// per epoch from each local aggregator.
+pub struct LocalHashAggExecutor {
+    pub(super) input: Box<dyn Executor>,
+    pub(super) info: ExecutorInfo,
+    pub(super) agg_calls: Vec<AggCall>,
+    pub(super) key_indices: Vec<usize>,
+}
+
+impl Executor for LocalHashAggExecutor {
+    fn execute(self: Box<Self>) -> BoxedMessageStream {
+        self.execute_inner().boxed()
+    }
+
+    fn schema(&self) -> &Schema {
+        &self.info.schema
+    }
+
+    fn pk_indices(&self) -> PkIndicesRef {
+        &self.info.pk_indices
+    }
+
+    fn identity(&self) -> &str {
+        &self.info.identity
+    }
+}
+
+type GroupStates = Vec<Box<dyn StreamingAggStateImpl>>;
+
+impl LocalHashAggExecutor {
+    fn create_states(agg_calls: &[AggCall]) -> StreamExecutorResult<GroupStates> {
+        agg_calls
+            .iter()
+            .map(|agg_call| {
+                create_streaming_agg_state(
+                    agg_call.args.arg_types(),
+                    &agg_call.kind,
+                    &agg_call.return_type,
+                    None,
+                )
+            })
+            .try_collect()
+    }
+
+    fn apply_chunk(
+        agg_calls: &[AggCall],
+        key_indices: &[usize],
+        groups: &mut HashMap<Row, GroupStates>,
+        chunk: StreamChunk,
+    ) -> StreamExecutorResult<()> {
+        let (ops, columns, visibility) = chunk.into_inner();
+
+        // Generate the visibility map of each group key in this chunk.
+        let mut key_to_vis_maps: HashMap<Row, Vec<bool>> = HashMap::new();
+        for row_idx in 0..ops.len() {
+            if let Some(vis_map) = &visibility && !vis_map.is_set(row_idx)? {
+                continue;
+            }
+            let key = Row(key_indices
+                .iter()
+                .map(|idx| columns[*idx].array_ref().datum_at(row_idx))
+                .collect());
+            key_to_vis_maps
+                .entry(key)
+                .or_insert_with(|| vec![false; ops.len()])[row_idx] = true;
+        }
+
+        for (key, vis_map) in key_to_vis_maps {
+            let vis_map: Bitmap = vis_map.try_into()?;
+            let states = match groups.entry(key) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => entry.insert(Self::create_states(agg_calls)?),
+            };
+            agg_calls
+                .iter()
+                .zip_eq(states.iter_mut())
+                .try_for_each(|(agg_call, state)| {
+                    let cols = agg_call
+                        .args
+                        .val_indices()
+                        .iter()
+                        .map(|idx| columns[*idx].array_ref())
+                        .collect_vec();
+                    state.apply_batch(&ops, Some(&vis_map), &cols[..])
+                })?;
+        }
+        Ok(())
+    }
+
+    /// Builds a chunk of the partial results of `groups`.
+    fn build_chunk(
+        schema: &Schema,
+        groups: &[(Row, GroupStates)],
+    ) -> StreamExecutorResult<StreamChunk> {
+        let mut builders = schema
+            .create_array_builders(groups.len())
+            .map_err(StreamExecutorError::eval_error)?;
+        for (key, states) in groups {
+            let key_len = key.0.len();
+            for (builder, datum) in builders[..key_len].iter_mut().zip_eq(key.0.iter()) {
+                builder.append_datum(datum)?;
+            }
+            for (builder, state) in builders[key_len..].iter_mut().zip_eq(states.iter()) {
+                builder.append_datum(&state.get_output()?)?;
+            }
+        }
+        let columns: Vec<Column> = builders
+            .into_iter()
+            .map(|builder| -> Result<_> { Ok(Column::new(Arc::new(builder.finish()?))) })
+            .try_collect()
+            .map_err(StreamExecutorError::eval_error)?;
+        let ops = vec![Op::Insert; groups.len()];
+
+        Ok(StreamChunk::new(ops, columns, None))
+    }
+
+    #[try_stream(ok = Message, error = StreamExecutorError)]
+    async fn execute_inner(self) {
+        let LocalHashAggExecutor {
+            input,
+            info,
+            agg_calls,
+            key_indices,
+        } = self;
+        let input = input.execute();
+        let mut groups = HashMap::new();
+
+        #[for_await]
+        for msg in input {
+            let msg = msg?;
+            match msg {
+                Message::Chunk(chunk) => {
+                    Self::apply_chunk(&agg_calls, &key_indices, &mut groups, chunk)?;
+                }
+                m @ Message::Barrier(_) => {
+                    // The partial results are not kept across epochs.
+                    let dirty_groups = std::mem::take(&mut groups).into_iter().collect_vec();
+                    for batch in dirty_groups.chunks(PROCESSING_WINDOW_SIZE) {
+                        yield Message::Chunk(Self::build_chunk(&info.schema, batch)?);
+                    }
+
+                    yield m;
+                }
+            }
+        }
+    }
+}
+
+impl LocalHashAggExecutor {
+    pub fn new(
+        input: Box<dyn Executor>,
+        agg_calls: Vec<AggCall>,
+        pk_indices: PkIndices,
+        executor_id: u64,
+        key_indices: Vec<usize>,
+    ) -> Result<Self> {
+        let schema = generate_agg_schema(input.as_ref(), &agg_calls, Some(&key_indices));
+        let info = ExecutorInfo {
+            schema,
+            pk_indices,
+            identity: format!("LocalHashAggExecutor-{:X}", executor_id),
+        };
+
+        Ok(LocalHashAggExecutor {
+            input,
+            info,
+            agg_calls,
+            key_indices,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use futures::StreamExt;
+    use risingwave_common::array::stream_chunk::StreamChunkTestExt;
+    use risingwave_common::array::StreamChunk;
+    use risingwave_common::catalog::schema_test_utils;
+    use risingwave_common::error::Result;
+    use risingwave_common::types::DataType;
+    use risingwave_expr::expr::AggKind;
+
+    use super::*;
+    use crate::executor::aggregation::{AggArgs, AggCall};
+    use crate::executor::test_utils::MockSource;
+    use crate::executor::{Executor, LocalHashAggExecutor};
+
+    #[tokio::test]
+    async fn test_local_hash_agg() -> Result<()> {
+        let schema = schema_test_utils::iii();
+        let (mut tx, source) = MockSource::channel(schema, vec![2]);
+        tx.push_barrier(1, false);
+        tx.push_chunk(StreamChunk::from_pretty(
+            " I   I    I
+            + 1 200 1001
+            + 1  14 1002 D
+            + 1 300 1003",
+        ));
+        tx.push_barrier(2, false);
+        tx.push_chunk(StreamChunk::from_pretty(
+            " I   I    I
+            - 1 200 1001
+            + 1 500 1004",
+        ));
+        tx.push_barrier(3, false);
+        tx.push_barrier(4, false);
+
+        let agg_calls = vec![
+            AggCall {
+                kind: AggKind::RowCount,
+                args: AggArgs::None,
+                return_type: DataType::Int64,
+                append_only: false,
+            },
+            AggCall {
+                kind: AggKind::Sum,
+                args: AggArgs::Unary(DataType::Int64, 1),
+                return_type: DataType::Int64,
+                append_only: false,
+            },
+        ];
+
+        let local_agg = Box::new(LocalHashAggExecutor::new(
+            Box::new(source),
+            agg_calls,
+            vec![],
+            1,
+            vec![0],
+        )?);
+        let mut local_agg = local_agg.execute();
+
+        // Consume the init barrier
+        local_agg.next().await.unwrap().unwrap();
+        let msg = local_agg.next().await.unwrap().unwrap();
+        assert_eq!(
+            msg.into_chunk().unwrap(),
+            StreamChunk::from_pretty(
+                " I I   I
+                + 1 2 500"
+            )
+        );
+        assert_matches!(
+            local_agg.next().await.unwrap().unwrap(),
+            Message::Barrier { .. }
+        );
+
+        // Only the changes of this epoch are emitted.
+        let msg = local_agg.next().await.unwrap().unwrap();
+        assert_eq!(
+            msg.into_chunk().unwrap(),
+            StreamChunk::from_pretty(
+                " I I   I
+                + 1 0 300"
+            )
+        );
+        assert_matches!(
+            local_agg.next().await.unwrap().unwrap(),
+            Message::Barrier { .. }
+        );
+
+        // Nothing changed in this epoch.
+        assert_matches!(
+            local_agg.next().await.unwrap().unwrap(),
+            Message::Barrier { .. }
+        );
+
+        Ok(())
+    }
+}
@@ -22,6 +22,7 @@ use risingwave_common::types::DataType;
 use risingwave_common::util::ordered::{deserialize_column_id, SENTINEL_CELL_ID};
 use risingwave_common::util::sort_util::{OrderPair, OrderType};
 use risingwave_common::util::value_encoding::deserialize_cell;
+use risingwave_pb::plan_common::HandleConflictBehavior;
 use risingwave_storage::memory::MemoryStateStore;
 use risingwave_storage::{Keyspace, StateStore};
 
@@ -132,6 +133,7 @@ fn create_arrangement(
         column_ids,
         1,
         vec![0usize],
+        HandleConflictBehavior::NoCheck,
     ))
 }
 
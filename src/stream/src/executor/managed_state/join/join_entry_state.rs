@@ -50,7 +50,6 @@ impl JoinEntryState {
         self.cached.iter()
     }
 
-    #[allow(dead_code)]
     pub fn values(&mut self) -> JoinEntryStateValues<'_> {
         self.cached.values()
     }
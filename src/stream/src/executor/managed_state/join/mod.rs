@@ -18,7 +18,7 @@ use std::collections::BTreeMap;
 use std::ops::{Deref, DerefMut, Index};
 use std::sync::Arc;
 
-use futures::pin_mut;
+use futures::{pin_mut, StreamExt, TryStreamExt};
 use futures_async_stream::for_await;
 use itertools::Itertools;
 pub use join_entry_state::JoinEntryState;
@@ -33,6 +33,7 @@ use risingwave_storage::table::state_table::StateTable;
 use risingwave_storage::{Keyspace, StateStore};
 use stats_alloc::{SharedStatsAlloc, StatsAlloc};
 
+use crate::common::HotKeyDetector;
 use crate::executor::monitor::StreamingMetrics;
 
 type DegreeType = u64;
@@ -169,6 +170,8 @@ pub struct JoinHashMap<K: HashKey, S: StateStore> {
     state_table: StateTable<S>,
     /// Metrics of the hash map
     metrics: JoinHashMapMetrics,
+    /// Detector of the join keys taking most of the rows of an epoch
+    hot_keys: HotKeyDetector<K>,
 }
 
 impl<K: HashKey, S: StateStore> JoinHashMap<K, S> {
@@ -224,6 +227,7 @@ impl<K: HashKey, S: StateStore> JoinHashMap<K, S> {
             state_table,
             alloc,
             metrics: JoinHashMapMetrics::new(metrics, actor_id, side),
+            hot_keys: HotKeyDetector::default(),
         }
     }
 
@@ -293,6 +297,32 @@ impl<K: HashKey, S: StateStore> JoinHashMap<K, S> {
         }
     }
 
+    /// Fetches the states of the `keys` missing in memory from the state store, at most
+    /// `concurrency` at a time, so that the following [`Self::remove_state`] calls for them hit
+    /// the cache. Keys containing nulls never match and are skipped.
+    pub async fn prefetch_states(&mut self, keys: &[K], concurrency: usize) -> RwResult<()> {
+        let missing = keys
+            .iter()
+            .filter(|key| !key.has_null() && !self.inner.contains(key))
+            .unique()
+            .collect_vec();
+        if missing.is_empty() {
+            return Ok(());
+        }
+        self.metrics.lookup_miss_count += missing.len();
+        // `buffered` yields the states in the order of `missing`, though they are fetched
+        // concurrently.
+        let states: Vec<JoinEntryState> =
+            futures::stream::iter(missing.iter().map(|key| self.fetch_cached_state(key)))
+                .buffered(concurrency.max(1))
+                .try_collect()
+                .await?;
+        for (key, state) in missing.into_iter().zip_eq(states) {
+            self.inner.put(key.clone(), state);
+        }
+        Ok(())
+    }
+
     /// Fetch cache from the state store. Should only be called if the key does not exist in memory.
     /// Will return a empty `JoinEntryState` even when state does not exist in remote.
     async fn fetch_cached_state(&self, key: &K) -> RwResult<JoinEntryState> {
@@ -317,6 +347,15 @@ impl<K: HashKey, S: StateStore> JoinHashMap<K, S> {
 
     pub async fn flush(&mut self) -> RwResult<()> {
         self.metrics.flush();
+        let executor = format!("hash_join_{}", self.metrics.side);
+        self.hot_keys.report(
+            &self
+                .metrics
+                .metrics
+                .hot_key_ratio
+                .with_label_values(&[&self.metrics.actor_id, &executor]),
+            &executor,
+        );
         self.state_table
             .commit_with_value_meta(self.current_epoch)
             .await
@@ -325,6 +364,7 @@ impl<K: HashKey, S: StateStore> JoinHashMap<K, S> {
 
     /// Insert a key
     pub fn insert(&mut self, join_key: &K, pk: Row, value: JoinRow) -> RwResult<()> {
+        self.hot_keys.observe(join_key);
         if let Some(entry) = self.inner.get_mut(join_key) {
             entry.insert(pk, value.clone());
         }
@@ -336,6 +376,7 @@ impl<K: HashKey, S: StateStore> JoinHashMap<K, S> {
 
     /// Delete a key
     pub fn delete(&mut self, join_key: &K, pk: Row, value: JoinRow) -> RwResult<()> {
+        self.hot_keys.observe(join_key);
         if let Some(entry) = self.inner.get_mut(join_key) {
             entry.remove(pk);
         }
@@ -345,6 +386,27 @@ impl<K: HashKey, S: StateStore> JoinHashMap<K, S> {
         Ok(())
     }
 
+    /// Removes the cached join keys satisfying `is_expired` and deletes their rows from the state
+    /// table, returning the number of purged keys. Rows of join keys not in the cache are left in
+    /// the state table.
+    pub fn purge_expired(&mut self, mut is_expired: impl FnMut(&Row) -> bool) -> RwResult<usize> {
+        let mut expired = vec![];
+        for (key, _) in self.inner.iter() {
+            let key_row = key.clone().deserialize(self.join_key_data_types.iter())?;
+            if is_expired(&key_row) {
+                expired.push(key.clone());
+            }
+        }
+        for key in &expired {
+            if let Some(mut state) = self.inner.pop(key) {
+                for value in state.values() {
+                    self.state_table.delete(value.clone().into_row())?;
+                }
+            }
+        }
+        Ok(expired.len())
+    }
+
     /// Insert a [`JoinEntryState`]
     pub fn insert_state(&mut self, key: &K, state: JoinEntryState) {
         self.inner.put(key.clone(), state);
@@ -382,3 +444,73 @@ impl<K: HashKey, S: StateStore> DerefMut for JoinHashMap<K, S> {
         &mut self.inner
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::{DataChunk, DataChunkTestExt};
+    use risingwave_common::catalog::TableId;
+    use risingwave_common::hash::Key64;
+    use risingwave_storage::memory::MemoryStateStore;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_prefetch_states() {
+        let keyspace = Keyspace::table_root(MemoryStateStore::new(), &TableId::new(0));
+        let create_map = || {
+            JoinHashMap::<Key64, _>::new(
+                1024,
+                vec![1],
+                vec![0],
+                vec![DataType::Int64, DataType::Int64],
+                keyspace.clone(),
+                None,
+                Arc::new(StreamingMetrics::unused()),
+                0,
+                "left",
+            )
+        };
+        let chunk = DataChunk::from_pretty(
+            "I I
+             1 10
+             2 20
+             1 11
+             3 30
+             . 40",
+        );
+        let keys = Key64::build(&[0], &chunk).unwrap();
+
+        let mut map = create_map();
+        map.update_epoch(1);
+        for (key, row) in keys.iter().zip_eq(chunk.rows()) {
+            let row = row.to_owned_row();
+            map.insert(key, row.by_indices(&[1]), JoinRow::new(row, 0))
+                .unwrap();
+        }
+        map.flush().await.unwrap();
+
+        // A new map starts with an empty cache, which is filled by the prefetch.
+        let mut map = create_map();
+        map.update_epoch(2);
+        map.prefetch_states(&keys, 2).await.unwrap();
+        assert_eq!(map.len(), 3);
+        assert!(!map.contains(&keys[4]));
+
+        // Each state is put to the cache of its own key, though they're fetched concurrently.
+        let row = |key: i64, pk: i64| {
+            Row(vec![
+                Some(ScalarImpl::Int64(key)),
+                Some(ScalarImpl::Int64(pk)),
+            ])
+        };
+        for (idx, expected) in [
+            (0, vec![row(1, 10), row(1, 11)]),
+            (1, vec![row(2, 20)]),
+            (3, vec![row(3, 30)]),
+        ] {
+            let mut state = map.pop(&keys[idx]).unwrap();
+            let rows = state.values().map(|value| value.row.clone()).collect_vec();
+            assert_eq!(rows, expected);
+        }
+    }
+}
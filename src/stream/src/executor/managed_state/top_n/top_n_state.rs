@@ -20,6 +20,7 @@ use itertools::Itertools;
 use madsim::collections::BTreeMap;
 use risingwave_common::array::Row;
 use risingwave_common::catalog::{ColumnDesc, ColumnId};
+use risingwave_common::collection::evictable::scaled_capacity;
 use risingwave_common::types::DataType;
 use risingwave_common::util::ordered::*;
 use risingwave_common::util::sort_util::OrderType;
@@ -103,8 +104,11 @@ impl<S: StateStore, const TOP_N_TYPE: usize> ManagedTopNState<S, TOP_N_TYPE> {
         self.state_table.is_dirty()
     }
 
+    /// Evicts entries beyond `top_n_count` from the cache, or fewer under memory pressure. The
+    /// cache is refilled from the state table when it runs empty.
     pub fn retain_top_n(&mut self) {
         if let Some(count) = self.top_n_count {
+            let count = scaled_capacity(count);
             while self.top_n.len() > count {
                 match TOP_N_TYPE {
                     TOP_N_MIN => {
@@ -206,9 +210,11 @@ impl<S: StateStore, const TOP_N_TYPE: usize> ManagedTopNState<S, TOP_N_TYPE> {
         pin_mut!(state_table_iter);
 
         loop {
-            if let Some(top_n_count) = self.top_n_count && self.top_n.len() >= top_n_count {
-                    break;
-                }
+            if let Some(top_n_count) = self.top_n_count
+                && self.top_n.len() >= top_n_count
+            {
+                break;
+            }
             match state_table_iter.next().await {
                 Some(next_res) => {
                     let row = next_res.unwrap().into_owned();
@@ -269,7 +275,9 @@ impl<S: StateStore, const TOP_N_TYPE: usize> ManagedTopNState<S, TOP_N_TYPE> {
             if let Some(prev_row) = prev_row {
                 debug_assert_eq!(prev_row, row);
             }
-            if let Some(top_n_count) = self.top_n_count && top_n_count == self.top_n.len() {
+            if let Some(top_n_count) = self.top_n_count
+                && top_n_count == self.top_n.len()
+            {
                 break;
             }
         }
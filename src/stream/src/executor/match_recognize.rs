@@ -0,0 +1,552 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeMap, HashMap};
+
+use futures::StreamExt;
+use futures_async_stream::try_stream;
+use itertools::Itertools;
+use risingwave_common::array::{Op, Row, StreamChunk};
+use risingwave_common::catalog::{ColumnDesc, ColumnId, Field, Schema};
+use risingwave_common::types::{DataType, Datum, ScalarImpl};
+use risingwave_common::util::sort_util::OrderType;
+use risingwave_expr::expr::BoxedExpression;
+pub use risingwave_pb::stream_plan::match_recognize_node::measure::Kind as MeasureKind;
+pub use risingwave_pb::stream_plan::match_recognize_node::pattern_term::Quantifier as PatternQuantifier;
+use risingwave_storage::table::state_table::StateTable;
+use risingwave_storage::{Keyspace, StateStore};
+
+use super::error::StreamExecutorError;
+use super::{
+    BoxedExecutor, BoxedMessageStream, Executor, ExecutorInfo, Message, PkIndices, PkIndicesRef,
+    StreamExecutorResult,
+};
+
+/// A pattern variable of `MATCH_RECOGNIZE`.
+#[derive(Debug)]
+pub struct PatternTerm {
+    pub quantifier: PatternQuantifier,
+    /// The condition on the rows accepted by the pattern variable.
+    pub condition: BoxedExpression,
+}
+
+impl PatternTerm {
+    fn is_optional(&self) -> bool {
+        matches!(
+            self.quantifier,
+            PatternQuantifier::ZeroOrMore | PatternQuantifier::ZeroOrOne
+        )
+    }
+
+    fn is_repeatable(&self) -> bool {
+        matches!(
+            self.quantifier,
+            PatternQuantifier::ZeroOrMore | PatternQuantifier::OneOrMore
+        )
+    }
+}
+
+/// The value of `column` in the first or the last row accepted by the pattern term `term` in a
+/// match.
+#[derive(Debug, Clone)]
+pub struct Measure {
+    pub kind: MeasureKind,
+    pub term: usize,
+    pub column: usize,
+}
+
+/// The slot of the row keeping the counters of a partition in the state table.
+const HEADER_SLOT: i32 = -1;
+
+/// A partial match.
+#[derive(Debug, Clone)]
+struct Run {
+    /// The sequence number of the first row of the match in its partition.
+    start: i64,
+    measures: Vec<Datum>,
+}
+
+#[derive(Debug, Default)]
+struct Partition {
+    /// The number of rows seen, which is also the sequence number of the next row.
+    row_count: i64,
+    /// The number of matches emitted, which is also the match number of the last match.
+    match_count: i64,
+    /// The partial matches, keyed by the index of the pattern term that accepted their last rows.
+    runs: BTreeMap<usize, Run>,
+    /// The rows of the partition in the state table.
+    persisted: Vec<Row>,
+    dirty: bool,
+}
+
+/// [`MatchRecognizeExecutor`] finds the rows matching a pattern in each partition of an
+/// append-only input, i.e. `MATCH_RECOGNIZE`, and emits the partition keys, the measures and the
+/// match number for each match.
+///
+/// The pattern is a sequence of quantified pattern terms, and runs as an NFA whose states are the
+/// terms accepting the last rows of the partial matches. A new partial match starts at every row.
+/// If two partial matches reach the same state, the one starting earlier is kept, since they have
+/// the same future. A match is emitted as soon as the rest of the pattern accepts nothing, e.g.
+/// `A B+` completes at the first `B`. All other partial matches of the partition are then dropped,
+/// i.e. `AFTER MATCH SKIP PAST LAST ROW`.
+///
+/// The state table is keyed by the partition keys and a slot, where the slot is either the state
+/// of a partial match, or [`HEADER_SLOT`] for the counters of the partition. The columns following
+/// the slot are
+///
+/// - for a partial match, the sequence number of its first row, NULL, and its measures;
+/// - for the header, the number of rows seen, the number of matches emitted, and NULLs.
+pub struct MatchRecognizeExecutor<S: StateStore> {
+    input: Option<BoxedExecutor>,
+    info: ExecutorInfo,
+    partition_by: Vec<usize>,
+    pattern: Vec<PatternTerm>,
+    measures: Vec<Measure>,
+    /// Whether a partial match is complete once accepted by each pattern term, i.e. all the
+    /// following terms are optional.
+    completes_at: Vec<bool>,
+    state_table: StateTable<S>,
+    partitions: HashMap<Row, Partition>,
+}
+
+impl<S: StateStore> MatchRecognizeExecutor<S> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        input: BoxedExecutor,
+        partition_by: Vec<usize>,
+        pattern: Vec<PatternTerm>,
+        measures: Vec<Measure>,
+        keyspace: Keyspace<S>,
+        dist_keys: Vec<usize>,
+        pk_indices: PkIndices,
+        executor_id: u64,
+    ) -> Self {
+        assert!(!pattern.is_empty());
+        let input_schema = input.schema();
+        let partition_fields = partition_by
+            .iter()
+            .map(|&idx| input_schema[idx].clone())
+            .collect_vec();
+        let measure_fields = measures
+            .iter()
+            .map(|measure| input_schema[measure.column].clone())
+            .collect_vec();
+        let schema = Schema::new(
+            partition_fields
+                .iter()
+                .chain(&measure_fields)
+                .cloned()
+                .chain(std::iter::once(Field::unnamed(DataType::Int64)))
+                .collect(),
+        );
+
+        // Same as the internal table inferred by the frontend.
+        let column_descs = partition_fields
+            .iter()
+            .map(|field| field.data_type())
+            .chain([DataType::Int32, DataType::Int64, DataType::Int64])
+            .chain(measure_fields.iter().map(|field| field.data_type()))
+            .enumerate()
+            .map(|(idx, data_type)| ColumnDesc::unnamed(ColumnId::from(idx as i32), data_type))
+            .collect_vec();
+        let state_pk_indices = (0..=partition_by.len()).collect_vec();
+        let state_table = StateTable::new(
+            keyspace,
+            column_descs,
+            vec![OrderType::Ascending; state_pk_indices.len()],
+            Some(dist_keys),
+            state_pk_indices,
+        );
+
+        let completes_at = (0..pattern.len())
+            .map(|idx| pattern[idx + 1..].iter().all(PatternTerm::is_optional))
+            .collect();
+
+        Self {
+            input: Some(input),
+            info: ExecutorInfo {
+                schema,
+                pk_indices,
+                identity: format!("MatchRecognizeExecutor {:X}", executor_id),
+            },
+            partition_by,
+            pattern,
+            measures,
+            completes_at,
+            state_table,
+            partitions: HashMap::new(),
+        }
+    }
+
+    fn state_pk(partition_key: &Row, slot: i32) -> Row {
+        Row(partition_key
+            .0
+            .iter()
+            .cloned()
+            .chain(std::iter::once(Some(ScalarImpl::Int32(slot))))
+            .collect())
+    }
+
+    async fn load_partition(
+        &self,
+        partition_key: &Row,
+        epoch: u64,
+    ) -> StreamExecutorResult<Partition> {
+        let mut partition = Partition::default();
+        let key_len = partition_key.0.len();
+        let Some(header) = self
+            .state_table
+            .get_owned_row(&Self::state_pk(partition_key, HEADER_SLOT), epoch)
+            .await?
+        else {
+            return Ok(partition);
+        };
+        partition.row_count = *header.0[key_len + 1].as_ref().unwrap().as_int64();
+        partition.match_count = *header.0[key_len + 2].as_ref().unwrap().as_int64();
+        partition.persisted.push(header);
+        for slot in 0..self.pattern.len() {
+            if let Some(row) = self
+                .state_table
+                .get_owned_row(&Self::state_pk(partition_key, slot as i32), epoch)
+                .await?
+            {
+                let run = Run {
+                    start: *row.0[key_len + 1].as_ref().unwrap().as_int64(),
+                    measures: row.0[key_len + 3..].to_vec(),
+                };
+                partition.runs.insert(slot, run);
+                partition.persisted.push(row);
+            }
+        }
+        Ok(partition)
+    }
+
+    /// Returns the pattern terms that may accept the next row of a partial match whose last row
+    /// was accepted by `slot`, or of a new partial match if `slot` is `None`, in the order of
+    /// preference.
+    fn next_terms(&self, slot: Option<usize>) -> Vec<usize> {
+        let mut terms = vec![];
+        let mut next = match slot {
+            Some(slot) => {
+                if self.pattern[slot].is_repeatable() {
+                    terms.push(slot);
+                }
+                slot + 1
+            }
+            None => 0,
+        };
+        while next < self.pattern.len() {
+            terms.push(next);
+            if !self.pattern[next].is_optional() {
+                break;
+            }
+            next += 1;
+        }
+        terms
+    }
+
+    /// Feeds a row to the NFA of its partition, and returns the measures if a match completes.
+    fn process_row(
+        &self,
+        partition: &mut Partition,
+        row: &Row,
+    ) -> StreamExecutorResult<Option<Vec<Datum>>> {
+        let seq = partition.row_count;
+        partition.row_count += 1;
+        partition.dirty = true;
+
+        // The conditions are evaluated lazily, at most once for each term.
+        let mut accepted: Vec<Option<bool>> = vec![None; self.pattern.len()];
+        let mut runs = std::mem::take(&mut partition.runs)
+            .into_iter()
+            .map(|(slot, run)| (Some(slot), run))
+            .sorted_by_key(|(slot, run)| (run.start, *slot))
+            .collect_vec();
+        runs.push((
+            None,
+            Run {
+                start: seq,
+                measures: vec![None; self.measures.len()],
+            },
+        ));
+
+        let mut next_runs = BTreeMap::new();
+        for (slot, run) in runs {
+            for term in self.next_terms(slot) {
+                let accepts = match accepted[term] {
+                    Some(accepts) => accepts,
+                    None => {
+                        let datum = self.pattern[term].condition.eval_row(row)?;
+                        let accepts = matches!(datum, Some(ScalarImpl::Bool(true)));
+                        accepted[term] = Some(accepts);
+                        accepts
+                    }
+                };
+                if !accepts {
+                    continue;
+                }
+
+                let mut next = run.clone();
+                for (measure, datum) in self.measures.iter().zip_eq(&mut next.measures) {
+                    let is_first_row = slot != Some(term);
+                    if measure.term == term && (measure.kind == MeasureKind::Last || is_first_row) {
+                        *datum = row.0[measure.column].clone();
+                    }
+                }
+                if self.completes_at[term] {
+                    partition.match_count += 1;
+                    return Ok(Some(next.measures));
+                }
+                next_runs.entry(term).or_insert(next);
+            }
+        }
+        partition.runs = next_runs;
+        Ok(None)
+    }
+
+    fn flush(&mut self) -> StreamExecutorResult<()> {
+        for (partition_key, partition) in &mut self.partitions {
+            if !partition.dirty {
+                continue;
+            }
+            let header = Self::state_pk(partition_key, HEADER_SLOT)
+                .0
+                .into_iter()
+                .chain([
+                    Some(ScalarImpl::Int64(partition.row_count)),
+                    Some(ScalarImpl::Int64(partition.match_count)),
+                ])
+                .chain(std::iter::repeat(None).take(self.measures.len()))
+                .collect();
+            let rows = std::iter::once(Row(header))
+                .chain(partition.runs.iter().map(|(&slot, run)| {
+                    Row(Self::state_pk(partition_key, slot as i32)
+                        .0
+                        .into_iter()
+                        .chain([Some(ScalarImpl::Int64(run.start)), None])
+                        .chain(run.measures.iter().cloned())
+                        .collect())
+                }))
+                .collect_vec();
+            for row in std::mem::take(&mut partition.persisted) {
+                self.state_table.delete(row)?;
+            }
+            for row in &rows {
+                self.state_table.insert(row.clone())?;
+            }
+            partition.persisted = rows;
+            partition.dirty = false;
+        }
+        Ok(())
+    }
+
+    #[try_stream(ok = Message, error = StreamExecutorError)]
+    async fn execute_inner(mut self) {
+        let input = self.input.take().unwrap().execute();
+        let data_types = self.info.schema.data_types();
+        let mut epoch = 0;
+
+        #[for_await]
+        for msg in input {
+            match msg? {
+                Message::Chunk(chunk) => {
+                    let mut matches = vec![];
+                    for (op, row) in chunk.rows() {
+                        // The input is append-only.
+                        if op != Op::Insert {
+                            continue;
+                        }
+                        let row = row.to_owned_row();
+                        let partition_key = row.by_indices(&self.partition_by);
+                        if !self.partitions.contains_key(&partition_key) {
+                            let partition = self.load_partition(&partition_key, epoch).await?;
+                            self.partitions.insert(partition_key.clone(), partition);
+                        }
+                        let mut partition = self.partitions.remove(&partition_key).unwrap();
+                        let result = self.process_row(&mut partition, &row);
+                        let match_number = partition.match_count;
+                        self.partitions.insert(partition_key.clone(), partition);
+                        if let Some(measures) = result? {
+                            let output = partition_key
+                                .0
+                                .into_iter()
+                                .chain(measures)
+                                .chain(std::iter::once(Some(ScalarImpl::Int64(match_number))))
+                                .collect();
+                            matches.push((Op::Insert, Row(output)));
+                        }
+                    }
+                    if !matches.is_empty() {
+                        yield Message::Chunk(StreamChunk::from_rows(&matches, &data_types)?);
+                    }
+                }
+                Message::Barrier(barrier) => {
+                    self.flush()?;
+                    self.state_table
+                        .commit_with_value_meta(barrier.epoch.prev)
+                        .await?;
+                    epoch = barrier.epoch.curr;
+                    yield Message::Barrier(barrier);
+                }
+            }
+        }
+    }
+}
+
+impl<S: StateStore> Executor for MatchRecognizeExecutor<S> {
+    fn execute(self: Box<Self>) -> BoxedMessageStream {
+        self.execute_inner().boxed()
+    }
+
+    fn schema(&self) -> &Schema {
+        &self.info.schema
+    }
+
+    fn pk_indices(&self) -> PkIndicesRef {
+        &self.info.pk_indices
+    }
+
+    fn identity(&self) -> &str {
+        &self.info.identity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::stream_chunk::StreamChunkTestExt;
+    use risingwave_common::catalog::TableId;
+    use risingwave_expr::expr::expr_binary_nonnull::new_binary_expr;
+    use risingwave_expr::expr::{InputRefExpression, LiteralExpression};
+    use risingwave_pb::expr::expr_node::Type;
+    use risingwave_storage::memory::MemoryStateStore;
+
+    use super::*;
+    use crate::executor::test_utils::{next_barrier, next_chunk, MessageSender, MockSource};
+
+    fn condition(cmp: Type, value: i64) -> BoxedExpression {
+        new_binary_expr(
+            cmp,
+            DataType::Boolean,
+            Box::new(InputRefExpression::new(DataType::Int64, 1)),
+            Box::new(LiteralExpression::new(
+                DataType::Int64,
+                Some(ScalarImpl::Int64(value)),
+            )),
+        )
+    }
+
+    /// `PARTITION BY p MEASURES FIRST(A.v), LAST(B.v), LAST(C.v) PATTERN (A B* C)`, where
+    /// `A` is `v < 10`, `B` is `v >= 10` and `C` is `v >= 100`.
+    fn create_executor(
+        keyspace: Keyspace<MemoryStateStore>,
+    ) -> (MessageSender, BoxedMessageStream) {
+        let schema = Schema::new(vec![
+            Field::unnamed(DataType::Int64),
+            Field::unnamed(DataType::Int64),
+        ]);
+        let (tx, source) = MockSource::channel(schema, vec![]);
+        let pattern = vec![
+            PatternTerm {
+                quantifier: PatternQuantifier::One,
+                condition: condition(Type::LessThan, 10),
+            },
+            PatternTerm {
+                quantifier: PatternQuantifier::ZeroOrMore,
+                condition: condition(Type::GreaterThanOrEqual, 10),
+            },
+            PatternTerm {
+                quantifier: PatternQuantifier::One,
+                condition: condition(Type::GreaterThanOrEqual, 100),
+            },
+        ];
+        let measures = vec![
+            Measure {
+                kind: MeasureKind::First,
+                term: 0,
+                column: 1,
+            },
+            Measure {
+                kind: MeasureKind::Last,
+                term: 1,
+                column: 1,
+            },
+            Measure {
+                kind: MeasureKind::Last,
+                term: 2,
+                column: 1,
+            },
+        ];
+        let executor = MatchRecognizeExecutor::new(
+            Box::new(source),
+            vec![0],
+            pattern,
+            measures,
+            keyspace,
+            vec![0],
+            vec![0, 4],
+            1,
+        );
+        (tx, Box::new(executor).execute())
+    }
+
+    #[tokio::test]
+    async fn test_match_recognize() {
+        let keyspace = Keyspace::table_root(MemoryStateStore::new(), &TableId::from(0x2333));
+        let (mut tx, mut match_recognize) = create_executor(keyspace.clone());
+        tx.push_barrier(1, false);
+        next_barrier(&mut match_recognize).await;
+
+        tx.push_chunk(StreamChunk::from_pretty(
+            " I   I
+            + 1   5
+            + 2   7
+            + 1  20
+            + 1  30
+            + 2 150
+            + 1 200",
+        ));
+        assert_eq!(
+            next_chunk(&mut match_recognize).await,
+            StreamChunk::from_pretty(
+                " I I  I   I I
+                + 2 7  . 150 1
+                + 1 5 30 200 1"
+            )
+        );
+
+        // The partial match of partition 1 survives the recovery.
+        tx.push_chunk(StreamChunk::from_pretty(
+            " I  I
+            + 1  3
+            + 1 40",
+        ));
+        tx.push_barrier(2, false);
+        next_barrier(&mut match_recognize).await;
+
+        let (mut tx, mut match_recognize) = create_executor(keyspace);
+        tx.push_barrier(3, false);
+        next_barrier(&mut match_recognize).await;
+        tx.push_chunk(StreamChunk::from_pretty(
+            " I   I
+            + 1 100",
+        ));
+        assert_eq!(
+            next_chunk(&mut match_recognize).await,
+            StreamChunk::from_pretty(
+                " I I  I   I I
+                + 1 3 40 100 2"
+            )
+        );
+    }
+}
@@ -20,8 +20,11 @@ use futures::{Stream, StreamExt};
 use futures_async_stream::for_await;
 use risingwave_common::catalog::Schema;
 use risingwave_common::error::Result;
-use risingwave_pb::task_service::GetStreamResponse;
-use risingwave_rpc_client::ComputeClient;
+use risingwave_common::util::addr::HostAddr;
+use risingwave_pb::common::buffer::CompressionType;
+use risingwave_pb::task_service::{GetStreamResponse, GetStreamWithPermitsRequest};
+use risingwave_rpc_client::permits::PermitSender;
+use risingwave_rpc_client::ComputeClientPool;
 use tokio::sync::mpsc::{Receiver, Sender};
 use tokio_stream::wrappers::ReceiverStream;
 use tonic::Streaming;
@@ -34,23 +37,35 @@ use crate::task::UpDownActorIds;
 /// Receive data from `gRPC` and forwards to `MergerExecutor`/`ReceiverExecutor`
 pub struct RemoteInput {
     stream: Streaming<GetStreamResponse>,
+    /// Returns permits to the upstream after forwarding each message.
+    permit_sender: PermitSender<GetStreamWithPermitsRequest>,
     sender: Sender<Message>,
     up_down_ids: UpDownActorIds,
     metrics: Arc<StreamingMetrics>,
 }
 
 impl RemoteInput {
-    /// Create a remote input from compute client and related info. Should provide the corresponding
-    /// compute client of where the actor is placed.
+    /// Create a remote input from the compute node `upstream_addr` where the upstream actor is
+    /// placed, with a client from `client_pool`. Opening the stream is retried if the connection
+    /// is broken.
     pub async fn create(
-        client: ComputeClient,
+        client_pool: &ComputeClientPool,
+        upstream_addr: HostAddr,
         up_down_ids: UpDownActorIds,
         sender: Sender<Message>,
         metrics: Arc<StreamingMetrics>,
+        compression: CompressionType,
     ) -> Result<Self> {
-        let stream = client.get_stream(up_down_ids.0, up_down_ids.1).await?;
+        let (stream, permit_sender) = client_pool
+            .retry(upstream_addr, |client| async move {
+                client
+                    .get_stream(up_down_ids.0, up_down_ids.1, compression)
+                    .await
+            })
+            .await?;
         Ok(Self {
             stream,
+            permit_sender,
             sender,
             up_down_ids,
             metrics,
@@ -77,6 +92,7 @@ impl RemoteInput {
                     match msg_res {
                         Ok(msg) => {
                             self.sender.send(msg).await.unwrap();
+                            self.permit_sender.add_permits(bytes);
                         }
                         Err(e) => {
                             error!("RemoteInput forward message error:{}", e);
@@ -256,13 +272,14 @@ mod tests {
         ExchangeService, ExchangeServiceServer,
     };
     use risingwave_pb::task_service::{
-        GetDataRequest, GetDataResponse, GetStreamRequest, GetStreamResponse,
+        GetDataRequest, GetDataResponse, GetDataWithPermitsRequest, GetStreamRequest,
+        GetStreamResponse, GetStreamWithPermitsRequest,
     };
-    use risingwave_rpc_client::ComputeClient;
+    use risingwave_rpc_client::ComputeClientPool;
     use tokio::sync::mpsc::channel;
     use tokio::time::sleep;
     use tokio_stream::wrappers::ReceiverStream;
-    use tonic::{Request, Response, Status};
+    use tonic::{Request, Response, Status, Streaming};
 
     use super::*;
     use crate::executor::merge::RemoteInput;
@@ -345,7 +362,11 @@ mod tests {
     #[async_trait::async_trait]
     impl ExchangeService for FakeExchangeService {
         type GetDataStream = ReceiverStream<std::result::Result<GetDataResponse, Status>>;
+        type GetDataWithPermitsStream =
+            ReceiverStream<std::result::Result<GetDataResponse, Status>>;
         type GetStreamStream = ReceiverStream<std::result::Result<GetStreamResponse, Status>>;
+        type GetStreamWithPermitsStream =
+            ReceiverStream<std::result::Result<GetStreamResponse, Status>>;
 
         async fn get_data(
             &self,
@@ -354,10 +375,24 @@ mod tests {
             unimplemented!()
         }
 
+        async fn get_data_with_permits(
+            &self,
+            _: Request<Streaming<GetDataWithPermitsRequest>>,
+        ) -> std::result::Result<Response<Self::GetDataWithPermitsStream>, Status> {
+            unimplemented!()
+        }
+
         async fn get_stream(
             &self,
             _request: Request<GetStreamRequest>,
         ) -> std::result::Result<Response<Self::GetStreamStream>, Status> {
+            unimplemented!()
+        }
+
+        async fn get_stream_with_permits(
+            &self,
+            _request: Request<Streaming<GetStreamWithPermitsRequest>>,
+        ) -> std::result::Result<Response<Self::GetStreamWithPermitsStream>, Status> {
             let (tx, rx) = tokio::sync::mpsc::channel(10);
             self.rpc_called.store(true, Ordering::SeqCst);
             // send stream_chunk
@@ -418,10 +453,12 @@ mod tests {
         let (tx, mut rx) = channel(16);
         let input_handle = tokio::spawn(async move {
             let remote_input = RemoteInput::create(
-                ComputeClient::new(addr.into()).await.unwrap(),
+                &ComputeClientPool::default(),
+                addr.into(),
                 (0, 0),
                 tx,
                 Arc::new(StreamingMetrics::unused()),
+                CompressionType::None,
             )
             .await
             .unwrap();
@@ -32,7 +32,7 @@ use risingwave_pb::common::ActorInfo;
 use risingwave_pb::data::barrier::Mutation as ProstMutation;
 use risingwave_pb::data::stream_message::StreamMessage;
 use risingwave_pb::data::{
-    AddMutation, Barrier as ProstBarrier, DispatcherMutation, Epoch as ProstEpoch,
+    AddMutation, Barrier as ProstBarrier, ConfigMutation, DispatcherMutation, Epoch as ProstEpoch,
     SourceChangeSplitMutation, StopMutation, StreamMessage as ProstStreamMessage, UpdateMutation,
 };
 use smallvec::SmallVec;
@@ -42,34 +42,43 @@ use crate::task::{ActorId, DispatcherId, ENABLE_BARRIER_AGGREGATION};
 
 mod actor;
 pub mod aggregation;
+mod asof_join;
 mod barrier_align;
 mod batch_query;
 mod chain;
 mod debug;
 pub mod dispatch;
+mod dynamic_filter;
 mod error;
 mod filter;
+mod gap_fill;
 mod global_simple_agg;
 mod hash_agg;
 pub mod hash_join;
 mod hop_window;
+mod local_hash_agg;
 mod local_simple_agg;
 mod lookup;
 mod lookup_union;
 mod managed_state;
+mod match_recognize;
 pub mod merge;
 pub mod monitor;
 mod mview;
+mod now;
 mod project;
 mod rearranged_chain;
 pub mod receiver;
+mod reconfig;
 mod simple;
 mod sink;
 mod source;
+mod tokenize;
 mod top_n;
 mod top_n_appendonly;
 mod top_n_executor;
 mod union;
+mod watermark;
 
 #[cfg(test)]
 mod integration_tests;
@@ -77,28 +86,37 @@ mod integration_tests;
 mod test_utils;
 
 pub use actor::{Actor, ActorContext, ActorContextRef, OperatorInfo, OperatorInfoStatus};
+pub use asof_join::{AsOfJoinExecutor, AsOfJoinSide};
 pub use batch_query::BatchQueryExecutor;
 pub use chain::ChainExecutor;
 pub use debug::DebugExecutor;
 pub use dispatch::DispatchExecutor;
+pub use dynamic_filter::DynamicFilterExecutor;
 pub use filter::FilterExecutor;
+pub use gap_fill::GapFillExecutor;
 pub use global_simple_agg::SimpleAggExecutor;
 pub use hash_agg::HashAggExecutor;
 pub use hash_join::*;
 pub use hop_window::HopWindowExecutor;
+pub use local_hash_agg::LocalHashAggExecutor;
 pub use local_simple_agg::LocalSimpleAggExecutor;
 pub use lookup::*;
 pub use lookup_union::LookupUnionExecutor;
+pub use match_recognize::{MatchRecognizeExecutor, Measure as MatchRecognizeMeasure, PatternTerm};
 pub use merge::MergeExecutor;
 pub use mview::*;
+pub use now::NowExecutor;
 pub use project::ProjectExecutor;
 pub use rearranged_chain::RearrangedChainExecutor;
+pub use reconfig::{ActorConfig, ConfigChange};
 use risingwave_pb::source::{ConnectorSplit, ConnectorSplits};
 use simple::{SimpleExecutor, SimpleExecutorWrapper};
 pub use source::*;
+pub use tokenize::TokenizeExecutor;
 pub use top_n::TopNExecutor;
 pub use top_n_appendonly::AppendOnlyTopNExecutor;
 pub use union::UnionExecutor;
+pub use watermark::Watermark;
 
 pub type BoxedExecutor = Box<dyn Executor>;
 pub type BoxedMessageStream = BoxStream<'static, StreamExecutorResult<Message>>;
@@ -177,6 +195,7 @@ pub enum Mutation {
     UpdateOutputs(HashMap<(ActorId, DispatcherId), Vec<ActorInfo>>),
     AddOutput(AddOutput),
     SourceChangeSplit(HashMap<ActorId, ConnectorState>),
+    Config(HashMap<ActorId, Vec<ConfigChange>>),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -264,6 +283,12 @@ impl Barrier {
         matches!(self.mutation.as_deref(), Some(Mutation::Stop(actors)) if actors.contains(&actor_id))
     }
 
+    /// Whether the barrier creates a streaming job. Actors receiving it as the first barrier are
+    /// created by it, rather than rebuilt in recovery.
+    pub fn is_add_output(&self) -> bool {
+        matches!(self.mutation.as_deref(), Some(Mutation::AddOutput(_)))
+    }
+
     pub fn is_to_add_output(&self, actor_id: ActorId) -> bool {
         matches!(
             self.mutation.as_deref(),
@@ -337,6 +362,9 @@ impl Mutation {
                         .collect(),
                 })
             }
+            Mutation::Config(changes) => ProstMutation::Config(ConfigMutation {
+                changes: ConfigChange::to_protobuf_changes(changes),
+            }),
         }
     }
 
@@ -410,6 +438,21 @@ impl Mutation {
                         .collect::<HashMap<ActorId, ConnectorState>>(),
                 )
             }
+            ProstMutation::Config(config) => {
+                let mut changes: HashMap<ActorId, Vec<ConfigChange>> = HashMap::new();
+                for change in &config.changes {
+                    let Some(prost_change) = &change.change else {
+                        continue;
+                    };
+                    for &actor_id in &change.actor_ids {
+                        changes
+                            .entry(actor_id)
+                            .or_default()
+                            .push(ConfigChange::from_protobuf(prost_change));
+                    }
+                }
+                Mutation::Config(changes)
+            }
         };
         Ok(mutation)
     }
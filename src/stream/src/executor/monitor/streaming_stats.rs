@@ -16,7 +16,8 @@ use prometheus::core::{AtomicF64, AtomicI64, AtomicU64, GenericCounterVec, Gener
 use prometheus::{
     exponential_buckets, histogram_opts, register_gauge_vec_with_registry,
     register_histogram_vec_with_registry, register_int_counter_vec_with_registry,
-    register_int_gauge_vec_with_registry, HistogramVec, Registry,
+    register_int_gauge_vec_with_registry, register_int_gauge_with_registry, HistogramVec, IntGauge,
+    Registry,
 };
 
 pub struct StreamingMetrics {
@@ -35,11 +36,18 @@ pub struct StreamingMetrics {
     pub actor_poll_cnt: GenericGaugeVec<AtomicI64>,
     pub actor_idle_duration: GenericGaugeVec<AtomicF64>,
     pub actor_idle_cnt: GenericGaugeVec<AtomicI64>,
+    pub actor_in_record_cnt: GenericCounterVec<AtomicU64>,
+    pub actor_out_record_cnt: GenericCounterVec<AtomicU64>,
+    pub actor_output_blocking_duration_ns: GenericCounterVec<AtomicU64>,
+    pub actor_processing_latency: HistogramVec,
     pub source_output_row_count: GenericCounterVec<AtomicU64>,
     pub exchange_recv_size: GenericCounterVec<AtomicU64>,
     pub join_lookup_miss_count: GenericCounterVec<AtomicU64>,
     pub join_total_lookup_count: GenericCounterVec<AtomicU64>,
     pub join_barrier_align_duration: HistogramVec,
+    pub hot_key_ratio: GenericGaugeVec<AtomicF64>,
+    pub memory_allocated_bytes: IntGauge,
+    pub cache_capacity_permille: IntGauge,
 }
 
 impl StreamingMetrics {
@@ -172,6 +180,42 @@ impl StreamingMetrics {
         )
         .unwrap();
 
+        let actor_in_record_cnt = register_int_counter_vec_with_registry!(
+            "stream_actor_in_record_cnt",
+            "Total number of rows an actor received from upstream actors",
+            &["actor_id", "fragment_id", "table_id"],
+            registry
+        )
+        .unwrap();
+
+        let actor_out_record_cnt = register_int_counter_vec_with_registry!(
+            "stream_actor_out_record_cnt",
+            "Total number of rows an actor sent to downstream actors",
+            &["actor_id", "fragment_id", "table_id"],
+            registry
+        )
+        .unwrap();
+
+        let actor_output_blocking_duration_ns = register_int_counter_vec_with_registry!(
+            "stream_actor_output_blocking_duration_ns",
+            "Total time (ns) an actor spent sending messages to downstream actors",
+            &["actor_id", "fragment_id", "table_id"],
+            registry
+        )
+        .unwrap();
+
+        let opts = histogram_opts!(
+            "stream_actor_processing_latency",
+            "Time between a barrier entering an actor and leaving it",
+            exponential_buckets(0.0001, 2.0, 21).unwrap() // max 104s
+        );
+        let actor_processing_latency = register_histogram_vec_with_registry!(
+            opts,
+            &["actor_id", "fragment_id", "table_id"],
+            registry
+        )
+        .unwrap();
+
         let join_lookup_miss_count = register_int_counter_vec_with_registry!(
             "stream_join_lookup_miss_count",
             "Join executor lookup miss duration",
@@ -197,6 +241,28 @@ impl StreamingMetrics {
             register_histogram_vec_with_registry!(opts, &["actor_id", "wait_side"], registry)
                 .unwrap();
 
+        let hot_key_ratio = register_gauge_vec_with_registry!(
+            "stream_hot_key_ratio",
+            "Share of the rows taken by the hottest key of hash agg or join in the last epoch",
+            &["actor_id", "executor"],
+            registry
+        )
+        .unwrap();
+
+        let memory_allocated_bytes = register_int_gauge_with_registry!(
+            "stream_memory_allocated_bytes",
+            "Memory allocated by the compute node as reported by jemalloc",
+            registry
+        )
+        .unwrap();
+
+        let cache_capacity_permille = register_int_gauge_with_registry!(
+            "stream_cache_capacity_permille",
+            "Fraction in permille of their target capacity that executor caches keep",
+            registry
+        )
+        .unwrap();
+
         Self {
             registry,
             executor_row_count,
@@ -213,11 +279,18 @@ impl StreamingMetrics {
             actor_poll_cnt,
             actor_idle_duration,
             actor_idle_cnt,
+            actor_in_record_cnt,
+            actor_out_record_cnt,
+            actor_output_blocking_duration_ns,
+            actor_processing_latency,
             source_output_row_count,
             exchange_recv_size,
             join_lookup_miss_count,
             join_total_lookup_count,
             join_barrier_align_duration,
+            hot_key_ratio,
+            memory_allocated_bytes,
+            cache_capacity_permille,
         }
     }
 
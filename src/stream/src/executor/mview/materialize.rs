@@ -18,9 +18,10 @@ use futures::StreamExt;
 use futures_async_stream::try_stream;
 use itertools::Itertools;
 use risingwave_common::array::Op::*;
-use risingwave_common::array::Row;
+use risingwave_common::array::{Op, Row, StreamChunk};
 use risingwave_common::catalog::{ColumnDesc, ColumnId, Schema};
 use risingwave_common::util::sort_util::OrderPair;
+use risingwave_pb::plan_common::HandleConflictBehavior;
 use risingwave_storage::table::state_table::StateTable;
 use risingwave_storage::{Keyspace, StateStore};
 
@@ -38,6 +39,9 @@ pub struct MaterializeExecutor<S: StateStore> {
     /// Columns of arrange keys (including pk, group keys, join keys, etc.)
     arrange_columns: Vec<usize>,
 
+    /// How to handle a row inserted with an existing primary key.
+    conflict_behavior: HandleConflictBehavior,
+
     info: ExecutorInfo,
 }
 
@@ -49,6 +53,7 @@ impl<S: StateStore> MaterializeExecutor<S> {
         column_ids: Vec<ColumnId>,
         executor_id: u64,
         distribution_keys: Vec<usize>,
+        conflict_behavior: HandleConflictBehavior,
     ) -> Self {
         let arrange_columns: Vec<usize> = keys.iter().map(|k| k.column_idx).collect();
         let arrange_columns_set: HashSet<usize> =
@@ -77,6 +82,7 @@ impl<S: StateStore> MaterializeExecutor<S> {
                 arrange_columns.clone(),
             ),
             arrange_columns: arrange_columns.clone(),
+            conflict_behavior,
             info: ExecutorInfo {
                 schema,
                 pk_indices: arrange_columns,
@@ -88,10 +94,19 @@ impl<S: StateStore> MaterializeExecutor<S> {
     #[try_stream(ok = Message, error = StreamExecutorError)]
     async fn execute_inner(mut self) {
         let input = self.input.execute();
+        let mut epoch = 0;
         #[for_await]
         for msg in input {
             let msg = msg?;
             yield match msg {
+                Message::Chunk(chunk)
+                    if self.conflict_behavior != HandleConflictBehavior::NoCheck =>
+                {
+                    match self.handle_conflict(chunk, epoch).await? {
+                        Some(chunk) => Message::Chunk(chunk),
+                        None => continue,
+                    }
+                }
                 Message::Chunk(chunk) => {
                     for (idx, op) in chunk.ops().iter().enumerate() {
                         // check visibility
@@ -130,11 +145,72 @@ impl<S: StateStore> MaterializeExecutor<S> {
                     self.state_table
                         .commit_with_value_meta(b.epoch.prev)
                         .await?;
+                    epoch = b.epoch.curr;
                     Message::Barrier(b)
                 }
             }
         }
     }
+
+    /// Applies the chunk to the state table against the existing rows of the same primary keys,
+    /// and returns the changes actually made, or `None` if there is no change.
+    async fn handle_conflict(
+        &mut self,
+        chunk: StreamChunk,
+        epoch: u64,
+    ) -> Result<Option<StreamChunk>, StreamExecutorError> {
+        let mut changes = vec![];
+        for (idx, op) in chunk.ops().iter().enumerate() {
+            let visible = chunk
+                .visibility()
+                .as_ref()
+                .map(|x| x.is_set(idx).unwrap())
+                .unwrap_or(true);
+            if !visible {
+                continue;
+            }
+
+            let row = Row(chunk
+                .columns()
+                .iter()
+                .map(|x| x.array_ref().datum_at(idx))
+                .collect_vec());
+            let pk = row.by_indices(&self.arrange_columns);
+            let old_row = self.state_table.get_owned_row(&pk, epoch).await?;
+
+            match (op, old_row) {
+                (Insert | UpdateInsert, None) => {
+                    self.state_table.insert(row.clone())?;
+                    changes.push((Op::Insert, row));
+                }
+                (Insert | UpdateInsert, Some(old_row)) => match self.conflict_behavior {
+                    HandleConflictBehavior::Overwrite => {
+                        if old_row != row {
+                            self.state_table.delete(old_row.clone())?;
+                            self.state_table.insert(row.clone())?;
+                            changes.push((Op::UpdateDelete, old_row));
+                            changes.push((Op::UpdateInsert, row));
+                        }
+                    }
+                    HandleConflictBehavior::Ignore => {}
+                    HandleConflictBehavior::NoCheck => unreachable!(),
+                },
+                // Delete the stored row, which may differ from the deleted one in the columns other
+                // than the primary key after an overwrite.
+                (Delete | UpdateDelete, Some(old_row)) => {
+                    self.state_table.delete(old_row.clone())?;
+                    changes.push((Op::Delete, old_row));
+                }
+                (Delete | UpdateDelete, None) => {}
+            }
+        }
+
+        if changes.is_empty() {
+            return Ok(None);
+        }
+        let data_types = self.info.schema.data_types();
+        Ok(Some(StreamChunk::from_rows(&changes, &data_types)?))
+    }
 }
 
 impl<S: StateStore> Executor for MaterializeExecutor<S> {
@@ -173,6 +249,7 @@ mod tests {
     use risingwave_common::catalog::{ColumnDesc, Field, Schema, TableId};
     use risingwave_common::types::DataType;
     use risingwave_common::util::sort_util::{OrderPair, OrderType};
+    use risingwave_pb::plan_common::HandleConflictBehavior;
     use risingwave_storage::memory::MemoryStateStore;
     use risingwave_storage::table::cell_based_table::CellBasedTable;
     use risingwave_storage::Keyspace;
@@ -232,6 +309,7 @@ mod tests {
             column_ids,
             1,
             vec![0],
+            HandleConflictBehavior::NoCheck,
         ))
         .execute();
 
@@ -261,4 +339,101 @@ mod tests {
             _ => unreachable!(),
         }
     }
+
+    async fn run_conflict_test(behavior: HandleConflictBehavior, expected: [StreamChunk; 2]) {
+        let schema = Schema::new(vec![
+            Field::unnamed(DataType::Int32),
+            Field::unnamed(DataType::Int32),
+        ]);
+        let chunk1 = StreamChunk::from_pretty(
+            " i i
+            + 1 4
+            + 2 5
+            + 1 6",
+        );
+        let chunk2 = StreamChunk::from_pretty(
+            " i i
+            + 2 7
+            - 3 8
+            - 1 4",
+        );
+        let source = MockSource::with_messages(
+            schema,
+            PkIndices::new(),
+            vec![
+                Message::Barrier(Barrier::new_test_barrier(1)),
+                Message::Chunk(chunk1),
+                Message::Barrier(Barrier::new_test_barrier(2)),
+                Message::Chunk(chunk2),
+                Message::Barrier(Barrier::new_test_barrier(3)),
+            ],
+        );
+
+        let keyspace = Keyspace::table_root(MemoryStateStore::new(), &TableId::new(1));
+        let mut materialize_executor = Box::new(MaterializeExecutor::new(
+            Box::new(source),
+            keyspace,
+            vec![OrderPair::new(0, OrderType::Ascending)],
+            vec![0.into(), 1.into()],
+            1,
+            vec![0],
+            behavior,
+        ))
+        .execute();
+
+        for expected in expected {
+            materialize_executor.next().await.transpose().unwrap();
+            let chunk = materialize_executor
+                .next()
+                .await
+                .unwrap()
+                .unwrap()
+                .into_chunk()
+                .unwrap();
+            assert_eq!(chunk, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_materialize_overwrite_on_conflict() {
+        run_conflict_test(
+            HandleConflictBehavior::Overwrite,
+            [
+                StreamChunk::from_pretty(
+                    " i i
+                    +  1 4
+                    +  2 5
+                    U- 1 4
+                    U+ 1 6",
+                ),
+                // The row is deleted by its primary key, with the values it is overwritten with.
+                StreamChunk::from_pretty(
+                    " i i
+                    U- 2 5
+                    U+ 2 7
+                    -  1 6",
+                ),
+            ],
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_materialize_ignore_on_conflict() {
+        run_conflict_test(
+            HandleConflictBehavior::Ignore,
+            [
+                StreamChunk::from_pretty(
+                    " i i
+                    + 1 4
+                    + 2 5",
+                ),
+                StreamChunk::from_pretty(
+                    " i i
+                    - 1 4",
+                ),
+            ],
+        )
+        .await;
+    }
 }
@@ -0,0 +1,152 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::SystemTime;
+
+use chrono::NaiveDateTime;
+use futures::StreamExt;
+use futures_async_stream::try_stream;
+use risingwave_common::array::{Op, Row, StreamChunk};
+use risingwave_common::catalog::{Field, Schema};
+use risingwave_common::types::{DataType, NaiveDateTimeWrapper};
+use risingwave_common::util::epoch;
+use tokio::sync::mpsc::UnboundedReceiver;
+
+use super::error::StreamExecutorError;
+use super::{Barrier, BoxedMessageStream, Executor, ExecutorInfo, Message, PkIndicesRef};
+
+/// [`NowExecutor`] produces the current timestamp as a single row, which is updated after each
+/// barrier with the physical time of the new epoch. Since it's derived from the epoch, the
+/// timestamp is the same on all actors and never goes down, even after recovery.
+pub struct NowExecutor {
+    /// Receiver of barrier channel.
+    barrier_receiver: UnboundedReceiver<Barrier>,
+
+    info: ExecutorInfo,
+}
+
+impl NowExecutor {
+    pub fn new(barrier_receiver: UnboundedReceiver<Barrier>, executor_id: u64) -> Self {
+        let schema = Schema::new(vec![Field::with_name(DataType::Timestamp, "now")]);
+        Self {
+            barrier_receiver,
+            info: ExecutorInfo {
+                schema,
+                pk_indices: vec![],
+                identity: format!("NowExecutor {:X}", executor_id),
+            },
+        }
+    }
+
+    #[try_stream(ok = Message, error = StreamExecutorError)]
+    async fn execute_inner(self) {
+        let Self {
+            mut barrier_receiver,
+            info,
+        } = self;
+        let data_types = info.schema.data_types();
+        let mut last_row: Option<Row> = None;
+
+        while let Some(barrier) = barrier_receiver.recv().await {
+            let row = Row(vec![Some(epoch_to_timestamp(barrier.epoch.curr).into())]);
+            yield Message::Barrier(barrier);
+
+            let changes = match last_row.replace(row.clone()) {
+                None => vec![(Op::Insert, row)],
+                // Epochs generated in the same millisecond have the same timestamp.
+                Some(last_row) if last_row == row => continue,
+                Some(last_row) => vec![(Op::UpdateDelete, last_row), (Op::UpdateInsert, row)],
+            };
+            yield Message::Chunk(StreamChunk::from_rows(&changes, &data_types)?);
+        }
+    }
+}
+
+fn epoch_to_timestamp(epoch: u64) -> NaiveDateTimeWrapper {
+    let since_unix_epoch = epoch::Epoch(epoch)
+        .as_system_time()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap();
+    NaiveDateTimeWrapper::new(NaiveDateTime::from_timestamp(
+        since_unix_epoch.as_secs() as i64,
+        since_unix_epoch.subsec_nanos(),
+    ))
+}
+
+impl Executor for NowExecutor {
+    fn execute(self: Box<Self>) -> BoxedMessageStream {
+        self.execute_inner().boxed()
+    }
+
+    fn schema(&self) -> &Schema {
+        &self.info.schema
+    }
+
+    fn pk_indices(&self) -> PkIndicesRef {
+        &self.info.pk_indices
+    }
+
+    fn identity(&self) -> &str {
+        &self.info.identity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use futures::StreamExt;
+    use risingwave_common::array::stream_chunk::StreamChunkTestExt;
+    use risingwave_common::array::StreamChunk;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    use super::*;
+
+    /// Returns the epoch whose physical time is `secs` seconds after the singularity date.
+    fn epoch_after_secs(secs: u64) -> u64 {
+        (secs * 1000) << 16
+    }
+
+    #[tokio::test]
+    async fn test_now() {
+        let (tx, rx) = unbounded_channel();
+        let mut now = Box::new(NowExecutor::new(rx, 1)).execute();
+
+        tx.send(Barrier::new_test_barrier(epoch_after_secs(1)))
+            .unwrap();
+        assert_matches!(now.next().await.unwrap().unwrap(), Message::Barrier(_));
+        assert_eq!(
+            now.next().await.unwrap().unwrap().into_chunk().unwrap(),
+            StreamChunk::from_pretty(
+                " TS
+                + 2021-04-01T00:00:01"
+            )
+        );
+
+        // The epoch is in the same millisecond, so the timestamp is unchanged.
+        tx.send(Barrier::new_test_barrier(epoch_after_secs(1) + 1))
+            .unwrap();
+        tx.send(Barrier::new_test_barrier(epoch_after_secs(3)))
+            .unwrap();
+        assert_matches!(now.next().await.unwrap().unwrap(), Message::Barrier(_));
+        assert_matches!(now.next().await.unwrap().unwrap(), Message::Barrier(_));
+        assert_eq!(
+            now.next().await.unwrap().unwrap().into_chunk().unwrap(),
+            StreamChunk::from_pretty(
+                "  TS
+                U- 2021-04-01T00:00:01
+                U+ 2021-04-01T00:00:03"
+            )
+        );
+    }
+}
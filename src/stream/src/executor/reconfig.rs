@@ -0,0 +1,142 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_pb::data::config_change::Change as ProstChange;
+use risingwave_pb::data::ConfigChange as ProstConfigChange;
+
+use super::{Barrier, Mutation};
+use crate::task::ActorId;
+
+/// A runtime reconfiguration of actors, delivered by the [`Mutation::Config`] of a barrier, so
+/// that all the actors it targets switch to it at the same epoch boundary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigChange {
+    /// Stop reading from the source if true, or resume reading if false.
+    PauseSource(bool),
+    /// Maximum rows per second read by the source actor. `None` means unlimited.
+    SourceRateLimit(Option<u32>),
+}
+
+impl ConfigChange {
+    pub fn to_protobuf(self) -> ProstChange {
+        match self {
+            ConfigChange::PauseSource(paused) => ProstChange::PauseSource(paused),
+            ConfigChange::SourceRateLimit(limit) => {
+                ProstChange::SourceRateLimit(limit.unwrap_or(0))
+            }
+        }
+    }
+
+    pub fn from_protobuf(prost: &ProstChange) -> Self {
+        match prost {
+            ProstChange::PauseSource(paused) => ConfigChange::PauseSource(*paused),
+            ProstChange::SourceRateLimit(limit) => {
+                ConfigChange::SourceRateLimit(Some(*limit).filter(|limit| *limit > 0))
+            }
+        }
+    }
+
+    /// Converts the changes of each actor to protobuf, one [`ProstConfigChange`] per actor and
+    /// change.
+    pub fn to_protobuf_changes<'a>(
+        changes: impl IntoIterator<Item = (&'a ActorId, &'a Vec<ConfigChange>)>,
+    ) -> Vec<ProstConfigChange> {
+        changes
+            .into_iter()
+            .flat_map(|(&actor_id, changes)| {
+                changes.iter().map(move |change| ProstConfigChange {
+                    actor_ids: vec![actor_id],
+                    change: Some(change.to_protobuf()),
+                })
+            })
+            .collect()
+    }
+}
+
+/// The runtime configuration of an actor, updated by the config mutations of barriers. Executors
+/// keep it and apply each barrier to it, rather than parsing the mutations themselves.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ActorConfig {
+    /// Whether reading from the source is paused.
+    pub source_paused: bool,
+    /// Maximum rows per second read by the source actor. `None` means unlimited.
+    pub source_rate_limit: Option<u32>,
+}
+
+impl ActorConfig {
+    /// Applies the config changes of the barrier that target the actor. Returns whether the
+    /// config is changed.
+    pub fn apply(&mut self, barrier: &Barrier, actor_id: ActorId) -> bool {
+        let Some(Mutation::Config(changes)) = barrier.mutation.as_deref() else {
+            return false;
+        };
+        let Some(changes) = changes.get(&actor_id) else {
+            return false;
+        };
+        let old = self.clone();
+        for change in changes {
+            match *change {
+                ConfigChange::PauseSource(paused) => self.source_paused = paused,
+                ConfigChange::SourceRateLimit(limit) => self.source_rate_limit = limit,
+            }
+        }
+        *self != old
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use madsim::collections::HashMap;
+    use maplit::hashmap;
+
+    use super::*;
+
+    #[test]
+    fn test_apply_config_changes() {
+        let mut config = ActorConfig::default();
+        assert!(!config.apply(&Barrier::new_test_barrier(1), 1));
+
+        let barrier = Barrier::new_test_barrier(2).with_mutation(Mutation::Config(hashmap! {
+            1 => vec![ConfigChange::PauseSource(true), ConfigChange::SourceRateLimit(Some(100))],
+            2 => vec![ConfigChange::PauseSource(true)],
+        }));
+        assert!(config.apply(&barrier, 1));
+        assert_eq!(
+            config,
+            ActorConfig {
+                source_paused: true,
+                source_rate_limit: Some(100),
+            }
+        );
+        // Applying the same changes again doesn't change the config.
+        assert!(!config.apply(&barrier, 1));
+
+        // Changes to other actors are ignored.
+        let barrier = Barrier::new_test_barrier(3).with_mutation(Mutation::Config(hashmap! {
+            2 => vec![ConfigChange::PauseSource(false)],
+        }));
+        assert!(!config.apply(&barrier, 1));
+        assert!(config.source_paused);
+    }
+
+    #[test]
+    fn test_config_mutation_protobuf() {
+        let changes: HashMap<ActorId, Vec<ConfigChange>> = hashmap! {
+            1 => vec![ConfigChange::PauseSource(true), ConfigChange::SourceRateLimit(None)],
+        };
+        let barrier = Barrier::new_test_barrier(1).with_mutation(Mutation::Config(changes));
+        let prost = barrier.to_protobuf();
+        assert_eq!(Barrier::from_protobuf(&prost).unwrap(), barrier);
+    }
+}
@@ -13,31 +13,60 @@
 // limitations under the License.
 
 use futures::StreamExt;
-use futures_async_stream::try_stream;
+use futures_async_stream::{for_await, try_stream};
 use risingwave_common::catalog::Schema;
 use risingwave_connector::sink::Sink;
 
 use super::error::StreamExecutorError;
 use super::{BoxedExecutor, Executor, Message};
 
+/// `SinkExecutor` writes the chunks of its input to an external sink. On each barrier, the sink
+/// commits the epoch that ends and begins the next one.
 pub struct SinkExecutor<S: Sink> {
     child: BoxedExecutor,
-    _external_sink: S,
+    external_sink: S,
     identity: String,
 }
 
-impl<S: Sink> SinkExecutor<S> {
-    pub fn _new(materialize_executor: BoxedExecutor, _external_sink: S) -> Self {
+impl<S: Sink + Send> SinkExecutor<S> {
+    pub fn _new(materialize_executor: BoxedExecutor, external_sink: S) -> Self {
         Self {
             child: materialize_executor,
-            _external_sink,
+            external_sink,
             identity: "SinkExecutor".to_string(),
         }
     }
 
     #[try_stream(ok = Message, error = StreamExecutorError)]
     async fn execute_inner(self) {
-        todo!()
+        let Self {
+            child,
+            mut external_sink,
+            ..
+        } = self;
+        let schema = child.schema().clone();
+
+        #[for_await]
+        for msg in child.execute() {
+            let msg = msg?;
+            match &msg {
+                Message::Chunk(chunk) => external_sink
+                    .write_batch(chunk.clone(), &schema)
+                    .await
+                    .map_err(StreamExecutorError::sink_error)?,
+                Message::Barrier(barrier) => {
+                    external_sink
+                        .commit(barrier.epoch.prev, &schema)
+                        .await
+                        .map_err(StreamExecutorError::sink_error)?;
+                    external_sink
+                        .begin_epoch(barrier.epoch.curr)
+                        .await
+                        .map_err(StreamExecutorError::sink_error)?;
+                }
+            }
+            yield msg;
+        }
     }
 }
 
@@ -51,7 +80,7 @@ impl<S: Sink + 'static + Send> Executor for SinkExecutor<S> {
     }
 
     fn pk_indices(&self) -> super::PkIndicesRef {
-        todo!();
+        self.child.pk_indices()
     }
 
     fn identity(&self) -> &str {
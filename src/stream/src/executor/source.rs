@@ -14,6 +14,7 @@
 
 use std::fmt::{Debug, Formatter};
 use std::sync::Arc;
+use std::time::Duration;
 
 use either::Either;
 use futures::stream::{select_with_strategy, PollNext};
@@ -28,7 +29,7 @@ use risingwave_connector::{ConnectorState, SplitImpl, SplitMetaData};
 use risingwave_source::*;
 use risingwave_storage::{Keyspace, StateStore};
 use tokio::sync::mpsc::UnboundedReceiver;
-use tokio::sync::{Mutex, Notify};
+use tokio::sync::{watch, Mutex, Notify};
 use tokio::time::Instant;
 
 use super::error::StreamExecutorError;
@@ -140,6 +141,8 @@ struct SourceReader {
     /// Expected barrier latency in ms. If there are no barrier within the expected barrier
     /// latency, source will stall.
     expected_barrier_latency_ms: u64,
+    /// The config of the actor, updated by the barriers.
+    config: watch::Receiver<ActorConfig>,
 }
 
 impl SourceReader {
@@ -148,17 +151,40 @@ impl SourceReader {
         stream_reader: Arc<Mutex<Box<SourceStreamReaderImpl>>>,
         notifier: Arc<Notify>,
         expected_barrier_latency_ms: u64,
+        mut config: watch::Receiver<ActorConfig>,
     ) {
         'outer: loop {
             let now = Instant::now();
 
             // We allow data to flow for `expected_barrier_latency_ms` milliseconds.
             while now.elapsed().as_millis() < expected_barrier_latency_ms as u128 {
+                // Stop reading while paused. Barriers still flow, since they're polled separately.
+                loop {
+                    let paused = config.borrow().source_paused;
+                    if !paused {
+                        break;
+                    }
+                    if config.changed().await.is_err() {
+                        break 'outer;
+                    }
+                }
+
                 let mut reader_guard = stream_reader.lock().await;
                 let chunk_result = reader_guard.next().await;
                 drop(reader_guard);
                 match chunk_result {
-                    Ok(chunk) => yield chunk,
+                    Ok(chunk) => {
+                        let rows = chunk.chunk.cardinality();
+                        yield chunk;
+                        // Throttle by delaying the next read until the rows are within the limit.
+                        let rate_limit = config.borrow().source_rate_limit;
+                        if let Some(rate_limit) = rate_limit {
+                            tokio::time::sleep(Duration::from_secs_f64(
+                                rows as f64 / rate_limit as f64,
+                            ))
+                            .await;
+                        }
+                    }
                     Err(e) => {
                         error!("hang up stream reader due to polling error: {}", e);
                         break 'outer;
@@ -199,6 +225,7 @@ impl SourceReader {
             self.stream_reader,
             notifier,
             self.expected_barrier_latency_ms,
+            self.config,
         );
         select_with_strategy(
             barrier_receiver.map(Either::Left),
@@ -209,28 +236,44 @@ impl SourceReader {
 }
 
 impl<S: StateStore> SourceExecutor<S> {
-    fn get_diff(&self, rhs: ConnectorState) -> ConnectorState {
-        // rhs can not be None because we do not support split number reduction
-
-        let split_change = rhs.unwrap();
+    /// Resolves the splits the actor reads after a split change from meta, returning `None` if
+    /// they stay the same. The splits being read continue from the offsets reached, and the ones
+    /// newly assigned, including those moved from another actor, from the offsets in the source
+    /// state if any.
+    async fn get_diff(&self, rhs: ConnectorState, epoch: u64) -> Result<ConnectorState> {
+        // All splits of the actor are removed if `rhs` is `None`.
+        let split_change = rhs.unwrap_or_default();
         let mut target_state: Vec<SplitImpl> = Vec::with_capacity(split_change.len());
-        let mut no_change_flag = true;
+        let mut no_change_flag = split_change.len() == self.stream_source_splits.len();
         for sc in &split_change {
             // SplitImpl is identified by its id, target_state always follows offsets in cache
-            // here we introduce a hypothesis that every split is polled at least once in one epoch
-            match self.state_cache.get(&sc.id()) {
-                Some(s) => target_state.push(s.clone()),
-                None => {
-                    no_change_flag = false;
-                    target_state.push(sc.clone())
-                }
+            if let Some(s) = self.state_cache.get(&sc.id()) {
+                target_state.push(s.clone());
+                continue;
             }
+            let current = self
+                .stream_source_splits
+                .iter()
+                .find(|split| split.id() == sc.id());
+            if current.is_none() {
+                no_change_flag = false;
+            }
+            // Not polled in this epoch, so the latest offset is in the state store, if any.
+            let recovered = self
+                .split_state_store
+                .try_recover_from_state_store(sc, epoch)
+                .await?;
+            target_state.push(
+                recovered
+                    .or_else(|| current.cloned())
+                    .unwrap_or_else(|| sc.clone()),
+            );
         }
 
         if no_change_flag {
-            None
+            Ok(None)
         } else {
-            Some(target_state)
+            Ok(Some(target_state))
         }
     }
 
@@ -282,6 +325,11 @@ impl<S: StateStore> SourceExecutor<S> {
             }
         }
 
+        // The config is reapplied by the first barrier after recovery.
+        let mut config = ActorConfig::default();
+        config.apply(&barrier, self.actor_id);
+        let (config_tx, config_rx) = watch::channel(config.clone());
+
         let epoch = barrier.epoch.prev;
 
         let mut boot_state = self.stream_source_splits.clone();
@@ -319,6 +367,7 @@ impl<S: StateStore> SourceExecutor<S> {
             stream_reader: stream_reader.clone(),
             barrier_receiver,
             expected_barrier_latency_ms: self.expected_barrier_latency_ms,
+            config: config_rx,
         };
         yield Message::Barrier(barrier);
 
@@ -338,7 +387,11 @@ impl<S: StateStore> SourceExecutor<S> {
                                 barrier.mutation.as_deref()
                             {
                                 if let Some(target_splits) = mapping.get(&self.actor_id).cloned() {
-                                    match self.get_diff(target_splits) {
+                                    match self
+                                        .get_diff(target_splits, epoch)
+                                        .await
+                                        .map_err(StreamExecutorError::source_error)?
+                                    {
                                         None => {}
                                         Some(target_state) => {
                                             log::info!(
@@ -358,6 +411,15 @@ impl<S: StateStore> SourceExecutor<S> {
                                     }
                                 }
                             }
+                            if config.apply(&barrier, self.actor_id) {
+                                log::info!(
+                                    "actor {:?} apply config change to {:?}",
+                                    self.actor_id,
+                                    config
+                                );
+                                // The reader keeps the receiver as long as the executor runs.
+                                let _ = config_tx.send(config.clone());
+                            }
                             self.state_cache.clear();
                             yield Message::Barrier(barrier)
                         }
@@ -453,7 +515,7 @@ mod tests {
     use risingwave_pb::data::data_type::TypeName;
     use risingwave_pb::data::DataType as ProstDataType;
     use risingwave_pb::plan_common::{
-        ColumnCatalog as ProstColumnCatalog, ColumnDesc as ProstColumnDesc,
+        ColumnCatalog as ProstColumnCatalog, ColumnDesc as ProstColumnDesc, HandleConflictBehavior,
         RowFormatType as ProstRowFormatType,
     };
     use risingwave_source::*;
@@ -547,7 +609,10 @@ mod tests {
             let source = source.clone();
             tokio::spawn(async move {
                 let table_source = source.as_table_v2().unwrap();
-                table_source.blocking_write_chunk(chunk).await.unwrap();
+                table_source
+                    .blocking_write_chunk(chunk, DmlPriority::High)
+                    .await
+                    .unwrap();
             });
         };
 
@@ -669,7 +734,10 @@ mod tests {
             let source = source.clone();
             tokio::spawn(async move {
                 let table_source = source.as_table_v2().unwrap();
-                table_source.blocking_write_chunk(chunk).await.unwrap();
+                table_source
+                    .blocking_write_chunk(chunk, DmlPriority::High)
+                    .await
+                    .unwrap();
             });
         };
 
@@ -791,6 +859,7 @@ mod tests {
             column_ids.clone(),
             2,
             vec![0usize],
+            HandleConflictBehavior::NoCheck,
         )
         .boxed()
         .execute();
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use assert_matches::assert_matches;
 use futures::StreamExt;
 use futures_async_stream::try_stream;
 use risingwave_common::catalog::{Schema, TableId};
@@ -20,7 +21,7 @@ use risingwave_storage::Keyspace;
 use tokio::sync::mpsc;
 
 use super::error::StreamExecutorError;
-use super::{Barrier, Executor, Message, PkIndices, StreamChunk};
+use super::{Barrier, BoxedMessageStream, Executor, Message, PkIndices, StreamChunk};
 
 pub struct MockSource {
     schema: Schema,
@@ -131,6 +132,16 @@ impl Executor for MockSource {
     }
 }
 
+/// Returns the next message of the executor, which must be a chunk.
+pub async fn next_chunk(stream: &mut BoxedMessageStream) -> StreamChunk {
+    stream.next().await.unwrap().unwrap().into_chunk().unwrap()
+}
+
+/// Consumes the next message of the executor, which must be a barrier.
+pub async fn next_barrier(stream: &mut BoxedMessageStream) {
+    assert_matches!(stream.next().await.unwrap().unwrap(), Message::Barrier(_));
+}
+
 pub fn create_in_memory_keyspace() -> Keyspace<MemoryStateStore> {
     Keyspace::table_root(MemoryStateStore::new(), &TableId::from(0x2333))
 }
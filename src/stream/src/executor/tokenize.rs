@@ -0,0 +1,159 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use itertools::Itertools;
+use risingwave_common::array::{Op, Row, StreamChunk};
+use risingwave_common::catalog::{Field, Schema};
+use risingwave_common::types::{DataType, ScalarImpl};
+use risingwave_expr::vector_op::fulltext::lexemes;
+
+use super::{
+    Executor, ExecutorInfo, PkIndicesRef, SimpleExecutor, SimpleExecutorWrapper,
+    StreamExecutorResult,
+};
+
+pub type TokenizeExecutor = SimpleExecutorWrapper<SimpleTokenizeExecutor>;
+
+impl TokenizeExecutor {
+    pub fn new(input: Box<dyn Executor>, column_idx: usize, executor_id: u64) -> Self {
+        let info = input.info();
+
+        SimpleExecutorWrapper {
+            input,
+            inner: SimpleTokenizeExecutor::new(info, column_idx, executor_id),
+        }
+    }
+}
+
+/// `TokenizeExecutor` splits the text of a column into the lexemes of full-text search, and emits
+/// the row once for each distinct lexeme with the lexeme appended, which maintains the inverted
+/// index of a full-text index. Rows with a NULL text have no lexemes and are dropped.
+pub struct SimpleTokenizeExecutor {
+    info: ExecutorInfo,
+    column_idx: usize,
+}
+
+impl SimpleTokenizeExecutor {
+    pub fn new(input_info: ExecutorInfo, column_idx: usize, executor_id: u64) -> Self {
+        let mut schema = input_info.schema;
+        schema.fields.push(Field::unnamed(DataType::Varchar));
+        // A row and one of its lexemes identify an output row.
+        let mut pk_indices = input_info.pk_indices;
+        pk_indices.push(schema.len() - 1);
+        Self {
+            info: ExecutorInfo {
+                schema,
+                pk_indices,
+                identity: format!("TokenizeExecutor {:X}", executor_id),
+            },
+            column_idx,
+        }
+    }
+}
+
+impl SimpleExecutor for SimpleTokenizeExecutor {
+    fn map_filter_chunk(
+        &mut self,
+        chunk: StreamChunk,
+    ) -> StreamExecutorResult<Option<StreamChunk>> {
+        let chunk = chunk.compact()?;
+        let mut changes = vec![];
+        for (idx, op) in chunk.ops().iter().enumerate() {
+            let row = Row(chunk
+                .columns()
+                .iter()
+                .map(|x| x.array_ref().datum_at(idx))
+                .collect_vec());
+            let Some(ScalarImpl::Utf8(text)) = &row.0[self.column_idx] else {
+                continue;
+            };
+            // The lexemes of the old and the new text of an update may differ, so it's emitted as
+            // deletes and inserts.
+            let op = match op {
+                Op::Insert | Op::UpdateInsert => Op::Insert,
+                Op::Delete | Op::UpdateDelete => Op::Delete,
+            };
+            for lexeme in lexemes(text) {
+                let mut values = row.0.clone();
+                values.push(Some(ScalarImpl::Utf8(lexeme)));
+                changes.push((op, Row(values)));
+            }
+        }
+
+        if changes.is_empty() {
+            return Ok(None);
+        }
+        let data_types = self.info.schema.data_types();
+        Ok(Some(StreamChunk::from_rows(&changes, &data_types)?))
+    }
+
+    fn schema(&self) -> &Schema {
+        &self.info.schema
+    }
+
+    fn pk_indices(&self) -> PkIndicesRef {
+        &self.info.pk_indices
+    }
+
+    fn identity(&self) -> &str {
+        &self.info.identity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use risingwave_common::array::stream_chunk::StreamChunkTestExt;
+    use risingwave_common::catalog::{Field, Schema};
+    use risingwave_common::types::DataType;
+
+    use super::*;
+    use crate::executor::test_utils::MockSource;
+    use crate::executor::{Executor, Message};
+
+    #[tokio::test]
+    async fn test_tokenize() {
+        let schema = Schema {
+            fields: vec![
+                Field::unnamed(DataType::Int64),
+                Field::unnamed(DataType::Varchar),
+            ],
+        };
+        let (mut tx, source) = MockSource::channel(schema, vec![0]);
+        tx.push_chunk(StreamChunk::from_pretty(
+            "  I T
+            +  1 Fat,cat
+            +  2 .
+            U- 3 rat
+            U+ 3 RAT",
+        ));
+        let tokenize = Box::new(TokenizeExecutor::new(Box::new(source), 1, 1));
+        assert_eq!(tokenize.pk_indices(), &[0, 2]);
+        let mut tokenize = tokenize.execute();
+
+        let Message::Chunk(chunk) = tokenize.next().await.unwrap().unwrap() else {
+            unreachable!()
+        };
+        assert_eq!(
+            chunk,
+            StreamChunk::from_pretty(
+                " I T       T
+                + 1 Fat,cat cat
+                + 1 Fat,cat fat
+                - 3 rat     rat
+                + 3 RAT     rat"
+            )
+        );
+    }
+}
@@ -0,0 +1,136 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use chrono::Duration;
+use risingwave_common::array::{Array, StreamChunk};
+use risingwave_common::buffer::Bitmap;
+use risingwave_common::types::{Datum, NaiveDateTimeWrapper, ScalarImpl};
+
+/// Event-time watermark of a timestamp column, which trails the largest event time seen by a
+/// delay.
+///
+/// Rows older than the watermark are late and dropped, so that the state of keys older than the
+/// watermark is never accessed again and can be purged.
+#[derive(Debug)]
+pub struct Watermark {
+    /// Index of the event-time column in the input.
+    column_index: usize,
+    delay: Duration,
+    /// The largest event time seen.
+    max_event_time: Option<NaiveDateTimeWrapper>,
+}
+
+impl Watermark {
+    pub fn new(column_index: usize, delay_ms: u64) -> Self {
+        Self {
+            column_index,
+            delay: Duration::milliseconds(delay_ms as i64),
+            max_event_time: None,
+        }
+    }
+
+    /// Returns the current watermark, or `None` if no row has been seen yet.
+    pub fn current(&self) -> Option<NaiveDateTimeWrapper> {
+        self.max_event_time
+            .and_then(|time| time.0.checked_sub_signed(self.delay))
+            .map(NaiveDateTimeWrapper)
+    }
+
+    /// Whether the event time `datum` is older than the watermark. Nulls are never late.
+    pub fn is_late(&self, datum: &Datum) -> bool {
+        match (datum, self.current()) {
+            (Some(ScalarImpl::NaiveDateTime(time)), Some(watermark)) => *time < watermark,
+            _ => false,
+        }
+    }
+
+    /// Hides the late rows of `chunk`, then advances the watermark by the remaining rows.
+    pub fn filter_late_rows(&mut self, chunk: StreamChunk) -> StreamChunk {
+        let watermark = self.current();
+        let (ops, columns, visibility) = chunk.into_inner();
+        let times = columns[self.column_index].array_ref().as_naivedatetime();
+
+        let mut has_late = false;
+        let vis = (0..ops.len())
+            .map(|i| {
+                if let Some(visibility) = &visibility
+                    && !visibility.is_set(i).unwrap()
+                {
+                    return false;
+                }
+                let time = times.value_at(i);
+                if let Some(time) = time
+                    && let Some(watermark) = watermark
+                    && time < watermark
+                {
+                    has_late = true;
+                    return false;
+                }
+                if time > self.max_event_time {
+                    self.max_event_time = time;
+                }
+                true
+            })
+            .collect::<Vec<_>>();
+
+        let visibility = if has_late {
+            Some(Bitmap::try_from(vis).unwrap())
+        } else {
+            visibility
+        };
+        StreamChunk::new(ops, columns, visibility)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::array::stream_chunk::StreamChunkTestExt;
+
+    use super::*;
+
+    #[test]
+    fn test_filter_late_rows() {
+        let mut watermark = Watermark::new(1, 60 * 1000);
+        assert_eq!(watermark.current(), None);
+
+        let chunk = watermark.filter_late_rows(StreamChunk::from_pretty(
+            " I TS
+            + 1 2022-02-02T10:05:00
+            + 2 2022-02-02T10:00:00
+            + 3 .",
+        ));
+        assert_eq!(chunk.visibility(), None);
+        assert_eq!(
+            watermark.current(),
+            Some(NaiveDateTimeWrapper("2022-02-02T10:04:00".parse().unwrap()))
+        );
+
+        let chunk = watermark.filter_late_rows(StreamChunk::from_pretty(
+            " I TS
+            + 4 2022-02-02T10:03:59
+            + 5 2022-02-02T10:04:00
+            + 6 2022-02-02T10:10:00",
+        ));
+        assert_eq!(
+            chunk.visibility().unwrap().iter().collect::<Vec<_>>(),
+            vec![false, true, true]
+        );
+        assert!(
+            watermark.is_late(&Some(ScalarImpl::NaiveDateTime(NaiveDateTimeWrapper(
+                "2022-02-02T10:08:59".parse().unwrap()
+            ))))
+        );
+        assert!(!watermark.is_late(&None));
+    }
+}
@@ -0,0 +1,85 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_common::catalog::TableId;
+use risingwave_pb::plan_common::JoinType;
+
+use super::*;
+use crate::executor::{AsOfJoinExecutor, AsOfJoinSide};
+
+pub struct AsOfJoinExecutorBuilder;
+
+impl ExecutorBuilder for AsOfJoinExecutorBuilder {
+    fn new_boxed_executor(
+        mut params: ExecutorParams,
+        node: &StreamNode,
+        store: impl StateStore,
+        _stream: &mut LocalStreamManagerCore,
+    ) -> Result<BoxedExecutor> {
+        let node = try_match_expand!(node.get_node_body().unwrap(), NodeBody::AsOfJoin)?;
+        let source_r = params.input.remove(1);
+        let source_l = params.input.remove(0);
+        let outer = match node.get_join_type()? {
+            JoinType::Inner => false,
+            JoinType::LeftOuter => true,
+            join_type => {
+                return Err(ErrorCode::NotImplemented(
+                    format!("ASOF join of type {:?}", join_type),
+                    None.into(),
+                )
+                .into())
+            }
+        };
+        let side_l = AsOfJoinSide {
+            eq_keys: node.left_key.iter().map(|key| *key as usize).collect(),
+            asof_key: node.left_asof_key as usize,
+        };
+        let side_r = AsOfJoinSide {
+            eq_keys: node.right_key.iter().map(|key| *key as usize).collect(),
+            asof_key: node.right_asof_key as usize,
+        };
+
+        let table_l = node.left_table.as_ref().unwrap();
+        let table_r = node.right_table.as_ref().unwrap();
+        let keyspace_l = Keyspace::table_root(store.clone(), &TableId::from(table_l.id));
+        let keyspace_r = Keyspace::table_root(store, &TableId::from(table_r.id));
+        let dist_keys_l = table_l
+            .distribution_keys
+            .iter()
+            .map(|key| *key as usize)
+            .collect_vec();
+        let dist_keys_r = table_r
+            .distribution_keys
+            .iter()
+            .map(|key| *key as usize)
+            .collect_vec();
+
+        Ok(Box::new(AsOfJoinExecutor::new(
+            source_l,
+            source_r,
+            side_l,
+            side_r,
+            outer,
+            node.inclusive,
+            params.pk_indices,
+            params.executor_id,
+            keyspace_l,
+            keyspace_r,
+            dist_keys_l,
+            dist_keys_r,
+            params.actor_id as u64,
+            params.executor_stats,
+        )))
+    }
+}
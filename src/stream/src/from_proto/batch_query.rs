@@ -75,7 +75,7 @@ impl ExecutorBuilder for BatchQueryExecutorBuilder {
         let hash_filter = params.vnode_bitmap.expect("no vnode bitmap");
 
         let schema = table.schema().clone();
-        let executor = BatchQueryExecutor::new(
+        let mut executor = BatchQueryExecutor::new(
             table,
             None,
             ExecutorInfo {
@@ -87,6 +87,9 @@ impl ExecutorBuilder for BatchQueryExecutorBuilder {
             hash_filter,
             pk_descs,
         );
+        if node.base_epoch != 0 {
+            executor = executor.with_base_epoch(node.base_epoch);
+        }
 
         Ok(executor.boxed())
     }
@@ -0,0 +1,56 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_common::catalog::TableId;
+
+use super::*;
+use crate::executor::DynamicFilterExecutor;
+
+pub struct DynamicFilterExecutorBuilder;
+
+impl ExecutorBuilder for DynamicFilterExecutorBuilder {
+    fn new_boxed_executor(
+        mut params: ExecutorParams,
+        node: &StreamNode,
+        store: impl StateStore,
+        _stream: &mut LocalStreamManagerCore,
+    ) -> Result<BoxedExecutor> {
+        let node = try_match_expand!(node.get_node_body().unwrap(), NodeBody::DynamicFilter)?;
+        let source_r = params.input.remove(1);
+        let source_l = params.input.remove(0);
+        let key_l = node.left_key as usize;
+        let comparator = node.get_comparator()?;
+
+        let table = node.left_table.as_ref().unwrap();
+        let keyspace = Keyspace::table_root(store, &TableId::from(table.id));
+        let dist_key_l = table
+            .distribution_keys
+            .iter()
+            .map(|key| *key as usize)
+            .collect_vec();
+
+        Ok(Box::new(DynamicFilterExecutor::new(
+            source_l,
+            source_r,
+            key_l,
+            params.pk_indices,
+            params.executor_id,
+            comparator,
+            keyspace,
+            dist_key_l,
+            params.actor_id as u64,
+            params.executor_stats,
+        )))
+    }
+}
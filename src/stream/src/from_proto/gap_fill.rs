@@ -0,0 +1,59 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_common::catalog::TableId;
+use risingwave_common::util::gap_fill::GapFiller;
+use risingwave_pb::plan_common::GapFillStrategy;
+
+use super::*;
+use crate::executor::GapFillExecutor;
+
+pub struct GapFillExecutorBuilder;
+
+impl ExecutorBuilder for GapFillExecutorBuilder {
+    fn new_boxed_executor(
+        mut params: ExecutorParams,
+        node: &StreamNode,
+        store: impl StateStore,
+        _stream: &mut LocalStreamManagerCore,
+    ) -> Result<BoxedExecutor> {
+        let node = try_match_expand!(node.get_node_body().unwrap(), NodeBody::GapFill)?;
+        let filler = GapFiller::new(
+            node.time_col as usize,
+            node.get_interval()?.into(),
+            node.partition_by.iter().map(|idx| *idx as usize).collect(),
+            node.strategies
+                .iter()
+                .map(|strategy| GapFillStrategy::from_i32(*strategy).unwrap())
+                .collect(),
+        );
+
+        let table = node.get_state_table()?;
+        let keyspace = Keyspace::table_root(store, &TableId::from(table.id));
+        let dist_keys = table
+            .distribution_keys
+            .iter()
+            .map(|key| *key as usize)
+            .collect_vec();
+
+        Ok(Box::new(GapFillExecutor::new(
+            params.input.remove(0),
+            filler,
+            keyspace,
+            dist_keys,
+            params.pk_indices,
+            params.executor_id,
+        )))
+    }
+}
@@ -48,14 +48,18 @@ impl ExecutorBuilder for SimpleAggExecutorBuilder {
             .map(|key| *key as usize)
             .collect::<Vec<_>>();
 
-        Ok(SimpleAggExecutor::new(
+        let executor = SimpleAggExecutor::new(
             params.input.remove(0),
             agg_calls,
             keyspace,
             params.pk_indices,
             params.executor_id,
             key_indices,
-        )?
-        .boxed())
+        )?;
+        Ok(if node.reemit {
+            executor.with_reemit(store).boxed()
+        } else {
+            executor.boxed()
+        })
     }
 }
@@ -15,12 +15,16 @@
 //! Global Streaming Hash Aggregators
 
 use std::marker::PhantomData;
+use std::sync::Arc;
 
+use risingwave_common::buffer::Bitmap;
 use risingwave_common::catalog::TableId;
 use risingwave_common::hash::{calc_hash_key_kind, HashKey, HashKeyDispatcher};
+use risingwave_pb::stream_plan::WatermarkCleanup;
 
 use super::*;
 use crate::executor::aggregation::AggCall;
+use crate::executor::monitor::StreamingMetrics;
 use crate::executor::{HashAggExecutor, PkIndices};
 
 struct HashAggExecutorDispatcher<S: StateStore>(PhantomData<S>);
@@ -32,6 +36,11 @@ struct HashAggExecutorDispatcherArgs<S: StateStore> {
     keyspace: Vec<Keyspace<S>>,
     pk_indices: PkIndices,
     executor_id: u64,
+    actor_id: u64,
+    metrics: Arc<StreamingMetrics>,
+    watermark_cleanup: Option<WatermarkCleanup>,
+    state_fetch_concurrency: usize,
+    reemit: Option<(S, Option<Bitmap>)>,
 }
 
 impl<S: StateStore> HashKeyDispatcher for HashAggExecutorDispatcher<S> {
@@ -39,15 +48,27 @@ impl<S: StateStore> HashKeyDispatcher for HashAggExecutorDispatcher<S> {
     type Output = Result<BoxedExecutor>;
 
     fn dispatch<K: HashKey>(args: Self::Input) -> Self::Output {
-        Ok(HashAggExecutor::<K, S>::new(
+        let executor = HashAggExecutor::<K, S>::new(
             args.input,
             args.agg_calls,
             args.keyspace,
             args.pk_indices,
             args.executor_id,
             args.key_indices,
+            args.actor_id,
+            args.metrics,
         )?
-        .boxed())
+        .with_state_fetch_concurrency(args.state_fetch_concurrency);
+        let executor = match args.reemit {
+            Some((state_store, vnodes)) => executor.with_reemit(state_store, vnodes),
+            None => executor,
+        };
+        Ok(match args.watermark_cleanup {
+            Some(cleanup) => executor
+                .with_watermark(cleanup.key_index as usize, cleanup.delay_ms)
+                .boxed(),
+            None => executor.boxed(),
+        })
     }
 }
 
@@ -91,6 +112,11 @@ impl ExecutorBuilder for HashAggExecutorBuilder {
             keyspace,
             pk_indices: params.pk_indices,
             executor_id: params.executor_id,
+            actor_id: params.actor_id as u64,
+            metrics: params.executor_stats,
+            watermark_cleanup: node.watermark_cleanup.clone(),
+            state_fetch_concurrency: params.env.config().state_fetch_concurrency,
+            reemit: node.reemit.then(|| (store, params.vnode_bitmap)),
         };
         HashAggExecutorDispatcher::dispatch_by_kind(kind, args)
     }
@@ -126,6 +126,15 @@ impl ExecutorBuilder for HashJoinExecutorBuilder {
             is_append_only,
             actor_id: params.actor_id as u64,
             metrics: params.executor_stats,
+            left_watermark: node
+                .left_watermark
+                .as_ref()
+                .map(|w| (w.key_index as usize, w.delay_ms)),
+            right_watermark: node
+                .right_watermark
+                .as_ref()
+                .map(|w| (w.key_index as usize, w.delay_ms)),
+            state_fetch_concurrency: params.env.config().state_fetch_concurrency,
         };
 
         for_all_join_types! { impl_create_hash_join_executor };
@@ -151,6 +160,9 @@ struct HashJoinExecutorDispatcherArgs<S: StateStore> {
     is_append_only: bool,
     actor_id: u64,
     metrics: Arc<StreamingMetrics>,
+    left_watermark: Option<(usize, u64)>,
+    right_watermark: Option<(usize, u64)>,
+    state_fetch_concurrency: usize,
 }
 
 impl<S: StateStore, const T: JoinTypePrimitive> HashKeyDispatcher
@@ -160,7 +172,7 @@ impl<S: StateStore, const T: JoinTypePrimitive> HashKeyDispatcher
     type Output = Result<BoxedExecutor>;
 
     fn dispatch<K: HashKey>(args: Self::Input) -> Self::Output {
-        Ok(Box::new(HashJoinExecutor::<K, S, T>::new(
+        let executor = HashJoinExecutor::<K, S, T>::new(
             args.source_l,
             args.source_r,
             args.params_l,
@@ -175,6 +187,11 @@ impl<S: StateStore, const T: JoinTypePrimitive> HashKeyDispatcher
             args.keyspace_r,
             args.is_append_only,
             args.metrics,
+        )
+        .with_state_fetch_concurrency(args.state_fetch_concurrency);
+        Ok(Box::new(executor.with_watermarks(
+            args.left_watermark,
+            args.right_watermark,
         )))
     }
 }
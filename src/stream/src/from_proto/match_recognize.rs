@@ -0,0 +1,73 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risingwave_common::catalog::TableId;
+use risingwave_expr::expr::build_from_prost;
+
+use super::*;
+use crate::executor::{MatchRecognizeExecutor, MatchRecognizeMeasure, PatternTerm};
+
+pub struct MatchRecognizeExecutorBuilder;
+
+impl ExecutorBuilder for MatchRecognizeExecutorBuilder {
+    fn new_boxed_executor(
+        mut params: ExecutorParams,
+        node: &StreamNode,
+        store: impl StateStore,
+        _stream: &mut LocalStreamManagerCore,
+    ) -> Result<BoxedExecutor> {
+        let node = try_match_expand!(node.get_node_body().unwrap(), NodeBody::MatchRecognize)?;
+        let partition_by = node.partition_by.iter().map(|idx| *idx as usize).collect();
+        let pattern = node
+            .pattern
+            .iter()
+            .map(|term| {
+                Ok(PatternTerm {
+                    quantifier: term.get_quantifier()?,
+                    condition: build_from_prost(term.get_condition()?)?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let measures = node
+            .measures
+            .iter()
+            .map(|measure| {
+                Ok(MatchRecognizeMeasure {
+                    kind: measure.get_kind()?,
+                    term: measure.term as usize,
+                    column: measure.column as usize,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let table = node.get_state_table()?;
+        let keyspace = Keyspace::table_root(store, &TableId::from(table.id));
+        let dist_keys = table
+            .distribution_keys
+            .iter()
+            .map(|key| *key as usize)
+            .collect_vec();
+
+        Ok(Box::new(MatchRecognizeExecutor::new(
+            params.input.remove(0),
+            partition_by,
+            pattern,
+            measures,
+            keyspace,
+            dist_keys,
+            params.pk_indices,
+            params.executor_id,
+        )))
+    }
+}
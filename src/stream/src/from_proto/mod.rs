@@ -14,20 +14,27 @@
 
 //! Build executor from protobuf.
 
+mod asof_join;
 mod batch_query;
 mod chain;
+mod dynamic_filter;
 mod filter;
+mod gap_fill;
 mod global_simple_agg;
 mod hash_agg;
 mod hash_join;
 mod hop_window;
+mod local_hash_agg;
 mod local_simple_agg;
 mod lookup;
 mod lookup_union;
+mod match_recognize;
 mod merge;
 mod mview;
+mod now;
 mod project;
 mod source;
+mod tokenize;
 mod top_n;
 mod top_n_appendonly;
 mod union;
@@ -40,20 +47,27 @@ use risingwave_pb::stream_plan::stream_node::NodeBody;
 use risingwave_pb::stream_plan::StreamNode;
 use risingwave_storage::{Keyspace, StateStore};
 
+use self::asof_join::*;
 use self::batch_query::*;
 use self::chain::*;
+use self::dynamic_filter::*;
 use self::filter::*;
+use self::gap_fill::*;
 use self::global_simple_agg::*;
 use self::hash_agg::*;
 use self::hash_join::*;
 use self::hop_window::*;
+use self::local_hash_agg::*;
 use self::local_simple_agg::*;
 use self::lookup::*;
 use self::lookup_union::*;
+use self::match_recognize::*;
 use self::merge::*;
 use self::mview::*;
+use self::now::*;
 use self::project::*;
 use self::source::*;
+use self::tokenize::*;
 use self::top_n::*;
 use self::top_n_appendonly::*;
 use self::union::*;
@@ -105,6 +119,7 @@ pub fn create_executor(
         NodeBody::TopN => TopNExecutorBuilder,
         NodeBody::AppendOnlyTopN => AppendOnlyTopNExecutorBuilder,
         NodeBody::LocalSimpleAgg => LocalSimpleAggExecutorBuilder,
+        NodeBody::LocalHashAgg => LocalHashAggExecutorBuilder,
         NodeBody::GlobalSimpleAgg => SimpleAggExecutorBuilder,
         NodeBody::HashAgg => HashAggExecutorBuilder,
         NodeBody::HashJoin => HashJoinExecutorBuilder,
@@ -118,5 +133,11 @@ pub fn create_executor(
         NodeBody::Lookup => LookupExecutorBuilder,
         NodeBody::Union => UnionExecutorBuilder,
         NodeBody::LookupUnion => LookupUnionExecutorBuilder,
+        NodeBody::Now => NowExecutorBuilder,
+        NodeBody::DynamicFilter => DynamicFilterExecutorBuilder,
+        NodeBody::MatchRecognize => MatchRecognizeExecutorBuilder,
+        NodeBody::AsOfJoin => AsOfJoinExecutorBuilder,
+        NodeBody::Tokenize => TokenizeExecutorBuilder,
+        NodeBody::GapFill => GapFillExecutorBuilder,
     }
 }
@@ -14,6 +14,7 @@
 
 use risingwave_common::catalog::{ColumnId, TableId};
 use risingwave_common::util::sort_util::OrderPair;
+use risingwave_pb::plan_common::HandleConflictBehavior;
 
 use super::*;
 use crate::executor::MaterializeExecutor;
@@ -56,6 +57,7 @@ impl ExecutorBuilder for MaterializeExecutorBuilder {
             column_ids,
             params.executor_id,
             distribution_keys,
+            node.handle_pk_conflict_behavior(),
         );
 
         Ok(executor.boxed())
@@ -102,6 +104,7 @@ impl ExecutorBuilder for ArrangeExecutorBuilder {
             column_ids,
             params.executor_id,
             distribution_keys,
+            HandleConflictBehavior::NoCheck,
         );
 
         Ok(executor.boxed())
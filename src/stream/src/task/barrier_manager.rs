@@ -14,7 +14,9 @@
 
 use madsim::collections::{HashMap, HashSet};
 use risingwave_common::error::Result;
-use risingwave_pb::stream_service::barrier_complete_response::CreateMviewProgress as ProstCreateMviewProgress;
+use risingwave_pb::stream_service::barrier_complete_response::{
+    ActorStats, CreateMviewProgress as ProstCreateMviewProgress,
+};
 use tokio::sync::mpsc::UnboundedSender;
 use tokio::sync::oneshot;
 
@@ -40,6 +42,8 @@ pub struct CollectResult {
     pub create_mview_progress: Vec<ProstCreateMviewProgress>,
 
     pub synced_sstables: Vec<LocalSstableInfo>,
+
+    pub actor_stats: Vec<ActorStats>,
 }
 
 enum BarrierState {
@@ -175,6 +179,18 @@ impl LocalBarrierManager {
             .expect("no rx for local mode")
     }
 
+    /// Report the statistics of an actor for the epoch, before the actor collects the barrier.
+    pub fn update_actor_stats(&mut self, stats: ActorStats) {
+        match &mut self.state {
+            #[cfg(test)]
+            BarrierState::Local => {}
+
+            BarrierState::Managed(managed_state) => {
+                managed_state.update_actor_stats(stats);
+            }
+        }
+    }
+
     /// When a [`StreamConsumer`] (typically [`DispatchExecutor`]) get a barrier, it should report
     /// and collect this barrier with its own `actor_id` using this function.
     pub fn collect(&mut self, actor_id: ActorId, barrier: &Barrier) -> Result<()> {
@@ -16,7 +16,7 @@ use std::collections::HashMap;
 use std::iter::once;
 
 use madsim::collections::HashSet;
-use risingwave_pb::stream_service::barrier_complete_response::CreateMviewProgress;
+use risingwave_pb::stream_service::barrier_complete_response::{ActorStats, CreateMviewProgress};
 use tokio::sync::oneshot;
 
 use super::progress::ChainState;
@@ -60,6 +60,9 @@ pub(super) struct ManagedBarrierState {
     inner: ManagedBarrierStateInner,
 
     pub create_mview_progress: HashMap<ActorId, ChainState>,
+
+    /// Statistics of the actors since the last barrier was notified.
+    actor_stats: HashMap<ActorId, ActorStats>,
 }
 
 impl ManagedBarrierState {
@@ -71,6 +74,25 @@ impl ManagedBarrierState {
                 last_epoch: None,
             },
             create_mview_progress: Default::default(),
+            actor_stats: Default::default(),
+        }
+    }
+
+    /// Accumulate the statistics of an actor. An actor may report for the next epoch before the
+    /// barrier of this epoch is collected from all actors, in which case both are reported
+    /// together.
+    pub(super) fn update_actor_stats(&mut self, stats: ActorStats) {
+        match self.actor_stats.get_mut(&stats.actor_id) {
+            Some(acc) => {
+                acc.input_rows += stats.input_rows;
+                acc.output_rows += stats.output_rows;
+                acc.processing_latency_ns =
+                    acc.processing_latency_ns.max(stats.processing_latency_ns);
+                acc.output_blocking_ns += stats.output_blocking_ns;
+            }
+            None => {
+                self.actor_stats.insert(stats.actor_id, stats);
+            }
         }
     }
 
@@ -120,6 +142,9 @@ impl ManagedBarrierState {
                     let result = CollectResult {
                         create_mview_progress,
                         synced_sstables: vec![],
+                        actor_stats: std::mem::take(&mut self.actor_stats)
+                            .into_values()
+                            .collect(),
                     };
                     if collect_notifier.send(result).is_err() {
                         warn!("failed to notify barrier collection with epoch {}", epoch)
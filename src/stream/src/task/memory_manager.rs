@@ -0,0 +1,142 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use risingwave_common::collection::evictable::{
+    cache_capacity_permille, set_cache_capacity_permille,
+};
+use risingwave_common::config::StreamingConfig;
+use tikv_jemalloc_ctl::{epoch, stats};
+use tokio::sync::oneshot::Sender;
+use tokio::task::JoinHandle;
+
+use crate::executor::monitor::StreamingMetrics;
+
+/// Usage ratio of the memory available above which caches are shrunk quickly.
+const AGGRESSIVE_THRESHOLD: f64 = 0.9;
+/// Usage ratio of the memory available above which caches are shrunk gradually.
+const GRACEFUL_THRESHOLD: f64 = 0.8;
+/// Usage ratio of the memory available below which caches grow back.
+const RELAXED_THRESHOLD: f64 = 0.7;
+
+/// Caches never shrink below this fraction in permille of their target capacity.
+const MIN_CACHE_CAPACITY_PERMILLE: usize = 10;
+/// Fraction in permille of the target capacity caches grow back by each interval.
+const CACHE_CAPACITY_GROW_STEP: usize = 50;
+
+/// Watches the memory allocated by the compute node and shrinks the executor caches (agg groups,
+/// join entries and top-n states) under memory pressure, trading hit ratio for stability instead
+/// of getting the node killed for running out of memory.
+///
+/// The memory allocated is read from jemalloc. Caches are shrunk by lowering the fraction of their
+/// target capacity they keep when evicting at barriers, see
+/// [`scaled_capacity`](risingwave_common::collection::evictable::scaled_capacity).
+pub struct GlobalMemoryManager {
+    /// Memory in bytes the compute node may use.
+    total_memory_available_bytes: usize,
+    interval: Duration,
+    metrics: Arc<StreamingMetrics>,
+}
+
+impl GlobalMemoryManager {
+    pub fn new(config: &StreamingConfig, metrics: Arc<StreamingMetrics>) -> Self {
+        Self {
+            total_memory_available_bytes: config.total_memory_available_bytes,
+            interval: Duration::from_millis(config.memory_manager_interval_ms),
+            metrics,
+        }
+    }
+
+    /// Starts the memory manager. Returns `None` if no memory limit is configured.
+    pub fn start(self) -> Option<(JoinHandle<()>, Sender<()>)> {
+        if self.total_memory_available_bytes == 0 {
+            return None;
+        }
+        let (shutdown_tx, mut shutdown_rx) = tokio::sync::oneshot::channel();
+        let join_handle = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(self.interval);
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {},
+                    _ = &mut shutdown_rx => {
+                        tracing::info!("Memory manager is shutting down");
+                        return;
+                    }
+                }
+                match Self::allocated_bytes() {
+                    Ok(allocated) => self.tick(allocated),
+                    Err(err) => tracing::warn!("Failed to read jemalloc stats: {}", err),
+                }
+            }
+        });
+        Some((join_handle, shutdown_tx))
+    }
+
+    fn allocated_bytes() -> Result<usize, tikv_jemalloc_ctl::Error> {
+        // Stats are cached by jemalloc until the epoch is advanced.
+        epoch::advance()?;
+        stats::allocated::read()
+    }
+
+    fn tick(&self, allocated: usize) {
+        let current = cache_capacity_permille();
+        let next = next_cache_capacity_permille(
+            current,
+            allocated as f64 / self.total_memory_available_bytes as f64,
+        );
+        if next != current {
+            tracing::info!(
+                "Memory allocated {} of {} bytes, resizing caches to {} permille of their capacity",
+                allocated,
+                self.total_memory_available_bytes,
+                next
+            );
+            set_cache_capacity_permille(next);
+        }
+        self.metrics.memory_allocated_bytes.set(allocated as i64);
+        self.metrics.cache_capacity_permille.set(next as i64);
+    }
+}
+
+/// Returns the fraction in permille of their target capacity caches keep, given the current one
+/// and the ratio of the memory available that is allocated.
+fn next_cache_capacity_permille(current: usize, usage_ratio: f64) -> usize {
+    let next = if usage_ratio > AGGRESSIVE_THRESHOLD {
+        current / 2
+    } else if usage_ratio > GRACEFUL_THRESHOLD {
+        current * 9 / 10
+    } else if usage_ratio < RELAXED_THRESHOLD {
+        current + CACHE_CAPACITY_GROW_STEP
+    } else {
+        current
+    };
+    next.clamp(MIN_CACHE_CAPACITY_PERMILLE, 1000)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_cache_capacity_permille() {
+        assert_eq!(next_cache_capacity_permille(1000, 0.5), 1000);
+        assert_eq!(next_cache_capacity_permille(500, 0.5), 550);
+        assert_eq!(next_cache_capacity_permille(500, 0.75), 500);
+        assert_eq!(next_cache_capacity_permille(1000, 0.85), 900);
+        assert_eq!(next_cache_capacity_permille(1000, 0.95), 500);
+        assert_eq!(next_cache_capacity_permille(15, 1.5), 10);
+    }
+}
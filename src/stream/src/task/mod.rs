@@ -24,10 +24,14 @@ use crate::executor::Message;
 
 mod barrier_manager;
 mod env;
+mod memory_manager;
+mod savepoint;
 mod stream_manager;
 
 pub use barrier_manager::*;
 pub use env::*;
+pub use memory_manager::*;
+pub use savepoint::*;
 pub use stream_manager::*;
 
 /// Default capacity of channel if two actors are on the same node
@@ -0,0 +1,167 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::cmp::Ordering;
+
+use bytes::Bytes;
+use risingwave_storage::error::StorageResult;
+use risingwave_storage::storage_value::StorageValue;
+use risingwave_storage::{Keyspace, StateStore, StateStoreIter};
+
+/// Number of changed keys written in one batch when restoring a table.
+const RESTORE_BATCH_SIZE: usize = 4096;
+
+/// Rolls the table of `keyspace` back to the snapshot of `savepoint_epoch`, by comparing it with
+/// the snapshot of `current_epoch` and writing the difference at `write_epoch`: keys added since
+/// the savepoint are deleted, and keys removed or updated since then are put back with the value
+/// at the savepoint. Returns the number of keys written.
+///
+/// No one else should write the table until `write_epoch` is committed.
+pub async fn restore_table<S: StateStore>(
+    keyspace: &Keyspace<S>,
+    savepoint_epoch: u64,
+    current_epoch: u64,
+    write_epoch: u64,
+) -> StorageResult<usize> {
+    let mut savepoint_iter = keyspace.iter(savepoint_epoch).await?;
+    let mut current_iter = keyspace.iter(current_epoch).await?;
+    let mut savepoint_next = savepoint_iter.next().await?;
+    let mut current_next = current_iter.next().await?;
+
+    let mut restored = 0;
+    let mut changes = Vec::with_capacity(RESTORE_BATCH_SIZE);
+    loop {
+        let ordering = match (&savepoint_next, &current_next) {
+            (None, None) => break,
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (Some((savepoint_key, _)), Some((current_key, _))) => savepoint_key.cmp(current_key),
+        };
+        match ordering {
+            // Removed since the savepoint.
+            Ordering::Less => {
+                let (key, value) = savepoint_next.take().unwrap();
+                changes.push((key, Some(value)));
+                savepoint_next = savepoint_iter.next().await?;
+            }
+            // Added since the savepoint.
+            Ordering::Greater => {
+                let (key, _) = current_next.take().unwrap();
+                changes.push((key, None));
+                current_next = current_iter.next().await?;
+            }
+            // Possibly updated since the savepoint.
+            Ordering::Equal => {
+                let (key, value) = savepoint_next.take().unwrap();
+                let (_, current_value) = current_next.take().unwrap();
+                if value != current_value {
+                    changes.push((key, Some(value)));
+                }
+                savepoint_next = savepoint_iter.next().await?;
+                current_next = current_iter.next().await?;
+            }
+        }
+        if changes.len() >= RESTORE_BATCH_SIZE {
+            restored += changes.len();
+            ingest_changes(keyspace, std::mem::take(&mut changes), write_epoch).await?;
+        }
+    }
+    restored += changes.len();
+    ingest_changes(keyspace, changes, write_epoch).await?;
+
+    Ok(restored)
+}
+
+/// Writes the changes to the keys of `keyspace`, where `None` means deleting the key.
+async fn ingest_changes<S: StateStore>(
+    keyspace: &Keyspace<S>,
+    changes: Vec<(Bytes, Option<Bytes>)>,
+    epoch: u64,
+) -> StorageResult<()> {
+    if changes.is_empty() {
+        return Ok(());
+    }
+    let mut write_batch = keyspace.state_store().start_write_batch();
+    let mut local = write_batch.prefixify(keyspace);
+    for (key, value) in changes {
+        match value {
+            Some(value) => local.put(key, StorageValue::new_default_put(value)),
+            None => local.delete(key),
+        }
+    }
+    write_batch.ingest(epoch).await
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::catalog::TableId;
+    use risingwave_storage::memory::MemoryStateStore;
+
+    use super::*;
+
+    async fn write<S: StateStore>(
+        keyspace: &Keyspace<S>,
+        kvs: &[(&str, Option<&str>)],
+        epoch: u64,
+    ) {
+        let changes = kvs
+            .iter()
+            .map(|(k, v)| {
+                (
+                    Bytes::copy_from_slice(k.as_bytes()),
+                    v.map(|v| Bytes::copy_from_slice(v.as_bytes())),
+                )
+            })
+            .collect();
+        ingest_changes(keyspace, changes, epoch).await.unwrap();
+    }
+
+    async fn scan<S: StateStore>(keyspace: &Keyspace<S>, epoch: u64) -> Vec<(Bytes, Bytes)> {
+        keyspace.scan(None, epoch).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_restore_table() {
+        let store = MemoryStateStore::new();
+        let keyspace = Keyspace::table_root(store.clone(), &TableId::new(1));
+        let other = Keyspace::table_root(store, &TableId::new(2));
+
+        write(
+            &keyspace,
+            &[("a", Some("1")), ("b", Some("2")), ("c", Some("3"))],
+            1,
+        )
+        .await;
+        write(&other, &[("a", Some("1"))], 1).await;
+        let savepoint = scan(&keyspace, 1).await;
+
+        // Delete, update and insert after the savepoint.
+        write(
+            &keyspace,
+            &[("a", None), ("b", Some("4")), ("d", Some("5"))],
+            2,
+        )
+        .await;
+        write(&other, &[("b", Some("2"))], 2).await;
+
+        let restored = restore_table(&keyspace, 1, 2, 3).await.unwrap();
+        assert_eq!(restored, 3);
+        assert_eq!(scan(&keyspace, 3).await, savepoint);
+        // Other tables are untouched.
+        assert_eq!(scan(&other, 3).await.len(), 2);
+
+        // Nothing to write if the table is at the savepoint.
+        assert_eq!(restore_table(&keyspace, 1, 3, 4).await.unwrap(), 0);
+    }
+}
@@ -20,20 +20,23 @@ use itertools::Itertools;
 use madsim::collections::{HashMap, HashSet};
 use parking_lot::Mutex;
 use risingwave_common::buffer::Bitmap;
+use risingwave_common::catalog::TableId;
 use risingwave_common::config::StreamingConfig;
 use risingwave_common::error::{ErrorCode, Result, RwError};
 use risingwave_common::try_match_expand;
 use risingwave_common::util::addr::{is_local_address, HostAddr};
 use risingwave_common::util::compress::decompress_data;
+use risingwave_hummock_sdk::LocalSstableInfo;
 use risingwave_pb::common::ActorInfo;
 use risingwave_pb::stream_plan::stream_node::NodeBody;
 use risingwave_pb::{stream_plan, stream_service};
-use risingwave_rpc_client::ComputeClientPool;
-use risingwave_storage::{dispatch_state_store, StateStore, StateStoreImpl};
+use risingwave_rpc_client::ComputeClientPoolRef;
+use risingwave_storage::{dispatch_state_store, Keyspace, StateStore, StateStoreImpl};
 use tokio::sync::mpsc::{channel, Receiver};
+use tokio::sync::Notify;
 use tokio::task::JoinHandle;
 
-use super::{unique_executor_id, unique_operator_id, CollectResult};
+use super::{restore_table, unique_executor_id, unique_operator_id, CollectResult};
 use crate::executor::dispatch::*;
 use crate::executor::merge::RemoteInput;
 use crate::executor::monitor::StreamingMetrics;
@@ -80,15 +83,21 @@ pub struct LocalStreamManagerCore {
     ///
     /// TODO: currently the client pool won't be cleared. Should remove compute clients when
     /// disconnected.
-    compute_client_pool: ComputeClientPool,
+    compute_client_pool: ComputeClientPoolRef,
 
     /// Config of streaming engine
     pub(crate) config: StreamingConfig,
+
+    /// Epochs collected but not synced yet, which are synced by the next checkpoint barrier.
+    uncheckpointed_epochs: Vec<u64>,
 }
 
 /// `LocalStreamManager` manages all stream executors in this project.
 pub struct LocalStreamManager {
     core: Mutex<LocalStreamManagerCore>,
+
+    /// Notified when a checkpoint barrier is collected and the state store is synced.
+    checkpoint_notify: Notify,
 }
 
 pub struct ExecutorParams {
@@ -139,6 +148,7 @@ impl LocalStreamManager {
     fn with_core(core: LocalStreamManagerCore) -> Self {
         Self {
             core: Mutex::new(core),
+            checkpoint_notify: Notify::new(),
         }
     }
 
@@ -147,12 +157,14 @@ impl LocalStreamManager {
         state_store: StateStoreImpl,
         streaming_metrics: Arc<StreamingMetrics>,
         config: StreamingConfig,
+        compute_client_pool: ComputeClientPoolRef,
     ) -> Self {
         Self::with_core(LocalStreamManagerCore::new(
             addr,
             state_store,
             streaming_metrics,
             config,
+            compute_client_pool,
         ))
     }
 
@@ -175,7 +187,8 @@ impl LocalStreamManager {
     }
 
     /// Use `prev_epoch` to find collect rx. And wait for all actor to be collected before
-    /// returning.
+    /// returning. If `need_sync`, the writes of `prev_epoch` and the previous epochs not synced yet
+    /// are synced.
     pub async fn collect_barrier_and_sync(
         &self,
         prev_epoch: u64,
@@ -191,25 +204,48 @@ impl LocalStreamManager {
 
         // Sync states from shared buffer to S3 before telling meta service we've done.
         if need_sync {
+            let epochs = {
+                let mut core = self.core.lock();
+                let mut epochs = std::mem::take(&mut core.uncheckpointed_epochs);
+                epochs.push(prev_epoch);
+                epochs
+            };
             dispatch_state_store!(self.state_store(), store, {
-                match store.sync(Some(prev_epoch)).await {
-                    Ok(_) => {
-                        collect_result.synced_sstables =
-                        store.get_uncommitted_ssts(prev_epoch);
+                for epoch in epochs {
+                    match store.sync(Some(epoch)).await {
+                        Ok(_) => {
+                            collect_result
+                                .synced_sstables
+                                .extend(store.get_uncommitted_ssts(epoch));
+                        }
+                        // TODO: Handle sync failure by propagating it
+                        // back to global barrier manager
+                        Err(e) => panic!(
+                            "Failed to sync state store after receiving barrier prev_epoch {:?} due to {}",
+                            epoch, e
+                        ),
                     }
-                    // TODO: Handle sync failure by propagating it
-                    // back to global barrier manager
-                    Err(e) => panic!(
-                        "Failed to sync state store after receiving barrier prev_epoch {:?} due to {}",
-                        prev_epoch, e
-                    ),
                 }
             });
+            self.checkpoint_notify.notify_waiters();
+        } else {
+            self.core.lock().uncheckpointed_epochs.push(prev_epoch);
         }
 
         collect_result
     }
 
+    /// Waits for the next checkpoint barrier to be collected and synced, so that the state
+    /// written so far is persisted. Used in graceful shutdown. Returns immediately if there's no
+    /// actor on this node.
+    pub async fn wait_next_checkpoint(&self) {
+        let notified = self.checkpoint_notify.notified();
+        if self.core.lock().handles.is_empty() {
+            return;
+        }
+        notified.await;
+    }
+
     /// Broadcast a barrier to all senders. Returns immediately, and caller won't be notified when
     /// this barrier is finished.
     #[cfg(test)]
@@ -258,6 +294,33 @@ impl LocalStreamManager {
         Ok(())
     }
 
+    /// Rolls the tables back to `savepoint_epoch` by writing the difference from `current_epoch`
+    /// at `write_epoch`, see [`restore_table`]. The writes are synced, and the SSTs are returned
+    /// for meta to commit. Called in recovery, when all actors are stopped.
+    pub async fn restore_tables(
+        &self,
+        table_ids: &[TableId],
+        savepoint_epoch: u64,
+        current_epoch: u64,
+        write_epoch: u64,
+    ) -> Result<Vec<LocalSstableInfo>> {
+        dispatch_state_store!(self.state_store(), store, {
+            for table_id in table_ids {
+                let keyspace = Keyspace::table_root(store.clone(), table_id);
+                let restored =
+                    restore_table(&keyspace, savepoint_epoch, current_epoch, write_epoch).await?;
+                tracing::info!(
+                    "restored {} keys of table {} to savepoint {}",
+                    restored,
+                    table_id,
+                    savepoint_epoch
+                );
+            }
+            store.sync(Some(write_epoch)).await?;
+            Ok(store.get_uncommitted_ssts(write_epoch))
+        })
+    }
+
     pub fn take_receiver(&self, ids: UpDownActorIds) -> Result<Receiver<Message>> {
         let core = self.core.lock();
         core.context.take_receiver(&ids)
@@ -339,9 +402,16 @@ impl LocalStreamManagerCore {
         state_store: StateStoreImpl,
         streaming_metrics: Arc<StreamingMetrics>,
         config: StreamingConfig,
+        compute_client_pool: ComputeClientPoolRef,
     ) -> Self {
         let context = SharedContext::new(addr);
-        Self::with_store_and_context(state_store, context, streaming_metrics, config)
+        Self::with_store_and_context(
+            state_store,
+            context,
+            streaming_metrics,
+            config,
+            compute_client_pool,
+        )
     }
 
     fn with_store_and_context(
@@ -349,6 +419,7 @@ impl LocalStreamManagerCore {
         context: SharedContext,
         streaming_metrics: Arc<StreamingMetrics>,
         config: StreamingConfig,
+        compute_client_pool: ComputeClientPoolRef,
     ) -> Self {
         let (tx, rx) = channel(LOCAL_OUTPUT_CHANNEL_SIZE);
 
@@ -361,13 +432,15 @@ impl LocalStreamManagerCore {
             mock_source: (Some(tx), Some(rx)),
             state_store,
             streaming_metrics,
-            compute_client_pool: ComputeClientPool::new(u64::MAX),
+            compute_client_pool,
             config,
+            uncheckpointed_epochs: vec![],
         }
     }
 
     #[cfg(test)]
     fn for_test() -> Self {
+        use risingwave_rpc_client::ComputeClientPool;
         use risingwave_storage::monitor::StateStoreMetrics;
 
         let register = prometheus::Registry::new();
@@ -377,6 +450,7 @@ impl LocalStreamManagerCore {
             SharedContext::for_test(),
             streaming_metrics,
             StreamingConfig::default(),
+            Arc::new(ComputeClientPool::default()),
         )
     }
 
@@ -394,6 +468,7 @@ impl LocalStreamManagerCore {
         input: BoxedExecutor,
         dispatchers: &[stream_plan::Dispatcher],
         actor_id: ActorId,
+        actor_context: &ActorContextRef,
     ) -> Result<impl StreamConsumer> {
         // create downstream receivers
         let mut dispatcher_impls = Vec::with_capacity(dispatchers.len());
@@ -450,6 +525,7 @@ impl LocalStreamManagerCore {
             dispatcher_impls,
             actor_id,
             self.context.clone(),
+            actor_context.clone(),
         ))
     }
 
@@ -587,13 +663,16 @@ impl LocalStreamManagerCore {
 
                         let pool = self.compute_client_pool.clone();
                         let metrics = self.streaming_metrics.clone();
+                        let compression = self.config.exchange_compression.into();
                         tokio::spawn(async move {
                             let init_client = async move {
                                 let remote_input = RemoteInput::create(
-                                    pool.get_client_for_addr(upstream_addr).await?,
+                                    &pool,
+                                    upstream_addr,
                                     (up_id, actor_id),
                                     sender,
                                     metrics,
+                                    compression,
                                 )
                                 .await?;
                                 Ok::<_, RwError>(remote_input)
@@ -643,10 +722,13 @@ impl LocalStreamManagerCore {
                 vnode_bitmap,
             )?;
 
-            let dispatcher = self.create_dispatcher(executor, &actor.dispatcher, actor_id)?;
+            let dispatcher =
+                self.create_dispatcher(executor, &actor.dispatcher, actor_id, &actor_context)?;
             let actor = Actor::new(
                 dispatcher,
                 actor_id,
+                actor.fragment_id,
+                actor.table_id,
                 self.context.clone(),
                 self.streaming_metrics.clone(),
                 actor_context,
@@ -743,7 +825,9 @@ impl LocalStreamManagerCore {
     ) -> Result<()> {
         for actor in req.get_info() {
             let ret = self.actor_infos.insert(actor.get_actor_id(), actor.clone());
-            if let Some(prev_actor) = ret && actor != &prev_actor{
+            if let Some(prev_actor) = ret
+                && actor != &prev_actor
+            {
                 return Err(ErrorCode::InternalError(format!(
                     "actor info mismatch when broadcasting {}",
                     actor.get_actor_id()
@@ -777,6 +861,8 @@ impl LocalStreamManagerCore {
             handle.abort();
         }
         self.actor_infos.clear();
+        // Writes of the epochs not checkpointed are discarded by the recovery.
+        self.uncheckpointed_epochs.clear();
     }
 
     fn build_channel_for_chain_node(
@@ -0,0 +1,269 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use clap::StructOpt;
+use madsim::net::NetSim;
+use madsim::runtime::{Handle, NodeHandle};
+use pgwire::pg_server::{Session, SessionManager};
+use risingwave_common::catalog::{DEFAULT_DATABASE_NAME, DEFAULT_SUPPER_USER};
+use risingwave_frontend::session::{SessionImpl, SessionManagerImpl};
+
+/// Embedded cluster in the simulation runtime: one meta node, `compute_nodes` compute nodes and
+/// a client node which runs a frontend in process to execute SQL.
+pub struct Cluster {
+    config: Configuration,
+    handle: Handle,
+    meta: NodeHandle,
+    compute_nodes: Vec<NodeHandle>,
+    client: NodeHandle,
+    session_mgr: Arc<SessionManagerImpl>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Configuration {
+    /// Number of compute nodes.
+    pub compute_nodes: usize,
+    /// Time to wait for a node to be ready after it is started or restarted.
+    pub startup_delay: Duration,
+}
+
+impl Default for Configuration {
+    fn default() -> Self {
+        Self {
+            compute_nodes: 1,
+            startup_delay: Duration::from_secs(1),
+        }
+    }
+}
+
+const META_IP: &str = "192.168.1.1";
+const CLIENT_IP: &str = "192.168.100.1";
+
+fn compute_ip(i: usize) -> String {
+    format!("192.168.3.{}", i + 1)
+}
+
+impl Cluster {
+    /// Starts all nodes of the cluster. Must be called inside a madsim runtime, e.g. in a
+    /// `#[madsim::test]`.
+    pub async fn start(config: Configuration) -> Result<Self> {
+        let handle = Handle::current();
+
+        let meta = handle
+            .create_node()
+            .name("meta")
+            .ip(META_IP.parse::<IpAddr>()?)
+            .init(|| async {
+                let opts = risingwave_meta::MetaNodeOpts::parse_from([
+                    "meta-node",
+                    "--listen-addr",
+                    "0.0.0.0:5690",
+                    "--host",
+                    META_IP,
+                    "--backend",
+                    "mem",
+                ]);
+                risingwave_meta::start(opts).await
+            })
+            .build();
+        // wait for the meta service to be ready
+        madsim::time::sleep(config.startup_delay).await;
+
+        let compute_nodes = (0..config.compute_nodes)
+            .map(|i| {
+                let ip = compute_ip(i);
+                handle
+                    .create_node()
+                    .name(format!("compute-{}", i + 1))
+                    .ip(ip.parse::<IpAddr>().unwrap())
+                    .init(move || {
+                        let opts = risingwave_compute::ComputeNodeOpts::parse_from([
+                            "compute-node",
+                            "--host",
+                            "0.0.0.0:5688",
+                            "--client-address",
+                            &format!("{}:5688", ip),
+                            "--meta-address",
+                            &format!("http://{}:5690", META_IP),
+                            "--state-store",
+                            "hummock+memory-shared",
+                        ]);
+                        risingwave_compute::start(opts)
+                    })
+                    .build()
+            })
+            .collect();
+        madsim::time::sleep(config.startup_delay).await;
+
+        let client = handle
+            .create_node()
+            .name("client")
+            .ip(CLIENT_IP.parse::<IpAddr>()?)
+            .build();
+        let session_mgr = client
+            .spawn(async move {
+                let opts = risingwave_frontend::FrontendOpts::parse_from([
+                    "frontend-node",
+                    "--host",
+                    &format!("{}:4566", CLIENT_IP),
+                    "--meta-addr",
+                    &format!("http://{}:5690", META_IP),
+                ]);
+                SessionManagerImpl::new(&opts).await
+            })
+            .await
+            .map_err(|e| anyhow!("client task panicked: {}", e))?
+            .map_err(|e| anyhow!("failed to start frontend: {}", e))?;
+
+        Ok(Self {
+            config,
+            handle,
+            meta,
+            compute_nodes,
+            client,
+            session_mgr: Arc::new(session_mgr),
+        })
+    }
+
+    /// Opens a session to `database` on the frontend of the client node. Session variables set
+    /// by `SET` are kept until the session is dropped.
+    pub fn session(&self, database: &str) -> Result<ClusterSession> {
+        let session = self
+            .session_mgr
+            .connect(database, DEFAULT_SUPPER_USER)
+            .map_err(|e| anyhow!("failed to connect to {}: {}", database, e))?;
+        Ok(ClusterSession {
+            client: self.client.clone(),
+            session,
+        })
+    }
+
+    /// Runs a SQL statement on a new session to the default database. See
+    /// [`ClusterSession::run`].
+    pub async fn run(&self, sql: impl Into<String>) -> Result<String> {
+        self.session(DEFAULT_DATABASE_NAME)?.run(sql).await
+    }
+
+    /// Retries `run` until the result satisfies `f`, or fails after `timeout`. Used to wait
+    /// for streaming jobs to converge.
+    pub async fn wait_until(
+        &self,
+        sql: impl Into<String>,
+        mut f: impl FnMut(&str) -> bool,
+        interval: Duration,
+        timeout: Duration,
+    ) -> Result<String> {
+        let sql = sql.into();
+        let deadline = madsim::time::Instant::now() + timeout;
+        loop {
+            let result = self.run(sql.clone()).await?;
+            if f(&result) {
+                return Ok(result);
+            }
+            if madsim::time::Instant::now() >= deadline {
+                return Err(anyhow!(
+                    "`{}` does not converge in {:?}, last result: {}",
+                    sql,
+                    timeout,
+                    result
+                ));
+            }
+            madsim::time::sleep(interval).await;
+        }
+    }
+
+    /// Kills the compute node with the given index. Its in-flight RPCs fail immediately.
+    pub fn kill_compute_node(&self, i: usize) {
+        tracing::info!("kill compute-{}", i + 1);
+        self.handle.kill(self.compute_nodes[i].id());
+    }
+
+    /// Restarts the compute node with the given index, and waits for it to be ready.
+    pub async fn restart_compute_node(&self, i: usize) {
+        tracing::info!("restart compute-{}", i + 1);
+        self.handle.restart(self.compute_nodes[i].id());
+        madsim::time::sleep(self.config.startup_delay).await;
+    }
+
+    /// Restarts the meta node, and waits for it to be ready.
+    pub async fn restart_meta_node(&self) {
+        tracing::info!("restart meta");
+        self.handle.restart(self.meta.id());
+        madsim::time::sleep(self.config.startup_delay).await;
+    }
+
+    /// Cuts off all network traffic of the compute node with the given index.
+    pub fn partition_compute_node(&self, i: usize) {
+        tracing::info!("partition compute-{}", i + 1);
+        NetSim::current().clog_node(self.compute_nodes[i].id());
+    }
+
+    /// Heals the network partition of the compute node with the given index.
+    pub fn heal_compute_node(&self, i: usize) {
+        tracing::info!("heal compute-{}", i + 1);
+        NetSim::current().unclog_node(self.compute_nodes[i].id());
+    }
+
+    /// Shifts the clock used to generate epochs by `skew_ms`. A negative value moves the clock
+    /// backwards.
+    pub fn set_clock_skew(&self, skew_ms: i64) {
+        tracing::info!("set clock skew to {}ms", skew_ms);
+        risingwave_common::util::epoch::set_simulated_clock_skew(skew_ms);
+    }
+
+    pub fn config(&self) -> &Configuration {
+        &self.config
+    }
+}
+
+/// A session on the frontend of the client node.
+#[derive(Clone)]
+pub struct ClusterSession {
+    client: NodeHandle,
+    session: Arc<SessionImpl>,
+}
+
+impl ClusterSession {
+    /// Runs a SQL statement, and returns the result rows, with columns separated by spaces and
+    /// rows by new lines.
+    pub async fn run(&self, sql: impl Into<String>) -> Result<String> {
+        let sql = sql.into();
+        let session = self.session.clone();
+        self.client
+            .spawn(async move {
+                let rsp = session
+                    .run_statement(&sql)
+                    .await
+                    .map_err(|e| anyhow!("failed to run `{}`: {}", sql, e))?;
+                let rows = rsp
+                    .iter()
+                    .map(|row| {
+                        row.values()
+                            .iter()
+                            .map(|v| v.as_deref().unwrap_or("NULL"))
+                            .collect::<Vec<_>>()
+                            .join(" ")
+                    })
+                    .collect::<Vec<_>>();
+                Ok(rows.join("\n"))
+            })
+            .await
+            .map_err(|e| anyhow!("client task panicked: {}", e))?
+    }
+}
@@ -0,0 +1,28 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Deterministic simulation tests of RisingWave.
+//!
+//! All nodes of a cluster run as simulated nodes in one [madsim] runtime, so that network
+//! partitions, node crashes and clock skew can be injected at exact points and every failure can
+//! be reproduced with the same seed (`MADSIM_TEST_SEED`). Build with `--cfg madsim`, e.g.
+//! `./risedev sit-test`.
+//!
+//! The `risingwave_simulation` binary runs sqllogictest files against such a cluster, e.g.
+//! `./risedev sslt -- './e2e_test/streaming/**/*.slt' -j 8`.
+
+#![cfg(madsim)]
+
+pub mod cluster;
+pub mod slt;
@@ -0,0 +1,63 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg_attr(not(madsim), allow(dead_code))]
+
+use clap::Parser;
+
+/// Runs sqllogictest files against an embedded cluster in the simulation runtime.
+#[derive(Debug, Parser)]
+pub struct Args {
+    /// Glob of sqllogictest files.
+    files: String,
+
+    /// Number of compute nodes.
+    #[clap(long, default_value = "2")]
+    compute_nodes: usize,
+
+    /// Number of files to run in parallel. Each file runs in a new database if greater than 1.
+    #[clap(short, long, default_value = "1")]
+    jobs: usize,
+}
+
+#[cfg(not(madsim))]
+fn main() {
+    println!("This binary requires the simulation runtime. Run it with `./risedev sslt`.");
+}
+
+#[cfg(madsim)]
+fn main() {
+    use std::sync::Arc;
+
+    use risingwave_simulation::cluster::{Cluster, Configuration};
+    use risingwave_simulation::slt::run_slt_files;
+
+    let args = Args::parse();
+    risingwave_logging::oneshot_common();
+    risingwave_logging::init_risingwave_logger(false, false);
+
+    let runtime = madsim::runtime::Runtime::new();
+    let result = runtime.block_on(async move {
+        let cluster = Cluster::start(Configuration {
+            compute_nodes: args.compute_nodes,
+            ..Default::default()
+        })
+        .await?;
+        run_slt_files(Arc::new(cluster), &args.files, args.jobs).await
+    });
+    if let Err(e) = result {
+        eprintln!("{:#}", e);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,161 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runs sqllogictest files against an embedded [`Cluster`].
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{anyhow, bail, Result};
+use futures::{stream, StreamExt};
+use risingwave_common::catalog::{DEFAULT_DATABASE_NAME, PG_CATALOG_SCHEMA_NAME};
+use sqllogictest::Record;
+
+use crate::cluster::{Cluster, ClusterSession};
+
+/// Error of a SQL statement, which is compared with the expected error by sqllogictest.
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+pub struct SqlError(String);
+
+/// [`sqllogictest::AsyncDB`] on a session of the embedded cluster.
+struct SessionDB(ClusterSession);
+
+#[async_trait::async_trait]
+impl sqllogictest::AsyncDB for SessionDB {
+    type Error = SqlError;
+
+    async fn run(&mut self, sql: &str) -> Result<String, Self::Error> {
+        self.0
+            .run(sql)
+            .await
+            .map_err(|e| SqlError(format!("{:#}", e)))
+    }
+
+    fn engine_name(&self) -> &str {
+        "risingwave"
+    }
+}
+
+/// Runs one sqllogictest file on a session to `database`.
+///
+/// The runner of sqllogictest 0.5 blocks the thread on `sleep` records, which are used to wait
+/// for streaming jobs to converge. They are run here to advance the simulated clock instead.
+pub async fn run_slt_file(cluster: &Cluster, database: &str, path: &Path) -> Result<()> {
+    let records =
+        sqllogictest::parse_file(path).map_err(|e| anyhow!("{}: {}", path.display(), e))?;
+    let session = cluster.session(database)?;
+    let mut runner = sqllogictest::Runner::new(SessionDB(session));
+    for record in records {
+        match record {
+            Record::Halt { .. } => break,
+            Record::Sleep { duration, .. } => madsim::time::sleep(duration).await,
+            record => runner
+                .run_async(record)
+                .await
+                .map_err(|e| anyhow!("{}: {}", path.display(), e))?,
+        }
+    }
+    Ok(())
+}
+
+/// Drops `database` together with the relations and schemas left in it, e.g. by a file failing
+/// halfway. As relations may depend on each other, they are dropped in rounds until none is left.
+async fn drop_database(cluster: &Cluster, database: &str) -> Result<()> {
+    let session = cluster.session(database)?;
+    let schemas = session.run("show schemas;").await?;
+    let schemas = schemas
+        .lines()
+        .filter(|schema| *schema != PG_CATALOG_SCHEMA_NAME)
+        .collect::<Vec<_>>();
+    loop {
+        let mut drops = vec![];
+        for schema in &schemas {
+            for (kind, show) in [
+                ("materialized view", "materialized views"),
+                ("table", "tables"),
+                ("source", "sources"),
+            ] {
+                let names = session
+                    .run(format!("show {} from {};", show, schema))
+                    .await?;
+                drops.extend(
+                    names
+                        .lines()
+                        .map(|name| format!("drop {} {}.{};", kind, schema, name)),
+                );
+            }
+        }
+        if drops.is_empty() {
+            break;
+        }
+        let mut dropped = false;
+        for sql in drops {
+            dropped |= session.run(sql).await.is_ok();
+        }
+        if !dropped {
+            bail!("failed to drop the relations left in database {}", database);
+        }
+    }
+    for schema in schemas {
+        session.run(format!("drop schema {};", schema)).await?;
+    }
+    cluster.run(format!("drop database {};", database)).await?;
+    Ok(())
+}
+
+/// Runs all sqllogictest files matching `pattern`, at most `jobs` files at the same time.
+///
+/// When `jobs` is greater than 1, each file runs in a new database so that files don't see
+/// each other's tables.
+pub async fn run_slt_files(cluster: Arc<Cluster>, pattern: &str, jobs: usize) -> Result<()> {
+    let files = glob::glob(pattern)?.collect::<Result<Vec<PathBuf>, _>>()?;
+    if files.is_empty() {
+        bail!("no file matches {}", pattern);
+    }
+
+    let results = stream::iter(files.into_iter().enumerate())
+        .map(|(i, path)| {
+            let cluster = cluster.clone();
+            async move {
+                tracing::info!("running {}", path.display());
+                let result = if jobs <= 1 {
+                    run_slt_file(&cluster, DEFAULT_DATABASE_NAME, &path).await
+                } else {
+                    let database = format!("slt_{}", i);
+                    cluster
+                        .run(format!("create database {};", database))
+                        .await?;
+                    let result = run_slt_file(&cluster, &database, &path).await;
+                    // Drop the database even if the file fails, so that the streaming jobs
+                    // left by it don't slow down the other files.
+                    let dropped = drop_database(&cluster, &database).await;
+                    result.and(dropped)
+                };
+                if let Err(e) = &result {
+                    tracing::error!("{:#}", e);
+                }
+                result
+            }
+        })
+        .buffer_unordered(jobs.max(1))
+        .collect::<Vec<_>>()
+        .await;
+
+    let failed = results.iter().filter(|r| r.is_err()).count();
+    if failed > 0 {
+        bail!("{} of {} files failed", failed, results.len());
+    }
+    Ok(())
+}
@@ -0,0 +1,139 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Injects faults into the barrier and RPC layers through failpoints, and checks that the cluster
+//! recovers to a consistent state. Failpoints are global to the process, so each test is expected
+//! to run in its own process, which is the default of `cargo nextest`.
+
+#![cfg(all(madsim, feature = "failpoints"))]
+
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+use anyhow::Result;
+use risingwave_simulation::cluster::{Cluster, Configuration};
+
+const CONVERGE_INTERVAL: Duration = Duration::from_millis(500);
+const CONVERGE_TIMEOUT: Duration = Duration::from_secs(60);
+
+async fn start_cluster() -> Result<Cluster> {
+    let cluster = Cluster::start(Configuration {
+        compute_nodes: 2,
+        ..Default::default()
+    })
+    .await?;
+    cluster.run("create table t (v int);").await?;
+    cluster
+        .run("create materialized view mv as select count(*) as c, sum(v) as s from t;")
+        .await?;
+    Ok(cluster)
+}
+
+/// Inserts `rows` into `t`, which continue the rows inserted before from 1, and waits until `mv`
+/// reflects exactly `1..=rows.end()`, i.e. no row is lost or applied twice.
+async fn insert_and_check(cluster: &Cluster, rows: RangeInclusive<usize>) -> Result<()> {
+    let n = *rows.end();
+    for i in rows {
+        cluster
+            .run(format!("insert into t values ({});", i))
+            .await?;
+    }
+    let expected = format!("{} {}", n, n * (n + 1) / 2);
+    cluster
+        .wait_until(
+            "select * from mv;",
+            |result| result == expected,
+            CONVERGE_INTERVAL,
+            CONVERGE_TIMEOUT,
+        )
+        .await?;
+    Ok(())
+}
+
+/// Fails the failpoint `name` for `times` times after some data has been written, and checks that
+/// no data is lost or duplicated once the cluster recovers.
+async fn test_failpoint(name: &str, times: usize) -> Result<()> {
+    let cluster = start_cluster().await?;
+    insert_and_check(&cluster, 1..=10).await?;
+
+    fail::cfg(name, &format!("{}*return", times)).unwrap();
+    // Barriers keep being injected in the background, so the failpoint is hit even if the
+    // statements below do not touch it directly.
+    madsim::time::sleep(Duration::from_secs(5)).await;
+    fail::remove(name);
+
+    insert_and_check(&cluster, 11..=20).await?;
+    Ok(())
+}
+
+#[madsim::test]
+async fn test_inject_barrier_err() -> Result<()> {
+    test_failpoint("inject_barrier_err", 1).await
+}
+
+#[madsim::test]
+async fn test_collect_barrier_err() -> Result<()> {
+    test_failpoint("collect_barrier_err", 1).await
+}
+
+#[madsim::test]
+async fn test_commit_epoch_err() -> Result<()> {
+    test_failpoint("commit_epoch_err", 3).await
+}
+
+#[madsim::test]
+async fn test_sync_shared_buffer_epoch_err() -> Result<()> {
+    test_failpoint("sync_shared_buffer_epoch_err", 3).await
+}
+
+#[madsim::test]
+async fn test_stream_exchange_send_err() -> Result<()> {
+    let cluster = start_cluster().await?;
+    insert_and_check(&cluster, 1..=10).await?;
+
+    fail::cfg("stream_local_output_send_err", "1*return").unwrap();
+    fail::cfg("stream_remote_output_send_err", "1*return").unwrap();
+    // The data of this insertion reaches the exchange while the failpoints are active. It should
+    // be replayed after recovery instead of being lost.
+    cluster.run("insert into t values (0);").await?;
+    madsim::time::sleep(Duration::from_secs(5)).await;
+    fail::remove("stream_local_output_send_err");
+    fail::remove("stream_remote_output_send_err");
+
+    cluster.run("delete from t where v = 0;").await?;
+    insert_and_check(&cluster, 11..=20).await?;
+    Ok(())
+}
+
+#[madsim::test]
+async fn test_object_store_upload_err() -> Result<()> {
+    test_failpoint("mem_upload_err", 2).await
+}
+
+/// Fails creating the new job of `ALTER MATERIALIZED VIEW AS` after the old one is stopped, and
+/// checks that the view is rolled back to the old job with all of its states.
+#[madsim::test]
+async fn test_alter_mview_rollback() -> Result<()> {
+    let cluster = start_cluster().await?;
+    insert_and_check(&cluster, 1..=10).await?;
+
+    fail::cfg("replace_mview_err", "1*return").unwrap();
+    let alter = "alter materialized view mv as \
+                 select count(*) as c, sum(v) as s from t having count(*) > 0;";
+    assert!(cluster.run(alter).await.is_err());
+    fail::remove("replace_mview_err");
+
+    insert_and_check(&cluster, 11..=20).await?;
+    Ok(())
+}
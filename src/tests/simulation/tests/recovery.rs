@@ -0,0 +1,120 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(madsim)]
+
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+use anyhow::Result;
+use risingwave_simulation::cluster::{Cluster, Configuration};
+
+const CONVERGE_INTERVAL: Duration = Duration::from_millis(500);
+const CONVERGE_TIMEOUT: Duration = Duration::from_secs(60);
+
+async fn start_cluster(compute_nodes: usize) -> Result<Cluster> {
+    let cluster = Cluster::start(Configuration {
+        compute_nodes,
+        ..Default::default()
+    })
+    .await?;
+    cluster.run("create table t (v int);").await?;
+    cluster
+        .run("create materialized view mv as select count(*) as c, sum(v) as s from t;")
+        .await?;
+    Ok(cluster)
+}
+
+/// Inserts `rows` into `t`, which continue the rows inserted before from 1, and waits until `mv`
+/// reflects exactly `1..=rows.end()`, i.e. no row is lost or applied twice.
+async fn insert_and_check(cluster: &Cluster, rows: RangeInclusive<usize>) -> Result<()> {
+    let n = *rows.end();
+    for i in rows {
+        cluster
+            .run(format!("insert into t values ({});", i))
+            .await?;
+    }
+    cluster.run("flush;").await?;
+    let expected = format!("{} {}", n, n * (n + 1) / 2);
+    cluster
+        .wait_until(
+            "select * from mv;",
+            |result| result == expected,
+            CONVERGE_INTERVAL,
+            CONVERGE_TIMEOUT,
+        )
+        .await?;
+    Ok(())
+}
+
+#[madsim::test]
+async fn test_kill_compute_node() -> Result<()> {
+    let cluster = start_cluster(2).await?;
+    insert_and_check(&cluster, 1..=10).await?;
+
+    cluster.kill_compute_node(1);
+    cluster.restart_compute_node(1).await;
+    insert_and_check(&cluster, 11..=20).await?;
+    Ok(())
+}
+
+#[madsim::test]
+async fn test_restart_meta_node() -> Result<()> {
+    let cluster = start_cluster(1).await?;
+    insert_and_check(&cluster, 1..=10).await?;
+
+    cluster.restart_meta_node().await;
+    insert_and_check(&cluster, 11..=20).await?;
+    Ok(())
+}
+
+#[madsim::test]
+async fn test_network_partition() -> Result<()> {
+    let cluster = start_cluster(2).await?;
+    insert_and_check(&cluster, 1..=10).await?;
+
+    cluster.partition_compute_node(0);
+    madsim::time::sleep(Duration::from_secs(5)).await;
+    cluster.heal_compute_node(0);
+    insert_and_check(&cluster, 11..=20).await?;
+    Ok(())
+}
+
+#[madsim::test]
+async fn test_clock_skew() -> Result<()> {
+    let cluster = start_cluster(1).await?;
+    insert_and_check(&cluster, 1..=10).await?;
+
+    // Epochs must keep increasing even if the clock goes backwards.
+    cluster.set_clock_skew(-10_000);
+    insert_and_check(&cluster, 11..=20).await?;
+    cluster.set_clock_skew(0);
+    insert_and_check(&cluster, 21..=30).await?;
+    Ok(())
+}
+
+#[madsim::test]
+async fn test_clock_skew_on_meta_restart() -> Result<()> {
+    let cluster = start_cluster(1).await?;
+    insert_and_check(&cluster, 1..=10).await?;
+
+    // The restarted meta node has a slower clock, but its epochs must still be greater than the
+    // ones generated before.
+    cluster.set_clock_skew(-10_000);
+    cluster.restart_meta_node().await;
+    insert_and_check(&cluster, 11..=20).await?;
+    cluster.set_clock_skew(0);
+    insert_and_check(&cluster, 21..=30).await?;
+    Ok(())
+}
@@ -0,0 +1,39 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(madsim)]
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use risingwave_simulation::cluster::{Cluster, Configuration};
+use risingwave_simulation::slt::run_slt_files;
+
+const TESTDATA: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/testdata/*.slt");
+
+#[madsim::test]
+async fn test_slt_serial() -> Result<()> {
+    let cluster = Cluster::start(Configuration::default()).await?;
+    run_slt_files(Arc::new(cluster), TESTDATA, 1).await
+}
+
+#[madsim::test]
+async fn test_slt_parallel() -> Result<()> {
+    let cluster = Cluster::start(Configuration {
+        compute_nodes: 3,
+        ..Default::default()
+    })
+    .await?;
+    run_slt_files(Arc::new(cluster), TESTDATA, 4).await
+}
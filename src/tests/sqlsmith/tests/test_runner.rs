@@ -40,6 +40,9 @@ mod tests {
                 Statement::CreateTable {
                     name,
                     columns,
+                    constraints,
+                    append_only,
+                    on_conflict,
                     with_options,
                     ..
                 } => {
@@ -48,6 +51,9 @@ mod tests {
                         context,
                         name.clone(),
                         columns.clone(),
+                        constraints,
+                        append_only,
+                        on_conflict,
                         with_options,
                     )
                     .await
@@ -14,6 +14,13 @@
 
 use thiserror::Error;
 
+use crate::pg_server::BoxedError;
+
+/// SQLSTATE code of errors without a specific code.
+pub const SQLSTATE_INTERNAL_ERROR: &str = "XX000";
+/// SQLSTATE code of canceled queries.
+pub const SQLSTATE_QUERY_CANCELED: &str = "57014";
+
 /// Error type used in pgwire crates.
 #[derive(Error, Debug)]
 pub enum PsqlError {
@@ -27,3 +34,75 @@ impl PsqlError {
         PsqlError::CancelError("ERROR:  canceling statement due to user request".to_string())
     }
 }
+
+/// An error returned by the session with the SQLSTATE code and the hint to report in the
+/// `ErrorResponse`. Other errors are reported with [`SQLSTATE_INTERNAL_ERROR`] and no hint.
+#[derive(Error, Debug)]
+#[error("{error}")]
+pub struct PgError {
+    pub sqlstate: String,
+    pub hint: Option<String>,
+    pub error: BoxedError,
+}
+
+impl PgError {
+    pub fn new(sqlstate: impl Into<String>, error: impl Into<BoxedError>) -> Self {
+        Self {
+            sqlstate: sqlstate.into(),
+            hint: None,
+            error: error.into(),
+        }
+    }
+
+    pub fn with_hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+}
+
+/// Returns the SQLSTATE code and the hint of `error` reported to the client.
+pub fn error_report(
+    error: &(dyn std::error::Error + Send + Sync + 'static),
+) -> (&str, Option<&str>) {
+    if let Some(e) = error.downcast_ref::<PgError>() {
+        (&e.sqlstate, e.hint.as_deref())
+    } else if let Some(PsqlError::CancelError(_)) = error.downcast_ref::<PsqlError>() {
+        (SQLSTATE_QUERY_CANCELED, None)
+    } else if let Some(inner) = error
+        .downcast_ref::<std::io::Error>()
+        .and_then(|e| e.get_ref())
+    {
+        // Errors of the session manager are wrapped in `io::Error` during the startup.
+        error_report(inner)
+    } else {
+        (SQLSTATE_INTERNAL_ERROR, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Error, ErrorKind};
+
+    use super::*;
+
+    #[test]
+    fn test_error_report() {
+        let error: BoxedError = Box::new(Error::new(ErrorKind::Other, "io"));
+        assert_eq!(error_report(&*error), (SQLSTATE_INTERNAL_ERROR, None));
+
+        let error: BoxedError = Box::new(PsqlError::cancel());
+        assert_eq!(error_report(&*error), (SQLSTATE_QUERY_CANCELED, None));
+
+        let error: BoxedError = Box::new(
+            PgError::new("42601", Error::new(ErrorKind::Other, "syntax error")).with_hint("hint"),
+        );
+        assert_eq!(error.to_string(), "syntax error");
+        assert_eq!(error_report(&*error), ("42601", Some("hint")));
+
+        let error: BoxedError = Box::new(Error::new(
+            ErrorKind::Other,
+            PgError::new("3D000", Error::new(ErrorKind::Other, "no database")),
+        ));
+        assert_eq!(error_report(&*error), ("3D000", None));
+    }
+}
@@ -57,11 +57,32 @@ fn parse_params(type_description: &[TypeOid], raw_params: &[Bytes]) -> Vec<Strin
                 TypeOid::Timestamp => todo!(),
                 TypeOid::Timestampz => todo!(),
                 TypeOid::Decimal => todo!(),
+                TypeOid::Uuid => format!("'{}'::UUID", uuid_param_to_str(param)),
             }
         })
         .collect()
 }
 
+/// A uuid param is either in the binary format of 16 bytes, or in the text format.
+fn uuid_param_to_str(param: &Bytes) -> String {
+    if param.len() == 16 {
+        let hex = param
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<String>();
+        format!(
+            "{}-{}-{}-{}-{}",
+            &hex[0..8],
+            &hex[8..12],
+            &hex[12..16],
+            &hex[16..20],
+            &hex[20..32]
+        )
+    } else {
+        cstr_to_str(param).unwrap().to_string()
+    }
+}
+
 /// Replace generic params in query into real params.
 ///
 /// # Example
@@ -371,4 +392,17 @@ mod tests {
             assert!(res == "INSERT INTO nperson (name,data) VALUES ('A','B')");
         }
     }
+
+    #[test]
+    fn test_parse_uuid_params() {
+        let text = "a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11";
+        let binary: Vec<u8> = vec![
+            0xa0, 0xee, 0xbc, 0x99, 0x9c, 0x0b, 0x4e, 0xf8, 0xbb, 0x6d, 0x6b, 0xb9, 0xbd, 0x38,
+            0x0a, 0x11,
+        ];
+        let raw_params = vec![text.into(), binary.into()];
+        let params = parse_params(&[TypeOid::Uuid; 2], &raw_params);
+        let expected = format!("'{}'::UUID", text);
+        assert_eq!(params, vec![expected.clone(), expected]);
+    }
 }
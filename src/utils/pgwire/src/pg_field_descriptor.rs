@@ -43,6 +43,7 @@ impl PgFieldDescriptor {
             | TypeOid::Time
             | TypeOid::Timestampz => 8,
             TypeOid::SmallInt => 2,
+            TypeOid::Uuid => 16,
             TypeOid::CharArray | TypeOid::Varchar | TypeOid::Decimal => -1,
         };
 
@@ -101,6 +102,7 @@ pub enum TypeOid {
     Timestamp,
     Timestampz,
     Decimal,
+    Uuid,
 }
 
 impl TypeOid {
@@ -108,6 +110,7 @@ impl TypeOid {
     pub fn as_type(oid: i32) -> Result<TypeOid, String> {
         match oid {
             1043 => Ok(TypeOid::Varchar),
+            2950 => Ok(TypeOid::Uuid),
             _ => todo!(),
         }
     }
@@ -133,6 +136,7 @@ impl TypeOid {
             TypeOid::Timestamp => 1114,
             TypeOid::Timestampz => 1184,
             TypeOid::Decimal => 1700,
+            TypeOid::Uuid => 2950,
         }
     }
 }
@@ -21,9 +21,10 @@ use byteorder::{BigEndian, ByteOrder};
 use bytes::{Buf, BufMut, Bytes, BytesMut};
 use tokio::io::{AsyncRead, AsyncReadExt};
 
+use crate::error::error_report;
 use crate::pg_field_descriptor::{PgFieldDescriptor, TypeOid};
 use crate::pg_response::StatementType;
-use crate::pg_server::BoxedError;
+use crate::pg_server::{BoxedError, Notification};
 use crate::types::Row;
 
 /// Messages that can be sent from pg client to server. Implement `read`.
@@ -326,6 +327,7 @@ pub enum BeMessage<'a> {
     RowDescription(&'a [PgFieldDescriptor]),
     ErrorResponse(BoxedError),
     CloseComplete,
+    NotificationResponse(&'a Notification),
 }
 
 #[derive(Debug)]
@@ -529,6 +531,21 @@ impl<'a> BeMessage<'a> {
                 write_body(buf, |_| Ok(()))?;
             }
 
+            // NotificationResponse
+            // +-----+-----------+------------------+-------------+-------------+
+            // | 'A' | int32 len | int32 process id | str channel | str payload |
+            // +-----+-----------+------------------+-------------+-------------+
+            BeMessage::NotificationResponse(notification) => {
+                buf.put_u8(b'A');
+                write_body(buf, |buf| {
+                    // Notifications are not raised by other backends, so the process id is unused.
+                    buf.put_i32(0);
+                    write_cstr(buf, notification.channel.as_bytes())?;
+                    write_cstr(buf, notification.payload.as_bytes())?;
+                    Ok(())
+                })?;
+            }
+
             BeMessage::PortalSuspended => {
                 buf.put_u8(b's');
                 write_body(buf, |_| Ok(()))?;
@@ -567,8 +584,9 @@ impl<'a> BeMessage<'a> {
             }
 
             BeMessage::ErrorResponse(error) => {
-                // For all the errors set Severity to Error and error code to
-                // 'internal error'.
+                // For all the errors set Severity to Error. The error code is 'internal error'
+                // unless the session reports a specific one.
+                let (sqlstate, hint) = error_report(&**error);
 
                 // 'E' signalizes ErrorResponse messages
                 buf.put_u8(b'E');
@@ -577,11 +595,16 @@ impl<'a> BeMessage<'a> {
                     write_cstr(buf, &Bytes::from("ERROR"))?;
 
                     buf.put_u8(b'C'); // SQLSTATE error code
-                    write_cstr(buf, &Bytes::from("XX000"))?;
+                    write_cstr(buf, sqlstate.as_bytes())?;
 
                     buf.put_u8(b'M'); // the message
                     write_cstr(buf, error.to_string().as_bytes())?;
 
+                    if let Some(hint) = hint {
+                        buf.put_u8(b'H'); // the hint
+                        write_cstr(buf, hint.as_bytes())?;
+                    }
+
                     buf.put_u8(0); // terminator
                     Ok(())
                 })
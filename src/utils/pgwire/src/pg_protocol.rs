@@ -18,7 +18,7 @@ use std::sync::Arc;
 use std::{result, str, vec};
 
 use bytes::{Bytes, BytesMut};
-use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
 
 use crate::error::PsqlError;
 use crate::pg_extended::{PgPortal, PgStatement};
@@ -36,8 +36,9 @@ pub struct PgProtocol<S, SM>
 where
     SM: SessionManager,
 {
-    /// Used for write/read message in tcp connection.
-    stream: S,
+    /// Used for write/read message in tcp connection. Reads are buffered so that waiting for the
+    /// next message can be interrupted by notifications without losing data.
+    stream: BufReader<S>,
     /// Write into buffer before flush to stream.
     buf_out: BytesMut,
     /// Current states of pg connection.
@@ -73,7 +74,7 @@ where
 {
     pub fn new(stream: S, session_mgr: Arc<SM>) -> Self {
         Self {
-            stream,
+            stream: BufReader::new(stream),
             is_terminate: false,
             state: PgProtocolState::Startup,
             buf_out: BytesMut::with_capacity(10 * 1024),
@@ -111,6 +112,7 @@ where
         named_statements: &mut HashMap<String, PgStatement>,
         named_portals: &mut HashMap<String, PgPortal>,
     ) -> Result<bool> {
+        self.wait_for_message().await?;
         let msg = match self.read_message().await {
             Ok(msg) => msg,
             Err(e) => {
@@ -339,6 +341,29 @@ where
         Ok(false)
     }
 
+    /// Waits until the next message from the client is available, and meanwhile delivers the
+    /// notifications of the session to the client.
+    async fn wait_for_message(&mut self) -> Result<()> {
+        let session = match (&self.state, &self.session) {
+            (PgProtocolState::Regular, Some(session)) => session.clone(),
+            _ => return Ok(()),
+        };
+        loop {
+            // Filling the read buffer is cancel safe, so no partial message is lost when a
+            // notification arrives first.
+            let notification = tokio::select! {
+                biased;
+                ready = self.stream.fill_buf() => {
+                    ready?;
+                    return Ok(());
+                }
+                notification = session.next_notification() => notification,
+            };
+            self.write_message(&BeMessage::NotificationResponse(&notification))
+                .await?;
+        }
+    }
+
     async fn read_message(&mut self) -> Result<FeMessage> {
         match self.state {
             PgProtocolState::Startup => FeStartupMessage::read(&mut self.stream).await,
@@ -26,6 +26,10 @@ pub enum StatementType {
     SELECT,
     MOVE,
     FETCH,
+    DECLARE_CURSOR,
+    CLOSE_CURSOR,
+    LISTEN,
+    UNLISTEN,
     COPY,
     EXPLAIN,
     CREATE_TABLE,
@@ -34,6 +38,7 @@ pub enum StatementType {
     CREATE_DATABASE,
     CREATE_SCHEMA,
     CREATE_USER,
+    CREATE_SUBSCRIPTION,
     DESCRIBE_TABLE,
     GRANT_PRIVILEGE,
     DROP_TABLE,
@@ -43,6 +48,7 @@ pub enum StatementType {
     DROP_SCHEMA,
     DROP_DATABASE,
     DROP_USER,
+    DROP_SUBSCRIPTION,
     REVOKE_PRIVILEGE,
     // Introduce ORDER_BY statement type cuz Calcite unvalidated AST has SqlKind.ORDER_BY. Note
     // that Statement Type is not designed to be one to one mapping with SqlKind.
@@ -53,6 +59,13 @@ pub enum StatementType {
     START_TRANSACTION,
     ABORT,
     FLUSH,
+    ALTER_SYSTEM,
+    ALTER_USER,
+    ALTER_MATERIALIZED_VIEW,
+    SAVEPOINT,
+    COMMENT,
+    UNLOAD,
+    REPLAY,
     OTHER,
     // EMPTY is used when query statement is empty (e.g. ";").
     EMPTY,
@@ -86,6 +99,7 @@ impl StatementType {
                 | StatementType::COPY
                 | StatementType::FETCH
                 | StatementType::SELECT
+                | StatementType::UNLOAD
         )
     }
 }
@@ -139,9 +153,12 @@ impl PgResponse {
         matches!(
             self.stmt_type,
             StatementType::SELECT
+                | StatementType::FETCH
                 | StatementType::EXPLAIN
                 | StatementType::SHOW_COMMAND
                 | StatementType::DESCRIBE_TABLE
+                | StatementType::UNLOAD
+                | StatementType::REPLAY
         )
     }
 
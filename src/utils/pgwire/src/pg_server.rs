@@ -12,12 +12,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::future::Future;
 use std::io;
 use std::io::ErrorKind;
 use std::result::Result;
 use std::sync::Arc;
+use std::time::Duration;
 
 use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
 
 use crate::pg_field_descriptor::PgFieldDescriptor;
 use crate::pg_protocol::PgProtocol;
@@ -43,6 +46,19 @@ pub trait Session: Send + Sync {
         sql: &str,
     ) -> Result<Vec<PgFieldDescriptor>, BoxedError>;
     fn user_authenticator(&self) -> &UserAuthenticator;
+
+    /// Waits for the next asynchronous notification of the session, which is delivered to the
+    /// client while the connection is idle. Sessions without notifications never return.
+    async fn next_notification(&self) -> Notification {
+        std::future::pending().await
+    }
+}
+
+/// An asynchronous notification raised on a channel the session is listening on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    pub channel: String,
+    pub payload: String,
 }
 
 #[derive(Debug, Clone)]
@@ -72,19 +88,37 @@ impl UserAuthenticator {
 
 /// Binds a Tcp listener at `addr`. Spawn a coroutine to serve every new connection.
 pub async fn pg_serve(addr: &str, session_mgr: Arc<impl SessionManager>) -> io::Result<()> {
+    pg_serve_with_shutdown(addr, session_mgr, std::future::pending(), Duration::ZERO).await
+}
+
+/// Serves until `shutdown` completes. Then stops accepting new connections, and waits for the
+/// existing connections to be closed by the clients for at most `drain_timeout`.
+pub async fn pg_serve_with_shutdown(
+    addr: &str,
+    session_mgr: Arc<impl SessionManager>,
+    shutdown: impl Future<Output = ()>,
+    drain_timeout: Duration,
+) -> io::Result<()> {
     let listener = TcpListener::bind(addr).await.unwrap();
-    // accept connections and process them, spawning a new thread for each one
     tracing::info!("Server Listening at {}", addr);
+    // Each connection holds a sender, so that the receiver gets `None` after all the connections
+    // are closed.
+    let (conn_tx, mut conn_rx) = mpsc::channel::<()>(1);
+    tokio::pin!(shutdown);
     loop {
         let session_mgr = session_mgr.clone();
-        let conn_ret = listener.accept().await;
+        let conn_ret = tokio::select! {
+            conn_ret = listener.accept() => conn_ret,
+            _ = &mut shutdown => break,
+        };
         match conn_ret {
             Ok((stream, peer_addr)) => {
                 tracing::info!("New connection: {}", peer_addr);
+                let conn_tx = conn_tx.clone();
                 tokio::spawn(async move {
-                    // connection succeeded
                     pg_serve_conn(stream, session_mgr).await;
                     tracing::info!("Connection {} closed", peer_addr);
+                    drop(conn_tx);
                 });
             }
 
@@ -93,6 +127,17 @@ pub async fn pg_serve(addr: &str, session_mgr: Arc<impl SessionManager>) -> io::
             }
         }
     }
+
+    drop(listener);
+    drop(conn_tx);
+    tracing::info!("Stop accepting new connections, draining the existing ones");
+    if tokio::time::timeout(drain_timeout, conn_rx.recv())
+        .await
+        .is_err()
+    {
+        tracing::warn!("Timeout draining connections, shutting down");
+    }
+    Ok(())
 }
 
 async fn pg_serve_conn(socket: TcpStream, session_mgr: Arc<impl SessionManager>) {
@@ -136,11 +181,13 @@ mod tests {
     use std::sync::Arc;
 
     use tokio_postgres::types::*;
-    use tokio_postgres::NoTls;
+    use tokio_postgres::{NoTls, SimpleQueryMessage};
 
     use crate::pg_field_descriptor::{PgFieldDescriptor, TypeOid};
     use crate::pg_response::{PgResponse, StatementType};
-    use crate::pg_server::{pg_serve, Session, SessionManager, UserAuthenticator};
+    use crate::pg_server::{
+        pg_serve, pg_serve_with_shutdown, Session, SessionManager, UserAuthenticator,
+    };
     use crate::types::Row;
 
     struct MockSessionManager {}
@@ -347,4 +394,44 @@ mod tests {
             assert_eq!(value, "BB");
         }
     }
+
+    #[tokio::test]
+    async fn test_pg_serve_with_shutdown() {
+        let session_mgr = Arc::new(MockSessionManager {});
+        let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+        let mut server = tokio::spawn(async move {
+            pg_serve_with_shutdown(
+                "127.0.0.1:10001",
+                session_mgr,
+                async move {
+                    shutdown_rx.await.unwrap();
+                },
+                std::time::Duration::from_secs(60),
+            )
+            .await
+        });
+
+        let (client, connection) = tokio_postgres::connect("host=localhost port=10001", NoTls)
+            .await
+            .unwrap();
+        let connection = tokio::spawn(connection);
+
+        // The existing connection is still served after shutdown starts.
+        shutdown_tx.send(()).unwrap();
+        let messages = client.simple_query("SELECT 'AA';").await.unwrap();
+        match &messages[0] {
+            SimpleQueryMessage::Row(row) => assert_eq!(row.get(0), Some("AA")),
+            _ => unreachable!(),
+        }
+        assert!(
+            tokio::time::timeout(std::time::Duration::from_millis(100), &mut server)
+                .await
+                .is_err()
+        );
+
+        // The server exits after the connection is closed.
+        drop(client);
+        connection.await.unwrap().unwrap();
+        server.await.unwrap().unwrap();
+    }
 }